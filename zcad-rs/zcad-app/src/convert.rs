@@ -0,0 +1,413 @@
+//! `convert` 子命令：按通配模式收集图纸，并行转换为目标格式并输出汇总报告。
+//!
+//! 输入按内容识别格式（DXF、原生 JSON、`.zcadz`；DWG 会被识别并报告为暂不支持），
+//! 输出使用 `zcad-io` 的各个门面与导出器。任一文件失败时进程以状态码 1 退出。
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use tracing::{info, warn};
+use walkdir::WalkDir;
+use zcad_core::document::{Document, PurgeOptions};
+use zcad_io::{
+    DocumentSaver, DxfFacade, IoError, JsonFacade, PdfExporter, PngExporter, SvgExporter,
+    open_document,
+};
+
+/// 目录作为输入时收集的扩展名。
+const DRAWING_EXTENSIONS: [&str; 4] = ["dxf", "dwg", "json", "zcadz"];
+
+#[derive(Args, Debug)]
+pub struct ConvertArgs {
+    /// 输入文件、目录或通配模式（支持 `*`、`?` 与 `**`），可指定多个
+    #[arg(required = true, value_name = "INPUT")]
+    inputs: Vec<String>,
+
+    /// 目标格式
+    #[arg(long, short = 't', value_enum)]
+    to: TargetFormat,
+
+    /// 输出目录，默认写到输入文件所在目录
+    #[arg(long, short = 'o', value_name = "DIR")]
+    output_dir: Option<PathBuf>,
+
+    /// 并行任务数，默认等于可用 CPU 数
+    #[arg(long, short = 'j')]
+    jobs: Option<usize>,
+
+    /// 覆盖已存在的输出文件（默认跳过）
+    #[arg(long)]
+    overwrite: bool,
+
+    /// 保存前清理未被引用的块、图层、线型、样式与图像定义
+    #[arg(long)]
+    purge: bool,
+
+    /// 以 JSON 格式在标准输出打印汇总报告
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetFormat {
+    Dxf,
+    Json,
+    Svg,
+    Pdf,
+    Png,
+}
+
+impl TargetFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            TargetFormat::Dxf => "dxf",
+            TargetFormat::Json => "json",
+            TargetFormat::Svg => "svg",
+            TargetFormat::Pdf => "pdf",
+            TargetFormat::Png => "png",
+        }
+    }
+
+    fn save(self, document: &Document, path: &Path) -> Result<(), IoError> {
+        match self {
+            TargetFormat::Dxf => DxfFacade::new().save(document, path),
+            TargetFormat::Json => JsonFacade::new().save(document, path),
+            TargetFormat::Svg => SvgExporter::new().save(document, path),
+            TargetFormat::Pdf => PdfExporter::new().save(document, path),
+            TargetFormat::Png => PngExporter::new().save(document, path),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Converted,
+    Skipped,
+    Failed,
+}
+
+/// 单个文件的转换结果。
+#[derive(Debug, Serialize)]
+struct FileReport {
+    input: PathBuf,
+    output: Option<PathBuf>,
+    status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entities: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    elapsed_ms: u128,
+}
+
+/// 汇总报告。
+#[derive(Debug, Serialize)]
+struct Report {
+    format: TargetFormat,
+    converted: usize,
+    skipped: usize,
+    failed: usize,
+    elapsed_ms: u128,
+    files: Vec<FileReport>,
+}
+
+/// 执行转换并打印报告，返回进程退出码。
+pub fn run(args: &ConvertArgs) -> i32 {
+    let started = Instant::now();
+    let mut inputs = Vec::new();
+    let mut unmatched = Vec::new();
+    for pattern in &args.inputs {
+        let matched = expand_input(pattern);
+        if matched.is_empty() {
+            unmatched.push(pattern.clone());
+        }
+        inputs.extend(matched);
+    }
+    let mut seen = HashSet::new();
+    inputs.retain(|path| seen.insert(path.clone()));
+
+    if let Some(dir) = &args.output_dir
+        && let Err(err) = fs::create_dir_all(dir)
+    {
+        eprintln!("无法创建输出目录 {}: {err}", dir.display());
+        return 1;
+    }
+
+    let tasks = plan(&inputs, args);
+    let jobs = args
+        .jobs
+        .unwrap_or_else(|| {
+            std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get)
+        })
+        .clamp(1, tasks.len().max(1));
+    info!(files = tasks.len(), jobs, format = ?args.to, "开始批量转换");
+
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<FileReport>>> = Mutex::new(tasks.iter().map(|_| None).collect());
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(task) = tasks.get(index) else {
+                        break;
+                    };
+                    let report = execute(task, args);
+                    results.lock().unwrap()[index] = Some(report);
+                }
+            });
+        }
+    });
+
+    let mut files: Vec<FileReport> = results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect();
+    files.extend(unmatched.into_iter().map(|pattern| FileReport {
+        input: PathBuf::from(pattern),
+        output: None,
+        status: Status::Failed,
+        entities: None,
+        message: Some("没有匹配的文件".to_string()),
+        elapsed_ms: 0,
+    }));
+    let count = |status| files.iter().filter(|file| file.status == status).count();
+    let report = Report {
+        format: args.to,
+        converted: count(Status::Converted),
+        skipped: count(Status::Skipped),
+        failed: count(Status::Failed),
+        elapsed_ms: started.elapsed().as_millis(),
+        files,
+    };
+    print_report(&report, args.json);
+    i32::from(report.failed > 0)
+}
+
+/// 待执行的转换；`conflict` 记录规划阶段就能确定的失败原因。
+struct Task {
+    input: PathBuf,
+    output: PathBuf,
+    conflict: Option<String>,
+}
+
+fn plan(inputs: &[PathBuf], args: &ConvertArgs) -> Vec<Task> {
+    let extension = args.to.extension();
+    let mut outputs = HashSet::new();
+    inputs
+        .iter()
+        .map(|input| {
+            let output = match &args.output_dir {
+                Some(dir) => {
+                    let stem = input.file_stem().unwrap_or(input.as_os_str());
+                    dir.join(format!("{}.{extension}", stem.to_string_lossy()))
+                }
+                None => input.with_extension(extension),
+            };
+            let conflict = if output == *input {
+                Some("输出文件与输入相同".to_string())
+            } else if !outputs.insert(output.clone()) {
+                Some(format!("输出文件 {} 与其他输入重名", output.display()))
+            } else {
+                None
+            };
+            Task {
+                input: input.clone(),
+                output,
+                conflict,
+            }
+        })
+        .collect()
+}
+
+fn execute(task: &Task, args: &ConvertArgs) -> FileReport {
+    let started = Instant::now();
+    let mut report = FileReport {
+        input: task.input.clone(),
+        output: Some(task.output.clone()),
+        status: Status::Failed,
+        entities: None,
+        message: task.conflict.clone(),
+        elapsed_ms: 0,
+    };
+    if report.message.is_none() {
+        if !args.overwrite && task.output.exists() {
+            report.status = Status::Skipped;
+            report.message = Some("输出文件已存在".to_string());
+        } else {
+            match open_document(&task.input)
+                .map(|mut document| {
+                    if args.purge {
+                        document.purge(&PurgeOptions::default());
+                    }
+                    document
+                })
+                .and_then(|document| args.to.save(&document, &task.output).map(|()| document))
+            {
+                Ok(document) => {
+                    report.status = Status::Converted;
+                    report.entities = Some(document.entities().count());
+                }
+                Err(err) => {
+                    warn!(input = %task.input.display(), error = %err, "转换失败");
+                    report.message = Some(err.to_string());
+                }
+            }
+        }
+    }
+    report.elapsed_ms = started.elapsed().as_millis();
+    report
+}
+
+fn print_report(report: &Report, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(report) {
+            Ok(text) => println!("{text}"),
+            Err(err) => eprintln!("报告序列化失败: {err}"),
+        }
+        return;
+    }
+    for file in &report.files {
+        let status = match file.status {
+            Status::Converted => "成功",
+            Status::Skipped => "跳过",
+            Status::Failed => "失败",
+        };
+        let target = file
+            .output
+            .as_ref()
+            .map(|output| format!(" -> {}", output.display()))
+            .unwrap_or_default();
+        let detail = file
+            .message
+            .as_ref()
+            .map(|message| format!("（{message}）"))
+            .unwrap_or_default();
+        println!("[{status}] {}{target}{detail}", file.input.display());
+    }
+    println!(
+        "转换完成：成功 {}，跳过 {}，失败 {}，用时 {} ms",
+        report.converted, report.skipped, report.failed, report.elapsed_ms
+    );
+}
+
+/// 展开单个输入：不含通配符时按文件或目录（目录下的图纸文件）处理，
+/// 否则从最长的无通配前缀目录开始遍历匹配，结果按路径排序。
+pub(crate) fn expand_input(pattern: &str) -> Vec<PathBuf> {
+    let path = Path::new(pattern);
+    if !has_wildcard(pattern) {
+        if path.is_file() {
+            return vec![path.to_path_buf()];
+        }
+        if path.is_dir() {
+            let mut files: Vec<PathBuf> = fs::read_dir(path)
+                .into_iter()
+                .flatten()
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && is_drawing(path))
+                .collect();
+            files.sort();
+            return files;
+        }
+        return Vec::new();
+    }
+
+    let components: Vec<String> = path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    let literal = components
+        .iter()
+        .position(|component| has_wildcard(component))
+        .unwrap_or(components.len());
+    let base: PathBuf = if literal == 0 {
+        PathBuf::from(".")
+    } else {
+        components[..literal].iter().collect()
+    };
+    let rest: Vec<&str> = components[literal..].iter().map(String::as_str).collect();
+    let recursive = rest.contains(&"**");
+    let mut files: Vec<PathBuf> = WalkDir::new(&base)
+        .min_depth(1)
+        .max_depth(if recursive { usize::MAX } else { rest.len() })
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let relative = entry.path().strip_prefix(&base).ok()?;
+            let names: Vec<String> = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            let names: Vec<&str> = names.iter().map(String::as_str).collect();
+            match_components(&rest, &names).then(|| {
+                if literal == 0 {
+                    relative.to_path_buf()
+                } else {
+                    entry.path().to_path_buf()
+                }
+            })
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn has_wildcard(text: &str) -> bool {
+    text.contains(['*', '?'])
+}
+
+fn is_drawing(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            DRAWING_EXTENSIONS
+                .iter()
+                .any(|known| extension.eq_ignore_ascii_case(known))
+        })
+}
+
+/// 逐级匹配路径分量，`**` 匹配零个或多个目录。
+fn match_components(pattern: &[&str], names: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => names.is_empty(),
+        Some((&"**", rest)) => (0..=names.len()).any(|skip| match_components(rest, &names[skip..])),
+        Some((first, rest)) => names
+            .split_first()
+            .is_some_and(|(name, names)| match_name(first, name) && match_components(rest, names)),
+    }
+}
+
+/// 单个分量的通配匹配：`*` 匹配任意字符序列，`?` 匹配单个字符。
+fn match_name(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            n = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|ch| *ch == '*')
+}
@@ -0,0 +1,436 @@
+//! `info` 与 `stats` 子命令：无需图形界面即可查看客户图纸的概况。
+//!
+//! `info` 输出格式与版本、对象与图层数量、图纸范围以及图像、底图、外部参照等外部依赖；
+//! `stats` 输出按类型、按图层的对象统计、块的使用情况与内存估算。
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use serde::Serialize;
+use zcad_config::AppConfig;
+use zcad_core::document::{Document, Entity};
+use zcad_io::writer::DxfVersion;
+use zcad_io::{DocumentFormat, DocumentLoader, DxfFacade, IoError, detect_acadver, open_document};
+
+#[derive(Args, Debug)]
+pub struct InspectArgs {
+    /// 图纸文件（DXF、原生 JSON 或 `.zcadz`）
+    #[arg(value_name = "FILE")]
+    path: PathBuf,
+
+    /// 以 JSON 格式输出
+    #[arg(long)]
+    json: bool,
+}
+
+/// `info` 的输出。
+#[derive(Debug, Serialize)]
+struct DrawingInfo {
+    path: PathBuf,
+    format: &'static str,
+    /// `$ACADVER` 或 DWG 文件头中的版本号。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    release: Option<String>,
+    file_size: u64,
+    entities: usize,
+    layers: usize,
+    blocks: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bounds: Option<BoundsInfo>,
+    dependencies: Vec<Dependency>,
+}
+
+#[derive(Debug, Serialize)]
+struct BoundsInfo {
+    min: [f64; 2],
+    max: [f64; 2],
+    size: [f64; 2],
+}
+
+/// 图纸引用的外部文件。
+#[derive(Debug, Serialize)]
+struct Dependency {
+    /// `image`、`pdf`/`dgn`/`dwf` 底图或 `xref`。
+    kind: &'static str,
+    name: String,
+    path: String,
+    /// 找到的文件；未找到时为 `None`。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resolved: Option<String>,
+    /// 模型空间中引用该依赖的对象数。
+    references: usize,
+}
+
+/// `stats` 的输出。
+#[derive(Debug, Serialize)]
+struct DrawingStats {
+    path: PathBuf,
+    entities: usize,
+    by_type: Vec<TypeCount>,
+    by_layer: Vec<LayerCount>,
+    blocks: Vec<BlockUsage>,
+    /// 所有对象（含块定义内）的内存估算字节数。
+    estimated_bytes: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct TypeCount {
+    kind: &'static str,
+    count: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct LayerCount {
+    layer: String,
+    visible: bool,
+    count: usize,
+    by_type: BTreeMap<&'static str, usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct BlockUsage {
+    name: String,
+    entities: usize,
+    /// 模型空间中的直接参照数。
+    references: usize,
+    /// 其他块定义中的嵌套参照数。
+    nested_references: usize,
+}
+
+/// 执行 `info`，返回进程退出码。
+pub fn run_info(args: &InspectArgs, config: &AppConfig) -> i32 {
+    match load(&args.path, config) {
+        Ok(loaded) => {
+            let info = drawing_info(&args.path, &loaded);
+            if args.json {
+                print_json(&info);
+            } else {
+                print_info(&info);
+            }
+            0
+        }
+        Err(err) => {
+            eprintln!("无法读取 {}: {err}", args.path.display());
+            1
+        }
+    }
+}
+
+/// 执行 `stats`，返回进程退出码。
+pub fn run_stats(args: &InspectArgs, config: &AppConfig) -> i32 {
+    match load(&args.path, config) {
+        Ok(loaded) => {
+            let stats = drawing_stats(&args.path, &loaded.document);
+            if args.json {
+                print_json(&stats);
+            } else {
+                print_stats(&stats);
+            }
+            0
+        }
+        Err(err) => {
+            eprintln!("无法读取 {}: {err}", args.path.display());
+            1
+        }
+    }
+}
+
+struct LoadedDrawing {
+    document: Document,
+    format: DocumentFormat,
+    version: Option<String>,
+    file_size: u64,
+}
+
+/// 按内容识别格式加载图纸；DXF 按资源配置追加图像搜索目录，按几何配置设置容差。
+fn load(path: &Path, config: &AppConfig) -> Result<LoadedDrawing, IoError> {
+    let bytes = fs::read(path).map_err(|source| IoError::ReadError {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let format = DocumentFormat::detect(&bytes);
+    let version = detect_acadver(&bytes);
+    let document = match format {
+        DocumentFormat::Dxf => DxfFacade::new()
+            .with_image_resources(&config.resources)
+            .with_geometry(&config.geometry)
+            .load(path)?,
+        _ => open_document(path)?,
+    };
+    Ok(LoadedDrawing {
+        document,
+        format,
+        version,
+        file_size: bytes.len() as u64,
+    })
+}
+
+fn drawing_info(path: &Path, loaded: &LoadedDrawing) -> DrawingInfo {
+    let document = &loaded.document;
+    let bounds = document
+        .bounds()
+        .filter(|bounds| !bounds.is_empty())
+        .map(|bounds| {
+            let (min, max) = (bounds.min(), bounds.max());
+            BoundsInfo {
+                min: [min.x(), min.y()],
+                max: [max.x(), max.y()],
+                size: [max.x() - min.x(), max.y() - min.y()],
+            }
+        });
+    DrawingInfo {
+        path: path.to_path_buf(),
+        format: match loaded.format {
+            DocumentFormat::Dxf => "dxf",
+            DocumentFormat::Json => "json",
+            DocumentFormat::Zcadz => "zcadz",
+            DocumentFormat::Dwg => "dwg",
+        },
+        release: loaded
+            .version
+            .as_deref()
+            .and_then(DxfVersion::from_acadver)
+            .map(|version| format!("{version:?}")),
+        version: loaded.version.clone(),
+        file_size: loaded.file_size,
+        entities: document.entities().count(),
+        layers: document.layers().count(),
+        blocks: document.blocks().count(),
+        bounds,
+        dependencies: dependencies(path, document),
+    }
+}
+
+fn dependencies(path: &Path, document: &Document) -> Vec<Dependency> {
+    let base = path.parent().unwrap_or(Path::new("."));
+    let mut image_references: HashMap<&str, usize> = HashMap::new();
+    let mut underlay_references: HashMap<&str, usize> = HashMap::new();
+    for (_, entity) in document.entities() {
+        match entity {
+            Entity::RasterImage(image) => {
+                *image_references.entry(&image.image_def_handle).or_default() += 1;
+            }
+            Entity::Underlay(underlay) => {
+                *underlay_references
+                    .entry(&underlay.definition_handle)
+                    .or_default() += 1;
+            }
+            _ => {}
+        }
+    }
+
+    let mut dependencies: Vec<Dependency> = document
+        .raster_image_definitions()
+        .map(|(handle, definition)| Dependency {
+            kind: "image",
+            name: definition.name.clone().unwrap_or_else(|| handle.clone()),
+            path: definition.file_path.clone(),
+            resolved: definition
+                .resolved_path
+                .clone()
+                .or_else(|| resolve_relative(base, &definition.file_path)),
+            references: image_references.get(handle.as_str()).copied().unwrap_or(0),
+        })
+        .collect();
+    dependencies.extend(document.underlay_definitions().map(|definition| {
+        Dependency {
+            kind: match definition.kind.entity_name() {
+                "PDFUNDERLAY" => "pdf",
+                "DGNUNDERLAY" => "dgn",
+                _ => "dwf",
+            },
+            name: definition
+                .name
+                .clone()
+                .unwrap_or_else(|| definition.handle.clone()),
+            path: definition.file_path.clone(),
+            resolved: resolve_relative(base, &definition.file_path),
+            references: underlay_references
+                .get(definition.handle.as_str())
+                .copied()
+                .unwrap_or(0),
+        }
+    }));
+    dependencies.extend(document.xrefs().map(|xref| {
+        let references = document
+            .entities()
+            .filter(|(_, entity)| {
+                matches!(entity, Entity::BlockReference(reference) if reference.name == xref.name)
+            })
+            .count();
+        Dependency {
+            kind: "xref",
+            name: xref.name.clone(),
+            path: xref.path.clone(),
+            resolved: resolve_relative(base, &xref.path),
+            references,
+        }
+    }));
+    dependencies.sort_by(|a, b| (a.kind, &a.name).cmp(&(b.kind, &b.name)));
+    dependencies
+}
+
+/// 依赖路径按原样或相对图纸目录查找，兼容 Windows 分隔符。
+fn resolve_relative(base: &Path, path: &str) -> Option<String> {
+    let normalized = path.replace('\\', "/");
+    let candidate = Path::new(&normalized);
+    [
+        candidate.to_path_buf(),
+        base.join(candidate),
+        base.join(candidate.file_name()?),
+    ]
+    .into_iter()
+    .find(|candidate| candidate.is_file())
+    .map(|found| found.to_string_lossy().into_owned())
+}
+
+fn drawing_stats(path: &Path, document: &Document) -> DrawingStats {
+    let mut by_type: BTreeMap<&'static str, usize> = BTreeMap::new();
+    let mut by_layer: BTreeMap<&str, BTreeMap<&'static str, usize>> = BTreeMap::new();
+    let mut references: HashMap<&str, usize> = HashMap::new();
+    for (_, entity) in document.entities() {
+        *by_type.entry(entity.kind_name()).or_default() += 1;
+        *by_layer
+            .entry(entity.layer_name())
+            .or_default()
+            .entry(entity.kind_name())
+            .or_default() += 1;
+        if let Entity::BlockReference(reference) = entity {
+            *references.entry(&reference.name).or_default() += 1;
+        }
+    }
+    let mut nested: HashMap<&str, usize> = HashMap::new();
+    for block in document.blocks() {
+        for entity in &block.entities {
+            if let Entity::BlockReference(reference) = entity {
+                *nested.entry(&reference.name).or_default() += 1;
+            }
+        }
+    }
+
+    let mut by_type: Vec<TypeCount> = by_type
+        .into_iter()
+        .map(|(kind, count)| TypeCount { kind, count })
+        .collect();
+    by_type.sort_by(|a, b| b.count.cmp(&a.count).then(a.kind.cmp(b.kind)));
+    let by_layer = by_layer
+        .into_iter()
+        .map(|(layer, by_type)| LayerCount {
+            layer: layer.to_string(),
+            visible: document
+                .layer(layer)
+                .is_none_or(|layer| layer.is_displayed()),
+            count: by_type.values().sum(),
+            by_type,
+        })
+        .collect();
+    let mut blocks: Vec<BlockUsage> = document
+        .blocks()
+        .map(|block| BlockUsage {
+            name: block.name.clone(),
+            entities: block.entities.len(),
+            references: references.get(block.name.as_str()).copied().unwrap_or(0),
+            nested_references: nested.get(block.name.as_str()).copied().unwrap_or(0),
+        })
+        .collect();
+    blocks.sort_by(|a, b| a.name.cmp(&b.name));
+
+    DrawingStats {
+        path: path.to_path_buf(),
+        entities: document.entities().count(),
+        by_type,
+        by_layer,
+        blocks,
+        estimated_bytes: document.memory_report().total_bytes(),
+    }
+}
+
+fn print_json(value: &impl Serialize) {
+    match serde_json::to_string_pretty(value) {
+        Ok(text) => println!("{text}"),
+        Err(err) => eprintln!("输出序列化失败: {err}"),
+    }
+}
+
+fn print_info(info: &DrawingInfo) {
+    println!("文件：{}（{} 字节）", info.path.display(), info.file_size);
+    let version = match (&info.version, &info.release) {
+        (Some(version), Some(release)) => format!("，{version}（{release}）"),
+        (Some(version), None) => format!("，{version}"),
+        _ => String::new(),
+    };
+    println!("格式：{}{version}", info.format.to_uppercase());
+    println!(
+        "对象：{}，图层：{}，块定义：{}",
+        info.entities, info.layers, info.blocks
+    );
+    match &info.bounds {
+        Some(bounds) => println!(
+            "范围：({}, {}) - ({}, {})，尺寸 {} × {}",
+            bounds.min[0],
+            bounds.min[1],
+            bounds.max[0],
+            bounds.max[1],
+            bounds.size[0],
+            bounds.size[1]
+        ),
+        None => println!("范围：空"),
+    }
+    if info.dependencies.is_empty() {
+        println!("外部依赖：无");
+        return;
+    }
+    let missing = info
+        .dependencies
+        .iter()
+        .filter(|dependency| dependency.resolved.is_none())
+        .count();
+    println!("外部依赖：{}（缺失 {missing}）", info.dependencies.len());
+    for dependency in &info.dependencies {
+        let state = match &dependency.resolved {
+            Some(resolved) => format!("找到 {resolved}"),
+            None => "缺失".to_string(),
+        };
+        println!(
+            "  [{}] {} {}（引用 {}，{state}）",
+            dependency.kind, dependency.name, dependency.path, dependency.references
+        );
+    }
+}
+
+fn print_stats(stats: &DrawingStats) {
+    println!("文件：{}", stats.path.display());
+    println!(
+        "对象：{}，内存估算 {:.1} KiB",
+        stats.entities,
+        stats.estimated_bytes as f64 / 1024.0
+    );
+    println!("按类型：");
+    for entry in &stats.by_type {
+        println!("  {:<16} {}", entry.kind, entry.count);
+    }
+    println!("按图层：");
+    for layer in &stats.by_layer {
+        let types = layer
+            .by_type
+            .iter()
+            .map(|(kind, count)| format!("{kind} {count}"))
+            .collect::<Vec<_>>()
+            .join("，");
+        let hidden = if layer.visible { "" } else { "（关闭）" };
+        println!("  {}{hidden} {}：{types}", layer.layer, layer.count);
+    }
+    if !stats.blocks.is_empty() {
+        println!("块：");
+        for block in &stats.blocks {
+            println!(
+                "  {} 对象 {}，参照 {}，嵌套参照 {}",
+                block.name, block.entities, block.references, block.nested_references
+            );
+        }
+    }
+}
@@ -1,42 +1,77 @@
 use std::path::PathBuf;
 
+use clap::{Args, Parser, Subcommand};
 use tracing::{error, info, warn};
 use tracing_subscriber::{EnvFilter, fmt};
 use zcad_config::{AppConfig, ConfigError, FrontendMode};
 
+mod convert;
+mod inspect;
 mod runtime_assets;
+mod validate;
+
+#[derive(Parser, Debug)]
+#[command(name = "zcad", version)]
+#[command(about = "ZCAD Rust 应用：启动前端或执行图纸批处理命令", long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    launch: LaunchArgs,
+    /// 配置文件路径，未指定时自动查找
+    #[arg(long, global = true, value_name = "FILE")]
+    config: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// 批量转换图纸格式（dxf、json、svg、pdf、png）
+    Convert(convert::ConvertArgs),
+    /// 查看图纸格式、版本、范围与外部依赖
+    Info(inspect::InspectArgs),
+    /// 统计图纸对象类型、图层与块的使用情况
+    Stats(inspect::InspectArgs),
+    /// 诊断解析并审核图纸，输出问题列表
+    Validate(validate::ValidateArgs),
+}
+
+#[derive(Args, Debug)]
+struct LaunchArgs {
+    /// 以 Bevy 窗口模式启动
+    #[arg(long, conflicts_with = "cli")]
+    bevy: bool,
+    /// 以 CLI 模式启动
+    #[arg(long)]
+    cli: bool,
+}
 
 fn main() {
-    let mut args = std::env::args().skip(1);
-    let mut override_mode: Option<FrontendMode> = None;
-    let mut config_override: Option<PathBuf> = None;
+    let cli = Cli::parse();
+    let config = load_configuration(cli.config);
+    init_logging(&config);
 
-    while let Some(arg) = args.next() {
-        match arg.as_str() {
-            "--bevy" => override_mode = Some(FrontendMode::Bevy),
-            "--cli" => override_mode = Some(FrontendMode::Cli),
-            "--config" => {
-                let Some(path) = args.next() else {
-                    eprintln!("`--config` 需要提供配置文件路径");
-                    std::process::exit(1);
-                };
-                config_override = Some(PathBuf::from(path));
-            }
-            other => {
-                eprintln!("未知参数：{other}");
-                std::process::exit(1);
-            }
-        }
+    if let Some(command) = cli.command {
+        let code = match command {
+            Command::Convert(args) => convert::run(&args),
+            Command::Info(args) => inspect::run_info(&args, &config),
+            Command::Stats(args) => inspect::run_stats(&args, &config),
+            Command::Validate(args) => validate::run(&args, &config),
+        };
+        std::process::exit(code);
     }
 
-    let config = load_configuration(config_override);
-    init_logging(&config);
     info!("启动 ZCAD Rust 应用");
-
     if let Err(err) = runtime_assets::ensure_runtime_assets(&config.resources) {
         warn!(error = %err, "无法准备运行时资源");
     }
 
+    let override_mode = if cli.launch.bevy {
+        Some(FrontendMode::Bevy)
+    } else if cli.launch.cli {
+        Some(FrontendMode::Cli)
+    } else {
+        None
+    };
     let mode = override_mode.unwrap_or(config.frontend.default_mode);
     match mode {
         FrontendMode::Bevy => {
@@ -68,7 +103,10 @@ fn load_configuration(override_path: Option<PathBuf>) -> AppConfig {
             Ok(cfg) => cfg,
             Err(err) => {
                 match &err {
-                    ConfigError::Io { path, .. } | ConfigError::Parse { path, .. } => {
+                    ConfigError::Io { path, .. }
+                    | ConfigError::Parse { path, .. }
+                    | ConfigError::Write { path, .. }
+                    | ConfigError::Serialize { path, .. } => {
                         warn!(path = %path.display(), error = %err, "加载默认配置失败，使用内建默认值");
                     }
                     ConfigError::Context { .. } => {
@@ -84,7 +122,8 @@ fn load_configuration(override_path: Option<PathBuf>) -> AppConfig {
 fn init_logging(config: &AppConfig) {
     let filter =
         EnvFilter::try_new(config.logging.level.clone()).unwrap_or_else(|_| EnvFilter::new("info"));
-    let subscriber = fmt().with_env_filter(filter);
+    // 日志写到 stderr，stdout 留给命令的报告输出
+    let subscriber = fmt().with_env_filter(filter).with_writer(std::io::stderr);
     if subscriber.try_init().is_err() {
         // 已初始化，忽略
     }
@@ -0,0 +1,292 @@
+//! `validate` 子命令：以诊断模式解析图纸并执行文档审核，供 CI 检查来稿图纸质量。
+//!
+//! DXF 以 [`DxfFacade::load_with_diagnostics`] 读取，跳过的实体记为解析问题，
+//! 审核发现通过诊断中的句柄表映射回 DXF 句柄；其他格式只做审核。
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use zcad_config::AppConfig;
+use zcad_core::document::{AuditSeverity, Document};
+use zcad_io::{DocumentFormat, DxfDiagnostics, DxfFacade, IoError, open_document};
+
+use crate::convert::expand_input;
+
+#[derive(Args, Debug)]
+pub struct ValidateArgs {
+    /// 图纸文件、目录或通配模式（支持 `*`、`?` 与 `**`）
+    #[arg(required = true, value_name = "INPUT")]
+    inputs: Vec<String>,
+
+    /// 达到该级别的问题使退出码为 1
+    #[arg(long, value_enum, default_value_t = FailOn::Error)]
+    fail_on: FailOn,
+
+    /// 以 JSON 格式输出结果
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FailOn {
+    Error,
+    Warning,
+    Info,
+    /// 只报告，不影响退出码
+    Never,
+}
+
+impl FailOn {
+    fn threshold(self) -> Option<AuditSeverity> {
+        match self {
+            FailOn::Error => Some(AuditSeverity::Error),
+            FailOn::Warning => Some(AuditSeverity::Warning),
+            FailOn::Info => Some(AuditSeverity::Info),
+            FailOn::Never => None,
+        }
+    }
+}
+
+/// 问题来源：解析阶段或文档审核。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Source {
+    Parser,
+    Audit,
+}
+
+#[derive(Debug, Serialize)]
+struct Finding {
+    severity: AuditSeverity,
+    source: Source,
+    /// 审核检查项或解析问题类别，例如 `missing_block`、`unsupported_entity`。
+    check: &'static str,
+    /// 实体类型，例如 `LINE`。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entity: Option<String>,
+    /// DXF 实体句柄（组码 5）。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    handle: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block: Option<String>,
+    /// DXF 中实体起始的行号。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    message: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct Counts {
+    errors: usize,
+    warnings: usize,
+    infos: usize,
+}
+
+impl Counts {
+    fn add(&mut self, severity: AuditSeverity) {
+        match severity {
+            AuditSeverity::Error => self.errors += 1,
+            AuditSeverity::Warning => self.warnings += 1,
+            AuditSeverity::Info => self.infos += 1,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct FileReport {
+    path: PathBuf,
+    #[serde(flatten)]
+    counts: Counts,
+    findings: Vec<Finding>,
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    files: usize,
+    #[serde(flatten)]
+    counts: Counts,
+    passed: bool,
+    reports: Vec<FileReport>,
+}
+
+/// 执行 `validate`，返回进程退出码。
+pub fn run(args: &ValidateArgs, config: &AppConfig) -> i32 {
+    let mut inputs = Vec::new();
+    let mut reports = Vec::new();
+    for pattern in &args.inputs {
+        let matched = expand_input(pattern);
+        if matched.is_empty() {
+            reports.push(FileReport {
+                path: PathBuf::from(pattern),
+                counts: Counts::default(),
+                findings: vec![load_failure(format!("没有匹配的图纸文件：{pattern}"))],
+            });
+        }
+        inputs.extend(matched);
+    }
+    let mut seen = HashSet::new();
+    inputs.retain(|path| seen.insert(path.clone()));
+    reports.extend(inputs.iter().map(|path| validate_file(path, config)));
+
+    let mut counts = Counts::default();
+    for report in &mut reports {
+        report.counts = Counts::default();
+        for finding in &report.findings {
+            report.counts.add(finding.severity);
+            counts.add(finding.severity);
+        }
+    }
+    let passed = args.fail_on.threshold().is_none_or(|threshold| {
+        reports
+            .iter()
+            .flat_map(|report| &report.findings)
+            .all(|finding| finding.severity < threshold)
+    });
+    let report = Report {
+        files: reports.len(),
+        counts,
+        passed,
+        reports,
+    };
+    print_report(&report, args.json);
+    if passed { 0 } else { 1 }
+}
+
+fn validate_file(path: &Path, config: &AppConfig) -> FileReport {
+    let findings = match load(path, config) {
+        Ok((document, diagnostics)) => collect_findings(&document, diagnostics.as_ref()),
+        Err(err) => vec![load_failure(err.to_string())],
+    };
+    FileReport {
+        path: path.to_path_buf(),
+        counts: Counts::default(),
+        findings,
+    }
+}
+
+/// DXF 使用诊断模式读取，其他格式按内容识别后普通读取。
+fn load(path: &Path, config: &AppConfig) -> Result<(Document, Option<DxfDiagnostics>), IoError> {
+    let bytes = fs::read(path).map_err(|source| IoError::ReadError {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    if DocumentFormat::detect(&bytes) == DocumentFormat::Dxf {
+        let (document, diagnostics) = DxfFacade::new()
+            .with_image_resources(&config.resources)
+            .load_with_diagnostics(path)?;
+        Ok((document, Some(diagnostics)))
+    } else {
+        Ok((open_document(path)?, None))
+    }
+}
+
+fn collect_findings(document: &Document, diagnostics: Option<&DxfDiagnostics>) -> Vec<Finding> {
+    let mut findings: Vec<Finding> = diagnostics
+        .into_iter()
+        .flat_map(|diagnostics| &diagnostics.findings)
+        .map(|diagnostic| Finding {
+            severity: diagnostic.severity,
+            source: Source::Parser,
+            check: if diagnostic.severity == AuditSeverity::Error {
+                "invalid_entity"
+            } else {
+                "unsupported_entity"
+            },
+            entity: Some(diagnostic.entity.clone()),
+            handle: diagnostic.handle.clone(),
+            block: diagnostic.block.clone(),
+            line: Some(diagnostic.line),
+            message: diagnostic.message.clone(),
+        })
+        .collect();
+    findings.extend(document.audit().into_iter().map(|finding| {
+        Finding {
+            severity: finding.severity,
+            source: Source::Audit,
+            check: finding.check,
+            entity: finding
+                .entity
+                .and_then(|id| document.entity(id))
+                .map(|entity| entity.kind_name().to_string()),
+            handle: finding.entity.and_then(|id| {
+                diagnostics
+                    .and_then(|diagnostics| diagnostics.entity_handle(id))
+                    .map(str::to_string)
+            }),
+            block: finding.block,
+            line: None,
+            message: finding.message,
+        }
+    }));
+    // 严重问题在前，同级保持解析与审核的原始顺序
+    findings.sort_by_key(|finding| Reverse(finding.severity));
+    findings
+}
+
+fn load_failure(message: String) -> Finding {
+    Finding {
+        severity: AuditSeverity::Error,
+        source: Source::Parser,
+        check: "load_failed",
+        entity: None,
+        handle: None,
+        block: None,
+        line: None,
+        message,
+    }
+}
+
+fn severity_label(severity: AuditSeverity) -> &'static str {
+    match severity {
+        AuditSeverity::Error => "error",
+        AuditSeverity::Warning => "warning",
+        AuditSeverity::Info => "info",
+    }
+}
+
+fn print_report(report: &Report, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(report) {
+            Ok(text) => println!("{text}"),
+            Err(err) => eprintln!("报告序列化失败: {err}"),
+        }
+        return;
+    }
+    for file in &report.reports {
+        for finding in &file.findings {
+            let location = match finding.line {
+                Some(line) => format!("{}:{line}", file.path.display()),
+                None => file.path.display().to_string(),
+            };
+            let mut subject = Vec::new();
+            if let Some(block) = &finding.block {
+                subject.push(format!("块 {block}"));
+            }
+            if let Some(entity) = &finding.entity {
+                subject.push(entity.clone());
+            }
+            if let Some(handle) = &finding.handle {
+                subject.push(format!("#{handle}"));
+            }
+            let subject = if subject.is_empty() {
+                String::new()
+            } else {
+                format!(" {}", subject.join(" "))
+            };
+            println!(
+                "{location}: {} [{}]{subject}: {}",
+                severity_label(finding.severity),
+                finding.check,
+                finding.message
+            );
+        }
+    }
+    println!(
+        "校验完成：{} 个文件，错误 {}，警告 {}，提示 {}",
+        report.files, report.counts.errors, report.counts.warnings, report.counts.infos
+    );
+}
@@ -0,0 +1,186 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use serde_json::Value;
+use tempfile::tempdir;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../zcad-io/tests/data")
+        .join(format!("{name}.dxf"))
+}
+
+/// 在临时目录中准备 `drawings/a.dxf`、`drawings/b.dxf` 与 `drawings/nested/c.dxf`。
+fn setup_drawings() -> tempfile::TempDir {
+    let dir = tempdir().expect("tempdir");
+    let drawings = dir.path().join("drawings");
+    fs::create_dir_all(drawings.join("nested")).unwrap();
+    fs::copy(fixture("basic_entities"), drawings.join("a.dxf")).unwrap();
+    fs::copy(fixture("hatch_simple"), drawings.join("b.dxf")).unwrap();
+    fs::copy(fixture("block_insert"), drawings.join("nested/c.dxf")).unwrap();
+    dir
+}
+
+fn convert(dir: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("zcad-app"));
+    cmd.current_dir(dir).arg("convert").args(args);
+    cmd
+}
+
+fn json_report(output: &std::process::Output) -> Value {
+    serde_json::from_slice(&output.stdout).expect("报告应为 JSON")
+}
+
+#[test]
+fn convert_expands_globs_and_writes_outputs() {
+    let dir = setup_drawings();
+    let output = convert(
+        dir.path(),
+        &[
+            "drawings/*.dxf",
+            "--to",
+            "svg",
+            "-o",
+            "out",
+            "--json",
+            "-j",
+            "2",
+        ],
+    )
+    .output()
+    .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let report = json_report(&output);
+    assert_eq!(report["format"], "svg");
+    assert_eq!(report["converted"], 2);
+    assert_eq!(report["failed"], 0);
+    assert_eq!(report["files"].as_array().unwrap().len(), 2);
+    assert!(report["files"][0]["entities"].as_u64().unwrap() > 0);
+    for name in ["a.svg", "b.svg"] {
+        let svg = fs::read_to_string(dir.path().join("out").join(name)).unwrap();
+        assert!(svg.starts_with("<svg"), "{name}");
+    }
+    assert!(!dir.path().join("out/c.svg").exists());
+
+    // 已存在的输出默认跳过
+    convert(dir.path(), &["drawings/*.dxf", "--to", "svg", "-o", "out"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("成功 0，跳过 2，失败 0"));
+}
+
+#[test]
+fn convert_recurses_with_double_star_next_to_inputs() {
+    let dir = setup_drawings();
+    let output = convert(dir.path(), &["drawings/**/*.dxf", "--to", "png", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(json_report(&output)["converted"], 3);
+    let png = fs::read(dir.path().join("drawings/nested/c.png")).unwrap();
+    assert!(png.starts_with(b"\x89PNG"));
+
+    convert(dir.path(), &["drawings", "--to", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("成功 2，跳过 0，失败 0"));
+    let json: Value =
+        serde_json::from_str(&fs::read_to_string(dir.path().join("drawings/a.json")).unwrap())
+            .expect("原生 JSON 无效");
+    assert!(json.is_object());
+}
+
+#[test]
+fn convert_reports_failures_with_exit_code() {
+    let dir = setup_drawings();
+    fs::write(dir.path().join("drawings/legacy.dwg"), b"AC1015\0\0\0\0").unwrap();
+    let output = convert(
+        dir.path(),
+        &[
+            "drawings/legacy.dwg",
+            "drawings/a.dxf",
+            "missing/*.dxf",
+            "--to",
+            "dxf",
+            "-o",
+            "out",
+            "--json",
+        ],
+    )
+    .output()
+    .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let report = json_report(&output);
+    assert_eq!(report["converted"], 1);
+    assert_eq!(report["failed"], 2);
+    let files = report["files"].as_array().unwrap();
+    assert_eq!(files[0]["status"], "failed");
+    assert!(files[0]["message"].as_str().unwrap().contains("AC1015"));
+    assert_eq!(files[2]["input"], "missing/*.dxf");
+
+    // 不指定输出目录时 DXF 转 DXF 会覆盖输入，应拒绝
+    convert(
+        dir.path(),
+        &["drawings/a.dxf", "--to", "dxf", "--overwrite"],
+    )
+    .assert()
+    .failure()
+    .stdout(predicate::str::contains("输出文件与输入相同"));
+}
+
+#[test]
+fn convert_purge_drops_unreferenced_layers() {
+    let dir = tempdir().expect("tempdir");
+    // 图层表中多出一个没有实体使用的图层
+    let dxf = concat!(
+        "0\nSECTION\n",
+        "2\nTABLES\n",
+        "0\nTABLE\n",
+        "2\nLAYER\n",
+        "0\nLAYER\n",
+        "2\nUNUSED\n",
+        "70\n0\n",
+        "62\n3\n",
+        "6\nCONTINUOUS\n",
+        "0\nENDTAB\n",
+        "0\nENDSEC\n",
+        "0\nSECTION\n",
+        "2\nENTITIES\n",
+        "0\nLINE\n",
+        "8\n0\n",
+        "10\n0.0\n",
+        "20\n0.0\n",
+        "11\n1.0\n",
+        "21\n0.0\n",
+        "0\nENDSEC\n",
+        "0\nEOF\n",
+    );
+    fs::write(dir.path().join("plan.dxf"), dxf).unwrap();
+
+    let layers = |dir: &Path| -> Vec<String> {
+        let text = fs::read_to_string(dir.join("plan.json")).unwrap();
+        let document: Value = serde_json::from_str(&text).unwrap();
+        let mut names: Vec<String> = document["document"]["layers"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        names.sort();
+        names
+    };
+    convert(dir.path(), &["plan.dxf", "--to", "json"])
+        .assert()
+        .success();
+    assert_eq!(layers(dir.path()), vec!["0", "UNUSED"]);
+    convert(
+        dir.path(),
+        &["plan.dxf", "--to", "json", "--purge", "--overwrite"],
+    )
+    .assert()
+    .success();
+    assert_eq!(layers(dir.path()), vec!["0"]);
+}
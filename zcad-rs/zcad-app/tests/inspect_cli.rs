@@ -0,0 +1,115 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use serde_json::Value;
+use tempfile::tempdir;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../zcad-io/tests/data")
+        .join(format!("{name}.dxf"))
+}
+
+fn zcad(args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("zcad-app"));
+    cmd.args(args);
+    cmd
+}
+
+fn json_output(args: &[&str]) -> Value {
+    let output = zcad(args).output().unwrap();
+    assert!(output.status.success(), "{output:?}");
+    serde_json::from_slice(&output.stdout).expect("输出应为 JSON")
+}
+
+#[test]
+fn info_reports_version_bounds_and_counts() {
+    let path = fixture("mleader_block");
+    let info = json_output(&["info", path.to_str().unwrap(), "--json"]);
+    assert_eq!(info["format"], "dxf");
+    assert_eq!(info["version"], "AC1032");
+    assert_eq!(info["release"], "R2018");
+    assert_eq!(info["entities"], 1);
+    assert_eq!(info["blocks"], 1);
+
+    let path = fixture("block_insert");
+    let info = json_output(&["info", path.to_str().unwrap(), "--json"]);
+    assert!(info.get("version").is_none());
+    assert_eq!(info["bounds"]["min"], serde_json::json!([10.0, 10.0]));
+    // 块内直线经 2 倍缩放与 45° 旋转后覆盖插入点右上方
+    let size = info["bounds"]["size"].as_array().unwrap();
+    for value in size {
+        assert!(
+            (value.as_f64().unwrap() - 50f64.sqrt()).abs() < 1e-9,
+            "{size:?}"
+        );
+    }
+
+    zcad(&["info", path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("对象：1，图层：1，块定义：1"))
+        .stdout(predicate::str::contains("外部依赖：无"));
+}
+
+#[test]
+fn info_lists_found_and_missing_dependencies() {
+    let dir = tempdir().expect("tempdir");
+    fs::copy(fixture("image_basic"), dir.path().join("image_basic.dxf")).unwrap();
+    fs::create_dir(dir.path().join("images")).unwrap();
+    fs::write(dir.path().join("images/sample.png"), b"\x89PNG").unwrap();
+    let path = dir.path().join("image_basic.dxf");
+    let info = json_output(&["info", path.to_str().unwrap(), "--json"]);
+    let image = &info["dependencies"][0];
+    assert_eq!(image["kind"], "image");
+    assert_eq!(image["path"], "images/sample.png");
+    assert_eq!(image["references"], 1);
+    assert!(image["resolved"].as_str().unwrap().ends_with("sample.png"));
+
+    let path = fixture("pdf_underlay");
+    zcad(&["info", path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("外部依赖：2（缺失 2）"))
+        .stdout(predicate::str::contains("[pdf] site-plan"));
+
+    let path = fixture("xref_host");
+    let info = json_output(&["info", path.to_str().unwrap(), "--json"]);
+    let xref = &info["dependencies"][0];
+    assert_eq!(xref["kind"], "xref");
+    assert_eq!(xref["name"], "SITE");
+    assert!(
+        xref["resolved"]
+            .as_str()
+            .unwrap()
+            .ends_with("xref_site.dxf")
+    );
+}
+
+#[test]
+fn stats_counts_types_layers_and_block_usage() {
+    let path = fixture("block_insert");
+    let stats = json_output(&["stats", path.to_str().unwrap(), "--json"]);
+    assert_eq!(stats["entities"], 1);
+    assert_eq!(stats["by_type"][0]["kind"], "INSERT");
+    assert_eq!(stats["by_layer"][0]["layer"], "0");
+    assert_eq!(stats["by_layer"][0]["by_type"]["INSERT"], 1);
+    assert_eq!(stats["blocks"][0]["name"], "MYBLOCK");
+    assert_eq!(stats["blocks"][0]["references"], 1);
+    assert!(stats["estimated_bytes"].as_u64().unwrap() > 0);
+
+    let path = fixture("basic_entities");
+    zcad(&["stats", path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("按类型："))
+        .stdout(predicate::str::contains("LINE"));
+
+    zcad(&["stats", "missing.dxf"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("无法读取 missing.dxf"));
+}
@@ -0,0 +1,90 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use serde_json::Value;
+use tempfile::tempdir;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../zcad-io/tests/data")
+        .join(format!("{name}.dxf"))
+}
+
+fn validate(args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("zcad-app"));
+    cmd.arg("validate").args(args);
+    cmd
+}
+
+#[test]
+fn validate_reports_parser_and_audit_findings_as_json() {
+    let path = fixture("invalid/diagnostics_mixed");
+    let output = validate(&[path.to_str().unwrap(), "--json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let report: Value = serde_json::from_slice(&output.stdout).expect("报告应为 JSON");
+    assert_eq!(report["passed"], false);
+    assert_eq!(report["errors"], 2);
+    assert_eq!(report["warnings"], 3);
+    assert_eq!(report["infos"], 1);
+
+    let findings = report["reports"][0]["findings"].as_array().unwrap();
+    // 错误在前：先解析问题，后审核问题
+    assert_eq!(findings[0]["severity"], "error");
+    assert_eq!(findings[0]["source"], "parser");
+    assert_eq!(findings[0]["check"], "invalid_entity");
+    assert_eq!(findings[0]["handle"], "1C");
+    assert_eq!(findings[0]["line"], 73);
+    assert_eq!(findings[1]["source"], "audit");
+    assert_eq!(findings[1]["check"], "missing_block");
+    assert_eq!(findings[1]["entity"], "INSERT");
+    assert_eq!(findings[1]["handle"], "1E");
+    let degenerate = findings
+        .iter()
+        .find(|finding| finding["check"] == "degenerate_geometry")
+        .unwrap();
+    assert_eq!(degenerate["severity"], "warning");
+    assert_eq!(degenerate["handle"], "1D");
+    assert_eq!(findings.last().unwrap()["check"], "unused_block");
+    assert_eq!(findings.last().unwrap()["block"], "MARK");
+}
+
+#[test]
+fn validate_fail_threshold_controls_exit_code() {
+    let clean = fixture("block_insert");
+    validate(&[clean.to_str().unwrap(), "--fail-on", "info"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "校验完成：1 个文件，错误 0，警告 0，提示 0",
+        ));
+
+    let mixed = fixture("invalid/diagnostics_mixed");
+    validate(&[mixed.to_str().unwrap(), "--fail-on", "never"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "diagnostics_mixed.dxf:63: warning [unsupported_entity] POINT #1B",
+        ));
+}
+
+#[test]
+fn validate_reports_unreadable_inputs() {
+    let dir = tempdir().expect("tempdir");
+    fs::write(dir.path().join("broken.dxf"), "0\nSECTION\n2\nENTITIES\n").unwrap();
+    let broken = dir.path().join("broken.dxf");
+    let output = validate(&[broken.to_str().unwrap(), "missing/*.dxf", "--json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["files"], 2);
+    assert_eq!(report["reports"][0]["path"], "missing/*.dxf");
+    for file in report["reports"].as_array().unwrap() {
+        assert_eq!(file["findings"][0]["check"], "load_failed");
+    }
+}
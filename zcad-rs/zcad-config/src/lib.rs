@@ -5,6 +5,10 @@ use std::path::{Path, PathBuf};
 use serde::Deserialize;
 use thiserror::Error;
 
+mod recent;
+
+pub use recent::{DrawingStats, RECENT_FILES_NAME, RecentFileEntry, RecentFiles, RecentViewport};
+
 /// 应用配置的根结构。
 #[derive(Debug, Clone, Deserialize)]
 pub struct AppConfig {
@@ -14,6 +18,12 @@ pub struct AppConfig {
     pub frontend: FrontendConfig,
     #[serde(default)]
     pub resources: ResourceConfig,
+    #[serde(default)]
+    pub recent: RecentConfig,
+    #[serde(default)]
+    pub geometry: GeometryConfig,
+    #[serde(default)]
+    pub grid: GridConfig,
 }
 
 impl Default for AppConfig {
@@ -22,6 +32,9 @@ impl Default for AppConfig {
             logging: LoggingConfig::default(),
             frontend: FrontendConfig::default(),
             resources: ResourceConfig::default(),
+            recent: RecentConfig::default(),
+            geometry: GeometryConfig::default(),
+            grid: GridConfig::default(),
         }
     }
 }
@@ -60,6 +73,28 @@ impl AppConfig {
             Ok(Self::default())
         }
     }
+
+    /// 配置目录：`ZCAD_CONFIG` 所在目录，否则为 `./config`。
+    pub fn config_dir() -> PathBuf {
+        if let Some(path) = env::var_os("ZCAD_CONFIG")
+            && let Some(parent) = Path::new(&path).parent()
+        {
+            return parent.to_path_buf();
+        }
+        env::current_dir()
+            .map(|dir| dir.join("config"))
+            .unwrap_or_else(|_| PathBuf::from("config"))
+    }
+
+    /// 打开最近文件列表，默认存放在配置目录下的 `recent.toml`。
+    pub fn open_recent_files(&self) -> Result<RecentFiles, ConfigError> {
+        let path = self
+            .recent
+            .file
+            .clone()
+            .unwrap_or_else(|| Self::config_dir().join(RECENT_FILES_NAME));
+        RecentFiles::load(path, self.recent.max_entries)
+    }
 }
 
 /// 日志配置，支持设置默认等级。
@@ -145,6 +180,97 @@ impl Default for ResourceConfig {
     }
 }
 
+/// 最近文件列表配置。
+#[derive(Debug, Clone, Deserialize)]
+pub struct RecentConfig {
+    #[serde(default = "RecentConfig::default_max_entries")]
+    pub max_entries: usize,
+    /// 覆盖默认的列表存放路径。
+    #[serde(default)]
+    pub file: Option<PathBuf>,
+}
+
+impl RecentConfig {
+    fn default_max_entries() -> usize {
+        10
+    }
+}
+
+impl Default for RecentConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: Self::default_max_entries(),
+            file: None,
+        }
+    }
+}
+
+/// 几何比较容差，作为新建与加载文档的默认容差；加载 DXF 时还会按图纸范围放大长度容差。
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct GeometryConfig {
+    #[serde(default = "GeometryConfig::default_linear_tolerance")]
+    pub linear_tolerance: f64,
+    /// 角度容差（弧度）。
+    #[serde(default = "GeometryConfig::default_angular_tolerance")]
+    pub angular_tolerance: f64,
+}
+
+impl GeometryConfig {
+    fn default_linear_tolerance() -> f64 {
+        1e-9
+    }
+
+    fn default_angular_tolerance() -> f64 {
+        1e-9
+    }
+}
+
+impl Default for GeometryConfig {
+    fn default() -> Self {
+        Self {
+            linear_tolerance: Self::default_linear_tolerance(),
+            angular_tolerance: Self::default_angular_tolerance(),
+        }
+    }
+}
+
+/// 栅格与栅格捕捉的默认设置，新建场景时采用。
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct GridConfig {
+    /// 栅格显示间距。
+    #[serde(default = "GridConfig::default_spacing")]
+    pub spacing: f64,
+    /// 栅格捕捉间距；等轴测模式下为沿等轴测轴的间距。
+    #[serde(default = "GridConfig::default_spacing")]
+    pub snap_spacing: f64,
+    #[serde(default)]
+    pub visible: bool,
+    /// 是否启用栅格捕捉。
+    #[serde(default)]
+    pub snap: bool,
+    /// 使用等轴测栅格。
+    #[serde(default)]
+    pub isometric: bool,
+}
+
+impl GridConfig {
+    fn default_spacing() -> f64 {
+        10.0
+    }
+}
+
+impl Default for GridConfig {
+    fn default() -> Self {
+        Self {
+            spacing: Self::default_spacing(),
+            snap_spacing: Self::default_spacing(),
+            visible: false,
+            snap: false,
+            isometric: false,
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum ConfigError {
     #[error("读取配置文件 {path:?} 失败: {source}")]
@@ -159,6 +285,18 @@ pub enum ConfigError {
         #[source]
         source: toml::de::Error,
     },
+    #[error("写入文件 {path:?} 失败: {source}")]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("序列化文件 {path:?} 失败: {source}")]
+    Serialize {
+        path: PathBuf,
+        #[source]
+        source: toml::ser::Error,
+    },
     #[error("{message}")]
     Context {
         message: String,
@@ -180,6 +318,11 @@ mod tests {
         assert!(cfg.resources.image_roots.is_empty());
         assert!(cfg.resources.runtime_root.is_none());
         assert!(cfg.resources.auto_copy_runtime);
+        assert_eq!(cfg.recent.max_entries, 10);
+        assert!(cfg.recent.file.is_none());
+        assert_eq!(cfg.geometry.linear_tolerance, 1e-9);
+        assert_eq!(cfg.grid.spacing, 10.0);
+        assert!(!cfg.grid.snap && !cfg.grid.isometric);
     }
 
     #[test]
@@ -199,6 +342,18 @@ mod tests {
             image_roots = ["../assets", "../textures"]
             runtime_root = "../runtime"
             auto_copy_runtime = false
+
+            [recent]
+            max_entries = 5
+            file = "recent.toml"
+
+            [geometry]
+            linear_tolerance = 1e-6
+
+            [grid]
+            snap_spacing = 2.5
+            snap = true
+            isometric = true
             "#
         )
         .unwrap();
@@ -216,5 +371,12 @@ mod tests {
             Some("../runtime".to_string())
         );
         assert!(!cfg.resources.auto_copy_runtime);
+        assert_eq!(cfg.recent.max_entries, 5);
+        assert_eq!(cfg.recent.file, Some(PathBuf::from("recent.toml")));
+        assert_eq!(cfg.geometry.linear_tolerance, 1e-6);
+        assert_eq!(cfg.geometry.angular_tolerance, 1e-9);
+        assert_eq!(cfg.grid.spacing, 10.0);
+        assert_eq!(cfg.grid.snap_spacing, 2.5);
+        assert!(cfg.grid.snap && cfg.grid.isometric && !cfg.grid.visible);
     }
 }
@@ -0,0 +1,249 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ConfigError;
+
+/// 最近文件列表的默认文件名，位于配置目录下。
+pub const RECENT_FILES_NAME: &str = "recent.toml";
+
+/// 上次关闭图纸时的视口位置，用于重新打开时恢复视图。
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RecentViewport {
+    pub center: [f64; 2],
+    pub zoom: f64,
+}
+
+/// 打开图纸时统计的快速概览，供启动页展示。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DrawingStats {
+    #[serde(default)]
+    pub entity_count: usize,
+    #[serde(default)]
+    pub layer_count: usize,
+    #[serde(default)]
+    pub block_count: usize,
+    #[serde(default)]
+    pub file_size: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentFileEntry {
+    pub path: PathBuf,
+    /// 最近一次打开的时间（Unix 秒）。
+    #[serde(default)]
+    pub last_opened: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub viewport: Option<RecentViewport>,
+    #[serde(default)]
+    pub stats: DrawingStats,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RecentFilesData {
+    #[serde(default)]
+    files: Vec<RecentFileEntry>,
+}
+
+/// 持久化的最近文件列表，按最近打开时间倒序排列。
+#[derive(Debug, Clone)]
+pub struct RecentFiles {
+    path: PathBuf,
+    max_entries: usize,
+    entries: Vec<RecentFileEntry>,
+}
+
+impl RecentFiles {
+    /// 从指定文件读取最近文件列表；文件不存在时返回空列表。
+    pub fn load(path: impl Into<PathBuf>, max_entries: usize) -> Result<Self, ConfigError> {
+        let path = path.into();
+        let mut entries = if path.exists() {
+            let content = fs::read_to_string(&path).map_err(|source| ConfigError::Io {
+                path: path.clone(),
+                source,
+            })?;
+            toml::from_str::<RecentFilesData>(&content)
+                .map_err(|source| ConfigError::Parse {
+                    path: path.clone(),
+                    source,
+                })?
+                .files
+        } else {
+            Vec::new()
+        };
+        entries.truncate(max_entries);
+        Ok(Self {
+            path,
+            max_entries,
+            entries,
+        })
+    }
+
+    /// 将列表写回磁盘，必要时创建配置目录。
+    pub fn save(&self) -> Result<(), ConfigError> {
+        if let Some(parent) = self.path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent).map_err(|source| ConfigError::Write {
+                path: parent.to_path_buf(),
+                source,
+            })?;
+        }
+        let data = RecentFilesData {
+            files: self.entries.clone(),
+        };
+        let content = toml::to_string_pretty(&data).map_err(|source| ConfigError::Serialize {
+            path: self.path.clone(),
+            source,
+        })?;
+        fs::write(&self.path, content).map_err(|source| ConfigError::Write {
+            path: self.path.clone(),
+            source,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &RecentFileEntry> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, path: impl AsRef<Path>) -> Option<&RecentFileEntry> {
+        let path = path.as_ref();
+        self.entries.iter().find(|entry| entry.path == path)
+    }
+
+    /// 上次记录的视口；尚未保存过视口时返回 `None`。
+    pub fn last_viewport(&self, path: impl AsRef<Path>) -> Option<RecentViewport> {
+        self.get(path).and_then(|entry| entry.viewport)
+    }
+
+    /// 记录一次打开操作：条目移到列表首位并刷新统计，已保存的视口会被保留。
+    pub fn record_open(&mut self, path: impl Into<PathBuf>, stats: DrawingStats) {
+        let path = path.into();
+        let viewport = self.take(&path).and_then(|entry| entry.viewport);
+        self.entries.insert(
+            0,
+            RecentFileEntry {
+                path,
+                last_opened: unix_now(),
+                viewport,
+                stats,
+            },
+        );
+        self.entries.truncate(self.max_entries);
+    }
+
+    /// 更新已记录文件的视口，文件不在列表中时返回 `false`。
+    pub fn update_viewport(&mut self, path: impl AsRef<Path>, viewport: RecentViewport) -> bool {
+        let path = path.as_ref();
+        match self.entries.iter_mut().find(|entry| entry.path == path) {
+            Some(entry) => {
+                entry.viewport = Some(viewport);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove(&mut self, path: impl AsRef<Path>) -> Option<RecentFileEntry> {
+        self.take(path.as_ref())
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn take(&mut self, path: &Path) -> Option<RecentFileEntry> {
+        let index = self.entries.iter().position(|entry| entry.path == path)?;
+        Some(self.entries.remove(index))
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(entity_count: usize) -> DrawingStats {
+        DrawingStats {
+            entity_count,
+            ..DrawingStats::default()
+        }
+    }
+
+    #[test]
+    fn record_open_moves_entry_to_front_and_keeps_viewport() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let mut recent = RecentFiles::load(dir.path().join(RECENT_FILES_NAME), 2)
+            .expect("load empty recent list");
+        assert!(recent.is_empty());
+
+        recent.record_open("a.dxf", stats(1));
+        let viewport = RecentViewport {
+            center: [5.0, -3.0],
+            zoom: 2.5,
+        };
+        assert!(recent.update_viewport("a.dxf", viewport));
+        assert!(!recent.update_viewport("missing.dxf", viewport));
+
+        recent.record_open("b.dxf", stats(2));
+        recent.record_open("a.dxf", stats(3));
+        let paths: Vec<_> = recent.entries().map(|entry| entry.path.clone()).collect();
+        assert_eq!(paths, vec![PathBuf::from("a.dxf"), PathBuf::from("b.dxf")]);
+        assert_eq!(recent.last_viewport("a.dxf"), Some(viewport));
+        assert_eq!(recent.get("a.dxf").unwrap().stats.entity_count, 3);
+
+        recent.record_open("c.dxf", stats(4));
+        assert_eq!(recent.len(), 2);
+        assert!(recent.get("b.dxf").is_none());
+    }
+
+    #[test]
+    fn save_and_reload_round_trip() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("nested").join(RECENT_FILES_NAME);
+        let mut recent = RecentFiles::load(&path, 10).expect("load empty recent list");
+        recent.record_open(
+            "plan.dxf",
+            DrawingStats {
+                entity_count: 12,
+                layer_count: 3,
+                block_count: 1,
+                file_size: 2048,
+            },
+        );
+        recent.update_viewport(
+            "plan.dxf",
+            RecentViewport {
+                center: [1.0, 2.0],
+                zoom: 0.5,
+            },
+        );
+        recent.save().expect("save recent list");
+
+        let reloaded = RecentFiles::load(&path, 10).expect("reload recent list");
+        assert_eq!(
+            reloaded.entries().collect::<Vec<_>>(),
+            recent.entries().collect::<Vec<_>>()
+        );
+    }
+}
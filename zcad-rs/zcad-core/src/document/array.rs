@@ -0,0 +1,287 @@
+//! 复制与阵列（COPY / ARRAY）。
+//!
+//! 副本按原实体克隆后施加变换，保留图层与特性覆盖并分配新编号。复制的填充不再与原边界对象关联，
+//! 标注的匿名块随变换清除、由定义点重新生成。阵列沿用经典 ARRAY 命令的参数：原对象位于第一项，
+//! 只生成其余各项。
+
+use std::collections::HashSet;
+use std::f64::consts::TAU;
+
+use crate::geometry::{Point2, Transform2, Vector2};
+
+use super::{Document, Entity, EntityId};
+
+/// 矩形阵列参数。行沿阵列的 Y 方向、列沿 X 方向排列，间距为负时向反方向排列。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RectangularArray {
+    pub rows: usize,
+    pub columns: usize,
+    pub row_spacing: f64,
+    pub column_spacing: f64,
+    /// 阵列整体的旋转角（弧度），对应经典命令中的捕捉角。
+    pub angle: f64,
+}
+
+impl Default for RectangularArray {
+    fn default() -> Self {
+        Self {
+            rows: 1,
+            columns: 1,
+            row_spacing: 1.0,
+            column_spacing: 1.0,
+            angle: 0.0,
+        }
+    }
+}
+
+/// 环形阵列参数。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PolarArray {
+    pub center: Point2,
+    /// 项目总数，包括原对象。
+    pub count: usize,
+    /// 填充角（弧度），正值逆时针；整圆时各项均分一周，否则首末两项分别位于两端。
+    pub fill_angle: f64,
+    /// 是否随阵列旋转对象；为 `false` 时对象只按其范围中心平移。
+    pub rotate_items: bool,
+}
+
+impl Default for PolarArray {
+    fn default() -> Self {
+        Self {
+            center: Point2::new(0.0, 0.0),
+            count: 1,
+            fill_angle: TAU,
+            rotate_items: true,
+        }
+    }
+}
+
+impl Document {
+    /// 按偏移复制实体，返回副本编号（与去重后的选择集顺序一致）；不存在或重复的编号被忽略。
+    pub fn copy_entities(
+        &mut self,
+        ids: impl IntoIterator<Item = EntityId>,
+        offset: Vector2,
+    ) -> Vec<EntityId> {
+        let selection = self.selection(ids);
+        let transform = Transform2::translation(offset);
+        self.copy_selection(&selection, || transform)
+    }
+
+    /// 矩形阵列，返回新生成的实体编号，按行、列、选择集顺序排列。
+    pub fn array_rectangular(
+        &mut self,
+        ids: impl IntoIterator<Item = EntityId>,
+        array: &RectangularArray,
+    ) -> Vec<EntityId> {
+        let selection = self.selection(ids);
+        let rotation = Transform2::rotation(array.angle);
+        let mut created = Vec::new();
+        for row in 0..array.rows {
+            for column in 0..array.columns {
+                if row == 0 && column == 0 {
+                    continue;
+                }
+                let offset = rotation.apply_vector(Vector2::new(
+                    column as f64 * array.column_spacing,
+                    row as f64 * array.row_spacing,
+                ));
+                let transform = Transform2::translation(offset);
+                created.extend(self.copy_selection(&selection, || transform));
+            }
+        }
+        created
+    }
+
+    /// 环形阵列，返回新生成的实体编号，按阵列项、选择集顺序排列。
+    pub fn array_polar(
+        &mut self,
+        ids: impl IntoIterator<Item = EntityId>,
+        array: &PolarArray,
+    ) -> Vec<EntityId> {
+        let selection = self.selection(ids);
+        if array.count < 2 {
+            return Vec::new();
+        }
+        let is_full_circle = (array.fill_angle.abs() - TAU).abs() <= 1e-9;
+        let divisions = if is_full_circle {
+            array.count
+        } else {
+            array.count - 1
+        };
+        let step = array.fill_angle / divisions as f64;
+        // 不旋转对象时，各对象按自身范围中心的转动量平移
+        let anchors: Vec<Option<Point2>> = selection
+            .iter()
+            .map(|id| self.entity_bounds(*id).map(|bounds| bounds.center()))
+            .collect();
+        let mut created = Vec::new();
+        for item in 1..array.count {
+            let rotation = Transform2::rotation_about(array.center, step * item as f64);
+            let mut anchors = anchors.iter();
+            created.extend(self.copy_selection(&selection, || {
+                match anchors.next().copied().flatten() {
+                    Some(anchor) if !array.rotate_items => {
+                        let moved = rotation.apply_point(anchor);
+                        Transform2::translation(Vector2::new(
+                            moved.x() - anchor.x(),
+                            moved.y() - anchor.y(),
+                        ))
+                    }
+                    _ => rotation,
+                }
+            }));
+        }
+        created
+    }
+
+    /// 去重并过滤不存在的编号，保持给定顺序。
+    fn selection(&self, ids: impl IntoIterator<Item = EntityId>) -> Vec<EntityId> {
+        let mut seen = HashSet::new();
+        ids.into_iter()
+            .filter(|id| self.entity(*id).is_some() && seen.insert(*id))
+            .collect()
+    }
+
+    /// 逐个复制选择集，`transform` 按选择集顺序为每个实体给出变换。
+    fn copy_selection(
+        &mut self,
+        selection: &[EntityId],
+        mut transform: impl FnMut() -> Transform2,
+    ) -> Vec<EntityId> {
+        let mut created = Vec::with_capacity(selection.len());
+        for &id in selection {
+            let Some(mut entity) = self.entity(id).cloned() else {
+                continue;
+            };
+            entity.transform(&transform());
+            if let Entity::Hatch(hatch) = &mut entity {
+                hatch.is_associative = false;
+                for hatch_loop in &mut hatch.loops {
+                    hatch_loop.boundary_handles.clear();
+                    hatch_loop.boundary_entities.clear();
+                }
+            }
+            let properties = self.entity_properties(id).cloned();
+            let copy = self.add_entity(entity);
+            if let Some(properties) = properties {
+                self.set_entity_properties(copy, properties);
+            }
+            created.push(copy);
+        }
+        created
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use super::super::{EntityProperties, ObjectColor};
+    use super::*;
+
+    fn center(doc: &Document, id: EntityId) -> (f64, f64) {
+        let center = doc.entity_bounds(id).unwrap().center();
+        let round = |value: f64| (value * 1e9).round() / 1e9;
+        (round(center.x()), round(center.y()))
+    }
+
+    #[test]
+    fn copies_keep_layer_and_properties() {
+        let mut doc = Document::new();
+        let circle = doc.add_circle(Point2::new(0.0, 0.0), 1.0, "HOLE");
+        doc.set_entity_properties(
+            circle,
+            EntityProperties {
+                color: ObjectColor::Index(1),
+                ..EntityProperties::default()
+            },
+        );
+        let copies =
+            doc.copy_entities([circle, circle, EntityId::new(404)], Vector2::new(5.0, 0.0));
+        assert_eq!(copies.len(), 1);
+        assert_ne!(copies[0], circle);
+        assert_eq!(center(&doc, copies[0]), (5.0, 0.0));
+        assert_eq!(doc.entity(copies[0]).unwrap().layer_name(), "HOLE");
+        assert_eq!(
+            doc.entity_properties(copies[0]).map(|p| p.color),
+            Some(ObjectColor::Index(1))
+        );
+        assert_eq!(doc.entities_on_layer("HOLE").count(), 2);
+        assert_eq!(center(&doc, circle), (0.0, 0.0));
+    }
+
+    #[test]
+    fn rectangular_array_fills_rotated_grid() {
+        let mut doc = Document::new();
+        let circle = doc.add_circle(Point2::new(0.0, 0.0), 0.5, "0");
+        let created = doc.array_rectangular(
+            [circle],
+            &RectangularArray {
+                rows: 2,
+                columns: 3,
+                row_spacing: 4.0,
+                column_spacing: -2.0,
+                angle: FRAC_PI_2,
+            },
+        );
+        assert_eq!(created.len(), 5);
+        let centers: Vec<_> = created.iter().map(|id| center(&doc, *id)).collect();
+        // 旋转 90° 后列方向为 +Y（间距为负故向 -Y 排列），行方向为 -X
+        assert_eq!(
+            centers,
+            vec![
+                (0.0, -2.0),
+                (0.0, -4.0),
+                (-4.0, 0.0),
+                (-4.0, -2.0),
+                (-4.0, -4.0)
+            ]
+        );
+        assert!(
+            doc.array_rectangular([circle], &RectangularArray::default())
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn polar_array_spreads_items_over_fill_angle() {
+        let mut doc = Document::new();
+        let line = doc.add_line(Point2::new(9.0, 0.0), Point2::new(11.0, 0.0), "0");
+        let full = doc.array_polar(
+            [line],
+            &PolarArray {
+                count: 4,
+                ..PolarArray::default()
+            },
+        );
+        assert_eq!(
+            full.iter().map(|id| center(&doc, *id)).collect::<Vec<_>>(),
+            vec![(0.0, 10.0), (-10.0, 0.0), (0.0, -10.0)]
+        );
+        let Some(Entity::Line(rotated)) = doc.entity(full[0]) else {
+            unreachable!()
+        };
+        assert!((rotated.end.y() - 11.0).abs() < 1e-9);
+
+        // 半圆内 3 项：首末位于两端；不旋转时对象保持水平
+        let half = doc.array_polar(
+            [line],
+            &PolarArray {
+                count: 3,
+                fill_angle: PI,
+                rotate_items: false,
+                ..PolarArray::default()
+            },
+        );
+        assert_eq!(
+            half.iter().map(|id| center(&doc, *id)).collect::<Vec<_>>(),
+            vec![(0.0, 10.0), (-10.0, 0.0)]
+        );
+        let Some(Entity::Line(shifted)) = doc.entity(half[0]) else {
+            unreachable!()
+        };
+        assert!((shifted.end.x() - shifted.start.x() - 2.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,495 @@
+//! 关联标注：把标注与引线的定义点绑定到其他实体的特征点（端点、圆心等），
+//! 被引用的实体移动后由 [`Document::update_associative_dimensions`] 重新计算几何与测量值。
+//!
+//! 被引用的实体删除或不再具有该特征点时，对应的关联自动解除，标注保持最后的位置。
+
+use std::f64::consts::{FRAC_PI_2, TAU};
+use std::fmt;
+
+use glam::DVec2;
+use serde::{Deserialize, Serialize};
+
+use super::flatten::ccw_sweep;
+use super::{Document, Ellipse, Entity, EntityId};
+use crate::geometry::Point2;
+
+/// 实体上的特征点。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapRole {
+    /// 直线、圆弧、开放椭圆弧与多段线的起点。
+    Start,
+    End,
+    /// 直线或圆弧的中点。
+    Midpoint,
+    /// 圆、圆弧与椭圆的圆心。
+    Center,
+    /// 多段线第 n 个顶点。
+    Vertex(usize),
+    /// 圆或椭圆的象限点，0–3 依次为 0°、90°、180°、270°（椭圆沿主轴起算）。
+    Quadrant(u8),
+    /// 文字与块参照的插入点。
+    Insertion,
+}
+
+/// 实体特征点的引用。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapReference {
+    pub entity: EntityId,
+    pub role: SnapRole,
+}
+
+/// 标注的定义点，按 DXF 组码区分。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DimensionPoint {
+    /// 组码 10。
+    Definition,
+    /// 组码 12。
+    Secondary,
+    /// 组码 13。
+    DimensionLine,
+    /// 组码 14。
+    ExtensionOrigin,
+    /// 组码 15。
+    ExtensionEnd,
+    /// 组码 16。
+    ArcDefinition,
+    Center,
+}
+
+/// 注释对象上可被关联的点。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnotationPoint {
+    Dimension(DimensionPoint),
+    /// 引线第 n 个顶点。
+    LeaderVertex(usize),
+}
+
+/// 注释对象上一个点与特征点之间的关联。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PointAssociation {
+    pub point: AnnotationPoint,
+    pub snap: SnapReference,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssociationError {
+    NotFound(EntityId),
+    /// 只有标注与引线可以建立关联。
+    NotAnnotation(EntityId),
+    /// 注释对象上没有该点，例如引线顶点序号越界。
+    InvalidPoint(EntityId),
+    /// 被引用的实体没有该特征点。
+    MissingSnapPoint(EntityId),
+    /// 注释对象不能引用自身。
+    SelfReference(EntityId),
+}
+
+impl fmt::Display for AssociationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssociationError::NotFound(id) => write!(f, "entity {} not found", id.get()),
+            AssociationError::NotAnnotation(id) => {
+                write!(f, "entity {} is not a dimension or leader", id.get())
+            }
+            AssociationError::InvalidPoint(id) => {
+                write!(f, "entity {} has no such annotation point", id.get())
+            }
+            AssociationError::MissingSnapPoint(id) => {
+                write!(f, "entity {} has no such snap point", id.get())
+            }
+            AssociationError::SelfReference(id) => {
+                write!(f, "entity {} cannot be associated with itself", id.get())
+            }
+        }
+    }
+}
+
+impl std::error::Error for AssociationError {}
+
+impl Entity {
+    /// 特征点的世界坐标，实体没有该特征点时为 `None`。
+    pub fn snap_point(&self, role: SnapRole) -> Option<Point2> {
+        let polar = |center: Point2, radius: f64, angle: f64| {
+            Point2::from(center.as_vec2() + DVec2::from_angle(angle) * radius)
+        };
+        match (self, role) {
+            (Entity::Line(line), SnapRole::Start) => Some(line.start),
+            (Entity::Line(line), SnapRole::End) => Some(line.end),
+            (Entity::Line(line), SnapRole::Midpoint) => Some(Point2::from(
+                line.start.as_vec2().midpoint(line.end.as_vec2()),
+            )),
+            (Entity::Circle(circle), SnapRole::Center) => Some(circle.center),
+            (Entity::Circle(circle), SnapRole::Quadrant(index)) if index < 4 => Some(polar(
+                circle.center,
+                circle.radius,
+                f64::from(index) * FRAC_PI_2,
+            )),
+            (Entity::Arc(arc), SnapRole::Center) => Some(arc.center),
+            (Entity::Arc(arc), SnapRole::Start) => {
+                Some(polar(arc.center, arc.radius, arc.start_angle))
+            }
+            (Entity::Arc(arc), SnapRole::End) => Some(polar(arc.center, arc.radius, arc.end_angle)),
+            (Entity::Arc(arc), SnapRole::Midpoint) => {
+                let sweep = ccw_sweep(arc.start_angle, arc.end_angle);
+                Some(polar(arc.center, arc.radius, arc.start_angle + sweep / 2.0))
+            }
+            (Entity::Ellipse(ellipse), SnapRole::Center) => Some(ellipse.center),
+            (Entity::Ellipse(ellipse), SnapRole::Quadrant(index)) if index < 4 => {
+                Some(ellipse_point(ellipse, f64::from(index) * FRAC_PI_2))
+            }
+            (Entity::Ellipse(ellipse), SnapRole::Start) if !is_full_ellipse(ellipse) => {
+                Some(ellipse_point(ellipse, ellipse.start_parameter))
+            }
+            (Entity::Ellipse(ellipse), SnapRole::End) if !is_full_ellipse(ellipse) => {
+                Some(ellipse_point(ellipse, ellipse.end_parameter))
+            }
+            (Entity::Polyline(polyline), SnapRole::Vertex(index)) => {
+                polyline.vertices.get(index).map(|vertex| vertex.position)
+            }
+            (Entity::Polyline(polyline), SnapRole::Start) if !polyline.is_closed => {
+                polyline.vertices.first().map(|vertex| vertex.position)
+            }
+            (Entity::Polyline(polyline), SnapRole::End) if !polyline.is_closed => {
+                polyline.vertices.last().map(|vertex| vertex.position)
+            }
+            (Entity::Text(text), SnapRole::Insertion) => Some(text.insert),
+            (Entity::MText(text), SnapRole::Insertion) => Some(text.insert),
+            (Entity::BlockReference(reference), SnapRole::Insertion) => Some(reference.insert),
+            _ => None,
+        }
+    }
+}
+
+pub(super) fn is_full_ellipse(ellipse: &Ellipse) -> bool {
+    ccw_sweep(ellipse.start_parameter, ellipse.end_parameter) >= TAU - 1e-12
+}
+
+pub(super) fn ellipse_point(ellipse: &Ellipse, parameter: f64) -> Point2 {
+    let major = ellipse.major_axis.as_vec2();
+    let minor = major.perp() * ellipse.ratio;
+    Point2::from(ellipse.center.as_vec2() + major * parameter.cos() + minor * parameter.sin())
+}
+
+/// 注释对象上某个点的当前位置；标注中尚未记录的可选点为 `None`。
+fn annotation_point(entity: &Entity, point: AnnotationPoint) -> Option<Point2> {
+    match (entity, point) {
+        (Entity::Dimension(dimension), AnnotationPoint::Dimension(point)) => match point {
+            DimensionPoint::Definition => Some(dimension.definition_point),
+            DimensionPoint::Secondary => dimension.secondary_point,
+            DimensionPoint::DimensionLine => dimension.dimension_line_point,
+            DimensionPoint::ExtensionOrigin => dimension.extension_line_origin,
+            DimensionPoint::ExtensionEnd => dimension.extension_line_end,
+            DimensionPoint::ArcDefinition => dimension.arc_definition_point,
+            DimensionPoint::Center => dimension.center_point,
+        },
+        (Entity::Leader(leader), AnnotationPoint::LeaderVertex(index)) => {
+            leader.vertices.get(index).copied()
+        }
+        _ => None,
+    }
+}
+
+/// 注释对象是否具有该点：标注的任一定义点都可以关联，引线顶点须在范围内。
+fn accepts_point(entity: &Entity, point: AnnotationPoint) -> bool {
+    match (entity, point) {
+        (Entity::Dimension(_), AnnotationPoint::Dimension(_)) => true,
+        (Entity::Leader(leader), AnnotationPoint::LeaderVertex(index)) => {
+            index < leader.vertices.len()
+        }
+        _ => false,
+    }
+}
+
+fn set_annotation_point(entity: &mut Entity, point: AnnotationPoint, value: Point2) {
+    match (entity, point) {
+        (Entity::Dimension(dimension), AnnotationPoint::Dimension(point)) => match point {
+            DimensionPoint::Definition => dimension.definition_point = value,
+            DimensionPoint::Secondary => dimension.secondary_point = Some(value),
+            DimensionPoint::DimensionLine => dimension.dimension_line_point = Some(value),
+            DimensionPoint::ExtensionOrigin => dimension.extension_line_origin = Some(value),
+            DimensionPoint::ExtensionEnd => dimension.extension_line_end = Some(value),
+            DimensionPoint::ArcDefinition => dimension.arc_definition_point = Some(value),
+            DimensionPoint::Center => dimension.center_point = Some(value),
+        },
+        (Entity::Leader(leader), AnnotationPoint::LeaderVertex(index)) => {
+            if let Some(vertex) = leader.vertices.get_mut(index) {
+                *vertex = value;
+            }
+        }
+        _ => {}
+    }
+}
+
+impl Document {
+    /// 把标注或引线上的点关联到另一个实体的特征点，同一点已有关联时替换；
+    /// 点立即移动到特征点的当前位置。
+    pub fn associate(
+        &mut self,
+        target: EntityId,
+        point: AnnotationPoint,
+        snap: SnapReference,
+    ) -> Result<(), AssociationError> {
+        if target == snap.entity {
+            return Err(AssociationError::SelfReference(target));
+        }
+        let entity = self
+            .entity(target)
+            .ok_or(AssociationError::NotFound(target))?;
+        if !matches!(entity, Entity::Dimension(_) | Entity::Leader(_)) {
+            return Err(AssociationError::NotAnnotation(target));
+        }
+        if !accepts_point(entity, point) {
+            return Err(AssociationError::InvalidPoint(target));
+        }
+        self.entity(snap.entity)
+            .ok_or(AssociationError::NotFound(snap.entity))?
+            .snap_point(snap.role)
+            .ok_or(AssociationError::MissingSnapPoint(snap.entity))?;
+
+        let links = self.associations.entry(target).or_default();
+        links.retain(|link| link.point != point);
+        links.push(PointAssociation { point, snap });
+        self.update_associations_of(target);
+        Ok(())
+    }
+
+    /// 注释对象上的全部关联。
+    pub fn associations(&self, target: EntityId) -> &[PointAssociation] {
+        self.associations.get(&target).map_or(&[], Vec::as_slice)
+    }
+
+    /// 解除注释对象的全部关联，返回解除的条数。
+    pub fn dissociate(&mut self, target: EntityId) -> usize {
+        self.associations
+            .remove(&target)
+            .map_or(0, |links| links.len())
+    }
+
+    /// 按被引用实体的当前特征点更新关联标注与引线，返回几何发生变化的注释对象数。
+    ///
+    /// 标注的关联点移动后，文字位置按全部关联点的平均位移平移，测量值按定义点重新计算，
+    /// 原有的标注渲染块已不再对应新几何，因此清除块引用，改由定义点绘制。
+    pub fn update_associative_dimensions(&mut self) -> usize {
+        let mut targets: Vec<EntityId> = self.associations.keys().copied().collect();
+        targets.sort_by_key(|id| id.get());
+        targets
+            .into_iter()
+            .filter(|target| self.update_associations_of(*target))
+            .count()
+    }
+
+    fn update_associations_of(&mut self, target: EntityId) -> bool {
+        let Some(mut links) = self.associations.remove(&target) else {
+            return false;
+        };
+        let Some(entity) = self.entity(target) else {
+            return false;
+        };
+        let tolerance = self.tolerance();
+        let mut moves = Vec::new();
+        links.retain(|link| {
+            let Some(snapped) = self
+                .entity(link.snap.entity)
+                .and_then(|source| source.snap_point(link.snap.role))
+            else {
+                return false;
+            };
+            let current = annotation_point(entity, link.point);
+            if current.is_none_or(|current| !tolerance.points_equal(current, snapped)) {
+                moves.push((link.point, current, snapped));
+            }
+            true
+        });
+        let linked = links.len();
+        if linked > 0 {
+            self.associations.insert(target, links);
+        }
+        if moves.is_empty() {
+            return false;
+        }
+
+        if self.entities.get(target).is_none() {
+            return false;
+        }
+        self.record_modified(target);
+        let Some(entity) = self.entities.get_geometry_mut(target) else {
+            return false;
+        };
+        let mut shift = DVec2::ZERO;
+        for (point, current, snapped) in moves {
+            if let Some(current) = current {
+                shift += snapped.as_vec2() - current.as_vec2();
+            }
+            set_annotation_point(entity, point, snapped);
+        }
+        if let Entity::Dimension(dimension) = entity {
+            if linked > 0 {
+                dimension.text_midpoint =
+                    Point2::from(dimension.text_midpoint.as_vec2() + shift / linked as f64);
+            }
+            if let Some(measurement) = dimension.computed_measurement() {
+                dimension.measurement = Some(measurement);
+            }
+            dimension.block_name = None;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::DimensionKind;
+    use crate::geometry::{Transform2, Vector2};
+
+    fn linear_dimension(doc: &mut Document) -> EntityId {
+        doc.add_dimension(
+            DimensionKind::Aligned,
+            Point2::new(0.0, 2.0),
+            Point2::new(2.0, 2.5),
+            Some(Point2::new(0.0, 0.0)),
+            Some(Point2::new(4.0, 0.0)),
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(4.0),
+            0.0,
+            None,
+            None,
+            Some("*D1".to_string()),
+            None,
+            None,
+            None,
+            "DIMS",
+        )
+    }
+
+    fn dimension(doc: &Document, id: EntityId) -> &crate::document::Dimension {
+        match doc.entity(id) {
+            Some(Entity::Dimension(dimension)) => dimension,
+            other => panic!("expected dimension, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn snap_points_follow_entity_geometry() {
+        let mut doc = Document::new();
+        let line = doc.add_line(Point2::new(0.0, 0.0), Point2::new(4.0, 2.0), "0");
+        let circle = doc.add_circle(Point2::new(1.0, 1.0), 2.0, "0");
+        let arc = doc.add_arc(Point2::new(0.0, 0.0), 1.0, 0.0, std::f64::consts::PI, "0");
+        let line = doc.entity(line).unwrap();
+        assert_eq!(
+            line.snap_point(SnapRole::Midpoint),
+            Some(Point2::new(2.0, 1.0))
+        );
+        assert_eq!(line.snap_point(SnapRole::Center), None);
+        let circle = doc.entity(circle).unwrap();
+        let quadrant = circle.snap_point(SnapRole::Quadrant(1)).unwrap();
+        assert!(quadrant.as_vec2().distance(DVec2::new(1.0, 3.0)) < 1e-12);
+        assert_eq!(circle.snap_point(SnapRole::Quadrant(4)), None);
+        let top = doc
+            .entity(arc)
+            .unwrap()
+            .snap_point(SnapRole::Midpoint)
+            .unwrap();
+        assert!(top.as_vec2().distance(DVec2::new(0.0, 1.0)) < 1e-12);
+    }
+
+    #[test]
+    fn dimensions_follow_moved_entities() {
+        let mut doc = Document::new();
+        let wall = doc.add_line(Point2::new(0.0, 0.0), Point2::new(4.0, 0.0), "0");
+        let dim = linear_dimension(&mut doc);
+        for (point, role) in [
+            (DimensionPoint::DimensionLine, SnapRole::Start),
+            (DimensionPoint::ExtensionOrigin, SnapRole::End),
+        ] {
+            doc.associate(
+                dim,
+                AnnotationPoint::Dimension(point),
+                SnapReference { entity: wall, role },
+            )
+            .unwrap();
+        }
+        assert_eq!(doc.associations(dim).len(), 2);
+        assert_eq!(doc.update_associative_dimensions(), 0);
+
+        doc.scale_entities([wall], Point2::new(0.0, 0.0), 1.5);
+        assert_eq!(doc.update_associative_dimensions(), 1);
+        let updated = dimension(&doc, dim);
+        assert_eq!(updated.extension_line_origin, Some(Point2::new(6.0, 0.0)));
+        assert_eq!(updated.measurement, Some(6.0));
+        assert_eq!(updated.text_midpoint, Point2::new(3.0, 2.5));
+        assert!(updated.block_name.is_none());
+
+        // 删除被引用的实体后关联解除，标注保持原位
+        doc.remove_entity(wall);
+        assert_eq!(doc.update_associative_dimensions(), 0);
+        assert!(doc.associations(dim).is_empty());
+        assert_eq!(dimension(&doc, dim).measurement, Some(6.0));
+    }
+
+    #[test]
+    fn leaders_follow_and_invalid_associations_are_rejected() {
+        let mut doc = Document::new();
+        let circle = doc.add_circle(Point2::new(0.0, 0.0), 1.0, "0");
+        let leader = doc.add_leader(
+            vec![Point2::new(0.5, 0.5), Point2::new(3.0, 3.0)],
+            "0",
+            None,
+            true,
+        );
+        let center = SnapReference {
+            entity: circle,
+            role: SnapRole::Center,
+        };
+        doc.associate(leader, AnnotationPoint::LeaderVertex(0), center)
+            .unwrap();
+        match doc.entity(leader) {
+            Some(Entity::Leader(leader)) => assert_eq!(leader.vertices[0], Point2::new(0.0, 0.0)),
+            other => panic!("expected leader, got {other:?}"),
+        }
+        doc.transform_entities([circle], &Transform2::translation(Vector2::new(1.0, 2.0)));
+        assert_eq!(doc.update_associative_dimensions(), 1);
+        match doc.entity(leader) {
+            Some(Entity::Leader(leader)) => assert_eq!(leader.vertices[0], Point2::new(1.0, 2.0)),
+            other => panic!("expected leader, got {other:?}"),
+        }
+
+        assert_eq!(
+            doc.associate(leader, AnnotationPoint::LeaderVertex(5), center),
+            Err(AssociationError::InvalidPoint(leader))
+        );
+        assert_eq!(
+            doc.associate(circle, AnnotationPoint::LeaderVertex(0), center),
+            Err(AssociationError::SelfReference(circle))
+        );
+        let other = doc.add_circle(Point2::new(5.0, 5.0), 1.0, "0");
+        assert_eq!(
+            doc.associate(other, AnnotationPoint::LeaderVertex(0), center),
+            Err(AssociationError::NotAnnotation(other))
+        );
+        assert_eq!(
+            doc.associate(
+                leader,
+                AnnotationPoint::LeaderVertex(1),
+                SnapReference {
+                    entity: circle,
+                    role: SnapRole::Insertion,
+                },
+            ),
+            Err(AssociationError::MissingSnapPoint(circle))
+        );
+        assert_eq!(doc.dissociate(leader), 1);
+
+        doc.associate(leader, AnnotationPoint::LeaderVertex(0), center)
+            .unwrap();
+        doc.remove_entity(leader);
+        assert!(doc.associations(leader).is_empty());
+    }
+}
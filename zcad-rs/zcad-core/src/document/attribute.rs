@@ -0,0 +1,320 @@
+//! 块属性：按属性定义同步块参照的属性（ATTSYNC），以及按标记修改属性值。
+//!
+//! 块参照上的属性位于参照所在的坐标系（模型空间参照即世界坐标），由属性定义经插入变换得到。
+//! 属性标记不区分大小写。
+
+use std::collections::HashMap;
+use std::fmt;
+
+use glam::DAffine2;
+
+use super::transform::transform_attribute;
+use super::{Attribute, AttributeDefinition, BlockReference, Document, Entity, EntityId};
+use crate::geometry::{Point2, Transform2};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeError {
+    BlockNotFound(String),
+    EntityNotFound(EntityId),
+    NotBlockReference(EntityId),
+    TagNotFound(String),
+    /// 常量属性的值由定义决定，不能在参照上修改。
+    Constant(String),
+}
+
+impl fmt::Display for AttributeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttributeError::BlockNotFound(name) => write!(f, "block {name:?} not found"),
+            AttributeError::EntityNotFound(id) => write!(f, "entity {} not found", id.get()),
+            AttributeError::NotBlockReference(id) => {
+                write!(f, "entity {} is not a block reference", id.get())
+            }
+            AttributeError::TagNotFound(tag) => write!(f, "attribute tag {tag:?} not found"),
+            AttributeError::Constant(tag) => write!(f, "attribute {tag:?} is constant"),
+        }
+    }
+}
+
+impl std::error::Error for AttributeError {}
+
+impl AttributeDefinition {
+    /// 按定义生成属性，`placement` 把块坐标变换到参照所在的坐标系。
+    pub fn instantiate(&self, text: impl Into<String>, placement: &Transform2) -> Attribute {
+        let mut attribute = Attribute {
+            tag: self.tag.clone(),
+            text: text.into(),
+            insert: self.insert,
+            height: self.height,
+            rotation: self.rotation,
+            width_factor: self.width_factor,
+            oblique: self.oblique,
+            style: self.style.clone(),
+            prompt: self.prompt.clone(),
+            alignment: self.alignment,
+            horizontal_align: self.horizontal_align,
+            vertical_align: self.vertical_align,
+            line_spacing_factor: self.line_spacing_factor,
+            line_spacing_style: self.line_spacing_style,
+            is_invisible: self.is_invisible,
+            is_constant: self.is_constant,
+            is_verify: self.is_verify,
+            is_preset: self.is_preset,
+            lock_position: self.lock_position,
+            layer: self.layer.clone(),
+        };
+        transform_attribute(&mut attribute, placement);
+        attribute
+    }
+}
+
+/// 块坐标到参照所在坐标系的变换（先减去块基点，再缩放、旋转并平移到插入点）。
+pub(super) fn reference_placement(reference: &BlockReference, base_point: Point2) -> Transform2 {
+    Transform2::from_affine(
+        DAffine2::from_scale_angle_translation(
+            reference.scale.as_vec2(),
+            reference.rotation,
+            reference.insert.as_vec2(),
+        ) * DAffine2::from_translation(-base_point.as_vec2()),
+    )
+}
+
+impl Document {
+    /// ATTSYNC：按块的属性定义重建其全部参照（含嵌套在其他块中的）的属性。
+    /// 定义中新增的属性取默认值，已删除的属性被丢弃，标记相同的属性保留原值，
+    /// 位置与样式恢复为定义经插入变换后的结果。返回同步的参照数。
+    pub fn sync_block_attributes(&mut self, name: &str) -> Result<usize, AttributeError> {
+        let block = self
+            .blocks
+            .get(name)
+            .ok_or_else(|| AttributeError::BlockNotFound(name.to_string()))?;
+        let definitions = block.attributes.clone();
+        let base_point = block.base_point;
+        for definition in &definitions {
+            self.ensure_layer(&definition.layer);
+        }
+
+        self.record_modified_where(
+            |entity| matches!(entity, Entity::BlockReference(reference) if reference.name == name),
+        );
+        let mut synced = 0;
+        let model = self.entities.iter_mut().map(|(_, entity)| entity);
+        let nested = self
+            .blocks
+            .values_mut()
+            .flat_map(|block| block.entities.iter_mut());
+        for entity in model.chain(nested) {
+            let Entity::BlockReference(reference) = entity else {
+                continue;
+            };
+            if reference.name != name {
+                continue;
+            }
+            let values: HashMap<String, String> = reference
+                .attributes
+                .drain(..)
+                .rev()
+                .map(|attribute| (attribute.tag.to_uppercase(), attribute.text))
+                .collect();
+            let placement = reference_placement(reference, base_point);
+            reference.attributes = definitions
+                .iter()
+                .map(|definition| {
+                    let text = match values.get(&definition.tag.to_uppercase()) {
+                        Some(text) if !definition.is_constant => text.clone(),
+                        _ => definition.default_text.clone(),
+                    };
+                    definition.instantiate(text, &placement)
+                })
+                .collect();
+            synced += 1;
+        }
+        Ok(synced)
+    }
+
+    /// 修改模型空间块参照上指定标记的属性值；同一标记出现多次时全部修改。
+    pub fn set_attribute_value(
+        &mut self,
+        id: EntityId,
+        tag: &str,
+        value: impl Into<String>,
+    ) -> Result<(), AttributeError> {
+        let entity = self
+            .entities
+            .get(id)
+            .ok_or(AttributeError::EntityNotFound(id))?;
+        let Entity::BlockReference(reference) = entity else {
+            return Err(AttributeError::NotBlockReference(id));
+        };
+        let Some(first) = reference
+            .attributes
+            .iter()
+            .find(|attribute| attribute.tag.eq_ignore_ascii_case(tag))
+        else {
+            return Err(AttributeError::TagNotFound(tag.to_string()));
+        };
+        if first.is_constant {
+            return Err(AttributeError::Constant(first.tag.clone()));
+        }
+        let value = value.into();
+        self.record_modified(id);
+        if let Some(Entity::BlockReference(reference)) = self.entities.get_mut(id) {
+            for attribute in reference
+                .attributes
+                .iter_mut()
+                .filter(|attribute| attribute.tag.eq_ignore_ascii_case(tag))
+            {
+                attribute.text = value.clone();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::*;
+    use crate::document::BlockDefinition;
+    use crate::geometry::Vector2;
+
+    fn definition(tag: &str, default_text: &str, insert: Point2) -> AttributeDefinition {
+        AttributeDefinition {
+            tag: tag.to_string(),
+            prompt: None,
+            default_text: default_text.to_string(),
+            insert,
+            height: 1.0,
+            rotation: 0.0,
+            width_factor: 1.0,
+            oblique: 0.0,
+            style: None,
+            alignment: None,
+            horizontal_align: 0,
+            vertical_align: 0,
+            line_spacing_factor: 1.0,
+            line_spacing_style: 1,
+            is_invisible: false,
+            is_constant: false,
+            is_verify: false,
+            is_preset: false,
+            lock_position: false,
+            layer: "0".to_string(),
+        }
+    }
+
+    fn attributes(doc: &Document, id: EntityId) -> Vec<(String, String, Point2)> {
+        let Some(Entity::BlockReference(reference)) = doc.entity(id) else {
+            panic!("应为块参照");
+        };
+        reference
+            .attributes
+            .iter()
+            .map(|attribute| {
+                (
+                    attribute.tag.clone(),
+                    attribute.text.clone(),
+                    attribute.insert,
+                )
+            })
+            .collect()
+    }
+
+    fn sample() -> (Document, EntityId) {
+        let mut doc = Document::new();
+        doc.add_block_definition(BlockDefinition {
+            name: "DOOR".to_string(),
+            base_point: Point2::new(1.0, 0.0),
+            entities: Vec::new(),
+            attributes: vec![
+                definition("MARK", "D?", Point2::new(1.0, 1.0)),
+                definition("FIRE", "NONE", Point2::new(1.0, 2.0)),
+            ],
+        });
+        let door = doc.add_block_reference(
+            "DOOR",
+            Point2::new(10.0, 0.0),
+            Vector2::new(2.0, 2.0),
+            FRAC_PI_2,
+            Vec::new(),
+            "0",
+        );
+        (doc, door)
+    }
+
+    #[test]
+    fn new_references_place_attributes_through_insertion() {
+        let (doc, door) = sample();
+        let placed = attributes(&doc, door);
+        assert!(
+            doc.tolerance()
+                .points_equal(placed[0].2, Point2::new(8.0, 0.0))
+        );
+        assert!(
+            doc.tolerance()
+                .points_equal(placed[1].2, Point2::new(6.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn sync_adds_drops_and_keeps_values() {
+        let (mut doc, door) = sample();
+        doc.set_attribute_value(door, "mark", "D01").unwrap();
+
+        let block = doc.blocks.get_mut("DOOR").unwrap();
+        block.attributes.remove(1);
+        block
+            .attributes
+            .push(definition("WIDTH", "900", Point2::new(1.0, 3.0)));
+        let mut constant = definition("MAKER", "ACME", Point2::new(1.0, 4.0));
+        constant.is_constant = true;
+        block.attributes.push(constant);
+
+        assert_eq!(doc.sync_block_attributes("DOOR"), Ok(1));
+        let synced = attributes(&doc, door);
+        let values: Vec<(&str, &str)> = synced
+            .iter()
+            .map(|(tag, text, _)| (tag.as_str(), text.as_str()))
+            .collect();
+        assert_eq!(
+            values,
+            [("MARK", "D01"), ("WIDTH", "900"), ("MAKER", "ACME")]
+        );
+        assert!(
+            doc.tolerance()
+                .points_equal(synced[1].2, Point2::new(4.0, 0.0))
+        );
+
+        assert_eq!(
+            doc.set_attribute_value(door, "MAKER", "Other"),
+            Err(AttributeError::Constant("MAKER".to_string()))
+        );
+        assert_eq!(
+            doc.set_attribute_value(door, "FIRE", "EI30"),
+            Err(AttributeError::TagNotFound("FIRE".to_string()))
+        );
+        assert_eq!(
+            doc.sync_block_attributes("WINDOW"),
+            Err(AttributeError::BlockNotFound("WINDOW".to_string()))
+        );
+    }
+
+    #[test]
+    fn synced_references_revert_with_captured_changes() {
+        let (mut doc, door) = sample();
+        let before = attributes(&doc, door);
+        doc.blocks.get_mut("DOOR").unwrap().attributes.pop();
+
+        let (synced, changes) = doc.capture_changes(|doc| doc.sync_block_attributes("DOOR"));
+        assert_eq!(synced, Ok(1));
+        assert_eq!(attributes(&doc, door).len(), 1);
+        doc.revert_changes(&changes);
+        assert_eq!(attributes(&doc, door), before);
+        let line = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+        assert_eq!(
+            doc.set_attribute_value(line, "MARK", "X"),
+            Err(AttributeError::NotBlockReference(line))
+        );
+    }
+}
@@ -0,0 +1,791 @@
+//! 文档审核：检查块、图像、底图与线型引用是否完整，以及几何是否有效。
+//!
+//! [`Document::audit`] 只读取文档，结果供命令行或 CI 判断来稿图纸的质量；
+//! 标记为可修复的发现可交给 [`Document::fix`] 修复，其余问题需要人工处理。
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use super::{
+    Document, Entity, EntityId, LINETYPE_BYBLOCK, LINETYPE_BYLAYER, LINETYPE_CONTINUOUS,
+    MLeaderContent,
+};
+use crate::geometry::Point2;
+
+/// 审核发现的严重程度，按 `Info < Warning < Error` 排序。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// 一条审核发现。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuditFinding {
+    pub severity: AuditSeverity,
+    /// 检查项标识，例如 `missing_block`。
+    pub check: &'static str,
+    /// 模型空间中的实体；块定义内的问题为 `None`。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entity: Option<EntityId>,
+    /// 问题所在的块定义。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block: Option<String>,
+    /// 问题所在的非图形对象句柄，例如图像定义反应器。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
+    pub message: String,
+    /// 可由 [`Document::fix`] 自动修复。
+    pub fixable: bool,
+}
+
+impl AuditFinding {
+    fn new(severity: AuditSeverity, check: &'static str, message: String) -> Self {
+        Self {
+            severity,
+            check,
+            entity: None,
+            block: None,
+            handle: None,
+            message,
+            fixable: false,
+        }
+    }
+}
+
+impl Document {
+    /// 审核文档，按模型空间、块定义、表记录、非图形对象的顺序返回全部发现。
+    ///
+    /// 错误：引用不存在的块、块循环引用、图像或底图缺少定义、坐标不是有限值；
+    /// 警告：退化几何（零长度、非正半径等）、线型表中不存在的线型、测量值与定义点不符的标注、
+    /// 仅大小写不同的块名、悬空的图像反应器或图像字典条目；
+    /// 提示：未被引用的块定义与图像定义反应器。
+    pub fn audit(&self) -> Vec<AuditFinding> {
+        let mut findings = Vec::new();
+        for (id, entity) in &self.entities {
+            let start = findings.len();
+            self.audit_entity(entity, &mut findings);
+            if let Some(properties) = self.entity_properties(*id) {
+                self.audit_linetype(&properties.linetype, entity.kind_name(), &mut findings);
+            }
+            for finding in &mut findings[start..] {
+                finding.entity = Some(*id);
+                // 块定义与图层中的同类问题没有稳定的定位方式，只修复模型空间实体
+                finding.fixable = match finding.check {
+                    "degenerate_geometry" => is_disposable(entity),
+                    "missing_linetype" | "dangling_image_reactor" | "dimension_measurement" => true,
+                    _ => false,
+                };
+            }
+        }
+
+        let mut block_names: Vec<&String> = self.blocks.keys().collect();
+        block_names.sort();
+        for name in &block_names {
+            let start = findings.len();
+            for entity in &self.blocks[*name].entities {
+                self.audit_entity(entity, &mut findings);
+            }
+            for finding in &mut findings[start..] {
+                finding.block = Some((*name).clone());
+            }
+        }
+        self.audit_block_cycles(&block_names, &mut findings);
+        self.audit_duplicate_blocks(&block_names, &mut findings);
+        self.audit_unused_blocks(&block_names, &mut findings);
+
+        let mut layers: Vec<_> = self.layers.values().collect();
+        layers.sort_by(|a, b| a.name.cmp(&b.name));
+        for layer in layers {
+            let start = findings.len();
+            self.audit_linetype(&layer.linetype, "LAYER", &mut findings);
+            for finding in &mut findings[start..] {
+                finding.message = format!("图层 {}：{}", layer.name, finding.message);
+            }
+        }
+        self.audit_image_objects(&mut findings);
+        findings
+    }
+
+    /// 修复 `findings` 中标记为可修复的问题，返回实际修复的条数。
+    ///
+    /// 每条发现在修复前重新核对，已失效的发现（例如实体已被删除）会被跳过，
+    /// 因此可以直接传入较早的审核结果。修复方式：
+    /// - 退化的直线、圆、圆弧、椭圆与多段线：删除实体；
+    /// - 实体使用的线型不存在：改为随层；
+    /// - 标注测量值与定义点不符：按定义点重新计算；
+    /// - 图像指向不存在的反应器：清除引用，保存时重新生成；
+    /// - 未被引用的图像定义反应器：删除；
+    /// - 指向不存在图像定义的字典条目：删除。
+    pub fn fix(&mut self, findings: &[AuditFinding]) -> usize {
+        findings
+            .iter()
+            .filter(|finding| finding.fixable && self.fix_finding(finding))
+            .count()
+    }
+
+    fn fix_finding(&mut self, finding: &AuditFinding) -> bool {
+        match (finding.check, finding.entity, finding.handle.as_deref()) {
+            ("degenerate_geometry", Some(id), _) => {
+                let disposable = self.entity(id).is_some_and(|entity| {
+                    is_disposable(entity) && {
+                        let mut check = Vec::new();
+                        self.audit_entity(entity, &mut check);
+                        check.iter().any(|item| item.check == "degenerate_geometry")
+                    }
+                });
+                disposable && self.remove_entity(id).is_some()
+            }
+            ("missing_linetype", Some(id), _) => {
+                let Some(mut properties) = self.entity_properties(id).cloned() else {
+                    return false;
+                };
+                let mut check = Vec::new();
+                self.audit_linetype(&properties.linetype, "", &mut check);
+                if check.is_empty() {
+                    return false;
+                }
+                properties.linetype = LINETYPE_BYLAYER.to_string();
+                self.set_entity_properties(id, properties);
+                true
+            }
+            ("dimension_measurement", Some(id), _) => {
+                let mut check = Vec::new();
+                if let Some(entity) = self.entity(id) {
+                    self.audit_entity(entity, &mut check);
+                }
+                if !check
+                    .iter()
+                    .any(|item| item.check == "dimension_measurement")
+                {
+                    return false;
+                }
+                match self.entity_mut(id) {
+                    Some(Entity::Dimension(dimension)) => {
+                        dimension.measurement = dimension.computed_measurement();
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            ("dangling_image_reactor", Some(id), _) => {
+                let reactors = &self.image_def_reactors;
+                let image = self
+                    .entities
+                    .iter_mut()
+                    .find(|(entity_id, _)| *entity_id == id);
+                match image {
+                    Some((_, Entity::RasterImage(image)))
+                        if image
+                            .image_def_reactor_handle
+                            .as_ref()
+                            .is_some_and(|handle| !reactors.contains_key(handle)) =>
+                    {
+                        image.image_def_reactor_handle = None;
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            ("unreferenced_image_reactor", _, Some(handle)) => {
+                if self.image_reactor_is_referenced(handle)
+                    || self.image_def_reactors.remove(handle).is_none()
+                {
+                    return false;
+                }
+                if let Some(dictionary) = self.image_dictionary.as_mut() {
+                    for entry in &mut dictionary.entries {
+                        if entry.reactor_handle.as_deref() == Some(handle) {
+                            entry.reactor_handle = None;
+                        }
+                    }
+                }
+                true
+            }
+            ("dangling_image_dictionary_entry", _, Some(handle)) => {
+                if self.image_definitions.contains_key(handle) {
+                    return false;
+                }
+                let Some(dictionary) = self.image_dictionary.as_mut() else {
+                    return false;
+                };
+                let before = dictionary.entries.len();
+                dictionary
+                    .entries
+                    .retain(|entry| entry.image_def_handle != handle);
+                dictionary.entries.len() < before
+            }
+            _ => false,
+        }
+    }
+
+    fn audit_entity(&self, entity: &Entity, findings: &mut Vec<AuditFinding>) {
+        let kind = entity.kind_name();
+        if !entity_is_finite(entity) {
+            findings.push(AuditFinding::new(
+                AuditSeverity::Error,
+                "invalid_coordinates",
+                format!("{kind} 含有非有限坐标或尺寸"),
+            ));
+            return;
+        }
+        let degenerate = |message: String| {
+            AuditFinding::new(AuditSeverity::Warning, "degenerate_geometry", message)
+        };
+        match entity {
+            Entity::Line(line) if line.start == line.end => {
+                findings.push(degenerate("LINE 长度为零".to_string()));
+            }
+            Entity::Circle(circle) if circle.radius <= 0.0 => {
+                findings.push(degenerate(format!(
+                    "CIRCLE 半径 {} 不是正数",
+                    circle.radius
+                )));
+            }
+            Entity::Arc(arc) if arc.radius <= 0.0 => {
+                findings.push(degenerate(format!("ARC 半径 {} 不是正数", arc.radius)));
+            }
+            Entity::Ellipse(ellipse)
+                if ellipse.major_axis.length_squared() == 0.0 || ellipse.ratio <= 0.0 =>
+            {
+                findings.push(degenerate("ELLIPSE 主轴长度或轴比为零".to_string()));
+            }
+            Entity::Polyline(polyline) if polyline.vertices.len() < 2 => {
+                findings.push(degenerate(format!(
+                    "LWPOLYLINE 只有 {} 个顶点",
+                    polyline.vertices.len()
+                )));
+            }
+            Entity::Text(text) if text.height <= 0.0 => {
+                findings.push(degenerate(format!("TEXT 字高 {} 不是正数", text.height)));
+            }
+            Entity::MText(text) if text.height <= 0.0 => {
+                findings.push(degenerate(format!("MTEXT 字高 {} 不是正数", text.height)));
+            }
+            Entity::BlockReference(reference) => {
+                if !self.blocks.contains_key(&reference.name)
+                    && self.xref(&reference.name).is_none()
+                {
+                    findings.push(AuditFinding::new(
+                        AuditSeverity::Error,
+                        "missing_block",
+                        format!("INSERT 引用的块 {} 不存在", reference.name),
+                    ));
+                }
+                if reference.scale.x() == 0.0 || reference.scale.y() == 0.0 {
+                    findings.push(degenerate(format!("INSERT {} 的比例为零", reference.name)));
+                }
+            }
+            Entity::RasterImage(image) => {
+                if self
+                    .raster_image_definition(&image.image_def_handle)
+                    .is_none()
+                {
+                    findings.push(AuditFinding::new(
+                        AuditSeverity::Error,
+                        "missing_image_definition",
+                        format!("IMAGE 引用的图像定义 {} 不存在", image.image_def_handle),
+                    ));
+                }
+                if let Some(handle) = &image.image_def_reactor_handle
+                    && !self.image_def_reactors.contains_key(handle)
+                {
+                    findings.push(AuditFinding::new(
+                        AuditSeverity::Warning,
+                        "dangling_image_reactor",
+                        format!("IMAGE 引用的图像定义反应器 {handle} 不存在"),
+                    ));
+                }
+            }
+            Entity::Dimension(dimension) => {
+                let limit = if dimension.kind.is_angular() {
+                    self.tolerance().angular
+                } else {
+                    self.tolerance().linear
+                };
+                if let (Some(stored), Some(computed)) =
+                    (dimension.measurement, dimension.computed_measurement())
+                    && (stored - computed).abs() > limit
+                {
+                    findings.push(AuditFinding::new(
+                        AuditSeverity::Warning,
+                        "dimension_measurement",
+                        format!("DIMENSION 记录的测量值 {stored} 与定义点计算的 {computed} 不符"),
+                    ));
+                }
+            }
+            Entity::Underlay(underlay)
+                if self
+                    .underlay_definition(&underlay.definition_handle)
+                    .is_none() =>
+            {
+                findings.push(AuditFinding::new(
+                    AuditSeverity::Error,
+                    "missing_underlay_definition",
+                    format!(
+                        "{kind} 引用的底图定义 {} 不存在",
+                        underlay.definition_handle
+                    ),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    fn audit_linetype(&self, linetype: &str, owner: &str, findings: &mut Vec<AuditFinding>) {
+        let builtin = [LINETYPE_BYLAYER, LINETYPE_BYBLOCK, LINETYPE_CONTINUOUS];
+        if linetype.is_empty()
+            || builtin
+                .iter()
+                .any(|name| linetype.eq_ignore_ascii_case(name))
+            || self.linetype(linetype).is_some()
+        {
+            return;
+        }
+        findings.push(AuditFinding::new(
+            AuditSeverity::Warning,
+            "missing_linetype",
+            format!("{owner} 使用的线型 {linetype} 不在线型表中"),
+        ));
+    }
+
+    /// 深度优先查找块之间的循环引用，每个环只报告一次。
+    fn audit_block_cycles(&self, block_names: &[&String], findings: &mut Vec<AuditFinding>) {
+        fn visit<'a>(
+            document: &'a Document,
+            name: &'a str,
+            path: &mut Vec<&'a str>,
+            done: &mut HashSet<&'a str>,
+            findings: &mut Vec<AuditFinding>,
+        ) {
+            if let Some(position) = path.iter().position(|entry| *entry == name) {
+                let mut cycle = path[position..].to_vec();
+                cycle.push(name);
+                let mut finding = AuditFinding::new(
+                    AuditSeverity::Error,
+                    "recursive_block",
+                    format!("块循环引用：{}", cycle.join(" -> ")),
+                );
+                finding.block = Some(name.to_string());
+                findings.push(finding);
+                return;
+            }
+            if !done.insert(name) {
+                return;
+            }
+            let Some(block) = document.blocks.get(name) else {
+                return;
+            };
+            path.push(name);
+            for name in block.entities.iter().filter_map(referenced_block) {
+                visit(document, name, path, done, findings);
+            }
+            path.pop();
+        }
+
+        let mut done = HashSet::new();
+        for name in block_names {
+            visit(self, name, &mut Vec::new(), &mut done, findings);
+        }
+    }
+
+    /// DXF 块名不区分大小写，仅大小写不同的块在写出后会互相覆盖；每组报告一次。
+    fn audit_duplicate_blocks(&self, block_names: &[&String], findings: &mut Vec<AuditFinding>) {
+        let mut groups: Vec<Vec<&str>> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        for name in block_names {
+            let key = name.to_uppercase();
+            match index.get(&key) {
+                Some(&group) => groups[group].push(name),
+                None => {
+                    index.insert(key, groups.len());
+                    groups.push(vec![name]);
+                }
+            }
+        }
+        for group in groups.into_iter().filter(|group| group.len() > 1) {
+            let mut finding = AuditFinding::new(
+                AuditSeverity::Warning,
+                "duplicate_block",
+                format!("块名仅大小写不同：{}", group.join(", ")),
+            );
+            finding.block = Some(group[0].to_string());
+            findings.push(finding);
+        }
+    }
+
+    /// 图像定义反应器与图像字典：未被任何 IMAGE 引用的反应器、指向不存在图像定义的字典条目。
+    fn audit_image_objects(&self, findings: &mut Vec<AuditFinding>) {
+        let mut reactors: Vec<&String> = self.image_def_reactors.keys().collect();
+        reactors.sort();
+        for handle in reactors {
+            if self.image_reactor_is_referenced(handle) {
+                continue;
+            }
+            let mut finding = AuditFinding::new(
+                AuditSeverity::Info,
+                "unreferenced_image_reactor",
+                format!("图像定义反应器 {handle} 未被任何 IMAGE 引用"),
+            );
+            finding.handle = Some(handle.clone());
+            finding.fixable = true;
+            findings.push(finding);
+        }
+
+        let entries = self
+            .image_dictionary
+            .iter()
+            .flat_map(|dictionary| &dictionary.entries);
+        for entry in entries {
+            if self.image_definitions.contains_key(&entry.image_def_handle) {
+                continue;
+            }
+            let mut finding = AuditFinding::new(
+                AuditSeverity::Warning,
+                "dangling_image_dictionary_entry",
+                format!(
+                    "图像字典条目 {} 指向的图像定义 {} 不存在",
+                    entry.name, entry.image_def_handle
+                ),
+            );
+            finding.handle = Some(entry.image_def_handle.clone());
+            finding.fixable = true;
+            findings.push(finding);
+        }
+    }
+
+    fn image_reactor_is_referenced(&self, handle: &str) -> bool {
+        let model = self.entities.iter().map(|(_, entity)| entity);
+        let nested = self.blocks.values().flat_map(|block| block.entities.iter());
+        model.chain(nested).any(|entity| {
+            matches!(entity, Entity::RasterImage(image)
+                if image.image_def_reactor_handle.as_deref() == Some(handle))
+        })
+    }
+
+    /// 模型空间与其他块都未引用的普通块；匿名块（`*` 开头）由标注等对象隐式使用，不报告。
+    fn audit_unused_blocks(&self, block_names: &[&String], findings: &mut Vec<AuditFinding>) {
+        let mut references: HashMap<&str, usize> = HashMap::new();
+        let model = self.entities.iter().map(|(_, entity)| entity);
+        let nested = self.blocks.values().flat_map(|block| block.entities.iter());
+        for name in model.chain(nested).filter_map(referenced_block) {
+            *references.entry(name).or_default() += 1;
+        }
+        for name in block_names {
+            if name.starts_with('*') || references.contains_key(name.as_str()) {
+                continue;
+            }
+            let mut finding = AuditFinding::new(
+                AuditSeverity::Info,
+                "unused_block",
+                format!("块 {name} 未被引用"),
+            );
+            finding.block = Some((*name).clone());
+            findings.push(finding);
+        }
+    }
+}
+
+/// 块参照、以块为内容的多重引线或标注所引用的块名。
+pub(super) fn referenced_block(entity: &Entity) -> Option<&str> {
+    match entity {
+        Entity::BlockReference(reference) => Some(&reference.name),
+        Entity::Dimension(dimension) => dimension.block_name.as_deref(),
+        Entity::MLeader(leader) => match &leader.content {
+            MLeaderContent::Block { block } => block.block_name.as_deref(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// 退化后不可见、删除也不丢失信息的几何实体。
+fn is_disposable(entity: &Entity) -> bool {
+    matches!(
+        entity,
+        Entity::Line(_)
+            | Entity::Circle(_)
+            | Entity::Arc(_)
+            | Entity::Ellipse(_)
+            | Entity::Polyline(_)
+    )
+}
+
+fn point_is_finite(point: Point2) -> bool {
+    point.x().is_finite() && point.y().is_finite()
+}
+
+/// 检查主要定位点与尺寸，其余类型以范围判断。
+fn entity_is_finite(entity: &Entity) -> bool {
+    match entity {
+        Entity::Line(line) => point_is_finite(line.start) && point_is_finite(line.end),
+        Entity::Circle(circle) => point_is_finite(circle.center) && circle.radius.is_finite(),
+        Entity::Arc(arc) => {
+            point_is_finite(arc.center)
+                && arc.radius.is_finite()
+                && arc.start_angle.is_finite()
+                && arc.end_angle.is_finite()
+        }
+        Entity::Polyline(polyline) => polyline
+            .vertices
+            .iter()
+            .all(|vertex| point_is_finite(vertex.position) && vertex.bulge.is_finite()),
+        Entity::Text(text) => point_is_finite(text.insert) && text.height.is_finite(),
+        Entity::MText(text) => point_is_finite(text.insert) && text.height.is_finite(),
+        Entity::BlockReference(reference) => {
+            point_is_finite(reference.insert)
+                && reference.scale.x().is_finite()
+                && reference.scale.y().is_finite()
+                && reference.rotation.is_finite()
+        }
+        _ => entity.bounds().is_none_or(|bounds| {
+            bounds.is_empty() || (point_is_finite(bounds.min()) && point_is_finite(bounds.max()))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{
+        BlockDefinition, EntityProperties, ImageDefReactor, ImageDictionary, ImageDictionaryEntry,
+        Line, RasterImageDefinition, RasterImageDisplayOptions,
+    };
+    use crate::geometry::Vector2;
+
+    fn block(name: &str, entities: Vec<Entity>) -> BlockDefinition {
+        BlockDefinition {
+            name: name.to_string(),
+            base_point: Point2::new(0.0, 0.0),
+            entities,
+            attributes: Vec::new(),
+        }
+    }
+
+    fn reference(name: &str) -> Entity {
+        Entity::BlockReference(crate::document::BlockReference {
+            name: name.to_string(),
+            insert: Point2::new(0.0, 0.0),
+            scale: Vector2::new(1.0, 1.0),
+            rotation: 0.0,
+            attributes: Vec::new(),
+            layer: "0".to_string(),
+        })
+    }
+
+    #[test]
+    fn clean_document_has_no_findings() {
+        let mut document = Document::new();
+        document.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+        document.add_circle(Point2::new(0.0, 0.0), 2.0, "0");
+        assert!(document.audit().is_empty());
+    }
+
+    #[test]
+    fn audit_reports_geometry_and_missing_references() {
+        let mut document = Document::new();
+        let line = document.add_line(Point2::new(1.0, 1.0), Point2::new(1.0, 1.0), "0");
+        let circle = document.add_circle(Point2::new(f64::NAN, 0.0), 1.0, "0");
+        let insert = document.add_block_reference(
+            "MISSING",
+            Point2::new(0.0, 0.0),
+            Vector2::new(1.0, 1.0),
+            0.0,
+            Vec::new(),
+            "0",
+        );
+        document.set_entity_properties(
+            line,
+            EntityProperties {
+                linetype: "DASHED".to_string(),
+                ..EntityProperties::default()
+            },
+        );
+
+        let findings = document.audit();
+        let summary: Vec<_> = findings
+            .iter()
+            .map(|finding| (finding.severity, finding.check, finding.entity))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (AuditSeverity::Warning, "degenerate_geometry", Some(line)),
+                (AuditSeverity::Warning, "missing_linetype", Some(line)),
+                (AuditSeverity::Error, "invalid_coordinates", Some(circle)),
+                (AuditSeverity::Error, "missing_block", Some(insert)),
+            ]
+        );
+        assert!(findings[3].message.contains("MISSING"));
+    }
+
+    #[test]
+    fn audit_reports_block_cycles_and_unused_blocks() {
+        let mut document = Document::new();
+        document.add_block_definition(block("A", vec![reference("B")]));
+        document.add_block_definition(block("B", vec![reference("A")]));
+        document.add_block_definition(block(
+            "UNUSED",
+            vec![Entity::Line(Line {
+                start: Point2::new(0.0, 0.0),
+                end: Point2::new(1.0, 0.0),
+                layer: "0".to_string(),
+            })],
+        ));
+        document.add_block_definition(block("*D1", Vec::new()));
+
+        let findings = document.audit();
+        let cycles: Vec<_> = findings
+            .iter()
+            .filter(|finding| finding.check == "recursive_block")
+            .collect();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].message, "块循环引用：A -> B -> A");
+        let unused: Vec<_> = findings
+            .iter()
+            .filter(|finding| finding.check == "unused_block")
+            .map(|finding| finding.block.as_deref().unwrap())
+            .collect();
+        assert_eq!(unused, vec!["UNUSED"]);
+        assert_eq!(
+            findings.iter().map(|finding| finding.severity).max(),
+            Some(AuditSeverity::Error)
+        );
+    }
+
+    #[test]
+    fn fix_repairs_fixable_findings_only() {
+        let mut document = Document::new();
+        let keep = document.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+        document.add_circle(Point2::new(0.0, 0.0), 0.0, "0");
+        document.add_polyline(Vec::new(), false, "0");
+        let text = document.add_text(Point2::new(0.0, 0.0), "注释", 0.0, 0.0, "0");
+        document.set_entity_properties(
+            keep,
+            EntityProperties {
+                linetype: "DASHED".to_string(),
+                ..EntityProperties::default()
+            },
+        );
+        document.add_block_definition(block("Door", vec![reference("DOOR")]));
+        document.add_block_definition(block("DOOR", Vec::new()));
+
+        let findings = document.audit();
+        let fixable: Vec<_> = findings
+            .iter()
+            .filter(|finding| finding.fixable)
+            .map(|finding| finding.check)
+            .collect();
+        assert_eq!(
+            fixable,
+            vec![
+                "missing_linetype",
+                "degenerate_geometry",
+                "degenerate_geometry"
+            ]
+        );
+        let duplicate = findings
+            .iter()
+            .find(|finding| finding.check == "duplicate_block")
+            .unwrap();
+        assert_eq!(duplicate.message, "块名仅大小写不同：DOOR, Door");
+
+        assert_eq!(document.fix(&findings), 3);
+        let ids: Vec<_> = document.entities().map(|(id, _)| *id).collect();
+        assert_eq!(ids, vec![keep, text]);
+        assert!(document.entity_properties(keep).is_none());
+        // 已修复的发现再次传入时不会重复处理
+        assert_eq!(document.fix(&findings), 0);
+
+        let remaining: Vec<_> = document
+            .audit()
+            .into_iter()
+            .map(|finding| (finding.check, finding.fixable))
+            .collect();
+        assert_eq!(
+            remaining,
+            vec![
+                ("degenerate_geometry", false),
+                ("duplicate_block", false),
+                ("unused_block", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn audit_and_fix_image_bookkeeping_objects() {
+        let mut document = Document::new();
+        document.add_raster_image_definition(RasterImageDefinition {
+            handle: "D1".to_string(),
+            name: Some("logo".to_string()),
+            file_path: "logo.png".to_string(),
+            image_size_pixels: None,
+            pixel_size: None,
+            resolved_path: None,
+        });
+        let image = document.add_raster_image(
+            "0",
+            "D1",
+            Point2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(0.0, 1.0),
+            Vector2::new(10.0, 10.0),
+            RasterImageDisplayOptions {
+                show_image: true,
+                show_border: false,
+                use_clipping: false,
+                brightness: None,
+                contrast: None,
+                fade: None,
+            },
+            None,
+            Some("R9".to_string()),
+        );
+        document.add_image_def_reactor(ImageDefReactor {
+            handle: "R1".to_string(),
+            class_version: 2,
+            owner_handle: Some("D1".to_string()),
+            image_handle: None,
+        });
+        document.set_image_dictionary(ImageDictionary {
+            handle: Some("A0".to_string()),
+            entries: vec![
+                ImageDictionaryEntry {
+                    name: "logo".to_string(),
+                    image_def_handle: "D1".to_string(),
+                    reactor_handle: Some("R1".to_string()),
+                },
+                ImageDictionaryEntry {
+                    name: "gone".to_string(),
+                    image_def_handle: "D2".to_string(),
+                    reactor_handle: None,
+                },
+            ],
+        });
+
+        let findings = document.audit();
+        let summary: Vec<_> = findings
+            .iter()
+            .map(|finding| (finding.check, finding.entity, finding.handle.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("dangling_image_reactor", Some(image), None),
+                ("unreferenced_image_reactor", None, Some("R1")),
+                ("dangling_image_dictionary_entry", None, Some("D2")),
+            ]
+        );
+        assert!(findings.iter().all(|finding| finding.fixable));
+
+        assert_eq!(document.fix(&findings), 3);
+        assert!(document.audit().is_empty());
+        assert!(document.image_def_reactor("R1").is_none());
+        let dictionary = document.image_dictionary().unwrap();
+        assert_eq!(dictionary.entries.len(), 1);
+        assert_eq!(dictionary.entries[0].reactor_handle, None);
+    }
+}
@@ -0,0 +1,351 @@
+//! 块定义编辑：重定义块内容、修改基点与块改名。
+//!
+//! 块参照按名称引用块定义，重定义后所有参照（含嵌套在其他块中的与以块为内容的多重引线）
+//! 自动显示新内容；修改基点时调整各参照的插入点，使已有参照在图中的位置保持不变。
+//! 外部参照块及其依赖块由参照文件决定，不能在宿主中编辑。
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use glam::DVec2;
+
+use super::audit::referenced_block;
+use super::{BlockDefinition, Document, Entity, MLeaderContent, XREF_SEPARATOR};
+use crate::geometry::{Point2, Vector2};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockEditError {
+    NotFound(String),
+    /// 已有同名（不区分大小写）块。
+    AlreadyExists(String),
+    /// 名称为空、以 `*` 开头（匿名块）或包含外部参照分隔符。
+    InvalidName(String),
+    /// 外部参照块或其依赖块。
+    XRef(String),
+    /// 新内容直接或间接引用了块自身。
+    SelfReference(String),
+}
+
+impl fmt::Display for BlockEditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockEditError::NotFound(name) => write!(f, "block {name:?} not found"),
+            BlockEditError::AlreadyExists(name) => write!(f, "block {name:?} already exists"),
+            BlockEditError::InvalidName(name) => write!(f, "invalid block name {name:?}"),
+            BlockEditError::XRef(name) => {
+                write!(f, "block {name:?} belongs to an external reference")
+            }
+            BlockEditError::SelfReference(name) => {
+                write!(f, "block {name:?} cannot reference itself")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BlockEditError {}
+
+impl Document {
+    /// 以新的实体替换块内容，属性定义保持不变。返回显示受影响（直接或经嵌套块引用该块）的
+    /// 模型空间实体数。
+    pub fn redefine_block(
+        &mut self,
+        name: &str,
+        entities: Vec<Entity>,
+    ) -> Result<usize, BlockEditError> {
+        self.editable_block(name)?;
+        let mut pending: Vec<&str> = entities.iter().filter_map(referenced_block).collect();
+        let mut visited = HashSet::new();
+        while let Some(current) = pending.pop() {
+            if current == name {
+                return Err(BlockEditError::SelfReference(name.to_string()));
+            }
+            if visited.insert(current)
+                && let Some(block) = self.blocks.get(current)
+            {
+                pending.extend(block.entities.iter().filter_map(referenced_block));
+            }
+        }
+
+        for entity in &entities {
+            self.ensure_layer(entity.layer_name());
+        }
+        let block = self.blocks.get_mut(name).expect("已检查块存在");
+        block.entities = entities;
+        self.entities.invalidate_extents();
+        Ok(self.block_dependent_count(name))
+    }
+
+    /// 修改块基点，并平移各参照的插入点，使参照在图中的位置不变。返回调整的参照数，
+    /// 包括块参照与以块为内容的多重引线。
+    pub fn set_block_base_point(
+        &mut self,
+        name: &str,
+        base_point: Point2,
+    ) -> Result<usize, BlockEditError> {
+        let block = self.editable_block(name)?;
+        let delta = block.base_point.vector_to(base_point).as_vec2();
+        self.blocks.get_mut(name).expect("已检查块存在").base_point = base_point;
+
+        self.record_modified_where(|entity| match entity {
+            Entity::BlockReference(reference) => reference.name == name,
+            Entity::MLeader(mleader) => matches!(
+                &mleader.content,
+                MLeaderContent::Block { block } if block.block_name.as_deref() == Some(name)
+            ),
+            _ => false,
+        });
+        let mut adjusted = 0;
+        let model = self.entities.iter_mut().map(|(_, entity)| entity);
+        let nested = self
+            .blocks
+            .values_mut()
+            .flat_map(|block| block.entities.iter_mut());
+        for entity in model.chain(nested) {
+            let (insert, scale, rotation) = match entity {
+                Entity::BlockReference(reference) if reference.name == name => {
+                    (&mut reference.insert, reference.scale, reference.rotation)
+                }
+                Entity::MLeader(mleader) => match &mut mleader.content {
+                    MLeaderContent::Block { block }
+                        if block.block_name.as_deref() == Some(name) =>
+                    {
+                        (&mut block.location, block.scale, block.rotation)
+                    }
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            let offset = DVec2::from_angle(rotation).rotate(scale.as_vec2() * delta);
+            *insert = insert.translate(Vector2::new(offset.x, offset.y));
+            adjusted += 1;
+        }
+        self.entities.invalidate_extents();
+        Ok(adjusted)
+    }
+
+    /// 块改名，同步更新模型空间与各块中的块参照、多重引线与标注引用以及块句柄映射。
+    /// 返回更新的引用数；只改变大小写的改名是允许的。
+    pub fn rename_block(&mut self, name: &str, new_name: &str) -> Result<usize, BlockEditError> {
+        self.editable_block(name)?;
+        if new_name.trim().is_empty()
+            || new_name.starts_with('*')
+            || new_name.contains(XREF_SEPARATOR)
+        {
+            return Err(BlockEditError::InvalidName(new_name.to_string()));
+        }
+        if self
+            .blocks
+            .keys()
+            .any(|existing| existing != name && existing.eq_ignore_ascii_case(new_name))
+        {
+            return Err(BlockEditError::AlreadyExists(new_name.to_string()));
+        }
+        if name == new_name {
+            return Ok(0);
+        }
+
+        let count = |document: &Document| {
+            document
+                .entities()
+                .map(|(_, entity)| entity)
+                .chain(document.blocks.values().flat_map(|block| &block.entities))
+                .filter(|entity| referenced_block(entity) == Some(name))
+                .count()
+        };
+        let renamed = count(self);
+        let mut definition = self.blocks.remove(name).expect("已检查块存在");
+        definition.name = new_name.to_string();
+        self.blocks.insert(new_name.to_string(), definition);
+        let block_map = HashMap::from([(name.to_string(), new_name.to_string())]);
+        self.rename_all_references(&HashMap::new(), &block_map);
+        for block in self.block_handles.values_mut() {
+            if block == name {
+                *block = new_name.to_string();
+            }
+        }
+        Ok(renamed)
+    }
+
+    /// 存在且不属于外部参照的块。
+    fn editable_block(&self, name: &str) -> Result<&BlockDefinition, BlockEditError> {
+        let block = self
+            .blocks
+            .get(name)
+            .ok_or_else(|| BlockEditError::NotFound(name.to_string()))?;
+        if self.xrefs.contains_key(name) || name.contains(XREF_SEPARATOR) {
+            return Err(BlockEditError::XRef(name.to_string()));
+        }
+        Ok(block)
+    }
+
+    /// 直接或经嵌套块引用 `name` 的模型空间实体数。
+    fn block_dependent_count(&self, name: &str) -> usize {
+        let mut dependents = HashSet::from([name]);
+        loop {
+            let before = dependents.len();
+            for block in self.blocks.values() {
+                if block
+                    .entities
+                    .iter()
+                    .filter_map(referenced_block)
+                    .any(|referenced| dependents.contains(referenced))
+                {
+                    dependents.insert(block.name.as_str());
+                }
+            }
+            if dependents.len() == before {
+                break;
+            }
+        }
+        self.entities()
+            .filter_map(|(_, entity)| referenced_block(entity))
+            .filter(|referenced| dependents.contains(referenced))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::*;
+    use crate::document::{BlockReference, EntityId, Line};
+
+    fn line(start: Point2, end: Point2) -> Entity {
+        Entity::Line(Line {
+            start,
+            end,
+            layer: "0".to_string(),
+        })
+    }
+
+    fn block(name: &str, base_point: Point2, entities: Vec<Entity>) -> BlockDefinition {
+        BlockDefinition {
+            name: name.to_string(),
+            base_point,
+            entities,
+            attributes: Vec::new(),
+        }
+    }
+
+    fn reference(name: &str, insert: Point2, rotation: f64) -> Entity {
+        Entity::BlockReference(BlockReference {
+            name: name.to_string(),
+            insert,
+            scale: Vector2::new(2.0, 2.0),
+            rotation,
+            attributes: Vec::new(),
+            layer: "0".to_string(),
+        })
+    }
+
+    fn sample() -> (Document, EntityId) {
+        let mut doc = Document::new();
+        doc.add_block_definition(block(
+            "BOLT",
+            Point2::new(0.0, 0.0),
+            vec![line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0))],
+        ));
+        doc.add_block_definition(block(
+            "PLATE",
+            Point2::new(0.0, 0.0),
+            vec![reference("BOLT", Point2::new(5.0, 0.0), 0.0)],
+        ));
+        let direct = doc.add_entity(reference("BOLT", Point2::new(10.0, 10.0), 0.0));
+        doc.add_entity(reference("PLATE", Point2::new(0.0, 0.0), 0.0));
+        (doc, direct)
+    }
+
+    #[test]
+    fn redefine_updates_bounds_of_all_dependents() {
+        let (mut doc, direct) = sample();
+        assert_eq!(
+            doc.entity_bounds(direct).unwrap().max(),
+            Point2::new(12.0, 10.0)
+        );
+
+        let affected = doc
+            .redefine_block(
+                "BOLT",
+                vec![line(Point2::new(0.0, 0.0), Point2::new(0.0, 3.0))],
+            )
+            .unwrap();
+        assert_eq!(affected, 2);
+        assert_eq!(
+            doc.entity_bounds(direct).unwrap().max(),
+            Point2::new(10.0, 16.0)
+        );
+        assert_eq!(doc.bounds().unwrap().max(), Point2::new(10.0, 16.0));
+
+        assert_eq!(
+            doc.redefine_block("BOLT", vec![reference("PLATE", Point2::new(0.0, 0.0), 0.0)]),
+            Err(BlockEditError::SelfReference("BOLT".to_string()))
+        );
+        assert_eq!(
+            doc.redefine_block("MISSING", Vec::new()),
+            Err(BlockEditError::NotFound("MISSING".to_string()))
+        );
+    }
+
+    #[test]
+    fn base_point_change_keeps_references_in_place() {
+        let (mut doc, direct) = sample();
+        let rotated = doc.add_entity(reference("BOLT", Point2::new(0.0, 20.0), FRAC_PI_2));
+        let before = [
+            doc.entity_bounds(direct).unwrap(),
+            doc.entity_bounds(rotated).unwrap(),
+        ];
+
+        assert_eq!(
+            doc.set_block_base_point("BOLT", Point2::new(1.0, 0.0)),
+            Ok(3)
+        );
+        assert_eq!(doc.block("BOLT").unwrap().base_point, Point2::new(1.0, 0.0));
+        let Some(Entity::BlockReference(moved)) = doc.entity(rotated) else {
+            panic!("应为块参照");
+        };
+        assert!(
+            doc.tolerance()
+                .points_equal(moved.insert, Point2::new(0.0, 22.0))
+        );
+        for (id, expected) in [direct, rotated].into_iter().zip(before) {
+            let after = doc.entity_bounds(id).unwrap();
+            assert!(doc.tolerance().points_equal(after.min(), expected.min()));
+            assert!(doc.tolerance().points_equal(after.max(), expected.max()));
+        }
+    }
+
+    #[test]
+    fn rename_updates_references_and_handles() {
+        let (mut doc, direct) = sample();
+        doc.add_block_definition_with_handle(
+            block("TAG", Point2::new(0.0, 0.0), Vec::new()),
+            Some("1F".to_string()),
+            None,
+        );
+
+        assert_eq!(doc.rename_block("BOLT", "M8"), Ok(2));
+        assert!(doc.block("BOLT").is_none());
+        assert_eq!(doc.block("M8").unwrap().name, "M8");
+        let Some(Entity::BlockReference(renamed)) = doc.entity(direct) else {
+            panic!("应为块参照");
+        };
+        assert_eq!(renamed.name, "M8");
+        assert!(matches!(
+            &doc.block("PLATE").unwrap().entities[0],
+            Entity::BlockReference(nested) if nested.name == "M8"
+        ));
+
+        assert_eq!(doc.rename_block("TAG", "Label"), Ok(0));
+        assert_eq!(doc.block_name_by_handle("1F"), Some("Label"));
+        assert_eq!(
+            doc.rename_block("M8", "plate"),
+            Err(BlockEditError::AlreadyExists("plate".to_string()))
+        );
+        assert_eq!(
+            doc.rename_block("M8", "*U1"),
+            Err(BlockEditError::InvalidName("*U1".to_string()))
+        );
+        assert_eq!(doc.rename_block("M8", "m8"), Ok(2));
+    }
+}
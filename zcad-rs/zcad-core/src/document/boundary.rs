@@ -0,0 +1,689 @@
+//! 边界检测（BPOLY）：由种子点在一组候选对象中找出包围它的最小闭合区域，
+//! 结果为闭合多段线，可转为填充边界环，用于点选填充与房间面积提取。
+//!
+//! 候选对象先拆成直线段与凸度圆弧段（椭圆与样条按离散精度近似），在相互交点与端点落在其他段上的位置打断，
+//! 去掉悬挂段后构成平面图。沿每条有向边始终取最左转的下一条边追踪出所有面，
+//! 逆时针的面为有界区域，取包含种子点的最小者为外边界；顺时针的环是各连通部分的外轮廓，
+//! 落在外边界内且不包围种子点的即为孤岛。
+
+use std::f64::consts::PI;
+use std::fmt;
+
+use glam::DVec2;
+
+use crate::geometry::intersect::{IntersectOptions, closest_parameter, intersect};
+use crate::geometry::{BulgeArc, Point2};
+use crate::tessellate::{self, TessellateOptions};
+
+use super::contain::{Containment, ring_containment};
+use super::{
+    Arc, Document, Entity, EntityId, HatchEdge, HatchLoop, Line, Polyline, PolylineVertex,
+};
+
+/// 边界检测选项。
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundaryOptions {
+    /// 检测外边界内的孤岛。
+    pub detect_islands: bool,
+    /// 端点合并与交点判断的距离容差，不小于文档容差。
+    pub tolerance: f64,
+    /// 椭圆与样条的离散精度。
+    pub tessellate: TessellateOptions,
+    /// 生成多段线所在的图层。
+    pub layer: String,
+}
+
+impl Default for BoundaryOptions {
+    fn default() -> Self {
+        Self {
+            detect_islands: true,
+            tolerance: 1e-6,
+            tessellate: TessellateOptions::default(),
+            layer: "0".to_string(),
+        }
+    }
+}
+
+/// 检测到的边界：逆时针的外边界与顺时针的孤岛。
+#[derive(Debug, Clone)]
+pub struct Boundary {
+    pub outer: Polyline,
+    pub islands: Vec<Polyline>,
+}
+
+impl Boundary {
+    /// 转为填充边界环，外边界在前。
+    pub fn hatch_loops(&self) -> Vec<HatchLoop> {
+        std::iter::once(&self.outer)
+            .chain(&self.islands)
+            .map(|polyline| {
+                let count = polyline.vertices.len();
+                HatchLoop {
+                    is_polyline: true,
+                    is_closed: true,
+                    edges: (0..count)
+                        .map(|index| {
+                            let vertex = &polyline.vertices[index];
+                            HatchEdge::PolylineSegment {
+                                start: vertex.position,
+                                end: polyline.vertices[(index + 1) % count].position,
+                                bulge: vertex.bulge,
+                            }
+                        })
+                        .collect(),
+                    boundary_handles: Vec::new(),
+                    boundary_entities: Vec::new(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoundaryError {
+    NotFound(EntityId),
+    /// 候选对象没有围成包含种子点的闭合区域。
+    NotEnclosed,
+}
+
+impl fmt::Display for BoundaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoundaryError::NotFound(id) => write!(f, "entity {} not found", id.get()),
+            BoundaryError::NotEnclosed => write!(f, "no closed boundary encloses the seed point"),
+        }
+    }
+}
+
+impl std::error::Error for BoundaryError {}
+
+impl Document {
+    /// 在候选对象中检测包围 `seed` 的边界；直线、圆弧、圆、椭圆、多段线、样条与修订云线参与，其他对象忽略。
+    pub fn trace_boundary(
+        &self,
+        seed: Point2,
+        candidates: &[EntityId],
+        options: &BoundaryOptions,
+    ) -> Result<Boundary, BoundaryError> {
+        let tolerance = options.tolerance.max(self.tolerance().linear);
+        let mut segments = Vec::new();
+        for id in candidates {
+            let entity = self.entity(*id).ok_or(BoundaryError::NotFound(*id))?;
+            segments.extend(segments_of(entity, &options.tessellate, tolerance));
+        }
+        let graph = Graph::new(split(&segments, tolerance), tolerance);
+        let cycles = graph.cycles();
+        let seed = seed.as_vec2();
+        let (outer, outer_area) = cycles
+            .iter()
+            .map(|cycle| (cycle, area(cycle)))
+            .filter(|(cycle, area)| *area > tolerance * tolerance && contains(cycle, seed))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .ok_or(BoundaryError::NotEnclosed)?;
+        let mut islands: Vec<&Vec<Segment>> = Vec::new();
+        if options.detect_islands {
+            let candidates: Vec<&Vec<Segment>> = cycles
+                .iter()
+                .filter(|cycle| {
+                    let area = area(cycle);
+                    area < 0.0
+                        && -area < outer_area
+                        && contains(outer, cycle[0].point(0.5))
+                        && !contains(cycle, seed)
+                })
+                .collect();
+            // 孤岛内的孤岛不与外边界相邻
+            islands = candidates
+                .iter()
+                .enumerate()
+                .filter(|(index, island)| {
+                    !candidates.iter().enumerate().any(|(other, outer)| {
+                        other != *index && contains(outer, island[0].point(0.5))
+                    })
+                })
+                .map(|(_, island)| *island)
+                .collect();
+        }
+        let polyline = |cycle: &Vec<Segment>| Polyline {
+            vertices: merged(cycle, tolerance)
+                .into_iter()
+                .map(|segment| {
+                    PolylineVertex::with_bulge(Point2::from_vec(segment.start), segment.bulge)
+                })
+                .collect(),
+            is_closed: true,
+            layer: options.layer.clone(),
+        };
+        Ok(Boundary {
+            outer: polyline(outer),
+            islands: islands.into_iter().map(polyline).collect(),
+        })
+    }
+}
+
+/// 直线段或凸度圆弧段。
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    start: DVec2,
+    end: DVec2,
+    bulge: f64,
+}
+
+impl Segment {
+    fn sweep(&self) -> f64 {
+        4.0 * self.bulge.atan()
+    }
+
+    /// 所在圆弧；直线段返回 `None`。
+    fn arc(&self) -> Option<BulgeArc> {
+        BulgeArc::new(self.start, self.end, self.bulge)
+    }
+
+    fn is_arc(&self) -> bool {
+        self.arc().is_some()
+    }
+
+    fn point(&self, fraction: f64) -> DVec2 {
+        match self.arc() {
+            Some(arc) => arc.point(fraction),
+            None => self.start.lerp(self.end, fraction),
+        }
+    }
+
+    fn sub(&self, from: f64, to: f64) -> Segment {
+        Segment {
+            start: self.point(from),
+            end: self.point(to),
+            bulge: if self.is_arc() {
+                (self.sweep() * (to - from) / 4.0).tan()
+            } else {
+                0.0
+            },
+        }
+    }
+
+    fn reversed(&self) -> Segment {
+        Segment {
+            start: self.end,
+            end: self.start,
+            bulge: -self.bulge,
+        }
+    }
+
+    /// 起点处的切向角与带符号曲率（左转为正），用于在节点处排序出边。
+    fn departure(&self) -> (f64, f64) {
+        let chord = self.end - self.start;
+        let Some(BulgeArc { radius, sweep, .. }) = self.arc() else {
+            return (chord.to_angle(), 0.0);
+        };
+        (
+            DVec2::from_angle(-sweep / 2.0).rotate(chord).to_angle(),
+            sweep.signum() / radius,
+        )
+    }
+
+    /// 参与求交的临时实体及其参数到本段比例的换算。
+    fn entity(&self) -> (Entity, Box<dyn Fn(f64) -> f64>) {
+        let Some(BulgeArc {
+            center,
+            radius,
+            sweep,
+            ..
+        }) = self.arc()
+        else {
+            let line = Entity::Line(Line {
+                start: Point2::from_vec(self.start),
+                end: Point2::from_vec(self.end),
+                layer: String::new(),
+            });
+            return (line, Box::new(|parameter| parameter));
+        };
+        // 顺时针段按反向的逆时针圆弧求交
+        let (from, to) = if sweep > 0.0 {
+            (self.start, self.end)
+        } else {
+            (self.end, self.start)
+        };
+        let arc = Entity::Arc(Arc {
+            center: Point2::from_vec(center),
+            radius,
+            start_angle: (from - center).to_angle(),
+            end_angle: (to - center).to_angle(),
+            layer: String::new(),
+        });
+        let span = sweep.abs();
+        let map: Box<dyn Fn(f64) -> f64> = if sweep > 0.0 {
+            Box::new(move |parameter| parameter / span)
+        } else {
+            Box::new(move |parameter| 1.0 - parameter / span)
+        };
+        (arc, map)
+    }
+
+    /// 带符号面积的贡献：弦的叉积项加弓形面积。
+    fn area(&self) -> f64 {
+        let chordal = self.start.perp_dot(self.end) / 2.0;
+        let Some(BulgeArc { radius, sweep, .. }) = self.arc() else {
+            return chordal;
+        };
+        let sweep = sweep.abs();
+        chordal + self.bulge.signum() * radius * radius / 2.0 * (sweep - sweep.sin())
+    }
+
+    fn bounds(&self) -> (DVec2, DVec2) {
+        match self.arc() {
+            Some(BulgeArc { center, radius, .. }) => {
+                (center - DVec2::splat(radius), center + DVec2::splat(radius))
+            }
+            None => (self.start.min(self.end), self.start.max(self.end)),
+        }
+    }
+}
+
+/// 实体拆成的段；圆弧每段不超过半圈，避免凸度过大。
+fn segments_of(entity: &Entity, options: &TessellateOptions, tolerance: f64) -> Vec<Segment> {
+    let from_vertices = |vertices: &[PolylineVertex], is_closed: bool| {
+        let count = vertices.len();
+        let segment_count = if is_closed {
+            count
+        } else {
+            count.saturating_sub(1)
+        };
+        (0..segment_count)
+            .flat_map(|index| {
+                let vertex = &vertices[index];
+                let next = &vertices[(index + 1) % count];
+                halves(Segment {
+                    start: vertex.position.as_vec2(),
+                    end: next.position.as_vec2(),
+                    bulge: vertex.bulge,
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+    let segments = match entity {
+        Entity::Line(line) => vec![Segment {
+            start: line.start.as_vec2(),
+            end: line.end.as_vec2(),
+            bulge: 0.0,
+        }],
+        Entity::Circle(circle) => {
+            let center = circle.center.as_vec2();
+            let (right, left) = (
+                center + DVec2::X * circle.radius,
+                center - DVec2::X * circle.radius,
+            );
+            vec![
+                Segment {
+                    start: right,
+                    end: left,
+                    bulge: 1.0,
+                },
+                Segment {
+                    start: left,
+                    end: right,
+                    bulge: 1.0,
+                },
+            ]
+        }
+        Entity::Arc(arc) => {
+            let sweep = super::ccw_sweep(arc.start_angle, arc.end_angle);
+            let center = arc.center.as_vec2();
+            let start = center + DVec2::from_angle(arc.start_angle) * arc.radius;
+            let end = center + DVec2::from_angle(arc.start_angle + sweep) * arc.radius;
+            halves(Segment {
+                start,
+                end,
+                bulge: (sweep / 4.0).tan(),
+            })
+        }
+        Entity::Polyline(polyline) => from_vertices(&polyline.vertices, polyline.is_closed),
+        Entity::RevisionCloud(cloud) => from_vertices(&cloud.vertices, cloud.is_closed),
+        Entity::Ellipse(_) | Entity::Spline(_) => tessellate::curve(entity, options)
+            .unwrap_or_default()
+            .windows(2)
+            .map(|pair| Segment {
+                start: pair[0].as_vec2(),
+                end: pair[1].as_vec2(),
+                bulge: 0.0,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    segments
+        .into_iter()
+        .filter(|segment| segment.start.distance(segment.end) > tolerance)
+        .collect()
+}
+
+fn halves(segment: Segment) -> Vec<Segment> {
+    if segment.sweep().abs() <= PI + 1e-9 {
+        vec![segment]
+    } else {
+        vec![segment.sub(0.0, 0.5), segment.sub(0.5, 1.0)]
+    }
+}
+
+/// 在交点与端点落在其他段上的位置打断所有段。
+fn split(segments: &[Segment], tolerance: f64) -> Vec<Segment> {
+    let entities: Vec<_> = segments.iter().map(Segment::entity).collect();
+    let bounds: Vec<_> = segments.iter().map(Segment::bounds).collect();
+    let mut cuts: Vec<Vec<f64>> = vec![Vec::new(); segments.len()];
+    let options = IntersectOptions {
+        tolerance,
+        ..IntersectOptions::default()
+    };
+    for first in 0..segments.len() {
+        for second in first + 1..segments.len() {
+            let ((min_a, max_a), (min_b, max_b)) = (bounds[first], bounds[second]);
+            if min_a.cmpgt(max_b + tolerance).any() || min_b.cmpgt(max_a + tolerance).any() {
+                continue;
+            }
+            let ((entity_a, map_a), (entity_b, map_b)) = (&entities[first], &entities[second]);
+            for hit in intersect(entity_a, entity_b, &options) {
+                cuts[first].push(map_a(hit.first));
+                cuts[second].push(map_b(hit.second));
+            }
+            // 端点落在另一段上（T 形接头、共线重叠）
+            for (own, other, map) in [(first, second, map_b), (second, first, map_a)] {
+                for end in [segments[own].start, segments[own].end] {
+                    if let Some((parameter, closest)) =
+                        closest_parameter(&entities[other].0, Point2::from_vec(end))
+                        && closest.as_vec2().distance(end) <= tolerance
+                    {
+                        cuts[other].push(map(parameter));
+                    }
+                }
+            }
+        }
+    }
+    let mut pieces = Vec::new();
+    for (segment, mut cuts) in segments.iter().zip(cuts) {
+        cuts.retain(|cut| *cut > 0.0 && *cut < 1.0);
+        cuts.push(0.0);
+        cuts.push(1.0);
+        cuts.sort_by(f64::total_cmp);
+        for pair in cuts.windows(2) {
+            let piece = segment.sub(pair[0], pair[1]);
+            if piece.start.distance(piece.end) > tolerance {
+                pieces.push(piece);
+            }
+        }
+    }
+    pieces
+}
+
+/// 平面图：合并后的节点与无向边，边 `k` 对应有向边 `2k`（正向）与 `2k + 1`（反向）。
+struct Graph {
+    edges: Vec<(usize, usize, Segment)>,
+    /// 每个节点按出发切向逆时针排序的出边。
+    outgoing: Vec<Vec<usize>>,
+}
+
+impl Graph {
+    fn new(pieces: Vec<Segment>, tolerance: f64) -> Self {
+        let mut nodes: Vec<DVec2> = Vec::new();
+        let mut node = |point: DVec2| {
+            if let Some(index) = nodes
+                .iter()
+                .position(|node| node.distance(point) <= tolerance)
+            {
+                return index;
+            }
+            nodes.push(point);
+            nodes.len() - 1
+        };
+        let mut edges: Vec<(usize, usize, Segment)> = Vec::new();
+        for piece in pieces {
+            let (a, b) = (node(piece.start), node(piece.end));
+            if a == b {
+                continue;
+            }
+            // 重叠的段只保留一条
+            let middle = piece.point(0.5);
+            if edges.iter().any(|(p, q, other)| {
+                ((*p, *q) == (a, b) || (*p, *q) == (b, a))
+                    && other.point(0.5).distance(middle) <= tolerance
+            }) {
+                continue;
+            }
+            edges.push((a, b, piece));
+        }
+        let node_count = nodes.len();
+        // 反复去掉悬挂边
+        loop {
+            let mut degree = vec![0usize; node_count];
+            for (a, b, _) in &edges {
+                degree[*a] += 1;
+                degree[*b] += 1;
+            }
+            let before = edges.len();
+            edges.retain(|(a, b, _)| degree[*a] > 1 && degree[*b] > 1);
+            if edges.len() == before {
+                break;
+            }
+        }
+        let mut outgoing = vec![Vec::new(); node_count];
+        for (index, (a, b, _)) in edges.iter().enumerate() {
+            outgoing[*a].push(2 * index);
+            outgoing[*b].push(2 * index + 1);
+        }
+        let mut graph = Self {
+            edges,
+            outgoing: Vec::new(),
+        };
+        for list in &mut outgoing {
+            list.sort_by(|a, b| {
+                let (angle_a, curvature_a) = graph.segment(*a).departure();
+                let (angle_b, curvature_b) = graph.segment(*b).departure();
+                angle_a
+                    .total_cmp(&angle_b)
+                    .then(curvature_a.total_cmp(&curvature_b))
+            });
+        }
+        graph.outgoing = outgoing;
+        graph
+    }
+
+    fn segment(&self, half: usize) -> Segment {
+        let segment = self.edges[half / 2].2;
+        if half.is_multiple_of(2) {
+            segment
+        } else {
+            segment.reversed()
+        }
+    }
+
+    fn target(&self, half: usize) -> usize {
+        let (a, b, _) = self.edges[half / 2];
+        if half.is_multiple_of(2) { b } else { a }
+    }
+
+    /// 到达终点后取反向边顺时针方向的相邻出边，即最左转的一条，面始终在左侧。
+    fn next(&self, half: usize) -> usize {
+        let list = &self.outgoing[self.target(half)];
+        let twin = half ^ 1;
+        let position = list.iter().position(|edge| *edge == twin).unwrap_or(0);
+        list[(position + list.len() - 1) % list.len()]
+    }
+
+    fn cycles(&self) -> Vec<Vec<Segment>> {
+        let mut visited = vec![false; self.edges.len() * 2];
+        let mut cycles = Vec::new();
+        for start in 0..visited.len() {
+            if visited[start] {
+                continue;
+            }
+            let mut cycle = Vec::new();
+            let mut half = start;
+            while !visited[half] {
+                visited[half] = true;
+                cycle.push(self.segment(half));
+                half = self.next(half);
+            }
+            if half == start {
+                cycles.push(cycle);
+            }
+        }
+        cycles
+    }
+}
+
+fn area(cycle: &[Segment]) -> f64 {
+    cycle.iter().map(Segment::area).sum()
+}
+
+/// 点是否严格在环内，圆弧段精确判断。
+fn contains(cycle: &[Segment], point: DVec2) -> bool {
+    let ring: Vec<_> = cycle
+        .iter()
+        .map(|segment| (segment.start, segment.end, segment.bulge))
+        .collect();
+    ring_containment(&ring, point, 0.0) == Containment::Inside
+}
+
+/// 合并同一直线或同一圆上相接的段，去掉打断产生的多余顶点。
+fn merged(cycle: &[Segment], tolerance: f64) -> Vec<Segment> {
+    let joinable = |a: &Segment, b: &Segment| match (a.arc(), b.arc()) {
+        (None, None) => {
+            let (u, v) = (a.end - a.start, b.end - b.start);
+            u.perp_dot(v).abs() <= tolerance * u.length().max(v.length()) && u.dot(v) > 0.0
+        }
+        (Some(first), Some(second)) => {
+            first.sweep.signum() == second.sweep.signum()
+                && first.center.distance(second.center) <= tolerance
+                && (first.radius - second.radius).abs() <= tolerance
+                && (first.sweep + second.sweep).abs() < 2.0 * PI - 1e-6
+        }
+        _ => false,
+    };
+    let join = |a: &Segment, b: &Segment| Segment {
+        start: a.start,
+        end: b.end,
+        bulge: if a.is_arc() {
+            ((a.sweep() + b.sweep()) / 4.0).tan()
+        } else {
+            0.0
+        },
+    };
+    let mut result: Vec<Segment> = Vec::new();
+    for segment in cycle {
+        match result.last_mut() {
+            Some(last) if joinable(last, segment) => *last = join(last, segment),
+            _ => result.push(*segment),
+        }
+    }
+    if result.len() > 2 && joinable(&result[result.len() - 1], &result[0]) {
+        let last = result.remove(result.len() - 1);
+        result[0] = join(&last, &result[0]);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polyline_area(polyline: &Polyline) -> f64 {
+        let count = polyline.vertices.len();
+        (0..count)
+            .map(|index| {
+                Segment {
+                    start: polyline.vertices[index].position.as_vec2(),
+                    end: polyline.vertices[(index + 1) % count].position.as_vec2(),
+                    bulge: polyline.vertices[index].bulge,
+                }
+                .area()
+            })
+            .sum()
+    }
+
+    fn rectangle(doc: &mut Document, min: (f64, f64), max: (f64, f64)) -> Vec<EntityId> {
+        let corners = [
+            Point2::new(min.0, min.1),
+            Point2::new(max.0, min.1),
+            Point2::new(max.0, max.1),
+            Point2::new(min.0, max.1),
+        ];
+        (0..4)
+            .map(|index| doc.add_line(corners[index], corners[(index + 1) % 4], "0"))
+            .collect()
+    }
+
+    #[test]
+    fn traces_room_with_island_and_ignores_dangling_lines() {
+        let mut doc = Document::new();
+        let mut ids = rectangle(&mut doc, (0.0, 0.0), (10.0, 6.0));
+        // 一条伸出墙外的线与一条内部悬挂线
+        ids.push(doc.add_line(Point2::new(5.0, -2.0), Point2::new(5.0, 2.0), "0"));
+        ids.push(doc.add_circle(Point2::new(7.0, 3.0), 1.0, "0"));
+        let boundary = doc
+            .trace_boundary(Point2::new(2.0, 3.0), &ids, &BoundaryOptions::default())
+            .unwrap();
+        // 打断产生的 (5, 0) 顶点被合并
+        assert_eq!(boundary.outer.vertices.len(), 4);
+        assert!((polyline_area(&boundary.outer) - 60.0).abs() < 1e-9);
+        assert_eq!(boundary.islands.len(), 1);
+        assert!((polyline_area(&boundary.islands[0]) + PI).abs() < 1e-9);
+        let loops = boundary.hatch_loops();
+        assert_eq!(loops.len(), 2);
+        assert!(loops.iter().all(|loop_path| loop_path.is_polyline));
+
+        let without = doc
+            .trace_boundary(
+                Point2::new(2.0, 3.0),
+                &ids,
+                &BoundaryOptions {
+                    detect_islands: false,
+                    ..BoundaryOptions::default()
+                },
+            )
+            .unwrap();
+        assert!(without.islands.is_empty());
+
+        // 种子点在圆内时圆即边界
+        let inner = doc
+            .trace_boundary(Point2::new(7.0, 3.0), &ids, &BoundaryOptions::default())
+            .unwrap();
+        assert!((polyline_area(&inner.outer) - PI).abs() < 1e-9);
+    }
+
+    #[test]
+    fn finds_smallest_region_among_overlaps() {
+        let mut doc = Document::new();
+        let mut ids = rectangle(&mut doc, (0.0, 0.0), (4.0, 4.0));
+        ids.extend(rectangle(&mut doc, (2.0, 2.0), (6.0, 6.0)));
+        let boundary = doc
+            .trace_boundary(Point2::new(3.0, 3.0), &ids, &BoundaryOptions::default())
+            .unwrap();
+        assert!((polyline_area(&boundary.outer) - 4.0).abs() < 1e-9);
+
+        // 直线与圆弧围成的半圆区域
+        let mut doc = Document::new();
+        let ids = vec![
+            doc.add_line(Point2::new(-2.0, 0.0), Point2::new(2.0, 0.0), "0"),
+            doc.add_arc(Point2::new(0.0, 0.0), 2.0, 0.0, PI, "0"),
+        ];
+        let boundary = doc
+            .trace_boundary(Point2::new(0.0, 1.0), &ids, &BoundaryOptions::default())
+            .unwrap();
+        assert_eq!(boundary.outer.vertices.len(), 2);
+        assert!((polyline_area(&boundary.outer) - 2.0 * PI).abs() < 1e-9);
+
+        assert_eq!(
+            doc.trace_boundary(Point2::new(0.0, -1.0), &ids, &BoundaryOptions::default())
+                .unwrap_err(),
+            BoundaryError::NotEnclosed
+        );
+        let missing = EntityId::new(999);
+        assert_eq!(
+            doc.trace_boundary(
+                Point2::new(0.0, 1.0),
+                &[missing],
+                &BoundaryOptions::default()
+            )
+            .unwrap_err(),
+            BoundaryError::NotFound(missing)
+        );
+    }
+}
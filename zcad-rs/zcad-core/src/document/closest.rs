@@ -0,0 +1,373 @@
+//! 最近点查询：实体上离给定点最近的位置与距离，供捕捉、标注与拾取使用。
+//!
+//! 曲线按几何精确计算，椭圆与样条迭代逼近；填充取边界；文字、形、图像、遮罩、底图与三维面视为实心区域，
+//! 点落在区域内时距离为零。块参照只计插入点与属性位置，标注只计定义点，块内容见 [`Document::flatten`]。
+
+use glam::DVec2;
+
+use crate::geometry::intersect::closest_parameter;
+use crate::geometry::{Bounds2D, Point2, Vector2};
+
+#[cfg(doc)]
+use super::Document;
+use super::explode::explode_hatch;
+use super::{Entity, MLeaderContent, Polyline, PolylineVertex, RasterImageClip};
+
+impl Entity {
+    /// 实体上离 `point` 最近的点；没有几何（如空多段线）时返回 `None`。
+    pub fn closest_point(&self, point: Point2) -> Option<Point2> {
+        let target = point.as_vec2();
+        match self {
+            Entity::Line(_)
+            | Entity::Circle(_)
+            | Entity::Arc(_)
+            | Entity::Ellipse(_)
+            | Entity::Polyline(_)
+            | Entity::Spline(_) => closest_parameter(self, point).map(|(_, closest)| closest),
+            Entity::RevisionCloud(cloud) => {
+                on_polyline(cloud.vertices.clone(), cloud.is_closed, point)
+            }
+            Entity::Leader(leader) => on_polyline(
+                leader
+                    .vertices
+                    .iter()
+                    .copied()
+                    .map(PolylineVertex::new)
+                    .collect(),
+                false,
+                point,
+            ),
+            Entity::MLeader(mleader) => {
+                let location = match &mleader.content {
+                    MLeaderContent::MText { location, .. } => Some(*location),
+                    MLeaderContent::Block { block } => Some(block.location),
+                    MLeaderContent::None => None,
+                };
+                let lines = mleader.leader_lines.iter().filter_map(|line| {
+                    on_polyline(
+                        line.vertices
+                            .iter()
+                            .copied()
+                            .map(PolylineVertex::new)
+                            .collect(),
+                        false,
+                        point,
+                    )
+                });
+                nearest(lines.chain(location), target)
+            }
+            Entity::Hatch(hatch) => nearest(
+                explode_hatch(hatch)
+                    .iter()
+                    .filter_map(|edge| edge.closest_point(point)),
+                target,
+            ),
+            Entity::Text(text) => in_region(&rectangle(&text.estimated_bounds()), target),
+            Entity::MText(mtext) => in_region(&rectangle(&mtext.estimated_bounds()), target),
+            Entity::Shape(shape) => in_region(&shape.outline(), target),
+            Entity::RasterImage(image) => in_region(
+                &image_outline(
+                    image.insert,
+                    image.u_vector,
+                    image.v_vector,
+                    image.image_size,
+                    image.clip.as_ref(),
+                ),
+                target,
+            ),
+            Entity::Wipeout(wipeout) => in_region(
+                &image_outline(
+                    wipeout.insert,
+                    wipeout.u_vector,
+                    wipeout.v_vector,
+                    wipeout.image_size,
+                    wipeout.clip.as_ref(),
+                ),
+                target,
+            ),
+            Entity::Underlay(underlay) => {
+                let outline = underlay.clip_outline();
+                if outline.is_empty() {
+                    Some(underlay.insert)
+                } else {
+                    in_region(&outline, target)
+                }
+            }
+            Entity::Face3D(face) => {
+                let corners = face
+                    .vertices
+                    .iter()
+                    .map(|vertex| Point2::new(vertex.x(), vertex.y()))
+                    .collect::<Vec<_>>();
+                in_region(&corners, target)
+            }
+            Entity::BlockReference(reference) => nearest(
+                std::iter::once(reference.insert).chain(
+                    reference
+                        .attributes
+                        .iter()
+                        .flat_map(|attr| std::iter::once(attr.insert).chain(attr.alignment)),
+                ),
+                target,
+            ),
+            Entity::Dimension(dimension) => nearest(
+                [
+                    Some(dimension.definition_point),
+                    Some(dimension.text_midpoint),
+                    dimension.dimension_line_point,
+                    dimension.extension_line_origin,
+                    dimension.extension_line_end,
+                    dimension.secondary_point,
+                    dimension.arc_definition_point,
+                    dimension.center_point,
+                    dimension.jog_point,
+                ]
+                .into_iter()
+                .flatten(),
+                target,
+            ),
+        }
+    }
+
+    /// 点到实体的最近距离。
+    pub fn distance_to(&self, point: Point2) -> Option<f64> {
+        self.closest_point(point)
+            .map(|closest| closest.as_vec2().distance(point.as_vec2()))
+    }
+}
+
+fn nearest(candidates: impl IntoIterator<Item = Point2>, target: DVec2) -> Option<Point2> {
+    candidates.into_iter().min_by(|a, b| {
+        a.as_vec2()
+            .distance_squared(target)
+            .total_cmp(&b.as_vec2().distance_squared(target))
+    })
+}
+
+fn on_polyline(vertices: Vec<PolylineVertex>, is_closed: bool, point: Point2) -> Option<Point2> {
+    if vertices.len() == 1 {
+        return Some(vertices[0].position);
+    }
+    let polyline = Entity::Polyline(Polyline {
+        vertices,
+        is_closed,
+        layer: String::new(),
+    });
+    closest_parameter(&polyline, point).map(|(_, closest)| closest)
+}
+
+fn rectangle(bounds: &Bounds2D) -> Vec<Point2> {
+    if bounds.is_empty() {
+        return Vec::new();
+    }
+    let (min, max) = (bounds.min(), bounds.max());
+    vec![
+        min,
+        Point2::new(max.x(), min.y()),
+        max,
+        Point2::new(min.x(), max.y()),
+    ]
+}
+
+/// 图像或遮罩在世界坐标中的外框，有裁剪时取裁剪边界。
+fn image_outline(
+    insert: Point2,
+    u_vector: Vector2,
+    v_vector: Vector2,
+    image_size: Vector2,
+    clip: Option<&RasterImageClip>,
+) -> Vec<Point2> {
+    let local = match clip {
+        Some(RasterImageClip::Rectangle { min, max, .. }) => vec![
+            *min,
+            Point2::new(max.x(), min.y()),
+            *max,
+            Point2::new(min.x(), max.y()),
+        ],
+        Some(RasterImageClip::Polygon { vertices, .. }) => vertices.clone(),
+        None => vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(image_size.x(), 0.0),
+            Point2::new(image_size.x(), image_size.y()),
+            Point2::new(0.0, image_size.y()),
+        ],
+    };
+    let (origin, u, v) = (insert.as_vec2(), u_vector.as_vec2(), v_vector.as_vec2());
+    local
+        .into_iter()
+        .map(|point| Point2::from_vec(origin + u * point.x() + v * point.y()))
+        .collect()
+}
+
+/// 闭合区域：点在区域内（奇偶规则）时返回该点，否则返回边界上的最近点。
+fn in_region(corners: &[Point2], target: DVec2) -> Option<Point2> {
+    match corners {
+        [] => return None,
+        [single] => return Some(*single),
+        _ => {}
+    }
+    let count = corners.len();
+    let mut inside = false;
+    let mut best: Option<DVec2> = None;
+    for index in 0..count {
+        let (a, b) = (
+            corners[index].as_vec2(),
+            corners[(index + 1) % count].as_vec2(),
+        );
+        if (a.y > target.y) != (b.y > target.y)
+            && target.x < a.x + (target.y - a.y) / (b.y - a.y) * (b.x - a.x)
+        {
+            inside = !inside;
+        }
+        let direction = b - a;
+        let length = direction.length_squared();
+        let fraction = if length <= f64::EPSILON {
+            0.0
+        } else {
+            ((target - a).dot(direction) / length).clamp(0.0, 1.0)
+        };
+        let candidate = a.lerp(b, fraction);
+        if best
+            .is_none_or(|best| candidate.distance_squared(target) < best.distance_squared(target))
+        {
+            best = Some(candidate);
+        }
+    }
+    if inside {
+        Some(Point2::from_vec(target))
+    } else {
+        best.map(Point2::from_vec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::document::{Document, HatchEdge, HatchLoop, HatchStyle, Spline};
+
+    fn assert_near(actual: Option<Point2>, x: f64, y: f64) {
+        let actual = actual.expect("expected a closest point");
+        assert!(
+            (actual.x() - x).abs() < 1e-9 && (actual.y() - y).abs() < 1e-9,
+            "{actual:?} != ({x}, {y})"
+        );
+    }
+
+    #[test]
+    fn finds_closest_points_on_curves() {
+        let mut doc = Document::new();
+        let line = doc.add_line(Point2::new(0.0, 0.0), Point2::new(4.0, 0.0), "0");
+        let line = doc.entity(line).unwrap();
+        assert_near(line.closest_point(Point2::new(1.0, 3.0)), 1.0, 0.0);
+        assert_near(line.closest_point(Point2::new(9.0, 1.0)), 4.0, 0.0);
+        assert_eq!(line.distance_to(Point2::new(1.0, 3.0)), Some(3.0));
+
+        let arc = doc.add_arc(Point2::new(0.0, 0.0), 2.0, 0.0, PI / 2.0, "0");
+        let arc = doc.entity(arc).unwrap();
+        assert_near(
+            arc.closest_point(Point2::new(3.0, 3.0)),
+            2f64.sqrt(),
+            2f64.sqrt(),
+        );
+        // 圆弧外的点取较近的端点
+        assert_near(arc.closest_point(Point2::new(1.0, -3.0)), 2.0, 0.0);
+        let circle = doc.add_circle(Point2::new(0.0, 0.0), 2.0, "0");
+        assert_near(
+            doc.entity(circle)
+                .unwrap()
+                .closest_point(Point2::new(0.0, -5.0)),
+            0.0,
+            -2.0,
+        );
+
+        // 直线段后接逆时针半圆
+        let polyline = doc.add_polyline_with_vertices(
+            vec![
+                PolylineVertex::new(Point2::new(0.0, 0.0)),
+                PolylineVertex::with_bulge(Point2::new(2.0, 0.0), 1.0),
+                PolylineVertex::new(Point2::new(2.0, 2.0)),
+            ],
+            false,
+            "0",
+        );
+        let polyline = doc.entity(polyline).unwrap();
+        assert_near(polyline.closest_point(Point2::new(5.0, 1.0)), 3.0, 1.0);
+        assert_eq!(polyline.distance_to(Point2::new(5.0, 1.0)), Some(2.0));
+
+        let spline = Entity::Spline(Spline {
+            degree: 3,
+            is_rational: false,
+            is_closed: false,
+            is_periodic: false,
+            control_points: vec![
+                Point2::new(0.0, 0.0),
+                Point2::new(1.0, 2.0),
+                Point2::new(3.0, 2.0),
+                Point2::new(4.0, 0.0),
+            ],
+            fit_points: Vec::new(),
+            knot_values: Vec::new(),
+            weights: Vec::new(),
+            start_tangent: None,
+            end_tangent: None,
+            layer: "0".to_string(),
+        });
+        assert_near(spline.closest_point(Point2::new(2.0, 4.0)), 2.0, 1.5);
+    }
+
+    #[test]
+    fn treats_text_as_region_and_hatch_as_boundary() {
+        let mut doc = Document::new();
+        let text = doc.add_text(Point2::new(0.0, 0.0), "ABCD", 1.0, 0.0, "0");
+        let text = doc.entity(text).unwrap();
+        assert_eq!(text.distance_to(Point2::new(0.5, 0.5)), Some(0.0));
+        assert_near(text.closest_point(Point2::new(1.0, -2.0)), 1.0, 0.0);
+
+        let square = HatchLoop {
+            is_polyline: false,
+            is_closed: true,
+            edges: vec![
+                HatchEdge::Line {
+                    start: Point2::new(0.0, 0.0),
+                    end: Point2::new(4.0, 0.0),
+                },
+                HatchEdge::Line {
+                    start: Point2::new(4.0, 0.0),
+                    end: Point2::new(4.0, 4.0),
+                },
+            ],
+            boundary_handles: Vec::new(),
+            boundary_entities: Vec::new(),
+        };
+        let hatch = doc.add_hatch(
+            "SOLID",
+            true,
+            vec![square],
+            None,
+            Vec::new(),
+            HatchStyle::Normal,
+            false,
+            Vec::new(),
+            "0",
+        );
+        let hatch = doc.entity(hatch).unwrap();
+        assert_near(hatch.closest_point(Point2::new(5.0, 2.0)), 4.0, 2.0);
+
+        let reference = doc.add_block_reference(
+            "MISSING",
+            Point2::new(10.0, 10.0),
+            Vector2::new(1.0, 1.0),
+            0.0,
+            Vec::new(),
+            "0",
+        );
+        assert_eq!(
+            doc.entity(reference)
+                .unwrap()
+                .distance_to(Point2::new(13.0, 14.0)),
+            Some(5.0)
+        );
+    }
+}
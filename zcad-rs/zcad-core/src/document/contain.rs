@@ -0,0 +1,474 @@
+//! 点包含判断：点位于闭合曲线或填充区域的内部、外部还是边界上，供框选、孤岛检测与边界追踪使用。
+//!
+//! 凸度多段线精确判断：奇偶射线检测中圆弧段按 y 单调子弧直接求交，不经离散。
+//! 圆与椭圆按解析式判断；样条以及填充中的椭圆、样条边先离散为折线。到边界的距离不超过容差时判为在边界上。
+//! 填充按孤岛样式筛选边界环，再以奇偶规则统计包含该点的环数。
+
+use std::f64::consts::{PI, TAU};
+
+use glam::DVec2;
+
+use crate::geometry::{BulgeArc, Point2};
+use crate::tessellate::{self, TessellateOptions};
+
+use super::{
+    Entity, Hatch, HatchEdge, HatchLoop, HatchStyle, PolylineVertex, ccw_sweep, edge_sweep,
+};
+
+/// 点与闭合区域的位置关系。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Containment {
+    Inside,
+    Outside,
+    /// 到边界的距离不超过容差。
+    Boundary,
+}
+
+impl Containment {
+    /// 在内部或边界上。
+    pub fn is_enclosed(self) -> bool {
+        !matches!(self, Containment::Outside)
+    }
+}
+
+/// 闭合环的一段：起点、终点与凸度。
+type Piece = (DVec2, DVec2, f64);
+
+impl Entity {
+    /// 判断点与闭合实体的位置关系；开放曲线与非曲线实体返回 `None`。
+    pub fn containment(&self, point: Point2, tolerance: f64) -> Option<Containment> {
+        let (target, tolerance) = (point.as_vec2(), tolerance.abs());
+        match self {
+            Entity::Circle(circle) => {
+                let distance = target.distance(circle.center.as_vec2());
+                let radius = circle.radius.abs();
+                Some(if (distance - radius).abs() <= tolerance {
+                    Containment::Boundary
+                } else if distance < radius {
+                    Containment::Inside
+                } else {
+                    Containment::Outside
+                })
+            }
+            Entity::Ellipse(ellipse) => {
+                let sweep = ccw_sweep(ellipse.start_parameter, ellipse.end_parameter);
+                if sweep < TAU - 1e-9 {
+                    return None;
+                }
+                if self.distance_to(point)? <= tolerance {
+                    return Some(Containment::Boundary);
+                }
+                let major = ellipse.major_axis.as_vec2();
+                let minor = major.perp() * ellipse.ratio;
+                let offset = target - ellipse.center.as_vec2();
+                let local = DVec2::new(
+                    offset.dot(major) / major.length_squared(),
+                    offset.dot(minor) / minor.length_squared(),
+                );
+                Some(if local.length_squared() < 1.0 {
+                    Containment::Inside
+                } else {
+                    Containment::Outside
+                })
+            }
+            Entity::Polyline(polyline) if polyline.is_closed => Some(ring_containment(
+                &vertex_ring(&polyline.vertices),
+                target,
+                tolerance,
+            )),
+            Entity::RevisionCloud(cloud) if cloud.is_closed => Some(ring_containment(
+                &vertex_ring(&cloud.vertices),
+                target,
+                tolerance,
+            )),
+            Entity::Spline(spline) => {
+                let points = tessellate::spline(spline, &fine());
+                let (first, last) = (points.first()?.as_vec2(), points.last()?.as_vec2());
+                if !spline.is_closed && !spline.is_periodic && first.distance(last) > tolerance {
+                    return None;
+                }
+                if self.distance_to(point)? <= tolerance {
+                    return Some(Containment::Boundary);
+                }
+                Some(ring_containment(&point_ring(&points), target, 0.0))
+            }
+            Entity::Hatch(hatch) => hatch.containment(point, tolerance),
+            _ => None,
+        }
+    }
+}
+
+impl HatchLoop {
+    /// 判断点与单个边界环的位置关系，边界引用边不计入。
+    pub fn containment(&self, point: Point2, tolerance: f64) -> Containment {
+        ring_containment(&loop_ring(self), point.as_vec2(), tolerance.abs())
+    }
+}
+
+impl Hatch {
+    /// 判断点是否落在填充区域内；没有边界环时返回 `None`。
+    pub fn containment(&self, point: Point2, tolerance: f64) -> Option<Containment> {
+        let rings: Vec<Vec<Piece>> = self
+            .loops
+            .iter()
+            .map(loop_ring)
+            .filter(|ring| !ring.is_empty())
+            .collect();
+        if rings.is_empty() {
+            return None;
+        }
+        let max_depth = match self.style {
+            HatchStyle::Normal => usize::MAX,
+            HatchStyle::Outer => 1,
+            HatchStyle::Ignore => 0,
+        };
+        let (target, tolerance) = (point.as_vec2(), tolerance.abs());
+        let mut inside = false;
+        for (index, ring) in rings.iter().enumerate() {
+            let probe = ring[0].0;
+            let depth = rings
+                .iter()
+                .enumerate()
+                .filter(|(other, outer)| {
+                    *other != index && ring_containment(outer, probe, 0.0) == Containment::Inside
+                })
+                .count();
+            if depth > max_depth {
+                continue;
+            }
+            match ring_containment(ring, target, tolerance) {
+                Containment::Boundary => return Some(Containment::Boundary),
+                Containment::Inside => inside = !inside,
+                Containment::Outside => {}
+            }
+        }
+        Some(if inside {
+            Containment::Inside
+        } else {
+            Containment::Outside
+        })
+    }
+}
+
+/// 奇偶规则的点在多边形内判断，多边形首尾自动相接。
+pub(crate) fn polygon_contains(polygon: &[DVec2], point: DVec2) -> bool {
+    let mut inside = false;
+    for (index, a) in polygon.iter().enumerate() {
+        let b = polygon[(index + 1) % polygon.len()];
+        if crosses(*a, b, point) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// 由起点、终点与凸度组成的闭合环的包含判断，`tolerance` 为零时只区分内外。
+pub(crate) fn ring_containment(ring: &[Piece], point: DVec2, tolerance: f64) -> Containment {
+    let mut inside = false;
+    for &(start, end, bulge) in ring {
+        if tolerance > 0.0 && distance_to_piece(start, end, bulge, point) <= tolerance {
+            return Containment::Boundary;
+        }
+        let crossed = match BulgeArc::new(start, end, bulge) {
+            Some(arc) => arc_crosses(&arc, start, end, point),
+            None => crosses(start, end, point),
+        };
+        if crossed {
+            inside = !inside;
+        }
+    }
+    if inside {
+        Containment::Inside
+    } else {
+        Containment::Outside
+    }
+}
+
+/// 向 +X 方向的射线是否穿过线段（半开区间规则，避免顶点重复计数）。
+fn crosses(a: DVec2, b: DVec2, point: DVec2) -> bool {
+    (a.y > point.y) != (b.y > point.y)
+        && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+}
+
+/// 向 +X 方向的射线穿过圆弧的次数是否为奇数。圆弧在最高、最低点处拆成 y 单调的子弧，
+/// 每段子弧按与直线段相同的半开区间规则计数。
+fn arc_crosses(arc: &BulgeArc, start: DVec2, end: DVec2, point: DVec2) -> bool {
+    let BulgeArc {
+        center,
+        radius,
+        start_angle: from,
+        sweep,
+    } = *arc;
+    // 子弧分界：弧内经过的 π/2 + kπ
+    let (low, high) = if sweep > 0.0 {
+        (from, from + sweep)
+    } else {
+        (from + sweep, from)
+    };
+    let mut cuts: Vec<f64> = Vec::new();
+    let mut turn = ((low - PI / 2.0) / PI).floor() + 1.0;
+    while turn * PI + PI / 2.0 < high {
+        cuts.push(turn * PI + PI / 2.0 - from);
+        turn += 1.0;
+    }
+    cuts.sort_by(|a, b| a.abs().total_cmp(&b.abs()));
+
+    let at = |offset: f64| center + DVec2::from_angle(from + offset) * radius;
+    let mut crossings = false;
+    let mut previous = (0.0, start);
+    for next in cuts
+        .into_iter()
+        .map(|offset| (offset, at(offset)))
+        .chain([(sweep, end)])
+    {
+        let ((a_offset, a), (b_offset, b)) = (previous, next);
+        if (a.y > point.y) != (b.y > point.y) {
+            let side = (from + (a_offset + b_offset) / 2.0).cos().signum();
+            let reach = (radius * radius - (point.y - center.y).powi(2))
+                .max(0.0)
+                .sqrt();
+            if point.x < center.x + side * reach {
+                crossings = !crossings;
+            }
+        }
+        previous = next;
+    }
+    crossings
+}
+
+fn distance_to_piece(start: DVec2, end: DVec2, bulge: f64, point: DVec2) -> f64 {
+    let Some(BulgeArc {
+        center,
+        radius,
+        sweep,
+        ..
+    }) = BulgeArc::new(start, end, bulge)
+    else {
+        let direction = end - start;
+        let length_squared = direction.length_squared();
+        if length_squared <= f64::EPSILON {
+            return point.distance(start);
+        }
+        let t = ((point - start).dot(direction) / length_squared).clamp(0.0, 1.0);
+        return point.distance(start + direction * t);
+    };
+    let turned = (start - center).angle_to(point - center) * sweep.signum();
+    if turned.rem_euclid(TAU) <= sweep.abs() {
+        (point.distance(center) - radius).abs()
+    } else {
+        point.distance(start).min(point.distance(end))
+    }
+}
+
+fn vertex_ring(vertices: &[PolylineVertex]) -> Vec<Piece> {
+    let count = vertices.len();
+    (0..count)
+        .map(|index| {
+            let next = &vertices[(index + 1) % count];
+            (
+                vertices[index].position.as_vec2(),
+                next.position.as_vec2(),
+                vertices[index].bulge,
+            )
+        })
+        .collect()
+}
+
+fn point_ring(points: &[Point2]) -> Vec<Piece> {
+    let count = points.len();
+    (0..count)
+        .map(|index| {
+            (
+                points[index].as_vec2(),
+                points[(index + 1) % count].as_vec2(),
+                0.0,
+            )
+        })
+        .collect()
+}
+
+/// 填充边界环转为凸度环：圆弧边按不超过半圈分段，椭圆与样条边离散为直线段。
+fn loop_ring(loop_path: &HatchLoop) -> Vec<Piece> {
+    let mut ring = Vec::new();
+    for edge in &loop_path.edges {
+        match edge {
+            HatchEdge::Line { start, end } => ring.push((start.as_vec2(), end.as_vec2(), 0.0)),
+            HatchEdge::PolylineSegment { start, end, bulge } => {
+                ring.push((start.as_vec2(), end.as_vec2(), *bulge))
+            }
+            HatchEdge::Arc {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+                is_counter_clockwise,
+            } => {
+                let (start, sweep) = edge_sweep(*start_angle, *end_angle, *is_counter_clockwise);
+                let pieces = (sweep.abs() / PI).ceil().max(1.0) as usize;
+                let step = sweep / pieces as f64;
+                let at = |angle: f64| center.as_vec2() + DVec2::from_angle(angle) * *radius;
+                for index in 0..pieces {
+                    let from = start + step * index as f64;
+                    ring.push((at(from), at(from + step), (step / 4.0).tan()));
+                }
+            }
+            HatchEdge::Ellipse { .. } | HatchEdge::Spline { .. } => {
+                let points = tessellate::hatch_edge(edge, &fine());
+                ring.extend(
+                    points
+                        .windows(2)
+                        .map(|pair| (pair[0].as_vec2(), pair[1].as_vec2(), 0.0)),
+                );
+            }
+            HatchEdge::BoundaryReference { .. } => {}
+        }
+    }
+    ring
+}
+
+/// 离散椭圆与样条时使用的精度。
+fn fine() -> TessellateOptions {
+    TessellateOptions {
+        chord_tolerance: 1e-6,
+        ..TessellateOptions::per_turn(1024)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{Circle, Ellipse, Line, Polyline, Spline};
+    use crate::geometry::Vector2;
+
+    fn at(entity: &Entity, x: f64, y: f64) -> Option<Containment> {
+        entity.containment(Point2::new(x, y), 1e-6)
+    }
+
+    #[test]
+    fn bulged_polylines_are_exact() {
+        // 2×2 正方形，右边外凸半圆，上边内凹半圆
+        let polyline = Entity::Polyline(Polyline {
+            vertices: vec![
+                PolylineVertex::new(Point2::new(0.0, 0.0)),
+                PolylineVertex::with_bulge(Point2::new(2.0, 0.0), 1.0),
+                PolylineVertex::with_bulge(Point2::new(2.0, 2.0), -1.0),
+                PolylineVertex::new(Point2::new(0.0, 2.0)),
+            ],
+            is_closed: true,
+            layer: "0".to_string(),
+        });
+        assert_eq!(at(&polyline, 2.9, 1.0), Some(Containment::Inside));
+        assert_eq!(at(&polyline, 3.0, 1.0), Some(Containment::Boundary));
+        assert_eq!(at(&polyline, 3.1, 1.0), Some(Containment::Outside));
+        assert_eq!(at(&polyline, 1.0, 1.5), Some(Containment::Outside));
+        assert_eq!(at(&polyline, 1.0, 0.9), Some(Containment::Inside));
+        assert_eq!(at(&polyline, 1.0, 1.0), Some(Containment::Boundary));
+        assert_eq!(at(&polyline, 0.0, 0.5), Some(Containment::Boundary));
+        // 射线穿过顶点
+        assert_eq!(at(&polyline, -1.0, 0.0), Some(Containment::Outside));
+        assert_eq!(at(&polyline, -1.0, 2.0), Some(Containment::Outside));
+
+        let Entity::Polyline(mut open) = polyline.clone() else {
+            unreachable!()
+        };
+        open.is_closed = false;
+        assert_eq!(at(&Entity::Polyline(open), 1.0, 0.5), None);
+        let line = Entity::Line(Line {
+            start: Point2::new(0.0, 0.0),
+            end: Point2::new(1.0, 0.0),
+            layer: "0".to_string(),
+        });
+        assert_eq!(at(&line, 0.5, 0.0), None);
+    }
+
+    #[test]
+    fn circles_ellipses_and_closed_splines() {
+        let circle = Entity::Circle(Circle {
+            center: Point2::new(1.0, 1.0),
+            radius: 2.0,
+            layer: "0".to_string(),
+        });
+        assert_eq!(at(&circle, 2.0, 2.0), Some(Containment::Inside));
+        assert_eq!(at(&circle, 3.0, 1.0), Some(Containment::Boundary));
+        assert_eq!(at(&circle, 3.0, 3.0), Some(Containment::Outside));
+
+        let ellipse = Entity::Ellipse(Ellipse {
+            center: Point2::new(0.0, 0.0),
+            major_axis: Vector2::new(0.0, 4.0),
+            ratio: 0.5,
+            start_parameter: 0.0,
+            end_parameter: TAU,
+            layer: "0".to_string(),
+        });
+        assert_eq!(at(&ellipse, 0.0, 3.9), Some(Containment::Inside));
+        assert_eq!(at(&ellipse, 1.9, 0.0), Some(Containment::Inside));
+        assert_eq!(at(&ellipse, 2.1, 0.0), Some(Containment::Outside));
+        assert_eq!(at(&ellipse, -2.0, 0.0), Some(Containment::Boundary));
+
+        let spline = Entity::Spline(Spline {
+            degree: 2,
+            is_rational: false,
+            is_closed: false,
+            is_periodic: false,
+            control_points: vec![
+                Point2::new(0.0, 0.0),
+                Point2::new(4.0, 0.0),
+                Point2::new(4.0, 4.0),
+                Point2::new(0.0, 4.0),
+                Point2::new(0.0, 0.0),
+            ],
+            fit_points: Vec::new(),
+            knot_values: Vec::new(),
+            weights: Vec::new(),
+            start_tangent: None,
+            end_tangent: None,
+            layer: "0".to_string(),
+        });
+        assert_eq!(at(&spline, 2.0, 2.0), Some(Containment::Inside));
+        assert_eq!(at(&spline, 3.9, 3.9), Some(Containment::Outside));
+        assert!(at(&spline, 0.0, 0.0).unwrap().is_enclosed());
+    }
+
+    #[test]
+    fn hatch_islands_follow_style() {
+        let circle = |radius: f64| HatchLoop {
+            is_polyline: false,
+            is_closed: true,
+            edges: vec![HatchEdge::Arc {
+                center: Point2::new(0.0, 0.0),
+                radius,
+                start_angle: 0.0,
+                end_angle: TAU,
+                is_counter_clockwise: true,
+            }],
+            boundary_handles: Vec::new(),
+            boundary_entities: Vec::new(),
+        };
+        let mut hatch = Hatch {
+            pattern_name: "SOLID".to_string(),
+            is_solid: true,
+            loops: vec![circle(3.0), circle(2.0), circle(1.0)],
+            gradient: None,
+            pattern_lines: Vec::new(),
+            style: HatchStyle::Normal,
+            is_associative: false,
+            seed_points: Vec::new(),
+            layer: "0".to_string(),
+        };
+        let probe = |hatch: &Hatch, x: f64| hatch.containment(Point2::new(x, 0.0), 1e-6);
+        assert_eq!(probe(&hatch, 2.5), Some(Containment::Inside));
+        assert_eq!(probe(&hatch, 1.5), Some(Containment::Outside));
+        assert_eq!(probe(&hatch, 0.5), Some(Containment::Inside));
+        assert_eq!(probe(&hatch, 2.0), Some(Containment::Boundary));
+        assert_eq!(probe(&hatch, 3.5), Some(Containment::Outside));
+
+        hatch.style = HatchStyle::Outer;
+        assert_eq!(probe(&hatch, 0.5), Some(Containment::Outside));
+        hatch.style = HatchStyle::Ignore;
+        assert_eq!(probe(&hatch, 1.5), Some(Containment::Inside));
+        assert_eq!(
+            hatch.loops[1].containment(Point2::new(1.5, 0.0), 0.0),
+            Containment::Inside
+        );
+    }
+}
@@ -0,0 +1,344 @@
+//! 标注测量值：按标注类型从定义点重新计算实际测量值，用于核对组码 42 与重新生成标注。
+//!
+//! 各类型使用的定义点沿用 DXF 组码：`definition_point` 为组码 10，`dimension_line_point`、
+//! `extension_line_origin`、`extension_line_end` 依次为组码 13、14、15，`arc_definition_point`
+//! 为组码 16；弧长标注的圆心（组码 15）与折弯半径标注的弦点（组码 13）已在读取时归入
+//! `center_point` 与 `extension_line_end`。
+
+use std::f64::consts::{PI, TAU};
+
+use glam::DVec2;
+
+use super::flatten::ccw_sweep;
+use super::{Dimension, DimensionKind, Document, Entity, EntityId};
+use crate::geometry::Point2;
+
+impl DimensionKind {
+    /// 测量值为弧度的角度标注。
+    pub fn is_angular(self) -> bool {
+        matches!(self, DimensionKind::Angular | DimensionKind::Angular3Point)
+    }
+}
+
+impl Dimension {
+    /// 由定义点计算的测量值：角度标注为弧度，其余为图形单位下的长度；定义点不足或退化时为 `None`。
+    ///
+    /// 坐标标注没有记录基准轴，按引线方向推断：引线接近竖直时标注 X 坐标，否则标注 Y 坐标。
+    pub fn computed_measurement(&self) -> Option<f64> {
+        let point = |point: Option<Point2>| point.map(Point2::as_vec2);
+        let definition = self.definition_point.as_vec2();
+        match self.kind {
+            DimensionKind::Linear => {
+                let (first, second) = (
+                    point(self.dimension_line_point)?,
+                    point(self.extension_line_origin)?,
+                );
+                let direction = DVec2::from_angle(self.rotation);
+                Some((second - first).dot(direction).abs())
+            }
+            DimensionKind::Aligned => {
+                let (first, second) = (
+                    point(self.dimension_line_point)?,
+                    point(self.extension_line_origin)?,
+                );
+                Some(first.distance(second))
+            }
+            DimensionKind::Diameter | DimensionKind::Radius | DimensionKind::JoggedRadius => {
+                Some(definition.distance(point(self.extension_line_end)?))
+            }
+            DimensionKind::Angular => two_line_angle(
+                (
+                    point(self.dimension_line_point)?,
+                    point(self.extension_line_origin)?,
+                ),
+                (point(self.extension_line_end)?, definition),
+                point(self.arc_definition_point),
+            ),
+            DimensionKind::Angular3Point => {
+                let vertex = point(self.extension_line_end)?;
+                let (first, second) = (
+                    point(self.dimension_line_point)? - vertex,
+                    point(self.extension_line_origin)? - vertex,
+                );
+                if first == DVec2::ZERO || second == DVec2::ZERO {
+                    return None;
+                }
+                let start = first.to_angle();
+                let sweep = (second.to_angle() - start).rem_euclid(TAU);
+                let arc = definition - vertex;
+                if arc != DVec2::ZERO && (arc.to_angle() - start).rem_euclid(TAU) > sweep {
+                    Some(TAU - sweep)
+                } else {
+                    Some(sweep)
+                }
+            }
+            DimensionKind::Ordinate => {
+                let (feature, leader) = (
+                    point(self.dimension_line_point)?,
+                    point(self.extension_line_origin)?,
+                );
+                let offset = feature - definition;
+                let run = leader - feature;
+                Some(if run.y.abs() >= run.x.abs() {
+                    offset.x.abs()
+                } else {
+                    offset.y.abs()
+                })
+            }
+            DimensionKind::ArcLength => {
+                let center = point(self.center_point)?;
+                let first = point(self.extension_line_origin)?;
+                let radius = center.distance(first);
+                let sweep = match &self.arc {
+                    Some(arc) if arc.start_angle != arc.end_angle => {
+                        ccw_sweep(arc.start_angle, arc.end_angle)
+                    }
+                    _ => {
+                        let second = point(self.extension_line_end)?;
+                        ((second - center).to_angle() - (first - center).to_angle()).rem_euclid(TAU)
+                    }
+                };
+                Some(radius * sweep)
+            }
+            DimensionKind::Unknown(_) => None,
+        }
+    }
+}
+
+/// 两条直线之间的夹角：取被圆弧位置点所在扇区的张角，缺少圆弧位置时取两条线方向的夹角；
+/// 两线平行时为 `None`。
+fn two_line_angle(
+    first: (DVec2, DVec2),
+    second: (DVec2, DVec2),
+    arc: Option<DVec2>,
+) -> Option<f64> {
+    let (u, v) = (first.1 - first.0, second.1 - second.0);
+    let cross = u.perp_dot(v);
+    if u == DVec2::ZERO || v == DVec2::ZERO || cross.abs() <= f64::EPSILON * u.length() * v.length()
+    {
+        return None;
+    }
+    let vertex = first.0 + u * (second.0 - first.0).perp_dot(v) / cross;
+    let Some(arc) = arc
+        .map(|arc| arc - vertex)
+        .filter(|arc| *arc != DVec2::ZERO)
+    else {
+        return Some(u.angle_to(v).abs());
+    };
+    // 两条直线把平面分成四个扇区，相邻扇区张角互补
+    let mut rays = [
+        u.to_angle(),
+        u.to_angle() + PI,
+        v.to_angle(),
+        v.to_angle() + PI,
+    ]
+    .map(|angle| angle.rem_euclid(TAU));
+    rays.sort_by(f64::total_cmp);
+    let target = arc.to_angle().rem_euclid(TAU);
+    let index = rays
+        .iter()
+        .rposition(|ray| *ray <= target)
+        .unwrap_or(rays.len() - 1);
+    let next = rays[(index + 1) % rays.len()];
+    Some((next - rays[index]).rem_euclid(TAU))
+}
+
+impl Document {
+    /// 以定义点重新计算模型空间标注的测量值，返回被更新的标注数。
+    ///
+    /// 与已有测量值相差不超过文档容差（角度标注用角度容差）的标注保持不变，无法计算的标注也不修改。
+    pub fn regenerate_dimension_measurements(&mut self) -> usize {
+        let tolerance = self.tolerance();
+        let updated: Vec<(EntityId, f64)> = self
+            .entities
+            .iter()
+            .filter_map(|(id, entity)| {
+                let Entity::Dimension(dimension) = entity else {
+                    return None;
+                };
+                let computed = dimension.computed_measurement()?;
+                let limit = if dimension.kind.is_angular() {
+                    tolerance.angular
+                } else {
+                    tolerance.linear
+                };
+                dimension
+                    .measurement
+                    .is_none_or(|value| (value - computed).abs() > limit)
+                    .then_some((*id, computed))
+            })
+            .collect();
+        for &(id, computed) in &updated {
+            self.record_modified(id);
+            if let Some(Entity::Dimension(dimension)) = self.entities.get_geometry_mut(id) {
+                dimension.measurement = Some(computed);
+            }
+        }
+        updated.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::*;
+    use crate::document::DimensionArc;
+
+    fn dimension(kind: DimensionKind) -> Dimension {
+        Dimension {
+            kind,
+            definition_point: Point2::new(0.0, 0.0),
+            text_midpoint: Point2::new(0.0, 0.0),
+            dimension_line_point: None,
+            extension_line_origin: None,
+            extension_line_end: None,
+            secondary_point: None,
+            arc_definition_point: None,
+            center_point: None,
+            text: None,
+            measurement: None,
+            rotation: 0.0,
+            text_rotation: None,
+            oblique_angle: None,
+            block_name: None,
+            style_name: None,
+            jog_point: None,
+            arc: None,
+            layer: "0".to_string(),
+        }
+    }
+
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.expect("measurement should be computable");
+        assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn computes_linear_aligned_and_radial_measurements() {
+        let mut linear = dimension(DimensionKind::Linear);
+        linear.dimension_line_point = Some(Point2::new(0.0, 0.0));
+        linear.extension_line_origin = Some(Point2::new(3.0, 4.0));
+        assert_close(linear.computed_measurement(), 3.0);
+        linear.rotation = FRAC_PI_2;
+        assert_close(linear.computed_measurement(), 4.0);
+
+        let aligned = Dimension {
+            kind: DimensionKind::Aligned,
+            ..linear.clone()
+        };
+        assert_close(aligned.computed_measurement(), 5.0);
+
+        let mut radius = dimension(DimensionKind::Radius);
+        radius.definition_point = Point2::new(1.0, 1.0);
+        radius.extension_line_end = Some(Point2::new(1.0, 3.5));
+        assert_close(radius.computed_measurement(), 2.5);
+        radius.kind = DimensionKind::Diameter;
+        assert_close(radius.computed_measurement(), 2.5);
+
+        assert_eq!(
+            dimension(DimensionKind::Linear).computed_measurement(),
+            None
+        );
+        assert_eq!(
+            dimension(DimensionKind::Unknown(7)).computed_measurement(),
+            None
+        );
+    }
+
+    #[test]
+    fn computes_angular_measurements_by_arc_position() {
+        // 两条直线分别沿 X 轴与 45° 方向，交于原点
+        let mut angular = dimension(DimensionKind::Angular);
+        angular.dimension_line_point = Some(Point2::new(1.0, 0.0));
+        angular.extension_line_origin = Some(Point2::new(2.0, 0.0));
+        angular.extension_line_end = Some(Point2::new(1.0, 1.0));
+        angular.definition_point = Point2::new(2.0, 2.0);
+        angular.arc_definition_point = Some(Point2::new(2.0, 0.5));
+        assert_close(angular.computed_measurement(), PI / 4.0);
+        angular.arc_definition_point = Some(Point2::new(0.0, 2.0));
+        assert_close(angular.computed_measurement(), 3.0 * PI / 4.0);
+        // 平行线没有夹角
+        angular.definition_point = Point2::new(5.0, 2.0);
+        angular.extension_line_end = Some(Point2::new(3.0, 2.0));
+        assert_eq!(angular.computed_measurement(), None);
+
+        let mut three_point = dimension(DimensionKind::Angular3Point);
+        three_point.extension_line_end = Some(Point2::new(1.0, 1.0));
+        three_point.dimension_line_point = Some(Point2::new(2.0, 1.0));
+        three_point.extension_line_origin = Some(Point2::new(1.0, 2.0));
+        three_point.definition_point = Point2::new(2.0, 2.0);
+        assert_close(three_point.computed_measurement(), FRAC_PI_2);
+        three_point.definition_point = Point2::new(0.0, 0.0);
+        assert_close(three_point.computed_measurement(), 3.0 * FRAC_PI_2);
+    }
+
+    #[test]
+    fn computes_ordinate_and_arc_length_measurements() {
+        let mut ordinate = dimension(DimensionKind::Ordinate);
+        ordinate.definition_point = Point2::new(1.0, 1.0);
+        ordinate.dimension_line_point = Some(Point2::new(4.0, 3.0));
+        ordinate.extension_line_origin = Some(Point2::new(4.0, 6.0));
+        assert_close(ordinate.computed_measurement(), 3.0);
+        ordinate.extension_line_origin = Some(Point2::new(8.0, 3.0));
+        assert_close(ordinate.computed_measurement(), 2.0);
+
+        let mut arc_length = dimension(DimensionKind::ArcLength);
+        arc_length.center_point = Some(Point2::new(0.0, 0.0));
+        arc_length.extension_line_origin = Some(Point2::new(2.0, 0.0));
+        arc_length.extension_line_end = Some(Point2::new(-2.0, 0.0));
+        assert_close(arc_length.computed_measurement(), 2.0 * PI);
+        arc_length.arc = Some(DimensionArc {
+            start_angle: 0.0,
+            end_angle: FRAC_PI_2,
+            is_partial: false,
+            has_leader: false,
+            leader_start: None,
+            leader_end: None,
+        });
+        assert_close(arc_length.computed_measurement(), PI);
+    }
+
+    #[test]
+    fn regenerates_stale_measurements_within_tolerance() {
+        let mut doc = Document::new();
+        let mut stale = dimension(DimensionKind::Aligned);
+        stale.dimension_line_point = Some(Point2::new(0.0, 0.0));
+        stale.extension_line_origin = Some(Point2::new(3.0, 4.0));
+        stale.measurement = Some(4.0);
+        let fresh = Dimension {
+            measurement: Some(5.0 + 1e-12),
+            ..stale.clone()
+        };
+        let stale = doc.add_entity(Entity::Dimension(stale));
+        let fresh = doc.add_entity(Entity::Dimension(fresh));
+        doc.add_entity(Entity::Dimension(dimension(DimensionKind::Linear)));
+
+        assert_eq!(doc.regenerate_dimension_measurements(), 1);
+        let measurement = |id| match doc.entity(id) {
+            Some(Entity::Dimension(dimension)) => dimension.measurement,
+            _ => None,
+        };
+        assert_eq!(measurement(stale), Some(5.0));
+        assert_eq!(measurement(fresh), Some(5.0 + 1e-12));
+        assert_eq!(doc.regenerate_dimension_measurements(), 0);
+    }
+
+    #[test]
+    fn audit_reports_and_fixes_mismatched_measurements() {
+        let mut doc = Document::new();
+        let mut radius = dimension(DimensionKind::Radius);
+        radius.extension_line_end = Some(Point2::new(0.0, 2.0));
+        radius.measurement = Some(3.0);
+        let id = doc.add_entity(Entity::Dimension(radius));
+
+        let findings = doc.audit();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].check, "dimension_measurement");
+        assert_eq!(findings[0].entity, Some(id));
+        assert!(findings[0].fixable);
+        assert_eq!(doc.fix(&findings), 1);
+        assert!(doc.audit().is_empty());
+        assert_eq!(doc.fix(&findings), 0);
+    }
+}
@@ -0,0 +1,622 @@
+//! 标注样式（DIMSTYLE）与标注文字格式化：单位格式、精度、消零、前后缀、换算单位与公差。
+//!
+//! 标注文字替代（组码 1）中的 `<>` 替换为测量值文字，`[]` 替换为换算单位文字；
+//! 只含一个空格的替代文字表示不显示文字。
+
+use serde::{Deserialize, Serialize};
+
+use super::{AngleFormat, Dimension, Document, LengthFormat, Units};
+
+/// 未指定标注样式或样式表中找不到时使用的样式名。
+pub const DIMSTYLE_STANDARD: &str = "Standard";
+
+/// 消零设置，对应 `$DIMZIN`、`$DIMALTZ`、`$DIMTZIN` 的位编码。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZeroSuppression {
+    /// 去掉小数点前的零（`0.50` 显示为 `.50`）。
+    pub leading: bool,
+    /// 去掉小数末尾的零（`1.50` 显示为 `1.5`）。
+    pub trailing: bool,
+    /// 英尺为零时省略英尺部分。
+    pub zero_feet: bool,
+    /// 英寸为零时省略英寸部分。
+    pub zero_inches: bool,
+}
+
+impl Default for ZeroSuppression {
+    fn default() -> Self {
+        Self::from_code(0)
+    }
+}
+
+impl ZeroSuppression {
+    /// 低两位：0 省略零英尺与零英寸，1 都保留，2 只省略零英寸，3 只省略零英尺；
+    /// 位 4 去掉前导零，位 8 去掉末尾零。
+    pub fn from_code(code: i16) -> Self {
+        Self {
+            leading: code & 4 != 0,
+            trailing: code & 8 != 0,
+            zero_feet: matches!(code & 3, 0 | 3),
+            zero_inches: matches!(code & 3, 0 | 2),
+        }
+    }
+
+    pub fn code(self) -> i16 {
+        let feet_inches = match (self.zero_feet, self.zero_inches) {
+            (true, true) => 0,
+            (false, false) => 1,
+            (false, true) => 2,
+            (true, false) => 3,
+        };
+        feet_inches | if self.leading { 4 } else { 0 } | if self.trailing { 8 } else { 0 }
+    }
+
+    /// 对文字开头的十进制数去掉前导零或末尾零，其后的单位符号保持不变。
+    fn apply_decimal(self, text: &str) -> String {
+        let (sign, rest) = match text.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", text),
+        };
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let (number, unit) = rest.split_at(end);
+        let mut number = number.to_string();
+        if self.trailing && number.contains('.') {
+            number = number
+                .trim_end_matches('0')
+                .trim_end_matches('.')
+                .to_string();
+        }
+        if self.leading && number.starts_with("0.") {
+            number.remove(0);
+        }
+        if number.is_empty() {
+            number.push('0');
+        }
+        format!("{sign}{number}{unit}")
+    }
+}
+
+/// 公差的显示方式（`$DIMTOL` 与 `$DIMLIM`）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToleranceDisplay {
+    /// 对称公差，例如 `10.00±0.05`，取上偏差。
+    #[default]
+    Symmetrical,
+    /// 上下偏差，例如 `10.00 +0.05/-0.02`。
+    Deviation,
+    /// 极限尺寸，例如 `10.05/9.98`，不显示测量值本身。
+    Limits,
+}
+
+/// 标注公差。
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DimTolerance {
+    pub display: ToleranceDisplay,
+    /// 上偏差（`$DIMTP`）。
+    pub plus: f64,
+    /// 下偏差（`$DIMTM`），以正值表示向下的偏差。
+    pub minus: f64,
+    /// 偏差的小数位数（`$DIMTDEC`）。
+    pub precision: u8,
+    /// 偏差的消零设置（`$DIMTZIN`）。
+    pub zero_suppression: ZeroSuppression,
+}
+
+impl Default for DimTolerance {
+    fn default() -> Self {
+        Self {
+            display: ToleranceDisplay::Symmetrical,
+            plus: 0.0,
+            minus: 0.0,
+            precision: 2,
+            zero_suppression: ZeroSuppression::default(),
+        }
+    }
+}
+
+/// 换算单位（`$DIMALT`），显示在主单位之后的方括号中；角度标注不显示。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlternateUnits {
+    /// 换算比例（`$DIMALTF`），默认英寸换算为毫米。
+    pub scale: f64,
+    pub format: LengthFormat,
+    /// 小数位数（`$DIMALTD`）。
+    pub precision: u8,
+    /// 舍入间隔（`$DIMALTRND`），0 表示不舍入。
+    pub rounding: f64,
+    pub zero_suppression: ZeroSuppression,
+    #[serde(default)]
+    pub prefix: String,
+    #[serde(default)]
+    pub suffix: String,
+}
+
+impl Default for AlternateUnits {
+    fn default() -> Self {
+        Self {
+            scale: 25.4,
+            format: LengthFormat::Decimal,
+            precision: 2,
+            rounding: 0.0,
+            zero_suppression: ZeroSuppression::default(),
+            prefix: String::new(),
+            suffix: String::new(),
+        }
+    }
+}
+
+/// 标注样式中与文字格式化有关的变量。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DimStyle {
+    pub name: String,
+    /// 线性标注的单位格式（`$DIMLUNIT`）。
+    pub length_format: LengthFormat,
+    /// 线性标注的精度（`$DIMDEC`），含义同 [`Units::length_precision`]。
+    pub precision: u8,
+    /// 角度标注的单位格式（`$DIMAUNIT`）。
+    pub angle_format: AngleFormat,
+    /// 角度标注的精度（`$DIMADEC`）。
+    pub angle_precision: u8,
+    /// 线性测量值的比例（`$DIMLFAC`）。
+    pub linear_scale: f64,
+    /// 线性测量值的舍入间隔（`$DIMRND`），0 表示不舍入。
+    pub rounding: f64,
+    /// 线性标注的消零设置（`$DIMZIN`）。
+    pub zero_suppression: ZeroSuppression,
+    /// 角度标注的消零设置（`$DIMAZIN`），只使用前导零与末尾零两项。
+    pub angle_zero_suppression: ZeroSuppression,
+    /// 测量值前缀与后缀（`$DIMPOST` 中 `<>` 两侧的文字）。
+    #[serde(default)]
+    pub prefix: String,
+    #[serde(default)]
+    pub suffix: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alternate: Option<AlternateUnits>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tolerance: Option<DimTolerance>,
+}
+
+impl Default for DimStyle {
+    fn default() -> Self {
+        Self::from_units(DIMSTYLE_STANDARD, &Units::default())
+    }
+}
+
+impl DimStyle {
+    /// 以文档单位的格式与精度建立样式。
+    pub fn from_units(name: impl Into<String>, units: &Units) -> Self {
+        Self {
+            name: name.into(),
+            length_format: units.length_format,
+            precision: units.length_precision,
+            angle_format: units.angle_format,
+            angle_precision: units.angle_precision,
+            linear_scale: 1.0,
+            rounding: 0.0,
+            zero_suppression: ZeroSuppression::default(),
+            angle_zero_suppression: ZeroSuppression::default(),
+            prefix: String::new(),
+            suffix: String::new(),
+            alternate: None,
+            tolerance: None,
+        }
+    }
+
+    /// 由 `$DIMPOST` 设置前缀与后缀，例如 `Ø<>mm`；不含 `<>` 时整段作为后缀。
+    pub fn set_post(&mut self, post: &str) {
+        let (prefix, suffix) = post.split_once("<>").unwrap_or(("", post));
+        self.prefix = prefix.to_string();
+        self.suffix = suffix.to_string();
+    }
+
+    /// 测量值的完整文字：主单位（含前后缀与公差）以及方括号中的换算单位。
+    /// 角度标注的测量值为弧度。
+    pub fn format_measurement(&self, value: f64, angular: bool) -> String {
+        let primary = self.primary_text(value, angular);
+        match self.alternate_text(value, angular) {
+            Some(alternate) => format!("{primary} [{alternate}]"),
+            None => primary,
+        }
+    }
+
+    /// 按替代文字生成标注文字：缺省替代或 `<>` 显示测量值，替代中的 `<>` 与 `[]`
+    /// 分别替换为主单位与换算单位文字，只含空格时不显示。缺少测量值时 `<>` 原样保留。
+    pub fn dimension_text(
+        &self,
+        measurement: Option<f64>,
+        text_override: Option<&str>,
+        angular: bool,
+    ) -> Option<String> {
+        let text = text_override.unwrap_or("");
+        if text == " " {
+            return None;
+        }
+        let Some(value) = measurement else {
+            return (!text.is_empty()).then(|| text.to_string());
+        };
+        if text.is_empty() {
+            return Some(self.format_measurement(value, angular));
+        }
+        if text.contains("[]") {
+            let alternate = self.alternate_text(value, angular).unwrap_or_default();
+            return Some(
+                text.replace("<>", &self.primary_text(value, angular))
+                    .replace("[]", &alternate),
+            );
+        }
+        Some(text.replace("<>", &self.format_measurement(value, angular)))
+    }
+
+    /// 主单位文字：前缀、测量值、后缀与公差。
+    pub fn primary_text(&self, value: f64, angular: bool) -> String {
+        if angular {
+            let number = self.angle_number(value, self.angle_precision);
+            let tolerance = self.tolerance.map(|tolerance| {
+                let deviation = |deviation| self.angle_number(deviation, tolerance.precision);
+                (
+                    tolerance,
+                    deviation(tolerance.plus),
+                    deviation(tolerance.minus),
+                )
+            });
+            let limits = |offset: f64| self.angle_number(value + offset, self.angle_precision);
+            return self.decorate(number, tolerance, limits);
+        }
+        let value = round_to(value * self.linear_scale, self.rounding);
+        let number = length_number(
+            value,
+            self.length_format,
+            self.precision,
+            self.zero_suppression,
+        );
+        let tolerance = self.tolerance.map(|tolerance| {
+            let deviation = |deviation| {
+                length_number(
+                    deviation,
+                    self.length_format,
+                    tolerance.precision,
+                    tolerance.zero_suppression,
+                )
+            };
+            (
+                tolerance,
+                deviation(tolerance.plus),
+                deviation(tolerance.minus),
+            )
+        });
+        let limits = |offset: f64| {
+            length_number(
+                value + offset,
+                self.length_format,
+                self.precision,
+                self.zero_suppression,
+            )
+        };
+        self.decorate(number, tolerance, limits)
+    }
+
+    /// 换算单位文字，未启用换算单位或角度标注时为 `None`。
+    pub fn alternate_text(&self, value: f64, angular: bool) -> Option<String> {
+        let alternate = self.alternate.as_ref().filter(|_| !angular)?;
+        let value = round_to(
+            value * self.linear_scale * alternate.scale,
+            alternate.rounding,
+        );
+        let number = length_number(
+            value,
+            alternate.format,
+            alternate.precision,
+            alternate.zero_suppression,
+        );
+        Some(format!("{}{number}{}", alternate.prefix, alternate.suffix))
+    }
+
+    fn angle_number(&self, radians: f64, precision: u8) -> String {
+        let units = Units {
+            angle_format: self.angle_format,
+            angle_precision: precision,
+            ..Units::default()
+        };
+        let text = units.format_angle(radians);
+        if self.angle_format == AngleFormat::DegreesMinutesSeconds {
+            text
+        } else {
+            self.angle_zero_suppression.apply_decimal(&text)
+        }
+    }
+
+    /// 组合前后缀与公差；极限尺寸由 `limits` 按偏差生成上下限。
+    fn decorate(
+        &self,
+        number: String,
+        tolerance: Option<(DimTolerance, String, String)>,
+        limits: impl Fn(f64) -> String,
+    ) -> String {
+        let (prefix, suffix) = (&self.prefix, &self.suffix);
+        match tolerance {
+            None => format!("{prefix}{number}{suffix}"),
+            Some((tolerance, plus, minus)) => match tolerance.display {
+                ToleranceDisplay::Symmetrical => format!("{prefix}{number}{suffix}±{plus}"),
+                ToleranceDisplay::Deviation => {
+                    format!("{prefix}{number}{suffix} +{plus}/-{minus}")
+                }
+                ToleranceDisplay::Limits => format!(
+                    "{prefix}{}/{}{suffix}",
+                    limits(tolerance.plus),
+                    limits(-tolerance.minus)
+                ),
+            },
+        }
+    }
+}
+
+/// 按舍入间隔取整，间隔不为正时原样返回。
+fn round_to(value: f64, interval: f64) -> f64 {
+    if interval > 0.0 {
+        (value / interval).round() * interval
+    } else {
+        value
+    }
+}
+
+/// 长度数值文字，按格式应用消零：十进制去前导零与末尾零，工程与建筑格式省略零英尺或零英寸。
+fn length_number(
+    value: f64,
+    format: LengthFormat,
+    precision: u8,
+    zero_suppression: ZeroSuppression,
+) -> String {
+    let units = Units {
+        length_format: format,
+        length_precision: precision,
+        ..Units::default()
+    };
+    let text = units.format_length(value);
+    match format {
+        LengthFormat::Decimal => zero_suppression.apply_decimal(&text),
+        LengthFormat::Engineering | LengthFormat::Architectural => {
+            let (sign, rest) = match text.strip_prefix('-') {
+                Some(rest) => ("-", rest),
+                None => ("", text.as_str()),
+            };
+            let Some((feet, inches)) = rest.split_once("'-") else {
+                return text;
+            };
+            let inches = inches.trim_end_matches('"');
+            let inches = if format == LengthFormat::Engineering {
+                zero_suppression.apply_decimal(inches)
+            } else {
+                inches.to_string()
+            };
+            let zero_feet = feet == "0";
+            let zero_inches = inches.parse::<f64>().is_ok_and(|inches| inches == 0.0);
+            if zero_feet && zero_suppression.zero_feet && !zero_inches {
+                format!("{sign}{inches}\"")
+            } else if zero_inches && zero_suppression.zero_inches {
+                format!("{sign}{feet}'")
+            } else {
+                format!("{sign}{feet}'-{inches}\"")
+            }
+        }
+        LengthFormat::Scientific | LengthFormat::Fractional => text,
+    }
+}
+
+impl Document {
+    /// 写入标注样式表记录，同名样式会被替换。
+    pub fn add_dim_style(&mut self, style: DimStyle) {
+        self.dim_styles.insert(style.name.clone(), style);
+    }
+
+    /// 按名称查找标注样式，名称不区分大小写。
+    pub fn dim_style(&self, name: &str) -> Option<&DimStyle> {
+        self.dim_styles.get(name).or_else(|| {
+            self.dim_styles
+                .values()
+                .find(|style| style.name.eq_ignore_ascii_case(name))
+        })
+    }
+
+    #[inline]
+    pub fn dim_styles(&self) -> impl Iterator<Item = &DimStyle> {
+        self.dim_styles.values()
+    }
+
+    /// 标注生效的样式：标注指定的样式，其次为 `Standard`，都没有时按文档单位生成。
+    pub fn resolved_dim_style(&self, dimension: &Dimension) -> DimStyle {
+        dimension
+            .style_name
+            .as_deref()
+            .and_then(|name| self.dim_style(name))
+            .or_else(|| self.dim_style(DIMSTYLE_STANDARD))
+            .cloned()
+            .unwrap_or_else(|| DimStyle::from_units(DIMSTYLE_STANDARD, &self.units()))
+    }
+
+    /// 标注显示的文字，按生效样式格式化测量值并处理替代文字；不显示文字时为 `None`。
+    pub fn dimension_text(&self, dimension: &Dimension) -> Option<String> {
+        self.resolved_dim_style(dimension).dimension_text(
+            dimension.measurement,
+            dimension.text.as_deref(),
+            dimension.kind.is_angular(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::document::DimensionKind;
+    use crate::geometry::Point2;
+
+    fn style(format: LengthFormat, precision: u8, zero_suppression: i16) -> DimStyle {
+        DimStyle {
+            length_format: format,
+            precision,
+            zero_suppression: ZeroSuppression::from_code(zero_suppression),
+            ..DimStyle::default()
+        }
+    }
+
+    #[test]
+    fn zero_suppression_codes_round_trip() {
+        for code in 0..16 {
+            assert_eq!(ZeroSuppression::from_code(code).code(), code);
+        }
+        let decimal = style(LengthFormat::Decimal, 3, 12);
+        assert_eq!(decimal.format_measurement(0.5, false), ".5");
+        assert_eq!(decimal.format_measurement(2.0, false), "2");
+        assert_eq!(decimal.format_measurement(-0.25, false), "-.25");
+        assert_eq!(
+            style(LengthFormat::Decimal, 2, 8).format_measurement(0.0, false),
+            "0"
+        );
+        assert_eq!(
+            style(LengthFormat::Decimal, 2, 0).format_measurement(0.5, false),
+            "0.50"
+        );
+    }
+
+    #[test]
+    fn suppresses_zero_feet_and_inches() {
+        let architectural = style(LengthFormat::Architectural, 4, 0);
+        assert_eq!(architectural.format_measurement(6.5, false), "6 1/2\"");
+        assert_eq!(architectural.format_measurement(24.0, false), "2'");
+        assert_eq!(architectural.format_measurement(15.5, false), "1'-3 1/2\"");
+        let keep = style(LengthFormat::Architectural, 4, 1);
+        assert_eq!(keep.format_measurement(24.0, false), "2'-0\"");
+        let engineering = style(LengthFormat::Engineering, 2, 8);
+        assert_eq!(engineering.format_measurement(15.5, false), "1'-3.5\"");
+    }
+
+    #[test]
+    fn applies_scale_rounding_prefix_and_alternate_units() {
+        let mut style = DimStyle {
+            linear_scale: 2.0,
+            rounding: 0.25,
+            alternate: Some(AlternateUnits {
+                precision: 1,
+                suffix: " mm".to_string(),
+                ..AlternateUnits::default()
+            }),
+            ..DimStyle::default()
+        };
+        style.set_post("Ø<>\"");
+        assert_eq!(style.prefix, "Ø");
+        assert_eq!(style.format_measurement(1.1, false), "Ø2.25\" [55.9 mm]");
+        // 角度标注既不缩放也不显示换算单位
+        assert_eq!(style.format_measurement(PI / 2.0, true), "Ø90°\"");
+    }
+
+    #[test]
+    fn formats_tolerances() {
+        let with = |display| DimStyle {
+            tolerance: Some(DimTolerance {
+                display,
+                plus: 0.05,
+                minus: 0.02,
+                precision: 3,
+                zero_suppression: ZeroSuppression::from_code(8),
+            }),
+            ..DimStyle::default()
+        };
+        assert_eq!(
+            with(ToleranceDisplay::Symmetrical).format_measurement(10.0, false),
+            "10.00±0.05"
+        );
+        assert_eq!(
+            with(ToleranceDisplay::Deviation).format_measurement(10.0, false),
+            "10.00 +0.05/-0.02"
+        );
+        assert_eq!(
+            with(ToleranceDisplay::Limits).format_measurement(10.0, false),
+            "10.05/9.98"
+        );
+    }
+
+    #[test]
+    fn substitutes_overrides() {
+        let style = DimStyle {
+            alternate: Some(AlternateUnits::default()),
+            ..DimStyle::default()
+        };
+        assert_eq!(
+            style.dimension_text(Some(1.0), None, false).as_deref(),
+            Some("1.00 [25.40]")
+        );
+        assert_eq!(
+            style
+                .dimension_text(Some(1.0), Some("L=<>"), false)
+                .as_deref(),
+            Some("L=1.00 [25.40]")
+        );
+        assert_eq!(
+            style
+                .dimension_text(Some(1.0), Some("<> ([] mm)"), false)
+                .as_deref(),
+            Some("1.00 (25.40 mm)")
+        );
+        assert_eq!(
+            style
+                .dimension_text(Some(1.0), Some("TYP"), false)
+                .as_deref(),
+            Some("TYP")
+        );
+        assert_eq!(style.dimension_text(Some(1.0), Some(" "), false), None);
+        assert_eq!(style.dimension_text(None, None, false), None);
+        assert_eq!(
+            style.dimension_text(None, Some("<>"), false).as_deref(),
+            Some("<>")
+        );
+    }
+
+    #[test]
+    fn document_resolves_dimension_style() {
+        let mut dimension = Dimension {
+            kind: DimensionKind::Linear,
+            definition_point: Point2::new(15.5, 0.0),
+            text_midpoint: Point2::new(7.0, 1.0),
+            dimension_line_point: None,
+            extension_line_origin: None,
+            extension_line_end: None,
+            secondary_point: None,
+            arc_definition_point: None,
+            center_point: None,
+            text: None,
+            measurement: Some(15.5),
+            rotation: 0.0,
+            text_rotation: None,
+            oblique_angle: None,
+            block_name: None,
+            style_name: Some("ARCH".to_string()),
+            jog_point: None,
+            arc: None,
+            layer: "0".to_string(),
+        };
+        let mut doc = Document::new();
+        assert_eq!(doc.dimension_text(&dimension).as_deref(), Some("15.50"));
+        doc.set_units(Units {
+            length_format: LengthFormat::Fractional,
+            length_precision: 1,
+            ..Units::default()
+        });
+        assert_eq!(doc.dimension_text(&dimension).as_deref(), Some("15 1/2"));
+
+        doc.add_dim_style(DimStyle {
+            name: "Arch".to_string(),
+            ..style(LengthFormat::Architectural, 3, 0)
+        });
+        assert_eq!(
+            doc.dimension_text(&dimension).as_deref(),
+            Some("1'-3 1/2\"")
+        );
+        dimension.kind = DimensionKind::Angular;
+        dimension.measurement = Some(PI / 4.0);
+        assert_eq!(doc.dimension_text(&dimension).as_deref(), Some("45°"));
+    }
+}
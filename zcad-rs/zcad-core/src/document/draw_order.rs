@@ -0,0 +1,265 @@
+//! 绘制顺序：[`Document::display_list`] 给出实体的渲染顺序，是各导出器与交互渲染共同遵循的约定。
+//!
+//! 排序依次按图层的 [`Layer::draw_priority`](super::Layer::draw_priority)、绘制层级与层级内序号：
+//! 未显式排序的实体按类型取默认层级（光栅图像与底图在最下，其次是填充，普通图形与区域覆盖居中，
+//! 文字与标注在最上），层级内保持文档顺序；前置/后置的实体分别位于全部默认层级之上/之下。
+//! DXF 的 SORTENTSTABLE 读入后经 [`Document::set_draw_sequence`] 记录为显式顺序。
+
+use serde::{Deserialize, Serialize};
+
+use super::{Document, Entity, EntityId};
+
+/// 后置的实体。
+const TIER_BACK: i32 = -1;
+/// 光栅图像与底图。
+const TIER_BACKGROUND: i32 = 0;
+/// 填充。
+const TIER_FILL: i32 = 1;
+/// 普通图形、区域覆盖，以及按 SORTENTSTABLE 排列的实体。
+const TIER_GEOMETRY: i32 = 2;
+/// 文字、标注与引线。
+const TIER_ANNOTATION: i32 = 3;
+/// 前置的实体。
+const TIER_FRONT: i32 = 4;
+
+/// 显式指定的绘制位置，先比较层级再比较序号。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub(super) struct DrawOrderKey {
+    tier: i32,
+    sequence: i64,
+}
+
+fn default_tier(entity: &Entity) -> i32 {
+    match entity {
+        Entity::RasterImage(_) | Entity::Underlay(_) => TIER_BACKGROUND,
+        Entity::Hatch(_) => TIER_FILL,
+        Entity::Text(_)
+        | Entity::MText(_)
+        | Entity::Dimension(_)
+        | Entity::Leader(_)
+        | Entity::MLeader(_) => TIER_ANNOTATION,
+        _ => TIER_GEOMETRY,
+    }
+}
+
+impl Document {
+    /// 显示中的模型空间实体，按渲染顺序（先绘制的在前）返回。
+    pub fn display_list(&self) -> Vec<&(EntityId, Entity)> {
+        let mut list = self.entities_in_draw_order();
+        list.retain(|(_, entity)| {
+            self.layer(entity.layer_name())
+                .is_none_or(|layer| layer.is_displayed())
+        });
+        list
+    }
+
+    /// 全部模型空间实体（含关闭、冻结图层上的），按渲染顺序返回。
+    pub fn entities_in_draw_order(&self) -> Vec<&(EntityId, Entity)> {
+        self.sort_positions(0..self.entities.len())
+    }
+
+    /// 给定的模型空间实体按渲染顺序返回，去掉不存在的与重复的；只对这些实体排序，
+    /// 供拾取等先经空间索引筛选的查询使用。
+    pub fn in_draw_order(
+        &self,
+        ids: impl IntoIterator<Item = EntityId>,
+    ) -> Vec<&(EntityId, Entity)> {
+        let mut positions: Vec<usize> = ids
+            .into_iter()
+            .filter_map(|id| self.entities.position(id))
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+        self.sort_positions(positions)
+    }
+
+    fn sort_positions(
+        &self,
+        positions: impl IntoIterator<Item = usize>,
+    ) -> Vec<&(EntityId, Entity)> {
+        let mut keyed: Vec<(i32, DrawOrderKey, &(EntityId, Entity))> = positions
+            .into_iter()
+            .map(|index| {
+                let item = &self.entities[index];
+                let (id, entity) = item;
+                let priority = self
+                    .layer(entity.layer_name())
+                    .map_or(0, |layer| layer.draw_priority);
+                (priority, self.draw_order_key(*id, entity, index), item)
+            })
+            .collect();
+        keyed.sort_by_key(|(priority, key, _)| (*priority, *key));
+        keyed.into_iter().map(|(_, _, item)| item).collect()
+    }
+
+    /// 前置：实体移到其他全部实体之上（同一图层优先级内），多个实体保持原有的相对顺序。
+    /// 返回实际调整的实体数。
+    pub fn bring_to_front(&mut self, ids: &[EntityId]) -> usize {
+        let next = self
+            .draw_order
+            .values()
+            .filter(|key| key.tier == TIER_FRONT)
+            .map(|key| key.sequence + 1)
+            .max()
+            .unwrap_or(0);
+        let selected = self.selection_in_draw_order(ids);
+        for (offset, id) in selected.iter().enumerate() {
+            self.draw_order.insert(
+                *id,
+                DrawOrderKey {
+                    tier: TIER_FRONT,
+                    sequence: next + offset as i64,
+                },
+            );
+        }
+        selected.len()
+    }
+
+    /// 后置：实体移到其他全部实体之下（同一图层优先级内），多个实体保持原有的相对顺序。
+    /// 返回实际调整的实体数。
+    pub fn send_to_back(&mut self, ids: &[EntityId]) -> usize {
+        let first = self
+            .draw_order
+            .values()
+            .filter(|key| key.tier == TIER_BACK)
+            .map(|key| key.sequence)
+            .min()
+            .unwrap_or(0);
+        let selected = self.selection_in_draw_order(ids);
+        let count = selected.len() as i64;
+        for (offset, id) in selected.iter().enumerate() {
+            self.draw_order.insert(
+                *id,
+                DrawOrderKey {
+                    tier: TIER_BACK,
+                    sequence: first - count + offset as i64,
+                },
+            );
+        }
+        selected.len()
+    }
+
+    /// 按给定顺序排列实体（对应 SORTENTSTABLE）：列出的实体不再按类型分层，
+    /// 彼此按列表顺序绘制，之后新增的同层级实体位于其上。返回实际记录的实体数。
+    pub fn set_draw_sequence(&mut self, ids: &[EntityId]) -> usize {
+        let mut count = 0;
+        for id in ids {
+            if self.entity(*id).is_none() {
+                continue;
+            }
+            self.draw_order.insert(
+                *id,
+                DrawOrderKey {
+                    tier: TIER_GEOMETRY,
+                    sequence: count,
+                },
+            );
+            count += 1;
+        }
+        count as usize
+    }
+
+    /// 是否有实体设定了显式绘制顺序；写出 DXF 时据此决定是否输出 SORTENTSTABLE。
+    #[inline]
+    pub fn has_draw_order(&self) -> bool {
+        !self.draw_order.is_empty()
+    }
+
+    /// 清除全部显式绘制顺序，恢复按类型与文档顺序绘制。
+    pub fn clear_draw_order(&mut self) {
+        self.draw_order.clear();
+    }
+
+    fn draw_order_key(&self, id: EntityId, entity: &Entity, index: usize) -> DrawOrderKey {
+        self.draw_order.get(&id).copied().unwrap_or(DrawOrderKey {
+            tier: default_tier(entity),
+            sequence: index as i64,
+        })
+    }
+
+    /// 选中的实体按当前绘制顺序排列，去掉不存在的与重复的。
+    fn selection_in_draw_order(&self, ids: &[EntityId]) -> Vec<EntityId> {
+        self.in_draw_order(ids.iter().copied())
+            .into_iter()
+            .map(|(id, _)| *id)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{HatchStyle, RasterImageDisplayOptions};
+    use crate::geometry::{Point2, Vector2};
+
+    fn order(doc: &Document) -> Vec<EntityId> {
+        doc.display_list().iter().map(|(id, _)| *id).collect()
+    }
+
+    fn add_solid(doc: &mut Document) -> EntityId {
+        doc.add_hatch(
+            "SOLID",
+            true,
+            Vec::new(),
+            None,
+            Vec::new(),
+            HatchStyle::Normal,
+            false,
+            Vec::new(),
+            "0",
+        )
+    }
+
+    #[test]
+    fn type_defaults_place_fills_below_and_annotations_above() {
+        let mut doc = Document::new();
+        let text = doc.add_text(Point2::new(0.0, 0.0), "A", 1.0, 0.0, "0");
+        let line = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 1.0), "0");
+        let wipeout = doc.add_wipeout(
+            "0",
+            Point2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(0.0, 1.0),
+            Vector2::new(1.0, 1.0),
+            RasterImageDisplayOptions::default(),
+            None,
+        );
+        let hatch = add_solid(&mut doc);
+
+        assert_eq!(order(&doc), [hatch, line, wipeout, text]);
+    }
+
+    #[test]
+    fn explicit_order_and_layer_priority() {
+        let mut doc = Document::new();
+        let a = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+        let b = doc.add_line(Point2::new(0.0, 1.0), Point2::new(1.0, 1.0), "0");
+        let c = doc.add_line(Point2::new(0.0, 2.0), Point2::new(1.0, 2.0), "0");
+        let hatch = add_solid(&mut doc);
+
+        assert_eq!(doc.bring_to_front(&[a, hatch]), 2);
+        assert_eq!(order(&doc), [b, c, hatch, a]);
+        assert_eq!(doc.send_to_back(&[c]), 1);
+        assert_eq!(order(&doc), [c, b, hatch, a]);
+
+        let d = doc.add_line(Point2::new(0.0, 3.0), Point2::new(1.0, 3.0), "BACK");
+        doc.layer_mut("BACK").unwrap().draw_priority = -1;
+        assert_eq!(order(&doc), [d, c, b, hatch, a]);
+        doc.layer_mut("BACK").unwrap().is_frozen = true;
+        assert_eq!(order(&doc), [c, b, hatch, a]);
+        assert_eq!(doc.entities_in_draw_order().len(), 5);
+
+        doc.clear_draw_order();
+        assert!(!doc.has_draw_order());
+        assert_eq!(doc.set_draw_sequence(&[c, a, b]), 3);
+        assert_eq!(order(&doc), [hatch, c, a, b]);
+        doc.remove_entity(a);
+        assert_eq!(order(&doc), [hatch, c, b]);
+        let subset: Vec<EntityId> = doc
+            .in_draw_order([b, a, d, hatch, b])
+            .iter()
+            .map(|(id, _)| *id)
+            .collect();
+        assert_eq!(subset, [d, hatch, b]);
+    }
+}
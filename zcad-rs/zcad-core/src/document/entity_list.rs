@@ -0,0 +1,341 @@
+//! 模型空间实体表：按插入顺序保存实体，并维护编号到位置的索引，使按编号查找为 O(1)。
+//!
+//! 按图层的索引在首次查询时建立，追加实体时增量维护；删除或可变访问可能改变位置与图层，
+//! 此时直接作废，下次查询再重建。
+//!
+//! 整体范围同样惰性缓存：追加实体时直接扩展；删除或修改的实体原范围严格位于整体范围内部时缓存仍然有效，
+//! 只需并入修改后的范围，触及边界时才标记为脏并在下次查询时全量重算。
+//!
+//! 空间索引在首次范围查询时装载，此后随追加、删除与修改同步更新；整体可变遍历后作废重建。
+//!
+//! 实体范围由文档在查询时提供（块参照需要展开块定义）。块参照的范围随块定义变化，无法在此单独维护，
+//! 涉及块参照的编辑直接作废范围缓存与空间索引；块定义变化时由文档调用 [`EntityList::invalidate_extents`]。
+//! 序列化格式与 `Vec<(EntityId, Entity)>` 相同，索引与缓存不参与序列化。
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::spatial::SpatialIndex;
+use super::{Bounds2D, Entity, EntityId};
+
+#[derive(Clone, Default)]
+pub(super) struct EntityList {
+    items: Vec<(EntityId, Entity)>,
+    index: HashMap<EntityId, usize>,
+    /// 图层名到实体位置（升序）的索引。
+    layers: OnceLock<HashMap<String, Vec<usize>>>,
+    /// 所有实体的整体范围；未初始化表示需要重算。
+    bounds: OnceLock<Option<Bounds2D>>,
+    /// 有范围实体的空间索引。
+    spatial: OnceLock<SpatialIndex>,
+    /// 经 `get_mut` 借出、尚未并入 `bounds` 与 `spatial` 的实体。
+    touched: Option<EntityId>,
+}
+
+impl EntityList {
+    fn from_vec(items: Vec<(EntityId, Entity)>) -> Self {
+        let mut list = Self {
+            items,
+            ..Self::default()
+        };
+        list.reindex_from(0);
+        list
+    }
+
+    /// 重建 `start` 及之后条目的索引。
+    fn reindex_from(&mut self, start: usize) {
+        for (position, (id, _)) in self.items.iter().enumerate().skip(start) {
+            self.index.insert(*id, position);
+        }
+    }
+
+    /// 所有实体的整体范围，没有可计算范围的实体时返回 `None`；`extent` 计算单个实体的范围。
+    pub(super) fn bounds(
+        &self,
+        mut extent: impl FnMut(&Entity) -> Option<Bounds2D>,
+    ) -> Option<Bounds2D> {
+        let cached = *self.bounds.get_or_init(|| {
+            self.items
+                .iter()
+                .filter_map(|(_, entity)| extent(entity))
+                .reduce(|mut total, bounds| {
+                    total.include_bounds(&bounds);
+                    total
+                })
+        });
+        let touched = self.touched.and_then(|id| extent(self.get(id)?));
+        match (cached, touched) {
+            (Some(mut total), Some(bounds)) => {
+                total.include_bounds(&bounds);
+                Some(total)
+            }
+            (cached, touched) => cached.or(touched),
+        }
+    }
+
+    /// 把上次借出的实体并入范围缓存。
+    fn settle_bounds(&mut self) {
+        let Some(id) = self.touched.take() else {
+            return;
+        };
+        if self.get(id).is_some_and(depends_on_blocks) {
+            self.invalidate_extents();
+            return;
+        }
+        let touched = self.get(id).and_then(Entity::bounds);
+        self.extend_bounds(touched);
+        if let (Some(spatial), Some(bounds)) = (self.spatial.get_mut(), touched) {
+            spatial.insert(id, bounds);
+        }
+    }
+
+    /// 范围与 `area` 相交的实体编号，按文档顺序返回。
+    pub(super) fn query_rect(
+        &self,
+        area: &Bounds2D,
+        mut extent: impl FnMut(&Entity) -> Option<Bounds2D>,
+    ) -> Vec<EntityId> {
+        // 借出的实体不进入索引，由下面单独判断，随后的 `settle_bounds` 再把它插入索引
+        let spatial = self.spatial.get_or_init(|| {
+            SpatialIndex::from_entries(
+                self.items
+                    .iter()
+                    .filter(|(id, _)| self.touched != Some(*id))
+                    .filter_map(|(id, entity)| Some((*id, extent(entity)?))),
+            )
+        });
+        let mut positions: Vec<usize> = spatial
+            .query_rect(area)
+            .into_iter()
+            .filter_map(|id| self.index.get(&id).copied())
+            .collect();
+        // 借出的实体已移出索引，按当前范围单独判断
+        if let Some(id) = self.touched
+            && let Some(bounds) = self.get(id).and_then(&mut extent)
+            && bounds.intersects(area)
+        {
+            positions.push(self.index[&id]);
+        }
+        positions.sort_unstable();
+        positions
+            .into_iter()
+            .map(|position| self.items[position].0)
+            .collect()
+    }
+
+    /// 作废范围缓存与空间索引，下次查询时全量重建。
+    pub(super) fn invalidate_extents(&mut self) {
+        self.bounds.take();
+        self.spatial.take();
+        self.touched = None;
+    }
+
+    fn extend_bounds(&mut self, bounds: Option<Bounds2D>) {
+        if let (Some(cached), Some(bounds)) = (self.bounds.get_mut(), bounds) {
+            match cached {
+                Some(total) => total.include_bounds(&bounds),
+                None => *cached = Some(bounds),
+            }
+        }
+    }
+
+    /// 实体即将被删除或修改：原范围触及整体边界时作废缓存。
+    fn release_bounds(&mut self, bounds: Option<Bounds2D>) {
+        let (Some(Some(total)), Some(bounds)) = (self.bounds.get(), bounds) else {
+            return;
+        };
+        let (min, max) = (bounds.min(), bounds.max());
+        let interior = min.x() > total.min().x()
+            && min.y() > total.min().y()
+            && max.x() < total.max().x()
+            && max.y() < total.max().y();
+        if !interior {
+            self.bounds.take();
+        }
+    }
+
+    /// 按图层分组的实体位置，首次调用时建立。
+    pub(super) fn layer_index(&self) -> &HashMap<String, Vec<usize>> {
+        self.layers.get_or_init(|| {
+            let mut layers: HashMap<String, Vec<usize>> = HashMap::new();
+            for (position, (_, entity)) in self.items.iter().enumerate() {
+                layers
+                    .entry(entity.layer_name().to_string())
+                    .or_default()
+                    .push(position);
+            }
+            layers
+        })
+    }
+
+    /// 可变遍历；调用方只应修改实体，不应修改编号。
+    #[inline]
+    pub(super) fn iter_mut(&mut self) -> std::slice::IterMut<'_, (EntityId, Entity)> {
+        self.layers.take();
+        self.invalidate_extents();
+        self.items.iter_mut()
+    }
+
+    #[inline]
+    pub(super) fn get(&self, id: EntityId) -> Option<&Entity> {
+        let position = *self.index.get(&id)?;
+        Some(&self.items[position].1)
+    }
+
+    /// 调用方可能修改图层与几何，因此作废图层索引，并在下次查询范围时并入该实体的新范围。
+    pub(super) fn get_mut(&mut self, id: EntityId) -> Option<&mut Entity> {
+        self.index.get(&id)?;
+        self.layers.take();
+        self.get_geometry_mut(id)
+    }
+
+    /// 与 [`EntityList::get_mut`] 相同，但调用方保证不改变图层，图层索引保持有效。
+    pub(super) fn get_geometry_mut(&mut self, id: EntityId) -> Option<&mut Entity> {
+        let position = *self.index.get(&id)?;
+        self.settle_bounds();
+        if depends_on_blocks(&self.items[position].1) {
+            self.invalidate_extents();
+            return Some(&mut self.items[position].1);
+        }
+        self.release_bounds(self.items[position].1.bounds());
+        if let Some(spatial) = self.spatial.get_mut() {
+            spatial.remove(id);
+        }
+        if self.bounds.get().is_some() || self.spatial.get().is_some() {
+            self.touched = Some(id);
+        }
+        Some(&mut self.items[position].1)
+    }
+
+    /// 追加实体；编号已存在时旧条目不再可按编号查找，调用方应保证编号唯一。
+    pub(super) fn push(&mut self, (id, entity): (EntityId, Entity)) {
+        self.settle_bounds();
+        let position = self.items.len();
+        if depends_on_blocks(&entity) {
+            self.invalidate_extents();
+        } else {
+            let bounds = entity.bounds();
+            self.extend_bounds(bounds);
+            if let (Some(spatial), Some(bounds)) = (self.spatial.get_mut(), bounds) {
+                spatial.insert(id, bounds);
+            }
+        }
+        if let Some(layers) = self.layers.get_mut() {
+            layers
+                .entry(entity.layer_name().to_string())
+                .or_default()
+                .push(position);
+        }
+        self.index.insert(id, position);
+        self.items.push((id, entity));
+    }
+
+    /// 在 `position` 处插入实体（超出末尾时追加），用于按原位置恢复被删除的实体。
+    pub(super) fn insert(&mut self, position: usize, (id, entity): (EntityId, Entity)) {
+        if position >= self.items.len() {
+            self.push((id, entity));
+            return;
+        }
+        self.settle_bounds();
+        if depends_on_blocks(&entity) {
+            self.invalidate_extents();
+        } else {
+            let bounds = entity.bounds();
+            self.extend_bounds(bounds);
+            if let (Some(spatial), Some(bounds)) = (self.spatial.get_mut(), bounds) {
+                spatial.insert(id, bounds);
+            }
+        }
+        self.items.insert(position, (id, entity));
+        self.reindex_from(position);
+        self.layers.take();
+    }
+
+    /// 实体在表中的位置。
+    #[inline]
+    pub(super) fn position(&self, id: EntityId) -> Option<usize> {
+        self.index.get(&id).copied()
+    }
+
+    /// 删除实体并保持其余实体顺序，后续条目的索引随之前移。
+    pub(super) fn remove(&mut self, id: EntityId) -> Option<Entity> {
+        let position = *self.index.get(&id)?;
+        self.settle_bounds();
+        self.index.remove(&id);
+        let (_, entity) = self.items.remove(position);
+        self.reindex_from(position);
+        self.layers.take();
+        if depends_on_blocks(&entity) {
+            self.invalidate_extents();
+            return Some(entity);
+        }
+        self.release_bounds(entity.bounds());
+        if let Some(spatial) = self.spatial.get_mut() {
+            spatial.remove(id);
+        }
+        Some(entity)
+    }
+}
+
+/// 范围取决于块定义的实体。
+fn depends_on_blocks(entity: &Entity) -> bool {
+    matches!(entity, Entity::BlockReference(_))
+}
+
+/// 只读访问按插入顺序排列的条目；修改须经过维护索引的方法。
+impl Deref for EntityList {
+    type Target = [(EntityId, Entity)];
+
+    fn deref(&self) -> &Self::Target {
+        &self.items
+    }
+}
+
+impl IntoIterator for EntityList {
+    type Item = (EntityId, Entity);
+    type IntoIter = std::vec::IntoIter<(EntityId, Entity)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a EntityList {
+    type Item = &'a (EntityId, Entity);
+    type IntoIter = std::slice::Iter<'a, (EntityId, Entity)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut EntityList {
+    type Item = &'a mut (EntityId, Entity);
+    type IntoIter = std::slice::IterMut<'a, (EntityId, Entity)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl fmt::Debug for EntityList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.items.fmt(f)
+    }
+}
+
+impl Serialize for EntityList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.items.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for EntityList {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::deserialize(deserializer).map(Self::from_vec)
+    }
+}
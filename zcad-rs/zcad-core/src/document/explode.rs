@@ -0,0 +1,446 @@
+//! 分解（EXPLODE）：把复合实体拆为下一层的组成实体。
+//!
+//! 与压平不同，分解只展开一层且尽量保留精确几何：多段线与修订云线拆为直线和圆弧，填充拆为边界边，
+//! 块参照拆为经插入变换的块内实体（属性转为单行文字），标注拆为其匿名块中的图元。尚无标注渲染器，
+//! 缺少匿名块的标注不能分解。组成实体沿用原实体的特性覆盖，块内 0 层实体继承原实体所在图层。
+
+use std::f64::consts::TAU;
+
+use glam::DAffine2;
+
+use crate::geometry::{BulgeArc, Point2, STRAIGHT_BULGE, Transform2};
+
+use super::flatten::edge_sweep;
+use super::{
+    Arc, Attribute, BlockDefinition, Document, Ellipse, Entity, EntityId, Hatch, HatchEdge, Line,
+    PolylineVertex, Spline, Text, normalize_angle,
+};
+
+impl Document {
+    /// 计算实体分解后的组成实体，不修改文档；实体不可分解（或块定义缺失）时返回 `None`。
+    pub fn exploded(&self, entity: &Entity) -> Option<Vec<Entity>> {
+        match entity {
+            Entity::Polyline(polyline) => Some(explode_vertices(
+                &polyline.vertices,
+                polyline.is_closed,
+                &polyline.layer,
+            )),
+            Entity::RevisionCloud(cloud) => Some(explode_vertices(
+                &cloud.vertices,
+                cloud.is_closed,
+                &cloud.layer,
+            )),
+            Entity::Hatch(hatch) => Some(explode_hatch(hatch)),
+            Entity::BlockReference(reference) => {
+                let block = self.block(&reference.name)?;
+                let transform = Transform2::from_affine(DAffine2::from_scale_angle_translation(
+                    reference.scale.as_vec2(),
+                    reference.rotation,
+                    reference.insert.as_vec2(),
+                ));
+                let mut parts = block_content(block, &transform, &reference.layer);
+                parts.extend(
+                    reference
+                        .attributes
+                        .iter()
+                        .filter(|attribute| !attribute.is_invisible)
+                        .map(attribute_text),
+                );
+                Some(parts)
+            }
+            Entity::Dimension(dimension) => {
+                let block = self.dimension_block(dimension)?;
+                Some(block_content(
+                    block,
+                    &Transform2::IDENTITY,
+                    &dimension.layer,
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// 分解模型空间实体：原实体被组成实体替换，返回新实体编号；不可分解时文档不变并返回空列表。
+    pub fn explode_entity(&mut self, id: EntityId) -> Vec<EntityId> {
+        let Some(parts) = self.entity(id).and_then(|entity| self.exploded(entity)) else {
+            return Vec::new();
+        };
+        let properties = self.entity_properties(id).cloned();
+        self.remove_entity(id);
+        parts
+            .into_iter()
+            .map(|part| {
+                let part_id = self.add_entity(part);
+                if let Some(properties) = &properties {
+                    self.set_entity_properties(part_id, properties.clone());
+                }
+                part_id
+            })
+            .collect()
+    }
+}
+
+/// 块内实体经 `transform` 与基点平移后的副本。
+fn block_content(block: &BlockDefinition, transform: &Transform2, layer: &str) -> Vec<Entity> {
+    let placement = Transform2::from_affine(
+        transform.as_affine() * DAffine2::from_translation(-block.base_point.as_vec2()),
+    );
+    block
+        .entities
+        .iter()
+        .map(|child| {
+            let mut child = child.clone();
+            child.transform(&placement);
+            if child.layer_name() == "0" {
+                child.set_layer_name(layer);
+            }
+            child
+        })
+        .collect()
+}
+
+fn attribute_text(attribute: &Attribute) -> Entity {
+    Entity::Text(Text {
+        insert: attribute.insert,
+        content: attribute.text.clone(),
+        height: attribute.height,
+        rotation: attribute.rotation,
+        layer: attribute.layer.clone(),
+        horizontal_align: attribute.horizontal_align,
+        vertical_align: attribute.vertical_align,
+        alignment: attribute.alignment,
+        is_backward: false,
+        is_upside_down: false,
+    })
+}
+
+fn explode_vertices(vertices: &[PolylineVertex], is_closed: bool, layer: &str) -> Vec<Entity> {
+    let count = vertices.len();
+    let segments = if is_closed && count > 1 {
+        count
+    } else {
+        count.saturating_sub(1)
+    };
+    (0..segments)
+        .filter_map(|index| {
+            let vertex = &vertices[index];
+            let next = &vertices[(index + 1) % count];
+            segment(vertex.position, next.position, vertex.bulge, layer)
+        })
+        .collect()
+}
+
+/// 一段多段线：凸度为零时为直线，否则为圆弧；退化的零长度段返回 `None`。
+fn segment(start: Point2, end: Point2, bulge: f64, layer: &str) -> Option<Entity> {
+    let (a, b) = (start.as_vec2(), end.as_vec2());
+    if a.distance(b) <= STRAIGHT_BULGE {
+        return None;
+    }
+    let Some(arc) = BulgeArc::new(a, b, bulge) else {
+        return Some(Entity::Line(Line {
+            start,
+            end,
+            layer: layer.to_string(),
+        }));
+    };
+    let (from, to) = (arc.start_angle, (b - arc.center).to_angle());
+    // 负凸度为顺时针圆弧，换为从终点出发的逆时针圆弧
+    let (start_angle, end_angle) = if bulge > 0.0 { (from, to) } else { (to, from) };
+    Some(Entity::Arc(Arc {
+        center: Point2::from_vec(arc.center),
+        radius: arc.radius,
+        start_angle: normalize_angle(start_angle),
+        end_angle: normalize_angle(end_angle),
+        layer: layer.to_string(),
+    }))
+}
+
+/// 填充的边界边；引用其他对象的边界不重复生成。
+pub(super) fn explode_hatch(hatch: &Hatch) -> Vec<Entity> {
+    let layer = hatch.layer.as_str();
+    let mut parts = Vec::new();
+    for edge in hatch.loops.iter().flat_map(|hatch_loop| &hatch_loop.edges) {
+        let part = match edge {
+            HatchEdge::Line { start, end } => segment(*start, *end, 0.0, layer),
+            HatchEdge::PolylineSegment { start, end, bulge } => {
+                segment(*start, *end, *bulge, layer)
+            }
+            HatchEdge::Arc {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+                is_counter_clockwise,
+            } => {
+                let (start_angle, end_angle) =
+                    counter_clockwise(*start_angle, *end_angle, *is_counter_clockwise);
+                Some(Entity::Arc(Arc {
+                    center: *center,
+                    radius: *radius,
+                    start_angle,
+                    end_angle,
+                    layer: layer.to_string(),
+                }))
+            }
+            HatchEdge::Ellipse {
+                center,
+                major_axis,
+                minor_ratio,
+                start_angle,
+                end_angle,
+                is_counter_clockwise,
+            } => {
+                let (start_parameter, end_parameter) =
+                    counter_clockwise(*start_angle, *end_angle, *is_counter_clockwise);
+                Some(Entity::Ellipse(Ellipse {
+                    center: *center,
+                    major_axis: *major_axis,
+                    ratio: *minor_ratio,
+                    start_parameter,
+                    end_parameter,
+                    layer: layer.to_string(),
+                }))
+            }
+            HatchEdge::Spline {
+                control_points,
+                fit_points,
+                knot_values,
+                degree,
+                is_rational,
+                is_periodic,
+            } => Some(Entity::Spline(Spline {
+                degree: *degree,
+                is_rational: *is_rational,
+                is_closed: *is_periodic,
+                is_periodic: *is_periodic,
+                control_points: control_points.clone(),
+                fit_points: fit_points.clone(),
+                knot_values: knot_values.clone(),
+                weights: Vec::new(),
+                start_tangent: None,
+                end_tangent: None,
+                layer: layer.to_string(),
+            })),
+            HatchEdge::BoundaryReference { .. } => None,
+        };
+        parts.extend(part);
+    }
+    parts
+}
+
+/// 填充边的角度区间转为逆时针的起止角，整圆为 `0..2π`。
+fn counter_clockwise(start: f64, end: f64, is_counter_clockwise: bool) -> (f64, f64) {
+    let (start, sweep) = edge_sweep(start, end, is_counter_clockwise);
+    if sweep.abs() >= TAU - 1e-9 {
+        return (0.0, TAU);
+    }
+    let (from, to) = if sweep >= 0.0 {
+        (start, start + sweep)
+    } else {
+        (start + sweep, start)
+    };
+    (normalize_angle(from), normalize_angle(to))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use crate::geometry::Vector2;
+
+    use super::super::{
+        Dimension, DimensionKind, EntityProperties, HatchLoop, HatchStyle, ObjectColor,
+    };
+    use super::*;
+
+    fn kinds(doc: &Document, ids: &[EntityId]) -> Vec<&'static str> {
+        ids.iter()
+            .map(|id| match doc.entity(*id) {
+                Some(Entity::Line(_)) => "line",
+                Some(Entity::Arc(_)) => "arc",
+                Some(Entity::Ellipse(_)) => "ellipse",
+                Some(Entity::Circle(_)) => "circle",
+                Some(Entity::Text(_)) => "text",
+                _ => "other",
+            })
+            .collect()
+    }
+
+    fn assert_close(actual: Point2, x: f64, y: f64) {
+        assert!(
+            (actual.x() - x).abs() < 1e-9 && (actual.y() - y).abs() < 1e-9,
+            "{actual:?} != ({x}, {y})"
+        );
+    }
+
+    #[test]
+    fn polylines_split_into_lines_and_arcs() {
+        let mut doc = Document::new();
+        let polyline = doc.add_polyline_with_vertices(
+            vec![
+                PolylineVertex::new(Point2::new(0.0, 0.0)),
+                PolylineVertex::with_bulge(Point2::new(2.0, 0.0), -1.0),
+                PolylineVertex::new(Point2::new(2.0, 2.0)),
+                PolylineVertex::new(Point2::new(2.0, 2.0)),
+            ],
+            true,
+            "OUTLINE",
+        );
+        doc.set_entity_properties(
+            polyline,
+            EntityProperties {
+                color: ObjectColor::Index(3),
+                ..EntityProperties::default()
+            },
+        );
+        let parts = doc.explode_entity(polyline);
+        assert!(doc.entity(polyline).is_none());
+        assert_eq!(kinds(&doc, &parts), vec!["line", "arc", "line"]);
+        let Some(Entity::Arc(arc)) = doc.entity(parts[1]) else {
+            unreachable!()
+        };
+        // 自下而上的顺时针半圆向左凸出，表示为自上而下的逆时针圆弧
+        assert_close(arc.center, 2.0, 1.0);
+        assert!((arc.radius - 1.0).abs() < 1e-9);
+        assert!((arc.start_angle - FRAC_PI_2).abs() < 1e-9);
+        assert!((arc.end_angle - 3.0 * FRAC_PI_2).abs() < 1e-9);
+        assert!(parts.iter().all(|id| {
+            doc.entity(*id).unwrap().layer_name() == "OUTLINE"
+                && doc.entity_properties(*id).map(|p| p.color) == Some(ObjectColor::Index(3))
+        }));
+
+        // 不可分解的实体保持不变
+        let line = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+        assert!(doc.explode_entity(line).is_empty());
+        assert!(doc.entity(line).is_some());
+    }
+
+    #[test]
+    fn hatches_split_into_boundary_edges() {
+        let mut doc = Document::new();
+        let hatch = doc.add_hatch(
+            "SOLID",
+            true,
+            vec![HatchLoop {
+                is_polyline: false,
+                is_closed: true,
+                edges: vec![
+                    HatchEdge::Line {
+                        start: Point2::new(-1.0, 0.0),
+                        end: Point2::new(1.0, 0.0),
+                    },
+                    HatchEdge::Arc {
+                        center: Point2::new(0.0, 0.0),
+                        radius: 1.0,
+                        start_angle: 0.0,
+                        end_angle: PI,
+                        is_counter_clockwise: false,
+                    },
+                    HatchEdge::BoundaryReference {
+                        handle: "2A".to_string(),
+                    },
+                ],
+                boundary_handles: Vec::new(),
+                boundary_entities: Vec::new(),
+            }],
+            None,
+            Vec::new(),
+            HatchStyle::Normal,
+            false,
+            Vec::new(),
+            "FILL",
+        );
+        let parts = doc.explode_entity(hatch);
+        assert_eq!(kinds(&doc, &parts), vec!["line", "arc"]);
+        let Some(Entity::Arc(arc)) = doc.entity(parts[1]) else {
+            unreachable!()
+        };
+        // 顺时针边从 0 走到 -π，即下半圆
+        assert!((arc.start_angle - PI).abs() < 1e-9 && arc.end_angle.abs() < 1e-9);
+    }
+
+    #[test]
+    fn block_references_and_dimensions_expand_one_level() {
+        let mut doc = Document::new();
+        let mut scratch = Document::new();
+        scratch.add_line(Point2::new(1.0, 0.0), Point2::new(2.0, 0.0), "0");
+        scratch.add_circle(Point2::new(1.0, 0.0), 0.5, "KEEP");
+        doc.add_block_definition(BlockDefinition {
+            name: "PIN".to_string(),
+            base_point: Point2::new(1.0, 0.0),
+            entities: scratch.entities().map(|(_, e)| e.clone()).collect(),
+            attributes: Vec::new(),
+        });
+        let reference = doc.add_block_reference(
+            "PIN",
+            Point2::new(10.0, 10.0),
+            Vector2::new(2.0, 2.0),
+            FRAC_PI_2,
+            Vec::new(),
+            "PARTS",
+        );
+        let parts = doc.explode_entity(reference);
+        assert_eq!(kinds(&doc, &parts), vec!["line", "circle"]);
+        let Some(Entity::Line(line)) = doc.entity(parts[0]) else {
+            unreachable!()
+        };
+        assert_close(line.start, 10.0, 10.0);
+        assert_close(line.end, 10.0, 12.0);
+        assert_eq!(line.layer, "PARTS");
+        assert_eq!(doc.entity(parts[1]).unwrap().layer_name(), "KEEP");
+
+        let missing = doc.add_block_reference(
+            "NONE",
+            Point2::new(0.0, 0.0),
+            Vector2::new(1.0, 1.0),
+            0.0,
+            Vec::new(),
+            "0",
+        );
+        assert!(doc.explode_entity(missing).is_empty());
+
+        // 标注按匿名块展开；没有匿名块时无法分解
+        doc.add_block_definition(BlockDefinition {
+            name: "*D1".to_string(),
+            base_point: Point2::new(0.0, 0.0),
+            entities: vec![Entity::Line(Line {
+                start: Point2::new(0.0, 0.0),
+                end: Point2::new(5.0, 0.0),
+                layer: "0".to_string(),
+            })],
+            attributes: Vec::new(),
+        });
+        let mut dimension = sample_dimension();
+        let bare = doc.add_entity(Entity::Dimension(dimension.clone()));
+        assert!(doc.explode_entity(bare).is_empty());
+        dimension.block_name = Some("*D1".to_string());
+        let rendered = doc.add_entity(Entity::Dimension(dimension));
+        let parts = doc.explode_entity(rendered);
+        assert_eq!(kinds(&doc, &parts), vec!["line"]);
+        assert_eq!(doc.entity(parts[0]).unwrap().layer_name(), "DIMS");
+    }
+
+    fn sample_dimension() -> Dimension {
+        Dimension {
+            kind: DimensionKind::Linear,
+            definition_point: Point2::new(5.0, 0.0),
+            text_midpoint: Point2::new(2.5, 1.0),
+            dimension_line_point: None,
+            extension_line_origin: None,
+            extension_line_end: None,
+            secondary_point: None,
+            arc_definition_point: None,
+            center_point: None,
+            text: None,
+            measurement: Some(5.0),
+            rotation: 0.0,
+            text_rotation: None,
+            oblique_angle: None,
+            block_name: None,
+            style_name: None,
+            jog_point: None,
+            arc: None,
+            layer: "DIMS".to_string(),
+        }
+    }
+}
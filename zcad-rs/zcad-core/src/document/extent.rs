@@ -0,0 +1,197 @@
+//! 考虑块内容的实体范围：块参照按所引用块定义的内容范围，经基点、插入点、比例与旋转变换得到。
+//!
+//! 块定义的局部范围按名称缓存，嵌套块逐层变换轴对齐范围的四角，结果偏保守。
+//! 引用缺失、循环引用或超过嵌套深度时，块参照只计插入点与属性。
+
+use std::collections::HashMap;
+
+use glam::DAffine2;
+
+use crate::geometry::{Bounds2D, Point2};
+
+use super::{BlockReference, Document, Entity};
+
+/// 与展开块参照的默认深度一致。
+const MAX_BLOCK_DEPTH: usize = 16;
+
+pub(super) struct ExtentResolver<'a> {
+    document: &'a Document,
+    /// 块定义在块坐标系下的内容范围。
+    blocks: HashMap<&'a str, Option<Bounds2D>>,
+    /// 正在展开的块，用于发现循环引用。
+    visiting: Vec<&'a str>,
+}
+
+impl<'a> ExtentResolver<'a> {
+    pub(super) fn new(document: &'a Document) -> Self {
+        Self {
+            document,
+            blocks: HashMap::new(),
+            visiting: Vec::new(),
+        }
+    }
+
+    pub(super) fn entity(&mut self, entity: &Entity) -> Option<Bounds2D> {
+        match entity {
+            Entity::BlockReference(reference) => Some(self.reference(reference)),
+            _ => entity.bounds(),
+        }
+    }
+
+    fn reference(&mut self, reference: &BlockReference) -> Bounds2D {
+        let mut bounds = Bounds2D::empty();
+        let content = self.document.block(&reference.name).and_then(|block| {
+            let local = self.block(&reference.name)?;
+            let transform = DAffine2::from_scale_angle_translation(
+                reference.scale.as_vec2(),
+                reference.rotation,
+                reference.insert.as_vec2(),
+            ) * DAffine2::from_translation(-block.base_point.as_vec2());
+            Some(transform_bounds(transform, &local))
+        });
+        match content {
+            Some(content) => bounds.include_bounds(&content),
+            None => bounds.include_point(reference.insert),
+        }
+        // 属性已是世界坐标
+        for attr in &reference.attributes {
+            bounds.include_point(attr.insert);
+            if let Some(alignment) = attr.alignment {
+                bounds.include_point(alignment);
+            }
+        }
+        bounds
+    }
+
+    fn block(&mut self, name: &str) -> Option<Bounds2D> {
+        let (name, block) = self.document.blocks.get_key_value(name)?;
+        let name = name.as_str();
+        if let Some(cached) = self.blocks.get(name) {
+            return *cached;
+        }
+        if self.visiting.len() >= MAX_BLOCK_DEPTH || self.visiting.contains(&name) {
+            return None;
+        }
+        self.visiting.push(name);
+        let local = block
+            .entities
+            .iter()
+            .filter_map(|child| self.entity(child))
+            .reduce(|mut total, bounds| {
+                total.include_bounds(&bounds);
+                total
+            });
+        self.visiting.pop();
+        self.blocks.insert(name, local);
+        local
+    }
+}
+
+/// 变换轴对齐范围的四角，返回包住结果的轴对齐范围。
+fn transform_bounds(transform: DAffine2, bounds: &Bounds2D) -> Bounds2D {
+    let (min, max) = (bounds.min(), bounds.max());
+    let mut result = Bounds2D::empty();
+    for (x, y) in [
+        (min.x(), min.y()),
+        (max.x(), min.y()),
+        (max.x(), max.y()),
+        (min.x(), max.y()),
+    ] {
+        let corner = transform.transform_point2(Point2::new(x, y).as_vec2());
+        result.include_point(Point2::from_vec(corner));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use crate::geometry::Vector2;
+
+    use super::super::BlockDefinition;
+    use super::*;
+
+    fn block(name: &str, base_point: Point2, entities: Vec<Entity>) -> BlockDefinition {
+        BlockDefinition {
+            name: name.to_string(),
+            base_point,
+            entities,
+            attributes: Vec::new(),
+        }
+    }
+
+    fn reference(name: &str, insert: Point2, scale: f64, rotation: f64) -> Entity {
+        Entity::BlockReference(BlockReference {
+            name: name.to_string(),
+            insert,
+            scale: Vector2::new(scale, scale),
+            rotation,
+            attributes: Vec::new(),
+            layer: "0".to_string(),
+        })
+    }
+
+    fn extent(bounds: Bounds2D) -> [f64; 4] {
+        let round = |value: f64| (value * 1e9).round() / 1e9;
+        [
+            round(bounds.min().x()),
+            round(bounds.min().y()),
+            round(bounds.max().x()),
+            round(bounds.max().y()),
+        ]
+    }
+
+    #[test]
+    fn block_references_cover_transformed_content() {
+        let mut doc = Document::new();
+        let mut scratch = Document::new();
+        scratch.add_line(Point2::new(1.0, 0.0), Point2::new(3.0, 1.0), "0");
+        let contents = scratch
+            .entities()
+            .map(|(_, entity)| entity.clone())
+            .collect();
+        doc.add_block_definition(block("BAR", Point2::new(1.0, 0.0), contents));
+        doc.add_block_definition(block(
+            "PAIR",
+            Point2::new(0.0, 0.0),
+            vec![
+                reference("BAR", Point2::new(0.0, 0.0), 1.0, 0.0),
+                reference("BAR", Point2::new(0.0, 5.0), 1.0, 0.0),
+            ],
+        ));
+        // 循环引用只计插入点
+        doc.add_block_definition(block(
+            "LOOP",
+            Point2::new(0.0, 0.0),
+            vec![reference("LOOP", Point2::new(7.0, 7.0), 1.0, 0.0)],
+        ));
+
+        let single = doc.add_entity(reference("BAR", Point2::new(10.0, 10.0), 2.0, FRAC_PI_2));
+        assert_eq!(
+            extent(doc.entity_bounds(single).unwrap()),
+            [8.0, 10.0, 10.0, 14.0]
+        );
+        let nested = doc.add_entity(reference("PAIR", Point2::new(20.0, 0.0), 1.0, 0.0));
+        assert_eq!(
+            extent(doc.entity_bounds(nested).unwrap()),
+            [20.0, 0.0, 22.0, 6.0]
+        );
+        let looped = doc.add_entity(reference("LOOP", Point2::new(-5.0, 0.0), 1.0, 0.0));
+        assert_eq!(
+            extent(doc.entity_bounds(looped).unwrap()),
+            [2.0, 7.0, 2.0, 7.0]
+        );
+        let missing = doc.add_entity(reference("NONE", Point2::new(1.0, 2.0), 1.0, 0.0));
+        assert_eq!(
+            extent(doc.entity_bounds(missing).unwrap()),
+            [1.0, 2.0, 1.0, 2.0]
+        );
+        assert_eq!(extent(doc.bounds().unwrap()), [1.0, 0.0, 22.0, 14.0]);
+
+        // 修改块定义后整体范围随之更新
+        doc.add_block_definition(block("BAR", Point2::new(0.0, 0.0), Vec::new()));
+        assert_eq!(extent(doc.bounds().unwrap()), [1.0, 0.0, 20.0, 10.0]);
+        assert_eq!(doc.query_point(Point2::new(10.0, 10.0), 0.1), vec![single]);
+    }
+}
@@ -0,0 +1,339 @@
+//! 数据提取（DATAEXTRACTION）：把块参照的属性整理成表格，用于门窗表、材料清单（BOM）等明细表。
+//!
+//! 每个块参照一行，列为块名、图层、插入点与各属性标记；按块汇总时块名与全部属性值相同的参照
+//! 合并为一行并计数。属性标记不区分大小写，列按标记首次出现的顺序排列。CSV/JSON 输出见 zcad-io。
+
+use std::collections::HashMap;
+
+use glam::DAffine2;
+use serde::Serialize;
+
+use super::{BlockReference, Document, Entity};
+use crate::geometry::Point2;
+
+/// 数据提取选项。
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractionOptions {
+    /// 只提取这些块（不区分大小写）的参照；为空时提取全部块。
+    pub blocks: Vec<String>,
+    /// 同时提取模型空间块参照所引用块中的嵌套参照，每个实例各计一次。
+    pub include_nested: bool,
+    /// 按块名与属性值汇总计数，汇总行不含图层与插入点。
+    pub group_by_block: bool,
+    /// 提取匿名块（`*` 开头，如标注、动态块实例）的参照。
+    pub include_anonymous: bool,
+    /// 嵌套展开的最大深度，防止循环引用。
+    pub max_block_depth: usize,
+}
+
+impl Default for ExtractionOptions {
+    fn default() -> Self {
+        Self {
+            blocks: Vec::new(),
+            include_nested: false,
+            group_by_block: false,
+            include_anonymous: false,
+            max_block_depth: 16,
+        }
+    }
+}
+
+/// 提取结果中的一行。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExtractionRow {
+    pub block: String,
+    /// 汇总行为 `None`。
+    pub layer: Option<String>,
+    /// 世界坐标插入点，汇总行为 `None`。
+    pub position: Option<Point2>,
+    pub count: usize,
+    /// 与 [`ExtractionTable::tags`] 一一对应，参照缺少的属性为空字符串。
+    pub values: Vec<String>,
+}
+
+/// 数据提取结果。
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ExtractionTable {
+    pub grouped: bool,
+    pub tags: Vec<String>,
+    pub rows: Vec<ExtractionRow>,
+}
+
+impl ExtractionTable {
+    /// 按标记取某行的属性值。
+    pub fn value<'a>(&self, row: &'a ExtractionRow, tag: &str) -> Option<&'a str> {
+        let index = self
+            .tags
+            .iter()
+            .position(|existing| existing.eq_ignore_ascii_case(tag))?;
+        row.values.get(index).map(String::as_str)
+    }
+
+    /// 各行计数之和。
+    pub fn total_count(&self) -> usize {
+        self.rows.iter().map(|row| row.count).sum()
+    }
+}
+
+impl Document {
+    /// 提取块参照属性，参照按文档顺序（嵌套参照紧随其外层参照）输出，汇总时按块名与属性值排序。
+    pub fn extract_attributes(&self, options: &ExtractionOptions) -> ExtractionTable {
+        let mut collector = Collector {
+            document: self,
+            options,
+            tags: Vec::new(),
+            tag_index: HashMap::new(),
+            rows: Vec::new(),
+        };
+        for (_, entity) in self.entities() {
+            if let Entity::BlockReference(reference) = entity {
+                collector.reference(reference, DAffine2::IDENTITY, 0);
+            }
+        }
+
+        let width = collector.tags.len();
+        let mut rows = collector.rows;
+        for row in &mut rows {
+            row.values.resize(width, String::new());
+        }
+        if options.group_by_block {
+            let mut grouped: Vec<ExtractionRow> = Vec::new();
+            let mut index: HashMap<(String, Vec<String>), usize> = HashMap::new();
+            for row in rows {
+                let key = (row.block.clone(), row.values.clone());
+                match index.get(&key) {
+                    Some(position) => grouped[*position].count += row.count,
+                    None => {
+                        index.insert(key, grouped.len());
+                        grouped.push(ExtractionRow {
+                            layer: None,
+                            position: None,
+                            ..row
+                        });
+                    }
+                }
+            }
+            grouped.sort_by(|a, b| a.block.cmp(&b.block).then_with(|| a.values.cmp(&b.values)));
+            rows = grouped;
+        }
+        ExtractionTable {
+            grouped: options.group_by_block,
+            tags: collector.tags,
+            rows,
+        }
+    }
+}
+
+struct Collector<'a> {
+    document: &'a Document,
+    options: &'a ExtractionOptions,
+    tags: Vec<String>,
+    /// 大写标记到列号。
+    tag_index: HashMap<String, usize>,
+    rows: Vec<ExtractionRow>,
+}
+
+impl Collector<'_> {
+    fn reference(&mut self, reference: &BlockReference, transform: DAffine2, depth: usize) {
+        if self.selected(&reference.name) {
+            let mut values = Vec::new();
+            for attribute in &reference.attributes {
+                let column = self.column(&attribute.tag);
+                if values.len() <= column {
+                    values.resize(column + 1, String::new());
+                }
+                if values[column].is_empty() {
+                    values[column] = attribute.text.clone();
+                }
+            }
+            self.rows.push(ExtractionRow {
+                block: reference.name.clone(),
+                layer: Some(reference.layer.clone()),
+                position: Some(Point2::from_vec(
+                    transform.transform_point2(reference.insert.as_vec2()),
+                )),
+                count: 1,
+                values,
+            });
+        }
+
+        if !self.options.include_nested || depth >= self.options.max_block_depth {
+            return;
+        }
+        let Some(block) = self.document.block(&reference.name) else {
+            return;
+        };
+        let transform = transform
+            * DAffine2::from_scale_angle_translation(
+                reference.scale.as_vec2(),
+                reference.rotation,
+                reference.insert.as_vec2(),
+            )
+            * DAffine2::from_translation(-block.base_point.as_vec2());
+        for child in &block.entities {
+            if let Entity::BlockReference(nested) = child {
+                self.reference(nested, transform, depth + 1);
+            }
+        }
+    }
+
+    fn selected(&self, name: &str) -> bool {
+        if name.starts_with('*') && !self.options.include_anonymous {
+            return false;
+        }
+        self.options.blocks.is_empty()
+            || self
+                .options
+                .blocks
+                .iter()
+                .any(|block| block.eq_ignore_ascii_case(name))
+    }
+
+    fn column(&mut self, tag: &str) -> usize {
+        let key = tag.to_uppercase();
+        if let Some(column) = self.tag_index.get(&key) {
+            return *column;
+        }
+        self.tags.push(tag.to_string());
+        self.tag_index.insert(key, self.tags.len() - 1);
+        self.tags.len() - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{Attribute, BlockDefinition};
+    use crate::geometry::Vector2;
+
+    fn attribute(tag: &str, text: &str) -> Attribute {
+        Attribute {
+            tag: tag.to_string(),
+            text: text.to_string(),
+            insert: Point2::new(0.0, 0.0),
+            height: 1.0,
+            rotation: 0.0,
+            width_factor: 1.0,
+            oblique: 0.0,
+            style: None,
+            prompt: None,
+            alignment: None,
+            horizontal_align: 0,
+            vertical_align: 0,
+            line_spacing_factor: 1.0,
+            line_spacing_style: 1,
+            is_invisible: false,
+            is_constant: false,
+            is_verify: false,
+            is_preset: false,
+            lock_position: false,
+            layer: "0".to_string(),
+        }
+    }
+
+    fn reference(name: &str, insert: Point2, attributes: Vec<Attribute>) -> BlockReference {
+        BlockReference {
+            name: name.to_string(),
+            insert,
+            scale: Vector2::new(1.0, 1.0),
+            rotation: 0.0,
+            attributes,
+            layer: "DOORS".to_string(),
+        }
+    }
+
+    fn sample() -> Document {
+        let mut doc = Document::new();
+        for name in ["DOOR", "ROOM"] {
+            doc.add_block_definition(BlockDefinition {
+                name: name.to_string(),
+                base_point: Point2::new(0.0, 0.0),
+                entities: Vec::new(),
+                attributes: Vec::new(),
+            });
+        }
+        doc.add_block_definition(BlockDefinition {
+            name: "SUITE".to_string(),
+            base_point: Point2::new(0.0, 0.0),
+            entities: vec![Entity::BlockReference(reference(
+                "DOOR",
+                Point2::new(1.0, 0.0),
+                vec![attribute("TYPE", "D1")],
+            ))],
+            attributes: Vec::new(),
+        });
+        let doors = [("D1", "900"), ("D2", "800"), ("D1", "900")];
+        for (index, (kind, width)) in doors.into_iter().enumerate() {
+            doc.add_entity(Entity::BlockReference(reference(
+                "DOOR",
+                Point2::new(index as f64 * 10.0, 0.0),
+                vec![attribute("TYPE", kind), attribute("Width", width)],
+            )));
+        }
+        doc.add_entity(Entity::BlockReference(reference(
+            "ROOM",
+            Point2::new(0.0, 5.0),
+            vec![attribute("NAME", "Lobby"), attribute("type", "public")],
+        )));
+        doc.add_entity(Entity::BlockReference(reference(
+            "SUITE",
+            Point2::new(100.0, 0.0),
+            Vec::new(),
+        )));
+        doc
+    }
+
+    #[test]
+    fn rows_follow_document_order_with_shared_columns() {
+        let doc = sample();
+        let table = doc.extract_attributes(&ExtractionOptions::default());
+        assert!(!table.grouped);
+        assert_eq!(table.tags, ["TYPE", "Width", "NAME"]);
+        assert_eq!(table.rows.len(), 5);
+        let room = &table.rows[3];
+        assert_eq!(room.values, ["public", "", "Lobby"]);
+        assert_eq!(table.value(room, "name"), Some("Lobby"));
+        assert_eq!(room.position, Some(Point2::new(0.0, 5.0)));
+        assert_eq!(table.rows[4].block, "SUITE");
+
+        let nested = doc.extract_attributes(&ExtractionOptions {
+            blocks: vec!["door".to_string()],
+            include_nested: true,
+            ..ExtractionOptions::default()
+        });
+        assert_eq!(nested.rows.len(), 4);
+        assert_eq!(nested.rows[3].position, Some(Point2::new(101.0, 0.0)));
+    }
+
+    #[test]
+    fn grouping_counts_identical_references() {
+        let doc = sample();
+        let table = doc.extract_attributes(&ExtractionOptions {
+            blocks: vec!["DOOR".to_string()],
+            include_nested: true,
+            group_by_block: true,
+            ..ExtractionOptions::default()
+        });
+        let summary: Vec<(&str, usize, Vec<&str>)> = table
+            .rows
+            .iter()
+            .map(|row| {
+                (
+                    row.block.as_str(),
+                    row.count,
+                    row.values.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("DOOR", 1, vec!["D1", ""]),
+                ("DOOR", 2, vec!["D1", "900"]),
+                ("DOOR", 1, vec!["D2", "800"]),
+            ]
+        );
+        assert_eq!(table.total_count(), 4);
+        assert!(table.rows.iter().all(|row| row.layer.is_none()));
+    }
+}
@@ -0,0 +1,517 @@
+//! 填充几何：由填充边界环求实体填充的三角形与图案填充裁剪后的线段，供导出与绘制使用。
+//!
+//! 边界环先按 [`TessellateOptions`] 离散为多边形，再按孤岛样式取舍：`Normal` 用全部环，
+//! `Outer` 只用最外层边界与第一层孤岛，`Ignore` 只用最外层边界。保留的环按填充规则（奇偶或非零环绕）
+//! 确定内部：实体填充按扫描线切成梯形再分为三角形，图案线与边界求交后按规则取内部区间，
+//! 再按虚线长度拆分。边界引用边没有几何，不参与。
+
+use glam::DVec2;
+
+use crate::geometry::Point2;
+use crate::tessellate::{self, TessellateOptions};
+
+use super::contain::polygon_contains;
+use super::{Hatch, HatchPatternLine, HatchStyle};
+
+/// 坐标比较容差。
+const TOLERANCE: f64 = 1e-9;
+/// 每组图案线的最大条数，超过时放弃该组，防止比例失当的图案耗尽内存。
+const MAX_PATTERN_LINES: usize = 20_000;
+/// 单个内部区间内的最大虚线周期数，超过时按实线处理。
+const MAX_DASH_PERIODS: usize = 10_000;
+
+/// 判断点是否在填充内部的规则。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FillRule {
+    /// 奇偶规则：穿越边界次数为奇数时在内部，DXF 填充的默认解释。
+    #[default]
+    EvenOdd,
+    /// 非零环绕规则：边界环绕数不为零时在内部。
+    NonZero,
+}
+
+/// 填充几何的计算选项。
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct HatchFillOptions {
+    /// 边界曲线的离散精度。
+    pub tessellate: TessellateOptions,
+    /// 内部判断规则。
+    pub rule: FillRule,
+}
+
+/// 填充几何：实体填充与渐变填充给出三角形，图案填充给出线段，点以首尾相同的线段表示。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HatchFill {
+    /// 逆时针三角形。
+    pub triangles: Vec<[Point2; 3]>,
+    /// 图案线段。
+    pub lines: Vec<[Point2; 2]>,
+}
+
+impl Hatch {
+    /// 计算填充几何；非实体填充且没有图案线时结果为空。
+    pub fn fill(&self, options: &HatchFillOptions) -> HatchFill {
+        let polygons = self.fill_polygons(&options.tessellate);
+        let mut fill = HatchFill::default();
+        if polygons.is_empty() {
+            return fill;
+        }
+        if self.is_solid || self.gradient.is_some() {
+            fill.triangles = triangulate(&edges(&polygons), options.rule);
+        } else {
+            let (min, max) = extent(&polygons);
+            for line in &self.pattern_lines {
+                pattern_lines(line, &polygons, options.rule, min, max, &mut fill.lines);
+            }
+        }
+        fill
+    }
+
+    /// 按孤岛样式保留的边界多边形。
+    fn fill_polygons(&self, options: &TessellateOptions) -> Vec<Vec<DVec2>> {
+        let polygons: Vec<Vec<DVec2>> = self
+            .loops
+            .iter()
+            .map(|loop_path| {
+                tessellate::hatch_loop(loop_path, options)
+                    .into_iter()
+                    .map(Point2::as_vec2)
+                    .collect::<Vec<_>>()
+            })
+            .filter(|points| points.len() >= 3)
+            .collect();
+        let max_depth = match self.style {
+            HatchStyle::Normal => return polygons,
+            HatchStyle::Outer => 1,
+            HatchStyle::Ignore => 0,
+        };
+        let depths: Vec<usize> = polygons
+            .iter()
+            .enumerate()
+            .map(|(index, polygon)| {
+                polygons
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, outer)| *other != index && polygon_contains(outer, polygon[0]))
+                    .count()
+            })
+            .collect();
+        polygons
+            .into_iter()
+            .zip(depths)
+            .filter(|(_, depth)| *depth <= max_depth)
+            .map(|(polygon, _)| polygon)
+            .collect()
+    }
+}
+
+/// 有向边，按 y 递增存放，`winding` 记录原方向（向上为 +1）。
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    low: DVec2,
+    high: DVec2,
+    winding: i32,
+}
+
+impl Edge {
+    fn x_at(&self, y: f64) -> f64 {
+        let t = (y - self.low.y) / (self.high.y - self.low.y);
+        self.low.x + (self.high.x - self.low.x) * t
+    }
+}
+
+/// 多边形的全部非水平边。
+fn edges(polygons: &[Vec<DVec2>]) -> Vec<Edge> {
+    let mut edges = Vec::new();
+    for polygon in polygons {
+        for (index, start) in polygon.iter().enumerate() {
+            let end = polygon[(index + 1) % polygon.len()];
+            if (end.y - start.y).abs() <= TOLERANCE {
+                continue;
+            }
+            edges.push(if start.y < end.y {
+                Edge {
+                    low: *start,
+                    high: end,
+                    winding: 1,
+                }
+            } else {
+                Edge {
+                    low: end,
+                    high: *start,
+                    winding: -1,
+                }
+            });
+        }
+    }
+    edges
+}
+
+fn inside(rule: FillRule, winding: i32) -> bool {
+    match rule {
+        FillRule::EvenOdd => winding % 2 != 0,
+        FillRule::NonZero => winding != 0,
+    }
+}
+
+/// 扫描线梯形剖分：在所有顶点与边交点的 y 处切开，相邻切线之间的各边互不相交，
+/// 按规则取内部区间得到梯形，每个梯形分为两个三角形。
+fn triangulate(edges: &[Edge], rule: FillRule) -> Vec<[Point2; 3]> {
+    let mut cuts: Vec<f64> = edges
+        .iter()
+        .flat_map(|edge| [edge.low.y, edge.high.y])
+        .collect();
+    for (index, first) in edges.iter().enumerate() {
+        for second in &edges[index + 1..] {
+            if let Some(y) = crossing_y(first, second) {
+                cuts.push(y);
+            }
+        }
+    }
+    cuts.sort_by(f64::total_cmp);
+    cuts.dedup_by(|next, previous| *next - *previous <= TOLERANCE);
+
+    let mut triangles = Vec::new();
+    for slab in cuts.windows(2) {
+        let (bottom, top) = (slab[0], slab[1]);
+        let middle = (bottom + top) / 2.0;
+        let mut active: Vec<&Edge> = edges
+            .iter()
+            .filter(|edge| edge.low.y <= middle && edge.high.y >= middle)
+            .collect();
+        active.sort_by(|a, b| a.x_at(middle).total_cmp(&b.x_at(middle)));
+        let mut winding = 0;
+        let mut left: Option<&Edge> = None;
+        for edge in active {
+            let was_inside = inside(rule, winding);
+            winding += edge.winding;
+            match (was_inside, inside(rule, winding)) {
+                (false, true) => left = Some(edge),
+                (true, false) => {
+                    if let Some(left) = left.take() {
+                        trapezoid(left, edge, bottom, top, &mut triangles);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    triangles
+}
+
+/// 两边在各自 y 范围内部相交时的交点 y。
+fn crossing_y(first: &Edge, second: &Edge) -> Option<f64> {
+    let (low, high) = (
+        first.low.y.max(second.low.y),
+        first.high.y.min(second.high.y),
+    );
+    if high - low <= TOLERANCE {
+        return None;
+    }
+    let (a_low, a_high) = (first.x_at(low), first.x_at(high));
+    let (b_low, b_high) = (second.x_at(low), second.x_at(high));
+    let (gap_low, gap_high) = (a_low - b_low, a_high - b_high);
+    if gap_low * gap_high >= 0.0 {
+        return None;
+    }
+    Some(low + (high - low) * gap_low / (gap_low - gap_high))
+}
+
+fn trapezoid(left: &Edge, right: &Edge, bottom: f64, top: f64, triangles: &mut Vec<[Point2; 3]>) {
+    let corners = [
+        DVec2::new(left.x_at(bottom), bottom),
+        DVec2::new(right.x_at(bottom), bottom),
+        DVec2::new(right.x_at(top), top),
+        DVec2::new(left.x_at(top), top),
+    ];
+    for [a, b, c] in [
+        [corners[0], corners[1], corners[2]],
+        [corners[0], corners[2], corners[3]],
+    ] {
+        if (b - a).perp_dot(c - a) > TOLERANCE * TOLERANCE {
+            triangles.push([a, b, c].map(Point2::from_vec));
+        }
+    }
+}
+
+fn extent(polygons: &[Vec<DVec2>]) -> (DVec2, DVec2) {
+    polygons.iter().flatten().fold(
+        (DVec2::splat(f64::MAX), DVec2::splat(f64::MIN)),
+        |(min, max), point| (min.min(*point), max.max(*point)),
+    )
+}
+
+/// 展开一组平行图案线，裁剪到填充内部并按虚线拆分。
+fn pattern_lines(
+    line: &HatchPatternLine,
+    polygons: &[Vec<DVec2>],
+    rule: FillRule,
+    min: DVec2,
+    max: DVec2,
+    lines: &mut Vec<[Point2; 2]>,
+) {
+    let direction = DVec2::from_angle(line.angle);
+    let normal = direction.perp();
+    let offset = line.offset.as_vec2();
+    let spacing = offset.dot(normal);
+    if spacing.abs() <= TOLERANCE {
+        return;
+    }
+    let base = line.base_point.as_vec2();
+    let corners = [min, DVec2::new(max.x, min.y), max, DVec2::new(min.x, max.y)];
+    let (low, high) = project_range(&corners, |corner| (corner - base).dot(normal) / spacing);
+    let (first, last) = (low.floor() as i64, high.ceil() as i64);
+    if (last - first) as usize > MAX_PATTERN_LINES {
+        return;
+    }
+    for index in first..=last {
+        let origin = base + offset * index as f64;
+        for (start, end) in inside_intervals(origin, direction, polygons, rule) {
+            dashes(&line.dash_lengths, start, end, |from, to| {
+                lines.push([
+                    Point2::from_vec(origin + direction * from),
+                    Point2::from_vec(origin + direction * to),
+                ]);
+            });
+        }
+    }
+}
+
+/// 过 `origin` 方向 `direction` 的直线落在填充内部的参数区间。
+fn inside_intervals(
+    origin: DVec2,
+    direction: DVec2,
+    polygons: &[Vec<DVec2>],
+    rule: FillRule,
+) -> Vec<(f64, f64)> {
+    let normal = direction.perp();
+    let mut crossings: Vec<(f64, i32)> = Vec::new();
+    for polygon in polygons {
+        for (index, start) in polygon.iter().enumerate() {
+            let end = polygon[(index + 1) % polygon.len()];
+            let (from, to) = ((*start - origin).dot(normal), (end - origin).dot(normal));
+            // 半开区间判断，直线恰好经过顶点时只计一次
+            if (from > 0.0) == (to > 0.0) {
+                continue;
+            }
+            let point = *start + (end - *start) * (from / (from - to));
+            let sign = if to > from { 1 } else { -1 };
+            crossings.push(((point - origin).dot(direction), sign));
+        }
+    }
+    crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut intervals = Vec::new();
+    let mut winding = 0;
+    let mut entered = None;
+    for (parameter, sign) in crossings {
+        let was_inside = inside(rule, winding);
+        winding += sign;
+        match (was_inside, inside(rule, winding)) {
+            (false, true) => entered = Some(parameter),
+            (true, false) => {
+                if let Some(start) = entered.take()
+                    && parameter - start > TOLERANCE
+                {
+                    intervals.push((start, parameter));
+                }
+            }
+            _ => {}
+        }
+    }
+    intervals
+}
+
+/// 按虚线长度拆分区间 `start..end`：正值为实线，负值为空白，0 为点；
+/// 相位从图案线原点起算。没有空白时整段为实线。
+fn dashes(lengths: &[f64], start: f64, end: f64, mut emit: impl FnMut(f64, f64)) {
+    let period: f64 = lengths.iter().map(|length| length.abs()).sum();
+    if lengths.iter().all(|length| *length > 0.0)
+        || period <= TOLERANCE
+        || (end - start) / period > MAX_DASH_PERIODS as f64
+    {
+        emit(start, end);
+        return;
+    }
+    let mut position = (start / period).floor() * period;
+    while position < end {
+        for length in lengths {
+            let next = position + length.abs();
+            if *length > 0.0 {
+                let (from, to) = (position.max(start), next.min(end));
+                if to > from {
+                    emit(from, to);
+                }
+            } else if *length == 0.0 && position >= start && position <= end {
+                emit(position, position);
+            }
+            position = next;
+        }
+    }
+}
+
+fn project_range(points: &[DVec2], project: impl Fn(DVec2) -> f64) -> (f64, f64) {
+    points
+        .iter()
+        .map(|point| project(*point))
+        .fold((f64::MAX, f64::MIN), |(low, high), value| {
+            (low.min(value), high.max(value))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{HatchEdge, HatchLoop};
+    use crate::geometry::Vector2;
+
+    fn square(min: f64, max: f64, clockwise: bool) -> HatchLoop {
+        let mut corners = [
+            Point2::new(min, min),
+            Point2::new(max, min),
+            Point2::new(max, max),
+            Point2::new(min, max),
+        ];
+        if clockwise {
+            corners.reverse();
+        }
+        HatchLoop {
+            is_polyline: true,
+            is_closed: true,
+            edges: (0..4)
+                .map(|index| HatchEdge::PolylineSegment {
+                    start: corners[index],
+                    end: corners[(index + 1) % 4],
+                    bulge: 0.0,
+                })
+                .collect(),
+            boundary_handles: Vec::new(),
+            boundary_entities: Vec::new(),
+        }
+    }
+
+    fn hatch(loops: Vec<HatchLoop>, style: HatchStyle) -> Hatch {
+        Hatch {
+            pattern_name: "SOLID".to_string(),
+            is_solid: true,
+            loops,
+            gradient: None,
+            pattern_lines: Vec::new(),
+            style,
+            is_associative: false,
+            seed_points: Vec::new(),
+            layer: "0".to_string(),
+        }
+    }
+
+    fn area(triangles: &[[Point2; 3]]) -> f64 {
+        triangles
+            .iter()
+            .map(|[a, b, c]| (b.as_vec2() - a.as_vec2()).perp_dot(c.as_vec2() - a.as_vec2()) / 2.0)
+            .sum()
+    }
+
+    #[test]
+    fn triangulates_solid_fill_with_islands() {
+        let options = HatchFillOptions::default();
+        let loops = vec![
+            square(0.0, 10.0, false),
+            square(2.0, 8.0, false),
+            square(4.0, 6.0, false),
+        ];
+        let normal = hatch(loops.clone(), HatchStyle::Normal).fill(&options);
+        assert!(normal.lines.is_empty());
+        assert!((area(&normal.triangles) - (100.0 - 36.0 + 4.0)).abs() < 1e-9);
+        let outer = hatch(loops.clone(), HatchStyle::Outer).fill(&options);
+        assert!((area(&outer.triangles) - 64.0).abs() < 1e-9);
+        let ignore = hatch(loops.clone(), HatchStyle::Ignore).fill(&options);
+        assert!((area(&ignore.triangles) - 100.0).abs() < 1e-9);
+
+        // 同向嵌套的环在非零规则下整体填充，反向的孤岛仍然挖空
+        let nonzero = HatchFillOptions {
+            rule: FillRule::NonZero,
+            ..HatchFillOptions::default()
+        };
+        let same = hatch(loops[..2].to_vec(), HatchStyle::Normal).fill(&nonzero);
+        assert!((area(&same.triangles) - 100.0).abs() < 1e-9);
+        let opposite = hatch(
+            vec![square(0.0, 10.0, false), square(2.0, 8.0, true)],
+            HatchStyle::Normal,
+        )
+        .fill(&nonzero);
+        assert!((area(&opposite.triangles) - 64.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn triangulates_curved_and_overlapping_boundaries() {
+        let circle = HatchLoop {
+            is_polyline: false,
+            is_closed: true,
+            edges: vec![HatchEdge::Arc {
+                center: Point2::new(0.0, 0.0),
+                radius: 1.0,
+                start_angle: 0.0,
+                end_angle: std::f64::consts::TAU,
+                is_counter_clockwise: true,
+            }],
+            boundary_handles: Vec::new(),
+            boundary_entities: Vec::new(),
+        };
+        let options = HatchFillOptions {
+            tessellate: TessellateOptions::per_turn(64),
+            ..HatchFillOptions::default()
+        };
+        let fill = hatch(vec![circle], HatchStyle::Normal).fill(&options);
+        let polygon = 32.0 * (std::f64::consts::TAU / 64.0).sin();
+        assert!((area(&fill.triangles) - polygon).abs() < 1e-9);
+
+        // 两个部分重叠的正方形：奇偶规则挖去重叠部分
+        let mut shifted = square(0.0, 2.0, false);
+        for edge in &mut shifted.edges {
+            if let HatchEdge::PolylineSegment { start, end, .. } = edge {
+                *start = Point2::new(start.x() + 1.0, start.y() + 1.0);
+                *end = Point2::new(end.x() + 1.0, end.y() + 1.0);
+            }
+        }
+        let fill = hatch(vec![square(0.0, 2.0, false), shifted], HatchStyle::Normal)
+            .fill(&HatchFillOptions::default());
+        assert!((area(&fill.triangles) - 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn clips_pattern_lines_to_boundary() {
+        let mut pattern = hatch(
+            vec![square(0.0, 10.0, false), square(4.0, 6.0, false)],
+            HatchStyle::Normal,
+        );
+        pattern.is_solid = false;
+        pattern.pattern_name = "ANSI31".to_string();
+        pattern.pattern_lines = vec![HatchPatternLine {
+            angle: 0.0,
+            base_point: Point2::new(0.0, 0.5),
+            offset: Vector2::new(0.0, 1.0),
+            dash_lengths: Vec::new(),
+        }];
+        let fill = pattern.fill(&HatchFillOptions::default());
+        assert!(fill.triangles.is_empty());
+        // y = 0.5 .. 9.5 共十条，其中 y = 4.5、5.5 两条被孤岛截成两段
+        assert_eq!(fill.lines.len(), 12);
+        let total: f64 = fill
+            .lines
+            .iter()
+            .map(|[a, b]| a.as_vec2().distance(b.as_vec2()))
+            .sum();
+        assert!((total - (100.0 - 4.0)).abs() < 1e-9);
+
+        pattern.loops.truncate(1);
+        pattern.pattern_lines[0].dash_lengths = vec![1.0, -0.5, 0.0, -0.5];
+        let fill = pattern.fill(&HatchFillOptions::default());
+        let row: Vec<_> = fill
+            .lines
+            .iter()
+            .filter(|[a, _]| (a.y() - 0.5).abs() < 1e-9)
+            .collect();
+        // 每 2 个单位一段实线与一个点，共 5 段 5 点
+        assert_eq!(row.len(), 10);
+        assert_eq!(row.iter().filter(|[a, b]| a == b).count(), 5);
+    }
+}
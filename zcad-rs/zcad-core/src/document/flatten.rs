@@ -0,0 +1,1227 @@
+//! FLATTEN：把文档压平为只含直线、圆、圆弧、多段线与单行文字的简单文档，
+//! 供仅支持最小 DXF 子集的下游工具使用。导出器可选择保留填充与区域覆盖，
+//! 以便按面域绘制。
+
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+
+use glam::{DAffine2, DVec2, DVec3};
+
+use super::{
+    BlockReference, Document, Entity, EntityId, Hatch, HatchEdge, HatchLoop, HatchPatternLine,
+    LeaderLine, MLeaderContent, MTEXT_LINE_SPACING, PolylineVertex, RasterImageClip,
+    TEXT_WIDTH_FACTOR, Text, ThreeDFace, Wipeout,
+};
+use crate::geometry::{Point2, Vector2};
+use crate::tessellate::{self, TessellateOptions};
+
+/// 标注、多重引线缺少字高时使用的默认字高。
+const DEFAULT_TEXT_HEIGHT: f64 = 2.5;
+
+/// 消隐时判定遮挡的最小高差，避免共面或共边的 3D 面互相遮挡。
+const DEPTH_TOLERANCE: f64 = 1e-9;
+
+/// 3D 面投影到 XY 平面后的输出形式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FaceOutput {
+    /// 每条可见边输出为一条直线。
+    #[default]
+    Edges,
+    /// 每个面输出为一条闭合多段线，忽略隐蔽边标记并合并重合顶点。
+    Polyline,
+    /// 俯视消隐：可见边中被上方（Z 更大）其他 3D 面遮挡的部分被剔除。
+    HiddenLine,
+}
+
+/// FLATTEN 选项。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlattenOptions {
+    /// 圆、圆弧、椭圆、凸度段、样条与填充边界的离散精度。
+    pub tessellation: TessellateOptions,
+    /// 以文字包围框轮廓代替文字对象（尚无字体轮廓时的近似）。
+    pub outline_text: bool,
+    /// 块嵌套展开的最大深度，防止循环引用。
+    pub max_block_depth: usize,
+    /// 保留填充与区域覆盖：填充边界离散为直线边，图案线随块变换，
+    /// 区域覆盖变换到世界坐标，供需要绘制面域的导出器使用。
+    pub keep_fills: bool,
+    /// 3D 面的输出形式。
+    pub faces: FaceOutput,
+}
+
+impl Default for FlattenOptions {
+    fn default() -> Self {
+        Self {
+            tessellation: TessellateOptions::default(),
+            outline_text: false,
+            max_block_depth: 16,
+            keep_fills: false,
+            faces: FaceOutput::Edges,
+        }
+    }
+}
+
+impl Document {
+    /// 生成压平后的新文档：块参照被炸开，样条与椭圆离散为多段线，
+    /// 3D 面投影到 XY 平面，填充仅保留边界。图层表与实体特性原样复制，
+    /// 对象按源文档的绘制顺序排列。模型中只有 3D 面带 Z 坐标，其余实体读入时已位于 XY 平面，
+    /// 因此压平结果是纯二维图形。
+    pub fn flatten(&self, options: &FlattenOptions) -> Document {
+        self.flatten_with_sources(options).0
+    }
+
+    /// 同 [`Document::flatten`]，另返回每个压平对象对应的源实体标识，
+    /// 供导出器为拆分出的图元附加源实体（如块参照属性）的信息。
+    pub fn flatten_with_sources(
+        &self,
+        options: &FlattenOptions,
+    ) -> (Document, HashMap<EntityId, EntityId>) {
+        let mut flat = Document::new();
+        flat.layers = self.layers.clone();
+        if options.keep_fills {
+            flat.wipeout_variables = self.wipeout_variables.clone();
+        }
+        let mut flattener = Flattener {
+            source: self,
+            target: &mut flat,
+            options,
+            occluders: Vec::new(),
+        };
+        if options.faces == FaceOutput::HiddenLine {
+            let mut occluders = Vec::new();
+            for (_, entity) in self.entities() {
+                flattener.collect_triangles(entity, DAffine2::IDENTITY, 0, &mut occluders);
+            }
+            flattener.occluders = occluders;
+        }
+        let mut inherited = Vec::new();
+        let mut sources = HashMap::new();
+        for (id, entity) in self.entities_in_draw_order() {
+            let first = flattener.target.entities.len();
+            flattener.entity(entity, DAffine2::IDENTITY, 0);
+            sources.extend(
+                flattener.target.entities[first..]
+                    .iter()
+                    .map(|(new_id, _)| (*new_id, *id)),
+            );
+            if let Some(properties) = self.entity_properties.get(id) {
+                inherited.extend(
+                    flattener.target.entities[first..]
+                        .iter()
+                        .map(|(new_id, _)| (*new_id, properties.clone())),
+                );
+            }
+        }
+        // 拆分出的对象沿用源实体的特性覆盖
+        flat.entity_properties.extend(inherited);
+        // 压平结果按源文档的绘制顺序排列；炸开的对象按类型重新分层会打乱这一顺序时才显式记录
+        let emitted: Vec<EntityId> = flat.entities().map(|(id, _)| *id).collect();
+        let reordered = flat
+            .entities_in_draw_order()
+            .iter()
+            .map(|(id, _)| *id)
+            .ne(emitted.iter().copied());
+        if reordered {
+            flat.set_draw_sequence(&emitted);
+        }
+        (flat, sources)
+    }
+}
+
+struct Flattener<'a> {
+    source: &'a Document,
+    target: &'a mut Document,
+    options: &'a FlattenOptions,
+    /// 消隐模式下全部 3D 面（含块内）在世界坐标中的三角形。
+    occluders: Vec<[DVec3; 3]>,
+}
+
+impl Flattener<'_> {
+    fn entity(&mut self, entity: &Entity, transform: DAffine2, depth: usize) {
+        let layer = entity.layer_name();
+        match entity {
+            Entity::Line(line) => {
+                self.target.add_line(
+                    apply(transform, line.start),
+                    apply(transform, line.end),
+                    layer,
+                );
+            }
+            Entity::Circle(circle) => match similarity_scale(transform) {
+                Some(scale) => {
+                    self.target.add_circle(
+                        apply(transform, circle.center),
+                        circle.radius * scale,
+                        layer,
+                    );
+                }
+                None => {
+                    let points = tessellate::arc(
+                        circle.center,
+                        circle.radius,
+                        0.0,
+                        TAU,
+                        &self.options.tessellation,
+                    );
+                    self.polyline(transform, points, true, layer);
+                }
+            },
+            Entity::Arc(arc) => match similarity_scale(transform) {
+                Some(scale) => {
+                    let rotation = rotation_of(transform);
+                    self.target.add_arc(
+                        apply(transform, arc.center),
+                        arc.radius * scale,
+                        arc.start_angle + rotation,
+                        arc.end_angle + rotation,
+                        layer,
+                    );
+                }
+                None => {
+                    let sweep = ccw_sweep(arc.start_angle, arc.end_angle);
+                    let points = tessellate::arc(
+                        arc.center,
+                        arc.radius,
+                        arc.start_angle,
+                        sweep,
+                        &self.options.tessellation,
+                    );
+                    self.polyline(transform, points, false, layer);
+                }
+            },
+            Entity::Ellipse(ellipse) => {
+                let sweep = ccw_sweep(ellipse.start_parameter, ellipse.end_parameter);
+                let points = tessellate::ellipse(
+                    ellipse.center,
+                    ellipse.major_axis,
+                    ellipse.ratio,
+                    ellipse.start_parameter,
+                    sweep,
+                    &self.options.tessellation,
+                );
+                let closed = (sweep - TAU).abs() <= 1e-9;
+                self.polyline(transform, points, closed, layer);
+            }
+            Entity::Polyline(polyline) => {
+                if similarity_scale(transform).is_some() {
+                    let vertices = polyline
+                        .vertices
+                        .iter()
+                        .map(|vertex| {
+                            PolylineVertex::with_bulge(
+                                apply(transform, vertex.position),
+                                vertex.bulge,
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    self.target
+                        .add_polyline_with_vertices(vertices, polyline.is_closed, layer);
+                } else {
+                    let points = tessellate::polyline(
+                        &polyline.vertices,
+                        polyline.is_closed,
+                        &self.options.tessellation,
+                    );
+                    self.polyline(transform, points, false, layer);
+                }
+            }
+            Entity::Spline(spline) => {
+                let points = tessellate::spline(spline, &self.options.tessellation);
+                self.polyline(transform, points, spline.is_closed, layer);
+            }
+            Entity::Text(text) => {
+                let width = text_width(&text.content, text.height);
+                let frame = DAffine2::from_scale_angle_translation(
+                    text.mirror(),
+                    text.effective_rotation(),
+                    text.baseline_start(width).as_vec2(),
+                );
+                self.text_in_frame(
+                    transform,
+                    frame,
+                    &text.content,
+                    text.height,
+                    text.is_upside_down && !text.is_backward,
+                    layer,
+                );
+            }
+            Entity::MText(mtext) => {
+                let rotation = mtext.direction.y().atan2(mtext.direction.x());
+                let step =
+                    DVec2::new(rotation.sin(), -rotation.cos()) * mtext.height * MTEXT_LINE_SPACING;
+                for (index, line) in mtext.content.split("\\P").enumerate() {
+                    let insert = mtext.insert.as_vec2() + step * index as f64;
+                    self.text(
+                        transform,
+                        Point2::from_vec(insert),
+                        line,
+                        mtext.height,
+                        rotation,
+                        layer,
+                    );
+                }
+            }
+            Entity::BlockReference(reference) => {
+                self.block_reference(reference, transform, depth);
+            }
+            Entity::Hatch(hatch) if self.options.keep_fills => {
+                self.hatch(hatch, transform, layer);
+            }
+            Entity::Hatch(hatch) => {
+                for loop_path in &hatch.loops {
+                    let points = self.sample_hatch_edges(&loop_path.edges);
+                    self.polyline(transform, points, true, layer);
+                }
+            }
+            Entity::Dimension(dimension) => {
+                if let Some(block) = self.source.dimension_block(dimension) {
+                    if depth < self.options.max_block_depth {
+                        let block_transform =
+                            transform * DAffine2::from_translation(-block.base_point.as_vec2());
+                        for child in &block.entities {
+                            self.entity(child, block_transform, depth + 1);
+                        }
+                    }
+                    return;
+                }
+                for (start, end) in [
+                    (
+                        dimension.extension_line_origin,
+                        dimension.extension_line_end,
+                    ),
+                    (
+                        Some(dimension.definition_point),
+                        dimension.dimension_line_point,
+                    ),
+                    (Some(dimension.definition_point), dimension.secondary_point),
+                    (
+                        dimension.center_point.or(Some(dimension.definition_point)),
+                        dimension.jog_point,
+                    ),
+                    (dimension.jog_point, dimension.extension_line_end),
+                ] {
+                    if let (Some(start), Some(end)) = (start, end) {
+                        self.target
+                            .add_line(apply(transform, start), apply(transform, end), layer);
+                    }
+                }
+                if let Some(label) = self.source.dimension_text(dimension) {
+                    self.text(
+                        transform,
+                        dimension.text_midpoint,
+                        &label,
+                        DEFAULT_TEXT_HEIGHT,
+                        dimension.text_rotation.unwrap_or(dimension.rotation),
+                        layer,
+                    );
+                }
+            }
+            Entity::Leader(leader) => {
+                self.polyline(transform, leader.vertices.clone(), false, layer);
+            }
+            Entity::MLeader(mleader) => {
+                for LeaderLine { vertices } in &mleader.leader_lines {
+                    self.polyline(transform, vertices.clone(), false, layer);
+                }
+                match &mleader.content {
+                    MLeaderContent::MText { text, location } => {
+                        let height = mleader.text_height.unwrap_or(DEFAULT_TEXT_HEIGHT);
+                        self.text(transform, *location, text, height, 0.0, layer);
+                    }
+                    MLeaderContent::Block { block } => {
+                        let name = block.block_name.clone().or_else(|| {
+                            block
+                                .block_handle
+                                .as_deref()
+                                .and_then(|handle| self.source.block_name_by_handle(handle))
+                                .map(str::to_string)
+                        });
+                        if let Some(name) = name {
+                            let reference = BlockReference {
+                                name,
+                                insert: block.location,
+                                scale: block.scale,
+                                rotation: block.rotation,
+                                attributes: Vec::new(),
+                                layer: layer.to_string(),
+                            };
+                            self.block_reference(&reference, transform, depth);
+                        }
+                    }
+                    MLeaderContent::None => {}
+                }
+            }
+            Entity::RasterImage(image) => {
+                let points = frame_points(
+                    image.insert,
+                    image.u_vector,
+                    image.v_vector,
+                    image.image_size,
+                    image.clip.as_ref(),
+                );
+                self.polyline(transform, points, true, layer);
+            }
+            Entity::Wipeout(wipeout) if self.options.keep_fills => {
+                self.target.add_entity(Entity::Wipeout(Wipeout {
+                    layer: layer.to_string(),
+                    insert: apply(transform, wipeout.insert),
+                    u_vector: apply_vector(transform, wipeout.u_vector),
+                    v_vector: apply_vector(transform, wipeout.v_vector),
+                    ..wipeout.clone()
+                }));
+            }
+            // WIPEOUTFRAME 关闭时边框不可见，压平后不保留
+            Entity::Wipeout(_) if !self.source.wipeout_frame_visible() => {}
+            Entity::Wipeout(wipeout) => {
+                let points = frame_points(
+                    wipeout.insert,
+                    wipeout.u_vector,
+                    wipeout.v_vector,
+                    wipeout.image_size,
+                    wipeout.clip.as_ref(),
+                );
+                self.polyline(transform, points, true, layer);
+            }
+            Entity::Face3D(face) => {
+                self.face(face, transform, layer);
+            }
+            Entity::Shape(shape) => {
+                // SHX 图形尚未解析，以外框近似
+                self.polyline(transform, shape.outline().to_vec(), true, layer);
+            }
+            Entity::Underlay(underlay) => {
+                // 底图内容不做栅格化，仅保留裁剪边界
+                let outline = underlay.clip_outline();
+                if !outline.is_empty() {
+                    self.polyline(transform, outline, true, layer);
+                }
+            }
+            Entity::RevisionCloud(cloud) => {
+                self.entity(&Entity::Polyline(cloud.to_polyline()), transform, depth);
+            }
+        }
+    }
+
+    fn block_reference(&mut self, reference: &BlockReference, transform: DAffine2, depth: usize) {
+        let Some(block) = self.source.block(&reference.name) else {
+            return;
+        };
+        if depth >= self.options.max_block_depth {
+            return;
+        }
+        let insert = transform
+            * DAffine2::from_scale_angle_translation(
+                reference.scale.as_vec2(),
+                reference.rotation,
+                reference.insert.as_vec2(),
+            );
+        let block_transform = insert * DAffine2::from_translation(-block.base_point.as_vec2());
+        for child in &block.entities {
+            let mut child = child.clone();
+            // 块内 0 层实体继承参照所在图层
+            if child.layer_name() == "0" {
+                child.set_layer_name(reference.layer.clone());
+            }
+            self.entity(&child, block_transform, depth + 1);
+        }
+        // 属性已是世界坐标，只需叠加外层变换
+        for attribute in reference
+            .attributes
+            .iter()
+            .filter(|attr| !attr.is_invisible)
+        {
+            self.text(
+                transform,
+                attribute.insert,
+                &attribute.text,
+                attribute.height,
+                attribute.rotation,
+                &attribute.layer,
+            );
+        }
+    }
+
+    fn face(&mut self, face: &ThreeDFace, transform: DAffine2, layer: &str) {
+        let vertices = face
+            .vertices
+            .map(|vertex| lift(transform, vertex.as_vec3()));
+        if self.options.faces == FaceOutput::Polyline {
+            let mut points: Vec<Point2> = Vec::new();
+            for vertex in vertices {
+                let point = Point2::from_vec(vertex.truncate());
+                if points.last().is_none_or(|last| !points_close(*last, point)) {
+                    points.push(point);
+                }
+            }
+            if points.len() > 2 && points_close(points[0], points[points.len() - 1]) {
+                points.pop();
+            }
+            let closed = points.len() > 2;
+            self.polyline(DAffine2::IDENTITY, points, closed, layer);
+            return;
+        }
+
+        let own = face_triangles(&vertices);
+        for (index, start) in vertices.iter().enumerate() {
+            let end = vertices[(index + 1) % vertices.len()];
+            if face.invisible_edges[index]
+                || points_close(
+                    Point2::from_vec(start.truncate()),
+                    Point2::from_vec(end.truncate()),
+                )
+            {
+                continue;
+            }
+            if self.options.faces == FaceOutput::Edges {
+                self.target.add_line(
+                    Point2::from_vec(start.truncate()),
+                    Point2::from_vec(end.truncate()),
+                    layer,
+                );
+                continue;
+            }
+            for (from, to) in visible_spans(*start, end, &self.occluders, &own) {
+                self.target.add_line(
+                    Point2::from_vec(start.lerp(end, from).truncate()),
+                    Point2::from_vec(start.lerp(end, to).truncate()),
+                    layer,
+                );
+            }
+        }
+    }
+
+    /// 收集实体（含块参照与标注块内容）中 3D 面的世界坐标三角形，遍历规则与压平一致。
+    fn collect_triangles(
+        &self,
+        entity: &Entity,
+        transform: DAffine2,
+        depth: usize,
+        out: &mut Vec<[DVec3; 3]>,
+    ) {
+        let (block, block_transform) = match entity {
+            Entity::Face3D(face) => {
+                let vertices = face
+                    .vertices
+                    .map(|vertex| lift(transform, vertex.as_vec3()));
+                out.extend(face_triangles(&vertices));
+                return;
+            }
+            Entity::BlockReference(reference) => {
+                let Some(block) = self.source.block(&reference.name) else {
+                    return;
+                };
+                let insert = transform
+                    * DAffine2::from_scale_angle_translation(
+                        reference.scale.as_vec2(),
+                        reference.rotation,
+                        reference.insert.as_vec2(),
+                    );
+                (
+                    block,
+                    insert * DAffine2::from_translation(-block.base_point.as_vec2()),
+                )
+            }
+            Entity::Dimension(dimension) => {
+                let Some(block) = self.source.dimension_block(dimension) else {
+                    return;
+                };
+                (
+                    block,
+                    transform * DAffine2::from_translation(-block.base_point.as_vec2()),
+                )
+            }
+            _ => return,
+        };
+        if depth >= self.options.max_block_depth {
+            return;
+        }
+        for child in &block.entities {
+            self.collect_triangles(child, block_transform, depth + 1, out);
+        }
+    }
+
+    fn text(
+        &mut self,
+        transform: DAffine2,
+        insert: Point2,
+        content: &str,
+        height: f64,
+        rotation: f64,
+        layer: &str,
+    ) {
+        let frame = DAffine2::from_angle_translation(rotation, insert.as_vec2());
+        self.text_in_frame(transform, frame, content, height, false, layer);
+    }
+
+    /// 在字形坐标系 `frame`（原点为左侧基线起点）中输出文字。镜像的块参照中，
+    /// `$MIRRTEXT` 为 0 时绕文字中线翻转回可读方向；仍为镜像的结果以反向文字输出，
+    /// `prefer_upside_down` 时改用等价的倒置文字表示。
+    fn text_in_frame(
+        &mut self,
+        transform: DAffine2,
+        frame: DAffine2,
+        content: &str,
+        height: f64,
+        prefer_upside_down: bool,
+        layer: &str,
+    ) {
+        if content.is_empty() {
+            return;
+        }
+        let width = text_width(content, height);
+        let mut frame = frame;
+        if transform.matrix2.determinant() < 0.0 && !self.source.mirror_text() {
+            frame = frame
+                * DAffine2::from_translation(DVec2::new(width, 0.0))
+                * DAffine2::from_scale(DVec2::new(-1.0, 1.0));
+        }
+        if self.options.outline_text {
+            let corners = [
+                DVec2::ZERO,
+                DVec2::new(width, 0.0),
+                DVec2::new(width, height),
+                DVec2::new(0.0, height),
+            ]
+            .map(|corner| Point2::from_vec(frame.transform_point2(corner)));
+            self.polyline(transform, corners.to_vec(), true, layer);
+            return;
+        }
+        let placed = transform * frame;
+        let x_axis = placed.matrix2.x_axis;
+        let is_mirrored = placed.matrix2.determinant() < 0.0;
+        let is_upside_down = is_mirrored && prefer_upside_down;
+        let is_backward = is_mirrored && !prefer_upside_down;
+        let direction = if is_backward { -x_axis } else { x_axis };
+        self.target.add_justified_text(Text {
+            insert: Point2::from_vec(placed.translation),
+            content: content.to_string(),
+            height: height * x_axis.length(),
+            rotation: direction.to_angle(),
+            layer: layer.to_string(),
+            horizontal_align: 0,
+            vertical_align: 0,
+            alignment: None,
+            is_backward,
+            is_upside_down,
+        });
+    }
+
+    /// 保留填充：边界离散为首尾相接的直线边，图案线与种子点随变换映射。
+    fn hatch(&mut self, hatch: &Hatch, transform: DAffine2, layer: &str) {
+        let loops = hatch
+            .loops
+            .iter()
+            .map(|loop_path| self.sample_hatch_edges(&loop_path.edges))
+            .filter(|points| points.len() >= 3)
+            .map(|points| {
+                let points = points
+                    .into_iter()
+                    .map(|point| apply(transform, point))
+                    .collect::<Vec<_>>();
+                let edges = points
+                    .iter()
+                    .zip(points.iter().cycle().skip(1))
+                    .map(|(start, end)| HatchEdge::Line {
+                        start: *start,
+                        end: *end,
+                    })
+                    .collect();
+                HatchLoop {
+                    is_polyline: false,
+                    is_closed: true,
+                    edges,
+                    boundary_handles: Vec::new(),
+                    boundary_entities: Vec::new(),
+                }
+            })
+            .collect::<Vec<_>>();
+        if loops.is_empty() {
+            return;
+        }
+        let pattern_lines = hatch
+            .pattern_lines
+            .iter()
+            .map(|line| {
+                let direction = transform.transform_vector2(DVec2::from_angle(line.angle));
+                HatchPatternLine {
+                    angle: direction.to_angle(),
+                    base_point: apply(transform, line.base_point),
+                    offset: apply_vector(transform, line.offset),
+                    dash_lengths: line
+                        .dash_lengths
+                        .iter()
+                        .map(|length| length * direction.length())
+                        .collect(),
+                }
+            })
+            .collect();
+        let gradient = hatch.gradient.clone().map(|mut gradient| {
+            gradient.angle = transform
+                .transform_vector2(DVec2::from_angle(gradient.angle))
+                .to_angle();
+            gradient
+        });
+        self.target.add_hatch(
+            hatch.pattern_name.clone(),
+            hatch.is_solid,
+            loops,
+            gradient,
+            pattern_lines,
+            hatch.style,
+            false,
+            hatch
+                .seed_points
+                .iter()
+                .map(|point| apply(transform, *point))
+                .collect(),
+            layer,
+        );
+    }
+
+    fn polyline(&mut self, transform: DAffine2, points: Vec<Point2>, closed: bool, layer: &str) {
+        if points.len() < 2 {
+            return;
+        }
+        let vertices = points
+            .into_iter()
+            .map(|point| PolylineVertex::new(apply(transform, point)))
+            .collect::<Vec<_>>();
+        self.target
+            .add_polyline_with_vertices(vertices, closed, layer);
+    }
+
+    fn sample_hatch_edges(&self, edges: &[HatchEdge]) -> Vec<Point2> {
+        let mut points = Vec::new();
+        for edge in edges {
+            let edge_points = tessellate::hatch_edge(edge, &self.options.tessellation);
+            append_points(&mut points, edge_points);
+        }
+        if points.len() > 2
+            && let (Some(first), Some(last)) = (points.first(), points.last())
+            && points_close(*first, *last)
+        {
+            points.pop();
+        }
+        points
+    }
+}
+
+/// 按字宽系数估算单行文字宽度。
+fn text_width(content: &str, height: f64) -> f64 {
+    height * TEXT_WIDTH_FACTOR * content.chars().count() as f64
+}
+
+fn apply(transform: DAffine2, point: Point2) -> Point2 {
+    Point2::from_vec(transform.transform_point2(point.as_vec2()))
+}
+
+fn apply_vector(transform: DAffine2, vector: Vector2) -> Vector2 {
+    let vector = transform.transform_vector2(vector.as_vec2());
+    Vector2::new(vector.x, vector.y)
+}
+
+/// 变换为无镜像的相似变换时返回其缩放系数，圆、圆弧与凸度可保持原样。
+fn similarity_scale(transform: DAffine2) -> Option<f64> {
+    let x_axis = transform.matrix2.x_axis;
+    let y_axis = transform.matrix2.y_axis;
+    let scale = x_axis.length();
+    let is_similar = (scale - y_axis.length()).abs() <= 1e-9 * scale.max(1.0)
+        && x_axis.dot(y_axis).abs() <= 1e-9 * scale.max(1.0)
+        && transform.matrix2.determinant() > 0.0;
+    is_similar.then_some(scale)
+}
+
+fn rotation_of(transform: DAffine2) -> f64 {
+    transform.matrix2.x_axis.to_angle()
+}
+
+pub(crate) fn ccw_sweep(start: f64, end: f64) -> f64 {
+    let sweep = (end - start).rem_euclid(TAU);
+    if sweep <= 1e-12 { TAU } else { sweep }
+}
+
+pub(crate) fn edge_sweep(start: f64, end: f64, is_counter_clockwise: bool) -> (f64, f64) {
+    if is_counter_clockwise {
+        (start, ccw_sweep(start, end))
+    } else {
+        // 顺时针边的角度按 DXF 约定取反
+        (-start, -ccw_sweep(start, end))
+    }
+}
+
+fn frame_points(
+    insert: Point2,
+    u_vector: Vector2,
+    v_vector: Vector2,
+    image_size: Vector2,
+    clip: Option<&RasterImageClip>,
+) -> Vec<Point2> {
+    let local = match clip {
+        Some(RasterImageClip::Rectangle { min, max, .. }) => vec![
+            *min,
+            Point2::new(max.x(), min.y()),
+            *max,
+            Point2::new(min.x(), max.y()),
+        ],
+        Some(RasterImageClip::Polygon { vertices, .. }) => vertices.clone(),
+        None => vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(image_size.x(), 0.0),
+            Point2::new(image_size.x(), image_size.y()),
+            Point2::new(0.0, image_size.y()),
+        ],
+    };
+    let origin = insert.as_vec2();
+    let u = u_vector.as_vec2();
+    let v = v_vector.as_vec2();
+    local
+        .into_iter()
+        .map(|point| Point2::from_vec(origin + u * point.x() + v * point.y()))
+        .collect()
+}
+
+fn append_points(points: &mut Vec<Point2>, mut next: Vec<Point2>) {
+    if let (Some(last), Some(first)) = (points.last(), next.first())
+        && points_close(*last, *first)
+    {
+        next.remove(0);
+    }
+    points.extend(next);
+}
+
+fn points_close(a: Point2, b: Point2) -> bool {
+    (a.as_vec2() - b.as_vec2()).length_squared() <= 1e-12
+}
+
+/// 对三维点的 XY 分量施加平面变换，Z 保持不变。
+fn lift(transform: DAffine2, point: DVec3) -> DVec3 {
+    transform.transform_point2(point.truncate()).extend(point.z)
+}
+
+/// 把 3D 面拆为两个三角形；第四点与第三点重合时只有一个。
+fn face_triangles(vertices: &[DVec3; 4]) -> Vec<[DVec3; 3]> {
+    let mut triangles = vec![[vertices[0], vertices[1], vertices[2]]];
+    if vertices[3] != vertices[2] {
+        triangles.push([vertices[0], vertices[2], vertices[3]]);
+    }
+    triangles
+}
+
+/// 返回线段 `start`→`end` 未被遮挡部分的参数区间，`own` 为线段所属面的三角形。
+fn visible_spans(
+    start: DVec3,
+    end: DVec3,
+    occluders: &[[DVec3; 3]],
+    own: &[[DVec3; 3]],
+) -> Vec<(f64, f64)> {
+    let mut hidden = Vec::new();
+    for triangle in occluders.iter().filter(|triangle| !own.contains(triangle)) {
+        hidden_spans(start, end, triangle, &mut hidden);
+    }
+    hidden.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut visible = Vec::new();
+    let mut cursor = 0.0_f64;
+    for (from, to) in hidden {
+        if from > cursor + 1e-9 {
+            visible.push((cursor, from));
+        }
+        cursor = cursor.max(to);
+    }
+    if cursor < 1.0 - 1e-9 {
+        visible.push((cursor, 1.0));
+    }
+    visible
+}
+
+/// 俯视时线段被三角形遮挡的参数区间：线段在三角形投影内且位于其所在平面下方。
+fn hidden_spans(start: DVec3, end: DVec3, triangle: &[DVec3; 3], out: &mut Vec<(f64, f64)>) {
+    let [a, b, c] = triangle.map(|vertex| vertex.truncate());
+    let area = (b - a).perp_dot(c - a);
+    // 竖直的面投影为线段，不遮挡任何东西
+    if area.abs() <= 1e-12 {
+        return;
+    }
+    let barycentric = |point: DVec2| {
+        let u = (point - a).perp_dot(c - a) / area;
+        let v = (b - a).perp_dot(point - a) / area;
+        DVec3::new(1.0 - u - v, u, v)
+    };
+    let depth = |point: DVec3| {
+        let weights = barycentric(point.truncate());
+        weights.dot(DVec3::new(triangle[0].z, triangle[1].z, triangle[2].z)) - point.z
+    };
+
+    let origin = start.truncate();
+    let direction = end.truncate() - origin;
+    let mut cuts = vec![0.0, 1.0];
+    for (from, to) in [(a, b), (b, c), (c, a)] {
+        let edge = to - from;
+        let denominator = direction.perp_dot(edge);
+        if denominator.abs() <= 1e-12 {
+            continue;
+        }
+        let t = (from - origin).perp_dot(edge) / denominator;
+        let s = (from - origin).perp_dot(direction) / denominator;
+        if t > 0.0 && t < 1.0 && (-1e-12..=1.0 + 1e-12).contains(&s) {
+            cuts.push(t);
+        }
+    }
+    // 线段穿过面所在平面的位置
+    let (depth_start, depth_end) = (depth(start), depth(end));
+    if depth_start * depth_end < 0.0 {
+        cuts.push(depth_start / (depth_start - depth_end));
+    }
+    cuts.sort_by(f64::total_cmp);
+
+    for pair in cuts.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        if to - from <= 1e-12 {
+            continue;
+        }
+        let middle = start.lerp(end, (from + to) * 0.5);
+        let inside = barycentric(middle.truncate()).min_element() > 1e-9;
+        if inside && depth(middle) > DEPTH_TOLERANCE {
+            out.push((from, to));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{BlockDefinition, Layer};
+    use crate::geometry::Point3;
+
+    fn entity_kinds(document: &Document) -> Vec<&'static str> {
+        document
+            .entities()
+            .map(|(_, entity)| match entity {
+                Entity::Line(_) => "line",
+                Entity::Circle(_) => "circle",
+                Entity::Arc(_) => "arc",
+                Entity::Polyline(_) => "polyline",
+                Entity::Text(_) => "text",
+                _ => "other",
+            })
+            .collect()
+    }
+
+    #[test]
+    fn flatten_explodes_blocks_with_transform() {
+        let mut document = Document::new();
+        document.add_block_definition(BlockDefinition {
+            name: "MARK".to_string(),
+            base_point: Point2::new(1.0, 0.0),
+            entities: vec![
+                Entity::Line(crate::document::Line {
+                    start: Point2::new(1.0, 0.0),
+                    end: Point2::new(2.0, 0.0),
+                    layer: "0".to_string(),
+                }),
+                Entity::Circle(crate::document::Circle {
+                    center: Point2::new(1.0, 0.0),
+                    radius: 1.0,
+                    layer: "MARKS".to_string(),
+                }),
+            ],
+            attributes: Vec::new(),
+        });
+        let rotated = document.add_block_reference(
+            "MARK",
+            Point2::new(10.0, 10.0),
+            Vector2::new(2.0, 2.0),
+            std::f64::consts::FRAC_PI_2,
+            Vec::new(),
+            "SYMBOLS",
+        );
+        let stretched = document.add_block_reference(
+            "MARK",
+            Point2::new(0.0, 0.0),
+            Vector2::new(2.0, 1.0),
+            0.0,
+            Vec::new(),
+            "SYMBOLS",
+        );
+
+        let flat = document.flatten(&FlattenOptions::default());
+        assert_eq!(
+            entity_kinds(&flat),
+            vec!["line", "circle", "line", "polyline"]
+        );
+        assert!(flat.blocks().next().is_none());
+        let (_, Entity::Line(line)) = flat.entities().next().unwrap() else {
+            panic!("期望第一个实体为直线");
+        };
+        assert_eq!(line.layer, "SYMBOLS");
+        assert!((line.start.x() - 10.0).abs() < 1e-9);
+        assert!((line.end.x() - 10.0).abs() < 1e-9);
+        assert!((line.end.y() - 12.0).abs() < 1e-9);
+        let (_, Entity::Circle(circle)) = flat.entities().nth(1).unwrap() else {
+            panic!("期望第二个实体为圆");
+        };
+        assert_eq!(circle.layer, "MARKS");
+        assert!((circle.radius - 2.0).abs() < 1e-9);
+        assert!(flat.layers().any(|layer: &Layer| layer.name == "SYMBOLS"));
+
+        let (flat, sources) = document.flatten_with_sources(&FlattenOptions::default());
+        let origins = flat
+            .entities()
+            .map(|(id, _)| sources[id])
+            .collect::<Vec<_>>();
+        assert_eq!(origins, vec![rotated, rotated, stretched, stretched]);
+    }
+
+    #[test]
+    fn flatten_samples_splines_and_outlines_text() {
+        let mut document = Document::new();
+        document.add_spline(
+            2,
+            false,
+            false,
+            false,
+            vec![
+                Point2::new(0.0, 0.0),
+                Point2::new(1.0, 2.0),
+                Point2::new(2.0, 0.0),
+            ],
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+            None,
+            None,
+            "CURVES",
+        );
+        document.add_text(Point2::new(0.0, 0.0), "AB", 1.0, 0.0, "NOTES");
+
+        let options = FlattenOptions {
+            tessellation: TessellateOptions::per_turn(8),
+            outline_text: true,
+            ..FlattenOptions::default()
+        };
+        let flat = document.flatten(&options);
+        assert_eq!(entity_kinds(&flat), vec!["polyline", "polyline"]);
+
+        let (_, Entity::Polyline(curve)) = flat.entities().next().unwrap() else {
+            panic!("期望样条被离散为多段线");
+        };
+        // 按切向夹角自适应二分，顶点关于曲线中点对称
+        let count = curve.vertices.len();
+        assert!(count > 3 && count % 2 == 1, "{count}");
+        let middle = curve.vertices[count / 2].position;
+        assert!((middle.x() - 1.0).abs() < 1e-9);
+        assert!((middle.y() - 1.0).abs() < 1e-9);
+
+        let (_, Entity::Polyline(outline)) = flat.entities().nth(1).unwrap() else {
+            panic!("期望文字被替换为轮廓");
+        };
+        assert!(outline.is_closed);
+        assert!((outline.vertices[2].position.x() - 1.2).abs() < 1e-9);
+    }
+
+    fn mirrored_text_document(mirror_text: bool) -> Document {
+        let mut document = Document::new();
+        document.set_mirror_text(mirror_text);
+        document.add_block_definition(BlockDefinition {
+            name: "LABEL".to_string(),
+            base_point: Point2::new(0.0, 0.0),
+            entities: vec![Entity::Text(Text {
+                insert: Point2::new(1.0, 0.0),
+                content: "AB".to_string(),
+                height: 1.0,
+                rotation: 0.0,
+                layer: "0".to_string(),
+                horizontal_align: 0,
+                vertical_align: 0,
+                alignment: None,
+                is_backward: false,
+                is_upside_down: false,
+            })],
+            attributes: Vec::new(),
+        });
+        document.add_block_reference(
+            "LABEL",
+            Point2::new(0.0, 0.0),
+            Vector2::new(-1.0, 1.0),
+            0.0,
+            Vec::new(),
+            "0",
+        );
+        document
+    }
+
+    fn flattened_text(document: &Document) -> Text {
+        let flat = document.flatten(&FlattenOptions::default());
+        let Some((_, Entity::Text(text))) = flat.entities().next() else {
+            panic!("期望展开出单行文字");
+        };
+        text.clone()
+    }
+
+    #[test]
+    fn flatten_keeps_mirrored_text_readable_without_mirrtext() {
+        // 文字估算宽度 1.2，镜像后占据 x ∈ [-2.2, -1]，保持可读时从左端起排
+        let text = flattened_text(&mirrored_text_document(false));
+        assert!(!text.is_backward && !text.is_upside_down);
+        assert!((text.insert.x() + 2.2).abs() < 1e-9, "{:?}", text.insert);
+        assert!(text.rotation.abs() < 1e-9);
+
+        let text = flattened_text(&mirrored_text_document(true));
+        assert!(text.is_backward, "$MIRRTEXT 为 1 时文字应随块镜像");
+        assert!((text.insert.x() + 1.0).abs() < 1e-9, "{:?}", text.insert);
+        assert!(text.rotation.abs() < 1e-9);
+    }
+
+    fn square_face(document: &mut Document, min: (f64, f64), max: (f64, f64), z: f64) {
+        document.add_face3d(
+            [
+                Point3::new(min.0, min.1, z),
+                Point3::new(max.0, min.1, z),
+                Point3::new(max.0, max.1, z),
+                Point3::new(min.0, max.1, z),
+            ],
+            [false; 4],
+            "FACES",
+        );
+    }
+
+    fn line_spans(document: &Document) -> Vec<[f64; 4]> {
+        document
+            .entities()
+            .map(|(_, entity)| {
+                let Entity::Line(line) = entity else {
+                    panic!("期望 3D 面的边输出为直线");
+                };
+                [line.start.x(), line.start.y(), line.end.x(), line.end.y()]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn flatten_removes_hidden_face_edges() {
+        let mut document = Document::new();
+        square_face(&mut document, (0.0, 0.0), (10.0, 10.0), 0.0);
+        square_face(&mut document, (2.0, -1.0), (4.0, 11.0), 5.0);
+
+        let flat = document.flatten(&FlattenOptions::default());
+        assert_eq!(line_spans(&flat).len(), 8);
+
+        let options = FlattenOptions {
+            faces: FaceOutput::HiddenLine,
+            ..FlattenOptions::default()
+        };
+        let spans = line_spans(&document.flatten(&options));
+        assert_eq!(spans.len(), 10);
+        // 底面的下边被上方的条形面遮住 x ∈ [2, 4]
+        let close = |span: [f64; 4], expected: [f64; 4]| {
+            span.iter()
+                .zip(expected)
+                .all(|(value, expected)| (value - expected).abs() < 1e-9)
+        };
+        assert!(close(spans[0], [0.0, 0.0, 2.0, 0.0]), "{:?}", spans[0]);
+        assert!(close(spans[1], [4.0, 0.0, 10.0, 0.0]), "{:?}", spans[1]);
+        assert!(close(spans[6], [2.0, -1.0, 4.0, -1.0]), "{:?}", spans[6]);
+    }
+
+    #[test]
+    fn flatten_collapses_faces_to_polylines() {
+        let mut document = Document::new();
+        let apex = Point3::new(0.0, 5.0, 3.0);
+        document.add_face3d(
+            [
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(10.0, 0.0, 1.0),
+                apex,
+                apex,
+            ],
+            [false, false, true, false],
+            "FACES",
+        );
+
+        let options = FlattenOptions {
+            faces: FaceOutput::Polyline,
+            ..FlattenOptions::default()
+        };
+        let flat = document.flatten(&options);
+        let Some((_, Entity::Polyline(outline))) = flat.entities().next() else {
+            panic!("期望 3D 面输出为多段线");
+        };
+        assert!(outline.is_closed);
+        assert_eq!(outline.vertices.len(), 3);
+        assert_eq!(outline.vertices[2].position, Point2::new(0.0, 5.0));
+    }
+
+    #[test]
+    fn flatten_keeps_fills_in_world_coordinates() {
+        let mut document = Document::new();
+        document.add_block_definition(BlockDefinition {
+            name: "PAD".to_string(),
+            base_point: Point2::new(0.0, 0.0),
+            entities: vec![Entity::Hatch(Hatch {
+                pattern_name: "ANSI31".to_string(),
+                is_solid: false,
+                loops: vec![HatchLoop {
+                    is_polyline: false,
+                    is_closed: true,
+                    edges: vec![HatchEdge::Arc {
+                        center: Point2::new(0.0, 0.0),
+                        radius: 1.0,
+                        start_angle: 0.0,
+                        end_angle: TAU,
+                        is_counter_clockwise: true,
+                    }],
+                    boundary_handles: vec!["2A".to_string()],
+                    boundary_entities: Vec::new(),
+                }],
+                gradient: None,
+                pattern_lines: vec![HatchPatternLine {
+                    angle: 0.0,
+                    base_point: Point2::new(0.0, 0.0),
+                    offset: Vector2::new(0.0, 0.5),
+                    dash_lengths: vec![1.0, -0.5],
+                }],
+                style: Default::default(),
+                is_associative: true,
+                seed_points: Vec::new(),
+                layer: "0".to_string(),
+            })],
+            attributes: Vec::new(),
+        });
+        document.add_block_reference(
+            "PAD",
+            Point2::new(10.0, 0.0),
+            Vector2::new(2.0, 2.0),
+            std::f64::consts::FRAC_PI_2,
+            Vec::new(),
+            "PADS",
+        );
+
+        let options = FlattenOptions {
+            tessellation: TessellateOptions::per_turn(8),
+            keep_fills: true,
+            ..FlattenOptions::default()
+        };
+        let flat = document.flatten(&options);
+        let Some((_, Entity::Hatch(hatch))) = flat.entities().next() else {
+            panic!("期望保留填充");
+        };
+        assert_eq!(hatch.layer, "PADS");
+        assert!(!hatch.is_associative);
+        assert_eq!(hatch.loops.len(), 1);
+        assert_eq!(hatch.loops[0].edges.len(), 8);
+        assert!(hatch.loops[0].boundary_handles.is_empty());
+        let HatchEdge::Line { start, .. } = &hatch.loops[0].edges[0] else {
+            panic!("期望边界离散为直线边");
+        };
+        assert!((start.x() - 10.0).abs() < 1e-9 && (start.y() - 2.0).abs() < 1e-9);
+
+        let line = &hatch.pattern_lines[0];
+        assert!((line.angle - std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+        assert!((line.base_point.x() - 10.0).abs() < 1e-9);
+        assert!((line.offset.x() + 1.0).abs() < 1e-9);
+        assert_eq!(line.dash_lengths, vec![2.0, -1.0]);
+
+        let outline = document.flatten(&FlattenOptions::default());
+        assert_eq!(entity_kinds(&outline), vec!["polyline"]);
+    }
+}
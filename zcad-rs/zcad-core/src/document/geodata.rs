@@ -0,0 +1,219 @@
+//! 地理位置数据（GEODATA 对象）：设计点与参考点的对应关系、单位比例、北向与坐标系定义。
+
+use std::f64::consts::FRAC_PI_2;
+
+use serde::{Deserialize, Serialize};
+
+use super::Document;
+use crate::geometry::{Point2, Point3, Vector2, Vector3};
+
+/// WGS84 椭球长半轴（米），地理坐标类型按局部切平面近似换算经纬度。
+const WGS84_SEMI_MAJOR_AXIS: f64 = 6_378_137.0;
+
+/// 参考点的坐标类型（DXF 组码 70）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeoCoordinateType {
+    #[default]
+    Unknown,
+    /// 局部网格：参考点为局部坐标。
+    LocalGrid,
+    /// 投影网格：参考点为投影坐标系中的东坐标/北坐标。
+    ProjectedGrid,
+    /// 地理坐标：参考点为经度/纬度（度）。
+    Geographic,
+}
+
+impl GeoCoordinateType {
+    pub fn from_code(code: i16) -> Self {
+        match code {
+            1 => GeoCoordinateType::LocalGrid,
+            2 => GeoCoordinateType::ProjectedGrid,
+            3 => GeoCoordinateType::Geographic,
+            _ => GeoCoordinateType::Unknown,
+        }
+    }
+
+    pub fn code(self) -> i16 {
+        match self {
+            GeoCoordinateType::Unknown => 0,
+            GeoCoordinateType::LocalGrid => 1,
+            GeoCoordinateType::ProjectedGrid => 2,
+            GeoCoordinateType::Geographic => 3,
+        }
+    }
+}
+
+/// 图纸的地理位置信息。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeoData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
+    /// 对象版本（组码 90），R2010 起为 2。
+    pub version: i32,
+    pub coordinate_type: GeoCoordinateType,
+    /// 图纸中的设计点（组码 10/20/30）。
+    pub design_point: Point3,
+    /// 设计点对应的参考点（组码 11/21/31），含义由 `coordinate_type` 决定。
+    pub reference_point: Point3,
+    /// 设计单位到米的水平比例（组码 40）。
+    pub horizontal_unit_scale: f64,
+    /// 水平单位（组码 91，INSUNITS 编码）。
+    pub horizontal_units: i32,
+    /// 设计单位到米的垂直比例（组码 41）。
+    pub vertical_unit_scale: f64,
+    pub vertical_units: i32,
+    /// 图纸中的北向（组码 12/22）。
+    pub north_direction: Vector2,
+    /// 上方向（组码 210/220/230）。
+    pub up_direction: Vector3,
+    /// 比例估算方法（组码 95）。
+    #[serde(default)]
+    pub scale_estimation_method: i32,
+    /// 用户指定的比例因子（组码 141）。
+    #[serde(default = "default_scale")]
+    pub user_scale_factor: f64,
+    /// 是否进行海平面校正（组码 294）。
+    #[serde(default)]
+    pub sea_level_correction: bool,
+    /// 海平面高程（组码 142）。
+    #[serde(default)]
+    pub sea_level_elevation: f64,
+    /// 坐标系定义字符串（组码 301/303，通常为 XML 或 WKT）。
+    #[serde(default)]
+    pub coordinate_system: String,
+}
+
+fn default_scale() -> f64 {
+    1.0
+}
+
+impl Default for GeoData {
+    fn default() -> Self {
+        Self {
+            handle: None,
+            version: 2,
+            coordinate_type: GeoCoordinateType::Unknown,
+            design_point: Point3::new(0.0, 0.0, 0.0),
+            reference_point: Point3::new(0.0, 0.0, 0.0),
+            horizontal_unit_scale: 1.0,
+            horizontal_units: 0,
+            vertical_unit_scale: 1.0,
+            vertical_units: 0,
+            north_direction: Vector2::new(0.0, 1.0),
+            up_direction: Vector3::new(0.0, 0.0, 1.0),
+            scale_estimation_method: 0,
+            user_scale_factor: 1.0,
+            sea_level_correction: false,
+            sea_level_elevation: 0.0,
+            coordinate_system: String::new(),
+        }
+    }
+}
+
+impl GeoData {
+    /// 图纸北向相对 +Y 轴的旋转角（弧度），设计坐标需旋转该角度才能对齐正北。
+    pub fn north_rotation(&self) -> f64 {
+        let north = self.north_direction;
+        if north.x() == 0.0 && north.y() == 0.0 {
+            return 0.0;
+        }
+        FRAC_PI_2 - north.y().atan2(north.x())
+    }
+
+    /// 设计点相对偏移换算为以米为单位、已对齐正北的（东，北）偏移。
+    fn metric_offset(&self, point: Point2) -> (f64, f64) {
+        let scale = self.horizontal_unit_scale * self.user_scale_factor;
+        let dx = (point.x() - self.design_point.x()) * scale;
+        let dy = (point.y() - self.design_point.y()) * scale;
+        let (sin, cos) = self.north_rotation().sin_cos();
+        (dx * cos - dy * sin, dx * sin + dy * cos)
+    }
+
+    /// 图纸坐标换算到地图坐标：局部/投影网格返回网格坐标，地理坐标返回（经度，纬度）。
+    /// 地理坐标按参考点处的局部切平面近似，适用于常见的场地尺度。
+    pub fn to_map(&self, point: Point2) -> Point2 {
+        let (east, north) = self.metric_offset(point);
+        match self.coordinate_type {
+            GeoCoordinateType::Geographic => {
+                let latitude = self.reference_point.y().to_radians();
+                let longitude = self.reference_point.x()
+                    + (east / (WGS84_SEMI_MAJOR_AXIS * latitude.cos())).to_degrees();
+                let latitude =
+                    self.reference_point.y() + (north / WGS84_SEMI_MAJOR_AXIS).to_degrees();
+                Point2::new(longitude, latitude)
+            }
+            GeoCoordinateType::Unknown
+            | GeoCoordinateType::LocalGrid
+            | GeoCoordinateType::ProjectedGrid => Point2::new(
+                self.reference_point.x() + east,
+                self.reference_point.y() + north,
+            ),
+        }
+    }
+
+    /// GeoJSON 坐标（WGS84 经度、纬度）；只有地理坐标类型可以不借助投影库直接换算。
+    pub fn to_geojson_position(&self, point: Point2) -> Option<[f64; 2]> {
+        match self.coordinate_type {
+            GeoCoordinateType::Geographic => {
+                let map = self.to_map(point);
+                Some([map.x(), map.y()])
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Document {
+    #[inline]
+    pub fn geodata(&self) -> Option<&GeoData> {
+        self.geodata.as_ref()
+    }
+
+    pub fn set_geodata(&mut self, geodata: GeoData) {
+        *self.geodata = Some(geodata);
+    }
+
+    pub fn clear_geodata(&mut self) {
+        *self.geodata = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn design_points_map_through_unit_scale_and_north_rotation() {
+        let geodata = GeoData {
+            coordinate_type: GeoCoordinateType::ProjectedGrid,
+            design_point: Point3::new(100.0, 100.0, 0.0),
+            reference_point: Point3::new(500_000.0, 4_000_000.0, 0.0),
+            horizontal_unit_scale: 0.001,
+            // 图纸 +X 指向正北
+            north_direction: Vector2::new(1.0, 0.0),
+            ..GeoData::default()
+        };
+        let map = geodata.to_map(Point2::new(1100.0, 100.0));
+        assert!(close(map.x(), 500_000.0) && close(map.y(), 4_000_001.0));
+        assert!(geodata.to_geojson_position(Point2::new(0.0, 0.0)).is_none());
+
+        let geographic = GeoData {
+            coordinate_type: GeoCoordinateType::Geographic,
+            reference_point: Point3::new(121.5, 31.2, 0.0),
+            ..GeoData::default()
+        };
+        let position = geographic
+            .to_geojson_position(Point2::new(0.0, 1000.0))
+            .expect("地理坐标可直接输出 GeoJSON");
+        assert!(close(position[0], 121.5));
+        assert!(close(
+            position[1] - 31.2,
+            (1000.0 / WGS84_SEMI_MAJOR_AXIS).to_degrees()
+        ));
+    }
+}
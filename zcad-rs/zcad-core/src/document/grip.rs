@@ -0,0 +1,476 @@
+//! 夹点：实体上可直接拖动的特征点（端点、中点、圆心、象限点、顶点与插入点）及拖动夹点的编辑语义。
+//!
+//! 直线拖动端点只移动该端点，拖动中点平移整条直线；圆拖动圆心平移，拖动象限点改变半径；圆弧拖动端点或
+//! 中点时过三个夹点重新作圆弧，拖动圆心平移；椭圆拖动轴端点改变该半轴，短轴拉长超过长轴时两轴互换。
+//! 多段线拖动顶点只移动该顶点，拖动直线段中点平移该段，拖动圆弧段中点改变凸度使圆弧经过目标点；
+//! 修订云线、引线、样条控制点与三维面顶点只移动该点；文字、块参照等以插入点定位的对象拖动插入点时整体平移。
+//! 填充、标注与多重引线没有夹点。
+
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use glam::DVec2;
+
+use crate::geometry::{Point2, Point3, Transform2, Vector2};
+
+use super::simplify::circumcenter;
+use super::{Ellipse, Entity, ccw_sweep, normalize_angle};
+
+/// 视为退化的长度。
+const EPSILON: f64 = 1e-9;
+
+/// 夹点类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GripKind {
+    Endpoint,
+    Midpoint,
+    Center,
+    /// 圆的象限点或椭圆的轴端点。
+    Quadrant,
+    Vertex,
+    Insertion,
+}
+
+/// 一个夹点；拖动时以它在 [`Entity::grips`] 中的序号指定。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Grip {
+    pub kind: GripKind,
+    pub point: Point2,
+}
+
+impl Grip {
+    fn new(kind: GripKind, point: Point2) -> Self {
+        Self { kind, point }
+    }
+}
+
+impl Entity {
+    /// 实体的夹点，顺序固定：直线为起点、中点、终点；圆为圆心与 0°、90°、180°、270° 象限点；
+    /// 圆弧为起点、中点、终点、圆心；椭圆为中心与长轴正向起逆时针的四个轴端点；
+    /// 多段线先列全部顶点，再列各段中点。
+    pub fn grips(&self) -> Vec<Grip> {
+        match self {
+            Entity::Line(line) => vec![
+                Grip::new(GripKind::Endpoint, line.start),
+                Grip::new(GripKind::Midpoint, midpoint(line.start, line.end)),
+                Grip::new(GripKind::Endpoint, line.end),
+            ],
+            Entity::Circle(circle) => {
+                let mut grips = vec![Grip::new(GripKind::Center, circle.center)];
+                grips.extend((0..4).map(|quarter| {
+                    let angle = quarter as f64 * FRAC_PI_2;
+                    Grip::new(
+                        GripKind::Quadrant,
+                        polar(circle.center, circle.radius, angle),
+                    )
+                }));
+                grips
+            }
+            Entity::Arc(arc) => {
+                let sweep = ccw_sweep(arc.start_angle, arc.end_angle);
+                vec![
+                    Grip::new(
+                        GripKind::Endpoint,
+                        polar(arc.center, arc.radius, arc.start_angle),
+                    ),
+                    Grip::new(
+                        GripKind::Midpoint,
+                        polar(arc.center, arc.radius, arc.start_angle + sweep / 2.0),
+                    ),
+                    Grip::new(
+                        GripKind::Endpoint,
+                        polar(arc.center, arc.radius, arc.end_angle),
+                    ),
+                    Grip::new(GripKind::Center, arc.center),
+                ]
+            }
+            Entity::Ellipse(ellipse) => {
+                let (major, minor) = axes(ellipse);
+                let center = ellipse.center.as_vec2();
+                let mut grips = vec![Grip::new(GripKind::Center, ellipse.center)];
+                grips.extend(
+                    [
+                        center + major,
+                        center + minor,
+                        center - major,
+                        center - minor,
+                    ]
+                    .map(|point| Grip::new(GripKind::Quadrant, Point2::from_vec(point))),
+                );
+                grips
+            }
+            Entity::Polyline(polyline) => {
+                let vertices = &polyline.vertices;
+                let mut grips: Vec<Grip> = vertices
+                    .iter()
+                    .map(|vertex| Grip::new(GripKind::Vertex, vertex.position))
+                    .collect();
+                grips.extend(
+                    (0..segment_count(vertices.len(), polyline.is_closed)).map(|index| {
+                        let (start, end) =
+                            (&vertices[index], &vertices[(index + 1) % vertices.len()]);
+                        Grip::new(
+                            GripKind::Midpoint,
+                            bulge_midpoint(start.position, end.position, start.bulge),
+                        )
+                    }),
+                );
+                grips
+            }
+            Entity::RevisionCloud(cloud) => vertex_grips(cloud.vertices.iter().map(|v| v.position)),
+            Entity::Leader(leader) => vertex_grips(leader.vertices.iter().copied()),
+            Entity::Spline(spline) => vertex_grips(spline.control_points.iter().copied()),
+            Entity::Face3D(face) => {
+                let count = face_vertex_count(&face.vertices);
+                vertex_grips(
+                    face.vertices[..count]
+                        .iter()
+                        .map(|vertex| Point2::new(vertex.x(), vertex.y())),
+                )
+            }
+            Entity::Text(text) => vec![Grip::new(GripKind::Insertion, text.insert)],
+            Entity::MText(mtext) => vec![Grip::new(GripKind::Insertion, mtext.insert)],
+            Entity::Shape(shape) => vec![Grip::new(GripKind::Insertion, shape.insert)],
+            Entity::BlockReference(reference) => {
+                vec![Grip::new(GripKind::Insertion, reference.insert)]
+            }
+            Entity::RasterImage(image) => vec![Grip::new(GripKind::Insertion, image.insert)],
+            Entity::Wipeout(wipeout) => vec![Grip::new(GripKind::Insertion, wipeout.insert)],
+            Entity::Underlay(underlay) => vec![Grip::new(GripKind::Insertion, underlay.insert)],
+            Entity::Hatch(_) | Entity::Dimension(_) | Entity::MLeader(_) => Vec::new(),
+        }
+    }
+
+    /// 把第 `index` 个夹点拖到 `target`，语义见模块说明。夹点不存在或拖动后几何退化
+    /// （如圆半径为零、圆弧三点共线）时返回 `false`，实体保持不变。
+    pub fn stretch_grip(&mut self, index: usize, target: Point2) -> bool {
+        let grips = self.grips();
+        let Some(grip) = grips.get(index).copied() else {
+            return false;
+        };
+        if grip.kind == GripKind::Insertion
+            || (grip.kind == GripKind::Center && !matches!(self, Entity::Ellipse(_)))
+            || (grip.kind == GripKind::Midpoint && matches!(self, Entity::Line(_)))
+        {
+            self.transform(&Transform2::translation(grip.point.vector_to(target)));
+            return true;
+        }
+        let target_vec = target.as_vec2();
+        match self {
+            Entity::Line(line) => {
+                if index == 0 {
+                    line.start = target;
+                } else {
+                    line.end = target;
+                }
+                true
+            }
+            Entity::Circle(circle) => {
+                let radius = circle.center.as_vec2().distance(target_vec);
+                if radius <= EPSILON {
+                    return false;
+                }
+                circle.radius = radius;
+                true
+            }
+            Entity::Arc(arc) => {
+                let mut points = [0, 1, 2].map(|grip| grips[grip].point.as_vec2());
+                points[index] = target_vec;
+                let [start, middle, end] = points;
+                let turn = turn(start, middle, end);
+                if turn == 0.0 {
+                    return false;
+                }
+                let Some(center) = circumcenter(start, middle, end) else {
+                    return false;
+                };
+                let (from, to) = if turn > 0.0 {
+                    (start, end)
+                } else {
+                    (end, start)
+                };
+                arc.center = Point2::from_vec(center);
+                arc.radius = start.distance(center);
+                arc.start_angle = normalize_angle((from - center).to_angle());
+                arc.end_angle = normalize_angle((to - center).to_angle());
+                true
+            }
+            Entity::Ellipse(ellipse) => {
+                let center = ellipse.center.as_vec2();
+                if grip.kind == GripKind::Center {
+                    ellipse.center = target;
+                    return true;
+                }
+                let offset = target_vec - center;
+                let (major, minor) = axes(ellipse);
+                let (major, minor_length) = match index {
+                    1 => (offset, minor.length()),
+                    3 => (-offset, minor.length()),
+                    _ => (major, offset.length()),
+                };
+                set_axes(ellipse, major, minor_length)
+            }
+            Entity::Polyline(polyline) => {
+                let count = polyline.vertices.len();
+                if index < count {
+                    polyline.vertices[index].position = target;
+                    return true;
+                }
+                let segment = index - count;
+                let next = (segment + 1) % count;
+                let (start, end) = (
+                    polyline.vertices[segment].position,
+                    polyline.vertices[next].position,
+                );
+                if polyline.vertices[segment].bulge.abs() <= EPSILON {
+                    let offset = grip.point.vector_to(target);
+                    polyline.vertices[segment].position = start.translate(offset);
+                    polyline.vertices[next].position = end.translate(offset);
+                } else {
+                    polyline.vertices[segment].bulge =
+                        bulge_through(start.as_vec2(), target_vec, end.as_vec2());
+                }
+                true
+            }
+            Entity::RevisionCloud(cloud) => {
+                cloud.vertices[index].position = target;
+                true
+            }
+            Entity::Leader(leader) => {
+                leader.vertices[index] = target;
+                true
+            }
+            Entity::Spline(spline) => {
+                spline.control_points[index] = target;
+                // 拟合点已不再描述曲线
+                spline.fit_points.clear();
+                true
+            }
+            Entity::Face3D(face) => {
+                let count = face_vertex_count(&face.vertices);
+                let moved = |vertex: Point3| Point3::new(target.x(), target.y(), vertex.z());
+                face.vertices[index] = moved(face.vertices[index]);
+                // 三角形的第四点与第三点重合，一并移动
+                if count == 3 && index == 2 {
+                    face.vertices[3] = moved(face.vertices[3]);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn vertex_grips(points: impl Iterator<Item = Point2>) -> Vec<Grip> {
+    points
+        .map(|point| Grip::new(GripKind::Vertex, point))
+        .collect()
+}
+
+fn midpoint(a: Point2, b: Point2) -> Point2 {
+    Point2::from_vec((a.as_vec2() + b.as_vec2()) / 2.0)
+}
+
+fn polar(center: Point2, radius: f64, angle: f64) -> Point2 {
+    Point2::from_vec(center.as_vec2() + DVec2::from_angle(angle) * radius)
+}
+
+fn segment_count(vertices: usize, is_closed: bool) -> usize {
+    match vertices {
+        0 | 1 => 0,
+        count if is_closed => count,
+        count => count - 1,
+    }
+}
+
+/// 三维面实际使用的顶点数：第四点与第三点重合时为三角形。
+fn face_vertex_count(vertices: &[Point3; 4]) -> usize {
+    if vertices[3].0.distance(vertices[2].0) <= EPSILON {
+        3
+    } else {
+        4
+    }
+}
+
+/// 凸度段的中点：直线段为弦中点，圆弧段为弧中点。
+fn bulge_midpoint(start: Point2, end: Point2, bulge: f64) -> Point2 {
+    let (a, b) = (start.as_vec2(), end.as_vec2());
+    // 正凸度为逆时针圆弧，弧中点位于弦的右侧，拱高为凸度乘半弦长
+    Point2::from_vec((a + b) / 2.0 - (b - a).perp() * bulge / 2.0)
+}
+
+/// 自 `start` 经 `through` 到 `end` 的圆弧凸度；三点共线时为零。
+fn bulge_through(start: DVec2, through: DVec2, end: DVec2) -> f64 {
+    let turn = turn(start, through, end);
+    let Some(center) = circumcenter(start, through, end).filter(|_| turn != 0.0) else {
+        return 0.0;
+    };
+    let (from, to) = ((start - center).to_angle(), (end - center).to_angle());
+    if turn > 0.0 {
+        ((to - from).rem_euclid(TAU) / 4.0).tan()
+    } else {
+        -((from - to).rem_euclid(TAU) / 4.0).tan()
+    }
+}
+
+/// 依次经过三点时的转向：正为逆时针，负为顺时针，近乎共线时为零。
+fn turn(a: DVec2, b: DVec2, c: DVec2) -> f64 {
+    let (ab, bc) = (b - a, c - b);
+    let cross = ab.perp_dot(bc);
+    if cross.abs() <= EPSILON * ab.length() * bc.length() {
+        0.0
+    } else {
+        cross
+    }
+}
+
+/// 椭圆的长半轴与短半轴向量，短轴为长轴逆时针转 90°。
+fn axes(ellipse: &Ellipse) -> (DVec2, DVec2) {
+    let major = ellipse.major_axis.0;
+    (major, major.perp() * ellipse.ratio)
+}
+
+/// 以新的长半轴与短半轴长度重设椭圆；短轴更长时两轴互换，参数随之偏移 90° 以保持曲线不变。
+fn set_axes(ellipse: &mut Ellipse, major: DVec2, minor_length: f64) -> bool {
+    let major_length = major.length();
+    if major_length <= EPSILON || minor_length <= EPSILON {
+        return false;
+    }
+    if minor_length <= major_length {
+        ellipse.major_axis = Vector2(major);
+        ellipse.ratio = minor_length / major_length;
+    } else {
+        ellipse.major_axis = Vector2(major.perp() / major_length * minor_length);
+        ellipse.ratio = major_length / minor_length;
+        let full = ccw_sweep(ellipse.start_parameter, ellipse.end_parameter) >= TAU - EPSILON;
+        if !full {
+            ellipse.start_parameter = normalize_angle(ellipse.start_parameter - FRAC_PI_2);
+            ellipse.end_parameter = normalize_angle(ellipse.end_parameter - FRAC_PI_2);
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{Arc, Circle, Document, Line, Polyline, PolylineVertex};
+
+    fn close(a: Point2, b: Point2) -> bool {
+        a.as_vec2().distance(b.as_vec2()) < 1e-9
+    }
+
+    #[test]
+    fn lines_circles_and_arcs_stretch_by_grip() {
+        let mut line = Entity::Line(Line {
+            start: Point2::new(0.0, 0.0),
+            end: Point2::new(10.0, 0.0),
+            layer: "0".into(),
+        });
+        assert!(close(line.grips()[1].point, Point2::new(5.0, 0.0)));
+        assert!(line.stretch_grip(2, Point2::new(10.0, 10.0)));
+        assert!(line.stretch_grip(1, Point2::new(5.0, 6.0)));
+        let grips = line.grips();
+        assert!(close(grips[0].point, Point2::new(0.0, 1.0)));
+        assert!(close(grips[2].point, Point2::new(10.0, 11.0)));
+        assert!(!line.stretch_grip(3, Point2::new(0.0, 0.0)));
+
+        let mut circle = Entity::Circle(Circle {
+            center: Point2::new(0.0, 0.0),
+            radius: 2.0,
+            layer: "0".into(),
+        });
+        assert_eq!(circle.grips()[0].kind, GripKind::Center);
+        assert!(circle.stretch_grip(2, Point2::new(3.0, 4.0)));
+        assert!(matches!(&circle, Entity::Circle(c) if (c.radius - 5.0).abs() < 1e-12));
+        assert!(!circle.stretch_grip(1, Point2::new(0.0, 0.0)));
+
+        // 上半圆，把中点拉低成较扁的圆弧
+        let mut arc = Entity::Arc(Arc {
+            center: Point2::new(0.0, 0.0),
+            radius: 5.0,
+            start_angle: 0.0,
+            end_angle: std::f64::consts::PI,
+            layer: "0".into(),
+        });
+        assert!(close(arc.grips()[1].point, Point2::new(0.0, 5.0)));
+        assert!(arc.stretch_grip(1, Point2::new(0.0, 2.5)));
+        let grips = arc.grips();
+        assert!(close(grips[0].point, Point2::new(5.0, 0.0)));
+        assert!(close(grips[1].point, Point2::new(0.0, 2.5)));
+        assert!(close(grips[2].point, Point2::new(-5.0, 0.0)));
+        // 中点拉到弦的另一侧，圆弧翻到下方
+        assert!(arc.stretch_grip(1, Point2::new(0.0, -1.0)));
+        assert!(close(arc.grips()[1].point, Point2::new(0.0, -1.0)));
+        assert!(!arc.stretch_grip(1, Point2::new(1.0, 0.0)));
+    }
+
+    #[test]
+    fn polyline_segments_stretch_or_bend() {
+        let mut doc = Document::new();
+        let id = doc.add_polyline(
+            [
+                Point2::new(0.0, 0.0),
+                Point2::new(10.0, 0.0),
+                Point2::new(10.0, 10.0),
+            ],
+            false,
+            "0",
+        );
+        let mut polyline = doc.entity(id).unwrap().clone();
+        let grips = polyline.grips();
+        assert_eq!(grips.len(), 5);
+        assert_eq!(grips[3].kind, GripKind::Midpoint);
+
+        // 平移第一段
+        assert!(polyline.stretch_grip(3, Point2::new(5.0, -2.0)));
+        let Entity::Polyline(Polyline { vertices, .. }) = &polyline else {
+            unreachable!();
+        };
+        assert!(close(vertices[1].position, Point2::new(10.0, -2.0)));
+        let mut bent = polyline.clone();
+        let Entity::Polyline(edit) = &mut bent else {
+            unreachable!();
+        };
+        edit.vertices[1].bulge = 0.5;
+        // 圆弧段的中点拖到目标点，变为经过目标点的半圆
+        let target = Point2::new(16.0, 4.0);
+        assert!(bent.stretch_grip(4, target));
+        let Entity::Polyline(Polyline { vertices, .. }) = &bent else {
+            unreachable!();
+        };
+        let PolylineVertex { position, bulge } = vertices[1].clone();
+        assert!((bulge - 1.0).abs() < 1e-9, "{bulge}");
+        assert!(close(
+            bulge_midpoint(position, vertices[2].position, bulge),
+            target
+        ));
+    }
+
+    #[test]
+    fn ellipse_axes_swap_and_text_moves() {
+        let mut ellipse = Entity::Ellipse(Ellipse {
+            center: Point2::new(0.0, 0.0),
+            major_axis: Vector2::new(4.0, 0.0),
+            ratio: 0.5,
+            start_parameter: 0.0,
+            end_parameter: TAU,
+            layer: "0".into(),
+        });
+        assert!(close(ellipse.grips()[2].point, Point2::new(0.0, 2.0)));
+        assert!(ellipse.stretch_grip(2, Point2::new(0.0, 8.0)));
+        let Entity::Ellipse(edited) = &ellipse else {
+            unreachable!();
+        };
+        assert!((edited.ratio - 0.5).abs() < 1e-12);
+        assert!(close(
+            Point2::from_vec(edited.major_axis.0),
+            Point2::new(0.0, 8.0)
+        ));
+
+        let mut doc = Document::new();
+        let id = doc.add_text(Point2::new(1.0, 1.0), "A", 2.5, 0.0, "0");
+        let mut text = doc.entity(id).unwrap().clone();
+        assert!(text.stretch_grip(0, Point2::new(4.0, 5.0)));
+        assert!(close(text.grips()[0].point, Point2::new(4.0, 5.0)));
+    }
+}
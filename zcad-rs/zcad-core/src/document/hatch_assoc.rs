@@ -0,0 +1,622 @@
+//! 关联填充：填充环路记录围成它的边界对象，边界对象修改后由 [`Document::regenerate_hatch`]
+//! 按其当前几何重建环路的边。
+//!
+//! 单个闭合对象（圆、闭合多段线、完整椭圆、闭合样条）直接构成一个环路；多个开放对象（直线、圆弧、
+//! 开放多段线、椭圆弧、开放样条）按端点首尾串接，必要时反向，最终须回到起点。边界对象被删除时，
+//! [`Document::regenerate_associative_hatches`] 自动解除该填充的关联，填充保持最后的形状。
+
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+use std::fmt;
+
+use glam::DVec2;
+
+use super::associate::{ellipse_point, is_full_ellipse};
+use super::flatten::edge_sweep;
+use super::transform::encode_sweep;
+use super::{Document, Entity, EntityId, HatchEdge, HatchLoop};
+use crate::geometry::{BulgeArc, Point2};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HatchRegenError {
+    NotFound(EntityId),
+    NotHatch(EntityId),
+    /// 填充未标记为关联填充。
+    NotAssociative(EntityId),
+    /// 环路引用的边界对象已不存在。
+    MissingBoundary(EntityId),
+    /// 该对象不能作为填充边界。
+    UnsupportedBoundary(EntityId),
+    /// 第 n 个环路的边界对象无法首尾相接成闭合环路。
+    OpenLoop(usize),
+}
+
+impl fmt::Display for HatchRegenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HatchRegenError::NotFound(id) => write!(f, "entity {} not found", id.get()),
+            HatchRegenError::NotHatch(id) => write!(f, "entity {} is not a hatch", id.get()),
+            HatchRegenError::NotAssociative(id) => {
+                write!(f, "hatch {} is not associative", id.get())
+            }
+            HatchRegenError::MissingBoundary(id) => {
+                write!(f, "boundary entity {} no longer exists", id.get())
+            }
+            HatchRegenError::UnsupportedBoundary(id) => {
+                write!(f, "entity {} cannot bound a hatch", id.get())
+            }
+            HatchRegenError::OpenLoop(index) => {
+                write!(
+                    f,
+                    "boundary objects of loop {index} do not form a closed loop"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for HatchRegenError {}
+
+impl Document {
+    /// 按句柄表把各填充环路的 `boundary_handles` 解析为边界对象编号，返回解析成功的环路数。
+    ///
+    /// 只有全部句柄都能解析的环路才会记录边界对象，否则保持为空。
+    pub fn resolve_hatch_boundaries(&mut self, handles: &HashMap<String, EntityId>) -> usize {
+        let resolvable = |hatch_loop: &HatchLoop| {
+            !hatch_loop.boundary_handles.is_empty()
+                && hatch_loop
+                    .boundary_handles
+                    .iter()
+                    .all(|handle| handles.contains_key(handle))
+        };
+        let touched = self.record_modified_where(
+            |entity| matches!(entity, Entity::Hatch(hatch) if hatch.loops.iter().any(resolvable)),
+        );
+        if !touched {
+            return 0;
+        }
+        let mut resolved = 0;
+        for (_, entity) in self.entities.iter_mut() {
+            let Entity::Hatch(hatch) = entity else {
+                continue;
+            };
+            for hatch_loop in &mut hatch.loops {
+                if hatch_loop.boundary_handles.is_empty() {
+                    continue;
+                }
+                let ids: Option<Vec<EntityId>> = hatch_loop
+                    .boundary_handles
+                    .iter()
+                    .map(|handle| handles.get(handle).copied())
+                    .collect();
+                if let Some(ids) = ids {
+                    hatch_loop.boundary_entities = ids;
+                    resolved += 1;
+                }
+            }
+        }
+        resolved
+    }
+
+    /// 按边界对象的当前几何重建关联填充的环路；未记录边界对象的环路保持不变。失败时文档不变。
+    pub fn regenerate_hatch(&mut self, id: EntityId) -> Result<(), HatchRegenError> {
+        let hatch = match self.entity(id) {
+            Some(Entity::Hatch(hatch)) => hatch,
+            Some(_) => return Err(HatchRegenError::NotHatch(id)),
+            None => return Err(HatchRegenError::NotFound(id)),
+        };
+        if !hatch.is_associative {
+            return Err(HatchRegenError::NotAssociative(id));
+        }
+        let mut loops = Vec::with_capacity(hatch.loops.len());
+        for (index, hatch_loop) in hatch.loops.iter().enumerate() {
+            if hatch_loop.boundary_entities.is_empty() {
+                loops.push(hatch_loop.clone());
+                continue;
+            }
+            let edges = self.chain_boundary(index, &hatch_loop.boundary_entities)?;
+            let is_polyline = edges
+                .iter()
+                .all(|edge| matches!(edge, HatchEdge::PolylineSegment { .. }));
+            loops.push(HatchLoop {
+                is_polyline,
+                is_closed: true,
+                edges: if is_polyline {
+                    edges
+                } else {
+                    edges.into_iter().map(curve_edge).collect()
+                },
+                boundary_handles: hatch_loop.boundary_handles.clone(),
+                boundary_entities: hatch_loop.boundary_entities.clone(),
+            });
+        }
+        if matches!(self.entities.get(id), Some(Entity::Hatch(_))) {
+            self.record_modified(id);
+        }
+        if let Some(Entity::Hatch(hatch)) = self.entities.get_geometry_mut(id) {
+            hatch.loops = loops;
+        }
+        Ok(())
+    }
+
+    /// 重建全部关联填充，返回成功重建的数量。边界对象已被删除的填充解除关联。
+    pub fn regenerate_associative_hatches(&mut self) -> usize {
+        let hatches: Vec<EntityId> = self
+            .entities()
+            .filter(|(_, entity)| {
+                matches!(entity, Entity::Hatch(hatch)
+                    if hatch.is_associative
+                        && hatch.loops.iter().any(|hatch_loop| !hatch_loop.boundary_entities.is_empty()))
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        let mut regenerated = 0;
+        for id in hatches {
+            match self.regenerate_hatch(id) {
+                Ok(()) => regenerated += 1,
+                Err(HatchRegenError::MissingBoundary(_)) => {
+                    if let Some(Entity::Hatch(hatch)) = self.entities.get_geometry_mut(id) {
+                        hatch.is_associative = false;
+                        for hatch_loop in &mut hatch.loops {
+                            hatch_loop.boundary_handles.clear();
+                            hatch_loop.boundary_entities.clear();
+                        }
+                    }
+                }
+                Err(_) => {}
+            }
+        }
+        regenerated
+    }
+
+    /// 把一个环路的边界对象串接为首尾相接的边。
+    fn chain_boundary(
+        &self,
+        index: usize,
+        boundary: &[EntityId],
+    ) -> Result<Vec<HatchEdge>, HatchRegenError> {
+        let mut pieces = Vec::with_capacity(boundary.len());
+        for &id in boundary {
+            let entity = self
+                .entity(id)
+                .ok_or(HatchRegenError::MissingBoundary(id))?;
+            let (edges, is_closed) =
+                boundary_edges(entity).ok_or(HatchRegenError::UnsupportedBoundary(id))?;
+            pieces.push((edges, is_closed));
+        }
+        if let [(edges, true)] = pieces.as_slice() {
+            return Ok(edges.clone());
+        }
+        if pieces.iter().any(|(_, is_closed)| *is_closed) {
+            return Err(HatchRegenError::OpenLoop(index));
+        }
+
+        let tolerance = self.tolerance();
+        let touches = |a: DVec2, b: DVec2| tolerance.points_equal(Point2::from(a), Point2::from(b));
+        let mut rest: Vec<Vec<HatchEdge>> = pieces.into_iter().map(|(edges, _)| edges).collect();
+        let mut chain = rest.remove(0);
+        let head = edge_ends(&chain[0]).0;
+        while !rest.is_empty() {
+            let tail = edge_ends(&chain[chain.len() - 1]).1;
+            let found = rest.iter().position(|edges| {
+                touches(tail, edge_ends(&edges[0]).0)
+                    || touches(tail, edge_ends(&edges[edges.len() - 1]).1)
+            });
+            let Some(found) = found else {
+                return Err(HatchRegenError::OpenLoop(index));
+            };
+            let edges = rest.remove(found);
+            if touches(tail, edge_ends(&edges[0]).0) {
+                chain.extend(edges);
+            } else {
+                chain.extend(edges.iter().rev().map(reversed_edge));
+            }
+        }
+        if !touches(edge_ends(&chain[chain.len() - 1]).1, head) {
+            return Err(HatchRegenError::OpenLoop(index));
+        }
+        Ok(chain)
+    }
+}
+
+/// 边界对象对应的填充边及其是否自身闭合；不能作为边界的对象返回 `None`。
+fn boundary_edges(entity: &Entity) -> Option<(Vec<HatchEdge>, bool)> {
+    match entity {
+        Entity::Line(line) => Some((
+            vec![HatchEdge::Line {
+                start: line.start,
+                end: line.end,
+            }],
+            false,
+        )),
+        Entity::Arc(arc) => Some((
+            vec![HatchEdge::Arc {
+                center: arc.center,
+                radius: arc.radius,
+                start_angle: arc.start_angle,
+                end_angle: arc.end_angle,
+                is_counter_clockwise: true,
+            }],
+            false,
+        )),
+        Entity::Circle(circle) => Some((
+            vec![HatchEdge::Arc {
+                center: circle.center,
+                radius: circle.radius,
+                start_angle: 0.0,
+                end_angle: TAU,
+                is_counter_clockwise: true,
+            }],
+            true,
+        )),
+        Entity::Ellipse(ellipse) => Some((
+            vec![HatchEdge::Ellipse {
+                center: ellipse.center,
+                major_axis: ellipse.major_axis,
+                minor_ratio: ellipse.ratio,
+                start_angle: ellipse.start_parameter,
+                end_angle: ellipse.end_parameter,
+                is_counter_clockwise: true,
+            }],
+            is_full_ellipse(ellipse),
+        )),
+        Entity::Polyline(polyline) if polyline.vertices.len() >= 2 => {
+            let count = polyline.vertices.len();
+            let segments = if polyline.is_closed { count } else { count - 1 };
+            let edges = (0..segments)
+                .map(|index| HatchEdge::PolylineSegment {
+                    start: polyline.vertices[index].position,
+                    end: polyline.vertices[(index + 1) % count].position,
+                    bulge: polyline.vertices[index].bulge,
+                })
+                .collect();
+            Some((edges, polyline.is_closed))
+        }
+        Entity::Spline(spline)
+            if spline.control_points.len() >= 2 || spline.fit_points.len() >= 2 =>
+        {
+            Some((
+                vec![HatchEdge::Spline {
+                    control_points: spline.control_points.clone(),
+                    fit_points: spline.fit_points.clone(),
+                    knot_values: spline.knot_values.clone(),
+                    degree: spline.degree,
+                    is_rational: spline.is_rational,
+                    is_periodic: spline.is_periodic,
+                }],
+                spline.is_closed || spline.is_periodic,
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// 填充边的起点与终点。
+fn edge_ends(edge: &HatchEdge) -> (DVec2, DVec2) {
+    match edge {
+        HatchEdge::Line { start, end } | HatchEdge::PolylineSegment { start, end, .. } => {
+            (start.as_vec2(), end.as_vec2())
+        }
+        HatchEdge::Arc {
+            center,
+            radius,
+            start_angle,
+            end_angle,
+            is_counter_clockwise,
+        } => {
+            let (start, sweep) = edge_sweep(*start_angle, *end_angle, *is_counter_clockwise);
+            let center = center.as_vec2();
+            (
+                center + DVec2::from_angle(start) * *radius,
+                center + DVec2::from_angle(start + sweep) * *radius,
+            )
+        }
+        HatchEdge::Ellipse {
+            center,
+            major_axis,
+            minor_ratio,
+            start_angle,
+            end_angle,
+            is_counter_clockwise,
+        } => {
+            let (start, sweep) = edge_sweep(*start_angle, *end_angle, *is_counter_clockwise);
+            let ellipse = super::Ellipse {
+                center: *center,
+                major_axis: *major_axis,
+                ratio: *minor_ratio,
+                start_parameter: start,
+                end_parameter: start + sweep,
+                layer: String::new(),
+            };
+            (
+                ellipse_point(&ellipse, start).as_vec2(),
+                ellipse_point(&ellipse, start + sweep).as_vec2(),
+            )
+        }
+        HatchEdge::Spline {
+            control_points,
+            fit_points,
+            ..
+        } => {
+            let points = if control_points.is_empty() {
+                fit_points
+            } else {
+                control_points
+            };
+            (
+                points.first().map_or(DVec2::ZERO, |point| point.as_vec2()),
+                points.last().map_or(DVec2::ZERO, |point| point.as_vec2()),
+            )
+        }
+        HatchEdge::BoundaryReference { .. } => (DVec2::ZERO, DVec2::ZERO),
+    }
+}
+
+/// 方向相反的同一条边。
+fn reversed_edge(edge: &HatchEdge) -> HatchEdge {
+    match edge.clone() {
+        HatchEdge::Line { start, end } => HatchEdge::Line {
+            start: end,
+            end: start,
+        },
+        HatchEdge::PolylineSegment { start, end, bulge } => HatchEdge::PolylineSegment {
+            start: end,
+            end: start,
+            bulge: -bulge,
+        },
+        HatchEdge::Arc {
+            center,
+            radius,
+            start_angle,
+            end_angle,
+            is_counter_clockwise,
+        } => {
+            let (start, sweep) = edge_sweep(start_angle, end_angle, is_counter_clockwise);
+            let (start_angle, end_angle, is_counter_clockwise) =
+                encode_sweep(start + sweep, -sweep);
+            HatchEdge::Arc {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+                is_counter_clockwise,
+            }
+        }
+        HatchEdge::Ellipse {
+            center,
+            major_axis,
+            minor_ratio,
+            start_angle,
+            end_angle,
+            is_counter_clockwise,
+        } => {
+            let (start, sweep) = edge_sweep(start_angle, end_angle, is_counter_clockwise);
+            let (start_angle, end_angle, is_counter_clockwise) =
+                encode_sweep(start + sweep, -sweep);
+            HatchEdge::Ellipse {
+                center,
+                major_axis,
+                minor_ratio,
+                start_angle,
+                end_angle,
+                is_counter_clockwise,
+            }
+        }
+        HatchEdge::Spline {
+            mut control_points,
+            mut fit_points,
+            knot_values,
+            degree,
+            is_rational,
+            is_periodic,
+        } => {
+            control_points.reverse();
+            fit_points.reverse();
+            // 节点向量按 t ↦ a + b − t 翻转
+            let (first, last) = (
+                knot_values.first().copied().unwrap_or(0.0),
+                knot_values.last().copied().unwrap_or(0.0),
+            );
+            HatchEdge::Spline {
+                control_points,
+                fit_points,
+                knot_values: knot_values
+                    .iter()
+                    .rev()
+                    .map(|knot| first + last - knot)
+                    .collect(),
+                degree,
+                is_rational,
+                is_periodic,
+            }
+        }
+        edge @ HatchEdge::BoundaryReference { .. } => edge,
+    }
+}
+
+/// 非多段线环路不能包含凸度段，按凸度换成直线边或圆弧边。
+fn curve_edge(edge: HatchEdge) -> HatchEdge {
+    let HatchEdge::PolylineSegment { start, end, bulge } = edge else {
+        return edge;
+    };
+    let Some(arc) = BulgeArc::new(start.as_vec2(), end.as_vec2(), bulge) else {
+        return HatchEdge::Line { start, end };
+    };
+    let (start_angle, end_angle, is_counter_clockwise) = encode_sweep(arc.start_angle, arc.sweep);
+    HatchEdge::Arc {
+        center: Point2::from(arc.center),
+        radius: arc.radius,
+        start_angle,
+        end_angle,
+        is_counter_clockwise,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{HatchStyle, PolylineVertex};
+
+    fn associative_hatch(doc: &mut Document, boundary: Vec<EntityId>) -> EntityId {
+        doc.add_hatch(
+            "SOLID",
+            true,
+            vec![HatchLoop {
+                is_polyline: false,
+                is_closed: true,
+                edges: Vec::new(),
+                boundary_handles: Vec::new(),
+                boundary_entities: boundary,
+            }],
+            None,
+            Vec::new(),
+            HatchStyle::default(),
+            true,
+            Vec::new(),
+            "HATCH",
+        )
+    }
+
+    fn loop_of(doc: &Document, id: EntityId) -> &HatchLoop {
+        match doc.entity(id) {
+            Some(Entity::Hatch(hatch)) => &hatch.loops[0],
+            other => panic!("expected hatch, got {other:?}"),
+        }
+    }
+
+    fn vertex_chain(hatch_loop: &HatchLoop) -> Vec<DVec2> {
+        hatch_loop
+            .edges
+            .iter()
+            .map(|edge| edge_ends(edge).0)
+            .collect()
+    }
+
+    #[test]
+    fn closed_polyline_boundary_follows_its_vertices() {
+        let mut doc = Document::new();
+        let square = doc.add_polyline(
+            [
+                Point2::new(0.0, 0.0),
+                Point2::new(2.0, 0.0),
+                Point2::new(2.0, 2.0),
+                Point2::new(0.0, 2.0),
+            ],
+            true,
+            "0",
+        );
+        let hatch = associative_hatch(&mut doc, vec![square]);
+        doc.regenerate_hatch(hatch).unwrap();
+        assert!(loop_of(&doc, hatch).is_polyline);
+        assert_eq!(loop_of(&doc, hatch).edges.len(), 4);
+
+        if let Some(Entity::Polyline(polyline)) = doc.entity_mut(square) {
+            polyline.vertices[2].position = Point2::new(5.0, 5.0);
+        }
+        doc.regenerate_hatch(hatch).unwrap();
+        assert_eq!(vertex_chain(loop_of(&doc, hatch))[2], DVec2::new(5.0, 5.0));
+        assert!(doc.bounds().unwrap().max().x() >= 5.0);
+    }
+
+    #[test]
+    fn open_entities_are_chained_and_reversed_into_a_loop() {
+        let mut doc = Document::new();
+        let bottom = doc.add_line(Point2::new(0.0, 0.0), Point2::new(4.0, 0.0), "0");
+        // 右侧竖线方向与链相反
+        let right = doc.add_line(Point2::new(4.0, 2.0), Point2::new(4.0, 0.0), "0");
+        let top = doc.add_arc(Point2::new(2.0, 2.0), 2.0, 0.0, std::f64::consts::PI, "0");
+        let left = doc.add_polyline_with_vertices(
+            [
+                PolylineVertex::new(Point2::new(0.0, 0.0)),
+                PolylineVertex::new(Point2::new(0.0, 2.0)),
+            ],
+            false,
+            "0",
+        );
+        let hatch = associative_hatch(&mut doc, vec![bottom, top, left, right]);
+        doc.regenerate_hatch(hatch).unwrap();
+
+        let hatch_loop = loop_of(&doc, hatch);
+        assert!(!hatch_loop.is_polyline);
+        assert_eq!(hatch_loop.edges.len(), 4);
+        for (index, edge) in hatch_loop.edges.iter().enumerate() {
+            let next = &hatch_loop.edges[(index + 1) % hatch_loop.edges.len()];
+            assert!(edge_ends(edge).1.distance(edge_ends(next).0) < 1e-9);
+        }
+        assert!(matches!(hatch_loop.edges[3], HatchEdge::Line { .. }));
+    }
+
+    #[test]
+    fn disconnected_or_missing_boundaries_are_reported() {
+        let mut doc = Document::new();
+        let a = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+        let b = doc.add_line(Point2::new(1.0, 0.0), Point2::new(1.0, 1.0), "0");
+        let hatch = associative_hatch(&mut doc, vec![a, b]);
+        assert_eq!(
+            doc.regenerate_hatch(hatch),
+            Err(HatchRegenError::OpenLoop(0))
+        );
+
+        let circle = doc.add_circle(Point2::new(0.0, 0.0), 1.0, "0");
+        let round = associative_hatch(&mut doc, vec![circle]);
+        assert_eq!(doc.regenerate_associative_hatches(), 1);
+        doc.remove_entity(circle);
+        assert_eq!(
+            doc.regenerate_hatch(round),
+            Err(HatchRegenError::MissingBoundary(circle))
+        );
+        assert_eq!(doc.regenerate_associative_hatches(), 0);
+        let Some(Entity::Hatch(round)) = doc.entity(round) else {
+            panic!("expected hatch");
+        };
+        assert!(!round.is_associative);
+        assert!(round.loops[0].boundary_entities.is_empty());
+        assert_eq!(round.loops[0].edges.len(), 1);
+    }
+
+    #[test]
+    fn handles_resolve_to_entity_ids() {
+        let mut doc = Document::new();
+        let circle = doc.add_circle(Point2::new(0.0, 0.0), 1.0, "0");
+        let hatch = associative_hatch(&mut doc, Vec::new());
+        if let Some(Entity::Hatch(hatch)) = doc.entity_mut(hatch) {
+            hatch.loops[0].boundary_handles = vec!["2A".to_string()];
+            hatch.loops.push(HatchLoop {
+                boundary_handles: vec!["FF".to_string()],
+                ..hatch.loops[0].clone()
+            });
+        }
+        let handles = HashMap::from([("2A".to_string(), circle)]);
+        assert_eq!(doc.resolve_hatch_boundaries(&handles), 1);
+        // 解析记入修改记录，撤销后恢复为未解析
+        let (_, changes) = doc.capture_changes(|doc| {
+            let handles = HashMap::from([("FF".to_string(), circle)]);
+            doc.resolve_hatch_boundaries(&handles)
+        });
+        assert_eq!(changes.len(), 1);
+        doc.revert_changes(&changes);
+        let Some(Entity::Hatch(hatch)) = doc.entity(hatch) else {
+            panic!("expected hatch");
+        };
+        assert_eq!(hatch.loops[0].boundary_entities, vec![circle]);
+        assert!(hatch.loops[1].boundary_entities.is_empty());
+    }
+
+    #[test]
+    fn bulged_segments_become_arcs_in_mixed_loops() {
+        let edge = curve_edge(HatchEdge::PolylineSegment {
+            start: Point2::new(1.0, 0.0),
+            end: Point2::new(-1.0, 0.0),
+            bulge: 1.0,
+        });
+        let (start, end) = edge_ends(&edge);
+        assert!(start.distance(DVec2::new(1.0, 0.0)) < 1e-9);
+        assert!(end.distance(DVec2::new(-1.0, 0.0)) < 1e-9);
+        let HatchEdge::Arc { center, radius, .. } = edge else {
+            panic!("expected arc edge");
+        };
+        assert!(center.as_vec2().length() < 1e-9 && (radius - 1.0).abs() < 1e-9);
+        let reversed = reversed_edge(&edge);
+        assert!(edge_ends(&reversed).0.distance(end) < 1e-9);
+    }
+}
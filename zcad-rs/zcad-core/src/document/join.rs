@@ -0,0 +1,291 @@
+//! 合并（JOIN）：把首尾相接的直线、圆弧与开放多段线合并为一条带凸度的多段线。
+//!
+//! 选择集中的对象按端点依次串接，必要时反向；端点距离不超过容差即视为相接。全部对象串成一条链后，
+//! 原对象被删除，新多段线取第一个对象的图层与特性覆盖。链的首尾相接时结果自动闭合。
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::geometry::Point2;
+
+use super::flatten::ccw_sweep;
+use super::pedit::reversed_vertices;
+use super::{Document, Entity, EntityId, PolylineVertex};
+
+/// 合并选项。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JoinOptions {
+    /// 端点重合的距离容差，不小于文档容差。
+    pub tolerance: f64,
+    /// 首尾不相接时也以直线段闭合结果。
+    pub close: bool,
+}
+
+impl Default for JoinOptions {
+    fn default() -> Self {
+        Self {
+            tolerance: 1e-6,
+            close: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JoinError {
+    /// 选择集为空。
+    Empty,
+    NotFound(EntityId),
+    /// 只能合并直线、圆弧与开放多段线。
+    Unsupported(EntityId),
+    /// 该对象无法与其余对象首尾相接。
+    Disconnected(EntityId),
+}
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JoinError::Empty => write!(f, "nothing to join"),
+            JoinError::NotFound(id) => write!(f, "entity {} not found", id.get()),
+            JoinError::Unsupported(id) => {
+                write!(f, "entity {} cannot be joined into a polyline", id.get())
+            }
+            JoinError::Disconnected(id) => {
+                write!(
+                    f,
+                    "entity {} is not connected to the other entities",
+                    id.get()
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for JoinError {}
+
+impl Document {
+    /// 合并首尾相接的直线、圆弧与开放多段线，返回新多段线的编号；失败时文档不变。
+    pub fn join_entities(
+        &mut self,
+        ids: impl IntoIterator<Item = EntityId>,
+        options: &JoinOptions,
+    ) -> Result<EntityId, JoinError> {
+        let mut seen = HashSet::new();
+        let mut pieces = Vec::new();
+        for id in ids {
+            if !seen.insert(id) {
+                continue;
+            }
+            let entity = self.entity(id).ok_or(JoinError::NotFound(id))?;
+            let path = path_of(entity).ok_or(JoinError::Unsupported(id))?;
+            pieces.push((id, path));
+        }
+        let mut pieces = pieces.into_iter();
+        let (first, mut chain) = pieces.next().ok_or(JoinError::Empty)?;
+        let mut rest: Vec<_> = pieces.collect();
+
+        let tolerance = options.tolerance.abs().max(self.tolerance().linear);
+        let touches = |a: &PolylineVertex, b: &PolylineVertex| {
+            a.position.as_vec2().distance(b.position.as_vec2()) <= tolerance
+        };
+        while !rest.is_empty() {
+            let head = chain[0].clone();
+            let tail = chain[chain.len() - 1].clone();
+            let found = rest.iter().position(|(_, path)| {
+                let (start, end) = (&path[0], &path[path.len() - 1]);
+                touches(&tail, start)
+                    || touches(&tail, end)
+                    || touches(&head, start)
+                    || touches(&head, end)
+            });
+            let Some(index) = found else {
+                return Err(JoinError::Disconnected(rest[0].0));
+            };
+            let (_, mut path) = rest.remove(index);
+            if touches(&tail, &path[0]) || touches(&tail, &path[path.len() - 1]) {
+                if !touches(&tail, &path[0]) {
+                    path = reversed_vertices(&path, false);
+                }
+                // 相接点取链上已有的位置，凸度沿用新段
+                chain.pop();
+                path[0].position = tail.position;
+                chain.extend(path);
+            } else {
+                if !touches(&head, &path[path.len() - 1]) {
+                    path = reversed_vertices(&path, false);
+                }
+                path.pop();
+                path.extend(chain);
+                chain = path;
+            }
+        }
+
+        let mut is_closed = options.close;
+        if chain.len() > 2 && touches(&chain[0], &chain[chain.len() - 1]) {
+            chain.pop();
+            is_closed = true;
+        }
+        if let Some(last) = chain.last_mut().filter(|_| !is_closed) {
+            last.bulge = 0.0;
+        }
+
+        let layer = self
+            .entity(first)
+            .map(|entity| entity.layer_name().to_string());
+        let properties = self.entity_properties(first).cloned();
+        for id in seen {
+            self.remove_entity(id);
+        }
+        let joined = self.add_polyline_with_vertices(chain, is_closed, layer.unwrap_or_default());
+        if let Some(properties) = properties {
+            self.set_entity_properties(joined, properties);
+        }
+        Ok(joined)
+    }
+}
+
+/// 对象的开放路径；最后一个顶点的凸度无意义。
+fn path_of(entity: &Entity) -> Option<Vec<PolylineVertex>> {
+    match entity {
+        Entity::Line(line) => Some(vec![
+            PolylineVertex::new(line.start),
+            PolylineVertex::new(line.end),
+        ]),
+        Entity::Arc(arc) => {
+            let sweep = ccw_sweep(arc.start_angle, arc.end_angle);
+            let point = |angle: f64| {
+                Point2::new(
+                    arc.center.x() + arc.radius * angle.cos(),
+                    arc.center.y() + arc.radius * angle.sin(),
+                )
+            };
+            Some(vec![
+                PolylineVertex::with_bulge(point(arc.start_angle), (sweep / 4.0).tan()),
+                PolylineVertex::new(point(arc.start_angle + sweep)),
+            ])
+        }
+        Entity::Polyline(polyline) if !polyline.is_closed && polyline.vertices.len() >= 2 => {
+            Some(polyline.vertices.clone())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use super::*;
+    use crate::geometry::Tolerance;
+
+    fn vertices(doc: &Document, id: EntityId) -> (Vec<(f64, f64, f64)>, bool) {
+        let Some(Entity::Polyline(polyline)) = doc.entity(id) else {
+            panic!("expected polyline");
+        };
+        let round = |value: f64| (value * 1e9).round() / 1e9;
+        (
+            polyline
+                .vertices
+                .iter()
+                .map(|vertex| {
+                    (
+                        round(vertex.position.x()),
+                        round(vertex.position.y()),
+                        round(vertex.bulge),
+                    )
+                })
+                .collect(),
+            polyline.is_closed,
+        )
+    }
+
+    #[test]
+    fn joins_lines_and_arcs_in_any_order_and_direction() {
+        let mut doc = Document::new();
+        // 逆时针上半圆弧，从 (2, 0) 到 (0, 0)
+        let arc = doc.add_arc(Point2::new(1.0, 0.0), 1.0, 0.0, PI, "A");
+        let bottom = doc.add_line(Point2::new(2.0, -1.0), Point2::new(0.0, -1.0), "B");
+        let right = doc.add_line(Point2::new(2.0, -1.0), Point2::new(2.0, 1e-9), "B");
+        let left = doc.add_polyline([Point2::new(0.0, -1.0), Point2::new(0.0, 0.0)], false, "C");
+        let joined = doc
+            .join_entities([arc, bottom, left, right], &JoinOptions::default())
+            .unwrap();
+        for id in [arc, bottom, left, right] {
+            assert!(doc.entity(id).is_none());
+        }
+        assert_eq!(doc.entity(joined).unwrap().layer_name(), "A");
+        let (points, is_closed) = vertices(&doc, joined);
+        assert!(is_closed);
+        assert_eq!(
+            points,
+            vec![
+                (2.0, 0.0, 1.0),
+                (0.0, 0.0, 0.0),
+                (0.0, -1.0, 0.0),
+                (2.0, -1.0, 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn reversed_arcs_flip_bulge_and_open_chains_can_be_closed() {
+        let mut doc = Document::new();
+        let line = doc.add_line(Point2::new(3.0, 1.0), Point2::new(2.0, 1.0), "0");
+        // 逆时针四分之一圆弧从 (1, 0) 到 (2, 1)，终点接在直线末端，需反向串接
+        let arc = doc.add_arc(Point2::new(1.0, 1.0), 1.0, -FRAC_PI_2, 0.0, "0");
+        let options = JoinOptions {
+            close: true,
+            ..JoinOptions::default()
+        };
+        let joined = doc.join_entities([line, arc], &options).unwrap();
+        let (points, is_closed) = vertices(&doc, joined);
+        assert!(is_closed);
+        let quarter = (FRAC_PI_2 / 4.0).tan();
+        let quarter = (quarter * 1e9).round() / 1e9;
+        assert_eq!(
+            points,
+            vec![(3.0, 1.0, 0.0), (2.0, 1.0, -quarter), (1.0, 0.0, 0.0)]
+        );
+
+        let far = doc.add_line(Point2::new(5.0, 5.0), Point2::new(6.0, 5.0), "0");
+        let circle = doc.add_circle(Point2::new(0.0, 0.0), 1.0, "0");
+        assert_eq!(
+            doc.join_entities([joined, far], &JoinOptions::default()),
+            Err(JoinError::Unsupported(joined))
+        );
+        let next = doc.add_line(Point2::new(6.0, 5.0), Point2::new(7.0, 5.0), "0");
+        let other = doc.add_line(Point2::new(9.0, 9.0), Point2::new(9.0, 8.0), "0");
+        assert_eq!(
+            doc.join_entities([far, next, other], &JoinOptions::default()),
+            Err(JoinError::Disconnected(other))
+        );
+        assert!(doc.entity(far).is_some());
+        assert_eq!(
+            doc.join_entities([circle], &JoinOptions::default()),
+            Err(JoinError::Unsupported(circle))
+        );
+        assert_eq!(
+            doc.join_entities([], &JoinOptions::default()),
+            Err(JoinError::Empty)
+        );
+    }
+
+    #[test]
+    fn document_tolerance_widens_endpoint_matching() {
+        let mut doc = Document::new();
+        let first = doc.add_line(Point2::new(1e9, 0.0), Point2::new(1e9 + 1.0, 0.0), "0");
+        let second = doc.add_line(
+            Point2::new(1e9 + 1.0 + 1e-5, 0.0),
+            Point2::new(1e9 + 2.0, 0.0),
+            "0",
+        );
+        assert_eq!(
+            doc.join_entities([first, second], &JoinOptions::default()),
+            Err(JoinError::Disconnected(second))
+        );
+        doc.set_tolerance(Tolerance::new(1e-4, 1e-9));
+        assert!(
+            doc.join_entities([first, second], &JoinOptions::default())
+                .is_ok()
+        );
+    }
+}
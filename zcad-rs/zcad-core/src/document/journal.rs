@@ -0,0 +1,491 @@
+//! 修改日志：按修订记录模型空间实体的新建、修改与删除，每条记录都带有撤销所需的旧数据。
+//!
+//! 日志是可选的，调用 [`Document::enable_journal`] 后开始记录，随原生格式保存，也可单独序列化，
+//! 用于“自某修订以来改了什么”的报告以及崩溃后把较新的日志重放到上次保存的图纸上。
+//!
+//! 按编号修改的实体，以及改名、改基点、同步属性等批量原地修改涉及的实体，都先登记修改前的数据，
+//! 到 [`Document::commit_revision`] 或 [`Document::flush_journal`] 时再与当前数据配对成一条记录，
+//! 同一实体在此期间的多次修改合并为一条。删除实体一律经由 [`Document::remove_entity`]，
+//! 删除时立即定稿已登记的修改，保证记录顺序与实际编辑顺序一致。
+//! 新建与删除记录附带实体的特性覆盖；单独修改特性覆盖、用户数据、块定义与各类表记录不在日志范围内，
+//! 撤销时由 [`Document::capture_tables`] 按表另行记录。
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Document, Entity, EntityId, EntityProperties};
+
+/// 一条实体修改记录。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+    /// 新建实体；`position` 为记录时在模型空间中的位置。
+    Added {
+        id: EntityId,
+        position: usize,
+        entity: Entity,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        properties: Option<EntityProperties>,
+    },
+    Modified {
+        id: EntityId,
+        before: Box<Entity>,
+        after: Box<Entity>,
+    },
+    /// 删除实体；`position` 为删除前的位置，撤销时按此位置连同特性覆盖一起恢复。
+    Removed {
+        id: EntityId,
+        position: usize,
+        entity: Entity,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        properties: Option<EntityProperties>,
+    },
+}
+
+impl Change {
+    #[inline]
+    pub fn id(&self) -> EntityId {
+        match self {
+            Change::Added { id, .. } | Change::Modified { id, .. } | Change::Removed { id, .. } => {
+                *id
+            }
+        }
+    }
+}
+
+/// 一次提交的修订。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Revision {
+    /// 修订号，从 1 开始递增。
+    pub number: u64,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    pub changes: Vec<Change>,
+}
+
+/// 若干修订合并后的净变化：先建后删的实体不出现，新建后又修改的实体只算新建。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangeSummary {
+    pub added: Vec<EntityId>,
+    pub modified: Vec<EntityId>,
+    pub removed: Vec<EntityId>,
+}
+
+impl ChangeSummary {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// 修改日志：已提交的修订与尚未提交的修改。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Journal {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    revisions: Vec<Revision>,
+    /// 已定稿、尚未提交的修改。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    current: Vec<Change>,
+    /// 已登记、尚未定稿的实体及其修改前的数据；新建实体为 `None`。
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pending: HashMap<EntityId, Option<Entity>>,
+}
+
+impl Journal {
+    /// 最近一次提交的修订号；尚未提交过时为 0。
+    #[inline]
+    pub fn revision(&self) -> u64 {
+        self.revisions.last().map_or(0, |revision| revision.number)
+    }
+
+    #[inline]
+    pub fn revisions(&self) -> &[Revision] {
+        &self.revisions
+    }
+
+    /// 已定稿但尚未提交的修改。
+    #[inline]
+    pub fn uncommitted(&self) -> &[Change] {
+        &self.current
+    }
+
+    /// 修订号大于 `revision` 的已提交修改，按发生顺序返回。
+    pub fn changes_since(&self, revision: u64) -> impl Iterator<Item = &Change> {
+        self.revisions
+            .iter()
+            .filter(move |committed| committed.number > revision)
+            .flat_map(|committed| &committed.changes)
+    }
+
+    /// 自 `revision` 以来的净变化，各列表按实体编号排序。
+    pub fn summary_since(&self, revision: u64) -> ChangeSummary {
+        #[derive(Clone, Copy)]
+        enum Net {
+            Added,
+            Modified,
+            Removed,
+        }
+
+        let mut net: HashMap<EntityId, Net> = HashMap::new();
+        for change in self.changes_since(revision) {
+            let id = change.id();
+            let previous = net.get(&id).copied();
+            match (change, previous) {
+                // 删除后又恢复的实体视为修改
+                (Change::Added { .. }, Some(Net::Removed)) => {
+                    net.insert(id, Net::Modified);
+                }
+                (Change::Added { .. }, _) => {
+                    net.insert(id, Net::Added);
+                }
+                (Change::Modified { .. }, Some(Net::Added)) => {}
+                (Change::Modified { .. }, _) => {
+                    net.insert(id, Net::Modified);
+                }
+                (Change::Removed { .. }, Some(Net::Added)) => {
+                    net.remove(&id);
+                }
+                (Change::Removed { .. }, _) => {
+                    net.insert(id, Net::Removed);
+                }
+            }
+        }
+
+        let mut summary = ChangeSummary::default();
+        for (id, state) in net {
+            match state {
+                Net::Added => summary.added.push(id),
+                Net::Modified => summary.modified.push(id),
+                Net::Removed => summary.removed.push(id),
+            }
+        }
+        summary.added.sort_by_key(|id| id.get());
+        summary.modified.sort_by_key(|id| id.get());
+        summary.removed.sort_by_key(|id| id.get());
+        summary
+    }
+}
+
+impl Document {
+    /// 开始记录修改日志；已在记录时保持不变。
+    pub fn enable_journal(&mut self) {
+        if self.journal.is_none() {
+            self.journal = Some(Journal::default());
+        }
+    }
+
+    /// 停止记录并取出日志，其中的登记先行定稿。
+    pub fn take_journal(&mut self) -> Option<Journal> {
+        self.flush_journal();
+        self.journal.take()
+    }
+
+    /// 当前日志；尚未定稿的登记需先调用 [`Document::flush_journal`] 才会出现在记录中。
+    #[inline]
+    pub fn journal(&self) -> Option<&Journal> {
+        self.journal.as_ref()
+    }
+
+    /// 把已登记的实体与其当前数据配对，定稿为未提交的修改。
+    pub fn flush_journal(&mut self) {
+        let Some(journal) = &mut self.journal else {
+            return;
+        };
+        let mut pending: Vec<(usize, EntityId, Option<Entity>)> = journal
+            .pending
+            .drain()
+            .filter_map(|(id, before)| Some((self.entities.position(id)?, id, before)))
+            .collect();
+        // 按位置升序定稿，重放新建记录时各自落回原位
+        pending.sort_by_key(|(position, ..)| *position);
+        for (position, id, before) in pending {
+            let entity = self.entities[position].1.clone();
+            journal.current.push(match before {
+                None => Change::Added {
+                    id,
+                    position,
+                    entity,
+                    properties: self.entity_properties.get(&id).cloned(),
+                },
+                Some(before) => Change::Modified {
+                    id,
+                    before: Box::new(before),
+                    after: Box::new(entity),
+                },
+            });
+        }
+    }
+
+    /// 把未提交的修改提交为新修订，返回修订号；没有修改时不新建修订，返回当前修订号。
+    /// 未记录日志时返回 `None`。
+    pub fn commit_revision(&mut self, description: impl Into<String>) -> Option<u64> {
+        self.flush_journal();
+        let journal = self.journal.as_mut()?;
+        if journal.current.is_empty() {
+            return Some(journal.revision());
+        }
+        let number = journal.revision() + 1;
+        journal.revisions.push(Revision {
+            number,
+            description: description.into(),
+            changes: std::mem::take(&mut journal.current),
+        });
+        Some(number)
+    }
+
+    /// 执行 `edit` 并返回其间的修改记录，供撤销与重做使用；正在记录日志时这些修改同样进入日志。
+    pub fn capture_changes<R>(&mut self, edit: impl FnOnce(&mut Self) -> R) -> (R, Vec<Change>) {
+        self.flush_journal();
+        let outer = self.journal.replace(Journal::default());
+        let result = edit(self);
+        self.flush_journal();
+        let changes = self
+            .journal
+            .take()
+            .map(|journal| journal.current)
+            .unwrap_or_default();
+        self.journal = outer;
+        if let Some(journal) = &mut self.journal {
+            journal.current.extend(changes.iter().cloned());
+        }
+        (result, changes)
+    }
+
+    /// 按顺序重做一组修改。正在记录日志时，这些修改同样会被记录。
+    pub fn apply_changes<'a>(&mut self, changes: impl IntoIterator<Item = &'a Change>) {
+        for change in changes {
+            match change {
+                Change::Added {
+                    id,
+                    position,
+                    entity,
+                    properties,
+                } => self.put_entity(*id, *position, entity.clone(), properties.clone()),
+                Change::Modified { id, after, .. } => {
+                    self.replace_entity(*id, after.as_ref().clone());
+                }
+                Change::Removed { id, .. } => {
+                    self.remove_entity(*id);
+                }
+            }
+        }
+    }
+
+    /// 按逆序撤销一组修改：删除新建的实体，还原修改前的数据，并在原位置恢复被删除的实体。
+    pub fn revert_changes(&mut self, changes: &[Change]) {
+        for change in changes.iter().rev() {
+            match change {
+                Change::Added { id, .. } => {
+                    self.remove_entity(*id);
+                }
+                Change::Modified { id, before, .. } => {
+                    self.replace_entity(*id, before.as_ref().clone());
+                }
+                Change::Removed {
+                    id,
+                    position,
+                    entity,
+                    properties,
+                } => self.put_entity(*id, *position, entity.clone(), properties.clone()),
+            }
+        }
+    }
+
+    /// 崩溃恢复：把 `journal` 中比本文档日志更新的修订以及未提交的修改重放到本文档上，
+    /// 之后改用 `journal` 继续记录。返回重放的记录数。
+    ///
+    /// 本文档应是崩溃前最后保存的图纸；崩溃时尚未定稿的登记没有修改后的数据，无法恢复。
+    pub fn recover_from_journal(&mut self, journal: Journal) -> usize {
+        let base = self.journal.as_ref().map_or(0, Journal::revision);
+        let replay: Vec<Change> = journal
+            .changes_since(base)
+            .chain(&journal.current)
+            .cloned()
+            .collect();
+        // 重放期间不记录，重放结果已包含在 `journal` 中
+        self.journal = None;
+        self.apply_changes(&replay);
+        self.journal = Some(Journal {
+            pending: HashMap::new(),
+            ..journal
+        });
+        replay.len()
+    }
+
+    /// 以指定编号把实体及其特性覆盖放到 `position`；编号已存在时替换其数据。
+    fn put_entity(
+        &mut self,
+        id: EntityId,
+        position: usize,
+        entity: Entity,
+        properties: Option<EntityProperties>,
+    ) {
+        if self.entities.get(id).is_some() {
+            self.replace_entity(id, entity);
+        } else {
+            self.ensure_layer(entity.layer_name());
+            self.next_entity_id = self.next_entity_id.max(id.get() + 1);
+            self.record_created(id);
+            self.entities.insert(position, (id, entity));
+        }
+        if let Some(properties) = properties {
+            self.set_entity_properties(id, properties);
+        }
+    }
+
+    /// 新建实体时调用（实体可在随后加入）：登记来源信息与日志。
+    pub(super) fn record_created(&mut self, id: EntityId) {
+        self.stamp_created(id);
+        if let Some(journal) = &mut self.journal {
+            journal.pending.insert(id, None);
+        }
+    }
+
+    /// 修改实体前调用：登记来源信息，并为日志保存修改前的数据。
+    pub(super) fn record_modified(&mut self, id: EntityId) {
+        self.stamp_modified(id);
+        if let Some(journal) = &mut self.journal
+            && !journal.pending.contains_key(&id)
+            && let Some(before) = self.entities.get(id)
+        {
+            journal.pending.insert(id, Some(before.clone()));
+        }
+    }
+
+    /// 原地批量修改模型空间前调用：登记满足 `touches` 的实体，返回是否有实体需要修改。
+    pub(super) fn record_modified_where(&mut self, touches: impl Fn(&Entity) -> bool) -> bool {
+        let ids: Vec<EntityId> = self
+            .entities
+            .iter()
+            .filter(|(_, entity)| touches(entity))
+            .map(|(id, _)| *id)
+            .collect();
+        for &id in &ids {
+            self.record_modified(id);
+        }
+        !ids.is_empty()
+    }
+
+    /// 删除实体前调用：之前的登记先行定稿，再记录被删除的数据与位置。
+    pub(super) fn record_removed(&mut self, id: EntityId) {
+        self.stamp_removed(id);
+        let Some(journal) = &mut self.journal else {
+            return;
+        };
+        // 本次登记期间新建的实体删除后不留记录
+        if journal.pending.get(&id).is_some_and(Option::is_none) {
+            journal.pending.remove(&id);
+            return;
+        }
+        self.flush_journal();
+        let (Some(journal), Some(position)) = (&mut self.journal, self.entities.position(id))
+        else {
+            return;
+        };
+        journal.current.push(Change::Removed {
+            id,
+            position,
+            entity: self.entities[position].1.clone(),
+            properties: self.entity_properties.get(&id).cloned(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{Point2, Vector2};
+
+    fn start_point(doc: &Document, id: EntityId) -> Option<Point2> {
+        match doc.entity(id)? {
+            Entity::Line(line) => Some(line.start),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn journal_records_revisions_and_reports_net_changes() {
+        let mut doc = Document::new();
+        let kept = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+        let doomed = doc.add_circle(Point2::new(5.0, 5.0), 1.0, "0");
+        doc.enable_journal();
+
+        let line = doc.add_line(Point2::new(0.0, 2.0), Point2::new(1.0, 2.0), "0");
+        doc.move_entities([line, kept], Vector2::new(1.0, 0.0));
+        doc.move_entities([kept], Vector2::new(1.0, 0.0));
+        assert_eq!(doc.commit_revision("draw"), Some(1));
+        let first = &doc.journal().unwrap().revisions()[0];
+        assert_eq!(first.description, "draw");
+        // 同一实体的多次修改合并为一条
+        assert_eq!(first.changes.len(), 2);
+
+        let scratch = doc.add_circle(Point2::new(9.0, 9.0), 1.0, "0");
+        doc.remove_entity(scratch);
+        doc.remove_entity(doomed);
+        doc.move_entities([line], Vector2::new(0.0, 1.0));
+        assert_eq!(doc.commit_revision("tidy"), Some(2));
+        assert_eq!(doc.commit_revision("nothing"), Some(2));
+
+        let journal = doc.journal().unwrap();
+        let since_start = journal.summary_since(0);
+        assert_eq!(since_start.added, vec![line]);
+        assert_eq!(since_start.modified, vec![kept]);
+        assert_eq!(since_start.removed, vec![doomed]);
+        let since_first = journal.summary_since(1);
+        assert!(since_first.added.is_empty());
+        assert_eq!(since_first.modified, vec![line]);
+        assert_eq!(since_first.removed, vec![doomed]);
+        assert!(journal.summary_since(2).is_empty());
+    }
+
+    #[test]
+    fn changes_revert_and_replay_in_place() {
+        let mut doc = Document::new();
+        let first = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+        let middle = doc.add_line(Point2::new(0.0, 1.0), Point2::new(1.0, 1.0), "0");
+        let last = doc.add_line(Point2::new(0.0, 2.0), Point2::new(1.0, 2.0), "0");
+        doc.enable_journal();
+
+        doc.move_entities([first], Vector2::new(3.0, 0.0));
+        doc.remove_entity(middle);
+        let added = doc.add_circle(Point2::new(0.0, 0.0), 1.0, "0");
+        doc.commit_revision("edit");
+        let changes = doc.journal().unwrap().revisions()[0].changes.clone();
+
+        doc.revert_changes(&changes);
+        let order: Vec<EntityId> = doc.entities().map(|(id, _)| *id).collect();
+        assert_eq!(order, vec![first, middle, last]);
+        assert_eq!(start_point(&doc, first), Some(Point2::new(0.0, 0.0)));
+        assert!(doc.entity(added).is_none());
+
+        doc.apply_changes(&changes);
+        let order: Vec<EntityId> = doc.entities().map(|(id, _)| *id).collect();
+        assert_eq!(order, vec![first, last, added]);
+        assert_eq!(start_point(&doc, first), Some(Point2::new(3.0, 0.0)));
+    }
+
+    #[test]
+    fn newer_journal_recovers_saved_document() {
+        let mut doc = Document::new();
+        doc.enable_journal();
+        let line = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+        doc.commit_revision("saved");
+        let saved = doc.clone();
+
+        doc.move_entities([line], Vector2::new(2.0, 0.0));
+        doc.commit_revision("lost");
+        let circle = doc.add_circle(Point2::new(0.0, 0.0), 1.0, "walls");
+        doc.flush_journal();
+        let journal = doc.journal().unwrap().clone();
+
+        let mut recovered = saved;
+        assert_eq!(recovered.recover_from_journal(journal), 2);
+        assert_eq!(start_point(&recovered, line), Some(Point2::new(2.0, 0.0)));
+        assert!(recovered.entity(circle).is_some());
+        assert!(recovered.layer("walls").is_some());
+        assert_eq!(recovered.journal().unwrap().revision(), 2);
+        // 新实体不会复用恢复出的编号
+        assert!(recovered.add_circle(Point2::new(0.0, 0.0), 2.0, "0").get() > circle.get());
+    }
+}
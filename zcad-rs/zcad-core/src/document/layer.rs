@@ -0,0 +1,442 @@
+//! 图层管理：重命名、删除、合并图层，以及修改图层的默认颜色、线型与线宽。
+//!
+//! 按名称查找图层时先精确匹配，找不到再忽略大小写。图层上的对象包括模型空间与全部块定义中的实体、块参照的属性
+//! 以及块的属性定义，重命名与合并时一并改到新图层。`0` 与 `Defpoints` 图层不能重命名、删除或并入其他图层。
+
+use std::fmt;
+
+use super::purge::PROTECTED_LAYERS;
+use super::{
+    Document, Entity, EntityId, LINETYPE_BYBLOCK, LINETYPE_BYLAYER, Layer, LineWeight, ObjectColor,
+};
+
+/// 图层名中不允许出现的字符。
+const INVALID_NAME_CHARS: &[char] = &['<', '>', '/', '\\', '"', ':', ';', '?', '*', '|', '=', '`'];
+
+/// 删除仍有对象的图层时的处理方式。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum LayerDeletePolicy {
+    /// 图层上仍有对象时拒绝删除。
+    #[default]
+    RejectIfUsed,
+    /// 把图层上的对象移到指定图层后删除。
+    MoveTo(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayerError {
+    NotFound(String),
+    AlreadyExists(String),
+    /// 图层名为空或含有不允许的字符。
+    InvalidName(String),
+    /// `0` 与 `Defpoints` 图层。
+    Protected(String),
+    /// 图层上仍有对象。
+    InUse(String),
+    /// 对象不能移到正被删除或合并的图层本身。
+    InvalidTarget(String),
+    /// 图层的默认特性不能为随层或随块。
+    InvalidProperty,
+}
+
+impl fmt::Display for LayerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayerError::NotFound(name) => write!(f, "layer {name} not found"),
+            LayerError::AlreadyExists(name) => write!(f, "layer {name} already exists"),
+            LayerError::InvalidName(name) => write!(f, "invalid layer name {name:?}"),
+            LayerError::Protected(name) => write!(f, "layer {name} cannot be modified"),
+            LayerError::InUse(name) => write!(f, "layer {name} still contains objects"),
+            LayerError::InvalidTarget(name) => {
+                write!(f, "objects cannot be moved onto layer {name}")
+            }
+            LayerError::InvalidProperty => {
+                write!(f, "layer properties cannot be ByLayer or ByBlock")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LayerError {}
+
+impl Document {
+    /// 重命名图层并更新全部引用；只改变大小写也视为重命名。
+    pub fn rename_layer(&mut self, name: &str, new_name: &str) -> Result<(), LayerError> {
+        let key = self.protected_layer_key(name)?;
+        validate_layer_name(new_name)?;
+        if let Some(existing) = self.layer_key(new_name)
+            && existing != key
+        {
+            return Err(LayerError::AlreadyExists(existing));
+        }
+        if let Some(mut layer) = self.layers.remove(&key) {
+            layer.name = new_name.to_string();
+            self.layers.insert(new_name.to_string(), layer);
+        }
+        self.rename_layer_in_states(&key, new_name);
+        self.reassign_layers(&[key], new_name);
+        Ok(())
+    }
+
+    /// 删除图层，返回移到其他图层的对象数。
+    pub fn delete_layer(
+        &mut self,
+        name: &str,
+        policy: &LayerDeletePolicy,
+    ) -> Result<usize, LayerError> {
+        let key = self.protected_layer_key(name)?;
+        let moved = match policy {
+            LayerDeletePolicy::RejectIfUsed => {
+                if self.layer_reference_count(&key) > 0 {
+                    return Err(LayerError::InUse(key));
+                }
+                0
+            }
+            LayerDeletePolicy::MoveTo(target) => {
+                let target = self
+                    .layer_key(target)
+                    .ok_or_else(|| LayerError::NotFound(target.clone()))?;
+                if target == key {
+                    return Err(LayerError::InvalidTarget(target));
+                }
+                self.reassign_layers(std::slice::from_ref(&key), &target)
+            }
+        };
+        self.layers.remove(&key);
+        Ok(moved)
+    }
+
+    /// 把若干图层上的对象并入目标图层并删除这些图层，返回移动的对象数。失败时文档不变。
+    pub fn merge_layers(&mut self, sources: &[&str], target: &str) -> Result<usize, LayerError> {
+        let target = self
+            .layer_key(target)
+            .ok_or_else(|| LayerError::NotFound(target.to_string()))?;
+        let mut keys = Vec::with_capacity(sources.len());
+        for source in sources {
+            let key = self.protected_layer_key(source)?;
+            if key == target {
+                return Err(LayerError::InvalidTarget(target));
+            }
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        let moved = self.reassign_layers(&keys, &target);
+        for key in &keys {
+            self.layers.remove(key);
+        }
+        Ok(moved)
+    }
+
+    pub fn set_layer_color(&mut self, name: &str, color: ObjectColor) -> Result<(), LayerError> {
+        if matches!(color, ObjectColor::ByLayer | ObjectColor::ByBlock) {
+            return Err(LayerError::InvalidProperty);
+        }
+        self.layer_record_mut(name)?.color = color;
+        Ok(())
+    }
+
+    pub fn set_layer_linetype(
+        &mut self,
+        name: &str,
+        linetype: impl Into<String>,
+    ) -> Result<(), LayerError> {
+        let linetype = linetype.into();
+        if linetype.is_empty()
+            || linetype.eq_ignore_ascii_case(LINETYPE_BYLAYER)
+            || linetype.eq_ignore_ascii_case(LINETYPE_BYBLOCK)
+        {
+            return Err(LayerError::InvalidProperty);
+        }
+        self.layer_record_mut(name)?.linetype = linetype;
+        Ok(())
+    }
+
+    pub fn set_layer_lineweight(
+        &mut self,
+        name: &str,
+        lineweight: LineWeight,
+    ) -> Result<(), LayerError> {
+        if matches!(lineweight, LineWeight::ByLayer | LineWeight::ByBlock) {
+            return Err(LayerError::InvalidProperty);
+        }
+        self.layer_record_mut(name)?.lineweight = lineweight;
+        Ok(())
+    }
+
+    /// 图层表中的键，优先精确匹配，其次忽略大小写。
+    fn layer_key(&self, name: &str) -> Option<String> {
+        if self.layers.contains_key(name) {
+            return Some(name.to_string());
+        }
+        self.layers
+            .keys()
+            .find(|key| key.eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    fn protected_layer_key(&self, name: &str) -> Result<String, LayerError> {
+        let key = self
+            .layer_key(name)
+            .ok_or_else(|| LayerError::NotFound(name.to_string()))?;
+        if PROTECTED_LAYERS
+            .iter()
+            .any(|protected| key.eq_ignore_ascii_case(protected))
+        {
+            return Err(LayerError::Protected(key));
+        }
+        Ok(key)
+    }
+
+    fn layer_record_mut(&mut self, name: &str) -> Result<&mut Layer, LayerError> {
+        let key = self
+            .layer_key(name)
+            .ok_or_else(|| LayerError::NotFound(name.to_string()))?;
+        self.layers
+            .get_mut(&key)
+            .ok_or_else(|| LayerError::NotFound(name.to_string()))
+    }
+
+    /// 引用该图层的实体、属性与属性定义数量。
+    fn layer_reference_count(&self, name: &str) -> usize {
+        let on_layer = |layer: &str| layer == name;
+        let entities: usize = self
+            .entities
+            .iter()
+            .map(|(_, entity)| entity)
+            .chain(self.blocks.values().flat_map(|block| &block.entities))
+            .map(|entity| layer_count(entity, &on_layer))
+            .sum();
+        entities
+            + self
+                .blocks
+                .values()
+                .flat_map(|block| &block.attributes)
+                .filter(|definition| on_layer(&definition.layer))
+                .count()
+    }
+
+    /// 把位于 `from` 中任一图层上的对象改到 `to`，返回改动的对象数。
+    fn reassign_layers(&mut self, from: &[String], to: &str) -> usize {
+        let on_source = |layer: &str| from.iter().any(|name| name == layer);
+        let mut moved = 0;
+        let touched: Vec<EntityId> = self
+            .entities
+            .iter()
+            .filter(|(_, entity)| layer_count(entity, &on_source) > 0)
+            .map(|(id, _)| *id)
+            .collect();
+        for &id in &touched {
+            self.record_modified(id);
+        }
+        // 没有需要改动的对象时不触发模型空间的索引重建
+        if !touched.is_empty() {
+            for (_, entity) in self.entities.iter_mut() {
+                moved += reassign_entity(entity, &on_source, to);
+            }
+        }
+        for block in self.blocks.values_mut() {
+            for entity in &mut block.entities {
+                moved += reassign_entity(entity, &on_source, to);
+            }
+            for definition in &mut block.attributes {
+                if on_source(&definition.layer) {
+                    definition.layer = to.to_string();
+                    moved += 1;
+                }
+            }
+        }
+        moved
+    }
+}
+
+/// 实体及其属性中位于匹配图层上的对象数。
+fn layer_count(entity: &Entity, on_layer: &impl Fn(&str) -> bool) -> usize {
+    let attributes = match entity {
+        Entity::BlockReference(reference) => reference
+            .attributes
+            .iter()
+            .filter(|attribute| on_layer(&attribute.layer))
+            .count(),
+        _ => 0,
+    };
+    usize::from(on_layer(entity.layer_name())) + attributes
+}
+
+fn reassign_entity(entity: &mut Entity, on_source: &impl Fn(&str) -> bool, to: &str) -> usize {
+    let count = layer_count(entity, on_source);
+    if on_source(entity.layer_name()) {
+        entity.set_layer_name(to);
+    }
+    if let Entity::BlockReference(reference) = entity {
+        for attribute in &mut reference.attributes {
+            if on_source(&attribute.layer) {
+                attribute.layer = to.to_string();
+            }
+        }
+    }
+    count
+}
+
+fn validate_layer_name(name: &str) -> Result<(), LayerError> {
+    if name.trim().is_empty() || name.contains(INVALID_NAME_CHARS) {
+        return Err(LayerError::InvalidName(name.to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{Attribute, BlockDefinition};
+    use crate::geometry::{Point2, Vector2};
+
+    fn attribute(layer: &str) -> Attribute {
+        Attribute {
+            tag: "TAG".to_string(),
+            text: "1".to_string(),
+            insert: Point2::new(0.0, 0.0),
+            height: 1.0,
+            rotation: 0.0,
+            width_factor: 1.0,
+            oblique: 0.0,
+            style: None,
+            prompt: None,
+            alignment: None,
+            horizontal_align: 0,
+            vertical_align: 0,
+            line_spacing_factor: 1.0,
+            line_spacing_style: 0,
+            is_invisible: false,
+            is_constant: false,
+            is_verify: false,
+            is_preset: false,
+            lock_position: false,
+            layer: layer.to_string(),
+        }
+    }
+
+    fn sample() -> Document {
+        let mut doc = Document::new();
+        doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "WALL");
+        doc.add_circle(Point2::new(0.0, 0.0), 1.0, "DOOR");
+        doc.add_entity(Entity::BlockReference(crate::document::BlockReference {
+            name: "TAG".to_string(),
+            insert: Point2::new(0.0, 0.0),
+            scale: Vector2::new(1.0, 1.0),
+            rotation: 0.0,
+            attributes: vec![attribute("WALL")],
+            layer: "0".to_string(),
+        }));
+        let line = Entity::Line(crate::document::Line {
+            start: Point2::new(0.0, 0.0),
+            end: Point2::new(0.0, 1.0),
+            layer: "DOOR".to_string(),
+        });
+        doc.add_block_definition(BlockDefinition {
+            name: "TAG".to_string(),
+            base_point: Point2::new(0.0, 0.0),
+            entities: vec![line],
+            attributes: Vec::new(),
+        });
+        doc
+    }
+
+    fn layers_of(doc: &Document) -> Vec<String> {
+        let mut names: Vec<String> = doc.layers().map(|layer| layer.name.clone()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn rename_updates_entities_attributes_and_blocks() {
+        let mut doc = sample();
+        doc.rename_layer("wall", "A-WALL").unwrap();
+        assert_eq!(layers_of(&doc), ["0", "A-WALL", "DOOR"]);
+        assert_eq!(doc.entities_on_layer("A-WALL").count(), 1);
+        let Some(Entity::BlockReference(reference)) = doc.entities().nth(2).map(|(_, e)| e) else {
+            panic!("expected block reference");
+        };
+        assert_eq!(reference.attributes[0].layer, "A-WALL");
+
+        assert_eq!(
+            doc.rename_layer("DOOR", "a-wall"),
+            Err(LayerError::AlreadyExists("A-WALL".to_string()))
+        );
+        assert_eq!(
+            doc.rename_layer("DOOR", "A|DOOR"),
+            Err(LayerError::InvalidName("A|DOOR".to_string()))
+        );
+        assert_eq!(
+            doc.rename_layer("0", "BASE"),
+            Err(LayerError::Protected("0".to_string()))
+        );
+        doc.rename_layer("door", "Door").unwrap();
+        assert_eq!(doc.block("TAG").unwrap().entities[0].layer_name(), "Door");
+    }
+
+    #[test]
+    fn delete_rejects_used_layers_or_moves_their_objects() {
+        let mut doc = sample();
+        assert_eq!(
+            doc.delete_layer("DOOR", &LayerDeletePolicy::default()),
+            Err(LayerError::InUse("DOOR".to_string()))
+        );
+        assert_eq!(
+            doc.delete_layer("DOOR", &LayerDeletePolicy::MoveTo("door".to_string())),
+            Err(LayerError::InvalidTarget("DOOR".to_string()))
+        );
+        let moved = doc
+            .delete_layer("DOOR", &LayerDeletePolicy::MoveTo("0".to_string()))
+            .unwrap();
+        assert_eq!(moved, 2);
+        assert_eq!(layers_of(&doc), ["0", "WALL"]);
+
+        doc.ensure_layer("EMPTY");
+        assert_eq!(
+            doc.delete_layer("EMPTY", &LayerDeletePolicy::default()),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn merge_moves_objects_and_removes_sources() {
+        let mut doc = sample();
+        doc.ensure_layer("ARCH");
+        assert_eq!(
+            doc.merge_layers(&["WALL", "0"], "ARCH"),
+            Err(LayerError::Protected("0".to_string()))
+        );
+        assert_eq!(layers_of(&doc), ["0", "ARCH", "DOOR", "WALL"]);
+        let moved = doc.merge_layers(&["WALL", "DOOR", "wall"], "arch").unwrap();
+        assert_eq!(moved, 4);
+        assert_eq!(layers_of(&doc), ["0", "ARCH"]);
+        assert_eq!(doc.entities_on_layer("ARCH").count(), 2);
+    }
+
+    #[test]
+    fn property_setters_reject_by_layer_values() {
+        let mut doc = sample();
+        doc.set_layer_color("wall", ObjectColor::Index(3)).unwrap();
+        doc.set_layer_linetype("WALL", "DASHED").unwrap();
+        doc.set_layer_lineweight("WALL", LineWeight::Hundredths(35))
+            .unwrap();
+        let layer = doc.layer("WALL").unwrap();
+        assert_eq!(layer.color, ObjectColor::Index(3));
+        assert_eq!(layer.linetype, "DASHED");
+        assert_eq!(layer.lineweight, LineWeight::Hundredths(35));
+
+        assert_eq!(
+            doc.set_layer_color("WALL", ObjectColor::ByLayer),
+            Err(LayerError::InvalidProperty)
+        );
+        assert_eq!(
+            doc.set_layer_linetype("WALL", "ByBlock"),
+            Err(LayerError::InvalidProperty)
+        );
+        assert_eq!(
+            doc.set_layer_lineweight("MISSING", LineWeight::Default),
+            Err(LayerError::NotFound("MISSING".to_string()))
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! 图层状态：以名称保存各图层的开关、冻结、锁定与颜色，之后可一次恢复，用于在不同的出图设置间切换。
+//!
+//! 恢复时只修改状态中记录且当前仍存在的图层；状态保存之后新建的图层保持不变。
+//! DXF 中图层状态保存在 `ACAD_LAYERSTATES` 字典的 XRECORD 中。
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Document, Layer, ObjectColor};
+
+/// 单个图层在某一状态中的设置。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayerStateEntry {
+    pub layer: String,
+    pub is_visible: bool,
+    pub is_frozen: bool,
+    pub is_locked: bool,
+    pub color: ObjectColor,
+}
+
+impl LayerStateEntry {
+    pub fn capture(layer: &Layer) -> Self {
+        Self {
+            layer: layer.name.clone(),
+            is_visible: layer.is_visible,
+            is_frozen: layer.is_frozen,
+            is_locked: layer.is_locked,
+            color: layer.color,
+        }
+    }
+
+    fn apply(&self, layer: &mut Layer) {
+        layer.is_visible = self.is_visible;
+        layer.is_frozen = self.is_frozen;
+        layer.is_locked = self.is_locked;
+        layer.color = self.color;
+    }
+}
+
+/// 命名图层状态，图层按名称排序。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayerState {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    pub layers: Vec<LayerStateEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayerStateError {
+    NotFound(String),
+    /// 名称为空。
+    InvalidName,
+}
+
+impl fmt::Display for LayerStateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayerStateError::NotFound(name) => write!(f, "layer state {name} not found"),
+            LayerStateError::InvalidName => write!(f, "layer state name cannot be empty"),
+        }
+    }
+}
+
+impl std::error::Error for LayerStateError {}
+
+impl Document {
+    /// 以当前全部图层的设置保存（或覆盖同名的）图层状态。
+    pub fn save_layer_state(
+        &mut self,
+        name: &str,
+        description: impl Into<String>,
+    ) -> Result<(), LayerStateError> {
+        if name.trim().is_empty() {
+            return Err(LayerStateError::InvalidName);
+        }
+        let mut layers: Vec<LayerStateEntry> =
+            self.layers.values().map(LayerStateEntry::capture).collect();
+        layers.sort_by(|a, b| a.layer.cmp(&b.layer));
+        self.remove_layer_state(name);
+        self.add_layer_state(LayerState {
+            name: name.to_string(),
+            description: description.into(),
+            layers,
+        });
+        Ok(())
+    }
+
+    /// 恢复图层状态，返回被修改的图层数。
+    pub fn restore_layer_state(&mut self, name: &str) -> Result<usize, LayerStateError> {
+        let state = self
+            .layer_state(name)
+            .cloned()
+            .ok_or_else(|| LayerStateError::NotFound(name.to_string()))?;
+        let mut restored = 0;
+        for entry in &state.layers {
+            if let Some(layer) = self.layers.get_mut(&entry.layer) {
+                entry.apply(layer);
+                restored += 1;
+            }
+        }
+        Ok(restored)
+    }
+
+    /// 写入图层状态，同名（不区分大小写）状态会被替换。
+    pub fn add_layer_state(&mut self, state: LayerState) {
+        self.remove_layer_state(&state.name);
+        self.layer_states.insert(state.name.clone(), state);
+    }
+
+    /// 删除图层状态，返回被删除的状态。
+    pub fn remove_layer_state(&mut self, name: &str) -> Option<LayerState> {
+        let key = self.layer_state(name)?.name.clone();
+        self.layer_states.remove(&key)
+    }
+
+    /// 按名称查找图层状态，名称不区分大小写。
+    pub fn layer_state(&self, name: &str) -> Option<&LayerState> {
+        self.layer_states.get(name).or_else(|| {
+            self.layer_states
+                .values()
+                .find(|state| state.name.eq_ignore_ascii_case(name))
+        })
+    }
+
+    #[inline]
+    pub fn layer_states(&self) -> impl Iterator<Item = &LayerState> {
+        self.layer_states.values()
+    }
+
+    /// 图层改名后同步更新各状态中的图层名。
+    pub(super) fn rename_layer_in_states(&mut self, from: &str, to: &str) {
+        for state in self.layer_states.values_mut() {
+            for entry in &mut state.layers {
+                if entry.layer == from {
+                    entry.layer = to.to_string();
+                }
+            }
+            state.layers.sort_by(|a, b| a.layer.cmp(&b.layer));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Point2;
+
+    #[test]
+    fn restore_brings_back_saved_layer_settings() {
+        let mut doc = Document::new();
+        doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "WALL");
+        doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "NOTES");
+        doc.save_layer_state("Full", "all layers on").unwrap();
+
+        let notes = doc.layer_mut("NOTES").unwrap();
+        notes.is_frozen = true;
+        notes.color = ObjectColor::Index(2);
+        doc.layer_mut("WALL").unwrap().is_locked = true;
+        doc.save_layer_state("Plot", "").unwrap();
+        assert_eq!(doc.visible_entities().count(), 1);
+
+        assert_eq!(doc.restore_layer_state("FULL"), Ok(3));
+        let notes = doc.layer("NOTES").unwrap();
+        assert!(notes.is_displayed());
+        assert_eq!(notes.color, ObjectColor::Index(7));
+        assert!(!doc.layer("WALL").unwrap().is_locked);
+        assert_eq!(doc.visible_entities().count(), 2);
+
+        doc.ensure_layer("NEW");
+        doc.layer_mut("NEW").unwrap().is_visible = false;
+        assert_eq!(doc.restore_layer_state("plot"), Ok(3));
+        assert!(doc.layer("NOTES").unwrap().is_frozen);
+        assert!(!doc.layer("NEW").unwrap().is_visible);
+    }
+
+    #[test]
+    fn states_are_replaced_case_insensitively_and_follow_renames() {
+        let mut doc = Document::new();
+        doc.ensure_layer("WALL");
+        doc.save_layer_state("Plot", "first").unwrap();
+        doc.save_layer_state("PLOT", "second").unwrap();
+        assert_eq!(doc.layer_states().count(), 1);
+        assert_eq!(doc.layer_state("plot").unwrap().description, "second");
+        assert_eq!(
+            doc.save_layer_state(" ", ""),
+            Err(LayerStateError::InvalidName)
+        );
+        assert_eq!(
+            doc.restore_layer_state("Missing"),
+            Err(LayerStateError::NotFound("Missing".to_string()))
+        );
+
+        doc.rename_layer("WALL", "A-WALL").unwrap();
+        let names: Vec<&str> = doc
+            .layer_state("Plot")
+            .unwrap()
+            .layers
+            .iter()
+            .map(|entry| entry.layer.as_str())
+            .collect();
+        assert_eq!(names, ["0", "A-WALL"]);
+        assert!(doc.remove_layer_state("plot").is_some());
+        assert_eq!(doc.layer_states().count(), 0);
+    }
+}
@@ -0,0 +1,144 @@
+//! 线型表（LTYPE），包括嵌入文字或型的复杂线型元素。
+
+use serde::{Deserialize, Serialize};
+
+use super::{Document, EntityId, LINETYPE_BYBLOCK, LINETYPE_BYLAYER};
+use crate::geometry::Vector2;
+
+/// 线型定义：按顺序重复的划线、空白与点。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Linetype {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// 一个完整图案的长度（DXF 组码 40）。
+    pub pattern_length: f64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub elements: Vec<LinetypeElement>,
+}
+
+impl Linetype {
+    /// 含嵌入文字或型的元素时为复杂线型。
+    pub fn is_complex(&self) -> bool {
+        self.elements
+            .iter()
+            .any(|element| element.embedded.is_some())
+    }
+
+    /// 没有元素的线型为实线。
+    pub fn is_continuous(&self) -> bool {
+        self.elements.is_empty()
+    }
+}
+
+/// 线型图案中的一个元素（DXF 组码 49）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinetypeElement {
+    /// 正值为划线，负值为空白，零为点。
+    pub length: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedded: Option<LinetypeEmbedded>,
+}
+
+/// 复杂线型元素中嵌入的文字或型（DXF 组码 74 位 2/4）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinetypeEmbedded {
+    pub content: LinetypeEmbeddedContent,
+    /// 文字样式或型文件的 STYLE 句柄（组码 340）。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style_handle: Option<String>,
+    /// 比例（组码 46）。
+    pub scale: f64,
+    /// 旋转角（弧度，组码 50）。
+    pub rotation: f64,
+    /// 组码 74 位 1：旋转角相对世界坐标而非线的方向。
+    #[serde(default)]
+    pub is_absolute_rotation: bool,
+    /// 相对元素起点的偏移（组码 44/45）。
+    pub offset: Vector2,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinetypeEmbeddedContent {
+    /// 嵌入文字（组码 9），例如 GAS_LINE 中的 “GAS”。
+    Text(String),
+    /// 型文件中的型编号（组码 75）。
+    Shape(i16),
+}
+
+impl Document {
+    /// 写入线型表记录，同名线型会被替换。
+    pub fn add_linetype(&mut self, linetype: Linetype) {
+        self.linetypes.insert(linetype.name.clone(), linetype);
+    }
+
+    /// 按名称查找线型，名称不区分大小写。
+    pub fn linetype(&self, name: &str) -> Option<&Linetype> {
+        self.linetypes.get(name).or_else(|| {
+            self.linetypes
+                .values()
+                .find(|linetype| linetype.name.eq_ignore_ascii_case(name))
+        })
+    }
+
+    #[inline]
+    pub fn linetypes(&self) -> impl Iterator<Item = &Linetype> {
+        self.linetypes.values()
+    }
+
+    /// 实体最终生效的线型定义；实线或线型表中缺少定义时返回 `None`。
+    pub fn resolved_linetype(&self, id: EntityId) -> Option<&Linetype> {
+        let properties = self.resolve_properties(id)?;
+        if properties.linetype.eq_ignore_ascii_case(LINETYPE_BYLAYER)
+            || properties.linetype.eq_ignore_ascii_case(LINETYPE_BYBLOCK)
+        {
+            return None;
+        }
+        self.linetype(&properties.linetype)
+            .filter(|linetype| !linetype.is_continuous())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Point2;
+
+    #[test]
+    fn resolved_linetype_follows_layer_default() {
+        let mut doc = Document::new();
+        doc.add_linetype(Linetype {
+            name: "GAS_LINE".to_string(),
+            description: "Gas line ----GAS----".to_string(),
+            pattern_length: 0.7,
+            elements: vec![
+                LinetypeElement {
+                    length: 0.5,
+                    embedded: None,
+                },
+                LinetypeElement {
+                    length: -0.2,
+                    embedded: Some(LinetypeEmbedded {
+                        content: LinetypeEmbeddedContent::Text("GAS".to_string()),
+                        style_handle: Some("11".to_string()),
+                        scale: 0.1,
+                        rotation: 0.0,
+                        is_absolute_rotation: false,
+                        offset: Vector2::new(-0.1, -0.05),
+                    }),
+                },
+            ],
+        });
+        let id = doc.add_line(Point2::new(0.0, 0.0), Point2::new(5.0, 0.0), "UTIL");
+        assert!(
+            doc.resolved_linetype(id).is_none(),
+            "默认实线不应解析出线型"
+        );
+
+        doc.layer_mut("UTIL").unwrap().linetype = "Gas_Line".to_string();
+        let linetype = doc.resolved_linetype(id).expect("应解析到图层线型");
+        assert!(linetype.is_complex());
+        assert_eq!(linetype.elements.len(), 2);
+    }
+}
@@ -0,0 +1,280 @@
+//! 特性匹配（MATCHPROP，格式刷）：把源实体的图层、颜色、线型、线宽以及文字、填充与标注特性
+//! 批量复制到目标实体，用于统一图面规范。
+//!
+//! 通用特性适用于所有实体；文字样式与字高只在源与目标都是文字（单行文字或多行文字）时复制，
+//! 填充图案只在两者都是填充时复制，标注样式只在两者都是标注或引线时复制。
+//! 位于锁定图层上的目标不被修改。
+
+use std::fmt;
+
+use super::{Document, Entity, EntityId};
+
+/// 参与匹配的特性，默认全部复制。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PropertyMask {
+    pub layer: bool,
+    pub color: bool,
+    pub linetype: bool,
+    pub lineweight: bool,
+    /// 多行文字的文字样式。单行文字的样式未建模，不受影响。
+    pub text_style: bool,
+    pub text_height: bool,
+    /// 填充图案、实体填充标志、渐变色与孤岛检测样式；边界与关联性保持不变。
+    pub hatch_pattern: bool,
+    /// 标注与引线的标注样式名。
+    pub dimension_style: bool,
+}
+
+impl Default for PropertyMask {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl PropertyMask {
+    pub const fn all() -> Self {
+        Self {
+            layer: true,
+            color: true,
+            linetype: true,
+            lineweight: true,
+            text_style: true,
+            text_height: true,
+            hatch_pattern: true,
+            dimension_style: true,
+        }
+    }
+
+    pub const fn none() -> Self {
+        Self {
+            layer: false,
+            color: false,
+            linetype: false,
+            lineweight: false,
+            text_style: false,
+            text_height: false,
+            hatch_pattern: false,
+            dimension_style: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchPropertiesError {
+    SourceNotFound(EntityId),
+}
+
+impl fmt::Display for MatchPropertiesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatchPropertiesError::SourceNotFound(id) => {
+                write!(f, "source entity {} not found", id.get())
+            }
+        }
+    }
+}
+
+impl std::error::Error for MatchPropertiesError {}
+
+impl Document {
+    /// 把源实体中 `mask` 选中的特性复制到各目标实体。不存在的目标、源实体自身以及锁定图层上的
+    /// 目标被跳过；返回处理的目标数。
+    pub fn match_properties(
+        &mut self,
+        source_id: EntityId,
+        target_ids: &[EntityId],
+        mask: PropertyMask,
+    ) -> Result<usize, MatchPropertiesError> {
+        let source = self
+            .entity(source_id)
+            .ok_or(MatchPropertiesError::SourceNotFound(source_id))?
+            .clone();
+        let source_properties = self
+            .entity_properties(source_id)
+            .cloned()
+            .unwrap_or_default();
+        if mask.layer {
+            self.ensure_layer(source.layer_name());
+        }
+
+        let mut matched = 0;
+        for &target_id in target_ids {
+            if target_id == source_id {
+                continue;
+            }
+            let Some(target) = self.entity(target_id) else {
+                continue;
+            };
+            if self
+                .layer(target.layer_name())
+                .is_some_and(|layer| layer.is_locked)
+            {
+                continue;
+            }
+
+            let mut updated = target.clone();
+            if mask.layer {
+                updated.set_layer_name(source.layer_name());
+            }
+            copy_specific(&source, &mut updated, mask);
+            self.record_modified(target_id);
+            *self.entities.get_mut(target_id).expect("已检查实体存在") = updated;
+
+            let mut properties = self
+                .entity_properties(target_id)
+                .cloned()
+                .unwrap_or_default();
+            if mask.color {
+                properties.color = source_properties.color;
+            }
+            if mask.linetype {
+                properties.linetype = source_properties.linetype.clone();
+            }
+            if mask.lineweight {
+                properties.lineweight = source_properties.lineweight;
+            }
+            self.set_entity_properties(target_id, properties);
+            matched += 1;
+        }
+        Ok(matched)
+    }
+}
+
+/// 复制与实体类型相关的特性，类型不匹配时不做修改。
+fn copy_specific(source: &Entity, target: &mut Entity, mask: PropertyMask) {
+    if mask.text_height
+        && let Some(height) = text_height(source)
+    {
+        match target {
+            Entity::Text(text) => text.height = height,
+            Entity::MText(mtext) => mtext.height = height,
+            _ => {}
+        }
+    }
+    if mask.text_style
+        && let (Entity::MText(source), Entity::MText(target)) = (source, &mut *target)
+    {
+        target.style = source.style.clone();
+    }
+    if mask.hatch_pattern
+        && let (Entity::Hatch(source), Entity::Hatch(target)) = (source, &mut *target)
+    {
+        target.pattern_name = source.pattern_name.clone();
+        target.is_solid = source.is_solid;
+        target.pattern_lines = source.pattern_lines.clone();
+        target.gradient = source.gradient.clone();
+        target.style = source.style;
+    }
+    if mask.dimension_style
+        && let Some(style) = dimension_style(source)
+    {
+        match target {
+            Entity::Dimension(dimension) => dimension.style_name = style.clone(),
+            Entity::Leader(leader) => leader.style_name = style.clone(),
+            _ => {}
+        }
+    }
+}
+
+fn text_height(entity: &Entity) -> Option<f64> {
+    match entity {
+        Entity::Text(text) => Some(text.height),
+        Entity::MText(mtext) => Some(mtext.height),
+        _ => None,
+    }
+}
+
+fn dimension_style(entity: &Entity) -> Option<&Option<String>> {
+    match entity {
+        Entity::Dimension(dimension) => Some(&dimension.style_name),
+        Entity::Leader(leader) => Some(&leader.style_name),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{EntityProperties, LineWeight, ObjectColor};
+    use crate::geometry::Point2;
+
+    #[test]
+    fn copies_common_properties_to_every_target() {
+        let mut doc = Document::new();
+        let source = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "A-WALL");
+        doc.set_entity_properties(
+            source,
+            EntityProperties {
+                color: ObjectColor::Index(1),
+                linetype: "DASHED".to_string(),
+                lineweight: LineWeight::ByLayer,
+            },
+        );
+        let circle = doc.add_circle(Point2::new(5.0, 5.0), 1.0, "0");
+        let text = doc.add_text(Point2::new(0.0, 0.0), "N", 2.5, 0.0, "0");
+        doc.set_entity_properties(
+            text,
+            EntityProperties {
+                lineweight: LineWeight::Hundredths(50),
+                ..EntityProperties::default()
+            },
+        );
+
+        let matched = doc
+            .match_properties(source, &[circle, text, source], PropertyMask::default())
+            .unwrap();
+        assert_eq!(matched, 2);
+        for id in [circle, text] {
+            assert_eq!(doc.entity(id).unwrap().layer_name(), "A-WALL");
+            let properties = doc.entity_properties(id).unwrap();
+            assert_eq!(properties.color, ObjectColor::Index(1));
+            assert_eq!(properties.linetype, "DASHED");
+            assert_eq!(properties.lineweight, LineWeight::ByLayer);
+        }
+
+        let by_layer = doc.add_line(Point2::new(0.0, 1.0), Point2::new(1.0, 1.0), "0");
+        let mask = PropertyMask {
+            color: true,
+            linetype: true,
+            lineweight: true,
+            ..PropertyMask::none()
+        };
+        assert_eq!(doc.match_properties(by_layer, &[circle], mask), Ok(1));
+        assert!(doc.entity_properties(circle).is_none());
+        assert_eq!(doc.entity(circle).unwrap().layer_name(), "A-WALL");
+    }
+
+    #[test]
+    fn type_specific_properties_and_locked_layers() {
+        let mut doc = Document::new();
+        let source = doc.add_text(Point2::new(0.0, 0.0), "A", 5.0, 0.0, "TEXT");
+        let target = doc.add_text(Point2::new(0.0, 10.0), "B", 2.5, 0.0, "0");
+        let locked = doc.add_text(Point2::new(0.0, 20.0), "C", 2.5, 0.0, "FROZEN");
+        doc.layer_mut("FROZEN").unwrap().is_locked = true;
+        let line = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "TEXT");
+
+        let mask = PropertyMask {
+            text_height: true,
+            ..PropertyMask::none()
+        };
+        assert_eq!(
+            doc.match_properties(source, &[target, locked, line], mask),
+            Ok(2)
+        );
+        let Some(Entity::Text(matched)) = doc.entity(target) else {
+            panic!("应为单行文字");
+        };
+        assert_eq!(matched.height, 5.0);
+        assert_eq!(matched.layer, "0");
+        let Some(Entity::Text(untouched)) = doc.entity(locked) else {
+            panic!("应为单行文字");
+        };
+        assert_eq!(untouched.height, 2.5);
+
+        let missing = EntityId::new(9999);
+        assert_eq!(
+            doc.match_properties(missing, &[target], mask),
+            Err(MatchPropertiesError::SourceNotFound(missing))
+        );
+    }
+}
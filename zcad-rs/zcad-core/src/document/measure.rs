@@ -0,0 +1,582 @@
+//! 面积、周长与形心测量。
+//!
+//! 直线段与凸度圆弧按解析式计算，椭圆与样条沿参数做高斯积分。开放曲线的面积按首尾连线闭合计算，
+//! 周长只计曲线本身。面积带符号，逆时针为正；面积为零时形心取曲线长度的形心。
+//! 填充按边界环的嵌套层次计算：偶数层加、奇数层减，面积恒为正，周长为各环之和。
+
+use std::f64::consts::PI;
+
+use glam::DVec2;
+
+use crate::geometry::{BulgeArc, Point2, Vector2};
+
+use super::contain::polygon_contains;
+use super::{Entity, Hatch, HatchEdge, HatchLoop, PolylineVertex, Spline, ccw_sweep, edge_sweep};
+
+/// 高斯-勒让德五点积分的节点与权重（区间 [-1, 1]）。
+const GAUSS: [(f64, f64); 5] = [
+    (0.0, 0.568_888_888_888_888_9),
+    (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+    (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+];
+/// 椭圆整圈与样条每个节点区间的积分子区间数。
+const SUBDIVISIONS: usize = 64;
+
+/// 测量结果。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    /// 带符号面积，逆时针为正。
+    pub area: f64,
+    /// 周长（闭合曲线）或长度（开放曲线）。
+    pub perimeter: f64,
+    pub centroid: Point2,
+}
+
+impl Entity {
+    /// 测量直线、圆弧、圆、椭圆、多段线、样条、修订云线与填充；其他实体返回 `None`。
+    pub fn measure(&self) -> Option<Measurement> {
+        match self {
+            Entity::Line(line) => {
+                let mut moments = Moments::new(line.start.as_vec2());
+                moments.segment(line.start.as_vec2(), line.end.as_vec2(), 0.0);
+                Some(moments.closed())
+            }
+            Entity::Circle(circle) => {
+                let center = circle.center.as_vec2();
+                let (right, left) = (
+                    center + DVec2::X * circle.radius,
+                    center - DVec2::X * circle.radius,
+                );
+                let mut moments = Moments::new(right);
+                moments.segment(right, left, 1.0);
+                moments.segment(left, right, 1.0);
+                Some(moments.closed())
+            }
+            Entity::Arc(arc) => {
+                let center = arc.center.as_vec2();
+                let start = center + DVec2::from_angle(arc.start_angle) * arc.radius;
+                let mut moments = Moments::new(start);
+                moments.arc(
+                    center,
+                    arc.radius,
+                    arc.start_angle,
+                    ccw_sweep(arc.start_angle, arc.end_angle),
+                );
+                Some(moments.closed())
+            }
+            Entity::Ellipse(ellipse) => {
+                let sweep = ccw_sweep(ellipse.start_parameter, ellipse.end_parameter);
+                let mut moments = Moments::new(ellipse.center.as_vec2());
+                moments.ellipse(
+                    ellipse.center.as_vec2(),
+                    ellipse.major_axis,
+                    ellipse.ratio,
+                    ellipse.start_parameter,
+                    sweep,
+                );
+                Some(moments.closed())
+            }
+            Entity::Polyline(polyline) => vertices(&polyline.vertices, polyline.is_closed),
+            Entity::RevisionCloud(cloud) => vertices(&cloud.vertices, cloud.is_closed),
+            Entity::Spline(spline) => {
+                let mut moments = Moments::new(spline.point_at(spline.domain()?.0)?.0);
+                moments.spline(spline)?;
+                Some(moments.closed())
+            }
+            Entity::Hatch(hatch) => hatch.measure(),
+            _ => None,
+        }
+    }
+}
+
+impl HatchLoop {
+    /// 测量单个边界环，边界引用边没有几何，不计入。
+    pub fn measure(&self) -> Option<Measurement> {
+        let mut moments: Option<Moments> = None;
+        for edge in &self.edges {
+            let moments = moments.get_or_insert_with(|| Moments::new(edge_start(edge)));
+            match edge {
+                HatchEdge::Line { start, end } => {
+                    moments.segment(start.as_vec2(), end.as_vec2(), 0.0)
+                }
+                HatchEdge::PolylineSegment { start, end, bulge } => {
+                    moments.segment(start.as_vec2(), end.as_vec2(), *bulge)
+                }
+                HatchEdge::Arc {
+                    center,
+                    radius,
+                    start_angle,
+                    end_angle,
+                    is_counter_clockwise,
+                } => {
+                    let (start, sweep) =
+                        edge_sweep(*start_angle, *end_angle, *is_counter_clockwise);
+                    moments.arc(center.as_vec2(), *radius, start, sweep);
+                }
+                HatchEdge::Ellipse {
+                    center,
+                    major_axis,
+                    minor_ratio,
+                    start_angle,
+                    end_angle,
+                    is_counter_clockwise,
+                } => {
+                    let (start, sweep) =
+                        edge_sweep(*start_angle, *end_angle, *is_counter_clockwise);
+                    moments.ellipse(center.as_vec2(), *major_axis, *minor_ratio, start, sweep);
+                }
+                HatchEdge::Spline {
+                    control_points,
+                    fit_points,
+                    knot_values,
+                    degree,
+                    is_rational,
+                    is_periodic,
+                } => {
+                    moments.spline(&Spline {
+                        degree: *degree,
+                        is_rational: *is_rational,
+                        is_closed: false,
+                        is_periodic: *is_periodic,
+                        control_points: control_points.clone(),
+                        fit_points: fit_points.clone(),
+                        knot_values: knot_values.clone(),
+                        weights: Vec::new(),
+                        start_tangent: None,
+                        end_tangent: None,
+                        layer: String::new(),
+                    });
+                }
+                HatchEdge::BoundaryReference { .. } => {}
+            }
+        }
+        moments.map(|moments| moments.closed())
+    }
+}
+
+impl Hatch {
+    /// 测量填充区域：按嵌套层次加减各环面积，结果面积为正。
+    pub fn measure(&self) -> Option<Measurement> {
+        let loops: Vec<(Measurement, Vec<DVec2>)> = self
+            .loops
+            .iter()
+            .filter_map(|loop_path| {
+                let measurement = loop_path.measure()?;
+                let polygon = crate::tessellate::hatch_loop(
+                    loop_path,
+                    &crate::tessellate::TessellateOptions::per_turn(64),
+                )
+                .into_iter()
+                .map(Point2::as_vec2)
+                .collect::<Vec<_>>();
+                Some((measurement, polygon))
+            })
+            .collect();
+        if loops.is_empty() {
+            return None;
+        }
+        let (mut area, mut perimeter, mut moment) = (0.0, 0.0, DVec2::ZERO);
+        for (index, (measurement, polygon)) in loops.iter().enumerate() {
+            let depth = polygon.first().map_or(0, |probe| {
+                loops
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, (_, outer))| {
+                        *other != index && polygon_contains(outer, *probe)
+                    })
+                    .count()
+            });
+            let sign = if depth % 2 == 0 { 1.0 } else { -1.0 };
+            let region = measurement.area.abs() * sign;
+            area += region;
+            perimeter += measurement.perimeter;
+            moment += measurement.centroid.as_vec2() * region;
+        }
+        let centroid = if area.abs() > f64::EPSILON {
+            moment / area
+        } else {
+            loops[0].0.centroid.as_vec2()
+        };
+        Some(Measurement {
+            area: area.abs(),
+            perimeter,
+            centroid: Point2::from_vec(centroid),
+        })
+    }
+}
+
+fn vertices(vertices: &[PolylineVertex], is_closed: bool) -> Option<Measurement> {
+    let first = vertices.first()?;
+    let mut moments = Moments::new(first.position.as_vec2());
+    let count = vertices.len();
+    let segment_count = if is_closed {
+        count
+    } else {
+        count.saturating_sub(1)
+    };
+    for index in 0..segment_count {
+        let vertex = &vertices[index];
+        let next = &vertices[(index + 1) % count];
+        moments.segment(
+            vertex.position.as_vec2(),
+            next.position.as_vec2(),
+            vertex.bulge,
+        );
+    }
+    Some(moments.closed())
+}
+
+fn edge_start(edge: &HatchEdge) -> DVec2 {
+    match edge {
+        HatchEdge::Line { start, .. } | HatchEdge::PolylineSegment { start, .. } => start.as_vec2(),
+        HatchEdge::Arc { center, .. } | HatchEdge::Ellipse { center, .. } => center.as_vec2(),
+        HatchEdge::Spline { control_points, .. } => control_points
+            .first()
+            .map_or(DVec2::ZERO, |point| point.as_vec2()),
+        HatchEdge::BoundaryReference { .. } => DVec2::ZERO,
+    }
+}
+
+/// 沿曲线累加的面积矩与长度矩。面积按原点（平移到 `origin` 以减小抵消误差）与曲线构成的扇形计算，
+/// 首尾不接时由 [`Moments::closed`] 补上闭合弦。
+struct Moments {
+    origin: DVec2,
+    start: Option<DVec2>,
+    end: DVec2,
+    area: f64,
+    moment: DVec2,
+    length: f64,
+    length_moment: DVec2,
+}
+
+impl Moments {
+    fn new(origin: DVec2) -> Self {
+        Self {
+            origin,
+            start: None,
+            end: DVec2::ZERO,
+            area: 0.0,
+            moment: DVec2::ZERO,
+            length: 0.0,
+            length_moment: DVec2::ZERO,
+        }
+    }
+
+    fn visit(&mut self, start: DVec2, end: DVec2) {
+        self.start.get_or_insert(start - self.origin);
+        self.end = end - self.origin;
+    }
+
+    /// 与原点构成的三角形。
+    fn fan(&mut self, a: DVec2, b: DVec2) {
+        let cross = a.perp_dot(b);
+        self.area += cross / 2.0;
+        self.moment += (a + b) * cross / 6.0;
+    }
+
+    /// 直线段或凸度圆弧段。
+    fn segment(&mut self, start: DVec2, end: DVec2, bulge: f64) {
+        self.visit(start, end);
+        let (a, b) = (start - self.origin, end - self.origin);
+        self.fan(a, b);
+        let Some(BulgeArc {
+            center,
+            radius,
+            sweep,
+            ..
+        }) = BulgeArc::new(a, b, bulge)
+        else {
+            let length = a.distance(b);
+            self.length += length;
+            self.length_moment += (a + b) / 2.0 * length;
+            return;
+        };
+        let angle = sweep.abs();
+        // 圆弧中点方向
+        let middle = DVec2::from_angle(sweep / 2.0)
+            .rotate(a - center)
+            .normalize();
+        let half = angle / 2.0;
+        // 弓形：弦与圆弧之间的面积及其形心
+        let segment_area = radius * radius / 2.0 * (angle - angle.sin());
+        if segment_area > 0.0 {
+            let distance = 4.0 * radius * half.sin().powi(3) / (3.0 * (angle - angle.sin()));
+            let signed = segment_area * sweep.signum();
+            self.area += signed;
+            self.moment += (center + middle * distance) * signed;
+        }
+        let arc_length = radius * angle;
+        self.length += arc_length;
+        self.length_moment += (center + middle * radius * half.sin() / half) * arc_length;
+    }
+
+    /// 圆弧：从 `start_angle` 转过 `sweep`，超过半圈时分段以免凸度过大。
+    fn arc(&mut self, center: DVec2, radius: f64, start_angle: f64, sweep: f64) {
+        let pieces = (sweep.abs() / PI).ceil().max(1.0) as usize;
+        let step = sweep / pieces as f64;
+        for index in 0..pieces {
+            let from = start_angle + step * index as f64;
+            self.segment(
+                center + DVec2::from_angle(from) * radius,
+                center + DVec2::from_angle(from + step) * radius,
+                (step / 4.0).tan(),
+            );
+        }
+    }
+
+    fn ellipse(&mut self, center: DVec2, major_axis: Vector2, ratio: f64, start: f64, sweep: f64) {
+        let major = major_axis.as_vec2();
+        let minor = major.perp() * ratio;
+        let point = |t: f64| center + major * t.cos() + minor * t.sin();
+        let derivative = |t: f64| -major * t.sin() + minor * t.cos();
+        let pieces = ((sweep.abs() / (2.0 * PI)) * SUBDIVISIONS as f64)
+            .ceil()
+            .max(1.0) as usize;
+        let step = sweep / pieces as f64;
+        for index in 0..pieces {
+            let from = start + step * index as f64;
+            self.parametric(from, from + step, point, derivative);
+        }
+    }
+
+    fn spline(&mut self, spline: &Spline) -> Option<()> {
+        let nurbs = spline.nurbs()?;
+        for (low, high) in nurbs.spans() {
+            let step = (high - low) / SUBDIVISIONS as f64;
+            for index in 0..SUBDIVISIONS {
+                let from = low + step * index as f64;
+                self.parametric(
+                    from,
+                    from + step,
+                    |t| nurbs.point(t).as_vec2(),
+                    |t| nurbs.derivative(t),
+                );
+            }
+        }
+        Some(())
+    }
+
+    /// 参数曲线 `from..to` 的一段，以五点高斯积分累加。
+    fn parametric(
+        &mut self,
+        from: f64,
+        to: f64,
+        point: impl Fn(f64) -> DVec2,
+        derivative: impl Fn(f64) -> DVec2,
+    ) {
+        self.visit(point(from), point(to));
+        let (middle, half) = ((from + to) / 2.0, (to - from) / 2.0);
+        for (node, weight) in GAUSS {
+            let t = middle + half * node;
+            let (p, dp) = (point(t) - self.origin, derivative(t));
+            let weight = weight * half;
+            let cross = p.perp_dot(dp);
+            self.area += cross / 2.0 * weight;
+            self.moment += p * cross / 3.0 * weight;
+            let speed = dp.length() * weight.abs();
+            self.length += speed;
+            self.length_moment += p * speed;
+        }
+    }
+
+    /// 补上从终点回到起点的闭合弦，换算为测量结果。
+    fn closed(mut self) -> Measurement {
+        if let Some(start) = self.start {
+            let end = self.end;
+            self.fan(end, start);
+        }
+        let scale = self.length.max(f64::EPSILON);
+        let centroid = if self.area.abs() > 1e-12 * scale * scale {
+            self.moment / self.area
+        } else if self.length > 0.0 {
+            self.length_moment / self.length
+        } else {
+            self.start.unwrap_or(DVec2::ZERO)
+        };
+        Measurement {
+            area: self.area,
+            perimeter: self.length,
+            centroid: Point2::from_vec(centroid + self.origin),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{Circle, Ellipse, HatchStyle, Line, Polyline};
+
+    fn assert_measure(
+        actual: Option<Measurement>,
+        area: f64,
+        perimeter: f64,
+        centroid: (f64, f64),
+    ) {
+        let actual = actual.expect("expected a measurement");
+        assert!((actual.area - area).abs() < 1e-9, "area {}", actual.area);
+        assert!(
+            (actual.perimeter - perimeter).abs() < 1e-9,
+            "perimeter {}",
+            actual.perimeter
+        );
+        assert!(
+            (actual.centroid.x() - centroid.0).abs() < 1e-9
+                && (actual.centroid.y() - centroid.1).abs() < 1e-9,
+            "centroid {:?}",
+            actual.centroid
+        );
+    }
+
+    #[test]
+    fn measures_lines_circles_and_bulged_polylines() {
+        let line = Entity::Line(Line {
+            start: Point2::new(0.0, 0.0),
+            end: Point2::new(3.0, 4.0),
+            layer: "0".to_string(),
+        });
+        assert_measure(line.measure(), 0.0, 5.0, (1.5, 2.0));
+
+        let circle = Entity::Circle(Circle {
+            center: Point2::new(100.0, -50.0),
+            radius: 2.0,
+            layer: "0".to_string(),
+        });
+        assert_measure(circle.measure(), 4.0 * PI, 4.0 * PI, (100.0, -50.0));
+
+        // 2×2 正方形的右边换成外凸半圆
+        let polyline = Entity::Polyline(Polyline {
+            vertices: vec![
+                PolylineVertex::new(Point2::new(0.0, 0.0)),
+                PolylineVertex::with_bulge(Point2::new(2.0, 0.0), 1.0),
+                PolylineVertex::new(Point2::new(2.0, 2.0)),
+                PolylineVertex::new(Point2::new(0.0, 2.0)),
+            ],
+            is_closed: true,
+            layer: "0".to_string(),
+        });
+        let half_disc = PI / 2.0;
+        let centroid_x = (4.0 * 1.0 + half_disc * (2.0 + 4.0 / (3.0 * PI))) / (4.0 + half_disc);
+        assert_measure(
+            polyline.measure(),
+            4.0 + half_disc,
+            6.0 + PI,
+            (centroid_x, 1.0),
+        );
+
+        // 顺时针方向面积为负
+        let Entity::Polyline(mut reversed) = polyline.clone() else {
+            unreachable!()
+        };
+        reversed.vertices = vec![
+            PolylineVertex::new(Point2::new(0.0, 0.0)),
+            PolylineVertex::new(Point2::new(0.0, 2.0)),
+            PolylineVertex::with_bulge(Point2::new(2.0, 2.0), -1.0),
+            PolylineVertex::new(Point2::new(2.0, 0.0)),
+        ];
+        assert_measure(
+            Entity::Polyline(reversed).measure(),
+            -(4.0 + half_disc),
+            6.0 + PI,
+            (centroid_x, 1.0),
+        );
+    }
+
+    #[test]
+    fn integrates_ellipses_and_splines() {
+        let ellipse = Entity::Ellipse(Ellipse {
+            center: Point2::new(1.0, 1.0),
+            major_axis: Vector2::new(3.0, 0.0),
+            ratio: 0.5,
+            start_parameter: 0.0,
+            end_parameter: 2.0 * PI,
+            layer: "0".to_string(),
+        });
+        let measured = ellipse.measure().unwrap();
+        assert!((measured.area - PI * 3.0 * 1.5).abs() < 1e-9);
+        // Ramanujan 近似
+        let (a, b) = (3.0_f64, 1.5_f64);
+        let h = ((a - b) / (a + b)).powi(2);
+        let perimeter = PI * (a + b) * (1.0 + 3.0 * h / (10.0 + (4.0 - 3.0 * h).sqrt()));
+        assert!((measured.perimeter - perimeter).abs() < 1e-6);
+        assert!(measured.centroid.as_vec2().distance(DVec2::ONE) < 1e-9);
+
+        // 二次贝塞尔与弦围成的面积为 2/3 × 底 × 高
+        let spline = Entity::Spline(Spline {
+            degree: 2,
+            is_rational: false,
+            is_closed: false,
+            is_periodic: false,
+            control_points: vec![
+                Point2::new(2.0, 0.0),
+                Point2::new(1.0, 2.0),
+                Point2::new(0.0, 0.0),
+            ],
+            fit_points: Vec::new(),
+            knot_values: Vec::new(),
+            weights: Vec::new(),
+            start_tangent: None,
+            end_tangent: None,
+            layer: "0".to_string(),
+        });
+        let measured = spline.measure().unwrap();
+        assert!((measured.area - 4.0 / 3.0).abs() < 1e-9);
+        assert!(measured.centroid.as_vec2().distance(DVec2::new(1.0, 0.4)) < 1e-9);
+    }
+
+    #[test]
+    fn subtracts_hatch_islands() {
+        let square = |min: f64, max: f64| HatchLoop {
+            is_polyline: false,
+            is_closed: true,
+            edges: vec![
+                HatchEdge::Line {
+                    start: Point2::new(min, min),
+                    end: Point2::new(max, min),
+                },
+                HatchEdge::Line {
+                    start: Point2::new(max, min),
+                    end: Point2::new(max, max),
+                },
+                HatchEdge::Line {
+                    start: Point2::new(max, max),
+                    end: Point2::new(min, max),
+                },
+                HatchEdge::Line {
+                    start: Point2::new(min, max),
+                    end: Point2::new(min, min),
+                },
+            ],
+            boundary_handles: Vec::new(),
+            boundary_entities: Vec::new(),
+        };
+        let circle = HatchLoop {
+            is_polyline: false,
+            is_closed: true,
+            edges: vec![HatchEdge::Arc {
+                center: Point2::new(7.0, 5.0),
+                radius: 1.0,
+                start_angle: 0.0,
+                end_angle: 2.0 * PI,
+                is_counter_clockwise: false,
+            }],
+            boundary_handles: Vec::new(),
+            boundary_entities: Vec::new(),
+        };
+        let hatch = Entity::Hatch(Hatch {
+            pattern_name: "SOLID".to_string(),
+            is_solid: true,
+            loops: vec![square(0.0, 10.0), circle],
+            gradient: None,
+            pattern_lines: Vec::new(),
+            style: HatchStyle::Normal,
+            is_associative: false,
+            seed_points: Vec::new(),
+            layer: "0".to_string(),
+        });
+        let area = 100.0 - PI;
+        let centroid_x = (100.0 * 5.0 - PI * 7.0) / area;
+        assert_measure(hatch.measure(), area, 40.0 + 2.0 * PI, (centroid_x, 5.0));
+    }
+}
@@ -0,0 +1,465 @@
+//! 文档内存占用估算，帮助大型图纸判断哪些内容值得清理或重新成块。
+//!
+//! 估算值为实体枚举本身的大小加上其字符串与数组在堆上的容量，不包括哈希表
+//! 桶与分配器开销，只适合用于比较相对大小。
+
+use std::collections::HashMap;
+use std::mem::size_of;
+
+use super::{
+    Attribute, Dimension, Document, Entity, EntityId, Hatch, HatchEdge, HatchLoop,
+    HatchPatternLine, LeaderLine, MLeader, MLeaderContent, RasterImageClip, TextRun,
+};
+
+/// 报告中“最大实体”与“重复字符串”列表的长度。
+const MEMORY_REPORT_TOP: usize = 10;
+
+/// 文档内存占用报告，由 [`Document::memory_report`] 生成。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryReport {
+    /// 按实体类型汇总，按字节数从大到小排列；包含块定义中的实体。
+    pub by_kind: Vec<EntityKindMemory>,
+    /// 块定义占用，按字节数从大到小排列。
+    pub blocks: Vec<BlockMemory>,
+    /// 模型空间中占用最大的实体。
+    pub largest_entities: Vec<EntityMemory>,
+    pub strings: StringUsage,
+}
+
+impl MemoryReport {
+    /// 所有实体（含块定义内）的估算字节数。
+    pub fn total_bytes(&self) -> usize {
+        self.by_kind.iter().map(EntityKindMemory::bytes).sum()
+    }
+
+    pub fn entity_count(&self) -> usize {
+        self.by_kind.iter().map(|kind| kind.count).sum()
+    }
+
+    pub fn kind(&self, kind: &str) -> Option<&EntityKindMemory> {
+        self.by_kind.iter().find(|entry| entry.kind == kind)
+    }
+}
+
+/// 某一实体类型的占用。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityKindMemory {
+    pub kind: &'static str,
+    pub count: usize,
+    /// 实体枚举本身占用（`count * size_of::<Entity>()`）。
+    pub inline_bytes: usize,
+    /// 字符串与数组的堆容量。
+    pub heap_bytes: usize,
+}
+
+impl EntityKindMemory {
+    #[inline]
+    pub fn bytes(&self) -> usize {
+        self.inline_bytes + self.heap_bytes
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockMemory {
+    pub name: String,
+    pub entity_count: usize,
+    pub bytes: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityMemory {
+    pub id: EntityId,
+    pub kind: &'static str,
+    pub bytes: usize,
+}
+
+/// 实体字符串的重复情况，用于评估字符串驻留的收益。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StringUsage {
+    /// 非空字符串个数。
+    pub count: usize,
+    pub total_bytes: usize,
+    pub unique_count: usize,
+    pub unique_bytes: usize,
+    /// 重复浪费最多的字符串，按浪费字节数从大到小排列。
+    pub most_duplicated: Vec<DuplicatedString>,
+}
+
+impl StringUsage {
+    /// 若相同内容只保存一份可节省的字节数。
+    #[inline]
+    pub fn duplicated_bytes(&self) -> usize {
+        self.total_bytes - self.unique_bytes
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicatedString {
+    pub value: String,
+    pub occurrences: usize,
+    pub wasted_bytes: usize,
+}
+
+impl Entity {
+    /// 实体类型名，与 DXF 实体名保持一致。
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            Entity::Line(_) => "LINE",
+            Entity::Circle(_) => "CIRCLE",
+            Entity::Arc(_) => "ARC",
+            Entity::Ellipse(_) => "ELLIPSE",
+            Entity::Polyline(_) => "LWPOLYLINE",
+            Entity::Spline(_) => "SPLINE",
+            Entity::Text(_) => "TEXT",
+            Entity::MText(_) => "MTEXT",
+            Entity::BlockReference(_) => "INSERT",
+            Entity::Hatch(_) => "HATCH",
+            Entity::Dimension(_) => "DIMENSION",
+            Entity::Leader(_) => "LEADER",
+            Entity::MLeader(_) => "MULTILEADER",
+            Entity::RasterImage(_) => "IMAGE",
+            Entity::Wipeout(_) => "WIPEOUT",
+            Entity::Face3D(_) => "3DFACE",
+            Entity::Shape(_) => "SHAPE",
+            Entity::Underlay(underlay) => underlay.kind.entity_name(),
+            // DXF 中以 LWPOLYLINE 保存，这里单独归类便于识别
+            Entity::RevisionCloud(_) => "REVCLOUD",
+        }
+    }
+}
+
+/// 累计堆容量并统计字符串出现次数。
+#[derive(Default)]
+struct HeapCounter<'a> {
+    heap_bytes: usize,
+    strings: HashMap<&'a str, usize>,
+}
+
+impl<'a> HeapCounter<'a> {
+    fn string(&mut self, value: &'a String) {
+        self.heap_bytes += value.capacity();
+        if !value.is_empty() {
+            *self.strings.entry(value.as_str()).or_default() += 1;
+        }
+    }
+
+    fn optional_string(&mut self, value: &'a Option<String>) {
+        if let Some(value) = value {
+            self.string(value);
+        }
+    }
+
+    /// 只计数组容量；元素本身不含堆数据时使用。
+    fn vec<T>(&mut self, values: &Vec<T>) {
+        self.heap_bytes += values.capacity() * size_of::<T>();
+    }
+
+    fn entity(&mut self, entity: &'a Entity) {
+        match entity {
+            Entity::Line(line) => self.string(&line.layer),
+            Entity::Circle(circle) => self.string(&circle.layer),
+            Entity::Arc(arc) => self.string(&arc.layer),
+            Entity::Ellipse(ellipse) => self.string(&ellipse.layer),
+            Entity::Polyline(polyline) => {
+                self.vec(&polyline.vertices);
+                self.string(&polyline.layer);
+            }
+            Entity::Spline(spline) => {
+                self.vec(&spline.control_points);
+                self.vec(&spline.fit_points);
+                self.vec(&spline.knot_values);
+                self.vec(&spline.weights);
+                self.string(&spline.layer);
+            }
+            Entity::Text(text) => {
+                self.string(&text.content);
+                self.string(&text.layer);
+            }
+            Entity::MText(mtext) => {
+                self.string(&mtext.content);
+                self.optional_string(&mtext.style);
+                self.string(&mtext.layer);
+                self.vec(&mtext.runs);
+                mtext.runs.iter().for_each(|run| self.text_run(run));
+            }
+            Entity::BlockReference(reference) => {
+                self.string(&reference.name);
+                self.vec(&reference.attributes);
+                reference
+                    .attributes
+                    .iter()
+                    .for_each(|attribute| self.attribute(attribute));
+                self.string(&reference.layer);
+            }
+            Entity::Hatch(hatch) => self.hatch(hatch),
+            Entity::Dimension(dimension) => self.dimension(dimension),
+            Entity::Leader(leader) => {
+                self.string(&leader.layer);
+                self.optional_string(&leader.style_name);
+                self.vec(&leader.vertices);
+            }
+            Entity::MLeader(mleader) => self.mleader(mleader),
+            Entity::RasterImage(image) => {
+                self.string(&image.layer);
+                self.string(&image.image_def_handle);
+                self.optional_string(&image.image_def_reactor_handle);
+                self.clip(&image.clip);
+            }
+            Entity::Wipeout(wipeout) => {
+                self.string(&wipeout.layer);
+                self.clip(&wipeout.clip);
+            }
+            Entity::Face3D(face) => self.string(&face.layer),
+            Entity::Shape(shape) => {
+                self.string(&shape.name);
+                self.optional_string(&shape.style_handle);
+                self.string(&shape.layer);
+            }
+            Entity::Underlay(underlay) => {
+                self.string(&underlay.definition_handle);
+                self.vec(&underlay.clip_boundary);
+                self.string(&underlay.layer);
+            }
+            Entity::RevisionCloud(cloud) => {
+                self.vec(&cloud.vertices);
+                self.string(&cloud.layer);
+            }
+        }
+    }
+
+    fn attribute(&mut self, attribute: &'a Attribute) {
+        self.string(&attribute.tag);
+        self.string(&attribute.text);
+        self.optional_string(&attribute.style);
+        self.optional_string(&attribute.prompt);
+        self.string(&attribute.layer);
+    }
+
+    fn text_run(&mut self, run: &'a TextRun) {
+        self.string(&run.text);
+        self.optional_string(&run.format.font);
+        if let Some(stack) = &run.stack {
+            self.string(&stack.upper);
+            self.string(&stack.lower);
+        }
+    }
+
+    fn hatch(&mut self, hatch: &'a Hatch) {
+        self.string(&hatch.pattern_name);
+        self.vec(&hatch.loops);
+        hatch
+            .loops
+            .iter()
+            .for_each(|hatch_loop| self.hatch_loop(hatch_loop));
+        if let Some(gradient) = &hatch.gradient {
+            self.string(&gradient.name);
+        }
+        self.vec(&hatch.pattern_lines);
+        hatch
+            .pattern_lines
+            .iter()
+            .for_each(|line: &HatchPatternLine| self.vec(&line.dash_lengths));
+        self.vec(&hatch.seed_points);
+        self.string(&hatch.layer);
+    }
+
+    fn hatch_loop(&mut self, hatch_loop: &'a HatchLoop) {
+        self.vec(&hatch_loop.edges);
+        for edge in &hatch_loop.edges {
+            match edge {
+                HatchEdge::BoundaryReference { handle } => self.string(handle),
+                HatchEdge::Spline {
+                    control_points,
+                    fit_points,
+                    knot_values,
+                    ..
+                } => {
+                    self.vec(control_points);
+                    self.vec(fit_points);
+                    self.vec(knot_values);
+                }
+                HatchEdge::Line { .. }
+                | HatchEdge::Arc { .. }
+                | HatchEdge::PolylineSegment { .. }
+                | HatchEdge::Ellipse { .. } => {}
+            }
+        }
+        self.vec(&hatch_loop.boundary_handles);
+        hatch_loop
+            .boundary_handles
+            .iter()
+            .for_each(|handle| self.string(handle));
+        self.vec(&hatch_loop.boundary_entities);
+    }
+
+    fn dimension(&mut self, dimension: &'a Dimension) {
+        self.optional_string(&dimension.text);
+        self.optional_string(&dimension.block_name);
+        self.string(&dimension.layer);
+    }
+
+    fn mleader(&mut self, mleader: &'a MLeader) {
+        self.string(&mleader.layer);
+        self.optional_string(&mleader.style_name);
+        self.vec(&mleader.leader_lines);
+        mleader
+            .leader_lines
+            .iter()
+            .for_each(|line: &LeaderLine| self.vec(&line.vertices));
+        match &mleader.content {
+            MLeaderContent::MText { text, .. } => self.string(text),
+            MLeaderContent::Block { block } => {
+                self.optional_string(&block.block_handle);
+                self.optional_string(&block.block_name);
+            }
+            MLeaderContent::None => {}
+        }
+    }
+
+    fn clip(&mut self, clip: &Option<RasterImageClip>) {
+        if let Some(RasterImageClip::Polygon { vertices, .. }) = clip {
+            self.vec(vertices);
+        }
+    }
+}
+
+/// 单个实体的估算字节数，同时把其字符串计入 `counter`。
+fn measure<'a>(counter: &mut HeapCounter<'a>, entity: &'a Entity) -> usize {
+    let before = counter.heap_bytes;
+    counter.entity(entity);
+    size_of::<Entity>() + counter.heap_bytes - before
+}
+
+impl Document {
+    /// 估算实体占用的内存：按类型汇总、块定义占用、最大的实体以及字符串重复情况。
+    pub fn memory_report(&self) -> MemoryReport {
+        let mut counter = HeapCounter::default();
+        let mut kinds: HashMap<&'static str, EntityKindMemory> = HashMap::new();
+        let mut record = |kind: &'static str, bytes: usize| {
+            let entry = kinds.entry(kind).or_insert(EntityKindMemory {
+                kind,
+                count: 0,
+                inline_bytes: 0,
+                heap_bytes: 0,
+            });
+            entry.count += 1;
+            entry.inline_bytes += size_of::<Entity>();
+            entry.heap_bytes += bytes - size_of::<Entity>();
+        };
+
+        let mut largest_entities = Vec::with_capacity(self.entities.len());
+        for (id, entity) in &self.entities {
+            let bytes = measure(&mut counter, entity);
+            record(entity.kind_name(), bytes);
+            largest_entities.push(EntityMemory {
+                id: *id,
+                kind: entity.kind_name(),
+                bytes,
+            });
+        }
+        largest_entities.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(a.id.get().cmp(&b.id.get())));
+        largest_entities.truncate(MEMORY_REPORT_TOP);
+
+        let mut blocks = Vec::with_capacity(self.blocks.len());
+        for block in self.blocks.values() {
+            let mut bytes = 0;
+            for entity in &block.entities {
+                let entity_bytes = measure(&mut counter, entity);
+                record(entity.kind_name(), entity_bytes);
+                bytes += entity_bytes;
+            }
+            blocks.push(BlockMemory {
+                name: block.name.clone(),
+                entity_count: block.entities.len(),
+                bytes,
+            });
+        }
+        blocks.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+
+        let mut by_kind: Vec<_> = kinds.into_values().collect();
+        by_kind.sort_by(|a, b| b.bytes().cmp(&a.bytes()).then(a.kind.cmp(b.kind)));
+
+        MemoryReport {
+            by_kind,
+            blocks,
+            largest_entities,
+            strings: string_usage(&counter.strings),
+        }
+    }
+}
+
+fn string_usage(strings: &HashMap<&str, usize>) -> StringUsage {
+    let mut usage = StringUsage::default();
+    let mut duplicated = Vec::new();
+    for (value, &occurrences) in strings {
+        usage.count += occurrences;
+        usage.total_bytes += value.len() * occurrences;
+        usage.unique_count += 1;
+        usage.unique_bytes += value.len();
+        if occurrences > 1 {
+            duplicated.push(DuplicatedString {
+                value: value.to_string(),
+                occurrences,
+                wasted_bytes: value.len() * (occurrences - 1),
+            });
+        }
+    }
+    duplicated.sort_by(|a, b| {
+        b.wasted_bytes
+            .cmp(&a.wasted_bytes)
+            .then_with(|| a.value.cmp(&b.value))
+    });
+    duplicated.truncate(MEMORY_REPORT_TOP);
+    usage.most_duplicated = duplicated;
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{BlockDefinition, PolylineVertex};
+    use crate::geometry::Point2;
+
+    #[test]
+    fn memory_report_groups_kinds_and_detects_duplicate_strings() {
+        let mut doc = Document::new();
+        for index in 0..3 {
+            let x = index as f64;
+            doc.add_line(Point2::new(x, 0.0), Point2::new(x, 1.0), "WALLS");
+        }
+        let vertices = (0..100).map(|index| Point2::new(index as f64, 0.0));
+        let polyline = doc.add_polyline(vertices, false, "WALLS");
+        doc.add_block_definition(BlockDefinition {
+            name: "DOOR".to_string(),
+            base_point: Point2::new(0.0, 0.0),
+            entities: vec![Entity::Line(crate::document::Line {
+                start: Point2::new(0.0, 0.0),
+                end: Point2::new(1.0, 0.0),
+                layer: "WALLS".to_string(),
+            })],
+            attributes: Vec::new(),
+        });
+
+        let report = doc.memory_report();
+        assert_eq!(report.entity_count(), 5);
+        let lines = report.kind("LINE").expect("line kind");
+        assert_eq!(lines.count, 4);
+        assert_eq!(lines.inline_bytes, 4 * size_of::<Entity>());
+        assert_eq!(report.by_kind[0].kind, "LWPOLYLINE");
+        assert_eq!(report.largest_entities[0].id, polyline);
+        assert!(report.largest_entities[0].bytes >= 100 * size_of::<PolylineVertex>());
+        assert_eq!(report.blocks.len(), 1);
+        assert_eq!(report.blocks[0].entity_count, 1);
+
+        assert_eq!(report.strings.count, 5);
+        assert_eq!(report.strings.unique_count, 1);
+        assert_eq!(report.strings.duplicated_bytes(), 4 * "WALLS".len());
+        let top = &report.strings.most_duplicated[0];
+        assert_eq!(top.value, "WALLS");
+        assert_eq!(top.occurrences, 5);
+        assert_eq!(
+            report.total_bytes(),
+            report.by_kind.iter().map(|k| k.bytes()).sum()
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! MTEXT 格式化文字：按字体、字高、颜色等行内格式切分后的文字段。
+//!
+//! 文字段的格式均相对实体自身样式给出，`None` 表示沿用实体的样式、字高与颜色。
+
+use serde::{Deserialize, Serialize};
+
+use super::ObjectColor;
+
+/// 文字段相对所在行的垂直对齐（MTEXT `\A` 格式码）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextRunAlignment {
+    #[default]
+    Bottom,
+    Center,
+    Top,
+}
+
+impl TextRunAlignment {
+    pub fn from_code(code: i16) -> Option<Self> {
+        match code {
+            0 => Some(TextRunAlignment::Bottom),
+            1 => Some(TextRunAlignment::Center),
+            2 => Some(TextRunAlignment::Top),
+            _ => None,
+        }
+    }
+
+    pub fn code(self) -> i16 {
+        match self {
+            TextRunAlignment::Bottom => 0,
+            TextRunAlignment::Center => 1,
+            TextRunAlignment::Top => 2,
+        }
+    }
+}
+
+/// 文字段格式；各字段为 `None` 时沿用实体设置。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TextFormat {
+    /// 字体名（`\f`）或字体文件（`\F`）。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font: Option<String>,
+    #[serde(default)]
+    pub bold: bool,
+    #[serde(default)]
+    pub italic: bool,
+    /// 绝对字高；相对字高（`\H0.5x;`）在解析时按实体字高换算。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<ObjectColor>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width_factor: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alignment: Option<TextRunAlignment>,
+}
+
+impl TextFormat {
+    /// 是否完全沿用实体样式。
+    pub fn is_default(&self) -> bool {
+        *self == TextFormat::default()
+    }
+}
+
+/// 堆叠分数的分隔方式（`\S` 格式码中的 `/`、`#` 与 `^`）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StackKind {
+    /// `/`：上下堆叠并带水平分数线。
+    Horizontal,
+    /// `#`：斜线分数。
+    Diagonal,
+    /// `^`：公差形式，上下堆叠无分数线。
+    Tolerance,
+}
+
+impl StackKind {
+    pub fn from_separator(separator: char) -> Option<Self> {
+        match separator {
+            '/' => Some(StackKind::Horizontal),
+            '#' => Some(StackKind::Diagonal),
+            '^' => Some(StackKind::Tolerance),
+            _ => None,
+        }
+    }
+
+    pub fn separator(self) -> char {
+        match self {
+            StackKind::Horizontal => '/',
+            StackKind::Diagonal => '#',
+            StackKind::Tolerance => '^',
+        }
+    }
+}
+
+/// 堆叠文字（分数或公差）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StackedText {
+    pub upper: String,
+    pub lower: String,
+    pub kind: StackKind,
+}
+
+/// 一段格式相同的文字。堆叠段的 `text` 为上下文字以分隔符连接的纯文本。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextRun {
+    pub text: String,
+    #[serde(default)]
+    pub format: TextFormat,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack: Option<StackedText>,
+}
+
+impl TextRun {
+    pub fn new(text: impl Into<String>, format: TextFormat) -> Self {
+        Self {
+            text: text.into(),
+            format,
+            stack: None,
+        }
+    }
+
+    pub fn stacked(stack: StackedText, format: TextFormat) -> Self {
+        Self {
+            text: format!("{}{}{}", stack.upper, stack.kind.separator(), stack.lower),
+            format,
+            stack: Some(stack),
+        }
+    }
+}
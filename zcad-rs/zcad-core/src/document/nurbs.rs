@@ -0,0 +1,620 @@
+//! 样条（NURBS）求值：按参数取点与一阶导数、按弦高容差离散，以及由拟合点全局插值求控制点。
+//!
+//! 节点个数与控制点不符时按钳制均匀节点处理，非有理样条或权重个数不符时权重均为 1。
+//! 只有拟合点的样条先按弦长参数化做全局插值再求值，起止切向给出时作为端点导数约束。
+
+use glam::DVec2;
+
+use crate::geometry::{Point2, Vector2};
+
+use super::Spline;
+
+/// 分母、主元判零的容差。
+const EPSILON: f64 = 1e-12;
+/// 离散时每个节点区间的最大二分深度。
+const MAX_DEPTH: usize = 16;
+
+impl Spline {
+    /// 参数定义域 `(起点参数, 终点参数)`；没有可求值的几何时返回 `None`。
+    pub fn domain(&self) -> Option<(f64, f64)> {
+        self.nurbs().map(|nurbs| nurbs.domain())
+    }
+
+    /// 参数 `t` 处的点，超出定义域时取端点。
+    pub fn point_at(&self, t: f64) -> Option<Point2> {
+        let nurbs = self.nurbs()?;
+        Some(nurbs.point(nurbs.clamp(t)))
+    }
+
+    /// 参数 `t` 处对参数的一阶导数（切向量，长度随参数化而定），超出定义域时取端点。
+    pub fn derivative_at(&self, t: f64) -> Option<Vector2> {
+        let nurbs = self.nurbs()?;
+        Some(Vector2::from(nurbs.derivative(nurbs.clamp(t))))
+    }
+
+    /// 按弦高容差离散为折线：每个节点区间二分，直到区间内各点离弦不超过 `tolerance`。
+    /// 没有可求值的几何时返回控制点。
+    pub fn tessellate(&self, tolerance: f64) -> Vec<Point2> {
+        self.tessellate_within(tolerance.max(EPSILON), 0.0)
+    }
+
+    /// 同时按弦高容差与相邻切向最大夹角离散，两者中不大于零的一项不参与判断。
+    pub(crate) fn tessellate_within(&self, tolerance: f64, max_angle: f64) -> Vec<Point2> {
+        let Some(nurbs) = self.nurbs() else {
+            return self.control_points.clone();
+        };
+        let limits = Limits {
+            tolerance,
+            max_angle,
+        };
+        let mut points = vec![nurbs.point(nurbs.domain().0)];
+        for (start, end) in nurbs.spans() {
+            subdivide(&nurbs, start, end, &limits, 0, &mut points);
+        }
+        points
+    }
+
+    /// 由拟合点全局插值求控制点，返回带控制点与节点的非有理样条，拟合点与切向保持不变。
+    /// 拟合点不足两个（去除重合点后）时返回 `None`。
+    pub fn interpolate_fit_points(&self) -> Option<Spline> {
+        let nurbs = interpolate(
+            &self.fit_points,
+            self.degree.max(1) as usize,
+            self.is_closed,
+            self.start_tangent,
+            self.end_tangent,
+        )?;
+        Some(Spline {
+            degree: nurbs.degree as i32,
+            is_rational: false,
+            is_closed: self.is_closed,
+            is_periodic: false,
+            control_points: nurbs.controls,
+            fit_points: self.fit_points.clone(),
+            knot_values: nurbs.knots,
+            weights: Vec::new(),
+            start_tangent: self.start_tangent,
+            end_tangent: self.end_tangent,
+            layer: self.layer.clone(),
+        })
+    }
+
+    /// 求值所用的完整 NURBS 数据：控制点足够时直接使用，否则由拟合点插值，
+    /// 两者都不足时把次数降到控制点数减一。
+    pub(super) fn nurbs(&self) -> Option<Nurbs> {
+        let degree = self.degree.max(1) as usize;
+        let count = self.control_points.len();
+        if count <= degree {
+            if let Some(nurbs) = interpolate(
+                &self.fit_points,
+                degree,
+                self.is_closed,
+                self.start_tangent,
+                self.end_tangent,
+            ) {
+                return Some(nurbs);
+            }
+            if count < 2 {
+                return None;
+            }
+            let degree = count - 1;
+            return Some(Nurbs {
+                degree,
+                controls: self.control_points.clone(),
+                weights: vec![1.0; count],
+                knots: clamped_uniform_knots(count, degree),
+            });
+        }
+        let knots = if self.knot_values.len() == count + degree + 1 {
+            self.knot_values.clone()
+        } else {
+            clamped_uniform_knots(count, degree)
+        };
+        let weights = if self.is_rational && self.weights.len() == count {
+            self.weights.clone()
+        } else {
+            vec![1.0; count]
+        };
+        Some(Nurbs {
+            degree,
+            controls: self.control_points.clone(),
+            weights,
+            knots,
+        })
+    }
+}
+
+/// 可直接求值的 NURBS：控制点数大于次数，节点数为控制点数加次数加一。
+pub(super) struct Nurbs {
+    pub(super) degree: usize,
+    pub(super) controls: Vec<Point2>,
+    pub(super) weights: Vec<f64>,
+    pub(super) knots: Vec<f64>,
+}
+
+impl Nurbs {
+    pub(super) fn domain(&self) -> (f64, f64) {
+        (self.knots[self.degree], self.knots[self.controls.len()])
+    }
+
+    fn clamp(&self, t: f64) -> f64 {
+        let (start, end) = self.domain();
+        t.clamp(start, end)
+    }
+
+    /// 定义域内长度非零的节点区间。
+    pub(super) fn spans(&self) -> Vec<(f64, f64)> {
+        let (start, end) = self.domain();
+        self.knots[self.degree..=self.controls.len()]
+            .windows(2)
+            .map(|pair| (pair[0].max(start), pair[1].min(end)))
+            .filter(|(low, high)| high - low > EPSILON)
+            .collect()
+    }
+
+    pub(super) fn point(&self, t: f64) -> Point2 {
+        de_boor(&self.controls, &self.weights, &self.knots, self.degree, t)
+    }
+
+    /// 有理导数：C' = (A' - C·w') / w，A 为加权控制点的组合，w 为权函数。
+    pub(super) fn derivative(&self, t: f64) -> DVec2 {
+        let span = find_span(&self.knots, self.degree, self.controls.len(), t);
+        let values = basis_functions(&self.knots, self.degree, span, t);
+        let derivatives = basis_derivatives(&self.knots, self.degree, span, t);
+        let (mut point, mut weight) = (DVec2::ZERO, 0.0);
+        let (mut point_derivative, mut weight_derivative) = (DVec2::ZERO, 0.0);
+        for j in 0..=self.degree {
+            let index = span - self.degree + j;
+            let w = self.weights[index];
+            let control = self.controls[index].as_vec2() * w;
+            point += control * values[j];
+            weight += w * values[j];
+            point_derivative += control * derivatives[j];
+            weight_derivative += w * derivatives[j];
+        }
+        (point_derivative - point / weight * weight_derivative) / weight
+    }
+}
+
+/// 钳制均匀节点向量：两端各重复次数加一次，内部在 [0, 1] 上均分。
+pub(crate) fn clamped_uniform_knots(control_count: usize, degree: usize) -> Vec<f64> {
+    let spans = (control_count - degree) as f64;
+    (0..control_count + degree + 1)
+        .map(|i| {
+            if i <= degree {
+                0.0
+            } else if i >= control_count {
+                1.0
+            } else {
+                (i - degree) as f64 / spans
+            }
+        })
+        .collect()
+}
+
+/// de Boor 算法在齐次坐标下求有理 B 样条上的点。
+pub(crate) fn de_boor(
+    controls: &[Point2],
+    weights: &[f64],
+    knots: &[f64],
+    degree: usize,
+    t: f64,
+) -> Point2 {
+    let span = find_span(knots, degree, controls.len(), t);
+    let mut points: Vec<(DVec2, f64)> = (0..=degree)
+        .map(|j| {
+            let index = span - degree + j;
+            let weight = weights[index];
+            (controls[index].as_vec2() * weight, weight)
+        })
+        .collect();
+    for r in 1..=degree {
+        for j in (r..=degree).rev() {
+            let i = span - degree + j;
+            let denominator = knots[i + degree + 1 - r] - knots[i];
+            let alpha = if denominator.abs() <= f64::EPSILON {
+                0.0
+            } else {
+                (t - knots[i]) / denominator
+            };
+            let (prev_point, prev_weight) = points[j - 1];
+            let (point, weight) = points[j];
+            points[j] = (
+                prev_point * (1.0 - alpha) + point * alpha,
+                prev_weight * (1.0 - alpha) + weight * alpha,
+            );
+        }
+    }
+    let (point, weight) = points[degree];
+    Point2::from_vec(point / weight)
+}
+
+/// `t` 所在的节点区间下标，终点归入最后一个区间。
+fn find_span(knots: &[f64], degree: usize, control_count: usize, t: f64) -> usize {
+    let mut span = degree;
+    while span + 1 < control_count && t >= knots[span + 1] {
+        span += 1;
+    }
+    span
+}
+
+/// 区间 `span` 上非零的 `degree + 1` 个基函数 N(span - degree .. span)。
+fn basis_functions(knots: &[f64], degree: usize, span: usize, t: f64) -> Vec<f64> {
+    let mut values = vec![0.0; degree + 1];
+    let mut left = vec![0.0; degree + 1];
+    let mut right = vec![0.0; degree + 1];
+    values[0] = 1.0;
+    for j in 1..=degree {
+        left[j] = t - knots[span + 1 - j];
+        right[j] = knots[span + j] - t;
+        let mut saved = 0.0;
+        for r in 0..j {
+            let denominator = right[r + 1] + left[j - r];
+            let temp = if denominator.abs() <= EPSILON {
+                0.0
+            } else {
+                values[r] / denominator
+            };
+            values[r] = saved + right[r + 1] * temp;
+            saved = left[j - r] * temp;
+        }
+        values[j] = saved;
+    }
+    values
+}
+
+/// 区间 `span` 上非零基函数的一阶导数，由低一次的基函数组合而成。
+fn basis_derivatives(knots: &[f64], degree: usize, span: usize, t: f64) -> Vec<f64> {
+    let lower = basis_functions(knots, degree - 1, span, t);
+    let order = degree as f64;
+    let term = |index: usize, value: f64, offset: usize| {
+        let denominator = knots[index + degree + offset] - knots[index + offset];
+        if denominator.abs() <= EPSILON {
+            0.0
+        } else {
+            order * value / denominator
+        }
+    };
+    (0..=degree)
+        .map(|j| {
+            let index = span - degree + j;
+            let rising = if j > 0 {
+                term(index, lower[j - 1], 0)
+            } else {
+                0.0
+            };
+            let falling = if j < degree {
+                term(index, lower[j], 1)
+            } else {
+                0.0
+            };
+            rising - falling
+        })
+        .collect()
+}
+
+/// 离散的精度限制。
+struct Limits {
+    tolerance: f64,
+    max_angle: f64,
+}
+
+fn subdivide(
+    nurbs: &Nurbs,
+    start: f64,
+    end: f64,
+    limits: &Limits,
+    depth: usize,
+    points: &mut Vec<Point2>,
+) {
+    let (first, last) = (nurbs.point(start).as_vec2(), nurbs.point(end).as_vec2());
+    // 取区间内三点检查，避免 S 形区间的中点恰好落在弦上
+    let chord_flat = limits.tolerance <= 0.0
+        || [0.25, 0.5, 0.75].iter().all(|fraction| {
+            let inner = nurbs.point(start + (end - start) * fraction).as_vec2();
+            distance_to_segment(inner, first, last) <= limits.tolerance
+        });
+    // 切向转角取两半之和，避免区间内转过大半圈时首尾切向看似接近
+    let angle_flat = limits.max_angle <= 0.0 || {
+        let tangents = [start, (start + end) / 2.0, end].map(|t| nurbs.derivative(t));
+        tangents
+            .iter()
+            .any(|tangent| tangent.length_squared() <= EPSILON)
+            || tangents[0].angle_to(tangents[1]).abs() + tangents[1].angle_to(tangents[2]).abs()
+                <= limits.max_angle
+    };
+    if depth >= MAX_DEPTH || (chord_flat && angle_flat) {
+        points.push(Point2::from_vec(last));
+        return;
+    }
+    let middle = (start + end) / 2.0;
+    subdivide(nurbs, start, middle, limits, depth + 1, points);
+    subdivide(nurbs, middle, end, limits, depth + 1, points);
+}
+
+fn distance_to_segment(point: DVec2, start: DVec2, end: DVec2) -> f64 {
+    let direction = end - start;
+    let length = direction.length_squared();
+    if length <= EPSILON {
+        return point.distance(start);
+    }
+    let fraction = ((point - start).dot(direction) / length).clamp(0.0, 1.0);
+    point.distance(start + direction * fraction)
+}
+
+/// 全局插值（Piegl & Tiller 9.2）：弦长参数化，节点取参数的滑动平均，
+/// 切向约束在对应端点重复一次参数；切向按总弦长缩放为参数导数。
+fn interpolate(
+    fit_points: &[Point2],
+    degree: usize,
+    is_closed: bool,
+    start_tangent: Option<Vector2>,
+    end_tangent: Option<Vector2>,
+) -> Option<Nurbs> {
+    let mut points: Vec<DVec2> = Vec::with_capacity(fit_points.len() + 1);
+    for point in fit_points {
+        let point = point.as_vec2();
+        if points
+            .last()
+            .is_none_or(|last| last.distance(point) > EPSILON)
+        {
+            points.push(point);
+        }
+    }
+    if is_closed
+        && points.len() > 2
+        && let (Some(first), Some(last)) = (points.first().copied(), points.last())
+        && first.distance(*last) > EPSILON
+    {
+        points.push(first);
+    }
+    if points.len() < 2 {
+        return None;
+    }
+    let chords: Vec<f64> = points
+        .windows(2)
+        .map(|pair| pair[0].distance(pair[1]))
+        .collect();
+    let total: f64 = chords.iter().sum();
+    let mut parameters = Vec::with_capacity(points.len());
+    let mut travelled = 0.0;
+    parameters.push(0.0);
+    for chord in &chords {
+        travelled += chord;
+        parameters.push(travelled / total);
+    }
+    *parameters.last_mut()? = 1.0;
+
+    // 一次插值无法约束切向
+    let tangent = |tangent: Option<Vector2>| {
+        tangent
+            .filter(|_| degree >= 2)
+            .and_then(|tangent| tangent.as_vec2().try_normalize())
+            .map(|unit| unit * total)
+    };
+    let (start_tangent, end_tangent) = (tangent(start_tangent), tangent(end_tangent));
+    let mut averaged = parameters.clone();
+    if start_tangent.is_some() {
+        averaged.insert(0, 0.0);
+    }
+    if end_tangent.is_some() {
+        averaged.push(1.0);
+    }
+    let count = averaged.len();
+    let degree = degree.min(count - 1);
+    let mut knots = vec![0.0; degree + 1];
+    knots.extend(
+        (1..count - degree).map(|j| averaged[j..j + degree].iter().sum::<f64>() / degree as f64),
+    );
+    knots.extend(std::iter::repeat_n(1.0, degree + 1));
+
+    let mut rows: Vec<(Vec<f64>, DVec2)> = Vec::with_capacity(count);
+    let row = |values: Vec<f64>, span: usize, target: DVec2| {
+        let mut coefficients = vec![0.0; count];
+        coefficients[span - degree..=span].copy_from_slice(&values);
+        (coefficients, target)
+    };
+    if let Some(tangent) = start_tangent {
+        let span = find_span(&knots, degree, count, 0.0);
+        rows.push(row(
+            basis_derivatives(&knots, degree, span, 0.0),
+            span,
+            tangent,
+        ));
+    }
+    for (point, parameter) in points.iter().zip(&parameters) {
+        let span = find_span(&knots, degree, count, *parameter);
+        rows.push(row(
+            basis_functions(&knots, degree, span, *parameter),
+            span,
+            *point,
+        ));
+    }
+    if let Some(tangent) = end_tangent {
+        let span = find_span(&knots, degree, count, 1.0);
+        rows.push(row(
+            basis_derivatives(&knots, degree, span, 1.0),
+            span,
+            tangent,
+        ));
+    }
+    let controls = solve(rows)?;
+    Some(Nurbs {
+        degree,
+        controls: controls.into_iter().map(Point2::from_vec).collect(),
+        weights: vec![1.0; count],
+        knots,
+    })
+}
+
+/// 列主元高斯消元，右端为二维点，x、y 同时求解。
+fn solve(mut rows: Vec<(Vec<f64>, DVec2)>) -> Option<Vec<DVec2>> {
+    let size = rows.len();
+    for column in 0..size {
+        let pivot = (column..size).max_by(|a, b| {
+            rows[*a].0[column]
+                .abs()
+                .total_cmp(&rows[*b].0[column].abs())
+        })?;
+        if rows[pivot].0[column].abs() <= EPSILON {
+            return None;
+        }
+        rows.swap(column, pivot);
+        let (head, tail) = rows.split_at_mut(column + 1);
+        let (pivot_row, pivot_target) = &head[column];
+        for (coefficients, target) in tail {
+            let factor = coefficients[column] / pivot_row[column];
+            if factor == 0.0 {
+                continue;
+            }
+            for (value, pivot_value) in coefficients[column..].iter_mut().zip(&pivot_row[column..])
+            {
+                *value -= factor * pivot_value;
+            }
+            *target -= *pivot_target * factor;
+        }
+    }
+    let mut solution = vec![DVec2::ZERO; size];
+    for index in (0..size).rev() {
+        let (coefficients, target) = &rows[index];
+        let known: DVec2 = (index + 1..size)
+            .map(|column| solution[column] * coefficients[column])
+            .sum();
+        solution[index] = (*target - known) / coefficients[index];
+    }
+    Some(solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    use super::*;
+
+    fn spline(degree: i32, control_points: Vec<Point2>, weights: Vec<f64>) -> Spline {
+        Spline {
+            degree,
+            is_rational: !weights.is_empty(),
+            is_closed: false,
+            is_periodic: false,
+            control_points,
+            fit_points: Vec::new(),
+            knot_values: Vec::new(),
+            weights,
+            start_tangent: None,
+            end_tangent: None,
+            layer: "0".to_string(),
+        }
+    }
+
+    fn quarter_circle() -> Spline {
+        spline(
+            2,
+            vec![
+                Point2::new(1.0, 0.0),
+                Point2::new(1.0, 1.0),
+                Point2::new(0.0, 1.0),
+            ],
+            vec![1.0, FRAC_1_SQRT_2, 1.0],
+        )
+    }
+
+    #[test]
+    fn evaluates_points_and_derivatives() {
+        let bezier = spline(
+            2,
+            vec![
+                Point2::new(0.0, 0.0),
+                Point2::new(1.0, 2.0),
+                Point2::new(2.0, 0.0),
+            ],
+            Vec::new(),
+        );
+        assert_eq!(bezier.domain(), Some((0.0, 1.0)));
+        assert_eq!(bezier.point_at(0.5), Some(Point2::new(1.0, 1.0)));
+        let derivative = bezier.derivative_at(0.5).unwrap();
+        assert!((derivative.x() - 2.0).abs() < 1e-12 && derivative.y().abs() < 1e-12);
+        assert_eq!(bezier.point_at(3.0), Some(Point2::new(2.0, 0.0)));
+
+        let arc = quarter_circle();
+        for step in 0..=8 {
+            let t = step as f64 / 8.0;
+            let point = arc.point_at(t).unwrap().as_vec2();
+            let tangent = arc.derivative_at(t).unwrap().as_vec2();
+            assert!((point.length() - 1.0).abs() < 1e-12);
+            assert!(point.dot(tangent).abs() < 1e-9);
+        }
+
+        // 非均匀节点的三次样条，导数与差分一致
+        let mut cubic = spline(
+            3,
+            vec![
+                Point2::new(0.0, 0.0),
+                Point2::new(1.0, 3.0),
+                Point2::new(3.0, -1.0),
+                Point2::new(5.0, 2.0),
+                Point2::new(6.0, 0.0),
+            ],
+            vec![1.0, 2.0, 0.5, 1.0, 1.5],
+        );
+        cubic.knot_values = vec![0.0, 0.0, 0.0, 0.0, 0.3, 2.0, 2.0, 2.0, 2.0];
+        for t in [0.1, 0.7, 1.5] {
+            let step = 1e-6;
+            let expected = (cubic.point_at(t + step).unwrap().as_vec2()
+                - cubic.point_at(t - step).unwrap().as_vec2())
+                / (2.0 * step);
+            let actual = cubic.derivative_at(t).unwrap().as_vec2();
+            assert!(actual.distance(expected) < 1e-6, "{actual} != {expected}");
+        }
+    }
+
+    #[test]
+    fn tessellates_within_tolerance() {
+        let arc = quarter_circle();
+        let points = arc.tessellate(1e-4);
+        assert_eq!(points.first(), Some(&Point2::new(1.0, 0.0)));
+        assert_eq!(points.last(), Some(&Point2::new(0.0, 1.0)));
+        for pair in points.windows(2) {
+            let middle = (pair[0].as_vec2() + pair[1].as_vec2()) / 2.0;
+            assert!(1.0 - middle.length() <= 1e-4);
+        }
+        assert!(points.len() < arc.tessellate(1e-6).len());
+    }
+
+    #[test]
+    fn interpolates_fit_points() {
+        let fit_points = vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(1.0, 2.0),
+            Point2::new(3.0, 3.0),
+            Point2::new(4.0, 1.0),
+            Point2::new(6.0, 0.0),
+        ];
+        let mut fitted = spline(3, Vec::new(), Vec::new());
+        fitted.fit_points = fit_points.clone();
+        let interpolated = fitted.interpolate_fit_points().unwrap();
+        assert_eq!(interpolated.control_points.len(), 5);
+        assert_eq!(interpolated.knot_values.len(), 9);
+        let samples = interpolated.tessellate(1e-9);
+        for fit in &fit_points {
+            assert!(
+                samples
+                    .iter()
+                    .any(|point| point.as_vec2().distance(fit.as_vec2()) < 1e-6),
+                "curve misses {fit:?}"
+            );
+        }
+        // 只有拟合点的样条直接按插值结果求值
+        assert_eq!(fitted.point_at(1.0), Some(Point2::new(6.0, 0.0)));
+
+        fitted.start_tangent = Some(Vector2::new(0.0, 1.0));
+        fitted.end_tangent = Some(Vector2::new(1.0, 0.0));
+        let constrained = fitted.interpolate_fit_points().unwrap();
+        assert_eq!(constrained.control_points.len(), 7);
+        let start = constrained.derivative_at(0.0).unwrap().as_vec2();
+        let end = constrained.derivative_at(1.0).unwrap().as_vec2();
+        assert!(start.x.abs() < 1e-9 && start.y > 0.0);
+        assert!(end.y.abs() < 1e-9 && end.x > 0.0);
+        assert_eq!(constrained.point_at(0.0), Some(Point2::new(0.0, 0.0)));
+    }
+}
@@ -0,0 +1,394 @@
+//! 对象捕捉（OSNAP）：实体上的端点、中点、圆心、象限点、垂足、切点、最近点与节点。
+//!
+//! 捕捉按基本图元计算：多段线与修订云线拆为直线和圆弧，块参照与标注逐层分解，引线、多重引线与三维面
+//! 取其各边，填充与文字等不参与捕捉。垂足与切点相对给定的基点计算；交点涉及两个图元，由调用方借助
+//! [`intersect`](crate::geometry::intersect::intersect) 两两求得。节点为标注的定义点与文字中点。
+
+use std::f64::consts::{PI, TAU};
+
+use glam::DVec2;
+
+use crate::geometry::Point2;
+use crate::geometry::intersect::closest_parameter;
+use crate::tessellate::{self, TessellateOptions};
+
+use super::associate::is_full_ellipse;
+use super::{Arc, Document, Entity, Line, SnapRole, ccw_sweep};
+
+/// 分解嵌套块的最大层数，防止循环引用的块无限展开。
+const MAX_DEPTH: usize = 16;
+/// 判断点是否落在圆弧或椭圆弧范围内的角度容差。
+const ANGLE_EPSILON: f64 = 1e-9;
+
+/// 对象捕捉方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OsnapMode {
+    Endpoint,
+    Midpoint,
+    Center,
+    Quadrant,
+    Intersection,
+    /// 从基点向图元作垂线的垂足。
+    Perpendicular,
+    /// 从基点向圆或圆弧作切线的切点。
+    Tangent,
+    /// 图元上离光标最近的点。
+    Nearest,
+    /// 标注的定义点与文字中点。
+    Node,
+}
+
+impl OsnapMode {
+    pub const ALL: [OsnapMode; 9] = [
+        OsnapMode::Endpoint,
+        OsnapMode::Midpoint,
+        OsnapMode::Center,
+        OsnapMode::Quadrant,
+        OsnapMode::Intersection,
+        OsnapMode::Perpendicular,
+        OsnapMode::Tangent,
+        OsnapMode::Nearest,
+        OsnapMode::Node,
+    ];
+}
+
+impl Document {
+    /// 实体用于捕捉的基本图元：直线、圆、圆弧、椭圆、样条与标注；标注另附其分解出的图元。
+    pub fn osnap_primitives(&self, entity: &Entity) -> Vec<Entity> {
+        let mut primitives = Vec::new();
+        self.collect_primitives(entity, 0, &mut primitives);
+        primitives
+    }
+
+    fn collect_primitives(&self, entity: &Entity, depth: usize, primitives: &mut Vec<Entity>) {
+        match entity {
+            Entity::Line(_)
+            | Entity::Circle(_)
+            | Entity::Arc(_)
+            | Entity::Ellipse(_)
+            | Entity::Spline(_) => primitives.push(entity.clone()),
+            Entity::Hatch(_) => {}
+            Entity::Leader(leader) => {
+                primitives.extend(edges(&leader.vertices, false, &leader.layer));
+            }
+            Entity::MLeader(mleader) => {
+                for line in &mleader.leader_lines {
+                    primitives.extend(edges(&line.vertices, false, &mleader.layer));
+                }
+            }
+            Entity::Face3D(face) => {
+                let corners = face
+                    .vertices
+                    .map(|vertex| Point2::new(vertex.x(), vertex.y()));
+                primitives.extend(edges(&corners, true, &face.layer));
+            }
+            _ => {
+                if matches!(entity, Entity::Dimension(_)) {
+                    primitives.push(entity.clone());
+                }
+                if depth < MAX_DEPTH
+                    && let Some(parts) = self.exploded(entity)
+                {
+                    for part in &parts {
+                        self.collect_primitives(part, depth + 1, primitives);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Entity {
+    /// 基本图元（见 [`Document::osnap_primitives`]）上给定方式的捕捉点。`cursor` 用于最近点；
+    /// `base` 为垂足与切点的基点，没有基点时不产生这两种点。交点不由单个图元给出，总是为空。
+    pub fn osnap_points(
+        &self,
+        mode: OsnapMode,
+        cursor: Point2,
+        base: Option<Point2>,
+    ) -> Vec<Point2> {
+        match mode {
+            OsnapMode::Endpoint => match self {
+                Entity::Spline(spline) if !spline.is_closed => {
+                    tessellate::curve(self, &TessellateOptions::default())
+                        .and_then(|points| Some(vec![*points.first()?, *points.last()?]))
+                        .unwrap_or_default()
+                }
+                _ => [SnapRole::Start, SnapRole::End]
+                    .into_iter()
+                    .filter_map(|role| self.primitive_snap_point(role))
+                    .collect(),
+            },
+            OsnapMode::Midpoint => self
+                .primitive_snap_point(SnapRole::Midpoint)
+                .into_iter()
+                .collect(),
+            OsnapMode::Center => self
+                .primitive_snap_point(SnapRole::Center)
+                .into_iter()
+                .collect(),
+            OsnapMode::Quadrant => match self {
+                Entity::Arc(arc) => (0..4)
+                    .map(|quarter| f64::from(quarter) * PI / 2.0)
+                    .filter(|angle| on_arc(arc, *angle))
+                    .map(|angle| polar(arc.center.as_vec2(), arc.radius, angle))
+                    .collect(),
+                Entity::Ellipse(ellipse) => (0..4u8)
+                    .filter(|quarter| {
+                        is_full_ellipse(ellipse)
+                            || within_sweep(
+                                ellipse.start_parameter,
+                                ellipse.end_parameter,
+                                f64::from(*quarter) * PI / 2.0,
+                            )
+                    })
+                    .filter_map(|quarter| self.snap_point(SnapRole::Quadrant(quarter)))
+                    .collect(),
+                Entity::Circle(_) => (0..4u8)
+                    .filter_map(|quarter| self.snap_point(SnapRole::Quadrant(quarter)))
+                    .collect(),
+                _ => Vec::new(),
+            },
+            OsnapMode::Perpendicular => base.map_or_else(Vec::new, |base| self.perpendicular(base)),
+            OsnapMode::Tangent => base.map_or_else(Vec::new, |base| self.tangent(base)),
+            OsnapMode::Nearest => match self {
+                Entity::Dimension(_) => Vec::new(),
+                _ => self.closest_point(cursor).into_iter().collect(),
+            },
+            OsnapMode::Node => match self {
+                Entity::Dimension(dimension) => {
+                    let mut points = vec![dimension.definition_point, dimension.text_midpoint];
+                    points.extend(
+                        [
+                            dimension.dimension_line_point,
+                            dimension.extension_line_origin,
+                            dimension.extension_line_end,
+                            dimension.secondary_point,
+                            dimension.arc_definition_point,
+                            dimension.center_point,
+                        ]
+                        .into_iter()
+                        .flatten(),
+                    );
+                    points
+                }
+                _ => Vec::new(),
+            },
+            OsnapMode::Intersection => Vec::new(),
+        }
+    }
+
+    /// 仅对基本图元取特征点，标注等其余实体没有端点、中点与圆心。
+    fn primitive_snap_point(&self, role: SnapRole) -> Option<Point2> {
+        match self {
+            Entity::Line(_) | Entity::Circle(_) | Entity::Arc(_) | Entity::Ellipse(_) => {
+                self.snap_point(role)
+            }
+            _ => None,
+        }
+    }
+
+    fn perpendicular(&self, base: Point2) -> Vec<Point2> {
+        let target = base.as_vec2();
+        match self {
+            Entity::Line(line) => {
+                let (start, end) = (line.start.as_vec2(), line.end.as_vec2());
+                let direction = end - start;
+                let length_squared = direction.length_squared();
+                if length_squared <= f64::EPSILON {
+                    return Vec::new();
+                }
+                let t = (target - start).dot(direction) / length_squared;
+                if (-ANGLE_EPSILON..=1.0 + ANGLE_EPSILON).contains(&t) {
+                    vec![Point2::from(start + direction * t)]
+                } else {
+                    Vec::new()
+                }
+            }
+            Entity::Circle(circle) => {
+                radial(circle.center.as_vec2(), circle.radius, target, |_| true)
+            }
+            Entity::Arc(arc) => radial(arc.center.as_vec2(), arc.radius, target, |angle| {
+                on_arc(arc, angle)
+            }),
+            Entity::Ellipse(_) | Entity::Spline(_) => closest_parameter(self, base)
+                .map(|(_, point)| point)
+                .into_iter()
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn tangent(&self, base: Point2) -> Vec<Point2> {
+        let (center, radius, arc) = match self {
+            Entity::Circle(circle) => (circle.center.as_vec2(), circle.radius, None),
+            Entity::Arc(arc) => (arc.center.as_vec2(), arc.radius, Some(arc)),
+            _ => return Vec::new(),
+        };
+        let offset = base.as_vec2() - center;
+        let distance = offset.length();
+        if distance <= radius {
+            return Vec::new();
+        }
+        let direction = offset.y.atan2(offset.x);
+        let spread = (radius / distance).acos();
+        [direction - spread, direction + spread]
+            .into_iter()
+            .filter(|angle| arc.is_none_or(|arc| on_arc(arc, *angle)))
+            .map(|angle| polar(center, radius, angle))
+            .collect()
+    }
+}
+
+/// 圆上与基点连线通过圆心的两个点（近点与远点）。
+fn radial(center: DVec2, radius: f64, target: DVec2, accepts: impl Fn(f64) -> bool) -> Vec<Point2> {
+    let offset = target - center;
+    if offset.length_squared() <= f64::EPSILON {
+        return Vec::new();
+    }
+    let direction = offset.y.atan2(offset.x);
+    [direction, direction + PI]
+        .into_iter()
+        .filter(|angle| accepts(*angle))
+        .map(|angle| polar(center, radius, angle))
+        .collect()
+}
+
+fn polar(center: DVec2, radius: f64, angle: f64) -> Point2 {
+    Point2::from(center + DVec2::from_angle(angle) * radius)
+}
+
+fn on_arc(arc: &Arc, angle: f64) -> bool {
+    within_sweep(arc.start_angle, arc.end_angle, angle)
+}
+
+/// 角度是否落在自 `start` 逆时针转到 `end` 的范围内。
+fn within_sweep(start: f64, end: f64, angle: f64) -> bool {
+    let offset = (angle - start).rem_euclid(TAU);
+    offset <= ccw_sweep(start, end) + ANGLE_EPSILON || offset >= TAU - ANGLE_EPSILON
+}
+
+/// 折线各边对应的直线，跳过零长度的边。
+fn edges(points: &[Point2], is_closed: bool, layer: &str) -> Vec<Entity> {
+    let closing = is_closed
+        .then(|| Some((*points.last()?, *points.first()?)))
+        .flatten();
+    points
+        .windows(2)
+        .map(|pair| (pair[0], pair[1]))
+        .chain(closing)
+        .filter(|(start, end)| start != end)
+        .map(|(start, end)| {
+            Entity::Line(Line {
+                start,
+                end,
+                layer: layer.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Circle;
+    use super::*;
+
+    fn sorted(points: Vec<Point2>) -> Vec<(i64, i64)> {
+        let mut rounded: Vec<_> = points
+            .into_iter()
+            .map(|point| {
+                (
+                    (point.x() * 1e6).round() as i64,
+                    (point.y() * 1e6).round() as i64,
+                )
+            })
+            .collect();
+        rounded.sort_unstable();
+        rounded
+    }
+
+    #[test]
+    fn primitives_expose_feature_points() {
+        let mut doc = Document::new();
+        let polyline = doc.add_polyline(
+            [
+                Point2::new(0.0, 0.0),
+                Point2::new(10.0, 0.0),
+                Point2::new(10.0, 10.0),
+            ],
+            false,
+            "0",
+        );
+        let primitives = doc.osnap_primitives(doc.entity(polyline).unwrap());
+        assert_eq!(primitives.len(), 2);
+        let cursor = Point2::new(0.0, 0.0);
+        assert_eq!(
+            primitives[1].osnap_points(OsnapMode::Midpoint, cursor, None),
+            [Point2::new(10.0, 5.0)]
+        );
+
+        let arc = Entity::Arc(Arc {
+            center: Point2::new(0.0, 0.0),
+            radius: 5.0,
+            start_angle: 0.0,
+            end_angle: PI,
+            layer: "0".into(),
+        });
+        assert_eq!(
+            sorted(arc.osnap_points(OsnapMode::Quadrant, cursor, None)),
+            sorted(vec![
+                Point2::new(5.0, 0.0),
+                Point2::new(0.0, 5.0),
+                Point2::new(-5.0, 0.0),
+            ])
+        );
+        // 基点在圆弧下方时远点不在圆弧上
+        let base = Some(Point2::new(0.0, -10.0));
+        assert_eq!(
+            sorted(arc.osnap_points(OsnapMode::Perpendicular, cursor, base)),
+            sorted(vec![Point2::new(0.0, 5.0)])
+        );
+        assert!(
+            arc.osnap_points(OsnapMode::Tangent, cursor, None)
+                .is_empty()
+        );
+
+        // 从 (10, 0) 向半径 5 的圆作切线，切点与圆心、基点构成 60° 角
+        let circle = Entity::Circle(Circle {
+            center: Point2::new(0.0, 0.0),
+            radius: 5.0,
+            layer: "0".into(),
+        });
+        let half = 3.0_f64.sqrt() * 2.5;
+        assert_eq!(
+            sorted(circle.osnap_points(OsnapMode::Tangent, cursor, Some(Point2::new(10.0, 0.0)))),
+            sorted(vec![Point2::new(2.5, half), Point2::new(2.5, -half)])
+        );
+        assert_eq!(
+            sorted(circle.osnap_points(OsnapMode::Nearest, Point2::new(0.0, 8.0), None)),
+            sorted(vec![Point2::new(0.0, 5.0)])
+        );
+
+        let line = Entity::Line(Line {
+            start: Point2::new(0.0, 0.0),
+            end: Point2::new(10.0, 0.0),
+            layer: "0".into(),
+        });
+        assert_eq!(
+            line.osnap_points(
+                OsnapMode::Perpendicular,
+                cursor,
+                Some(Point2::new(4.0, 3.0))
+            ),
+            [Point2::new(4.0, 0.0)]
+        );
+        assert!(
+            line.osnap_points(
+                OsnapMode::Perpendicular,
+                cursor,
+                Some(Point2::new(14.0, 3.0))
+            )
+            .is_empty()
+        );
+    }
+}
@@ -0,0 +1,281 @@
+//! 多段线顶点编辑（PEDIT）：插入、删除、移动顶点，修改凸度，反向与打开/闭合。
+//!
+//! 顶点 `i` 的凸度描述从该顶点到下一顶点的一段；闭合多段线最后一个顶点的凸度描述回到起点的闭合段，
+//! 开放多段线最后一个顶点的凸度不起作用。编辑保持编号、图层与特性覆盖不变。
+
+use std::fmt;
+
+use crate::geometry::Point2;
+
+use super::{Document, Entity, EntityId, Polyline, PolylineVertex};
+
+/// 多段线至少保留的顶点数。
+const MIN_VERTICES: usize = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolylineEditError {
+    NotFound(EntityId),
+    NotPolyline(EntityId),
+    /// 顶点序号越界。
+    VertexOutOfRange {
+        index: usize,
+        len: usize,
+    },
+    /// 删除后顶点数将少于两个。
+    TooFewVertices,
+    /// 简化或拟合公差必须为正的有限值。
+    InvalidTolerance,
+}
+
+impl fmt::Display for PolylineEditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolylineEditError::NotFound(id) => write!(f, "entity {} not found", id.get()),
+            PolylineEditError::NotPolyline(id) => {
+                write!(f, "entity {} is not a polyline", id.get())
+            }
+            PolylineEditError::VertexOutOfRange { index, len } => {
+                write!(f, "vertex {index} out of range for {len} vertices")
+            }
+            PolylineEditError::TooFewVertices => {
+                write!(f, "a polyline needs at least {MIN_VERTICES} vertices")
+            }
+            PolylineEditError::InvalidTolerance => {
+                write!(f, "tolerance must be positive and finite")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PolylineEditError {}
+
+impl Document {
+    /// 在 `index` 处插入顶点（`index` 等于顶点数时追加到末尾）。
+    pub fn insert_vertex(
+        &mut self,
+        id: EntityId,
+        index: usize,
+        vertex: PolylineVertex,
+    ) -> Result<(), PolylineEditError> {
+        let polyline = self.polyline_mut(id)?;
+        let len = polyline.vertices.len();
+        if index > len {
+            return Err(PolylineEditError::VertexOutOfRange { index, len });
+        }
+        polyline.vertices.insert(index, vertex);
+        Ok(())
+    }
+
+    /// 删除顶点并返回它；多段线至少保留两个顶点。
+    pub fn remove_vertex(
+        &mut self,
+        id: EntityId,
+        index: usize,
+    ) -> Result<PolylineVertex, PolylineEditError> {
+        let polyline = self.polyline_mut(id)?;
+        let len = checked_index(polyline, index)?;
+        if len <= MIN_VERTICES {
+            return Err(PolylineEditError::TooFewVertices);
+        }
+        Ok(polyline.vertices.remove(index))
+    }
+
+    pub fn move_vertex(
+        &mut self,
+        id: EntityId,
+        index: usize,
+        position: Point2,
+    ) -> Result<(), PolylineEditError> {
+        let polyline = self.polyline_mut(id)?;
+        checked_index(polyline, index)?;
+        polyline.vertices[index].position = position;
+        Ok(())
+    }
+
+    /// 修改从顶点 `index` 出发的一段的凸度，0 为直线段。
+    pub fn set_bulge(
+        &mut self,
+        id: EntityId,
+        index: usize,
+        bulge: f64,
+    ) -> Result<(), PolylineEditError> {
+        let polyline = self.polyline_mut(id)?;
+        checked_index(polyline, index)?;
+        polyline.vertices[index].bulge = bulge;
+        Ok(())
+    }
+
+    /// 反转多段线方向，几何形状不变。
+    pub fn reverse_polyline(&mut self, id: EntityId) -> Result<(), PolylineEditError> {
+        let polyline = self.polyline_mut(id)?;
+        polyline.vertices = reversed_vertices(&polyline.vertices, polyline.is_closed);
+        Ok(())
+    }
+
+    /// 打开或闭合多段线；闭合段为直线，打开时丢弃原闭合段的凸度。
+    pub fn set_polyline_closed(
+        &mut self,
+        id: EntityId,
+        is_closed: bool,
+    ) -> Result<(), PolylineEditError> {
+        let polyline = self.polyline_mut(id)?;
+        if polyline.is_closed != is_closed
+            && let Some(last) = polyline.vertices.last_mut()
+        {
+            last.bulge = 0.0;
+        }
+        polyline.is_closed = is_closed;
+        Ok(())
+    }
+
+    pub(super) fn polyline_mut(
+        &mut self,
+        id: EntityId,
+    ) -> Result<&mut Polyline, PolylineEditError> {
+        if !matches!(self.entity(id), Some(Entity::Polyline(_))) {
+            return Err(match self.entity(id) {
+                Some(_) => PolylineEditError::NotPolyline(id),
+                None => PolylineEditError::NotFound(id),
+            });
+        }
+        self.record_modified(id);
+        // 顶点编辑不改变图层
+        match self.entities.get_geometry_mut(id) {
+            Some(Entity::Polyline(polyline)) => Ok(polyline),
+            _ => Err(PolylineEditError::NotFound(id)),
+        }
+    }
+}
+
+fn checked_index(polyline: &Polyline, index: usize) -> Result<usize, PolylineEditError> {
+    let len = polyline.vertices.len();
+    if index < len {
+        Ok(len)
+    } else {
+        Err(PolylineEditError::VertexOutOfRange { index, len })
+    }
+}
+
+/// 反向后的顶点：顺序倒转，每段凸度取反并移到该段新的起点上。开放路径新终点的凸度为 0。
+pub(super) fn reversed_vertices(
+    vertices: &[PolylineVertex],
+    is_closed: bool,
+) -> Vec<PolylineVertex> {
+    let count = vertices.len();
+    (0..count)
+        .rev()
+        .map(|index| {
+            let bulge = match index {
+                0 if is_closed => -vertices[count - 1].bulge,
+                0 => 0.0,
+                _ => -vertices[index - 1].bulge,
+            };
+            PolylineVertex::with_bulge(vertices[index].position, bulge)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(doc: &Document, id: EntityId) -> (Vec<(f64, f64, f64)>, bool) {
+        let Some(Entity::Polyline(polyline)) = doc.entity(id) else {
+            panic!("expected polyline");
+        };
+        (
+            polyline
+                .vertices
+                .iter()
+                .map(|vertex| (vertex.position.x(), vertex.position.y(), vertex.bulge))
+                .collect(),
+            polyline.is_closed,
+        )
+    }
+
+    #[test]
+    fn edits_vertices_and_keeps_bounds_current() {
+        let mut doc = Document::new();
+        let id = doc.add_polyline(
+            [Point2::new(0.0, 0.0), Point2::new(4.0, 0.0)],
+            false,
+            "EDGE",
+        );
+        assert_eq!(doc.bounds().unwrap().max(), Point2::new(4.0, 0.0));
+
+        doc.insert_vertex(
+            id,
+            1,
+            PolylineVertex::with_bulge(Point2::new(2.0, 2.0), 0.5),
+        )
+        .unwrap();
+        doc.move_vertex(id, 2, Point2::new(6.0, 0.0)).unwrap();
+        doc.set_bulge(id, 0, -1.0).unwrap();
+        assert_eq!(
+            snapshot(&doc, id),
+            (
+                vec![(0.0, 0.0, -1.0), (2.0, 2.0, 0.5), (6.0, 0.0, 0.0)],
+                false
+            )
+        );
+        assert_eq!(doc.bounds().unwrap().max().x(), 6.0);
+        assert_eq!(doc.query_point(Point2::new(6.0, 0.0), 0.1), vec![id]);
+        assert_eq!(doc.entities_on_layer("EDGE").count(), 1);
+
+        assert_eq!(
+            doc.remove_vertex(id, 1).unwrap().position,
+            Point2::new(2.0, 2.0)
+        );
+        assert_eq!(
+            doc.remove_vertex(id, 0).unwrap_err(),
+            PolylineEditError::TooFewVertices
+        );
+        assert_eq!(
+            doc.move_vertex(id, 5, Point2::new(0.0, 0.0)),
+            Err(PolylineEditError::VertexOutOfRange { index: 5, len: 2 })
+        );
+        let line = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+        assert_eq!(
+            doc.reverse_polyline(line),
+            Err(PolylineEditError::NotPolyline(line))
+        );
+        assert_eq!(
+            doc.set_bulge(EntityId::new(999), 0, 0.0),
+            Err(PolylineEditError::NotFound(EntityId::new(999)))
+        );
+    }
+
+    #[test]
+    fn reversing_moves_bulges_to_segment_starts() {
+        let mut doc = Document::new();
+        let id = doc.add_polyline_with_vertices(
+            vec![
+                PolylineVertex::with_bulge(Point2::new(0.0, 0.0), 0.25),
+                PolylineVertex::with_bulge(Point2::new(1.0, 0.0), 0.5),
+                PolylineVertex::with_bulge(Point2::new(1.0, 1.0), 0.75),
+            ],
+            true,
+            "0",
+        );
+        doc.reverse_polyline(id).unwrap();
+        assert_eq!(
+            snapshot(&doc, id),
+            (
+                vec![(1.0, 1.0, -0.5), (1.0, 0.0, -0.25), (0.0, 0.0, -0.75)],
+                true
+            )
+        );
+
+        doc.set_polyline_closed(id, false).unwrap();
+        let (vertices, is_closed) = snapshot(&doc, id);
+        assert!(!is_closed);
+        assert_eq!(vertices[2], (0.0, 0.0, 0.0));
+        doc.reverse_polyline(id).unwrap();
+        assert_eq!(
+            snapshot(&doc, id).0,
+            vec![(0.0, 0.0, 0.25), (1.0, 0.0, 0.5), (1.0, 1.0, 0.0)]
+        );
+        doc.set_polyline_closed(id, true).unwrap();
+        assert!(snapshot(&doc, id).1);
+    }
+}
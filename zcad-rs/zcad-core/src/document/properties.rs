@@ -0,0 +1,340 @@
+//! 对象特性（颜色、线型、线宽）与随层/随块解析。
+//!
+//! 图层保存新建实体的默认特性；实体只在特性不是“随层”时才在文档中记录覆盖值，
+//! 渲染与导出统一通过 [`Document::resolve_properties`] 取得最终生效的特性。
+
+use serde::{Deserialize, Serialize};
+
+use super::{Document, EntityId, Layer};
+
+/// 随层线型名。
+pub const LINETYPE_BYLAYER: &str = "ByLayer";
+/// 随块线型名。
+pub const LINETYPE_BYBLOCK: &str = "ByBlock";
+/// 实线线型名，也是图层的默认线型。
+pub const LINETYPE_CONTINUOUS: &str = "Continuous";
+
+/// 图层默认颜色（ACI 7，白/黑）。
+const DEFAULT_LAYER_COLOR: u8 = 7;
+
+/// 对象颜色（DXF 组码 62/420）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectColor {
+    #[default]
+    ByLayer,
+    ByBlock,
+    /// AutoCAD 颜色索引 1..=255。
+    Index(u8),
+    /// 24 位真彩色，`0xRRGGBB`。
+    TrueColor(u32),
+}
+
+impl ObjectColor {
+    /// 由 ACI 组码值构造：0 为随块，256 为随层；负值表示图层关闭，取其绝对值。
+    pub fn from_aci(code: i16) -> Self {
+        match code.unsigned_abs() {
+            0 => ObjectColor::ByBlock,
+            index @ 1..=255 => ObjectColor::Index(index as u8),
+            _ => ObjectColor::ByLayer,
+        }
+    }
+
+    /// 对应的 ACI 组码值；真彩色没有精确的索引，返回 `None`。
+    pub fn aci(self) -> Option<i16> {
+        match self {
+            ObjectColor::ByLayer => Some(256),
+            ObjectColor::ByBlock => Some(0),
+            ObjectColor::Index(index) => Some(index as i16),
+            ObjectColor::TrueColor(_) => None,
+        }
+    }
+
+    pub fn is_by_layer(self) -> bool {
+        matches!(self, ObjectColor::ByLayer)
+    }
+
+    /// 具体颜色的 `0xRRGGBB` 值，随层/随块返回 `None`。ACI 采用与前端一致的
+    /// 简化调色板：标准 7 色之外统一为灰色，7 号按白色处理。
+    pub fn rgb(self) -> Option<u32> {
+        match self {
+            ObjectColor::ByLayer | ObjectColor::ByBlock => None,
+            ObjectColor::Index(index) => Some(match index {
+                1 => 0xFF0000,
+                2 => 0xFFFF00,
+                3 => 0x00FF00,
+                4 => 0x00FFFF,
+                5 => 0x0000FF,
+                6 => 0xFF00FF,
+                7 => 0xFFFFFF,
+                _ => 0xB3B3B3,
+            }),
+            ObjectColor::TrueColor(rgb) => Some(rgb & 0xFF_FFFF),
+        }
+    }
+}
+
+/// 对象线宽（DXF 组码 370），具体值以 0.01 mm 为单位。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineWeight {
+    #[default]
+    ByLayer,
+    ByBlock,
+    /// 使用系统默认线宽（LWDEFAULT）。
+    Default,
+    Hundredths(u16),
+}
+
+impl LineWeight {
+    pub fn from_code(code: i16) -> Self {
+        match code {
+            -1 => LineWeight::ByLayer,
+            -2 => LineWeight::ByBlock,
+            value if value >= 0 => LineWeight::Hundredths(value as u16),
+            _ => LineWeight::Default,
+        }
+    }
+
+    pub fn code(self) -> i16 {
+        match self {
+            LineWeight::ByLayer => -1,
+            LineWeight::ByBlock => -2,
+            LineWeight::Default => -3,
+            LineWeight::Hundredths(value) => value as i16,
+        }
+    }
+
+    /// 以毫米表示的具体线宽；随层、随块与默认值返回 `None`。
+    pub fn millimeters(self) -> Option<f64> {
+        match self {
+            LineWeight::Hundredths(value) => Some(value as f64 / 100.0),
+            _ => None,
+        }
+    }
+}
+
+/// 实体自身记录的特性，缺省全部随层。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntityProperties {
+    #[serde(default)]
+    pub color: ObjectColor,
+    #[serde(default = "default_entity_linetype")]
+    pub linetype: String,
+    #[serde(default)]
+    pub lineweight: LineWeight,
+}
+
+impl Default for EntityProperties {
+    fn default() -> Self {
+        Self {
+            color: ObjectColor::ByLayer,
+            linetype: LINETYPE_BYLAYER.to_string(),
+            lineweight: LineWeight::ByLayer,
+        }
+    }
+}
+
+impl EntityProperties {
+    /// 所有特性均随层时无需在文档中单独记录。
+    pub fn is_by_layer(&self) -> bool {
+        self.color.is_by_layer()
+            && self.linetype.eq_ignore_ascii_case(LINETYPE_BYLAYER)
+            && self.lineweight == LineWeight::ByLayer
+    }
+}
+
+/// 解析随层/随块之后实际生效的特性。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedProperties {
+    /// 具体颜色，只会是 [`ObjectColor::Index`] 或 [`ObjectColor::TrueColor`]。
+    pub color: ObjectColor,
+    pub linetype: String,
+    /// 具体线宽或 [`LineWeight::Default`]。
+    pub lineweight: LineWeight,
+}
+
+impl Default for ResolvedProperties {
+    fn default() -> Self {
+        Self {
+            color: ObjectColor::Index(DEFAULT_LAYER_COLOR),
+            linetype: LINETYPE_CONTINUOUS.to_string(),
+            lineweight: LineWeight::Default,
+        }
+    }
+}
+
+pub(super) fn default_layer_color() -> ObjectColor {
+    ObjectColor::Index(DEFAULT_LAYER_COLOR)
+}
+
+pub(super) fn default_layer_linetype() -> String {
+    LINETYPE_CONTINUOUS.to_string()
+}
+
+pub(super) fn default_layer_lineweight() -> LineWeight {
+    LineWeight::Default
+}
+
+fn default_entity_linetype() -> String {
+    LINETYPE_BYLAYER.to_string()
+}
+
+impl Layer {
+    /// 图层默认特性，随层实体以此为准。
+    pub fn default_properties(&self) -> ResolvedProperties {
+        let fallback = ResolvedProperties::default();
+        ResolvedProperties {
+            color: match self.color {
+                ObjectColor::ByLayer | ObjectColor::ByBlock => fallback.color,
+                concrete => concrete,
+            },
+            linetype: if self.linetype.eq_ignore_ascii_case(LINETYPE_BYLAYER)
+                || self.linetype.eq_ignore_ascii_case(LINETYPE_BYBLOCK)
+                || self.linetype.is_empty()
+            {
+                fallback.linetype
+            } else {
+                self.linetype.clone()
+            },
+            lineweight: match self.lineweight {
+                LineWeight::ByLayer | LineWeight::ByBlock => fallback.lineweight,
+                concrete => concrete,
+            },
+        }
+    }
+}
+
+impl Document {
+    #[inline]
+    pub fn layer(&self, name: &str) -> Option<&Layer> {
+        self.layers.get(name)
+    }
+
+    #[inline]
+    pub fn layer_mut(&mut self, name: &str) -> Option<&mut Layer> {
+        self.layers.get_mut(name)
+    }
+
+    /// 实体记录的特性覆盖；未记录时表示全部随层。
+    #[inline]
+    pub fn entity_properties(&self, id: EntityId) -> Option<&EntityProperties> {
+        self.entity_properties.get(&id)
+    }
+
+    /// 设置实体特性，全部随层时清除覆盖记录。
+    pub fn set_entity_properties(&mut self, id: EntityId, properties: EntityProperties) {
+        if properties.is_by_layer() {
+            self.entity_properties.remove(&id);
+        } else {
+            self.entity_properties.insert(id, properties);
+        }
+    }
+
+    /// 解析实体最终生效的特性；实体不存在时返回 `None`。
+    /// 模型空间中没有外层块参照，随块特性按默认值处理。
+    pub fn resolve_properties(&self, id: EntityId) -> Option<ResolvedProperties> {
+        let entity = self.entity(id)?;
+        Some(self.resolve_layer_properties(
+            entity.layer_name(),
+            self.entity_properties.get(&id),
+            None,
+        ))
+    }
+
+    /// 按图层与实体特性解析；`by_block` 为外层块参照已解析的特性，
+    /// 用于块内随块对象。
+    pub fn resolve_layer_properties(
+        &self,
+        layer: &str,
+        properties: Option<&EntityProperties>,
+        by_block: Option<&ResolvedProperties>,
+    ) -> ResolvedProperties {
+        let layer_defaults = self
+            .layers
+            .get(layer)
+            .map(Layer::default_properties)
+            .unwrap_or_default();
+        let Some(properties) = properties else {
+            return layer_defaults;
+        };
+        let block_defaults = by_block.cloned().unwrap_or_default();
+        ResolvedProperties {
+            color: match properties.color {
+                ObjectColor::ByLayer => layer_defaults.color,
+                ObjectColor::ByBlock => block_defaults.color,
+                concrete => concrete,
+            },
+            linetype: if properties.linetype.eq_ignore_ascii_case(LINETYPE_BYLAYER)
+                || properties.linetype.is_empty()
+            {
+                layer_defaults.linetype
+            } else if properties.linetype.eq_ignore_ascii_case(LINETYPE_BYBLOCK) {
+                block_defaults.linetype
+            } else {
+                properties.linetype.clone()
+            },
+            lineweight: match properties.lineweight {
+                LineWeight::ByLayer => layer_defaults.lineweight,
+                LineWeight::ByBlock => block_defaults.lineweight,
+                concrete => concrete,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Point2;
+
+    #[test]
+    fn by_layer_entities_follow_layer_defaults() {
+        let mut doc = Document::new();
+        let id = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "WALL");
+        let layer = doc.layer_mut("WALL").expect("layer created by add_line");
+        layer.color = ObjectColor::Index(1);
+        layer.linetype = "DASHED".to_string();
+        layer.lineweight = LineWeight::Hundredths(50);
+
+        let resolved = doc.resolve_properties(id).expect("entity exists");
+        assert_eq!(resolved.color, ObjectColor::Index(1));
+        assert_eq!(resolved.linetype, "DASHED");
+        assert_eq!(resolved.lineweight.millimeters(), Some(0.5));
+
+        doc.set_entity_properties(
+            id,
+            EntityProperties {
+                color: ObjectColor::TrueColor(0x336699),
+                ..EntityProperties::default()
+            },
+        );
+        let resolved = doc.resolve_properties(id).expect("entity exists");
+        assert_eq!(resolved.color, ObjectColor::TrueColor(0x336699));
+        assert_eq!(resolved.linetype, "DASHED");
+
+        doc.set_entity_properties(id, EntityProperties::default());
+        assert!(doc.entity_properties(id).is_none());
+    }
+
+    #[test]
+    fn by_block_properties_use_parent_reference() {
+        let doc = Document::new();
+        let properties = EntityProperties {
+            color: ObjectColor::ByBlock,
+            linetype: LINETYPE_BYBLOCK.to_string(),
+            lineweight: LineWeight::ByBlock,
+        };
+        let parent = ResolvedProperties {
+            color: ObjectColor::Index(3),
+            linetype: "CENTER".to_string(),
+            lineweight: LineWeight::Hundredths(25),
+        };
+        let resolved = doc.resolve_layer_properties("0", Some(&properties), Some(&parent));
+        assert_eq!(resolved, parent);
+        let standalone = doc.resolve_layer_properties("0", Some(&properties), None);
+        assert_eq!(standalone, ResolvedProperties::default());
+        assert_eq!(ObjectColor::from_aci(-5), ObjectColor::Index(5));
+        assert_eq!(LineWeight::from_code(-3), LineWeight::Default);
+    }
+}
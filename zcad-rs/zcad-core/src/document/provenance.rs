@@ -0,0 +1,223 @@
+//! 来源信息：记录文档与各实体的创建、修改时间以及作者与工具，用于审计追踪。
+//!
+//! 来源信息是可选的，调用 [`Document::enable_provenance`] 指定当前编辑者后开始记录：新建实体、
+//! 按编号修改、替换与删除实体，以及变换、顶点编辑、特性匹配、图层合并等按实体进行的编辑会自动更新时间戳，
+//! 任何实体变化同时更新文档的修改时间。时间以自 Unix 纪元起的毫秒数表示，随原生格式保存。
+
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Document, EntityId};
+
+/// 编辑者：作者与所用工具。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Editor {
+    pub author: String,
+    /// 生成或修改对象的程序，例如 `zcad-app 0.1`。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+}
+
+impl Editor {
+    pub fn new(author: impl Into<String>) -> Self {
+        Self {
+            author: author.into(),
+            tool: None,
+        }
+    }
+
+    pub fn with_tool(mut self, tool: impl Into<String>) -> Self {
+        self.tool = Some(tool.into());
+        self
+    }
+}
+
+/// 文档或实体的来源信息。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// 创建时间；开始记录前已存在的实体为 `None`。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<Editor>,
+    pub modified: u64,
+    pub modified_by: Editor,
+}
+
+impl Provenance {
+    fn created(now: u64, editor: &Editor) -> Self {
+        Self {
+            created: Some(now),
+            created_by: Some(editor.clone()),
+            modified: now,
+            modified_by: editor.clone(),
+        }
+    }
+
+    fn touch(&mut self, now: u64, editor: &Editor) {
+        self.modified = self.modified.max(now);
+        self.modified_by = editor.clone();
+    }
+}
+
+/// 文档中保存的来源记录。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct ProvenanceLog {
+    /// 当前编辑者，之后的修改都记在其名下。
+    editor: Editor,
+    document: Provenance,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    entities: HashMap<EntityId, Provenance>,
+}
+
+impl Document {
+    /// 以 `editor` 为当前编辑者开始记录来源信息；已在记录时只切换编辑者。
+    pub fn enable_provenance(&mut self, editor: Editor) {
+        if let Some(log) = &mut self.provenance {
+            log.editor = editor;
+            return;
+        }
+        let now = self.provenance_now();
+        self.provenance = Some(ProvenanceLog {
+            document: Provenance::created(now, &editor),
+            editor,
+            entities: HashMap::new(),
+        });
+    }
+
+    /// 停止记录并清除全部来源信息。
+    pub fn clear_provenance(&mut self) {
+        self.provenance = None;
+    }
+
+    /// 以自定义时钟（返回 Unix 毫秒数）代替系统时间，用于没有系统时钟的平台或可重复的测试。
+    pub fn set_provenance_clock(&mut self, clock: fn() -> u64) {
+        self.provenance_clock = Some(clock);
+    }
+
+    /// 当前编辑者；未记录来源信息时返回 `None`。
+    #[inline]
+    pub fn editor(&self) -> Option<&Editor> {
+        self.provenance.as_ref().map(|log| &log.editor)
+    }
+
+    #[inline]
+    pub fn document_provenance(&self) -> Option<&Provenance> {
+        self.provenance.as_ref().map(|log| &log.document)
+    }
+
+    #[inline]
+    pub fn entity_provenance(&self, id: EntityId) -> Option<&Provenance> {
+        self.provenance.as_ref()?.entities.get(&id)
+    }
+
+    /// 记录新建实体。
+    pub(super) fn stamp_created(&mut self, id: EntityId) {
+        if self.provenance.is_none() {
+            return;
+        }
+        let now = self.provenance_now();
+        let log = self.provenance.as_mut().expect("已检查正在记录");
+        log.entities
+            .insert(id, Provenance::created(now, &log.editor));
+        log.document.touch(now, &log.editor);
+    }
+
+    /// 记录实体被修改；开始记录前已存在的实体补建不含创建时间的记录。
+    pub(super) fn stamp_modified(&mut self, id: EntityId) {
+        if self.provenance.is_none() {
+            return;
+        }
+        let now = self.provenance_now();
+        let log = self.provenance.as_mut().expect("已检查正在记录");
+        let editor = &log.editor;
+        log.entities
+            .entry(id)
+            .and_modify(|provenance| provenance.touch(now, editor))
+            .or_insert_with(|| Provenance {
+                created: None,
+                created_by: None,
+                modified: now,
+                modified_by: editor.clone(),
+            });
+        log.document.touch(now, editor);
+    }
+
+    /// 记录实体被删除：移除其来源记录并更新文档的修改时间。
+    pub(super) fn stamp_removed(&mut self, id: EntityId) {
+        if self.provenance.is_none() {
+            return;
+        }
+        let now = self.provenance_now();
+        let log = self.provenance.as_mut().expect("已检查正在记录");
+        log.entities.remove(&id);
+        log.document.touch(now, &log.editor);
+    }
+
+    fn provenance_now(&self) -> u64 {
+        match self.provenance_clock {
+            Some(clock) => clock(),
+            None => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_millis() as u64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use super::*;
+    use crate::geometry::{Point2, Vector2};
+
+    static NOW: AtomicU64 = AtomicU64::new(1_000);
+
+    fn tick() -> u64 {
+        NOW.fetch_add(1, Ordering::SeqCst)
+    }
+
+    #[test]
+    fn document_edits_maintain_provenance() {
+        let mut doc = Document::new();
+        let before = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+        assert!(doc.document_provenance().is_none());
+
+        doc.set_provenance_clock(tick);
+        doc.enable_provenance(Editor::new("alice").with_tool("zcad"));
+        let opened = doc.document_provenance().unwrap().created.unwrap();
+        let circle = doc.add_circle(Point2::new(0.0, 0.0), 1.0, "0");
+        let created = doc.entity_provenance(circle).unwrap().clone();
+        assert!(created.created.unwrap() > opened);
+        assert_eq!(created.created_by.as_ref().unwrap().author, "alice");
+        assert!(doc.entity_provenance(before).is_none());
+
+        doc.enable_provenance(Editor::new("bob"));
+        doc.move_entities([circle, before], Vector2::new(1.0, 0.0));
+        let modified = doc.entity_provenance(circle).unwrap();
+        assert_eq!(modified.created, created.created);
+        assert!(modified.modified > created.modified);
+        assert_eq!(modified.modified_by, Editor::new("bob"));
+        let adopted = doc.entity_provenance(before).unwrap();
+        assert!(adopted.created.is_none());
+        assert_eq!(adopted.modified_by.author, "bob");
+
+        doc.remove_entity(circle);
+        assert!(doc.entity_provenance(circle).is_none());
+        let document = doc.document_provenance().unwrap();
+        assert_eq!(document.created_by.as_ref().unwrap().author, "alice");
+        assert_eq!(document.modified_by.author, "bob");
+        assert!(document.modified > modified_time(&doc, before));
+
+        doc.clear_provenance();
+        assert!(doc.editor().is_none());
+        assert!(doc.entity_provenance(before).is_none());
+    }
+
+    fn modified_time(doc: &Document, id: EntityId) -> u64 {
+        doc.entity_provenance(id)
+            .map_or(0, |provenance| provenance.modified)
+    }
+}
@@ -0,0 +1,416 @@
+//! 清理（PURGE）：删除未被引用的块定义、图层、线型、型文件样式与图像定义。
+//!
+//! 引用关系按模型空间与全部块定义统计；删除块后其内部引用随之失效，因此块会反复清理直到稳定，
+//! 之后再依次清理图层、线型、样式与图像定义。`0`、`Defpoints` 图层、内置线型与匿名块（`*` 开头，
+//! 由标注等对象隐式使用）始终保留。
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use super::audit::referenced_block;
+use super::{Document, Entity, LINETYPE_BYBLOCK, LINETYPE_BYLAYER, LINETYPE_CONTINUOUS};
+
+/// 始终保留的图层。
+pub(super) const PROTECTED_LAYERS: [&str; 2] = ["0", "DEFPOINTS"];
+
+/// PURGE 选项，默认清理全部类别。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PurgeOptions {
+    pub blocks: bool,
+    pub layers: bool,
+    pub linetypes: bool,
+    /// STYLE 表中的型文件记录（文档目前只保存这类文字样式）。
+    pub text_styles: bool,
+    pub images: bool,
+    /// 只列出将被删除的对象，不修改文档。
+    pub dry_run: bool,
+}
+
+impl Default for PurgeOptions {
+    fn default() -> Self {
+        Self {
+            blocks: true,
+            layers: true,
+            linetypes: true,
+            text_styles: true,
+            images: true,
+            dry_run: false,
+        }
+    }
+}
+
+/// 已删除（或试运行时将被删除）的对象，各类别按名称排序。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PurgeReport {
+    pub blocks: Vec<String>,
+    pub layers: Vec<String>,
+    pub linetypes: Vec<String>,
+    /// 型文件名，例如 `ltypeshp.shx`。
+    pub text_styles: Vec<String>,
+    /// 图像定义名称，未命名时为文件路径。
+    pub images: Vec<String>,
+}
+
+impl PurgeReport {
+    pub fn total(&self) -> usize {
+        self.blocks.len()
+            + self.layers.len()
+            + self.linetypes.len()
+            + self.text_styles.len()
+            + self.images.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+}
+
+impl Document {
+    /// 删除未被引用的符号，返回删除清单；`dry_run` 时在副本上执行，文档保持不变。
+    pub fn purge(&mut self, options: &PurgeOptions) -> PurgeReport {
+        if options.dry_run {
+            return self.clone().purge(&PurgeOptions {
+                dry_run: false,
+                ..*options
+            });
+        }
+        let mut report = PurgeReport::default();
+        if options.blocks {
+            self.purge_blocks(&mut report.blocks);
+        }
+        if options.layers {
+            self.purge_layers(&mut report.layers);
+        }
+        if options.linetypes {
+            self.purge_linetypes(&mut report.linetypes);
+        }
+        if options.text_styles {
+            self.purge_shape_files(&mut report.text_styles);
+        }
+        if options.images {
+            self.purge_image_definitions(&mut report.images);
+        }
+        for names in [
+            &mut report.blocks,
+            &mut report.layers,
+            &mut report.linetypes,
+            &mut report.text_styles,
+            &mut report.images,
+        ] {
+            names.sort();
+        }
+        report
+    }
+
+    /// 模型空间与全部块定义中的实体。
+    fn all_entities(&self) -> impl Iterator<Item = &Entity> {
+        let model = self.entities.iter().map(|(_, entity)| entity);
+        model.chain(self.blocks.values().flat_map(|block| block.entities.iter()))
+    }
+
+    fn purge_blocks(&mut self, removed: &mut Vec<String>) {
+        loop {
+            let referenced: HashSet<&str> =
+                self.all_entities().filter_map(referenced_block).collect();
+            let unused: Vec<String> = self
+                .blocks
+                .keys()
+                .filter(|name| !name.starts_with('*') && !referenced.contains(name.as_str()))
+                .cloned()
+                .collect();
+            if unused.is_empty() {
+                break;
+            }
+            for name in &unused {
+                self.blocks.remove(name);
+            }
+            self.block_handles.retain(|_, name| !unused.contains(name));
+            removed.extend(unused);
+        }
+    }
+
+    fn purge_layers(&mut self, removed: &mut Vec<String>) {
+        let mut referenced: HashSet<String> = self
+            .all_entities()
+            .flat_map(|entity| {
+                let attributes: Vec<&str> = match entity {
+                    Entity::BlockReference(reference) => reference
+                        .attributes
+                        .iter()
+                        .map(|attribute| attribute.layer.as_str())
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                attributes.into_iter().chain([entity.layer_name()])
+            })
+            .chain(
+                self.blocks
+                    .values()
+                    .flat_map(|block| &block.attributes)
+                    .map(|definition| definition.layer.as_str()),
+            )
+            .map(str::to_uppercase)
+            .collect();
+        referenced.extend(PROTECTED_LAYERS.iter().map(|name| name.to_string()));
+        let unused: Vec<String> = self
+            .layers
+            .keys()
+            .filter(|name| !referenced.contains(&name.to_uppercase()))
+            .cloned()
+            .collect();
+        for name in &unused {
+            self.layers.remove(name);
+        }
+        removed.extend(unused);
+    }
+
+    fn purge_linetypes(&mut self, removed: &mut Vec<String>) {
+        let referenced: HashSet<String> = self
+            .layers
+            .values()
+            .map(|layer| layer.linetype.as_str())
+            .chain(
+                self.entity_properties
+                    .values()
+                    .map(|properties| properties.linetype.as_str()),
+            )
+            .chain([LINETYPE_BYLAYER, LINETYPE_BYBLOCK, LINETYPE_CONTINUOUS])
+            .map(str::to_uppercase)
+            .collect();
+        let unused: Vec<String> = self
+            .linetypes
+            .keys()
+            .filter(|name| !referenced.contains(&name.to_uppercase()))
+            .cloned()
+            .collect();
+        for name in &unused {
+            self.linetypes.remove(name);
+        }
+        removed.extend(unused);
+    }
+
+    /// 型文件样式由 SHAPE 与复杂线型中的嵌入型引用，应在清理线型之后执行。
+    fn purge_shape_files(&mut self, removed: &mut Vec<String>) {
+        let mut referenced: HashSet<String> = self
+            .linetypes
+            .values()
+            .flat_map(|linetype| &linetype.elements)
+            .filter_map(|element| element.embedded.as_ref()?.style_handle.clone())
+            .collect();
+        for entity in self.all_entities() {
+            if let Entity::Shape(shape) = entity
+                && let Some(shape_file) = self.shape_file_for(shape)
+            {
+                referenced.insert(shape_file.handle.clone());
+            }
+        }
+        let unused: Vec<String> = self
+            .shape_files
+            .keys()
+            .filter(|handle| !referenced.contains(*handle))
+            .cloned()
+            .collect();
+        for handle in unused {
+            if let Some(shape_file) = self.shape_files.remove(&handle) {
+                removed.push(shape_file.file);
+            }
+        }
+    }
+
+    /// 同时删除图像字典中的对应条目与以该定义为所有者的反应器。
+    fn purge_image_definitions(&mut self, removed: &mut Vec<String>) {
+        let referenced: HashSet<&str> = self
+            .all_entities()
+            .filter_map(|entity| match entity {
+                Entity::RasterImage(image) => Some(image.image_def_handle.as_str()),
+                _ => None,
+            })
+            .collect();
+        let unused: Vec<String> = self
+            .image_definitions
+            .keys()
+            .filter(|handle| !referenced.contains(handle.as_str()))
+            .cloned()
+            .collect();
+        for handle in &unused {
+            if let Some(definition) = self.image_definitions.remove(handle) {
+                removed.push(definition.name.unwrap_or(definition.file_path));
+            }
+        }
+        self.image_def_reactors.retain(|_, reactor| {
+            reactor
+                .owner_handle
+                .as_ref()
+                .is_none_or(|owner| !unused.contains(owner))
+        });
+        if let Some(dictionary) = self.image_dictionary.as_mut() {
+            dictionary
+                .entries
+                .retain(|entry| !unused.contains(&entry.image_def_handle));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{
+        BlockDefinition, EntityProperties, ImageDefReactor, ImageDictionary, ImageDictionaryEntry,
+        Layer, Linetype, LinetypeElement, RasterImageDefinition, ShapeFile,
+    };
+    use crate::geometry::{Point2, Vector2};
+
+    fn block(name: &str, entities: Vec<Entity>) -> BlockDefinition {
+        BlockDefinition {
+            name: name.to_string(),
+            base_point: Point2::new(0.0, 0.0),
+            entities,
+            attributes: Vec::new(),
+        }
+    }
+
+    fn reference(name: &str, layer: &str) -> Entity {
+        Entity::BlockReference(crate::document::BlockReference {
+            name: name.to_string(),
+            insert: Point2::new(0.0, 0.0),
+            scale: Vector2::new(1.0, 1.0),
+            rotation: 0.0,
+            attributes: Vec::new(),
+            layer: layer.to_string(),
+        })
+    }
+
+    fn linetype(name: &str) -> Linetype {
+        Linetype {
+            name: name.to_string(),
+            description: String::new(),
+            pattern_length: 1.0,
+            elements: vec![LinetypeElement {
+                length: 0.5,
+                embedded: None,
+            }],
+        }
+    }
+
+    fn image_definition(handle: &str, name: &str) -> RasterImageDefinition {
+        RasterImageDefinition {
+            handle: handle.to_string(),
+            name: Some(name.to_string()),
+            file_path: format!("{name}.png"),
+            image_size_pixels: None,
+            pixel_size: None,
+            resolved_path: None,
+        }
+    }
+
+    fn sample() -> Document {
+        let mut document = Document::new();
+        document.add_layer(Layer {
+            linetype: "DASHED".to_string(),
+            ..Layer::new("WALL")
+        });
+        document.add_layer(Layer {
+            linetype: "HIDDEN".to_string(),
+            ..Layer::new("OLD")
+        });
+        document.ensure_layer("Defpoints");
+        document.add_linetype(linetype("DASHED"));
+        document.add_linetype(linetype("HIDDEN"));
+        document.add_linetype(linetype("CENTER"));
+        let line = document.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+        document.set_entity_properties(
+            line,
+            EntityProperties {
+                linetype: "center".to_string(),
+                ..EntityProperties::default()
+            },
+        );
+
+        // 外层块被引用，内层块只被未使用的块引用，清理后一并删除
+        document.add_block_definition(block("DOOR", vec![reference("HINGE", "WALL")]));
+        document.add_block_definition(block("HINGE", Vec::new()));
+        document.add_block_definition(block("UNUSED", vec![reference("NESTED", "OLD")]));
+        document.add_block_definition(block("NESTED", Vec::new()));
+        document.add_block_definition(block("*D1", Vec::new()));
+        document.add_block_reference(
+            "DOOR",
+            Point2::new(0.0, 0.0),
+            Vector2::new(1.0, 1.0),
+            0.0,
+            Vec::new(),
+            "0",
+        );
+
+        document.add_shape_file(ShapeFile {
+            handle: "S1".to_string(),
+            file: "ltypeshp.shx".to_string(),
+        });
+        document.add_raster_image_definition(image_definition("D1", "logo"));
+        document.add_image_def_reactor(ImageDefReactor {
+            handle: "R1".to_string(),
+            class_version: 2,
+            owner_handle: Some("D1".to_string()),
+            image_handle: None,
+        });
+        document.set_image_dictionary(ImageDictionary {
+            handle: Some("A0".to_string()),
+            entries: vec![ImageDictionaryEntry {
+                name: "logo".to_string(),
+                image_def_handle: "D1".to_string(),
+                reactor_handle: Some("R1".to_string()),
+            }],
+        });
+        document
+    }
+
+    #[test]
+    fn purge_removes_unreferenced_symbols() {
+        let mut document = sample();
+        let report = document.purge(&PurgeOptions::default());
+        assert_eq!(
+            report,
+            PurgeReport {
+                blocks: vec!["NESTED".to_string(), "UNUSED".to_string()],
+                layers: vec!["OLD".to_string()],
+                linetypes: vec!["HIDDEN".to_string()],
+                text_styles: vec!["ltypeshp.shx".to_string()],
+                images: vec!["logo".to_string()],
+            }
+        );
+        assert_eq!(report.total(), 6);
+
+        let mut blocks: Vec<_> = document.blocks().map(|block| block.name.as_str()).collect();
+        blocks.sort();
+        assert_eq!(blocks, vec!["*D1", "DOOR", "HINGE"]);
+        assert!(document.layer("Defpoints").is_some());
+        assert!(document.linetype("CENTER").is_some());
+        assert!(document.image_def_reactors().next().is_none());
+        assert!(document.image_dictionary().unwrap().entries.is_empty());
+        assert!(document.purge(&PurgeOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn dry_run_and_category_options_leave_other_symbols() {
+        let mut document = sample();
+        let preview = document.purge(&PurgeOptions {
+            dry_run: true,
+            ..PurgeOptions::default()
+        });
+        assert_eq!(preview.total(), 6);
+        assert_eq!(document.blocks().count(), 5);
+        assert!(document.layer("OLD").is_some());
+
+        let report = document.purge(&PurgeOptions {
+            blocks: false,
+            layers: false,
+            ..PurgeOptions::default()
+        });
+        assert!(report.blocks.is_empty() && report.layers.is_empty());
+        // 图层未清理时其线型仍被引用
+        assert!(report.linetypes.is_empty());
+        assert_eq!(report.text_styles, vec!["ltypeshp.shx".to_string()]);
+        assert!(document.linetype("HIDDEN").is_some());
+        assert_eq!(document.blocks().count(), 5);
+    }
+}
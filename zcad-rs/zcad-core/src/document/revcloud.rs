@@ -0,0 +1,199 @@
+//! 修订云线（REVCLOUD）：由一串外凸圆弧组成的多段线，用于圈注图纸修改。
+//!
+//! DXF 中修订云线以带 `RevcloudProps` 扩展数据的 LWPOLYLINE 保存。
+
+use serde::{Deserialize, Serialize};
+
+use super::{Document, Entity, EntityId, Polyline, PolylineVertex};
+use crate::geometry::Point2;
+
+/// 扩展数据中标识修订云线的应用名（组码 1001）。
+pub const REVCLOUD_XDATA_APP: &str = "RevcloudProps";
+
+/// 生成云线时每段圆弧的凸度，对应约 106° 的圆心角。
+const REVCLOUD_ARC_BULGE: f64 = 0.5;
+
+/// 修订云线样式。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RevisionCloudStyle {
+    #[default]
+    Normal,
+    /// 手绘样式：圆弧线宽渐变，几何与普通样式相同。
+    Calligraphy,
+}
+
+impl RevisionCloudStyle {
+    pub fn from_code(code: i16) -> Self {
+        match code {
+            1 => RevisionCloudStyle::Calligraphy,
+            _ => RevisionCloudStyle::Normal,
+        }
+    }
+
+    pub fn code(self) -> i16 {
+        match self {
+            RevisionCloudStyle::Normal => 0,
+            RevisionCloudStyle::Calligraphy => 1,
+        }
+    }
+}
+
+/// 修订云线实体。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RevisionCloud {
+    /// 云线的圆弧多段线顶点，凸度指向边界外侧。
+    pub vertices: Vec<PolylineVertex>,
+    pub is_closed: bool,
+    /// 生成云线时使用的弧长（相邻顶点的弦长上限）。
+    pub arc_length: f64,
+    pub style: RevisionCloudStyle,
+    pub layer: String,
+}
+
+impl RevisionCloud {
+    /// 沿边界生成云线：每条边按 `arc_length` 等分，每段替换为外凸圆弧。
+    pub fn from_boundary(
+        boundary: &[Point2],
+        is_closed: bool,
+        arc_length: f64,
+        style: RevisionCloudStyle,
+        layer: impl Into<String>,
+    ) -> Self {
+        let bulge = if signed_area(boundary) < 0.0 {
+            -REVCLOUD_ARC_BULGE
+        } else {
+            REVCLOUD_ARC_BULGE
+        };
+        let edge_count = if is_closed {
+            boundary.len()
+        } else {
+            boundary.len().saturating_sub(1)
+        };
+        let mut vertices = Vec::new();
+        for index in 0..edge_count {
+            let start = boundary[index];
+            let end = boundary[(index + 1) % boundary.len()];
+            let chord = end.as_vec2() - start.as_vec2();
+            let length = chord.length();
+            if length <= f64::EPSILON {
+                continue;
+            }
+            let count = if arc_length > 0.0 {
+                ((length / arc_length).round() as usize).max(1)
+            } else {
+                1
+            };
+            for step in 0..count {
+                let position =
+                    Point2::from_vec(start.as_vec2() + chord * (step as f64 / count as f64));
+                vertices.push(PolylineVertex::with_bulge(position, bulge));
+            }
+        }
+        if !is_closed && let Some(last) = boundary.last() {
+            vertices.push(PolylineVertex::new(*last));
+        }
+        Self {
+            vertices,
+            is_closed,
+            arc_length,
+            style,
+            layer: layer.into(),
+        }
+    }
+
+    /// 云线对应的普通多段线，供写出与不区分云线的下游使用。
+    pub fn to_polyline(&self) -> Polyline {
+        Polyline {
+            vertices: self.vertices.clone(),
+            is_closed: self.is_closed,
+            layer: self.layer.clone(),
+        }
+    }
+}
+
+/// 鞋带公式求有向面积，逆时针为正。
+fn signed_area(points: &[Point2]) -> f64 {
+    let count = points.len();
+    (0..count)
+        .map(|index| {
+            let a = points[index];
+            let b = points[(index + 1) % count];
+            a.x() * b.y() - b.x() * a.y()
+        })
+        .sum::<f64>()
+        / 2.0
+}
+
+impl Document {
+    /// 沿给定边界生成修订云线并加入模型空间。
+    pub fn add_revcloud(
+        &mut self,
+        boundary: &[Point2],
+        is_closed: bool,
+        arc_length: f64,
+        style: RevisionCloudStyle,
+        layer: impl Into<String>,
+    ) -> EntityId {
+        let cloud = RevisionCloud::from_boundary(boundary, is_closed, arc_length, style, layer);
+        self.add_revision_cloud(cloud)
+    }
+
+    /// 加入已有顶点的修订云线，例如从 DXF 读取的云线。
+    pub fn add_revision_cloud(&mut self, cloud: RevisionCloud) -> EntityId {
+        self.ensure_layer(&cloud.layer);
+        let id = self.next_id();
+        self.entities.push((id, Entity::RevisionCloud(cloud)));
+        id
+    }
+
+    /// 模型空间中的修订云线。
+    pub fn revision_clouds(&self) -> impl Iterator<Item = (EntityId, &RevisionCloud)> {
+        self.entities
+            .iter()
+            .filter_map(|(id, entity)| match entity {
+                Entity::RevisionCloud(cloud) => Some((*id, cloud)),
+                _ => None,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn revcloud_subdivides_edges_with_outward_bulges() {
+        let mut doc = Document::new();
+        let square = [
+            Point2::new(0.0, 0.0),
+            Point2::new(10.0, 0.0),
+            Point2::new(10.0, 10.0),
+            Point2::new(0.0, 10.0),
+        ];
+        let id = doc.add_revcloud(&square, true, 2.5, RevisionCloudStyle::Normal, "MARKUP");
+
+        let Some(Entity::RevisionCloud(cloud)) = doc.entity(id) else {
+            panic!("应为修订云线实体");
+        };
+        assert_eq!(cloud.vertices.len(), 16);
+        assert!(cloud.vertices.iter().all(|vertex| vertex.bulge > 0.0));
+        assert_eq!(doc.revision_clouds().count(), 1);
+
+        // 凸度指向外侧，范围超出原始边界
+        let bounds = doc.entity(id).and_then(Entity::bounds).expect("应有范围");
+        assert!(bounds.min().y() < 0.0 && bounds.max().x() > 10.0);
+
+        let mut clockwise = square;
+        clockwise.reverse();
+        let open = RevisionCloud::from_boundary(
+            &clockwise[..2],
+            false,
+            5.0,
+            RevisionCloudStyle::Calligraphy,
+            "MARKUP",
+        );
+        assert_eq!(open.vertices.len(), 3);
+        assert_eq!(open.vertices[2].bulge, 0.0);
+    }
+}
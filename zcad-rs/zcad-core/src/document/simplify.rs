@@ -0,0 +1,397 @@
+//! 多段线简化与圆弧拟合，用于清理导入的离散数据或数字化等高线。
+//!
+//! 简化（weed）对直线段组成的链做 Douglas–Peucker 抽稀，带凸度的段及其端点原样保留；
+//! 圆弧拟合把落在同一圆上的连续直线段合并为一个凸度段。两者都以公差限制新旧路径之间的偏差。
+
+use std::f64::consts::{PI, TAU};
+
+use glam::DVec2;
+
+use crate::geometry::Point2;
+
+use super::{Document, EntityId, Polyline, PolylineEditError, PolylineVertex};
+
+/// 视为直线段的凸度上限。
+const STRAIGHT_BULGE: f64 = 1e-12;
+
+impl Polyline {
+    /// Douglas–Peucker 简化：删除与保留路径距离不超过 `tolerance` 的顶点。
+    pub fn simplified(&self, tolerance: f64) -> Polyline {
+        let count = self.vertices.len();
+        let straight = |index: usize| self.vertices[index].bulge.abs() <= STRAIGHT_BULGE;
+        let segment_count = if self.is_closed {
+            count
+        } else {
+            count.saturating_sub(1)
+        };
+        // 凸度段的两端固定保留
+        let mut keep: Vec<bool> = (0..count)
+            .map(|index| {
+                let outgoing = index < segment_count && !straight(index);
+                let incoming = match index {
+                    0 if self.is_closed => !straight(count - 1),
+                    0 => false,
+                    _ => !straight(index - 1),
+                };
+                outgoing || incoming
+            })
+            .collect();
+        if !self.is_closed && count > 0 {
+            keep[0] = true;
+            keep[count - 1] = true;
+        }
+        if self.is_closed && count > 0 && !keep.contains(&true) {
+            let origin = self.vertices[0].position.as_vec2();
+            let farthest = (1..count)
+                .max_by(|a, b| {
+                    let da = self.vertices[*a]
+                        .position
+                        .as_vec2()
+                        .distance_squared(origin);
+                    let db = self.vertices[*b]
+                        .position
+                        .as_vec2()
+                        .distance_squared(origin);
+                    da.total_cmp(&db)
+                })
+                .unwrap_or(0);
+            keep[0] = true;
+            keep[farthest] = true;
+        }
+
+        let anchors: Vec<usize> = (0..count).filter(|index| keep[*index]).collect();
+        let pairs = match anchors.len() {
+            0 => 0,
+            len => len - 1 + usize::from(self.is_closed),
+        };
+        for pair in 0..pairs {
+            let from = anchors[pair];
+            let to = anchors[(pair + 1) % anchors.len()];
+            let to = if to <= from { to + count } else { to };
+            if !straight(from) {
+                continue;
+            }
+            let chain: Vec<DVec2> = (from..=to)
+                .map(|index| self.vertices[index % count].position.as_vec2())
+                .collect();
+            douglas_peucker(&chain, tolerance, &mut |offset| {
+                keep[(from + offset) % count] = true
+            });
+        }
+
+        Polyline {
+            vertices: (0..count)
+                .filter(|index| keep[*index])
+                .map(|index| self.vertices[index].clone())
+                .collect(),
+            is_closed: self.is_closed,
+            layer: self.layer.clone(),
+        }
+    }
+
+    /// 圆弧拟合：把至少三个顶点、偏差不超过 `tolerance` 的连续直线段合并为单个凸度段。
+    pub fn arc_fitted(&self, tolerance: f64) -> Polyline {
+        let mut points: Vec<DVec2> = self
+            .vertices
+            .iter()
+            .map(|vertex| vertex.position.as_vec2())
+            .collect();
+        if self.is_closed
+            && let Some(first) = points.first().copied()
+        {
+            points.push(first);
+        }
+        let straight = |index: usize| self.vertices[index].bulge.abs() <= STRAIGHT_BULGE;
+        let last = points.len().saturating_sub(1);
+
+        let mut vertices = Vec::with_capacity(self.vertices.len());
+        let mut index = 0;
+        while index < last {
+            if !straight(index) {
+                vertices.push(self.vertices[index].clone());
+                index += 1;
+                continue;
+            }
+            let mut best = None;
+            let mut end = index + 2;
+            while end <= last && straight(end - 1) {
+                match fit_arc(&points[index..=end], tolerance) {
+                    Some(bulge) => best = Some((end, bulge)),
+                    None => break,
+                }
+                end += 1;
+            }
+            match best {
+                Some((end, bulge)) => {
+                    vertices.push(PolylineVertex::with_bulge(
+                        Point2::from_vec(points[index]),
+                        bulge,
+                    ));
+                    index = end;
+                }
+                None => {
+                    vertices.push(PolylineVertex::new(Point2::from_vec(points[index])));
+                    index += 1;
+                }
+            }
+        }
+        if !self.is_closed
+            && let Some(vertex) = self.vertices.last()
+        {
+            vertices.push(vertex.clone());
+        }
+
+        Polyline {
+            vertices,
+            is_closed: self.is_closed,
+            layer: self.layer.clone(),
+        }
+    }
+}
+
+impl Document {
+    /// 按公差简化多段线，返回删除的顶点数。
+    pub fn simplify_polyline(
+        &mut self,
+        id: EntityId,
+        tolerance: f64,
+    ) -> Result<usize, PolylineEditError> {
+        checked_tolerance(tolerance)?;
+        let polyline = self.polyline_mut(id)?;
+        let before = polyline.vertices.len();
+        polyline.vertices = polyline.simplified(tolerance).vertices;
+        Ok(before - polyline.vertices.len())
+    }
+
+    /// 按公差把多段线中近似圆弧的直线段合并为凸度段，返回删除的顶点数。
+    pub fn fit_polyline_arcs(
+        &mut self,
+        id: EntityId,
+        tolerance: f64,
+    ) -> Result<usize, PolylineEditError> {
+        checked_tolerance(tolerance)?;
+        let polyline = self.polyline_mut(id)?;
+        let before = polyline.vertices.len();
+        polyline.vertices = polyline.arc_fitted(tolerance).vertices;
+        Ok(before - polyline.vertices.len())
+    }
+}
+
+fn checked_tolerance(tolerance: f64) -> Result<(), PolylineEditError> {
+    if tolerance.is_finite() && tolerance > 0.0 {
+        Ok(())
+    } else {
+        Err(PolylineEditError::InvalidTolerance)
+    }
+}
+
+/// 对首尾已保留的链做 Douglas–Peucker，对需要保留的内部点回调其在链中的序号。
+fn douglas_peucker(chain: &[DVec2], tolerance: f64, keep: &mut impl FnMut(usize)) {
+    let mut stack = vec![(0, chain.len() - 1)];
+    while let Some((from, to)) = stack.pop() {
+        if to <= from + 1 {
+            continue;
+        }
+        let (index, distance) = (from + 1..to)
+            .map(|index| {
+                (
+                    index,
+                    distance_to_segment(chain[index], chain[from], chain[to]),
+                )
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or((from, 0.0));
+        if distance > tolerance {
+            keep(index);
+            stack.push((from, index));
+            stack.push((index, to));
+        }
+    }
+}
+
+fn distance_to_segment(point: DVec2, start: DVec2, end: DVec2) -> f64 {
+    let direction = end - start;
+    let length_squared = direction.length_squared();
+    if length_squared <= f64::EPSILON {
+        return point.distance(start);
+    }
+    let t = ((point - start).dot(direction) / length_squared).clamp(0.0, 1.0);
+    point.distance(start + direction * t)
+}
+
+/// 过首、中、尾三点作圆，检查各点与各弦相对该圆的偏差并要求沿同一方向单调前进，成功时返回凸度。
+fn fit_arc(points: &[DVec2], tolerance: f64) -> Option<f64> {
+    let (first, middle, last) = (
+        points[0],
+        points[points.len() / 2],
+        points[points.len() - 1],
+    );
+    let center = circumcenter(first, middle, last)?;
+    let radius = first.distance(center);
+    let turn = (middle - first).perp_dot(last - middle).signum();
+
+    let mut sweep = 0.0;
+    for pair in points.windows(2) {
+        let delta = wrapped((pair[1] - center).to_angle() - (pair[0] - center).to_angle());
+        if delta * turn <= 0.0 {
+            return None;
+        }
+        // 弦中点到圆弧的距离（拱高）
+        let sagitta = radius * (1.0 - (delta / 2.0).cos());
+        if sagitta > tolerance {
+            return None;
+        }
+        sweep += delta;
+    }
+    if sweep.abs() >= TAU - 1e-9
+        || points
+            .iter()
+            .any(|point| (point.distance(center) - radius).abs() > tolerance)
+    {
+        return None;
+    }
+    Some((sweep / 4.0).tan())
+}
+
+pub(super) fn circumcenter(a: DVec2, b: DVec2, c: DVec2) -> Option<DVec2> {
+    let (ab, ac) = (b - a, c - a);
+    let denominator = 2.0 * ab.perp_dot(ac);
+    if denominator.abs() <= f64::EPSILON * ab.length_squared().max(ac.length_squared()) {
+        return None;
+    }
+    let offset = (ac.perp() * ab.length_squared() - ab.perp() * ac.length_squared()) / denominator;
+    Some(a - offset)
+}
+
+/// 把角度差折算到 (-π, π]。
+fn wrapped(angle: f64) -> f64 {
+    let angle = (angle + PI).rem_euclid(TAU) - PI;
+    if angle <= -PI { angle + TAU } else { angle }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::Entity;
+
+    fn polyline(doc: &Document, id: EntityId) -> &Polyline {
+        let Some(Entity::Polyline(polyline)) = doc.entity(id) else {
+            panic!("expected polyline");
+        };
+        polyline
+    }
+
+    #[test]
+    fn weeds_collinear_and_noisy_vertices() {
+        let mut doc = Document::new();
+        let id = doc.add_polyline_with_vertices(
+            vec![
+                PolylineVertex::new(Point2::new(0.0, 0.0)),
+                PolylineVertex::new(Point2::new(1.0, 0.01)),
+                PolylineVertex::new(Point2::new(2.0, -0.01)),
+                PolylineVertex::new(Point2::new(3.0, 0.0)),
+                PolylineVertex::with_bulge(Point2::new(4.0, 1.0), 0.5),
+                PolylineVertex::new(Point2::new(5.0, 1.0)),
+                PolylineVertex::new(Point2::new(6.0, 1.0)),
+            ],
+            false,
+            "0",
+        );
+        assert_eq!(doc.simplify_polyline(id, 0.05), Ok(2));
+        let positions: Vec<_> = polyline(&doc, id)
+            .vertices
+            .iter()
+            .map(|vertex| (vertex.position.x(), vertex.position.y(), vertex.bulge))
+            .collect();
+        assert_eq!(
+            positions,
+            vec![
+                (0.0, 0.0, 0.0),
+                (3.0, 0.0, 0.0),
+                (4.0, 1.0, 0.5),
+                (5.0, 1.0, 0.0),
+                (6.0, 1.0, 0.0)
+            ]
+        );
+
+        // 闭合矩形的边中点被删除，四角保留
+        let square = doc.add_polyline(
+            [
+                Point2::new(0.0, 0.0),
+                Point2::new(1.0, 0.0),
+                Point2::new(2.0, 0.0),
+                Point2::new(2.0, 2.0),
+                Point2::new(1.0, 2.0),
+                Point2::new(0.0, 2.0),
+            ],
+            true,
+            "0",
+        );
+        assert_eq!(doc.simplify_polyline(square, 0.01), Ok(2));
+        assert!(polyline(&doc, square).is_closed);
+
+        assert_eq!(
+            doc.simplify_polyline(id, 0.0),
+            Err(PolylineEditError::InvalidTolerance)
+        );
+        let line = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+        assert_eq!(
+            doc.simplify_polyline(line, 1.0),
+            Err(PolylineEditError::NotPolyline(line))
+        );
+    }
+
+    #[test]
+    fn fits_tessellated_arcs_into_bulges() {
+        let mut doc = Document::new();
+        // 半圆离散为 16 段，后接一段直线
+        let mut points: Vec<Point2> = (0..=16)
+            .map(|step| {
+                let angle = PI * step as f64 / 16.0;
+                Point2::new(angle.cos(), angle.sin())
+            })
+            .collect();
+        points.push(Point2::new(-1.0, -3.0));
+        let id = doc.add_polyline(points, false, "0");
+
+        assert_eq!(doc.fit_polyline_arcs(id, 0.01), Ok(15));
+        let fitted = polyline(&doc, id);
+        assert_eq!(fitted.vertices.len(), 3);
+        assert!((fitted.vertices[0].bulge - 1.0).abs() < 1e-9);
+        assert!(
+            fitted.vertices[1]
+                .position
+                .0
+                .distance(DVec2::new(-1.0, 0.0))
+                < 1e-12
+        );
+        assert_eq!(fitted.vertices[1].bulge, 0.0);
+
+        // 整圆闭合多段线拟合后仍闭合，面积与原多边形相差不超过一个弓形
+        let circle = doc.add_polyline(
+            (0..32).map(|step| {
+                let angle = TAU * step as f64 / 32.0;
+                Point2::new(2.0 * angle.cos(), 2.0 * angle.sin())
+            }),
+            true,
+            "0",
+        );
+        assert!(doc.fit_polyline_arcs(circle, 0.02).unwrap() >= 29);
+        let area = doc.entity(circle).unwrap().measure().unwrap().area;
+        assert!((area - 4.0 * PI).abs() < 0.01, "area {area}");
+        assert!(polyline(&doc, circle).is_closed);
+
+        // 公差过小时无法拟合，顶点不变
+        let zigzag = doc.add_polyline(
+            [
+                Point2::new(0.0, 0.0),
+                Point2::new(1.0, 1.0),
+                Point2::new(2.0, 0.0),
+                Point2::new(3.0, 1.0),
+            ],
+            false,
+            "0",
+        );
+        assert_eq!(doc.fit_polyline_arcs(zigzag, 1e-6), Ok(0));
+    }
+}
@@ -0,0 +1,298 @@
+//! 实体范围的空间索引（R 树），用于交互拾取与视口裁剪。
+//!
+//! 建树采用 STR（Sort-Tile-Recursive）批量装载。增量编辑不改动已装载的树：新插入的条目暂存在
+//! 待合并列表中，删除或更新的条目在树中记为失效；两者累计超过阈值时整体重建，以保持查询性能。
+//! 查询结果基于轴对齐范围，是候选集而非精确命中，调用方按需再做几何判断。
+
+use std::collections::{HashMap, HashSet};
+
+use crate::geometry::{Bounds2D, Point2};
+
+use super::EntityId;
+
+/// 每个节点最多容纳的子项数。
+const NODE_CAPACITY: usize = 16;
+/// 待合并与失效条目少于该数量时不重建。
+const REBUILD_THRESHOLD: usize = 64;
+
+#[derive(Debug, Clone)]
+struct Node {
+    bounds: Bounds2D,
+    children: Children,
+}
+
+#[derive(Debug, Clone)]
+enum Children {
+    Leaf(Vec<(EntityId, Bounds2D)>),
+    Branch(Vec<usize>),
+}
+
+/// 实体编号到范围的 R 树索引。
+#[derive(Debug, Clone, Default)]
+pub struct SpatialIndex {
+    items: HashMap<EntityId, Bounds2D>,
+    /// 扁平存储的节点，根节点位于末尾。
+    nodes: Vec<Node>,
+    /// 建树后插入、尚未装入树中的条目。
+    pending: Vec<EntityId>,
+    /// 树中已失效（被删除或更新）的条目。
+    stale: HashSet<EntityId>,
+}
+
+impl SpatialIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 批量装载条目；编号重复时保留最后一个范围。
+    pub fn from_entries(entries: impl IntoIterator<Item = (EntityId, Bounds2D)>) -> Self {
+        let mut index = Self {
+            items: entries.into_iter().collect(),
+            ..Self::default()
+        };
+        index.rebuild();
+        index
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// 已登记的范围。
+    #[inline]
+    pub fn bounds(&self, id: EntityId) -> Option<Bounds2D> {
+        self.items.get(&id).copied()
+    }
+
+    /// 插入或更新条目。
+    pub fn insert(&mut self, id: EntityId, bounds: Bounds2D) {
+        self.detach(id);
+        self.items.insert(id, bounds);
+        self.pending.push(id);
+        self.rebuild_if_fragmented();
+    }
+
+    /// 删除条目，不存在时返回 `false`。
+    pub fn remove(&mut self, id: EntityId) -> bool {
+        if !self.detach(id) {
+            return false;
+        }
+        self.rebuild_if_fragmented();
+        true
+    }
+
+    /// 按当前条目重新装载整棵树。
+    pub fn rebuild(&mut self) {
+        self.pending.clear();
+        self.stale.clear();
+        self.nodes.clear();
+        let mut level: Vec<(Bounds2D, usize)> = pack(
+            self.items
+                .iter()
+                .map(|(id, bounds)| (*bounds, (*id, *bounds)))
+                .collect(),
+        )
+        .into_iter()
+        .map(|group| {
+            let entries: Vec<_> = group.into_iter().map(|(_, entry)| entry).collect();
+            self.push_node(Children::Leaf(entries))
+        })
+        .collect();
+        while level.len() > 1 {
+            level = pack(level)
+                .into_iter()
+                .map(|group| {
+                    let children = group.into_iter().map(|(_, node)| node).collect();
+                    self.push_node(Children::Branch(children))
+                })
+                .collect();
+        }
+    }
+
+    /// 范围与 `area` 相交（含边界接触）的条目，顺序不确定。
+    pub fn query_rect(&self, area: &Bounds2D) -> Vec<EntityId> {
+        let mut found = Vec::new();
+        if area.is_empty() {
+            return found;
+        }
+        let mut stack: Vec<usize> = self.nodes.len().checked_sub(1).into_iter().collect();
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            if !node.bounds.intersects(area) {
+                continue;
+            }
+            match &node.children {
+                Children::Leaf(entries) => found.extend(
+                    entries
+                        .iter()
+                        .filter(|(id, bounds)| bounds.intersects(area) && !self.stale.contains(id))
+                        .map(|(id, _)| *id),
+                ),
+                Children::Branch(children) => stack.extend(children),
+            }
+        }
+        found.extend(
+            self.pending
+                .iter()
+                .filter(|id| self.items[*id].intersects(area))
+                .copied(),
+        );
+        found
+    }
+
+    /// 范围到 `point` 的距离不超过 `tolerance` 的条目，顺序不确定。
+    pub fn query_point(&self, point: Point2, tolerance: f64) -> Vec<EntityId> {
+        let tolerance = tolerance.abs();
+        self.query_rect(&Bounds2D::new(
+            Point2::new(point.x() - tolerance, point.y() - tolerance),
+            Point2::new(point.x() + tolerance, point.y() + tolerance),
+        ))
+    }
+
+    /// 从条目表、待合并列表中移除，树中的旧条目记为失效。
+    fn detach(&mut self, id: EntityId) -> bool {
+        if self.items.remove(&id).is_none() {
+            return false;
+        }
+        match self.pending.iter().position(|pending| *pending == id) {
+            Some(position) => {
+                self.pending.swap_remove(position);
+            }
+            None => {
+                self.stale.insert(id);
+            }
+        }
+        true
+    }
+
+    fn rebuild_if_fragmented(&mut self) {
+        let churn = self.pending.len() + self.stale.len();
+        if churn > REBUILD_THRESHOLD.max(self.items.len() / 4) {
+            self.rebuild();
+        }
+    }
+
+    fn push_node(&mut self, children: Children) -> (Bounds2D, usize) {
+        let mut bounds = Bounds2D::empty();
+        match &children {
+            Children::Leaf(entries) => {
+                for (_, entry) in entries {
+                    bounds.include_bounds(entry);
+                }
+            }
+            Children::Branch(nodes) => {
+                for node in nodes {
+                    bounds.include_bounds(&self.nodes[*node].bounds);
+                }
+            }
+        }
+        self.nodes.push(Node { bounds, children });
+        (bounds, self.nodes.len() - 1)
+    }
+}
+
+/// STR 分组：按中心 x 切成竖条，条内按中心 y 排序后每 `NODE_CAPACITY` 个成组。
+fn pack<T>(mut items: Vec<(Bounds2D, T)>) -> Vec<Vec<(Bounds2D, T)>> {
+    if items.is_empty() {
+        return Vec::new();
+    }
+    let node_count = items.len().div_ceil(NODE_CAPACITY);
+    let slice_count = (node_count as f64).sqrt().ceil() as usize;
+    let slice_len = slice_count * NODE_CAPACITY;
+    items.sort_by(|(a, _), (b, _)| a.center().x().total_cmp(&b.center().x()));
+
+    let mut groups = Vec::with_capacity(node_count);
+    let mut rest = items;
+    while !rest.is_empty() {
+        let tail = rest.split_off(slice_len.min(rest.len()));
+        let mut slice = std::mem::replace(&mut rest, tail);
+        slice.sort_by(|(a, _), (b, _)| a.center().y().total_cmp(&b.center().y()));
+        while !slice.is_empty() {
+            let tail = slice.split_off(NODE_CAPACITY.min(slice.len()));
+            groups.push(std::mem::replace(&mut slice, tail));
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f64, y: f64, size: f64) -> Bounds2D {
+        Bounds2D::new(Point2::new(x, y), Point2::new(x + size, y + size))
+    }
+
+    fn sorted(ids: Vec<EntityId>) -> Vec<u64> {
+        let mut ids: Vec<u64> = ids.into_iter().map(EntityId::get).collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// 线性扫描作为对照。
+    fn brute_force(index: &SpatialIndex, area: &Bounds2D) -> Vec<u64> {
+        let mut ids: Vec<u64> = index
+            .items
+            .iter()
+            .filter(|(_, bounds)| bounds.intersects(area))
+            .map(|(id, _)| id.get())
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    #[test]
+    fn queries_match_linear_scan_across_edits() {
+        let grid = (0..40u64).flat_map(|x| (0..40u64).map(move |y| (x, y)));
+        let mut index = SpatialIndex::from_entries(grid.map(|(x, y)| {
+            (
+                EntityId::new(x * 100 + y),
+                square(x as f64 * 10.0, y as f64 * 10.0, 5.0),
+            )
+        }));
+        assert_eq!(index.len(), 1600);
+        let areas = [
+            square(0.0, 0.0, 1.0),
+            square(-50.0, -50.0, 1000.0),
+            square(103.0, 203.0, 30.0),
+            square(6.0, 6.0, 3.0),
+            Bounds2D::empty(),
+        ];
+        let check = |index: &SpatialIndex| {
+            for area in &areas {
+                assert_eq!(sorted(index.query_rect(area)), brute_force(index, area));
+            }
+        };
+        check(&index);
+        assert_eq!(
+            sorted(index.query_point(Point2::new(7.0, 7.0), 2.0)),
+            vec![0]
+        );
+        assert!(index.query_point(Point2::new(7.5, 7.5), 2.0).is_empty());
+
+        // 增量插入、移动与删除，跨越重建阈值前后结果都应正确
+        for step in 0..500u64 {
+            let id = EntityId::new(step * 7 % 4000);
+            match step % 3 {
+                0 => index.insert(id, square(step as f64, 0.0, 2.0)),
+                1 => {
+                    index.remove(id);
+                }
+                _ => index.insert(EntityId::new(10_000 + step), square(6.5, 6.5, 1.0)),
+            }
+            if step % 50 == 0 {
+                check(&index);
+            }
+        }
+        check(&index);
+        assert!(!index.remove(EntityId::new(999_999)));
+        index.rebuild();
+        check(&index);
+    }
+}
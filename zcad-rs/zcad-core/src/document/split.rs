@@ -0,0 +1,755 @@
+//! 打断（BREAK）：在一点把直线、圆弧、多段线与样条曲线分成两段，或删除两点之间的部分。
+//!
+//! 打断点先投影到对象上最近的位置。开放对象在一点处一分为二，给出两点时删除其间的部分、保留两端；
+//! 圆、闭合多段线与闭合样条需要两点，删除从第一点沿对象方向（圆为逆时针）到第二点的部分，余下一段开放对象。
+//! 第一段沿用原编号与特性覆盖，其余各段为新编号并复制特性覆盖。
+
+use std::f64::consts::TAU;
+use std::fmt;
+
+use glam::{DVec2, DVec3};
+
+use crate::geometry::{BulgeArc, Point2};
+
+use super::nurbs::{clamped_uniform_knots, de_boor};
+use super::{Arc, Circle, Document, Entity, EntityId, Line, Polyline, PolylineVertex, Spline};
+
+/// 参数比较容差。
+const TOLERANCE: f64 = 1e-9;
+/// 样条投影时每个节点区间的初始采样数。
+const SPLINE_SAMPLES: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BreakError {
+    NotFound(EntityId),
+    /// 只能打断直线、圆弧、圆、多段线与带控制点的夹持样条。
+    Unsupported(EntityId),
+    /// 闭合对象需要两个不重合的打断点。
+    ClosedNeedsTwoPoints(EntityId),
+}
+
+impl fmt::Display for BreakError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BreakError::NotFound(id) => write!(f, "entity {} not found", id.get()),
+            BreakError::Unsupported(id) => write!(f, "entity {} cannot be broken", id.get()),
+            BreakError::ClosedNeedsTwoPoints(id) => {
+                write!(
+                    f,
+                    "closed entity {} needs two distinct break points",
+                    id.get()
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for BreakError {}
+
+impl Document {
+    /// 打断实体，返回打断后各段的编号；两点之间覆盖整个对象时实体被删除并返回空列表。
+    pub fn break_entity(
+        &mut self,
+        id: EntityId,
+        point: Point2,
+        second_point: Option<Point2>,
+    ) -> Result<Vec<EntityId>, BreakError> {
+        let entity = self.entity(id).ok_or(BreakError::NotFound(id))?;
+        let curve = Curve::of(entity).ok_or(BreakError::Unsupported(id))?;
+        let (start, end) = curve.domain();
+        let first = curve.parameter(point.as_vec2());
+        let second = second_point.map(|point| curve.parameter(point.as_vec2()));
+
+        let ranges = if curve.is_closed() {
+            let period = end - start;
+            match second {
+                Some(second) if (second - first).abs() > TOLERANCE * period.max(1.0) => {
+                    let stop = if first < second {
+                        first + period
+                    } else {
+                        first
+                    };
+                    vec![(second, stop)]
+                }
+                _ => return Err(BreakError::ClosedNeedsTwoPoints(id)),
+            }
+        } else {
+            let second = second.unwrap_or(first);
+            let (low, high) = (first.min(second), first.max(second));
+            vec![(start, low), (high, end)]
+        };
+        let span = TOLERANCE * (end - start).max(1.0);
+        let ranges: Vec<_> = ranges
+            .into_iter()
+            .filter(|(from, to)| to - from > span)
+            .collect();
+        if let [(from, to)] = ranges[..]
+            && from - start <= span
+            && end - to <= span
+        {
+            return Ok(vec![id]);
+        }
+
+        let pieces: Vec<Entity> = ranges
+            .iter()
+            .map(|&(from, to)| curve.piece(from, to))
+            .collect();
+        let properties = self.entity_properties(id).cloned();
+        let mut pieces = pieces.into_iter();
+        let Some(first_piece) = pieces.next() else {
+            self.remove_entity(id);
+            return Ok(Vec::new());
+        };
+        self.replace_entity(id, first_piece);
+        let mut ids = vec![id];
+        for piece in pieces {
+            let piece_id = self.add_entity(piece);
+            if let Some(properties) = properties.clone() {
+                self.set_entity_properties(piece_id, properties);
+            }
+            ids.push(piece_id);
+        }
+        Ok(ids)
+    }
+}
+
+/// 可打断的对象及其参数化：直线为 0..1，圆弧为相对起始角的转角，圆为角度，
+/// 多段线为段序号加段内比例，样条为节点参数。
+enum Curve<'a> {
+    Line(&'a Line),
+    Arc(&'a Arc, f64),
+    Circle(&'a Circle),
+    Polyline(&'a Polyline),
+    Spline(&'a Spline, Nurbs),
+}
+
+impl<'a> Curve<'a> {
+    fn of(entity: &'a Entity) -> Option<Self> {
+        match entity {
+            Entity::Line(line) => Some(Curve::Line(line)),
+            Entity::Arc(arc) => {
+                let sweep = (arc.end_angle - arc.start_angle).rem_euclid(TAU);
+                Some(Curve::Arc(
+                    arc,
+                    if sweep <= TOLERANCE { TAU } else { sweep },
+                ))
+            }
+            Entity::Circle(circle) => Some(Curve::Circle(circle)),
+            Entity::Polyline(polyline) if polyline.vertices.len() >= 2 => {
+                Some(Curve::Polyline(polyline))
+            }
+            Entity::Spline(spline) => Nurbs::of(spline).map(|nurbs| Curve::Spline(spline, nurbs)),
+            _ => None,
+        }
+    }
+
+    fn domain(&self) -> (f64, f64) {
+        match self {
+            Curve::Line(_) => (0.0, 1.0),
+            Curve::Arc(_, sweep) => (0.0, *sweep),
+            Curve::Circle(_) => (0.0, TAU),
+            Curve::Polyline(polyline) => (0.0, segment_count(polyline) as f64),
+            Curve::Spline(_, nurbs) => nurbs.domain(),
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        match self {
+            Curve::Line(_) | Curve::Arc(..) => false,
+            Curve::Circle(_) => true,
+            Curve::Polyline(polyline) => polyline.is_closed,
+            Curve::Spline(spline, nurbs) => {
+                let (start, end) = nurbs.domain();
+                spline.is_closed && nurbs.point(start).distance(nurbs.point(end)) <= TOLERANCE
+            }
+        }
+    }
+
+    /// 对象上离 `point` 最近处的参数。
+    fn parameter(&self, point: DVec2) -> f64 {
+        match self {
+            Curve::Line(line) => {
+                let start = line.start.as_vec2();
+                let direction = line.end.as_vec2() - start;
+                let length = direction.length_squared();
+                if length <= TOLERANCE * TOLERANCE {
+                    0.0
+                } else {
+                    ((point - start).dot(direction) / length).clamp(0.0, 1.0)
+                }
+            }
+            Curve::Arc(arc, sweep) => {
+                let offset =
+                    ((point - arc.center.as_vec2()).to_angle() - arc.start_angle).rem_euclid(TAU);
+                if offset <= *sweep {
+                    offset
+                } else if offset - sweep < TAU - offset {
+                    *sweep
+                } else {
+                    0.0
+                }
+            }
+            Curve::Circle(circle) => (point - circle.center.as_vec2()).to_angle().rem_euclid(TAU),
+            Curve::Polyline(polyline) => {
+                let mut best = (f64::INFINITY, 0.0);
+                for index in 0..segment_count(polyline) {
+                    let (start, end, bulge) = segment(polyline, index);
+                    let fraction = segment_parameter(start, end, bulge, point);
+                    let distance = segment_point(start, end, bulge, fraction).distance(point);
+                    if distance < best.0 {
+                        best = (distance, index as f64 + fraction);
+                    }
+                }
+                best.1
+            }
+            Curve::Spline(_, nurbs) => nurbs.parameter(point),
+        }
+    }
+
+    /// 参数区间 `from..to` 对应的一段；闭合对象的 `to` 可以越过一个周期。
+    fn piece(&self, from: f64, to: f64) -> Entity {
+        match self {
+            Curve::Line(line) => {
+                let start = line.start.as_vec2();
+                let direction = line.end.as_vec2() - start;
+                Entity::Line(Line {
+                    start: Point2::from_vec(start + direction * from),
+                    end: Point2::from_vec(start + direction * to),
+                    layer: line.layer.clone(),
+                })
+            }
+            Curve::Arc(arc, _) => Entity::Arc(Arc {
+                start_angle: (arc.start_angle + from).rem_euclid(TAU),
+                end_angle: (arc.start_angle + to).rem_euclid(TAU),
+                ..(*arc).clone()
+            }),
+            Curve::Circle(circle) => Entity::Arc(Arc {
+                center: circle.center,
+                radius: circle.radius,
+                start_angle: from.rem_euclid(TAU),
+                end_angle: to.rem_euclid(TAU),
+                layer: circle.layer.clone(),
+            }),
+            Curve::Polyline(polyline) => Entity::Polyline(Polyline {
+                vertices: polyline_range(polyline, from, to),
+                is_closed: false,
+                layer: polyline.layer.clone(),
+            }),
+            Curve::Spline(spline, nurbs) => {
+                let (start, end) = nurbs.domain();
+                let range = if to <= end + TOLERANCE {
+                    nurbs.range(from, to.min(end))
+                } else {
+                    let tail = nurbs.range(from, end);
+                    let head = nurbs.range(start, to - (end - start));
+                    tail.joined(&head)
+                };
+                Entity::Spline(range.into_spline(spline))
+            }
+        }
+    }
+}
+
+fn segment_count(polyline: &Polyline) -> usize {
+    if polyline.is_closed {
+        polyline.vertices.len()
+    } else {
+        polyline.vertices.len() - 1
+    }
+}
+
+fn segment(polyline: &Polyline, index: usize) -> (DVec2, DVec2, f64) {
+    let count = polyline.vertices.len();
+    let vertex = &polyline.vertices[index % count];
+    (
+        vertex.position.as_vec2(),
+        polyline.vertices[(index + 1) % count].position.as_vec2(),
+        vertex.bulge,
+    )
+}
+
+fn segment_point(start: DVec2, end: DVec2, bulge: f64, fraction: f64) -> DVec2 {
+    match BulgeArc::new(start, end, bulge) {
+        None => start.lerp(end, fraction),
+        Some(arc) => arc.point(fraction),
+    }
+}
+
+/// 段上离 `point` 最近处的段内比例。
+fn segment_parameter(start: DVec2, end: DVec2, bulge: f64, point: DVec2) -> f64 {
+    match BulgeArc::new(start, end, bulge) {
+        None => {
+            let direction = end - start;
+            let length = direction.length_squared();
+            if length <= TOLERANCE * TOLERANCE {
+                0.0
+            } else {
+                ((point - start).dot(direction) / length).clamp(0.0, 1.0)
+            }
+        }
+        Some(BulgeArc {
+            center,
+            start_angle,
+            sweep,
+            ..
+        }) => {
+            let offset = ((point - center).to_angle() - start_angle) * sweep.signum();
+            let offset = offset.rem_euclid(TAU);
+            if offset <= sweep.abs() {
+                offset / sweep.abs()
+            } else if offset - sweep.abs() < TAU - offset {
+                1.0
+            } else {
+                0.0
+            }
+        }
+    }
+}
+
+/// 参数区间内的顶点，端部不完整的凸度段按比例缩小圆心角。
+fn polyline_range(polyline: &Polyline, from: f64, to: f64) -> Vec<PolylineVertex> {
+    let partial = |index: usize, start: f64, stop: f64| {
+        let (_, _, bulge) = segment(polyline, index);
+        (bulge.atan() * (stop - start)).tan()
+    };
+    let point = |parameter: f64| {
+        // 整数参数取前一段的终点，开放多段线的末端不会绕回起点
+        let index = (parameter.ceil() as usize).saturating_sub(1);
+        let (start, end, bulge) = segment(polyline, index);
+        Point2::from_vec(segment_point(start, end, bulge, parameter - index as f64))
+    };
+    let mut vertices = Vec::new();
+    let mut index = from.floor() as usize;
+    let mut cursor = from;
+    while to - cursor > TOLERANCE {
+        let stop = ((index + 1) as f64).min(to);
+        if stop - cursor > TOLERANCE {
+            let bulge = partial(index, cursor - index as f64, stop - index as f64);
+            vertices.push(PolylineVertex::with_bulge(point(cursor), bulge));
+        }
+        cursor = stop;
+        index += 1;
+    }
+    vertices.push(PolylineVertex::new(point(to)));
+    vertices
+}
+
+/// 齐次坐标表示的夹持 NURBS 曲线。
+#[derive(Debug, Clone)]
+struct Nurbs {
+    degree: usize,
+    knots: Vec<f64>,
+    /// `(x·w, y·w, w)`。
+    points: Vec<DVec3>,
+}
+
+impl Nurbs {
+    fn of(spline: &Spline) -> Option<Self> {
+        let degree = usize::try_from(spline.degree).ok().filter(|&d| d >= 1)?;
+        let controls = &spline.control_points;
+        if controls.len() <= degree {
+            return None;
+        }
+        let knots = if spline.knot_values.len() == controls.len() + degree + 1 {
+            spline.knot_values.clone()
+        } else {
+            clamped_uniform_knots(controls.len(), degree)
+        };
+        let is_clamped = knots[..=degree]
+            .iter()
+            .all(|k| (k - knots[0]).abs() <= TOLERANCE)
+            && knots[knots.len() - degree - 1..]
+                .iter()
+                .all(|k| (k - knots[knots.len() - 1]).abs() <= TOLERANCE);
+        if !is_clamped {
+            return None;
+        }
+        let points = controls
+            .iter()
+            .enumerate()
+            .map(|(index, point)| {
+                let weight = if spline.is_rational && spline.weights.len() == controls.len() {
+                    spline.weights[index]
+                } else {
+                    1.0
+                };
+                (point.as_vec2() * weight).extend(weight)
+            })
+            .collect();
+        Some(Self {
+            degree,
+            knots,
+            points,
+        })
+    }
+
+    fn domain(&self) -> (f64, f64) {
+        (self.knots[self.degree], self.knots[self.points.len()])
+    }
+
+    fn point(&self, parameter: f64) -> DVec2 {
+        let controls: Vec<Point2> = self
+            .points
+            .iter()
+            .map(|point| Point2::from_vec(point.truncate() / point.z))
+            .collect();
+        let weights: Vec<f64> = self.points.iter().map(|point| point.z).collect();
+        de_boor(&controls, &weights, &self.knots, self.degree, parameter).as_vec2()
+    }
+
+    /// 采样后在最近采样点两侧三分搜索。
+    fn parameter(&self, target: DVec2) -> f64 {
+        let (start, end) = self.domain();
+        let samples = SPLINE_SAMPLES * (self.points.len() - self.degree);
+        let at = |index: usize| start + (end - start) * index as f64 / samples as f64;
+        let distance = |parameter: f64| self.point(parameter).distance_squared(target);
+        let nearest = (0..=samples)
+            .min_by(|a, b| distance(at(*a)).total_cmp(&distance(at(*b))))
+            .unwrap_or(0);
+        let (mut low, mut high) = (
+            at(nearest.saturating_sub(1)),
+            at((nearest + 1).min(samples)),
+        );
+        for _ in 0..100 {
+            let third = (high - low) / 3.0;
+            if third <= TOLERANCE * TOLERANCE {
+                break;
+            }
+            if distance(low + third) <= distance(high - third) {
+                high -= third;
+            } else {
+                low += third;
+            }
+        }
+        (low + high) / 2.0
+    }
+
+    /// 插入节点 `u` 直至其重数等于次数，返回首个 `u` 的位置。
+    fn refine(&mut self, u: f64) -> usize {
+        let degree = self.degree;
+        let snapped = self
+            .knots
+            .iter()
+            .copied()
+            .find(|knot| (knot - u).abs() <= TOLERANCE)
+            .unwrap_or(u);
+        loop {
+            let multiplicity = self.knots.iter().filter(|&&knot| knot == snapped).count();
+            if multiplicity >= degree {
+                break;
+            }
+            let span = (degree..self.points.len())
+                .rev()
+                .find(|&index| self.knots[index] <= snapped)
+                .unwrap_or(degree);
+            let mut points = Vec::with_capacity(self.points.len() + 1);
+            points.extend_from_slice(&self.points[..=span - degree]);
+            for index in span - degree + 1..=span {
+                let alpha = (snapped - self.knots[index])
+                    / (self.knots[index + degree] - self.knots[index]);
+                points.push(self.points[index - 1] * (1.0 - alpha) + self.points[index] * alpha);
+            }
+            points.extend_from_slice(&self.points[span..]);
+            self.points = points;
+            self.knots.insert(span + 1, snapped);
+        }
+        self.knots
+            .iter()
+            .position(|&knot| knot == snapped)
+            .unwrap_or(0)
+    }
+
+    /// 在 `u` 处分成前后两段。
+    fn split(&self, u: f64) -> (Nurbs, Nurbs) {
+        let mut refined = self.clone();
+        let first = refined.refine(u);
+        let degree = self.degree;
+        let knot = refined.knots[first];
+        let left = Nurbs {
+            degree,
+            knots: refined.knots[..first]
+                .iter()
+                .copied()
+                .chain(std::iter::repeat_n(knot, degree + 1))
+                .collect(),
+            points: refined.points[..first].to_vec(),
+        };
+        let right = Nurbs {
+            degree,
+            knots: std::iter::repeat_n(knot, degree + 1)
+                .chain(refined.knots[first + degree..].iter().copied())
+                .collect(),
+            points: refined.points[first - 1..].to_vec(),
+        };
+        (left, right)
+    }
+
+    fn range(&self, from: f64, to: f64) -> Nurbs {
+        let (start, end) = self.domain();
+        let tail = if from - start > TOLERANCE {
+            self.split(from).1
+        } else {
+            self.clone()
+        };
+        if end - to > TOLERANCE {
+            tail.split(to).0
+        } else {
+            tail
+        }
+    }
+
+    /// 首尾相接地拼接两段，接点处节点重数为次数。
+    fn joined(&self, next: &Nurbs) -> Nurbs {
+        let degree = self.degree;
+        let shift = self.knots[self.knots.len() - 1] - next.knots[0];
+        let mut knots = self.knots[..self.knots.len() - 1].to_vec();
+        knots.extend(next.knots[degree + 1..].iter().map(|knot| knot + shift));
+        let mut points = self.points.clone();
+        points.extend_from_slice(&next.points[1..]);
+        Nurbs {
+            degree,
+            knots,
+            points,
+        }
+    }
+
+    fn into_spline(self, source: &Spline) -> Spline {
+        let is_rational = self
+            .points
+            .iter()
+            .any(|point| (point.z - 1.0).abs() > TOLERANCE);
+        Spline {
+            degree: source.degree,
+            is_rational,
+            is_closed: false,
+            is_periodic: false,
+            control_points: self
+                .points
+                .iter()
+                .map(|point| Point2::from_vec(point.truncate() / point.z))
+                .collect(),
+            fit_points: Vec::new(),
+            knot_values: self.knots,
+            weights: if is_rational {
+                self.points.iter().map(|point| point.z).collect()
+            } else {
+                Vec::new()
+            },
+            start_tangent: None,
+            end_tangent: None,
+            layer: source.layer.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use super::*;
+    use crate::document::{EntityProperties, ObjectColor};
+
+    fn round(value: f64) -> f64 {
+        (value * 1e9).round() / 1e9 + 0.0
+    }
+
+    fn vertices(doc: &Document, id: EntityId) -> Vec<(f64, f64, f64)> {
+        let Some(Entity::Polyline(polyline)) = doc.entity(id) else {
+            panic!("expected polyline");
+        };
+        polyline
+            .vertices
+            .iter()
+            .map(|vertex| {
+                (
+                    round(vertex.position.x()),
+                    round(vertex.position.y()),
+                    round(vertex.bulge),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn splits_lines_and_removes_gaps() {
+        let mut doc = Document::new();
+        let line = doc.add_line(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), "EDGE");
+        doc.set_entity_properties(
+            line,
+            EntityProperties {
+                color: ObjectColor::Index(3),
+                ..EntityProperties::default()
+            },
+        );
+        let ids = doc
+            .break_entity(line, Point2::new(4.0, 1.0), Some(Point2::new(6.0, -1.0)))
+            .unwrap();
+        assert_eq!(ids.len(), 2);
+        assert_eq!(ids[0], line);
+        let ends: Vec<_> = ids
+            .iter()
+            .map(|id| match doc.entity(*id) {
+                Some(Entity::Line(line)) => (line.start.x(), line.end.x()),
+                _ => panic!("expected line"),
+            })
+            .collect();
+        assert_eq!(ends, vec![(0.0, 4.0), (6.0, 10.0)]);
+        assert_eq!(
+            doc.entity_properties(ids[1]).map(|p| p.color),
+            Some(ObjectColor::Index(3))
+        );
+        assert_eq!(doc.entities_on_layer("EDGE").count(), 2);
+
+        // 端点处打断不改变对象，覆盖整段时删除对象
+        assert_eq!(
+            doc.break_entity(ids[1], Point2::new(10.0, 0.0), None),
+            Ok(vec![ids[1]])
+        );
+        assert_eq!(
+            doc.break_entity(line, Point2::new(-1.0, 0.0), Some(Point2::new(5.0, 0.0))),
+            Ok(Vec::new())
+        );
+        assert!(doc.entity(line).is_none());
+        assert_eq!(
+            doc.break_entity(line, Point2::new(0.0, 0.0), None),
+            Err(BreakError::NotFound(line))
+        );
+    }
+
+    #[test]
+    fn breaks_arcs_and_circles() {
+        let mut doc = Document::new();
+        let arc = doc.add_arc(Point2::new(0.0, 0.0), 2.0, 0.0, PI, "0");
+        let ids = doc.break_entity(arc, Point2::new(0.0, 5.0), None).unwrap();
+        let angles: Vec<_> = ids
+            .iter()
+            .map(|id| match doc.entity(*id) {
+                Some(Entity::Arc(arc)) => (round(arc.start_angle), round(arc.end_angle)),
+                _ => panic!("expected arc"),
+            })
+            .collect();
+        assert_eq!(
+            angles,
+            vec![(0.0, round(FRAC_PI_2)), (round(FRAC_PI_2), round(PI))]
+        );
+
+        let circle = doc.add_circle(Point2::new(0.0, 0.0), 1.0, "0");
+        assert_eq!(
+            doc.break_entity(circle, Point2::new(1.0, 0.0), None),
+            Err(BreakError::ClosedNeedsTwoPoints(circle))
+        );
+        // 删除从第一点逆时针到第二点的部分
+        let ids = doc
+            .break_entity(circle, Point2::new(0.0, 3.0), Some(Point2::new(-2.0, 0.0)))
+            .unwrap();
+        assert_eq!(ids, vec![circle]);
+        let Some(Entity::Arc(arc)) = doc.entity(circle) else {
+            panic!("expected arc");
+        };
+        assert_eq!(
+            (round(arc.start_angle), round(arc.end_angle)),
+            (round(PI), round(FRAC_PI_2))
+        );
+        let text = doc.add_text(Point2::new(0.0, 0.0), "A", 1.0, 0.0, "0");
+        assert_eq!(
+            doc.break_entity(text, Point2::new(0.0, 0.0), None),
+            Err(BreakError::Unsupported(text))
+        );
+    }
+
+    #[test]
+    fn breaks_polylines_through_bulges() {
+        let mut doc = Document::new();
+        // 直线段后接逆时针半圆
+        let open = doc.add_polyline_with_vertices(
+            vec![
+                PolylineVertex::new(Point2::new(0.0, 0.0)),
+                PolylineVertex::with_bulge(Point2::new(2.0, 0.0), 1.0),
+                PolylineVertex::new(Point2::new(2.0, 2.0)),
+            ],
+            false,
+            "0",
+        );
+        let ids = doc.break_entity(open, Point2::new(3.5, 1.0), None).unwrap();
+        let quarter = round((PI / 8.0).tan());
+        assert_eq!(
+            vertices(&doc, ids[0]),
+            vec![(0.0, 0.0, 0.0), (2.0, 0.0, quarter), (3.0, 1.0, 0.0)]
+        );
+        assert_eq!(
+            vertices(&doc, ids[1]),
+            vec![(3.0, 1.0, quarter), (2.0, 2.0, 0.0)]
+        );
+
+        let square = doc.add_polyline(
+            [
+                Point2::new(0.0, 0.0),
+                Point2::new(4.0, 0.0),
+                Point2::new(4.0, 4.0),
+                Point2::new(0.0, 4.0),
+            ],
+            true,
+            "0",
+        );
+        let ids = doc
+            .break_entity(square, Point2::new(2.0, 4.0), Some(Point2::new(2.0, 0.0)))
+            .unwrap();
+        assert_eq!(ids, vec![square]);
+        assert_eq!(
+            vertices(&doc, square),
+            vec![
+                (2.0, 0.0, 0.0),
+                (4.0, 0.0, 0.0),
+                (4.0, 4.0, 0.0),
+                (2.0, 4.0, 0.0)
+            ]
+        );
+        let Some(Entity::Polyline(polyline)) = doc.entity(square) else {
+            unreachable!()
+        };
+        assert!(!polyline.is_closed);
+    }
+
+    #[test]
+    fn splits_splines_by_knot_insertion() {
+        let mut doc = Document::new();
+        let controls = vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(1.0, 2.0),
+            Point2::new(3.0, 2.0),
+            Point2::new(4.0, 0.0),
+        ];
+        let spline = Spline {
+            degree: 3,
+            is_rational: false,
+            is_closed: false,
+            is_periodic: false,
+            control_points: controls,
+            fit_points: Vec::new(),
+            knot_values: vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0],
+            weights: Vec::new(),
+            start_tangent: None,
+            end_tangent: None,
+            layer: "0".to_string(),
+        };
+        let original = Nurbs::of(&spline).unwrap();
+        let id = doc.add_entity(Entity::Spline(spline));
+        let ids = doc.break_entity(id, Point2::new(2.0, 3.0), None).unwrap();
+        assert_eq!(ids.len(), 2);
+        let pieces: Vec<Nurbs> = ids
+            .iter()
+            .map(|id| match doc.entity(*id) {
+                Some(Entity::Spline(spline)) => Nurbs::of(spline).unwrap(),
+                _ => panic!("expected spline"),
+            })
+            .collect();
+        // 对称曲线在参数 0.5 处到达最高点 (2, 1.5)
+        let joint = pieces[0].point(pieces[0].domain().1);
+        assert!(joint.distance(DVec2::new(2.0, 1.5)) < 1e-6);
+        assert!(pieces[1].point(pieces[1].domain().0).distance(joint) < 1e-9);
+        for t in [0.1, 0.3, 0.45] {
+            assert!(pieces[0].point(t).distance(original.point(t)) < 1e-6);
+            assert!(pieces[1].point(1.0 - t).distance(original.point(1.0 - t)) < 1e-6);
+        }
+    }
+}
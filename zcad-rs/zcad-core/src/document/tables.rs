@@ -0,0 +1,261 @@
+//! 非实体状态的撤销记录：修改日志只记录模型空间实体的增删改，图层、块定义、各类表记录、特性覆盖、
+//! 用户数据、绘图次序与图层状态等按表记录，撤销时连同实体修改一起还原。
+//!
+//! 记录期间某张表第一次被可变访问时才保存其修改前的内容，一次编辑的代价只与它改动的表有关。
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::ops::{Deref, DerefMut};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::draw_order::DrawOrderKey;
+use super::{
+    BlockDefinition, DimStyle, Document, EntityId, EntityProperties, GeoData, ImageDefReactor,
+    ImageDictionary, Layer, LayerState, Linetype, PointAssociation, RasterImageDefinition,
+    RasterImageVariables, ShapeFile, UnderlayDefinition, Units, UserData, WipeoutVariables, XRef,
+};
+use crate::geometry::Tolerance;
+
+/// 文档中的一张表；记录期间第一次可变访问时保存修改前的内容，见 [`Document::capture_tables`]。
+#[derive(Default)]
+pub(crate) struct Tracked<T> {
+    value: T,
+    /// `None` 表示未在记录，`Some(None)` 表示正在记录但尚未修改。
+    saved: Option<Option<T>>,
+}
+
+impl<T> Tracked<T> {
+    #[inline]
+    pub(super) fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Tracked<HashMap<K, V>> {
+    /// 移除键对应的记录；键不存在时不视为修改。
+    pub(super) fn discard(&mut self, key: &K) -> Option<V> {
+        if self.value.contains_key(key) {
+            self.remove(key)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Clone> Tracked<T> {
+    /// 开始记录，返回外层记录的状态。
+    fn begin(&mut self) -> Option<Option<T>> {
+        self.saved.replace(None)
+    }
+
+    /// 结束记录并恢复外层状态，返回修改前的内容；期间未修改时返回 `None`。
+    fn finish(&mut self, outer: Option<Option<T>>) -> Option<T> {
+        let before = self.saved.take().flatten();
+        self.saved = match (outer, &before) {
+            (Some(None), Some(before)) => Some(Some(before.clone())),
+            (outer, _) => outer,
+        };
+        before
+    }
+}
+
+impl<T> Deref for Tracked<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: Clone> DerefMut for Tracked<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        if let Some(saved @ None) = &mut self.saved {
+            *saved = Some(self.value.clone());
+        }
+        &mut self.value
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Tracked<T>
+where
+    &'a T: IntoIterator,
+{
+    type Item = <&'a T as IntoIterator>::Item;
+    type IntoIter = <&'a T as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.value.into_iter()
+    }
+}
+
+impl<'a, T: Clone> IntoIterator for &'a mut Tracked<T>
+where
+    &'a mut T: IntoIterator,
+{
+    type Item = <&'a mut T as IntoIterator>::Item;
+    type IntoIter = <&'a mut T as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.deref_mut().into_iter()
+    }
+}
+
+/// 复制的文档不继承记录状态。
+impl<T: Clone> Clone for Tracked<T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            saved: None,
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Tracked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<T: Serialize> Serialize for Tracked<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Tracked<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(|value| Self { value, saved: None })
+    }
+}
+
+macro_rules! tables {
+    ($($field:ident: $ty:ty,)*) => {
+        /// 一次编辑改动过的非实体状态，未改动的表不保存，见 [`Document::capture_tables`]。
+        #[derive(Debug, Clone, Default, PartialEq)]
+        pub struct TableSnapshot {
+            $($field: Option<$ty>,)*
+        }
+
+        impl TableSnapshot {
+            /// 是否未保存任何表。
+            pub fn is_empty(&self) -> bool {
+                $(self.$field.is_none())&&*
+            }
+        }
+
+        /// 嵌套记录时外层各表的记录状态。
+        struct OuterTables {
+            $($field: Option<Option<$ty>>,)*
+        }
+
+        impl Document {
+            /// 执行 `edit`，返回其间改动过的非实体状态在修改前与修改后的内容，供撤销与事务回滚使用。
+            /// 只保存实际改变的表；可以嵌套调用。
+            pub fn capture_tables<R>(
+                &mut self,
+                edit: impl FnOnce(&mut Self) -> R,
+            ) -> (R, TableSnapshot, TableSnapshot) {
+                let outer = OuterTables {
+                    $($field: self.$field.begin(),)*
+                };
+                let result = edit(self);
+                let mut before = TableSnapshot::default();
+                let mut after = TableSnapshot::default();
+                $(
+                    if let Some(saved) = self.$field.finish(outer.$field)
+                        && saved != *self.$field
+                    {
+                        after.$field = Some((*self.$field).clone());
+                        before.$field = Some(saved);
+                    }
+                )*
+                (result, before, after)
+            }
+
+            /// 把 `snapshot` 中保存的表恢复到文档；块定义或外部参照变化时作废范围缓存。
+            pub fn restore_tables(&mut self, snapshot: &TableSnapshot) {
+                if snapshot.blocks.is_some() || snapshot.xrefs.is_some() {
+                    self.entities.invalidate_extents();
+                }
+                $(
+                    if let Some(value) = &snapshot.$field {
+                        *self.$field = value.clone();
+                    }
+                )*
+            }
+        }
+    };
+}
+
+tables! {
+    layers: HashMap<String, Layer>,
+    blocks: HashMap<String, BlockDefinition>,
+    block_handles: HashMap<String, String>,
+    image_definitions: HashMap<String, RasterImageDefinition>,
+    image_def_reactors: HashMap<String, ImageDefReactor>,
+    image_dictionary: Option<ImageDictionary>,
+    raster_image_variables: Option<RasterImageVariables>,
+    wipeout_variables: Option<WipeoutVariables>,
+    mirror_text: bool,
+    xrefs: HashMap<String, XRef>,
+    entity_properties: HashMap<EntityId, EntityProperties>,
+    shape_files: HashMap<String, ShapeFile>,
+    linetypes: HashMap<String, Linetype>,
+    underlay_definitions: HashMap<String, UnderlayDefinition>,
+    geodata: Option<GeoData>,
+    tolerance: Tolerance,
+    units: Units,
+    dim_styles: HashMap<String, DimStyle>,
+    associations: HashMap<EntityId, Vec<PointAssociation>>,
+    layer_states: HashMap<String, LayerState>,
+    draw_order: HashMap<EntityId, DrawOrderKey>,
+    user_data: HashMap<EntityId, UserData>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::ObjectColor;
+    use crate::geometry::Point2;
+
+    #[test]
+    fn restores_layers_properties_and_user_data() {
+        let mut doc = Document::new();
+        let line = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "A");
+        let ((), before, after) = doc.capture_tables(|doc| {
+            doc.rename_layer("A", "B").unwrap();
+            doc.set_layer_color("B", ObjectColor::Index(3)).unwrap();
+            doc.set_user_value(line, "tag", "x").unwrap();
+        });
+        // 只保存改动过的表
+        assert!(before.layers.is_some() && before.user_data.is_some());
+        assert!(before.blocks.is_none() && after.blocks.is_none());
+
+        doc.restore_tables(&before);
+        assert!(doc.layer("B").is_none());
+        assert!(doc.layer("A").is_some());
+        assert!(doc.user_data(line).is_none());
+        doc.restore_tables(&after);
+        assert!(doc.layer("B").is_some());
+        assert!(doc.user_value(line, "tag").is_some());
+    }
+
+    #[test]
+    fn nested_captures_keep_the_outer_state() {
+        let mut doc = Document::new();
+        let ((), before, _) = doc.capture_tables(|doc| {
+            let ((), inner, _) = doc.capture_tables(|doc| doc.ensure_layer("A"));
+            assert!(inner.layers.is_some());
+            doc.ensure_layer("B");
+        });
+        doc.restore_tables(&before);
+        assert!(doc.layer("A").is_none() && doc.layer("B").is_none());
+
+        let ((), unchanged, _) = doc.capture_tables(|doc| doc.set_mirror_text(false));
+        assert!(unchanged.is_empty());
+    }
+}
@@ -0,0 +1,1045 @@
+//! 实体的仿射变换。
+//!
+//! 相似变换（含镜像）下圆、圆弧与凸度保持原类型，镜像时圆弧起止角互换、凸度取反；非等比缩放或剪切时
+//! 圆与圆弧转为椭圆，多段线、云线与填充边界中的凸度段按圆心角采样为直线段。
+//!
+//! 文字、块参照等以插入点、旋转角与比例描述的对象，按局部 X 轴变换后的方向求新旋转角，局部 Y 轴在其
+//! 法向上的分量作为新的 Y 比例（为负表示镜像），剪切分量无法表示而被舍弃。光栅图像与擦除对象直接变换
+//! u/v 向量，裁剪边界位于像素坐标系中，保持不变。
+
+use std::collections::HashSet;
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+use glam::{DMat2, DVec2};
+
+use crate::geometry::{BulgeArc, Point2, Transform2, Vector2};
+
+use super::flatten::{ccw_sweep, edge_sweep};
+use super::{
+    Attribute, Dimension, DimensionKind, Document, Ellipse, Entity, EntityId, HatchEdge,
+    MLeaderContent, PolylineVertex, normalize_angle,
+};
+
+/// 凸度圆弧采样时每段的最大圆心角。
+const ARC_SAMPLE_STEP: f64 = PI / 32.0;
+const EPSILON: f64 = 1e-12;
+
+impl Entity {
+    /// 对实体施加仿射变换；非等比缩放下圆与圆弧变为椭圆，其余实体保持类型。
+    pub fn transform(&mut self, transform: &Transform2) {
+        if *transform == Transform2::IDENTITY {
+            return;
+        }
+        let map = Mapper::new(transform);
+        match self {
+            Entity::Line(line) => {
+                line.start = map.point(line.start);
+                line.end = map.point(line.end);
+            }
+            Entity::Circle(circle) => match map.uniform {
+                Some(scale) => {
+                    circle.center = map.point(circle.center);
+                    circle.radius *= scale;
+                }
+                None => {
+                    let radius = circle.radius;
+                    let arc = map.elliptic(
+                        circle.center,
+                        DVec2::new(radius, 0.0),
+                        DVec2::new(0.0, radius),
+                        0.0,
+                        TAU,
+                    );
+                    *self = Entity::Ellipse(arc.into_ellipse(std::mem::take(&mut circle.layer)));
+                }
+            },
+            Entity::Arc(arc) => match map.uniform {
+                Some(scale) => {
+                    arc.center = map.point(arc.center);
+                    arc.radius *= scale;
+                    let (start, end) = (map.angle(arc.start_angle), map.angle(arc.end_angle));
+                    (arc.start_angle, arc.end_angle) = if map.mirrored {
+                        (end, start)
+                    } else {
+                        (start, end)
+                    };
+                }
+                None => {
+                    let radius = arc.radius;
+                    let elliptic = map.elliptic(
+                        arc.center,
+                        DVec2::new(radius, 0.0),
+                        DVec2::new(0.0, radius),
+                        arc.start_angle,
+                        ccw_sweep(arc.start_angle, arc.end_angle),
+                    );
+                    *self = Entity::Ellipse(elliptic.into_ellipse(std::mem::take(&mut arc.layer)));
+                }
+            },
+            Entity::Ellipse(ellipse) => {
+                let major = ellipse.major_axis.as_vec2();
+                let arc = map.elliptic(
+                    ellipse.center,
+                    major,
+                    major.perp() * ellipse.ratio,
+                    ellipse.start_parameter,
+                    ccw_sweep(ellipse.start_parameter, ellipse.end_parameter),
+                );
+                *ellipse = arc.into_ellipse(std::mem::take(&mut ellipse.layer));
+            }
+            Entity::Polyline(polyline) => {
+                polyline.vertices = map.vertices(&polyline.vertices, polyline.is_closed);
+            }
+            Entity::RevisionCloud(cloud) => {
+                cloud.vertices = map.vertices(&cloud.vertices, cloud.is_closed);
+                cloud.arc_length *= map.mean_scale();
+            }
+            Entity::Spline(spline) => {
+                map.points(&mut spline.control_points);
+                map.points(&mut spline.fit_points);
+                for tangent in [&mut spline.start_tangent, &mut spline.end_tangent]
+                    .into_iter()
+                    .flatten()
+                {
+                    *tangent = map.vector(*tangent);
+                }
+            }
+            Entity::Text(text) => {
+                let frame = map.frame(text.rotation);
+                text.insert = map.point(text.insert);
+                text.alignment = text.alignment.map(|point| map.point(point));
+                text.rotation = frame.rotation;
+                text.height *= frame.y_scale.abs();
+                if frame.y_scale < 0.0 {
+                    text.is_upside_down = !text.is_upside_down;
+                }
+            }
+            Entity::MText(mtext) => {
+                let direction = mtext.direction.as_vec2();
+                let frame = map.frame(direction.y.atan2(direction.x));
+                mtext.insert = map.point(mtext.insert);
+                mtext.direction = Vector2::from(DVec2::from_angle(frame.rotation));
+                mtext.height *= frame.y_scale.abs();
+                mtext.reference_width = mtext.reference_width.map(|width| width * frame.x_scale);
+            }
+            Entity::BlockReference(reference) => {
+                let frame = map.frame(reference.rotation);
+                reference.insert = map.point(reference.insert);
+                reference.rotation = frame.rotation;
+                reference.scale = Vector2::new(
+                    reference.scale.x() * frame.x_scale,
+                    reference.scale.y() * frame.y_scale,
+                );
+                for attribute in &mut reference.attributes {
+                    map.attribute(attribute);
+                }
+            }
+            Entity::Hatch(hatch) => {
+                for hatch_loop in &mut hatch.loops {
+                    hatch_loop.edges = std::mem::take(&mut hatch_loop.edges)
+                        .into_iter()
+                        .flat_map(|edge| map.hatch_edge(edge))
+                        .collect();
+                }
+                for line in &mut hatch.pattern_lines {
+                    let stretch = map.frame(line.angle).x_scale;
+                    line.angle = map.angle(line.angle);
+                    line.base_point = map.point(line.base_point);
+                    line.offset = map.vector(line.offset);
+                    for dash in &mut line.dash_lengths {
+                        *dash *= stretch;
+                    }
+                }
+                map.points(&mut hatch.seed_points);
+                if let Some(gradient) = &mut hatch.gradient {
+                    gradient.angle = map.angle(gradient.angle);
+                }
+            }
+            Entity::Dimension(dimension) => map.dimension(dimension),
+            Entity::Leader(leader) => map.points(&mut leader.vertices),
+            Entity::MLeader(mleader) => {
+                for line in &mut mleader.leader_lines {
+                    map.points(&mut line.vertices);
+                }
+                match &mut mleader.content {
+                    MLeaderContent::MText { location, .. } => *location = map.point(*location),
+                    MLeaderContent::Block { block } => {
+                        let frame = map.frame(block.rotation);
+                        block.location = map.point(block.location);
+                        block.rotation = frame.rotation;
+                        block.scale = Vector2::new(
+                            block.scale.x() * frame.x_scale,
+                            block.scale.y() * frame.y_scale,
+                        );
+                    }
+                    MLeaderContent::None => {}
+                }
+                let scale = map.mean_scale();
+                for length in [
+                    &mut mleader.text_height,
+                    &mut mleader.dogleg_length,
+                    &mut mleader.landing_gap,
+                ]
+                .into_iter()
+                .flatten()
+                {
+                    *length *= scale;
+                }
+            }
+            Entity::RasterImage(image) => {
+                image.insert = map.point(image.insert);
+                image.u_vector = map.vector(image.u_vector);
+                image.v_vector = map.vector(image.v_vector);
+            }
+            Entity::Wipeout(wipeout) => {
+                wipeout.insert = map.point(wipeout.insert);
+                wipeout.u_vector = map.vector(wipeout.u_vector);
+                wipeout.v_vector = map.vector(wipeout.v_vector);
+            }
+            Entity::Face3D(face) => {
+                // 只变换 XY，高程保持不变
+                for vertex in &mut face.vertices {
+                    let planar = map.point(Point2::new(vertex.x(), vertex.y()));
+                    vertex.0.x = planar.x();
+                    vertex.0.y = planar.y();
+                }
+            }
+            Entity::Shape(shape) => {
+                let frame = map.frame(shape.rotation);
+                shape.insert = map.point(shape.insert);
+                shape.rotation = frame.rotation;
+                shape.size *= frame.y_scale.abs();
+                shape.width_factor *= frame.aspect();
+            }
+            Entity::Underlay(underlay) => {
+                let frame = map.frame(underlay.rotation);
+                underlay.insert = map.point(underlay.insert);
+                underlay.rotation = frame.rotation;
+                underlay.scale = Vector2::new(
+                    underlay.scale.x() * frame.x_scale,
+                    underlay.scale.y() * frame.y_scale,
+                );
+            }
+        }
+    }
+}
+
+impl Document {
+    /// 对一组模型空间实体施加变换，返回实际变换的实体数；不存在或重复的编号被忽略。
+    ///
+    /// 实体保留编号、图层与特性覆盖，范围缓存与空间索引随之更新。
+    pub fn transform_entities(
+        &mut self,
+        ids: impl IntoIterator<Item = EntityId>,
+        transform: &Transform2,
+    ) -> usize {
+        self.edit_entities(ids, |entity| entity.transform(transform))
+    }
+
+    /// 平移（MOVE）。
+    pub fn move_entities(
+        &mut self,
+        ids: impl IntoIterator<Item = EntityId>,
+        offset: Vector2,
+    ) -> usize {
+        self.transform_entities(ids, &Transform2::translation(offset))
+    }
+
+    /// 绕基点逆时针旋转（ROTATE），角度为弧度。
+    pub fn rotate_entities(
+        &mut self,
+        ids: impl IntoIterator<Item = EntityId>,
+        base: Point2,
+        angle: f64,
+    ) -> usize {
+        self.transform_entities(ids, &Transform2::rotation_about(base, angle))
+    }
+
+    /// 以基点等比缩放（SCALE）。
+    pub fn scale_entities(
+        &mut self,
+        ids: impl IntoIterator<Item = EntityId>,
+        base: Point2,
+        factor: f64,
+    ) -> usize {
+        self.transform_entities(ids, &Transform2::scaling_about(base, factor, factor))
+    }
+
+    /// 关于过 `start`、`end` 的直线镜像（MIRROR）。`$MIRRTEXT` 为 0 时单行与多行文字只镜像位置，
+    /// 字形保持可读。
+    pub fn mirror_entities(
+        &mut self,
+        ids: impl IntoIterator<Item = EntityId>,
+        start: Point2,
+        end: Point2,
+    ) -> usize {
+        let mirror = Transform2::mirror(start, end);
+        let readable = !self.mirror_text();
+        self.edit_entities(ids, |entity| {
+            entity.transform(&mirror);
+            if readable {
+                keep_readable(entity, mirror.linear());
+            }
+        })
+    }
+
+    fn edit_entities(
+        &mut self,
+        ids: impl IntoIterator<Item = EntityId>,
+        mut edit: impl FnMut(&mut Entity),
+    ) -> usize {
+        let mut seen = HashSet::new();
+        let mut count = 0;
+        for id in ids {
+            if !seen.insert(id) {
+                continue;
+            }
+            if self.entities.get(id).is_none() {
+                continue;
+            }
+            self.record_modified(id);
+            // 变换保持图层不变，图层索引无需重建
+            if let Some(entity) = self.entities.get_geometry_mut(id) {
+                edit(entity);
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+/// 把镜像后的文字恢复为可读：在沿基线与垂直基线两种翻转中，选择字头方向与镜像前更接近的一种。
+/// `mirror` 为反射的线性部分。
+fn keep_readable(entity: &mut Entity, mirror: DMat2) {
+    // 绕字高中线翻转后字头恰为原字头的镜像，与原字头同侧时取该方案，否则绕基线翻转
+    let flips_baseline = |direction: DVec2| {
+        let up = direction.perp();
+        up.dot(mirror * up) > 0.0
+    };
+    match entity {
+        Entity::Text(text) => {
+            text.is_upside_down = !text.is_upside_down;
+            if flips_baseline(DVec2::from_angle(text.rotation)) {
+                text.rotation = normalize_angle(text.rotation + PI);
+                if text.is_fitted()
+                    && let Some(alignment) = text.alignment.as_mut()
+                {
+                    std::mem::swap(&mut text.insert, alignment);
+                }
+            }
+        }
+        Entity::MText(mtext) if flips_baseline(mtext.direction.as_vec2()) => {
+            mtext.direction = Vector2::from(-mtext.direction.as_vec2());
+        }
+        _ => {}
+    }
+}
+
+/// 局部坐标系变换后的旋转角与两轴比例。
+struct Frame {
+    rotation: f64,
+    x_scale: f64,
+    /// 局部 Y 轴在新 X 轴法向上的分量，为负表示镜像。
+    y_scale: f64,
+}
+
+impl Frame {
+    /// 宽度因子的变化倍数。
+    fn aspect(&self) -> f64 {
+        if self.y_scale.abs() > EPSILON {
+            self.x_scale / self.y_scale.abs()
+        } else {
+            1.0
+        }
+    }
+}
+
+/// 以主轴表示的椭圆弧；`sweep` 带符号，为负表示按参数递减方向。
+struct EllipticArc {
+    center: Point2,
+    major: DVec2,
+    ratio: f64,
+    start: f64,
+    sweep: f64,
+}
+
+impl EllipticArc {
+    fn into_ellipse(self, layer: String) -> Ellipse {
+        let (start_parameter, end_parameter) = if self.sweep.abs() >= TAU - 1e-9 {
+            (0.0, TAU)
+        } else if self.sweep >= 0.0 {
+            (
+                normalize_angle(self.start),
+                normalize_angle(self.start + self.sweep),
+            )
+        } else {
+            (
+                normalize_angle(self.start + self.sweep),
+                normalize_angle(self.start),
+            )
+        };
+        Ellipse {
+            center: self.center,
+            major_axis: Vector2::from(self.major),
+            ratio: self.ratio,
+            start_parameter,
+            end_parameter,
+            layer,
+        }
+    }
+}
+
+/// 对单个属性施加仿射变换，与块参照整体变换时属性的处理一致。
+pub(super) fn transform_attribute(attribute: &mut Attribute, transform: &Transform2) {
+    Mapper::new(transform).attribute(attribute);
+}
+
+struct Mapper<'a> {
+    transform: &'a Transform2,
+    linear: DMat2,
+    uniform: Option<f64>,
+    mirrored: bool,
+}
+
+impl<'a> Mapper<'a> {
+    fn new(transform: &'a Transform2) -> Self {
+        Self {
+            transform,
+            linear: transform.linear(),
+            uniform: transform.uniform_scale(),
+            mirrored: transform.is_mirroring(),
+        }
+    }
+
+    fn point(&self, point: Point2) -> Point2 {
+        self.transform.apply_point(point)
+    }
+
+    fn vector(&self, vector: Vector2) -> Vector2 {
+        self.transform.apply_vector(vector)
+    }
+
+    fn points(&self, points: &mut [Point2]) {
+        for point in points {
+            *point = self.point(*point);
+        }
+    }
+
+    /// 面积缩放的平方根，用于无方向的长度（如箭头尺寸）。
+    fn mean_scale(&self) -> f64 {
+        self.uniform
+            .unwrap_or_else(|| self.transform.determinant().abs().sqrt())
+    }
+
+    /// 方向角 `angle` 变换后的方向角，与原角度相差不超过半圈，保持原有的取值范围。
+    fn angle(&self, angle: f64) -> f64 {
+        let mapped = (self.linear * DVec2::from_angle(angle)).to_angle();
+        angle + (mapped - angle + PI).rem_euclid(TAU) - PI
+    }
+
+    fn frame(&self, rotation: f64) -> Frame {
+        let x_axis = self.linear * DVec2::from_angle(rotation);
+        let y_axis = self.linear * DVec2::from_angle(rotation).perp();
+        let x_scale = x_axis.length();
+        let y_scale = if x_scale > EPSILON {
+            x_axis.perp_dot(y_axis) / x_scale
+        } else {
+            y_axis.length()
+        };
+        Frame {
+            rotation: self.angle(rotation),
+            x_scale,
+            y_scale,
+        }
+    }
+
+    /// 变换以共轭半径 `u`、`v` 描述的椭圆弧 `center + u·cos t + v·sin t`，求新椭圆的主轴与参数范围。
+    fn elliptic(&self, center: Point2, u: DVec2, v: DVec2, start: f64, sweep: f64) -> EllipticArc {
+        let (u, v) = (self.linear * u, self.linear * v);
+        // 参数平移 offset 后两共轭半径正交，即为主轴与次轴
+        let mut offset = 0.5 * (2.0 * u.dot(v)).atan2(u.length_squared() - v.length_squared());
+        let (sin, cos) = offset.sin_cos();
+        let mut major = u * cos + v * sin;
+        let mut minor = v * cos - u * sin;
+        if minor.length_squared() > major.length_squared() {
+            (major, minor) = (minor, -major);
+            offset += FRAC_PI_2;
+        }
+        let length = major.length();
+        let ratio = if length > EPSILON {
+            minor.length() / length
+        } else {
+            1.0
+        };
+        // 次轴位于主轴顺时针一侧时参数方向反转
+        let (start, sweep) = if major.perp_dot(minor) >= 0.0 {
+            (start - offset, sweep)
+        } else {
+            (offset - start, -sweep)
+        };
+        EllipticArc {
+            center: self.point(center),
+            major,
+            ratio,
+            start,
+            sweep,
+        }
+    }
+
+    fn vertices(&self, vertices: &[PolylineVertex], is_closed: bool) -> Vec<PolylineVertex> {
+        if self.uniform.is_some() {
+            let sign = if self.mirrored { -1.0 } else { 1.0 };
+            return vertices
+                .iter()
+                .map(|vertex| {
+                    PolylineVertex::with_bulge(self.point(vertex.position), vertex.bulge * sign)
+                })
+                .collect();
+        }
+        let mut result = Vec::with_capacity(vertices.len());
+        for (index, vertex) in vertices.iter().enumerate() {
+            result.push(PolylineVertex::new(self.point(vertex.position)));
+            let next = match vertices.get(index + 1) {
+                Some(next) => Some(next),
+                None if is_closed => vertices.first(),
+                None => None,
+            };
+            if let Some(next) = next {
+                result.extend(
+                    bulge_interior(vertex.position, next.position, vertex.bulge)
+                        .into_iter()
+                        .map(|point| PolylineVertex::new(self.point(point))),
+                );
+            }
+        }
+        result
+    }
+
+    fn attribute(&self, attribute: &mut Attribute) {
+        let frame = self.frame(attribute.rotation);
+        attribute.insert = self.point(attribute.insert);
+        attribute.alignment = attribute.alignment.map(|point| self.point(point));
+        attribute.rotation = frame.rotation;
+        attribute.height *= frame.y_scale.abs();
+        attribute.width_factor *= frame.aspect();
+    }
+
+    fn hatch_edge(&self, edge: HatchEdge) -> Vec<HatchEdge> {
+        match edge {
+            HatchEdge::Line { start, end } => vec![HatchEdge::Line {
+                start: self.point(start),
+                end: self.point(end),
+            }],
+            HatchEdge::PolylineSegment { start, end, bulge } => {
+                if self.uniform.is_some() {
+                    let bulge = if self.mirrored { -bulge } else { bulge };
+                    return vec![HatchEdge::PolylineSegment {
+                        start: self.point(start),
+                        end: self.point(end),
+                        bulge,
+                    }];
+                }
+                let mut points = vec![start];
+                points.extend(bulge_interior(start, end, bulge));
+                points.push(end);
+                points
+                    .windows(2)
+                    .map(|pair| HatchEdge::PolylineSegment {
+                        start: self.point(pair[0]),
+                        end: self.point(pair[1]),
+                        bulge: 0.0,
+                    })
+                    .collect()
+            }
+            HatchEdge::Arc {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+                is_counter_clockwise,
+            } => {
+                let (start, sweep) = edge_sweep(start_angle, end_angle, is_counter_clockwise);
+                if let Some(scale) = self.uniform {
+                    let sweep = if self.mirrored { -sweep } else { sweep };
+                    let (start_angle, end_angle, is_counter_clockwise) =
+                        encode_sweep(self.angle(start), sweep);
+                    return vec![HatchEdge::Arc {
+                        center: self.point(center),
+                        radius: radius * scale,
+                        start_angle,
+                        end_angle,
+                        is_counter_clockwise,
+                    }];
+                }
+                let arc = self.elliptic(
+                    center,
+                    DVec2::new(radius, 0.0),
+                    DVec2::new(0.0, radius),
+                    start,
+                    sweep,
+                );
+                vec![elliptic_edge(arc)]
+            }
+            HatchEdge::Ellipse {
+                center,
+                major_axis,
+                minor_ratio,
+                start_angle,
+                end_angle,
+                is_counter_clockwise,
+            } => {
+                let (start, sweep) = edge_sweep(start_angle, end_angle, is_counter_clockwise);
+                let major = major_axis.as_vec2();
+                let arc = self.elliptic(center, major, major.perp() * minor_ratio, start, sweep);
+                vec![elliptic_edge(arc)]
+            }
+            HatchEdge::BoundaryReference { handle } => {
+                vec![HatchEdge::BoundaryReference { handle }]
+            }
+            HatchEdge::Spline {
+                mut control_points,
+                mut fit_points,
+                knot_values,
+                degree,
+                is_rational,
+                is_periodic,
+            } => {
+                self.points(&mut control_points);
+                self.points(&mut fit_points);
+                vec![HatchEdge::Spline {
+                    control_points,
+                    fit_points,
+                    knot_values,
+                    degree,
+                    is_rational,
+                    is_periodic,
+                }]
+            }
+        }
+    }
+
+    fn dimension(&self, dimension: &mut Dimension) {
+        dimension.definition_point = self.point(dimension.definition_point);
+        dimension.text_midpoint = self.point(dimension.text_midpoint);
+        for point in [
+            &mut dimension.dimension_line_point,
+            &mut dimension.extension_line_origin,
+            &mut dimension.extension_line_end,
+            &mut dimension.secondary_point,
+            &mut dimension.arc_definition_point,
+            &mut dimension.center_point,
+            &mut dimension.jog_point,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *point = self.point(*point);
+        }
+        dimension.rotation = self.angle(dimension.rotation);
+        dimension.text_rotation = dimension.text_rotation.map(|angle| self.angle(angle));
+        // 角度在相似变换下不变；坐标标注的测量值取决于原点，无法换算
+        dimension.measurement = match dimension.kind {
+            DimensionKind::Angular | DimensionKind::Angular3Point if self.uniform.is_some() => {
+                dimension.measurement
+            }
+            DimensionKind::Angular | DimensionKind::Angular3Point | DimensionKind::Ordinate => None,
+            _ => self
+                .uniform
+                .and_then(|scale| dimension.measurement.map(|value| value * scale)),
+        };
+        if let Some(arc) = &mut dimension.arc {
+            let (start, end) = (self.angle(arc.start_angle), self.angle(arc.end_angle));
+            (arc.start_angle, arc.end_angle) = if self.mirrored {
+                (end, start)
+            } else {
+                (start, end)
+            };
+            for point in [&mut arc.leader_start, &mut arc.leader_end]
+                .into_iter()
+                .flatten()
+            {
+                *point = self.point(*point);
+            }
+        }
+        // 匿名块中的标注图形已过时，清除后由定义点重新生成
+        dimension.block_name = None;
+    }
+}
+
+/// 带符号扫掠转为填充边界边的起止角与方向，顺时针边按 DXF 约定存储取反后的角度。
+pub(super) fn encode_sweep(start: f64, sweep: f64) -> (f64, f64, bool) {
+    if sweep >= 0.0 {
+        (start, start + sweep, true)
+    } else {
+        (-start, -start - sweep, false)
+    }
+}
+
+fn elliptic_edge(arc: EllipticArc) -> HatchEdge {
+    let (start_angle, end_angle, is_counter_clockwise) = encode_sweep(arc.start, arc.sweep);
+    HatchEdge::Ellipse {
+        center: arc.center,
+        major_axis: Vector2::from(arc.major),
+        minor_ratio: arc.ratio,
+        start_angle,
+        end_angle,
+        is_counter_clockwise,
+    }
+}
+
+/// 凸度圆弧的内部采样点（不含端点），相邻点的圆心角不超过 [`ARC_SAMPLE_STEP`]。
+fn bulge_interior(start: Point2, end: Point2, bulge: f64) -> Vec<Point2> {
+    let Some(arc) = BulgeArc::new(start.as_vec2(), end.as_vec2(), bulge) else {
+        return Vec::new();
+    };
+    let steps = (arc.sweep.abs() / ARC_SAMPLE_STEP).ceil().max(1.0) as usize;
+    (1..steps)
+        .map(|step| Point2::from_vec(arc.point(step as f64 / steps as f64)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_4;
+
+    use super::super::{Arc, Circle, Hatch, HatchLoop, Polyline, RasterImage, Text};
+    use super::*;
+
+    fn assert_close(actual: Point2, expected: Point2) {
+        assert!(
+            actual.as_vec2().distance(expected.as_vec2()) < 1e-9,
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    fn ellipse_point(ellipse: &Ellipse, parameter: f64) -> Point2 {
+        let major = ellipse.major_axis.as_vec2();
+        let minor = major.perp() * ellipse.ratio;
+        Point2::from_vec(
+            ellipse.center.as_vec2() + major * parameter.cos() + minor * parameter.sin(),
+        )
+    }
+
+    fn arc_point(center: Point2, radius: f64, angle: f64) -> Point2 {
+        Point2::from_vec(center.as_vec2() + DVec2::from_angle(angle) * radius)
+    }
+
+    fn arc(start_angle: f64, end_angle: f64) -> Entity {
+        Entity::Arc(Arc {
+            center: Point2::new(1.0, 1.0),
+            radius: 2.0,
+            start_angle,
+            end_angle,
+            layer: "0".to_string(),
+        })
+    }
+
+    #[test]
+    fn arcs_keep_type_under_similarity_and_become_ellipses_otherwise() {
+        let center = Point2::new(1.0, 1.0);
+        let start = arc_point(center, 2.0, 0.0);
+        let end = arc_point(center, 2.0, FRAC_PI_2);
+        let mirror = Transform2::mirror(Point2::new(0.0, 0.0), Point2::new(0.0, 1.0))
+            .then(Transform2::scaling(3.0, 3.0));
+        let mut mirrored = arc(0.0, FRAC_PI_2);
+        mirrored.transform(&mirror);
+        let Entity::Arc(result) = &mirrored else {
+            panic!("expected arc, got {mirrored:?}");
+        };
+        assert!((result.radius - 6.0).abs() < 1e-9);
+        // 镜像后绕向反转，原终点成为逆时针起点
+        assert_close(
+            arc_point(result.center, result.radius, result.start_angle),
+            mirror.apply_point(end),
+        );
+        assert_close(
+            arc_point(result.center, result.radius, result.end_angle),
+            mirror.apply_point(start),
+        );
+
+        let skew = Transform2::from_affine(glam::DAffine2::from_cols_array(&[
+            2.0, 0.0, 0.5, 1.0, 4.0, -1.0,
+        ]));
+        let mut skewed = arc(0.0, FRAC_PI_2);
+        skewed.transform(&skew);
+        let Entity::Ellipse(result) = &skewed else {
+            panic!("expected ellipse, got {skewed:?}");
+        };
+        assert!(result.ratio > 0.0 && result.ratio <= 1.0);
+        assert_close(
+            ellipse_point(result, result.start_parameter),
+            skew.apply_point(start),
+        );
+        assert_close(
+            ellipse_point(result, result.end_parameter),
+            skew.apply_point(end),
+        );
+        let midpoint = skew.apply_point(arc_point(center, 2.0, FRAC_PI_4));
+        let middle =
+            result.start_parameter + ccw_sweep(result.start_parameter, result.end_parameter) / 2.0;
+        assert_close(ellipse_point(result, middle), midpoint);
+
+        // 非等比缩放的整圆变为长轴沿 Y 的整椭圆
+        let mut circle = Entity::Circle(Circle {
+            center,
+            radius: 1.0,
+            layer: "A".to_string(),
+        });
+        circle.transform(&Transform2::scaling(1.0, 2.0));
+        let Entity::Ellipse(result) = &circle else {
+            panic!("expected ellipse, got {circle:?}");
+        };
+        assert_eq!(result.layer, "A");
+        assert!((result.ratio - 0.5).abs() < 1e-9);
+        assert!((result.major_axis.as_vec2().length() - 2.0).abs() < 1e-9);
+        assert!(result.major_axis.x().abs() < 1e-9);
+        assert_eq!((result.start_parameter, result.end_parameter), (0.0, TAU));
+
+        // 镜像后的椭圆弧端点仍落在原弧的像上
+        let mut ellipse = Entity::Ellipse(result.clone());
+        if let Entity::Ellipse(ellipse) = &mut ellipse {
+            ellipse.start_parameter = 0.3;
+            ellipse.end_parameter = 2.0;
+        }
+        let Entity::Ellipse(before) = ellipse.clone() else {
+            unreachable!()
+        };
+        let flip = Transform2::mirror(Point2::new(0.0, 0.0), Point2::new(1.0, 1.0));
+        ellipse.transform(&flip);
+        let Entity::Ellipse(after) = &ellipse else {
+            unreachable!()
+        };
+        assert_close(
+            ellipse_point(after, after.start_parameter),
+            flip.apply_point(ellipse_point(&before, 2.0)),
+        );
+        assert_close(
+            ellipse_point(after, after.end_parameter),
+            flip.apply_point(ellipse_point(&before, 0.3)),
+        );
+    }
+
+    #[test]
+    fn bulges_flip_under_mirror_and_are_sampled_under_stretch() {
+        let polyline = Entity::Polyline(Polyline {
+            vertices: vec![
+                PolylineVertex::with_bulge(Point2::new(0.0, 0.0), 1.0),
+                PolylineVertex::new(Point2::new(2.0, 0.0)),
+            ],
+            is_closed: false,
+            layer: "0".to_string(),
+        });
+        let mut mirrored = polyline.clone();
+        mirrored.transform(&Transform2::scaling(-1.0, 1.0));
+        let Entity::Polyline(result) = &mirrored else {
+            unreachable!()
+        };
+        assert_eq!(result.vertices[0].bulge, -1.0);
+        assert_close(result.vertices[1].position, Point2::new(-2.0, 0.0));
+
+        let mut stretched = polyline;
+        stretched.transform(&Transform2::scaling(1.0, 3.0));
+        let Entity::Polyline(result) = &stretched else {
+            unreachable!()
+        };
+        // 半圆按 π/32 采样为 32 段
+        assert_eq!(result.vertices.len(), 33);
+        assert!(result.vertices.iter().all(|vertex| vertex.bulge == 0.0));
+        assert_close(result.vertices[16].position, Point2::new(1.0, -3.0));
+        assert_close(result.vertices[32].position, Point2::new(2.0, 0.0));
+    }
+
+    #[test]
+    fn oriented_entities_follow_transformed_axes() {
+        let mut text = Entity::Text(Text {
+            insert: Point2::new(1.0, 0.0),
+            content: "A".to_string(),
+            height: 2.0,
+            rotation: 0.0,
+            layer: "0".to_string(),
+            horizontal_align: 0,
+            vertical_align: 0,
+            alignment: None,
+            is_backward: false,
+            is_upside_down: false,
+        });
+        let transform = Transform2::scaling(2.0, -2.0).then(Transform2::rotation(FRAC_PI_2));
+        text.transform(&transform);
+        let Entity::Text(result) = &text else {
+            unreachable!()
+        };
+        assert_close(result.insert, Point2::new(0.0, 2.0));
+        assert!((result.rotation - FRAC_PI_2).abs() < 1e-9);
+        assert!((result.height - 4.0).abs() < 1e-9);
+        assert!(result.is_upside_down);
+
+        let mut image = Entity::RasterImage(RasterImage {
+            layer: "0".to_string(),
+            image_def_handle: "1F".to_string(),
+            insert: Point2::new(0.0, 0.0),
+            u_vector: Vector2::new(0.5, 0.0),
+            v_vector: Vector2::new(0.0, 0.5),
+            image_size: Vector2::new(10.0, 10.0),
+            display_options: Default::default(),
+            image_def_reactor_handle: None,
+            clip: None,
+        });
+        let before = image.bounds().unwrap();
+        image.transform(&Transform2::rotation(FRAC_PI_2).then(Transform2::scaling(1.0, 2.0)));
+        let Entity::RasterImage(result) = &image else {
+            unreachable!()
+        };
+        assert_close(
+            Point2::from_vec(result.u_vector.as_vec2()),
+            Point2::new(0.0, 1.0),
+        );
+        assert_close(
+            Point2::from_vec(result.v_vector.as_vec2()),
+            Point2::new(-0.5, 0.0),
+        );
+        let after = image.bounds().unwrap();
+        assert_close(after.min(), Point2::new(-5.0, 0.0));
+        assert_close(after.max(), Point2::new(0.0, 10.0));
+        assert_close(before.max(), Point2::new(5.0, 5.0));
+    }
+
+    #[test]
+    fn document_edits_preserve_layers_and_refresh_bounds() {
+        let mut doc = Document::new();
+        let line = doc.add_line(Point2::new(0.0, 0.0), Point2::new(2.0, 0.0), "WALL");
+        let circle = doc.add_circle(Point2::new(5.0, 5.0), 1.0, "HOLE");
+        let label = doc.add_text(Point2::new(0.0, 2.0), "AB", 1.0, 0.0, "NOTE");
+        assert_eq!(doc.entities_on_layer("HOLE").count(), 1);
+        assert_eq!(doc.query_point(Point2::new(5.0, 5.0), 0.1), vec![circle]);
+
+        let moved = doc.move_entities(
+            [circle, circle, EntityId::new(999)],
+            Vector2::new(10.0, 0.0),
+        );
+        assert_eq!(moved, 1);
+        assert!(doc.query_point(Point2::new(5.0, 5.0), 0.1).is_empty());
+        assert_eq!(doc.query_point(Point2::new(15.0, 5.0), 0.1), vec![circle]);
+        assert_close(doc.bounds().unwrap().max(), Point2::new(16.0, 6.0));
+
+        doc.rotate_entities([line], Point2::new(0.0, 0.0), FRAC_PI_2);
+        doc.scale_entities([line], Point2::new(0.0, 0.0), 2.0);
+        let Some(Entity::Line(rotated)) = doc.entity(line) else {
+            unreachable!()
+        };
+        assert_close(rotated.end, Point2::new(0.0, 4.0));
+
+        // 非等比缩放把圆变为椭圆，图层与特性覆盖随编号保留
+        doc.transform_entities([circle], &Transform2::scaling(1.0, 2.0));
+        assert!(matches!(doc.entity(circle), Some(Entity::Ellipse(e)) if e.layer == "HOLE"));
+        assert_eq!(doc.entities_on_layer("HOLE").count(), 1);
+
+        // $MIRRTEXT 为 0：文字位置随镜像，字形保持可读
+        doc.set_mirror_text(false);
+        doc.mirror_entities([label], Point2::new(-1.0, 0.0), Point2::new(-1.0, 1.0));
+        let Some(Entity::Text(text)) = doc.entity(label) else {
+            unreachable!()
+        };
+        assert_close(text.insert, Point2::new(-2.0, 2.0));
+        assert!(!text.is_upside_down && !text.is_backward);
+        assert!(text.rotation.abs() < 1e-9 || (text.rotation - TAU).abs() < 1e-9);
+        doc.mirror_entities([label], Point2::new(0.0, 0.0), Point2::new(1.0, 0.0));
+        let Some(Entity::Text(text)) = doc.entity(label) else {
+            unreachable!()
+        };
+        assert_close(text.insert, Point2::new(-2.0, -2.0));
+        assert!(!text.is_upside_down && text.rotation.abs() < 1e-9);
+
+        doc.set_mirror_text(true);
+        doc.mirror_entities([label], Point2::new(0.0, 0.0), Point2::new(1.0, 0.0));
+        let Some(Entity::Text(text)) = doc.entity(label) else {
+            unreachable!()
+        };
+        assert!(text.is_upside_down);
+    }
+
+    #[test]
+    fn hatch_arc_edges_keep_their_endpoints() {
+        let edge = HatchEdge::Arc {
+            center: Point2::new(0.0, 0.0),
+            radius: 1.0,
+            start_angle: 0.0,
+            end_angle: FRAC_PI_2,
+            is_counter_clockwise: false,
+        };
+        // 顺时针边实际从 0 走到 -π/2
+        let (start, end) = (Point2::new(1.0, 0.0), Point2::new(0.0, -1.0));
+        for transform in [
+            Transform2::mirror(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0)),
+            Transform2::scaling(2.0, 1.0),
+        ] {
+            let mut hatch = Entity::Hatch(Hatch {
+                pattern_name: "SOLID".to_string(),
+                is_solid: true,
+                loops: vec![HatchLoop {
+                    is_polyline: false,
+                    is_closed: true,
+                    edges: vec![edge.clone()],
+                    boundary_handles: Vec::new(),
+                    boundary_entities: Vec::new(),
+                }],
+                gradient: None,
+                pattern_lines: Vec::new(),
+                style: Default::default(),
+                is_associative: false,
+                seed_points: vec![Point2::new(0.5, -0.5)],
+                layer: "0".to_string(),
+            });
+            hatch.transform(&transform);
+            let Entity::Hatch(result) = &hatch else {
+                unreachable!()
+            };
+            let (center, major, ratio, start_angle, end_angle, ccw) =
+                match &result.loops[0].edges[0] {
+                    HatchEdge::Arc {
+                        center,
+                        radius,
+                        start_angle,
+                        end_angle,
+                        is_counter_clockwise,
+                    } => (
+                        *center,
+                        DVec2::new(*radius, 0.0),
+                        1.0,
+                        *start_angle,
+                        *end_angle,
+                        *is_counter_clockwise,
+                    ),
+                    HatchEdge::Ellipse {
+                        center,
+                        major_axis,
+                        minor_ratio,
+                        start_angle,
+                        end_angle,
+                        is_counter_clockwise,
+                    } => (
+                        *center,
+                        major_axis.as_vec2(),
+                        *minor_ratio,
+                        *start_angle,
+                        *end_angle,
+                        *is_counter_clockwise,
+                    ),
+                    other => panic!("unexpected edge {other:?}"),
+                };
+            let (from, sweep) = edge_sweep(start_angle, end_angle, ccw);
+            let point = |parameter: f64| {
+                Point2::from_vec(
+                    center.as_vec2()
+                        + major * parameter.cos()
+                        + major.perp() * ratio * parameter.sin(),
+                )
+            };
+            assert_close(point(from), transform.apply_point(start));
+            assert_close(point(from + sweep), transform.apply_point(end));
+            assert_close(
+                result.seed_points[0],
+                transform.apply_point(Point2::new(0.5, -0.5)),
+            );
+        }
+    }
+}
@@ -0,0 +1,331 @@
+//! 参考底图（PDFUNDERLAY/DGNUNDERLAY/DWFUNDERLAY）及其定义对象。
+//!
+//! 底图内容本身不做栅格化，只记录文件依赖、放置方式与裁剪边界。
+
+use serde::{Deserialize, Serialize};
+
+use super::{Document, Entity, EntityId};
+use crate::geometry::{Point2, Vector2};
+
+/// 参考底图格式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnderlayKind {
+    Pdf,
+    Dgn,
+    Dwf,
+}
+
+impl UnderlayKind {
+    pub const ALL: [UnderlayKind; 3] = [UnderlayKind::Pdf, UnderlayKind::Dgn, UnderlayKind::Dwf];
+
+    /// DXF 实体名，例如 `PDFUNDERLAY`。
+    pub fn entity_name(self) -> &'static str {
+        match self {
+            UnderlayKind::Pdf => "PDFUNDERLAY",
+            UnderlayKind::Dgn => "DGNUNDERLAY",
+            UnderlayKind::Dwf => "DWFUNDERLAY",
+        }
+    }
+
+    /// OBJECTS 段中的定义对象名，例如 `PDFDEFINITION`。
+    pub fn definition_name(self) -> &'static str {
+        match self {
+            UnderlayKind::Pdf => "PDFDEFINITION",
+            UnderlayKind::Dgn => "DGNDEFINITION",
+            UnderlayKind::Dwf => "DWFDEFINITION",
+        }
+    }
+
+    /// 根字典中登记定义对象的条目名。
+    pub fn dictionary_key(self) -> &'static str {
+        match self {
+            UnderlayKind::Pdf => "ACAD_PDFDEFINITIONS",
+            UnderlayKind::Dgn => "ACAD_DGNDEFINITIONS",
+            UnderlayKind::Dwf => "ACAD_DWFDEFINITIONS",
+        }
+    }
+
+    pub fn from_entity_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.entity_name() == name)
+    }
+
+    pub fn from_definition_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.definition_name() == name)
+    }
+}
+
+/// 底图显示选项（DXF 组码 280/281/282）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnderlayDisplayOptions {
+    /// 位 1：启用裁剪。
+    pub is_clipping: bool,
+    /// 位 2：显示底图。
+    pub is_on: bool,
+    /// 位 4：单色显示。
+    pub is_monochrome: bool,
+    /// 位 8：根据背景色调整。
+    pub adjust_for_background: bool,
+    /// 位 16：反向裁剪，隐藏边界内部。
+    pub is_clip_inverted: bool,
+    /// 对比度 20..=100。
+    pub contrast: i16,
+    /// 淡入度 0..=80。
+    pub fade: i16,
+}
+
+impl Default for UnderlayDisplayOptions {
+    fn default() -> Self {
+        Self {
+            is_clipping: false,
+            is_on: true,
+            is_monochrome: false,
+            adjust_for_background: false,
+            is_clip_inverted: false,
+            contrast: 100,
+            fade: 0,
+        }
+    }
+}
+
+impl UnderlayDisplayOptions {
+    pub fn from_flags(flags: i16) -> Self {
+        Self {
+            is_clipping: flags & 1 != 0,
+            is_on: flags & 2 != 0,
+            is_monochrome: flags & 4 != 0,
+            adjust_for_background: flags & 8 != 0,
+            is_clip_inverted: flags & 16 != 0,
+            ..Self::default()
+        }
+    }
+
+    pub fn flags(&self) -> i16 {
+        i16::from(self.is_clipping)
+            | (i16::from(self.is_on) << 1)
+            | (i16::from(self.is_monochrome) << 2)
+            | (i16::from(self.adjust_for_background) << 3)
+            | (i16::from(self.is_clip_inverted) << 4)
+    }
+}
+
+/// 参考底图实体。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Underlay {
+    pub kind: UnderlayKind,
+    /// 引用的定义对象句柄（组码 340）。
+    pub definition_handle: String,
+    pub insert: Point2,
+    pub scale: Vector2,
+    /// 旋转角（弧度）。
+    pub rotation: f64,
+    pub display_options: UnderlayDisplayOptions,
+    /// 底图坐标系中的裁剪边界（组码 11/21）；两个点表示矩形的对角。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clip_boundary: Vec<Point2>,
+    pub layer: String,
+}
+
+impl Underlay {
+    /// 底图坐标到世界坐标：先缩放、再旋转，最后平移到插入点。
+    pub fn to_world(&self, point: Point2) -> Point2 {
+        let (sin, cos) = self.rotation.sin_cos();
+        let x = point.x() * self.scale.x();
+        let y = point.y() * self.scale.y();
+        Point2::new(
+            self.insert.x() + x * cos - y * sin,
+            self.insert.y() + x * sin + y * cos,
+        )
+    }
+
+    /// 世界坐标下的裁剪边界；矩形边界展开为四个角点，未设置边界时返回空。
+    pub fn clip_outline(&self) -> Vec<Point2> {
+        let local = match self.clip_boundary.as_slice() {
+            [a, b] => vec![*a, Point2::new(b.x(), a.y()), *b, Point2::new(a.x(), b.y())],
+            points if points.len() >= 3 => points.to_vec(),
+            _ => Vec::new(),
+        };
+        local
+            .into_iter()
+            .map(|point| self.to_world(point))
+            .collect()
+    }
+}
+
+/// 底图定义对象（PDFDEFINITION/DGNDEFINITION/DWFDEFINITION），记录外部文件与页面。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnderlayDefinition {
+    pub handle: String,
+    pub kind: UnderlayKind,
+    /// 底图文件路径（组码 1）。
+    pub file_path: String,
+    /// PDF 页码、DGN 模型名或 DWF 图纸名（组码 2）。
+    #[serde(default)]
+    pub page: String,
+    /// 定义字典中的条目名，即界面中显示的底图名称。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// 底图文件依赖：同一文件与页面的所有参照汇总为一条。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnderlayDependency {
+    pub kind: UnderlayKind,
+    pub file_path: String,
+    pub page: String,
+    /// 模型空间与块定义中引用该定义的底图数量。
+    pub reference_count: usize,
+}
+
+impl Document {
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_underlay(
+        &mut self,
+        kind: UnderlayKind,
+        definition_handle: impl Into<String>,
+        insert: Point2,
+        scale: Vector2,
+        rotation: f64,
+        display_options: UnderlayDisplayOptions,
+        clip_boundary: Vec<Point2>,
+        layer: impl Into<String>,
+    ) -> EntityId {
+        let layer = layer.into();
+        self.ensure_layer(&layer);
+        let id = self.next_id();
+        self.entities.push((
+            id,
+            Entity::Underlay(Underlay {
+                kind,
+                definition_handle: definition_handle.into(),
+                insert,
+                scale,
+                rotation,
+                display_options,
+                clip_boundary,
+                layer,
+            }),
+        ));
+        id
+    }
+
+    /// 写入底图定义，同句柄的定义会被替换。
+    pub fn add_underlay_definition(&mut self, definition: UnderlayDefinition) {
+        self.underlay_definitions
+            .insert(definition.handle.clone(), definition);
+    }
+
+    #[inline]
+    pub fn underlay_definition(&self, handle: &str) -> Option<&UnderlayDefinition> {
+        self.underlay_definitions.get(handle)
+    }
+
+    #[inline]
+    pub fn underlay_definitions(&self) -> impl Iterator<Item = &UnderlayDefinition> {
+        self.underlay_definitions.values()
+    }
+
+    #[inline]
+    pub fn underlay_definition_mut(&mut self, handle: &str) -> Option<&mut UnderlayDefinition> {
+        self.underlay_definitions.get_mut(handle)
+    }
+
+    /// 底图文件依赖列表，按文件路径与页面排序；定义未被引用时计数为 0。
+    pub fn underlay_dependencies(&self) -> Vec<UnderlayDependency> {
+        let mut dependencies: Vec<UnderlayDependency> = Vec::new();
+        let mut definitions: Vec<&UnderlayDefinition> =
+            self.underlay_definitions.values().collect();
+        definitions.sort_by(|a, b| a.handle.cmp(&b.handle));
+        let references = self
+            .entities
+            .iter()
+            .map(|(_, entity)| entity)
+            .chain(self.blocks.values().flat_map(|block| block.entities.iter()))
+            .filter_map(|entity| match entity {
+                Entity::Underlay(underlay) => Some(underlay.definition_handle.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        for definition in definitions {
+            let count = references
+                .iter()
+                .filter(|handle| **handle == definition.handle)
+                .count();
+            match dependencies.iter_mut().find(|dependency| {
+                dependency.kind == definition.kind
+                    && dependency.file_path == definition.file_path
+                    && dependency.page == definition.page
+            }) {
+                Some(dependency) => dependency.reference_count += count,
+                None => dependencies.push(UnderlayDependency {
+                    kind: definition.kind,
+                    file_path: definition.file_path.clone(),
+                    page: definition.page.clone(),
+                    reference_count: count,
+                }),
+            }
+        }
+        dependencies.sort_by(|a, b| {
+            a.file_path
+                .cmp(&b.file_path)
+                .then_with(|| a.page.cmp(&b.page))
+        });
+        dependencies
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clip_outline_follows_insert_scale_and_rotation() {
+        let mut doc = Document::new();
+        doc.add_underlay_definition(UnderlayDefinition {
+            handle: "2A".to_string(),
+            kind: UnderlayKind::Pdf,
+            file_path: "plans/site.pdf".to_string(),
+            page: "1".to_string(),
+            name: Some("site-1".to_string()),
+        });
+        let id = doc.add_underlay(
+            UnderlayKind::Pdf,
+            "2A",
+            Point2::new(10.0, 0.0),
+            Vector2::new(2.0, 2.0),
+            std::f64::consts::FRAC_PI_2,
+            UnderlayDisplayOptions::from_flags(3),
+            vec![Point2::new(0.0, 0.0), Point2::new(1.0, 1.0)],
+            "UNDERLAY",
+        );
+        doc.add_underlay(
+            UnderlayKind::Pdf,
+            "2A",
+            Point2::new(0.0, 0.0),
+            Vector2::new(1.0, 1.0),
+            0.0,
+            UnderlayDisplayOptions::default(),
+            Vec::new(),
+            "UNDERLAY",
+        );
+
+        let Some(Entity::Underlay(underlay)) = doc.entity(id) else {
+            panic!("应为参考底图实体");
+        };
+        assert!(underlay.display_options.is_clipping);
+        assert_eq!(underlay.display_options.flags(), 3);
+        let outline = underlay.clip_outline();
+        assert_eq!(outline.len(), 4);
+        let corner = outline[2];
+        assert!((corner.x() - 8.0).abs() < 1e-9 && (corner.y() - 2.0).abs() < 1e-9);
+
+        let dependencies = doc.underlay_dependencies();
+        assert_eq!(dependencies.len(), 1);
+        assert_eq!(dependencies[0].file_path, "plans/site.pdf");
+        assert_eq!(dependencies[0].reference_count, 2);
+    }
+}
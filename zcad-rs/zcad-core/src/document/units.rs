@@ -0,0 +1,438 @@
+//! 图形单位：插入单位（`$INSUNITS`）与长度、角度的显示格式（`$LUNITS`/`$LUPREC`、`$AUNITS`/`$AUPREC`）。
+//!
+//! 插入单位决定把一个文档插入另一个文档时的缩放比例；显示格式用于标注文字等需要把数值转换为
+//! 文字的场合。工程与建筑格式约定一个图形单位为一英寸。
+
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+use super::Document;
+
+/// 插入单位，取值与 DXF 头变量 `$INSUNITS` 的编码一致。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InsertUnits {
+    /// 无单位，插入时不缩放。
+    #[default]
+    Unitless,
+    Inches,
+    Feet,
+    Miles,
+    Millimeters,
+    Centimeters,
+    Meters,
+    Kilometers,
+    Microinches,
+    Mils,
+    Yards,
+    Angstroms,
+    Nanometers,
+    Microns,
+    Decimeters,
+    Decameters,
+    Hectometers,
+    Gigameters,
+    AstronomicalUnits,
+    LightYears,
+    Parsecs,
+}
+
+impl InsertUnits {
+    const ALL: [InsertUnits; 21] = [
+        InsertUnits::Unitless,
+        InsertUnits::Inches,
+        InsertUnits::Feet,
+        InsertUnits::Miles,
+        InsertUnits::Millimeters,
+        InsertUnits::Centimeters,
+        InsertUnits::Meters,
+        InsertUnits::Kilometers,
+        InsertUnits::Microinches,
+        InsertUnits::Mils,
+        InsertUnits::Yards,
+        InsertUnits::Angstroms,
+        InsertUnits::Nanometers,
+        InsertUnits::Microns,
+        InsertUnits::Decimeters,
+        InsertUnits::Decameters,
+        InsertUnits::Hectometers,
+        InsertUnits::Gigameters,
+        InsertUnits::AstronomicalUnits,
+        InsertUnits::LightYears,
+        InsertUnits::Parsecs,
+    ];
+
+    /// 未知编码视为无单位。
+    pub fn from_code(code: i16) -> Self {
+        usize::try_from(code)
+            .ok()
+            .and_then(|index| Self::ALL.get(index).copied())
+            .unwrap_or_default()
+    }
+
+    pub fn code(self) -> i16 {
+        Self::ALL
+            .iter()
+            .position(|units| *units == self)
+            .map_or(0, |index| index as i16)
+    }
+
+    /// 一个单位折合的米数，无单位时为 `None`。
+    pub fn meters_per_unit(self) -> Option<f64> {
+        const INCH: f64 = 0.0254;
+        let meters = match self {
+            InsertUnits::Unitless => return None,
+            InsertUnits::Inches => INCH,
+            InsertUnits::Feet => 12.0 * INCH,
+            InsertUnits::Miles => 63_360.0 * INCH,
+            InsertUnits::Millimeters => 1e-3,
+            InsertUnits::Centimeters => 1e-2,
+            InsertUnits::Meters => 1.0,
+            InsertUnits::Kilometers => 1e3,
+            InsertUnits::Microinches => 1e-6 * INCH,
+            InsertUnits::Mils => 1e-3 * INCH,
+            InsertUnits::Yards => 36.0 * INCH,
+            InsertUnits::Angstroms => 1e-10,
+            InsertUnits::Nanometers => 1e-9,
+            InsertUnits::Microns => 1e-6,
+            InsertUnits::Decimeters => 1e-1,
+            InsertUnits::Decameters => 1e1,
+            InsertUnits::Hectometers => 1e2,
+            InsertUnits::Gigameters => 1e9,
+            InsertUnits::AstronomicalUnits => 149_597_870_700.0,
+            InsertUnits::LightYears => 9_460_730_472_580_800.0,
+            InsertUnits::Parsecs => 3.085_677_581_491_367e16,
+        };
+        Some(meters)
+    }
+
+    /// 以本单位表示的长度换算到 `target` 单位时的乘数；任一方无单位时为 1。
+    pub fn conversion_factor(self, target: InsertUnits) -> f64 {
+        match (self.meters_per_unit(), target.meters_per_unit()) {
+            (Some(from), Some(to)) => from / to,
+            _ => 1.0,
+        }
+    }
+}
+
+/// 长度显示格式，取值与 `$LUNITS` 的编码一致。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LengthFormat {
+    /// 科学计数，例如 `1.5000E+01`。
+    Scientific,
+    #[default]
+    Decimal,
+    /// 英尺与十进制英寸，例如 `1'-3.50"`。
+    Engineering,
+    /// 英尺与分数英寸，例如 `1'-3 1/2"`。
+    Architectural,
+    /// 分数，例如 `15 1/2`。
+    Fractional,
+}
+
+impl LengthFormat {
+    pub fn from_code(code: i16) -> Self {
+        match code {
+            1 => LengthFormat::Scientific,
+            3 => LengthFormat::Engineering,
+            4 => LengthFormat::Architectural,
+            5 => LengthFormat::Fractional,
+            _ => LengthFormat::Decimal,
+        }
+    }
+
+    pub fn code(self) -> i16 {
+        match self {
+            LengthFormat::Scientific => 1,
+            LengthFormat::Decimal => 2,
+            LengthFormat::Engineering => 3,
+            LengthFormat::Architectural => 4,
+            LengthFormat::Fractional => 5,
+        }
+    }
+}
+
+/// 角度显示格式，取值与 `$AUNITS` 的编码一致（不含测量员单位）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AngleFormat {
+    #[default]
+    DecimalDegrees,
+    /// 度分秒，例如 `45°30'15"`。
+    DegreesMinutesSeconds,
+    Gradians,
+    Radians,
+}
+
+impl AngleFormat {
+    pub fn from_code(code: i16) -> Self {
+        match code {
+            1 => AngleFormat::DegreesMinutesSeconds,
+            2 => AngleFormat::Gradians,
+            3 => AngleFormat::Radians,
+            _ => AngleFormat::DecimalDegrees,
+        }
+    }
+
+    pub fn code(self) -> i16 {
+        match self {
+            AngleFormat::DecimalDegrees => 0,
+            AngleFormat::DegreesMinutesSeconds => 1,
+            AngleFormat::Gradians => 2,
+            AngleFormat::Radians => 3,
+        }
+    }
+}
+
+/// 文档的单位设置。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Units {
+    pub insertion: InsertUnits,
+    pub length_format: LengthFormat,
+    /// 长度精度：小数位数；分数与建筑格式为分母的二进制指数（3 表示 1/8）。
+    pub length_precision: u8,
+    pub angle_format: AngleFormat,
+    /// 角度精度：小数位数；度分秒格式下 0 只显示度，1–2 显示到分，3–4 显示到秒，更大值为秒的小数位数加 4。
+    pub angle_precision: u8,
+}
+
+impl Default for Units {
+    fn default() -> Self {
+        Self {
+            insertion: InsertUnits::Unitless,
+            length_format: LengthFormat::Decimal,
+            length_precision: 2,
+            angle_format: AngleFormat::DecimalDegrees,
+            angle_precision: 0,
+        }
+    }
+}
+
+impl Units {
+    /// 按长度格式与精度把图形单位下的长度转换为文字。
+    pub fn format_length(&self, value: f64) -> String {
+        if !value.is_finite() {
+            return value.to_string();
+        }
+        let precision = usize::from(self.length_precision);
+        let sign = if value < 0.0 { "-" } else { "" };
+        let magnitude = value.abs();
+        match self.length_format {
+            LengthFormat::Decimal => format!("{value:.precision$}"),
+            LengthFormat::Scientific => {
+                let text = format!("{value:.precision$e}");
+                let (mantissa, exponent) = text.split_once('e').unwrap_or((&text, "0"));
+                let exponent: i32 = exponent.parse().unwrap_or(0);
+                let exponent_sign = if exponent < 0 { '-' } else { '+' };
+                format!("{mantissa}E{exponent_sign}{:02}", exponent.abs())
+            }
+            LengthFormat::Fractional => {
+                format!("{sign}{}", fraction(magnitude, self.length_precision))
+            }
+            LengthFormat::Engineering => {
+                let scale = 10f64.powi(i32::from(self.length_precision));
+                let (feet, inches) = feet_and_inches((magnitude * scale).round() / scale);
+                format!("{sign}{feet}'-{inches:.precision$}\"")
+            }
+            LengthFormat::Architectural => {
+                let denominator = fraction_denominator(self.length_precision);
+                let (feet, inches) =
+                    feet_and_inches((magnitude * denominator).round() / denominator);
+                let inches = fraction(inches, self.length_precision);
+                format!("{sign}{feet}'-{inches}\"")
+            }
+        }
+    }
+
+    /// 按角度格式与精度把弧度值转换为文字。
+    pub fn format_angle(&self, radians: f64) -> String {
+        if !radians.is_finite() {
+            return radians.to_string();
+        }
+        let precision = usize::from(self.angle_precision);
+        match self.angle_format {
+            AngleFormat::DecimalDegrees => format!("{:.precision$}°", radians.to_degrees()),
+            AngleFormat::Gradians => format!("{:.precision$}g", radians * 200.0 / PI),
+            AngleFormat::Radians => format!("{radians:.precision$}r"),
+            AngleFormat::DegreesMinutesSeconds => {
+                let sign = if radians < 0.0 { "-" } else { "" };
+                let degrees = radians.abs().to_degrees();
+                match self.angle_precision {
+                    0 => format!("{sign}{degrees:.0}°"),
+                    1 | 2 => {
+                        let minutes = (degrees * 60.0).round() as u64;
+                        format!("{sign}{}°{:02}'", minutes / 60, minutes % 60)
+                    }
+                    _ => {
+                        let decimals = precision.saturating_sub(4);
+                        let scale = 10f64.powi(decimals as i32);
+                        let seconds = (degrees * 3600.0 * scale).round() / scale;
+                        let whole = seconds.trunc() as u64;
+                        let fraction = seconds - whole as f64;
+                        let seconds = (whole % 60) as f64 + fraction;
+                        let width = if decimals > 0 { decimals + 3 } else { 2 };
+                        format!(
+                            "{sign}{}°{:02}'{seconds:0width$.decimals$}\"",
+                            whole / 3600,
+                            whole / 60 % 60,
+                        )
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 分数格式的分母：2 的 `precision` 次方，上限 1/256。
+fn fraction_denominator(precision: u8) -> f64 {
+    f64::from(1u32 << precision.min(8))
+}
+
+/// 非负数的带分数文字，例如 `15 1/2`、`3/8`、`2`。
+fn fraction(value: f64, precision: u8) -> String {
+    let denominator = fraction_denominator(precision) as u64;
+    let units = (value * denominator as f64).round() as u64;
+    let (whole, mut numerator) = (units / denominator, units % denominator);
+    let mut denominator = denominator;
+    while numerator > 0 && numerator % 2 == 0 {
+        numerator /= 2;
+        denominator /= 2;
+    }
+    match (whole, numerator) {
+        (_, 0) => whole.to_string(),
+        (0, _) => format!("{numerator}/{denominator}"),
+        _ => format!("{whole} {numerator}/{denominator}"),
+    }
+}
+
+/// 以英寸计的非负长度拆分为整英尺与剩余英寸。
+fn feet_and_inches(inches: f64) -> (u64, f64) {
+    let feet = (inches / 12.0).floor();
+    (feet as u64, inches - feet * 12.0)
+}
+
+impl Document {
+    /// 文档的单位设置，对应头变量 `$INSUNITS`、`$LUNITS`、`$LUPREC`、`$AUNITS`、`$AUPREC`。
+    pub fn units(&self) -> Units {
+        *self.units
+    }
+
+    pub fn set_units(&mut self, units: Units) {
+        *self.units = units;
+    }
+
+    /// 把 `source` 单位下的长度换算到本文档单位的乘数。
+    pub fn unit_scale_from(&self, source: InsertUnits) -> f64 {
+        source.conversion_factor(self.units.insertion)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn units(length_format: LengthFormat, length_precision: u8) -> Units {
+        Units {
+            length_format,
+            length_precision,
+            ..Units::default()
+        }
+    }
+
+    #[test]
+    fn codes_round_trip_and_convert() {
+        for code in 0..=20 {
+            assert_eq!(InsertUnits::from_code(code).code(), code);
+        }
+        assert_eq!(InsertUnits::from_code(99), InsertUnits::Unitless);
+        assert_eq!(InsertUnits::from_code(4), InsertUnits::Millimeters);
+        assert!(
+            (InsertUnits::Inches.conversion_factor(InsertUnits::Millimeters) - 25.4).abs() < 1e-12
+        );
+        assert!(
+            (InsertUnits::Millimeters.conversion_factor(InsertUnits::Meters) - 1e-3).abs() < 1e-15
+        );
+        assert_eq!(
+            InsertUnits::Unitless.conversion_factor(InsertUnits::Feet),
+            1.0
+        );
+        assert_eq!(
+            InsertUnits::Feet.conversion_factor(InsertUnits::Unitless),
+            1.0
+        );
+    }
+
+    #[test]
+    fn formats_lengths() {
+        assert_eq!(units(LengthFormat::Decimal, 2).format_length(15.5), "15.50");
+        assert_eq!(
+            units(LengthFormat::Scientific, 4).format_length(15.5),
+            "1.5500E+01"
+        );
+        assert_eq!(
+            units(LengthFormat::Scientific, 1).format_length(0.0012),
+            "1.2E-03"
+        );
+        assert_eq!(
+            units(LengthFormat::Fractional, 3).format_length(15.5),
+            "15 1/2"
+        );
+        assert_eq!(
+            units(LengthFormat::Fractional, 3).format_length(0.376),
+            "3/8"
+        );
+        assert_eq!(
+            units(LengthFormat::Engineering, 2).format_length(15.5),
+            "1'-3.50\""
+        );
+        assert_eq!(
+            units(LengthFormat::Engineering, 2).format_length(23.999),
+            "2'-0.00\""
+        );
+        assert_eq!(
+            units(LengthFormat::Architectural, 4).format_length(15.5),
+            "1'-3 1/2\""
+        );
+        assert_eq!(
+            units(LengthFormat::Architectural, 4).format_length(-6.0),
+            "-0'-6\""
+        );
+    }
+
+    #[test]
+    fn formats_angles() {
+        let angle = |angle_format, angle_precision| Units {
+            angle_format,
+            angle_precision,
+            ..Units::default()
+        };
+        let value = (45.0 + 30.0 / 60.0 + 15.0 / 3600.0_f64).to_radians();
+        assert_eq!(
+            angle(AngleFormat::DecimalDegrees, 2).format_angle(value),
+            "45.50°"
+        );
+        assert_eq!(
+            angle(AngleFormat::DegreesMinutesSeconds, 0).format_angle(value),
+            "46°"
+        );
+        assert_eq!(
+            angle(AngleFormat::DegreesMinutesSeconds, 2).format_angle(value),
+            "45°30'"
+        );
+        assert_eq!(
+            angle(AngleFormat::DegreesMinutesSeconds, 4).format_angle(value),
+            "45°30'15\""
+        );
+        assert_eq!(
+            angle(AngleFormat::DegreesMinutesSeconds, 5).format_angle(value),
+            "45°30'15.0\""
+        );
+        assert_eq!(
+            angle(AngleFormat::Gradians, 1).format_angle(PI / 2.0),
+            "100.0g"
+        );
+        assert_eq!(angle(AngleFormat::Radians, 3).format_angle(PI), "3.142r");
+    }
+}
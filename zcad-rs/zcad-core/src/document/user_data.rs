@@ -0,0 +1,239 @@
+//! 实体用户数据：以字符串为键、JSON 形式的值为内容，供基于 zcad-core 的应用为实体打标签。
+//!
+//! 用户数据随原生格式序列化；DXF 中可选地以 `ZCAD_USERDATA` 扩展数据（组码 1001）保存，
+//! 每个键值对写为一组以 `{`/`}`（组码 1002）括起的字符串，先是键，其后是值的 JSON 文本。
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Document, EntityId};
+
+/// 扩展数据中标识用户数据的应用名（组码 1001）。
+pub const USER_DATA_XDATA_APP: &str = "ZCAD_USERDATA";
+
+/// 单个实体的用户数据，按键排序。
+pub type UserData = BTreeMap<String, UserValue>;
+
+/// 用户数据的值，序列化形式与对应的 JSON 值一致。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum UserValue {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Real(f64),
+    String(String),
+    List(Vec<UserValue>),
+    Map(BTreeMap<String, UserValue>),
+}
+
+impl UserValue {
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            UserValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            UserValue::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// 整数与实数都可按实数读取。
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            UserValue::Integer(value) => Some(*value as f64),
+            UserValue::Real(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            UserValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl From<bool> for UserValue {
+    fn from(value: bool) -> Self {
+        UserValue::Bool(value)
+    }
+}
+
+impl From<i32> for UserValue {
+    fn from(value: i32) -> Self {
+        UserValue::Integer(value.into())
+    }
+}
+
+impl From<i64> for UserValue {
+    fn from(value: i64) -> Self {
+        UserValue::Integer(value)
+    }
+}
+
+impl From<f64> for UserValue {
+    fn from(value: f64) -> Self {
+        UserValue::Real(value)
+    }
+}
+
+impl From<&str> for UserValue {
+    fn from(value: &str) -> Self {
+        UserValue::String(value.to_string())
+    }
+}
+
+impl From<String> for UserValue {
+    fn from(value: String) -> Self {
+        UserValue::String(value)
+    }
+}
+
+impl From<Vec<UserValue>> for UserValue {
+    fn from(value: Vec<UserValue>) -> Self {
+        UserValue::List(value)
+    }
+}
+
+impl From<BTreeMap<String, UserValue>> for UserValue {
+    fn from(value: BTreeMap<String, UserValue>) -> Self {
+        UserValue::Map(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserDataError {
+    EntityNotFound(EntityId),
+    /// 键为空。
+    InvalidKey,
+}
+
+impl fmt::Display for UserDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UserDataError::EntityNotFound(id) => write!(f, "entity {} not found", id.get()),
+            UserDataError::InvalidKey => write!(f, "user data key cannot be empty"),
+        }
+    }
+}
+
+impl std::error::Error for UserDataError {}
+
+impl Document {
+    /// 实体的全部用户数据；未设置时返回 `None`。
+    #[inline]
+    pub fn user_data(&self, id: EntityId) -> Option<&UserData> {
+        self.user_data.get(&id)
+    }
+
+    #[inline]
+    pub fn user_value(&self, id: EntityId, key: &str) -> Option<&UserValue> {
+        self.user_data.get(&id)?.get(key)
+    }
+
+    /// 设置实体的一个用户数据项，返回同键的旧值。
+    pub fn set_user_value(
+        &mut self,
+        id: EntityId,
+        key: impl Into<String>,
+        value: impl Into<UserValue>,
+    ) -> Result<Option<UserValue>, UserDataError> {
+        let key = key.into();
+        if key.is_empty() {
+            return Err(UserDataError::InvalidKey);
+        }
+        if self.entity(id).is_none() {
+            return Err(UserDataError::EntityNotFound(id));
+        }
+        Ok(self
+            .user_data
+            .entry(id)
+            .or_default()
+            .insert(key, value.into()))
+    }
+
+    /// 删除实体的一个用户数据项，最后一项删除后不再保留该实体的记录。
+    pub fn remove_user_value(&mut self, id: EntityId, key: &str) -> Option<UserValue> {
+        let data = self.user_data.get_mut(&id)?;
+        let removed = data.remove(key);
+        if data.is_empty() {
+            self.user_data.remove(&id);
+        }
+        removed
+    }
+
+    /// 清除实体的全部用户数据，返回被清除的内容。
+    pub fn clear_user_data(&mut self, id: EntityId) -> Option<UserData> {
+        self.user_data.remove(&id)
+    }
+
+    /// 带有指定用户数据键的实体，按实体编号排序。
+    pub fn entities_with_user_key(&self, key: &str) -> Vec<EntityId> {
+        let mut ids: Vec<EntityId> = self
+            .user_data
+            .iter()
+            .filter(|(_, data)| data.contains_key(key))
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort_by_key(|id| id.get());
+        ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Point2;
+
+    #[test]
+    fn user_values_are_set_queried_and_removed() {
+        let mut doc = Document::new();
+        let line = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+        let circle = doc.add_circle(Point2::new(0.0, 0.0), 1.0, "0");
+
+        assert_eq!(doc.set_user_value(line, "asset", "P-101"), Ok(None));
+        assert_eq!(doc.set_user_value(line, "rating", 2.5), Ok(None));
+        assert_eq!(
+            doc.set_user_value(line, "asset", "P-102"),
+            Ok(Some(UserValue::from("P-101")))
+        );
+        doc.set_user_value(circle, "asset", UserValue::Null)
+            .unwrap();
+        assert_eq!(
+            doc.user_value(line, "asset").and_then(UserValue::as_str),
+            Some("P-102")
+        );
+        assert_eq!(
+            doc.user_value(line, "rating").and_then(UserValue::as_f64),
+            Some(2.5)
+        );
+        assert_eq!(doc.entities_with_user_key("asset"), vec![line, circle]);
+
+        assert_eq!(
+            doc.remove_user_value(circle, "asset"),
+            Some(UserValue::Null)
+        );
+        assert!(doc.user_data(circle).is_none());
+
+        let missing = EntityId::new(9999);
+        assert_eq!(
+            doc.set_user_value(missing, "asset", true),
+            Err(UserDataError::EntityNotFound(missing))
+        );
+        assert_eq!(
+            doc.set_user_value(line, "", true),
+            Err(UserDataError::InvalidKey)
+        );
+
+        doc.remove_entity(line);
+        assert!(doc.user_data(line).is_none());
+    }
+}
@@ -0,0 +1,529 @@
+//! 外部参照（XRef）管理：附着时为图层/块加上 `参照名|` 前缀，
+//! 绑定时改写为 `参照名$N$` 并并入宿主，拆离时移除全部依赖资源。
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{BlockDefinition, Document, Entity, EntityId, Layer, MLeaderContent};
+use crate::geometry::{Point2, Transform2};
+
+/// 依赖外部参照的资源名分隔符，与 AutoCAD 的 `xref|layer` 约定一致。
+pub const XREF_SEPARATOR: char = '|';
+
+/// 绑定后资源名使用的分隔符（`xref$0$layer`）。
+const BIND_SEPARATOR: char = '$';
+
+/// 附着在宿主文档上的外部参照记录。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct XRef {
+    pub name: String,
+    /// DXF 中记录的原始路径（组码 1），未做解析。
+    pub path: String,
+    /// 是否已载入参照内容；仅登记的参照（例如文件缺失）为 false。
+    pub is_loaded: bool,
+    /// 已加前缀的依赖图层名。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub layers: Vec<String>,
+    /// 已加前缀的依赖块名（不含参照块本身）。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocks: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XRefError {
+    /// 参照名为空或包含分隔符。
+    InvalidName(String),
+    /// 宿主中已有同名块。
+    NameConflict(String),
+    /// 参照已载入，需要先拆离。
+    AlreadyLoaded(String),
+    NotFound(String),
+    /// 参照尚未载入内容，无法绑定。
+    NotLoaded(String),
+}
+
+impl fmt::Display for XRefError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XRefError::InvalidName(name) => write!(f, "invalid xref name {name:?}"),
+            XRefError::NameConflict(name) => {
+                write!(f, "block {name:?} already exists in host document")
+            }
+            XRefError::AlreadyLoaded(name) => write!(f, "xref {name:?} is already loaded"),
+            XRefError::NotFound(name) => write!(f, "xref {name:?} not found"),
+            XRefError::NotLoaded(name) => write!(f, "xref {name:?} is not loaded"),
+        }
+    }
+}
+
+impl std::error::Error for XRefError {}
+
+/// 返回依赖外部参照的资源名，例如 `SITE|WALLS`。
+fn xref_dependent_name(xref: &str, resource: &str) -> String {
+    format!("{xref}{XREF_SEPARATOR}{resource}")
+}
+
+impl Document {
+    /// 登记一个尚未载入内容的外部参照（例如 DXF 中的 XRef 块），已存在时返回 false。
+    pub fn register_xref(&mut self, name: impl Into<String>, path: impl Into<String>) -> bool {
+        let name = name.into();
+        if self.xrefs.contains_key(&name) {
+            return false;
+        }
+        let xref = XRef {
+            name: name.clone(),
+            path: path.into(),
+            is_loaded: false,
+            layers: Vec::new(),
+            blocks: Vec::new(),
+        };
+        self.xrefs.insert(name, xref);
+        true
+    }
+
+    /// 将参照文档附着到宿主：图层/块加 `name|` 前缀，模型空间实体成为名为 `name` 的参照块。
+    /// 图层 0 与宿主共享，不加前缀；两个文档的插入单位不同时参照内容按单位比例缩放。
+    pub fn attach_xref(
+        &mut self,
+        name: impl Into<String>,
+        path: impl Into<String>,
+        source: Document,
+    ) -> Result<(), XRefError> {
+        let name = name.into();
+        if name.trim().is_empty() || name.contains(XREF_SEPARATOR) {
+            return Err(XRefError::InvalidName(name));
+        }
+        if self.xrefs.get(&name).is_some_and(|xref| xref.is_loaded) {
+            return Err(XRefError::AlreadyLoaded(name));
+        }
+        if self.blocks.contains_key(&name) {
+            return Err(XRefError::NameConflict(name));
+        }
+
+        let source_units = source.units().insertion;
+        let layer_map: HashMap<String, String> = source
+            .layers
+            .keys()
+            .filter(|layer| layer.as_str() != "0")
+            .map(|layer| (layer.clone(), xref_dependent_name(&name, layer)))
+            .collect();
+        let block_map: HashMap<String, String> = source
+            .blocks
+            .keys()
+            .map(|block| (block.clone(), xref_dependent_name(&name, block)))
+            .collect();
+
+        for (original, layer) in &source.layers {
+            if let Some(renamed) = layer_map.get(original) {
+                let namespaced = Layer {
+                    name: renamed.clone(),
+                    ..layer.clone()
+                };
+                self.layers.insert(renamed.clone(), namespaced);
+            }
+        }
+        for (_, mut definition) in source.blocks.into_inner() {
+            rename_block_definition(&mut definition, &layer_map, &block_map);
+            self.blocks.insert(definition.name.clone(), definition);
+        }
+
+        // 参照内容按插入单位换算到宿主单位，嵌套块随块参照的比例一并缩放
+        let scale = self.unit_scale_from(source_units);
+        let unit_transform = (scale != 1.0).then(|| Transform2::scaling(scale, scale));
+        let entities = source
+            .entities
+            .into_iter()
+            .map(|(_, mut entity)| {
+                rename_entity(&mut entity, &layer_map, &block_map);
+                if let Some(transform) = &unit_transform {
+                    entity.transform(transform);
+                }
+                entity
+            })
+            .collect();
+        self.blocks.insert(
+            name.clone(),
+            BlockDefinition {
+                name: name.clone(),
+                base_point: Point2::new(0.0, 0.0),
+                entities,
+                attributes: Vec::new(),
+            },
+        );
+        // 已有的同名参照此时才有内容
+        self.entities.invalidate_extents();
+        self.ensure_layer("0");
+
+        let path = path.into();
+        let xref = XRef {
+            name: name.clone(),
+            path,
+            is_loaded: true,
+            layers: sorted_values(layer_map),
+            blocks: sorted_values(block_map),
+        };
+        self.xrefs.insert(name, xref);
+        Ok(())
+    }
+
+    /// 绑定参照：依赖资源改名为 `name$N$原名`（N 取首个不冲突的序号），
+    /// 参照块转为普通块并从参照表中移除。
+    pub fn bind_xref(&mut self, name: &str) -> Result<(), XRefError> {
+        let xref = self
+            .xrefs
+            .get(name)
+            .ok_or_else(|| XRefError::NotFound(name.to_string()))?;
+        if !xref.is_loaded {
+            return Err(XRefError::NotLoaded(name.to_string()));
+        }
+        let xref = self.xrefs.remove(name).expect("xref checked above");
+
+        let mut taken: HashSet<String> = self.layers.keys().cloned().collect();
+        let layer_map = bound_names(name, &xref.layers, &mut taken);
+        let mut taken: HashSet<String> = self.blocks.keys().cloned().collect();
+        let block_map = bound_names(name, &xref.blocks, &mut taken);
+
+        for (from, to) in &layer_map {
+            if let Some(mut layer) = self.layers.remove(from) {
+                layer.name = to.clone();
+                self.layers.insert(to.clone(), layer);
+            }
+        }
+        for (from, to) in &block_map {
+            if let Some(definition) = self.blocks.remove(from) {
+                self.blocks.insert(to.clone(), definition);
+            }
+            for target in self.block_handles.values_mut() {
+                if target == from {
+                    *target = to.clone();
+                }
+            }
+        }
+        self.rename_all_references(&layer_map, &block_map);
+        Ok(())
+    }
+
+    /// 拆离参照：移除依赖图层/块、参照块以及所有引用该参照块的块参照，返回移除的参照数量。
+    pub fn detach_xref(&mut self, name: &str) -> Result<usize, XRefError> {
+        let xref = self
+            .xrefs
+            .remove(name)
+            .ok_or_else(|| XRefError::NotFound(name.to_string()))?;
+
+        for layer in &xref.layers {
+            self.layers.remove(layer);
+        }
+        let mut removed_blocks: HashSet<&str> = xref.blocks.iter().map(String::as_str).collect();
+        removed_blocks.insert(name);
+        self.blocks
+            .retain(|block, _| !removed_blocks.contains(block.as_str()));
+        self.block_handles
+            .retain(|_, block| !removed_blocks.contains(block.as_str()));
+
+        let is_reference = |entity: &Entity| matches!(entity, Entity::BlockReference(reference) if reference.name == name);
+        let references: Vec<EntityId> = self
+            .entities
+            .iter()
+            .filter(|(_, entity)| is_reference(entity))
+            .map(|(id, _)| *id)
+            .collect();
+        for &id in &references {
+            self.remove_entity(id);
+        }
+        let mut removed = references.len();
+        for definition in self.blocks.values_mut() {
+            let before = definition.entities.len();
+            definition.entities.retain(|entity| !is_reference(entity));
+            removed += before - definition.entities.len();
+        }
+        Ok(removed)
+    }
+
+    #[inline]
+    pub fn xref(&self, name: &str) -> Option<&XRef> {
+        self.xrefs.get(name)
+    }
+
+    #[inline]
+    pub fn xrefs(&self) -> impl Iterator<Item = &XRef> {
+        self.xrefs.values()
+    }
+
+    /// 在模型空间与全部块定义中批量替换图层名与块名引用。
+    pub(super) fn rename_all_references(
+        &mut self,
+        layer_map: &HashMap<String, String>,
+        block_map: &HashMap<String, String>,
+    ) {
+        let touched = self.record_modified_where(|entity| {
+            let mut renamed = entity.clone();
+            rename_entity(&mut renamed, layer_map, block_map);
+            renamed != *entity
+        });
+        if touched {
+            for (_, entity) in &mut self.entities {
+                rename_entity(entity, layer_map, block_map);
+            }
+        }
+        for definition in self.blocks.values_mut() {
+            rename_block_definition(definition, layer_map, block_map);
+        }
+    }
+}
+
+fn sorted_values(map: HashMap<String, String>) -> Vec<String> {
+    let mut values: Vec<String> = map.into_values().collect();
+    values.sort();
+    values
+}
+
+/// 为依赖资源生成绑定名，`taken` 记录已占用名称以避免冲突。
+fn bound_names(
+    xref: &str,
+    dependents: &[String],
+    taken: &mut HashSet<String>,
+) -> HashMap<String, String> {
+    let prefix = xref_dependent_name(xref, "");
+    let mut renamed = HashMap::new();
+    for dependent in dependents {
+        let resource = dependent.strip_prefix(&prefix).unwrap_or(dependent);
+        let mut index = 0usize;
+        let bound = loop {
+            let candidate = format!("{xref}{BIND_SEPARATOR}{index}{BIND_SEPARATOR}{resource}");
+            if !taken.contains(&candidate) {
+                break candidate;
+            }
+            index += 1;
+        };
+        taken.insert(bound.clone());
+        renamed.insert(dependent.clone(), bound);
+    }
+    renamed
+}
+
+fn rename_block_definition(
+    definition: &mut BlockDefinition,
+    layer_map: &HashMap<String, String>,
+    block_map: &HashMap<String, String>,
+) {
+    if let Some(renamed) = block_map.get(&definition.name) {
+        definition.name = renamed.clone();
+    }
+    for entity in &mut definition.entities {
+        rename_entity(entity, layer_map, block_map);
+    }
+    for attribute in &mut definition.attributes {
+        rename_in_place(&mut attribute.layer, layer_map);
+    }
+}
+
+fn rename_entity(
+    entity: &mut Entity,
+    layer_map: &HashMap<String, String>,
+    block_map: &HashMap<String, String>,
+) {
+    if let Some(renamed) = layer_map.get(entity.layer_name()) {
+        entity.set_layer_name(renamed.clone());
+    }
+    match entity {
+        Entity::BlockReference(reference) => {
+            rename_in_place(&mut reference.name, block_map);
+            for attribute in &mut reference.attributes {
+                rename_in_place(&mut attribute.layer, layer_map);
+            }
+        }
+        Entity::Dimension(dimension) => {
+            if let Some(block_name) = dimension.block_name.as_mut() {
+                rename_in_place(block_name, block_map);
+            }
+        }
+        Entity::MLeader(mleader) => {
+            if let MLeaderContent::Block { block } = &mut mleader.content
+                && let Some(block_name) = block.block_name.as_mut()
+            {
+                rename_in_place(block_name, block_map);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn rename_in_place(value: &mut String, map: &HashMap<String, String>) {
+    if let Some(renamed) = map.get(value.as_str()) {
+        *value = renamed.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{Circle, InsertUnits, Units};
+    use crate::geometry::Vector2;
+
+    fn site_document() -> Document {
+        let mut site = Document::new();
+        site.add_line(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), "WALLS");
+        site.add_circle(Point2::new(5.0, 5.0), 1.0, "0");
+        site.add_block_definition(BlockDefinition {
+            name: "TREE".to_string(),
+            base_point: Point2::new(0.0, 0.0),
+            entities: vec![Entity::Circle(Circle {
+                center: Point2::new(0.0, 0.0),
+                radius: 2.0,
+                layer: "PLANTS".to_string(),
+            })],
+            attributes: Vec::new(),
+        });
+        site.add_block_reference(
+            "TREE",
+            Point2::new(3.0, 3.0),
+            Vector2::new(1.0, 1.0),
+            0.0,
+            Vec::new(),
+            "PLANTS",
+        );
+        site
+    }
+
+    fn host_with_site() -> Document {
+        let mut host = Document::new();
+        host.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 1.0), "WALLS");
+        host.attach_xref("SITE", "site.dxf", site_document())
+            .expect("attach xref");
+        host.add_block_reference(
+            "SITE",
+            Point2::new(100.0, 0.0),
+            Vector2::new(1.0, 1.0),
+            0.0,
+            Vec::new(),
+            "0",
+        );
+        host
+    }
+
+    #[test]
+    fn attach_namespaces_layers_and_blocks() {
+        let host = host_with_site();
+        let xref = host.xref("SITE").expect("xref registered");
+        assert!(xref.is_loaded);
+        assert_eq!(xref.layers, vec!["SITE|PLANTS", "SITE|WALLS"]);
+        assert_eq!(xref.blocks, vec!["SITE|TREE"]);
+
+        let layers: Vec<&str> = host.layers().map(|layer| layer.name.as_str()).collect();
+        assert!(layers.contains(&"WALLS"));
+        assert!(layers.contains(&"SITE|WALLS"));
+
+        let site_block = host.block("SITE").expect("xref block");
+        assert_eq!(site_block.entities.len(), 3);
+        assert_eq!(site_block.entities[0].layer_name(), "SITE|WALLS");
+        assert_eq!(site_block.entities[1].layer_name(), "0");
+        match &site_block.entities[2] {
+            Entity::BlockReference(reference) => assert_eq!(reference.name, "SITE|TREE"),
+            other => panic!("expected block reference, got {other:?}"),
+        }
+        let tree = host.block("SITE|TREE").expect("namespaced block");
+        assert_eq!(tree.entities[0].layer_name(), "SITE|PLANTS");
+
+        let mut again = host.clone();
+        assert_eq!(
+            again.attach_xref("SITE", "site.dxf", Document::new()),
+            Err(XRefError::AlreadyLoaded("SITE".to_string()))
+        );
+    }
+
+    #[test]
+    fn attach_scales_content_between_insertion_units() {
+        let mut site = site_document();
+        site.set_units(Units {
+            insertion: InsertUnits::Meters,
+            ..Units::default()
+        });
+        let mut host = Document::new();
+        host.set_units(Units {
+            insertion: InsertUnits::Millimeters,
+            ..Units::default()
+        });
+        host.attach_xref("SITE", "site.dxf", site)
+            .expect("attach xref");
+
+        let site_block = host.block("SITE").expect("xref block");
+        match &site_block.entities[0] {
+            Entity::Line(line) => assert_eq!(line.end, Point2::new(10_000.0, 0.0)),
+            other => panic!("expected line, got {other:?}"),
+        }
+        match &site_block.entities[2] {
+            Entity::BlockReference(reference) => {
+                assert_eq!(reference.insert, Point2::new(3000.0, 3000.0));
+                assert_eq!(reference.scale, Vector2::new(1000.0, 1000.0));
+            }
+            other => panic!("expected block reference, got {other:?}"),
+        }
+        // 嵌套块定义保持原单位，由块参照比例换算
+        let tree = host.block("SITE|TREE").expect("namespaced block");
+        assert!(matches!(&tree.entities[0], Entity::Circle(circle) if circle.radius == 2.0));
+    }
+
+    #[test]
+    fn bind_renames_dependents_and_avoids_collisions() {
+        let mut host = host_with_site();
+        host.ensure_layer("SITE$0$WALLS");
+        host.bind_xref("SITE").expect("bind xref");
+
+        assert!(host.xref("SITE").is_none());
+        let layers: Vec<&str> = host.layers().map(|layer| layer.name.as_str()).collect();
+        assert!(!layers.iter().any(|layer| layer.contains(XREF_SEPARATOR)));
+        assert!(layers.contains(&"SITE$1$WALLS"));
+        assert!(layers.contains(&"SITE$0$PLANTS"));
+
+        let site_block = host.block("SITE").expect("bound block");
+        assert_eq!(site_block.entities[0].layer_name(), "SITE$1$WALLS");
+        match &site_block.entities[2] {
+            Entity::BlockReference(reference) => assert_eq!(reference.name, "SITE$0$TREE"),
+            other => panic!("expected block reference, got {other:?}"),
+        }
+        assert!(host.block("SITE$0$TREE").is_some());
+        assert!(host.block("SITE|TREE").is_none());
+    }
+
+    #[test]
+    fn detach_removes_dependents_and_references() {
+        let mut host = host_with_site();
+        let (reference, _) = host
+            .entities()
+            .find(|(_, entity)| matches!(entity, Entity::BlockReference(_)))
+            .expect("site reference");
+        let reference = *reference;
+        host.set_user_value(reference, "tag", "x").unwrap();
+
+        let (removed, changes) = host.capture_changes(|host| host.detach_xref("SITE"));
+        assert_eq!(removed, Ok(1));
+        assert!(host.block("SITE").is_none());
+        assert!(host.block("SITE|TREE").is_none());
+        assert!(host.layers().all(|layer| !layer.name.starts_with("SITE|")));
+        assert_eq!(host.entities().count(), 1);
+        assert!(host.user_data(reference).is_none());
+        assert_eq!(
+            host.detach_xref("SITE"),
+            Err(XRefError::NotFound("SITE".to_string()))
+        );
+        // 删除的参照进入修改记录，可以撤销
+        host.revert_changes(&changes);
+        assert!(host.entity(reference).is_some());
+    }
+
+    #[test]
+    fn registered_xref_cannot_be_bound_until_loaded() {
+        let mut host = Document::new();
+        assert!(host.register_xref("SITE", "missing.dxf"));
+        assert!(!host.register_xref("SITE", "other.dxf"));
+        assert_eq!(
+            host.bind_xref("SITE"),
+            Err(XRefError::NotLoaded("SITE".to_string()))
+        );
+        host.attach_xref("SITE", "site.dxf", site_document())
+            .expect("attach registered xref");
+        assert!(host.xref("SITE").is_some_and(|xref| xref.is_loaded));
+    }
+}
@@ -0,0 +1,69 @@
+//! 凸度段：多段线与填充边界中以凸度（圆心角四分之一的正切）描述的一段圆弧。
+
+use glam::DVec2;
+
+use super::Tolerance;
+
+/// 凸度绝对值或弦长不超过此值的段按直线处理。
+pub(crate) const STRAIGHT_BULGE: f64 = Tolerance::DEFAULT.linear;
+
+/// 凸度段所在的圆弧。
+#[derive(Debug, Clone, Copy)]
+pub struct BulgeArc {
+    pub center: DVec2,
+    pub radius: f64,
+    /// 起点相对圆心的方向角。
+    pub start_angle: f64,
+    /// 带符号圆心角，正值逆时针。
+    pub sweep: f64,
+}
+
+impl BulgeArc {
+    /// 自 `start` 至 `end`、凸度为 `bulge` 的圆弧；直线段与零长度段返回 `None`。
+    pub fn new(start: DVec2, end: DVec2, bulge: f64) -> Option<Self> {
+        let chord = end - start;
+        let length = chord.length();
+        if bulge.abs() <= STRAIGHT_BULGE || length <= STRAIGHT_BULGE {
+            return None;
+        }
+        let sweep = 4.0 * bulge.atan();
+        let radius = length / (2.0 * (sweep / 2.0).sin());
+        let center = (start + end) / 2.0 + chord.perp() / length * radius * (sweep / 2.0).cos();
+        Some(Self {
+            center,
+            radius: radius.abs(),
+            start_angle: (start - center).to_angle(),
+            sweep,
+        })
+    }
+
+    /// 圆弧上按圆心角比例 `fraction`（0 为起点、1 为终点）取的点。
+    #[inline]
+    pub fn point(&self, fraction: f64) -> DVec2 {
+        self.center + DVec2::from_angle(self.start_angle + self.sweep * fraction) * self.radius
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    #[test]
+    fn semicircle_and_straight_segments() {
+        let arc = BulgeArc::new(DVec2::new(0.0, 0.0), DVec2::new(2.0, 0.0), 1.0).unwrap();
+        assert!(arc.center.distance(DVec2::new(1.0, 0.0)) < 1e-12);
+        assert!((arc.radius - 1.0).abs() < 1e-12);
+        assert!((arc.sweep - PI).abs() < 1e-12);
+        // 正凸度逆时针，弧中点在弦的右侧
+        assert!(arc.point(0.5).distance(DVec2::new(1.0, -1.0)) < 1e-12);
+        assert!(arc.point(1.0).distance(DVec2::new(2.0, 0.0)) < 1e-12);
+
+        let clockwise = BulgeArc::new(DVec2::new(0.0, 0.0), DVec2::new(2.0, 0.0), -1.0).unwrap();
+        assert!(clockwise.point(0.5).distance(DVec2::new(1.0, 1.0)) < 1e-12);
+
+        assert!(BulgeArc::new(DVec2::new(0.0, 0.0), DVec2::new(2.0, 0.0), 1e-12).is_none());
+        assert!(BulgeArc::new(DVec2::new(1.0, 1.0), DVec2::new(1.0, 1.0), 1.0).is_none());
+    }
+}
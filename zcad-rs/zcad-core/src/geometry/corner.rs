@@ -0,0 +1,469 @@
+//! 圆角与倒角（FILLET / CHAMFER）：以相切圆弧或倒角线连接两条直线或圆弧，并修剪或延伸原对象。
+//!
+//! 两对象所在直线或整圆的交点中，取离两对象端点最近的一个作为拐角；每个对象保留远离拐角的一侧，
+//! 靠近拐角的端点移到切点或倒角点。倒角距离在圆弧上按弧长计量。生成的圆弧或直线沿用第一个对象的图层。
+
+use std::f64::consts::{PI, TAU};
+use std::fmt;
+
+use glam::DVec2;
+
+use super::intersect::{circle_circle, line_circle, line_line};
+use super::{Point2, Tolerance};
+use crate::document::{Arc, Entity, Line};
+
+/// 坐标比较容差。
+const TOLERANCE: f64 = Tolerance::DEFAULT.linear;
+
+/// 圆角或倒角的结果。
+#[derive(Debug, Clone)]
+pub struct Corner {
+    /// 修剪或延伸后的第一个对象。
+    pub first: Entity,
+    pub second: Entity,
+    /// 圆角弧或倒角线；半径或两个倒角距离为零时只修剪到拐角。
+    pub joint: Option<Entity>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CornerError {
+    /// 只支持长度不为零的直线与圆弧。
+    Unsupported,
+    /// 两对象平行、相切或延伸后也不相交。
+    NoCorner,
+    /// 半径或倒角距离超出对象保留部分的长度。
+    TooLarge,
+}
+
+impl fmt::Display for CornerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CornerError::Unsupported => write!(f, "only lines and arcs can be filleted"),
+            CornerError::NoCorner => write!(f, "entities do not meet at a corner"),
+            CornerError::TooLarge => write!(f, "radius or distance is too large for the entities"),
+        }
+    }
+}
+
+impl std::error::Error for CornerError {}
+
+/// 以给定半径的相切圆弧连接两对象。
+pub fn fillet(first: &Entity, second: &Entity, radius: f64) -> Result<Corner, CornerError> {
+    let (a, b) = sides(first, second)?;
+    let radius = radius.abs();
+    if radius <= TOLERANCE {
+        return Ok(Corner {
+            first: a.trimmed(a.corner)?,
+            second: b.trimmed(b.corner)?,
+            joint: None,
+        });
+    }
+    // 圆心位于两对象保留部分所夹的一侧
+    let turn = a.tangent.perp_dot(b.tangent);
+    if turn.abs() <= TOLERANCE {
+        return Err(CornerError::NoCorner);
+    }
+    let first_offset = a.offset(a.tangent.perp() * turn.signum(), radius)?;
+    let second_offset = b.offset(b.tangent.perp() * -turn.signum(), radius)?;
+    let center = nearest(first_offset.intersections(&second_offset), a.corner)
+        .ok_or(CornerError::TooLarge)?;
+    let first_tangent = a.foot(center);
+    let second_tangent = b.foot(center);
+
+    let mut start = (first_tangent - center).to_angle().rem_euclid(TAU);
+    let mut end = (second_tangent - center).to_angle().rem_euclid(TAU);
+    if (end - start).rem_euclid(TAU) > PI {
+        std::mem::swap(&mut start, &mut end);
+    }
+    Ok(Corner {
+        first: a.trimmed(first_tangent)?,
+        second: b.trimmed(second_tangent)?,
+        joint: Some(Entity::Arc(Arc {
+            center: Point2::from_vec(center),
+            radius,
+            start_angle: start,
+            end_angle: end,
+            layer: first.layer_name().to_string(),
+        })),
+    })
+}
+
+/// 以倒角线连接两对象，两个距离分别自拐角沿各对象量取。
+pub fn chamfer(
+    first: &Entity,
+    second: &Entity,
+    first_distance: f64,
+    second_distance: f64,
+) -> Result<Corner, CornerError> {
+    let (a, b) = sides(first, second)?;
+    let start = a.point_at(first_distance.abs());
+    let end = b.point_at(second_distance.abs());
+    let joint = (start.distance(end) > TOLERANCE).then(|| {
+        Entity::Line(Line {
+            start: Point2::from_vec(start),
+            end: Point2::from_vec(end),
+            layer: first.layer_name().to_string(),
+        })
+    });
+    Ok(Corner {
+        first: a.trimmed(start)?,
+        second: b.trimmed(end)?,
+        joint,
+    })
+}
+
+/// 无限长直线或整圆。
+#[derive(Debug, Clone, Copy)]
+enum Form {
+    Line { point: DVec2, direction: DVec2 },
+    Circle { center: DVec2, radius: f64 },
+}
+
+impl Form {
+    fn intersections(&self, other: &Form) -> Vec<DVec2> {
+        match (*self, *other) {
+            (
+                Form::Line { point, direction },
+                Form::Line {
+                    point: q,
+                    direction: v,
+                },
+            ) => line_line(point, direction, q, v).into_iter().collect(),
+            (Form::Line { point, direction }, Form::Circle { center, radius })
+            | (Form::Circle { center, radius }, Form::Line { point, direction }) => {
+                line_circle(point, direction, center, radius)
+            }
+            (
+                Form::Circle { center, radius },
+                Form::Circle {
+                    center: c,
+                    radius: r,
+                },
+            ) => circle_circle(center, radius, c, r),
+        }
+    }
+}
+
+fn form_of(entity: &Entity) -> Option<Form> {
+    match entity {
+        Entity::Line(line) => {
+            let direction = line.end.as_vec2() - line.start.as_vec2();
+            (direction.length() > TOLERANCE).then_some(Form::Line {
+                point: line.start.as_vec2(),
+                direction,
+            })
+        }
+        Entity::Arc(arc) => (arc.radius > TOLERANCE).then_some(Form::Circle {
+            center: arc.center.as_vec2(),
+            radius: arc.radius,
+        }),
+        _ => None,
+    }
+}
+
+fn endpoints(entity: &Entity) -> [DVec2; 2] {
+    match entity {
+        Entity::Line(line) => [line.start.as_vec2(), line.end.as_vec2()],
+        Entity::Arc(arc) => [arc.start_angle, arc.end_angle]
+            .map(|angle| arc.center.as_vec2() + DVec2::from_angle(angle) * arc.radius),
+        _ => [DVec2::ZERO; 2],
+    }
+}
+
+fn nearest(points: Vec<DVec2>, target: DVec2) -> Option<DVec2> {
+    points.into_iter().min_by(|a, b| {
+        a.distance_squared(target)
+            .total_cmp(&b.distance_squared(target))
+    })
+}
+
+/// 确定拐角并返回两对象在拐角处的一侧。
+fn sides<'a>(first: &'a Entity, second: &'a Entity) -> Result<(Side<'a>, Side<'a>), CornerError> {
+    let a = form_of(first).ok_or(CornerError::Unsupported)?;
+    let b = form_of(second).ok_or(CornerError::Unsupported)?;
+    let distance_to_ends = |entity: &Entity, point: DVec2| {
+        endpoints(entity)
+            .into_iter()
+            .map(|end| end.distance(point))
+            .fold(f64::INFINITY, f64::min)
+    };
+    let corner = a
+        .intersections(&b)
+        .into_iter()
+        .min_by(|p, q| {
+            let score =
+                |point: DVec2| distance_to_ends(first, point) + distance_to_ends(second, point);
+            score(*p).total_cmp(&score(*q))
+        })
+        .ok_or(CornerError::NoCorner)?;
+    Ok((Side::new(first, a, corner), Side::new(second, b, corner)))
+}
+
+/// 对象在拐角处的一侧，保留远离拐角的部分。
+struct Side<'a> {
+    entity: &'a Entity,
+    form: Form,
+    corner: DVec2,
+    /// 拐角靠近对象起点（圆弧按角度计）。
+    at_start: bool,
+    /// 拐角处指向保留部分的单位切向。
+    tangent: DVec2,
+}
+
+impl<'a> Side<'a> {
+    fn new(entity: &'a Entity, form: Form, corner: DVec2) -> Self {
+        let (at_start, tangent) = match (entity, form) {
+            (Entity::Line(line), Form::Line { direction, .. }) => {
+                let at_start =
+                    line.start.as_vec2().distance(corner) <= line.end.as_vec2().distance(corner);
+                let unit = direction.normalize();
+                (at_start, if at_start { unit } else { -unit })
+            }
+            (Entity::Arc(arc), Form::Circle { center, .. }) => {
+                let angle = (corner - center).to_angle();
+                let gap = |a: f64, b: f64| {
+                    let difference = (a - b).rem_euclid(TAU);
+                    difference.min(TAU - difference)
+                };
+                let at_start = gap(angle, arc.start_angle) <= gap(angle, arc.end_angle);
+                let ccw = DVec2::from_angle(angle).perp();
+                (at_start, if at_start { ccw } else { -ccw })
+            }
+            _ => (true, DVec2::ZERO),
+        };
+        Self {
+            entity,
+            form,
+            corner,
+            at_start,
+            tangent,
+        }
+    }
+
+    /// 自拐角沿保留部分量取 `distance` 处的点。
+    fn point_at(&self, distance: f64) -> DVec2 {
+        match self.form {
+            Form::Line { .. } => self.corner + self.tangent * distance,
+            Form::Circle { center, radius } => {
+                let angle = (self.corner - center).to_angle();
+                let turn = if self.at_start { 1.0 } else { -1.0 };
+                center + DVec2::from_angle(angle + turn * distance / radius) * radius
+            }
+        }
+    }
+
+    /// 点在直线或整圆上的垂足。
+    fn foot(&self, point: DVec2) -> DVec2 {
+        match self.form {
+            Form::Line { .. } => {
+                self.corner + self.tangent * (point - self.corner).dot(self.tangent)
+            }
+            Form::Circle { center, radius } => {
+                center + (point - center).normalize_or_zero() * radius
+            }
+        }
+    }
+
+    /// 向 `normal` 一侧偏移 `distance` 后的直线或整圆。
+    fn offset(&self, normal: DVec2, distance: f64) -> Result<Form, CornerError> {
+        match self.form {
+            Form::Line { direction, .. } => Ok(Form::Line {
+                point: self.corner + normal * distance,
+                direction,
+            }),
+            Form::Circle { center, radius } => {
+                let outward = normal.dot(self.corner - center) > 0.0;
+                let radius = if outward {
+                    radius + distance
+                } else {
+                    radius - distance
+                };
+                if radius <= TOLERANCE {
+                    return Err(CornerError::TooLarge);
+                }
+                Ok(Form::Circle { center, radius })
+            }
+        }
+    }
+
+    /// 把靠近拐角的端点移到 `point`；保留部分反向或收缩为零时报错。
+    fn trimmed(&self, point: DVec2) -> Result<Entity, CornerError> {
+        let mut entity = self.entity.clone();
+        match &mut entity {
+            Entity::Line(line) => {
+                let far = if self.at_start { line.end } else { line.start };
+                if (far.as_vec2() - point).dot(self.tangent) <= TOLERANCE {
+                    return Err(CornerError::TooLarge);
+                }
+                if self.at_start {
+                    line.start = Point2::from_vec(point);
+                } else {
+                    line.end = Point2::from_vec(point);
+                }
+            }
+            Entity::Arc(arc) => {
+                let sweep = |from: f64, to: f64| (to - from).rem_euclid(TAU);
+                let corner = (self.corner - arc.center.as_vec2()).to_angle();
+                let angle = (point - arc.center.as_vec2()).to_angle().rem_euclid(TAU);
+                let (kept, span) = if self.at_start {
+                    (sweep(angle, arc.end_angle), sweep(corner, arc.end_angle))
+                } else {
+                    (
+                        sweep(arc.start_angle, angle),
+                        sweep(arc.start_angle, corner),
+                    )
+                };
+                // 越过远端时保留部分的圆心角接近整周
+                if kept <= TOLERANCE || kept > span + (TAU - span) / 2.0 {
+                    return Err(CornerError::TooLarge);
+                }
+                if self.at_start {
+                    arc.start_angle = angle;
+                } else {
+                    arc.end_angle = angle;
+                }
+            }
+            _ => return Err(CornerError::Unsupported),
+        }
+        Ok(entity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, SQRT_2};
+
+    use super::*;
+    use crate::document::Circle;
+
+    fn line(start: (f64, f64), end: (f64, f64)) -> Entity {
+        Entity::Line(Line {
+            start: Point2::new(start.0, start.1),
+            end: Point2::new(end.0, end.1),
+            layer: "0".to_string(),
+        })
+    }
+
+    fn ends(entity: &Entity) -> [(f64, f64); 2] {
+        let round = |value: f64| (value * 1e9).round() / 1e9 + 0.0;
+        endpoints(entity).map(|point| (round(point.x), round(point.y)))
+    }
+
+    #[test]
+    fn fillets_crossing_lines_and_trims_both() {
+        let horizontal = line((0.0, 0.0), (12.0, 0.0));
+        let vertical = line((10.0, -2.0), (10.0, 10.0));
+        let corner = fillet(&horizontal, &vertical, 2.0).unwrap();
+        assert_eq!(ends(&corner.first), [(0.0, 0.0), (8.0, 0.0)]);
+        assert_eq!(ends(&corner.second), [(10.0, 2.0), (10.0, 10.0)]);
+        let Some(Entity::Arc(arc)) = &corner.joint else {
+            panic!("expected fillet arc");
+        };
+        assert_eq!((arc.center, arc.radius), (Point2::new(8.0, 2.0), 2.0));
+        assert_eq!((arc.start_angle, arc.end_angle), (3.0 * FRAC_PI_2, 0.0));
+
+        let sharp = fillet(&horizontal, &vertical, 0.0).unwrap();
+        assert!(sharp.joint.is_none());
+        assert_eq!(ends(&sharp.first), [(0.0, 0.0), (10.0, 0.0)]);
+        assert_eq!(ends(&sharp.second), [(10.0, 0.0), (10.0, 10.0)]);
+
+        assert_eq!(
+            fillet(&horizontal, &vertical, 20.0).unwrap_err(),
+            CornerError::TooLarge
+        );
+        let parallel = line((0.0, 1.0), (5.0, 1.0));
+        assert_eq!(
+            fillet(&horizontal, &parallel, 1.0).unwrap_err(),
+            CornerError::NoCorner
+        );
+        let circle = Entity::Circle(Circle {
+            center: Point2::new(0.0, 0.0),
+            radius: 1.0,
+            layer: "0".to_string(),
+        });
+        assert_eq!(
+            fillet(&horizontal, &circle, 1.0).unwrap_err(),
+            CornerError::Unsupported
+        );
+    }
+
+    #[test]
+    fn chamfers_lines_and_extends_short_ones() {
+        let horizontal = line((0.0, 0.0), (8.0, 0.0));
+        let vertical = line((10.0, 1.0), (10.0, 10.0));
+        let corner = chamfer(&horizontal, &vertical, 2.0, 3.0).unwrap();
+        assert_eq!(ends(&corner.first), [(0.0, 0.0), (8.0, 0.0)]);
+        assert_eq!(ends(&corner.second), [(10.0, 3.0), (10.0, 10.0)]);
+        assert_eq!(
+            ends(corner.joint.as_ref().unwrap()),
+            [(8.0, 0.0), (10.0, 3.0)]
+        );
+        assert_eq!(
+            chamfer(&horizontal, &vertical, 11.0, 1.0).unwrap_err(),
+            CornerError::TooLarge
+        );
+    }
+
+    #[test]
+    fn fillets_and_chamfers_lines_with_arcs() {
+        let arc = Entity::Arc(Arc {
+            center: Point2::new(0.0, 0.0),
+            radius: 5.0,
+            start_angle: 0.0,
+            end_angle: FRAC_PI_2,
+            layer: "ARC".to_string(),
+        });
+        let edge = line((3.0, -2.0), (3.0, 6.0));
+        let corner = fillet(&edge, &arc, 0.5).unwrap();
+        let Some(Entity::Arc(round)) = &corner.joint else {
+            panic!("expected fillet arc");
+        };
+        // 圆心在直线右侧 0.5、大圆内侧 0.5 处
+        let center = round.center.as_vec2();
+        assert!((center.x - 3.5).abs() < 1e-9);
+        assert!((center.length() - 4.5).abs() < 1e-9);
+        assert_eq!(round.layer, "0");
+        let [_, tangent] = ends(&corner.first);
+        assert_eq!(tangent, (3.0, (center.y * 1e9).round() / 1e9));
+        let Entity::Arc(trimmed) = &corner.second else {
+            panic!("expected arc");
+        };
+        assert_eq!(trimmed.start_angle, 0.0);
+        assert!((trimmed.end_angle - center.y.atan2(center.x)).abs() < 1e-9);
+
+        // 两段圆弧交于上方一点：第一段保留到终点，第二段延伸终点到交点
+        let left = Entity::Arc(Arc {
+            center: Point2::new(0.0, 0.0),
+            radius: 1.0,
+            start_angle: 0.0,
+            end_angle: FRAC_PI_2,
+            layer: "0".to_string(),
+        });
+        let right = Entity::Arc(Arc {
+            center: Point2::new(2.0, 0.0),
+            radius: SQRT_2,
+            start_angle: FRAC_PI_2,
+            end_angle: 3.0 * FRAC_PI_4,
+            layer: "0".to_string(),
+        });
+        let height = 0.4375f64.sqrt();
+        let corner = chamfer(&left, &right, 0.0, 0.0).unwrap();
+        assert!(corner.joint.is_none());
+        let (Entity::Arc(first), Entity::Arc(second)) = (&corner.first, &corner.second) else {
+            panic!("expected arcs");
+        };
+        assert!((first.start_angle - height.atan2(0.75)).abs() < 1e-9);
+        assert_eq!(first.end_angle, FRAC_PI_2);
+        assert_eq!(second.start_angle, FRAC_PI_2);
+        assert!((second.end_angle - height.atan2(-1.25)).abs() < 1e-9);
+
+        let corner = fillet(&left, &right, 0.1).unwrap();
+        let Some(Entity::Arc(round)) = &corner.joint else {
+            panic!("expected fillet arc");
+        };
+        let center = round.center.as_vec2();
+        assert!((center.length() - 1.1).abs() < 1e-9);
+        assert!((center.distance(DVec2::new(2.0, 0.0)) - SQRT_2 - 0.1).abs() < 1e-9);
+        assert!(center.y > height);
+    }
+}
@@ -0,0 +1,254 @@
+//! 凸包与最小面积外接矩形，用于排样、标注定位与选择集的快速范围拟合。
+//!
+//! 凸包按 Andrew 单调链求取，逆时针排列且不含共线点；曲线实体先按离散选项取点，
+//! 其余实体取轴对齐范围的四角。最小外接矩形必有一边与凸包的某条边共线，逐边旋转比较面积。
+
+use glam::DVec2;
+
+use super::{Point2, Vector2};
+use crate::document::Entity;
+use crate::tessellate::{self, TessellateOptions};
+
+/// 任意方向的矩形。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrientedBox {
+    pub center: Point2,
+    /// 宽度方向的单位向量，高度方向为其逆时针旋转 90°。
+    pub axis: Vector2,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl OrientedBox {
+    pub fn area(&self) -> f64 {
+        self.width * self.height
+    }
+
+    /// 宽度方向与 X 轴的夹角（弧度）。
+    pub fn angle(&self) -> f64 {
+        self.axis.as_vec2().to_angle()
+    }
+
+    /// 逆时针排列的四个角点，从宽度方向与高度方向的最小角开始。
+    pub fn corners(&self) -> [Point2; 4] {
+        let u = self.axis.as_vec2() * self.width / 2.0;
+        let v = self.axis.as_vec2().perp() * self.height / 2.0;
+        let center = self.center.as_vec2();
+        [
+            center - u - v,
+            center + u - v,
+            center + u + v,
+            center - u + v,
+        ]
+        .map(Point2::from_vec)
+    }
+}
+
+/// 点集的凸包，逆时针排列；少于三个不共线的点时返回去重后的端点。
+pub fn convex_hull(points: &[Point2]) -> Vec<Point2> {
+    let mut sorted: Vec<DVec2> = points.iter().map(|point| point.as_vec2()).collect();
+    sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted.into_iter().map(Point2::from_vec).collect();
+    }
+
+    let mut hull: Vec<DVec2> = Vec::with_capacity(sorted.len() * 2);
+    // 下链自左向右，上链自右向左
+    for pass in [true, false] {
+        let start = hull.len();
+        let points: Box<dyn Iterator<Item = &DVec2>> = if pass {
+            Box::new(sorted.iter())
+        } else {
+            Box::new(sorted.iter().rev())
+        };
+        for &point in points {
+            while hull.len() >= start + 2
+                && (hull[hull.len() - 1] - hull[hull.len() - 2])
+                    .perp_dot(point - hull[hull.len() - 2])
+                    <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        // 链的终点是下一条链的起点
+        hull.pop();
+    }
+    hull.into_iter().map(Point2::from_vec).collect()
+}
+
+/// 一组实体的凸包：曲线按 `options` 离散，填充取各边界环，其他实体取范围四角。
+pub fn entities_convex_hull<'a>(
+    entities: impl IntoIterator<Item = &'a Entity>,
+    options: &TessellateOptions,
+) -> Vec<Point2> {
+    let mut points = Vec::new();
+    for entity in entities {
+        if let Some(curve) = tessellate::curve(entity, options) {
+            points.extend(curve);
+        } else if let Entity::Hatch(hatch) = entity
+            && !hatch.loops.is_empty()
+        {
+            for loop_path in &hatch.loops {
+                points.extend(tessellate::hatch_loop(loop_path, options));
+            }
+        } else if let Some(bounds) = entity.bounds() {
+            let (min, max) = (bounds.min(), bounds.max());
+            points.extend([
+                min,
+                Point2::new(max.x(), min.y()),
+                max,
+                Point2::new(min.x(), max.y()),
+            ]);
+        }
+    }
+    convex_hull(&points)
+}
+
+/// 点集的最小面积外接矩形；点集为空时返回 `None`，共线点集得到高度为零的矩形。
+pub fn minimum_area_obb(points: &[Point2]) -> Option<OrientedBox> {
+    let hull: Vec<DVec2> = convex_hull(points)
+        .into_iter()
+        .map(Point2::as_vec2)
+        .collect();
+    let first = *hull.first()?;
+    if hull.len() == 1 {
+        return Some(OrientedBox {
+            center: Point2::from_vec(first),
+            axis: Vector2::new(1.0, 0.0),
+            width: 0.0,
+            height: 0.0,
+        });
+    }
+
+    let mut best: Option<OrientedBox> = None;
+    for (index, start) in hull.iter().enumerate() {
+        let end = hull[(index + 1) % hull.len()];
+        let Some(axis) = (end - *start).try_normalize() else {
+            continue;
+        };
+        let normal = axis.perp();
+        let (mut low, mut high) = (DVec2::splat(f64::INFINITY), DVec2::splat(f64::NEG_INFINITY));
+        for point in &hull {
+            let local = DVec2::new(point.dot(axis), point.dot(normal));
+            low = low.min(local);
+            high = high.max(local);
+        }
+        let size = high - low;
+        let middle = (low + high) / 2.0;
+        let candidate = OrientedBox {
+            center: Point2::from_vec(axis * middle.x + normal * middle.y),
+            axis: Vector2(axis),
+            width: size.x,
+            height: size.y,
+        };
+        if best.is_none_or(|best| candidate.area() < best.area()) {
+            best = Some(candidate);
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{Circle, Line};
+
+    #[test]
+    fn hull_drops_interior_and_collinear_points() {
+        let points = [
+            Point2::new(0.0, 0.0),
+            Point2::new(1.0, 0.0),
+            Point2::new(2.0, 0.0),
+            Point2::new(2.0, 2.0),
+            Point2::new(1.0, 1.0),
+            Point2::new(0.0, 2.0),
+            Point2::new(2.0, 2.0),
+        ];
+        assert_eq!(
+            convex_hull(&points),
+            vec![
+                Point2::new(0.0, 0.0),
+                Point2::new(2.0, 0.0),
+                Point2::new(2.0, 2.0),
+                Point2::new(0.0, 2.0),
+            ]
+        );
+        assert_eq!(
+            convex_hull(&[Point2::new(1.0, 1.0), Point2::new(1.0, 1.0)]),
+            vec![Point2::new(1.0, 1.0)]
+        );
+        assert_eq!(
+            convex_hull(&[
+                Point2::new(0.0, 0.0),
+                Point2::new(1.0, 1.0),
+                Point2::new(2.0, 2.0)
+            ]),
+            vec![Point2::new(0.0, 0.0), Point2::new(2.0, 2.0)]
+        );
+    }
+
+    #[test]
+    fn hull_of_entities_samples_curves() {
+        let line = Entity::Line(Line {
+            start: Point2::new(-5.0, 0.0),
+            end: Point2::new(0.0, 0.0),
+            layer: "0".to_string(),
+        });
+        let circle = Entity::Circle(Circle {
+            center: Point2::new(3.0, 0.0),
+            radius: 1.0,
+            layer: "0".to_string(),
+        });
+        let hull = entities_convex_hull([&line, &circle], &TessellateOptions::per_turn(16));
+        assert_eq!(hull[0], Point2::new(-5.0, 0.0));
+        assert!(hull.iter().any(|point| *point == Point2::new(4.0, 0.0)));
+        assert!(
+            hull.iter()
+                .all(|point| point.x() >= -5.0 && point.x() <= 4.0 + 1e-12)
+        );
+    }
+
+    #[test]
+    fn minimum_box_aligns_with_rotated_rectangle() {
+        // 4×1 矩形旋转 30°，内部加一些点
+        let angle = 30_f64.to_radians();
+        let axis = DVec2::from_angle(angle);
+        let normal = axis.perp();
+        let origin = DVec2::new(10.0, -3.0);
+        let points: Vec<Point2> = [
+            (0.0, 0.0),
+            (4.0, 0.0),
+            (4.0, 1.0),
+            (0.0, 1.0),
+            (2.0, 0.5),
+            (1.0, 0.25),
+        ]
+        .into_iter()
+        .map(|(u, v)| Point2::from_vec(origin + axis * u + normal * v))
+        .collect();
+
+        let obb = minimum_area_obb(&points).unwrap();
+        assert!((obb.area() - 4.0).abs() < 1e-9);
+        let (long, short) = if obb.width > obb.height {
+            (obb.width, obb.height)
+        } else {
+            (obb.height, obb.width)
+        };
+        assert!((long - 4.0).abs() < 1e-9 && (short - 1.0).abs() < 1e-9);
+        let center = origin + axis * 2.0 + normal * 0.5;
+        assert!(obb.center.as_vec2().distance(center) < 1e-9);
+        for corner in obb.corners() {
+            assert!(
+                points
+                    .iter()
+                    .any(|point| point.as_vec2().distance(corner.as_vec2()) < 1e-9)
+            );
+        }
+
+        assert_eq!(minimum_area_obb(&[]), None);
+        let degenerate = minimum_area_obb(&[Point2::new(0.0, 0.0), Point2::new(3.0, 4.0)]).unwrap();
+        assert!((degenerate.width - 5.0).abs() < 1e-12 && degenerate.height.abs() < 1e-12);
+    }
+}
@@ -0,0 +1,858 @@
+//! 求交：计算直线、圆弧、圆、椭圆、多段线与样条曲线两两之间的交点，并给出交点在各对象上的参数；
+//! 另提供按参数取点与最近点查询。
+//!
+//! 参数约定与打断一致：直线为 0..1，圆弧为自起始角逆时针转过的角度，圆为角度，椭圆为椭圆参数，
+//! 多段线为段序号加段内比例（凸度段按圆心角比例），样条为节点参数。直线段与圆弧段精确求交；
+//! 椭圆与样条先离散为折线求近似交点，再按曲线方程用牛顿迭代修正。
+
+use std::f64::consts::TAU;
+
+use glam::DVec2;
+
+use super::{BulgeArc, Point2, Tolerance};
+use crate::document::{Entity, Spline, clamped_uniform_knots, de_boor};
+
+/// 判定相切与平行的容差。
+const TOLERANCE: f64 = Tolerance::DEFAULT.linear;
+/// 椭圆整周的离散段数。
+const ELLIPSE_SEGMENTS: usize = 128;
+/// 样条每个节点区间的离散段数。
+const SPLINE_SEGMENTS: usize = 16;
+/// 牛顿迭代的最大次数。
+const REFINE_ITERATIONS: usize = 16;
+
+/// 一个交点。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Intersection {
+    pub point: Point2,
+    /// 交点在第一个对象上的参数。
+    pub first: f64,
+    pub second: f64,
+}
+
+/// 求交选项。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IntersectOptions {
+    /// 交点落在对象端点外的容许距离，也用于合并重复交点。
+    pub tolerance: f64,
+    /// 第一个对象为直线时按无限长、为圆弧时按整圆求交，供延伸使用；其余对象不延伸。
+    pub extend_first: bool,
+    pub extend_second: bool,
+}
+
+impl Default for IntersectOptions {
+    fn default() -> Self {
+        Self {
+            tolerance: TOLERANCE,
+            extend_first: false,
+            extend_second: false,
+        }
+    }
+}
+
+/// 两对象的交点，按第一个对象上的参数升序排列；不支持的对象返回空列表。
+pub fn intersect(first: &Entity, second: &Entity, options: &IntersectOptions) -> Vec<Intersection> {
+    let (Some(a), Some(b)) = (
+        Curve::of(first, options.extend_first),
+        Curve::of(second, options.extend_second),
+    ) else {
+        return Vec::new();
+    };
+    let tolerance = options.tolerance.abs().max(TOLERANCE);
+    let (pieces_a, pieces_b) = (a.pieces(), b.pieces());
+    let refine = a.is_approximate() || b.is_approximate();
+
+    let mut found: Vec<Intersection> = Vec::new();
+    for piece_a in &pieces_a {
+        for piece_b in &pieces_b {
+            for point in piece_a.meet(piece_b) {
+                let (Some(s), Some(t)) = (
+                    piece_a.parameter(point, tolerance),
+                    piece_b.parameter(point, tolerance),
+                ) else {
+                    continue;
+                };
+                let (point, s, t) = if refine {
+                    refined(&a, &b, s, t).unwrap_or((point, s, t))
+                } else {
+                    (point, s, t)
+                };
+                if found
+                    .iter()
+                    .all(|known| known.point.as_vec2().distance(point) > tolerance)
+                {
+                    found.push(Intersection {
+                        point: Point2::from_vec(point),
+                        first: s,
+                        second: t,
+                    });
+                }
+            }
+        }
+    }
+    found.sort_by(|x, y| x.first.total_cmp(&y.first));
+    found
+}
+
+/// 对象上给定参数处的点；参数约定见模块说明，超出范围时直线与圆弧按延长线计算，其余对象取端点。
+pub fn point_at(entity: &Entity, parameter: f64) -> Option<Point2> {
+    Curve::of(entity, false).map(|curve| Point2::from_vec(curve.point(parameter)))
+}
+
+/// 对象上离 `point` 最近的点及其参数；椭圆与样条在最近的离散段附近三分搜索。
+pub fn closest_parameter(entity: &Entity, point: Point2) -> Option<(f64, Point2)> {
+    let curve = Curve::of(entity, false)?;
+    let target = point.as_vec2();
+    let (parameter, closest, width) = curve
+        .pieces()
+        .iter()
+        .map(|piece| piece.closest(target))
+        .min_by(|a, b| {
+            a.1.distance_squared(target)
+                .total_cmp(&b.1.distance_squared(target))
+        })?;
+    if !curve.is_approximate() {
+        return Some((parameter, Point2::from_vec(closest)));
+    }
+    let (start, end) = curve.domain()?;
+    let distance = |value: f64| curve.point(value).distance_squared(target);
+    let (mut low, mut high) = ((parameter - width).max(start), (parameter + width).min(end));
+    for _ in 0..REFINE_ITERATIONS * 8 {
+        let third = (high - low) / 3.0;
+        if third <= f64::EPSILON * high.abs().max(1.0) {
+            break;
+        }
+        if distance(low + third) <= distance(high - third) {
+            high -= third;
+        } else {
+            low += third;
+        }
+    }
+    // 三分法在极小值附近受平方距离平坦所限，再以牛顿法求 (C - P)·C' = 0 收尾
+    let mut parameter = (low + high) / 2.0;
+    for _ in 0..REFINE_ITERATIONS {
+        let step = 1e-5 * parameter.abs().max(1.0);
+        let (before, here, after) = (
+            curve.point(parameter - step),
+            curve.point(parameter),
+            curve.point(parameter + step),
+        );
+        let first = (after - before) / (2.0 * step);
+        let second = (after - here * 2.0 + before) / (step * step);
+        let slope = (here - target).dot(first);
+        let curvature = first.length_squared() + (here - target).dot(second);
+        if curvature <= f64::EPSILON {
+            break;
+        }
+        let next = (parameter - slope / curvature).clamp(start, end);
+        if distance(next) > distance(parameter) || next == parameter {
+            break;
+        }
+        parameter = next;
+    }
+    Some((parameter, Point2::from_vec(curve.point(parameter))))
+}
+
+/// 过 `p` 方向 `u` 的直线与过 `q` 方向 `v` 的直线的交点，平行时返回 `None`。
+pub(super) fn line_line(p: DVec2, u: DVec2, q: DVec2, v: DVec2) -> Option<DVec2> {
+    let cross = u.perp_dot(v);
+    if cross.abs() <= TOLERANCE * u.length() * v.length() {
+        return None;
+    }
+    let t = (q - p).perp_dot(v) / cross;
+    Some(p + u * t)
+}
+
+/// 直线与圆的交点，相切时两点重合。
+pub(super) fn line_circle(p: DVec2, u: DVec2, center: DVec2, radius: f64) -> Vec<DVec2> {
+    let Some(unit) = u.try_normalize() else {
+        return Vec::new();
+    };
+    let w = p - center;
+    let b = unit.dot(w);
+    let discriminant = b * b - (w.length_squared() - radius * radius);
+    if discriminant < -TOLERANCE {
+        return Vec::new();
+    }
+    let root = discriminant.max(0.0).sqrt();
+    vec![p + unit * (-b + root), p + unit * (-b - root)]
+}
+
+/// 两圆的交点，同心时返回空。
+pub(super) fn circle_circle(c1: DVec2, r1: f64, c2: DVec2, r2: f64) -> Vec<DVec2> {
+    let offset = c2 - c1;
+    let d = offset.length();
+    if d <= TOLERANCE {
+        return Vec::new();
+    }
+    let along = (r1 * r1 - r2 * r2 + d * d) / (2.0 * d);
+    let h2 = r1 * r1 - along * along;
+    if h2 < -TOLERANCE {
+        return Vec::new();
+    }
+    let base = c1 + offset / d * along;
+    let h = offset.perp() / d * h2.max(0.0).sqrt();
+    vec![base + h, base - h]
+}
+
+/// 直线段或圆弧段，`range` 为其在所属对象上的参数区间。
+#[derive(Debug, Clone, Copy)]
+enum Piece {
+    Segment {
+        start: DVec2,
+        end: DVec2,
+        range: (f64, f64),
+        /// 按无限长直线求交。
+        unbounded: bool,
+    },
+    Arc {
+        center: DVec2,
+        radius: f64,
+        start_angle: f64,
+        /// 带符号的圆心角，正值逆时针。
+        sweep: f64,
+        range: (f64, f64),
+        /// 按整圆求交。
+        unbounded: bool,
+    },
+}
+
+impl Piece {
+    fn meet(&self, other: &Piece) -> Vec<DVec2> {
+        match (*self, *other) {
+            (
+                Piece::Segment { start, end, .. },
+                Piece::Segment {
+                    start: q, end: v, ..
+                },
+            ) => line_line(start, end - start, q, v - q)
+                .into_iter()
+                .collect(),
+            (Piece::Segment { start, end, .. }, Piece::Arc { center, radius, .. })
+            | (Piece::Arc { center, radius, .. }, Piece::Segment { start, end, .. }) => {
+                line_circle(start, end - start, center, radius)
+            }
+            (
+                Piece::Arc { center, radius, .. },
+                Piece::Arc {
+                    center: c,
+                    radius: r,
+                    ..
+                },
+            ) => circle_circle(center, radius, c, r),
+        }
+    }
+
+    /// 段上离 `target` 最近的点，返回对象参数、该点与本段的参数宽度。
+    fn closest(&self, target: DVec2) -> (f64, DVec2, f64) {
+        match *self {
+            Piece::Segment {
+                start, end, range, ..
+            } => {
+                let direction = end - start;
+                let length = direction.length_squared();
+                let fraction = if length <= TOLERANCE * TOLERANCE {
+                    0.0
+                } else {
+                    ((target - start).dot(direction) / length).clamp(0.0, 1.0)
+                };
+                (
+                    range.0 + (range.1 - range.0) * fraction,
+                    start.lerp(end, fraction),
+                    range.1 - range.0,
+                )
+            }
+            Piece::Arc {
+                center,
+                radius,
+                start_angle,
+                sweep,
+                range,
+                unbounded,
+            } => {
+                let span = sweep.abs();
+                let turned =
+                    (((target - center).to_angle() - start_angle) * sweep.signum()).rem_euclid(TAU);
+                let at = |turned: f64| {
+                    center + DVec2::from_angle(start_angle + turned * sweep.signum()) * radius
+                };
+                let turned = if unbounded || turned <= span {
+                    turned
+                } else if at(span).distance_squared(target) < at(0.0).distance_squared(target) {
+                    span
+                } else {
+                    0.0
+                };
+                (
+                    range.0 + (range.1 - range.0) * turned / span,
+                    at(turned),
+                    range.1 - range.0,
+                )
+            }
+        }
+    }
+
+    /// 点在本段上时返回对象参数。
+    fn parameter(&self, point: DVec2, tolerance: f64) -> Option<f64> {
+        let (fraction, range) = match *self {
+            Piece::Segment {
+                start,
+                end,
+                range,
+                unbounded,
+            } => {
+                let direction = end - start;
+                let length = direction.length();
+                if length <= TOLERANCE {
+                    return None;
+                }
+                let fraction = (point - start).dot(direction) / (length * length);
+                let slack = tolerance / length;
+                if unbounded {
+                    (fraction, range)
+                } else if (-slack..=1.0 + slack).contains(&fraction) {
+                    (fraction.clamp(0.0, 1.0), range)
+                } else {
+                    return None;
+                }
+            }
+            Piece::Arc {
+                center,
+                radius,
+                start_angle,
+                sweep,
+                range,
+                unbounded,
+            } => {
+                let turned =
+                    (((point - center).to_angle() - start_angle) * sweep.signum()).rem_euclid(TAU);
+                let slack = tolerance / radius;
+                let span = sweep.abs();
+                let turned = if unbounded || turned <= span {
+                    turned
+                } else if turned <= span + slack {
+                    span
+                } else if turned >= TAU - slack {
+                    0.0
+                } else {
+                    return None;
+                };
+                (turned / span, range)
+            }
+        };
+        Some(range.0 + (range.1 - range.0) * fraction)
+    }
+}
+
+/// 可求交的对象。
+enum Curve<'a> {
+    Line(DVec2, DVec2, bool),
+    Arc {
+        center: DVec2,
+        radius: f64,
+        start_angle: f64,
+        sweep: f64,
+        unbounded: bool,
+    },
+    Ellipse {
+        center: DVec2,
+        major: DVec2,
+        minor: DVec2,
+        start: f64,
+        sweep: f64,
+    },
+    Polyline(Vec<(DVec2, DVec2, f64)>),
+    Spline(SplineCurve<'a>),
+}
+
+impl<'a> Curve<'a> {
+    fn of(entity: &'a Entity, extend: bool) -> Option<Self> {
+        match entity {
+            Entity::Line(line) => Some(Curve::Line(
+                line.start.as_vec2(),
+                line.end.as_vec2(),
+                extend,
+            )),
+            Entity::Arc(arc) if arc.radius > TOLERANCE => {
+                let sweep = (arc.end_angle - arc.start_angle).rem_euclid(TAU);
+                Some(Curve::Arc {
+                    center: arc.center.as_vec2(),
+                    radius: arc.radius,
+                    start_angle: arc.start_angle,
+                    sweep: if sweep <= TOLERANCE { TAU } else { sweep },
+                    unbounded: extend,
+                })
+            }
+            Entity::Circle(circle) if circle.radius > TOLERANCE => Some(Curve::Arc {
+                center: circle.center.as_vec2(),
+                radius: circle.radius,
+                start_angle: 0.0,
+                sweep: TAU,
+                unbounded: true,
+            }),
+            Entity::Ellipse(ellipse) => {
+                let major = ellipse.major_axis.as_vec2();
+                let sweep = (ellipse.end_parameter - ellipse.start_parameter).rem_euclid(TAU);
+                Some(Curve::Ellipse {
+                    center: ellipse.center.as_vec2(),
+                    major,
+                    minor: major.perp() * ellipse.ratio,
+                    start: ellipse.start_parameter,
+                    sweep: if sweep <= TOLERANCE { TAU } else { sweep },
+                })
+            }
+            Entity::Polyline(polyline) if polyline.vertices.len() >= 2 => {
+                let count = polyline.vertices.len();
+                let segments = if polyline.is_closed { count } else { count - 1 };
+                Some(Curve::Polyline(
+                    (0..segments)
+                        .map(|index| {
+                            let vertex = &polyline.vertices[index];
+                            let next = &polyline.vertices[(index + 1) % count];
+                            (
+                                vertex.position.as_vec2(),
+                                next.position.as_vec2(),
+                                vertex.bulge,
+                            )
+                        })
+                        .collect(),
+                ))
+            }
+            Entity::Spline(spline) => SplineCurve::of(spline).map(Curve::Spline),
+            _ => None,
+        }
+    }
+
+    /// 椭圆与样条以折线近似，交点需要修正。
+    fn is_approximate(&self) -> bool {
+        matches!(self, Curve::Ellipse { .. } | Curve::Spline(_))
+    }
+
+    fn domain(&self) -> Option<(f64, f64)> {
+        match self {
+            Curve::Line(_, _, true)
+            | Curve::Arc {
+                unbounded: true, ..
+            } => None,
+            Curve::Line(..) => Some((0.0, 1.0)),
+            Curve::Arc { sweep, .. } => Some((0.0, *sweep)),
+            Curve::Ellipse { start, sweep, .. } => Some((*start, start + sweep)),
+            Curve::Polyline(segments) => Some((0.0, segments.len() as f64)),
+            Curve::Spline(spline) => Some(spline.domain()),
+        }
+    }
+
+    fn pieces(&self) -> Vec<Piece> {
+        match self {
+            Curve::Line(start, end, unbounded) => vec![Piece::Segment {
+                start: *start,
+                end: *end,
+                range: (0.0, 1.0),
+                unbounded: *unbounded,
+            }],
+            Curve::Arc {
+                center,
+                radius,
+                start_angle,
+                sweep,
+                unbounded,
+            } => vec![Piece::Arc {
+                center: *center,
+                radius: *radius,
+                start_angle: *start_angle,
+                sweep: *sweep,
+                range: (0.0, *sweep),
+                unbounded: *unbounded,
+            }],
+            Curve::Polyline(segments) => segments
+                .iter()
+                .enumerate()
+                .map(|(index, &(start, end, bulge))| {
+                    let range = (index as f64, index as f64 + 1.0);
+                    match BulgeArc::new(start, end, bulge) {
+                        Some(arc) => Piece::Arc {
+                            center: arc.center,
+                            radius: arc.radius,
+                            start_angle: arc.start_angle,
+                            sweep: arc.sweep,
+                            range,
+                            unbounded: false,
+                        },
+                        None => Piece::Segment {
+                            start,
+                            end,
+                            range,
+                            unbounded: false,
+                        },
+                    }
+                })
+                .collect(),
+            Curve::Ellipse { sweep, .. } => {
+                let count = ((sweep / TAU * ELLIPSE_SEGMENTS as f64).ceil() as usize).max(2);
+                self.sampled(count)
+            }
+            Curve::Spline(spline) => self.sampled(SPLINE_SEGMENTS * spline.spans()),
+        }
+    }
+
+    /// 在参数域内等分为 `count` 段折线。
+    fn sampled(&self, count: usize) -> Vec<Piece> {
+        let Some((start, end)) = self.domain() else {
+            return Vec::new();
+        };
+        let at = |index: usize| start + (end - start) * index as f64 / count as f64;
+        (0..count)
+            .map(|index| Piece::Segment {
+                start: self.point(at(index)),
+                end: self.point(at(index + 1)),
+                range: (at(index), at(index + 1)),
+                unbounded: false,
+            })
+            .collect()
+    }
+
+    fn point(&self, parameter: f64) -> DVec2 {
+        match self {
+            Curve::Line(start, end, _) => start.lerp(*end, parameter),
+            Curve::Arc {
+                center,
+                radius,
+                start_angle,
+                ..
+            } => *center + DVec2::from_angle(start_angle + parameter) * *radius,
+            Curve::Ellipse {
+                center,
+                major,
+                minor,
+                ..
+            } => *center + *major * parameter.cos() + *minor * parameter.sin(),
+            Curve::Polyline(segments) => {
+                let parameter = parameter.clamp(0.0, segments.len() as f64);
+                let index = (parameter.ceil() as usize).saturating_sub(1);
+                let (start, end, bulge) = segments[index];
+                let fraction = parameter - index as f64;
+                match BulgeArc::new(start, end, bulge) {
+                    Some(arc) => arc.point(fraction),
+                    None => start.lerp(end, fraction),
+                }
+            }
+            Curve::Spline(spline) => spline.point(parameter),
+        }
+    }
+}
+
+/// 以控制点定义的样条，只有拟合点时按拟合点折线处理。
+struct SplineCurve<'a> {
+    spline: &'a Spline,
+    degree: usize,
+    knots: Vec<f64>,
+    weights: Vec<f64>,
+}
+
+impl<'a> SplineCurve<'a> {
+    fn of(spline: &'a Spline) -> Option<Self> {
+        let degree = spline.degree.max(1) as usize;
+        let count = spline.control_points.len();
+        if count <= degree {
+            return (spline.fit_points.len() >= 2).then_some(Self {
+                spline,
+                degree: 1,
+                knots: Vec::new(),
+                weights: Vec::new(),
+            });
+        }
+        let knots = if spline.knot_values.len() == count + degree + 1 {
+            spline.knot_values.clone()
+        } else {
+            clamped_uniform_knots(count, degree)
+        };
+        let weights = if spline.is_rational && spline.weights.len() == count {
+            spline.weights.clone()
+        } else {
+            vec![1.0; count]
+        };
+        Some(Self {
+            spline,
+            degree,
+            knots,
+            weights,
+        })
+    }
+
+    fn is_fitted(&self) -> bool {
+        self.knots.is_empty()
+    }
+
+    fn spans(&self) -> usize {
+        if self.is_fitted() {
+            self.spline.fit_points.len() - 1
+        } else {
+            self.spline.control_points.len() - self.degree
+        }
+    }
+
+    fn domain(&self) -> (f64, f64) {
+        if self.is_fitted() {
+            (0.0, self.spans() as f64)
+        } else {
+            (
+                self.knots[self.degree],
+                self.knots[self.spline.control_points.len()],
+            )
+        }
+    }
+
+    fn point(&self, parameter: f64) -> DVec2 {
+        let (start, end) = self.domain();
+        let parameter = parameter.clamp(start, end);
+        if self.is_fitted() {
+            let points = &self.spline.fit_points;
+            let index = (parameter.ceil() as usize).saturating_sub(1);
+            return points[index]
+                .as_vec2()
+                .lerp(points[index + 1].as_vec2(), parameter - index as f64);
+        }
+        de_boor(
+            &self.spline.control_points,
+            &self.weights,
+            &self.knots,
+            self.degree,
+            parameter,
+        )
+        .as_vec2()
+    }
+}
+
+/// 以牛顿迭代把近似交点修正到两条曲线上；不收敛时返回 `None`。
+fn refined(a: &Curve, b: &Curve, s: f64, t: f64) -> Option<(DVec2, f64, f64)> {
+    let clamp = |curve: &Curve, value: f64| match curve.domain() {
+        Some((start, end)) => value.clamp(start, end),
+        None => value,
+    };
+    let derivative = |curve: &Curve, value: f64| {
+        let step = 1e-7 * value.abs().max(1.0);
+        (curve.point(value + step) - curve.point(value - step)) / (2.0 * step)
+    };
+    let (mut s, mut t) = (s, t);
+    for _ in 0..REFINE_ITERATIONS {
+        let gap = a.point(s) - b.point(t);
+        if gap.length() <= 1e-14 {
+            break;
+        }
+        let (da, db) = (derivative(a, s), derivative(b, t));
+        let determinant = da.perp_dot(-db);
+        if determinant.abs() <= f64::EPSILON {
+            return None;
+        }
+        // 解 da·Δs - db·Δt = -gap
+        let ds = (-gap).perp_dot(-db) / determinant;
+        let dt = da.perp_dot(-gap) / determinant;
+        s = clamp(a, s + ds);
+        t = clamp(b, t + dt);
+    }
+    let (point_a, point_b) = (a.point(s), b.point(t));
+    (point_a.distance(point_b) <= 1e-6 * point_a.length().max(1.0)).then_some((
+        (point_a + point_b) * 0.5,
+        s,
+        t,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_3, PI};
+
+    use super::*;
+    use crate::document::{Arc, Circle, Ellipse, Line, Polyline, PolylineVertex};
+    use crate::geometry::Vector2;
+
+    fn line(start: (f64, f64), end: (f64, f64)) -> Entity {
+        Entity::Line(Line {
+            start: Point2::new(start.0, start.1),
+            end: Point2::new(end.0, end.1),
+            layer: "0".to_string(),
+        })
+    }
+
+    fn summary(found: &[Intersection]) -> Vec<(f64, f64, f64, f64)> {
+        let round = |value: f64| (value * 1e6).round() / 1e6 + 0.0;
+        found
+            .iter()
+            .map(|hit| {
+                (
+                    round(hit.point.x()),
+                    round(hit.point.y()),
+                    round(hit.first),
+                    round(hit.second),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn intersects_lines_arcs_and_circles() {
+        let options = IntersectOptions::default();
+        let horizontal = line((0.0, 0.0), (4.0, 0.0));
+        let vertical = line((1.0, -1.0), (1.0, 3.0));
+        assert_eq!(
+            summary(&intersect(&horizontal, &vertical, &options)),
+            vec![(1.0, 0.0, 0.25, 0.25)]
+        );
+        assert!(intersect(&horizontal, &line((5.0, -1.0), (5.0, 1.0)), &options).is_empty());
+
+        // 上半圆弧只与直线交于一点，整圆交于两点
+        let arc = Entity::Arc(Arc {
+            center: Point2::new(2.0, 0.0),
+            radius: 1.0,
+            start_angle: 0.0,
+            end_angle: PI,
+            layer: "0".to_string(),
+        });
+        let chord = line((0.0, 0.5), (4.0, 0.5));
+        let hits = intersect(&chord, &arc, &options);
+        assert_eq!(hits.len(), 2);
+        assert!((hits[0].second - 5.0 * PI / 6.0).abs() < 1e-9);
+        assert!((hits[1].second - PI / 6.0).abs() < 1e-9);
+        let circle = Entity::Circle(Circle {
+            center: Point2::new(3.0, 0.0),
+            radius: 1.0,
+            layer: "0".to_string(),
+        });
+        assert_eq!(
+            summary(&intersect(&arc, &circle, &options)),
+            vec![(
+                2.5,
+                round3(0.75f64.sqrt()),
+                round3(FRAC_PI_3),
+                round3(2.0 * FRAC_PI_3)
+            )]
+        );
+        assert_eq!(intersect(&horizontal, &circle, &options).len(), 2);
+    }
+
+    fn round3(value: f64) -> f64 {
+        (value * 1e6).round() / 1e6
+    }
+
+    #[test]
+    fn extends_lines_and_arcs_on_request() {
+        let short = line((0.0, 0.0), (1.0, 0.0));
+        let circle = Entity::Circle(Circle {
+            center: Point2::new(5.0, 0.0),
+            radius: 1.0,
+            layer: "0".to_string(),
+        });
+        assert!(intersect(&short, &circle, &IntersectOptions::default()).is_empty());
+        let options = IntersectOptions {
+            extend_first: true,
+            ..IntersectOptions::default()
+        };
+        assert_eq!(
+            summary(&intersect(&short, &circle, &options)),
+            vec![(4.0, 0.0, 4.0, round3(PI)), (6.0, 0.0, 6.0, 0.0)]
+        );
+
+        let quarter = Entity::Arc(Arc {
+            center: Point2::new(0.0, 0.0),
+            radius: 1.0,
+            start_angle: 0.0,
+            end_angle: FRAC_PI_2,
+            layer: "0".to_string(),
+        });
+        let below = line((-2.0, -0.5), (2.0, -0.5));
+        assert!(intersect(&quarter, &below, &IntersectOptions::default()).is_empty());
+        let hits = intersect(&quarter, &below, &options);
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|hit| hit.first > FRAC_PI_2));
+    }
+
+    #[test]
+    fn intersects_polylines_with_bulges() {
+        // 直线段后接逆时针半圆，竖线两次穿过半圆
+        let polyline = Entity::Polyline(Polyline {
+            vertices: vec![
+                PolylineVertex::new(Point2::new(0.0, 0.0)),
+                PolylineVertex::with_bulge(Point2::new(2.0, 0.0), 1.0),
+                PolylineVertex::new(Point2::new(2.0, 2.0)),
+            ],
+            is_closed: false,
+            layer: "0".to_string(),
+        });
+        let probe = line((2.5, -1.0), (2.5, 3.0));
+        let hits = intersect(&polyline, &probe, &IntersectOptions::default());
+        let y = 0.75f64.sqrt();
+        assert_eq!(
+            summary(&hits),
+            vec![
+                (
+                    2.5,
+                    round3(1.0 - y),
+                    round3(1.0 + 1.0 / 6.0),
+                    round3((2.0 - y) / 4.0)
+                ),
+                (
+                    2.5,
+                    round3(1.0 + y),
+                    round3(1.0 + 5.0 / 6.0),
+                    round3((2.0 + y) / 4.0)
+                ),
+            ]
+        );
+        // 经过顶点的交点只记一次
+        let through_vertex = line((2.0, -1.0), (2.0, 0.5));
+        assert_eq!(
+            intersect(&polyline, &through_vertex, &IntersectOptions::default()).len(),
+            1
+        );
+        assert_eq!(
+            point_at(&polyline, 1.5).map(|p| (round3(p.x()), round3(p.y()))),
+            Some((3.0, 1.0))
+        );
+    }
+
+    #[test]
+    fn refines_ellipse_and_spline_intersections() {
+        let ellipse = Entity::Ellipse(Ellipse {
+            center: Point2::new(0.0, 0.0),
+            major_axis: Vector2::new(2.0, 0.0),
+            ratio: 0.5,
+            start_parameter: 0.0,
+            end_parameter: TAU,
+            layer: "0".to_string(),
+        });
+        let probe = line((1.0, -5.0), (1.0, 5.0));
+        let hits = intersect(&ellipse, &probe, &IntersectOptions::default());
+        assert_eq!(hits.len(), 2);
+        assert!((hits[0].first - FRAC_PI_3).abs() < 1e-9);
+        assert!((hits[0].point.y() - 0.75f64.sqrt()).abs() < 1e-9);
+        assert!((hits[1].first - 5.0 * FRAC_PI_3).abs() < 1e-9);
+
+        let spline = Entity::Spline(Spline {
+            degree: 3,
+            is_rational: false,
+            is_closed: false,
+            is_periodic: false,
+            control_points: vec![
+                Point2::new(0.0, 0.0),
+                Point2::new(1.0, 2.0),
+                Point2::new(3.0, 2.0),
+                Point2::new(4.0, 0.0),
+            ],
+            fit_points: Vec::new(),
+            knot_values: Vec::new(),
+            weights: Vec::new(),
+            start_tangent: None,
+            end_tangent: None,
+            layer: "0".to_string(),
+        });
+        let hits = intersect(
+            &line((2.0, -1.0), (2.0, 3.0)),
+            &spline,
+            &IntersectOptions::default(),
+        );
+        assert_eq!(hits.len(), 1);
+        assert!((hits[0].second - 0.5).abs() < 1e-9);
+        assert!((hits[0].point.y() - 1.5).abs() < 1e-9);
+        assert!((hits[0].first - 0.625).abs() < 1e-9);
+    }
+}
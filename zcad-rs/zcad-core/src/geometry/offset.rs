@@ -0,0 +1,488 @@
+//! 偏移（OFFSET）：按带符号距离生成直线、圆弧、圆、椭圆与多段线的平行曲线。
+//!
+//! 正距离偏移到曲线前进方向的右侧，对逆时针的圆、圆弧与椭圆即向外；负距离偏移到左侧。多段线各段分别偏移后，
+//! 相邻两段延伸或修剪到离原顶点最近的交点，没有交点时以绕原顶点的圆弧连接；偏移后反向或收缩为零的段被移除。
+//! 椭圆的偏移线不再是椭圆，结果为按参数采样的多段线。
+
+use std::f64::consts::{PI, TAU};
+use std::fmt;
+
+use glam::DVec2;
+
+use super::intersect::{circle_circle, line_circle, line_line};
+use super::{BulgeArc, Point2, Tolerance};
+use crate::document::{Arc, Circle, Entity, Line, Polyline, PolylineVertex};
+
+/// 坐标比较容差。
+const TOLERANCE: f64 = Tolerance::DEFAULT.linear;
+/// 椭圆偏移整周的采样段数。
+const ELLIPSE_SEGMENTS: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OffsetError {
+    /// 只支持直线、圆弧、圆、椭圆与多段线。
+    Unsupported,
+    /// 原曲线长度为零。
+    Degenerate,
+    /// 向内偏移超过曲率半径，结果不存在。
+    Collapsed,
+}
+
+impl fmt::Display for OffsetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OffsetError::Unsupported => write!(f, "entity type cannot be offset"),
+            OffsetError::Degenerate => write!(f, "curve has no length"),
+            OffsetError::Collapsed => write!(f, "offset distance collapses the curve"),
+        }
+    }
+}
+
+impl std::error::Error for OffsetError {}
+
+/// 生成偏移曲线，结果沿用原对象的图层。
+pub fn offset(entity: &Entity, distance: f64) -> Result<Entity, OffsetError> {
+    match entity {
+        Entity::Line(line) => {
+            let normal = right_normal(line.end.as_vec2() - line.start.as_vec2())
+                .ok_or(OffsetError::Degenerate)?;
+            Ok(Entity::Line(Line {
+                start: Point2::from_vec(line.start.as_vec2() + normal * distance),
+                end: Point2::from_vec(line.end.as_vec2() + normal * distance),
+                layer: line.layer.clone(),
+            }))
+        }
+        Entity::Circle(circle) => Ok(Entity::Circle(Circle {
+            radius: offset_radius(circle.radius, distance)?,
+            ..circle.clone()
+        })),
+        Entity::Arc(arc) => Ok(Entity::Arc(Arc {
+            radius: offset_radius(arc.radius, distance)?,
+            ..arc.clone()
+        })),
+        Entity::Ellipse(ellipse) => {
+            let major = ellipse.major_axis.as_vec2();
+            let minor = major.perp() * ellipse.ratio;
+            if major.length() <= TOLERANCE || minor.length() <= TOLERANCE {
+                return Err(OffsetError::Degenerate);
+            }
+            if -distance >= minor.length().min(major.length()) {
+                return Err(OffsetError::Collapsed);
+            }
+            let start = ellipse.start_parameter;
+            let mut sweep = (ellipse.end_parameter - start).rem_euclid(TAU);
+            if sweep <= TOLERANCE {
+                sweep = TAU;
+            }
+            let is_closed = (sweep - TAU).abs() <= TOLERANCE;
+            let segments = ((sweep / TAU * ELLIPSE_SEGMENTS as f64).ceil() as usize).max(2);
+            let count = if is_closed { segments } else { segments + 1 };
+            let vertices = (0..count)
+                .map(|index| {
+                    let t = start + sweep * index as f64 / segments as f64;
+                    let point = ellipse.center.as_vec2() + major * t.cos() + minor * t.sin();
+                    let normal =
+                        right_normal(minor * t.cos() - major * t.sin()).unwrap_or_default();
+                    PolylineVertex::new(Point2::from_vec(point + normal * distance))
+                })
+                .collect();
+            Ok(Entity::Polyline(Polyline {
+                vertices,
+                is_closed,
+                layer: ellipse.layer.clone(),
+            }))
+        }
+        Entity::Polyline(polyline) => Ok(Entity::Polyline(Polyline {
+            vertices: offset_vertices(&polyline.vertices, polyline.is_closed, distance)?,
+            is_closed: polyline.is_closed,
+            layer: polyline.layer.clone(),
+        })),
+        _ => Err(OffsetError::Unsupported),
+    }
+}
+
+fn offset_radius(radius: f64, distance: f64) -> Result<f64, OffsetError> {
+    if radius <= TOLERANCE {
+        return Err(OffsetError::Degenerate);
+    }
+    let radius = radius + distance;
+    if radius > TOLERANCE {
+        Ok(radius)
+    } else {
+        Err(OffsetError::Collapsed)
+    }
+}
+
+/// 前进方向的右侧单位法向。
+fn right_normal(direction: DVec2) -> Option<DVec2> {
+    direction.try_normalize().map(|unit| -unit.perp())
+}
+
+/// 多段线的一段，记录偏移后的端点与偏移前的端点。
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    start: DVec2,
+    end: DVec2,
+    /// 圆弧段的圆心与半径；直线段为 `None`。
+    circle: Option<(DVec2, f64)>,
+    /// 圆弧段带符号的圆心角，正值逆时针。
+    sweep: f64,
+    origin_start: DVec2,
+    origin_end: DVec2,
+}
+
+impl Segment {
+    /// 偏移一段，圆弧向内收缩为零时返回 `None`。
+    fn offset(start: DVec2, end: DVec2, bulge: f64, distance: f64) -> Option<Self> {
+        let chord = end - start;
+        let length = chord.length();
+        if length <= TOLERANCE {
+            return None;
+        }
+        let Some(BulgeArc {
+            center,
+            radius,
+            sweep,
+            ..
+        }) = BulgeArc::new(start, end, bulge)
+        else {
+            let normal = right_normal(chord)? * distance;
+            return Some(Self {
+                start: start + normal,
+                end: end + normal,
+                circle: None,
+                sweep: 0.0,
+                origin_start: start,
+                origin_end: end,
+            });
+        };
+        // 逆时针圆弧的右侧为外侧
+        let offset_radius = radius + distance * sweep.signum();
+        if offset_radius <= TOLERANCE {
+            return None;
+        }
+        let scale = offset_radius / radius;
+        Some(Self {
+            start: center + (start - center) * scale,
+            end: center + (end - center) * scale,
+            circle: Some((center, offset_radius)),
+            sweep,
+            origin_start: start,
+            origin_end: end,
+        })
+    }
+
+    /// 修剪到 `start`、`end` 后的凸度；段反向或收缩为零时返回 `None`。
+    fn trimmed_bulge(&self, start: DVec2, end: DVec2) -> Option<f64> {
+        match self.circle {
+            None => {
+                let kept = (end - start).dot(self.end - self.start);
+                (kept > TOLERANCE * TOLERANCE).then_some(0.0)
+            }
+            Some((center, _)) => {
+                let from = (start - center).to_angle();
+                let to = (end - center).to_angle();
+                let sweep = if self.sweep > 0.0 {
+                    (to - from).rem_euclid(TAU)
+                } else {
+                    (from - to).rem_euclid(TAU)
+                };
+                // 修剪越过端点时圆心角接近整周
+                let limit = self.sweep.abs() + (TAU - self.sweep.abs()) / 2.0;
+                (sweep > TOLERANCE && sweep <= limit)
+                    .then(|| (sweep.copysign(self.sweep) / 4.0).tan())
+            }
+        }
+    }
+}
+
+/// 相邻两段的连接方式。
+#[derive(Debug, Clone, Copy)]
+enum Joint {
+    /// 两段延伸或修剪到同一点。
+    Point(DVec2),
+    /// 两段保持原端点，其间以给定凸度的一段连接。
+    Gap { from: DVec2, to: DVec2, bulge: f64 },
+}
+
+fn offset_vertices(
+    vertices: &[PolylineVertex],
+    is_closed: bool,
+    distance: f64,
+) -> Result<Vec<PolylineVertex>, OffsetError> {
+    let segment_count = if is_closed {
+        vertices.len()
+    } else {
+        vertices.len().saturating_sub(1)
+    };
+    let mut has_length = false;
+    let mut segments = Vec::with_capacity(segment_count);
+    for index in 0..segment_count {
+        let start = vertices[index].position.as_vec2();
+        let end = vertices[(index + 1) % vertices.len()].position.as_vec2();
+        has_length |= start.distance(end) > TOLERANCE;
+        segments.extend(Segment::offset(start, end, vertices[index].bulge, distance));
+    }
+    if !has_length {
+        return Err(OffsetError::Degenerate);
+    }
+
+    // 反复移除修剪后反向的段，直至各段有效
+    loop {
+        if segments.is_empty() {
+            return Err(OffsetError::Collapsed);
+        }
+        let count = segments.len();
+        let joint_count = if is_closed && count > 1 {
+            count
+        } else {
+            count - 1
+        };
+        let joints: Vec<Joint> = (0..joint_count)
+            .map(|index| join(&segments[index], &segments[(index + 1) % count], distance))
+            .collect();
+        let ends = |index: usize| {
+            let start = match index.checked_sub(1).or(is_closed.then_some(count - 1)) {
+                Some(previous) if previous < joints.len() => match joints[previous] {
+                    Joint::Point(point) => point,
+                    Joint::Gap { to, .. } => to,
+                },
+                _ => segments[index].start,
+            };
+            let end = match joints.get(index) {
+                Some(Joint::Point(point)) => *point,
+                Some(Joint::Gap { from, .. }) => *from,
+                None => segments[index].end,
+            };
+            (start, end)
+        };
+        let bulges: Vec<Option<f64>> = (0..count)
+            .map(|index| {
+                let (start, end) = ends(index);
+                segments[index].trimmed_bulge(start, end)
+            })
+            .collect();
+        if count > 1 && bulges.iter().any(Option::is_none) {
+            let mut bulges = bulges.iter();
+            segments.retain(|_| bulges.next().is_some_and(Option::is_some));
+            continue;
+        }
+
+        let mut result = Vec::with_capacity(count + joints.len() + 1);
+        for (index, bulge) in bulges.iter().enumerate() {
+            let (start, end) = ends(index);
+            let bulge = bulge.unwrap_or(0.0);
+            result.push(PolylineVertex::with_bulge(Point2::from_vec(start), bulge));
+            match joints.get(index) {
+                Some(Joint::Gap { from, bulge, .. }) => {
+                    result.push(PolylineVertex::with_bulge(Point2::from_vec(*from), *bulge));
+                }
+                None if !is_closed => result.push(PolylineVertex::new(Point2::from_vec(end))),
+                _ => {}
+            }
+        }
+        return Ok(result);
+    }
+}
+
+/// 连接 `previous` 的终点与 `next` 的起点。
+fn join(previous: &Segment, next: &Segment, distance: f64) -> Joint {
+    let (from, to) = (previous.end, next.start);
+    if from.distance(to) <= TOLERANCE {
+        return Joint::Point(from);
+    }
+    let pivot = previous.origin_end;
+    let nearest = intersections(previous, next).into_iter().min_by(|a, b| {
+        a.distance_squared(pivot)
+            .total_cmp(&b.distance_squared(pivot))
+    });
+    if let Some(point) = nearest {
+        return Joint::Point(point);
+    }
+    // 无交点时绕原顶点以圆弧连接；两段不再共用顶点时直接连线
+    let (a, b) = (from - pivot, to - pivot);
+    let shares_pivot = pivot.distance(next.origin_start) <= TOLERANCE
+        && (a.length() - b.length()).abs() <= TOLERANCE;
+    let bulge = if shares_pivot {
+        let mut sweep = a.perp_dot(b).atan2(a.dot(b));
+        if (sweep.abs() - PI).abs() <= TOLERANCE {
+            sweep = PI.copysign(distance);
+        }
+        (sweep / 4.0).tan()
+    } else {
+        0.0
+    };
+    Joint::Gap { from, to, bulge }
+}
+
+/// 两段所在直线或整圆的交点。
+fn intersections(a: &Segment, b: &Segment) -> Vec<DVec2> {
+    match (a.circle, b.circle) {
+        (None, None) => line_line(a.start, a.end - a.start, b.start, b.end - b.start)
+            .into_iter()
+            .collect(),
+        (None, Some((center, radius))) => line_circle(a.start, a.end - a.start, center, radius),
+        (Some((center, radius)), None) => line_circle(b.start, b.end - b.start, center, radius),
+        (Some((c1, r1)), Some((c2, r2))) => circle_circle(c1, r1, c2, r2),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::*;
+    use crate::document::Ellipse;
+    use crate::geometry::Vector2;
+
+    fn polyline(points: &[(f64, f64, f64)], is_closed: bool) -> Entity {
+        Entity::Polyline(Polyline {
+            vertices: points
+                .iter()
+                .map(|&(x, y, bulge)| PolylineVertex::with_bulge(Point2::new(x, y), bulge))
+                .collect(),
+            is_closed,
+            layer: "0".to_string(),
+        })
+    }
+
+    fn vertices(entity: &Entity) -> (Vec<(f64, f64, f64)>, bool) {
+        let Entity::Polyline(polyline) = entity else {
+            panic!("expected polyline");
+        };
+        let round = |value: f64| (value * 1e9).round() / 1e9 + 0.0;
+        (
+            polyline
+                .vertices
+                .iter()
+                .map(|vertex| {
+                    (
+                        round(vertex.position.x()),
+                        round(vertex.position.y()),
+                        round(vertex.bulge),
+                    )
+                })
+                .collect(),
+            polyline.is_closed,
+        )
+    }
+
+    #[test]
+    fn offsets_lines_and_circular_curves() {
+        let line = Entity::Line(Line {
+            start: Point2::new(0.0, 0.0),
+            end: Point2::new(2.0, 0.0),
+            layer: "EDGE".to_string(),
+        });
+        let Ok(Entity::Line(shifted)) = offset(&line, 1.5) else {
+            panic!("expected line");
+        };
+        assert_eq!(shifted.start, Point2::new(0.0, -1.5));
+        assert_eq!(shifted.end, Point2::new(2.0, -1.5));
+        assert_eq!(shifted.layer, "EDGE");
+
+        let arc = Entity::Arc(Arc {
+            center: Point2::new(0.0, 0.0),
+            radius: 2.0,
+            start_angle: 0.0,
+            end_angle: FRAC_PI_2,
+            layer: "0".to_string(),
+        });
+        let Ok(Entity::Arc(outer)) = offset(&arc, 0.5) else {
+            panic!("expected arc");
+        };
+        assert_eq!((outer.radius, outer.end_angle), (2.5, FRAC_PI_2));
+        assert_eq!(offset(&arc, -2.0).unwrap_err(), OffsetError::Collapsed);
+
+        let circle = Entity::Circle(Circle {
+            center: Point2::new(0.0, 0.0),
+            radius: 0.0,
+            layer: "0".to_string(),
+        });
+        assert_eq!(offset(&circle, 1.0).unwrap_err(), OffsetError::Degenerate);
+    }
+
+    #[test]
+    fn offsets_closed_polyline_with_mitred_corners() {
+        let square = polyline(
+            &[
+                (0.0, 0.0, 0.0),
+                (4.0, 0.0, 0.0),
+                (4.0, 4.0, 0.0),
+                (0.0, 4.0, 0.0),
+            ],
+            true,
+        );
+        assert_eq!(
+            vertices(&offset(&square, 1.0).unwrap()),
+            (
+                vec![
+                    (-1.0, -1.0, 0.0),
+                    (5.0, -1.0, 0.0),
+                    (5.0, 5.0, 0.0),
+                    (-1.0, 5.0, 0.0)
+                ],
+                true
+            )
+        );
+        assert_eq!(
+            vertices(&offset(&square, -1.0).unwrap()).0,
+            vec![
+                (1.0, 1.0, 0.0),
+                (3.0, 1.0, 0.0),
+                (3.0, 3.0, 0.0),
+                (1.0, 3.0, 0.0)
+            ]
+        );
+        assert_eq!(offset(&square, -2.0).unwrap_err(), OffsetError::Collapsed);
+    }
+
+    #[test]
+    fn offsets_bulges_and_drops_collapsed_segments() {
+        // 直线后接相切的逆时针半圆
+        let path = polyline(&[(0.0, 0.0, 0.0), (2.0, 0.0, 1.0), (2.0, 2.0, 0.0)], false);
+        assert_eq!(
+            vertices(&offset(&path, -0.5).unwrap()),
+            (
+                vec![(0.0, 0.5, 0.0), (2.0, 0.5, 1.0), (2.0, 1.5, 0.0)],
+                false
+            )
+        );
+        assert_eq!(
+            vertices(&offset(&path, -1.5).unwrap()).0,
+            vec![(0.0, 1.5, 0.0), (2.0, 1.5, 0.0)]
+        );
+    }
+
+    #[test]
+    fn joins_parallel_segments_with_round_corner() {
+        let hairpin = polyline(&[(0.0, 0.0, 0.0), (2.0, 0.0, 0.0), (0.0, 0.0, 0.0)], false);
+        assert_eq!(
+            vertices(&offset(&hairpin, 1.0).unwrap()).0,
+            vec![
+                (0.0, -1.0, 0.0),
+                (2.0, -1.0, 1.0),
+                (2.0, 1.0, 0.0),
+                (0.0, 1.0, 0.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn samples_offset_ellipse_into_polyline() {
+        let ellipse = Entity::Ellipse(Ellipse {
+            center: Point2::new(0.0, 0.0),
+            major_axis: Vector2::new(2.0, 0.0),
+            ratio: 0.5,
+            start_parameter: 0.0,
+            end_parameter: TAU,
+            layer: "0".to_string(),
+        });
+        let (points, is_closed) = vertices(&offset(&ellipse, 1.0).unwrap());
+        assert!(is_closed);
+        assert_eq!(points.len(), ELLIPSE_SEGMENTS);
+        assert_eq!(points[0], (3.0, 0.0, 0.0));
+        assert_eq!(points[ELLIPSE_SEGMENTS / 4], (0.0, 2.0, 0.0));
+        assert_eq!(offset(&ellipse, -1.0).unwrap_err(), OffsetError::Collapsed);
+    }
+}
@@ -0,0 +1,133 @@
+//! 几何比较容差：长度容差与角度容差，替代各处零散的 `f64::EPSILON` 判断。
+//!
+//! 坐标很大时双精度的最小可分辨距离随之增大，[`Tolerance::for_magnitude`] 按坐标量级放大长度容差，
+//! 避免远离原点的图纸中本应重合的点被判为不同。
+
+use std::f64::consts::{PI, TAU};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Bounds2D, Point2, Vector2};
+
+/// 按坐标量级放大长度容差时，相对于量级的双精度舍入单位倍数。
+const ROUNDING_UNITS: f64 = 256.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Tolerance {
+    /// 长度容差，两点距离不超过该值视为重合。
+    pub linear: f64,
+    /// 角度容差（弧度）。
+    pub angular: f64,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl Tolerance {
+    pub const DEFAULT: Tolerance = Tolerance {
+        linear: 1e-9,
+        angular: 1e-9,
+    };
+
+    pub fn new(linear: f64, angular: f64) -> Self {
+        Self {
+            linear: linear.abs(),
+            angular: angular.abs(),
+        }
+    }
+
+    /// 适用于坐标绝对值达到 `magnitude` 的图纸：长度容差不小于该量级下的舍入误差。
+    pub fn for_magnitude(self, magnitude: f64) -> Self {
+        let rounding = magnitude.abs() * f64::EPSILON * ROUNDING_UNITS;
+        Self {
+            linear: if rounding.is_finite() {
+                self.linear.max(rounding)
+            } else {
+                self.linear
+            },
+            ..self
+        }
+    }
+
+    /// 按范围内离原点最远的坐标放大长度容差，空范围时不变。
+    pub fn for_bounds(self, bounds: &Bounds2D) -> Self {
+        if bounds.is_empty() {
+            return self;
+        }
+        let (min, max) = (bounds.min().as_vec2().abs(), bounds.max().as_vec2().abs());
+        self.for_magnitude(min.max(max).max_element())
+    }
+
+    pub fn is_zero_length(&self, length: f64) -> bool {
+        length.abs() <= self.linear
+    }
+
+    pub fn lengths_equal(&self, a: f64, b: f64) -> bool {
+        (a - b).abs() <= self.linear
+    }
+
+    pub fn points_equal(&self, a: Point2, b: Point2) -> bool {
+        a.as_vec2().distance(b.as_vec2()) <= self.linear
+    }
+
+    /// 两个角度相差整圈的倍数时视为相等。
+    pub fn angles_equal(&self, a: f64, b: f64) -> bool {
+        let difference = (a - b).rem_euclid(TAU);
+        difference.min(TAU - difference) <= self.angular
+    }
+
+    /// 同向或反向平行；零向量与任何方向都不平行。
+    pub fn is_parallel(&self, u: Vector2, v: Vector2) -> bool {
+        let (u, v) = (u.as_vec2(), v.as_vec2());
+        if self.is_zero_length(u.length()) || self.is_zero_length(v.length()) {
+            return false;
+        }
+        let angle = u.angle_to(v).abs();
+        angle.min(PI - angle) <= self.angular
+    }
+
+    pub fn is_perpendicular(&self, u: Vector2, v: Vector2) -> bool {
+        let (u, v) = (u.as_vec2(), v.as_vec2());
+        if self.is_zero_length(u.length()) || self.is_zero_length(v.length()) {
+            return false;
+        }
+        (u.angle_to(v).abs() - PI / 2.0).abs() <= self.angular
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_points_angles_and_directions() {
+        let tolerance = Tolerance::new(1e-6, 1e-6);
+        assert!(tolerance.points_equal(Point2::new(1.0, 1.0), Point2::new(1.0, 1.0 + 1e-7)));
+        assert!(!tolerance.points_equal(Point2::new(1.0, 1.0), Point2::new(1.0, 1.0 + 1e-5)));
+        assert!(tolerance.angles_equal(-PI, PI));
+        assert!(tolerance.angles_equal(1e-7, TAU));
+        assert!(!tolerance.angles_equal(0.0, 1e-3));
+        assert!(tolerance.is_parallel(Vector2::new(1.0, 0.0), Vector2::new(-2.0, 1e-8)));
+        assert!(!tolerance.is_parallel(Vector2::new(1.0, 0.0), Vector2::new(0.0, 0.0)));
+        assert!(tolerance.is_perpendicular(Vector2::new(1.0, 1.0), Vector2::new(-1.0, 1.0)));
+        assert!(!tolerance.is_perpendicular(Vector2::new(1.0, 0.0), Vector2::new(1.0, 1.0)));
+    }
+
+    #[test]
+    fn scales_with_coordinate_magnitude() {
+        let base = Tolerance::DEFAULT;
+        assert_eq!(base.for_magnitude(1000.0), base);
+        // 1e8 量级时相邻双精度数相差约 1.5e-8，默认容差无法区分
+        let far = base.for_magnitude(1e8);
+        assert!(far.linear > 1e-8 && far.linear < 1e-5);
+        assert_eq!(far.angular, base.angular);
+        assert!(far.points_equal(Point2::new(1e8, 0.0), Point2::new(1e8 + 1.5e-8, 0.0)));
+
+        let bounds = Bounds2D::new(Point2::new(-3e8, 0.0), Point2::new(1.0, 2e8));
+        assert_eq!(base.for_bounds(&bounds), base.for_magnitude(3e8));
+        assert_eq!(base.for_bounds(&Bounds2D::empty()), base);
+    }
+}
@@ -0,0 +1,259 @@
+//! 二维仿射变换：平移、旋转、缩放、镜像及其组合，另提供与 3×3 齐次矩阵的互转。
+
+use std::ops::Mul;
+
+use glam::{DAffine2, DMat2, DMat3, DVec2, DVec3};
+
+use super::{Point2, Vector2};
+
+/// 3×3 齐次矩阵，作用于列向量 `(x, y, 1)`；最后一行不为 `(0, 0, 1)` 时表示投影变换。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix3(pub DMat3);
+
+impl Matrix3 {
+    pub const IDENTITY: Self = Self(DMat3::IDENTITY);
+
+    /// 按行给出矩阵元素。
+    pub fn from_rows(rows: [[f64; 3]; 3]) -> Self {
+        Self(DMat3::from_cols_array_2d(&rows).transpose())
+    }
+
+    pub fn to_rows(&self) -> [[f64; 3]; 3] {
+        self.0.transpose().to_cols_array_2d()
+    }
+
+    #[inline]
+    pub fn determinant(&self) -> f64 {
+        self.0.determinant()
+    }
+
+    /// 仿射矩阵转为 [`Transform2`]，投影矩阵返回 `None`。
+    pub fn to_transform(&self) -> Option<Transform2> {
+        (self.0.row(2) == DVec3::Z).then(|| Transform2(DAffine2::from_mat3(self.0)))
+    }
+}
+
+impl Default for Matrix3 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Mul for Matrix3 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(self.0 * rhs.0)
+    }
+}
+
+impl From<Transform2> for Matrix3 {
+    fn from(value: Transform2) -> Self {
+        Self(DMat3::from(value.0))
+    }
+}
+
+/// 二维仿射变换。`a * b` 与矩阵乘法一致，先施加 `b` 再施加 `a`；按施加顺序组合时使用 [`Transform2::then`]。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform2(DAffine2);
+
+impl Transform2 {
+    pub const IDENTITY: Self = Self(DAffine2::IDENTITY);
+
+    #[inline]
+    pub fn from_affine(affine: DAffine2) -> Self {
+        Self(affine)
+    }
+
+    #[inline]
+    pub fn as_affine(&self) -> DAffine2 {
+        self.0
+    }
+
+    /// 线性部分（不含平移）。
+    #[inline]
+    pub fn linear(&self) -> DMat2 {
+        self.0.matrix2
+    }
+
+    pub fn translation(offset: Vector2) -> Self {
+        Self(DAffine2::from_translation(offset.as_vec2()))
+    }
+
+    /// 绕原点逆时针旋转（弧度）。
+    pub fn rotation(angle: f64) -> Self {
+        Self(DAffine2::from_angle(angle))
+    }
+
+    pub fn rotation_about(center: Point2, angle: f64) -> Self {
+        Self::about(center, DAffine2::from_angle(angle))
+    }
+
+    /// 以原点为基点缩放，负系数表示沿对应轴镜像。
+    pub fn scaling(x: f64, y: f64) -> Self {
+        Self(DAffine2::from_scale(DVec2::new(x, y)))
+    }
+
+    pub fn scaling_about(center: Point2, x: f64, y: f64) -> Self {
+        Self::about(center, DAffine2::from_scale(DVec2::new(x, y)))
+    }
+
+    /// 关于过 `start`、`end` 两点的直线镜像；两点重合时返回恒等变换。
+    pub fn mirror(start: Point2, end: Point2) -> Self {
+        let direction = (end.as_vec2() - start.as_vec2()).normalize_or_zero();
+        if direction == DVec2::ZERO {
+            return Self::IDENTITY;
+        }
+        // 反射矩阵 2·d·dᵀ - I
+        let reflection = DMat2::from_cols(
+            DVec2::new(
+                2.0 * direction.x * direction.x - 1.0,
+                2.0 * direction.x * direction.y,
+            ),
+            DVec2::new(
+                2.0 * direction.x * direction.y,
+                2.0 * direction.y * direction.y - 1.0,
+            ),
+        );
+        Self::about(start, DAffine2::from_mat2(reflection))
+    }
+
+    /// 先施加 `self`，再施加 `next`。
+    #[inline]
+    pub fn then(self, next: Transform2) -> Self {
+        Self(next.0 * self.0)
+    }
+
+    /// 逆变换；线性部分奇异时返回 `None`。
+    pub fn inverse(&self) -> Option<Self> {
+        let determinant = self.determinant();
+        (determinant.is_finite() && determinant.abs() > f64::EPSILON)
+            .then(|| Self(self.0.inverse()))
+    }
+
+    #[inline]
+    pub fn apply_point(&self, point: Point2) -> Point2 {
+        Point2::from_vec(self.0.transform_point2(point.as_vec2()))
+    }
+
+    #[inline]
+    pub fn apply_vector(&self, vector: Vector2) -> Vector2 {
+        Vector2::from(self.0.transform_vector2(vector.as_vec2()))
+    }
+
+    #[inline]
+    pub fn determinant(&self) -> f64 {
+        self.0.matrix2.determinant()
+    }
+
+    /// 是否包含镜像（改变图形的绕向）。
+    #[inline]
+    pub fn is_mirroring(&self) -> bool {
+        self.determinant() < 0.0
+    }
+
+    /// 无剪切且两轴等比（可含镜像）时返回缩放系数，此时圆仍为圆。
+    pub fn uniform_scale(&self) -> Option<f64> {
+        let x_axis = self.0.matrix2.x_axis;
+        let y_axis = self.0.matrix2.y_axis;
+        let scale = x_axis.length();
+        let tolerance = 1e-9 * scale.max(1.0);
+        let is_similar = scale > f64::EPSILON
+            && (scale - y_axis.length()).abs() <= tolerance
+            && x_axis.dot(y_axis).abs() <= tolerance;
+        is_similar.then_some(scale)
+    }
+
+    /// 变换后 X 轴的方向角。
+    #[inline]
+    pub fn rotation_angle(&self) -> f64 {
+        self.0.matrix2.x_axis.to_angle()
+    }
+
+    /// 以 `center` 为基点施加线性变换。
+    fn about(center: Point2, linear: DAffine2) -> Self {
+        let center = center.as_vec2();
+        Self(DAffine2::from_translation(center) * linear * DAffine2::from_translation(-center))
+    }
+}
+
+impl Default for Transform2 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Mul for Transform2 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(self.0 * rhs.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::*;
+
+    fn assert_point(actual: Point2, x: f64, y: f64) {
+        assert!(
+            (actual.x() - x).abs() < 1e-9 && (actual.y() - y).abs() < 1e-9,
+            "{actual:?} != ({x}, {y})"
+        );
+    }
+
+    #[test]
+    fn composes_in_application_order() {
+        let center = Point2::new(1.0, 1.0);
+        let rotate = Transform2::rotation_about(center, FRAC_PI_2);
+        let shift = Transform2::translation(Vector2::new(10.0, 0.0));
+        let combined = rotate.then(shift);
+        assert_eq!(combined, shift * rotate);
+        assert_point(combined.apply_point(Point2::new(2.0, 1.0)), 11.0, 2.0);
+        assert_point(
+            combined
+                .inverse()
+                .unwrap()
+                .apply_point(Point2::new(11.0, 2.0)),
+            2.0,
+            1.0,
+        );
+        assert_eq!(
+            combined.uniform_scale().map(|s| (s * 1e9).round()),
+            Some(1e9)
+        );
+        assert!(Transform2::scaling(2.0, 1.0).uniform_scale().is_none());
+        assert!(Transform2::scaling(0.0, 1.0).inverse().is_none());
+    }
+
+    #[test]
+    fn mirrors_about_arbitrary_line() {
+        let mirror = Transform2::mirror(Point2::new(0.0, 1.0), Point2::new(1.0, 2.0));
+        assert!(mirror.is_mirroring());
+        assert_point(mirror.apply_point(Point2::new(1.0, 0.0)), -1.0, 2.0);
+        assert_point(mirror.apply_point(Point2::new(3.0, 4.0)), 3.0, 4.0);
+        let vector = mirror.apply_vector(Vector2::new(1.0, 0.0));
+        assert!((vector.x()).abs() < 1e-9 && (vector.y() - 1.0).abs() < 1e-9);
+        assert_eq!(
+            Transform2::mirror(Point2::new(1.0, 1.0), Point2::new(1.0, 1.0)),
+            Transform2::IDENTITY
+        );
+    }
+
+    #[test]
+    fn converts_to_and_from_homogeneous_matrices() {
+        let transform = Transform2::scaling_about(Point2::new(1.0, 0.0), 2.0, 3.0);
+        let matrix = Matrix3::from(transform);
+        assert_eq!(
+            matrix.to_rows(),
+            [[2.0, 0.0, -1.0], [0.0, 3.0, 0.0], [0.0, 0.0, 1.0]]
+        );
+        assert_eq!(Matrix3::from_rows(matrix.to_rows()), matrix);
+        assert_eq!(matrix.to_transform(), Some(transform));
+        assert_eq!((matrix * Matrix3::IDENTITY).determinant(), 6.0);
+        let projective = Matrix3::from_rows([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.5, 0.0, 1.0]]);
+        assert!(projective.to_transform().is_none());
+    }
+}
@@ -1,7 +1,23 @@
 pub mod geometry {
+    mod bulge;
+    mod corner;
+    mod hull;
+    pub mod intersect;
+    mod offset;
+    mod tolerance;
+    mod transform;
+
     use glam::{DVec2, DVec3};
     use serde::{Deserialize, Serialize};
 
+    pub use bulge::BulgeArc;
+    pub(crate) use bulge::STRAIGHT_BULGE;
+    pub use corner::{Corner, CornerError, chamfer, fillet};
+    pub use hull::{OrientedBox, convex_hull, entities_convex_hull, minimum_area_obb};
+    pub use offset::{OffsetError, offset};
+    pub use tolerance::Tolerance;
+    pub use transform::{Matrix3, Transform2};
+
     /// 二维点，内部以 `glam::DVec2` 表示，确保与双精度 Pascal 版本兼容。
     #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
     pub struct Point2(pub DVec2);
@@ -231,6 +247,17 @@ pub mod geometry {
             self.include_point(other.max);
         }
 
+        /// 两个范围是否相交，边界接触也视为相交。
+        #[inline]
+        pub fn intersects(&self, other: &Bounds2D) -> bool {
+            !self.is_empty()
+                && !other.is_empty()
+                && self.min.x() <= other.max.x()
+                && other.min.x() <= self.max.x()
+                && self.min.y() <= other.max.y()
+                && other.min.y() <= self.max.y()
+        }
+
         #[inline]
         pub fn center(&self) -> Point2 {
             debug_assert!(!self.is_empty());
@@ -243,13 +270,117 @@ pub mod geometry {
 }
 
 pub mod document {
+    mod array;
+    mod associate;
+    mod attribute;
+    mod audit;
+    mod block_edit;
+    mod boundary;
+    mod closest;
+    mod contain;
+    mod dimension;
+    mod dimstyle;
+    mod draw_order;
+    mod entity_list;
+    mod explode;
+    mod extent;
+    mod extract;
+    mod fill;
+    mod flatten;
+    mod geodata;
+    mod grip;
+    mod hatch_assoc;
+    mod join;
+    mod journal;
+    mod layer;
+    mod layer_state;
+    mod linetype;
+    mod match_props;
+    mod measure;
+    mod memory;
+    mod mtext;
+    mod nurbs;
+    mod osnap;
+    mod pedit;
+    mod properties;
+    mod provenance;
+    mod purge;
+    mod revcloud;
+    mod simplify;
+    mod spatial;
+    mod split;
+    mod tables;
+    mod transform;
+    mod underlay;
+    mod units;
+    mod user_data;
+    mod xref;
+
     use std::collections::HashMap;
     use std::f64::consts::{FRAC_PI_2, PI, TAU};
 
-    use glam::DVec2;
+    use glam::{DAffine2, DVec2};
     use serde::{Deserialize, Serialize};
 
-    use crate::geometry::{Bounds2D, Point2, Point3, Vector2, Vector3};
+    use crate::geometry::{Bounds2D, BulgeArc, Point2, Point3, Tolerance, Vector2, Vector3};
+
+    use draw_order::DrawOrderKey;
+    use entity_list::EntityList;
+    use extent::ExtentResolver;
+    use provenance::ProvenanceLog;
+
+    pub use array::{PolarArray, RectangularArray};
+    pub use associate::{
+        AnnotationPoint, AssociationError, DimensionPoint, PointAssociation, SnapReference,
+        SnapRole,
+    };
+    pub use attribute::AttributeError;
+    pub use audit::{AuditFinding, AuditSeverity};
+    pub use block_edit::BlockEditError;
+    pub use boundary::{Boundary, BoundaryError, BoundaryOptions};
+    pub use contain::Containment;
+    pub use dimstyle::{
+        AlternateUnits, DIMSTYLE_STANDARD, DimStyle, DimTolerance, ToleranceDisplay,
+        ZeroSuppression,
+    };
+    pub use extract::{ExtractionOptions, ExtractionRow, ExtractionTable};
+    pub use fill::{FillRule, HatchFill, HatchFillOptions};
+    pub use flatten::{FaceOutput, FlattenOptions};
+    pub(crate) use flatten::{ccw_sweep, edge_sweep};
+    pub use geodata::{GeoCoordinateType, GeoData};
+    pub use grip::{Grip, GripKind};
+    pub use hatch_assoc::HatchRegenError;
+    pub use join::{JoinError, JoinOptions};
+    pub use journal::{Change, ChangeSummary, Journal, Revision};
+    pub use layer::{LayerDeletePolicy, LayerError};
+    pub use layer_state::{LayerState, LayerStateEntry, LayerStateError};
+    pub use linetype::{Linetype, LinetypeElement, LinetypeEmbedded, LinetypeEmbeddedContent};
+    pub use match_props::{MatchPropertiesError, PropertyMask};
+    pub use measure::Measurement;
+    pub use memory::{
+        BlockMemory, DuplicatedString, EntityKindMemory, EntityMemory, MemoryReport, StringUsage,
+    };
+    pub use mtext::{StackKind, StackedText, TextFormat, TextRun, TextRunAlignment};
+    pub(crate) use nurbs::{clamped_uniform_knots, de_boor};
+    pub use osnap::OsnapMode;
+    pub use pedit::PolylineEditError;
+    pub use properties::{
+        EntityProperties, LINETYPE_BYBLOCK, LINETYPE_BYLAYER, LINETYPE_CONTINUOUS, LineWeight,
+        ObjectColor, ResolvedProperties,
+    };
+    pub use provenance::{Editor, Provenance};
+    pub use purge::{PurgeOptions, PurgeReport};
+    pub use revcloud::{REVCLOUD_XDATA_APP, RevisionCloud, RevisionCloudStyle};
+    pub use spatial::SpatialIndex;
+    pub use split::BreakError;
+    pub use tables::TableSnapshot;
+    use tables::Tracked;
+    pub use underlay::{
+        Underlay, UnderlayDefinition, UnderlayDependency, UnderlayDisplayOptions, UnderlayKind,
+    };
+    pub use units::{AngleFormat, InsertUnits, LengthFormat, Units};
+    pub use user_data::{USER_DATA_XDATA_APP, UserData, UserDataError, UserValue};
+    pub use xref::{XREF_SEPARATOR, XRef, XRefError};
 
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
     pub struct EntityId(u64);
@@ -267,10 +398,29 @@ pub mod document {
         }
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct Layer {
         pub name: String,
+        /// 图层开关（DXF 组码 62 取负表示关闭）。
         pub is_visible: bool,
+        /// 冻结（组码 70 标志位 1），冻结图层不显示也不参与重生成。
+        #[serde(default)]
+        pub is_frozen: bool,
+        /// 锁定（组码 70 标志位 4），锁定图层上的对象不可编辑。
+        #[serde(default)]
+        pub is_locked: bool,
+        /// 随层实体使用的默认颜色（DXF 组码 62/420）。
+        #[serde(default = "properties::default_layer_color")]
+        pub color: ObjectColor,
+        /// 随层实体使用的默认线型（DXF 组码 6）。
+        #[serde(default = "properties::default_layer_linetype")]
+        pub linetype: String,
+        /// 随层实体使用的默认线宽（DXF 组码 370）。
+        #[serde(default = "properties::default_layer_lineweight")]
+        pub lineweight: LineWeight,
+        /// 绘制优先级：数值小的图层先绘制，位于其他图层下方（DXF 中无对应组码）。
+        #[serde(default)]
+        pub draw_priority: i32,
     }
 
     impl Layer {
@@ -279,11 +429,23 @@ pub mod document {
             Self {
                 name: name.into(),
                 is_visible: true,
+                is_frozen: false,
+                is_locked: false,
+                color: properties::default_layer_color(),
+                linetype: properties::default_layer_linetype(),
+                lineweight: properties::default_layer_lineweight(),
+                draw_priority: 0,
             }
         }
+
+        /// 图层打开且未冻结时显示其上的对象。
+        #[inline]
+        pub fn is_displayed(&self) -> bool {
+            self.is_visible && !self.is_frozen
+        }
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub enum Entity {
         Line(Line),
         Circle(Circle),
@@ -301,6 +463,9 @@ pub mod document {
         RasterImage(RasterImage),
         Wipeout(Wipeout),
         Face3D(ThreeDFace),
+        Shape(Shape),
+        Underlay(Underlay),
+        RevisionCloud(RevisionCloud),
     }
 
     impl Entity {
@@ -323,10 +488,40 @@ pub mod document {
                 Entity::RasterImage(image) => &image.layer,
                 Entity::Wipeout(wipeout) => &wipeout.layer,
                 Entity::Face3D(face) => &face.layer,
+                Entity::Shape(shape) => &shape.layer,
+                Entity::Underlay(underlay) => &underlay.layer,
+                Entity::RevisionCloud(cloud) => &cloud.layer,
             }
         }
 
-        /// 计算实体的 2D 轴对齐范围，少数对象（文本、块参照）退化为点。
+        /// 修改实体所在图层，块参照的属性图层保持不变。
+        pub fn set_layer_name(&mut self, name: impl Into<String>) {
+            let name = name.into();
+            match self {
+                Entity::Line(line) => line.layer = name,
+                Entity::Circle(circle) => circle.layer = name,
+                Entity::Arc(arc) => arc.layer = name,
+                Entity::Ellipse(ellipse) => ellipse.layer = name,
+                Entity::Polyline(polyline) => polyline.layer = name,
+                Entity::Spline(spline) => spline.layer = name,
+                Entity::Text(text) => text.layer = name,
+                Entity::MText(mtext) => mtext.layer = name,
+                Entity::BlockReference(reference) => reference.layer = name,
+                Entity::Hatch(hatch) => hatch.layer = name,
+                Entity::Dimension(dimension) => dimension.layer = name,
+                Entity::Leader(leader) => leader.layer = name,
+                Entity::MLeader(mleader) => mleader.layer = name,
+                Entity::RasterImage(image) => image.layer = name,
+                Entity::Wipeout(wipeout) => wipeout.layer = name,
+                Entity::Face3D(face) => face.layer = name,
+                Entity::Shape(shape) => shape.layer = name,
+                Entity::Underlay(underlay) => underlay.layer = name,
+                Entity::RevisionCloud(cloud) => cloud.layer = name,
+            }
+        }
+
+        /// 计算实体的 2D 轴对齐范围，文字按字数估算，块参照只计插入点与属性；
+        /// 包含块内容的范围见 [`Document::entity_extent`]。
         pub fn bounds(&self) -> Option<Bounds2D> {
             let mut bounds = Bounds2D::empty();
             match self {
@@ -360,10 +555,10 @@ pub mod document {
                     }
                 }
                 Entity::Text(text) => {
-                    bounds.include_point(text.insert);
+                    bounds.include_bounds(&text.estimated_bounds());
                 }
                 Entity::MText(mtext) => {
-                    bounds.include_point(mtext.insert);
+                    bounds.include_bounds(&mtext.estimated_bounds());
                 }
                 Entity::BlockReference(reference) => {
                     bounds.include_point(reference.insert);
@@ -402,6 +597,9 @@ pub mod document {
                     if let Some(point) = dimension.center_point {
                         bounds.include_point(point);
                     }
+                    if let Some(point) = dimension.jog_point {
+                        bounds.include_point(point);
+                    }
                 }
                 Entity::Leader(leader) => {
                     for vertex in &leader.vertices {
@@ -449,6 +647,33 @@ pub mod document {
                         bounds.include_point(Point2::new(vertex.x(), vertex.y()));
                     }
                 }
+                Entity::Shape(shape) => {
+                    for corner in shape.outline() {
+                        bounds.include_point(corner);
+                    }
+                }
+                Entity::Underlay(underlay) => {
+                    // 底图内容范围未知，未裁剪时只计入插入点
+                    bounds.include_point(underlay.insert);
+                    for corner in underlay.clip_outline() {
+                        bounds.include_point(corner);
+                    }
+                }
+                Entity::RevisionCloud(cloud) => {
+                    let count = cloud.vertices.len();
+                    for (index, vertex) in cloud.vertices.iter().enumerate() {
+                        bounds.include_point(vertex.position);
+                        if index + 1 < count || cloud.is_closed {
+                            let next = cloud.vertices[(index + 1) % count].position;
+                            polyline_segment_bounds(
+                                vertex.position,
+                                next,
+                                vertex.bulge,
+                                &mut bounds,
+                            );
+                        }
+                    }
+                }
             }
             if bounds.is_empty() {
                 None
@@ -458,14 +683,14 @@ pub mod document {
         }
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct Line {
         pub start: Point2,
         pub end: Point2,
         pub layer: String,
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct Circle {
         pub center: Point2,
         pub radius: f64,
@@ -473,7 +698,7 @@ pub mod document {
     }
 
     /// 圆弧实体，角度以弧度形式储存，遵循数学正方向。
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct Arc {
         pub center: Point2,
         pub radius: f64,
@@ -483,7 +708,7 @@ pub mod document {
     }
 
     /// 椭圆实体，记录主轴向量与参数范围（单位为弧度）。
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct Ellipse {
         pub center: Point2,
         pub major_axis: Vector2,
@@ -493,14 +718,14 @@ pub mod document {
         pub layer: String,
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct Polyline {
         pub vertices: Vec<PolylineVertex>,
         pub is_closed: bool,
         pub layer: String,
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct Spline {
         pub degree: i32,
         pub is_rational: bool,
@@ -515,7 +740,7 @@ pub mod document {
         pub layer: String,
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct PolylineVertex {
         pub position: Point2,
         pub bulge: f64,
@@ -536,16 +761,118 @@ pub mod document {
         }
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    /// 单行文字的估算字宽系数（相对字高），用于文字轮廓与范围近似。
+    const TEXT_WIDTH_FACTOR: f64 = 0.6;
+    /// MTEXT 多行排布的行距系数（相对字高）。
+    const MTEXT_LINE_SPACING: f64 = 5.0 / 3.0;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct Text {
         pub insert: Point2,
         pub content: String,
         pub height: f64,
         pub rotation: f64,
         pub layer: String,
+        /// 水平对齐（DXF 组码 72）：0 左、1 中、2 右、3 对齐、4 中间、5 布满。
+        #[serde(default)]
+        pub horizontal_align: i16,
+        /// 垂直对齐（DXF 组码 73）：0 基线、1 底、2 中、3 顶。
+        #[serde(default)]
+        pub vertical_align: i16,
+        /// 第二对齐点（DXF 组码 11/21）。
+        #[serde(default)]
+        pub alignment: Option<Point2>,
+        /// 反向文字（DXF 组码 71 位 2），字形沿 X 方向镜像。
+        #[serde(default)]
+        pub is_backward: bool,
+        /// 倒置文字（DXF 组码 71 位 4），字形沿 Y 方向镜像。
+        #[serde(default)]
+        pub is_upside_down: bool,
+    }
+
+    impl Text {
+        /// 对齐与布满文字在插入点与第二对齐点之间排布。
+        pub fn is_fitted(&self) -> bool {
+            matches!(self.horizontal_align, 3 | 5) && self.alignment.is_some()
+        }
+
+        /// 文字定位点：左对齐基线文字及对齐/布满文字为插入点，其余对齐方式为第二对齐点。
+        pub fn anchor(&self) -> Point2 {
+            if self.is_fitted() || (self.horizontal_align == 0 && self.vertical_align == 0) {
+                return self.insert;
+            }
+            self.alignment.unwrap_or(self.insert)
+        }
+
+        /// 对齐/布满文字的方向由两个对齐点决定，其余使用组码 50 的旋转角。
+        pub fn effective_rotation(&self) -> f64 {
+            match self.alignment {
+                Some(alignment) if self.is_fitted() => {
+                    let direction = alignment.as_vec2() - self.insert.as_vec2();
+                    direction.y.atan2(direction.x)
+                }
+                _ => self.rotation,
+            }
+        }
+
+        /// 按估算的文字宽度求左侧基线起点，供以左下角排布文字的场合使用。
+        pub fn baseline_start(&self, width: f64) -> Point2 {
+            if self.is_fitted() {
+                return self.insert;
+            }
+            let horizontal = match self.horizontal_align {
+                1 | 4 => 0.5,
+                2 => 1.0,
+                _ => 0.0,
+            };
+            let vertical = match (self.horizontal_align, self.vertical_align) {
+                (_, 2) | (4, 0) => 0.5,
+                (_, 3) => 1.0,
+                _ => 0.0,
+            };
+            let offset = DVec2::new(width * horizontal, self.height * vertical) * self.mirror();
+            let rotated = DVec2::from_angle(self.effective_rotation()).rotate(offset);
+            Point2::from_vec(self.anchor().as_vec2() - rotated)
+        }
+
+        /// 按字数 × 字高 × 字宽系数估算的文字范围，考虑对齐、旋转与镜像。
+        pub fn estimated_bounds(&self) -> Bounds2D {
+            let mut bounds = Bounds2D::empty();
+            let width = TEXT_WIDTH_FACTOR * self.height.abs() * self.content.chars().count() as f64;
+            let frame = DAffine2::from_scale_angle_translation(
+                self.mirror(),
+                self.effective_rotation(),
+                self.baseline_start(width).as_vec2(),
+            );
+            for corner in [
+                DVec2::ZERO,
+                DVec2::new(width, 0.0),
+                DVec2::new(width, self.height),
+                DVec2::new(0.0, self.height),
+            ] {
+                bounds.include_point(Point2::from_vec(frame.transform_point2(corner)));
+            }
+            if let Some(alignment) = self.alignment.filter(|_| self.is_fitted()) {
+                bounds.include_point(alignment);
+            }
+            bounds
+        }
+
+        /// 字形坐标系的镜像系数：反向文字 X 取 -1，倒置文字 Y 取 -1。
+        pub fn mirror(&self) -> DVec2 {
+            DVec2::new(
+                if self.is_backward { -1.0 } else { 1.0 },
+                if self.is_upside_down { -1.0 } else { 1.0 },
+            )
+        }
+
+        /// 文字生成标志（DXF 组码 71）。
+        pub fn generation_flags(&self) -> i16 {
+            (i16::from(self.is_backward) << 1) | (i16::from(self.is_upside_down) << 2)
+        }
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct MText {
         pub insert: Point2,
         pub content: String,
@@ -556,17 +883,68 @@ pub mod document {
         pub drawing_direction: i16,
         pub style: Option<String>,
         pub layer: String,
+        /// 按行内格式切分的文字段，拼接后即 `content`；为空表示整段沿用实体样式。
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub runs: Vec<TextRun>,
+    }
+
+    impl MText {
+        /// 估算的文字范围：宽度取最长段落的字数 × 字高 × 字宽系数，指定参照宽度时按其折行；
+        /// 行数乘行距得到高度，再按附着点与方向定位。
+        pub fn estimated_bounds(&self) -> Bounds2D {
+            let height = self.height.abs();
+            let char_width = TEXT_WIDTH_FACTOR * height;
+            let wrap = self.reference_width.filter(|width| *width > 0.0);
+            let mut width: f64 = 0.0;
+            let mut lines = 0usize;
+            for paragraph in self.content.split('\n').flat_map(|line| line.split("\\P")) {
+                let natural = char_width * paragraph.chars().count() as f64;
+                match wrap {
+                    Some(wrap) if natural > wrap => {
+                        width = width.max(wrap);
+                        lines += (natural / wrap).ceil() as usize;
+                    }
+                    _ => {
+                        width = width.max(natural);
+                        lines += 1;
+                    }
+                }
+            }
+            let total_height =
+                height + height * MTEXT_LINE_SPACING * lines.saturating_sub(1) as f64;
+            // 附着点 1–9 依次为上、中、下三行的左、中、右
+            let code = (self.attachment_point.clamp(1, 9) - 1) as usize;
+            let left = -width * [0.0, 0.5, 1.0][code % 3];
+            let top = total_height * [0.0, 0.5, 1.0][code / 3];
+            let frame = DAffine2::from_angle_translation(
+                self.direction.y().atan2(self.direction.x()),
+                self.insert.as_vec2(),
+            );
+            let mut bounds = Bounds2D::empty();
+            for corner in [
+                DVec2::new(left, top),
+                DVec2::new(left + width, top),
+                DVec2::new(left + width, top - total_height),
+                DVec2::new(left, top - total_height),
+            ] {
+                bounds.include_point(Point2::from_vec(frame.transform_point2(corner)));
+            }
+            bounds
+        }
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct HatchLoop {
         pub is_polyline: bool,
         pub is_closed: bool,
         pub edges: Vec<HatchEdge>,
         pub boundary_handles: Vec<String>,
+        /// 关联边界对象，由 `boundary_handles` 解析而来，供 [`Document::regenerate_hatch`] 使用。
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub boundary_entities: Vec<EntityId>,
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub enum HatchEdge {
         Line {
             start: Point2,
@@ -605,7 +983,7 @@ pub mod document {
         },
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct HatchGradient {
         pub name: String,
         pub angle: f64,
@@ -616,16 +994,68 @@ pub mod document {
         pub color2: Option<u32>,
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    /// 图案填充定义行：角度以弧度储存，基点/偏移/虚线长度均为已缩放的图纸单位。
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct HatchPatternLine {
+        pub angle: f64,
+        pub base_point: Point2,
+        pub offset: Vector2,
+        /// 正值为实线段，负值为空白，0 表示点。
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub dash_lengths: Vec<f64>,
+    }
+
+    /// 孤岛检测样式（DXF 组码 75）。
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum HatchStyle {
+        /// 奇偶规则：自外向内交替填充。
+        #[default]
+        Normal,
+        /// 仅填充最外层区域。
+        Outer,
+        /// 忽略内部孤岛，填充整个外边界。
+        Ignore,
+    }
+
+    impl HatchStyle {
+        pub fn from_code(code: i16) -> Self {
+            match code {
+                1 => HatchStyle::Outer,
+                2 => HatchStyle::Ignore,
+                _ => HatchStyle::Normal,
+            }
+        }
+
+        pub fn code(self) -> i16 {
+            match self {
+                HatchStyle::Normal => 0,
+                HatchStyle::Outer => 1,
+                HatchStyle::Ignore => 2,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct Hatch {
         pub pattern_name: String,
         pub is_solid: bool,
         pub loops: Vec<HatchLoop>,
         pub gradient: Option<HatchGradient>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub pattern_lines: Vec<HatchPatternLine>,
+        #[serde(default)]
+        pub style: HatchStyle,
+        /// 组码 71：填充是否与边界对象关联。
+        #[serde(default)]
+        pub is_associative: bool,
+        /// 组码 98 后的种子点，用于重新计算边界。
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub seed_points: Vec<Point2>,
         pub layer: String,
     }
 
-    #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
     pub enum DimensionKind {
         Linear,
         Aligned,
@@ -634,10 +1064,27 @@ pub mod document {
         Radius,
         Angular3Point,
         Ordinate,
+        /// 弧长标注（ARC_DIMENSION，类型码 8）。
+        ArcLength,
+        /// 折弯半径标注（LARGE_RADIAL_DIMENSION，类型码 9）。
+        JoggedRadius,
         Unknown(i16),
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    /// 弧长标注的圆弧范围，角度以弧度储存。
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct DimensionArc {
+        pub start_angle: f64,
+        pub end_angle: f64,
+        /// 组码 70：仅标注圆弧的一部分。
+        pub is_partial: bool,
+        /// 组码 71：带有引线。
+        pub has_leader: bool,
+        pub leader_start: Option<Point2>,
+        pub leader_end: Option<Point2>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct Dimension {
         pub kind: DimensionKind,
         pub definition_point: Point2,
@@ -653,10 +1100,21 @@ pub mod document {
         pub rotation: f64,
         pub text_rotation: Option<f64>,
         pub oblique_angle: Option<f64>,
+        /// 组码 2：保存标注渲染几何的匿名块（`*D…`）。
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub block_name: Option<String>,
+        /// 组码 3：标注样式名。
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub style_name: Option<String>,
+        /// 折弯半径标注的折弯点。
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub jog_point: Option<Point2>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub arc: Option<DimensionArc>,
         pub layer: String,
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct Leader {
         pub layer: String,
         pub style_name: Option<String>,
@@ -664,12 +1122,12 @@ pub mod document {
         pub has_arrowhead: bool,
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct LeaderLine {
         pub vertices: Vec<Point2>,
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct MLeaderBlockContent {
         #[serde(default, skip_serializing_if = "Option::is_none")]
         pub block_handle: Option<String>,
@@ -682,7 +1140,7 @@ pub mod document {
         pub connection_type: Option<i16>,
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     #[serde(tag = "kind", rename_all = "snake_case")]
     pub enum MLeaderContent {
         /// 简化实现：记录 MLeader 的文字内容及插入点。
@@ -693,7 +1151,7 @@ pub mod document {
         None,
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct MLeader {
         pub layer: String,
         pub style_name: Option<String>,
@@ -711,7 +1169,7 @@ pub mod document {
     }
 
     /// 3D 面（3DFACE）实体，主要用于边缘模型。
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct ThreeDFace {
         pub layer: String,
         pub vertices: [Point3; 4],
@@ -719,6 +1177,54 @@ pub mod document {
         pub invisible_edges: [bool; 4],
     }
 
+    /// 型（SHAPE）实体，引用 SHX 型文件中的命名图形，常见于电气符号与复杂线型。
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct Shape {
+        pub name: String,
+        pub insert: Point2,
+        pub size: f64,
+        /// 旋转角（弧度）。
+        pub rotation: f64,
+        /// 相对 X 比例（DXF 组码 41）。
+        pub width_factor: f64,
+        /// 倾斜角（弧度，DXF 组码 51）。
+        #[serde(default)]
+        pub oblique: f64,
+        /// 型文件所在 STYLE 记录的句柄，见 [`Document::shape_file_for`]。
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub style_handle: Option<String>,
+        pub layer: String,
+    }
+
+    impl Shape {
+        /// 未解析 SHX 图形时使用的近似外框：以插入点为左下角、边长为型高度的方框。
+        pub fn outline(&self) -> [Point2; 4] {
+            let (sin, cos) = self.rotation.sin_cos();
+            let width = self.size * self.width_factor;
+            let skew = self.size * self.oblique.tan();
+            [
+                DVec2::ZERO,
+                DVec2::new(width, 0.0),
+                DVec2::new(width + skew, self.size),
+                DVec2::new(skew, self.size),
+            ]
+            .map(|local| {
+                Point2::from_vec(
+                    self.insert.as_vec2()
+                        + DVec2::new(local.x * cos - local.y * sin, local.x * sin + local.y * cos),
+                )
+            })
+        }
+    }
+
+    /// STYLE 表中标记为型文件的记录（组码 70 位 1），SHAPE 通过它定位 SHX 文件。
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct ShapeFile {
+        pub handle: String,
+        /// 型文件名（组码 3），通常为 `*.shx`。
+        pub file: String,
+    }
+
     impl ThreeDFace {
         /// 计算未归一化的法向量。若顶点退化则返回 None。
         pub fn normal(&self) -> Option<Vector3> {
@@ -742,7 +1248,7 @@ pub mod document {
         }
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct RasterImageDisplayOptions {
         pub show_image: bool,
         pub show_border: bool,
@@ -791,7 +1297,7 @@ pub mod document {
         }
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct RasterImage {
         pub layer: String,
         pub image_def_handle: String,
@@ -805,7 +1311,7 @@ pub mod document {
         pub clip: Option<RasterImageClip>,
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct RasterImageDefinition {
         pub handle: String,
         pub name: Option<String>,
@@ -816,7 +1322,7 @@ pub mod document {
         pub resolved_path: Option<String>,
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub enum RasterImageClip {
         Rectangle {
             min: Point2,
@@ -840,7 +1346,7 @@ pub mod document {
         }
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct Wipeout {
         pub layer: String,
         pub insert: Point2,
@@ -902,7 +1408,7 @@ pub mod document {
         }
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct ImageDefReactor {
         pub handle: String,
         pub class_version: i32,
@@ -912,7 +1418,7 @@ pub mod document {
         pub image_handle: Option<String>,
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct ImageDictionaryEntry {
         pub name: String,
         pub image_def_handle: String,
@@ -920,7 +1426,7 @@ pub mod document {
         pub reactor_handle: Option<String>,
     }
 
-    #[derive(Debug, Default, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
     pub struct ImageDictionary {
         #[serde(default, skip_serializing_if = "Option::is_none")]
         pub handle: Option<String>,
@@ -936,7 +1442,7 @@ pub mod document {
         }
     }
 
-    #[derive(Debug, Default, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
     pub struct RasterImageVariables {
         #[serde(default, skip_serializing_if = "Option::is_none")]
         pub handle: Option<String>,
@@ -950,7 +1456,31 @@ pub mod document {
         pub units: Option<i16>,
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    /// WIPEOUTVARIABLES 对象：全局区域覆盖边框显示设置（WIPEOUTFRAME）。
+    #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct WipeoutVariables {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub handle: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub class_version: Option<i32>,
+        /// 0 = 不显示也不打印边框，1 = 显示并打印，2 = 显示但不打印。
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub frame: Option<i16>,
+    }
+
+    impl WipeoutVariables {
+        /// 是否显示区域覆盖边框；未记录时按 AutoCAD 默认值显示。
+        pub fn frame_visible(&self) -> bool {
+            self.frame.is_none_or(|frame| frame != 0)
+        }
+
+        /// 是否打印区域覆盖边框。
+        pub fn frame_plotted(&self) -> bool {
+            self.frame.is_none_or(|frame| frame == 1)
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct Attribute {
         pub tag: String,
         pub text: String,
@@ -974,7 +1504,7 @@ pub mod document {
         pub layer: String,
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct BlockReference {
         pub name: String,
         pub insert: Point2,
@@ -984,7 +1514,7 @@ pub mod document {
         pub layer: String,
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct BlockDefinition {
         pub name: String,
         pub base_point: Point2,
@@ -992,7 +1522,7 @@ pub mod document {
         pub attributes: Vec<AttributeDefinition>,
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct AttributeDefinition {
         pub tag: String,
         pub prompt: Option<String>,
@@ -1018,19 +1548,57 @@ pub mod document {
 
     #[derive(Debug, Default, Clone, Serialize, Deserialize)]
     pub struct Document {
-        layers: HashMap<String, Layer>,
-        entities: Vec<(EntityId, Entity)>,
+        layers: Tracked<HashMap<String, Layer>>,
+        entities: EntityList,
         next_entity_id: u64,
-        blocks: HashMap<String, BlockDefinition>,
+        blocks: Tracked<HashMap<String, BlockDefinition>>,
         #[serde(default, skip_serializing_if = "HashMap::is_empty")]
-        block_handles: HashMap<String, String>,
-        image_definitions: HashMap<String, RasterImageDefinition>,
+        block_handles: Tracked<HashMap<String, String>>,
+        image_definitions: Tracked<HashMap<String, RasterImageDefinition>>,
         #[serde(default)]
-        image_def_reactors: HashMap<String, ImageDefReactor>,
+        image_def_reactors: Tracked<HashMap<String, ImageDefReactor>>,
         #[serde(default, skip_serializing_if = "Option::is_none")]
-        image_dictionary: Option<ImageDictionary>,
+        image_dictionary: Tracked<Option<ImageDictionary>>,
         #[serde(default, skip_serializing_if = "Option::is_none")]
-        raster_image_variables: Option<RasterImageVariables>,
+        raster_image_variables: Tracked<Option<RasterImageVariables>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        wipeout_variables: Tracked<Option<WipeoutVariables>>,
+        /// 镜像块参照时文字是否随之镜像（`$MIRRTEXT`）；为 `false` 时保持可读。
+        #[serde(default)]
+        mirror_text: Tracked<bool>,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        xrefs: Tracked<HashMap<String, XRef>>,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        entity_properties: Tracked<HashMap<EntityId, EntityProperties>>,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        shape_files: Tracked<HashMap<String, ShapeFile>>,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        linetypes: Tracked<HashMap<String, Linetype>>,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        underlay_definitions: Tracked<HashMap<String, UnderlayDefinition>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        geodata: Tracked<Option<GeoData>>,
+        #[serde(default)]
+        tolerance: Tracked<Tolerance>,
+        #[serde(default)]
+        units: Tracked<Units>,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        dim_styles: Tracked<HashMap<String, DimStyle>>,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        associations: Tracked<HashMap<EntityId, Vec<PointAssociation>>>,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        layer_states: Tracked<HashMap<String, LayerState>>,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        draw_order: Tracked<HashMap<EntityId, DrawOrderKey>>,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        user_data: Tracked<HashMap<EntityId, UserData>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        provenance: Option<ProvenanceLog>,
+        /// 来源信息使用的时钟，未设置时取系统时间。
+        #[serde(skip)]
+        provenance_clock: Option<fn() -> u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        journal: Option<Journal>,
     }
 
     impl Document {
@@ -1042,9 +1610,14 @@ pub mod document {
 
         pub fn ensure_layer(&mut self, name: impl AsRef<str>) {
             let key = name.as_ref();
-            self.layers
-                .entry(key.to_string())
-                .or_insert_with(|| Layer::new(key));
+            if !self.layers.contains_key(key) {
+                self.layers.insert(key.to_string(), Layer::new(key));
+            }
+        }
+
+        /// 写入图层表记录，同名图层会被替换。
+        pub fn add_layer(&mut self, layer: Layer) {
+            self.layers.insert(layer.name.clone(), layer);
         }
 
         pub fn add_line(
@@ -1216,19 +1789,25 @@ pub mod document {
             rotation: f64,
             layer: impl Into<String>,
         ) -> EntityId {
-            let layer = layer.into();
-            self.ensure_layer(&layer);
+            self.add_justified_text(Text {
+                insert,
+                content: content.into(),
+                height,
+                rotation,
+                layer: layer.into(),
+                horizontal_align: 0,
+                vertical_align: 0,
+                alignment: None,
+                is_backward: false,
+                is_upside_down: false,
+            })
+        }
+
+        /// 添加带对齐方式、第二对齐点与生成标志的单行文字。
+        pub fn add_justified_text(&mut self, text: Text) -> EntityId {
+            self.ensure_layer(&text.layer);
             let id = self.next_id();
-            self.entities.push((
-                id,
-                Entity::Text(Text {
-                    insert,
-                    content: content.into(),
-                    height,
-                    rotation,
-                    layer,
-                }),
-            ));
+            self.entities.push((id, Entity::Text(text)));
             id
         }
 
@@ -1244,23 +1823,25 @@ pub mod document {
             style: Option<String>,
             layer: impl Into<String>,
         ) -> EntityId {
-            let layer = layer.into();
-            self.ensure_layer(&layer);
+            self.add_formatted_mtext(MText {
+                insert,
+                content: content.into(),
+                height,
+                reference_width,
+                direction,
+                attachment_point,
+                drawing_direction,
+                style,
+                layer: layer.into(),
+                runs: Vec::new(),
+            })
+        }
+
+        /// 添加带格式文字段的多行文字；`runs` 拼接后应与 `content` 一致。
+        pub fn add_formatted_mtext(&mut self, mtext: MText) -> EntityId {
+            self.ensure_layer(&mtext.layer);
             let id = self.next_id();
-            self.entities.push((
-                id,
-                Entity::MText(MText {
-                    insert,
-                    content: content.into(),
-                    height,
-                    reference_width,
-                    direction,
-                    attachment_point,
-                    drawing_direction,
-                    style,
-                    layer,
-                }),
-            ));
+            self.entities.push((id, Entity::MText(mtext)));
             id
         }
 
@@ -1279,31 +1860,21 @@ pub mod document {
             let resolved_attributes = if attributes.is_empty() {
                 self.block(&name)
                     .map(|definition| {
+                        let placement = attribute::reference_placement(
+                            &BlockReference {
+                                name: name.clone(),
+                                insert,
+                                scale,
+                                rotation,
+                                attributes: Vec::new(),
+                                layer: layer.clone(),
+                            },
+                            definition.base_point,
+                        );
                         definition
                             .attributes
                             .iter()
-                            .map(|def| Attribute {
-                                tag: def.tag.clone(),
-                                text: def.default_text.clone(),
-                                insert: def.insert,
-                                height: def.height,
-                                rotation: def.rotation,
-                                width_factor: def.width_factor,
-                                oblique: def.oblique,
-                                style: def.style.clone(),
-                                prompt: def.prompt.clone(),
-                                alignment: def.alignment,
-                                horizontal_align: def.horizontal_align,
-                                vertical_align: def.vertical_align,
-                                line_spacing_factor: def.line_spacing_factor,
-                                line_spacing_style: def.line_spacing_style,
-                                is_invisible: def.is_invisible,
-                                is_constant: def.is_constant,
-                                is_verify: def.is_verify,
-                                is_preset: def.is_preset,
-                                lock_position: def.lock_position,
-                                layer: def.layer.clone(),
-                            })
+                            .map(|def| def.instantiate(def.default_text.clone(), &placement))
                             .collect()
                     })
                     .unwrap_or_default()
@@ -1329,12 +1900,17 @@ pub mod document {
             id
         }
 
+        #[allow(clippy::too_many_arguments)]
         pub fn add_hatch(
             &mut self,
             pattern_name: impl Into<String>,
             is_solid: bool,
             loops: Vec<HatchLoop>,
             gradient: Option<HatchGradient>,
+            pattern_lines: Vec<HatchPatternLine>,
+            style: HatchStyle,
+            is_associative: bool,
+            seed_points: Vec<Point2>,
             layer: impl Into<String>,
         ) -> EntityId {
             let layer = layer.into();
@@ -1347,6 +1923,10 @@ pub mod document {
                     is_solid,
                     loops,
                     gradient,
+                    pattern_lines,
+                    style,
+                    is_associative,
+                    seed_points,
                     layer,
                 }),
             ));
@@ -1370,6 +1950,10 @@ pub mod document {
             rotation: f64,
             text_rotation: Option<f64>,
             oblique_angle: Option<f64>,
+            block_name: Option<String>,
+            style_name: Option<String>,
+            jog_point: Option<Point2>,
+            arc: Option<DimensionArc>,
             layer: impl Into<String>,
         ) -> EntityId {
             let layer = layer.into();
@@ -1392,6 +1976,10 @@ pub mod document {
                     rotation,
                     text_rotation,
                     oblique_angle,
+                    block_name,
+                    style_name,
+                    jog_point,
+                    arc,
                     layer,
                 }),
             ));
@@ -1476,6 +2064,86 @@ pub mod document {
             id
         }
 
+        #[allow(clippy::too_many_arguments)]
+        pub fn add_shape(
+            &mut self,
+            name: impl Into<String>,
+            insert: Point2,
+            size: f64,
+            rotation: f64,
+            width_factor: f64,
+            oblique: f64,
+            style_handle: Option<String>,
+            layer: impl Into<String>,
+        ) -> EntityId {
+            let layer = layer.into();
+            self.ensure_layer(&layer);
+            let id = self.next_id();
+            self.entities.push((
+                id,
+                Entity::Shape(Shape {
+                    name: name.into(),
+                    insert,
+                    size,
+                    rotation,
+                    width_factor,
+                    oblique,
+                    style_handle,
+                    layer,
+                }),
+            ));
+            id
+        }
+
+        pub fn add_shape_file(&mut self, shape_file: ShapeFile) {
+            self.shape_files
+                .insert(shape_file.handle.clone(), shape_file);
+        }
+
+        #[inline]
+        pub fn shape_file(&self, handle: &str) -> Option<&ShapeFile> {
+            self.shape_files.get(handle)
+        }
+
+        #[inline]
+        pub fn shape_files(&self) -> impl Iterator<Item = &ShapeFile> {
+            self.shape_files.values()
+        }
+
+        /// SHAPE 使用的型文件：优先按样式句柄查找，未记录句柄时仅在文档只有一个型文件时返回。
+        pub fn shape_file_for(&self, shape: &Shape) -> Option<&ShapeFile> {
+            match shape.style_handle.as_deref() {
+                Some(handle) => self.shape_files.get(handle),
+                None if self.shape_files.len() == 1 => self.shape_files.values().next(),
+                None => None,
+            }
+        }
+
+        /// DXF 的 SHAPE 不记录样式句柄；文档只有一个型文件时为所有未关联的 SHAPE 补全句柄。
+        pub fn bind_unstyled_shapes(&mut self) {
+            if self.shape_files.len() != 1 {
+                return;
+            }
+            let Some(handle) = self.shape_files.keys().next().cloned() else {
+                return;
+            };
+            self.record_modified_where(
+                |entity| matches!(entity, Entity::Shape(shape) if shape.style_handle.is_none()),
+            );
+            let entities = self.entities.iter_mut().map(|(_, entity)| entity).chain(
+                self.blocks
+                    .values_mut()
+                    .flat_map(|block| block.entities.iter_mut()),
+            );
+            for entity in entities {
+                if let Entity::Shape(shape) = entity
+                    && shape.style_handle.is_none()
+                {
+                    shape.style_handle = Some(handle.clone());
+                }
+            }
+        }
+
         fn resolve_block_content_name_from_handles(
             handles: &HashMap<String, String>,
             block: &mut MLeaderBlockContent,
@@ -1571,11 +2239,11 @@ pub mod document {
         }
 
         pub fn set_image_dictionary(&mut self, dictionary: ImageDictionary) {
-            self.image_dictionary = Some(dictionary);
+            *self.image_dictionary = Some(dictionary);
         }
 
         pub fn clear_image_dictionary(&mut self) {
-            self.image_dictionary = None;
+            *self.image_dictionary = None;
         }
 
         pub fn image_dictionary(&self) -> Option<&ImageDictionary> {
@@ -1583,17 +2251,61 @@ pub mod document {
         }
 
         pub fn set_raster_image_variables(&mut self, variables: RasterImageVariables) {
-            self.raster_image_variables = Some(variables);
+            *self.raster_image_variables = Some(variables);
         }
 
         pub fn clear_raster_image_variables(&mut self) {
-            self.raster_image_variables = None;
+            *self.raster_image_variables = None;
         }
 
         pub fn raster_image_variables(&self) -> Option<&RasterImageVariables> {
             self.raster_image_variables.as_ref()
         }
 
+        pub fn set_wipeout_variables(&mut self, variables: WipeoutVariables) {
+            *self.wipeout_variables = Some(variables);
+        }
+
+        pub fn clear_wipeout_variables(&mut self) {
+            *self.wipeout_variables = None;
+        }
+
+        pub fn wipeout_variables(&self) -> Option<&WipeoutVariables> {
+            self.wipeout_variables.as_ref()
+        }
+
+        /// 对应头变量 `$MIRRTEXT`：镜像的块参照中文字是否随之镜像。
+        pub fn mirror_text(&self) -> bool {
+            *self.mirror_text
+        }
+
+        pub fn set_mirror_text(&mut self, mirror: bool) {
+            *self.mirror_text = mirror;
+        }
+
+        /// 文档的几何比较容差，供边界追踪、合并等按坐标比较的操作使用。
+        pub fn tolerance(&self) -> Tolerance {
+            *self.tolerance
+        }
+
+        pub fn set_tolerance(&mut self, tolerance: Tolerance) {
+            *self.tolerance = tolerance;
+        }
+
+        /// 渲染时是否绘制区域覆盖边框，缺少 WIPEOUTVARIABLES 时默认绘制。
+        pub fn wipeout_frame_visible(&self) -> bool {
+            self.wipeout_variables
+                .as_ref()
+                .is_none_or(WipeoutVariables::frame_visible)
+        }
+
+        /// 打印输出时是否绘制区域覆盖边框。
+        pub fn wipeout_frame_plotted(&self) -> bool {
+            self.wipeout_variables
+                .as_ref()
+                .is_none_or(WipeoutVariables::frame_plotted)
+        }
+
         pub fn add_raster_image(
             &mut self,
             layer: impl Into<String>,
@@ -1708,24 +2420,8 @@ pub mod document {
                         layer,
                     )
                 }
-                Entity::Text(text) => self.add_text(
-                    text.insert,
-                    text.content,
-                    text.height,
-                    text.rotation,
-                    text.layer,
-                ),
-                Entity::MText(mtext) => self.add_mtext(
-                    mtext.insert,
-                    mtext.content,
-                    mtext.height,
-                    mtext.reference_width,
-                    mtext.direction,
-                    mtext.attachment_point,
-                    mtext.drawing_direction,
-                    mtext.style,
-                    mtext.layer,
-                ),
+                Entity::Text(text) => self.add_justified_text(text),
+                Entity::MText(mtext) => self.add_formatted_mtext(mtext),
                 Entity::BlockReference(reference) => self.add_block_reference(
                     reference.name,
                     reference.insert,
@@ -1739,6 +2435,10 @@ pub mod document {
                     hatch.is_solid,
                     hatch.loops,
                     hatch.gradient,
+                    hatch.pattern_lines,
+                    hatch.style,
+                    hatch.is_associative,
+                    hatch.seed_points,
                     hatch.layer,
                 ),
                 Entity::Dimension(dimension) => self.add_dimension(
@@ -1756,6 +2456,10 @@ pub mod document {
                     dimension.rotation,
                     dimension.text_rotation,
                     dimension.oblique_angle,
+                    dimension.block_name,
+                    dimension.style_name,
+                    dimension.jog_point,
+                    dimension.arc,
                     dimension.layer,
                 ),
                 Entity::Leader(leader) => self.add_leader(
@@ -1822,6 +2526,51 @@ pub mod document {
                 Entity::Face3D(face) => {
                     self.add_face3d(face.vertices, face.invisible_edges, face.layer)
                 }
+                Entity::Shape(shape) => {
+                    let Shape {
+                        name,
+                        insert,
+                        size,
+                        rotation,
+                        width_factor,
+                        oblique,
+                        style_handle,
+                        layer,
+                    } = shape;
+                    self.add_shape(
+                        name,
+                        insert,
+                        size,
+                        rotation,
+                        width_factor,
+                        oblique,
+                        style_handle,
+                        layer,
+                    )
+                }
+                Entity::Underlay(underlay) => {
+                    let Underlay {
+                        kind,
+                        definition_handle,
+                        insert,
+                        scale,
+                        rotation,
+                        display_options,
+                        clip_boundary,
+                        layer,
+                    } = underlay;
+                    self.add_underlay(
+                        kind,
+                        definition_handle,
+                        insert,
+                        scale,
+                        rotation,
+                        display_options,
+                        clip_boundary,
+                        layer,
+                    )
+                }
+                Entity::RevisionCloud(cloud) => self.add_revision_cloud(cloud),
             }
         }
 
@@ -1835,6 +2584,54 @@ pub mod document {
             self.entities.iter()
         }
 
+        /// 指定图层上的实体，按文档顺序返回；图层名区分大小写，与 [`Document::layer`] 一致。
+        ///
+        /// 由按图层的索引支持，不遍历其他图层的实体。
+        pub fn entities_on_layer(&self, layer: &str) -> impl Iterator<Item = &(EntityId, Entity)> {
+            let positions = self
+                .entities
+                .layer_index()
+                .get(layer)
+                .map_or(&[][..], Vec::as_slice);
+            positions.iter().map(|&position| &self.entities[position])
+        }
+
+        /// 所在图层满足 `keep` 的实体，按文档顺序返回；`keep` 对每个图层只调用一次。
+        pub fn entities_on_layers(
+            &self,
+            mut keep: impl FnMut(&str) -> bool,
+        ) -> impl Iterator<Item = &(EntityId, Entity)> {
+            let mut positions: Vec<usize> = self
+                .entities
+                .layer_index()
+                .iter()
+                .filter(|(layer, _)| keep(layer))
+                .flat_map(|(_, positions)| positions.iter().copied())
+                .collect();
+            positions.sort_unstable();
+            positions
+                .into_iter()
+                .map(|position| &self.entities[position])
+        }
+
+        /// 满足条件的实体，按文档顺序返回。只按图层筛选时应使用 [`Document::entities_on_layers`]。
+        pub fn entities_filtered<P>(
+            &self,
+            mut predicate: P,
+        ) -> impl Iterator<Item = &(EntityId, Entity)>
+        where
+            P: FnMut(EntityId, &Entity) -> bool,
+        {
+            self.entities
+                .iter()
+                .filter(move |(id, entity)| predicate(*id, entity))
+        }
+
+        /// 显示中（打开且未冻结）的图层上的实体；图层表中不存在的图层视为可见。
+        pub fn visible_entities(&self) -> impl Iterator<Item = &(EntityId, Entity)> {
+            self.entities_on_layers(|layer| self.layer(layer).is_none_or(Layer::is_displayed))
+        }
+
         pub fn add_block_definition(&mut self, definition: BlockDefinition) {
             self.add_block_definition_with_handle(definition, None, None);
         }
@@ -1865,6 +2662,7 @@ pub mod document {
                 self.update_mleader_block_names();
             }
             self.blocks.insert(name, definition);
+            self.entities.invalidate_extents();
         }
 
         #[inline]
@@ -1872,49 +2670,113 @@ pub mod document {
             self.blocks.get(name)
         }
 
+        /// 标注关联的匿名块；未保留 `*D` 块或标注未引用块时返回 `None`。
+        pub fn dimension_block(&self, dimension: &Dimension) -> Option<&BlockDefinition> {
+            dimension
+                .block_name
+                .as_deref()
+                .and_then(|name| self.blocks.get(name))
+        }
+
         #[inline]
         pub fn block_name_by_handle(&self, handle: &str) -> Option<&str> {
             self.block_handles.get(handle).map(|name| name.as_str())
         }
 
+        /// 块及块记录句柄到块名的映射，写出 DXF 时据此保留原句柄。
+        pub fn block_handles(&self) -> impl Iterator<Item = (&str, &str)> {
+            self.block_handles
+                .iter()
+                .map(|(handle, name)| (handle.as_str(), name.as_str()))
+        }
+
         #[inline]
         pub fn blocks(&self) -> impl Iterator<Item = &BlockDefinition> {
             self.blocks.values()
         }
 
+        /// 按编号查找实体，O(1)。
         #[inline]
         pub fn entity(&self, id: EntityId) -> Option<&Entity> {
-            self.entities.iter().find_map(|(entity_id, entity)| {
-                if entity_id.get() == id.get() {
-                    Some(entity)
-                } else {
-                    None
-                }
-            })
+            self.entities.get(id)
         }
 
+        /// 原地修改实体；改到新图层时需自行调用 [`Document::ensure_layer`]，
+        /// 需要自动补建图层时使用 [`Document::replace_entity`]。
+        pub fn entity_mut(&mut self, id: EntityId) -> Option<&mut Entity> {
+            self.entities.get(id)?;
+            self.record_modified(id);
+            self.entities.get_mut(id)
+        }
+
+        /// 以新数据替换实体，保留编号与特性覆盖；返回旧数据，实体不存在时返回 `None`。
+        pub fn replace_entity(&mut self, id: EntityId, entity: Entity) -> Option<Entity> {
+            self.entities.get(id)?;
+            self.ensure_layer(entity.layer_name());
+            self.record_modified(id);
+            let slot = self.entities.get_mut(id)?;
+            Some(std::mem::replace(slot, entity))
+        }
+
+        /// 删除实体及其特性覆盖与用户数据，返回被删除的实体；编号不会被复用。
+        ///
+        /// 其后实体的位置索引需要前移，单次删除为 O(n)。
+        pub fn remove_entity(&mut self, id: EntityId) -> Option<Entity> {
+            self.entities.get(id)?;
+            self.record_removed(id);
+            let entity = self.entities.remove(id)?;
+            self.entity_properties.discard(&id);
+            self.associations.discard(&id);
+            self.draw_order.discard(&id);
+            self.user_data.discard(&id);
+            Some(entity)
+        }
+
+        /// 实体范围，块参照包含所引用块的内容。
         #[inline]
         pub fn entity_bounds(&self, id: EntityId) -> Option<Bounds2D> {
-            self.entity(id).and_then(Entity::bounds)
+            self.entity(id)
+                .and_then(|entity| self.entity_extent(entity))
         }
 
+        /// 计算任意实体（包括块定义内的实体）在当前文档中的范围：块参照按块定义内容经
+        /// 插入变换得到，嵌套块逐层展开；其余实体与 [`Entity::bounds`] 相同。
+        pub fn entity_extent(&self, entity: &Entity) -> Option<Bounds2D> {
+            ExtentResolver::new(self).entity(entity)
+        }
+
+        /// 范围与 `area` 相交的模型空间实体，按文档顺序返回。
+        ///
+        /// 由空间索引支持，索引在首次查询时建立并随编辑更新；判断基于实体的轴对齐范围，
+        /// 结果是候选集，精确命中需再按几何判断。
+        pub fn query_rect(&self, area: &Bounds2D) -> Vec<EntityId> {
+            let mut extents = ExtentResolver::new(self);
+            self.entities
+                .query_rect(area, |entity| extents.entity(entity))
+        }
+
+        /// 范围到 `point` 的距离不超过 `tolerance` 的模型空间实体，按文档顺序返回。
+        pub fn query_point(&self, point: Point2, tolerance: f64) -> Vec<EntityId> {
+            let tolerance = tolerance.abs();
+            self.query_rect(&Bounds2D::new(
+                Point2::new(point.x() - tolerance, point.y() - tolerance),
+                Point2::new(point.x() + tolerance, point.y() + tolerance),
+            ))
+        }
+
+        /// 模型空间实体的整体范围（块参照包含块内容）；结果被缓存，并随实体增删改增量更新。
         pub fn bounds(&self) -> Option<Bounds2D> {
-            let mut bounds = Bounds2D::empty();
-            let mut has = false;
-            for (_, entity) in &self.entities {
-                if let Some(entity_bounds) = entity.bounds() {
-                    bounds.include_bounds(&entity_bounds);
-                    has = true;
-                }
-            }
-            if has { Some(bounds) } else { None }
+            let mut extents = ExtentResolver::new(self);
+            self.entities.bounds(|entity| extents.entity(entity))
         }
 
         #[inline]
+        /// 为新建实体分配编号，并在记录来源信息或修改日志时登记。
         fn next_id(&mut self) -> EntityId {
-            let id = self.next_entity_id;
+            let id = EntityId(self.next_entity_id);
             self.next_entity_id += 1;
-            EntityId(id)
+            self.record_created(id);
+            id
         }
     }
 
@@ -2058,47 +2920,24 @@ pub mod document {
     }
 
     fn polyline_segment_bounds(start: Point2, end: Point2, bulge: f64, bounds: &mut Bounds2D) {
-        if bulge.abs() <= 1e-9 {
+        let Some(bulge_arc) = BulgeArc::new(start.as_vec2(), end.as_vec2(), bulge) else {
             return;
-        }
-
-        let start_vec = start.as_vec2();
-        let end_vec = end.as_vec2();
-        let chord = end_vec - start_vec;
-        let chord_len = chord.length();
-        if chord_len <= f64::EPSILON {
-            return;
-        }
-
-        let theta = 4.0 * bulge.atan();
-        if theta.abs() <= 1e-9 {
-            return;
-        }
-
-        let half_theta = theta / 2.0;
-        let sin_half = half_theta.sin();
-        if sin_half.abs() <= 1e-9 {
-            return;
-        }
-
-        let radius = chord_len / (2.0 * sin_half);
-        let midpoint = (start_vec + end_vec) * 0.5;
-        let perp = DVec2::new(-chord.y, chord.x);
-        if perp.length_squared() <= f64::EPSILON {
-            return;
-        }
-        let perp_dir = perp.normalize();
-        let sagitta = bulge * chord_len / 2.0;
-        let center_vec = midpoint + perp_dir * sagitta;
-        let center = Point2::from_vec(center_vec);
-
-        let start_dir = start_vec - center_vec;
-        let start_angle = start_dir.y.atan2(start_dir.x);
-        let end_angle = start_angle + theta;
-
+        };
+        // Arc 按逆时针方向，顺时针凸度段从终点角起算
+        let (start_angle, end_angle) = if bulge_arc.sweep >= 0.0 {
+            (
+                bulge_arc.start_angle,
+                bulge_arc.start_angle + bulge_arc.sweep,
+            )
+        } else {
+            (
+                bulge_arc.start_angle + bulge_arc.sweep,
+                bulge_arc.start_angle,
+            )
+        };
         let arc = Arc {
-            center,
-            radius: radius.abs(),
+            center: Point2::from_vec(bulge_arc.center),
+            radius: bulge_arc.radius,
             start_angle,
             end_angle,
             layer: String::new(),
@@ -2270,13 +3109,254 @@ pub mod document {
                 _ => panic!("expected block reference entity"),
             }
 
+            // 旋转 180° 的 "Hello" 向左下延伸 5 × 2.5 × 0.6 与一个字高
             let bounds = doc.bounds().expect("document bounds should exist");
-            assert!((bounds.min().x() - 0.0).abs() < 1e-9);
-            assert!((bounds.min().y() - 0.0).abs() < 1e-9);
+            assert!((bounds.min().x() + 6.5).abs() < 1e-9);
+            assert!((bounds.min().y() + 1.5).abs() < 1e-9);
             assert!((bounds.max().x() - 19.0).abs() < 1e-9);
             assert!((bounds.max().y() - 10.0).abs() < 1e-9);
         }
 
+        #[test]
+        fn replace_and_remove_entities_keep_ids_stable() {
+            let mut doc = Document::new();
+            let line = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+            let circle = doc.add_circle(Point2::new(5.0, 5.0), 1.0, "0");
+            doc.set_entity_properties(
+                line,
+                EntityProperties {
+                    color: ObjectColor::Index(1),
+                    ..EntityProperties::default()
+                },
+            );
+
+            let replacement = Entity::Circle(Circle {
+                center: Point2::new(2.0, 2.0),
+                radius: 3.0,
+                layer: "NEW".to_string(),
+            });
+            let previous = doc.replace_entity(line, replacement).expect("实体应存在");
+            assert!(matches!(previous, Entity::Line(_)));
+            assert!(matches!(doc.entity(line), Some(Entity::Circle(_))));
+            assert!(doc.layer("NEW").is_some());
+            assert!(doc.entity_properties(line).is_some());
+
+            assert!(doc.remove_entity(line).is_some());
+            assert!(doc.entity(line).is_none());
+            assert!(doc.entity_properties(line).is_none());
+            assert!(doc.remove_entity(line).is_none());
+            assert!(
+                doc.replace_entity(line, doc.entity(circle).unwrap().clone())
+                    .is_none()
+            );
+
+            // 删除后新增的实体不复用旧编号
+            let next = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 1.0), "0");
+            assert!(next.get() > circle.get());
+
+            if let Some(Entity::Circle(circle)) = doc.entity_mut(circle) {
+                circle.radius = 4.0;
+            }
+            assert!(matches!(doc.entity(circle), Some(Entity::Circle(c)) if c.radius == 4.0));
+            assert!(doc.entity_mut(line).is_none());
+        }
+
+        #[test]
+        fn spatial_queries_follow_edits() {
+            let mut doc = Document::new();
+            let left = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 1.0), "0");
+            let right = doc.add_circle(Point2::new(10.0, 0.0), 1.0, "0");
+            let window = Bounds2D::new(Point2::new(-1.0, -1.0), Point2::new(2.0, 2.0));
+            assert_eq!(doc.query_rect(&window), vec![left]);
+            assert_eq!(doc.query_point(Point2::new(11.5, 0.0), 0.5), vec![right]);
+            assert!(doc.query_point(Point2::new(5.0, 0.0), 0.5).is_empty());
+
+            // 索引建立后的追加、修改与删除都应反映在查询结果中
+            let added = doc.add_arc(Point2::new(0.5, 0.5), 0.25, 0.0, PI, "0");
+            if let Some(Entity::Circle(circle)) = doc.entity_mut(right) {
+                circle.center = Point2::new(0.0, 0.0);
+            }
+            assert_eq!(doc.query_rect(&window), vec![left, right, added]);
+            doc.remove_entity(left);
+            assert_eq!(doc.query_rect(&window), vec![right, added]);
+            assert!(doc.query_point(Point2::new(10.0, 0.0), 0.1).is_empty());
+        }
+
+        #[test]
+        fn first_spatial_query_after_mutable_access_lists_entity_once() {
+            let mut doc = Document::new();
+            let line = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 1.0), "0");
+            let circle = doc.add_circle(Point2::new(0.5, 0.5), 0.25, "0");
+            let far = doc.add_line(Point2::new(-10.0, -10.0), Point2::new(10.0, 10.0), "0");
+            // 整体范围已缓存而空间索引尚未建立时借出实体，首次查询才建立索引
+            assert!(doc.bounds().is_some());
+            if let Some(Entity::Circle(circle)) = doc.entity_mut(circle) {
+                circle.radius = 0.5;
+            }
+            let window = Bounds2D::new(Point2::new(-1.0, -1.0), Point2::new(2.0, 2.0));
+            assert_eq!(doc.query_rect(&window), vec![line, circle, far]);
+            doc.add_line(Point2::new(5.0, 5.0), Point2::new(6.0, 6.0), "0");
+            assert_eq!(doc.query_rect(&window), vec![line, circle, far]);
+        }
+
+        #[test]
+        fn text_bounds_estimate_extent_from_content() {
+            let mut doc = Document::new();
+            let extent = |doc: &Document, id| {
+                let bounds = doc.entity_bounds(id).unwrap();
+                [bounds.min(), bounds.max()]
+                    .map(|point| [point.x(), point.y()].map(|value| (value * 1e9).round() / 1e9))
+            };
+            let plain = doc.add_text(Point2::new(10.0, 0.0), "ABCDE", 2.0, 0.0, "0");
+            assert_eq!(extent(&doc, plain), [[10.0, 0.0], [16.0, 2.0]]);
+            let rotated = doc.add_text(Point2::new(0.0, 0.0), "AB", 1.0, FRAC_PI_2, "0");
+            assert_eq!(extent(&doc, rotated), [[-1.0, 0.0], [0.0, 1.2]]);
+
+            // 左上附着，两段文字按最长段落计宽
+            let paragraphs = doc.add_mtext(
+                Point2::new(0.0, 10.0),
+                "AB\nABCD",
+                1.0,
+                None,
+                Vector2::new(1.0, 0.0),
+                1,
+                1,
+                None,
+                "0",
+            );
+            let height = 1.0 + MTEXT_LINE_SPACING;
+            assert_eq!(
+                extent(&doc, paragraphs),
+                [[0.0, ((10.0 - height) * 1e9).round() / 1e9], [2.4, 10.0]]
+            );
+            // 正中附着，超出参照宽度的段落折为两行
+            let wrapped = doc.add_mtext(
+                Point2::new(0.0, 0.0),
+                "ABCDEFGHIJ",
+                1.0,
+                Some(3.0),
+                Vector2::new(1.0, 0.0),
+                5,
+                1,
+                None,
+                "0",
+            );
+            let half = ((height / 2.0) * 1e9).round() / 1e9;
+            assert_eq!(extent(&doc, wrapped), [[-1.5, -half], [1.5, half]]);
+        }
+
+        #[test]
+        fn bulged_segment_bounds_follow_the_arc() {
+            let extent = |bulge: f64| {
+                let mut bounds = Bounds2D::empty();
+                polyline_segment_bounds(
+                    Point2::new(0.0, 0.0),
+                    Point2::new(2.0, 0.0),
+                    bulge,
+                    &mut bounds,
+                );
+                let (min, max) = (bounds.min(), bounds.max());
+                [min.x(), min.y(), max.x(), max.y()]
+            };
+            let close = |actual: [f64; 4], expected: [f64; 4]| {
+                actual
+                    .iter()
+                    .zip(expected)
+                    .all(|(a, b)| (a - b).abs() < 1e-9)
+            };
+            // 半圆：圆心在弦中点，正凸度向弦右侧鼓出
+            assert!(close(extent(1.0), [0.0, -1.0, 2.0, 0.0]));
+            assert!(close(extent(-1.0), [0.0, 0.0, 2.0, 1.0]));
+            // 直线段不扩展范围
+            assert!(extent(0.0).iter().all(|value| value.is_infinite()));
+        }
+
+        #[test]
+        fn cached_bounds_follow_edits() {
+            let mut doc = Document::new();
+            assert!(doc.bounds().is_none());
+            let edge = doc.add_line(Point2::new(0.0, 0.0), Point2::new(10.0, 10.0), "0");
+            let inner = doc.add_circle(Point2::new(5.0, 5.0), 1.0, "0");
+            let extent = |doc: &Document| {
+                let bounds = doc.bounds().unwrap();
+                (
+                    bounds.min().x(),
+                    bounds.min().y(),
+                    bounds.max().x(),
+                    bounds.max().y(),
+                )
+            };
+            assert_eq!(extent(&doc), (0.0, 0.0, 10.0, 10.0));
+
+            // 内部实体被修改后扩展范围，无需全量重算
+            if let Some(Entity::Circle(circle)) = doc.entity_mut(inner) {
+                circle.radius = 8.0;
+            }
+            assert_eq!(extent(&doc), (-3.0, -3.0, 13.0, 13.0));
+            let far = doc.add_line(Point2::new(20.0, 0.0), Point2::new(21.0, 1.0), "0");
+            assert_eq!(extent(&doc), (-3.0, -3.0, 21.0, 13.0));
+
+            // 删除触及边界的实体会触发重算
+            doc.remove_entity(inner);
+            assert_eq!(extent(&doc), (0.0, 0.0, 21.0, 10.0));
+            doc.remove_entity(edge);
+            assert_eq!(extent(&doc), (20.0, 0.0, 21.0, 1.0));
+            doc.remove_entity(far);
+            assert!(doc.bounds().is_none());
+        }
+
+        #[test]
+        fn layer_queries_follow_edits() {
+            let mut doc = Document::new();
+            let a = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "WALL");
+            let b = doc.add_circle(Point2::new(0.0, 0.0), 1.0, "DOOR");
+            let ids = |items: Vec<&(EntityId, Entity)>| -> Vec<EntityId> {
+                items.into_iter().map(|(id, _)| *id).collect()
+            };
+            assert_eq!(ids(doc.entities_on_layer("WALL").collect()), vec![a]);
+
+            // 建立索引后追加、改层与删除都应反映在查询结果中
+            let c = doc.add_arc(Point2::new(0.0, 0.0), 1.0, 0.0, 1.0, "WALL");
+            assert_eq!(ids(doc.entities_on_layer("WALL").collect()), vec![a, c]);
+            doc.entity_mut(b).unwrap().set_layer_name("WALL");
+            assert_eq!(ids(doc.entities_on_layer("WALL").collect()), vec![a, b, c]);
+            doc.remove_entity(a);
+            assert_eq!(ids(doc.entities_on_layer("WALL").collect()), vec![b, c]);
+            assert_eq!(doc.entities_on_layer("DOOR").count(), 0);
+            assert_eq!(doc.entities_on_layer("MISSING").count(), 0);
+
+            let d = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 1.0), "HIDDEN");
+            doc.add_layer(Layer {
+                is_visible: false,
+                ..Layer::new("WALL")
+            });
+            assert_eq!(ids(doc.visible_entities().collect()), vec![d]);
+            let circles = doc.entities_filtered(|_, entity| matches!(entity, Entity::Circle(_)));
+            assert_eq!(ids(circles.collect()), vec![b]);
+        }
+
+        #[test]
+        fn entity_lookup_index_follows_removals() {
+            let mut doc = Document::new();
+            let ids: Vec<EntityId> = (0..5)
+                .map(|i| doc.add_circle(Point2::new(i as f64, 0.0), 1.0, "0"))
+                .collect();
+            doc.remove_entity(ids[1]);
+            doc.remove_entity(ids[3]);
+            let appended = doc.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+
+            for (position, id) in [ids[0], ids[2], ids[4]].into_iter().enumerate() {
+                let Some(Entity::Circle(circle)) = doc.entity(id) else {
+                    panic!("实体 {} 应存在", id.get());
+                };
+                assert_eq!(circle.center.x(), [0.0, 2.0, 4.0][position]);
+            }
+            assert!(doc.entity(ids[1]).is_none() && doc.entity(ids[3]).is_none());
+            assert!(matches!(doc.entity(appended), Some(Entity::Line(_))));
+            let order: Vec<_> = doc.entities().map(|(id, _)| *id).collect();
+            assert_eq!(order, vec![ids[0], ids[2], ids[4], appended]);
+        }
+
         #[test]
         fn three_d_face_normal_is_cross_product() {
             let face = ThreeDFace {
@@ -2310,5 +3390,75 @@ pub mod document {
             };
             assert!(face.normal().is_none());
         }
+
+        #[test]
+        fn shapes_bind_to_single_shape_file() {
+            let mut doc = Document::new();
+            let id = doc.add_shape(
+                "BOX",
+                Point2::new(1.0, 1.0),
+                2.0,
+                FRAC_PI_2,
+                1.0,
+                0.0,
+                None,
+                "SYMBOLS",
+            );
+            let Some(Entity::Shape(shape)) = doc.entity(id) else {
+                panic!("shape missing");
+            };
+            let outline = shape.outline();
+            assert!((outline[1].x() - 1.0).abs() < 1e-9 && (outline[1].y() - 3.0).abs() < 1e-9);
+            assert!((outline[2].x() + 1.0).abs() < 1e-9 && (outline[2].y() - 3.0).abs() < 1e-9);
+
+            doc.add_shape_file(ShapeFile {
+                handle: "1A".to_string(),
+                file: "symbols.shx".to_string(),
+            });
+            let ((), changes) = doc.capture_changes(|doc| doc.bind_unstyled_shapes());
+            assert_eq!(changes.len(), 1, "补全句柄应记入修改记录");
+            let Some(Entity::Shape(shape)) = doc.entity(id) else {
+                panic!("shape missing");
+            };
+            assert_eq!(shape.style_handle.as_deref(), Some("1A"));
+            let mut unbound = shape.clone();
+            unbound.style_handle = None;
+
+            doc.add_shape_file(ShapeFile {
+                handle: "1B".to_string(),
+                file: "other.shx".to_string(),
+            });
+            assert!(
+                doc.shape_file_for(&unbound).is_none(),
+                "多个型文件时不应猜测"
+            );
+        }
+
+        #[test]
+        fn text_justification_resolves_anchor_and_baseline() {
+            let mut text = Text {
+                insert: Point2::new(0.0, 0.0),
+                content: "ABCD".to_string(),
+                height: 2.0,
+                rotation: 0.0,
+                layer: "0".to_string(),
+                horizontal_align: 2,
+                vertical_align: 3,
+                alignment: Some(Point2::new(10.0, 5.0)),
+                is_backward: false,
+                is_upside_down: false,
+            };
+            assert_eq!(text.anchor(), Point2::new(10.0, 5.0));
+            let start = text.baseline_start(4.0);
+            assert!((start.x() - 6.0).abs() < 1e-9 && (start.y() - 3.0).abs() < 1e-9);
+
+            text.horizontal_align = 3;
+            text.vertical_align = 0;
+            assert_eq!(text.anchor(), Point2::new(0.0, 0.0));
+            assert_eq!(text.baseline_start(4.0), Point2::new(0.0, 0.0));
+            assert!((text.effective_rotation() - 0.5_f64.atan()).abs() < 1e-12);
+        }
     }
 }
+
+pub mod tessellate;
@@ -0,0 +1,426 @@
+//! 曲线离散：把圆弧、圆、椭圆、带凸度的多段线、样条与填充边界边转为折线点列，
+//! 供 SVG/PDF/PNG 导出与 GPU 绘制共用。
+//!
+//! 精度由弦高容差与最大圆心角共同控制，取两者中更密的一个；不大于零的一项不参与，两项都不参与时使用默认精度。
+//! 点列首尾为曲线的实际端点，闭合曲线的末点与首点重合。
+
+use std::f64::consts::TAU;
+
+use glam::DVec2;
+
+use crate::document::{
+    Entity, HatchEdge, HatchLoop, PolylineVertex, Spline, ccw_sweep, edge_sweep,
+};
+use crate::geometry::{BulgeArc, Point2, Vector2};
+
+/// 单条曲线的最大段数，防止极小容差下点数失控。
+const MAX_SEGMENTS: usize = 4096;
+
+/// 离散精度。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TessellateOptions {
+    /// 弦与曲线间的最大距离（弦高），不大于零时不按弦高限制。
+    pub chord_tolerance: f64,
+    /// 每段对应的最大圆心角（弧度），样条为相邻切向的最大夹角；不大于零时不按角度限制。
+    pub max_angle: f64,
+}
+
+impl Default for TessellateOptions {
+    fn default() -> Self {
+        Self {
+            chord_tolerance: 0.01,
+            max_angle: TAU / 64.0,
+        }
+    }
+}
+
+impl TessellateOptions {
+    /// 只按整圆段数控制，等同于每段圆心角为 `TAU / segments_per_turn`。
+    pub fn per_turn(segments_per_turn: usize) -> Self {
+        Self {
+            chord_tolerance: 0.0,
+            max_angle: TAU / segments_per_turn.max(4) as f64,
+        }
+    }
+
+    /// 半径为 `radius`、圆心角为 `sweep` 的圆弧所需段数，至少两段以保留中点。
+    fn segments(&self, radius: f64, sweep: f64) -> usize {
+        let mut step = f64::INFINITY;
+        if self.max_angle > 0.0 {
+            step = step.min(self.max_angle);
+        }
+        if self.chord_tolerance > 0.0 && self.chord_tolerance < radius {
+            step = step.min(2.0 * (1.0 - self.chord_tolerance / radius).acos());
+        }
+        if !step.is_finite() {
+            return Self::default().segments(radius, sweep);
+        }
+        // 扣除浮点误差，整圆按 TAU / n 控制时正好 n 段
+        ((sweep.abs() / step - 1e-9).ceil() as usize).clamp(2, MAX_SEGMENTS)
+    }
+}
+
+/// 离散直线、圆、圆弧、椭圆、多段线与样条；其他实体返回 `None`。
+pub fn curve(entity: &Entity, options: &TessellateOptions) -> Option<Vec<Point2>> {
+    match entity {
+        Entity::Line(line) => Some(vec![line.start, line.end]),
+        Entity::Circle(circle) => Some(arc(circle.center, circle.radius, 0.0, TAU, options)),
+        Entity::Arc(arc_entity) => Some(arc(
+            arc_entity.center,
+            arc_entity.radius,
+            arc_entity.start_angle,
+            ccw_sweep(arc_entity.start_angle, arc_entity.end_angle),
+            options,
+        )),
+        Entity::Ellipse(ellipse_entity) => Some(ellipse(
+            ellipse_entity.center,
+            ellipse_entity.major_axis,
+            ellipse_entity.ratio,
+            ellipse_entity.start_parameter,
+            ccw_sweep(ellipse_entity.start_parameter, ellipse_entity.end_parameter),
+            options,
+        )),
+        Entity::Polyline(polyline_entity) => Some(polyline(
+            &polyline_entity.vertices,
+            polyline_entity.is_closed,
+            options,
+        )),
+        Entity::Spline(spline_entity) => Some(spline(spline_entity, options)),
+        _ => None,
+    }
+}
+
+/// 圆弧：从 `start_angle` 转过 `sweep`（正值逆时针）。
+pub fn arc(
+    center: Point2,
+    radius: f64,
+    start_angle: f64,
+    sweep: f64,
+    options: &TessellateOptions,
+) -> Vec<Point2> {
+    let segments = options.segments(radius, sweep);
+    (0..=segments)
+        .map(|i| {
+            let angle = start_angle + sweep * i as f64 / segments as f64;
+            Point2::from_vec(center.as_vec2() + DVec2::from_angle(angle) * radius)
+        })
+        .collect()
+}
+
+/// 椭圆弧：从参数 `start` 转过 `sweep`，按长轴半径估计段数（弦高不超过同半径的圆）。
+pub fn ellipse(
+    center: Point2,
+    major_axis: Vector2,
+    ratio: f64,
+    start: f64,
+    sweep: f64,
+    options: &TessellateOptions,
+) -> Vec<Point2> {
+    let major = major_axis.as_vec2();
+    let minor = major.perp() * ratio;
+    let segments = options.segments(major.length().max(minor.length()), sweep);
+    (0..=segments)
+        .map(|i| {
+            let t = start + sweep * i as f64 / segments as f64;
+            Point2::from_vec(center.as_vec2() + major * t.cos() + minor * t.sin())
+        })
+        .collect()
+}
+
+/// 带凸度的线段，凸度为零时只有两个端点。
+pub fn bulge(start: Point2, end: Point2, bulge: f64, options: &TessellateOptions) -> Vec<Point2> {
+    let Some(bulge_arc) = BulgeArc::new(start.as_vec2(), end.as_vec2(), bulge) else {
+        return vec![start, end];
+    };
+    let mut points = arc(
+        Point2::from_vec(bulge_arc.center),
+        bulge_arc.radius,
+        bulge_arc.start_angle,
+        bulge_arc.sweep,
+        options,
+    );
+    // 端点使用原始坐标，避免累积误差
+    if let Some(last) = points.last_mut() {
+        *last = end;
+    }
+    points
+}
+
+/// 多段线顶点序列，相邻段的公共端点只出现一次。
+pub fn polyline(
+    vertices: &[PolylineVertex],
+    is_closed: bool,
+    options: &TessellateOptions,
+) -> Vec<Point2> {
+    let count = vertices.len();
+    let segment_count = if is_closed {
+        count
+    } else {
+        count.saturating_sub(1)
+    };
+    let mut points = Vec::new();
+    for index in 0..segment_count {
+        let current = &vertices[index];
+        let next = &vertices[(index + 1) % count];
+        append(
+            &mut points,
+            bulge(current.position, next.position, current.bulge, options),
+        );
+    }
+    if points.is_empty()
+        && let Some(vertex) = vertices.first()
+    {
+        points.push(vertex.position);
+    }
+    points
+}
+
+/// 样条：按节点区间自适应二分，只有拟合点时按插值曲线离散。
+pub fn spline(spline: &Spline, options: &TessellateOptions) -> Vec<Point2> {
+    if options.chord_tolerance <= 0.0 && options.max_angle <= 0.0 {
+        return self::spline(spline, &TessellateOptions::default());
+    }
+    spline.tessellate_within(options.chord_tolerance, options.max_angle)
+}
+
+/// 填充边界的一条边；边界引用没有几何，返回空列表。
+pub fn hatch_edge(edge: &HatchEdge, options: &TessellateOptions) -> Vec<Point2> {
+    match edge {
+        HatchEdge::Line { start, end } => vec![*start, *end],
+        HatchEdge::PolylineSegment {
+            start,
+            end,
+            bulge: value,
+        } => bulge(*start, *end, *value, options),
+        HatchEdge::Arc {
+            center,
+            radius,
+            start_angle,
+            end_angle,
+            is_counter_clockwise,
+        } => {
+            let (start, sweep) = edge_sweep(*start_angle, *end_angle, *is_counter_clockwise);
+            arc(*center, *radius, start, sweep, options)
+        }
+        HatchEdge::Ellipse {
+            center,
+            major_axis,
+            minor_ratio,
+            start_angle,
+            end_angle,
+            is_counter_clockwise,
+        } => {
+            let (start, sweep) = edge_sweep(*start_angle, *end_angle, *is_counter_clockwise);
+            ellipse(*center, *major_axis, *minor_ratio, start, sweep, options)
+        }
+        HatchEdge::Spline {
+            control_points,
+            fit_points,
+            knot_values,
+            degree,
+            is_rational,
+            is_periodic,
+        } => spline(
+            &Spline {
+                degree: *degree,
+                is_rational: *is_rational,
+                is_closed: false,
+                is_periodic: *is_periodic,
+                control_points: control_points.clone(),
+                fit_points: fit_points.clone(),
+                knot_values: knot_values.clone(),
+                weights: Vec::new(),
+                start_tangent: None,
+                end_tangent: None,
+                layer: String::new(),
+            },
+            options,
+        ),
+        HatchEdge::BoundaryReference { .. } => Vec::new(),
+    }
+}
+
+/// 填充边界环：各边首尾相接，末点与首点重合时去掉末点。
+pub fn hatch_loop(loop_path: &HatchLoop, options: &TessellateOptions) -> Vec<Point2> {
+    let mut points = Vec::new();
+    for edge in &loop_path.edges {
+        append(&mut points, hatch_edge(edge, options));
+    }
+    if points.len() > 2
+        && let (Some(first), Some(last)) = (points.first(), points.last())
+        && close(*first, *last)
+    {
+        points.pop();
+    }
+    points
+}
+
+fn append(points: &mut Vec<Point2>, mut next: Vec<Point2>) {
+    if let (Some(last), Some(first)) = (points.last(), next.first())
+        && close(*last, *first)
+    {
+        next.remove(0);
+    }
+    points.extend(next);
+}
+
+fn close(a: Point2, b: Point2) -> bool {
+    a.as_vec2().distance_squared(b.as_vec2()) <= 1e-18
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use super::*;
+    use crate::document::{Circle, Polyline};
+
+    fn max_chord_error(points: &[Point2], center: DVec2, radius: f64) -> f64 {
+        points
+            .windows(2)
+            .map(|pair| radius - ((pair[0].as_vec2() + pair[1].as_vec2()) / 2.0 - center).length())
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn arcs_respect_chord_tolerance_and_max_angle() {
+        let circle = Entity::Circle(Circle {
+            center: Point2::new(1.0, 1.0),
+            radius: 10.0,
+            layer: "0".to_string(),
+        });
+        let coarse = TessellateOptions {
+            chord_tolerance: 0.1,
+            max_angle: 0.0,
+        };
+        let points = curve(&circle, &coarse).unwrap();
+        assert!(max_chord_error(&points, DVec2::ONE, 10.0) <= 0.1);
+        assert!(
+            points
+                .first()
+                .unwrap()
+                .as_vec2()
+                .distance(points.last().unwrap().as_vec2())
+                < 1e-9
+        );
+        let fine = TessellateOptions {
+            chord_tolerance: 0.001,
+            max_angle: 0.0,
+        };
+        assert!(curve(&circle, &fine).unwrap().len() > points.len());
+
+        // 整圆 64 段
+        assert_eq!(
+            curve(&circle, &TessellateOptions::per_turn(64))
+                .unwrap()
+                .len(),
+            65
+        );
+        // 角度更密时以角度为准
+        let both = TessellateOptions {
+            chord_tolerance: 100.0,
+            max_angle: FRAC_PI_2 / 4.0,
+        };
+        assert_eq!(arc(Point2::new(0.0, 0.0), 1.0, 0.0, -PI, &both).len(), 9);
+    }
+
+    #[test]
+    fn bulged_polylines_keep_vertices() {
+        let polyline_entity = Entity::Polyline(Polyline {
+            vertices: vec![
+                PolylineVertex::new(Point2::new(0.0, 0.0)),
+                PolylineVertex::with_bulge(Point2::new(2.0, 0.0), 1.0),
+                PolylineVertex::new(Point2::new(2.0, 2.0)),
+            ],
+            is_closed: true,
+            layer: "0".to_string(),
+        });
+        let points = curve(&polyline_entity, &TessellateOptions::per_turn(16)).unwrap();
+        // 直线段 + 半圆 8 段 + 闭合段
+        assert_eq!(points.len(), 11);
+        assert_eq!(points[1], Point2::new(2.0, 0.0));
+        assert_eq!(points[9], Point2::new(2.0, 2.0));
+        assert_eq!(points[10], Point2::new(0.0, 0.0));
+        assert!(points[5].as_vec2().distance(DVec2::new(3.0, 1.0)) < 1e-12);
+    }
+
+    #[test]
+    fn hatch_loops_join_edges() {
+        let loop_path = HatchLoop {
+            is_polyline: false,
+            is_closed: true,
+            edges: vec![
+                HatchEdge::Line {
+                    start: Point2::new(-1.0, 0.0),
+                    end: Point2::new(1.0, 0.0),
+                },
+                HatchEdge::Arc {
+                    center: Point2::new(0.0, 0.0),
+                    radius: 1.0,
+                    start_angle: 0.0,
+                    end_angle: PI,
+                    is_counter_clockwise: true,
+                },
+            ],
+            boundary_handles: Vec::new(),
+            boundary_entities: Vec::new(),
+        };
+        let options = TessellateOptions::default();
+        let points = hatch_loop(&loop_path, &options);
+        assert_eq!(points.first(), Some(&Point2::new(-1.0, 0.0)));
+        assert_eq!(points[1], Point2::new(1.0, 0.0));
+        // 末点与首点重合，已去掉
+        assert!(
+            points
+                .last()
+                .unwrap()
+                .as_vec2()
+                .distance(DVec2::new(-1.0, 0.0))
+                > 1e-6
+        );
+        assert!(max_chord_error(&points[1..], DVec2::ZERO, 1.0) <= options.chord_tolerance);
+        assert!(
+            hatch_edge(
+                &HatchEdge::BoundaryReference {
+                    handle: "1F".to_string()
+                },
+                &options
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn splines_honor_max_angle() {
+        let quarter = Spline {
+            degree: 2,
+            is_rational: true,
+            is_closed: false,
+            is_periodic: false,
+            control_points: vec![
+                Point2::new(1.0, 0.0),
+                Point2::new(1.0, 1.0),
+                Point2::new(0.0, 1.0),
+            ],
+            fit_points: Vec::new(),
+            knot_values: Vec::new(),
+            weights: vec![1.0, std::f64::consts::FRAC_1_SQRT_2, 1.0],
+            start_tangent: None,
+            end_tangent: None,
+            layer: "0".to_string(),
+        };
+        let options = TessellateOptions {
+            chord_tolerance: 0.0,
+            max_angle: FRAC_PI_2 / 8.0,
+        };
+        let points = spline(&quarter, &options);
+        assert!(points.len() > 8);
+        for pair in points.windows(3) {
+            let (a, b) = (
+                pair[1].as_vec2() - pair[0].as_vec2(),
+                pair[2].as_vec2() - pair[1].as_vec2(),
+            );
+            assert!(a.angle_to(b).abs() <= FRAC_PI_2 / 8.0 + 1e-9);
+        }
+    }
+}
@@ -1,5 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
 
+use zcad_core::document::{
+    Change, Document, FaceOutput, FlattenOptions, ObjectColor, TableSnapshot,
+};
+use zcad_core::tessellate::TessellateOptions;
+
+use crate::errors::EngineError;
+use crate::filter::{EntityFilter, EntityKind};
 use crate::scene::Scene;
 
 #[derive(Debug, Clone)]
@@ -54,6 +63,11 @@ impl CommandBus {
         };
         bus.register(FocusSelectionCommand);
         bus.register(ClearSelectionCommand);
+        bus.register(FlattenCommand);
+        bus.register(MeasureCommand);
+        bus.register(QuickSelectCommand);
+        bus.register(UndoCommand);
+        bus.register(RedoCommand);
         bus
     }
 
@@ -73,11 +87,320 @@ impl CommandBus {
         }
     }
 
+    /// 是否已登记该名称的命令。
+    pub fn contains(&self, name: &str) -> bool {
+        self.handlers.contains_key(name)
+    }
+
     pub fn available_commands(&self) -> impl Iterator<Item = &&'static str> {
         self.handlers.keys()
     }
 }
 
+/// 历史默认保留的可撤销步数。
+pub const DEFAULT_HISTORY_LIMIT: usize = 100;
+
+/// 可撤销的编辑。`execute` 执行编辑，撤销后再次调用即为重做；`undo` 把场景恢复到执行前的状态。
+pub trait Command: Send {
+    /// 显示在撤销/重做菜单中的名称。
+    fn name(&self) -> &str;
+    fn execute(&mut self, scene: &mut Scene) -> Result<(), EngineError>;
+    fn undo(&mut self, scene: &mut Scene);
+}
+
+/// 一组文档修改记录：由 [`Scene::edit`] 捕获，撤销与重做都直接回放记录，不再执行原编辑。
+/// 图层、块定义、特性覆盖等实体以外的状态有变化时，同时保存改动过的表在修改前后的内容。
+pub struct DocumentChange {
+    name: String,
+    changes: Vec<Change>,
+    tables: Option<Box<TableChange>>,
+}
+
+struct TableChange {
+    before: TableSnapshot,
+    after: TableSnapshot,
+}
+
+impl DocumentChange {
+    pub fn new(name: impl Into<String>, changes: Vec<Change>) -> Self {
+        Self {
+            name: name.into(),
+            changes,
+            tables: None,
+        }
+    }
+
+    /// 附带实体以外状态在修改前后的内容，见 [`Document::capture_tables`]；两者相同时不保存。
+    pub fn with_tables(mut self, before: TableSnapshot, after: TableSnapshot) -> Self {
+        self.tables = (before != after).then(|| Box::new(TableChange { before, after }));
+        self
+    }
+
+    #[inline]
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// 是否既没有实体修改也没有其他状态变化。
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.tables.is_none()
+    }
+}
+
+impl Command for DocumentChange {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn execute(&mut self, scene: &mut Scene) -> Result<(), EngineError> {
+        let layers = scene.layer_snapshot();
+        scene.document_mut().apply_changes(&self.changes);
+        if let Some(tables) = &self.tables {
+            scene.document_mut().restore_tables(&tables.after);
+        }
+        scene.emit_changes(&self.changes, false);
+        scene.emit_layer_changes(layers);
+        Ok(())
+    }
+
+    fn undo(&mut self, scene: &mut Scene) {
+        let layers = scene.layer_snapshot();
+        scene.document_mut().revert_changes(&self.changes);
+        if let Some(tables) = &self.tables {
+            scene.document_mut().restore_tables(&tables.before);
+        }
+        scene.emit_changes(&self.changes, true);
+        scene.emit_layer_changes(layers);
+    }
+}
+
+/// 复合操作：作为一步撤销与重做，撤销时按逆序撤销各子命令。
+pub struct CommandGroup {
+    name: String,
+    commands: Vec<Box<dyn Command>>,
+}
+
+impl CommandGroup {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            commands: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, command: impl Command + 'static) {
+        self.commands.push(Box::new(command));
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+impl Command for CommandGroup {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 依次执行子命令；某个子命令失败时撤销已执行的部分。
+    fn execute(&mut self, scene: &mut Scene) -> Result<(), EngineError> {
+        for index in 0..self.commands.len() {
+            if let Err(err) = self.commands[index].execute(scene) {
+                for command in self.commands[..index].iter_mut().rev() {
+                    command.undo(scene);
+                }
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    fn undo(&mut self, scene: &mut Scene) {
+        for command in self.commands.iter_mut().rev() {
+            command.undo(scene);
+        }
+    }
+}
+
+/// 事务中的文档访问，见 [`Scene::transaction`]；可直接调用 [`Document`] 的方法。
+pub struct Transaction<'a> {
+    document: &'a mut Document,
+}
+
+impl<'a> Transaction<'a> {
+    pub(crate) fn new(document: &'a mut Document) -> Self {
+        Self { document }
+    }
+}
+
+impl Deref for Transaction<'_> {
+    type Target = Document;
+
+    fn deref(&self) -> &Document {
+        self.document
+    }
+}
+
+impl DerefMut for Transaction<'_> {
+    fn deref_mut(&mut self) -> &mut Document {
+        self.document
+    }
+}
+
+/// 撤销与重做历史。超过上限时丢弃最早的步骤；打开编组期间执行的命令合并为一步。
+pub struct CommandHistory {
+    undo: VecDeque<Box<dyn Command>>,
+    redo: Vec<Box<dyn Command>>,
+    limit: usize,
+    /// 尚未关闭的编组，最内层在末尾。
+    groups: Vec<CommandGroup>,
+}
+
+impl Default for CommandHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_LIMIT)
+    }
+}
+
+impl CommandHistory {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit,
+            groups: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// 调整上限，超出的最早步骤立即丢弃。
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.trim();
+    }
+
+    #[inline]
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    #[inline]
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// 下一步撤销的名称。
+    pub fn undo_name(&self) -> Option<&str> {
+        self.undo.back().map(|command| command.name())
+    }
+
+    /// 下一步重做的名称。
+    pub fn redo_name(&self) -> Option<&str> {
+        self.redo.last().map(|command| command.name())
+    }
+
+    /// 可撤销的步数。
+    #[inline]
+    pub fn undo_len(&self) -> usize {
+        self.undo.len()
+    }
+
+    #[inline]
+    pub fn redo_len(&self) -> usize {
+        self.redo.len()
+    }
+
+    /// 是否处于编组中。
+    #[inline]
+    pub fn is_grouping(&self) -> bool {
+        !self.groups.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.groups.clear();
+    }
+
+    /// 记录已执行的命令：处于编组中时并入最内层编组，否则作为新的一步并清空重做栈。
+    pub(crate) fn record(&mut self, command: Box<dyn Command>) {
+        if let Some(group) = self.groups.last_mut() {
+            group.commands.push(command);
+            return;
+        }
+        self.redo.clear();
+        self.undo.push_back(command);
+        self.trim();
+    }
+
+    pub(crate) fn begin_group(&mut self, name: String) {
+        self.groups.push(CommandGroup::new(name));
+    }
+
+    /// 关闭最内层编组，返回是否存在打开的编组；空编组不进入历史。
+    pub(crate) fn end_group(&mut self) -> bool {
+        let Some(group) = self.groups.pop() else {
+            return false;
+        };
+        if !group.is_empty() {
+            self.record(Box::new(group));
+        }
+        true
+    }
+
+    pub(crate) fn take_undo(&mut self) -> Option<Box<dyn Command>> {
+        while self.end_group() {}
+        self.undo.pop_back()
+    }
+
+    pub(crate) fn take_redo(&mut self) -> Option<Box<dyn Command>> {
+        while self.end_group() {}
+        self.redo.pop()
+    }
+
+    pub(crate) fn push_undone(&mut self, command: Box<dyn Command>) {
+        self.redo.push(command);
+    }
+
+    pub(crate) fn push_redone(&mut self, command: Box<dyn Command>) {
+        self.undo.push_back(command);
+        self.trim();
+    }
+
+    fn trim(&mut self) {
+        while self.undo.len() > self.limit {
+            self.undo.pop_front();
+        }
+    }
+}
+
+impl fmt::Debug for CommandHistory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = |commands: &mut dyn Iterator<Item = &Box<dyn Command>>| {
+            commands
+                .map(|command| command.name().to_string())
+                .collect::<Vec<_>>()
+        };
+        f.debug_struct("CommandHistory")
+            .field("undo", &names(&mut self.undo.iter()))
+            .field("redo", &names(&mut self.redo.iter()))
+            .field("limit", &self.limit)
+            .field("groups", &self.groups.len())
+            .finish()
+    }
+}
+
 struct FocusSelectionCommand;
 
 impl CommandHandler for FocusSelectionCommand {
@@ -112,10 +435,203 @@ impl CommandHandler for ClearSelectionCommand {
     }
 }
 
+/// 将当前文档压平为简单图元；参数 `outline_text` 以轮廓替代文字，`face_polyline` 把 3D 面输出为
+/// 闭合多段线，`hidden_line` 对 3D 面做俯视消隐，数字参数为整圆离散段数。
+struct FlattenCommand;
+
+impl CommandHandler for FlattenCommand {
+    fn name(&self) -> &'static str {
+        "flatten"
+    }
+
+    fn execute(
+        &self,
+        request: &CommandRequest,
+        context: &mut CommandContext<'_>,
+    ) -> CommandResponse {
+        let mut options = FlattenOptions::default();
+        for arg in &request.args {
+            if arg == "outline_text" {
+                options.outline_text = true;
+            } else if arg == "face_polyline" {
+                options.faces = FaceOutput::Polyline;
+            } else if arg == "hidden_line" {
+                options.faces = FaceOutput::HiddenLine;
+            } else if let Ok(segments) = arg.parse::<usize>() {
+                options.tessellation = TessellateOptions::per_turn(segments);
+            } else {
+                return CommandResponse::err(format!("flatten 无法识别的参数: {arg}"));
+            }
+        }
+        let flat = context.scene.document().flatten(&options);
+        let count = flat.entities().count();
+        context.scene.load_document(flat);
+        CommandResponse::ok(format!("文档已压平，共 {count} 个图元"))
+    }
+}
+
+/// 测量选中实体的面积、周长与形心，逐个实体汇报。
+struct MeasureCommand;
+
+impl CommandHandler for MeasureCommand {
+    fn name(&self) -> &'static str {
+        "measure"
+    }
+
+    fn execute(
+        &self,
+        _request: &CommandRequest,
+        context: &mut CommandContext<'_>,
+    ) -> CommandResponse {
+        if context.scene.selection_len() == 0 {
+            return CommandResponse::err("measure 需要先选中实体");
+        }
+        let lines: Vec<String> = context
+            .scene
+            .selection()
+            .filter_map(|id| {
+                let measurement = context.scene.entity(id)?.measure()?;
+                Some(format!(
+                    "#{}: 面积 {:.4}，周长 {:.4}，形心 ({:.4}, {:.4})",
+                    id.get(),
+                    measurement.area,
+                    measurement.perimeter,
+                    measurement.centroid.x(),
+                    measurement.centroid.y()
+                ))
+            })
+            .collect();
+        if lines.is_empty() {
+            return CommandResponse::err("选中实体均不可测量");
+        }
+        CommandResponse::ok(lines.join("\n"))
+    }
+}
+
+/// 快速选择，参数为 `键=值`：`type`、`layer` 与 `block` 可用逗号分隔多个值，`color` 为 ACI 编号或
+/// `bylayer`/`byblock`，`text` 为正则表达式，`area` 形如 `min..max`（可省略一端）。
+/// 默认替换当前选中集，参数 `append` 改为追加。
+struct QuickSelectCommand;
+
+impl QuickSelectCommand {
+    fn filter(args: &[String]) -> Result<(EntityFilter, bool), String> {
+        let mut filter = EntityFilter::new();
+        let mut append = false;
+        for arg in args {
+            if arg == "append" {
+                append = true;
+                continue;
+            }
+            let Some((key, value)) = arg.split_once('=') else {
+                return Err(format!("qselect 无法识别的参数: {arg}"));
+            };
+            let mut values = value.split(',').map(str::trim).filter(|v| !v.is_empty());
+            filter = match key {
+                "type" => values.try_fold(filter, |filter, name| {
+                    EntityKind::from_name(name)
+                        .map(|kind| filter.kind(kind))
+                        .ok_or_else(|| format!("qselect 未知的实体类型: {name}"))
+                })?,
+                "layer" => values.fold(filter, EntityFilter::layer),
+                "block" => values.fold(filter, EntityFilter::block),
+                "color" => filter.color(match value.to_ascii_lowercase().as_str() {
+                    "bylayer" => ObjectColor::ByLayer,
+                    "byblock" => ObjectColor::ByBlock,
+                    code => match code.parse::<u8>() {
+                        Ok(index @ 1..=255) => ObjectColor::Index(index),
+                        _ => return Err(format!("qselect 无效的颜色: {value}")),
+                    },
+                }),
+                "text" => filter.text(value).map_err(|err| format!("qselect {err}"))?,
+                "area" => {
+                    let bound = |text: &str, default: f64| {
+                        if text.is_empty() {
+                            Ok(default)
+                        } else {
+                            text.parse::<f64>()
+                                .map_err(|_| format!("qselect 无效的面积范围: {value}"))
+                        }
+                    };
+                    let (min, max) = value.split_once("..").unwrap_or((value, value));
+                    filter.area(bound(min, 0.0)?, bound(max, f64::INFINITY)?)
+                }
+                _ => return Err(format!("qselect 无法识别的条件: {key}")),
+            };
+        }
+        Ok((filter, append))
+    }
+}
+
+impl CommandHandler for QuickSelectCommand {
+    fn name(&self) -> &'static str {
+        "qselect"
+    }
+
+    fn execute(
+        &self,
+        request: &CommandRequest,
+        context: &mut CommandContext<'_>,
+    ) -> CommandResponse {
+        let (filter, append) = match Self::filter(&request.args) {
+            Ok(parsed) => parsed,
+            Err(message) => return CommandResponse::err(message),
+        };
+        if !append {
+            context.scene.clear_selection();
+        }
+        let added = context.scene.select_where(&filter);
+        CommandResponse::ok(format!(
+            "已选中 {added} 个实体，选中集共 {} 个",
+            context.scene.selection_len()
+        ))
+    }
+}
+
+struct UndoCommand;
+
+impl CommandHandler for UndoCommand {
+    fn name(&self) -> &'static str {
+        "undo"
+    }
+
+    fn execute(
+        &self,
+        _request: &CommandRequest,
+        context: &mut CommandContext<'_>,
+    ) -> CommandResponse {
+        match context.scene.undo() {
+            Some(name) => CommandResponse::ok(format!("已撤销: {name}")),
+            None => CommandResponse::err("没有可撤销的操作"),
+        }
+    }
+}
+
+struct RedoCommand;
+
+impl CommandHandler for RedoCommand {
+    fn name(&self) -> &'static str {
+        "redo"
+    }
+
+    fn execute(
+        &self,
+        _request: &CommandRequest,
+        context: &mut CommandContext<'_>,
+    ) -> CommandResponse {
+        match context.scene.redo() {
+            Ok(Some(name)) => CommandResponse::ok(format!("已重做: {name}")),
+            Ok(None) => CommandResponse::err("没有可重做的操作"),
+            Err(err) => CommandResponse::err(format!("重做失败: {err}")),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::scene::Scene;
+    use zcad_core::document::Entity;
+    use zcad_core::geometry::{Point2, Vector2};
 
     #[test]
     fn focus_and_clear_commands_work() {
@@ -141,4 +657,339 @@ mod tests {
         assert!(response.success);
         assert_eq!(context.scene.selection_len(), 0);
     }
+
+    #[test]
+    fn flatten_command_replaces_document() {
+        let mut scene = Scene::new();
+        scene.populate_demo();
+        let before = scene.document().entities().count();
+
+        let bus = CommandBus::new();
+        let mut context = CommandContext { scene: &mut scene };
+        let flatten = CommandRequest {
+            name: "flatten".to_string(),
+            args: vec!["outline_text".to_string(), "32".to_string()],
+        };
+        let response = bus.dispatch(&flatten, &mut context);
+        assert!(response.success);
+        assert_eq!(context.scene.document().entities().count(), before);
+        assert!(
+            context
+                .scene
+                .document()
+                .entities()
+                .all(|(_, entity)| matches!(
+                    entity,
+                    Entity::Line(_) | Entity::Circle(_) | Entity::Arc(_) | Entity::Polyline(_)
+                ))
+        );
+
+        let invalid = CommandRequest {
+            name: "flatten".to_string(),
+            args: vec!["bogus".to_string()],
+        };
+        assert!(!bus.dispatch(&invalid, &mut context).success);
+    }
+
+    #[test]
+    fn measure_command_reports_selected_entities() {
+        let mut scene = Scene::new();
+        let ids = scene.populate_demo();
+
+        let bus = CommandBus::new();
+        let measure = CommandRequest {
+            name: "measure".to_string(),
+            args: Vec::new(),
+        };
+        let mut context = CommandContext { scene: &mut scene };
+        assert!(!bus.dispatch(&measure, &mut context).success);
+
+        context.scene.select(ids.label).unwrap();
+        assert!(!bus.dispatch(&measure, &mut context).success);
+
+        context.scene.select(ids.circle).unwrap();
+        let response = bus.dispatch(&measure, &mut context);
+        assert!(response.success);
+        let message = response.message.unwrap();
+        assert!(message.contains("面积 490.8739"), "{message}");
+        assert!(message.contains("形心 (50.0000, 25.0000)"), "{message}");
+    }
+
+    #[test]
+    fn quick_select_command_parses_criteria() {
+        let mut scene = Scene::new();
+        let ids = scene.populate_demo();
+
+        let bus = CommandBus::new();
+        let mut context = CommandContext { scene: &mut scene };
+        let qselect = |args: &[&str]| CommandRequest {
+            name: "qselect".to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        };
+
+        let response = bus.dispatch(&qselect(&["layer=annot", "type=CIRCLE,ARC"]), &mut context);
+        assert!(response.success);
+        assert_eq!(context.scene.selection_len(), 2);
+        assert!(context.scene.is_selected(ids.arc));
+
+        // 默认替换选中集
+        assert!(
+            bus.dispatch(&qselect(&["area=400.."]), &mut context)
+                .success
+        );
+        assert_eq!(context.scene.selection().collect::<Vec<_>>(), [ids.circle]);
+        assert!(
+            bus.dispatch(&qselect(&["text=移植", "append"]), &mut context)
+                .success
+        );
+        assert_eq!(context.scene.selection_len(), 2);
+        assert!(context.scene.is_selected(ids.label));
+
+        assert!(
+            !bus.dispatch(&qselect(&["type=SOLID"]), &mut context)
+                .success
+        );
+        assert!(!bus.dispatch(&qselect(&["color=red"]), &mut context).success);
+        assert!(!bus.dispatch(&qselect(&["text=("]), &mut context).success);
+        assert!(!bus.dispatch(&qselect(&["bogus"]), &mut context).success);
+    }
+
+    #[test]
+    fn scene_edits_undo_and_redo() {
+        let mut scene = Scene::new();
+        let ids = scene.populate_demo();
+        assert!(scene.undo().is_none());
+
+        let line = scene.edit("画线", |document| {
+            document.add_line(Point2::new(0.0, 0.0), Point2::new(5.0, 0.0), "0")
+        });
+        scene.edit("移动", |document| {
+            document.move_entities([ids.circle], Vector2::new(10.0, 0.0))
+        });
+        scene.select(ids.arc).unwrap();
+        scene.remove_entity(ids.arc).unwrap();
+        assert_eq!(scene.history().undo_len(), 3);
+        assert_eq!(scene.history().undo_name(), Some("删除"));
+
+        assert_eq!(scene.undo().as_deref(), Some("删除"));
+        assert!(scene.entity(ids.arc).is_some());
+        assert_eq!(scene.undo().as_deref(), Some("移动"));
+        assert!(matches!(
+            scene.entity(ids.circle),
+            Some(Entity::Circle(circle)) if circle.center == Point2::new(50.0, 25.0)
+        ));
+        scene.undo();
+        assert!(scene.entity(line).is_none());
+        assert_eq!(scene.history().redo_len(), 3);
+
+        assert_eq!(scene.redo().unwrap().as_deref(), Some("画线"));
+        assert!(scene.entity(line).is_some());
+        // 新的编辑清空重做栈
+        scene.edit("删除", |document| document.remove_entity(ids.label));
+        assert!(!scene.history().can_redo());
+        assert_eq!(scene.redo().unwrap(), None);
+    }
+
+    #[test]
+    fn scene_edits_undo_layer_and_user_data_changes() {
+        let mut scene = Scene::new();
+        let line = scene.edit("画线", |document| {
+            document.add_line(Point2::new(0.0, 0.0), Point2::new(5.0, 0.0), "A")
+        });
+        scene
+            .edit("图层颜色", |document| {
+                document.set_layer_color("A", ObjectColor::Index(1))
+            })
+            .unwrap();
+        scene
+            .edit("重命名", |document| {
+                document.set_user_value(line, "tag", "x").unwrap();
+                document.rename_layer("A", "B")
+            })
+            .unwrap();
+        assert_eq!(scene.history().undo_len(), 3);
+
+        assert_eq!(scene.undo().as_deref(), Some("重命名"));
+        assert!(scene.document().layer("B").is_none());
+        assert_eq!(
+            scene.document().layer("A").map(|layer| layer.color),
+            Some(ObjectColor::Index(1))
+        );
+        assert!(scene.document().user_data(line).is_none());
+        assert!(matches!(
+            scene.entity(line),
+            Some(Entity::Line(line)) if line.layer == "A"
+        ));
+
+        assert_eq!(scene.undo().as_deref(), Some("图层颜色"));
+        assert_eq!(
+            scene.document().layer("A").map(|layer| layer.color),
+            Some(ObjectColor::Index(7))
+        );
+
+        scene.redo().unwrap();
+        scene.redo().unwrap();
+        assert!(scene.document().layer("A").is_none());
+        assert_eq!(
+            scene.document().layer("B").map(|layer| layer.color),
+            Some(ObjectColor::Index(1))
+        );
+        assert!(scene.document().user_value(line, "tag").is_some());
+    }
+
+    #[test]
+    fn scene_edits_undo_block_and_xref_references() {
+        use zcad_core::document::BlockDefinition;
+
+        let mut document = Document::new();
+        document.add_block_definition(BlockDefinition {
+            name: "B".to_string(),
+            base_point: Point2::new(0.0, 0.0),
+            entities: Vec::new(),
+            attributes: Vec::new(),
+        });
+        let insert = document.add_block_reference(
+            "B",
+            Point2::new(10.0, 10.0),
+            Vector2::new(1.0, 1.0),
+            0.0,
+            Vec::new(),
+            "0",
+        );
+        let mut site = Document::new();
+        site.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "WALLS");
+        document
+            .attach_xref("SITE", "site.dxf", site)
+            .expect("attach xref");
+        let wall = document.add_line(Point2::new(0.0, 0.0), Point2::new(0.0, 1.0), "SITE|WALLS");
+        let mut scene = Scene::with_document(document);
+        let reference = |scene: &Scene| match scene.entity(insert) {
+            Some(Entity::BlockReference(reference)) => (reference.name.clone(), reference.insert),
+            other => panic!("应为块参照: {other:?}"),
+        };
+        let layer = |scene: &Scene| scene.entity(wall).unwrap().layer_name().to_string();
+
+        scene
+            .edit("基点", |document| {
+                document.set_block_base_point("B", Point2::new(5.0, 0.0))
+            })
+            .unwrap();
+        scene
+            .edit("块改名", |document| document.rename_block("B", "C"))
+            .unwrap();
+        scene
+            .edit("绑定", |document| document.bind_xref("SITE"))
+            .unwrap();
+        assert_eq!(
+            reference(&scene),
+            ("C".to_string(), Point2::new(15.0, 10.0))
+        );
+        assert_eq!(layer(&scene), "SITE$0$WALLS");
+
+        assert_eq!(scene.undo().as_deref(), Some("绑定"));
+        assert_eq!(layer(&scene), "SITE|WALLS");
+        assert!(scene.document().xref("SITE").is_some());
+        assert_eq!(scene.undo().as_deref(), Some("块改名"));
+        assert_eq!(
+            reference(&scene),
+            ("B".to_string(), Point2::new(15.0, 10.0))
+        );
+        assert_eq!(scene.undo().as_deref(), Some("基点"));
+        assert_eq!(
+            reference(&scene),
+            ("B".to_string(), Point2::new(10.0, 10.0))
+        );
+
+        for _ in 0..3 {
+            scene.redo().unwrap();
+        }
+        assert_eq!(
+            reference(&scene),
+            ("C".to_string(), Point2::new(15.0, 10.0))
+        );
+        assert_eq!(layer(&scene), "SITE$0$WALLS");
+    }
+
+    #[test]
+    fn undo_groups_and_history_limit() {
+        let mut scene = Scene::new();
+        scene.set_history_limit(2);
+        for x in 0..3 {
+            scene.edit("画线", |document| {
+                document.add_line(Point2::new(x as f64, 0.0), Point2::new(x as f64, 1.0), "0")
+            });
+        }
+        assert_eq!(scene.history().undo_len(), 2);
+
+        scene.begin_undo_group("阵列");
+        for y in 1..4 {
+            scene.edit("画圆", |document| {
+                document.add_circle(Point2::new(0.0, y as f64), 0.5, "0")
+            });
+        }
+        assert!(scene.history().is_grouping());
+        assert!(scene.end_undo_group());
+        assert!(!scene.end_undo_group());
+        assert_eq!(scene.history().undo_name(), Some("阵列"));
+
+        let count = scene.document().entities().count();
+        let bus = CommandBus::new();
+        let mut context = CommandContext { scene: &mut scene };
+        let undo = CommandRequest {
+            name: "undo".to_string(),
+            args: Vec::new(),
+        };
+        let redo = CommandRequest {
+            name: "redo".to_string(),
+            args: Vec::new(),
+        };
+        assert!(bus.dispatch(&undo, &mut context).success);
+        assert_eq!(context.scene.document().entities().count(), count - 3);
+        assert!(bus.dispatch(&redo, &mut context).success);
+        assert_eq!(context.scene.document().entities().count(), count);
+
+        // 上限为 2：只剩编组与最后一次画线可以撤销
+        assert!(bus.dispatch(&undo, &mut context).success);
+        assert!(bus.dispatch(&undo, &mut context).success);
+        assert!(!bus.dispatch(&undo, &mut context).success);
+        assert_eq!(context.scene.document().entities().count(), 2);
+    }
+
+    /// 修改视口缩放的自定义命令。
+    struct ZoomCommand {
+        zoom: f64,
+        previous: f64,
+    }
+
+    impl Command for ZoomCommand {
+        fn name(&self) -> &str {
+            "缩放"
+        }
+
+        fn execute(&mut self, scene: &mut Scene) -> Result<(), EngineError> {
+            self.previous = scene.viewport().zoom;
+            scene.set_viewport_zoom(self.zoom);
+            Ok(())
+        }
+
+        fn undo(&mut self, scene: &mut Scene) {
+            scene.set_viewport_zoom(self.previous);
+        }
+    }
+
+    #[test]
+    fn custom_commands_execute_and_undo() {
+        let mut scene = Scene::new();
+        scene
+            .execute(ZoomCommand {
+                zoom: 4.0,
+                previous: 0.0,
+            })
+            .unwrap();
+        assert_eq!(scene.viewport().zoom, 4.0);
+        assert_eq!(scene.undo().as_deref(), Some("缩放"));
+        assert_eq!(scene.viewport().zoom, 1.0);
+        scene.redo().unwrap();
+        assert_eq!(scene.viewport().zoom, 4.0);
+    }
 }
@@ -0,0 +1,120 @@
+use zcad_core::document::{
+    Document, EntityId, EntityProperties, LineWeight, ObjectColor, ResolvedProperties,
+};
+use zcad_core::geometry::Point2;
+
+/// 绘图上下文：保存当前图层与当前对象特性（对应 CLAYER/CECOLOR/CELTYPE/CELWEIGHT），
+/// 新建实体时统一套用；特性为随层时实体直接继承图层默认值。
+#[derive(Debug, Clone)]
+pub struct DraftingContext {
+    current_layer: String,
+    properties: EntityProperties,
+}
+
+impl Default for DraftingContext {
+    fn default() -> Self {
+        Self {
+            current_layer: "0".to_string(),
+            properties: EntityProperties::default(),
+        }
+    }
+}
+
+impl DraftingContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn current_layer(&self) -> &str {
+        &self.current_layer
+    }
+
+    pub fn set_current_layer(&mut self, layer: impl Into<String>) {
+        self.current_layer = layer.into();
+    }
+
+    #[inline]
+    pub fn properties(&self) -> &EntityProperties {
+        &self.properties
+    }
+
+    pub fn set_properties(&mut self, properties: EntityProperties) {
+        self.properties = properties;
+    }
+
+    pub fn set_color(&mut self, color: ObjectColor) {
+        self.properties.color = color;
+    }
+
+    pub fn set_linetype(&mut self, linetype: impl Into<String>) {
+        self.properties.linetype = linetype.into();
+    }
+
+    pub fn set_lineweight(&mut self, lineweight: LineWeight) {
+        self.properties.lineweight = lineweight;
+    }
+
+    /// 新建实体在当前文档中将获得的最终特性，供界面预览。
+    pub fn resolve(&self, document: &Document) -> ResolvedProperties {
+        document.resolve_layer_properties(&self.current_layer, Some(&self.properties), None)
+    }
+
+    /// 在当前图层上创建实体并记录当前特性；`build` 接收目标图层名。
+    pub fn create(
+        &self,
+        document: &mut Document,
+        build: impl FnOnce(&mut Document, String) -> EntityId,
+    ) -> EntityId {
+        document.ensure_layer(&self.current_layer);
+        let id = build(document, self.current_layer.clone());
+        document.set_entity_properties(id, self.properties.clone());
+        id
+    }
+
+    pub fn add_line(&self, document: &mut Document, start: Point2, end: Point2) -> EntityId {
+        self.create(document, |doc, layer| doc.add_line(start, end, layer))
+    }
+
+    pub fn add_circle(&self, document: &mut Document, center: Point2, radius: f64) -> EntityId {
+        self.create(document, |doc, layer| doc.add_circle(center, radius, layer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_entities_inherit_layer_defaults_until_overridden() {
+        let mut document = Document::new();
+        document.ensure_layer("HIDDEN");
+        if let Some(layer) = document.layer_mut("HIDDEN") {
+            layer.color = ObjectColor::Index(8);
+            layer.linetype = "HIDDEN".to_string();
+        }
+
+        let mut context = DraftingContext::new();
+        context.set_current_layer("HIDDEN");
+        let by_layer =
+            context.add_line(&mut document, Point2::new(0.0, 0.0), Point2::new(10.0, 0.0));
+        assert!(document.entity_properties(by_layer).is_none());
+        let resolved = document.resolve_properties(by_layer).unwrap();
+        assert_eq!(resolved.color, ObjectColor::Index(8));
+        assert_eq!(resolved.linetype, "HIDDEN");
+
+        context.set_color(ObjectColor::Index(1));
+        assert_eq!(context.resolve(&document).color, ObjectColor::Index(1));
+        let red = context.add_circle(&mut document, Point2::new(0.0, 0.0), 5.0);
+        let resolved = document.resolve_properties(red).unwrap();
+        assert_eq!(resolved.color, ObjectColor::Index(1));
+        assert_eq!(resolved.linetype, "HIDDEN");
+
+        // 图层默认值修改后，随层特性随之变化
+        document.layer_mut("HIDDEN").unwrap().linetype = "DASHED".to_string();
+        assert_eq!(
+            document.resolve_properties(by_layer).unwrap().linetype,
+            "DASHED"
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! 场景事件：文档与运行时状态变化时向订阅者推送类型化事件，界面与渲染器据此增量更新，无需轮询。
+//!
+//! 经 [`Scene`](crate::scene::Scene) 的可撤销编辑（`edit`、`transaction`、`draft`、撤销与重做）
+//! 产生的实体与图层变化、选中集与视口的变化都会发出事件；经 `document_mut` 直接修改文档不会。
+
+use std::fmt;
+
+use zcad_core::document::{Change, EntityId};
+
+use crate::scene::ViewportState;
+
+/// 场景发生的变化。
+#[derive(Debug, Clone, PartialEq)]
+pub enum SceneEvent {
+    EntityAdded(EntityId),
+    EntityModified(EntityId),
+    EntityRemoved(EntityId),
+    /// 图层新建、删除或属性变化，携带图层名。
+    LayerChanged(String),
+    SelectionChanged,
+    ViewportChanged(ViewportState),
+    /// 整个文档被替换（重置或载入），订阅者应全部重建。
+    DocumentReplaced,
+}
+
+impl SceneEvent {
+    /// 执行一条修改记录对应的事件。
+    pub fn applied(change: &Change) -> Self {
+        match change {
+            Change::Added { id, .. } => Self::EntityAdded(*id),
+            Change::Modified { id, .. } => Self::EntityModified(*id),
+            Change::Removed { id, .. } => Self::EntityRemoved(*id),
+        }
+    }
+
+    /// 撤销一条修改记录对应的事件。
+    pub fn reverted(change: &Change) -> Self {
+        match change {
+            Change::Added { id, .. } => Self::EntityRemoved(*id),
+            Change::Modified { id, .. } => Self::EntityModified(*id),
+            Change::Removed { id, .. } => Self::EntityAdded(*id),
+        }
+    }
+}
+
+/// 订阅句柄，用于取消订阅。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Subscription(u64);
+
+type Listener = Box<dyn FnMut(&SceneEvent) + Send>;
+
+/// 事件总线：按订阅顺序同步通知全部订阅者。
+#[derive(Default)]
+pub struct EventBus {
+    next_id: u64,
+    listeners: Vec<(Subscription, Listener)>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn subscribe(
+        &mut self,
+        listener: impl FnMut(&SceneEvent) + Send + 'static,
+    ) -> Subscription {
+        let subscription = Subscription(self.next_id);
+        self.next_id += 1;
+        self.listeners.push((subscription, Box::new(listener)));
+        subscription
+    }
+
+    /// 取消订阅，返回该订阅此前是否存在。
+    pub fn unsubscribe(&mut self, subscription: Subscription) -> bool {
+        let before = self.listeners.len();
+        self.listeners.retain(|(id, _)| *id != subscription);
+        self.listeners.len() != before
+    }
+
+    #[inline]
+    pub fn has_subscribers(&self) -> bool {
+        !self.listeners.is_empty()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.listeners.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.listeners.is_empty()
+    }
+
+    pub fn emit(&mut self, event: &SceneEvent) {
+        for (_, listener) in &mut self.listeners {
+            listener(event);
+        }
+    }
+}
+
+impl fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventBus")
+            .field("subscribers", &self.listeners.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use zcad_core::document::{Document, ObjectColor};
+    use zcad_core::geometry::{Point2, Vector2};
+
+    use super::*;
+    use crate::scene::Scene;
+
+    fn record(scene: &mut Scene) -> (Subscription, Arc<Mutex<Vec<SceneEvent>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        let subscription = scene.subscribe(move |event| sink.lock().unwrap().push(event.clone()));
+        (subscription, events)
+    }
+
+    #[test]
+    fn scene_edits_emit_typed_events() {
+        let mut scene = Scene::new();
+        let (subscription, events) = record(&mut scene);
+
+        let line = scene.edit("画线", |doc| {
+            doc.add_line(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), "WALLS")
+        });
+        scene.select(line).unwrap();
+        scene.select(line).unwrap();
+        scene.pan_viewport(Vector2::new(5.0, 0.0));
+        scene.remove_entity(line).unwrap();
+        assert_eq!(scene.undo().as_deref(), Some("删除"));
+
+        let center = Point2::new(5.0, 0.0);
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                SceneEvent::EntityAdded(line),
+                SceneEvent::LayerChanged("WALLS".into()),
+                SceneEvent::SelectionChanged,
+                SceneEvent::ViewportChanged(ViewportState { center, zoom: 1.0 }),
+                SceneEvent::EntityRemoved(line),
+                SceneEvent::SelectionChanged,
+                SceneEvent::EntityAdded(line),
+            ]
+        );
+
+        assert!(scene.unsubscribe(subscription));
+        assert!(!scene.unsubscribe(subscription));
+        scene.load_document(Document::new());
+        assert_eq!(events.lock().unwrap().len(), 7);
+    }
+
+    #[test]
+    fn failed_transactions_and_unchanged_state_stay_silent() {
+        let mut scene = Scene::new();
+        let (_, events) = record(&mut scene);
+
+        let result: Result<(), &str> = scene.transaction("失败", |txn| {
+            txn.add_circle(Point2::new(0.0, 0.0), 1.0, "0");
+            Err("中止")
+        });
+        assert!(result.is_err());
+        scene.clear_selection();
+        scene.set_viewport_zoom(1.0);
+        assert!(!scene.deselect(EntityId::new(1)));
+        scene
+            .edit("图层", |doc| {
+                doc.set_layer_color("0", ObjectColor::Index(7))
+            })
+            .unwrap();
+        assert!(events.lock().unwrap().is_empty());
+
+        scene.load_document(Document::new());
+        assert_eq!(*events.lock().unwrap(), [SceneEvent::DocumentReplaced]);
+    }
+}
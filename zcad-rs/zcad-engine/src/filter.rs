@@ -0,0 +1,300 @@
+//! 快速选择（QSELECT）：按类型、图层、颜色、文字内容、块名与面积等结构化条件筛选实体。
+//!
+//! 同一条件多次设置时满足其一即可（如多个图层），不同条件须同时满足；未设置任何条件的过滤器匹配全部实体。
+//! 图层名与块名不区分大小写。
+
+use regex::Regex;
+use thiserror::Error;
+use zcad_core::document::{Document, Entity, EntityId, MLeaderContent, ObjectColor};
+
+/// 实体类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntityKind {
+    Line,
+    Circle,
+    Arc,
+    Ellipse,
+    Polyline,
+    Spline,
+    Text,
+    MText,
+    BlockReference,
+    Hatch,
+    Dimension,
+    Leader,
+    MLeader,
+    RasterImage,
+    Wipeout,
+    Face3D,
+    Shape,
+    Underlay,
+    RevisionCloud,
+}
+
+impl EntityKind {
+    pub const ALL: [EntityKind; 19] = [
+        EntityKind::Line,
+        EntityKind::Circle,
+        EntityKind::Arc,
+        EntityKind::Ellipse,
+        EntityKind::Polyline,
+        EntityKind::Spline,
+        EntityKind::Text,
+        EntityKind::MText,
+        EntityKind::BlockReference,
+        EntityKind::Hatch,
+        EntityKind::Dimension,
+        EntityKind::Leader,
+        EntityKind::MLeader,
+        EntityKind::RasterImage,
+        EntityKind::Wipeout,
+        EntityKind::Face3D,
+        EntityKind::Shape,
+        EntityKind::Underlay,
+        EntityKind::RevisionCloud,
+    ];
+
+    pub fn of(entity: &Entity) -> Self {
+        match entity {
+            Entity::Line(_) => EntityKind::Line,
+            Entity::Circle(_) => EntityKind::Circle,
+            Entity::Arc(_) => EntityKind::Arc,
+            Entity::Ellipse(_) => EntityKind::Ellipse,
+            Entity::Polyline(_) => EntityKind::Polyline,
+            Entity::Spline(_) => EntityKind::Spline,
+            Entity::Text(_) => EntityKind::Text,
+            Entity::MText(_) => EntityKind::MText,
+            Entity::BlockReference(_) => EntityKind::BlockReference,
+            Entity::Hatch(_) => EntityKind::Hatch,
+            Entity::Dimension(_) => EntityKind::Dimension,
+            Entity::Leader(_) => EntityKind::Leader,
+            Entity::MLeader(_) => EntityKind::MLeader,
+            Entity::RasterImage(_) => EntityKind::RasterImage,
+            Entity::Wipeout(_) => EntityKind::Wipeout,
+            Entity::Face3D(_) => EntityKind::Face3D,
+            Entity::Shape(_) => EntityKind::Shape,
+            Entity::Underlay(_) => EntityKind::Underlay,
+            Entity::RevisionCloud(_) => EntityKind::RevisionCloud,
+        }
+    }
+
+    /// 类型名，与 [`Entity::kind_name`] 一致；参考底图统称 `UNDERLAY`。
+    pub fn name(self) -> &'static str {
+        match self {
+            EntityKind::Line => "LINE",
+            EntityKind::Circle => "CIRCLE",
+            EntityKind::Arc => "ARC",
+            EntityKind::Ellipse => "ELLIPSE",
+            EntityKind::Polyline => "LWPOLYLINE",
+            EntityKind::Spline => "SPLINE",
+            EntityKind::Text => "TEXT",
+            EntityKind::MText => "MTEXT",
+            EntityKind::BlockReference => "INSERT",
+            EntityKind::Hatch => "HATCH",
+            EntityKind::Dimension => "DIMENSION",
+            EntityKind::Leader => "LEADER",
+            EntityKind::MLeader => "MULTILEADER",
+            EntityKind::RasterImage => "IMAGE",
+            EntityKind::Wipeout => "WIPEOUT",
+            EntityKind::Face3D => "3DFACE",
+            EntityKind::Shape => "SHAPE",
+            EntityKind::Underlay => "UNDERLAY",
+            EntityKind::RevisionCloud => "REVCLOUD",
+        }
+    }
+
+    /// 按类型名解析，不区分大小写；另接受 `POLYLINE` 与具体的参考底图实体名。
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_uppercase();
+        match name.as_str() {
+            "POLYLINE" => Some(EntityKind::Polyline),
+            "PDFUNDERLAY" | "DGNUNDERLAY" | "DWFUNDERLAY" => Some(EntityKind::Underlay),
+            _ => Self::ALL.into_iter().find(|kind| kind.name() == name),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum FilterError {
+    #[error("invalid text pattern: {0}")]
+    InvalidPattern(#[from] regex::Error),
+}
+
+/// 实体过滤条件，见模块说明。
+#[derive(Debug, Clone, Default)]
+pub struct EntityFilter {
+    kinds: Vec<EntityKind>,
+    layers: Vec<String>,
+    colors: Vec<ObjectColor>,
+    text: Option<Regex>,
+    blocks: Vec<String>,
+    area: Option<(f64, f64)>,
+}
+
+impl EntityFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn kind(mut self, kind: EntityKind) -> Self {
+        self.kinds.push(kind);
+        self
+    }
+
+    pub fn layer(mut self, layer: impl Into<String>) -> Self {
+        self.layers.push(layer.into());
+        self
+    }
+
+    /// 按颜色筛选：随层、随块比较实体自身的颜色设置，具体颜色比较解析后实际显示的颜色。
+    pub fn color(mut self, color: ObjectColor) -> Self {
+        self.colors.push(color);
+        self
+    }
+
+    /// 文字内容匹配正则表达式：单行与多行文字取其内容，多重引线取文字内容，块参照取各属性值。
+    pub fn text(mut self, pattern: &str) -> Result<Self, FilterError> {
+        self.text = Some(Regex::new(pattern)?);
+        Ok(self)
+    }
+
+    /// 块参照的块名。
+    pub fn block(mut self, name: impl Into<String>) -> Self {
+        self.blocks.push(name.into());
+        self
+    }
+
+    /// 面积（取绝对值）落在 `min..=max` 内；不可测量面积的实体不匹配。
+    pub fn area(mut self, min: f64, max: f64) -> Self {
+        self.area = Some((min, max));
+        self
+    }
+
+    /// 未设置任何条件。
+    pub fn is_empty(&self) -> bool {
+        self.kinds.is_empty()
+            && self.layers.is_empty()
+            && self.colors.is_empty()
+            && self.text.is_none()
+            && self.blocks.is_empty()
+            && self.area.is_none()
+    }
+
+    /// 判断模型空间中的实体是否满足全部条件。
+    pub fn matches(&self, document: &Document, id: EntityId, entity: &Entity) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(&EntityKind::of(entity)))
+            && (self.layers.is_empty()
+                || self
+                    .layers
+                    .iter()
+                    .any(|layer| layer.eq_ignore_ascii_case(entity.layer_name())))
+            && (self.colors.is_empty() || self.matches_color(document, id))
+            && self
+                .text
+                .as_ref()
+                .is_none_or(|pattern| texts(entity).any(|text| pattern.is_match(text)))
+            && (self.blocks.is_empty()
+                || matches!(entity, Entity::BlockReference(reference)
+                    if self.blocks.iter().any(|name| name.eq_ignore_ascii_case(&reference.name))))
+            && self.area.is_none_or(|(min, max)| {
+                entity
+                    .measure()
+                    .is_some_and(|measurement| (min..=max).contains(&measurement.area.abs()))
+            })
+    }
+
+    /// 模型空间中满足条件的实体，按文档顺序返回。
+    pub fn query(&self, document: &Document) -> Vec<EntityId> {
+        document
+            .entities()
+            .filter(|(id, entity)| self.matches(document, *id, entity))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    fn matches_color(&self, document: &Document, id: EntityId) -> bool {
+        let own = document
+            .entity_properties(id)
+            .map_or(ObjectColor::ByLayer, |properties| properties.color);
+        let resolved = document
+            .resolve_properties(id)
+            .map(|resolved| resolved.color);
+        self.colors.iter().any(|color| match color {
+            ObjectColor::ByLayer | ObjectColor::ByBlock => own == *color,
+            concrete => resolved == Some(*concrete),
+        })
+    }
+}
+
+/// 实体中可供文字匹配的内容。
+fn texts(entity: &Entity) -> Box<dyn Iterator<Item = &str> + '_> {
+    match entity {
+        Entity::Text(text) => Box::new(std::iter::once(text.content.as_str())),
+        Entity::MText(mtext) => Box::new(std::iter::once(mtext.content.as_str())),
+        Entity::MLeader(mleader) => match &mleader.content {
+            MLeaderContent::MText { text, .. } => Box::new(std::iter::once(text.as_str())),
+            _ => Box::new(std::iter::empty()),
+        },
+        Entity::BlockReference(reference) => Box::new(
+            reference
+                .attributes
+                .iter()
+                .map(|attribute| attribute.text.as_str()),
+        ),
+        _ => Box::new(std::iter::empty()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zcad_core::document::EntityProperties;
+    use zcad_core::geometry::Point2;
+
+    use super::*;
+
+    #[test]
+    fn filters_combine_criteria() {
+        let mut doc = Document::new();
+        let small = doc.add_circle(Point2::new(0.0, 0.0), 1.0, "WALLS");
+        let large = doc.add_circle(Point2::new(10.0, 0.0), 10.0, "walls");
+        let line = doc.add_line(Point2::new(0.0, 0.0), Point2::new(5.0, 0.0), "WALLS");
+        let note = doc.add_text(Point2::new(0.0, 5.0), "DN100 管道", 2.5, 0.0, "NOTES");
+        doc.add_text(Point2::new(0.0, 9.0), "说明", 2.5, 0.0, "NOTES");
+        doc.set_entity_properties(
+            line,
+            EntityProperties {
+                color: ObjectColor::Index(1),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(EntityFilter::new().query(&doc).len(), 5);
+        let circles = EntityFilter::new().kind(EntityKind::Circle).layer("WALLS");
+        assert_eq!(circles.query(&doc), [small, large]);
+        assert_eq!(circles.area(100.0, f64::INFINITY).query(&doc), [large]);
+
+        let red = EntityFilter::new().color(ObjectColor::Index(1));
+        assert_eq!(red.query(&doc), [line]);
+        let by_layer = EntityFilter::new()
+            .layer("WALLS")
+            .color(ObjectColor::ByLayer);
+        assert_eq!(by_layer.query(&doc), [small, large]);
+
+        let pipes = EntityFilter::new().text(r"^DN\d+").unwrap();
+        assert_eq!(pipes.query(&doc), [note]);
+        assert!(matches!(
+            EntityFilter::new().text("("),
+            Err(FilterError::InvalidPattern(_))
+        ));
+
+        assert_eq!(
+            EntityKind::from_name("lwpolyline"),
+            Some(EntityKind::Polyline)
+        );
+        assert_eq!(
+            EntityKind::from_name("INSERT"),
+            Some(EntityKind::BlockReference)
+        );
+        assert_eq!(EntityKind::from_name("SOLID"), None);
+    }
+}
@@ -0,0 +1,145 @@
+//! 栅格与栅格捕捉：栅格显示设置，以及把输入坐标圆整到最近的捕捉点。
+//!
+//! 矩形栅格的捕捉点位于以 `origin` 为基点、间距为 `snap_spacing` 的方格上；等轴测栅格的捕捉点沿
+//! 30°、90° 与 150° 三个等轴测轴排列，相邻点距离均为 `snap_spacing`。
+
+use zcad_config::GridConfig;
+use zcad_core::geometry::Point2;
+
+/// 栅格样式。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum GridStyle {
+    #[default]
+    Rectangular,
+    Isometric,
+}
+
+/// 栅格设置。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridSettings {
+    /// 栅格显示间距。
+    pub spacing: f64,
+    pub snap_spacing: f64,
+    pub visible: bool,
+    /// 是否启用栅格捕捉。
+    pub snap: bool,
+    pub style: GridStyle,
+    /// 栅格基点。
+    pub origin: Point2,
+}
+
+impl GridSettings {
+    pub fn from_config(config: &GridConfig) -> Self {
+        Self {
+            spacing: config.spacing,
+            snap_spacing: config.snap_spacing,
+            visible: config.visible,
+            snap: config.snap,
+            style: if config.isometric {
+                GridStyle::Isometric
+            } else {
+                GridStyle::Rectangular
+            },
+            origin: Point2::new(0.0, 0.0),
+        }
+    }
+
+    /// 离 `point` 最近的捕捉点，不论是否启用栅格捕捉；间距不为正数时原样返回。
+    pub fn round(&self, point: Point2) -> Point2 {
+        let spacing = self.snap_spacing;
+        if !(spacing > 0.0 && spacing.is_finite()) {
+            return point;
+        }
+        let (x, y) = (point.x() - self.origin.x(), point.y() - self.origin.y());
+        let (x, y) = match self.style {
+            GridStyle::Rectangular => (
+                (x / spacing).round() * spacing,
+                (y / spacing).round() * spacing,
+            ),
+            GridStyle::Isometric => {
+                // 等轴测点阵由两组宽 √3·s、高 s 的矩形点阵错开半格叠加而成
+                let (width, height) = (3.0_f64.sqrt() * spacing, spacing);
+                let on = |offset_x: f64, offset_y: f64| {
+                    (
+                        ((x - offset_x) / width).round() * width + offset_x,
+                        ((y - offset_y) / height).round() * height + offset_y,
+                    )
+                };
+                let first = on(0.0, 0.0);
+                let second = on(width / 2.0, height / 2.0);
+                let distance = |(px, py): (f64, f64)| (px - x).powi(2) + (py - y).powi(2);
+                if distance(second) < distance(first) {
+                    second
+                } else {
+                    first
+                }
+            }
+        };
+        Point2::new(x + self.origin.x(), y + self.origin.y())
+    }
+}
+
+impl Default for GridSettings {
+    fn default() -> Self {
+        Self::from_config(&GridConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::Scene;
+
+    #[test]
+    fn rounds_to_rectangular_and_isometric_points() {
+        let mut grid = GridSettings {
+            snap_spacing: 5.0,
+            origin: Point2::new(1.0, 0.0),
+            ..GridSettings::default()
+        };
+        assert_eq!(grid.round(Point2::new(8.9, -2.4)), Point2::new(11.0, 0.0));
+        assert_eq!(grid.round(Point2::new(-1.6, 13.0)), Point2::new(-4.0, 15.0));
+
+        grid.style = GridStyle::Isometric;
+        grid.origin = Point2::new(0.0, 0.0);
+        let width = 3.0_f64.sqrt() * 5.0;
+        assert_eq!(
+            grid.round(Point2::new(4.0, 2.0)),
+            Point2::new(width / 2.0, 2.5)
+        );
+        assert_eq!(grid.round(Point2::new(0.5, 4.0)), Point2::new(0.0, 5.0));
+        assert_eq!(grid.round(Point2::new(7.9, 0.4)), Point2::new(width, 0.0));
+
+        grid.snap_spacing = 0.0;
+        assert_eq!(grid.round(Point2::new(0.3, 0.4)), Point2::new(0.3, 0.4));
+    }
+
+    #[test]
+    fn scene_snaps_input_only_when_enabled() {
+        let mut scene = Scene::new();
+        assert_eq!(*scene.grid(), GridSettings::default());
+        let point = Point2::new(12.0, 26.0);
+        assert_eq!(scene.snap_to_grid(point), point);
+
+        scene.set_snap_enabled(true);
+        assert_eq!(scene.snap_to_grid(point), Point2::new(10.0, 30.0));
+        scene.set_grid(GridSettings {
+            snap_spacing: 4.0,
+            ..*scene.grid()
+        });
+        assert_eq!(scene.snap_to_grid(point), Point2::new(12.0, 28.0));
+
+        scene.set_grid_visible(true);
+        scene.reset();
+        assert!(scene.grid().visible && scene.grid().snap);
+
+        let config = GridConfig {
+            isometric: true,
+            ..GridConfig::default()
+        };
+        assert_eq!(
+            GridSettings::from_config(&config).style,
+            GridStyle::Isometric
+        );
+    }
+}
@@ -0,0 +1,408 @@
+//! 文本命令解释器：解析经典命令行输入（如 `LINE 0,0 100,0`、`CIRCLE 50,50 10`、`ZOOM E`、
+//! `ERASE LAST`）并调用场景与文档操作。
+//!
+//! 命令名不区分大小写，支持常用简写。点可写为绝对坐标 `x,y`、相对上一点的 `@dx,dy`、
+//! 相对极坐标 `@r<角度` 或以原点为基点的极坐标 `r<角度`，角度单位为度。
+//! 新建实体按场景的当前图层与特性创建，每条命令作为一步进入撤销历史。
+
+use std::f64::consts::PI;
+
+use thiserror::Error;
+use zcad_core::document::EntityId;
+use zcad_core::geometry::{Point2, Vector2};
+
+use crate::errors::EngineError;
+use crate::scene::Scene;
+use crate::script::CommandMacro;
+
+#[derive(Debug, Error)]
+pub enum InterpreterError {
+    #[error("empty command line")]
+    Empty,
+    #[error("unknown command: {0}")]
+    UnknownCommand(String),
+    #[error("{command} expects {expected}")]
+    MissingArgument {
+        command: &'static str,
+        expected: &'static str,
+    },
+    #[error("{command}: unexpected argument {argument}")]
+    UnexpectedArgument {
+        command: &'static str,
+        argument: String,
+    },
+    #[error("invalid point: {0}")]
+    InvalidPoint(String),
+    #[error("invalid number: {0}")]
+    InvalidNumber(String),
+    #[error("relative point {0} needs a previous point")]
+    NoLastPoint(String),
+    #[error("nothing to erase")]
+    NothingToErase,
+    #[error(transparent)]
+    Engine(#[from] EngineError),
+}
+
+/// 命令行解释器；记录上一点（相对坐标的基点）与最近创建的实体。
+#[derive(Debug, Clone, Default)]
+pub struct Interpreter {
+    last_point: Option<Point2>,
+    last_created: Option<EntityId>,
+    /// 正在录制的宏：执行成功的命令行。
+    recording: Option<Vec<String>>,
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 上一次输入的点，相对坐标以此为基点。
+    #[inline]
+    pub fn last_point(&self) -> Option<Point2> {
+        self.last_point
+    }
+
+    /// 开始录制宏，之后执行成功的命令都会被记下；已在录制时重新开始。
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Vec::new());
+    }
+
+    /// 结束录制并返回录下的宏；未在录制时返回 `None`。
+    pub fn stop_recording(&mut self) -> Option<CommandMacro> {
+        self.recording.take().map(CommandMacro::new)
+    }
+
+    #[inline]
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// 执行一行命令，返回执行结果的说明；命令失败时上一点保持不变。
+    pub fn execute(&mut self, scene: &mut Scene, line: &str) -> Result<String, InterpreterError> {
+        let last_point = self.last_point;
+        let message = self.dispatch(scene, line).inspect_err(|_| {
+            self.last_point = last_point;
+        })?;
+        if let Some(recording) = &mut self.recording {
+            recording.push(line.trim().to_string());
+        }
+        Ok(message)
+    }
+
+    fn dispatch(&mut self, scene: &mut Scene, line: &str) -> Result<String, InterpreterError> {
+        let mut tokens = line.split_whitespace();
+        let name = tokens.next().ok_or(InterpreterError::Empty)?;
+        let args: Vec<&str> = tokens.collect();
+        match name.to_ascii_uppercase().as_str() {
+            "LINE" | "L" => self.line(scene, &args),
+            "PLINE" | "PL" => self.pline(scene, &args),
+            "CIRCLE" | "C" => self.circle(scene, &args),
+            "ERASE" | "E" => self.erase(scene, &args),
+            "ZOOM" | "Z" => zoom(scene, &args),
+            "UNDO" | "U" => match scene.undo() {
+                Some(name) => Ok(format!("已撤销: {name}")),
+                None => Ok("没有可撤销的操作".to_string()),
+            },
+            "REDO" => match scene.redo()? {
+                Some(name) => Ok(format!("已重做: {name}")),
+                None => Ok("没有可重做的操作".to_string()),
+            },
+            _ => Err(InterpreterError::UnknownCommand(name.to_string())),
+        }
+    }
+
+    /// `LINE p1 p2 [p3 ...]`：依次连接各点，每段一条直线。
+    fn line(&mut self, scene: &mut Scene, args: &[&str]) -> Result<String, InterpreterError> {
+        let points = self.points(args)?;
+        if points.len() < 2 {
+            return Err(InterpreterError::MissingArgument {
+                command: "LINE",
+                expected: "at least two points",
+            });
+        }
+        scene.begin_undo_group("LINE");
+        let mut last = None;
+        for segment in points.windows(2) {
+            let (start, end) = (segment[0], segment[1]);
+            last = Some(scene.draft(|document, layer| document.add_line(start, end, layer)));
+        }
+        scene.end_undo_group();
+        self.last_created = last;
+        Ok(format!("已绘制 {} 条直线", points.len() - 1))
+    }
+
+    /// `PLINE p1 p2 [p3 ...] [CLOSE]`：以各点为顶点绘制多段线，末尾的 `C`/`CLOSE` 表示闭合。
+    fn pline(&mut self, scene: &mut Scene, args: &[&str]) -> Result<String, InterpreterError> {
+        let (closed, args) = match args.split_last() {
+            Some((last, rest)) if matches!(last.to_ascii_uppercase().as_str(), "C" | "CLOSE") => {
+                (true, rest)
+            }
+            _ => (false, args),
+        };
+        let points = self.points(args)?;
+        if points.len() < 2 {
+            return Err(InterpreterError::MissingArgument {
+                command: "PLINE",
+                expected: "at least two points",
+            });
+        }
+        let count = points.len();
+        let id = scene.draft(|document, layer| document.add_polyline(points, closed, layer));
+        self.last_created = Some(id);
+        Ok(format!("已绘制 {count} 个顶点的多段线"))
+    }
+
+    /// `CIRCLE center radius`，半径也可以给成圆周上的一点。
+    fn circle(&mut self, scene: &mut Scene, args: &[&str]) -> Result<String, InterpreterError> {
+        let [center, size] = args else {
+            return Err(InterpreterError::MissingArgument {
+                command: "CIRCLE",
+                expected: "a center and a radius",
+            });
+        };
+        let center = self.point(center)?;
+        let radius = match size.parse::<f64>() {
+            Ok(radius) => radius,
+            Err(_) if is_point(size) => center.as_vec2().distance(self.point(size)?.as_vec2()),
+            Err(_) => return Err(InterpreterError::InvalidNumber(size.to_string())),
+        };
+        if !(radius.is_finite() && radius > 0.0) {
+            return Err(InterpreterError::InvalidNumber(size.to_string()));
+        }
+        let id = scene.draft(|document, layer| document.add_circle(center, radius, layer));
+        self.last_point = Some(center);
+        self.last_created = Some(id);
+        Ok(format!("已绘制半径 {radius} 的圆"))
+    }
+
+    /// `ERASE LAST|ALL`；不带参数时删除当前选中的实体。
+    fn erase(&mut self, scene: &mut Scene, args: &[&str]) -> Result<String, InterpreterError> {
+        let ids: Vec<EntityId> = match args {
+            [] => scene.selection().collect(),
+            [target] => match target.to_ascii_uppercase().as_str() {
+                "LAST" | "L" => self
+                    .last_created
+                    .filter(|id| scene.entity(*id).is_some())
+                    .or_else(|| scene.document().entities().last().map(|(id, _)| *id))
+                    .into_iter()
+                    .collect(),
+                "ALL" => scene.document().entities().map(|(id, _)| *id).collect(),
+                _ => {
+                    return Err(InterpreterError::UnexpectedArgument {
+                        command: "ERASE",
+                        argument: target.to_string(),
+                    });
+                }
+            },
+            [_, extra, ..] => {
+                return Err(InterpreterError::UnexpectedArgument {
+                    command: "ERASE",
+                    argument: extra.to_string(),
+                });
+            }
+        };
+        if ids.is_empty() {
+            return Err(InterpreterError::NothingToErase);
+        }
+        scene.transaction("ERASE", |txn| {
+            for &id in &ids {
+                txn.remove_entity(id)
+                    .ok_or(EngineError::EntityNotFound(id.get()))?;
+            }
+            Ok::<_, EngineError>(())
+        })?;
+        self.last_created = None;
+        Ok(format!("已删除 {} 个实体", ids.len()))
+    }
+
+    fn points(&mut self, args: &[&str]) -> Result<Vec<Point2>, InterpreterError> {
+        args.iter().map(|arg| self.point(arg)).collect()
+    }
+
+    /// 解析一个点并把它记为上一点。
+    fn point(&mut self, text: &str) -> Result<Point2, InterpreterError> {
+        let (relative, body) = match text.strip_prefix('@') {
+            Some(body) => (true, body),
+            None => (false, text),
+        };
+        let offset =
+            parse_offset(body).ok_or_else(|| InterpreterError::InvalidPoint(text.into()))?;
+        let base = if relative {
+            self.last_point
+                .ok_or_else(|| InterpreterError::NoLastPoint(text.into()))?
+        } else {
+            Point2::new(0.0, 0.0)
+        };
+        let point = base.translate(offset);
+        // 相对坐标累加后仍可能溢出
+        if !(point.x().is_finite() && point.y().is_finite()) {
+            return Err(InterpreterError::InvalidPoint(text.into()));
+        }
+        self.last_point = Some(point);
+        Ok(point)
+    }
+}
+
+/// `ZOOM E|EXTENTS` 显示全部实体；`ZOOM <倍数>` 按倍数缩放。
+fn zoom(scene: &mut Scene, args: &[&str]) -> Result<String, InterpreterError> {
+    let [option] = args else {
+        return Err(InterpreterError::MissingArgument {
+            command: "ZOOM",
+            expected: "E(xtents) or a scale factor",
+        });
+    };
+    match option.to_ascii_uppercase().as_str() {
+        "E" | "EXTENTS" => {
+            scene.zoom_extents();
+            Ok("已缩放至图形范围".to_string())
+        }
+        factor => {
+            let factor = factor
+                .strip_suffix('X')
+                .unwrap_or(factor)
+                .parse::<f64>()
+                .ok()
+                .filter(|factor| factor.is_finite() && *factor > 0.0)
+                .ok_or_else(|| InterpreterError::InvalidNumber(option.to_string()))?;
+            scene.scale_viewport_zoom(factor);
+            Ok(format!("缩放倍数 {}", scene.viewport().zoom))
+        }
+    }
+}
+
+/// 解析 `x,y` 或 `r<角度` 形式的偏移；`NaN` 与无穷大不是有效坐标。
+fn parse_offset(text: &str) -> Option<Vector2> {
+    if let Some((distance, angle)) = text.split_once('<') {
+        let distance = parse_finite(distance)?;
+        let angle = parse_finite(angle)? * PI / 180.0;
+        return Some(Vector2::new(distance * angle.cos(), distance * angle.sin()));
+    }
+    let (x, y) = text.split_once(',')?;
+    Some(Vector2::new(parse_finite(x)?, parse_finite(y)?))
+}
+
+fn parse_finite(text: &str) -> Option<f64> {
+    text.trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+}
+
+fn is_point(text: &str) -> bool {
+    text.contains(',') || text.contains('<')
+}
+
+#[cfg(test)]
+mod tests {
+    use zcad_core::document::Entity;
+
+    use super::*;
+
+    fn assert_point(actual: Point2, x: f64, y: f64) {
+        assert!(
+            (actual.x() - x).abs() < 1e-9 && (actual.y() - y).abs() < 1e-9,
+            "{actual:?} != ({x}, {y})"
+        );
+    }
+
+    #[test]
+    fn draws_with_absolute_relative_and_polar_points() {
+        let mut scene = Scene::new();
+        let mut interpreter = Interpreter::new();
+
+        interpreter
+            .execute(&mut scene, "LINE 0,0 100,0 @0,50 @50<180")
+            .unwrap();
+        let lines: Vec<(Point2, Point2)> = scene
+            .document()
+            .entities()
+            .filter_map(|(_, entity)| match entity {
+                Entity::Line(line) => Some((line.start, line.end)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_point(lines[1].1, 100.0, 50.0);
+        assert_point(lines[2].1, 50.0, 50.0);
+        // 多段直线作为一步撤销
+        assert_eq!(scene.history().undo_len(), 1);
+
+        interpreter.execute(&mut scene, "c 50,50 @10,0").unwrap();
+        let Some((_, Entity::Circle(circle))) = scene.document().entities().last() else {
+            panic!("应创建圆");
+        };
+        assert!((circle.radius - 10.0).abs() < 1e-9);
+        assert_point(interpreter.last_point().unwrap(), 50.0, 50.0);
+
+        interpreter
+            .execute(&mut scene, "PLINE 0,0 10<90 @10,0 C")
+            .unwrap();
+        let Some((_, Entity::Polyline(polyline))) = scene.document().entities().last() else {
+            panic!("应创建多段线");
+        };
+        assert!(polyline.is_closed);
+        assert_eq!(polyline.vertices.len(), 3);
+        assert_point(polyline.vertices[2].position, 10.0, 10.0);
+    }
+
+    #[test]
+    fn erases_zooms_and_reports_errors() {
+        let mut scene = Scene::new();
+        let ids = scene.populate_demo();
+        let mut interpreter = Interpreter::new();
+
+        interpreter.execute(&mut scene, "CIRCLE 0,0 5").unwrap();
+        interpreter.execute(&mut scene, "ERASE LAST").unwrap();
+        assert_eq!(scene.document().entities().count(), 5);
+        interpreter.execute(&mut scene, "erase last").unwrap();
+        assert!(scene.entity(ids.label).is_none());
+        interpreter.execute(&mut scene, "U").unwrap();
+        assert!(scene.entity(ids.label).is_some());
+
+        scene.set_viewport_center(Point2::new(500.0, 500.0));
+        interpreter.execute(&mut scene, "ZOOM E").unwrap();
+        assert_point(scene.viewport().center, 50.0, 18.75);
+        interpreter.execute(&mut scene, "Z 2X").unwrap();
+        assert!((scene.viewport().zoom - 2.0).abs() < 1e-9);
+
+        let mut fresh = Interpreter::new();
+        assert!(matches!(
+            fresh.execute(&mut scene, "LINE @1,1 2,2"),
+            Err(InterpreterError::NoLastPoint(_))
+        ));
+        assert!(matches!(
+            fresh.execute(&mut scene, "LINE 0,0"),
+            Err(InterpreterError::MissingArgument { .. })
+        ));
+        let count = scene.document().entities().count();
+        for line in [
+            "LINE inf,0 0,0",
+            "LINE 0,0 NaN,1",
+            "LINE 0,0 1<inf",
+            "LINE 1e308,0 @1e308,0",
+        ] {
+            assert!(matches!(
+                fresh.execute(&mut scene, line),
+                Err(InterpreterError::InvalidPoint(_))
+            ));
+        }
+        assert_eq!(scene.document().entities().count(), count);
+        assert!(matches!(
+            fresh.execute(&mut scene, "CIRCLE 0,0 x"),
+            Err(InterpreterError::InvalidNumber(_))
+        ));
+        assert!(matches!(
+            fresh.execute(&mut scene, "ERASE"),
+            Err(InterpreterError::NothingToErase)
+        ));
+        assert!(matches!(
+            fresh.execute(&mut scene, "EXPLODE"),
+            Err(InterpreterError::UnknownCommand(_))
+        ));
+        assert!(matches!(
+            fresh.execute(&mut scene, "   "),
+            Err(InterpreterError::Empty)
+        ));
+    }
+}
@@ -1,4 +1,16 @@
 pub mod command;
+pub mod drafting;
+pub mod events;
+pub mod filter;
+pub mod grid;
+pub mod interpreter;
+pub mod plugin;
+pub mod script;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+pub mod selection;
+pub mod snap;
+pub mod tracking;
 
 pub mod errors {
     use thiserror::Error;
@@ -9,6 +21,8 @@ pub mod errors {
         DocumentNotInitialized,
         #[error("entity with id {0} not found")]
         EntityNotFound(u64),
+        #[error("grip {1} of entity {0} cannot be moved to the target point")]
+        InvalidGrip(u64, usize),
     }
 }
 
@@ -16,17 +30,29 @@ pub mod scene {
     use std::collections::HashSet;
 
     use tracing::debug;
-    use zcad_core::document::{Document, Entity, EntityId};
+    use zcad_core::document::{
+        Change, Document, Entity, EntityId, Grip, Layer, OsnapMode, TableSnapshot,
+    };
     use zcad_core::geometry::{Bounds2D, Point2, Vector2};
 
+    use crate::command::{Command, CommandHistory, DocumentChange, Transaction};
+    use crate::drafting::DraftingContext;
     use crate::errors::EngineError;
+    use crate::events::{EventBus, SceneEvent, Subscription};
+    use crate::filter::EntityFilter;
+    use crate::grid::GridSettings;
+    use crate::selection;
+    use crate::snap::{self, OsnapModes, SnapResult};
+    use crate::tracking::{ConstrainedPoint, TrackingSettings};
 
     const DEFAULT_ZOOM: f64 = 1.0;
     const MIN_ZOOM: f64 = 0.01;
     const MAX_ZOOM: f64 = 1_000.0;
+    /// 拾取时块嵌套展开的最大层数，防止循环引用的块无限展开。
+    const MAX_PICK_DEPTH: usize = 16;
 
     /// 记录视口状态（中心点与缩放），后续可扩展旋转角与裁剪范围。
-    #[derive(Debug, Clone, Copy)]
+    #[derive(Debug, Clone, Copy, PartialEq)]
     pub struct ViewportState {
         pub center: Point2,
         pub zoom: f64,
@@ -54,6 +80,12 @@ pub mod scene {
         document: Document,
         selected: HashSet<EntityId>,
         viewport: ViewportState,
+        drafting: DraftingContext,
+        osnap: OsnapModes,
+        grid: GridSettings,
+        tracking: TrackingSettings,
+        history: CommandHistory,
+        events: EventBus,
     }
 
     #[derive(Debug, Clone, Copy)]
@@ -71,6 +103,12 @@ pub mod scene {
                 document: Document::new(),
                 selected: HashSet::new(),
                 viewport: ViewportState::default(),
+                drafting: DraftingContext::default(),
+                osnap: OsnapModes::default(),
+                grid: GridSettings::default(),
+                tracking: TrackingSettings::default(),
+                history: CommandHistory::default(),
+                events: EventBus::new(),
             }
         }
 
@@ -81,22 +119,110 @@ pub mod scene {
             scene
         }
 
-        /// 使用默认样例重置场景。调用后会清空文档和选中状态。
+        /// 使用默认样例重置场景。调用后会清空文档、选中状态与撤销历史。
         pub fn reset(&mut self) {
             self.document = Document::new();
             self.selected.clear();
             self.viewport = ViewportState::default();
+            self.drafting = DraftingContext::default();
+            self.osnap = OsnapModes::default();
+            self.history.clear();
+            self.emit(SceneEvent::DocumentReplaced);
         }
 
-        /// 替换当前文档并重置运行时状态。
+        /// 替换当前文档并重置运行时状态，撤销历史随之清空。
         pub fn load_document(&mut self, document: Document) {
             self.document = document;
             self.selected.clear();
             self.viewport = ViewportState::default();
+            self.history.clear();
 
             if let Some(bounds) = self.document.bounds() {
                 self.viewport.center = bounds.center();
             }
+            self.emit(SceneEvent::DocumentReplaced);
+        }
+
+        /// 订阅场景事件，见 [`crate::events`]；返回的句柄用于取消订阅。
+        pub fn subscribe(
+            &mut self,
+            listener: impl FnMut(&SceneEvent) + Send + 'static,
+        ) -> Subscription {
+            self.events.subscribe(listener)
+        }
+
+        /// 取消订阅，返回该订阅此前是否存在。
+        pub fn unsubscribe(&mut self, subscription: Subscription) -> bool {
+            self.events.unsubscribe(subscription)
+        }
+
+        fn emit(&mut self, event: SceneEvent) {
+            if self.events.has_subscribers() {
+                self.events.emit(&event);
+            }
+        }
+
+        /// 文档修改被执行（`revert` 为假）或撤销后发出对应的实体事件。
+        pub(crate) fn emit_changes(&mut self, changes: &[Change], revert: bool) {
+            if !self.events.has_subscribers() {
+                return;
+            }
+            if revert {
+                for change in changes.iter().rev() {
+                    self.events.emit(&SceneEvent::reverted(change));
+                }
+            } else {
+                for change in changes {
+                    self.events.emit(&SceneEvent::applied(change));
+                }
+            }
+        }
+
+        /// 有订阅者时记录图层表，供 [`Scene::emit_layer_changes`] 比较。
+        pub(crate) fn layer_snapshot(&self) -> Option<Vec<Layer>> {
+            self.events
+                .has_subscribers()
+                .then(|| self.document.layers().cloned().collect())
+        }
+
+        /// 对比快照，为新建、删除或属性变化的图层发出事件。
+        pub(crate) fn emit_layer_changes(&mut self, before: Option<Vec<Layer>>) {
+            let Some(before) = before else {
+                return;
+            };
+            let mut changed: Vec<String> = before
+                .iter()
+                .filter(|layer| self.document.layer(&layer.name) != Some(*layer))
+                .map(|layer| layer.name.clone())
+                .collect();
+            changed.extend(
+                self.document
+                    .layers()
+                    .filter(|layer| !before.iter().any(|old| old.name == layer.name))
+                    .map(|layer| layer.name.clone()),
+            );
+            for name in changed {
+                self.events.emit(&SceneEvent::LayerChanged(name));
+            }
+        }
+
+        /// 修改选中集；选中集有变化时发出事件。
+        fn update_selection<R>(&mut self, update: impl FnOnce(&mut HashSet<EntityId>) -> R) -> R {
+            let before = self.selected.len();
+            let result = update(&mut self.selected);
+            if self.selected.len() != before {
+                self.emit(SceneEvent::SelectionChanged);
+            }
+            result
+        }
+
+        /// 修改视口；视口有变化时发出事件。
+        fn update_viewport(&mut self, update: impl FnOnce(&mut ViewportState)) {
+            let before = self.viewport;
+            update(&mut self.viewport);
+            if self.viewport != before {
+                self.emit(SceneEvent::ViewportChanged(self.viewport));
+            }
         }
 
         /// 返回当前选中实体数量。
@@ -116,13 +242,13 @@ pub mod scene {
             if self.document.entity(id).is_none() {
                 return Err(EngineError::EntityNotFound(id.get()));
             }
-            self.selected.insert(id);
+            self.update_selection(|selected| selected.insert(id));
             Ok(())
         }
 
         /// 取消选中指定实体，返回之前是否处于选中状态。
         pub fn deselect(&mut self, id: EntityId) -> bool {
-            self.selected.remove(&id)
+            self.update_selection(|selected| selected.remove(&id))
         }
 
         /// 切换实体选中状态，返回切换后的状态。
@@ -130,18 +256,21 @@ pub mod scene {
             if self.document.entity(id).is_none() {
                 return Err(EngineError::EntityNotFound(id.get()));
             }
-            if !self.selected.insert(id) {
-                self.selected.remove(&id);
-                Ok(false)
-            } else {
-                Ok(true)
-            }
+            let selected = self.update_selection(|selected| {
+                if !selected.insert(id) {
+                    selected.remove(&id);
+                    false
+                } else {
+                    true
+                }
+            });
+            Ok(selected)
         }
 
         /// 清空当前选中集。
         #[inline]
         pub fn clear_selection(&mut self) {
-            self.selected.clear();
+            self.update_selection(HashSet::clear);
         }
 
         /// 以迭代形式返回当前选中实体 ID。
@@ -163,6 +292,242 @@ pub mod scene {
             if has { Some(bounds) } else { None }
         }
 
+        /// 拾取 `point` 处的实体：返回几何落在拾取框半径 `aperture` 内的最上层（绘制顺序最后）实体，
+        /// 隐藏图层上的实体不参与拾取。
+        ///
+        /// 先以空间索引按范围筛出候选，再用最近点判定几何是否经过拾取框：圆内部、多段线围出的空白处
+        /// 不会选中，文字等区域实体在其轮廓内即可选中。块参照与标注按分解后的组成实体判定。
+        pub fn pick(&self, point: Point2, aperture: f64) -> Option<EntityId> {
+            let candidates = self.document.query_point(point, aperture);
+            self.document
+                .in_draw_order(candidates)
+                .into_iter()
+                .rev()
+                .find(|(id, entity)| {
+                    self.is_pickable(*id)
+                        && self
+                            .hit_distance(entity, point, 0)
+                            .is_some_and(|distance| distance <= aperture)
+                })
+                .map(|(id, _)| *id)
+        }
+
+        /// 实体几何到 `point` 的距离；块参照与标注逐层分解后取组成实体的最小值。
+        fn hit_distance(&self, entity: &Entity, point: Point2, depth: usize) -> Option<f64> {
+            if depth < MAX_PICK_DEPTH
+                && matches!(entity, Entity::BlockReference(_) | Entity::Dimension(_))
+                && let Some(parts) = self.document.exploded(entity)
+            {
+                return parts
+                    .iter()
+                    .filter_map(|part| self.hit_distance(part, point, depth + 1))
+                    .min_by(f64::total_cmp)
+                    .or_else(|| entity.distance_to(point));
+            }
+            entity.distance_to(point)
+        }
+
+        /// 栅格设置，见 [`crate::grid`]；重置与载入文档时保持不变。
+        #[inline]
+        pub fn grid(&self) -> &GridSettings {
+            &self.grid
+        }
+
+        pub fn set_grid(&mut self, grid: GridSettings) {
+            self.grid = grid;
+        }
+
+        pub fn set_grid_visible(&mut self, visible: bool) {
+            self.grid.visible = visible;
+        }
+
+        /// 启用或关闭栅格捕捉。
+        pub fn set_snap_enabled(&mut self, enabled: bool) {
+            self.grid.snap = enabled;
+        }
+
+        /// 输入坐标：启用栅格捕捉时圆整到最近的捕捉点，否则原样返回。
+        pub fn snap_to_grid(&self, point: Point2) -> Point2 {
+            if self.grid.snap {
+                self.grid.round(point)
+            } else {
+                point
+            }
+        }
+
+        /// 正交与极轴追踪设置，见 [`crate::tracking`]。
+        #[inline]
+        pub fn tracking(&self) -> &TrackingSettings {
+            &self.tracking
+        }
+
+        pub fn set_tracking(&mut self, tracking: TrackingSettings) {
+            self.tracking = tracking;
+        }
+
+        pub fn set_ortho(&mut self, enabled: bool) {
+            self.tracking.ortho = enabled;
+        }
+
+        pub fn set_polar_tracking(&mut self, enabled: bool) {
+            self.tracking.polar = enabled;
+        }
+
+        /// 以上一个输入点 `base` 为基点，对光标点应用正交或极轴追踪约束。
+        pub fn constrain_input(&self, base: Point2, cursor: Point2) -> ConstrainedPoint {
+            self.tracking.constrain(base, cursor)
+        }
+
+        /// 启用的对象捕捉方式。
+        #[inline]
+        pub fn osnap_modes(&self) -> OsnapModes {
+            self.osnap
+        }
+
+        pub fn set_osnap_modes(&mut self, modes: OsnapModes) {
+            self.osnap = modes;
+        }
+
+        /// 启用或关闭单个对象捕捉方式。
+        pub fn set_osnap_mode(&mut self, mode: OsnapMode, enabled: bool) {
+            self.osnap.set(mode, enabled);
+        }
+
+        /// 光标 `cursor` 周围靶框内优先级最高的对象捕捉点，见 [`crate::snap`]；
+        /// `base` 为垂足与切点的基点（通常是上一个输入点）。
+        pub fn osnap(
+            &self,
+            cursor: Point2,
+            aperture: f64,
+            base: Option<Point2>,
+        ) -> Option<SnapResult> {
+            self.osnap_candidates(cursor, aperture, base)
+                .into_iter()
+                .next()
+        }
+
+        /// 靶框内全部启用方式的捕捉点，按优先级排序。
+        pub fn osnap_candidates(
+            &self,
+            cursor: Point2,
+            aperture: f64,
+            base: Option<Point2>,
+        ) -> Vec<SnapResult> {
+            if self.osnap.is_empty() {
+                return Vec::new();
+            }
+            let candidates: Vec<EntityId> = self
+                .document
+                .query_point(cursor, aperture)
+                .into_iter()
+                .filter(|id| self.is_pickable(*id))
+                .collect();
+            snap::find_snaps(
+                &self.document,
+                &candidates,
+                cursor,
+                aperture,
+                self.osnap,
+                base,
+            )
+        }
+
+        /// 交叉窗选：选中范围与 `area` 相交的可见实体，返回新加入选中集的数量。
+        pub fn select_crossing(&mut self, area: &Bounds2D) -> usize {
+            let candidates: Vec<EntityId> = self
+                .document
+                .query_rect(area)
+                .into_iter()
+                .filter(|id| self.is_pickable(*id))
+                .collect();
+            self.update_selection(|selected| {
+                candidates
+                    .into_iter()
+                    .filter(|id| selected.insert(*id))
+                    .count()
+            })
+        }
+
+        /// 多边形窗选（WPolygon）：选中完全落在多边形内的可见实体，返回新加入选中集的数量。
+        /// 多边形自动闭合，少于三个顶点时不选中任何实体。
+        pub fn select_window_polygon(&mut self, polygon: &[Point2]) -> usize {
+            if polygon.len() < 3 {
+                return 0;
+            }
+            self.select_matching(polygon, |paths| {
+                selection::is_within_polygon(paths, polygon)
+            })
+        }
+
+        /// 多边形交叉选择（CPolygon）：选中位于多边形内或与其边界相交的可见实体，
+        /// 返回新加入选中集的数量。
+        pub fn select_crossing_polygon(&mut self, polygon: &[Point2]) -> usize {
+            if polygon.len() < 3 {
+                return 0;
+            }
+            self.select_matching(polygon, |paths| selection::crosses_polygon(paths, polygon))
+        }
+
+        /// 栏选（Fence）：选中与折线路径相交的可见实体，返回新加入选中集的数量。
+        pub fn select_fence(&mut self, path: &[Point2]) -> usize {
+            if path.len() < 2 {
+                return 0;
+            }
+            self.select_matching(path, |paths| selection::crosses_fence(paths, path))
+        }
+
+        /// 快速选择：选中满足 `filter` 的可见实体，返回新加入选中集的数量。
+        pub fn select_where(&mut self, filter: &EntityFilter) -> usize {
+            let matched: Vec<EntityId> = filter
+                .query(&self.document)
+                .into_iter()
+                .filter(|id| self.is_pickable(*id))
+                .collect();
+            self.update_selection(|selected| {
+                matched
+                    .into_iter()
+                    .filter(|id| selected.insert(*id))
+                    .count()
+            })
+        }
+
+        /// 在 `outline` 范围内的候选中选中几何满足 `test` 的可见实体。
+        fn select_matching(
+            &mut self,
+            outline: &[Point2],
+            test: impl Fn(&[Vec<Point2>]) -> bool,
+        ) -> usize {
+            let mut area = Bounds2D::empty();
+            for point in outline {
+                area.include_point(*point);
+            }
+            let matched: Vec<EntityId> = self
+                .document
+                .query_rect(&area)
+                .into_iter()
+                .filter(|id| self.is_pickable(*id))
+                .filter(|id| {
+                    self.document.entity(*id).is_some_and(|entity| {
+                        test(&selection::entity_paths(&self.document, entity))
+                    })
+                })
+                .collect();
+            self.update_selection(|selected| {
+                matched
+                    .into_iter()
+                    .filter(|id| selected.insert(*id))
+                    .count()
+            })
+        }
+
+        fn is_pickable(&self, id: EntityId) -> bool {
+            self.document.entity(id).is_some_and(|entity| {
+                self.document
+                    .layer(entity.layer_name())
+                    .is_none_or(|layer| layer.is_displayed())
+            })
+        }
+
         /// 获取当前视口状态。
         #[inline]
         pub fn viewport(&self) -> ViewportState {
@@ -172,23 +537,23 @@ pub mod scene {
         /// 重置视口到默认状态。
         #[inline]
         pub fn reset_viewport(&mut self) {
-            self.viewport = ViewportState::default();
+            self.update_viewport(|viewport| *viewport = ViewportState::default());
         }
 
         /// 设置视口中心点。
         #[inline]
         pub fn set_viewport_center(&mut self, center: Point2) {
-            self.viewport.center = center;
+            self.update_viewport(|viewport| viewport.center = center);
         }
 
         /// 平移视口中心。
         pub fn pan_viewport(&mut self, delta: Vector2) {
-            self.viewport.center = self.viewport.center.translate(delta);
+            self.update_viewport(|viewport| viewport.center = viewport.center.translate(delta));
         }
 
         /// 设置缩放倍数（自动限制在合法范围内）。
         pub fn set_viewport_zoom(&mut self, zoom: f64) {
-            self.viewport.zoom = ViewportState::clamp_zoom(zoom);
+            self.update_viewport(|viewport| viewport.zoom = ViewportState::clamp_zoom(zoom));
         }
 
         /// 按乘法因子调整缩放。
@@ -206,20 +571,178 @@ pub mod scene {
         pub fn focus_on_selection(&mut self) {
             let target = self.selection_bounds().or_else(|| self.document.bounds());
             if let Some(bounds) = target {
-                self.viewport.center = bounds.center();
+                self.set_viewport_center(bounds.center());
             }
         }
 
+        /// 缩放至图形范围：视口中心移到文档范围中心，缩放倍数复位；空文档时只复位缩放。
+        pub fn zoom_extents(&mut self) {
+            let center = self.document.bounds().map(|bounds| bounds.center());
+            self.update_viewport(|viewport| {
+                viewport.zoom = DEFAULT_ZOOM;
+                if let Some(center) = center {
+                    viewport.center = center;
+                }
+            });
+        }
+
         #[inline]
         pub fn document(&self) -> &Document {
             &self.document
         }
 
+        /// 直接修改文档；这样的修改不进入撤销历史，之后撤销更早的步骤可能覆盖它们，
+        /// 可撤销的编辑应使用 [`Scene::edit`] 或 [`Scene::execute`]。
         #[inline]
         pub fn document_mut(&mut self) -> &mut Document {
             &mut self.document
         }
 
+        #[inline]
+        pub fn history(&self) -> &CommandHistory {
+            &self.history
+        }
+
+        /// 调整可撤销步数的上限。
+        pub fn set_history_limit(&mut self, limit: usize) {
+            self.history.set_limit(limit);
+        }
+
+        /// 执行命令并记入撤销历史；执行失败时不记录。
+        pub fn execute(&mut self, mut command: impl Command + 'static) -> Result<(), EngineError> {
+            command.execute(self)?;
+            self.prune_selection();
+            self.history.record(Box::new(command));
+            Ok(())
+        }
+
+        /// 以可撤销的方式修改文档：`edit` 期间的实体增删改，以及图层、块定义、特性覆盖、用户数据等
+        /// 其他文档状态的变化被捕获为一步名为 `name` 的历史，没有修改时不记录。
+        pub fn edit<R>(
+            &mut self,
+            name: impl Into<String>,
+            edit: impl FnOnce(&mut Document) -> R,
+        ) -> R {
+            let layers = self.layer_snapshot();
+            let ((result, changes), before, after) = self
+                .document
+                .capture_tables(|document| document.capture_changes(edit));
+            self.emit_changes(&changes, false);
+            self.emit_layer_changes(layers);
+            self.record_changes(name, changes, before, after);
+            result
+        }
+
+        /// 在事务中批量修改文档：`edit` 返回 `Ok` 时其间的全部修改作为名为 `name` 的一步进入撤销历史；
+        /// 返回 `Err` 时回滚其间的全部修改并原样返回错误，文档不会停留在修改到一半的状态。
+        ///
+        /// 回滚范围与撤销相同：模型空间实体的增删改，以及图层、块定义、特性覆盖、用户数据等其他文档状态。
+        pub fn transaction<R, E>(
+            &mut self,
+            name: impl Into<String>,
+            edit: impl FnOnce(&mut Transaction<'_>) -> Result<R, E>,
+        ) -> Result<R, E> {
+            let layers = self.layer_snapshot();
+            let ((result, changes), before, after) = self.document.capture_tables(|document| {
+                document.capture_changes(|document| edit(&mut Transaction::new(document)))
+            });
+            match result {
+                Ok(value) => {
+                    self.emit_changes(&changes, false);
+                    self.emit_layer_changes(layers);
+                    self.record_changes(name, changes, before, after);
+                    Ok(value)
+                }
+                Err(err) => {
+                    self.document.revert_changes(&changes);
+                    self.document.restore_tables(&before);
+                    self.prune_selection();
+                    Err(err)
+                }
+            }
+        }
+
+        /// 撤销最近一步，返回其名称；没有可撤销的步骤时返回 `None`。打开的编组先行关闭。
+        pub fn undo(&mut self) -> Option<String> {
+            let mut command = self.history.take_undo()?;
+            command.undo(self);
+            self.prune_selection();
+            let name = command.name().to_string();
+            self.history.push_undone(command);
+            Some(name)
+        }
+
+        /// 重做最近撤销的一步，返回其名称；重做失败时该步被丢弃。
+        pub fn redo(&mut self) -> Result<Option<String>, EngineError> {
+            let Some(mut command) = self.history.take_redo() else {
+                return Ok(None);
+            };
+            command.execute(self)?;
+            self.prune_selection();
+            let name = command.name().to_string();
+            self.history.push_redone(command);
+            Ok(Some(name))
+        }
+
+        /// 开始撤销编组：到对应的 [`Scene::end_undo_group`] 为止执行的命令合并为一步，编组可嵌套。
+        pub fn begin_undo_group(&mut self, name: impl Into<String>) {
+            self.history.begin_group(name.into());
+        }
+
+        /// 关闭最内层撤销编组，返回是否存在打开的编组。
+        pub fn end_undo_group(&mut self) -> bool {
+            self.history.end_group()
+        }
+
+        /// 把捕获的修改与非实体状态在修改前后的内容记为一步历史。
+        fn record_changes(
+            &mut self,
+            name: impl Into<String>,
+            changes: Vec<Change>,
+            before: TableSnapshot,
+            after: TableSnapshot,
+        ) {
+            self.prune_selection();
+            let change = DocumentChange::new(name, changes).with_tables(before, after);
+            if !change.is_empty() {
+                self.history.record(Box::new(change));
+            }
+        }
+
+        /// 从选中集中移除已不存在的实体。
+        fn prune_selection(&mut self) {
+            let document = &self.document;
+            let before = self.selected.len();
+            self.selected.retain(|id| document.entity(*id).is_some());
+            if self.selected.len() != before {
+                self.emit(SceneEvent::SelectionChanged);
+            }
+        }
+
+        /// 当前图层与当前对象特性。
+        #[inline]
+        pub fn drafting(&self) -> &DraftingContext {
+            &self.drafting
+        }
+
+        #[inline]
+        pub fn drafting_mut(&mut self) -> &mut DraftingContext {
+            &mut self.drafting
+        }
+
+        /// 按当前图层与特性创建实体，见 [`DraftingContext::create`]；创建可撤销。
+        pub fn draft(&mut self, build: impl FnOnce(&mut Document, String) -> EntityId) -> EntityId {
+            let layers = self.layer_snapshot();
+            let drafting = &self.drafting;
+            let ((id, changes), before, after) = self.document.capture_tables(|document| {
+                document.capture_changes(|document| drafting.create(document, build))
+            });
+            self.emit_changes(&changes, false);
+            self.emit_layer_changes(layers);
+            self.record_changes("绘制", changes, before, after);
+            id
+        }
+
         /// 为 CLI / 快速验证填充一组示例实体，返回关键实体 ID。
         pub fn populate_demo(&mut self) -> DemoEntities {
             use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
@@ -275,6 +798,84 @@ pub mod scene {
         pub fn entity(&self, id: EntityId) -> Option<&Entity> {
             self.document().entity(id)
         }
+
+        /// 原地修改实体，选中状态保持不变；修改不进入撤销历史。
+        pub fn entity_mut(&mut self, id: EntityId) -> Option<&mut Entity> {
+            self.document.entity_mut(id)
+        }
+
+        /// 实体的夹点，见 [`Entity::grips`]；实体不存在时为空。
+        pub fn grips(&self, id: EntityId) -> Vec<Grip> {
+            self.document
+                .entity(id)
+                .map_or_else(Vec::new, Entity::grips)
+        }
+
+        /// 选中实体的夹点，按实体编号排序。
+        pub fn selection_grips(&self) -> Vec<(EntityId, Vec<Grip>)> {
+            let mut ids: Vec<EntityId> = self.selected.iter().copied().collect();
+            ids.sort_by_key(|id| id.get());
+            ids.into_iter()
+                .map(|id| (id, self.grips(id)))
+                .filter(|(_, grips)| !grips.is_empty())
+                .collect()
+        }
+
+        /// 在选中实体的夹点中找离 `point` 最近且不超过 `aperture` 的一个，返回实体编号与夹点序号。
+        pub fn pick_grip(&self, point: Point2, aperture: f64) -> Option<(EntityId, usize)> {
+            self.selection_grips()
+                .into_iter()
+                .flat_map(|(id, grips)| {
+                    grips.into_iter().enumerate().map(move |(index, grip)| {
+                        (id, index, grip.point.as_vec2().distance(point.as_vec2()))
+                    })
+                })
+                .filter(|(_, _, distance)| *distance <= aperture)
+                .min_by(|a, b| a.2.total_cmp(&b.2))
+                .map(|(id, index, _)| (id, index))
+        }
+
+        /// 把实体的第 `index` 个夹点拖到 `target`，编辑语义见 [`Entity::stretch_grip`]；编辑可撤销。
+        pub fn stretch_grip(
+            &mut self,
+            id: EntityId,
+            index: usize,
+            target: Point2,
+        ) -> Result<(), EngineError> {
+            let mut entity = self
+                .document
+                .entity(id)
+                .ok_or(EngineError::EntityNotFound(id.get()))?
+                .clone();
+            if !entity.stretch_grip(index, target) {
+                return Err(EngineError::InvalidGrip(id.get(), index));
+            }
+            self.edit("夹点编辑", |document| {
+                document.replace_entity(id, entity)
+            });
+            Ok(())
+        }
+
+        /// 替换实体数据，保留编号、特性覆盖与选中状态，返回旧数据；替换可撤销。
+        pub fn replace_entity(
+            &mut self,
+            id: EntityId,
+            entity: Entity,
+        ) -> Result<Entity, EngineError> {
+            self.edit("替换", |document| document.replace_entity(id, entity))
+                .ok_or(EngineError::EntityNotFound(id.get()))
+        }
+
+        /// 删除实体并将其移出选中集，返回被删除的实体。
+        ///
+        /// 编辑命令应通过该方法删除实体；直接经 [`Scene::document_mut`] 删除时，
+        /// 选中集中会残留失效的编号。
+        ///
+        /// 删除可撤销；撤销后实体回到原位置，但不会重新选中。
+        pub fn remove_entity(&mut self, id: EntityId) -> Result<Entity, EngineError> {
+            self.edit("删除", |document| document.remove_entity(id))
+                .ok_or(EngineError::EntityNotFound(id.get()))
+        }
     }
 
     impl Default for Scene {
@@ -374,7 +975,9 @@ pub mod scene {
 
             scene.focus_on_selection();
             let viewport = scene.viewport();
-            assert!((viewport.center.x() - 33.75).abs() < 1e-9);
+            // 文字旋转 45°，字高使左缘越过插入点
+            let label_left = 5.0 - 3.5 * std::f64::consts::FRAC_1_SQRT_2;
+            assert!((viewport.center.x() - (label_left + 62.5) / 2.0).abs() < 1e-9);
             assert!((viewport.center.y() - 24.75).abs() < 1e-9);
 
             scene.clear_selection();
@@ -412,5 +1015,221 @@ pub mod scene {
             assert!((viewport.center.x() - expected_center.x()).abs() < 1e-9);
             assert!((viewport.center.y() - expected_center.y()).abs() < 1e-9);
         }
+
+        #[test]
+        fn grips_of_selected_entities_stretch_undoably() {
+            let mut scene = Scene::new();
+            let ids = scene.populate_demo();
+            assert!(scene.selection_grips().is_empty());
+            scene.select(ids.baseline).unwrap();
+            scene.select(ids.circle).unwrap();
+            assert_eq!(scene.selection_grips().len(), 2);
+
+            // 直线终点与圆的 0° 象限点
+            assert_eq!(
+                scene.pick_grip(Point2::new(100.2, 0.0), 0.5),
+                Some((ids.baseline, 2))
+            );
+            assert_eq!(
+                scene.pick_grip(Point2::new(62.4, 25.0), 0.5),
+                Some((ids.circle, 1))
+            );
+            assert_eq!(scene.pick_grip(Point2::new(30.0, 30.0), 0.5), None);
+
+            scene
+                .stretch_grip(ids.baseline, 2, Point2::new(80.0, 10.0))
+                .unwrap();
+            assert_eq!(scene.grips(ids.baseline)[2].point, Point2::new(80.0, 10.0));
+            assert!(matches!(
+                scene.stretch_grip(ids.circle, 9, Point2::new(0.0, 0.0)),
+                Err(EngineError::InvalidGrip(_, 9))
+            ));
+            assert!(matches!(
+                scene.stretch_grip(ids.circle, 1, Point2::new(50.0, 25.0)),
+                Err(EngineError::InvalidGrip(_, 1))
+            ));
+
+            assert_eq!(scene.undo().as_deref(), Some("夹点编辑"));
+            assert_eq!(scene.grips(ids.baseline)[2].point, Point2::new(100.0, 0.0));
+        }
+
+        #[test]
+        fn removing_entities_updates_selection() {
+            let mut scene = Scene::new();
+            let ids = scene.populate_demo();
+            scene.select(ids.circle).unwrap();
+            scene.select(ids.arc).unwrap();
+
+            let removed = scene.remove_entity(ids.circle).expect("remove circle");
+            assert!(matches!(removed, Entity::Circle(_)));
+            assert!(!scene.is_selected(ids.circle));
+            assert_eq!(scene.selection_len(), 1);
+            assert!(matches!(
+                scene.remove_entity(ids.circle),
+                Err(EngineError::EntityNotFound(_))
+            ));
+
+            let replacement = scene.entity(ids.baseline).cloned().unwrap();
+            let previous = scene.replace_entity(ids.arc, replacement).unwrap();
+            assert!(matches!(previous, Entity::Arc(_)));
+            assert!(scene.is_selected(ids.arc));
+            if let Some(Entity::Line(line)) = scene.entity_mut(ids.arc) {
+                line.end = Point2::new(10.0, 0.0);
+            }
+            let bounds = scene.selection_bounds().unwrap();
+            assert_eq!(bounds.max().x(), 10.0);
+        }
+
+        #[test]
+        fn transactions_commit_as_one_step_or_roll_back() {
+            use zcad_core::document::{EntityProperties, ObjectColor};
+
+            let mut scene = Scene::new();
+            let ids = scene.populate_demo();
+            let order: Vec<EntityId> = scene.document().entities().map(|(id, _)| *id).collect();
+
+            let added = scene
+                .transaction("批量编辑", |txn| {
+                    txn.move_entities([ids.circle, ids.arc], Vector2::new(1.0, 0.0));
+                    txn.remove_entity(ids.baseline);
+                    Ok::<_, EngineError>(txn.add_circle(Point2::new(0.0, 0.0), 1.0, "0"))
+                })
+                .unwrap();
+            assert_eq!(scene.history().undo_len(), 1);
+            assert_eq!(scene.undo().as_deref(), Some("批量编辑"));
+            assert!(scene.entity(added).is_none());
+
+            scene.select(ids.polyline).unwrap();
+            let result: Result<(), EngineError> = scene.transaction("失败的编辑", |txn| {
+                txn.move_entities([ids.circle], Vector2::new(5.0, 0.0));
+                txn.remove_entity(ids.polyline);
+                txn.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 1.0), "NEW");
+                txn.set_user_value(ids.circle, "tag", "x").unwrap();
+                txn.set_layer_color("ANNOT", ObjectColor::Index(1)).unwrap();
+                txn.rename_layer("SKETCH", "OUTLINE").unwrap();
+                txn.set_entity_properties(
+                    ids.arc,
+                    EntityProperties {
+                        color: ObjectColor::Index(3),
+                        ..Default::default()
+                    },
+                );
+                Err(EngineError::EntityNotFound(ids.label.get()))
+            });
+            assert!(matches!(result, Err(EngineError::EntityNotFound(_))));
+            let after: Vec<EntityId> = scene.document().entities().map(|(id, _)| *id).collect();
+            assert_eq!(after, order);
+            assert!(matches!(
+                scene.entity(ids.circle),
+                Some(Entity::Circle(circle)) if circle.center == Point2::new(50.0, 25.0)
+            ));
+            assert!(scene.is_selected(ids.polyline));
+            // 图层、用户数据等非实体状态一并回滚
+            let document = scene.document();
+            assert!(document.layer("NEW").is_none());
+            assert!(document.layer("OUTLINE").is_none());
+            assert!(document.layer("SKETCH").is_some());
+            assert_ne!(
+                document.layer("ANNOT").map(|layer| layer.color),
+                Some(ObjectColor::Index(1))
+            );
+            assert!(document.user_data(ids.circle).is_none());
+            assert!(document.entity_properties(ids.arc).is_none());
+            // 回滚不进入撤销历史
+            assert_eq!(scene.history().undo_len(), 0);
+        }
+
+        #[test]
+        fn transactions_roll_back_block_edits_with_their_references() {
+            use zcad_core::document::BlockDefinition;
+
+            let mut document = Document::new();
+            document.add_block_definition(BlockDefinition {
+                name: "B".to_string(),
+                base_point: Point2::new(0.0, 0.0),
+                entities: Vec::new(),
+                attributes: Vec::new(),
+            });
+            let insert = document.add_block_reference(
+                "B",
+                Point2::new(10.0, 10.0),
+                Vector2::new(1.0, 1.0),
+                0.0,
+                Vec::new(),
+                "0",
+            );
+            let mut scene = Scene::with_document(document);
+
+            let result: Result<(), EngineError> = scene.transaction("改块", |txn| {
+                txn.set_block_base_point("B", Point2::new(5.0, 0.0))
+                    .unwrap();
+                txn.rename_block("B", "C").unwrap();
+                Err(EngineError::EntityNotFound(insert.get()))
+            });
+            assert!(result.is_err());
+            let document = scene.document();
+            assert!(document.block("B").is_some());
+            assert!(document.block("C").is_none());
+            assert!(matches!(
+                scene.entity(insert),
+                Some(Entity::BlockReference(reference))
+                    if reference.name == "B" && reference.insert == Point2::new(10.0, 10.0)
+            ));
+        }
+
+        #[test]
+        fn picking_and_crossing_selection_use_spatial_queries() {
+            let mut scene = Scene::new();
+            let ids = scene.populate_demo();
+
+            // 圆心在圆的范围内，但离圆周太远
+            assert_eq!(scene.pick(Point2::new(50.0, 25.0), 0.5), None);
+            assert_eq!(scene.pick(Point2::new(62.8, 25.0), 0.5), Some(ids.circle));
+            assert_eq!(scene.pick(Point2::new(80.0, 40.0), 0.5), None);
+            // 文本插入点同时靠近多段线，取后绘制的文本
+            assert_eq!(scene.pick(Point2::new(5.0, 12.0), 0.5), Some(ids.label));
+            assert_eq!(scene.pick(Point2::new(5.0, 8.0), 0.5), None);
+            assert_eq!(scene.pick(Point2::new(20.0, 10.2), 0.5), Some(ids.polyline));
+
+            let area = Bounds2D::new(Point2::new(36.0, 11.0), Point2::new(64.0, 39.0));
+            assert_eq!(scene.select_crossing(&area), 1);
+            assert!(scene.is_selected(ids.circle));
+            assert_eq!(scene.select_crossing(&area), 0);
+
+            let mut hidden = zcad_core::document::Layer::new("ANNOT");
+            hidden.is_visible = false;
+            scene.document_mut().add_layer(hidden);
+            assert_eq!(scene.pick(Point2::new(62.8, 25.0), 0.5), None);
+        }
+
+        #[test]
+        fn picking_block_references_tests_their_content() {
+            use zcad_core::document::{BlockDefinition, Line};
+
+            let mut document = Document::new();
+            document.add_block_definition(BlockDefinition {
+                name: "DOOR".to_string(),
+                base_point: Point2::new(0.0, 0.0),
+                entities: vec![Entity::Line(Line {
+                    start: Point2::new(0.0, 0.0),
+                    end: Point2::new(10.0, 10.0),
+                    layer: "0".to_string(),
+                })],
+                attributes: Vec::new(),
+            });
+            let door = document.add_block_reference(
+                "DOOR",
+                Point2::new(100.0, 0.0),
+                Vector2::new(1.0, 1.0),
+                0.0,
+                Vec::new(),
+                "0",
+            );
+            let scene = Scene::with_document(document);
+
+            assert_eq!(scene.pick(Point2::new(105.0, 5.2), 0.5), Some(door));
+            // 块范围内的空白处
+            assert_eq!(scene.pick(Point2::new(108.0, 2.0), 0.5), None);
+        }
     }
 }
@@ -0,0 +1,462 @@
+//! 插件系统：第三方通过实现注册接口扩展命令集与文件格式，无需修改本仓库。
+//!
+//! 插件实现 [`Plugin`]，在 [`Plugin::register`] 中向 [`PluginRegistry`] 登记命令
+//! （[`CommandHandler`]）、导入器（[`DocumentImporter`]）、导出器（[`DocumentExporter`]）
+//! 与实体后处理器（[`EntityPostProcessor`]，在导入后对每个实体执行）。
+//!
+//! 启用 `dynamic-plugins` 特性后可在运行时加载动态库插件：插件库使用 [`declare_plugin!`] 导出入口。
+//! 入口以 Rust ABI 传递 trait 对象，插件必须与宿主使用相同的编译器版本与 zcad-engine 版本构建。
+
+use std::collections::HashSet;
+
+use thiserror::Error;
+use zcad_core::document::{Document, EntityId};
+
+use crate::command::{CommandBus, CommandHandler};
+
+/// 插件接口版本；动态加载时与插件导出的版本比对。
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum PluginError {
+    #[error("plugin {0} is already installed")]
+    DuplicatePlugin(String),
+    #[error("command {0} is already registered")]
+    DuplicateCommand(String),
+    #[error("no plugin handles format {0}")]
+    UnsupportedFormat(String),
+    #[error("{format}: {message}")]
+    Format { format: String, message: String },
+    #[error("failed to load plugin library: {0}")]
+    Load(String),
+    #[error("plugin API version {found} is incompatible with {expected}")]
+    IncompatibleApi { found: u32, expected: u32 },
+}
+
+/// 插件入口。
+pub trait Plugin: Send + Sync {
+    /// 插件名，在同一注册表中唯一。
+    fn name(&self) -> &str;
+
+    fn version(&self) -> &str {
+        "0.0.0"
+    }
+
+    /// 向注册表登记插件提供的扩展。
+    fn register(&self, registry: &mut PluginRegistry) -> Result<(), PluginError>;
+}
+
+/// 把外部格式读入为文档。
+pub trait DocumentImporter: Send + Sync {
+    /// 格式名，用于提示与错误信息。
+    fn format(&self) -> &str;
+
+    /// 小写、不带点的扩展名。
+    fn extensions(&self) -> &[&str];
+
+    fn import(&self, data: &[u8]) -> Result<Document, String>;
+}
+
+/// 把文档写为外部格式。
+pub trait DocumentExporter: Send + Sync {
+    fn format(&self) -> &str;
+
+    fn extensions(&self) -> &[&str];
+
+    fn export(&self, document: &Document) -> Result<Vec<u8>, String>;
+}
+
+/// 实体后处理器：导入后逐个处理实体，例如统一图层或修正数据。
+pub trait EntityPostProcessor: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// 处理一个实体，返回是否做了修改。
+    fn process(&self, document: &mut Document, id: EntityId) -> bool;
+}
+
+/// 已安装插件的信息。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginInfo {
+    pub name: String,
+    pub version: String,
+}
+
+/// 插件注册表：保存全部扩展，命令总线包含内置命令与插件命令。
+pub struct PluginRegistry {
+    plugins: Vec<PluginInfo>,
+    commands: CommandBus,
+    importers: Vec<Box<dyn DocumentImporter>>,
+    exporters: Vec<Box<dyn DocumentExporter>>,
+    post_processors: Vec<Box<dyn EntityPostProcessor>>,
+    /// 动态库须在其提供的扩展之后释放，因此放在最后。
+    #[cfg(feature = "dynamic-plugins")]
+    libraries: Vec<libloading::Library>,
+}
+
+impl Default for PluginRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        Self {
+            plugins: Vec::new(),
+            commands: CommandBus::new(),
+            importers: Vec::new(),
+            exporters: Vec::new(),
+            post_processors: Vec::new(),
+            #[cfg(feature = "dynamic-plugins")]
+            libraries: Vec::new(),
+        }
+    }
+
+    /// 安装插件；同名插件只能安装一次。
+    pub fn install(&mut self, plugin: &dyn Plugin) -> Result<(), PluginError> {
+        let name = plugin.name().to_string();
+        if self.plugins.iter().any(|info| info.name == name) {
+            return Err(PluginError::DuplicatePlugin(name));
+        }
+        plugin.register(self)?;
+        self.plugins.push(PluginInfo {
+            name,
+            version: plugin.version().to_string(),
+        });
+        Ok(())
+    }
+
+    /// 加载动态库插件并安装。
+    ///
+    /// # Safety
+    ///
+    /// 动态库的初始化代码与入口函数会被执行；调用方须确认库可信，且以 [`declare_plugin!`]
+    /// 用相同的编译器与 zcad-engine 版本构建。
+    #[cfg(feature = "dynamic-plugins")]
+    pub unsafe fn load_library(
+        &mut self,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<(), PluginError> {
+        let load = |err: libloading::Error| PluginError::Load(err.to_string());
+        // SAFETY: 调用方保证库可信且按约定导出入口
+        unsafe {
+            let library = libloading::Library::new(path.as_ref()).map_err(load)?;
+            let version: libloading::Symbol<'_, fn() -> u32> =
+                library.get(b"zcad_plugin_api_version").map_err(load)?;
+            let found = version();
+            if found != PLUGIN_API_VERSION {
+                return Err(PluginError::IncompatibleApi {
+                    found,
+                    expected: PLUGIN_API_VERSION,
+                });
+            }
+            let entry: libloading::Symbol<'_, fn() -> Box<dyn Plugin>> =
+                library.get(b"zcad_plugin_entry").map_err(load)?;
+            let plugin = entry();
+            let installed = self.install(plugin.as_ref());
+            drop(plugin);
+            // 安装失败时插件可能已登记部分扩展，库仍须保留
+            self.libraries.push(library);
+            installed
+        }
+    }
+
+    #[inline]
+    pub fn plugins(&self) -> &[PluginInfo] {
+        &self.plugins
+    }
+
+    /// 登记命令；与已有命令重名时返回错误。
+    pub fn register_command<H: CommandHandler + 'static>(
+        &mut self,
+        handler: H,
+    ) -> Result<(), PluginError> {
+        if self.commands.contains(handler.name()) {
+            return Err(PluginError::DuplicateCommand(handler.name().to_string()));
+        }
+        self.commands.register(handler);
+        Ok(())
+    }
+
+    pub fn register_importer(&mut self, importer: impl DocumentImporter + 'static) {
+        self.importers.push(Box::new(importer));
+    }
+
+    pub fn register_exporter(&mut self, exporter: impl DocumentExporter + 'static) {
+        self.exporters.push(Box::new(exporter));
+    }
+
+    pub fn register_post_processor(&mut self, processor: impl EntityPostProcessor + 'static) {
+        self.post_processors.push(Box::new(processor));
+    }
+
+    /// 内置命令与插件命令。
+    #[inline]
+    pub fn commands(&self) -> &CommandBus {
+        &self.commands
+    }
+
+    /// 处理该扩展名的导入器，后登记的优先。
+    pub fn importer_for(&self, extension: &str) -> Option<&dyn DocumentImporter> {
+        let extension = normalize_extension(extension);
+        self.importers
+            .iter()
+            .rev()
+            .find(|importer| importer.extensions().contains(&extension.as_str()))
+            .map(Box::as_ref)
+    }
+
+    /// 处理该扩展名的导出器，后登记的优先。
+    pub fn exporter_for(&self, extension: &str) -> Option<&dyn DocumentExporter> {
+        let extension = normalize_extension(extension);
+        self.exporters
+            .iter()
+            .rev()
+            .find(|exporter| exporter.extensions().contains(&extension.as_str()))
+            .map(Box::as_ref)
+    }
+
+    /// 插件支持导入的全部扩展名。
+    pub fn import_extensions(&self) -> Vec<&str> {
+        let mut seen = HashSet::new();
+        self.importers
+            .iter()
+            .flat_map(|importer| importer.extensions().iter().copied())
+            .filter(|extension| seen.insert(*extension))
+            .collect()
+    }
+
+    /// 以插件导入器读入文档，随后对全部实体执行后处理。
+    pub fn import(&self, extension: &str, data: &[u8]) -> Result<Document, PluginError> {
+        let importer = self
+            .importer_for(extension)
+            .ok_or_else(|| PluginError::UnsupportedFormat(extension.to_string()))?;
+        let mut document = importer
+            .import(data)
+            .map_err(|message| PluginError::Format {
+                format: importer.format().to_string(),
+                message,
+            })?;
+        let ids: Vec<EntityId> = document.entities().map(|(id, _)| *id).collect();
+        self.post_process(&mut document, &ids);
+        Ok(document)
+    }
+
+    pub fn export(&self, extension: &str, document: &Document) -> Result<Vec<u8>, PluginError> {
+        let exporter = self
+            .exporter_for(extension)
+            .ok_or_else(|| PluginError::UnsupportedFormat(extension.to_string()))?;
+        exporter
+            .export(document)
+            .map_err(|message| PluginError::Format {
+                format: exporter.format().to_string(),
+                message,
+            })
+    }
+
+    /// 依登记顺序对指定实体执行全部后处理器，返回被修改的实体数。
+    pub fn post_process(&self, document: &mut Document, ids: &[EntityId]) -> usize {
+        let mut modified = 0;
+        for &id in ids {
+            let mut changed = false;
+            for processor in &self.post_processors {
+                changed |= processor.process(document, id);
+            }
+            modified += usize::from(changed);
+        }
+        modified
+    }
+}
+
+fn normalize_extension(extension: &str) -> String {
+    extension.trim_start_matches('.').to_ascii_lowercase()
+}
+
+/// 在插件动态库中导出入口，参数为构造插件的表达式：
+///
+/// ```ignore
+/// zcad_engine::declare_plugin!(MyPlugin::default());
+/// ```
+#[macro_export]
+macro_rules! declare_plugin {
+    ($constructor:expr) => {
+        #[unsafe(no_mangle)]
+        pub fn zcad_plugin_api_version() -> u32 {
+            $crate::plugin::PLUGIN_API_VERSION
+        }
+
+        #[unsafe(no_mangle)]
+        pub fn zcad_plugin_entry() -> ::std::boxed::Box<dyn $crate::plugin::Plugin> {
+            ::std::boxed::Box::new($constructor)
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use zcad_core::document::Entity;
+    use zcad_core::geometry::Point2;
+
+    use super::*;
+    use crate::command::{CommandContext, CommandRequest, CommandResponse};
+    use crate::scene::Scene;
+
+    /// 每行 `x1 y1 x2 y2` 表示一条直线的文本格式。
+    struct SegmentFormat;
+
+    impl DocumentImporter for SegmentFormat {
+        fn format(&self) -> &str {
+            "segments"
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["seg"]
+        }
+
+        fn import(&self, data: &[u8]) -> Result<Document, String> {
+            let text = std::str::from_utf8(data).map_err(|err| err.to_string())?;
+            let mut document = Document::new();
+            for line in text.lines().filter(|line| !line.trim().is_empty()) {
+                let values: Vec<f64> = line
+                    .split_whitespace()
+                    .map(|value| value.parse::<f64>().map_err(|err| err.to_string()))
+                    .collect::<Result<_, _>>()?;
+                let [x1, y1, x2, y2] = values[..] else {
+                    return Err(format!("bad segment: {line}"));
+                };
+                document.add_line(Point2::new(x1, y1), Point2::new(x2, y2), "0");
+            }
+            Ok(document)
+        }
+    }
+
+    impl DocumentExporter for SegmentFormat {
+        fn format(&self) -> &str {
+            "segments"
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["seg"]
+        }
+
+        fn export(&self, document: &Document) -> Result<Vec<u8>, String> {
+            let mut text = String::new();
+            for (_, entity) in document.entities() {
+                if let Entity::Line(line) = entity {
+                    text += &format!(
+                        "{} {} {} {}\n",
+                        line.start.x(),
+                        line.start.y(),
+                        line.end.x(),
+                        line.end.y()
+                    );
+                }
+            }
+            Ok(text.into_bytes())
+        }
+    }
+
+    /// 把导入的实体放到 IMPORTED 图层。
+    struct ImportLayer;
+
+    impl EntityPostProcessor for ImportLayer {
+        fn name(&self) -> &str {
+            "import-layer"
+        }
+
+        fn process(&self, document: &mut Document, id: EntityId) -> bool {
+            let Some(mut entity) = document.entity(id).cloned() else {
+                return false;
+            };
+            entity.set_layer_name("IMPORTED");
+            document.replace_entity(id, entity).is_some()
+        }
+    }
+
+    struct CountCommand;
+
+    impl CommandHandler for CountCommand {
+        fn name(&self) -> &'static str {
+            "count"
+        }
+
+        fn execute(
+            &self,
+            _request: &CommandRequest,
+            context: &mut CommandContext<'_>,
+        ) -> CommandResponse {
+            let count = context.scene.document().entities().count();
+            CommandResponse::ok(format!("{count}"))
+        }
+    }
+
+    struct SegmentPlugin;
+
+    impl Plugin for SegmentPlugin {
+        fn name(&self) -> &str {
+            "segments"
+        }
+
+        fn version(&self) -> &str {
+            "1.2.0"
+        }
+
+        fn register(&self, registry: &mut PluginRegistry) -> Result<(), PluginError> {
+            registry.register_importer(SegmentFormat);
+            registry.register_exporter(SegmentFormat);
+            registry.register_post_processor(ImportLayer);
+            registry.register_command(CountCommand)
+        }
+    }
+
+    #[test]
+    fn plugins_extend_commands_and_formats() {
+        let mut registry = PluginRegistry::new();
+        registry.install(&SegmentPlugin).unwrap();
+        assert!(matches!(
+            registry.install(&SegmentPlugin),
+            Err(PluginError::DuplicatePlugin(_))
+        ));
+        assert_eq!(
+            registry.plugins(),
+            [PluginInfo {
+                name: "segments".to_string(),
+                version: "1.2.0".to_string(),
+            }]
+        );
+        assert_eq!(registry.import_extensions(), ["seg"]);
+
+        let document = registry.import(".SEG", b"0 0 10 0\n10 0 10 5\n").unwrap();
+        assert_eq!(document.entities().count(), 2);
+        assert!(
+            document
+                .entities()
+                .all(|(_, entity)| entity.layer_name() == "IMPORTED")
+        );
+        assert_eq!(
+            registry.export("seg", &document).unwrap(),
+            b"0 0 10 0\n10 0 10 5\n"
+        );
+        assert!(matches!(
+            registry.import("seg", b"1 2 3"),
+            Err(PluginError::Format { .. })
+        ));
+        assert!(matches!(
+            registry.import("dwg", b""),
+            Err(PluginError::UnsupportedFormat(_))
+        ));
+
+        let mut scene = Scene::with_document(document);
+        let mut context = CommandContext { scene: &mut scene };
+        let request = CommandRequest {
+            name: "count".to_string(),
+            args: Vec::new(),
+        };
+        let response = registry.commands().dispatch(&request, &mut context);
+        assert_eq!(response.message.as_deref(), Some("2"));
+        assert!(matches!(
+            registry.register_command(CountCommand),
+            Err(PluginError::DuplicateCommand(_))
+        ));
+    }
+}
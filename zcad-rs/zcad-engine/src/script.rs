@@ -0,0 +1,244 @@
+//! 脚本与宏：按顺序执行一组文本命令，适合批量修图。
+//!
+//! 脚本（`.scr`）每行一条命令，空行与以 `;` 开头的注释行被跳过。整段脚本作为一步进入撤销历史；
+//! 出错时按 [`ErrorPolicy`] 停止或跳过该行继续。宏由 [`Interpreter::start_recording`] 录制交互命令得到，
+//! 可保存为脚本文本，之后原样重放。
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::interpreter::{Interpreter, InterpreterError};
+use crate::scene::Scene;
+
+/// 脚本中某行命令出错时的处理方式。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// 在第一处错误停止，之前已执行的命令保留。
+    #[default]
+    Stop,
+    /// 记录错误并继续执行后续命令。
+    Continue,
+}
+
+/// 脚本中出错的一行。
+#[derive(Debug)]
+pub struct ScriptError {
+    /// 从 1 开始的行号。
+    pub line: usize,
+    pub command: String,
+    pub error: InterpreterError,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {} ({}): {}", self.line, self.command, self.error)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+/// 脚本执行结果。
+#[derive(Debug, Default)]
+pub struct ScriptReport {
+    /// 执行成功的命令数。
+    pub executed: usize,
+    pub errors: Vec<ScriptError>,
+    /// 是否因错误提前停止。
+    pub stopped: bool,
+}
+
+impl ScriptReport {
+    #[inline]
+    pub fn is_success(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// 录制下来的命令序列。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandMacro {
+    commands: Vec<String>,
+}
+
+impl CommandMacro {
+    pub fn new(commands: Vec<String>) -> Self {
+        Self { commands }
+    }
+
+    /// 从脚本文本读取，跳过空行与注释行。
+    pub fn from_script(script: &str) -> Self {
+        Self::new(
+            script_lines(script)
+                .map(|(_, command)| command.to_string())
+                .collect(),
+        )
+    }
+
+    #[inline]
+    pub fn commands(&self) -> &[String] {
+        &self.commands
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// 转为脚本文本，每行一条命令。
+    pub fn to_script(&self) -> String {
+        let mut script = self.commands.join("\n");
+        script.push('\n');
+        script
+    }
+}
+
+/// 脚本执行器。
+#[derive(Debug, Clone, Default)]
+pub struct ScriptRunner {
+    policy: ErrorPolicy,
+}
+
+impl ScriptRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    #[inline]
+    pub fn policy(&self) -> ErrorPolicy {
+        self.policy
+    }
+
+    /// 执行脚本文本。
+    pub fn run_script(
+        &self,
+        interpreter: &mut Interpreter,
+        scene: &mut Scene,
+        script: &str,
+    ) -> ScriptReport {
+        self.run_lines(interpreter, scene, script_lines(script))
+    }
+
+    /// 读取并执行脚本文件。
+    pub fn run_file(
+        &self,
+        interpreter: &mut Interpreter,
+        scene: &mut Scene,
+        path: impl AsRef<Path>,
+    ) -> io::Result<ScriptReport> {
+        let script = fs::read_to_string(path)?;
+        Ok(self.run_script(interpreter, scene, &script))
+    }
+
+    /// 重放宏，行号即命令在宏中的序号。
+    pub fn run_macro(
+        &self,
+        interpreter: &mut Interpreter,
+        scene: &mut Scene,
+        commands: &CommandMacro,
+    ) -> ScriptReport {
+        let lines = commands
+            .commands
+            .iter()
+            .enumerate()
+            .map(|(index, command)| (index + 1, command.as_str()));
+        self.run_lines(interpreter, scene, lines)
+    }
+
+    fn run_lines<'a>(
+        &self,
+        interpreter: &mut Interpreter,
+        scene: &mut Scene,
+        lines: impl IntoIterator<Item = (usize, &'a str)>,
+    ) -> ScriptReport {
+        let mut report = ScriptReport::default();
+        scene.begin_undo_group("SCRIPT");
+        for (line, command) in lines {
+            match interpreter.execute(scene, command) {
+                Ok(_) => report.executed += 1,
+                Err(error) => {
+                    report.errors.push(ScriptError {
+                        line,
+                        command: command.to_string(),
+                        error,
+                    });
+                    if self.policy == ErrorPolicy::Stop {
+                        report.stopped = true;
+                        break;
+                    }
+                }
+            }
+        }
+        scene.end_undo_group();
+        report
+    }
+}
+
+/// 脚本中需要执行的行及其行号（从 1 开始）。
+fn script_lines(script: &str) -> impl Iterator<Item = (usize, &str)> {
+    script
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with(';'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &str = "; 修图脚本\nLINE 0,0 10,0\n\nBOGUS 1\nCIRCLE 0,0 5\n";
+
+    #[test]
+    fn scripts_follow_error_policy_and_undo_as_one_step() {
+        let mut scene = Scene::new();
+        let mut interpreter = Interpreter::new();
+        let report = ScriptRunner::new().run_script(&mut interpreter, &mut scene, SCRIPT);
+        assert!(report.stopped);
+        assert_eq!(report.executed, 1);
+        assert_eq!(report.errors[0].line, 4);
+        assert!(matches!(
+            report.errors[0].error,
+            InterpreterError::UnknownCommand(_)
+        ));
+        assert_eq!(scene.document().entities().count(), 1);
+
+        let report = ScriptRunner::new()
+            .with_policy(ErrorPolicy::Continue)
+            .run_script(&mut interpreter, &mut scene, SCRIPT);
+        assert!(!report.stopped);
+        assert_eq!(report.executed, 2);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(scene.document().entities().count(), 3);
+
+        assert_eq!(scene.undo().as_deref(), Some("SCRIPT"));
+        assert_eq!(scene.document().entities().count(), 1);
+    }
+
+    #[test]
+    fn recorded_macros_replay_through_scripts() {
+        let mut scene = Scene::new();
+        let mut interpreter = Interpreter::new();
+        interpreter.start_recording();
+        interpreter.execute(&mut scene, "LINE 0,0 5,5").unwrap();
+        assert!(interpreter.execute(&mut scene, "LINE @1,1").is_err());
+        interpreter.execute(&mut scene, "  circle @0,0 2 ").unwrap();
+        let recorded = interpreter.stop_recording().unwrap();
+        assert!(!interpreter.is_recording());
+        assert_eq!(recorded.commands(), ["LINE 0,0 5,5", "circle @0,0 2"]);
+
+        let restored = CommandMacro::from_script(&recorded.to_script());
+        assert_eq!(restored, recorded);
+
+        let mut other = Scene::new();
+        let report = ScriptRunner::new().run_macro(&mut Interpreter::new(), &mut other, &restored);
+        assert!(report.is_success());
+        assert_eq!(other.document().entities().count(), 2);
+    }
+}
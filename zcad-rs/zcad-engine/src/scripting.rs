@@ -0,0 +1,374 @@
+//! 嵌入式脚本（`scripting` 特性）：以 [Rhai](https://rhai.rs) 脚本遍历、查询、创建与修改实体，
+//! 无需重新编译即可编写自动化流程。
+//!
+//! 脚本中可用的函数（实体以整数编号表示，坐标可写整数或实数）：
+//!
+//! - 查询：`entities()`、`entities_on(layer)`、`kind(id)`、`layer(id)`、`property(id, name)`
+//!   （`name` 为 `color`/`linetype`/`lineweight`，返回解析后的最终特性）、`bounds(id)`、`layers()`
+//! - 创建与修改：`add_line(x1, y1, x2, y2)`、`add_circle(x, y, r)`、`move_entity(id, dx, dy)`、
+//!   `set_layer(id, layer)`、`erase(id)`、`set_current_layer(layer)`
+//! - 选择：`select(id)`、`selection()`、`clear_selection()`
+//! - 文本命令：`command("LINE 0,0 10,0")`，语法见 [`crate::interpreter`]
+//!
+//! `print` 的输出被收集到 [`ScriptOutput::printed`]。一次执行中的全部修改作为一步进入撤销历史；
+//! 脚本出错时已完成的修改保留，可整体撤销。
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rhai::{Array, Dynamic, Engine, EvalAltResult, INT, Map};
+use thiserror::Error;
+use zcad_core::document::{EntityId, LineWeight, ObjectColor};
+use zcad_core::geometry::{Point2, Vector2};
+
+use crate::interpreter::Interpreter;
+use crate::scene::Scene;
+
+type Shared<T> = Rc<RefCell<T>>;
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+#[derive(Debug, Error)]
+pub enum ScriptingError {
+    #[error("script error: {0}")]
+    Eval(String),
+}
+
+/// 一次脚本执行的结果。
+#[derive(Debug, Clone, Default)]
+pub struct ScriptOutput {
+    /// 脚本最后一个表达式的值；没有值时为空串。
+    pub value: String,
+    pub printed: Vec<String>,
+}
+
+/// 脚本引擎；注册的函数作用于传入 [`ScriptEngine::run`] 的场景。
+pub struct ScriptEngine {
+    engine: Engine,
+    scene: Shared<Scene>,
+    output: Shared<Vec<String>>,
+}
+
+impl Default for ScriptEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScriptEngine {
+    pub fn new() -> Self {
+        let scene = Rc::new(RefCell::new(Scene::new()));
+        let output: Shared<Vec<String>> = Rc::new(RefCell::new(Vec::new()));
+        let mut engine = Engine::new();
+        let printed = output.clone();
+        engine.on_print(move |text| printed.borrow_mut().push(text.to_string()));
+        register_queries(&mut engine, &scene);
+        register_edits(&mut engine, &scene);
+        Self {
+            engine,
+            scene,
+            output,
+        }
+    }
+
+    /// 限制单次执行的运算步数，防止脚本陷入死循环；0 表示不限制。
+    pub fn set_max_operations(&mut self, operations: u64) {
+        self.engine.set_max_operations(operations);
+    }
+
+    /// 在 `scene` 上执行脚本。
+    pub fn run(&mut self, scene: &mut Scene, script: &str) -> Result<ScriptOutput, ScriptingError> {
+        std::mem::swap(scene, &mut *self.scene.borrow_mut());
+        self.scene.borrow_mut().begin_undo_group("SCRIPT");
+        let result = self.engine.eval::<Dynamic>(script);
+        self.scene.borrow_mut().end_undo_group();
+        std::mem::swap(scene, &mut *self.scene.borrow_mut());
+        let printed = std::mem::take(&mut *self.output.borrow_mut());
+        match result {
+            Ok(value) => Ok(ScriptOutput {
+                value: value.to_string(),
+                printed,
+            }),
+            Err(err) => Err(ScriptingError::Eval(err.to_string())),
+        }
+    }
+}
+
+fn register_queries(engine: &mut Engine, scene: &Shared<Scene>) {
+    let shared = scene.clone();
+    engine.register_fn("entities", move || -> Array {
+        let scene = shared.borrow();
+        scene
+            .document()
+            .entities()
+            .map(|(id, _)| id_value(*id))
+            .collect()
+    });
+    let shared = scene.clone();
+    engine.register_fn("entities_on", move |layer: &str| -> Array {
+        let scene = shared.borrow();
+        scene
+            .document()
+            .entities()
+            .filter(|(_, entity)| entity.layer_name() == layer)
+            .map(|(id, _)| id_value(*id))
+            .collect()
+    });
+    let shared = scene.clone();
+    engine.register_fn("kind", move |id: INT| -> ScriptResult<String> {
+        let scene = shared.borrow();
+        let id = entity_id(&scene, id)?;
+        Ok(scene
+            .entity(id)
+            .map_or("", |entity| entity.kind_name())
+            .to_string())
+    });
+    let shared = scene.clone();
+    engine.register_fn("layer", move |id: INT| -> ScriptResult<String> {
+        let scene = shared.borrow();
+        let id = entity_id(&scene, id)?;
+        Ok(scene
+            .entity(id)
+            .map_or("", |entity| entity.layer_name())
+            .to_string())
+    });
+    let shared = scene.clone();
+    engine.register_fn(
+        "property",
+        move |id: INT, name: &str| -> ScriptResult<Dynamic> {
+            let scene = shared.borrow();
+            let id = entity_id(&scene, id)?;
+            let resolved = scene.document().resolve_properties(id).unwrap_or_default();
+            match name {
+                "color" => Ok(color_value(resolved.color)),
+                "linetype" => Ok(resolved.linetype.into()),
+                "lineweight" => Ok(lineweight_value(resolved.lineweight)),
+                _ => Err(format!("unknown property: {name}").into()),
+            }
+        },
+    );
+    let shared = scene.clone();
+    engine.register_fn("bounds", move |id: INT| -> ScriptResult<Dynamic> {
+        let scene = shared.borrow();
+        let id = entity_id(&scene, id)?;
+        let Some(bounds) = scene.document().entity_bounds(id) else {
+            return Ok(Dynamic::UNIT);
+        };
+        let mut map = Map::new();
+        map.insert("min_x".into(), bounds.min().x().into());
+        map.insert("min_y".into(), bounds.min().y().into());
+        map.insert("max_x".into(), bounds.max().x().into());
+        map.insert("max_y".into(), bounds.max().y().into());
+        Ok(map.into())
+    });
+    let shared = scene.clone();
+    engine.register_fn("layers", move || -> Array {
+        let scene = shared.borrow();
+        let mut names: Vec<String> = scene
+            .document()
+            .layers()
+            .map(|layer| layer.name.clone())
+            .collect();
+        names.sort_unstable();
+        names.into_iter().map(Dynamic::from).collect()
+    });
+    let shared = scene.clone();
+    engine.register_fn("selection", move || -> Array {
+        let scene = shared.borrow();
+        let mut ids: Vec<EntityId> = scene.selection().collect();
+        ids.sort_by_key(|id| id.get());
+        ids.into_iter().map(id_value).collect()
+    });
+}
+
+fn register_edits(engine: &mut Engine, scene: &Shared<Scene>) {
+    let shared = scene.clone();
+    engine.register_fn(
+        "add_line",
+        move |x1: Dynamic, y1: Dynamic, x2: Dynamic, y2: Dynamic| -> ScriptResult<INT> {
+            let start = Point2::new(number(x1)?, number(y1)?);
+            let end = Point2::new(number(x2)?, number(y2)?);
+            let id = shared
+                .borrow_mut()
+                .draft(|document, layer| document.add_line(start, end, layer));
+            Ok(id.get() as INT)
+        },
+    );
+    let shared = scene.clone();
+    engine.register_fn(
+        "add_circle",
+        move |x: Dynamic, y: Dynamic, radius: Dynamic| -> ScriptResult<INT> {
+            let center = Point2::new(number(x)?, number(y)?);
+            let radius = number(radius)?;
+            let id = shared
+                .borrow_mut()
+                .draft(|document, layer| document.add_circle(center, radius, layer));
+            Ok(id.get() as INT)
+        },
+    );
+    let shared = scene.clone();
+    engine.register_fn(
+        "move_entity",
+        move |id: INT, dx: Dynamic, dy: Dynamic| -> ScriptResult<()> {
+            let offset = Vector2::new(number(dx)?, number(dy)?);
+            let mut scene = shared.borrow_mut();
+            let id = entity_id(&scene, id)?;
+            scene.edit("移动", |document| document.move_entities([id], offset));
+            Ok(())
+        },
+    );
+    let shared = scene.clone();
+    engine.register_fn(
+        "set_layer",
+        move |id: INT, layer: &str| -> ScriptResult<()> {
+            let mut scene = shared.borrow_mut();
+            let id = entity_id(&scene, id)?;
+            let mut entity = scene.entity(id).cloned().expect("已检查实体存在");
+            entity.set_layer_name(layer);
+            scene
+                .replace_entity(id, entity)
+                .map_err(|err| err.to_string())?;
+            Ok(())
+        },
+    );
+    let shared = scene.clone();
+    engine.register_fn("erase", move |id: INT| -> ScriptResult<()> {
+        let mut scene = shared.borrow_mut();
+        let id = entity_id(&scene, id)?;
+        scene.remove_entity(id).map_err(|err| err.to_string())?;
+        Ok(())
+    });
+    let shared = scene.clone();
+    engine.register_fn("set_current_layer", move |layer: &str| {
+        shared.borrow_mut().drafting_mut().set_current_layer(layer);
+    });
+    let shared = scene.clone();
+    engine.register_fn("select", move |id: INT| -> ScriptResult<()> {
+        let mut scene = shared.borrow_mut();
+        let id = entity_id(&scene, id)?;
+        scene.select(id).map_err(|err| err.to_string())?;
+        Ok(())
+    });
+    let shared = scene.clone();
+    engine.register_fn("clear_selection", move || {
+        shared.borrow_mut().clear_selection();
+    });
+    let shared = scene.clone();
+    let interpreter = Rc::new(RefCell::new(Interpreter::new()));
+    engine.register_fn("command", move |line: &str| -> ScriptResult<String> {
+        let mut scene = shared.borrow_mut();
+        Ok(interpreter
+            .borrow_mut()
+            .execute(&mut scene, line)
+            .map_err(|err| err.to_string())?)
+    });
+}
+
+/// 校验脚本传入的实体编号。
+fn entity_id(scene: &Scene, id: INT) -> ScriptResult<EntityId> {
+    let entity = u64::try_from(id)
+        .map(EntityId::new)
+        .map_err(|_| format!("invalid entity id: {id}"))?;
+    if scene.entity(entity).is_none() {
+        return Err(format!("entity {id} not found").into());
+    }
+    Ok(entity)
+}
+
+#[inline]
+fn id_value(id: EntityId) -> Dynamic {
+    Dynamic::from(id.get() as INT)
+}
+
+/// 整数与实数都可作为坐标。
+fn number(value: Dynamic) -> ScriptResult<f64> {
+    if let Ok(value) = value.as_float() {
+        return Ok(value);
+    }
+    value
+        .as_int()
+        .map(|value| value as f64)
+        .map_err(|kind| format!("expected a number, got {kind}").into())
+}
+
+/// 颜色索引返回整数，真彩色返回 `#RRGGBB`。
+fn color_value(color: ObjectColor) -> Dynamic {
+    match color {
+        ObjectColor::Index(index) => Dynamic::from(index as INT),
+        ObjectColor::TrueColor(rgb) => format!("#{rgb:06X}").into(),
+        ObjectColor::ByLayer => "BYLAYER".into(),
+        ObjectColor::ByBlock => "BYBLOCK".into(),
+    }
+}
+
+/// 具体线宽返回以 0.01 mm 为单位的整数。
+fn lineweight_value(lineweight: LineWeight) -> Dynamic {
+    match lineweight {
+        LineWeight::Hundredths(value) => Dynamic::from(value as INT),
+        LineWeight::Default => "DEFAULT".into(),
+        LineWeight::ByLayer => "BYLAYER".into(),
+        LineWeight::ByBlock => "BYBLOCK".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zcad_core::document::Entity;
+
+    use super::*;
+
+    #[test]
+    fn scripts_query_and_edit_the_scene() {
+        let mut scene = Scene::new();
+        let ids = scene.populate_demo();
+        let mut engine = ScriptEngine::new();
+
+        let output = engine
+            .run(
+                &mut scene,
+                r#"
+                    let circles = 0;
+                    for id in entities() {
+                        if kind(id) == "CIRCLE" {
+                            circles += 1;
+                            move_entity(id, 10, 0.5);
+                        }
+                    }
+                    for id in entities_on("SKETCH") {
+                        set_layer(id, "ARCHIVE");
+                    }
+                    set_current_layer("NEW");
+                    let line = add_line(0, 0, 3, 4);
+                    print(`color ${property(line, "color")}`);
+                    command("CIRCLE 0,0 2");
+                    circles
+                "#,
+            )
+            .unwrap();
+        assert_eq!(output.value, "1");
+        assert_eq!(output.printed, ["color 7"]);
+        assert!(matches!(
+            scene.entity(ids.circle),
+            Some(Entity::Circle(circle)) if circle.center == Point2::new(60.0, 25.5)
+        ));
+        assert_eq!(scene.entity(ids.polyline).unwrap().layer_name(), "ARCHIVE");
+        assert_eq!(scene.document().entities().count(), 7);
+
+        // 整段脚本作为一步撤销
+        assert_eq!(scene.undo().as_deref(), Some("SCRIPT"));
+        assert_eq!(scene.document().entities().count(), 5);
+        assert_eq!(scene.entity(ids.polyline).unwrap().layer_name(), "SKETCH");
+    }
+
+    #[test]
+    fn script_errors_are_reported() {
+        let mut scene = Scene::new();
+        let mut engine = ScriptEngine::new();
+        assert!(engine.run(&mut scene, "erase(42)").is_err());
+        assert!(engine.run(&mut scene, "add_line(0, 0, \"x\", 1)").is_err());
+        assert!(engine.run(&mut scene, "command(\"BOGUS\")").is_err());
+
+        engine.set_max_operations(1_000);
+        assert!(engine.run(&mut scene, "loop {}").is_err());
+        assert!(engine.run(&mut scene, "bounds(").is_err());
+    }
+}
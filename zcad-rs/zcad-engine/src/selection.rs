@@ -0,0 +1,210 @@
+//! 多边形与栏选的几何判定：把实体近似为折线后，判断其位于多边形内、与多边形相交或与栏选路径相交。
+//!
+//! 直线、圆弧、多段线等曲线按默认精度离散；块参照、标注、填充与修订云线逐层分解后取组成实体；
+//! 文字等其余实体取范围矩形。语义与 AutoCAD 一致：窗选（WPolygon）要求实体完全落在多边形内，
+//! 交叉（CPolygon）只需实体有一部分在多边形内或与边界相交，栏选（Fence）选中与路径相交的实体。
+
+use zcad_core::document::{Document, Entity};
+use zcad_core::geometry::Point2;
+use zcad_core::tessellate::{self, TessellateOptions};
+
+/// 分解嵌套块的最大层数，防止循环引用的块无限展开。
+const MAX_DEPTH: usize = 16;
+
+/// 实体几何的折线近似，每条折线至少包含一个点。
+pub fn entity_paths(document: &Document, entity: &Entity) -> Vec<Vec<Point2>> {
+    let mut paths = Vec::new();
+    collect_paths(document, entity, 0, &mut paths);
+    paths
+}
+
+fn collect_paths(document: &Document, entity: &Entity, depth: usize, paths: &mut Vec<Vec<Point2>>) {
+    if let Some(points) = tessellate::curve(entity, &TessellateOptions::default()) {
+        if !points.is_empty() {
+            paths.push(points);
+        }
+        return;
+    }
+    if depth < MAX_DEPTH
+        && let Some(parts) = document.exploded(entity)
+        && !parts.is_empty()
+    {
+        for part in &parts {
+            collect_paths(document, part, depth + 1, paths);
+        }
+        return;
+    }
+    if let Some(bounds) = document.entity_extent(entity) {
+        let (min, max) = (bounds.min(), bounds.max());
+        paths.push(vec![
+            min,
+            Point2::new(max.x(), min.y()),
+            max,
+            Point2::new(min.x(), max.y()),
+            min,
+        ]);
+    }
+}
+
+/// 实体完全位于多边形内：全部点在内部，且没有线段穿过多边形边界。
+pub fn is_within_polygon(paths: &[Vec<Point2>], polygon: &[Point2]) -> bool {
+    !paths.is_empty()
+        && paths.iter().all(|path| {
+            path.iter().all(|point| contains(polygon, *point))
+                && !segments(path).any(|segment| crosses_ring(segment, polygon))
+        })
+}
+
+/// 实体有一部分位于多边形内，或与多边形边界相交。
+pub fn crosses_polygon(paths: &[Vec<Point2>], polygon: &[Point2]) -> bool {
+    paths.iter().any(|path| {
+        path.iter().any(|point| contains(polygon, *point))
+            || segments(path).any(|segment| crosses_ring(segment, polygon))
+    })
+}
+
+/// 实体与栏选路径（开放折线）相交。
+pub fn crosses_fence(paths: &[Vec<Point2>], fence: &[Point2]) -> bool {
+    paths.iter().any(|path| {
+        segments(path).any(|segment| {
+            fence
+                .windows(2)
+                .any(|edge| segments_intersect(segment, (edge[0], edge[1])))
+        })
+    })
+}
+
+type Segment = (Point2, Point2);
+
+/// 折线的各段；单点折线视为退化线段。
+fn segments(path: &[Point2]) -> impl Iterator<Item = Segment> + '_ {
+    let single = (path.len() == 1).then(|| (path[0], path[0]));
+    path.windows(2).map(|pair| (pair[0], pair[1])).chain(single)
+}
+
+fn crosses_ring(segment: Segment, ring: &[Point2]) -> bool {
+    (0..ring.len()).any(|index| {
+        let next = ring[(index + 1) % ring.len()];
+        segments_intersect(segment, (ring[index], next))
+    })
+}
+
+/// 奇偶规则判断点是否在多边形内部。
+fn contains(polygon: &[Point2], point: Point2) -> bool {
+    let (x, y) = (point.x(), point.y());
+    let mut inside = false;
+    for index in 0..polygon.len() {
+        let a = polygon[index];
+        let b = polygon[(index + 1) % polygon.len()];
+        if (a.y() > y) != (b.y() > y) {
+            let crossing = a.x() + (y - a.y()) / (b.y() - a.y()) * (b.x() - a.x());
+            if x < crossing {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// 两线段是否相交，端点接触与共线重叠也算相交。
+fn segments_intersect((p1, p2): Segment, (q1, q2): Segment) -> bool {
+    let d1 = orientation(q1, q2, p1);
+    let d2 = orientation(q1, q2, p2);
+    let d3 = orientation(p1, p2, q1);
+    let d4 = orientation(p1, p2, q2);
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+        && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+    {
+        return true;
+    }
+    (d1 == 0.0 && on_segment(q1, q2, p1))
+        || (d2 == 0.0 && on_segment(q1, q2, p2))
+        || (d3 == 0.0 && on_segment(p1, p2, q1))
+        || (d4 == 0.0 && on_segment(p1, p2, q2))
+}
+
+fn orientation(a: Point2, b: Point2, c: Point2) -> f64 {
+    (b.x() - a.x()) * (c.y() - a.y()) - (b.y() - a.y()) * (c.x() - a.x())
+}
+
+/// 已知共线时，`point` 是否落在线段 `a`–`b` 的范围内。
+fn on_segment(a: Point2, b: Point2, point: Point2) -> bool {
+    point.x() >= a.x().min(b.x())
+        && point.x() <= a.x().max(b.x())
+        && point.y() >= a.y().min(b.y())
+        && point.y() <= a.y().max(b.y())
+}
+
+#[cfg(test)]
+mod tests {
+    use zcad_core::document::Document;
+
+    use super::*;
+    use crate::scene::Scene;
+
+    fn square(min: f64, max: f64) -> Vec<Point2> {
+        vec![
+            Point2::new(min, min),
+            Point2::new(max, min),
+            Point2::new(max, max),
+            Point2::new(min, max),
+        ]
+    }
+
+    #[test]
+    fn polygon_selection_distinguishes_window_and_crossing() {
+        let mut document = Document::new();
+        let inside = document.add_line(Point2::new(1.0, 1.0), Point2::new(8.0, 8.0), "0");
+        let crossing = document.add_line(Point2::new(5.0, 5.0), Point2::new(15.0, 5.0), "0");
+        // 圆包住多边形但不与边界相交
+        document.add_circle(Point2::new(5.0, 5.0), 50.0, "0");
+        document.add_line(Point2::new(20.0, 0.0), Point2::new(30.0, 0.0), "0");
+        let mut scene = Scene::with_document(document);
+
+        // 凹多边形：直线两端都在多边形内，但中间穿出缺口
+        let notched = vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(10.0, 0.0),
+            Point2::new(10.0, 10.0),
+            Point2::new(5.0, 10.0),
+            Point2::new(5.0, 2.0),
+            Point2::new(3.0, 2.0),
+            Point2::new(3.0, 10.0),
+            Point2::new(0.0, 10.0),
+        ];
+        assert_eq!(scene.select_window_polygon(&notched), 0);
+
+        assert_eq!(scene.select_window_polygon(&square(0.0, 10.0)), 1);
+        assert!(scene.is_selected(inside));
+        scene.clear_selection();
+        assert_eq!(scene.select_crossing_polygon(&square(0.0, 10.0)), 2);
+        assert!(scene.is_selected(inside) && scene.is_selected(crossing));
+        // 少于三个顶点不构成多边形
+        assert_eq!(scene.select_crossing_polygon(&square(0.0, 10.0)[..2]), 0);
+    }
+
+    #[test]
+    fn fence_selects_everything_the_path_crosses() {
+        let mut document = Document::new();
+        let line = document.add_line(Point2::new(0.0, 0.0), Point2::new(0.0, 10.0), "0");
+        let circle = document.add_circle(Point2::new(20.0, 5.0), 3.0, "0");
+        // 路径从旁经过
+        document.add_circle(Point2::new(30.0, 9.0), 1.0, "0");
+        let label = document.add_text(Point2::new(60.0, 4.0), "A", 2.5, 0.0, "0");
+        let mut scene = Scene::with_document(document);
+
+        let fence = [
+            Point2::new(-5.0, 5.0),
+            Point2::new(20.0, 5.0),
+            Point2::new(40.0, 5.0),
+        ];
+        assert_eq!(scene.select_fence(&fence), 2);
+        assert!(scene.is_selected(line) && scene.is_selected(circle));
+
+        assert_eq!(
+            scene.select_fence(&[Point2::new(60.5, 0.0), Point2::new(60.5, 10.0)]),
+            1
+        );
+        assert!(scene.is_selected(label));
+    }
+}
@@ -0,0 +1,214 @@
+//! 对象捕捉搜索：在光标周围的靶框内收集各捕捉方式的候选点，按优先级给出结果。
+//!
+//! 候选点按与光标的距离排序，最近点优先级最低，只在靶框内没有其他捕捉点时使用。交点在靶框附近的
+//! 图元间两两求交，不延伸图元；同一实体内的图元（如多段线各段）之间也求交。
+
+use zcad_core::document::{Document, Entity, EntityId, OsnapMode};
+use zcad_core::geometry::intersect::{IntersectOptions, intersect};
+use zcad_core::geometry::{Bounds2D, Point2};
+
+/// 启用的捕捉方式集合。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OsnapModes(u16);
+
+impl OsnapModes {
+    pub const NONE: OsnapModes = OsnapModes(0);
+
+    pub fn all() -> Self {
+        OsnapMode::ALL
+            .into_iter()
+            .fold(Self::NONE, |modes, mode| modes.with(mode))
+    }
+
+    #[inline]
+    pub fn contains(self, mode: OsnapMode) -> bool {
+        self.0 & bit(mode) != 0
+    }
+
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn with(mut self, mode: OsnapMode) -> Self {
+        self.set(mode, true);
+        self
+    }
+
+    pub fn set(&mut self, mode: OsnapMode, enabled: bool) {
+        if enabled {
+            self.0 |= bit(mode);
+        } else {
+            self.0 &= !bit(mode);
+        }
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = OsnapMode> {
+        OsnapMode::ALL
+            .into_iter()
+            .filter(move |mode| self.contains(*mode))
+    }
+}
+
+/// 默认启用端点、圆心与交点，与 AutoCAD 的初始设置一致。
+impl Default for OsnapModes {
+    fn default() -> Self {
+        Self::NONE
+            .with(OsnapMode::Endpoint)
+            .with(OsnapMode::Center)
+            .with(OsnapMode::Intersection)
+    }
+}
+
+fn bit(mode: OsnapMode) -> u16 {
+    1 << mode as u16
+}
+
+/// 一个捕捉结果。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapResult {
+    pub mode: OsnapMode,
+    pub point: Point2,
+    /// 捕捉点所在的模型空间实体；交点取参与求交的第一个实体。
+    pub entity: EntityId,
+    /// 捕捉点到光标的距离。
+    pub distance: f64,
+}
+
+/// 在 `candidates` 中搜索靶框内的捕捉点，按优先级排序；`base` 为垂足与切点的基点。
+pub fn find_snaps(
+    document: &Document,
+    candidates: &[EntityId],
+    cursor: Point2,
+    aperture: f64,
+    modes: OsnapModes,
+    base: Option<Point2>,
+) -> Vec<SnapResult> {
+    let aperture = aperture.abs();
+    let area = Bounds2D::new(
+        Point2::new(cursor.x() - aperture, cursor.y() - aperture),
+        Point2::new(cursor.x() + aperture, cursor.y() + aperture),
+    );
+    let primitives: Vec<(EntityId, Entity)> = candidates
+        .iter()
+        .filter_map(|id| document.entity(*id).map(|entity| (*id, entity)))
+        .flat_map(|(id, entity)| {
+            document
+                .osnap_primitives(entity)
+                .into_iter()
+                .filter(|primitive| {
+                    matches!(primitive, Entity::Dimension(_))
+                        || primitive
+                            .bounds()
+                            .is_some_and(|bounds| bounds.intersects(&area))
+                })
+                .map(move |primitive| (id, primitive))
+        })
+        .collect();
+
+    let mut results = Vec::new();
+    let mut push = |mode: OsnapMode, point: Point2, entity: EntityId| {
+        let distance = point.as_vec2().distance(cursor.as_vec2());
+        if distance <= aperture {
+            results.push(SnapResult {
+                mode,
+                point,
+                entity,
+                distance,
+            });
+        }
+    };
+    for mode in modes.iter() {
+        if mode == OsnapMode::Intersection {
+            let options = IntersectOptions::default();
+            for (index, (id, first)) in primitives.iter().enumerate() {
+                for (_, second) in &primitives[index + 1..] {
+                    for hit in intersect(first, second, &options) {
+                        push(mode, hit.point, *id);
+                    }
+                }
+            }
+            continue;
+        }
+        for (id, primitive) in &primitives {
+            for point in primitive.osnap_points(mode, cursor, base) {
+                push(mode, point, *id);
+            }
+        }
+    }
+    results.sort_by(|a, b| {
+        (a.mode == OsnapMode::Nearest)
+            .cmp(&(b.mode == OsnapMode::Nearest))
+            .then(a.distance.total_cmp(&b.distance))
+    });
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use zcad_core::document::Document;
+
+    use super::*;
+    use crate::scene::Scene;
+
+    #[test]
+    fn modes_toggle_independently() {
+        let mut modes = OsnapModes::default();
+        assert!(modes.contains(OsnapMode::Endpoint));
+        assert!(!modes.contains(OsnapMode::Nearest));
+        modes.set(OsnapMode::Endpoint, false);
+        modes.set(OsnapMode::Nearest, true);
+        assert_eq!(
+            modes.iter().collect::<Vec<_>>(),
+            [
+                OsnapMode::Center,
+                OsnapMode::Intersection,
+                OsnapMode::Nearest
+            ]
+        );
+        assert_eq!(OsnapModes::all().iter().count(), OsnapMode::ALL.len());
+        assert!(OsnapModes::NONE.is_empty());
+    }
+
+    #[test]
+    fn scene_snaps_to_the_closest_enabled_point() {
+        let mut document = Document::new();
+        let horizontal = document.add_line(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), "0");
+        let vertical = document.add_line(Point2::new(4.0, -5.0), Point2::new(4.0, 5.0), "0");
+        let circle = document.add_circle(Point2::new(20.0, 0.0), 3.0, "0");
+        let mut scene = Scene::with_document(document);
+
+        let snap = scene.osnap(Point2::new(4.3, 0.2), 1.0, None).unwrap();
+        assert_eq!(
+            (snap.mode, snap.point),
+            (OsnapMode::Intersection, Point2::new(4.0, 0.0))
+        );
+        assert!(snap.entity == horizontal || snap.entity == vertical);
+
+        let snap = scene.osnap(Point2::new(9.6, 0.3), 1.0, None).unwrap();
+        assert_eq!(
+            (snap.mode, snap.point, snap.entity),
+            (OsnapMode::Endpoint, Point2::new(10.0, 0.0), horizontal)
+        );
+        assert!(scene.osnap(Point2::new(6.0, 0.3), 1.0, None).is_none());
+
+        // 最近点只在没有其他捕捉点时生效
+        scene.set_osnap_mode(OsnapMode::Nearest, true);
+        let snap = scene.osnap(Point2::new(6.0, 0.3), 1.0, None).unwrap();
+        assert_eq!(
+            (snap.mode, snap.point),
+            (OsnapMode::Nearest, Point2::new(6.0, 0.0))
+        );
+        assert_eq!(
+            scene.osnap(Point2::new(9.8, 0.1), 1.0, None).unwrap().mode,
+            OsnapMode::Endpoint
+        );
+
+        scene.set_osnap_modes(OsnapModes::NONE.with(OsnapMode::Tangent));
+        assert!(scene.osnap(Point2::new(20.0, 3.0), 1.0, None).is_none());
+        let base = Some(Point2::new(20.0, 6.0));
+        let snap = scene.osnap(Point2::new(22.5, 1.0), 1.0, base).unwrap();
+        assert_eq!(snap.entity, circle);
+        assert!((snap.point.y() - 1.5).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,220 @@
+//! 输入约束：根据基点把光标点约束到正交方向或极轴追踪角度上，并给出供界面显示的追踪信息。
+//!
+//! 正交模式把光标投影到过基点的水平或竖直方向（取偏移较大的一轴），优先于极轴追踪。极轴追踪在光标
+//! 方向与增量角的整数倍或附加角相差不超过捕获容差时，把光标投影到该角度的射线上。
+
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+use zcad_core::geometry::Point2;
+
+/// 极轴追踪设置，角度均为弧度。
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackingSettings {
+    pub ortho: bool,
+    pub polar: bool,
+    /// 极轴增量角，不为正数时只追踪附加角。
+    pub polar_increment: f64,
+    /// 增量角之外单独追踪的角度。
+    pub additional_angles: Vec<f64>,
+    /// 光标方向与追踪角度的最大偏差。
+    pub capture_tolerance: f64,
+}
+
+impl Default for TrackingSettings {
+    fn default() -> Self {
+        Self {
+            ortho: false,
+            polar: false,
+            polar_increment: FRAC_PI_2,
+            additional_angles: Vec::new(),
+            capture_tolerance: 5f64.to_radians(),
+        }
+    }
+}
+
+/// 约束的来源。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrackingKind {
+    Ortho,
+    Polar,
+}
+
+/// 追踪信息：自基点沿 `angle`（`0..2π`）方向、距离为 `distance` 的追踪线。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tracking {
+    pub kind: TrackingKind,
+    pub angle: f64,
+    pub distance: f64,
+}
+
+/// 约束后的输入点，未被约束时 `tracking` 为 `None` 且点保持不变。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConstrainedPoint {
+    pub point: Point2,
+    pub tracking: Option<Tracking>,
+}
+
+impl TrackingSettings {
+    /// 以 `base` 为基点约束光标点 `cursor`。
+    pub fn constrain(&self, base: Point2, cursor: Point2) -> ConstrainedPoint {
+        let unconstrained = ConstrainedPoint {
+            point: cursor,
+            tracking: None,
+        };
+        let (dx, dy) = (cursor.x() - base.x(), cursor.y() - base.y());
+        if dx == 0.0 && dy == 0.0 {
+            return unconstrained;
+        }
+        if self.ortho {
+            let angle = if dx.abs() >= dy.abs() {
+                if dx >= 0.0 { 0.0 } else { PI }
+            } else if dy >= 0.0 {
+                FRAC_PI_2
+            } else {
+                3.0 * FRAC_PI_2
+            };
+            return project(base, dx, dy, angle, TrackingKind::Ortho);
+        }
+        if !self.polar {
+            return unconstrained;
+        }
+        let direction = dy.atan2(dx).rem_euclid(TAU);
+        self.polar_angles(direction)
+            .map(|angle| (angle, angular_distance(angle, direction)))
+            .filter(|(_, deviation)| *deviation <= self.capture_tolerance)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or(unconstrained, |(angle, _)| {
+                project(base, dx, dy, angle, TrackingKind::Polar)
+            })
+    }
+
+    /// 光标方向附近可能捕获的追踪角度：相邻的两个增量角倍数与全部附加角。
+    fn polar_angles(&self, direction: f64) -> impl Iterator<Item = f64> + '_ {
+        let increment = self.polar_increment;
+        let multiples = (increment > 0.0 && increment.is_finite())
+            .then(|| {
+                let below = (direction / increment).floor() * increment;
+                [below, below + increment]
+            })
+            .into_iter()
+            .flatten();
+        multiples
+            .chain(self.additional_angles.iter().copied())
+            .map(|angle| angle.rem_euclid(TAU))
+    }
+}
+
+fn project(base: Point2, dx: f64, dy: f64, angle: f64, kind: TrackingKind) -> ConstrainedPoint {
+    let (sin, cos) = angle.sin_cos();
+    let distance = dx * cos + dy * sin;
+    ConstrainedPoint {
+        point: Point2::new(base.x() + distance * cos, base.y() + distance * sin),
+        tracking: Some(Tracking {
+            kind,
+            angle,
+            distance,
+        }),
+    }
+}
+
+/// 两个角度之间不超过 π 的夹角。
+fn angular_distance(a: f64, b: f64) -> f64 {
+    let difference = (a - b).rem_euclid(TAU);
+    difference.min(TAU - difference)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::Scene;
+
+    fn assert_close(actual: Point2, expected: Point2) {
+        assert!(
+            (actual.x() - expected.x()).abs() < 1e-9 && (actual.y() - expected.y()).abs() < 1e-9,
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn ortho_locks_to_the_dominant_axis() {
+        let settings = TrackingSettings {
+            ortho: true,
+            polar: true,
+            ..TrackingSettings::default()
+        };
+        let base = Point2::new(10.0, 10.0);
+        let constrained = settings.constrain(base, Point2::new(4.0, 12.0));
+        assert_eq!(constrained.point, Point2::new(4.0, 10.0));
+        assert_eq!(
+            constrained.tracking,
+            Some(Tracking {
+                kind: TrackingKind::Ortho,
+                angle: PI,
+                distance: 6.0,
+            })
+        );
+        let constrained = settings.constrain(base, Point2::new(11.0, 3.0));
+        assert_close(constrained.point, Point2::new(10.0, 3.0));
+        assert_eq!(constrained.tracking.unwrap().angle, 3.0 * FRAC_PI_2);
+        assert_eq!(settings.constrain(base, base).tracking, None);
+    }
+
+    #[test]
+    fn polar_tracking_captures_nearby_angles() {
+        let settings = TrackingSettings {
+            polar: true,
+            polar_increment: 30f64.to_radians(),
+            additional_angles: vec![(-10f64).to_radians()],
+            ..TrackingSettings::default()
+        };
+        let base = Point2::new(0.0, 0.0);
+
+        // 31° 方向捕获 30°
+        let cursor = Point2::new(31f64.to_radians().cos(), 31f64.to_radians().sin());
+        let constrained =
+            settings.constrain(base, Point2::new(cursor.x() * 10.0, cursor.y() * 10.0));
+        let tracking = constrained.tracking.unwrap();
+        assert_eq!(tracking.kind, TrackingKind::Polar);
+        assert!((tracking.angle - 30f64.to_radians()).abs() < 1e-12);
+        let distance = 10.0 * 1f64.to_radians().cos();
+        assert!((tracking.distance - distance).abs() < 1e-9);
+        assert_close(
+            constrained.point,
+            Point2::new(distance * 30f64.to_radians().cos(), distance * 0.5),
+        );
+
+        // 附加角 -10° 即 350°
+        let tracking = settings
+            .constrain(base, Point2::new(10.0, -1.6))
+            .tracking
+            .unwrap();
+        assert!((tracking.angle - 350f64.to_radians()).abs() < 1e-12);
+        // 偏离所有追踪角度时不约束
+        let cursor = Point2::new(10.0, 3.0);
+        assert_eq!(settings.constrain(base, cursor).point, cursor);
+        assert!(settings.constrain(base, cursor).tracking.is_none());
+    }
+
+    #[test]
+    fn scene_applies_its_tracking_settings() {
+        let mut scene = Scene::new();
+        let base = Point2::new(0.0, 0.0);
+        let cursor = Point2::new(10.0, 0.5);
+        assert_eq!(scene.constrain_input(base, cursor).point, cursor);
+
+        scene.set_ortho(true);
+        assert_eq!(
+            scene.constrain_input(base, cursor).point,
+            Point2::new(10.0, 0.0)
+        );
+        scene.set_ortho(false);
+        scene.set_tracking(TrackingSettings {
+            polar: true,
+            ..scene.tracking().clone()
+        });
+        assert_eq!(
+            scene.constrain_input(base, cursor).tracking.unwrap().kind,
+            TrackingKind::Polar
+        );
+    }
+}
@@ -0,0 +1,170 @@
+/*
+ * zcad C ABI —— 与 zcad-ffi/src 中的 #[repr(C)] 类型一一对应，修改时两边同步并递增 ZCAD_ABI_VERSION。
+ *
+ * 所有句柄由本库分配，必须用对应的 *_free 释放；字符串均为 UTF-8。
+ * 失败时返回非零 ZcadStatus，详细信息由 zcad_last_error() 按线程提供。
+ */
+#ifndef ZCAD_H
+#define ZCAD_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define ZCAD_ABI_VERSION 1
+
+typedef enum ZcadStatus {
+    ZCAD_STATUS_OK = 0,
+    ZCAD_STATUS_NULL_ARGUMENT = 1,
+    ZCAD_STATUS_INVALID_ARGUMENT = 2,
+    ZCAD_STATUS_READ_ERROR = 3,
+    ZCAD_STATUS_WRITE_ERROR = 4,
+    ZCAD_STATUS_UNSUPPORTED = 5,
+    ZCAD_STATUS_INVALID_DOCUMENT = 6,
+    ZCAD_STATUS_PANIC = 7,
+} ZcadStatus;
+
+typedef enum ZcadEntityKind {
+    ZCAD_ENTITY_LINE = 1,
+    ZCAD_ENTITY_CIRCLE = 2,
+    ZCAD_ENTITY_ARC = 3,
+    ZCAD_ENTITY_ELLIPSE = 4,
+    ZCAD_ENTITY_POLYLINE = 5,
+    ZCAD_ENTITY_SPLINE = 6,
+    ZCAD_ENTITY_TEXT = 7,
+    ZCAD_ENTITY_MTEXT = 8,
+    ZCAD_ENTITY_BLOCK_REFERENCE = 9,
+    ZCAD_ENTITY_HATCH = 10,
+    ZCAD_ENTITY_DIMENSION = 11,
+    ZCAD_ENTITY_LEADER = 12,
+    ZCAD_ENTITY_MLEADER = 13,
+    ZCAD_ENTITY_RASTER_IMAGE = 14,
+    ZCAD_ENTITY_WIPEOUT = 15,
+    ZCAD_ENTITY_FACE3D = 16,
+    ZCAD_ENTITY_SHAPE = 17,
+    ZCAD_ENTITY_UNDERLAY = 18,
+    ZCAD_ENTITY_REVISION_CLOUD = 19,
+} ZcadEntityKind;
+
+typedef struct ZcadDocument ZcadDocument;
+typedef struct ZcadEntityIter ZcadEntityIter;
+
+typedef struct ZcadPoint {
+    double x;
+    double y;
+} ZcadPoint;
+
+typedef struct ZcadBounds {
+    ZcadPoint min;
+    ZcadPoint max;
+} ZcadBounds;
+
+typedef struct ZcadLine {
+    ZcadPoint start;
+    ZcadPoint end;
+} ZcadLine;
+
+typedef struct ZcadCircle {
+    ZcadPoint center;
+    double radius;
+} ZcadCircle;
+
+/* 角度为弧度，逆时针为正。 */
+typedef struct ZcadArc {
+    ZcadPoint center;
+    double radius;
+    double start_angle;
+    double end_angle;
+} ZcadArc;
+
+typedef struct ZcadEllipse {
+    ZcadPoint center;
+    ZcadPoint major_axis;
+    double ratio;
+    double start_parameter;
+    double end_parameter;
+} ZcadEllipse;
+
+typedef struct ZcadPolylineVertex {
+    ZcadPoint position;
+    double bulge;
+} ZcadPolylineVertex;
+
+typedef struct ZcadPolyline {
+    const ZcadPolylineVertex *vertices;
+    size_t vertex_count;
+    bool closed;
+} ZcadPolyline;
+
+typedef struct ZcadSpline {
+    int32_t degree;
+    bool closed;
+    const ZcadPoint *control_points;
+    size_t control_point_count;
+    const ZcadPoint *fit_points;
+    size_t fit_point_count;
+} ZcadSpline;
+
+/* TEXT 与 MTEXT 共用，MTEXT 的 rotation 由方向向量换算。 */
+typedef struct ZcadText {
+    ZcadPoint insert;
+    double height;
+    double rotation;
+    const char *content;
+} ZcadText;
+
+typedef struct ZcadBlockReference {
+    const char *name;
+    ZcadPoint insert;
+    ZcadPoint scale;
+    double rotation;
+} ZcadBlockReference;
+
+/* 按 ZcadEntity.kind 读取对应成员，其余类型的数据全部为零。 */
+typedef union ZcadEntityData {
+    ZcadLine line;
+    ZcadCircle circle;
+    ZcadArc arc;
+    ZcadEllipse ellipse;
+    ZcadPolyline polyline;
+    ZcadSpline spline;
+    ZcadText text;
+    ZcadBlockReference block_reference;
+} ZcadEntityData;
+
+/* 指针成员在下一次 zcad_entity_iter_next 或释放迭代器前有效。 */
+typedef struct ZcadEntity {
+    uint64_t id;
+    ZcadEntityKind kind;
+    const char *kind_name;
+    const char *layer;
+    bool has_bounds;
+    ZcadBounds bounds;
+    ZcadEntityData data;
+} ZcadEntity;
+
+uint32_t zcad_abi_version(void);
+const char *zcad_last_error(void);
+
+ZcadDocument *zcad_document_new(void);
+ZcadStatus zcad_document_open(const char *path, ZcadDocument **out);
+ZcadStatus zcad_document_open_bytes(const uint8_t *data, size_t len, ZcadDocument **out);
+ZcadStatus zcad_document_save(const ZcadDocument *document, const char *path);
+void zcad_document_free(ZcadDocument *document);
+size_t zcad_document_entity_count(const ZcadDocument *document);
+bool zcad_document_bounds(const ZcadDocument *document, ZcadBounds *out);
+
+/* 图纸在迭代器释放前不得释放。 */
+ZcadEntityIter *zcad_entity_iter_new(const ZcadDocument *document);
+bool zcad_entity_iter_next(ZcadEntityIter *iter, ZcadEntity *out);
+void zcad_entity_iter_free(ZcadEntityIter *iter);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ZCAD_H */
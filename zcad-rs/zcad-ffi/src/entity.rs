@@ -0,0 +1,383 @@
+//! 实体遍历：每个实体转换为带类型标签的 [`ZcadEntity`]，常用类型的几何数据放在联合体中。
+//!
+//! 字符串与顶点数组由迭代器持有，在下一次 [`zcad_entity_iter_next`] 或释放迭代器前有效。
+
+use std::ffi::{CString, c_char};
+use std::ptr;
+
+use zcad_core::document::{Document, Entity, EntityId};
+use zcad_core::geometry::{Bounds2D, Point2, Vector2};
+
+use crate::{ZcadBounds, ZcadDocument, ZcadPoint};
+
+/// 实体类型标签，取值保持稳定，新类型只追加。
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZcadEntityKind {
+    Line = 1,
+    Circle = 2,
+    Arc = 3,
+    Ellipse = 4,
+    Polyline = 5,
+    Spline = 6,
+    Text = 7,
+    MText = 8,
+    BlockReference = 9,
+    Hatch = 10,
+    Dimension = 11,
+    Leader = 12,
+    MLeader = 13,
+    RasterImage = 14,
+    Wipeout = 15,
+    Face3D = 16,
+    Shape = 17,
+    Underlay = 18,
+    RevisionCloud = 19,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ZcadLine {
+    pub start: ZcadPoint,
+    pub end: ZcadPoint,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ZcadCircle {
+    pub center: ZcadPoint,
+    pub radius: f64,
+}
+
+/// 圆弧，角度为弧度，逆时针为正。
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ZcadArc {
+    pub center: ZcadPoint,
+    pub radius: f64,
+    pub start_angle: f64,
+    pub end_angle: f64,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ZcadEllipse {
+    pub center: ZcadPoint,
+    /// 主轴端点相对圆心的向量。
+    pub major_axis: ZcadPoint,
+    pub ratio: f64,
+    pub start_parameter: f64,
+    pub end_parameter: f64,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZcadPolylineVertex {
+    pub position: ZcadPoint,
+    pub bulge: f64,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ZcadPolyline {
+    pub vertices: *const ZcadPolylineVertex,
+    pub vertex_count: usize,
+    pub closed: bool,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ZcadSpline {
+    pub degree: i32,
+    pub closed: bool,
+    pub control_points: *const ZcadPoint,
+    pub control_point_count: usize,
+    pub fit_points: *const ZcadPoint,
+    pub fit_point_count: usize,
+}
+
+/// 单行与多行文字共用；多行文字的 `rotation` 由方向向量换算。
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ZcadText {
+    pub insert: ZcadPoint,
+    pub height: f64,
+    pub rotation: f64,
+    /// UTF-8 文字内容，多行文字为去除格式后的纯文本。
+    pub content: *const c_char,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ZcadBlockReference {
+    pub name: *const c_char,
+    pub insert: ZcadPoint,
+    pub scale: ZcadPoint,
+    pub rotation: f64,
+}
+
+/// 按 [`ZcadEntity::kind`] 读取对应成员；其余类型的数据全部为零。
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union ZcadEntityData {
+    pub line: ZcadLine,
+    pub circle: ZcadCircle,
+    pub arc: ZcadArc,
+    pub ellipse: ZcadEllipse,
+    pub polyline: ZcadPolyline,
+    pub spline: ZcadSpline,
+    pub text: ZcadText,
+    pub block_reference: ZcadBlockReference,
+}
+
+/// 带标签的实体视图。
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct ZcadEntity {
+    pub id: u64,
+    pub kind: ZcadEntityKind,
+    /// DXF 实体名，如 `LINE`、`LWPOLYLINE`。
+    pub kind_name: *const c_char,
+    pub layer: *const c_char,
+    pub has_bounds: bool,
+    pub bounds: ZcadBounds,
+    pub data: ZcadEntityData,
+}
+
+/// 实体迭代器，按图纸中的实体顺序输出。
+pub struct ZcadEntityIter {
+    document: *const ZcadDocument,
+    index: usize,
+    strings: Vec<CString>,
+    vertices: Vec<ZcadPolylineVertex>,
+    points: Vec<ZcadPoint>,
+}
+
+impl ZcadEntityIter {
+    fn string(&mut self, text: &str) -> *const c_char {
+        let text = CString::new(text.replace('\0', "")).unwrap_or_default();
+        let ptr = text.as_ptr();
+        self.strings.push(text);
+        ptr
+    }
+
+    fn view(&mut self, document: &Document, id: EntityId, entity: &Entity) -> ZcadEntity {
+        self.strings.clear();
+        self.vertices.clear();
+        self.points.clear();
+        let bounds = bounds(document.entity_extent(entity));
+        ZcadEntity {
+            id: id.get(),
+            kind: kind(entity),
+            kind_name: self.string(entity.kind_name()),
+            layer: self.string(entity.layer_name()),
+            has_bounds: bounds.is_some(),
+            bounds: bounds.unwrap_or_default(),
+            data: self.data(entity),
+        }
+    }
+
+    fn data(&mut self, entity: &Entity) -> ZcadEntityData {
+        // SAFETY: 联合体各成员均为数值与可空指针，全零是合法值，也保证未使用的字节为零。
+        let mut data: ZcadEntityData = unsafe { std::mem::zeroed() };
+        match entity {
+            Entity::Line(line) => {
+                data.line = ZcadLine {
+                    start: point(line.start),
+                    end: point(line.end),
+                };
+            }
+            Entity::Circle(circle) => {
+                data.circle = ZcadCircle {
+                    center: point(circle.center),
+                    radius: circle.radius,
+                };
+            }
+            Entity::Arc(arc) => {
+                data.arc = ZcadArc {
+                    center: point(arc.center),
+                    radius: arc.radius,
+                    start_angle: arc.start_angle,
+                    end_angle: arc.end_angle,
+                };
+            }
+            Entity::Ellipse(ellipse) => {
+                data.ellipse = ZcadEllipse {
+                    center: point(ellipse.center),
+                    major_axis: vector(ellipse.major_axis),
+                    ratio: ellipse.ratio,
+                    start_parameter: ellipse.start_parameter,
+                    end_parameter: ellipse.end_parameter,
+                };
+            }
+            Entity::Polyline(polyline) => {
+                self.vertices
+                    .extend(polyline.vertices.iter().map(|vertex| ZcadPolylineVertex {
+                        position: point(vertex.position),
+                        bulge: vertex.bulge,
+                    }));
+                data.polyline = ZcadPolyline {
+                    vertices: slice_ptr(&self.vertices),
+                    vertex_count: self.vertices.len(),
+                    closed: polyline.is_closed,
+                };
+            }
+            Entity::Spline(spline) => {
+                // 控制点与拟合点共用一块缓冲区，填充完毕后再取指针
+                self.points
+                    .extend(spline.control_points.iter().copied().map(point));
+                self.points
+                    .extend(spline.fit_points.iter().copied().map(point));
+                let control_count = spline.control_points.len();
+                let (control, fit) = self.points.split_at(control_count);
+                data.spline = ZcadSpline {
+                    degree: spline.degree,
+                    closed: spline.is_closed,
+                    control_points: slice_ptr(control),
+                    control_point_count: control.len(),
+                    fit_points: slice_ptr(fit),
+                    fit_point_count: fit.len(),
+                };
+            }
+            Entity::Text(text) => {
+                data.text = ZcadText {
+                    insert: point(text.insert),
+                    height: text.height,
+                    rotation: text.rotation,
+                    content: self.string(&text.content),
+                };
+            }
+            Entity::MText(mtext) => {
+                data.text = ZcadText {
+                    insert: point(mtext.insert),
+                    height: mtext.height,
+                    rotation: mtext.direction.y().atan2(mtext.direction.x()),
+                    content: self.string(&mtext.content),
+                };
+            }
+            Entity::BlockReference(reference) => {
+                data.block_reference = ZcadBlockReference {
+                    name: self.string(&reference.name),
+                    insert: point(reference.insert),
+                    scale: vector(reference.scale),
+                    rotation: reference.rotation,
+                };
+            }
+            _ => {}
+        }
+        data
+    }
+}
+
+/// 创建实体迭代器；空句柄返回空指针。
+///
+/// # Safety
+/// `document` 为空或有效句柄，且在迭代器释放前不得释放。
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zcad_entity_iter_new(
+    document: *const ZcadDocument,
+) -> *mut ZcadEntityIter {
+    if document.is_null() {
+        return ptr::null_mut();
+    }
+    Box::into_raw(Box::new(ZcadEntityIter {
+        document,
+        index: 0,
+        strings: Vec::new(),
+        vertices: Vec::new(),
+        points: Vec::new(),
+    }))
+}
+
+/// 取下一个实体写入 `out`，遍历结束或参数为空时返回 `false`。
+///
+/// `out` 中的字符串与数组指针在下一次调用或释放迭代器前有效。
+///
+/// # Safety
+/// `iter` 为空或有效迭代器，其图纸句柄仍然有效；`out` 为空或指向可写的 [`ZcadEntity`]。
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zcad_entity_iter_next(
+    iter: *mut ZcadEntityIter,
+    out: *mut ZcadEntity,
+) -> bool {
+    // SAFETY: 调用方保证指针有效。
+    let (Some(iter), Some(out)) = (unsafe { iter.as_mut() }, unsafe { out.as_mut() }) else {
+        return false;
+    };
+    // SAFETY: 图纸句柄在迭代器存活期间有效。
+    let document = unsafe { &(*iter.document).document };
+    let Some((id, entity)) = document.entities().nth(iter.index) else {
+        return false;
+    };
+    iter.index += 1;
+    *out = iter.view(document, *id, entity);
+    true
+}
+
+/// 释放迭代器；空指针为空操作。
+///
+/// # Safety
+/// `iter` 为空或由 [`zcad_entity_iter_new`] 创建且尚未释放。
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zcad_entity_iter_free(iter: *mut ZcadEntityIter) {
+    if !iter.is_null() {
+        // SAFETY: 迭代器由 `Box::into_raw` 创建，调用方保证只释放一次。
+        drop(unsafe { Box::from_raw(iter) });
+    }
+}
+
+fn kind(entity: &Entity) -> ZcadEntityKind {
+    match entity {
+        Entity::Line(_) => ZcadEntityKind::Line,
+        Entity::Circle(_) => ZcadEntityKind::Circle,
+        Entity::Arc(_) => ZcadEntityKind::Arc,
+        Entity::Ellipse(_) => ZcadEntityKind::Ellipse,
+        Entity::Polyline(_) => ZcadEntityKind::Polyline,
+        Entity::Spline(_) => ZcadEntityKind::Spline,
+        Entity::Text(_) => ZcadEntityKind::Text,
+        Entity::MText(_) => ZcadEntityKind::MText,
+        Entity::BlockReference(_) => ZcadEntityKind::BlockReference,
+        Entity::Hatch(_) => ZcadEntityKind::Hatch,
+        Entity::Dimension(_) => ZcadEntityKind::Dimension,
+        Entity::Leader(_) => ZcadEntityKind::Leader,
+        Entity::MLeader(_) => ZcadEntityKind::MLeader,
+        Entity::RasterImage(_) => ZcadEntityKind::RasterImage,
+        Entity::Wipeout(_) => ZcadEntityKind::Wipeout,
+        Entity::Face3D(_) => ZcadEntityKind::Face3D,
+        Entity::Shape(_) => ZcadEntityKind::Shape,
+        Entity::Underlay(_) => ZcadEntityKind::Underlay,
+        Entity::RevisionCloud(_) => ZcadEntityKind::RevisionCloud,
+    }
+}
+
+fn point(point: Point2) -> ZcadPoint {
+    ZcadPoint {
+        x: point.x(),
+        y: point.y(),
+    }
+}
+
+fn vector(vector: Vector2) -> ZcadPoint {
+    ZcadPoint {
+        x: vector.x(),
+        y: vector.y(),
+    }
+}
+
+/// 空数组返回空指针，避免向宿主暴露悬空的非空指针。
+fn slice_ptr<T>(items: &[T]) -> *const T {
+    if items.is_empty() {
+        ptr::null()
+    } else {
+        items.as_ptr()
+    }
+}
+
+pub(crate) fn bounds(bounds: Option<Bounds2D>) -> Option<ZcadBounds> {
+    bounds
+        .filter(|bounds| !bounds.is_empty())
+        .map(|bounds| ZcadBounds {
+            min: point(bounds.min()),
+            max: point(bounds.max()),
+        })
+}
@@ -0,0 +1,298 @@
+//! 稳定的 C ABI：加载图纸、以带标签的结构体遍历实体并保存，供 C/C++/C# 宿主程序嵌入。
+//!
+//! 头文件为 `include/zcad.h`，与本模块中的 `#[repr(C)]` 类型一一对应；新增字段只追加在结构体末尾，
+//! 并递增 [`ZCAD_ABI_VERSION`]。所有句柄由本库分配，必须用对应的 `*_free` 释放。
+//!
+//! 错误以 [`ZcadStatus`] 返回，详细信息通过 [`zcad_last_error`] 按线程获取。
+//! 任何 Rust 恐慌都在边界处捕获并转换为 [`ZcadStatus::Panic`]，不会穿越 FFI 边界。
+
+mod entity;
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::ptr;
+
+use zcad_core::document::Document;
+use zcad_io::IoError;
+
+pub use crate::entity::{
+    ZcadArc, ZcadBlockReference, ZcadCircle, ZcadEllipse, ZcadEntity, ZcadEntityData,
+    ZcadEntityIter, ZcadEntityKind, ZcadLine, ZcadPolyline, ZcadPolylineVertex, ZcadSpline,
+    ZcadText, zcad_entity_iter_free, zcad_entity_iter_new, zcad_entity_iter_next,
+};
+
+/// ABI 版本，结构体布局或函数签名变化时递增。
+pub const ZCAD_ABI_VERSION: u32 = 1;
+
+/// 函数返回的状态码。
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZcadStatus {
+    Ok = 0,
+    /// 必需的指针参数为空。
+    NullArgument = 1,
+    /// 参数无效，例如路径不是 UTF-8。
+    InvalidArgument = 2,
+    ReadError = 3,
+    WriteError = 4,
+    /// 不支持的格式或特性（如 DWG、未知扩展名）。
+    Unsupported = 5,
+    /// 图纸内容无效。
+    InvalidDocument = 6,
+    /// 库内部恐慌，句柄仍可释放但不应继续使用。
+    Panic = 7,
+}
+
+/// 二维点。
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ZcadPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// 轴对齐范围。
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ZcadBounds {
+    pub min: ZcadPoint,
+    pub max: ZcadPoint,
+}
+
+/// 不透明的图纸句柄。
+pub struct ZcadDocument {
+    document: Document,
+}
+
+impl ZcadDocument {
+    fn into_raw(document: Document) -> *mut ZcadDocument {
+        Box::into_raw(Box::new(ZcadDocument { document }))
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: impl Into<String>) {
+    let message = message.into().replace('\0', " ");
+    let message = CString::new(message).unwrap_or_default();
+    LAST_ERROR.with(|slot| *slot.borrow_mut() = Some(message));
+}
+
+fn clear_last_error() {
+    LAST_ERROR.with(|slot| *slot.borrow_mut() = None);
+}
+
+fn fail(status: ZcadStatus, message: impl Into<String>) -> ZcadStatus {
+    set_last_error(message);
+    status
+}
+
+fn io_failure(err: IoError) -> ZcadStatus {
+    let status = match &err {
+        IoError::UnsupportedFeature(_) => ZcadStatus::Unsupported,
+        IoError::ReadError { .. } => ZcadStatus::ReadError,
+        IoError::WriteError { .. } => ZcadStatus::WriteError,
+        IoError::InvalidDocument(_) => ZcadStatus::InvalidDocument,
+    };
+    fail(status, err.to_string())
+}
+
+/// 在 FFI 边界执行闭包：清空上次错误并捕获恐慌。
+fn guard(body: impl FnOnce() -> ZcadStatus) -> ZcadStatus {
+    guard_or(ZcadStatus::Panic, body)
+}
+
+/// 与 [`guard`] 相同，用于不返回状态码的导出函数：恐慌时记录错误并返回 `fallback`。
+fn guard_or<T>(fallback: T, body: impl FnOnce() -> T) -> T {
+    clear_last_error();
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(value) => value,
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|text| text.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "未知错误".to_string());
+            set_last_error(format!("内部错误: {message}"));
+            fallback
+        }
+    }
+}
+
+/// 读取以 NUL 结尾的 UTF-8 路径。
+///
+/// # Safety
+/// `path` 为空或指向有效的 C 字符串。
+unsafe fn path_arg<'a>(path: *const c_char) -> Result<&'a Path, ZcadStatus> {
+    if path.is_null() {
+        return Err(fail(ZcadStatus::NullArgument, "路径为空"));
+    }
+    // SAFETY: 调用方保证 `path` 指向以 NUL 结尾的字符串。
+    let text = unsafe { CStr::from_ptr(path) };
+    text.to_str()
+        .map(Path::new)
+        .map_err(|_| fail(ZcadStatus::InvalidArgument, "路径不是有效的 UTF-8"))
+}
+
+/// 库编译时的 ABI 版本，宿主应与头文件中的 `ZCAD_ABI_VERSION` 比较。
+#[unsafe(no_mangle)]
+pub extern "C" fn zcad_abi_version() -> u32 {
+    ZCAD_ABI_VERSION
+}
+
+/// 当前线程最近一次失败的错误描述（UTF-8），无错误时返回空指针。
+///
+/// 返回的指针在本线程下一次调用本库函数前有效。
+#[unsafe(no_mangle)]
+pub extern "C" fn zcad_last_error() -> *const c_char {
+    LAST_ERROR.with(|slot| {
+        slot.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// 新建空白图纸。
+#[unsafe(no_mangle)]
+pub extern "C" fn zcad_document_new() -> *mut ZcadDocument {
+    ZcadDocument::into_raw(Document::new())
+}
+
+/// 按内容识别格式（DXF、JSON、`.zcadz`）加载图纸，成功时把句柄写入 `out`。
+///
+/// # Safety
+/// `path` 为以 NUL 结尾的字符串，`out` 指向可写的句柄指针。
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zcad_document_open(
+    path: *const c_char,
+    out: *mut *mut ZcadDocument,
+) -> ZcadStatus {
+    guard(|| {
+        if out.is_null() {
+            return fail(ZcadStatus::NullArgument, "输出句柄为空");
+        }
+        // SAFETY: 由调用方保证。
+        let path = match unsafe { path_arg(path) } {
+            Ok(path) => path,
+            Err(status) => return status,
+        };
+        match zcad_io::open_document(path) {
+            Ok(document) => {
+                // SAFETY: `out` 非空且可写。
+                unsafe { *out = ZcadDocument::into_raw(document) };
+                ZcadStatus::Ok
+            }
+            Err(err) => io_failure(err),
+        }
+    })
+}
+
+/// 从内存缓冲区加载图纸，格式识别同 [`zcad_document_open`]。
+///
+/// # Safety
+/// `data` 指向至少 `len` 个可读字节（`len` 为 0 时可为空），`out` 指向可写的句柄指针。
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zcad_document_open_bytes(
+    data: *const u8,
+    len: usize,
+    out: *mut *mut ZcadDocument,
+) -> ZcadStatus {
+    guard(|| {
+        if out.is_null() || (data.is_null() && len > 0) {
+            return fail(ZcadStatus::NullArgument, "缓冲区或输出句柄为空");
+        }
+        let bytes = if len == 0 {
+            &[][..]
+        } else {
+            // SAFETY: 调用方保证 `data` 指向 `len` 个可读字节。
+            unsafe { std::slice::from_raw_parts(data, len) }
+        };
+        match zcad_io::open_document_bytes(bytes) {
+            Ok(document) => {
+                // SAFETY: `out` 非空且可写。
+                unsafe { *out = ZcadDocument::into_raw(document) };
+                ZcadStatus::Ok
+            }
+            Err(err) => io_failure(err),
+        }
+    })
+}
+
+/// 按扩展名选择格式保存图纸（见 `zcad_io::SaveFormat`）。
+///
+/// # Safety
+/// `document` 为有效句柄，`path` 为以 NUL 结尾的字符串。
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zcad_document_save(
+    document: *const ZcadDocument,
+    path: *const c_char,
+) -> ZcadStatus {
+    guard(|| {
+        // SAFETY: 调用方保证句柄有效。
+        let Some(handle) = (unsafe { document.as_ref() }) else {
+            return fail(ZcadStatus::NullArgument, "图纸句柄为空");
+        };
+        // SAFETY: 由调用方保证。
+        let path = match unsafe { path_arg(path) } {
+            Ok(path) => path,
+            Err(status) => return status,
+        };
+        match zcad_io::save_document(&handle.document, path) {
+            Ok(()) => ZcadStatus::Ok,
+            Err(err) => io_failure(err),
+        }
+    })
+}
+
+/// 释放图纸句柄；空指针为空操作。释放后该图纸上的迭代器不可再使用。
+///
+/// # Safety
+/// `document` 为空或由本库创建且尚未释放。
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zcad_document_free(document: *mut ZcadDocument) {
+    if !document.is_null() {
+        // SAFETY: 句柄由 `Box::into_raw` 创建，调用方保证只释放一次。
+        drop(unsafe { Box::from_raw(document) });
+    }
+}
+
+/// 实体数量，空句柄返回 0。
+///
+/// # Safety
+/// `document` 为空或有效句柄。
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zcad_document_entity_count(document: *const ZcadDocument) -> usize {
+    guard_or(0, || {
+        // SAFETY: 调用方保证句柄有效。
+        unsafe { document.as_ref() }.map_or(0, |handle| handle.document.entities().count())
+    })
+}
+
+/// 图纸范围写入 `out`；空图纸或参数为空时返回 `false`。
+///
+/// # Safety
+/// `document` 为空或有效句柄，`out` 为空或指向可写的 [`ZcadBounds`]。
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zcad_document_bounds(
+    document: *const ZcadDocument,
+    out: *mut ZcadBounds,
+) -> bool {
+    guard_or(false, || {
+        // SAFETY: 调用方保证指针有效。
+        let (Some(handle), Some(out)) = (unsafe { document.as_ref() }, unsafe { out.as_mut() })
+        else {
+            return false;
+        };
+        match entity::bounds(handle.document.bounds()) {
+            Some(bounds) => {
+                *out = bounds;
+                true
+            }
+            None => false,
+        }
+    })
+}
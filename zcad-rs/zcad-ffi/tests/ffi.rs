@@ -0,0 +1,220 @@
+use std::ffi::{CStr, CString, c_char};
+use std::mem::{MaybeUninit, offset_of, size_of};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::ptr;
+
+use zcad::*;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../zcad-io/tests/data")
+        .join(format!("{name}.dxf"))
+}
+
+fn c_path(path: &Path) -> CString {
+    CString::new(path.to_str().unwrap()).unwrap()
+}
+
+fn text(ptr: *const c_char) -> String {
+    assert!(!ptr.is_null());
+    unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_string()
+}
+
+fn open(path: &Path) -> *mut ZcadDocument {
+    let mut document = ptr::null_mut();
+    let status = unsafe { zcad_document_open(c_path(path).as_ptr(), &mut document) };
+    assert_eq!(status, ZcadStatus::Ok, "{:?}", last_error());
+    document
+}
+
+fn last_error() -> Option<String> {
+    let message = zcad_last_error();
+    (!message.is_null()).then(|| text(message))
+}
+
+fn collect(document: *const ZcadDocument) -> Vec<ZcadEntity> {
+    let iter = unsafe { zcad_entity_iter_new(document) };
+    assert!(!iter.is_null());
+    let mut entities = Vec::new();
+    let mut entity = MaybeUninit::<ZcadEntity>::uninit();
+    while unsafe { zcad_entity_iter_next(iter, entity.as_mut_ptr()) } {
+        entities.push(unsafe { entity.assume_init() });
+    }
+    unsafe { zcad_entity_iter_free(iter) };
+    entities
+}
+
+#[test]
+fn iterates_entities_as_tagged_structs() {
+    let document = open(&fixture("basic_entities"));
+    assert_eq!(unsafe { zcad_document_entity_count(document) }, 5);
+
+    let iter = unsafe { zcad_entity_iter_new(document) };
+    let mut entity = MaybeUninit::<ZcadEntity>::uninit();
+    let mut kinds = Vec::new();
+    while unsafe { zcad_entity_iter_next(iter, entity.as_mut_ptr()) } {
+        // 指针只在下一次 next 之前有效，逐个检查
+        let entity = unsafe { entity.assume_init_ref() };
+        kinds.push(entity.kind);
+        match entity.kind {
+            ZcadEntityKind::Line => {
+                let line = unsafe { entity.data.line };
+                assert_eq!(text(entity.kind_name), "LINE");
+                assert_eq!(text(entity.layer), "0");
+                assert_eq!((line.end.x, line.end.y), (100.0, 0.0));
+                assert!(entity.has_bounds);
+                assert_eq!(entity.bounds.max.x, 100.0);
+            }
+            ZcadEntityKind::Polyline => {
+                let polyline = unsafe { entity.data.polyline };
+                assert_eq!(text(entity.layer), "SKETCH");
+                assert_eq!(polyline.vertex_count, 3);
+                let vertices =
+                    unsafe { std::slice::from_raw_parts(polyline.vertices, polyline.vertex_count) };
+                assert_eq!(
+                    (vertices[0].position.x, vertices[0].position.y),
+                    (0.0, 10.0)
+                );
+            }
+            ZcadEntityKind::Text => {
+                let text_data = unsafe { entity.data.text };
+                assert_eq!(text(text_data.content), "Rust demo");
+                assert_eq!(text_data.height, 3.5);
+                assert!((text_data.rotation - 45f64.to_radians()).abs() < 1e-9);
+            }
+            _ => {}
+        }
+    }
+    // 迭代结束后继续调用仍返回 false
+    assert!(!unsafe { zcad_entity_iter_next(iter, entity.as_mut_ptr()) });
+    unsafe { zcad_entity_iter_free(iter) };
+
+    assert_eq!(
+        kinds,
+        [
+            ZcadEntityKind::Line,
+            ZcadEntityKind::Circle,
+            ZcadEntityKind::Arc,
+            ZcadEntityKind::Polyline,
+            ZcadEntityKind::Text,
+        ]
+    );
+    let mut bounds = ZcadBounds::default();
+    assert!(unsafe { zcad_document_bounds(document, &mut bounds) });
+    assert_eq!(bounds.min.x, 0.0);
+    unsafe { zcad_document_free(document) };
+}
+
+#[test]
+fn saves_by_extension_and_loads_from_memory() {
+    let dir = tempfile::tempdir().unwrap();
+    let document = open(&fixture("basic_entities"));
+    let json = dir.path().join("copy.json");
+    let status = unsafe { zcad_document_save(document, c_path(&json).as_ptr()) };
+    assert_eq!(status, ZcadStatus::Ok);
+    assert!(last_error().is_none());
+
+    let status =
+        unsafe { zcad_document_save(document, c_path(&dir.path().join("x.abc")).as_ptr()) };
+    assert_eq!(status, ZcadStatus::Unsupported);
+    assert!(last_error().unwrap().contains("abc"));
+
+    let bytes = std::fs::read(&json).unwrap();
+    let mut reloaded = ptr::null_mut();
+    let status = unsafe { zcad_document_open_bytes(bytes.as_ptr(), bytes.len(), &mut reloaded) };
+    assert_eq!(status, ZcadStatus::Ok);
+    let ids = |document| collect(document).iter().map(|e| e.id).collect::<Vec<_>>();
+    assert_eq!(ids(document), ids(reloaded));
+
+    unsafe {
+        zcad_document_free(reloaded);
+        zcad_document_free(document);
+    }
+}
+
+#[test]
+fn reports_errors_through_status_and_last_error() {
+    let mut document = ptr::null_mut();
+    let missing = c_path(Path::new("/nonexistent/drawing.dxf"));
+    let status = unsafe { zcad_document_open(missing.as_ptr(), &mut document) };
+    assert_eq!(status, ZcadStatus::ReadError);
+    assert!(document.is_null());
+    assert!(last_error().unwrap().contains("drawing.dxf"));
+
+    let status = unsafe { zcad_document_open(ptr::null(), &mut document) };
+    assert_eq!(status, ZcadStatus::NullArgument);
+    let status = unsafe { zcad_document_open(missing.as_ptr(), ptr::null_mut()) };
+    assert_eq!(status, ZcadStatus::NullArgument);
+
+    let garbage = b"not a drawing";
+    let status =
+        unsafe { zcad_document_open_bytes(garbage.as_ptr(), garbage.len(), &mut document) };
+    assert_ne!(status, ZcadStatus::Ok);
+    assert!(last_error().is_some());
+
+    // 空句柄均安全返回
+    assert_eq!(unsafe { zcad_document_entity_count(ptr::null()) }, 0);
+    assert!(unsafe { zcad_entity_iter_new(ptr::null()) }.is_null());
+    unsafe { zcad_document_free(ptr::null_mut()) };
+
+    let empty = zcad_document_new();
+    let mut bounds = ZcadBounds::default();
+    assert!(!unsafe { zcad_document_bounds(empty, &mut bounds) });
+    assert!(collect(empty).is_empty());
+    unsafe { zcad_document_free(empty) };
+    assert_eq!(zcad_abi_version(), ZCAD_ABI_VERSION);
+}
+
+/// 用系统 C 编译器检查头文件与 Rust 布局一致，未安装编译器时跳过。
+#[test]
+fn header_layout_matches_rust_types() {
+    let header_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("include");
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("layout.c");
+    std::fs::write(
+        &source,
+        r#"#include <stdio.h>
+#include "zcad.h"
+int main(void) {
+    printf("%zu %zu %zu %zu %zu %zu %zu %zu %zu %d\n",
+        sizeof(ZcadEntity), offsetof(ZcadEntity, kind), offsetof(ZcadEntity, bounds),
+        offsetof(ZcadEntity, data), sizeof(ZcadEntityData), sizeof(ZcadPolyline),
+        sizeof(ZcadSpline), offsetof(ZcadSpline, fit_points), sizeof(ZcadBlockReference),
+        ZCAD_ABI_VERSION);
+    return 0;
+}
+"#,
+    )
+    .unwrap();
+    let binary = dir.path().join("layout");
+    let compiled = Command::new("cc")
+        .arg("-std=c99")
+        .arg("-I")
+        .arg(&header_dir)
+        .arg(&source)
+        .arg("-o")
+        .arg(&binary)
+        .status();
+    let Ok(status) = compiled else {
+        eprintln!("未找到 C 编译器，跳过头文件检查");
+        return;
+    };
+    assert!(status.success(), "头文件编译失败");
+
+    let output = Command::new(&binary).output().unwrap();
+    let expected = format!(
+        "{} {} {} {} {} {} {} {} {} {}\n",
+        size_of::<ZcadEntity>(),
+        offset_of!(ZcadEntity, kind),
+        offset_of!(ZcadEntity, bounds),
+        offset_of!(ZcadEntity, data),
+        size_of::<ZcadEntityData>(),
+        size_of::<ZcadPolyline>(),
+        size_of::<ZcadSpline>(),
+        offset_of!(ZcadSpline, fit_points),
+        size_of::<ZcadBlockReference>(),
+        ZCAD_ABI_VERSION,
+    );
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+}
@@ -20,8 +20,8 @@ use tracing::{info, trace, warn};
 use crate::loader::{DocumentSource, load_scene_from_env_or_demo};
 use zcad_core::document::{
     Attribute, BlockDefinition, BlockReference, ClipMode, Document, Entity as DocEntity, HatchEdge,
-    HatchLoop, ImageDictionaryEntry, RasterImage, RasterImageClip, RasterImageDefinition,
-    RasterImageVariables, ThreeDFace, Wipeout,
+    HatchLoop, ImageDictionaryEntry, ObjectColor, RasterImage, RasterImageClip,
+    RasterImageDefinition, RasterImageVariables, ThreeDFace, Wipeout,
 };
 use zcad_core::geometry::{Bounds2D, Point2, Vector2, Vector3};
 use zcad_engine::command::{CommandBus, CommandContext, CommandRequest};
@@ -210,7 +210,15 @@ fn spawn_document_entities(
     doc: Res<LoadedDocument>,
 ) {
     let mut used_texture_keys: HashSet<String> = HashSet::new();
-    for (_, entity) in doc.0.entities() {
+    let mut line_materials: HashMap<ObjectColor, Handle<ColorMaterial>> = HashMap::new();
+    for (id, entity) in doc.0.display_list() {
+        // 随层颜色经图层默认值解析，同色实体共用材质
+        let line_material = match doc.0.resolve_properties(*id) {
+            Some(properties) => {
+                resolved_line_material(&mut color_materials, &mut line_materials, properties.color)
+            }
+            None => render_assets.line_material.clone(),
+        };
         match entity {
             DocEntity::Hatch(hatch) => {
                 spawn_hatch_fill(&mut commands, &mut meshes, &render_assets, hatch);
@@ -220,7 +228,7 @@ fn spawn_document_entities(
                             let _ = spawn_line_segment(
                                 &mut commands,
                                 &mut meshes,
-                                render_assets.line_material.clone(),
+                                line_material.clone(),
                                 *start,
                                 *end,
                                 0.0,
@@ -235,10 +243,11 @@ fn spawn_document_entities(
                     &mut commands,
                     &text_assets,
                     &text.content,
-                    text.insert,
+                    text.anchor(),
                     text.height,
-                    text.rotation,
-                    Anchor::BOTTOM_LEFT,
+                    text.effective_rotation(),
+                    attribute_anchor(text.horizontal_align, text.vertical_align),
+                    text.mirror(),
                 );
                 continue;
             }
@@ -305,15 +314,43 @@ fn spawn_document_entities(
                 spawn_face3d(&mut commands, &mut meshes, &render_assets, face);
                 continue;
             }
+            DocEntity::Dimension(dimension) => {
+                // 匿名块已保存标注的完整几何，直接按单位变换绘制
+                if let Some(block_name) = &dimension.block_name
+                    && doc.0.block(block_name).is_some()
+                {
+                    let reference = BlockReference {
+                        name: block_name.clone(),
+                        insert: Point2::new(0.0, 0.0),
+                        scale: Vector2::new(1.0, 1.0),
+                        rotation: 0.0,
+                        attributes: Vec::new(),
+                        layer: dimension.layer.clone(),
+                    };
+                    spawn_block_reference(
+                        &mut commands,
+                        &mut meshes,
+                        &mut color_materials,
+                        &mut image_assets,
+                        &mut texture_cache,
+                        &mut used_texture_keys,
+                        &render_assets,
+                        &text_assets,
+                        &doc.0,
+                        &reference,
+                    );
+                    continue;
+                }
+            }
             _ => {}
         }
-        for polyline in entity_polylines(entity) {
+        for polyline in entity_polylines(entity, &doc.0) {
             for segment in polyline.windows(2) {
                 if let [start, end] = segment {
                     let _ = spawn_line_segment(
                         &mut commands,
                         &mut meshes,
-                        render_assets.line_material.clone(),
+                        line_material.clone(),
                         *start,
                         *end,
                         0.0,
@@ -326,6 +363,17 @@ fn spawn_document_entities(
     texture_cache.retain_only(&used_texture_keys);
 }
 
+fn resolved_line_material(
+    materials: &mut Assets<ColorMaterial>,
+    cache: &mut HashMap<ObjectColor, Handle<ColorMaterial>>,
+    color: ObjectColor,
+) -> Handle<ColorMaterial> {
+    cache
+        .entry(color)
+        .or_insert_with(|| materials.add(ColorMaterial::from(object_color_to_bevy(color))))
+        .clone()
+}
+
 fn egui_overlay(
     mut contexts: EguiContexts,
     scene_res: Res<SceneResource>,
@@ -425,9 +473,11 @@ fn spawn_single_line_text(
     height: f64,
     rotation: f64,
     anchor: Anchor,
+    mirror: DVec2,
 ) {
     let mut transform = Transform::from_xyz(position.x() as f32, position.y() as f32, 1.0);
     transform.rotation = Quat::from_rotation_z(rotation as f32);
+    transform.scale = Vec3::new(mirror.x as f32, mirror.y as f32, 1.0);
     commands.spawn((
         Text2d::new(content.to_string()),
         TextFont {
@@ -628,6 +678,17 @@ mod tests {
         assert!((a - 0.85).abs() < 1e-6, "透明度异常: {a}");
     }
 
+    #[test]
+    fn object_color_to_bevy_resolves_index_and_true_color() {
+        let red = super::color_to_rgba(super::object_color_to_bevy(ObjectColor::Index(1)));
+        assert_eq!(red, [1.0, 0.0, 0.0, 1.0]);
+        let [r, g, b, a] = super::color_to_rgba(super::object_color_to_bevy(
+            ObjectColor::TrueColor(0x3366FF),
+        ));
+        assert!((r - 0.2).abs() < 1e-6 && (g - 0.4).abs() < 1e-6 && (b - 1.0).abs() < 1e-6);
+        assert!((a - 1.0).abs() < 1e-6, "实体颜色应不透明: {a}");
+    }
+
     #[test]
     fn apply_gradient_shift_wraps_into_unit_interval() {
         let shifted = super::apply_gradient_shift(0.9, 0.3);
@@ -968,8 +1029,8 @@ fn spawn_block_entity(
 ) {
     match entity {
         DocEntity::Text(text) => {
-            let position = apply_block_transform(reference, base_point, text.insert);
-            let rotation = text.rotation + reference.rotation;
+            let position = apply_block_transform(reference, base_point, text.anchor());
+            let rotation = text.effective_rotation() + reference.rotation;
             let height = text.height * average_scale(reference);
             spawn_single_line_text(
                 commands,
@@ -978,7 +1039,8 @@ fn spawn_block_entity(
                 position,
                 height,
                 rotation,
-                Anchor::BOTTOM_LEFT,
+                attribute_anchor(text.horizontal_align, text.vertical_align),
+                block_text_mirror(document, reference, text.mirror()),
             );
         }
         DocEntity::MText(mtext) => {
@@ -1059,7 +1121,7 @@ fn spawn_block_entity(
             );
         }
         _ => {
-            for polyline in entity_polylines(entity) {
+            for polyline in entity_polylines(entity, document) {
                 if polyline.len() < 2 {
                     continue;
                 }
@@ -1097,9 +1159,20 @@ fn spawn_block_attribute(
         height,
         rotation,
         attribute_anchor(attribute.horizontal_align, attribute.vertical_align),
+        DVec2::ONE,
     );
 }
 
+/// 镜像块参照中的文字：`$MIRRTEXT` 为 1 时随块一同镜像，否则保持可读。
+fn block_text_mirror(document: &Document, reference: &BlockReference, mirror: DVec2) -> DVec2 {
+    let scale = reference.scale.as_vec2();
+    if scale.x * scale.y < 0.0 && document.mirror_text() {
+        DVec2::new(-mirror.x, mirror.y)
+    } else {
+        mirror
+    }
+}
+
 fn attribute_anchor(horizontal: i16, vertical: i16) -> Anchor {
     let h = match horizontal {
         1 | 4 => 1,
@@ -1724,7 +1797,7 @@ fn vector3_to_vec3(vector: Vector3) -> Vec3 {
     Vec3::new(raw.x as f32, raw.y as f32, raw.z as f32)
 }
 
-fn entity_polylines(entity: &DocEntity) -> Vec<Vec<Point2>> {
+fn entity_polylines(entity: &DocEntity, document: &Document) -> Vec<Vec<Point2>> {
     match entity {
         DocEntity::Line(line) => vec![vec![line.start, line.end]],
         DocEntity::Circle(circle) => sample_circle(circle.center, circle.radius, 64),
@@ -1809,12 +1882,33 @@ fn entity_polylines(entity: &DocEntity) -> Vec<Vec<Point2>> {
             if let Some(point) = dimension.arc_definition_point {
                 lines.push(vec![dimension.definition_point, point]);
             }
+            if let Some(jog) = dimension.jog_point {
+                let center = dimension.center_point.unwrap_or(dimension.definition_point);
+                lines.push(vec![center, jog]);
+                if let Some(chord) = dimension.extension_line_end {
+                    lines.push(vec![jog, chord]);
+                }
+            }
+            if let (Some(arc), Some(center)) = (&dimension.arc, dimension.center_point) {
+                let radius = center
+                    .as_vec2()
+                    .distance(dimension.definition_point.as_vec2());
+                lines.extend(sample_arc_points(
+                    center,
+                    radius,
+                    arc.start_angle,
+                    arc.end_angle,
+                    true,
+                    16,
+                ));
+            }
             lines
         }
         DocEntity::BlockReference(_) => Vec::new(),
         DocEntity::Text(_) | DocEntity::MText(_) => Vec::new(),
         DocEntity::Hatch(_) => Vec::new(),
         DocEntity::RasterImage(_) => Vec::new(),
+        DocEntity::Wipeout(_) if !document.wipeout_frame_visible() => Vec::new(),
         DocEntity::Wipeout(wipeout) => {
             let (local_polygon, _) = wipeout_local_polygon(wipeout);
             if local_polygon.len() < 3 {
@@ -1836,6 +1930,39 @@ fn entity_polylines(entity: &DocEntity) -> Vec<Vec<Point2>> {
             }
         }
         DocEntity::Face3D(_) => Vec::new(),
+        DocEntity::Shape(shape) => {
+            // SHX 图形尚未解析，先绘制外框占位
+            let outline = shape.outline();
+            let mut points = outline.to_vec();
+            points.push(outline[0]);
+            vec![points]
+        }
+        DocEntity::Underlay(underlay) => {
+            // 底图内容尚未栅格化，仅绘制裁剪边界
+            let mut points = underlay.clip_outline();
+            if points.is_empty() {
+                return Vec::new();
+            }
+            points.push(points[0]);
+            vec![points]
+        }
+        DocEntity::RevisionCloud(cloud) => {
+            let count = cloud.vertices.len();
+            let segments = if cloud.is_closed {
+                count
+            } else {
+                count.saturating_sub(1)
+            };
+            let mut points: Vec<Point2> = Vec::new();
+            for index in 0..segments {
+                let start = &cloud.vertices[index];
+                let end = &cloud.vertices[(index + 1) % count];
+                let sampled = sample_polyline_segment(start.position, end.position, start.bulge);
+                let skip = usize::from(!points.is_empty());
+                points.extend(sampled.into_iter().skip(skip));
+            }
+            vec![points]
+        }
     }
 }
 
@@ -2132,6 +2259,17 @@ fn aci_to_color(index: u32) -> Color {
     }
 }
 
+fn object_color_to_bevy(color: ObjectColor) -> Color {
+    match color {
+        ObjectColor::Index(index) => aci_to_color(index as u32),
+        ObjectColor::TrueColor(rgb) => {
+            let [r, g, b, _] = color_to_rgba(true_color_to_color(rgb));
+            Color::srgb(r, g, b)
+        }
+        ObjectColor::ByLayer | ObjectColor::ByBlock => Color::WHITE,
+    }
+}
+
 fn gradient_color_to_bevy(raw: Option<u32>) -> Option<Color> {
     let value = raw?;
     if value == 0 {
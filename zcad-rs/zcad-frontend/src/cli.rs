@@ -1,7 +1,8 @@
-use crate::loader::{DocumentSource, load_scene_from_env_or_demo};
+use crate::loader::{DocumentSource, load_scene_from_env_or_demo, recent_files, remember_viewport};
 use tracing::{info, warn};
 use zcad_core::document::{
-    ClipMode, DimensionKind, Entity, HatchEdge, RasterImageClip, RasterImageDisplayOptions,
+    ClipMode, DimensionKind, Entity, HatchEdge, LineWeight, LinetypeEmbeddedContent, ObjectColor,
+    RasterImageClip, RasterImageDisplayOptions, ResolvedProperties,
 };
 use zcad_core::geometry::{Point2, Vector2};
 use zcad_engine::command::{CommandBus, CommandContext, CommandRequest};
@@ -59,7 +60,12 @@ pub fn run_demo() {
 
     println!("当前文档图层：");
     for layer in document.layers() {
-        println!("  - {} (可见: {})", layer.name, layer.is_visible);
+        println!(
+            "  - {} (可见: {}, {})",
+            layer.name,
+            layer.is_visible,
+            describe_properties(&layer.default_properties())
+        );
     }
 
     println!("当前文档实体：");
@@ -385,11 +391,14 @@ pub fn run_demo() {
                     })
                     .unwrap_or_else(|| "<未定义>".to_string());
                 println!(
-                    "  - 填充 #{}, Layer={}, 模式={}, 实心={}, 包围盒={}",
+                    "  - 填充 #{}, Layer={}, 模式={}, 实心={}, 样式={:?}, 关联={}, 种子点={}, 包围盒={}",
                     id.get(),
                     hatch.layer,
                     hatch.pattern_name,
                     if hatch.is_solid { "是" } else { "否" },
+                    hatch.style,
+                    if hatch.is_associative { "是" } else { "否" },
+                    hatch.seed_points.len(),
                     bounds_desc
                 );
                 if let Some(gradient) = &hatch.gradient {
@@ -406,6 +415,17 @@ pub fn run_demo() {
                         gradient.color2
                     );
                 }
+                for (index, line) in hatch.pattern_lines.iter().enumerate() {
+                    println!(
+                        "    图案线 {}: 角度={:.1}°, 基点={}, 偏移=({:.3}, {:.3}), 虚线={:?}",
+                        index + 1,
+                        line.angle.to_degrees(),
+                        format_point(line.base_point),
+                        line.offset.x(),
+                        line.offset.y(),
+                        line.dash_lengths
+                    );
+                }
                 for (index, loop_path) in hatch.loops.iter().enumerate() {
                     let edge_desc: Vec<String> =
                         loop_path.edges.iter().map(describe_hatch_edge).collect();
@@ -434,6 +454,8 @@ pub fn run_demo() {
                     DimensionKind::Radius => "半径".to_string(),
                     DimensionKind::Angular3Point => "三点角度".to_string(),
                     DimensionKind::Ordinate => "坐标".to_string(),
+                    DimensionKind::ArcLength => "弧长".to_string(),
+                    DimensionKind::JoggedRadius => "折弯半径".to_string(),
                     DimensionKind::Unknown(code) => format!("未知({code})"),
                 };
                 let dim_line = format_point_option(dimension.dimension_line_point);
@@ -536,9 +558,128 @@ pub fn run_demo() {
                     normal_label
                 );
             }
+            Entity::Shape(shape) => {
+                let shape_file = document
+                    .shape_file_for(shape)
+                    .map(|file| file.file.clone())
+                    .unwrap_or_else(|| "<未解析>".to_string());
+                println!(
+                    "  - 型 #{}, Layer={}, 名称={}, 插入点={}, 大小={:.3}, 旋转={:.1}°, 型文件={}",
+                    id.get(),
+                    shape.layer,
+                    shape.name,
+                    format_point(shape.insert),
+                    shape.size,
+                    shape.rotation.to_degrees(),
+                    shape_file
+                );
+            }
+            Entity::Underlay(underlay) => {
+                let source = document
+                    .underlay_definition(&underlay.definition_handle)
+                    .map(|definition| format!("{} [{}]", definition.file_path, definition.page))
+                    .unwrap_or_else(|| "<缺少定义>".to_string());
+                println!(
+                    "  - 参考底图 #{}, 类型={}, Layer={}, 插入点={}, 比例={}, 旋转={:.1}°, 裁剪点数={}, 文件={}",
+                    id.get(),
+                    underlay.kind.entity_name(),
+                    underlay.layer,
+                    format_point(underlay.insert),
+                    format_vector(underlay.scale),
+                    underlay.rotation.to_degrees(),
+                    underlay.clip_boundary.len(),
+                    source
+                );
+            }
+            Entity::RevisionCloud(cloud) => {
+                let bounds = entity
+                    .bounds()
+                    .map(|bounds| {
+                        format!(
+                            "{} - {}",
+                            format_point(bounds.min()),
+                            format_point(bounds.max())
+                        )
+                    })
+                    .unwrap_or_else(|| "<空>".to_string());
+                println!(
+                    "  - 修订云线 #{}, Layer={}, 样式={:?}, 弧长={:.3}, 圆弧数={}, 闭合={}, 范围={}",
+                    id.get(),
+                    cloud.layer,
+                    cloud.style,
+                    cloud.arc_length,
+                    cloud.vertices.len(),
+                    if cloud.is_closed { "是" } else { "否" },
+                    bounds
+                );
+            }
+        }
+        // 随层实体已在图层列表中给出特性，这里只列出带覆盖值的实体
+        if document.entity_properties(*id).is_some()
+            && let Some(resolved) = document.resolve_properties(*id)
+        {
+            println!("    特性: {}", describe_properties(&resolved));
+        }
+    }
+
+    let mut linetypes: Vec<_> = document
+        .linetypes()
+        .filter(|linetype| !linetype.is_continuous())
+        .collect();
+    if !linetypes.is_empty() {
+        linetypes.sort_by(|a, b| a.name.cmp(&b.name));
+        println!("线型：");
+        for linetype in linetypes {
+            let embedded: Vec<String> = linetype
+                .elements
+                .iter()
+                .filter_map(|element| element.embedded.as_ref())
+                .map(|embedded| match &embedded.content {
+                    LinetypeEmbeddedContent::Text(text) => format!("文字\"{text}\""),
+                    LinetypeEmbeddedContent::Shape(number) => format!("型 #{number}"),
+                })
+                .collect();
+            println!(
+                "  - {} 图案长度={:.3}, 元素数={}, 嵌入={}",
+                linetype.name,
+                linetype.pattern_length,
+                linetype.elements.len(),
+                if embedded.is_empty() {
+                    "<无>".to_string()
+                } else {
+                    embedded.join(", ")
+                }
+            );
         }
     }
 
+    let underlay_dependencies = document.underlay_dependencies();
+    if !underlay_dependencies.is_empty() {
+        println!("参考底图依赖：");
+        for dependency in &underlay_dependencies {
+            println!(
+                "  - [{}] {} 页面={}, 参照数={}",
+                dependency.kind.entity_name(),
+                dependency.file_path,
+                dependency.page,
+                dependency.reference_count
+            );
+        }
+    }
+
+    if let Some(geodata) = document.geodata() {
+        println!(
+            "地理位置：类型={:?}, 设计点=({:.3}, {:.3}) → 参考点=({:.6}, {:.6}), 水平比例={}, 北向旋转={:.2}°",
+            geodata.coordinate_type,
+            geodata.design_point.x(),
+            geodata.design_point.y(),
+            geodata.reference_point.x(),
+            geodata.reference_point.y(),
+            geodata.horizontal_unit_scale,
+            geodata.north_rotation().to_degrees()
+        );
+    }
+
     if document.blocks().next().is_some() {
         println!("块定义：");
         for block in document.blocks() {
@@ -552,6 +693,42 @@ pub fn run_demo() {
             );
         }
     }
+
+    let memory = document.memory_report();
+    println!(
+        "内存估算：实体约 {} 字节，字符串 {} 个（去重后 {} 个，可节省 {} 字节）",
+        memory.total_bytes(),
+        memory.strings.count,
+        memory.strings.unique_count,
+        memory.strings.duplicated_bytes()
+    );
+    for kind in &memory.by_kind {
+        println!(
+            "  - {} 数量={}, 约 {} 字节",
+            kind.kind,
+            kind.count,
+            kind.bytes()
+        );
+    }
+
+    let recent = recent_files();
+    if !recent.is_empty() {
+        println!("最近打开的文件：");
+        for entry in &recent {
+            println!(
+                "  - {} (实体数={}, 图层数={}, 块数={}, 大小={} 字节)",
+                entry.path.display(),
+                entry.stats.entity_count,
+                entry.stats.layer_count,
+                entry.stats.block_count,
+                entry.stats.file_size
+            );
+        }
+    }
+
+    if let DocumentSource::Dxf(path) = &loaded.source {
+        remember_viewport(path, context.scene);
+    }
 }
 
 fn dispatch_cli_command(
@@ -637,6 +814,23 @@ fn describe_hatch_edge(edge: &HatchEdge) -> String {
     }
 }
 
+fn describe_properties(properties: &ResolvedProperties) -> String {
+    let color = match properties.color {
+        ObjectColor::Index(index) => format!("ACI {index}"),
+        ObjectColor::TrueColor(rgb) => format!("#{rgb:06X}"),
+        ObjectColor::ByLayer => "随层".to_string(),
+        ObjectColor::ByBlock => "随块".to_string(),
+    };
+    let lineweight = match properties.lineweight {
+        LineWeight::Hundredths(value) => format!("{:.2}mm", value as f64 / 100.0),
+        _ => "默认".to_string(),
+    };
+    format!(
+        "颜色={color}, 线型={}, 线宽={lineweight}",
+        properties.linetype
+    )
+}
+
 fn format_point(point: Point2) -> String {
     format!("({:.2}, {:.2})", point.x(), point.y())
 }
@@ -1,7 +1,8 @@
 pub mod cli;
 pub mod errors;
 pub mod loader;
-pub mod resource_locator;
+#[cfg(feature = "render_testing")]
+pub mod render_testing;
 
 #[cfg(feature = "bevy_app")]
 pub mod bevy_app;
@@ -1,12 +1,13 @@
 use std::env;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 use tracing::{info, warn};
-use zcad_config::AppConfig;
+use zcad_config::{AppConfig, DrawingStats, RecentFileEntry, RecentViewport};
+use zcad_core::geometry::Point2;
+use zcad_engine::grid::GridSettings;
 use zcad_engine::scene::{DemoEntities, Scene};
-use zcad_io::{DocumentLoader, DxfFacade};
-
-use crate::resource_locator::{ImageLocator, apply_image_resolution};
+use zcad_io::{DocumentLoader, DxfFacade, xref::load_xrefs};
 
 /// 文档来源，便于前端呈现加载信息。
 #[derive(Debug, Clone)]
@@ -28,17 +29,20 @@ pub struct LoadedScene {
 pub fn load_scene_from_env_or_demo() -> LoadedScene {
     let mut scene = Scene::new();
     let config = load_app_config();
+    scene.set_grid(GridSettings::from_config(&config.grid));
     if let Some(path) = env::var_os("ZCAD_CLI_SAMPLE_DXF") {
         let path = PathBuf::from(path);
-        let loader = DxfFacade::new();
+        let loader = DxfFacade::new()
+            .with_dimension_blocks(true)
+            .with_image_resources(&config.resources);
         match loader.load(&path) {
             Ok(mut document) => {
                 info!(path = %path.display(), "从 DXF 加载文档成功");
-                apply_image_resolution(
-                    &mut document,
-                    &ImageLocator::from_config(path.parent(), &config),
-                );
+                for failure in load_xrefs(&mut document, path.parent(), &loader) {
+                    warn!(xref = %failure.name, error = %failure.error, "外部参照载入失败");
+                }
                 scene.load_document(document);
+                record_recent_open(&config, &path, &mut scene);
                 return LoadedScene {
                     scene,
                     source: DocumentSource::Dxf(path),
@@ -64,6 +68,74 @@ pub fn load_scene_from_env_or_demo() -> LoadedScene {
     }
 }
 
+/// 读取最近文件列表，供启动页展示；读取失败时返回空列表。
+pub fn recent_files() -> Vec<RecentFileEntry> {
+    match load_app_config().open_recent_files() {
+        Ok(recent) => recent.entries().cloned().collect(),
+        Err(err) => {
+            warn!(error = %err, "读取最近文件列表失败");
+            Vec::new()
+        }
+    }
+}
+
+/// 关闭图纸前保存当前视口，下次打开同一文件时恢复。
+pub fn remember_viewport(path: &Path, scene: &Scene) {
+    let config = load_app_config();
+    let mut recent = match config.open_recent_files() {
+        Ok(recent) => recent,
+        Err(err) => {
+            warn!(error = %err, "读取最近文件列表失败");
+            return;
+        }
+    };
+    let viewport = scene.viewport();
+    let key = recent_key(path);
+    if recent.update_viewport(
+        &key,
+        RecentViewport {
+            center: [viewport.center.x(), viewport.center.y()],
+            zoom: viewport.zoom,
+        },
+    ) && let Err(err) = recent.save()
+    {
+        warn!(error = %err, "保存最近文件列表失败");
+    }
+}
+
+/// 登记最近打开的文件，并恢复其上次保存的视口。
+fn record_recent_open(config: &AppConfig, path: &Path, scene: &mut Scene) {
+    let mut recent = match config.open_recent_files() {
+        Ok(recent) => recent,
+        Err(err) => {
+            warn!(error = %err, "读取最近文件列表失败");
+            return;
+        }
+    };
+    let key = recent_key(path);
+    if let Some(viewport) = recent.last_viewport(&key) {
+        scene.set_viewport_center(Point2::new(viewport.center[0], viewport.center[1]));
+        scene.set_viewport_zoom(viewport.zoom);
+        info!(path = %path.display(), "已恢复上次视口");
+    }
+
+    let document = scene.document();
+    let stats = DrawingStats {
+        entity_count: document.entities().count(),
+        layer_count: document.layers().count(),
+        block_count: document.blocks().count(),
+        file_size: fs::metadata(path).map(|meta| meta.len()).unwrap_or(0),
+    };
+    recent.record_open(key, stats);
+    if let Err(err) = recent.save() {
+        warn!(error = %err, "保存最近文件列表失败");
+    }
+}
+
+fn recent_key(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
 fn load_app_config() -> AppConfig {
     match AppConfig::discover() {
         Ok(cfg) => cfg,
@@ -0,0 +1,569 @@
+//! 无 GPU 的线框快照渲染与图像比对，供 CI 中的黄金图测试使用。
+//!
+//! 文档先经 FLATTEN 转为简单图元，再按实体解析后的颜色光栅化为 RGBA 图像；
+//! 比对同时检查逐像素容差与感知哈希距离，避免抗锯齿或字体细节差异导致误报。
+//! 下游嵌入渲染器的工程可直接复用 [`GoldenRenderHarness`] 校验自己的样例图纸。
+
+use std::env;
+use std::f64::consts::TAU;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use glam::DVec2;
+use image::Rgba;
+use thiserror::Error;
+use zcad_core::document::{Document, Entity, FlattenOptions, ObjectColor};
+use zcad_core::geometry::Point2;
+use zcad_core::tessellate::TessellateOptions;
+
+pub use image::RgbaImage;
+
+/// 设置为非空值时，测试会用当前结果覆盖参考图。
+pub const UPDATE_GOLDEN_ENV: &str = "ZCAD_UPDATE_GOLDEN";
+
+/// 感知哈希的网格边长（8×8 = 64 位）。
+const HASH_GRID: u32 = 8;
+
+#[derive(Debug, Error)]
+pub enum RenderTestError {
+    #[error("读取参考图 {path} 失败: {source}")]
+    Read {
+        path: PathBuf,
+        source: image::ImageError,
+    },
+    #[error("写入图像 {path} 失败: {source}")]
+    Write {
+        path: PathBuf,
+        source: image::ImageError,
+    },
+    #[error("无法创建目录 {path}: {source}")]
+    CreateDir { path: PathBuf, source: io::Error },
+    #[error("图像尺寸不一致：参考图 {expected:?}，实际 {actual:?}")]
+    SizeMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+    #[error("参考图 {path} 不存在，已根据当前结果生成，请确认后重新运行")]
+    ReferenceCreated { path: PathBuf },
+    #[error("快照 {name} 与参考图不一致（{diff}），实际结果已写入 {actual}")]
+    Mismatch {
+        name: String,
+        diff: ImageDiff,
+        actual: PathBuf,
+    },
+}
+
+/// 快照渲染参数。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderOptions {
+    pub width: u32,
+    pub height: u32,
+    /// 图纸范围到图像边缘的留白（像素）。
+    pub margin: u32,
+    pub background: [u8; 4],
+    /// 整圆离散段数，传给 FLATTEN。
+    pub curve_segments: usize,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            width: 256,
+            height: 256,
+            margin: 8,
+            background: [0, 0, 0, 255],
+            curve_segments: 64,
+        }
+    }
+}
+
+/// 比对容差。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffTolerance {
+    /// 单个通道允许的最大差值，超过即计为差异像素。
+    pub channel: u8,
+    /// 允许的差异像素占比。
+    pub max_differing_ratio: f64,
+    /// 允许的感知哈希汉明距离。
+    pub max_hash_distance: u32,
+}
+
+impl Default for DiffTolerance {
+    fn default() -> Self {
+        Self {
+            channel: 16,
+            max_differing_ratio: 0.001,
+            max_hash_distance: 4,
+        }
+    }
+}
+
+/// 两幅图像的比对结果。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageDiff {
+    pub differing_pixels: u64,
+    pub total_pixels: u64,
+    pub max_channel_delta: u8,
+    pub hash_distance: u32,
+}
+
+impl ImageDiff {
+    pub fn differing_ratio(&self) -> f64 {
+        if self.total_pixels == 0 {
+            0.0
+        } else {
+            self.differing_pixels as f64 / self.total_pixels as f64
+        }
+    }
+
+    pub fn is_within(&self, tolerance: &DiffTolerance) -> bool {
+        self.differing_ratio() <= tolerance.max_differing_ratio
+            && self.hash_distance <= tolerance.max_hash_distance
+    }
+}
+
+impl fmt::Display for ImageDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "差异像素 {}/{}，最大通道差 {}，哈希距离 {}",
+            self.differing_pixels, self.total_pixels, self.max_channel_delta, self.hash_distance
+        )
+    }
+}
+
+/// 将文档渲染为线框快照：块参照被炸开，文字以包围框轮廓表示，隐藏图层不绘制。
+pub fn render_document(document: &Document, options: &RenderOptions) -> RgbaImage {
+    let mut image = RgbaImage::from_pixel(options.width, options.height, Rgba(options.background));
+    let flat = document.flatten(&FlattenOptions {
+        tessellation: TessellateOptions::per_turn(options.curve_segments),
+        outline_text: true,
+        ..FlattenOptions::default()
+    });
+    let Some(bounds) = flat.bounds() else {
+        return image;
+    };
+
+    let view = Viewport::fit(bounds.min(), bounds.max(), options);
+    let segments = options.curve_segments.max(4);
+    for (id, entity) in flat.display_list() {
+        let color = flat
+            .resolve_properties(*id)
+            .map(|properties| object_color_to_rgba(properties.color))
+            .unwrap_or([255, 255, 255, 255]);
+        for polyline in entity_paths(entity, segments) {
+            for pair in polyline.windows(2) {
+                draw_line(
+                    &mut image,
+                    view.project(pair[0]),
+                    view.project(pair[1]),
+                    color,
+                );
+            }
+        }
+    }
+    image
+}
+
+/// 64 位平均哈希：缩放到 8×8 灰度后按均值二值化。
+pub fn perceptual_hash(image: &RgbaImage) -> u64 {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return 0;
+    }
+    let mut cells = [0.0_f64; (HASH_GRID * HASH_GRID) as usize];
+    for gy in 0..HASH_GRID {
+        let y0 = gy * height / HASH_GRID;
+        let y1 = ((gy + 1) * height / HASH_GRID).max(y0 + 1).min(height);
+        for gx in 0..HASH_GRID {
+            let x0 = gx * width / HASH_GRID;
+            let x1 = ((gx + 1) * width / HASH_GRID).max(x0 + 1).min(width);
+            let mut sum = 0.0;
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    sum += luminance(image.get_pixel(x, y));
+                }
+            }
+            let count = ((y1 - y0) * (x1 - x0)) as f64;
+            cells[(gy * HASH_GRID + gx) as usize] = sum / count;
+        }
+    }
+    let mean = cells.iter().sum::<f64>() / cells.len() as f64;
+    cells
+        .iter()
+        .enumerate()
+        .filter(|(_, value)| **value > mean)
+        .fold(0_u64, |hash, (index, _)| hash | (1 << index))
+}
+
+/// 逐像素比对两幅图像并计算感知哈希距离。
+pub fn compare_images(
+    expected: &RgbaImage,
+    actual: &RgbaImage,
+    tolerance: &DiffTolerance,
+) -> Result<ImageDiff, RenderTestError> {
+    if expected.dimensions() != actual.dimensions() {
+        return Err(RenderTestError::SizeMismatch {
+            expected: expected.dimensions(),
+            actual: actual.dimensions(),
+        });
+    }
+    let mut differing_pixels = 0;
+    let mut max_channel_delta = 0;
+    for (left, right) in expected.pixels().zip(actual.pixels()) {
+        let delta = channel_delta(left, right);
+        max_channel_delta = max_channel_delta.max(delta);
+        if delta > tolerance.channel {
+            differing_pixels += 1;
+        }
+    }
+    Ok(ImageDiff {
+        differing_pixels,
+        total_pixels: u64::from(expected.width()) * u64::from(expected.height()),
+        max_channel_delta,
+        hash_distance: (perceptual_hash(expected) ^ perceptual_hash(actual)).count_ones(),
+    })
+}
+
+/// 生成差异图：超出容差的像素标红，其余像素按参考图淡化显示。
+pub fn diff_image(expected: &RgbaImage, actual: &RgbaImage, channel_tolerance: u8) -> RgbaImage {
+    let (width, height) = expected.dimensions();
+    RgbaImage::from_fn(width, height, |x, y| {
+        let left = expected.get_pixel(x, y);
+        match actual.get_pixel_checked(x, y) {
+            Some(right) if channel_delta(left, right) <= channel_tolerance => {
+                let gray = (luminance(left) * 0.25) as u8;
+                Rgba([gray, gray, gray, 255])
+            }
+            _ => Rgba([255, 0, 0, 255]),
+        }
+    })
+}
+
+/// 黄金图测试工具：渲染文档并与参考目录中的 `<name>.png` 比对。
+///
+/// 参考图缺失时自动生成并返回 [`RenderTestError::ReferenceCreated`]；
+/// 比对失败时在同一目录写出 `<name>.actual.png` 与 `<name>.diff.png`。
+#[derive(Debug, Clone)]
+pub struct GoldenRenderHarness {
+    reference_dir: PathBuf,
+    options: RenderOptions,
+    tolerance: DiffTolerance,
+    update: bool,
+}
+
+impl GoldenRenderHarness {
+    /// 是否覆盖参考图由环境变量 [`UPDATE_GOLDEN_ENV`] 决定。
+    pub fn new(reference_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            reference_dir: reference_dir.into(),
+            options: RenderOptions::default(),
+            tolerance: DiffTolerance::default(),
+            update: env::var_os(UPDATE_GOLDEN_ENV).is_some_and(|value| !value.is_empty()),
+        }
+    }
+
+    pub fn with_options(mut self, options: RenderOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: DiffTolerance) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn with_update(mut self, update: bool) -> Self {
+        self.update = update;
+        self
+    }
+
+    pub fn reference_dir(&self) -> &Path {
+        &self.reference_dir
+    }
+
+    pub fn reference_path(&self, name: &str) -> PathBuf {
+        self.reference_dir.join(format!("{name}.png"))
+    }
+
+    /// 渲染并比对，返回差异统计。
+    pub fn check(&self, name: &str, document: &Document) -> Result<ImageDiff, RenderTestError> {
+        let actual = render_document(document, &self.options);
+        self.check_image(name, &actual)
+    }
+
+    /// 比对已渲染的图像，供使用自有渲染管线的调用方复用。
+    pub fn check_image(
+        &self,
+        name: &str,
+        actual: &RgbaImage,
+    ) -> Result<ImageDiff, RenderTestError> {
+        let reference = self.reference_path(name);
+        let exists = reference.exists();
+        if self.update || !exists {
+            self.write(&reference, actual)?;
+            if !exists && !self.update {
+                return Err(RenderTestError::ReferenceCreated { path: reference });
+            }
+            return compare_images(actual, actual, &self.tolerance);
+        }
+
+        let expected = image::open(&reference)
+            .map_err(|source| RenderTestError::Read {
+                path: reference.clone(),
+                source,
+            })?
+            .to_rgba8();
+        let diff = compare_images(&expected, actual, &self.tolerance)?;
+        if diff.is_within(&self.tolerance) {
+            return Ok(diff);
+        }
+
+        let actual_path = self.reference_dir.join(format!("{name}.actual.png"));
+        self.write(&actual_path, actual)?;
+        self.write(
+            &self.reference_dir.join(format!("{name}.diff.png")),
+            &diff_image(&expected, actual, self.tolerance.channel),
+        )?;
+        Err(RenderTestError::Mismatch {
+            name: name.to_string(),
+            diff,
+            actual: actual_path,
+        })
+    }
+
+    /// 测试断言形式的 [`check`](Self::check)，失败时 panic 并给出原因。
+    pub fn assert_matches(&self, name: &str, document: &Document) {
+        if let Err(err) = self.check(name, document) {
+            panic!("{err}");
+        }
+    }
+
+    fn write(&self, path: &Path, image: &RgbaImage) -> Result<(), RenderTestError> {
+        fs::create_dir_all(&self.reference_dir).map_err(|source| RenderTestError::CreateDir {
+            path: self.reference_dir.clone(),
+            source,
+        })?;
+        image.save(path).map_err(|source| RenderTestError::Write {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+/// 世界坐标到像素坐标的映射，保持纵横比并居中，Y 轴向上。
+struct Viewport {
+    origin: DVec2,
+    scale: f64,
+    offset: DVec2,
+    height: f64,
+}
+
+impl Viewport {
+    fn fit(min: Point2, max: Point2, options: &RenderOptions) -> Self {
+        let margin = f64::from(options.margin);
+        let available = DVec2::new(
+            (f64::from(options.width) - 2.0 * margin - 1.0).max(1.0),
+            (f64::from(options.height) - 2.0 * margin - 1.0).max(1.0),
+        );
+        let extent = max.as_vec2() - min.as_vec2();
+        let scale = match (extent.x > f64::EPSILON, extent.y > f64::EPSILON) {
+            (true, true) => (available.x / extent.x).min(available.y / extent.y),
+            (true, false) => available.x / extent.x,
+            (false, true) => available.y / extent.y,
+            (false, false) => 1.0,
+        };
+        Self {
+            origin: min.as_vec2(),
+            scale,
+            offset: DVec2::splat(margin) + (available - extent * scale) * 0.5,
+            height: f64::from(options.height),
+        }
+    }
+
+    fn project(&self, point: Point2) -> (i64, i64) {
+        let pixel = (point.as_vec2() - self.origin) * self.scale + self.offset;
+        (
+            pixel.x.round() as i64,
+            (self.height - 1.0 - pixel.y).round() as i64,
+        )
+    }
+}
+
+fn entity_paths(entity: &Entity, segments: usize) -> Vec<Vec<Point2>> {
+    match entity {
+        Entity::Line(line) => vec![vec![line.start, line.end]],
+        Entity::Circle(circle) => {
+            vec![arc_points(circle.center, circle.radius, 0.0, TAU, segments)]
+        }
+        Entity::Arc(arc) => {
+            let mut sweep = (arc.end_angle - arc.start_angle).rem_euclid(TAU);
+            if sweep <= f64::EPSILON {
+                sweep = TAU;
+            }
+            let count = ((segments as f64 * sweep / TAU).ceil() as usize).max(1);
+            vec![arc_points(
+                arc.center,
+                arc.radius,
+                arc.start_angle,
+                sweep,
+                count,
+            )]
+        }
+        Entity::Polyline(polyline) => {
+            let mut points: Vec<Point2> = polyline
+                .vertices
+                .iter()
+                .map(|vertex| vertex.position)
+                .collect();
+            if polyline.is_closed
+                && let Some(first) = points.first().copied()
+            {
+                points.push(first);
+            }
+            vec![points]
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn arc_points(center: Point2, radius: f64, start: f64, sweep: f64, count: usize) -> Vec<Point2> {
+    (0..=count)
+        .map(|step| {
+            let angle = start + sweep * step as f64 / count as f64;
+            Point2::new(
+                center.x() + radius * angle.cos(),
+                center.y() + radius * angle.sin(),
+            )
+        })
+        .collect()
+}
+
+/// Bresenham 直线，超出画布的像素被丢弃。
+fn draw_line(image: &mut RgbaImage, from: (i64, i64), to: (i64, i64), color: [u8; 4]) {
+    let (mut x, mut y) = from;
+    let dx = (to.0 - x).abs();
+    let dy = -(to.1 - y).abs();
+    let step_x = if x < to.0 { 1 } else { -1 };
+    let step_y = if y < to.1 { 1 } else { -1 };
+    let mut error = dx + dy;
+    loop {
+        if x >= 0 && y >= 0 && x < i64::from(image.width()) && y < i64::from(image.height()) {
+            image.put_pixel(x as u32, y as u32, Rgba(color));
+        }
+        if x == to.0 && y == to.1 {
+            break;
+        }
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+}
+
+fn object_color_to_rgba(color: ObjectColor) -> [u8; 4] {
+    match color {
+        ObjectColor::Index(index) => {
+            let [r, g, b] = aci_to_rgb(index);
+            [r, g, b, 255]
+        }
+        ObjectColor::TrueColor(rgb) => [(rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8, 255],
+        ObjectColor::ByLayer | ObjectColor::ByBlock => [255, 255, 255, 255],
+    }
+}
+
+/// 与 Bevy 前端一致的简化 ACI 调色板：标准 7 色之外统一为灰色。
+fn aci_to_rgb(index: u8) -> [u8; 3] {
+    match index {
+        1 => [255, 0, 0],
+        2 => [255, 255, 0],
+        3 => [0, 255, 0],
+        4 => [0, 255, 255],
+        5 => [0, 0, 255],
+        6 => [255, 0, 255],
+        7 => [255, 255, 255],
+        _ => [179, 179, 179],
+    }
+}
+
+fn luminance(pixel: &Rgba<u8>) -> f64 {
+    let [r, g, b, _] = pixel.0;
+    0.299 * f64::from(r) + 0.587 * f64::from(g) + 0.114 * f64::from(b)
+}
+
+fn channel_delta(left: &Rgba<u8>, right: &Rgba<u8>) -> u8 {
+    left.0
+        .iter()
+        .zip(right.0.iter())
+        .map(|(a, b)| a.abs_diff(*b))
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_images_respects_channel_tolerance_and_hash() {
+        let base = RgbaImage::from_fn(64, 64, |x, _| {
+            if x < 32 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+        let mut noisy = base.clone();
+        noisy.put_pixel(0, 0, Rgba([10, 10, 10, 255]));
+        let diff = compare_images(&base, &noisy, &DiffTolerance::default()).unwrap();
+        assert_eq!(diff.differing_pixels, 0);
+        assert_eq!(diff.max_channel_delta, 10);
+        assert_eq!(diff.hash_distance, 0);
+
+        let inverted = RgbaImage::from_fn(64, 64, |x, y| {
+            let pixel = base.get_pixel(x, y).0;
+            Rgba([255 - pixel[0], 255 - pixel[1], 255 - pixel[2], 255])
+        });
+        let diff = compare_images(&base, &inverted, &DiffTolerance::default()).unwrap();
+        assert_eq!(diff.differing_pixels, 64 * 64);
+        assert!(diff.hash_distance > 4, "反相图像的哈希应明显不同");
+        assert!(!diff.is_within(&DiffTolerance::default()));
+
+        let small = RgbaImage::new(8, 8);
+        assert!(matches!(
+            compare_images(&base, &small, &DiffTolerance::default()),
+            Err(RenderTestError::SizeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn render_document_uses_resolved_layer_color() {
+        let mut document = Document::new();
+        document.add_line(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), "RED");
+        document.add_line(Point2::new(0.0, 10.0), Point2::new(10.0, 10.0), "HIDDEN");
+        document.layer_mut("RED").unwrap().color = ObjectColor::Index(1);
+        document.layer_mut("HIDDEN").unwrap().is_visible = false;
+
+        let options = RenderOptions {
+            width: 32,
+            height: 32,
+            margin: 0,
+            ..RenderOptions::default()
+        };
+        let image = render_document(&document, &options);
+        assert_eq!(image.get_pixel(0, 31).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(31, 31).0, [255, 0, 0, 255]);
+        assert_eq!(
+            image.get_pixel(0, 0).0,
+            options.background,
+            "隐藏图层不应绘制"
+        );
+    }
+}
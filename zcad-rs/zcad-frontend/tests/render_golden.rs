@@ -0,0 +1,67 @@
+#![cfg(feature = "render_testing")]
+
+use std::path::PathBuf;
+
+use zcad_frontend::render_testing::{
+    GoldenRenderHarness, RenderOptions, RenderTestError, render_document,
+};
+use zcad_io::{DocumentLoader, DxfFacade};
+
+fn harness() -> GoldenRenderHarness {
+    GoldenRenderHarness::new(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/render"))
+}
+
+fn load_fixture(name: &str) -> zcad_core::document::Document {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../zcad-io/tests/data")
+        .join(format!("{name}.dxf"));
+    DxfFacade::new()
+        .with_dimension_blocks(true)
+        .load(&path)
+        .unwrap_or_else(|err| panic!("读取样例 {} 失败: {err}", path.display()))
+}
+
+#[test]
+fn render_basic_entities_matches_reference() {
+    harness().assert_matches("basic_entities", &load_fixture("basic_entities"));
+}
+
+#[test]
+fn render_block_hatch_matches_reference() {
+    harness().assert_matches("block_hatch", &load_fixture("block_hatch"));
+}
+
+#[test]
+fn render_layer_properties_matches_reference() {
+    let document = load_fixture("layer_properties");
+    harness().assert_matches("layer_properties", &document);
+
+    // WALL 图层默认红色，冻结的 ACCENT 图层不应出现
+    let image = render_document(&document, &RenderOptions::default());
+    let colors: std::collections::HashSet<[u8; 4]> = image.pixels().map(|pixel| pixel.0).collect();
+    assert!(colors.contains(&[255, 0, 0, 255]), "随层红色未生效");
+    assert!(!colors.contains(&[0x33, 0x66, 0x99, 255]), "冻结图层被绘制");
+}
+
+#[test]
+fn render_mismatch_writes_actual_and_diff() {
+    let dir = std::env::temp_dir().join(format!("zcad-render-golden-{}", std::process::id()));
+    let harness = GoldenRenderHarness::new(&dir).with_update(false);
+    let basic = load_fixture("basic_entities");
+    assert!(matches!(
+        harness.check("case", &basic),
+        Err(RenderTestError::ReferenceCreated { .. })
+    ));
+    harness.check("case", &basic).expect("相同文档应通过比对");
+
+    let other = load_fixture("block_hatch");
+    match harness.check("case", &other) {
+        Err(RenderTestError::Mismatch { diff, actual, .. }) => {
+            assert!(diff.differing_pixels > 0);
+            assert!(actual.exists());
+            assert!(dir.join("case.diff.png").exists());
+        }
+        other => panic!("期望比对失败，实际: {other:?}"),
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+}
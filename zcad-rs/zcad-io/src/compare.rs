@@ -0,0 +1,225 @@
+//! 文档语义比较，用于校验 DXF 写出后再读取的结果与原文档一致。
+//!
+//! 比较基于各对象的序列化形式：实数按相对容差比较，实体标识、图像反应器与
+//! 图像字典等书写时重新生成的簿记信息不参与比较。
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+use zcad_core::document::{Document, EntityId};
+
+/// 实数比较的相对容差。
+const REAL_TOLERANCE: f64 = 1e-9;
+
+/// 写出时按需重新分配的簿记字段，不参与比较。
+const BOOKKEEPING_FIELDS: &[&str] = &["image_def_reactor_handle"];
+
+/// 两份文档之间的首个差异。
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentDivergence {
+    /// 差异所在位置，例如 `entities[3].Line.end[0]`。
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for DocumentDivergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// 按图层、线型、型文件、图层状态、实体及其特性覆盖、用户数据与绘制顺序、块、外部参照、
+/// 图像与底图定义、单位、地理数据及光栅/遮罩变量的顺序比较两份文档，返回首个差异；
+/// 完全一致时返回 `None`。
+///
+/// 实体按文档顺序逐一比较，图层、图层状态与块按名称对齐；`expected` 未设置
+/// RASTERVARIABLES 时忽略 `actual` 中写出器补全的默认值。
+pub fn compare_documents(expected: &Document, actual: &Document) -> Option<DocumentDivergence> {
+    compare(expected, actual).err()
+}
+
+fn compare(expected: &Document, actual: &Document) -> Result<(), DocumentDivergence> {
+    let mut expected_layers: Vec<_> = expected.layers().collect();
+    let mut actual_layers: Vec<_> = actual.layers().collect();
+    expected_layers.sort_by(|a, b| a.name.cmp(&b.name));
+    actual_layers.sort_by(|a, b| a.name.cmp(&b.name));
+    compare_values("layers", &expected_layers, &actual_layers)?;
+
+    let mut expected_linetypes: Vec<_> = expected.linetypes().collect();
+    let mut actual_linetypes: Vec<_> = actual.linetypes().collect();
+    expected_linetypes.sort_by(|a, b| a.name.cmp(&b.name));
+    actual_linetypes.sort_by(|a, b| a.name.cmp(&b.name));
+    compare_values("linetypes", &expected_linetypes, &actual_linetypes)?;
+
+    let mut expected_shapes: Vec<_> = expected.shape_files().collect();
+    let mut actual_shapes: Vec<_> = actual.shape_files().collect();
+    expected_shapes.sort_by(|a, b| a.handle.cmp(&b.handle));
+    actual_shapes.sort_by(|a, b| a.handle.cmp(&b.handle));
+    compare_values("shape_files", &expected_shapes, &actual_shapes)?;
+
+    let mut expected_states: Vec<_> = expected.layer_states().collect();
+    let mut actual_states: Vec<_> = actual.layer_states().collect();
+    expected_states.sort_by(|a, b| a.name.cmp(&b.name));
+    actual_states.sort_by(|a, b| a.name.cmp(&b.name));
+    compare_values("layer_states", &expected_states, &actual_states)?;
+
+    let expected_entities: Vec<_> = expected.entities().map(|(_, entity)| entity).collect();
+    let actual_entities: Vec<_> = actual.entities().map(|(_, entity)| entity).collect();
+    compare_values("entities", &expected_entities, &actual_entities)?;
+
+    // 特性覆盖、用户数据与绘制顺序按实体在文档中的位置对齐
+    let properties = |document: &Document| {
+        document
+            .entities()
+            .map(|(id, _)| document.entity_properties(*id).cloned())
+            .collect::<Vec<_>>()
+    };
+    compare_values(
+        "entity_properties",
+        &properties(expected),
+        &properties(actual),
+    )?;
+    let user_data = |document: &Document| {
+        document
+            .entities()
+            .map(|(id, _)| document.user_data(*id).cloned())
+            .collect::<Vec<_>>()
+    };
+    compare_values("user_data", &user_data(expected), &user_data(actual))?;
+    let draw_order = |document: &Document| {
+        let positions: HashMap<EntityId, usize> = document
+            .entities()
+            .enumerate()
+            .map(|(position, (id, _))| (*id, position))
+            .collect();
+        document
+            .entities_in_draw_order()
+            .into_iter()
+            .map(|(id, _)| positions[id])
+            .collect::<Vec<_>>()
+    };
+    compare_values("draw_order", &draw_order(expected), &draw_order(actual))?;
+
+    let mut expected_blocks: Vec<_> = expected.blocks().collect();
+    let mut actual_blocks: Vec<_> = actual.blocks().collect();
+    expected_blocks.sort_by(|a, b| a.name.cmp(&b.name));
+    actual_blocks.sort_by(|a, b| a.name.cmp(&b.name));
+    compare_values("blocks", &expected_blocks, &actual_blocks)?;
+
+    let xref_key = |document: &Document| {
+        let mut xrefs: Vec<(String, String)> = document
+            .xrefs()
+            .map(|xref| (xref.name.clone(), xref.path.clone()))
+            .collect();
+        xrefs.sort();
+        xrefs
+    };
+    compare_values("xrefs", &xref_key(expected), &xref_key(actual))?;
+
+    // 解析路径取决于图纸所在目录，不属于文档内容
+    let image_definitions = |document: &Document| {
+        let mut definitions: Vec<_> = document
+            .raster_image_definitions()
+            .map(|(_, definition)| {
+                let mut definition = definition.clone();
+                definition.resolved_path = None;
+                definition
+            })
+            .collect();
+        definitions.sort_by(|a, b| a.handle.cmp(&b.handle));
+        definitions
+    };
+    compare_values(
+        "raster_image_definitions",
+        &image_definitions(expected),
+        &image_definitions(actual),
+    )?;
+
+    let mut expected_underlays: Vec<_> = expected.underlay_definitions().collect();
+    let mut actual_underlays: Vec<_> = actual.underlay_definitions().collect();
+    expected_underlays.sort_by(|a, b| a.handle.cmp(&b.handle));
+    actual_underlays.sort_by(|a, b| a.handle.cmp(&b.handle));
+    compare_values(
+        "underlay_definitions",
+        &expected_underlays,
+        &actual_underlays,
+    )?;
+
+    compare_values(
+        "mirror_text",
+        &expected.mirror_text(),
+        &actual.mirror_text(),
+    )?;
+    compare_values("units", &expected.units(), &actual.units())?;
+    compare_values("geodata", &expected.geodata(), &actual.geodata())?;
+    compare_values(
+        "wipeout_variables",
+        &expected.wipeout_variables(),
+        &actual.wipeout_variables(),
+    )?;
+    if expected.raster_image_variables().is_some() {
+        compare_values(
+            "raster_image_variables",
+            &expected.raster_image_variables(),
+            &actual.raster_image_variables(),
+        )?;
+    }
+    Ok(())
+}
+
+fn compare_values<T: Serialize + ?Sized>(
+    path: &str,
+    expected: &T,
+    actual: &T,
+) -> Result<(), DocumentDivergence> {
+    let expected = serde_json::to_value(expected).expect("文档对象可序列化");
+    let actual = serde_json::to_value(actual).expect("文档对象可序列化");
+    match diverge(path.to_string(), &expected, &actual) {
+        Some(divergence) => Err(divergence),
+        None => Ok(()),
+    }
+}
+
+fn diverge(path: String, expected: &Value, actual: &Value) -> Option<DocumentDivergence> {
+    let divergence = |message: String| {
+        Some(DocumentDivergence {
+            path: path.clone(),
+            message,
+        })
+    };
+    match (expected, actual) {
+        (Value::Number(a), Value::Number(b)) => {
+            let (a, b) = (a.as_f64()?, b.as_f64()?);
+            let tolerance = REAL_TOLERANCE * 1f64.max(a.abs()).max(b.abs());
+            if (a - b).abs() > tolerance {
+                return divergence(format!("期望 {a}，实际 {b}"));
+            }
+            None
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            if a.len() != b.len() {
+                return divergence(format!("期望 {} 项，实际 {} 项", a.len(), b.len()));
+            }
+            a.iter()
+                .zip(b)
+                .enumerate()
+                .find_map(|(index, (a, b))| diverge(format!("{path}[{index}]"), a, b))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            let compared = |key: &&String| !BOOKKEEPING_FIELDS.contains(&key.as_str());
+            if let Some(key) = a.keys().filter(compared).find(|key| !b.contains_key(*key)) {
+                return divergence(format!("缺少字段 {key}"));
+            }
+            if let Some(key) = b.keys().filter(compared).find(|key| !a.contains_key(*key)) {
+                return divergence(format!("多出字段 {key}"));
+            }
+            a.iter()
+                .filter(|(key, _)| compared(key))
+                .find_map(|(key, value)| diverge(format!("{path}.{key}"), value, &b[key]))
+        }
+        (a, b) if a == b => None,
+        (a, b) => divergence(format!("期望 {a}，实际 {b}")),
+    }
+}
@@ -0,0 +1,137 @@
+//! 数据提取导出：把 [`Document::extract_attributes`] 的结果写成 CSV 或 JSON，供表格软件生成明细表与材料清单。
+//!
+//! CSV 按 RFC 4180 转义，首行为列名：逐个参照时依次为块名、图层、插入点 X/Y 与各属性标记，
+//! 汇总时为块名、数量与各属性标记。JSON 为 [`ExtractionTable`] 的序列化结果。
+
+use std::fs;
+use std::path::Path;
+
+use zcad_core::document::{Document, ExtractionOptions, ExtractionTable};
+
+use crate::{DocumentSaver, IoError};
+
+/// 提取结果的输出格式。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExtractionFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl ExtractionFormat {
+    /// 按扩展名（不区分大小写）判断格式。
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// 数据提取导出选项。
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ExtractionExportOptions {
+    pub extraction: ExtractionOptions,
+    pub format: ExtractionFormat,
+    /// JSON 输出带缩进。
+    pub pretty: bool,
+}
+
+/// 数据提取导出器，实现 [`DocumentSaver`]。
+#[derive(Debug, Clone, Default)]
+pub struct ExtractionExporter {
+    options: ExtractionExportOptions,
+}
+
+impl ExtractionExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(mut self, options: ExtractionExportOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn options(&self) -> &ExtractionExportOptions {
+        &self.options
+    }
+
+    /// 提取属性并按选项中的格式生成文本。
+    pub fn export(&self, document: &Document) -> Result<String, IoError> {
+        let table = document.extract_attributes(&self.options.extraction);
+        match self.options.format {
+            ExtractionFormat::Csv => Ok(table_to_csv(&table)),
+            ExtractionFormat::Json => table_to_json(&table, self.options.pretty),
+        }
+    }
+}
+
+impl DocumentSaver for ExtractionExporter {
+    fn save(&self, document: &Document, path: &Path) -> Result<(), IoError> {
+        let data = self.export(document)?;
+        fs::write(path, data).map_err(|source| IoError::WriteError {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+/// 生成 CSV 文本，行以 CRLF 结尾。
+pub fn table_to_csv(table: &ExtractionTable) -> String {
+    let mut header: Vec<&str> = if table.grouped {
+        vec!["Block", "Count"]
+    } else {
+        vec!["Block", "Layer", "X", "Y"]
+    };
+    header.extend(table.tags.iter().map(String::as_str));
+
+    let mut out = String::new();
+    push_record(&mut out, header.into_iter().map(str::to_string));
+    for row in &table.rows {
+        let mut fields = vec![row.block.clone()];
+        if table.grouped {
+            fields.push(row.count.to_string());
+        } else {
+            fields.push(row.layer.clone().unwrap_or_default());
+            match row.position {
+                Some(position) => {
+                    fields.push(position.x().to_string());
+                    fields.push(position.y().to_string());
+                }
+                None => fields.extend([String::new(), String::new()]),
+            }
+        }
+        fields.extend(row.values.iter().cloned());
+        push_record(&mut out, fields.into_iter());
+    }
+    out
+}
+
+/// 生成 JSON 文本。
+pub fn table_to_json(table: &ExtractionTable, pretty: bool) -> Result<String, IoError> {
+    let result = if pretty {
+        serde_json::to_string_pretty(table)
+    } else {
+        serde_json::to_string(table)
+    };
+    result.map_err(|err| IoError::InvalidDocument(format!("数据提取结果序列化失败: {err}")))
+}
+
+fn push_record(out: &mut String, fields: impl Iterator<Item = String>) {
+    for (index, field) in fields.enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        if field.contains([',', '"', '\r', '\n']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(&field);
+        }
+    }
+    out.push_str("\r\n");
+}
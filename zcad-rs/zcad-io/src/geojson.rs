@@ -0,0 +1,341 @@
+//! GeoJSON 导出：将场地图纸中的线、多段线、填充与文字转换为 GIS 工具可读取的要素集合。
+//!
+//! 文档经 FLATTEN（保留填充）后逐个图元生成要素，曲线按 `tessellation` 离散；
+//! 填充边界按嵌套层次组成带洞的面。要素属性包含图层、解析后的颜色/线型/线宽、
+//! 源实体类型，块参照拆出的图元另附块名与属性值。
+//! 图纸带有 GEODATA 时可换算到地图坐标：地理坐标类型输出 WGS84 经纬度，
+//! 局部/投影网格输出网格坐标（GeoJSON 规范要求 WGS84，投影坐标需由下游按坐标系解释）。
+
+use std::fs;
+use std::path::Path;
+
+use glam::DVec2;
+use serde_json::{Map, Value, json};
+use zcad_core::document::{Document, Entity, EntityId, FlattenOptions, GeoData, Hatch, HatchStyle};
+use zcad_core::geometry::Point2;
+use zcad_core::tessellate::TessellateOptions;
+
+use crate::plot::{curve_points, ring_points};
+use crate::{DocumentSaver, IoError};
+
+/// GeoJSON 导出选项。
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoJsonExportOptions {
+    /// 图纸带有 GEODATA 时将坐标换算到地图坐标；关闭时输出图纸坐标。
+    pub apply_geodata: bool,
+    /// 闭合的多段线与圆输出为面（Polygon）；关闭时输出首尾相接的线（LineString）。
+    pub closed_as_polygons: bool,
+    /// 单行文字输出为带文字内容的点要素。
+    pub include_text: bool,
+    /// 圆弧、椭圆、凸度段与样条的离散精度。
+    pub tessellation: TessellateOptions,
+    /// 输出带缩进的 JSON。
+    pub pretty: bool,
+}
+
+impl Default for GeoJsonExportOptions {
+    fn default() -> Self {
+        Self {
+            apply_geodata: true,
+            closed_as_polygons: false,
+            include_text: true,
+            tessellation: TessellateOptions::default(),
+            pretty: false,
+        }
+    }
+}
+
+/// GeoJSON 导出器，实现 [`DocumentSaver`]。
+#[derive(Debug, Clone, Default)]
+pub struct GeoJsonExporter {
+    options: GeoJsonExportOptions,
+}
+
+impl GeoJsonExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(mut self, options: GeoJsonExportOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn options(&self) -> &GeoJsonExportOptions {
+        &self.options
+    }
+
+    /// 生成 `FeatureCollection`。
+    pub fn to_value(&self, document: &Document) -> Value {
+        let (flat, sources) = document.flatten_with_sources(&FlattenOptions {
+            tessellation: self.options.tessellation,
+            keep_fills: true,
+            ..FlattenOptions::default()
+        });
+        let converter = Converter {
+            options: &self.options,
+            geodata: document.geodata().filter(|_| self.options.apply_geodata),
+        };
+        let features = flat
+            .visible_entities()
+            .filter_map(|(id, entity)| {
+                let geometry = converter.geometry(entity)?;
+                let source = sources.get(id).copied().unwrap_or(*id);
+                Some(json!({
+                    "type": "Feature",
+                    "geometry": geometry,
+                    "properties": properties(document, &flat, *id, entity, source),
+                }))
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "type": "FeatureCollection",
+            "features": features,
+        })
+    }
+
+    /// 生成 GeoJSON 文本。
+    pub fn to_geojson(&self, document: &Document) -> Result<String, IoError> {
+        let value = self.to_value(document);
+        let result = if self.options.pretty {
+            serde_json::to_string_pretty(&value)
+        } else {
+            serde_json::to_string(&value)
+        };
+        result.map_err(|err| IoError::InvalidDocument(format!("GeoJSON 序列化失败: {err}")))
+    }
+}
+
+impl DocumentSaver for GeoJsonExporter {
+    fn save(&self, document: &Document, path: &Path) -> Result<(), IoError> {
+        let data = self.to_geojson(document)?;
+        fs::write(path, data).map_err(|source| IoError::WriteError {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+/// 要素属性：图元自身的图层与解析后的特性，以及源实体的类型和块参照信息。
+fn properties(
+    document: &Document,
+    flat: &Document,
+    id: EntityId,
+    entity: &Entity,
+    source: EntityId,
+) -> Map<String, Value> {
+    let resolved = flat.resolve_properties(id).unwrap_or_default();
+    let mut properties = Map::new();
+    properties.insert("layer".into(), json!(entity.layer_name()));
+    properties.insert(
+        "color".into(),
+        json!(
+            resolved
+                .color
+                .rgb()
+                .map(|rgb| format!("#{:06x}", rgb & 0xFF_FFFF))
+        ),
+    );
+    properties.insert("linetype".into(), json!(resolved.linetype));
+    properties.insert(
+        "lineweight".into(),
+        json!(resolved.lineweight.millimeters()),
+    );
+    let source_entity = document.entity(source);
+    properties.insert(
+        "source_type".into(),
+        json!(source_entity.map_or(entity.kind_name(), Entity::kind_name)),
+    );
+    properties.insert("source_id".into(), json!(source.get()));
+
+    match entity {
+        Entity::Text(text) => {
+            properties.insert("text".into(), json!(text.content));
+            properties.insert("height".into(), json!(text.height));
+            properties.insert("rotation".into(), json!(text.rotation.to_degrees()));
+        }
+        Entity::Hatch(hatch) => {
+            properties.insert("pattern".into(), json!(hatch.pattern_name));
+        }
+        _ => {}
+    }
+    if let Some(Entity::BlockReference(reference)) = source_entity {
+        properties.insert("block".into(), json!(reference.name));
+        let attributes = reference
+            .attributes
+            .iter()
+            .map(|attribute| (attribute.tag.clone(), json!(attribute.text)))
+            .collect::<Map<_, _>>();
+        properties.insert("attributes".into(), Value::Object(attributes));
+    }
+    properties
+}
+
+struct Converter<'a> {
+    options: &'a GeoJsonExportOptions,
+    geodata: Option<&'a GeoData>,
+}
+
+impl Converter<'_> {
+    fn geometry(&self, entity: &Entity) -> Option<Value> {
+        match entity {
+            Entity::Line(line) => {
+                Some(self.line_string(&[line.start.as_vec2(), line.end.as_vec2()]))
+            }
+            Entity::Arc(_) => {
+                Some(self.line_string(&curve_points(entity, &self.options.tessellation)))
+            }
+            Entity::Circle(_) => {
+                Some(self.closed(curve_points(entity, &self.options.tessellation)))
+            }
+            Entity::Polyline(polyline) => {
+                let points = curve_points(entity, &self.options.tessellation);
+                match points.len() {
+                    0 => None,
+                    1 => Some(self.point(points[0])),
+                    _ if polyline.is_closed => Some(self.closed(points)),
+                    _ => Some(self.line_string(&points)),
+                }
+            }
+            Entity::Text(text) if self.options.include_text => {
+                Some(self.point(text.insert.as_vec2()))
+            }
+            Entity::Hatch(hatch) => self.hatch(hatch),
+            // 区域覆盖只影响显示，其余对象已由 FLATTEN 转换为上述图元
+            _ => None,
+        }
+    }
+
+    /// 填充边界按嵌套深度分组：偶数层为外环，奇数层为其最近外环中的洞；
+    /// 外部样式只取前两层，忽略样式只取最外层。
+    fn hatch(&self, hatch: &Hatch) -> Option<Value> {
+        let max_depth = match hatch.style {
+            HatchStyle::Normal => usize::MAX,
+            HatchStyle::Outer => 1,
+            HatchStyle::Ignore => 0,
+        };
+        let rings = hatch
+            .loops
+            .iter()
+            .map(|loop_path| ring_points(loop_path, &self.options.tessellation))
+            .filter(|points| points.len() >= 3)
+            .collect::<Vec<_>>();
+        let containers = rings
+            .iter()
+            .enumerate()
+            .map(|(index, ring)| {
+                rings
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, outer)| *other != index && contains(outer, ring[0]))
+                    .map(|(other, _)| other)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut polygons: Vec<(usize, Vec<Value>)> = Vec::new();
+        for (index, ring) in rings.iter().enumerate() {
+            let depth = containers[index].len();
+            if depth % 2 == 0 && depth <= max_depth {
+                polygons.push((index, vec![self.ring(ring, true)]));
+            }
+        }
+        for (index, ring) in rings.iter().enumerate() {
+            let depth = containers[index].len();
+            if depth % 2 == 0 || depth > max_depth {
+                continue;
+            }
+            // 最近的外环是嵌套深度恰好少一层的容器
+            let parent = containers[index]
+                .iter()
+                .find(|outer| containers[**outer].len() + 1 == depth);
+            if let Some((_, polygon)) =
+                parent.and_then(|parent| polygons.iter_mut().find(|(outer, _)| outer == parent))
+            {
+                polygon.push(self.ring(ring, false));
+            }
+        }
+        match polygons.len() {
+            0 => None,
+            1 => Some(json!({
+                "type": "Polygon",
+                "coordinates": polygons.pop().unwrap().1,
+            })),
+            _ => Some(json!({
+                "type": "MultiPolygon",
+                "coordinates": polygons.into_iter().map(|(_, rings)| rings).collect::<Vec<_>>(),
+            })),
+        }
+    }
+
+    fn closed(&self, points: Vec<DVec2>) -> Value {
+        if self.options.closed_as_polygons && points.len() >= 3 {
+            return json!({
+                "type": "Polygon",
+                "coordinates": [self.ring(&points, true)],
+            });
+        }
+        let mut points = points;
+        points.push(points[0]);
+        self.line_string(&points)
+    }
+
+    fn point(&self, point: DVec2) -> Value {
+        json!({
+            "type": "Point",
+            "coordinates": self.position(point),
+        })
+    }
+
+    fn line_string(&self, points: &[DVec2]) -> Value {
+        json!({
+            "type": "LineString",
+            "coordinates": points.iter().map(|point| self.position(*point)).collect::<Vec<_>>(),
+        })
+    }
+
+    /// 闭合线性环：外环逆时针、洞顺时针（RFC 7946 右手规则），首尾点相同。
+    fn ring(&self, points: &[DVec2], exterior: bool) -> Value {
+        let mut positions = points
+            .iter()
+            .map(|point| self.position(*point))
+            .collect::<Vec<_>>();
+        let area = positions
+            .iter()
+            .zip(positions.iter().cycle().skip(1))
+            .map(|(a, b)| a[0] * b[1] - b[0] * a[1])
+            .sum::<f64>();
+        if (area > 0.0) != exterior {
+            positions.reverse();
+        }
+        positions.push(positions[0]);
+        json!(positions)
+    }
+
+    fn position(&self, point: DVec2) -> [f64; 2] {
+        let point = Point2::from_vec(point);
+        match self.geodata {
+            Some(geodata) => {
+                let map = geodata.to_map(point);
+                [map.x(), map.y()]
+            }
+            None => [point.x(), point.y()],
+        }
+    }
+}
+
+/// 射线法判断点是否在多边形内。
+fn contains(polygon: &[DVec2], point: DVec2) -> bool {
+    let mut inside = false;
+    let mut previous = polygon[polygon.len() - 1];
+    for current in polygon {
+        if (current.y > point.y) != (previous.y > point.y) {
+            let x = previous.x
+                + (point.y - previous.y) / (current.y - previous.y) * (current.x - previous.x);
+            if point.x < x {
+                inside = !inside;
+            }
+        }
+        previous = *current;
+    }
+    inside
+}
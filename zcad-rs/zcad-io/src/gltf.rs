@@ -0,0 +1,425 @@
+//! glTF 2.0 导出：把 3D 面（含多面网格、多边形网格解析出的面）输出为三角网格，
+//! 便于在通用 3D 查看器中预览三维 DXF 内容。
+//!
+//! 每个可见图层对应一个节点、网格与材质；颜色被对象覆盖的面另建以“图层名 #rrggbb”命名的材质。
+//! 块参照按插入变换展开，块内 0 层对象继承参照所在图层。3D 面按顶点 1-2-3、1-3-4
+//! 拆为两个三角形，各自使用面法线；退化三角形被丢弃。CAD 的 Z 轴向上，默认转换为
+//! glTF 约定的 Y 轴向上。
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use base64::Engine as _;
+use glam::{DAffine2, DVec3};
+use serde_json::{Value, json};
+use zcad_core::document::{Document, Entity, EntityProperties, ThreeDFace};
+
+use crate::plot::display_color;
+use crate::{DocumentSaver, IoError};
+
+/// GLB 文件头魔数 `glTF`。
+const GLB_MAGIC: u32 = 0x4654_6C67;
+/// GLB JSON 块类型。
+const GLB_CHUNK_JSON: u32 = 0x4E4F_534A;
+/// GLB 二进制块类型。
+const GLB_CHUNK_BIN: u32 = 0x004E_4942;
+/// bufferView 目标：顶点属性。
+const ARRAY_BUFFER: u32 = 34962;
+/// accessor 分量类型：32 位浮点。
+const COMPONENT_FLOAT: u32 = 5126;
+
+/// glTF 输出选项。
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfExportOptions {
+    /// 保存为二进制 GLB；关闭时写出 `.gltf` JSON，缓冲区以 data URI 内嵌。
+    pub binary: bool,
+    /// 把 CAD 的 Z 轴向上转换为 glTF 的 Y 轴向上。
+    pub y_up: bool,
+    /// 材质双面显示；3D 面没有可靠的朝向时应保持开启。
+    pub double_sided: bool,
+    /// 块嵌套展开的最大深度，防止循环引用。
+    pub max_block_depth: usize,
+}
+
+impl Default for GltfExportOptions {
+    fn default() -> Self {
+        Self {
+            binary: false,
+            y_up: true,
+            double_sided: true,
+            max_block_depth: 16,
+        }
+    }
+}
+
+/// glTF 2.0 导出器，实现 [`DocumentSaver`]。
+#[derive(Debug, Clone, Default)]
+pub struct GltfExporter {
+    options: GltfExportOptions,
+}
+
+impl GltfExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(mut self, options: GltfExportOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn options(&self) -> &GltfExportOptions {
+        &self.options
+    }
+
+    /// 生成 glTF JSON，缓冲区以 base64 data URI 内嵌。
+    pub fn to_gltf(&self, document: &Document) -> Value {
+        let (mut gltf, buffer) = self.build(document);
+        if !buffer.is_empty() {
+            gltf["buffers"] = json!([{
+                "byteLength": buffer.len(),
+                "uri": format!(
+                    "data:application/octet-stream;base64,{}",
+                    base64::engine::general_purpose::STANDARD.encode(&buffer)
+                ),
+            }]);
+        }
+        gltf
+    }
+
+    /// 生成二进制 GLB：JSON 块之后紧跟二进制缓冲区块。
+    pub fn to_glb(&self, document: &Document) -> Vec<u8> {
+        let (mut gltf, mut buffer) = self.build(document);
+        if !buffer.is_empty() {
+            gltf["buffers"] = json!([{ "byteLength": buffer.len() }]);
+        }
+        let mut json = gltf.to_string().into_bytes();
+        // 各块长度须为 4 的倍数：JSON 以空格补齐，二进制以零补齐
+        json.resize(json.len().next_multiple_of(4), b' ');
+        buffer.resize(buffer.len().next_multiple_of(4), 0);
+
+        let mut length = 12 + 8 + json.len();
+        if !buffer.is_empty() {
+            length += 8 + buffer.len();
+        }
+        let mut glb = Vec::with_capacity(length);
+        glb.extend_from_slice(&GLB_MAGIC.to_le_bytes());
+        glb.extend_from_slice(&2u32.to_le_bytes());
+        glb.extend_from_slice(&(length as u32).to_le_bytes());
+        glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        glb.extend_from_slice(&GLB_CHUNK_JSON.to_le_bytes());
+        glb.extend_from_slice(&json);
+        if !buffer.is_empty() {
+            glb.extend_from_slice(&(buffer.len() as u32).to_le_bytes());
+            glb.extend_from_slice(&GLB_CHUNK_BIN.to_le_bytes());
+            glb.extend_from_slice(&buffer);
+        }
+        glb
+    }
+
+    /// 组装除 `buffers` 以外的 glTF 结构及其二进制缓冲区。
+    fn build(&self, document: &Document) -> (Value, Vec<u8>) {
+        let mut collector = Collector {
+            document,
+            options: &self.options,
+            groups: Vec::new(),
+        };
+        for (id, entity) in document.display_list() {
+            collector.entity(
+                entity,
+                DAffine2::IDENTITY,
+                document.entity_properties(*id),
+                0,
+            );
+        }
+
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut buffer_views = Vec::new();
+        let mut accessors = Vec::new();
+        let mut materials: Vec<Value> = Vec::new();
+        let mut material_index: HashMap<(&str, u32), usize> = HashMap::new();
+        let mut meshes: Vec<Value> = Vec::new();
+        let mut nodes = Vec::new();
+        for group in &collector.groups {
+            let mut primitives = Vec::new();
+            for primitive in &group.primitives {
+                let key = (group.layer.as_str(), primitive.color);
+                let material = *material_index.entry(key).or_insert_with(|| {
+                    let layer_color = document
+                        .layer(&group.layer)
+                        .map_or(0x000000, |layer| display_color(layer.color, None));
+                    let name = if primitive.color == layer_color {
+                        group.layer.clone()
+                    } else {
+                        format!("{} #{:06x}", group.layer, primitive.color & 0xFF_FFFF)
+                    };
+                    materials.push(self.material(name, primitive.color));
+                    materials.len() - 1
+                });
+                let (min, max) = bounds(&primitive.positions);
+                let position = push_attribute(
+                    &mut buffer,
+                    &mut buffer_views,
+                    &mut accessors,
+                    &primitive.positions,
+                    Some((min, max)),
+                );
+                let normal = push_attribute(
+                    &mut buffer,
+                    &mut buffer_views,
+                    &mut accessors,
+                    &primitive.normals,
+                    None,
+                );
+                primitives.push(json!({
+                    "attributes": { "POSITION": position, "NORMAL": normal },
+                    "material": material,
+                    "mode": 4,
+                }));
+            }
+            nodes.push(json!({ "name": group.layer, "mesh": meshes.len() }));
+            meshes.push(json!({ "name": group.layer, "primitives": primitives }));
+        }
+
+        let mut gltf = json!({
+            "asset": { "version": "2.0", "generator": "zcad-rs" },
+            "scene": 0,
+            "scenes": [{ "nodes": (0..nodes.len()).collect::<Vec<_>>() }],
+        });
+        if !nodes.is_empty() {
+            gltf["nodes"] = json!(nodes);
+            gltf["meshes"] = json!(meshes);
+            gltf["materials"] = json!(materials);
+            gltf["bufferViews"] = json!(buffer_views);
+            gltf["accessors"] = json!(accessors);
+        }
+        (gltf, buffer)
+    }
+
+    /// 材质颜色为线性空间，由 sRGB 显示色换算。
+    fn material(&self, name: String, rgb: u32) -> Value {
+        let channel = |shift: u32| srgb_to_linear(((rgb >> shift) & 0xFF) as f64 / 255.0);
+        json!({
+            "name": name,
+            "pbrMetallicRoughness": {
+                "baseColorFactor": [channel(16), channel(8), channel(0), 1.0],
+                "metallicFactor": 0.0,
+                "roughnessFactor": 1.0,
+            },
+            "doubleSided": self.options.double_sided,
+        })
+    }
+}
+
+impl DocumentSaver for GltfExporter {
+    fn save(&self, document: &Document, path: &Path) -> Result<(), IoError> {
+        let data = if self.options.binary {
+            self.to_glb(document)
+        } else {
+            serde_json::to_vec(&self.to_gltf(document))
+                .map_err(|err| IoError::InvalidDocument(format!("glTF 序列化失败: {err}")))?
+        };
+        fs::write(path, data).map_err(|source| IoError::WriteError {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+/// 同一图层的网格。
+struct LayerGroup {
+    layer: String,
+    primitives: Vec<Primitive>,
+}
+
+/// 同一颜色的三角形，顶点不共享以保留面法线。
+struct Primitive {
+    color: u32,
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+}
+
+struct Collector<'a> {
+    document: &'a Document,
+    options: &'a GltfExportOptions,
+    groups: Vec<LayerGroup>,
+}
+
+impl Collector<'_> {
+    /// `properties` 为顶层实体的特性覆盖，块内对象沿用，与 FLATTEN 一致。
+    fn entity(
+        &mut self,
+        entity: &Entity,
+        transform: DAffine2,
+        properties: Option<&EntityProperties>,
+        depth: usize,
+    ) {
+        match entity {
+            Entity::Face3D(face) => self.face(face, transform, properties),
+            Entity::BlockReference(reference) => {
+                let Some(block) = self.document.block(&reference.name) else {
+                    return;
+                };
+                if depth >= self.options.max_block_depth {
+                    return;
+                }
+                let block_transform = transform
+                    * DAffine2::from_scale_angle_translation(
+                        reference.scale.as_vec2(),
+                        reference.rotation,
+                        reference.insert.as_vec2(),
+                    )
+                    * DAffine2::from_translation(-block.base_point.as_vec2());
+                for child in &block.entities {
+                    if child.layer_name() == "0" {
+                        let mut child = child.clone();
+                        child.set_layer_name(reference.layer.clone());
+                        self.entity(&child, block_transform, properties, depth + 1);
+                    } else {
+                        self.entity(child, block_transform, properties, depth + 1);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn face(
+        &mut self,
+        face: &ThreeDFace,
+        transform: DAffine2,
+        properties: Option<&EntityProperties>,
+    ) {
+        if self
+            .document
+            .layer(&face.layer)
+            .is_some_and(|layer| !layer.is_displayed())
+        {
+            return;
+        }
+        // 块变换只作用于 XY 平面，Z 保持不变
+        let vertices = face.vertices.map(|vertex| {
+            let vertex = vertex.as_vec3();
+            transform
+                .transform_point2(vertex.truncate())
+                .extend(vertex.z)
+        });
+        let mut triangles = vec![[vertices[0], vertices[1], vertices[2]]];
+        if vertices[3] != vertices[2] {
+            triangles.push([vertices[0], vertices[2], vertices[3]]);
+        }
+        let color = display_color(
+            self.document
+                .resolve_layer_properties(&face.layer, properties, None)
+                .color,
+            None,
+        );
+        let y_up = self.options.y_up;
+        let primitive = self.primitive(&face.layer, color);
+        for triangle in triangles {
+            let normal = (triangle[1] - triangle[0]).cross(triangle[2] - triangle[0]);
+            let length = normal.length();
+            if length <= f64::EPSILON * triangle[0].abs().max_element().max(1.0) {
+                continue;
+            }
+            let normal = axis(normal / length, y_up);
+            for vertex in triangle {
+                primitive.positions.push(axis(vertex, y_up));
+                primitive.normals.push(normal);
+            }
+        }
+    }
+
+    fn primitive(&mut self, layer: &str, color: u32) -> &mut Primitive {
+        let index = match self.groups.iter().position(|group| group.layer == layer) {
+            Some(index) => index,
+            None => {
+                self.groups.push(LayerGroup {
+                    layer: layer.to_string(),
+                    primitives: Vec::new(),
+                });
+                self.groups.len() - 1
+            }
+        };
+        let primitives = &mut self.groups[index].primitives;
+        let index = match primitives
+            .iter()
+            .position(|primitive| primitive.color == color)
+        {
+            Some(index) => index,
+            None => {
+                primitives.push(Primitive {
+                    color,
+                    positions: Vec::new(),
+                    normals: Vec::new(),
+                });
+                primitives.len() - 1
+            }
+        };
+        &mut primitives[index]
+    }
+}
+
+/// CAD 坐标转为 glTF 坐标：Y 轴向上时 (x, y, z) → (x, z, -y)。
+fn axis(point: DVec3, y_up: bool) -> [f32; 3] {
+    let point = if y_up {
+        DVec3::new(point.x, point.z, -point.y)
+    } else {
+        point
+    };
+    point.as_vec3().to_array()
+}
+
+fn bounds(values: &[[f32; 3]]) -> ([f32; 3], [f32; 3]) {
+    values.iter().fold(
+        ([f32::MAX; 3], [f32::MIN; 3]),
+        |(mut min, mut max), value| {
+            for index in 0..3 {
+                min[index] = min[index].min(value[index]);
+                max[index] = max[index].max(value[index]);
+            }
+            (min, max)
+        },
+    )
+}
+
+/// 追加一组 VEC3 浮点属性，返回 accessor 索引；POSITION 须带范围。
+fn push_attribute(
+    buffer: &mut Vec<u8>,
+    buffer_views: &mut Vec<Value>,
+    accessors: &mut Vec<Value>,
+    values: &[[f32; 3]],
+    range: Option<([f32; 3], [f32; 3])>,
+) -> usize {
+    let offset = buffer.len();
+    for value in values.iter().flatten() {
+        buffer.extend_from_slice(&value.to_le_bytes());
+    }
+    buffer_views.push(json!({
+        "buffer": 0,
+        "byteOffset": offset,
+        "byteLength": buffer.len() - offset,
+        "target": ARRAY_BUFFER,
+    }));
+    let mut accessor = json!({
+        "bufferView": buffer_views.len() - 1,
+        "componentType": COMPONENT_FLOAT,
+        "count": values.len(),
+        "type": "VEC3",
+    });
+    if let Some((min, max)) = range {
+        accessor["min"] = json!(min);
+        accessor["max"] = json!(max);
+    }
+    accessors.push(accessor);
+    accessors.len() - 1
+}
+
+fn srgb_to_linear(value: f64) -> f64 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
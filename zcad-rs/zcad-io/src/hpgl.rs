@@ -0,0 +1,260 @@
+//! HPGL/2 绘图仪输出（.plt）：供切割绘图仪与笔式绘图仪使用。
+//!
+//! 文档经 FLATTEN 后按图层分配笔号，同一支笔的对象集中输出以减少换笔；
+//! 圆与圆弧（含多段线凸度段）使用绘图仪的 `CI`/`AA` 指令，其余曲线离散为 `PD` 折线。
+//! 坐标单位为绘图仪单位（1/40 毫米），填充只输出边界，文字使用绘图仪内置字体（`LB`）。
+
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use glam::DVec2;
+use zcad_core::document::{Document, Entity, FlattenOptions, Polyline, Text};
+use zcad_core::geometry::BulgeArc;
+use zcad_core::tessellate::TessellateOptions;
+
+use crate::plot::{TEXT_WIDTH_FACTOR, drawing_extent, format_number};
+use crate::{DocumentSaver, IoError};
+
+/// 每毫米对应的绘图仪单位数。
+const PLOTTER_UNITS_PER_MM: f64 = 40.0;
+/// `LB` 标签的默认结束符（ETX）。
+const LABEL_TERMINATOR: char = '\u{3}';
+
+/// HPGL/2 输出选项。
+#[derive(Debug, Clone, PartialEq)]
+pub struct HpglExportOptions {
+    /// 每个图形单位对应的毫米数，即出图比例。
+    pub millimeters_per_unit: f64,
+    /// 将图纸范围左下角移至绘图仪原点；关闭时保留图形坐标。
+    pub origin_at_extents: bool,
+    /// 按图层名指定笔号；未列出的图层按出现顺序依次使用 1..=`pen_count`。
+    pub layer_pens: HashMap<String, u8>,
+    /// 自动分配时可用的笔数，至少为 1。
+    pub pen_count: u8,
+    /// 椭圆与样条的离散精度，传给 FLATTEN。
+    pub tessellation: TessellateOptions,
+}
+
+impl Default for HpglExportOptions {
+    fn default() -> Self {
+        Self {
+            millimeters_per_unit: 1.0,
+            origin_at_extents: true,
+            layer_pens: HashMap::new(),
+            pen_count: 8,
+            tessellation: TessellateOptions::default(),
+        }
+    }
+}
+
+/// HPGL/2 导出器，实现 [`DocumentSaver`]。
+#[derive(Debug, Clone, Default)]
+pub struct HpglExporter {
+    options: HpglExportOptions,
+}
+
+impl HpglExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(mut self, options: HpglExportOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn options(&self) -> &HpglExportOptions {
+        &self.options
+    }
+
+    /// 生成完整的 HPGL/2 指令流。
+    pub fn to_hpgl(&self, document: &Document) -> String {
+        let flat = document.flatten(&FlattenOptions {
+            tessellation: self.options.tessellation,
+            ..FlattenOptions::default()
+        });
+        let origin = if self.options.origin_at_extents {
+            drawing_extent(&flat).map_or(DVec2::ZERO, |(min, _)| min)
+        } else {
+            DVec2::ZERO
+        };
+        let plotter = Plotter {
+            origin,
+            scale: self.options.millimeters_per_unit * PLOTTER_UNITS_PER_MM,
+        };
+
+        let mut pens: Vec<(u8, String)> = Vec::new();
+        let mut automatic: HashMap<&str, u8> = HashMap::new();
+        let pen_count = self.options.pen_count.max(1);
+        for (_, entity) in flat.display_list() {
+            let layer = entity.layer_name();
+            let pen = match self.options.layer_pens.get(layer) {
+                Some(pen) => *pen,
+                None => {
+                    let next = (automatic.len() % usize::from(pen_count)) as u8 + 1;
+                    *automatic.entry(layer).or_insert(next)
+                }
+            };
+            let commands = plotter.entity(entity);
+            if commands.is_empty() {
+                continue;
+            }
+            match pens.iter_mut().find(|(existing, _)| *existing == pen) {
+                Some((_, output)) => output.push_str(&commands),
+                None => pens.push((pen, commands)),
+            }
+        }
+        pens.sort_by_key(|(pen, _)| *pen);
+
+        let mut output = String::from("IN;\n");
+        for (pen, commands) in pens {
+            let _ = writeln!(output, "SP{pen};");
+            output.push_str(&commands);
+        }
+        output.push_str("PU;SP0;\n");
+        output
+    }
+}
+
+impl DocumentSaver for HpglExporter {
+    fn save(&self, document: &Document, path: &Path) -> Result<(), IoError> {
+        fs::write(path, self.to_hpgl(document)).map_err(|source| IoError::WriteError {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+struct Plotter {
+    origin: DVec2,
+    /// 每个图形单位对应的绘图仪单位数。
+    scale: f64,
+}
+
+impl Plotter {
+    /// 单个实体的指令，以换行结束；无可绘制内容时返回空串。
+    fn entity(&self, entity: &Entity) -> String {
+        let mut commands = String::new();
+        match entity {
+            Entity::Line(line) => {
+                let _ = writeln!(
+                    commands,
+                    "PU{};PD{};",
+                    self.point(line.start.as_vec2()),
+                    self.point(line.end.as_vec2())
+                );
+            }
+            Entity::Circle(circle) => {
+                let _ = writeln!(
+                    commands,
+                    "PU{};CI{};",
+                    self.point(circle.center.as_vec2()),
+                    self.length(circle.radius.abs())
+                );
+            }
+            Entity::Arc(arc) => {
+                let mut sweep = (arc.end_angle - arc.start_angle).rem_euclid(TAU);
+                if sweep <= 1e-12 {
+                    sweep = TAU;
+                }
+                let center = arc.center.as_vec2();
+                let start = center + DVec2::from_angle(arc.start_angle) * arc.radius.abs();
+                let _ = writeln!(
+                    commands,
+                    "PU{};PD;AA{},{};",
+                    self.point(start),
+                    self.point(center),
+                    format_number(sweep.to_degrees(), 3)
+                );
+            }
+            Entity::Polyline(polyline) => self.polyline(polyline, &mut commands),
+            Entity::Text(text) => self.text(text, &mut commands),
+            // FLATTEN 已将其余对象转换为上述图元
+            _ => {}
+        }
+        commands
+    }
+
+    /// 直线段合并为一条 `PD` 折线，凸度段改用 `AA` 圆弧。
+    fn polyline(&self, polyline: &Polyline, commands: &mut String) {
+        let vertices = &polyline.vertices;
+        let Some(first) = vertices.first() else {
+            return;
+        };
+        let segments = if polyline.is_closed {
+            vertices.len()
+        } else {
+            vertices.len() - 1
+        };
+        let _ = write!(commands, "PU{};", self.point(first.position.as_vec2()));
+        let mut run: Vec<String> = Vec::new();
+        for index in 0..segments {
+            let current = &vertices[index];
+            let next = vertices[(index + 1) % vertices.len()].position.as_vec2();
+            match BulgeArc::new(current.position.as_vec2(), next, current.bulge) {
+                Some(arc) => {
+                    flush_run(&mut run, commands);
+                    let _ = write!(
+                        commands,
+                        "PD;AA{},{};",
+                        self.point(arc.center),
+                        format_number(arc.sweep.to_degrees(), 3)
+                    );
+                }
+                None => run.push(self.point(next)),
+            }
+        }
+        flush_run(&mut run, commands);
+        commands.push('\n');
+    }
+
+    /// 文字以绘图仪内置字体输出：`DI` 定方向、`SI` 定字符尺寸（厘米），
+    /// 非 ASCII 字符以 `?` 代替。
+    fn text(&self, text: &Text, commands: &mut String) {
+        if text.content.is_empty() || text.height <= 0.0 {
+            return;
+        }
+        let height_cm = text.height * self.scale / PLOTTER_UNITS_PER_MM / 10.0;
+        let direction = DVec2::from_angle(text.rotation);
+        let label: String = text
+            .content
+            .chars()
+            .map(|ch| {
+                if ch.is_ascii() && !ch.is_ascii_control() {
+                    ch
+                } else {
+                    '?'
+                }
+            })
+            .collect();
+        let _ = writeln!(
+            commands,
+            "PU{};DI{},{};SI{},{};LB{label}{LABEL_TERMINATOR}",
+            self.point(text.insert.as_vec2()),
+            format_number(direction.x, 4),
+            format_number(direction.y, 4),
+            format_number(height_cm * TEXT_WIDTH_FACTOR, 4),
+            format_number(height_cm, 4)
+        );
+    }
+
+    /// 图形坐标转为整数绘图仪单位，格式为 `x,y`。
+    fn point(&self, point: DVec2) -> String {
+        let point = ((point - self.origin) * self.scale).round();
+        format!("{},{}", point.x as i64, point.y as i64)
+    }
+
+    fn length(&self, value: f64) -> i64 {
+        (value * self.scale).round() as i64
+    }
+}
+
+fn flush_run(run: &mut Vec<String>, commands: &mut String) {
+    if !run.is_empty() {
+        let _ = write!(commands, "PD{};", run.join(","));
+        run.clear();
+    }
+}
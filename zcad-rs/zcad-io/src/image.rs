@@ -0,0 +1,206 @@
+//! IMAGE 路径解析：把 IMAGEDEF 中记录的文件路径解析为本机可用路径，并查找配套的世界文件。
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use zcad_config::ResourceConfig;
+use zcad_core::document::Document;
+
+use crate::IoError;
+
+/// 追加图像搜索目录的环境变量，多个目录按平台路径分隔符分隔。
+pub const IMAGE_ROOTS_ENV: &str = "ZCAD_IMAGE_ROOTS";
+
+/// 常见世界文件扩展名；另外按 `<图像扩展名首尾字母>w` 规则推导（如 `.tif` → `.tfw`）。
+const WORLD_FILE_EXTENSIONS: &[&str] = &["wld"];
+
+/// 图像路径解析器，依次尝试：绝对路径、相对图纸目录、配置的搜索目录，
+/// 最后在图纸目录与搜索目录中按文件名查找。
+#[derive(Debug, Clone, Default)]
+pub struct ImagePathResolver {
+    drawing_dir: Option<PathBuf>,
+    search_roots: Vec<PathBuf>,
+}
+
+impl ImagePathResolver {
+    pub fn new(drawing_dir: Option<&Path>) -> Self {
+        Self {
+            drawing_dir: drawing_dir.map(Path::to_path_buf),
+            search_roots: Vec::new(),
+        }
+    }
+
+    /// 由资源配置构造：`resources.image_roots` 与环境变量 `ZCAD_IMAGE_ROOTS` 中存在的目录。
+    pub fn from_resources(drawing_dir: Option<&Path>, resources: &ResourceConfig) -> Self {
+        let mut roots: Vec<PathBuf> = resources.image_roots.clone();
+        if let Some(env_paths) = env::var_os(IMAGE_ROOTS_ENV) {
+            roots.extend(env::split_paths(&env_paths));
+        }
+        Self::new(drawing_dir).with_search_roots(roots.into_iter().filter(|root| root.is_dir()))
+    }
+
+    /// 追加搜索目录，重复目录只保留第一次出现的位置。
+    pub fn with_search_roots(mut self, roots: impl IntoIterator<Item = PathBuf>) -> Self {
+        for root in roots {
+            if !self.search_roots.contains(&root) {
+                self.search_roots.push(root);
+            }
+        }
+        self
+    }
+
+    #[inline]
+    pub fn search_roots(&self) -> &[PathBuf] {
+        &self.search_roots
+    }
+
+    /// 解析 IMAGEDEF 文件路径，找不到文件时返回 `None`。
+    pub fn resolve(&self, raw: &str) -> Option<PathBuf> {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            return None;
+        }
+        let path = PathBuf::from(raw);
+        if path.is_absolute() && path.is_file() {
+            return Some(canonicalize_or_clone(&path));
+        }
+
+        // DXF 常以 Windows 分隔符保存相对路径
+        let relative = normalize_separators(raw);
+        let roots = || self.drawing_dir.iter().chain(&self.search_roots);
+        if !Path::new(&relative).is_absolute() && !is_windows_absolute(raw) {
+            for root in roots() {
+                let candidate = root.join(&relative);
+                if candidate.is_file() {
+                    return Some(canonicalize_or_clone(&candidate));
+                }
+            }
+        }
+
+        let file_name = file_name(raw)?;
+        roots()
+            .map(|root| root.join(file_name))
+            .find(|candidate| candidate.is_file())
+            .map(|candidate| canonicalize_or_clone(&candidate))
+    }
+
+    /// 为尚未解析的图像定义写入 `resolved_path`，返回本次解析成功的数量。
+    pub fn apply(&self, document: &mut Document) -> usize {
+        let mut resolved = 0;
+        for (_, definition) in document.raster_image_definitions_mut() {
+            if definition.resolved_path.is_some() {
+                continue;
+            }
+            if let Some(path) = self.resolve(&definition.file_path) {
+                definition.resolved_path = Some(path.to_string_lossy().into_owned());
+                resolved += 1;
+            }
+        }
+        resolved
+    }
+}
+
+/// 世界文件（ESRI world file）记录的仿射变换：像素坐标到世界坐标。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorldFile {
+    /// X 方向像素大小（A）。
+    pub pixel_size_x: f64,
+    /// 行旋转项（D）。
+    pub rotation_y: f64,
+    /// 列旋转项（B）。
+    pub rotation_x: f64,
+    /// Y 方向像素大小，通常为负值（E）。
+    pub pixel_size_y: f64,
+    /// 左上角像素中心的世界坐标（C, F）。
+    pub origin_x: f64,
+    pub origin_y: f64,
+}
+
+impl WorldFile {
+    /// 解析六行数值格式的世界文件内容。
+    pub fn parse(content: &str) -> Result<Self, IoError> {
+        let values = content
+            .split_whitespace()
+            .map(|token| {
+                token
+                    .parse::<f64>()
+                    .map_err(|_| IoError::InvalidDocument(format!("世界文件包含无效数值：{token}")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let [a, d, b, e, c, f] = values[..] else {
+            return Err(IoError::InvalidDocument(format!(
+                "世界文件应包含 6 个数值，实际为 {}",
+                values.len()
+            )));
+        };
+        Ok(Self {
+            pixel_size_x: a,
+            rotation_y: d,
+            rotation_x: b,
+            pixel_size_y: e,
+            origin_x: c,
+            origin_y: f,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, IoError> {
+        let content = fs::read_to_string(path).map_err(|source| IoError::ReadError {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse(&content)
+    }
+
+    /// 查找图像旁的世界文件：`.tif` → `.tfw`/`.tifw`，以及通用的 `.wld`。
+    pub fn find_for_image(image: &Path) -> Option<PathBuf> {
+        let extension = image.extension()?.to_str()?;
+        let mut candidates = Vec::new();
+        let mut chars = extension.chars();
+        if let (Some(first), Some(last)) = (chars.next(), chars.next_back()) {
+            candidates.push(format!("{first}{last}w"));
+        }
+        candidates.push(format!("{extension}w"));
+        candidates.extend(WORLD_FILE_EXTENSIONS.iter().map(|ext| ext.to_string()));
+        candidates
+            .into_iter()
+            .map(|ext| image.with_extension(ext))
+            .find(|candidate| candidate.is_file())
+    }
+
+    /// 像素坐标（列、行）对应的世界坐标。
+    pub fn pixel_to_world(&self, column: f64, row: f64) -> (f64, f64) {
+        (
+            self.pixel_size_x * column + self.rotation_x * row + self.origin_x,
+            self.rotation_y * column + self.pixel_size_y * row + self.origin_y,
+        )
+    }
+}
+
+fn canonicalize_or_clone(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn normalize_separators(raw: &str) -> String {
+    if cfg!(windows) {
+        raw.to_string()
+    } else {
+        raw.replace('\\', "/")
+    }
+}
+
+/// `C:\…` 或 `\\server\…` 形式的 Windows 绝对路径，在其他平台上只能按文件名查找。
+fn is_windows_absolute(raw: &str) -> bool {
+    let bytes = raw.as_bytes();
+    raw.starts_with("\\\\")
+        || (bytes.len() >= 3
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && matches!(bytes[2], b'\\' | b'/'))
+}
+
+fn file_name(raw: &str) -> Option<&str> {
+    raw.rsplit(['/', '\\'])
+        .next()
+        .filter(|name| !name.is_empty())
+}
@@ -0,0 +1,112 @@
+//! zcad 原生 JSON 格式：以带版本号的外层信封包裹 `Document` 的序列化结果，
+//! 读写无需经过 DXF 组码转换，实体标识与全部文档数据原样保留。
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use zcad_core::document::Document;
+
+use crate::image::ImagePathResolver;
+use crate::{DocumentLoader, DocumentSaver, IoError};
+
+/// 信封中的格式标识。
+pub const JSON_FORMAT_NAME: &str = "zcad-document";
+/// 当前写出的格式版本；读取时拒绝更高的版本。
+pub const JSON_FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct EnvelopeRef<'a> {
+    format: &'a str,
+    version: u32,
+    document: &'a Document,
+}
+
+/// 仅读取信封头，先校验格式与版本再解析文档本体。
+#[derive(Deserialize)]
+struct EnvelopeHeader {
+    format: String,
+    version: u32,
+}
+
+#[derive(Deserialize)]
+struct Envelope {
+    document: Document,
+}
+
+/// 原生 JSON 格式的读写门面。
+#[derive(Debug, Clone, Default)]
+pub struct JsonFacade {
+    pretty: bool,
+}
+
+impl JsonFacade {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 保存时输出带缩进的 JSON，便于比对与人工查看。
+    pub fn with_pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+
+    /// 将文档序列化为带信封的 JSON 文本。
+    pub fn to_json(&self, document: &Document) -> Result<String, IoError> {
+        let envelope = EnvelopeRef {
+            format: JSON_FORMAT_NAME,
+            version: JSON_FORMAT_VERSION,
+            document,
+        };
+        let result = if self.pretty {
+            serde_json::to_string_pretty(&envelope)
+        } else {
+            serde_json::to_string(&envelope)
+        };
+        result.map_err(|err| IoError::InvalidDocument(format!("文档序列化失败: {err}")))
+    }
+
+    /// 从带信封的 JSON 文本解析文档。
+    pub fn from_json(&self, data: &str) -> Result<Document, IoError> {
+        let header: EnvelopeHeader = serde_json::from_str(data)
+            .map_err(|err| IoError::InvalidDocument(format!("JSON 信封无效: {err}")))?;
+        if header.format != JSON_FORMAT_NAME {
+            return Err(IoError::InvalidDocument(format!(
+                "未知的 JSON 文档格式 {:?}（期望 {JSON_FORMAT_NAME:?}）",
+                header.format
+            )));
+        }
+        if header.version > JSON_FORMAT_VERSION {
+            return Err(IoError::UnsupportedFeature(format!(
+                "JSON 文档格式版本 {}（当前支持至 {JSON_FORMAT_VERSION}）",
+                header.version
+            )));
+        }
+        let envelope: Envelope = serde_json::from_str(data)
+            .map_err(|err| IoError::InvalidDocument(format!("JSON 文档内容无效: {err}")))?;
+        Ok(envelope.document)
+    }
+}
+
+impl DocumentLoader for JsonFacade {
+    fn load(&self, path: &Path) -> Result<Document, IoError> {
+        let data = fs::read_to_string(path).map_err(|source| IoError::ReadError {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let mut document = self.from_json(&data)?;
+        // 解析出的图像路径依赖图纸所在目录，按新位置重新解析
+        ImagePathResolver::new(path.parent()).apply(&mut document);
+        Ok(document)
+    }
+}
+
+impl DocumentSaver for JsonFacade {
+    fn save(&self, document: &Document, path: &Path) -> Result<(), IoError> {
+        let data = self.to_json(document)?;
+        fs::write(path, data).map_err(|source| IoError::WriteError {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
@@ -1,21 +1,60 @@
+pub mod compare;
+pub mod extract;
+pub mod geojson;
+pub mod gltf;
+pub mod hpgl;
+pub mod image;
+pub mod json;
+pub mod mtext;
+pub mod pdf;
+mod plot;
+pub mod raster;
+pub mod svg;
+pub mod svg_import;
+pub mod tessellate;
+pub mod writer;
+pub mod xref;
+pub mod zcadz;
+
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use serde::Serialize;
 use thiserror::Error;
+use zcad_config::{GeometryConfig, ResourceConfig};
 use zcad_core::{
     document::{
-        Arc, Attribute, AttributeDefinition, BlockDefinition, BlockReference, Circle, ClipMode,
-        Dimension, DimensionKind, Document, Ellipse, Entity, Hatch, HatchEdge, HatchGradient,
-        HatchLoop, ImageDefReactor, ImageDictionary, ImageDictionaryEntry, Leader, LeaderLine,
-        Line, MLeader, MLeaderBlockContent, MLeaderContent, MText, Polyline, PolylineVertex,
+        AngleFormat, Arc, Attribute, AttributeDefinition, AuditSeverity, BlockDefinition,
+        BlockReference, Circle, ClipMode, Dimension, DimensionArc, DimensionKind, Document,
+        Ellipse, Entity, EntityId, EntityProperties, GeoCoordinateType, GeoData, Hatch, HatchEdge,
+        HatchGradient, HatchLoop, HatchPatternLine, HatchStyle, ImageDefReactor, ImageDictionary,
+        ImageDictionaryEntry, InsertUnits, LINETYPE_BYBLOCK, LINETYPE_BYLAYER, Layer, LayerState,
+        LayerStateEntry, Leader, LeaderLine, LengthFormat, Line, LineWeight, Linetype,
+        LinetypeElement, LinetypeEmbedded, LinetypeEmbeddedContent, MLeader, MLeaderBlockContent,
+        MLeaderContent, MText, ObjectColor, Polyline, PolylineVertex, REVCLOUD_XDATA_APP,
         RasterImage, RasterImageClip, RasterImageDefinition, RasterImageDisplayOptions,
-        RasterImageVariables, Spline, Text, ThreeDFace, Wipeout,
+        RasterImageVariables, RevisionCloud, RevisionCloudStyle, Shape, ShapeFile, Spline, Text,
+        ThreeDFace, USER_DATA_XDATA_APP, Underlay, UnderlayDefinition, UnderlayDisplayOptions,
+        UnderlayKind, UserValue, Wipeout, WipeoutVariables,
     },
-    geometry::{Point2, Point3, Vector2},
+    geometry::{Point2, Point3, Tolerance, Vector2, Vector3},
 };
 
+pub use crate::geojson::GeoJsonExporter;
+pub use crate::gltf::GltfExporter;
+pub use crate::hpgl::HpglExporter;
+use crate::image::ImagePathResolver;
+pub use crate::json::JsonFacade;
+pub use crate::pdf::PdfExporter;
+pub use crate::raster::PngExporter;
+pub use crate::svg::SvgExporter;
+pub use crate::svg_import::SvgFacade;
+pub use crate::tessellate::Tessellator;
+use crate::writer::DxfWriteOptions;
+pub use crate::zcadz::ZcadzFacade;
+
 #[derive(Debug, Error)]
 pub enum IoError {
     #[error("unsupported feature: {0}")]
@@ -44,21 +83,97 @@ pub trait DocumentSaver {
     fn save(&self, document: &Document, path: &Path) -> Result<(), IoError>;
 }
 
-pub struct DxfFacade;
+pub struct DxfFacade {
+    retain_dimension_blocks: bool,
+    image_search_roots: Vec<PathBuf>,
+    write_options: DxfWriteOptions,
+    tolerance: Tolerance,
+}
 
 impl DxfFacade {
     pub fn new() -> Self {
-        Self
+        Self {
+            retain_dimension_blocks: false,
+            image_search_roots: Vec::new(),
+            write_options: DxfWriteOptions::default(),
+            tolerance: Tolerance::DEFAULT,
+        }
+    }
+
+    /// 保留 DIMENSION 引用的匿名块（`*D…`），在标注渲染器完成前直接绘制其几何。
+    pub fn with_dimension_blocks(mut self, retain: bool) -> Self {
+        self.retain_dimension_blocks = retain;
+        self
+    }
+
+    /// 按资源配置追加 IMAGE 搜索目录；图纸所在目录总会先被搜索。
+    pub fn with_image_resources(mut self, resources: &ResourceConfig) -> Self {
+        self.image_search_roots = ImagePathResolver::from_resources(None, resources)
+            .search_roots()
+            .to_vec();
+        self
+    }
+
+    /// 按几何配置设置文档的基础容差；读取时再按头变量 `$EXTMIN`/`$EXTMAX` 的坐标量级放大长度容差。
+    pub fn with_geometry(mut self, geometry: &GeometryConfig) -> Self {
+        self.tolerance = Tolerance::new(geometry.linear_tolerance, geometry.angular_tolerance);
+        self
+    }
+
+    /// 设置保存时使用的写出选项（精度、版本、句柄、换行与代码页）。
+    pub fn with_write_options(mut self, options: DxfWriteOptions) -> Self {
+        self.write_options = options;
+        self
     }
 }
 
-impl DocumentLoader for DxfFacade {
-    fn load(&self, path: &Path) -> Result<Document, IoError> {
+impl DxfFacade {
+    /// 以诊断模式读取：跳过无法解析的实体并记录原因，同时保留模型空间实体的 DXF 句柄。
+    ///
+    /// 段结构或组码错误等无法恢复的问题仍作为错误返回。
+    pub fn load_with_diagnostics(
+        &self,
+        path: &Path,
+    ) -> Result<(Document, DxfDiagnostics), IoError> {
+        let mut diagnostics = DxfDiagnostics::default();
+        let document = self.load_inner(path, Some(&mut diagnostics))?;
+        Ok((document, diagnostics))
+    }
+
+    fn load_inner(
+        &self,
+        path: &Path,
+        diagnostics: Option<&mut DxfDiagnostics>,
+    ) -> Result<Document, IoError> {
         let data = fs::read_to_string(path).map_err(|source| IoError::ReadError {
             path: path.to_path_buf(),
             source,
         })?;
-        let parser = DxfParser::new(&data);
+        let mut document = self.parse(&data, diagnostics)?;
+        ImagePathResolver::new(path.parent())
+            .with_search_roots(self.image_search_roots.iter().cloned())
+            .apply(&mut document);
+        Ok(document)
+    }
+}
+
+impl DxfFacade {
+    /// 从内存中的 DXF 文本解析文档，不访问文件系统；图像路径保持原样，不做解析。
+    pub fn from_dxf(&self, data: &str) -> Result<Document, IoError> {
+        self.parse(data, None)
+    }
+
+    fn parse(
+        &self,
+        data: &str,
+        diagnostics: Option<&mut DxfDiagnostics>,
+    ) -> Result<Document, IoError> {
+        let parser = DxfParser::new(
+            data,
+            self.retain_dimension_blocks,
+            self.tolerance,
+            diagnostics,
+        );
         parser.parse().map_err(|err| match err {
             DxfError::Unsupported { feature } => IoError::UnsupportedFeature(feature),
             DxfError::Invalid { message } => IoError::InvalidDocument(message),
@@ -66,12 +181,243 @@ impl DocumentLoader for DxfFacade {
     }
 }
 
+impl DocumentLoader for DxfFacade {
+    fn load(&self, path: &Path) -> Result<Document, IoError> {
+        self.load_inner(path, None)
+    }
+}
+
+/// [`DxfFacade::load_with_diagnostics`] 收集的解析问题。
+#[derive(Debug, Clone, Default)]
+pub struct DxfDiagnostics {
+    pub findings: Vec<DxfDiagnostic>,
+    /// 模型空间实体对应的 DXF 句柄（组码 5）。
+    pub entity_handles: HashMap<EntityId, String>,
+}
+
+impl DxfDiagnostics {
+    #[inline]
+    pub fn entity_handle(&self, id: EntityId) -> Option<&str> {
+        self.entity_handles.get(&id).map(String::as_str)
+    }
+}
+
+/// 一条解析问题：暂不支持的实体为警告，数据无效而被跳过的实体为错误。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DxfDiagnostic {
+    pub severity: AuditSeverity,
+    /// 实体起始标记（组码 0）所在行号。
+    pub line: usize,
+    /// 实体类型，例如 `LINE`。
+    pub entity: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
+    /// 实体所在的块定义；模型空间为 `None`。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block: Option<String>,
+    pub message: String,
+}
+
 impl DocumentSaver for DxfFacade {
-    fn save(&self, _document: &Document, path: &Path) -> Result<(), IoError> {
-        Err(IoError::UnsupportedFeature(format!(
-            "DXF writer for {:?} 尚未实现",
-            path
-        )))
+    fn save(&self, document: &Document, path: &Path) -> Result<(), IoError> {
+        let data = writer::write_document_with_options(document, &self.write_options);
+        fs::write(path, data).map_err(|source| IoError::WriteError {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+/// 可由 [`open_document`] 识别的图纸格式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentFormat {
+    Dxf,
+    Json,
+    Zcadz,
+    /// 二进制 DWG，仅用于识别，尚不支持读取。
+    Dwg,
+}
+
+impl DocumentFormat {
+    /// 按文件内容识别格式：`.zcadz` 魔数优先，其次是以版本号 `AC10xx` 开头的 DWG、
+    /// 以 `{` 开头的原生 JSON，其余按 DXF 处理。
+    pub fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(zcadz::ZCADZ_MAGIC) {
+            return DocumentFormat::Zcadz;
+        }
+        if bytes.len() >= 6
+            && bytes.starts_with(b"AC1")
+            && bytes[3..6].iter().all(u8::is_ascii_digit)
+        {
+            return DocumentFormat::Dwg;
+        }
+        let text = bytes
+            .strip_prefix(b"\xEF\xBB\xBF".as_slice())
+            .unwrap_or(bytes);
+        match text.iter().find(|byte| !byte.is_ascii_whitespace()) {
+            Some(b'{') => DocumentFormat::Json,
+            _ => DocumentFormat::Dxf,
+        }
+    }
+}
+
+/// 从文件内容读取 AutoCAD 版本号（如 `AC1027`）：DXF 取 HEADER 段的 `$ACADVER`，
+/// DWG 取文件头；原生格式与缺少该变量的 DXF 返回 `None`。
+pub fn detect_acadver(bytes: &[u8]) -> Option<String> {
+    match DocumentFormat::detect(bytes) {
+        DocumentFormat::Dwg => Some(String::from_utf8_lossy(&bytes[..6]).into_owned()),
+        DocumentFormat::Dxf => {
+            let text = String::from_utf8_lossy(bytes);
+            let mut lines = text.lines().map(str::trim);
+            while let Some(line) = lines.next() {
+                match line {
+                    "$ACADVER" => {
+                        // 变量名之后是组码 1 及其取值
+                        return (lines.next() == Some("1"))
+                            .then(|| lines.next())
+                            .flatten()
+                            .filter(|value| !value.is_empty())
+                            .map(str::to_string);
+                    }
+                    "ENDSEC" => return None,
+                    _ => {}
+                }
+            }
+            None
+        }
+        DocumentFormat::Json | DocumentFormat::Zcadz => None,
+    }
+}
+
+/// 按文件内容识别格式并加载图纸，不依赖扩展名。
+pub fn open_document(path: &Path) -> Result<Document, IoError> {
+    let bytes = fs::read(path).map_err(|source| IoError::ReadError {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    match DocumentFormat::detect(&bytes) {
+        DocumentFormat::Dxf => DxfFacade::new().load(path),
+        DocumentFormat::Json => JsonFacade::new().load(path),
+        DocumentFormat::Zcadz => ZcadzFacade::new().load(path),
+        DocumentFormat::Dwg => Err(IoError::UnsupportedFeature(format!(
+            "DWG {}（请先另存为 DXF）",
+            String::from_utf8_lossy(&bytes[..6])
+        ))),
+    }
+}
+
+/// 可由 [`save_document`] 写出的格式，均使用各导出器的默认选项。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveFormat {
+    Dxf,
+    Json,
+    Zcadz,
+    Svg,
+    Pdf,
+    Png,
+    GeoJson,
+    Gltf,
+    /// 二进制 glTF（`.glb`）。
+    Glb,
+    Hpgl,
+}
+
+impl SaveFormat {
+    pub const ALL: [SaveFormat; 10] = [
+        SaveFormat::Dxf,
+        SaveFormat::Json,
+        SaveFormat::Zcadz,
+        SaveFormat::Svg,
+        SaveFormat::Pdf,
+        SaveFormat::Png,
+        SaveFormat::GeoJson,
+        SaveFormat::Gltf,
+        SaveFormat::Glb,
+        SaveFormat::Hpgl,
+    ];
+
+    /// 按扩展名（不区分大小写，不含点）识别格式；`hpgl` 与 `plt` 均视为 HPGL。
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        let extension = extension.to_ascii_lowercase();
+        match extension.as_str() {
+            "hpgl" => Some(SaveFormat::Hpgl),
+            _ => Self::ALL
+                .into_iter()
+                .find(|format| format.extension() == extension),
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(Self::from_extension)
+    }
+
+    /// 默认扩展名，不含点。
+    pub fn extension(self) -> &'static str {
+        match self {
+            SaveFormat::Dxf => "dxf",
+            SaveFormat::Json => "json",
+            SaveFormat::Zcadz => "zcadz",
+            SaveFormat::Svg => "svg",
+            SaveFormat::Pdf => "pdf",
+            SaveFormat::Png => "png",
+            SaveFormat::GeoJson => "geojson",
+            SaveFormat::Gltf => "gltf",
+            SaveFormat::Glb => "glb",
+            SaveFormat::Hpgl => "plt",
+        }
+    }
+
+    pub fn save(self, document: &Document, path: &Path) -> Result<(), IoError> {
+        match self {
+            SaveFormat::Dxf => DxfFacade::new().save(document, path),
+            SaveFormat::Json => JsonFacade::new().save(document, path),
+            SaveFormat::Zcadz => ZcadzFacade::new().save(document, path),
+            SaveFormat::Svg => SvgExporter::new().save(document, path),
+            SaveFormat::Pdf => PdfExporter::new().save(document, path),
+            SaveFormat::Png => PngExporter::new().save(document, path),
+            SaveFormat::GeoJson => GeoJsonExporter::new().save(document, path),
+            SaveFormat::Gltf => GltfExporter::new().save(document, path),
+            SaveFormat::Glb => GltfExporter::new()
+                .with_options(gltf::GltfExportOptions {
+                    binary: true,
+                    ..gltf::GltfExportOptions::default()
+                })
+                .save(document, path),
+            SaveFormat::Hpgl => HpglExporter::new().save(document, path),
+        }
+    }
+}
+
+/// 按扩展名选择格式保存图纸，与 [`open_document`] 对应。
+pub fn save_document(document: &Document, path: &Path) -> Result<(), IoError> {
+    let format = SaveFormat::from_path(path).ok_or_else(|| {
+        IoError::UnsupportedFeature(format!("无法从扩展名识别保存格式: {}", path.display()))
+    })?;
+    format.save(document, path)
+}
+
+/// 按内容识别格式并从内存加载图纸，不访问文件系统，适用于上传内容与浏览器环境。
+///
+/// 与 [`open_document`] 不同，图像等外部引用的路径不做解析。
+pub fn open_document_bytes(bytes: &[u8]) -> Result<Document, IoError> {
+    let format = DocumentFormat::detect(bytes);
+    let text = || {
+        let text = bytes
+            .strip_prefix(b"\xEF\xBB\xBF".as_slice())
+            .unwrap_or(bytes);
+        std::str::from_utf8(text)
+            .map_err(|err| IoError::InvalidDocument(format!("图纸文本不是有效的 UTF-8: {err}")))
+    };
+    match format {
+        DocumentFormat::Dxf => DxfFacade::new().from_dxf(text()?),
+        DocumentFormat::Json => JsonFacade::new().from_json(text()?),
+        DocumentFormat::Zcadz => ZcadzFacade::new().from_bytes(bytes),
+        DocumentFormat::Dwg => Err(IoError::UnsupportedFeature(format!(
+            "DWG {}（请先另存为 DXF）",
+            String::from_utf8_lossy(&bytes[..6])
+        ))),
     }
 }
 
@@ -97,6 +443,13 @@ impl DxfError {
 
 struct DxfParser<'a> {
     reader: DxfReader<'a>,
+    retain_dimension_blocks: bool,
+    /// 文档的基础几何容差。
+    tolerance: Tolerance,
+    /// 诊断模式：跳过出错的实体而不是中止解析。
+    diagnostics: Option<&'a mut DxfDiagnostics>,
+    /// 模型空间实体的句柄到实体编号，用于解析填充边界与 SORTENTSTABLE 中的句柄引用。
+    entity_ids: HashMap<String, EntityId>,
 }
 
 #[derive(Debug)]
@@ -112,6 +465,36 @@ struct DictionaryEntry {
     handle: String,
 }
 
+/// XRECORD 的句柄与数据组码。
+type ParsedXRecord = (Option<String>, Vec<(i32, String)>);
+
+/// 块定义及其块句柄、块记录句柄。
+struct ParsedBlock {
+    definition: BlockDefinition,
+    block_handle: Option<String>,
+    record_handle: Option<String>,
+    /// 外部参照块（组码 70 含 4/8 标志）记录的路径（组码 1）。
+    xref_path: Option<String>,
+}
+
+/// 空图纸头变量 `$EXTMIN`/`$EXTMAX` 使用的占位坐标量级。
+const UNSET_EXTENT: f64 = 1e20;
+
+/// LAYER 组码 70：冻结与锁定标志。
+const LAYER_FLAG_FROZEN: i16 = 1;
+const LAYER_FLAG_LOCKED: i16 = 4;
+
+/// BLOCK 组码 70：外部参照与覆盖型外部参照标志。
+const BLOCK_FLAG_XREF: i16 = 4;
+const BLOCK_FLAG_XREF_OVERLAY: i16 = 8;
+
+/// 标注匿名块名称形如 `*D12`。
+fn is_dimension_block_name(name: &str) -> bool {
+    name.strip_prefix("*D")
+        .or_else(|| name.strip_prefix("*d"))
+        .is_some_and(|rest| !rest.is_empty() && rest.chars().all(|ch| ch.is_ascii_digit()))
+}
+
 enum PolyfaceRecord {
     Coordinate(Point3),
     Face { indices: [i32; 4] },
@@ -119,14 +502,60 @@ enum PolyfaceRecord {
 }
 
 impl<'a> DxfParser<'a> {
-    fn new(source: &'a str) -> Self {
+    fn new(
+        source: &'a str,
+        retain_dimension_blocks: bool,
+        tolerance: Tolerance,
+        diagnostics: Option<&'a mut DxfDiagnostics>,
+    ) -> Self {
         Self {
             reader: DxfReader::new(source),
+            retain_dimension_blocks,
+            tolerance,
+            diagnostics,
+            entity_ids: HashMap::new(),
+        }
+    }
+
+    /// 解析一个实体；诊断模式下出错时跳过剩余组码并记录问题，返回 `Ok(None)`。
+    fn parse_entity_recovering(
+        &mut self,
+        kind: &str,
+        block: Option<&str>,
+    ) -> Result<Option<Entity>, DxfError> {
+        // 实体标记已读取，当前行号为其值行
+        let line = self.reader.line_number.saturating_sub(1);
+        self.reader.begin_entity();
+        let err = match self.parse_entity(kind) {
+            Ok(entity) => return Ok(Some(entity)),
+            Err(err) => err,
+        };
+        let (severity, message) = match err {
+            DxfError::Unsupported { feature } if self.diagnostics.is_some() || block.is_some() => {
+                (AuditSeverity::Warning, feature)
+            }
+            DxfError::Invalid { message } if self.diagnostics.is_some() => {
+                (AuditSeverity::Error, message)
+            }
+            err => return Err(err),
+        };
+        self.skip_entity_body()?;
+        if let Some(diagnostics) = self.diagnostics.as_deref_mut() {
+            diagnostics.findings.push(DxfDiagnostic {
+                severity,
+                line,
+                entity: kind.to_string(),
+                handle: self.reader.entity_handle.clone(),
+                block: block.map(str::to_string),
+                message,
+            });
         }
+        Ok(None)
     }
 
     fn parse(mut self) -> Result<Document, DxfError> {
         let mut document = Document::new();
+        document.set_tolerance(self.tolerance);
         while let Some((code, value)) = self.reader.next_pair()? {
             if code != 0 {
                 return Err(DxfError::invalid(format!(
@@ -145,7 +574,9 @@ impl<'a> DxfParser<'a> {
                         )));
                     }
                     match name.as_str() {
+                        "HEADER" => self.parse_header(&mut document)?,
                         "ENTITIES" => self.parse_entities(&mut document)?,
+                        "TABLES" => self.parse_tables(&mut document)?,
                         "BLOCKS" => self.parse_blocks(&mut document)?,
                         "OBJECTS" => self.parse_objects(&mut document)?,
                         _ => self.skip_section()?,
@@ -159,9 +590,51 @@ impl<'a> DxfParser<'a> {
                 }
             }
         }
+        document.bind_unstyled_shapes();
         Ok(document)
     }
 
+    /// 读取 HEADER 段中已支持的头变量（`$MIRRTEXT`、单位相关变量，以及按 `$EXTMIN`/`$EXTMAX` 放大长度容差），其余跳过。
+    fn parse_header(&mut self, document: &mut Document) -> Result<(), DxfError> {
+        let mut variable = String::new();
+        loop {
+            match self.reader.next_pair()? {
+                Some((0, value)) if value == "ENDSEC" => break,
+                Some((9, value)) => variable = value.trim().to_string(),
+                Some((70, value)) if variable == "$MIRRTEXT" => {
+                    document.set_mirror_text(parse_i16(&value, "$MIRRTEXT（组码 70）")? != 0);
+                }
+                Some((70, value))
+                    if matches!(
+                        variable.as_str(),
+                        "$INSUNITS" | "$LUNITS" | "$LUPREC" | "$AUNITS" | "$AUPREC"
+                    ) =>
+                {
+                    let code = parse_i16(&value, &format!("{variable}（组码 70）"))?;
+                    let mut units = document.units();
+                    match variable.as_str() {
+                        "$INSUNITS" => units.insertion = InsertUnits::from_code(code),
+                        "$LUNITS" => units.length_format = LengthFormat::from_code(code),
+                        "$AUNITS" => units.angle_format = AngleFormat::from_code(code),
+                        "$LUPREC" => units.length_precision = code.clamp(0, 8) as u8,
+                        _ => units.angle_precision = code.clamp(0, 8) as u8,
+                    }
+                    document.set_units(units);
+                }
+                Some((10 | 20, value)) if variable == "$EXTMIN" || variable == "$EXTMAX" => {
+                    let coordinate = parse_f64(&value, &format!("{variable} 坐标"))?;
+                    // 空图纸的范围记为 ±1e20，不代表实际坐标
+                    if coordinate.abs() < UNSET_EXTENT {
+                        document.set_tolerance(document.tolerance().for_magnitude(coordinate));
+                    }
+                }
+                Some(_) => continue,
+                None => return Err(DxfError::invalid("HEADER 段未找到 ENDSEC 终止标记")),
+            }
+        }
+        Ok(())
+    }
+
     fn skip_section(&mut self) -> Result<(), DxfError> {
         loop {
             match self.reader.next_pair()? {
@@ -196,14 +669,272 @@ impl<'a> DxfParser<'a> {
                     self.parse_polyline_entity(document)?;
                 }
                 entity => {
-                    let parsed = self.parse_entity(entity)?;
-                    document.add_entity(parsed);
+                    if let Some(parsed) = self.parse_entity_recovering(entity, None)? {
+                        let id = document.add_entity(parsed);
+                        if let Some(properties) =
+                            parse_entity_properties(&self.reader.entity_properties)?
+                        {
+                            document.set_entity_properties(id, properties);
+                        }
+                        for (key, value) in parse_user_data(&self.reader.user_data)? {
+                            document
+                                .set_user_value(id, key, value)
+                                .map_err(|err| DxfError::invalid(format!("ZCAD_USERDATA {err}")))?;
+                        }
+                        if let Some(handle) = self.reader.entity_handle.take() {
+                            if let Some(diagnostics) = self.diagnostics.as_deref_mut() {
+                                diagnostics.entity_handles.insert(id, handle.clone());
+                            }
+                            self.entity_ids.insert(handle, id);
+                        }
+                    }
                 }
             }
         }
+        // 段结束后解析填充边界引用（组码 330）
+        document.resolve_hatch_boundaries(&self.entity_ids);
         Ok(())
     }
 
+    fn parse_tables(&mut self, document: &mut Document) -> Result<(), DxfError> {
+        loop {
+            let (code, value) = match self.reader.next_pair()? {
+                Some(pair) => pair,
+                None => return Err(DxfError::invalid("TABLES 段提前结束")),
+            };
+            if code != 0 {
+                return Err(DxfError::invalid(format!(
+                    "TABLES 段遇到组码 {code}（期望 0 表示表或记录起始）"
+                )));
+            }
+
+            match value.as_str() {
+                "ENDSEC" => break,
+                "LAYER" => {
+                    let layer = self.parse_layer_record()?;
+                    document.add_layer(layer);
+                }
+                "STYLE" => {
+                    if let Some(shape_file) = self.parse_style_record()? {
+                        document.add_shape_file(shape_file);
+                    }
+                }
+                "LTYPE" => {
+                    let linetype = self.parse_linetype_record()?;
+                    document.add_linetype(linetype);
+                }
+                _ => {
+                    // TABLE/ENDTAB 头部以及其它符号表记录暂不解析
+                    self.skip_entity_body()?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_layer_record(&mut self) -> Result<Layer, DxfError> {
+        let mut name: Option<String> = None;
+        let mut flags: i16 = 0;
+        let mut color: Option<i16> = None;
+        let mut true_color: Option<u32> = None;
+        let mut linetype: Option<String> = None;
+        let mut lineweight: Option<i16> = None;
+
+        loop {
+            match self.reader.next_pair()? {
+                Some((0, value)) => {
+                    self.reader.put_back((0, value));
+                    break;
+                }
+                Some((code, value)) => match code {
+                    2 => name = Some(value.trim().to_string()),
+                    70 => flags = parse_i16(&value, "LAYER 标志（组码 70）")?,
+                    62 => color = Some(parse_i16(&value, "LAYER 颜色（组码 62）")?),
+                    420 => {
+                        true_color =
+                            Some(parse_u32(&value, "LAYER 真彩色（组码 420）")? & 0xFF_FFFF);
+                    }
+                    6 => linetype = Some(value.trim().to_string()),
+                    370 => lineweight = Some(parse_i16(&value, "LAYER 线宽（组码 370）")?),
+                    _ => {}
+                },
+                None => return Err(DxfError::invalid("LAYER 记录未正确结束")),
+            }
+        }
+
+        let name = name.ok_or_else(|| DxfError::invalid("LAYER 缺少名称（组码 2）"))?;
+        let mut layer = Layer::new(name);
+        // 颜色为负表示图层关闭，标志位 1 表示冻结、4 表示锁定
+        layer.is_visible = color.is_none_or(|value| value >= 0);
+        layer.is_frozen = flags & LAYER_FLAG_FROZEN != 0;
+        layer.is_locked = flags & LAYER_FLAG_LOCKED != 0;
+        if let Some(rgb) = true_color {
+            layer.color = ObjectColor::TrueColor(rgb);
+        } else if let Some(ObjectColor::Index(index)) = color.map(ObjectColor::from_aci) {
+            layer.color = ObjectColor::Index(index);
+        }
+        if let Some(linetype) = linetype.filter(|value| !value.is_empty()) {
+            layer.linetype = linetype;
+        }
+        if let Some(code) = lineweight {
+            layer.lineweight = match LineWeight::from_code(code) {
+                LineWeight::Hundredths(value) => LineWeight::Hundredths(value),
+                _ => LineWeight::Default,
+            };
+        }
+        Ok(layer)
+    }
+
+    fn parse_linetype_record(&mut self) -> Result<Linetype, DxfError> {
+        #[derive(Default)]
+        struct ElementBuilder {
+            length: f64,
+            flags: i16,
+            shape_number: Option<i16>,
+            style_handle: Option<String>,
+            scale: Option<f64>,
+            rotation: f64,
+            offset_x: f64,
+            offset_y: f64,
+            text: Option<String>,
+        }
+
+        impl ElementBuilder {
+            fn finish(self) -> Result<LinetypeElement, DxfError> {
+                let content = if self.flags & 2 != 0 {
+                    let text = self
+                        .text
+                        .ok_or_else(|| DxfError::invalid("LTYPE 文字元素缺少文本（组码 9）"))?;
+                    Some(LinetypeEmbeddedContent::Text(text))
+                } else if self.flags & 4 != 0 {
+                    let number = self
+                        .shape_number
+                        .ok_or_else(|| DxfError::invalid("LTYPE 型元素缺少型编号（组码 75）"))?;
+                    Some(LinetypeEmbeddedContent::Shape(number))
+                } else {
+                    None
+                };
+                Ok(LinetypeElement {
+                    length: self.length,
+                    embedded: content.map(|content| LinetypeEmbedded {
+                        content,
+                        style_handle: self.style_handle,
+                        scale: self.scale.unwrap_or(1.0),
+                        rotation: self.rotation,
+                        is_absolute_rotation: self.flags & 1 != 0,
+                        offset: Vector2::new(self.offset_x, self.offset_y),
+                    }),
+                })
+            }
+        }
+
+        let mut name: Option<String> = None;
+        let mut description = String::new();
+        let mut pattern_length = 0.0;
+        let mut expected_elements: Option<usize> = None;
+        let mut elements: Vec<LinetypeElement> = Vec::new();
+        let mut current: Option<ElementBuilder> = None;
+
+        loop {
+            let (code, value) = match self.reader.next_pair()? {
+                Some((0, value)) => {
+                    self.reader.put_back((0, value));
+                    break;
+                }
+                Some(pair) => pair,
+                None => return Err(DxfError::invalid("LTYPE 记录未正确结束")),
+            };
+            match code {
+                2 => name = Some(value.trim().to_string()),
+                3 => description = value.trim().to_string(),
+                40 => pattern_length = parse_f64(&value, "LTYPE 图案长度（组码 40）")?,
+                73 => {
+                    let count = parse_i16(&value, "LTYPE 元素数量（组码 73）")?;
+                    expected_elements = Some(count.max(0) as usize);
+                }
+                49 => {
+                    if let Some(element) = current.take() {
+                        elements.push(element.finish()?);
+                    }
+                    current = Some(ElementBuilder {
+                        length: parse_f64(&value, "LTYPE 元素长度（组码 49）")?,
+                        ..ElementBuilder::default()
+                    });
+                }
+                74 | 75 | 340 | 46 | 50 | 44 | 45 | 9 => {
+                    let element = current.as_mut().ok_or_else(|| {
+                        DxfError::invalid(format!(
+                            "LTYPE 元素属性（组码 {code}）出现在组码 49 之前"
+                        ))
+                    })?;
+                    match code {
+                        74 => element.flags = parse_i16(&value, "LTYPE 复杂元素类型（组码 74）")?,
+                        75 => {
+                            element.shape_number =
+                                Some(parse_i16(&value, "LTYPE 型编号（组码 75）")?);
+                        }
+                        340 => element.style_handle = Some(value.trim().to_string()),
+                        46 => element.scale = Some(parse_f64(&value, "LTYPE 元素比例（组码 46）")?),
+                        50 => element.rotation = parse_f64(&value, "LTYPE 元素旋转（组码 50）")?,
+                        44 => element.offset_x = parse_f64(&value, "LTYPE 元素 X 偏移（组码 44）")?,
+                        45 => element.offset_y = parse_f64(&value, "LTYPE 元素 Y 偏移（组码 45）")?,
+                        _ => element.text = Some(value),
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Some(element) = current.take() {
+            elements.push(element.finish()?);
+        }
+
+        let name = name.ok_or_else(|| DxfError::invalid("LTYPE 缺少名称（组码 2）"))?;
+        if let Some(expected) = expected_elements
+            && expected != elements.len()
+        {
+            return Err(DxfError::invalid(format!(
+                "LTYPE {name} 声明了 {expected} 个元素（组码 73），实际读取 {} 个",
+                elements.len()
+            )));
+        }
+        Ok(Linetype {
+            name,
+            description,
+            pattern_length,
+            elements,
+        })
+    }
+
+    /// 仅保留型文件样式（组码 70 位 1），文字样式暂不解析。
+    fn parse_style_record(&mut self) -> Result<Option<ShapeFile>, DxfError> {
+        let mut handle: Option<String> = None;
+        let mut flags: i16 = 0;
+        let mut file: Option<String> = None;
+
+        loop {
+            match self.reader.next_pair()? {
+                Some((0, value)) => {
+                    self.reader.put_back((0, value));
+                    break;
+                }
+                Some((code, value)) => match code {
+                    5 => handle = Some(value.trim().to_string()),
+                    70 => flags = parse_i16(&value, "STYLE 标志（组码 70）")?,
+                    3 => file = Some(value.trim().to_string()),
+                    _ => {}
+                },
+                None => return Err(DxfError::invalid("STYLE 记录未正确结束")),
+            }
+        }
+
+        if flags & 1 == 0 {
+            return Ok(None);
+        }
+        let handle = handle.ok_or_else(|| DxfError::invalid("型文件 STYLE 缺少句柄（组码 5）"))?;
+        let file = file.ok_or_else(|| DxfError::invalid("型文件 STYLE 缺少文件名（组码 3）"))?;
+        Ok(Some(ShapeFile { handle, file }))
+    }
+
     fn parse_blocks(&mut self, document: &mut Document) -> Result<(), DxfError> {
         loop {
             let (code, value) = match self.reader.next_pair()? {
@@ -219,14 +950,16 @@ impl<'a> DxfParser<'a> {
             match value.as_str() {
                 "ENDSEC" => break,
                 "BLOCK" => {
-                    if let Some((definition, block_handle, record_handle)) =
-                        self.parse_block_definition()?
-                    {
-                        document.add_block_definition_with_handle(
-                            definition,
-                            block_handle,
-                            record_handle,
-                        );
+                    if let Some(block) = self.parse_block_definition()? {
+                        if let Some(path) = block.xref_path {
+                            document.register_xref(block.definition.name, path);
+                        } else {
+                            document.add_block_definition_with_handle(
+                                block.definition,
+                                block.block_handle,
+                                block.record_handle,
+                            );
+                        }
                     }
                 }
                 _ => {
@@ -242,7 +975,10 @@ impl<'a> DxfParser<'a> {
         let mut dictionaries: HashMap<String, ParsedDictionary> = HashMap::new();
         let mut root_entries: HashMap<String, String> = HashMap::new();
         let mut raster_variables_by_handle: HashMap<String, RasterImageVariables> = HashMap::new();
+        let mut wipeout_variables_by_handle: HashMap<String, WipeoutVariables> = HashMap::new();
         let mut reactor_by_owner: HashMap<String, String> = HashMap::new();
+        let mut xrecords: HashMap<String, Vec<(i32, String)>> = HashMap::new();
+        let mut sort_handles: HashMap<String, u64> = HashMap::new();
 
         loop {
             let (code, value) = match self.reader.next_pair()? {
@@ -272,6 +1008,28 @@ impl<'a> DxfParser<'a> {
                     let (handle, vars) = self.parse_raster_variables()?;
                     raster_variables_by_handle.insert(handle, vars);
                 }
+                "WIPEOUTVARIABLES" => {
+                    let (handle, vars) = self.parse_wipeout_variables()?;
+                    wipeout_variables_by_handle.insert(handle, vars);
+                }
+                "GEODATA" => {
+                    let geodata = self.parse_geodata()?;
+                    document.set_geodata(geodata);
+                }
+                name if UnderlayKind::from_definition_name(name).is_some() => {
+                    let kind = UnderlayKind::from_definition_name(name).expect("已匹配底图定义");
+                    let definition = self.parse_underlay_definition(kind)?;
+                    document.add_underlay_definition(definition);
+                }
+                "XRECORD" => {
+                    let (handle, pairs) = self.parse_xrecord()?;
+                    if let Some(handle) = handle {
+                        xrecords.insert(handle, pairs);
+                    }
+                }
+                "SORTENTSTABLE" => {
+                    sort_handles.extend(self.parse_sortents_table()?);
+                }
                 "DICTIONARY" => {
                     let dict = self.parse_dictionary()?;
                     if dict.owner.as_deref() == Some("0") {
@@ -308,18 +1066,80 @@ impl<'a> DxfParser<'a> {
             }
         }
 
+        // 底图定义字典的条目名即界面中显示的底图名称
+        for kind in UnderlayKind::ALL {
+            let Some(dict) = root_entries
+                .get(kind.dictionary_key())
+                .and_then(|handle| dictionaries.get(handle))
+            else {
+                continue;
+            };
+            for entry in &dict.entries {
+                if let Some(definition) = document.underlay_definition_mut(&entry.handle)
+                    && definition.name.is_none()
+                {
+                    definition.name = Some(entry.name.clone());
+                }
+            }
+        }
+
         if let Some(vars_handle) = root_entries.get("ACAD_IMAGE_VARS") {
             if let Some(vars) = raster_variables_by_handle.get(vars_handle) {
                 document.set_raster_image_variables(vars.clone());
             }
         }
 
+        if let Some(vars_handle) = root_entries.get("ACAD_WIPEOUT_VARS")
+            && let Some(vars) = wipeout_variables_by_handle.get(vars_handle)
+        {
+            document.set_wipeout_variables(vars.clone());
+        }
+
+        // 图层状态字典通常位于 LAYER 表的扩展字典中，也可能登记在根字典
+        let layer_states = dictionaries
+            .values()
+            .flat_map(|dict| &dict.entries)
+            .find(|entry| entry.name.eq_ignore_ascii_case(writer::LAYER_STATES_KEY))
+            .and_then(|entry| dictionaries.get(&entry.handle));
+        if let Some(dict) = layer_states {
+            for entry in &dict.entries {
+                if let Some(pairs) = xrecords.get(&entry.handle) {
+                    document.add_layer_state(layer_state_from_xrecord(&entry.name, pairs)?);
+                }
+            }
+        }
+
+        self.apply_sort_handles(document, &sort_handles);
         Ok(())
     }
 
-    fn parse_block_definition(
-        &mut self,
-    ) -> Result<Option<(BlockDefinition, Option<String>, Option<String>)>, DxfError> {
+    /// 按 SORTENTSTABLE 排列模型空间实体：表中的实体以排序句柄代替自身句柄参与排序。
+    /// 不引用模型空间实体的表（如图纸空间的）不产生影响。
+    fn apply_sort_handles(&self, document: &mut Document, sort_handles: &HashMap<String, u64>) {
+        if !self
+            .entity_ids
+            .keys()
+            .any(|handle| sort_handles.contains_key(handle))
+        {
+            return;
+        }
+        let mut keyed: Vec<(u64, EntityId)> = self
+            .entity_ids
+            .iter()
+            .filter_map(|(handle, id)| {
+                let key = match sort_handles.get(handle) {
+                    Some(key) => *key,
+                    None => u64::from_str_radix(handle, 16).ok()?,
+                };
+                Some((key, *id))
+            })
+            .collect();
+        keyed.sort_by_key(|(key, id)| (*key, id.get()));
+        let ordered: Vec<EntityId> = keyed.into_iter().map(|(_, id)| id).collect();
+        document.set_draw_sequence(&ordered);
+    }
+
+    fn parse_block_definition(&mut self) -> Result<Option<ParsedBlock>, DxfError> {
         let mut name: Option<String> = None;
         let mut base_x: f64 = 0.0;
         let mut base_y: f64 = 0.0;
@@ -328,6 +1148,8 @@ impl<'a> DxfParser<'a> {
         let mut attribute_defs: Vec<AttributeDefinition> = Vec::new();
         let mut block_handle: Option<String> = None;
         let mut record_handle: Option<String> = None;
+        let mut flags: i16 = 0;
+        let mut xref_path: Option<String> = None;
 
         loop {
             match self.reader.next_pair()? {
@@ -343,13 +1165,15 @@ impl<'a> DxfParser<'a> {
                                     let attr_def = self.parse_attdef()?;
                                     attribute_defs.push(attr_def);
                                 }
-                                _ => match self.parse_entity(entity_kind) {
-                                    Ok(entity) => entities.push(entity),
-                                    Err(DxfError::Unsupported { .. }) => {
-                                        self.skip_entity_body()?;
+                                _ => {
+                                    // 块内暂不支持的实体总是跳过
+                                    let block = name.clone().unwrap_or_default();
+                                    if let Some(entity) =
+                                        self.parse_entity_recovering(entity_kind, Some(&block))?
+                                    {
+                                        entities.push(entity);
                                     }
-                                    Err(err) => return Err(err),
-                                },
+                                }
                             }
                         } else {
                             self.skip_entity_body()?;
@@ -359,12 +1183,20 @@ impl<'a> DxfParser<'a> {
                 Some((code, value)) => match code {
                     2 => {
                         let trimmed = value.trim().to_string();
-                        collect_entities = !trimmed.starts_with('*');
+                        collect_entities = !trimmed.starts_with('*')
+                            || (self.retain_dimension_blocks && is_dimension_block_name(&trimmed));
                         name = Some(trimmed);
                     }
                     10 => base_x = parse_f64(&value, "BLOCK 基点 X")?,
                     20 => base_y = parse_f64(&value, "BLOCK 基点 Y")?,
-                    30 | 70 | 71 | 62 | 3 | 1 | 4 | 8 | 100 | 102 => {
+                    70 => flags = parse_i16(&value, "BLOCK 标志（组码 70）")?,
+                    1 => {
+                        let trimmed = value.trim();
+                        if !trimmed.is_empty() {
+                            xref_path = Some(trimmed.to_string());
+                        }
+                    }
+                    30 | 71 | 62 | 3 | 4 | 8 | 100 | 102 => {
                         // 暂时忽略的字段
                     }
                     330 => {
@@ -396,8 +1228,9 @@ impl<'a> DxfParser<'a> {
             return Ok(None);
         }
 
-        Ok(Some((
-            BlockDefinition {
+        let is_xref = flags & (BLOCK_FLAG_XREF | BLOCK_FLAG_XREF_OVERLAY) != 0;
+        Ok(Some(ParsedBlock {
+            definition: BlockDefinition {
                 name,
                 base_point: Point2::new(base_x, base_y),
                 entities,
@@ -405,7 +1238,8 @@ impl<'a> DxfParser<'a> {
             },
             block_handle,
             record_handle,
-        )))
+            xref_path: if is_xref { xref_path } else { None },
+        }))
     }
 
     fn parse_entity(&mut self, kind: &str) -> Result<Entity, DxfError> {
@@ -419,14 +1253,18 @@ impl<'a> DxfParser<'a> {
             "MTEXT" => self.parse_mtext(),
             "INSERT" => self.parse_insert(),
             "HATCH" => self.parse_hatch(),
-            "DIMENSION" => self.parse_dimension(),
+            "DIMENSION" | "ARC_DIMENSION" | "LARGE_RADIAL_DIMENSION" => self.parse_dimension(),
             "SPLINE" => self.parse_spline(),
             "LEADER" => self.parse_leader(),
             "MULTILEADER" => self.parse_mleader(),
             "IMAGE" => self.parse_image(),
             "WIPEOUT" => self.parse_wipeout(),
             "3DFACE" => self.parse_3dface(),
-            other => Err(DxfError::unsupported(format!("暂不支持的实体类型 {other}"))),
+            "SHAPE" => self.parse_shape(),
+            other => match UnderlayKind::from_entity_name(other) {
+                Some(underlay_kind) => self.parse_underlay(underlay_kind),
+                None => Err(DxfError::unsupported(format!("暂不支持的实体类型 {other}"))),
+            },
         }
     }
 
@@ -724,6 +1562,9 @@ impl<'a> DxfParser<'a> {
         let mut pending_x: Option<f64> = None;
         let mut pending_y: Option<f64> = None;
         let mut last_vertex_index: Option<usize> = None;
+        // 修订云线以 RevcloudProps 扩展数据标记
+        let mut in_revcloud_xdata = false;
+        let mut revcloud: Option<(Option<i16>, Option<f64>)> = None;
         loop {
             match self.reader.next_pair()? {
                 Some((0, value)) => {
@@ -732,6 +1573,22 @@ impl<'a> DxfParser<'a> {
                 }
                 Some((code, value)) => match code {
                     8 => layer = Some(value.trim().to_string()),
+                    1001 => {
+                        in_revcloud_xdata = value.trim() == REVCLOUD_XDATA_APP;
+                        if in_revcloud_xdata {
+                            revcloud.get_or_insert((None, None));
+                        }
+                    }
+                    1070 if in_revcloud_xdata => {
+                        if let Some((style @ None, _)) = revcloud.as_mut() {
+                            *style = Some(parse_i16(&value, "REVCLOUD 样式（组码 1070）")?);
+                        }
+                    }
+                    1040 if in_revcloud_xdata => {
+                        if let Some((_, arc_length @ None)) = revcloud.as_mut() {
+                            *arc_length = Some(parse_f64(&value, "REVCLOUD 弧长（组码 1040）")?);
+                        }
+                    }
                     70 => {
                         let flag = parse_i32(&value, "LWPOLYLINE 标志")?;
                         is_closed = flag & 0x01 == 0x01;
@@ -800,6 +1657,16 @@ impl<'a> DxfParser<'a> {
         }
 
         let layer = layer.unwrap_or_else(|| "0".to_string());
+        if let Some((style, arc_length)) = revcloud {
+            let arc_length = arc_length.unwrap_or_else(|| mean_chord_length(&vertices, is_closed));
+            return Ok(Entity::RevisionCloud(RevisionCloud {
+                vertices,
+                is_closed,
+                arc_length,
+                style: style.map(RevisionCloudStyle::from_code).unwrap_or_default(),
+                layer,
+            }));
+        }
         Ok(Entity::Polyline(Polyline {
             vertices,
             is_closed,
@@ -1279,6 +2146,11 @@ impl<'a> DxfParser<'a> {
         let mut height = None;
         let mut rotation_deg = 0.0;
         let mut text: Option<String> = None;
+        let mut generation_flags = 0;
+        let mut horizontal_align = 0;
+        let mut vertical_align = 0;
+        let mut alignment_x = None;
+        let mut alignment_y = None;
         loop {
             match self.reader.next_pair()? {
                 Some((0, value)) => {
@@ -1319,8 +2191,13 @@ impl<'a> DxfParser<'a> {
                             None => text = Some(entry),
                         }
                     }
-                    7 | 72 | 73 | 100 | 11 | 21 => {
-                        // 目前忽略：文字样式、对齐信息等
+                    71 => generation_flags = parse_i16(&value, "TEXT 生成标志（组码 71）")?,
+                    72 => horizontal_align = parse_i16(&value, "TEXT 水平对齐（组码 72）")?,
+                    73 => vertical_align = parse_i16(&value, "TEXT 垂直对齐（组码 73）")?,
+                    11 => alignment_x = Some(parse_f64(&value, "TEXT 对齐点 X（组码 11）")?),
+                    21 => alignment_y = Some(parse_f64(&value, "TEXT 对齐点 Y（组码 21）")?),
+                    7 | 100 => {
+                        // 目前忽略：文字样式、子类标记
                     }
                     _ => {}
                 },
@@ -1334,12 +2211,25 @@ impl<'a> DxfParser<'a> {
         let height = height.ok_or_else(|| DxfError::invalid("TEXT 缺少文字高度（组码 40）"))?;
         let content = text.ok_or_else(|| DxfError::invalid("TEXT 缺少文本内容（组码 1）"))?;
 
+        // 左对齐基线文字的第二对齐点无意义，读取时忽略
+        let alignment = match (alignment_x, alignment_y) {
+            (Some(x), Some(y)) if horizontal_align != 0 || vertical_align != 0 => {
+                Some(Point2::new(x, y))
+            }
+            _ => None,
+        };
+
         Ok(Entity::Text(Text {
             insert: Point2::new(ix, iy),
             content,
             height,
             rotation: rotation_deg.to_radians(),
             layer,
+            horizontal_align,
+            vertical_align,
+            alignment,
+            is_backward: generation_flags & 2 != 0,
+            is_upside_down: generation_flags & 4 != 0,
         }))
     }
 
@@ -1431,10 +2321,7 @@ impl<'a> DxfParser<'a> {
             return Err(DxfError::invalid("MTEXT 缺少内容（组码 1/3）"));
         }
 
-        let decoded_text = fragments
-            .into_iter()
-            .map(|frag| decode_mtext_content(&frag))
-            .collect::<String>();
+        let formatted = mtext::parse_mtext(&fragments.concat(), height);
 
         let direction = match (direction_x, direction_y) {
             (Some(x), Some(y)) => {
@@ -1456,7 +2343,7 @@ impl<'a> DxfParser<'a> {
 
         Ok(Entity::MText(MText {
             insert: Point2::new(ix, iy),
-            content: decoded_text,
+            content: formatted.text,
             height,
             reference_width,
             direction,
@@ -1464,6 +2351,7 @@ impl<'a> DxfParser<'a> {
             drawing_direction,
             style,
             layer,
+            runs: formatted.runs,
         }))
     }
 
@@ -1634,6 +2522,7 @@ impl<'a> DxfParser<'a> {
                     is_closed: self.is_closed,
                     edges: self.edges,
                     boundary_handles: self.boundary_handles,
+                    boundary_entities: Vec::new(),
                 })
             }
 
@@ -1882,6 +2771,48 @@ impl<'a> DxfParser<'a> {
             }
         }
 
+        struct PatternLineBuilder {
+            line: HatchPatternLine,
+            expected_dashes: Option<usize>,
+        }
+
+        impl PatternLineBuilder {
+            fn new(angle_degrees: f64) -> Self {
+                Self {
+                    line: HatchPatternLine {
+                        angle: angle_degrees.to_radians(),
+                        base_point: Point2::new(0.0, 0.0),
+                        offset: Vector2::new(0.0, 0.0),
+                        dash_lengths: Vec::new(),
+                    },
+                    expected_dashes: None,
+                }
+            }
+
+            fn finish(self) -> Result<HatchPatternLine, DxfError> {
+                if let Some(expected) = self.expected_dashes
+                    && expected != self.line.dash_lengths.len()
+                {
+                    return Err(DxfError::invalid(format!(
+                        "HATCH 图案定义行声明的虚线数量 {expected} 与实际数量 {} 不符",
+                        self.line.dash_lengths.len()
+                    )));
+                }
+                Ok(self.line)
+            }
+        }
+
+        fn current_pattern_line(
+            builders: &mut [PatternLineBuilder],
+            code: i32,
+        ) -> Result<&mut PatternLineBuilder, DxfError> {
+            builders.last_mut().ok_or_else(|| {
+                DxfError::invalid(format!(
+                    "HATCH 图案定义行缺少角度（组码 53）即出现了组码 {code}"
+                ))
+            })
+        }
+
         let mut layer = None;
         let mut pattern_name = "SOLID".to_string();
         let mut is_solid = false;
@@ -1889,6 +2820,12 @@ impl<'a> DxfParser<'a> {
         let mut current_loop: Option<PartialLoop> = None;
         let mut edge_builder: Option<EdgeBuilder> = None;
         let mut gradient_builder = GradientBuilder::default();
+        let mut pattern_builders: Vec<PatternLineBuilder> = Vec::new();
+        let mut style = HatchStyle::Normal;
+        let mut is_associative = false;
+        let mut expected_seeds: Option<usize> = None;
+        let mut seed_points: Vec<Point2> = Vec::new();
+        let mut pending_seed_x: Option<f64> = None;
 
         fn finalize_loop(
             current_loop: &mut Option<PartialLoop>,
@@ -1916,6 +2853,9 @@ impl<'a> DxfParser<'a> {
                         let flag = parse_i16(&value, "HATCH 旗标（组码 70）")?;
                         is_solid = (flag & 1) != 0;
                     }
+                    71 => {
+                        is_associative = parse_i16(&value, "HATCH 关联标记（组码 71）")? != 0;
+                    }
                     91 => {
                         // 环路数量，仅作校验参考
                         let _ = parse_i32(&value, "HATCH 环路数量（组码 91）")?;
@@ -1983,11 +2923,30 @@ impl<'a> DxfParser<'a> {
                                 parse_i32(&value, "HATCH 样条周期标记（组码 74）")? != 0;
                         }
                     }
-                    75 => {
+                    94 => {
                         if let Some(EdgeBuilder::Spline(spline)) = edge_builder.as_mut() {
+                            spline.degree = Some(parse_i32(&value, "HATCH 样条阶数（组码 94）")?);
+                        }
+                    }
+                    75 => {
+                        // 兼容旧数据：样条边尚未读到阶数时，75 仍视为样条阶数
+                        if let Some(EdgeBuilder::Spline(spline)) = edge_builder.as_mut()
+                            && spline.degree.is_none()
+                        {
                             spline.degree = Some(parse_i32(&value, "HATCH 样条阶数（组码 75）")?);
+                        } else {
+                            finalize_loop(&mut current_loop, &mut loops, &mut edge_builder)?;
+                            style = HatchStyle::from_code(parse_i16(
+                                &value,
+                                "HATCH 填充样式（组码 75）",
+                            )?);
                         }
                     }
+                    98 => {
+                        finalize_loop(&mut current_loop, &mut loops, &mut edge_builder)?;
+                        let count = parse_i32(&value, "HATCH 种子点数量（组码 98）")?;
+                        expected_seeds = Some(count.max(0) as usize);
+                    }
                     97 => {
                         // 引用对象数量，保留检查但实际以 330 条目为准
                         let _ = parse_i32(&value, "HATCH 边界引用数量（组码 97）")?;
@@ -1997,6 +2956,19 @@ impl<'a> DxfParser<'a> {
                             loop_data.boundary_handles.push(value.trim().to_string());
                         }
                     }
+                    10 if expected_seeds.is_some() => {
+                        let x = parse_f64(&value, "HATCH 种子点 X（组码 10）")?;
+                        if pending_seed_x.replace(x).is_some() {
+                            return Err(DxfError::invalid("HATCH 遇到重复的种子点 X（组码 10）"));
+                        }
+                    }
+                    20 if expected_seeds.is_some() => {
+                        let y = parse_f64(&value, "HATCH 种子点 Y（组码 20）")?;
+                        let x = pending_seed_x.take().ok_or_else(|| {
+                            DxfError::invalid("HATCH 种子点 Y 前未读取到对应的 X 值（组码 20）")
+                        })?;
+                        seed_points.push(Point2::new(x, y));
+                    }
                     10 => {
                         if let Some(loop_data) = current_loop.as_mut() {
                             if loop_data.is_polyline && edge_builder.is_none() {
@@ -2224,6 +3196,46 @@ impl<'a> DxfParser<'a> {
                     470 => {
                         gradient_builder.name = Some(value.trim().to_string());
                     }
+                    78 => {
+                        // 图案定义行数量，仅作校验参考
+                        let _ = parse_i32(&value, "HATCH 图案定义行数量（组码 78）")?;
+                    }
+                    53 => {
+                        let angle = parse_f64(&value, "HATCH 图案线角度（组码 53）")?;
+                        pattern_builders.push(PatternLineBuilder::new(angle));
+                    }
+                    43 => {
+                        let builder = current_pattern_line(&mut pattern_builders, code)?;
+                        builder.line.base_point.0.x =
+                            parse_f64(&value, "HATCH 图案线基点 X（组码 43）")?;
+                    }
+                    44 => {
+                        let builder = current_pattern_line(&mut pattern_builders, code)?;
+                        builder.line.base_point.0.y =
+                            parse_f64(&value, "HATCH 图案线基点 Y（组码 44）")?;
+                    }
+                    45 => {
+                        let builder = current_pattern_line(&mut pattern_builders, code)?;
+                        builder.line.offset.0.x =
+                            parse_f64(&value, "HATCH 图案线偏移 X（组码 45）")?;
+                    }
+                    46 => {
+                        let builder = current_pattern_line(&mut pattern_builders, code)?;
+                        builder.line.offset.0.y =
+                            parse_f64(&value, "HATCH 图案线偏移 Y（组码 46）")?;
+                    }
+                    79 => {
+                        let builder = current_pattern_line(&mut pattern_builders, code)?;
+                        let count = parse_i32(&value, "HATCH 图案线虚线数量（组码 79）")?;
+                        builder.expected_dashes = Some(count.max(0) as usize);
+                    }
+                    49 => {
+                        let builder = current_pattern_line(&mut pattern_builders, code)?;
+                        builder
+                            .line
+                            .dash_lengths
+                            .push(parse_f64(&value, "HATCH 图案线虚线长度（组码 49）")?);
+                    }
                     _ => {
                         // 其它未支持字段直接忽略
                     }
@@ -2236,18 +3248,60 @@ impl<'a> DxfParser<'a> {
             return Err(DxfError::invalid("HATCH 缺少边界定义"));
         }
 
+        let pattern_lines = pattern_builders
+            .into_iter()
+            .map(PatternLineBuilder::finish)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let Some(x) = pending_seed_x {
+            return Err(DxfError::invalid(format!(
+                "HATCH 种子点 X={x} 缺少对应的 Y 坐标"
+            )));
+        }
+        if let Some(expected) = expected_seeds
+            && expected != seed_points.len()
+        {
+            return Err(DxfError::invalid(format!(
+                "HATCH 声明的种子点数量 {expected} 与实际数量 {} 不符",
+                seed_points.len()
+            )));
+        }
+
         let layer = layer.unwrap_or_else(|| "0".to_string());
         Ok(Entity::Hatch(Hatch {
             pattern_name,
             is_solid,
             loops,
             gradient: gradient_builder.finish(),
+            pattern_lines,
+            style,
+            is_associative,
+            seed_points,
             layer,
         }))
     }
 
     fn parse_dimension(&mut self) -> Result<Entity, DxfError> {
+        /// 子类标记（组码 100）决定部分组码的含义。
+        #[derive(Clone, Copy, PartialEq)]
+        enum Subclass {
+            Common,
+            ArcLength,
+            LargeRadial,
+        }
+
         let mut layer = None;
+        let mut subclass = Subclass::Common;
+        let mut jog_x = None;
+        let mut jog_y = None;
+        let mut arc_start: Option<f64> = None;
+        let mut arc_end: Option<f64> = None;
+        let mut arc_is_partial = false;
+        let mut arc_has_leader = false;
+        let mut leader_start_x = None;
+        let mut leader_start_y = None;
+        let mut leader_end_x = None;
+        let mut leader_end_y = None;
         let mut flags: i16 = 0;
         let mut definition_x = None;
         let mut definition_y = None;
@@ -2270,6 +3324,8 @@ impl<'a> DxfParser<'a> {
         let mut rotation_deg: f64 = 0.0;
         let mut text_rotation_deg: Option<f64> = None;
         let mut oblique_angle_deg: Option<f64> = None;
+        let mut block_name: Option<String> = None;
+        let mut style_name: Option<String> = None;
 
         loop {
             match self.reader.next_pair()? {
@@ -2278,7 +3334,99 @@ impl<'a> DxfParser<'a> {
                     break;
                 }
                 Some((code, value)) => match code {
+                    100 => {
+                        subclass = match value.trim() {
+                            "AcDbArcDimension" => Subclass::ArcLength,
+                            "AcDbRadialDimensionLarge" => Subclass::LargeRadial,
+                            _ => Subclass::Common,
+                        };
+                    }
+                    // 以下为弧长标注子类（AcDbArcDimension）专有字段
+                    40 if subclass == Subclass::ArcLength => {
+                        arc_start = Some(parse_f64(&value, "ARC_DIMENSION 起始角（组码 40）")?);
+                    }
+                    41 if subclass == Subclass::ArcLength => {
+                        arc_end = Some(parse_f64(&value, "ARC_DIMENSION 终止角（组码 41）")?);
+                    }
+                    70 if subclass == Subclass::ArcLength => {
+                        arc_is_partial =
+                            parse_i16(&value, "ARC_DIMENSION 部分弧标记（组码 70）")? != 0;
+                    }
+                    71 if subclass == Subclass::ArcLength => {
+                        arc_has_leader =
+                            parse_i16(&value, "ARC_DIMENSION 引线标记（组码 71）")? != 0;
+                    }
+                    16 if subclass == Subclass::ArcLength => {
+                        leader_start_x =
+                            Some(parse_f64(&value, "ARC_DIMENSION 引线起点 X（组码 16）")?);
+                    }
+                    26 if subclass == Subclass::ArcLength => {
+                        leader_start_y =
+                            Some(parse_f64(&value, "ARC_DIMENSION 引线起点 Y（组码 26）")?);
+                    }
+                    17 if subclass == Subclass::ArcLength => {
+                        leader_end_x =
+                            Some(parse_f64(&value, "ARC_DIMENSION 引线终点 X（组码 17）")?);
+                    }
+                    27 if subclass == Subclass::ArcLength => {
+                        leader_end_y =
+                            Some(parse_f64(&value, "ARC_DIMENSION 引线终点 Y（组码 27）")?);
+                    }
+                    13 if subclass == Subclass::ArcLength => {
+                        ext_origin_x =
+                            Some(parse_f64(&value, "ARC_DIMENSION 尺寸界线 1 X（组码 13）")?);
+                    }
+                    23 if subclass == Subclass::ArcLength => {
+                        ext_origin_y =
+                            Some(parse_f64(&value, "ARC_DIMENSION 尺寸界线 1 Y（组码 23）")?);
+                    }
+                    14 if subclass == Subclass::ArcLength => {
+                        ext_end_x =
+                            Some(parse_f64(&value, "ARC_DIMENSION 尺寸界线 2 X（组码 14）")?);
+                    }
+                    24 if subclass == Subclass::ArcLength => {
+                        ext_end_y =
+                            Some(parse_f64(&value, "ARC_DIMENSION 尺寸界线 2 Y（组码 24）")?);
+                    }
+                    15 if subclass == Subclass::ArcLength => {
+                        center_x = Some(parse_f64(&value, "ARC_DIMENSION 圆心 X（组码 15）")?);
+                    }
+                    25 if subclass == Subclass::ArcLength => {
+                        center_y = Some(parse_f64(&value, "ARC_DIMENSION 圆心 Y（组码 25）")?);
+                    }
+                    // 折弯半径标注子类（AcDbRadialDimensionLarge）：
+                    // 13 为弦点（同半径标注的组码 15），14 为替代圆心，15 为折弯点
+                    13 if subclass == Subclass::LargeRadial => {
+                        ext_end_x = Some(parse_f64(&value, "DIMENSION 弦点 X（组码 13）")?);
+                    }
+                    23 if subclass == Subclass::LargeRadial => {
+                        ext_end_y = Some(parse_f64(&value, "DIMENSION 弦点 Y（组码 23）")?);
+                    }
+                    14 if subclass == Subclass::LargeRadial => {
+                        center_x = Some(parse_f64(&value, "DIMENSION 替代圆心 X（组码 14）")?);
+                    }
+                    24 if subclass == Subclass::LargeRadial => {
+                        center_y = Some(parse_f64(&value, "DIMENSION 替代圆心 Y（组码 24）")?);
+                    }
+                    15 if subclass == Subclass::LargeRadial => {
+                        jog_x = Some(parse_f64(&value, "DIMENSION 折弯点 X（组码 15）")?);
+                    }
+                    25 if subclass == Subclass::LargeRadial => {
+                        jog_y = Some(parse_f64(&value, "DIMENSION 折弯点 Y（组码 25）")?);
+                    }
                     8 => layer = Some(value.trim().to_string()),
+                    2 => {
+                        let trimmed = value.trim();
+                        if !trimmed.is_empty() {
+                            block_name = Some(trimmed.to_string());
+                        }
+                    }
+                    3 => {
+                        let trimmed = value.trim();
+                        if !trimmed.is_empty() {
+                            style_name = Some(trimmed.to_string());
+                        }
+                    }
                     70 => {
                         flags = parse_i16(&value, "DIMENSION 类型标志（组码 70）")?;
                     }
@@ -2351,7 +3499,7 @@ impl<'a> DxfParser<'a> {
                         text_rotation_deg =
                             Some(parse_f64(&value, "DIMENSION 文本旋转（组码 52）")?);
                     }
-                    210 | 220 | 230 | 100 | 101 | 102 | 71 | 72 => {
+                    210 | 220 | 230 | 101 | 102 | 71 | 72 => {
                         // 暂不使用的字段
                     }
                     _ => {}
@@ -2400,7 +3548,31 @@ impl<'a> DxfParser<'a> {
             _ => None,
         };
 
-        let kind = Self::dimension_kind_from_flags(flags);
+        let kind = match subclass {
+            Subclass::ArcLength => DimensionKind::ArcLength,
+            Subclass::LargeRadial => DimensionKind::JoggedRadius,
+            Subclass::Common => Self::dimension_kind_from_flags(flags),
+        };
+
+        let jog_point = match (jog_x, jog_y) {
+            (Some(x), Some(y)) => Some(Point2::new(x, y)),
+            _ => None,
+        };
+
+        let arc = matches!(kind, DimensionKind::ArcLength).then(|| DimensionArc {
+            start_angle: arc_start.unwrap_or(0.0),
+            end_angle: arc_end.unwrap_or(0.0),
+            is_partial: arc_is_partial,
+            has_leader: arc_has_leader,
+            leader_start: match (leader_start_x, leader_start_y) {
+                (Some(x), Some(y)) => Some(Point2::new(x, y)),
+                _ => None,
+            },
+            leader_end: match (leader_end_x, leader_end_y) {
+                (Some(x), Some(y)) => Some(Point2::new(x, y)),
+                _ => None,
+            },
+        });
 
         Ok(Entity::Dimension(Dimension {
             kind,
@@ -2417,6 +3589,10 @@ impl<'a> DxfParser<'a> {
             rotation: rotation_deg.to_radians(),
             text_rotation: text_rotation_deg.map(f64::to_radians),
             oblique_angle: oblique_angle_deg.map(f64::to_radians),
+            block_name,
+            style_name,
+            jog_point,
+            arc,
             layer,
         }))
     }
@@ -3158,6 +4334,173 @@ impl<'a> DxfParser<'a> {
         }))
     }
 
+    fn parse_shape(&mut self) -> Result<Entity, DxfError> {
+        let mut layer = None;
+        let mut name = None;
+        let mut insert_x = None;
+        let mut insert_y = None;
+        let mut size = None;
+        let mut rotation = 0.0;
+        let mut width_factor = 1.0;
+        let mut oblique = 0.0;
+        loop {
+            match self.reader.next_pair()? {
+                Some((0, value)) => {
+                    self.reader.put_back((0, value));
+                    break;
+                }
+                Some((code, value)) => match code {
+                    8 => layer = Some(value.trim().to_string()),
+                    2 => name = Some(value.trim().to_string()),
+                    10 => assign_coord(&mut insert_x, &value, "SHAPE 插入点 X（组码 10）")?,
+                    20 => assign_coord(&mut insert_y, &value, "SHAPE 插入点 Y（组码 20）")?,
+                    40 => size = Some(parse_f64(&value, "SHAPE 大小（组码 40）")?),
+                    50 => rotation = parse_f64(&value, "SHAPE 旋转角（组码 50）")?.to_radians(),
+                    41 => width_factor = parse_f64(&value, "SHAPE 相对 X 比例（组码 41）")?,
+                    51 => oblique = parse_f64(&value, "SHAPE 倾斜角（组码 51）")?.to_radians(),
+                    _ => {}
+                },
+                None => return Err(DxfError::invalid("SHAPE 未正确结束")),
+            }
+        }
+
+        let name = name.ok_or_else(|| DxfError::invalid("SHAPE 缺少型名称（组码 2）"))?;
+        let x = insert_x.ok_or_else(|| DxfError::invalid("SHAPE 缺少插入点 X（组码 10）"))?;
+        let y = insert_y.ok_or_else(|| DxfError::invalid("SHAPE 缺少插入点 Y（组码 20）"))?;
+        let size = size.ok_or_else(|| DxfError::invalid("SHAPE 缺少大小（组码 40）"))?;
+
+        Ok(Entity::Shape(Shape {
+            name,
+            insert: Point2::new(x, y),
+            size,
+            rotation,
+            width_factor,
+            oblique,
+            style_handle: None,
+            layer: layer.unwrap_or_else(|| "0".to_string()),
+        }))
+    }
+    fn parse_underlay(&mut self, kind: UnderlayKind) -> Result<Entity, DxfError> {
+        let entity = kind.entity_name();
+        let mut layer = None;
+        let mut definition_handle = None;
+        let mut insert_x = None;
+        let mut insert_y = None;
+        let mut scale_x = 1.0;
+        let mut scale_y = 1.0;
+        let mut rotation = 0.0;
+        let mut display_options = UnderlayDisplayOptions::default();
+        let mut clip_boundary = Vec::new();
+        let mut pending_clip_x: Option<f64> = None;
+        loop {
+            match self.reader.next_pair()? {
+                Some((0, value)) => {
+                    self.reader.put_back((0, value));
+                    break;
+                }
+                Some((code, value)) => match code {
+                    8 => layer = Some(value.trim().to_string()),
+                    340 => definition_handle = Some(value.trim().to_string()),
+                    10 => assign_coord(
+                        &mut insert_x,
+                        &value,
+                        &format!("{entity} 插入点 X（组码 10）"),
+                    )?,
+                    20 => assign_coord(
+                        &mut insert_y,
+                        &value,
+                        &format!("{entity} 插入点 Y（组码 20）"),
+                    )?,
+                    41 => scale_x = parse_f64(&value, &format!("{entity} X 比例（组码 41）"))?,
+                    42 => scale_y = parse_f64(&value, &format!("{entity} Y 比例（组码 42）"))?,
+                    50 => {
+                        rotation =
+                            parse_f64(&value, &format!("{entity} 旋转角（组码 50）"))?.to_radians()
+                    }
+                    280 => {
+                        let flags = parse_i16(&value, &format!("{entity} 显示标志（组码 280）"))?;
+                        display_options = UnderlayDisplayOptions {
+                            contrast: display_options.contrast,
+                            fade: display_options.fade,
+                            ..UnderlayDisplayOptions::from_flags(flags)
+                        };
+                    }
+                    281 => {
+                        display_options.contrast =
+                            parse_i16(&value, &format!("{entity} 对比度（组码 281）"))?
+                    }
+                    282 => {
+                        display_options.fade =
+                            parse_i16(&value, &format!("{entity} 淡入度（组码 282）"))?
+                    }
+                    11 => {
+                        pending_clip_x =
+                            Some(parse_f64(&value, &format!("{entity} 裁剪点 X（组码 11）"))?)
+                    }
+                    21 => {
+                        let y = parse_f64(&value, &format!("{entity} 裁剪点 Y（组码 21）"))?;
+                        let x = pending_clip_x.take().ok_or_else(|| {
+                            DxfError::invalid(format!("{entity} 裁剪点缺少 X（组码 11）"))
+                        })?;
+                        clip_boundary.push(Point2::new(x, y));
+                    }
+                    _ => {}
+                },
+                None => return Err(DxfError::invalid(format!("{entity} 未正确结束"))),
+            }
+        }
+
+        let definition_handle = definition_handle
+            .ok_or_else(|| DxfError::invalid(format!("{entity} 缺少引用的定义句柄（组码 340）")))?;
+        Ok(Entity::Underlay(Underlay {
+            kind,
+            definition_handle,
+            insert: Point2::new(insert_x.unwrap_or(0.0), insert_y.unwrap_or(0.0)),
+            scale: Vector2::new(scale_x, scale_y),
+            rotation,
+            display_options,
+            clip_boundary,
+            layer: layer.unwrap_or_else(|| "0".to_string()),
+        }))
+    }
+
+    fn parse_underlay_definition(
+        &mut self,
+        kind: UnderlayKind,
+    ) -> Result<UnderlayDefinition, DxfError> {
+        let object = kind.definition_name();
+        let mut handle = None;
+        let mut file_path = None;
+        let mut page = String::new();
+        loop {
+            match self.reader.next_pair()? {
+                Some((0, value)) => {
+                    self.reader.put_back((0, value));
+                    break;
+                }
+                Some((code, value)) => match code {
+                    5 => handle = Some(value.trim().to_string()),
+                    1 => file_path = Some(value.trim().to_string()),
+                    2 => page = value.trim().to_string(),
+                    _ => {}
+                },
+                None => return Err(DxfError::invalid(format!("{object} 未正确结束"))),
+            }
+        }
+
+        let handle =
+            handle.ok_or_else(|| DxfError::invalid(format!("{object} 缺少句柄（组码 5）")))?;
+        let file_path = file_path
+            .ok_or_else(|| DxfError::invalid(format!("{object} 缺少文件路径（组码 1）")))?;
+        Ok(UnderlayDefinition {
+            handle,
+            kind,
+            file_path,
+            page,
+            name: None,
+        })
+    }
+
     fn parse_image_def(&mut self) -> Result<RasterImageDefinition, DxfError> {
         let mut handle: Option<String> = None;
         let mut file_path: Option<String> = None;
@@ -3218,6 +4561,59 @@ impl<'a> DxfParser<'a> {
         })
     }
 
+    /// 读取 XRECORD 的句柄与数据组码，跳过反应器与扩展字典分组。
+    fn parse_xrecord(&mut self) -> Result<ParsedXRecord, DxfError> {
+        let mut handle = None;
+        let mut pairs = Vec::new();
+        let mut in_group = false;
+        loop {
+            match self.reader.next_pair()? {
+                Some((0, value)) => {
+                    self.reader.put_back((0, value));
+                    break;
+                }
+                Some((5, value)) if handle.is_none() => handle = Some(value.trim().to_string()),
+                Some((102, value)) => in_group = value.trim().starts_with('{'),
+                Some((100 | 330, _)) if !in_group => {}
+                Some(pair) => {
+                    if !in_group {
+                        pairs.push(pair);
+                    }
+                }
+                None => return Err(DxfError::invalid("XRECORD 未正确结束")),
+            }
+        }
+        Ok((handle, pairs))
+    }
+
+    /// 读取 SORTENTSTABLE 的条目：子类标记之后成对出现实体句柄（组码 331）与排序句柄（组码 5）。
+    fn parse_sortents_table(&mut self) -> Result<Vec<(String, u64)>, DxfError> {
+        let mut entries = Vec::new();
+        let mut in_table = false;
+        let mut pending: Option<String> = None;
+        loop {
+            match self.reader.next_pair()? {
+                Some((0, value)) => {
+                    self.reader.put_back((0, value));
+                    break;
+                }
+                Some((100, value)) => in_table = value.trim() == "AcDbSortentsTable",
+                Some((331, value)) if in_table => pending = Some(value.trim().to_string()),
+                Some((5, value)) if in_table => {
+                    if let Some(entity) = pending.take() {
+                        let sort = u64::from_str_radix(value.trim(), 16).map_err(|_| {
+                            DxfError::invalid(format!("SORTENTSTABLE 排序句柄无效: {value}"))
+                        })?;
+                        entries.push((entity, sort));
+                    }
+                }
+                Some(_) => {}
+                None => return Err(DxfError::invalid("SORTENTSTABLE 未正确结束")),
+            }
+        }
+        Ok(entries)
+    }
+
     fn parse_dictionary(&mut self) -> Result<ParsedDictionary, DxfError> {
         let mut handle: Option<String> = None;
         let mut owner: Option<String> = None;
@@ -3332,6 +4728,132 @@ impl<'a> DxfParser<'a> {
         Ok((handle, vars))
     }
 
+    fn parse_wipeout_variables(&mut self) -> Result<(String, WipeoutVariables), DxfError> {
+        let mut handle: Option<String> = None;
+        let mut class_version: Option<i32> = None;
+        let mut frame: Option<i16> = None;
+
+        loop {
+            match self.reader.next_pair()? {
+                Some((0, value)) => {
+                    self.reader.put_back((0, value));
+                    break;
+                }
+                Some((code, value)) => match code {
+                    5 => handle = Some(value.trim().to_string()),
+                    90 => {
+                        class_version =
+                            Some(parse_i32(&value, "WIPEOUTVARIABLES 类版本（组码 90）")?);
+                    }
+                    70 => {
+                        frame = Some(parse_i16(&value, "WIPEOUTVARIABLES 边框显示（组码 70）")?);
+                    }
+                    _ => {}
+                },
+                None => return Err(DxfError::invalid("WIPEOUTVARIABLES 未正确结束")),
+            }
+        }
+
+        let handle =
+            handle.ok_or_else(|| DxfError::invalid("WIPEOUTVARIABLES 缺少句柄（组码 5）"))?;
+        let vars = WipeoutVariables {
+            handle: Some(handle.clone()),
+            class_version,
+            frame,
+        };
+
+        Ok((handle, vars))
+    }
+
+    fn parse_geodata(&mut self) -> Result<GeoData, DxfError> {
+        let mut geodata = GeoData::default();
+        let mut design = [None; 3];
+        let mut reference = [None; 3];
+        let mut north = [None; 2];
+        let mut up = [None; 3];
+        let mut coordinate_system = String::new();
+
+        loop {
+            match self.reader.next_pair()? {
+                Some((0, value)) => {
+                    self.reader.put_back((0, value));
+                    break;
+                }
+                Some((code, value)) => match code {
+                    5 => geodata.handle = Some(value.trim().to_string()),
+                    90 => geodata.version = parse_i32(&value, "GEODATA 版本（组码 90）")?,
+                    70 => {
+                        geodata.coordinate_type = GeoCoordinateType::from_code(parse_i16(
+                            &value,
+                            "GEODATA 坐标类型（组码 70）",
+                        )?);
+                    }
+                    10 => assign_coord(&mut design[0], &value, "GEODATA 设计点 X（组码 10）")?,
+                    20 => assign_coord(&mut design[1], &value, "GEODATA 设计点 Y（组码 20）")?,
+                    30 => assign_coord(&mut design[2], &value, "GEODATA 设计点 Z（组码 30）")?,
+                    11 => assign_coord(&mut reference[0], &value, "GEODATA 参考点 X（组码 11）")?,
+                    21 => assign_coord(&mut reference[1], &value, "GEODATA 参考点 Y（组码 21）")?,
+                    31 => assign_coord(&mut reference[2], &value, "GEODATA 参考点 Z（组码 31）")?,
+                    12 => assign_coord(&mut north[0], &value, "GEODATA 北向 X（组码 12）")?,
+                    22 => assign_coord(&mut north[1], &value, "GEODATA 北向 Y（组码 22）")?,
+                    210 => assign_coord(&mut up[0], &value, "GEODATA 上方向 X（组码 210）")?,
+                    220 => assign_coord(&mut up[1], &value, "GEODATA 上方向 Y（组码 220）")?,
+                    230 => assign_coord(&mut up[2], &value, "GEODATA 上方向 Z（组码 230）")?,
+                    40 => {
+                        geodata.horizontal_unit_scale =
+                            parse_f64(&value, "GEODATA 水平单位比例（组码 40）")?;
+                    }
+                    91 => {
+                        geodata.horizontal_units =
+                            parse_i32(&value, "GEODATA 水平单位（组码 91）")?;
+                    }
+                    41 => {
+                        geodata.vertical_unit_scale =
+                            parse_f64(&value, "GEODATA 垂直单位比例（组码 41）")?;
+                    }
+                    92 => {
+                        geodata.vertical_units = parse_i32(&value, "GEODATA 垂直单位（组码 92）")?;
+                    }
+                    95 => {
+                        geodata.scale_estimation_method =
+                            parse_i32(&value, "GEODATA 比例估算方法（组码 95）")?;
+                    }
+                    141 => {
+                        geodata.user_scale_factor =
+                            parse_f64(&value, "GEODATA 用户比例因子（组码 141）")?;
+                    }
+                    294 => {
+                        geodata.sea_level_correction =
+                            parse_i16(&value, "GEODATA 海平面校正（组码 294）")? != 0;
+                    }
+                    142 => {
+                        geodata.sea_level_elevation =
+                            parse_f64(&value, "GEODATA 海平面高程（组码 142）")?;
+                    }
+                    // 坐标系定义超过 255 字符时以 303 续接，按出现顺序拼接
+                    301 | 303 => coordinate_system.push_str(&value),
+                    _ => {}
+                },
+                None => return Err(DxfError::invalid("GEODATA 未正确结束")),
+            }
+        }
+
+        if let [Some(x), Some(y), z] = design {
+            geodata.design_point = Point3::new(x, y, z.unwrap_or(0.0));
+        }
+        if let [Some(x), Some(y), z] = reference {
+            geodata.reference_point = Point3::new(x, y, z.unwrap_or(0.0));
+        }
+        if let [Some(x), Some(y)] = north {
+            geodata.north_direction = Vector2::new(x, y);
+        }
+        if let [Some(x), Some(y), Some(z)] = up {
+            geodata.up_direction = Vector3::new(x, y, z);
+        }
+        geodata.coordinate_system = coordinate_system;
+        Ok(geodata)
+    }
+
     fn parse_image_def_reactor(&mut self) -> Result<ImageDefReactor, DxfError> {
         let mut handle: Option<String> = None;
         let mut owner_handle: Option<String> = None;
@@ -3403,6 +4925,8 @@ impl<'a> DxfParser<'a> {
             4 => DimensionKind::Radius,
             5 => DimensionKind::Angular3Point,
             6 => DimensionKind::Ordinate,
+            8 => DimensionKind::ArcLength,
+            9 => DimensionKind::JoggedRadius,
             other => DimensionKind::Unknown(other),
         }
     }
@@ -3660,6 +5184,15 @@ struct DxfReader<'a> {
     lines: std::str::Lines<'a>,
     buffer: Option<(i32, String)>,
     line_number: usize,
+    /// 自 [`DxfReader::begin_entity`] 起读到的第一个句柄（组码 5）。
+    entity_handle: Option<String>,
+    /// 自 [`DxfReader::begin_entity`] 起读到的 `ZCAD_USERDATA` 扩展数据组码。
+    user_data: Vec<(i32, String)>,
+    in_user_data: bool,
+    /// 实体公共部分（`AcDbEntity` 子类）中的特性组码：颜色 62/420、线型 6、线宽 370。
+    entity_properties: Vec<(i32, String)>,
+    /// 是否仍在实体公共部分；遇到其他子类标记或下一个对象时结束，之后同名组码另有含义。
+    in_entity_header: bool,
 }
 
 impl<'a> DxfReader<'a> {
@@ -3668,9 +5201,22 @@ impl<'a> DxfReader<'a> {
             lines: source.lines(),
             buffer: None,
             line_number: 0,
+            entity_handle: None,
+            user_data: Vec::new(),
+            in_user_data: false,
+            entity_properties: Vec::new(),
+            in_entity_header: false,
         }
     }
 
+    fn begin_entity(&mut self) {
+        self.entity_handle = None;
+        self.user_data.clear();
+        self.in_user_data = false;
+        self.entity_properties.clear();
+        self.in_entity_header = true;
+    }
+
     fn next_pair(&mut self) -> Result<Option<(i32, String)>, DxfError> {
         if let Some(pair) = self.buffer.take() {
             return Ok(Some(pair));
@@ -3705,6 +5251,24 @@ impl<'a> DxfReader<'a> {
             ))
         })?;
         let value = value_line.trim_end_matches('\r').to_string();
+        if code == 5 && self.entity_handle.is_none() {
+            self.entity_handle = Some(value.trim().to_string());
+        }
+        if self.in_entity_header {
+            match code {
+                0 => self.in_entity_header = false,
+                100 => self.in_entity_header = value.trim() == "AcDbEntity",
+                6 | 62 | 370 | 420 => self.entity_properties.push((code, value.clone())),
+                _ => {}
+            }
+        }
+        if code == 1001 {
+            self.in_user_data = value.trim() == USER_DATA_XDATA_APP;
+        } else if self.in_user_data && code >= 1000 {
+            self.user_data.push((code, value.clone()));
+        } else if code < 1000 {
+            self.in_user_data = false;
+        }
         Ok(Some((code, value)))
     }
 
@@ -3724,6 +5288,27 @@ fn assign_coord(slot: &mut Option<f64>, raw: &str, context: &str) -> Result<(),
     Ok(())
 }
 
+/// 扩展数据缺少弧长时，以相邻顶点的平均弦长估算。
+fn mean_chord_length(vertices: &[PolylineVertex], is_closed: bool) -> f64 {
+    let count = vertices.len();
+    let segments = if is_closed {
+        count
+    } else {
+        count.saturating_sub(1)
+    };
+    if segments == 0 {
+        return 0.0;
+    }
+    let total: f64 = (0..segments)
+        .map(|index| {
+            let start = vertices[index].position.as_vec2();
+            let end = vertices[(index + 1) % count].position.as_vec2();
+            (end - start).length()
+        })
+        .sum();
+    total / segments as f64
+}
+
 fn build_face_vertex(
     index: usize,
     x: Option<f64>,
@@ -3745,6 +5330,122 @@ fn parse_f64(raw: &str, context: &str) -> Result<f64, DxfError> {
         .map_err(|_| DxfError::invalid(format!("{context} 解析失败（值：\"{raw}\"）")))
 }
 
+/// 由图层状态 XRECORD 构造图层状态：组码 301 为说明，每个图层以组码 8 开始，
+/// 其后 90 为标志（1 冻结、4 锁定），62 为颜色（负值表示关闭），420 为真彩色。
+fn layer_state_from_xrecord(name: &str, pairs: &[(i32, String)]) -> Result<LayerState, DxfError> {
+    let mut state = LayerState {
+        name: name.to_string(),
+        description: String::new(),
+        layers: Vec::new(),
+    };
+    for (code, value) in pairs {
+        if *code == 8 {
+            state.layers.push(LayerStateEntry {
+                layer: value.trim().to_string(),
+                is_visible: true,
+                is_frozen: false,
+                is_locked: false,
+                color: ObjectColor::Index(7),
+            });
+            continue;
+        }
+        let Some(entry) = state.layers.last_mut() else {
+            if *code == 301 {
+                state.description = value.trim().to_string();
+            }
+            continue;
+        };
+        match code {
+            90 => {
+                let flags = parse_i32(value, "图层状态标志（组码 90）")?;
+                entry.is_frozen = flags & i32::from(LAYER_FLAG_FROZEN) != 0;
+                entry.is_locked = flags & i32::from(LAYER_FLAG_LOCKED) != 0;
+            }
+            62 => {
+                let color = parse_i16(value, "图层状态颜色（组码 62）")?;
+                entry.is_visible = color >= 0;
+                if let ObjectColor::Index(index) = ObjectColor::from_aci(color) {
+                    entry.color = ObjectColor::Index(index);
+                }
+            }
+            420 => {
+                entry.color = ObjectColor::TrueColor(
+                    parse_u32(value, "图层状态真彩色（组码 420）")? & 0xFF_FFFF,
+                );
+            }
+            _ => {}
+        }
+    }
+    state.layers.sort_by(|a, b| a.layer.cmp(&b.layer));
+    Ok(state)
+}
+
+/// 解析 `ZCAD_USERDATA` 扩展数据：每项以 `{`/`}`（组码 1002）括起，第一个组码 1000 为键，
+/// 其余组码 1000 拼接为值的 JSON 文本。
+fn parse_user_data(pairs: &[(i32, String)]) -> Result<Vec<(String, UserValue)>, DxfError> {
+    let mut entries = Vec::new();
+    let mut current: Option<(Option<String>, String)> = None;
+    for (code, value) in pairs {
+        match (*code, value.trim(), current.as_mut()) {
+            (1002, "{", None) => current = Some((None, String::new())),
+            (1002, "}", Some(_)) => {
+                let (key, json) = current.take().expect("已匹配到未结束的项");
+                let key = key.ok_or_else(|| DxfError::invalid("ZCAD_USERDATA 项缺少键"))?;
+                let value = serde_json::from_str(&json).map_err(|err| {
+                    DxfError::invalid(format!("ZCAD_USERDATA 项 {key} 的值无法解析: {err}"))
+                })?;
+                entries.push((key, value));
+            }
+            (1000, _, Some((key @ None, _))) => *key = Some(value.clone()),
+            (1000, _, Some((Some(_), json))) => json.push_str(value),
+            _ => {
+                return Err(DxfError::invalid(format!(
+                    "ZCAD_USERDATA 遇到意外的组码 {code}"
+                )));
+            }
+        }
+    }
+    if current.is_some() {
+        return Err(DxfError::invalid("ZCAD_USERDATA 项未以 } 结束"));
+    }
+    Ok(entries)
+}
+
+/// 由实体公共部分的特性组码构造特性覆盖；没有这些组码时返回 `None`。
+/// 真彩色（组码 420）优先于索引色，随层、随块线型名按标准写法规范大小写。
+fn parse_entity_properties(pairs: &[(i32, String)]) -> Result<Option<EntityProperties>, DxfError> {
+    if pairs.is_empty() {
+        return Ok(None);
+    }
+    let mut properties = EntityProperties::default();
+    let mut true_color = None;
+    for (code, value) in pairs {
+        match code {
+            62 => {
+                properties.color = ObjectColor::from_aci(parse_i16(value, "实体颜色（组码 62）")?)
+            }
+            420 => true_color = Some(parse_u32(value, "实体真彩色（组码 420）")? & 0xFF_FFFF),
+            6 => {
+                let linetype = value.trim();
+                properties.linetype = [LINETYPE_BYLAYER, LINETYPE_BYBLOCK]
+                    .into_iter()
+                    .find(|name| name.eq_ignore_ascii_case(linetype))
+                    .unwrap_or(linetype)
+                    .to_string();
+            }
+            370 => {
+                properties.lineweight =
+                    LineWeight::from_code(parse_i16(value, "实体线宽（组码 370）")?);
+            }
+            _ => {}
+        }
+    }
+    if let Some(rgb) = true_color {
+        properties.color = ObjectColor::TrueColor(rgb);
+    }
+    Ok(Some(properties))
+}
+
 fn parse_i32(raw: &str, context: &str) -> Result<i32, DxfError> {
     raw.trim()
         .parse::<i32>()
@@ -3763,28 +5464,6 @@ fn parse_u32(raw: &str, context: &str) -> Result<u32, DxfError> {
         .map_err(|_| DxfError::invalid(format!("{context} 解析失败（值：\"{raw}\"）")))
 }
 
-fn decode_mtext_content(raw: &str) -> String {
-    let mut result = String::new();
-    let mut chars = raw.chars().peekable();
-    while let Some(ch) = chars.next() {
-        if ch == '\\' {
-            match chars.next() {
-                Some('P') | Some('p') => result.push('\n'),
-                Some('~') => result.push(' '),
-                Some('\\') => result.push('\\'),
-                Some(other) => {
-                    result.push('\\');
-                    result.push(other);
-                }
-                None => result.push('\\'),
-            }
-        } else {
-            result.push(ch);
-        }
-    }
-    result
-}
-
 fn decode_inline_text(raw: &str) -> String {
     let mut result = String::new();
     let mut chars = raw.chars().peekable();
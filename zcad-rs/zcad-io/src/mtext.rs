@@ -0,0 +1,281 @@
+//! MTEXT 行内格式码的解析与生成。
+//!
+//! 支持 `\f`/`\F` 字体、`\H` 字高、`\C`/`\c` 颜色、`\W` 宽度因子、`\A` 对齐、
+//! `\S` 堆叠分数以及 `{}` 格式分组；其余带参数的格式码（`\Q`、`\T` 等）被跳过。
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+use zcad_core::document::{
+    ObjectColor, StackKind, StackedText, TextFormat, TextRun, TextRunAlignment,
+};
+
+/// MTEXT 内容解析结果。
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormattedText {
+    /// 去除格式码后的纯文本，段落分隔为 `\n`。
+    pub text: String,
+    /// 按格式切分的文字段；全部沿用实体样式时为空。
+    pub runs: Vec<TextRun>,
+}
+
+/// 解析 MTEXT 原始内容。`height` 为实体字高，用于换算相对字高（`\H0.5x;`）。
+pub fn parse_mtext(raw: &str, height: f64) -> FormattedText {
+    let mut runs: Vec<TextRun> = Vec::new();
+    let mut format = TextFormat::default();
+    let mut groups: Vec<TextFormat> = Vec::new();
+    let mut chars = raw.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' => groups.push(format.clone()),
+            '}' => {
+                if let Some(outer) = groups.pop() {
+                    format = outer;
+                }
+            }
+            '\\' => match chars.next() {
+                Some('P') => push_text(&mut runs, &format, "\n"),
+                Some('p') => {
+                    // `\p…;` 为段落缩进与制表位设置，不带参数时按旧格式视为换行
+                    if chars
+                        .peek()
+                        .is_some_and(|next| matches!(next, 'x' | 'i' | 'l' | 'r' | 'q' | 't'))
+                    {
+                        read_argument(&mut chars);
+                    } else {
+                        push_text(&mut runs, &format, "\n");
+                    }
+                }
+                Some('~') => push_text(&mut runs, &format, " "),
+                Some(escaped @ ('\\' | '{' | '}')) => {
+                    push_text(&mut runs, &format, &escaped.to_string())
+                }
+                Some(code @ ('f' | 'F')) => {
+                    let argument = read_argument(&mut chars);
+                    apply_font(&mut format, &argument, code == 'F');
+                }
+                Some('H') => {
+                    let argument = read_argument(&mut chars);
+                    let current = format.height.unwrap_or(height);
+                    if let Some(value) = parse_scaled(&argument, current) {
+                        format.height = Some(value);
+                    }
+                }
+                Some('W') => {
+                    let argument = read_argument(&mut chars);
+                    let current = format.width_factor.unwrap_or(1.0);
+                    if let Some(value) = parse_scaled(&argument, current) {
+                        format.width_factor = Some(value);
+                    }
+                }
+                Some('C') => {
+                    let argument = read_argument(&mut chars);
+                    if let Ok(index) = argument.trim().parse::<i16>() {
+                        format.color = Some(ObjectColor::from_aci(index));
+                    }
+                }
+                Some('c') => {
+                    let argument = read_argument(&mut chars);
+                    if let Ok(bgr) = argument.trim().parse::<u32>() {
+                        format.color = Some(ObjectColor::TrueColor(swap_red_blue(bgr)));
+                    }
+                }
+                Some('A') => {
+                    let argument = read_argument(&mut chars);
+                    if let Some(alignment) = argument
+                        .trim()
+                        .parse::<i16>()
+                        .ok()
+                        .and_then(TextRunAlignment::from_code)
+                    {
+                        format.alignment = Some(alignment);
+                    }
+                }
+                Some('S') => {
+                    let argument = read_argument(&mut chars);
+                    match parse_stack(&argument) {
+                        Some(stack) => runs.push(TextRun::stacked(stack, format.clone())),
+                        None => push_text(&mut runs, &format, &argument),
+                    }
+                }
+                Some('Q' | 'T' | 'X') => {
+                    read_argument(&mut chars);
+                }
+                Some('L' | 'l' | 'O' | 'o' | 'K' | 'k' | 'N') => {}
+                Some(other) => {
+                    let mut literal = String::from('\\');
+                    literal.push(other);
+                    push_text(&mut runs, &format, &literal);
+                }
+                None => push_text(&mut runs, &format, "\\"),
+            },
+            other => push_text(&mut runs, &format, other.encode_utf8(&mut [0; 4])),
+        }
+    }
+
+    let text = runs.iter().map(|run| run.text.as_str()).collect();
+    if runs
+        .iter()
+        .all(|run| run.format.is_default() && run.stack.is_none())
+    {
+        runs.clear();
+    }
+    FormattedText { text, runs }
+}
+
+/// 读取格式码参数直到 `;`（不含）；`\;` 等转义原样保留，由调用方解释。
+fn read_argument(chars: &mut Peekable<Chars<'_>>) -> String {
+    let mut argument = String::new();
+    while let Some(ch) = chars.next() {
+        match ch {
+            ';' => break,
+            '\\' => {
+                argument.push(ch);
+                argument.extend(chars.next());
+            }
+            _ => argument.push(ch),
+        }
+    }
+    argument
+}
+
+/// 解析 `2.5` 或 `0.5x` 形式的数值；带 `x` 时相对当前值缩放。
+fn parse_scaled(argument: &str, current: f64) -> Option<f64> {
+    let argument = argument.trim();
+    match argument.strip_suffix(['x', 'X']) {
+        Some(factor) => factor.trim().parse::<f64>().ok().map(|f| f * current),
+        None => argument.parse::<f64>().ok(),
+    }
+}
+
+/// 字体参数形如 `Arial|b1|i0|c0|p34`；`\F` 的参数为字体文件名，不含粗斜体标志。
+fn apply_font(format: &mut TextFormat, argument: &str, is_file: bool) {
+    let mut parts = argument.split('|');
+    let family = parts.next().unwrap_or_default().trim();
+    format.font = (!family.is_empty()).then(|| family.to_string());
+    format.bold = false;
+    format.italic = false;
+    if is_file {
+        return;
+    }
+    for part in parts {
+        match part.trim() {
+            "b1" => format.bold = true,
+            "i1" => format.italic = true,
+            _ => {}
+        }
+    }
+}
+
+/// 堆叠分数 `1/2`、`1#2` 或公差 `+0.1^-0.2`，取首个分隔符；`\^` 等转义视为普通字符。
+fn parse_stack(argument: &str) -> Option<StackedText> {
+    let mut parts = [String::new(), String::new()];
+    let mut kind = None;
+    let mut chars = argument.chars();
+    while let Some(ch) = chars.next() {
+        let part = &mut parts[usize::from(kind.is_some())];
+        if ch == '\\' {
+            part.extend(chars.next());
+            continue;
+        }
+        match StackKind::from_separator(ch) {
+            Some(separator) if kind.is_none() => kind = Some(separator),
+            _ => part.push(ch),
+        }
+    }
+    let [upper, lower] = parts;
+    kind.map(|kind| StackedText { upper, lower, kind })
+}
+
+fn push_text(runs: &mut Vec<TextRun>, format: &TextFormat, text: &str) {
+    match runs.last_mut() {
+        Some(run) if run.stack.is_none() && run.format == *format => run.text.push_str(text),
+        _ => runs.push(TextRun::new(text, format.clone())),
+    }
+}
+
+/// MTEXT `\c` 的真彩色按 `0xBBGGRR` 存放，与 [`ObjectColor::TrueColor`] 的 `0xRRGGBB` 互换。
+fn swap_red_blue(value: u32) -> u32 {
+    ((value & 0xFF) << 16) | (value & 0xFF00) | ((value >> 16) & 0xFF)
+}
+
+/// 将纯文本或文字段编码为 MTEXT 内容片段序列；格式码与转义序列各自作为一个整体，
+/// 分段写出时不会被截断。
+pub fn encode_mtext(content: &str, runs: &[TextRun]) -> Vec<String> {
+    let mut tokens = Vec::new();
+    if runs.is_empty() {
+        push_escaped(&mut tokens, content);
+        return tokens;
+    }
+    for run in runs {
+        let is_plain = run.format.is_default() && run.stack.is_none();
+        if is_plain {
+            push_escaped(&mut tokens, &run.text);
+            continue;
+        }
+        tokens.push(format!("{{{}", format_codes(&run.format)));
+        match &run.stack {
+            Some(stack) => tokens.push(format!(
+                "\\S{}{}{};",
+                escape_stack(&stack.upper),
+                stack.kind.separator(),
+                escape_stack(&stack.lower)
+            )),
+            None => push_escaped(&mut tokens, &run.text),
+        }
+        tokens.push("}".to_string());
+    }
+    tokens
+}
+
+fn format_codes(format: &TextFormat) -> String {
+    let mut codes = String::new();
+    if format.font.is_some() || format.bold || format.italic {
+        codes.push_str(&format!(
+            "\\f{}|b{}|i{};",
+            format.font.as_deref().unwrap_or_default(),
+            u8::from(format.bold),
+            u8::from(format.italic)
+        ));
+    }
+    if let Some(height) = format.height {
+        codes.push_str(&format!("\\H{height};"));
+    }
+    if let Some(width) = format.width_factor {
+        codes.push_str(&format!("\\W{width};"));
+    }
+    match format.color {
+        Some(ObjectColor::TrueColor(rgb)) => {
+            codes.push_str(&format!("\\c{};", swap_red_blue(rgb)));
+        }
+        Some(color) => {
+            codes.push_str(&format!("\\C{};", color.aci().unwrap_or(256)));
+        }
+        None => {}
+    }
+    if let Some(alignment) = format.alignment {
+        codes.push_str(&format!("\\A{};", alignment.code()));
+    }
+    codes
+}
+
+fn push_escaped(tokens: &mut Vec<String>, text: &str) {
+    for ch in text.chars() {
+        tokens.push(match ch {
+            '\n' => "\\P".to_string(),
+            '\\' | '{' | '}' => format!("\\{ch}"),
+            other => other.to_string(),
+        });
+    }
+}
+
+fn escape_stack(text: &str) -> String {
+    let mut escaped = String::new();
+    for ch in text.chars() {
+        if matches!(ch, '/' | '#' | '^' | '\\' | ';') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
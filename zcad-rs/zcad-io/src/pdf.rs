@@ -0,0 +1,626 @@
+//! PDF 打印：按图纸尺寸与比例将文档输出为单页矢量 PDF，便于分享图纸。
+//!
+//! 文档经 FLATTEN（保留填充与区域覆盖）后写入页面内容流，坐标换算为点（1/72 英寸），
+//! 圆弧以三次贝塞尔曲线逼近；线宽按对象线宽的毫米数输出，不随打印比例缩放。
+//! 文字使用 PDF 标准字体 Helvetica，不嵌入字体，WinAnsi 之外的字符以 `?` 代替。
+//! 布局（图纸空间）尚未解析，目前只打印模型空间的范围或指定窗口。
+
+use std::f64::consts::TAU;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use glam::DVec2;
+use zcad_core::document::{
+    Document, Entity, EntityId, FlattenOptions, Hatch, HatchPatternLine, LineWeight, Polyline,
+    Text, Wipeout,
+};
+use zcad_core::geometry::{BulgeArc, Point2};
+use zcad_core::tessellate::TessellateOptions;
+
+use crate::plot::{
+    DEFAULT_LINEWEIGHT_MM, arc_beziers, dash_array, display_color, drawing_extent, extent,
+    format_number, gradient_axis, pattern_strokes, ring_points, wipeout_outline,
+};
+use crate::{DocumentSaver, IoError};
+
+/// 每毫米对应的 PDF 点数。
+const POINTS_PER_MM: f64 = 72.0 / 25.4;
+/// 内容流中坐标保留的小数位数（点）。
+const PRECISION: usize = 3;
+
+/// 图纸尺寸（毫米）。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaperSize {
+    pub width_mm: f64,
+    pub height_mm: f64,
+}
+
+impl PaperSize {
+    pub const A0: PaperSize = PaperSize::new(841.0, 1189.0);
+    pub const A1: PaperSize = PaperSize::new(594.0, 841.0);
+    pub const A2: PaperSize = PaperSize::new(420.0, 594.0);
+    pub const A3: PaperSize = PaperSize::new(297.0, 420.0);
+    pub const A4: PaperSize = PaperSize::new(210.0, 297.0);
+    pub const LETTER: PaperSize = PaperSize::new(215.9, 279.4);
+
+    pub const fn new(width_mm: f64, height_mm: f64) -> Self {
+        Self {
+            width_mm,
+            height_mm,
+        }
+    }
+
+    /// 横向：长边水平。
+    pub fn landscape(self) -> Self {
+        if self.width_mm >= self.height_mm {
+            self
+        } else {
+            Self::new(self.height_mm, self.width_mm)
+        }
+    }
+
+    /// 纵向：长边竖直。
+    pub fn portrait(self) -> Self {
+        if self.width_mm <= self.height_mm {
+            self
+        } else {
+            Self::new(self.height_mm, self.width_mm)
+        }
+    }
+}
+
+/// 打印范围。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlotArea {
+    /// 图纸全部可见对象的范围。
+    Extents,
+    /// 模型空间中的矩形窗口，窗口外的对象被裁掉。
+    Window { min: Point2, max: Point2 },
+}
+
+/// 打印比例。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlotScale {
+    /// 缩放至可打印区域。
+    Fit,
+    /// `paper_mm` 毫米图纸对应 `drawing_units` 个图形单位，例如 1:100 为 `(1.0, 100.0)`。
+    Ratio { paper_mm: f64, drawing_units: f64 },
+}
+
+/// PDF 打印选项。
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfPlotOptions {
+    pub paper: PaperSize,
+    pub area: PlotArea,
+    pub scale: PlotScale,
+    /// 图纸四周不可打印的边距（毫米）。
+    pub margin_mm: f64,
+    /// 按对象线宽打印；关闭时统一使用默认线宽。
+    pub plot_lineweights: bool,
+    /// 单色打印：对象颜色统一为黑色，渐变转为灰度。
+    pub monochrome: bool,
+    /// 曲线离散精度，传给 FLATTEN 并用于填充边界。
+    pub tessellation: TessellateOptions,
+    /// 以 FlateDecode 压缩页面内容流。
+    pub compress: bool,
+}
+
+impl Default for PdfPlotOptions {
+    fn default() -> Self {
+        Self {
+            paper: PaperSize::A4.landscape(),
+            area: PlotArea::Extents,
+            scale: PlotScale::Fit,
+            margin_mm: 10.0,
+            plot_lineweights: true,
+            monochrome: false,
+            tessellation: TessellateOptions::default(),
+            compress: true,
+        }
+    }
+}
+
+/// PDF 打印导出器，实现 [`DocumentSaver`]。
+#[derive(Debug, Clone, Default)]
+pub struct PdfExporter {
+    options: PdfPlotOptions,
+}
+
+impl PdfExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(mut self, options: PdfPlotOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn options(&self) -> &PdfPlotOptions {
+        &self.options
+    }
+
+    /// 将文档打印为完整的 PDF 文件内容。
+    pub fn to_pdf(&self, document: &Document) -> Vec<u8> {
+        let flat = document.flatten(&FlattenOptions {
+            tessellation: self.options.tessellation,
+            keep_fills: true,
+            ..FlattenOptions::default()
+        });
+        let page =
+            DVec2::new(self.options.paper.width_mm, self.options.paper.height_mm) * POINTS_PER_MM;
+        let mut plot = PdfPlot::new(&flat, &self.options, page);
+        for (id, entity) in flat.display_list() {
+            plot.entity(*id, entity);
+        }
+        plot.content.push_str("Q\n");
+        assemble(&plot.content, &plot.shadings, page, self.options.compress)
+    }
+}
+
+impl DocumentSaver for PdfExporter {
+    fn save(&self, document: &Document, path: &Path) -> Result<(), IoError> {
+        fs::write(path, self.to_pdf(document)).map_err(|source| IoError::WriteError {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+struct PdfPlot<'a> {
+    document: &'a Document,
+    options: &'a PdfPlotOptions,
+    /// 每个图形单位对应的点数。
+    scale: f64,
+    /// 打印范围中心及其在页面上的位置。
+    area_center: DVec2,
+    page_center: DVec2,
+    content: String,
+    shadings: Vec<String>,
+    stroke: Option<u32>,
+    fill: Option<u32>,
+    line_width: Option<f64>,
+}
+
+impl<'a> PdfPlot<'a> {
+    fn new(document: &'a Document, options: &'a PdfPlotOptions, page: DVec2) -> Self {
+        let margin = options.margin_mm.max(0.0) * POINTS_PER_MM;
+        let printable = (page - DVec2::splat(2.0 * margin)).max(DVec2::ONE);
+        let (min, max) = match options.area {
+            PlotArea::Extents => drawing_extent(document).unwrap_or((DVec2::ZERO, DVec2::ZERO)),
+            PlotArea::Window { min, max } => {
+                let (min, max) = (min.as_vec2(), max.as_vec2());
+                (min.min(max), min.max(max))
+            }
+        };
+        let size = max - min;
+        let scale = match options.scale {
+            PlotScale::Ratio {
+                paper_mm,
+                drawing_units,
+            } if paper_mm > 0.0 && drawing_units > 0.0 => paper_mm / drawing_units * POINTS_PER_MM,
+            _ => match (size.x > f64::EPSILON, size.y > f64::EPSILON) {
+                (true, true) => (printable.x / size.x).min(printable.y / size.y),
+                (true, false) => printable.x / size.x,
+                (false, true) => printable.y / size.y,
+                (false, false) => 1.0,
+            },
+        };
+        let mut content = String::new();
+        // 裁剪到可打印区域；页面级状态统一放在一对 q/Q 中
+        let _ = writeln!(
+            content,
+            "q\n{} {} {} {} re W n\n1 J 1 j",
+            format_number(margin, PRECISION),
+            format_number(margin, PRECISION),
+            format_number(printable.x, PRECISION),
+            format_number(printable.y, PRECISION)
+        );
+        Self {
+            document,
+            options,
+            scale,
+            area_center: (min + max) * 0.5,
+            page_center: page * 0.5,
+            content,
+            shadings: Vec::new(),
+            stroke: None,
+            fill: None,
+            line_width: None,
+        }
+    }
+
+    fn entity(&mut self, id: EntityId, entity: &Entity) {
+        let properties = self.document.resolve_properties(id).unwrap_or_default();
+        let color = if self.options.monochrome {
+            0x000000
+        } else {
+            display_color(properties.color, Some(0xFFFFFF))
+        };
+        let width = self.line_width_of(properties.lineweight);
+
+        match entity {
+            Entity::Line(line) => {
+                self.set_stroke(color, width);
+                self.move_to(line.start.as_vec2());
+                self.line_to(line.end.as_vec2());
+                self.content.push_str("S\n");
+            }
+            Entity::Circle(circle) => {
+                self.set_stroke(color, width);
+                let center = circle.center.as_vec2();
+                let radius = circle.radius.abs();
+                self.move_to(center + DVec2::X * radius);
+                self.arc(center, radius, 0.0, TAU);
+                self.content.push_str("h S\n");
+            }
+            Entity::Arc(arc) => {
+                self.set_stroke(color, width);
+                let mut sweep = (arc.end_angle - arc.start_angle).rem_euclid(TAU);
+                if sweep <= 1e-12 {
+                    sweep = TAU;
+                }
+                let center = arc.center.as_vec2();
+                let radius = arc.radius.abs();
+                self.move_to(center + DVec2::from_angle(arc.start_angle) * radius);
+                self.arc(center, radius, arc.start_angle, sweep);
+                self.content.push_str("S\n");
+            }
+            Entity::Polyline(polyline) => {
+                if polyline.vertices.is_empty() {
+                    return;
+                }
+                self.set_stroke(color, width);
+                self.polyline_path(polyline);
+                self.content
+                    .push_str(if polyline.is_closed { "h S\n" } else { "S\n" });
+            }
+            Entity::Text(text) => self.text(text, color),
+            Entity::Hatch(hatch) => self.hatch(hatch, color, width),
+            Entity::Wipeout(wipeout) => self.wipeout(wipeout, color, width),
+            // FLATTEN 已将其余对象转换为上述图元
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &Text, color: u32) {
+        let size = text.height * self.scale;
+        if text.content.is_empty() || size <= 0.0 {
+            return;
+        }
+        self.set_fill(color);
+        let direction = DVec2::from_angle(text.rotation);
+        let x_axis = if text.is_backward {
+            -direction
+        } else {
+            direction
+        };
+        let y_axis = if text.is_upside_down {
+            -direction.perp()
+        } else {
+            direction.perp()
+        };
+        let insert = self.project(text.insert.as_vec2());
+        let _ = writeln!(
+            self.content,
+            "BT /F1 {} Tf {} {} {} {} {} {} Tm ({}) Tj ET",
+            self.number(size),
+            self.number(x_axis.x),
+            self.number(x_axis.y),
+            self.number(y_axis.x),
+            self.number(y_axis.y),
+            self.number(insert.x),
+            self.number(insert.y),
+            encode_text(&text.content)
+        );
+    }
+
+    fn hatch(&mut self, hatch: &Hatch, color: u32, width: f64) {
+        let loops = hatch
+            .loops
+            .iter()
+            .map(|loop_path| ring_points(loop_path, &self.options.tessellation))
+            .filter(|points| points.len() >= 3)
+            .collect::<Vec<_>>();
+        if loops.is_empty() {
+            return;
+        }
+
+        if let Some(gradient) = &hatch.gradient {
+            let axis = gradient_axis(gradient, &loops);
+            let (start_color, end_color) = if self.options.monochrome {
+                (gray(axis.start_color), gray(axis.end_color))
+            } else {
+                (axis.start_color, axis.end_color)
+            };
+            let start = self.project(axis.start);
+            let end = self.project(axis.end);
+            self.shadings.push(format!(
+                "<< /ShadingType 2 /ColorSpace /DeviceRGB /Coords [{} {} {} {}] /Function << /FunctionType 2 /Domain [0 1] /C0 [{}] /C1 [{}] /N 1 >> /Extend [true true] >>",
+                self.number(start.x),
+                self.number(start.y),
+                self.number(end.x),
+                self.number(end.y),
+                rgb_components(start_color),
+                rgb_components(end_color)
+            ));
+            self.content.push_str("q\n");
+            self.loops_path(&loops);
+            let _ = writeln!(self.content, "W* n /Sh{} sh\nQ", self.shadings.len());
+            return;
+        }
+        if hatch.is_solid {
+            self.set_fill(color);
+            self.loops_path(&loops);
+            self.content.push_str("f*\n");
+            return;
+        }
+        self.set_stroke(color, width);
+        if hatch.pattern_lines.is_empty() {
+            self.loops_path(&loops);
+            self.content.push_str("S\n");
+            return;
+        }
+        let (min, max) = extent(loops.iter().flatten().copied());
+        self.content.push_str("q\n");
+        self.loops_path(&loops);
+        self.content.push_str("W* n\n");
+        for line in &hatch.pattern_lines {
+            self.pattern_family(line, min, max);
+        }
+        self.content.push_str("Q\n");
+    }
+
+    fn pattern_family(&mut self, line: &HatchPatternLine, min: DVec2, max: DVec2) {
+        let dashes = dash_array(&line.dash_lengths);
+        let period = dashes
+            .as_ref()
+            .map(|dashes| dashes.iter().sum::<f64>())
+            .unwrap_or(0.0);
+        let strokes = pattern_strokes(line, min, max, period);
+        if strokes.is_empty() {
+            return;
+        }
+        let list = dashes.as_ref().map(|dashes| {
+            dashes
+                .iter()
+                .map(|dash| self.number(dash * self.scale))
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+        match &dashes {
+            None => self.content.push_str("[] 0 d\n"),
+            // 页面默认圆头；没有点时改用平头，保持虚线长度准确
+            Some(dashes) if !dashes.iter().step_by(2).any(|dash| *dash <= 0.0) => {
+                self.content.push_str("0 J\n");
+            }
+            Some(_) => {}
+        }
+        for stroke in strokes {
+            if let Some(list) = &list {
+                let _ = writeln!(
+                    self.content,
+                    "[{list}] {} d",
+                    self.number(stroke.phase * self.scale)
+                );
+            }
+            self.move_to(stroke.start);
+            self.line_to(stroke.end);
+            self.content.push_str("S\n");
+        }
+    }
+
+    fn wipeout(&mut self, wipeout: &Wipeout, color: u32, width: f64) {
+        let points = wipeout_outline(wipeout);
+        if points.len() < 3 {
+            return;
+        }
+        self.set_fill(0xFFFFFF);
+        self.loops_path(std::slice::from_ref(&points));
+        if self.document.wipeout_frame_plotted() {
+            self.set_stroke(color, width);
+            self.content.push_str("B\n");
+        } else {
+            self.content.push_str("f\n");
+        }
+    }
+
+    fn loops_path(&mut self, loops: &[Vec<DVec2>]) {
+        for points in loops {
+            self.move_to(points[0]);
+            for point in &points[1..] {
+                self.line_to(*point);
+            }
+            self.content.push_str("h\n");
+        }
+    }
+
+    fn polyline_path(&mut self, polyline: &Polyline) {
+        let vertices = &polyline.vertices;
+        self.move_to(vertices[0].position.as_vec2());
+        let segments = if polyline.is_closed {
+            vertices.len()
+        } else {
+            vertices.len() - 1
+        };
+        for index in 0..segments {
+            let current = &vertices[index];
+            let next = vertices[(index + 1) % vertices.len()].position.as_vec2();
+            match BulgeArc::new(current.position.as_vec2(), next, current.bulge) {
+                Some(arc) => self.arc(arc.center, arc.radius, arc.start_angle, arc.sweep),
+                None => self.line_to(next),
+            }
+        }
+    }
+
+    /// 圆弧以贝塞尔曲线写出，当前点须位于弧起点。
+    fn arc(&mut self, center: DVec2, radius: f64, start_angle: f64, sweep: f64) {
+        for [control1, control2, end] in arc_beziers(center, radius, start_angle, sweep) {
+            let control1 = self.project(control1);
+            let control2 = self.project(control2);
+            let end = self.project(end);
+            let _ = writeln!(
+                self.content,
+                "{} {} {} {} {} {} c",
+                self.number(control1.x),
+                self.number(control1.y),
+                self.number(control2.x),
+                self.number(control2.y),
+                self.number(end.x),
+                self.number(end.y)
+            );
+        }
+    }
+
+    fn move_to(&mut self, point: DVec2) {
+        let point = self.project(point);
+        let _ = writeln!(
+            self.content,
+            "{} {} m",
+            self.number(point.x),
+            self.number(point.y)
+        );
+    }
+
+    fn line_to(&mut self, point: DVec2) {
+        let point = self.project(point);
+        let _ = writeln!(
+            self.content,
+            "{} {} l",
+            self.number(point.x),
+            self.number(point.y)
+        );
+    }
+
+    fn set_stroke(&mut self, color: u32, width: f64) {
+        if self.stroke != Some(color) {
+            let _ = writeln!(self.content, "{} RG", rgb_components(color));
+            self.stroke = Some(color);
+        }
+        if self
+            .line_width
+            .is_none_or(|current| (current - width).abs() > 1e-9)
+        {
+            let _ = writeln!(self.content, "{} w", self.number(width));
+            self.line_width = Some(width);
+        }
+    }
+
+    fn set_fill(&mut self, color: u32) {
+        if self.fill != Some(color) {
+            let _ = writeln!(self.content, "{} rg", rgb_components(color));
+            self.fill = Some(color);
+        }
+    }
+
+    /// 线宽（点）：不随打印比例缩放。
+    fn line_width_of(&self, lineweight: LineWeight) -> f64 {
+        let millimeters = if self.options.plot_lineweights {
+            lineweight.millimeters().unwrap_or(DEFAULT_LINEWEIGHT_MM)
+        } else {
+            DEFAULT_LINEWEIGHT_MM
+        };
+        millimeters * POINTS_PER_MM
+    }
+
+    fn project(&self, point: DVec2) -> DVec2 {
+        self.page_center + (point - self.area_center) * self.scale
+    }
+
+    fn number(&self, value: f64) -> String {
+        format_number(value, PRECISION)
+    }
+}
+
+/// 组装单页 PDF：目录、页树、页面、字体与内容流，附交叉引用表。
+fn assemble(content: &str, shadings: &[String], page: DVec2, compress: bool) -> Vec<u8> {
+    let (stream, filter) = if compress {
+        (
+            miniz_oxide::deflate::compress_to_vec_zlib(content.as_bytes(), 6),
+            " /Filter /FlateDecode",
+        )
+    } else {
+        (content.as_bytes().to_vec(), "")
+    };
+    let shading_resources = if shadings.is_empty() {
+        String::new()
+    } else {
+        let entries = shadings
+            .iter()
+            .enumerate()
+            .map(|(index, shading)| format!("/Sh{} {shading}", index + 1))
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!(" /Shading << {entries} >>")
+    };
+    let objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 4 0 R >>{shading_resources} >> /Contents 5 0 R >>",
+            format_number(page.x, PRECISION),
+            format_number(page.y, PRECISION)
+        )
+        .into_bytes(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_vec(),
+        [
+            format!("<< /Length {}{filter} >>\nstream\n", stream.len()).into_bytes(),
+            stream,
+            b"\nendstream".to_vec(),
+        ]
+        .concat(),
+    ];
+
+    let mut out = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        out.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+        out.extend_from_slice(object);
+        out.extend_from_slice(b"\nendobj\n");
+    }
+    let xref = out.len();
+    let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(table, "{offset:010} 00000 n ");
+    }
+    let _ = write!(
+        table,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+        objects.len() + 1
+    );
+    out.extend_from_slice(table.as_bytes());
+    out
+}
+
+fn rgb_components(rgb: u32) -> String {
+    let channel = |shift: u32| format_number(f64::from((rgb >> shift) & 0xFF) / 255.0, 4);
+    format!("{} {} {}", channel(16), channel(8), channel(0))
+}
+
+fn gray(rgb: u32) -> u32 {
+    let channel = |shift: u32| f64::from((rgb >> shift) & 0xFF);
+    let level = (0.299 * channel(16) + 0.587 * channel(8) + 0.114 * channel(0)).round() as u32;
+    (level << 16) | (level << 8) | level
+}
+
+/// 编码为 PDF 字面字符串：转义括号与反斜杠，Latin-1 补充字符以八进制写出。
+fn encode_text(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '(' | ')' | '\\' => {
+                encoded.push('\\');
+                encoded.push(ch);
+            }
+            ' '..='~' => encoded.push(ch),
+            '\u{A0}'..='\u{FF}' => {
+                let _ = write!(encoded, "\\{:03o}", ch as u32);
+            }
+            _ => encoded.push('?'),
+        }
+    }
+    encoded
+}
@@ -0,0 +1,311 @@
+//! 矢量导出共用的图纸范围、凸度弧、填充图案与颜色换算。
+
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use glam::DVec2;
+use zcad_core::document::{
+    Document, Entity, HatchGradient, HatchLoop, HatchPatternLine, ObjectColor, RasterImageClip,
+    Wipeout,
+};
+use zcad_core::geometry::BulgeArc;
+use zcad_core::tessellate::{self, TessellateOptions};
+
+/// 线宽为默认值（LWDEFAULT）时使用的毫米数。
+pub(crate) const DEFAULT_LINEWEIGHT_MM: f64 = 0.25;
+/// 文字宽度估算系数（相对字高），与 FLATTEN 一致。
+pub(crate) const TEXT_WIDTH_FACTOR: f64 = 0.6;
+/// 单组图案最多展开的线条数，避免极小间距导致输出膨胀。
+const MAX_PATTERN_LINES: usize = 20_000;
+
+/// 图纸范围：在实体范围的基础上补入凸度弧的外凸部分与按字宽估算的文字框，
+/// 避免两者被画布裁掉。
+pub(crate) fn drawing_extent(document: &Document) -> Option<(DVec2, DVec2)> {
+    let bounds = document.bounds()?;
+    let mut points = vec![bounds.min().as_vec2(), bounds.max().as_vec2()];
+    for (_, entity) in document.entities() {
+        match entity {
+            Entity::Polyline(polyline) => {
+                let vertices = &polyline.vertices;
+                for (index, vertex) in vertices.iter().enumerate() {
+                    let Some(next) = vertices
+                        .get(index + 1)
+                        .or_else(|| polyline.is_closed.then(|| &vertices[0]))
+                    else {
+                        continue;
+                    };
+                    points.extend(bulge_extremes(
+                        vertex.position.as_vec2(),
+                        next.position.as_vec2(),
+                        vertex.bulge,
+                    ));
+                }
+            }
+            Entity::Text(text) => {
+                let direction = DVec2::from_angle(text.rotation);
+                let width = direction
+                    * TEXT_WIDTH_FACTOR
+                    * text.height
+                    * text.content.chars().count() as f64;
+                let height = direction.perp() * text.height;
+                let insert = text.insert.as_vec2();
+                points.extend([insert + width, insert + height, insert + width + height]);
+            }
+            _ => {}
+        }
+    }
+    Some(extent(points.into_iter()))
+}
+
+/// 凸度弧段落在坐标轴方向上的极值点与弧中点。
+pub(crate) fn bulge_extremes(start: DVec2, end: DVec2, bulge: f64) -> Vec<DVec2> {
+    let Some(arc) = BulgeArc::new(start, end, bulge) else {
+        return Vec::new();
+    };
+    let mut points = vec![arc.point(0.5)];
+    for quadrant in 0..4 {
+        let angle = f64::from(quadrant) * FRAC_PI_2;
+        let offset = if arc.sweep >= 0.0 {
+            (angle - arc.start_angle).rem_euclid(TAU)
+        } else {
+            (arc.start_angle - angle).rem_euclid(TAU)
+        };
+        if offset <= arc.sweep.abs() {
+            points.push(arc.center + DVec2::from_angle(angle) * arc.radius);
+        }
+    }
+    points
+}
+
+/// 压平后的曲线图元按离散精度转为点列，闭合的圆与多段线不重复起点；其他图元返回空列表。
+pub(crate) fn curve_points(entity: &Entity, options: &TessellateOptions) -> Vec<DVec2> {
+    let Some(points) = tessellate::curve(entity, options) else {
+        return Vec::new();
+    };
+    let mut points: Vec<DVec2> = points.iter().map(|point| point.as_vec2()).collect();
+    let closed = match entity {
+        Entity::Circle(_) => true,
+        Entity::Polyline(polyline) => polyline.is_closed,
+        _ => false,
+    };
+    if closed && points.len() > 1 {
+        points.pop();
+    }
+    points
+}
+
+/// 以每段不超过 90° 的三次贝塞尔曲线逼近圆弧，返回各段的两个控制点与终点。
+pub(crate) fn arc_beziers(
+    center: DVec2,
+    radius: f64,
+    start_angle: f64,
+    sweep: f64,
+) -> Vec<[DVec2; 3]> {
+    let pieces = (sweep.abs() / FRAC_PI_2).ceil().max(1.0);
+    let step = sweep / pieces;
+    let handle = 4.0 / 3.0 * (step / 4.0).tan() * radius;
+    (0..pieces as usize)
+        .map(|index| {
+            let from = start_angle + step * index as f64;
+            let start = DVec2::from_angle(from);
+            let end = DVec2::from_angle(from + step);
+            [
+                center + start * radius + start.perp() * handle,
+                center + end * radius - end.perp() * handle,
+                center + end * radius,
+            ]
+        })
+        .collect()
+}
+
+/// 渐变颜色沿用前端约定：不超过 255 的值为 ACI，其余为真彩色。
+pub(crate) fn gradient_color(raw: Option<u32>) -> Option<u32> {
+    match raw? {
+        0 => None,
+        index @ 1..=255 => ObjectColor::Index(index as u8).rgb(),
+        rgb => Some(rgb & 0xFF_FFFF),
+    }
+}
+
+/// DXF 虚线长度（正为实线、负为空白、0 为点）转换为实/空交替的虚线列表；
+/// 全为实线时返回 `None`。
+pub(crate) fn dash_array(lengths: &[f64]) -> Option<Vec<f64>> {
+    if lengths.iter().all(|length| *length > 0.0) {
+        return None;
+    }
+    let mut dashes: Vec<f64> = Vec::new();
+    for length in lengths {
+        let is_gap = *length < 0.0;
+        let expects_gap = dashes.len() % 2 == 1;
+        if is_gap != expects_gap {
+            if dashes.is_empty() {
+                dashes.push(0.0);
+            } else {
+                *dashes.last_mut().unwrap() += length.abs();
+                continue;
+            }
+        }
+        dashes.push(length.abs());
+    }
+    if dashes.len() % 2 == 1 {
+        dashes.push(0.0);
+    }
+    Some(dashes)
+}
+
+/// 填充边界环按离散精度转为点列，末点不重复首点。
+pub(crate) fn ring_points(loop_path: &HatchLoop, options: &TessellateOptions) -> Vec<DVec2> {
+    tessellate::hatch_loop(loop_path, options)
+        .iter()
+        .map(|point| point.as_vec2())
+        .collect()
+}
+
+pub(crate) fn wipeout_outline(wipeout: &Wipeout) -> Vec<DVec2> {
+    let size = wipeout.image_size;
+    let local = match &wipeout.clip {
+        Some(RasterImageClip::Rectangle { min, max, .. }) => vec![
+            min.as_vec2(),
+            DVec2::new(max.x(), min.y()),
+            max.as_vec2(),
+            DVec2::new(min.x(), max.y()),
+        ],
+        Some(RasterImageClip::Polygon { vertices, .. }) => {
+            vertices.iter().map(|vertex| vertex.as_vec2()).collect()
+        }
+        None => vec![
+            DVec2::ZERO,
+            DVec2::new(size.x(), 0.0),
+            DVec2::new(size.x(), size.y()),
+            DVec2::new(0.0, size.y()),
+        ],
+    };
+    let origin = wipeout.insert.as_vec2();
+    let u = wipeout.u_vector.as_vec2();
+    let v = wipeout.v_vector.as_vec2();
+    local
+        .into_iter()
+        .map(|point| origin + u * point.x + v * point.y)
+        .collect()
+}
+
+pub(crate) fn extent(points: impl Iterator<Item = DVec2>) -> (DVec2, DVec2) {
+    points.fold(
+        (DVec2::splat(f64::MAX), DVec2::splat(f64::MIN)),
+        |(min, max), point| (min.min(point), max.max(point)),
+    )
+}
+
+pub(crate) fn luminance(rgb: u32) -> f64 {
+    let channel = |shift: u32| f64::from((rgb >> shift) & 0xFF);
+    0.299 * channel(16) + 0.587 * channel(8) + 0.114 * channel(0)
+}
+
+/// 一条图案线在填充范围内的线段；`phase` 为起点处的虚线相位。
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PatternStroke {
+    pub start: DVec2,
+    pub end: DVec2,
+    pub phase: f64,
+}
+
+/// 在 `min`..`max` 范围内展开一组平行图案线，超出之处由调用方按边界裁剪。
+/// 线条数超过上限时放弃该组，返回空列表。
+pub(crate) fn pattern_strokes(
+    line: &HatchPatternLine,
+    min: DVec2,
+    max: DVec2,
+    period: f64,
+) -> Vec<PatternStroke> {
+    let direction = DVec2::from_angle(line.angle);
+    let normal = direction.perp();
+    let offset = line.offset.as_vec2();
+    let spacing = offset.dot(normal);
+    if spacing.abs() <= 1e-9 {
+        return Vec::new();
+    }
+    let base = line.base_point.as_vec2();
+    let corners = [min, DVec2::new(max.x, min.y), max, DVec2::new(min.x, max.y)];
+    let (low, high) = project_range(&corners, |corner| (corner - base).dot(normal) / spacing);
+    let (first, last) = (low.floor() as i64, high.ceil() as i64);
+    if (last - first) as usize > MAX_PATTERN_LINES {
+        return Vec::new();
+    }
+    (first..=last)
+        .map(|index| {
+            let origin = base + offset * index as f64;
+            let (start, end) = project_range(&corners, |corner| (corner - origin).dot(direction));
+            PatternStroke {
+                start: origin + direction * start,
+                end: origin + direction * end,
+                phase: if period > 0.0 {
+                    start.rem_euclid(period)
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect()
+}
+
+/// 渐变填充的起止点（世界坐标）与两端颜色。
+pub(crate) struct GradientAxis {
+    pub start: DVec2,
+    pub end: DVec2,
+    pub start_color: u32,
+    pub end_color: u32,
+}
+
+/// 沿渐变角度覆盖全部边界的轴线，颜色缺省时取浅灰。
+pub(crate) fn gradient_axis(gradient: &HatchGradient, loops: &[Vec<DVec2>]) -> GradientAxis {
+    let direction = DVec2::from_angle(gradient.angle);
+    let points = loops.iter().flatten().copied().collect::<Vec<_>>();
+    let (low, high) = project_range(&points, |point| point.dot(direction));
+    let (min, max) = extent(points.iter().copied());
+    let center = (min + max) * 0.5;
+    let middle = center.dot(direction);
+    let start_color = gradient_color(gradient.color1).unwrap_or(0xCCCCCC);
+    let end_color = if gradient.is_single_color {
+        start_color
+    } else {
+        gradient_color(gradient.color2).unwrap_or(start_color)
+    };
+    GradientAxis {
+        start: center + direction * (low - middle),
+        end: center + direction * (high - middle),
+        start_color,
+        end_color,
+    }
+}
+
+/// 具体颜色转为 RGB；7 号色随背景明暗取黑或白，与 CAD 打印习惯一致。
+pub(crate) fn display_color(color: ObjectColor, background: Option<u32>) -> u32 {
+    match color {
+        ObjectColor::Index(7) => {
+            if background.is_some_and(|background| luminance(background) < 128.0) {
+                0xFFFFFF
+            } else {
+                0x000000
+            }
+        }
+        color => color.rgb().unwrap_or(0x000000),
+    }
+}
+
+fn project_range(points: &[DVec2], project: impl Fn(DVec2) -> f64) -> (f64, f64) {
+    points
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(low, high), point| {
+            let value = project(*point);
+            (low.min(value), high.max(value))
+        })
+}
+
+/// 按位数格式化并去掉多余的零，`-0` 统一为 `0`。
+pub(crate) fn format_number(value: f64, precision: usize) -> String {
+    let mut text = format!("{value:.precision$}");
+    if text.contains('.') {
+        let trimmed = text.trim_end_matches('0').trim_end_matches('.').len();
+        text.truncate(trimmed);
+    }
+    if text == "-0" { "0".to_string() } else { text }
+}
@@ -0,0 +1,555 @@
+//! 光栅导出：将文档按视口渲染为指定像素尺寸的 PNG，供命令行导出与缩略图生成使用。
+//!
+//! 文档经 FLATTEN（文字转为外框、保留填充与区域覆盖）后由 tiny-skia 光栅化，
+//! 支持抗锯齿与可配置的背景色（含透明背景）。线宽默认统一为 1 像素，
+//! 开启按线宽出图时以 96 DPI 将对象线宽换算为像素。
+
+use std::f64::consts::TAU;
+use std::fs;
+use std::path::Path;
+
+use glam::DVec2;
+use tiny_skia::{
+    BlendMode, Color, FillRule, GradientStop, LineCap, LineJoin, LinearGradient, Mask, Paint,
+    PathBuilder, Pixmap, Point, Shader, SpreadMode, Stroke, StrokeDash, Transform,
+};
+use zcad_core::document::{
+    Document, Entity, EntityId, FlattenOptions, Hatch, HatchPatternLine, LineWeight, Polyline,
+    Wipeout,
+};
+use zcad_core::geometry::BulgeArc;
+use zcad_core::tessellate::TessellateOptions;
+
+use crate::pdf::PlotArea;
+use crate::plot::{
+    DEFAULT_LINEWEIGHT_MM, arc_beziers, dash_array, display_color, drawing_extent, extent,
+    gradient_axis, pattern_strokes, ring_points, wipeout_outline,
+};
+use crate::{DocumentSaver, IoError};
+
+/// 每毫米对应的像素数（96 DPI）。
+const PIXELS_PER_MM: f64 = 96.0 / 25.4;
+
+/// 光栅导出选项。
+#[derive(Debug, Clone, PartialEq)]
+pub struct RasterExportOptions {
+    pub width: u32,
+    pub height: u32,
+    /// 导出范围：图纸范围或模型空间窗口，窗口外的对象被裁掉。
+    pub area: PlotArea,
+    /// 导出范围到图像边缘的留白（像素）。
+    pub margin: u32,
+    /// 背景色 RGBA，alpha 为 0 时输出透明背景。
+    pub background: [u8; 4],
+    pub anti_alias: bool,
+    /// 按对象线宽出图；关闭时所有线条为 1 像素。
+    pub plot_lineweights: bool,
+    /// 曲线离散精度，传给 FLATTEN 并用于填充边界。
+    pub tessellation: TessellateOptions,
+}
+
+impl RasterExportOptions {
+    /// 正方形缩略图：较小的留白，曲线每段圆心角放宽到整圆的 1/32。
+    pub fn thumbnail(size: u32) -> Self {
+        Self {
+            width: size,
+            height: size,
+            margin: (size / 32).max(1),
+            tessellation: TessellateOptions {
+                max_angle: TAU / 32.0,
+                ..TessellateOptions::default()
+            },
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for RasterExportOptions {
+    fn default() -> Self {
+        Self {
+            width: 1024,
+            height: 768,
+            area: PlotArea::Extents,
+            margin: 8,
+            background: [255, 255, 255, 255],
+            anti_alias: true,
+            plot_lineweights: false,
+            tessellation: TessellateOptions::default(),
+        }
+    }
+}
+
+/// 渲染结果：逐行排列的非预乘 RGBA 像素。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RasterImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl RasterImage {
+    /// 读取 `(x, y)` 处的 RGBA，越界时返回 `None`。
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let offset = (y as usize * self.width as usize + x as usize) * 4;
+        self.pixels[offset..offset + 4].try_into().ok()
+    }
+
+    /// 编码为 PNG 文件内容。
+    pub fn to_png(&self) -> Result<Vec<u8>, IoError> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.pixels))
+            .map_err(|err| IoError::InvalidDocument(format!("PNG 编码失败: {err}")))?;
+        Ok(bytes)
+    }
+}
+
+/// PNG 导出器，实现 [`DocumentSaver`]。
+#[derive(Debug, Clone, Default)]
+pub struct PngExporter {
+    options: RasterExportOptions,
+}
+
+impl PngExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(mut self, options: RasterExportOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn options(&self) -> &RasterExportOptions {
+        &self.options
+    }
+
+    /// 将文档渲染为 RGBA 图像；宽高为 0 时按 1 像素处理。
+    pub fn render(&self, document: &Document) -> RasterImage {
+        let width = self.options.width.max(1);
+        let height = self.options.height.max(1);
+        let mut pixmap = Pixmap::new(width, height).expect("图像尺寸不为零");
+        let [red, green, blue, alpha] = self.options.background;
+        pixmap.fill(Color::from_rgba8(red, green, blue, alpha));
+
+        let flat = document.flatten(&FlattenOptions {
+            tessellation: self.options.tessellation,
+            outline_text: true,
+            keep_fills: true,
+            ..FlattenOptions::default()
+        });
+        let mut raster = Raster::new(&flat, &self.options, &mut pixmap);
+        for (id, entity) in flat.display_list() {
+            raster.entity(*id, entity);
+        }
+
+        let pixels = pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect();
+        RasterImage {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// 将文档渲染并编码为 PNG 文件内容。
+    pub fn to_png(&self, document: &Document) -> Result<Vec<u8>, IoError> {
+        self.render(document).to_png()
+    }
+}
+
+impl DocumentSaver for PngExporter {
+    fn save(&self, document: &Document, path: &Path) -> Result<(), IoError> {
+        let bytes = self.to_png(document)?;
+        fs::write(path, bytes).map_err(|source| IoError::WriteError {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+struct Raster<'a> {
+    document: &'a Document,
+    options: &'a RasterExportOptions,
+    pixmap: &'a mut Pixmap,
+    /// 每个图形单位对应的像素数。
+    scale: f64,
+    area_center: DVec2,
+    image_center: DVec2,
+    /// 窗口导出时的裁剪蒙版。
+    clip: Option<Mask>,
+    /// 背景色（RGB），不透明度过低时视为无背景。
+    background: Option<u32>,
+}
+
+impl<'a> Raster<'a> {
+    fn new(
+        document: &'a Document,
+        options: &'a RasterExportOptions,
+        pixmap: &'a mut Pixmap,
+    ) -> Self {
+        let image = DVec2::new(f64::from(pixmap.width()), f64::from(pixmap.height()));
+        let margin = f64::from(options.margin);
+        let available = (image - DVec2::splat(2.0 * margin)).max(DVec2::ONE);
+        let (min, max) = match options.area {
+            PlotArea::Extents => drawing_extent(document).unwrap_or((DVec2::ZERO, DVec2::ZERO)),
+            PlotArea::Window { min, max } => {
+                let (min, max) = (min.as_vec2(), max.as_vec2());
+                (min.min(max), min.max(max))
+            }
+        };
+        let size = max - min;
+        let scale = match (size.x > f64::EPSILON, size.y > f64::EPSILON) {
+            (true, true) => (available.x / size.x).min(available.y / size.y),
+            (true, false) => available.x / size.x,
+            (false, true) => available.y / size.y,
+            (false, false) => 1.0,
+        };
+        let [red, green, blue, alpha] = options.background;
+        let mut raster = Self {
+            document,
+            options,
+            pixmap,
+            scale,
+            area_center: (min + max) * 0.5,
+            image_center: image * 0.5,
+            clip: None,
+            background: (alpha >= 128)
+                .then(|| u32::from(red) << 16 | u32::from(green) << 8 | u32::from(blue)),
+        };
+        if matches!(options.area, PlotArea::Window { .. }) {
+            let corners = [min, DVec2::new(max.x, min.y), max, DVec2::new(min.x, max.y)];
+            raster.clip = raster
+                .polygon_path(std::slice::from_ref(&corners.to_vec()))
+                .and_then(|path| {
+                    let mut mask = Mask::new(raster.pixmap.width(), raster.pixmap.height())?;
+                    mask.fill_path(&path, FillRule::Winding, false, Transform::identity());
+                    Some(mask)
+                });
+        }
+        raster
+    }
+
+    fn entity(&mut self, id: EntityId, entity: &Entity) {
+        let properties = self.document.resolve_properties(id).unwrap_or_default();
+        let color = display_color(properties.color, self.background);
+        let width = self.line_width_of(properties.lineweight);
+
+        let mut builder = PathBuilder::new();
+        match entity {
+            Entity::Line(line) => {
+                self.move_to(&mut builder, line.start.as_vec2());
+                self.line_to(&mut builder, line.end.as_vec2());
+            }
+            Entity::Circle(circle) => {
+                let center = circle.center.as_vec2();
+                let radius = circle.radius.abs();
+                self.move_to(&mut builder, center + DVec2::X * radius);
+                self.arc(&mut builder, center, radius, 0.0, TAU);
+                builder.close();
+            }
+            Entity::Arc(arc) => {
+                let mut sweep = (arc.end_angle - arc.start_angle).rem_euclid(TAU);
+                if sweep <= 1e-12 {
+                    sweep = TAU;
+                }
+                let center = arc.center.as_vec2();
+                let radius = arc.radius.abs();
+                self.move_to(
+                    &mut builder,
+                    center + DVec2::from_angle(arc.start_angle) * radius,
+                );
+                self.arc(&mut builder, center, radius, arc.start_angle, sweep);
+            }
+            Entity::Polyline(polyline) => {
+                if polyline.vertices.is_empty() {
+                    return;
+                }
+                self.polyline_path(&mut builder, polyline);
+            }
+            Entity::Hatch(hatch) => return self.hatch(hatch, color, width),
+            Entity::Wipeout(wipeout) => return self.wipeout(wipeout, color, width),
+            // FLATTEN 已将文字转为外框，其余对象转换为上述图元
+            _ => return,
+        }
+        if let Some(path) = builder.finish() {
+            let stroke = self.stroke(width, LineCap::Round, None);
+            let paint = self.paint(color);
+            self.pixmap.stroke_path(
+                &path,
+                &paint,
+                &stroke,
+                Transform::identity(),
+                self.clip.as_ref(),
+            );
+        }
+    }
+
+    fn hatch(&mut self, hatch: &Hatch, color: u32, width: f64) {
+        let loops = hatch
+            .loops
+            .iter()
+            .map(|loop_path| ring_points(loop_path, &self.options.tessellation))
+            .filter(|points| points.len() >= 3)
+            .collect::<Vec<_>>();
+        let Some(boundary) = self.polygon_path(&loops) else {
+            return;
+        };
+
+        if let Some(gradient) = &hatch.gradient {
+            let axis = gradient_axis(gradient, &loops);
+            let start = self.point(axis.start);
+            let end = self.point(axis.end);
+            let shader = LinearGradient::new(
+                start,
+                end,
+                vec![
+                    GradientStop::new(0.0, rgb_color(axis.start_color)),
+                    GradientStop::new(1.0, rgb_color(axis.end_color)),
+                ],
+                SpreadMode::Pad,
+                Transform::identity(),
+            )
+            .unwrap_or(Shader::SolidColor(rgb_color(axis.start_color)));
+            let paint = Paint {
+                shader,
+                anti_alias: self.options.anti_alias,
+                ..Paint::default()
+            };
+            self.pixmap.fill_path(
+                &boundary,
+                &paint,
+                FillRule::EvenOdd,
+                Transform::identity(),
+                self.clip.as_ref(),
+            );
+            return;
+        }
+        if hatch.is_solid {
+            let paint = self.paint(color);
+            self.pixmap.fill_path(
+                &boundary,
+                &paint,
+                FillRule::EvenOdd,
+                Transform::identity(),
+                self.clip.as_ref(),
+            );
+            return;
+        }
+        if hatch.pattern_lines.is_empty() {
+            let stroke = self.stroke(width, LineCap::Round, None);
+            let paint = self.paint(color);
+            self.pixmap.stroke_path(
+                &boundary,
+                &paint,
+                &stroke,
+                Transform::identity(),
+                self.clip.as_ref(),
+            );
+            return;
+        }
+        let Some(mut mask) = self.clip.clone().or_else(|| {
+            let mut mask = Mask::new(self.pixmap.width(), self.pixmap.height())?;
+            mask.invert();
+            Some(mask)
+        }) else {
+            return;
+        };
+        mask.intersect_path(
+            &boundary,
+            FillRule::EvenOdd,
+            self.options.anti_alias,
+            Transform::identity(),
+        );
+        let (min, max) = extent(loops.iter().flatten().copied());
+        for line in &hatch.pattern_lines {
+            self.pattern_family(line, min, max, color, width, &mask);
+        }
+    }
+
+    fn pattern_family(
+        &mut self,
+        line: &HatchPatternLine,
+        min: DVec2,
+        max: DVec2,
+        color: u32,
+        width: f64,
+        mask: &Mask,
+    ) {
+        let dashes = dash_array(&line.dash_lengths);
+        let period = dashes
+            .as_ref()
+            .map(|dashes| dashes.iter().sum::<f64>())
+            .unwrap_or(0.0);
+        let strokes = pattern_strokes(line, min, max, period);
+        // 没有点时使用平头，保持虚线长度准确
+        let cap = match &dashes {
+            Some(dashes) if dashes.iter().step_by(2).any(|dash| *dash <= 0.0) => LineCap::Round,
+            _ => LineCap::Butt,
+        };
+        let paint = self.paint(color);
+        for pattern in strokes {
+            let dash = dashes.as_ref().and_then(|dashes| {
+                StrokeDash::new(
+                    dashes
+                        .iter()
+                        .map(|dash| (dash * self.scale) as f32)
+                        .collect(),
+                    (pattern.phase * self.scale) as f32,
+                )
+            });
+            let mut builder = PathBuilder::new();
+            self.move_to(&mut builder, pattern.start);
+            self.line_to(&mut builder, pattern.end);
+            let Some(path) = builder.finish() else {
+                continue;
+            };
+            let stroke = self.stroke(width, cap, dash);
+            self.pixmap
+                .stroke_path(&path, &paint, &stroke, Transform::identity(), Some(mask));
+        }
+    }
+
+    /// 区域覆盖以背景色直接替换像素，透明背景下同样挖空其下的对象。
+    fn wipeout(&mut self, wipeout: &Wipeout, color: u32, width: f64) {
+        let points = wipeout_outline(wipeout);
+        let Some(path) = self.polygon_path(std::slice::from_ref(&points)) else {
+            return;
+        };
+        let [red, green, blue, alpha] = self.options.background;
+        let mut fill = Paint::default();
+        fill.set_color_rgba8(red, green, blue, alpha);
+        fill.anti_alias = self.options.anti_alias;
+        fill.blend_mode = BlendMode::Source;
+        self.pixmap.fill_path(
+            &path,
+            &fill,
+            FillRule::Winding,
+            Transform::identity(),
+            self.clip.as_ref(),
+        );
+        if self.document.wipeout_frame_plotted() {
+            let stroke = self.stroke(width, LineCap::Round, None);
+            let paint = self.paint(color);
+            self.pixmap.stroke_path(
+                &path,
+                &paint,
+                &stroke,
+                Transform::identity(),
+                self.clip.as_ref(),
+            );
+        }
+    }
+
+    fn polygon_path(&self, loops: &[Vec<DVec2>]) -> Option<tiny_skia::Path> {
+        let mut builder = PathBuilder::new();
+        for points in loops.iter().filter(|points| points.len() >= 3) {
+            self.move_to(&mut builder, points[0]);
+            for point in &points[1..] {
+                self.line_to(&mut builder, *point);
+            }
+            builder.close();
+        }
+        builder.finish()
+    }
+
+    fn polyline_path(&self, builder: &mut PathBuilder, polyline: &Polyline) {
+        let vertices = &polyline.vertices;
+        self.move_to(builder, vertices[0].position.as_vec2());
+        let segments = if polyline.is_closed {
+            vertices.len()
+        } else {
+            vertices.len() - 1
+        };
+        for index in 0..segments {
+            let current = &vertices[index];
+            let next = vertices[(index + 1) % vertices.len()].position.as_vec2();
+            match BulgeArc::new(current.position.as_vec2(), next, current.bulge) {
+                Some(arc) => self.arc(builder, arc.center, arc.radius, arc.start_angle, arc.sweep),
+                None => self.line_to(builder, next),
+            }
+        }
+        if polyline.is_closed {
+            builder.close();
+        }
+    }
+
+    fn arc(
+        &self,
+        builder: &mut PathBuilder,
+        center: DVec2,
+        radius: f64,
+        start_angle: f64,
+        sweep: f64,
+    ) {
+        for [control1, control2, end] in arc_beziers(center, radius, start_angle, sweep) {
+            let control1 = self.point(control1);
+            let control2 = self.point(control2);
+            let end = self.point(end);
+            builder.cubic_to(control1.x, control1.y, control2.x, control2.y, end.x, end.y);
+        }
+    }
+
+    fn move_to(&self, builder: &mut PathBuilder, point: DVec2) {
+        let point = self.point(point);
+        builder.move_to(point.x, point.y);
+    }
+
+    fn line_to(&self, builder: &mut PathBuilder, point: DVec2) {
+        let point = self.point(point);
+        builder.line_to(point.x, point.y);
+    }
+
+    fn paint(&self, color: u32) -> Paint<'static> {
+        Paint {
+            shader: Shader::SolidColor(rgb_color(color)),
+            anti_alias: self.options.anti_alias,
+            ..Paint::default()
+        }
+    }
+
+    fn stroke(&self, width: f64, line_cap: LineCap, dash: Option<StrokeDash>) -> Stroke {
+        Stroke {
+            width: width as f32,
+            line_cap,
+            line_join: LineJoin::Round,
+            dash,
+            ..Stroke::default()
+        }
+    }
+
+    /// 线宽（像素）：不随缩放变化，至少 1 像素。
+    fn line_width_of(&self, lineweight: LineWeight) -> f64 {
+        if !self.options.plot_lineweights {
+            return 1.0;
+        }
+        let millimeters = lineweight.millimeters().unwrap_or(DEFAULT_LINEWEIGHT_MM);
+        (millimeters * PIXELS_PER_MM).max(1.0)
+    }
+
+    /// 图形坐标转为像素坐标（Y 轴向下）。
+    fn point(&self, point: DVec2) -> Point {
+        let offset = (point - self.area_center) * self.scale;
+        Point::from_xy(
+            (self.image_center.x + offset.x) as f32,
+            (self.image_center.y - offset.y) as f32,
+        )
+    }
+}
+
+fn rgb_color(rgb: u32) -> Color {
+    Color::from_rgba8((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8, 255)
+}
@@ -0,0 +1,613 @@
+//! SVG 导出：文档经 FLATTEN（保留填充与区域覆盖）后输出为矢量图形，
+//! 便于嵌入网页与报告。
+//!
+//! 每个可见图层对应一个 `<g>` 分组，组上记录随层颜色与线宽，特性被覆盖的对象
+//! 单独写出样式；圆弧与凸度段保留为 SVG 弧线，椭圆与样条按离散精度转为折线。
+//! 分组按图层首次出现的顺序排列，跨图层的区域覆盖只遮挡同组内更早的对象。
+
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use glam::DVec2;
+use zcad_core::document::{
+    Document, Entity, EntityId, FlattenOptions, Hatch, HatchGradient, HatchPatternLine, LineWeight,
+    ObjectColor, Polyline, Text, Wipeout,
+};
+use zcad_core::geometry::{BulgeArc, Point2};
+use zcad_core::tessellate::TessellateOptions;
+
+use crate::pdf::PlotArea;
+use crate::plot::{
+    DEFAULT_LINEWEIGHT_MM, dash_array, display_color, drawing_extent, extent, format_number,
+    gradient_axis, pattern_strokes, ring_points, wipeout_outline,
+};
+use crate::{DocumentSaver, IoError};
+
+/// 屏幕像素与毫米的换算（96 DPI）。
+const PIXELS_PER_MM: f64 = 96.0 / 25.4;
+
+/// SVG 导出选项。
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgExportOptions {
+    /// 曲线离散精度，传给 FLATTEN 并用于填充边界。
+    pub tessellation: TessellateOptions,
+    /// 导出范围：图纸范围或模型空间窗口，窗口外的对象不输出。
+    pub area: PlotArea,
+    /// 图纸范围四周的留白，按范围较长边的比例计算；窗口范围不加留白。
+    pub margin: f64,
+    /// 背景色 `0xRRGGBB`；`None` 时背景透明，区域覆盖按白色遮挡。
+    pub background: Option<u32>,
+    /// 根元素的 `width` 属性（像素），高度按纵横比换算；`None` 时只写 `viewBox`。
+    pub width: Option<f64>,
+    /// 坐标保留的小数位数。
+    pub precision: usize,
+    pub font_family: String,
+}
+
+impl Default for SvgExportOptions {
+    fn default() -> Self {
+        Self {
+            tessellation: TessellateOptions::default(),
+            area: PlotArea::Extents,
+            margin: 0.02,
+            background: None,
+            width: None,
+            precision: 4,
+            font_family: "sans-serif".to_string(),
+        }
+    }
+}
+
+/// SVG 导出器，实现 [`DocumentSaver`]。
+#[derive(Debug, Clone, Default)]
+pub struct SvgExporter {
+    options: SvgExportOptions,
+}
+
+impl SvgExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(mut self, options: SvgExportOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn options(&self) -> &SvgExportOptions {
+        &self.options
+    }
+
+    /// 将文档渲染为完整的 SVG 文本。
+    pub fn to_svg(&self, document: &Document) -> String {
+        let flat = document.flatten(&FlattenOptions {
+            tessellation: self.options.tessellation,
+            keep_fills: true,
+            ..FlattenOptions::default()
+        });
+        SvgBuilder::new(&flat, &self.options).finish()
+    }
+}
+
+impl DocumentSaver for SvgExporter {
+    fn save(&self, document: &Document, path: &Path) -> Result<(), IoError> {
+        fs::write(path, self.to_svg(document)).map_err(|source| IoError::WriteError {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+/// 单个图层分组的样式与内容。
+struct LayerGroup {
+    name: String,
+    stroke: u32,
+    stroke_width: f64,
+    body: String,
+}
+
+struct SvgBuilder<'a> {
+    document: &'a Document,
+    options: &'a SvgExportOptions,
+    /// SVG 坐标原点对应的世界坐标（左上角）。
+    origin: DVec2,
+    size: DVec2,
+    groups: Vec<LayerGroup>,
+    group_index: HashMap<String, usize>,
+    defs: String,
+    next_def: usize,
+}
+
+impl<'a> SvgBuilder<'a> {
+    fn new(document: &'a Document, options: &'a SvgExportOptions) -> Self {
+        let extent = match options.area {
+            PlotArea::Extents => drawing_extent(document),
+            PlotArea::Window { min, max } => {
+                let (min, max) = (
+                    DVec2::new(min.x().min(max.x()), min.y().min(max.y())),
+                    DVec2::new(min.x().max(max.x()), min.y().max(max.y())),
+                );
+                return Self::with_view(document, options, DVec2::new(min.x, max.y), max - min);
+            }
+        };
+        let (origin, size) = match extent {
+            Some((min, max)) => {
+                let longest = (max - min).max_element();
+                let margin = if longest > 0.0 {
+                    longest * options.margin.max(0.0)
+                } else {
+                    1.0
+                };
+                (
+                    DVec2::new(min.x - margin, max.y + margin),
+                    max - min + DVec2::splat(2.0 * margin),
+                )
+            }
+            None => (DVec2::ZERO, DVec2::ZERO),
+        };
+        Self::with_view(document, options, origin, size)
+    }
+
+    fn with_view(
+        document: &'a Document,
+        options: &'a SvgExportOptions,
+        origin: DVec2,
+        size: DVec2,
+    ) -> Self {
+        let mut builder = Self {
+            document,
+            options,
+            origin,
+            size,
+            groups: Vec::new(),
+            group_index: HashMap::new(),
+            defs: String::new(),
+            next_def: 0,
+        };
+        for (id, entity) in document.display_list() {
+            if builder.in_view(entity) {
+                builder.entity(*id, entity);
+            }
+        }
+        builder
+    }
+
+    /// 窗口范围下跳过与视图不相交的对象。
+    fn in_view(&self, entity: &Entity) -> bool {
+        if !matches!(self.options.area, PlotArea::Window { .. }) {
+            return true;
+        }
+        self.document.entity_extent(entity).is_none_or(|bounds| {
+            let (min, max) = (bounds.min(), bounds.max());
+            let view_min = DVec2::new(self.origin.x, self.origin.y - self.size.y);
+            let view_max = DVec2::new(self.origin.x + self.size.x, self.origin.y);
+            min.x() <= view_max.x
+                && max.x() >= view_min.x
+                && min.y() <= view_max.y
+                && max.y() >= view_min.y
+        })
+    }
+
+    fn entity(&mut self, id: EntityId, entity: &Entity) {
+        let layer = entity.layer_name();
+        let properties = self.document.resolve_properties(id).unwrap_or_default();
+        let color = self.display_color(properties.color);
+        let stroke_width = stroke_width(properties.lineweight);
+        let group = self.group(layer);
+        let style = {
+            let group = &self.groups[group];
+            let mut style = String::new();
+            if color != group.stroke {
+                let _ = write!(style, r#" stroke="{}""#, hex(color));
+            }
+            if (stroke_width - group.stroke_width).abs() > 1e-9 {
+                let _ = write!(style, r#" stroke-width="{}""#, self.number(stroke_width));
+            }
+            style
+        };
+
+        let element = match entity {
+            Entity::Line(line) => {
+                let start = self.point(line.start);
+                let end = self.point(line.end);
+                format!(
+                    r#"<line x1="{}" y1="{}" x2="{}" y2="{}"{style}/>"#,
+                    self.number(start.x),
+                    self.number(start.y),
+                    self.number(end.x),
+                    self.number(end.y)
+                )
+            }
+            Entity::Circle(circle) => {
+                let center = self.point(circle.center);
+                format!(
+                    r#"<circle cx="{}" cy="{}" r="{}"{style}/>"#,
+                    self.number(center.x),
+                    self.number(center.y),
+                    self.number(circle.radius.abs())
+                )
+            }
+            Entity::Arc(arc) => {
+                let sweep = (arc.end_angle - arc.start_angle).rem_euclid(TAU);
+                if sweep <= 1e-12 {
+                    let center = self.point(arc.center);
+                    format!(
+                        r#"<circle cx="{}" cy="{}" r="{}"{style}/>"#,
+                        self.number(center.x),
+                        self.number(center.y),
+                        self.number(arc.radius.abs())
+                    )
+                } else {
+                    let center = arc.center.as_vec2();
+                    let start = center + DVec2::from_angle(arc.start_angle) * arc.radius;
+                    let end = center + DVec2::from_angle(arc.end_angle) * arc.radius;
+                    let mut data = String::new();
+                    self.move_to(&mut data, start);
+                    self.arc_to(&mut data, arc.radius.abs(), sweep, end);
+                    format!(r#"<path d="{}"{style}/>"#, data.trim_start())
+                }
+            }
+            Entity::Polyline(polyline) => {
+                let data = self.polyline_data(polyline);
+                if data.is_empty() {
+                    return;
+                }
+                format!(r#"<path d="{data}"{style}/>"#)
+            }
+            Entity::Text(text) => self.text(text, color),
+            Entity::Hatch(hatch) => self.hatch(hatch, color, &style),
+            Entity::Wipeout(wipeout) => self.wipeout(wipeout, &style),
+            // FLATTEN 已将其余对象转换为上述图元
+            _ => return,
+        };
+        if element.is_empty() {
+            return;
+        }
+        let body = &mut self.groups[group].body;
+        body.push_str("    ");
+        body.push_str(&element);
+        body.push('\n');
+    }
+
+    fn group(&mut self, layer: &str) -> usize {
+        if let Some(index) = self.group_index.get(layer) {
+            return *index;
+        }
+        let defaults = self
+            .document
+            .layer(layer)
+            .map(|layer| layer.default_properties())
+            .unwrap_or_default();
+        let index = self.groups.len();
+        self.groups.push(LayerGroup {
+            name: layer.to_string(),
+            stroke: self.display_color(defaults.color),
+            stroke_width: stroke_width(defaults.lineweight),
+            body: String::new(),
+        });
+        self.group_index.insert(layer.to_string(), index);
+        index
+    }
+
+    fn text(&self, text: &Text, color: u32) -> String {
+        // 字形坐标系：x 沿文字方向，y 向下；反向与倒置分别翻转两轴
+        let direction = DVec2::from_angle(text.rotation);
+        let mut x_axis = DVec2::new(direction.x, -direction.y);
+        let mut y_axis = DVec2::new(direction.y, direction.x);
+        if text.is_backward {
+            x_axis = -x_axis;
+        }
+        if text.is_upside_down {
+            y_axis = -y_axis;
+        }
+        let insert = self.point(text.insert);
+        format!(
+            r#"<text transform="matrix({} {} {} {} {} {})" font-size="{}" fill="{}" stroke="none">{}</text>"#,
+            self.number(x_axis.x),
+            self.number(x_axis.y),
+            self.number(y_axis.x),
+            self.number(y_axis.y),
+            self.number(insert.x),
+            self.number(insert.y),
+            self.number(text.height),
+            hex(color),
+            escape(&text.content)
+        )
+    }
+
+    fn hatch(&mut self, hatch: &Hatch, color: u32, style: &str) -> String {
+        let loops = hatch
+            .loops
+            .iter()
+            .map(|loop_path| ring_points(loop_path, &self.options.tessellation))
+            .filter(|points| points.len() >= 3)
+            .collect::<Vec<_>>();
+        let mut data = String::new();
+        for points in &loops {
+            self.move_to(&mut data, points[0]);
+            for point in &points[1..] {
+                self.line_to(&mut data, *point);
+            }
+            data.push_str(" Z");
+        }
+        let data = data.trim_start().to_string();
+        if data.is_empty() {
+            return String::new();
+        }
+
+        if let Some(gradient) = &hatch.gradient {
+            let id = self.gradient(gradient, &loops);
+            return format!(
+                r#"<path d="{data}" fill="url(#{id})" fill-rule="evenodd" stroke="none"/>"#
+            );
+        }
+        if hatch.is_solid {
+            return format!(
+                r#"<path d="{data}" fill="{}" fill-rule="evenodd" stroke="none"/>"#,
+                hex(color)
+            );
+        }
+        if hatch.pattern_lines.is_empty() {
+            return format!(r#"<path d="{data}"{style}/>"#);
+        }
+
+        let id = self.next_id("hatch-clip");
+        let _ = writeln!(
+            self.defs,
+            r#"    <clipPath id="{id}"><path d="{data}" clip-rule="evenodd"/></clipPath>"#
+        );
+        let (min, max) = extent(loops.iter().flatten().copied());
+        let mut element = format!(r#"<g clip-path="url(#{id})"{style}>"#);
+        for line in &hatch.pattern_lines {
+            self.pattern_family(&mut element, line, min, max);
+        }
+        element.push_str("</g>");
+        element
+    }
+
+    /// 展开一组平行图案线，虚线以 `stroke-dasharray` 表示。
+    fn pattern_family(&self, out: &mut String, line: &HatchPatternLine, min: DVec2, max: DVec2) {
+        let dashes = dash_array(&line.dash_lengths);
+        let period = dashes
+            .as_ref()
+            .map(|dashes| dashes.iter().sum::<f64>())
+            .unwrap_or(0.0);
+        for stroke in pattern_strokes(line, min, max, period) {
+            let mut data = String::new();
+            self.move_to(&mut data, stroke.start);
+            self.line_to(&mut data, stroke.end);
+            let _ = write!(out, r#"<path d="{}""#, data.trim_start());
+            if let Some(dashes) = &dashes {
+                let list = dashes
+                    .iter()
+                    .map(|dash| self.number(*dash))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let _ = write!(
+                    out,
+                    r#" stroke-dasharray="{list}" stroke-dashoffset="{}""#,
+                    self.number(stroke.phase)
+                );
+                // 分组默认圆头；没有点时改用平头，保持虚线长度准确
+                if !dashes.iter().step_by(2).any(|dash| *dash <= 0.0) {
+                    out.push_str(r#" stroke-linecap="butt""#);
+                }
+            }
+            out.push_str("/>");
+        }
+    }
+
+    fn gradient(&mut self, gradient: &HatchGradient, loops: &[Vec<DVec2>]) -> String {
+        let axis = gradient_axis(gradient, loops);
+        let start = self.point_vec(axis.start);
+        let end = self.point_vec(axis.end);
+        let (start_color, end_color) = (axis.start_color, axis.end_color);
+        let id = self.next_id("gradient");
+        let _ = writeln!(
+            self.defs,
+            r#"    <linearGradient id="{id}" gradientUnits="userSpaceOnUse" x1="{}" y1="{}" x2="{}" y2="{}"><stop offset="0" stop-color="{}"/><stop offset="1" stop-color="{}"/></linearGradient>"#,
+            self.number(start.x),
+            self.number(start.y),
+            self.number(end.x),
+            self.number(end.y),
+            hex(start_color),
+            hex(end_color)
+        );
+        id
+    }
+
+    fn wipeout(&self, wipeout: &Wipeout, style: &str) -> String {
+        let points = wipeout_outline(wipeout);
+        if points.len() < 3 {
+            return String::new();
+        }
+        let mut data = String::new();
+        self.move_to(&mut data, points[0]);
+        for point in &points[1..] {
+            self.line_to(&mut data, *point);
+        }
+        data.push_str(" Z");
+        let fill = hex(self.options.background.unwrap_or(0xFFFFFF));
+        let frame = if self.document.wipeout_frame_visible() {
+            style.to_string()
+        } else {
+            r#" stroke="none""#.to_string()
+        };
+        format!(r#"<path d="{}" fill="{fill}"{frame}/>"#, data.trim_start())
+    }
+
+    fn polyline_data(&self, polyline: &Polyline) -> String {
+        let vertices = &polyline.vertices;
+        let Some(first) = vertices.first() else {
+            return String::new();
+        };
+        let mut data = String::new();
+        self.move_to(&mut data, first.position.as_vec2());
+        let segments = if polyline.is_closed {
+            vertices.len()
+        } else {
+            vertices.len() - 1
+        };
+        for index in 0..segments {
+            let current = &vertices[index];
+            let next = vertices[(index + 1) % vertices.len()].position.as_vec2();
+            match BulgeArc::new(current.position.as_vec2(), next, current.bulge) {
+                Some(arc) => self.arc_to(&mut data, arc.radius, arc.sweep, next),
+                None => self.line_to(&mut data, next),
+            }
+        }
+        if polyline.is_closed {
+            data.push_str(" Z");
+        }
+        data.trim_start().to_string()
+    }
+
+    fn move_to(&self, data: &mut String, point: DVec2) {
+        let point = self.point_vec(point);
+        let _ = write!(data, " M{} {}", self.number(point.x), self.number(point.y));
+    }
+
+    fn line_to(&self, data: &mut String, point: DVec2) {
+        let point = self.point_vec(point);
+        let _ = write!(data, " L{} {}", self.number(point.x), self.number(point.y));
+    }
+
+    /// 世界坐标中逆时针为正的弧；Y 轴翻转后逆时针对应 SVG 的 sweep-flag 0。
+    fn arc_to(&self, data: &mut String, radius: f64, sweep: f64, end: DVec2) {
+        let end = self.point_vec(end);
+        let large = u8::from(sweep.abs() > std::f64::consts::PI);
+        let flag = u8::from(sweep < 0.0);
+        let radius = self.number(radius);
+        let _ = write!(
+            data,
+            " A{radius} {radius} 0 {large} {flag} {} {}",
+            self.number(end.x),
+            self.number(end.y)
+        );
+    }
+
+    fn point(&self, point: Point2) -> DVec2 {
+        self.point_vec(point.as_vec2())
+    }
+
+    fn point_vec(&self, point: DVec2) -> DVec2 {
+        DVec2::new(point.x - self.origin.x, self.origin.y - point.y)
+    }
+
+    fn number(&self, value: f64) -> String {
+        format_number(value, self.options.precision)
+    }
+
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.next_def += 1;
+        format!("{prefix}-{}", self.next_def)
+    }
+
+    fn display_color(&self, color: ObjectColor) -> u32 {
+        display_color(color, self.options.background)
+    }
+
+    fn finish(self) -> String {
+        let mut out = String::new();
+        let width = self.number(self.size.x);
+        let height = self.number(self.size.y);
+        let _ = write!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {width} {height}""#
+        );
+        if let Some(pixels) = self.options.width {
+            let ratio = if self.size.x > 0.0 {
+                self.size.y / self.size.x
+            } else {
+                1.0
+            };
+            let _ = write!(
+                out,
+                r#" width="{}" height="{}""#,
+                self.number(pixels),
+                self.number(pixels * ratio)
+            );
+        }
+        out.push_str(">\n");
+        let _ = writeln!(
+            out,
+            "  <style>path, line, circle {{ vector-effect: non-scaling-stroke; }} text {{ font-family: {}; white-space: pre; }}</style>",
+            escape(&self.options.font_family)
+        );
+        if !self.defs.is_empty() {
+            out.push_str("  <defs>\n");
+            out.push_str(&self.defs);
+            out.push_str("  </defs>\n");
+        }
+        if let Some(background) = self.options.background {
+            let _ = writeln!(
+                out,
+                r#"  <rect width="100%" height="100%" fill="{}"/>"#,
+                hex(background)
+            );
+        }
+        let mut used_ids = HashMap::new();
+        for group in &self.groups {
+            let base = layer_id(&group.name);
+            let count = used_ids.entry(base.clone()).or_insert(0);
+            *count += 1;
+            let id = if *count == 1 {
+                base
+            } else {
+                format!("{base}-{count}")
+            };
+            let _ = writeln!(
+                out,
+                r#"  <g id="{id}" data-layer="{}" fill="none" stroke="{}" stroke-width="{}" stroke-linecap="round" stroke-linejoin="round">"#,
+                escape(&group.name),
+                hex(group.stroke),
+                self.number(group.stroke_width)
+            );
+            out.push_str(&group.body);
+            out.push_str("  </g>\n");
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+}
+
+/// 线宽换算为屏幕像素，配合 `non-scaling-stroke` 在任意缩放下保持粗细。
+fn stroke_width(lineweight: LineWeight) -> f64 {
+    lineweight.millimeters().unwrap_or(DEFAULT_LINEWEIGHT_MM) * PIXELS_PER_MM
+}
+
+fn hex(rgb: u32) -> String {
+    format!("#{:06x}", rgb & 0xFF_FFFF)
+}
+
+fn layer_id(name: &str) -> String {
+    let sanitized = name
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    format!("layer-{sanitized}")
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
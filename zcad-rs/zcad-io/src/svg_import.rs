@@ -0,0 +1,1198 @@
+//! SVG 导入：把矢量图形工具导出的标志、详图转换为图纸对象。
+//!
+//! 支持 `path`、`line`、`rect`、`circle`、`ellipse`、`polyline`、`polygon`、`text`、
+//! `use` 与分组；分组与元素的 `transform` 逐级累积。Inkscape 图层（`inkscape:label`）
+//! 与根元素下的具名分组转换为图层，隐藏的图层保留为关闭状态。
+//! SVG 的 Y 轴向下，导入时翻转为 Y 轴向上，`viewBox` 左下角对齐原点；根元素宽度带物理单位时
+//! 按毫米换算。圆弧在保持相似变换时转换为圆、椭圆或多段线凸度，贝塞尔曲线按段数离散。
+//! 描边颜色（无描边时取填充色）写为对象真彩色，纯黑与纯白映射为 7 号色。
+
+use std::f64::consts::{PI, TAU};
+use std::fs;
+use std::path::Path;
+
+use glam::{DAffine2, DMat2, DVec2};
+use roxmltree::Node;
+use zcad_core::document::{
+    Document, EntityId, EntityProperties, HatchEdge, HatchLoop, HatchStyle, ObjectColor,
+    PolylineVertex, Text,
+};
+use zcad_core::geometry::{Point2, Vector2};
+
+use crate::svg::SvgExporter;
+use crate::{DocumentLoader, DocumentSaver, IoError};
+
+/// Inkscape 扩展属性的命名空间。
+const INKSCAPE_NAMESPACE: &str = "http://www.inkscape.org/namespaces/inkscape";
+/// `use` 引用展开的最大深度，防止循环引用。
+const MAX_USE_DEPTH: usize = 16;
+
+/// SVG 导入选项。
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgImportOptions {
+    /// 整圆离散段数；无法保留为圆弧的椭圆弧按比例取样，每段贝塞尔曲线取其四分之一。
+    pub curve_segments: usize,
+    /// 在单位换算之后额外施加的缩放。
+    pub scale: f64,
+    /// 填充的图形另生成实体填充（SOLID）。
+    pub import_fills: bool,
+    /// 不属于任何具名分组的对象所在图层。
+    pub default_layer: String,
+}
+
+impl Default for SvgImportOptions {
+    fn default() -> Self {
+        Self {
+            curve_segments: 64,
+            scale: 1.0,
+            import_fills: false,
+            default_layer: "0".to_string(),
+        }
+    }
+}
+
+/// SVG 读写门面：读取时转换为图纸对象，保存时使用 [`SvgExporter`]。
+#[derive(Debug, Clone, Default)]
+pub struct SvgFacade {
+    options: SvgImportOptions,
+    exporter: SvgExporter,
+}
+
+impl SvgFacade {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(mut self, options: SvgImportOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// 设置保存时使用的导出器。
+    pub fn with_exporter(mut self, exporter: SvgExporter) -> Self {
+        self.exporter = exporter;
+        self
+    }
+
+    pub fn options(&self) -> &SvgImportOptions {
+        &self.options
+    }
+
+    /// 从 SVG 文本解析文档。
+    pub fn from_svg(&self, data: &str) -> Result<Document, IoError> {
+        let tree = roxmltree::Document::parse(data)
+            .map_err(|err| IoError::InvalidDocument(format!("SVG 解析失败: {err}")))?;
+        let root = tree.root_element();
+        if root.tag_name().name() != "svg" {
+            return Err(IoError::InvalidDocument(format!(
+                "根元素为 <{}>，不是 <svg>",
+                root.tag_name().name()
+            )));
+        }
+        let mut importer = Importer {
+            options: &self.options,
+            tree: &tree,
+            document: Document::new(),
+        };
+        importer.document.ensure_layer(&self.options.default_layer);
+        let style = Style::default().inherit(root);
+        let transform = root_transform(root, self.options.scale);
+        importer.children(root, transform, &style, &self.options.default_layer, 0);
+        Ok(importer.document)
+    }
+}
+
+impl DocumentLoader for SvgFacade {
+    fn load(&self, path: &Path) -> Result<Document, IoError> {
+        let data = fs::read_to_string(path).map_err(|source| IoError::ReadError {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        self.from_svg(&data)
+    }
+}
+
+impl DocumentSaver for SvgFacade {
+    fn save(&self, document: &Document, path: &Path) -> Result<(), IoError> {
+        self.exporter.save(document, path)
+    }
+}
+
+/// 可继承的样式属性。
+#[derive(Debug, Clone)]
+struct Style {
+    stroke: Option<u32>,
+    fill: Option<u32>,
+    font_size: f64,
+    /// 水平对齐（与 TEXT 组码 72 一致）：0 左、1 中、2 右。
+    text_anchor: i16,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            stroke: None,
+            fill: Some(0x000000),
+            font_size: 16.0,
+            text_anchor: 0,
+        }
+    }
+}
+
+impl Style {
+    fn inherit(&self, node: Node) -> Self {
+        let mut style = self.clone();
+        if let Some(value) = property(node, "stroke") {
+            style.stroke = parse_paint(&value).unwrap_or(style.stroke);
+        }
+        if let Some(value) = property(node, "fill") {
+            style.fill = parse_paint(&value).unwrap_or(style.fill);
+        }
+        if let Some(size) = property(node, "font-size").and_then(|value| parse_length(&value)) {
+            style.font_size = size;
+        }
+        match property(node, "text-anchor").as_deref() {
+            Some("start") => style.text_anchor = 0,
+            Some("middle") => style.text_anchor = 1,
+            Some("end") => style.text_anchor = 2,
+            _ => {}
+        }
+        style
+    }
+
+    /// 对象颜色取描边色，无描边时取填充色。
+    fn color(&self) -> Option<u32> {
+        self.stroke.or(self.fill)
+    }
+}
+
+/// 路径段，坐标为 SVG 用户坐标。
+#[derive(Debug, Clone, Copy)]
+enum Segment {
+    Line(DVec2),
+    Cubic(DVec2, DVec2, DVec2),
+    Quadratic(DVec2, DVec2),
+    Arc(EllipticalArc),
+}
+
+/// 中心参数化的椭圆弧（SVG 规范 F.6.5）。
+#[derive(Debug, Clone, Copy)]
+struct EllipticalArc {
+    center: DVec2,
+    radii: DVec2,
+    rotation: f64,
+    start_angle: f64,
+    sweep: f64,
+}
+
+impl EllipticalArc {
+    fn point(&self, angle: f64) -> DVec2 {
+        self.center + DVec2::from_angle(self.rotation).rotate(self.radii * DVec2::from_angle(angle))
+    }
+
+    fn is_circular(&self) -> bool {
+        (self.radii.x - self.radii.y).abs() <= 1e-9 * self.radii.x.max(self.radii.y)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SubPath {
+    start: DVec2,
+    segments: Vec<Segment>,
+    closed: bool,
+}
+
+struct Importer<'a, 'input> {
+    options: &'a SvgImportOptions,
+    tree: &'a roxmltree::Document<'input>,
+    document: Document,
+}
+
+impl Importer<'_, '_> {
+    fn children(
+        &mut self,
+        node: Node,
+        transform: DAffine2,
+        style: &Style,
+        layer: &str,
+        depth: usize,
+    ) {
+        for child in node.children().filter(Node::is_element) {
+            self.element(child, transform, style, layer, depth);
+        }
+    }
+
+    fn element(
+        &mut self,
+        node: Node,
+        transform: DAffine2,
+        style: &Style,
+        layer: &str,
+        depth: usize,
+    ) {
+        let hidden = property(node, "display").as_deref() == Some("none")
+            || matches!(
+                property(node, "visibility").as_deref(),
+                Some("hidden" | "collapse")
+            );
+        let transform = transform * parse_transform(node.attribute("transform").unwrap_or(""));
+        let style = style.inherit(node);
+        let name = node.tag_name().name();
+        match name {
+            "g" | "svg" | "a" | "switch" => {
+                let group_layer = self.group_layer(node);
+                if hidden {
+                    // 隐藏的具名图层保留为关闭状态，其余隐藏分组直接跳过
+                    let Some(group_layer) = group_layer else {
+                        return;
+                    };
+                    self.document.ensure_layer(&group_layer);
+                    if let Some(layer) = self.document.layer_mut(&group_layer) {
+                        layer.is_visible = false;
+                    }
+                    self.children(node, transform, &style, &group_layer, depth);
+                    return;
+                }
+                let layer = group_layer.unwrap_or_else(|| layer.to_string());
+                let transform = if name == "svg" {
+                    transform
+                        * DAffine2::from_translation(DVec2::new(
+                            number(node, "x"),
+                            number(node, "y"),
+                        ))
+                } else {
+                    transform
+                };
+                self.children(node, transform, &style, &layer, depth);
+            }
+            _ if hidden => {}
+            "use" => {
+                if depth >= MAX_USE_DEPTH {
+                    return;
+                }
+                let href = node
+                    .attribute(("http://www.w3.org/1999/xlink", "href"))
+                    .or_else(|| node.attribute("href"));
+                let Some(target) = href.and_then(|href| href.strip_prefix('#')).and_then(|id| {
+                    self.tree
+                        .descendants()
+                        .find(|candidate| candidate.attribute("id") == Some(id))
+                }) else {
+                    return;
+                };
+                let offset =
+                    DAffine2::from_translation(DVec2::new(number(node, "x"), number(node, "y")));
+                self.element(target, transform * offset, &style, layer, depth + 1);
+            }
+            "path" => {
+                let paths = parse_path(node.attribute("d").unwrap_or(""));
+                self.shape(&paths, transform, &style, layer);
+            }
+            "line" => {
+                let path = SubPath {
+                    start: DVec2::new(number(node, "x1"), number(node, "y1")),
+                    segments: vec![Segment::Line(DVec2::new(
+                        number(node, "x2"),
+                        number(node, "y2"),
+                    ))],
+                    closed: false,
+                };
+                self.shape(&[path], transform, &style, layer);
+            }
+            "polyline" | "polygon" => {
+                let points = NumberScanner::new(node.attribute("points").unwrap_or(""))
+                    .pairs()
+                    .collect::<Vec<_>>();
+                let Some((first, rest)) = points.split_first() else {
+                    return;
+                };
+                let path = SubPath {
+                    start: *first,
+                    segments: rest.iter().map(|point| Segment::Line(*point)).collect(),
+                    closed: name == "polygon",
+                };
+                self.shape(&[path], transform, &style, layer);
+            }
+            "rect" => {
+                if let Some(path) = rect_path(node) {
+                    self.shape(&[path], transform, &style, layer);
+                }
+            }
+            "circle" => {
+                let radius = number(node, "r");
+                if radius > 0.0 {
+                    let center = DVec2::new(number(node, "cx"), number(node, "cy"));
+                    self.ellipse(center, DVec2::splat(radius), transform, &style, layer);
+                }
+            }
+            "ellipse" => {
+                let radii = DVec2::new(number(node, "rx"), number(node, "ry"));
+                if radii.x > 0.0 && radii.y > 0.0 {
+                    let center = DVec2::new(number(node, "cx"), number(node, "cy"));
+                    self.ellipse(center, radii, transform, &style, layer);
+                }
+            }
+            "text" => self.text(node, transform, &style, layer),
+            // defs、symbol、clipPath、渐变与元数据等不直接绘制
+            _ => {}
+        }
+    }
+
+    /// [`SvgExporter`] 写出的分组取 `data-layer`，Inkscape 图层取其标签，
+    /// 根元素下的其他分组取 `id`；其余分组沿用上级图层。
+    fn group_layer(&self, node: Node) -> Option<String> {
+        let label = if let Some(layer) = node.attribute("data-layer") {
+            Some(layer)
+        } else if node.attribute((INKSCAPE_NAMESPACE, "groupmode")) == Some("layer") {
+            node.attribute((INKSCAPE_NAMESPACE, "label"))
+                .or_else(|| node.attribute("id"))
+        } else if node.tag_name().name() == "g"
+            && node
+                .parent_element()
+                .is_some_and(|parent| parent == self.tree.root_element())
+        {
+            node.attribute("id")
+        } else {
+            None
+        };
+        label.map(layer_name).filter(|name| !name.is_empty())
+    }
+
+    fn shape(&mut self, paths: &[SubPath], transform: DAffine2, style: &Style, layer: &str) {
+        let similarity = similarity_scale(transform).is_some();
+        let mut loops = Vec::new();
+        for path in paths {
+            let vertices = self.vertices(path, transform, similarity);
+            if vertices.len() < 2 {
+                continue;
+            }
+            let id = if !path.closed && vertices.len() == 2 && vertices[0].bulge == 0.0 {
+                self.document
+                    .add_line(vertices[0].position, vertices[1].position, layer)
+            } else {
+                self.document
+                    .add_polyline_with_vertices(vertices.clone(), path.closed, layer)
+            };
+            self.apply_color(id, style.color());
+            if vertices.len() >= 3 || vertices.iter().any(|vertex| vertex.bulge != 0.0) {
+                loops.push(hatch_loop(&vertices));
+            }
+        }
+        self.fill(loops, style, layer);
+    }
+
+    fn ellipse(
+        &mut self,
+        center: DVec2,
+        radii: DVec2,
+        transform: DAffine2,
+        style: &Style,
+        layer: &str,
+    ) {
+        let world_center = Point2::from_vec(transform.transform_point2(center));
+        let id = match similarity_scale(transform) {
+            Some(scale) if (radii.x - radii.y).abs() <= 1e-12 => {
+                self.document
+                    .add_circle(world_center, radii.x * scale, layer)
+            }
+            Some(_) => {
+                let (major, ratio) = if radii.x >= radii.y {
+                    (DVec2::new(radii.x, 0.0), radii.y / radii.x)
+                } else {
+                    (DVec2::new(0.0, radii.y), radii.x / radii.y)
+                };
+                let axis = transform.transform_vector2(major);
+                self.document.add_ellipse(
+                    world_center,
+                    Vector2::new(axis.x, axis.y),
+                    ratio,
+                    0.0,
+                    TAU,
+                    layer,
+                )
+            }
+            None => {
+                let arc = EllipticalArc {
+                    center,
+                    radii,
+                    rotation: 0.0,
+                    start_angle: 0.0,
+                    sweep: TAU,
+                };
+                let path = SubPath {
+                    start: arc.point(0.0),
+                    segments: vec![Segment::Arc(arc)],
+                    closed: true,
+                };
+                return self.shape(&[path], transform, style, layer);
+            }
+        };
+        self.apply_color(id, style.color());
+        if self.options.import_fills && style.fill.is_some() {
+            let arc = EllipticalArc {
+                center,
+                radii,
+                rotation: 0.0,
+                start_angle: 0.0,
+                sweep: TAU,
+            };
+            let path = SubPath {
+                start: arc.point(0.0),
+                segments: vec![Segment::Arc(arc)],
+                closed: true,
+            };
+            let vertices = self.vertices(&path, transform, similarity_scale(transform).is_some());
+            self.fill(vec![hatch_loop(&vertices)], style, layer);
+        }
+    }
+
+    fn text(&mut self, node: Node, transform: DAffine2, style: &Style, layer: &str) {
+        let content = node
+            .descendants()
+            .filter(Node::is_text)
+            .filter_map(|text| text.text())
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if content.is_empty() {
+            return;
+        }
+        let first = |name| {
+            node.attribute(name)
+                .and_then(|value| NumberScanner::new(value).next_number())
+                .unwrap_or(0.0)
+        };
+        let anchor =
+            Point2::from_vec(transform.transform_point2(DVec2::new(first("x"), first("y"))));
+        let x_axis = transform.transform_vector2(DVec2::X);
+        let scale = transform.matrix2.determinant().abs().sqrt();
+        let id = self.document.add_justified_text(Text {
+            insert: anchor,
+            content,
+            height: style.font_size * scale,
+            rotation: x_axis.to_angle(),
+            layer: layer.to_string(),
+            horizontal_align: style.text_anchor,
+            vertical_align: 0,
+            alignment: (style.text_anchor != 0).then_some(anchor),
+            is_backward: false,
+            is_upside_down: false,
+        });
+        self.apply_color(id, style.fill.or(style.stroke));
+    }
+
+    /// 子路径转换为世界坐标的多段线顶点；闭合时去掉与起点重合的末点。
+    fn vertices(
+        &self,
+        path: &SubPath,
+        transform: DAffine2,
+        similarity: bool,
+    ) -> Vec<PolylineVertex> {
+        let mirrored = transform.matrix2.determinant() < 0.0;
+        let world = |point: DVec2| Point2::from_vec(transform.transform_point2(point));
+        let mut vertices = vec![PolylineVertex::new(world(path.start))];
+        let mut current = path.start;
+        let bezier_segments = (self.options.curve_segments / 4).max(2);
+        for segment in &path.segments {
+            match *segment {
+                Segment::Line(end) => {
+                    vertices.push(PolylineVertex::new(world(end)));
+                    current = end;
+                }
+                Segment::Cubic(control1, control2, end) => {
+                    vertices.extend((1..=bezier_segments).map(|index| {
+                        let t = index as f64 / bezier_segments as f64;
+                        let u = 1.0 - t;
+                        let point = current * (u * u * u)
+                            + control1 * (3.0 * u * u * t)
+                            + control2 * (3.0 * u * t * t)
+                            + end * (t * t * t);
+                        PolylineVertex::new(world(point))
+                    }));
+                    current = end;
+                }
+                Segment::Quadratic(control, end) => {
+                    vertices.extend((1..=bezier_segments).map(|index| {
+                        let t = index as f64 / bezier_segments as f64;
+                        let u = 1.0 - t;
+                        let point = current * (u * u) + control * (2.0 * u * t) + end * (t * t);
+                        PolylineVertex::new(world(point))
+                    }));
+                    current = end;
+                }
+                Segment::Arc(arc) if similarity && arc.is_circular() => {
+                    // 每段不超过半圆，凸度才有界
+                    let pieces = (arc.sweep.abs() / (PI + 1e-9)).ceil().max(1.0) as usize;
+                    let step = arc.sweep / pieces as f64;
+                    let world_step = if mirrored { -step } else { step };
+                    for index in 1..=pieces {
+                        vertices.last_mut().unwrap().bulge = (world_step / 4.0).tan();
+                        let point = arc.point(arc.start_angle + step * index as f64);
+                        vertices.push(PolylineVertex::new(world(point)));
+                    }
+                    current = arc.point(arc.start_angle + arc.sweep);
+                }
+                Segment::Arc(arc) => {
+                    let pieces = ((self.options.curve_segments.max(4) as f64) * arc.sweep.abs()
+                        / TAU)
+                        .ceil()
+                        .max(1.0) as usize;
+                    vertices.extend((1..=pieces).map(|index| {
+                        let angle = arc.start_angle + arc.sweep * index as f64 / pieces as f64;
+                        PolylineVertex::new(world(arc.point(angle)))
+                    }));
+                    current = arc.point(arc.start_angle + arc.sweep);
+                }
+            }
+        }
+        if path.closed
+            && vertices.len() > 2
+            && vertices[0]
+                .position
+                .as_vec2()
+                .distance_squared(vertices.last().unwrap().position.as_vec2())
+                <= 1e-18
+        {
+            vertices.pop();
+        }
+        vertices
+    }
+
+    fn fill(&mut self, loops: Vec<HatchLoop>, style: &Style, layer: &str) {
+        if !self.options.import_fills || loops.is_empty() {
+            return;
+        }
+        let Some(fill) = style.fill else {
+            return;
+        };
+        let id = self.document.add_hatch(
+            "SOLID",
+            true,
+            loops,
+            None,
+            Vec::new(),
+            HatchStyle::Normal,
+            false,
+            Vec::new(),
+            layer,
+        );
+        self.apply_color(id, Some(fill));
+    }
+
+    fn apply_color(&mut self, id: EntityId, color: Option<u32>) {
+        let Some(rgb) = color else {
+            return;
+        };
+        let color = match rgb {
+            0x000000 | 0xFFFFFF => ObjectColor::Index(7),
+            rgb => ObjectColor::TrueColor(rgb),
+        };
+        self.document.set_entity_properties(
+            id,
+            EntityProperties {
+                color,
+                ..EntityProperties::default()
+            },
+        );
+    }
+}
+
+/// 闭合的填充边界：按顶点凸度输出多段线边。
+fn hatch_loop(vertices: &[PolylineVertex]) -> HatchLoop {
+    let edges = vertices
+        .iter()
+        .enumerate()
+        .map(|(index, vertex)| HatchEdge::PolylineSegment {
+            start: vertex.position,
+            end: vertices[(index + 1) % vertices.len()].position,
+            bulge: vertex.bulge,
+        })
+        .collect();
+    HatchLoop {
+        is_polyline: true,
+        is_closed: true,
+        edges,
+        boundary_handles: Vec::new(),
+        boundary_entities: Vec::new(),
+    }
+}
+
+/// 根变换：`viewBox` 映射到原点并翻转 Y 轴；宽度带物理单位时换算为毫米。
+fn root_transform(root: Node, scale: f64) -> DAffine2 {
+    let view_box = root
+        .attribute("viewBox")
+        .map(|value| NumberScanner::new(value).numbers().collect::<Vec<_>>())
+        .filter(|values| values.len() == 4 && values[2] > 0.0 && values[3] > 0.0);
+    let (origin, size) = match &view_box {
+        Some(values) => (
+            DVec2::new(values[0], values[1]),
+            DVec2::new(values[2], values[3]),
+        ),
+        None => (
+            DVec2::ZERO,
+            DVec2::new(
+                root.attribute("width")
+                    .and_then(parse_length)
+                    .unwrap_or(0.0),
+                root.attribute("height")
+                    .and_then(parse_length)
+                    .unwrap_or(0.0),
+            ),
+        ),
+    };
+    let physical = |name: &str, extent: f64| {
+        root.attribute(name)
+            .and_then(physical_millimeters)
+            .filter(|_| view_box.is_some())
+            .map(|millimeters| millimeters / extent)
+    };
+    let unit_x = physical("width", size.x);
+    let unit_y = physical("height", size.y);
+    let units = DVec2::new(
+        unit_x.or(unit_y).unwrap_or(1.0),
+        unit_y.or(unit_x).unwrap_or(1.0),
+    );
+    DAffine2::from_scale(units * scale * DVec2::new(1.0, -1.0))
+        * DAffine2::from_translation(DVec2::new(-origin.x, -(origin.y + size.y)))
+}
+
+/// 带物理单位的长度换算为毫米；像素与无单位数值返回 `None`。
+fn physical_millimeters(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let (number, factor) = [
+        ("mm", 1.0),
+        ("cm", 10.0),
+        ("in", 25.4),
+        ("pt", 25.4 / 72.0),
+        ("pc", 25.4 / 6.0),
+    ]
+    .into_iter()
+    .find_map(|(unit, factor)| value.strip_suffix(unit).map(|number| (number, factor)))?;
+    number
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .map(|number| number * factor)
+}
+
+/// 解析长度，忽略单位（百分比视为无效）。
+fn parse_length(value: &str) -> Option<f64> {
+    if value.trim_end().ends_with('%') {
+        return None;
+    }
+    NumberScanner::new(value).next_number()
+}
+
+fn number(node: Node, name: &str) -> f64 {
+    node.attribute(name).and_then(parse_length).unwrap_or(0.0)
+}
+
+/// 读取样式属性：`style` 中的声明优先于同名表现属性。
+fn property(node: Node, name: &str) -> Option<String> {
+    node.attribute("style")
+        .and_then(|style| {
+            style.split(';').find_map(|declaration| {
+                let (key, value) = declaration.split_once(':')?;
+                (key.trim() == name).then(|| value.trim().to_string())
+            })
+        })
+        .or_else(|| node.attribute(name).map(|value| value.trim().to_string()))
+}
+
+/// 解析颜色值：外层 `None` 表示无法识别（沿用继承值），内层 `None` 表示 `none`。
+fn parse_paint(value: &str) -> Option<Option<u32>> {
+    let value = value.trim().to_ascii_lowercase();
+    if value == "none" || value == "transparent" {
+        return Some(None);
+    }
+    if value == "currentcolor" {
+        return Some(Some(0x000000));
+    }
+    if let Some(hex) = value.strip_prefix('#') {
+        let hex = match hex.len() {
+            3 => hex.chars().flat_map(|digit| [digit, digit]).collect(),
+            6 => hex.to_string(),
+            _ => return None,
+        };
+        return u32::from_str_radix(&hex, 16).ok().map(Some);
+    }
+    if let Some(arguments) = value
+        .strip_prefix("rgb(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        let channels = arguments
+            .split(',')
+            .map(|channel| {
+                let channel = channel.trim();
+                let value = match channel.strip_suffix('%') {
+                    Some(percent) => percent.trim().parse::<f64>().ok()? * 2.55,
+                    None => channel.parse::<f64>().ok()?,
+                };
+                Some(value.round().clamp(0.0, 255.0) as u32)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        return (channels.len() == 3)
+            .then(|| Some(channels[0] << 16 | channels[1] << 8 | channels[2]));
+    }
+    // 以 url(#…) 引用的渐变或图案按黑色处理
+    if value.starts_with("url(") {
+        return Some(Some(0x000000));
+    }
+    let rgb = match value.as_str() {
+        "black" => 0x000000,
+        "white" => 0xFFFFFF,
+        "red" => 0xFF0000,
+        "lime" => 0x00FF00,
+        "green" => 0x008000,
+        "blue" => 0x0000FF,
+        "yellow" => 0xFFFF00,
+        "cyan" | "aqua" => 0x00FFFF,
+        "magenta" | "fuchsia" => 0xFF00FF,
+        "gray" | "grey" => 0x808080,
+        "silver" => 0xC0C0C0,
+        "maroon" => 0x800000,
+        "olive" => 0x808000,
+        "navy" => 0x000080,
+        "purple" => 0x800080,
+        "teal" => 0x008080,
+        "orange" => 0xFFA500,
+        _ => return None,
+    };
+    Some(Some(rgb))
+}
+
+/// 解析 `transform` 属性，变换列表从左到右依次作用于外层坐标系。
+fn parse_transform(value: &str) -> DAffine2 {
+    let mut transform = DAffine2::IDENTITY;
+    let mut rest = value;
+    while let Some(open) = rest.find('(') {
+        let name = rest[..open].trim_matches(|ch: char| ch.is_whitespace() || ch == ',');
+        let Some(close) = rest[open..].find(')') else {
+            break;
+        };
+        let arguments = NumberScanner::new(&rest[open + 1..open + close])
+            .numbers()
+            .collect::<Vec<_>>();
+        let argument =
+            |index: usize, default: f64| arguments.get(index).copied().unwrap_or(default);
+        let step = match name {
+            "matrix" if arguments.len() == 6 => DAffine2::from_cols_array(&[
+                arguments[0],
+                arguments[1],
+                arguments[2],
+                arguments[3],
+                arguments[4],
+                arguments[5],
+            ]),
+            "translate" => {
+                DAffine2::from_translation(DVec2::new(argument(0, 0.0), argument(1, 0.0)))
+            }
+            "scale" => {
+                let x = argument(0, 1.0);
+                DAffine2::from_scale(DVec2::new(x, argument(1, x)))
+            }
+            "rotate" => {
+                let pivot = DVec2::new(argument(1, 0.0), argument(2, 0.0));
+                DAffine2::from_translation(pivot)
+                    * DAffine2::from_angle(argument(0, 0.0).to_radians())
+                    * DAffine2::from_translation(-pivot)
+            }
+            "skewX" => DAffine2::from_mat2(DMat2::from_cols(
+                DVec2::X,
+                DVec2::new(argument(0, 0.0).to_radians().tan(), 1.0),
+            )),
+            "skewY" => DAffine2::from_mat2(DMat2::from_cols(
+                DVec2::new(1.0, argument(0, 0.0).to_radians().tan()),
+                DVec2::Y,
+            )),
+            _ => DAffine2::IDENTITY,
+        };
+        transform *= step;
+        rest = &rest[open + close + 1..];
+    }
+    transform
+}
+
+/// 变换为相似变换（旋转、镜像、等比缩放与平移）时返回缩放系数。
+fn similarity_scale(transform: DAffine2) -> Option<f64> {
+    let x = transform.matrix2.x_axis;
+    let y = transform.matrix2.y_axis;
+    let scale = x.length();
+    let tolerance = 1e-9 * scale.max(1.0);
+    ((y.length() - scale).abs() <= tolerance && x.dot(y).abs() <= tolerance * scale && scale > 0.0)
+        .then_some(scale)
+}
+
+fn rect_path(node: Node) -> Option<SubPath> {
+    let origin = DVec2::new(number(node, "x"), number(node, "y"));
+    let size = DVec2::new(number(node, "width"), number(node, "height"));
+    if size.x <= 0.0 || size.y <= 0.0 {
+        return None;
+    }
+    let rx = node.attribute("rx").and_then(parse_length);
+    let ry = node.attribute("ry").and_then(parse_length);
+    let radii = DVec2::new(rx.or(ry).unwrap_or(0.0), ry.or(rx).unwrap_or(0.0))
+        .clamp(DVec2::ZERO, size * 0.5);
+    let max = origin + size;
+    if radii.x <= 0.0 || radii.y <= 0.0 {
+        return Some(SubPath {
+            start: origin,
+            segments: vec![
+                Segment::Line(DVec2::new(max.x, origin.y)),
+                Segment::Line(max),
+                Segment::Line(DVec2::new(origin.x, max.y)),
+            ],
+            closed: true,
+        });
+    }
+    // 圆角按 SVG 坐标（Y 向下）顺时针依次为右上、右下、左下、左上
+    let corner = |center: DVec2, start_angle: f64| {
+        Segment::Arc(EllipticalArc {
+            center,
+            radii,
+            rotation: 0.0,
+            start_angle,
+            sweep: PI / 2.0,
+        })
+    };
+    Some(SubPath {
+        start: DVec2::new(origin.x + radii.x, origin.y),
+        segments: vec![
+            Segment::Line(DVec2::new(max.x - radii.x, origin.y)),
+            corner(DVec2::new(max.x - radii.x, origin.y + radii.y), -PI / 2.0),
+            Segment::Line(DVec2::new(max.x, max.y - radii.y)),
+            corner(DVec2::new(max.x - radii.x, max.y - radii.y), 0.0),
+            Segment::Line(DVec2::new(origin.x + radii.x, max.y)),
+            corner(DVec2::new(origin.x + radii.x, max.y - radii.y), PI / 2.0),
+            Segment::Line(DVec2::new(origin.x, origin.y + radii.y)),
+            corner(DVec2::new(origin.x + radii.x, origin.y + radii.y), PI),
+        ],
+        closed: true,
+    })
+}
+
+/// 解析路径数据，返回各子路径；遇到无法识别的内容时保留已解析部分。
+fn parse_path(data: &str) -> Vec<SubPath> {
+    let mut scanner = NumberScanner::new(data);
+    let mut paths: Vec<SubPath> = Vec::new();
+    let mut current = DVec2::ZERO;
+    let mut start = DVec2::ZERO;
+    // 上一段的第二控制点，用于 S/T 的反射控制点
+    let mut last_cubic: Option<DVec2> = None;
+    let mut last_quadratic: Option<DVec2> = None;
+    let mut command: Option<char> = None;
+
+    loop {
+        scanner.skip_separators();
+        let Some(next) = scanner.peek() else {
+            break;
+        };
+        if next.is_ascii_alphabetic() {
+            scanner.advance();
+            command = Some(next);
+        } else if command.is_none() {
+            break;
+        }
+        let Some(letter) = command else {
+            break;
+        };
+        let relative = letter.is_ascii_lowercase();
+        let base = if relative { current } else { DVec2::ZERO };
+        let upper = letter.to_ascii_uppercase();
+        let (segment, reflect_cubic, reflect_quadratic) = match upper {
+            'Z' => {
+                if let Some(path) = paths.last_mut() {
+                    path.closed = true;
+                }
+                current = start;
+                command = None;
+                last_cubic = None;
+                last_quadratic = None;
+                continue;
+            }
+            'M' => {
+                let Some(point) = scanner.pair() else {
+                    break;
+                };
+                current = base + point;
+                start = current;
+                paths.push(SubPath {
+                    start,
+                    segments: Vec::new(),
+                    closed: false,
+                });
+                // 后续坐标对按 L/l 处理
+                command = Some(if relative { 'l' } else { 'L' });
+                last_cubic = None;
+                last_quadratic = None;
+                continue;
+            }
+            'L' => {
+                let Some(point) = scanner.pair() else {
+                    break;
+                };
+                (Segment::Line(base + point), None, None)
+            }
+            'H' => {
+                let Some(x) = scanner.next_number() else {
+                    break;
+                };
+                let x = if relative { current.x + x } else { x };
+                (Segment::Line(DVec2::new(x, current.y)), None, None)
+            }
+            'V' => {
+                let Some(y) = scanner.next_number() else {
+                    break;
+                };
+                let y = if relative { current.y + y } else { y };
+                (Segment::Line(DVec2::new(current.x, y)), None, None)
+            }
+            'C' => {
+                let (Some(control1), Some(control2), Some(end)) =
+                    (scanner.pair(), scanner.pair(), scanner.pair())
+                else {
+                    break;
+                };
+                let control2 = base + control2;
+                (
+                    Segment::Cubic(base + control1, control2, base + end),
+                    Some(control2),
+                    None,
+                )
+            }
+            'S' => {
+                let (Some(control2), Some(end)) = (scanner.pair(), scanner.pair()) else {
+                    break;
+                };
+                let control1 = last_cubic.map_or(current, |control| current * 2.0 - control);
+                let control2 = base + control2;
+                (
+                    Segment::Cubic(control1, control2, base + end),
+                    Some(control2),
+                    None,
+                )
+            }
+            'Q' => {
+                let (Some(control), Some(end)) = (scanner.pair(), scanner.pair()) else {
+                    break;
+                };
+                let control = base + control;
+                (Segment::Quadratic(control, base + end), None, Some(control))
+            }
+            'T' => {
+                let Some(end) = scanner.pair() else {
+                    break;
+                };
+                let control = last_quadratic.map_or(current, |control| current * 2.0 - control);
+                (Segment::Quadratic(control, base + end), None, Some(control))
+            }
+            'A' => {
+                let (Some(rx), Some(ry), Some(rotation), Some(large), Some(sweep), Some(end)) = (
+                    scanner.next_number(),
+                    scanner.next_number(),
+                    scanner.next_number(),
+                    scanner.flag(),
+                    scanner.flag(),
+                    scanner.pair(),
+                ) else {
+                    break;
+                };
+                let end = base + end;
+                match endpoint_arc(current, end, DVec2::new(rx, ry), rotation, large, sweep) {
+                    Some(arc) => (Segment::Arc(arc), None, None),
+                    None if current.distance_squared(end) > 0.0 => (Segment::Line(end), None, None),
+                    None => continue,
+                }
+            }
+            _ => break,
+        };
+        if paths.is_empty() {
+            // 缺少起始 M 时从原点开始
+            paths.push(SubPath {
+                start: current,
+                segments: Vec::new(),
+                closed: false,
+            });
+        } else if paths.last().is_some_and(|path| path.closed) {
+            // Z 之后未经 M 继续绘制时，新子路径从闭合点开始
+            paths.push(SubPath {
+                start,
+                segments: Vec::new(),
+                closed: false,
+            });
+        }
+        current = match segment {
+            Segment::Line(end) | Segment::Cubic(_, _, end) | Segment::Quadratic(_, end) => end,
+            Segment::Arc(arc) => arc.point(arc.start_angle + arc.sweep),
+        };
+        paths.last_mut().unwrap().segments.push(segment);
+        last_cubic = reflect_cubic;
+        last_quadratic = reflect_quadratic;
+    }
+    paths
+}
+
+/// 端点参数化的椭圆弧转换为中心参数化（SVG 规范 F.6.5、F.6.6）；
+/// 半径为零或两端点重合时返回 `None`。
+fn endpoint_arc(
+    from: DVec2,
+    to: DVec2,
+    radii: DVec2,
+    rotation_degrees: f64,
+    large_arc: bool,
+    sweep: bool,
+) -> Option<EllipticalArc> {
+    let mut radii = radii.abs();
+    if radii.x <= 0.0 || radii.y <= 0.0 || from.distance_squared(to) <= 0.0 {
+        return None;
+    }
+    let rotation = rotation_degrees.to_radians();
+    let axis = DVec2::from_angle(-rotation);
+    let prime = axis.rotate((from - to) * 0.5);
+    let lambda = (prime.x / radii.x).powi(2) + (prime.y / radii.y).powi(2);
+    if lambda > 1.0 {
+        radii *= lambda.sqrt();
+    }
+    let numerator =
+        (radii.x * radii.y).powi(2) - (radii.x * prime.y).powi(2) - (radii.y * prime.x).powi(2);
+    let denominator = (radii.x * prime.y).powi(2) + (radii.y * prime.x).powi(2);
+    let mut coefficient = (numerator / denominator).max(0.0).sqrt();
+    if large_arc == sweep {
+        coefficient = -coefficient;
+    }
+    let center_prime =
+        DVec2::new(radii.x * prime.y / radii.y, -radii.y * prime.x / radii.x) * coefficient;
+    let center = DVec2::from_angle(rotation).rotate(center_prime) + (from + to) * 0.5;
+    let start_vector = (prime - center_prime) / radii;
+    let end_vector = (-prime - center_prime) / radii;
+    let start_angle = start_vector.to_angle();
+    let mut delta = start_vector.angle_to(end_vector);
+    if !sweep && delta > 0.0 {
+        delta -= TAU;
+    } else if sweep && delta < 0.0 {
+        delta += TAU;
+    }
+    Some(EllipticalArc {
+        center,
+        radii,
+        rotation,
+        start_angle,
+        sweep: delta,
+    })
+}
+
+/// SVG 数值列表的扫描器：数值之间可用空白、逗号或符号分隔。
+struct NumberScanner<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> NumberScanner<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            bytes: text.as_bytes(),
+            position: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.bytes.get(self.position).map(|byte| *byte as char)
+    }
+
+    fn advance(&mut self) {
+        self.position += 1;
+    }
+
+    fn skip_separators(&mut self) {
+        while self
+            .peek()
+            .is_some_and(|ch| ch.is_ascii_whitespace() || ch == ',')
+        {
+            self.advance();
+        }
+    }
+
+    fn next_number(&mut self) -> Option<f64> {
+        self.skip_separators();
+        let start = self.position;
+        if matches!(self.peek(), Some('+' | '-')) {
+            self.advance();
+        }
+        let mut digits = false;
+        while self.peek().is_some_and(|ch| ch.is_ascii_digit()) {
+            self.advance();
+            digits = true;
+        }
+        if self.peek() == Some('.') {
+            self.advance();
+            while self.peek().is_some_and(|ch| ch.is_ascii_digit()) {
+                self.advance();
+                digits = true;
+            }
+        }
+        if !digits {
+            self.position = start;
+            return None;
+        }
+        if matches!(self.peek(), Some('e' | 'E')) {
+            let mark = self.position;
+            self.advance();
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.advance();
+            }
+            if self.peek().is_some_and(|ch| ch.is_ascii_digit()) {
+                while self.peek().is_some_and(|ch| ch.is_ascii_digit()) {
+                    self.advance();
+                }
+            } else {
+                self.position = mark;
+            }
+        }
+        std::str::from_utf8(&self.bytes[start..self.position])
+            .ok()?
+            .parse()
+            .ok()
+    }
+
+    fn pair(&mut self) -> Option<DVec2> {
+        let x = self.next_number()?;
+        let y = self.next_number()?;
+        Some(DVec2::new(x, y))
+    }
+
+    /// 弧线标志只占一个字符，可与后续数值紧挨。
+    fn flag(&mut self) -> Option<bool> {
+        self.skip_separators();
+        let flag = match self.peek()? {
+            '0' => false,
+            '1' => true,
+            _ => return None,
+        };
+        self.advance();
+        Some(flag)
+    }
+
+    fn numbers(mut self) -> impl Iterator<Item = f64> + 'a {
+        std::iter::from_fn(move || self.next_number())
+    }
+
+    fn pairs(mut self) -> impl Iterator<Item = DVec2> + 'a {
+        std::iter::from_fn(move || self.pair())
+    }
+}
+
+/// 图层名去掉 DXF 不允许的字符。
+fn layer_name(label: &str) -> String {
+    label
+        .trim()
+        .chars()
+        .map(|ch| {
+            if "<>/\\\":;?*|=`".contains(ch) {
+                '_'
+            } else {
+                ch
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,128 @@
+//! 离散化：文档经 FLATTEN 后逐个图元转换为带颜色的折线，供 Web 或 GPU 查看器直接绘制，
+//! 不依赖文件系统与渲染后端。
+//!
+//! 曲线由 [`zcad_core::tessellate`] 按弦高与圆心角精度取样；块参照拆出的图元记为块参照本身，
+//! 便于查看器按源实体拾取。填充边界输出为闭合的面路径，嵌套边界由查看器按奇偶规则填充。
+
+use glam::DVec2;
+use serde::Serialize;
+use zcad_core::document::{Document, Entity, EntityId, FlattenOptions};
+use zcad_core::geometry::Point2;
+use zcad_core::tessellate::TessellateOptions;
+
+use crate::plot::{curve_points, display_color, ring_points};
+
+/// 离散化器选项。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TessellatorOptions {
+    /// 曲线离散精度，传给 FLATTEN 并用于圆弧、圆、多段线与填充边界。
+    pub tessellation: TessellateOptions,
+    /// 以文字包围框轮廓代替文字；关闭时不输出文字。
+    pub outline_text: bool,
+    /// 输出填充边界作为面路径。
+    pub include_fills: bool,
+    /// 背景色（`0xRRGGBB`），决定 7 号色取黑或白；`None` 按白底处理。
+    pub background: Option<u32>,
+}
+
+impl Default for TessellatorOptions {
+    fn default() -> Self {
+        Self {
+            tessellation: TessellateOptions::default(),
+            outline_text: true,
+            include_fills: true,
+            background: None,
+        }
+    }
+}
+
+/// 一条离散后的路径。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TessellatedPath {
+    /// 源实体编号；块参照拆出的图元记为块参照。
+    pub source: EntityId,
+    pub layer: String,
+    /// 解析后的显示颜色 `0xRRGGBB`。
+    pub color: u32,
+    /// 首尾相接；闭合路径不重复起点。
+    pub closed: bool,
+    /// 填充边界，应按面绘制。
+    pub filled: bool,
+    pub points: Vec<Point2>,
+}
+
+/// 离散化器，按 [`TessellatorOptions`] 生成 [`TessellatedPath`]。
+#[derive(Debug, Clone, Default)]
+pub struct Tessellator {
+    options: TessellatorOptions,
+}
+
+impl Tessellator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(mut self, options: TessellatorOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn options(&self) -> &TessellatorOptions {
+        &self.options
+    }
+
+    /// 离散化整个文档，跳过隐藏图层上的图元；顺序与实体顺序一致。
+    pub fn tessellate(&self, document: &Document) -> Vec<TessellatedPath> {
+        let (flat, sources) = document.flatten_with_sources(&FlattenOptions {
+            tessellation: self.options.tessellation,
+            outline_text: self.options.outline_text,
+            keep_fills: self.options.include_fills,
+            ..FlattenOptions::default()
+        });
+        let mut paths = Vec::new();
+        for (id, entity) in flat.display_list() {
+            let layer = entity.layer_name();
+            let color = flat
+                .resolve_properties(*id)
+                .map(|resolved| display_color(resolved.color, self.options.background))
+                .unwrap_or(0x000000);
+            let source = sources.get(id).copied().unwrap_or(*id);
+            for (points, closed, filled) in self.entity_paths(entity) {
+                if points.len() < 2 {
+                    continue;
+                }
+                paths.push(TessellatedPath {
+                    source,
+                    layer: layer.to_string(),
+                    color,
+                    closed,
+                    filled,
+                    points: points.into_iter().map(Point2::from_vec).collect(),
+                });
+            }
+        }
+        paths
+    }
+
+    /// 单个压平后图元的点列、是否闭合与是否填充。
+    fn entity_paths(&self, entity: &Entity) -> Vec<(Vec<DVec2>, bool, bool)> {
+        let options = &self.options.tessellation;
+        match entity {
+            Entity::Line(line) => {
+                vec![(vec![line.start.as_vec2(), line.end.as_vec2()], false, false)]
+            }
+            Entity::Arc(_) => vec![(curve_points(entity, options), false, false)],
+            Entity::Circle(_) => vec![(curve_points(entity, options), true, false)],
+            Entity::Polyline(polyline) => {
+                vec![(curve_points(entity, options), polyline.is_closed, false)]
+            }
+            Entity::Hatch(hatch) => hatch
+                .loops
+                .iter()
+                .map(|loop_path| (ring_points(loop_path, options), true, true))
+                .collect(),
+            // 文字已由 FLATTEN 转为轮廓或不输出，区域覆盖只影响显示
+            _ => Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,2244 @@
+//! DXF 写出：组码对输出、光栅图像相关对象（IMAGE/WIPEOUT、IMAGEDEF、IMAGEDEF_REACTOR、
+//! RASTERVARIABLES、WIPEOUTVARIABLES 以及 ACAD_IMAGE_DICT 字典）的序列化，
+//! 以及 [`write_document`] 完整文档写出。
+//!
+//! 各实体与对象按读取器识别的组码写出，写出后重新读取应得到相同的文档内容，
+//! 见 [`crate::compare::compare_documents`]。
+//! 精度、版本、句柄、行尾与代码页由 [`DxfWriteOptions`] 在每次写出时指定。
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{Display, Write as _};
+use std::path::Path;
+
+use zcad_core::document::{
+    Arc, Attribute, AttributeDefinition, BlockDefinition, BlockReference, Circle, ClipMode,
+    Dimension, DimensionKind, Document, Ellipse, Entity, EntityId, EntityProperties, GeoData,
+    Hatch, HatchEdge, HatchLoop, ImageDefReactor, LINETYPE_BYLAYER, Layer, LayerState, Leader,
+    Line, LineWeight, Linetype, LinetypeEmbeddedContent, MLeader, MLeaderContent, MText,
+    ObjectColor, Polyline, REVCLOUD_XDATA_APP, RasterImage, RasterImageClip, RasterImageDefinition,
+    RasterImageDisplayOptions, RasterImageVariables, RevisionCloud, Shape, ShapeFile, Spline, Text,
+    ThreeDFace, USER_DATA_XDATA_APP, Underlay, UnderlayDefinition, UnderlayKind, UserData, Wipeout,
+    WipeoutVariables, XRef,
+};
+use zcad_core::geometry::{Point2, Point3, Vector2};
+
+/// 根字典中光栅图像字典的条目名。
+pub const IMAGE_DICTIONARY_KEY: &str = "ACAD_IMAGE_DICT";
+/// 根字典中 RASTERVARIABLES 的条目名。
+pub const IMAGE_VARIABLES_KEY: &str = "ACAD_IMAGE_VARS";
+/// 根字典中 WIPEOUTVARIABLES 的条目名。
+pub const WIPEOUT_VARIABLES_KEY: &str = "ACAD_WIPEOUT_VARS";
+/// 图层状态字典的条目名，AutoCAD 将其放在 LAYER 表的扩展字典中，写出时登记在根字典。
+pub const LAYER_STATES_KEY: &str = "ACAD_LAYERSTATES";
+/// 模型空间绘制顺序表的条目名，AutoCAD 将其放在块表记录的扩展字典中，写出时登记在根字典。
+pub const SORTENTS_KEY: &str = "ACAD_SORTENTS";
+
+/// 新建 IMAGEDEF_REACTOR 时使用的类版本。
+const IMAGE_DEF_REACTOR_CLASS_VERSION: i32 = 2;
+/// 新建 RASTERVARIABLES 时使用的类版本。
+const RASTER_VARIABLES_CLASS_VERSION: i32 = 0;
+/// 未指定代码页时写入的 `$DWGCODEPAGE`。
+const DEFAULT_CODE_PAGE: &str = "ANSI_1252";
+/// 扩展数据字符串（组码 1000）的最大长度。
+const XDATA_STRING_LIMIT: usize = 255;
+
+/// 写出的 DXF 版本，决定 `$ACADVER` 与是否输出子类标记。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DxfVersion {
+    R12,
+    R2000,
+    R2004,
+    R2007,
+    R2010,
+    R2013,
+    #[default]
+    R2018,
+}
+
+impl DxfVersion {
+    /// `$ACADVER` 取值。
+    pub fn acadver(self) -> &'static str {
+        match self {
+            DxfVersion::R12 => "AC1009",
+            DxfVersion::R2000 => "AC1015",
+            DxfVersion::R2004 => "AC1018",
+            DxfVersion::R2007 => "AC1021",
+            DxfVersion::R2010 => "AC1024",
+            DxfVersion::R2013 => "AC1027",
+            DxfVersion::R2018 => "AC1032",
+        }
+    }
+
+    /// 由 `$ACADVER` 取值识别版本，不在写出范围内的版本返回 `None`。
+    pub fn from_acadver(acadver: &str) -> Option<Self> {
+        [
+            DxfVersion::R12,
+            DxfVersion::R2000,
+            DxfVersion::R2004,
+            DxfVersion::R2007,
+            DxfVersion::R2010,
+            DxfVersion::R2013,
+            DxfVersion::R2018,
+        ]
+        .into_iter()
+        .find(|version| version.acadver() == acadver.trim())
+    }
+
+    /// R13 起才有子类标记（组码 100）。
+    pub fn has_subclass_markers(self) -> bool {
+        self != DxfVersion::R12
+    }
+}
+
+/// 行尾风格。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+/// 单次保存的格式选项。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DxfWriteOptions {
+    /// 实数保留的小数位数；`None` 时输出可精确往返的最短表示。
+    pub precision: Option<usize>,
+    pub version: DxfVersion,
+    /// 是否写出对象句柄。关闭时同时省略句柄引用与 OBJECTS 段，适合只交换几何的下游。
+    pub write_handles: bool,
+    pub line_ending: LineEnding,
+    /// 写入 `$DWGCODEPAGE` 的代码页，例如 `ANSI_1252`、`ANSI_936`。
+    /// 文本始终按 UTF-8 写出，R2007 之前的版本由读取方按该代码页解释。
+    pub code_page: String,
+    /// 以 `ZCAD_USERDATA` 扩展数据写出模型空间实体的用户数据，并登记对应的 APPID。
+    pub write_user_data: bool,
+}
+
+impl Default for DxfWriteOptions {
+    fn default() -> Self {
+        Self {
+            precision: None,
+            version: DxfVersion::default(),
+            write_handles: true,
+            line_ending: LineEnding::default(),
+            code_page: DEFAULT_CODE_PAGE.to_string(),
+            write_user_data: false,
+        }
+    }
+}
+
+/// 组码对输出缓冲区。
+#[derive(Debug, Default)]
+pub struct DxfWriter {
+    out: String,
+    options: DxfWriteOptions,
+    /// 待写出的实体特性覆盖，见 [`DxfWriter::set_entity_properties`]。
+    entity_properties: Option<EntityProperties>,
+}
+
+impl DxfWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(options: DxfWriteOptions) -> Self {
+        Self {
+            out: String::new(),
+            options,
+            entity_properties: None,
+        }
+    }
+
+    #[inline]
+    pub fn options(&self) -> &DxfWriteOptions {
+        &self.options
+    }
+
+    /// 写出一个组码对，组码按 DXF 习惯右对齐到三位。
+    pub fn pair(&mut self, code: i32, value: impl Display) {
+        let eol = self.options.line_ending.as_str();
+        let _ = write!(self.out, "{code:>3}{eol}{value}{eol}");
+    }
+
+    /// 按 `precision` 写出实数。
+    pub fn real(&mut self, code: i32, value: f64) {
+        match self.options.precision {
+            Some(precision) => self.pair(code, format_args!("{value:.precision$}")),
+            None => self.pair(code, value),
+        }
+    }
+
+    /// 写出对象自身的句柄（组码 5），关闭句柄时省略。
+    pub fn handle(&mut self, handle: &str) {
+        if self.options.write_handles {
+            self.pair(5, handle);
+        }
+    }
+
+    /// 写出指向其他对象的句柄引用（组码 330/340/350/360 等），关闭句柄时省略。
+    pub fn pointer(&mut self, code: i32, handle: &str) {
+        if self.options.write_handles {
+            self.pair(code, handle);
+        }
+    }
+
+    /// 设置随后写出的第一个实体的特性覆盖，由 [`DxfWriter::entity_layer`] 写在图层之后。
+    pub fn set_entity_properties(&mut self, properties: Option<&EntityProperties>) {
+        self.entity_properties = properties.cloned();
+    }
+
+    /// 写出实体的图层（组码 8），随后写出待写的特性覆盖：颜色 62（真彩色为 420）、
+    /// 线型 6 与线宽 370，随层的项省略。
+    pub fn entity_layer(&mut self, layer: &str) {
+        self.pair(8, layer);
+        let Some(properties) = self.entity_properties.take() else {
+            return;
+        };
+        match properties.color {
+            ObjectColor::ByLayer => {}
+            ObjectColor::TrueColor(rgb) => self.pair(420, rgb),
+            color => self.pair(62, color.aci().expect("索引色与随块都有 ACI")),
+        }
+        if properties.linetype != LINETYPE_BYLAYER {
+            self.pair(6, &properties.linetype);
+        }
+        if properties.lineweight != LineWeight::ByLayer {
+            self.pair(370, properties.lineweight.code());
+        }
+    }
+
+    /// 写出子类标记，R12 不输出。
+    pub fn subclass(&mut self, name: &str) {
+        if self.options.version.has_subclass_markers() {
+            self.pair(100, name);
+        }
+    }
+
+    pub fn point(&mut self, code: i32, point: Point2) {
+        self.real(code, point.x());
+        self.real(code + 10, point.y());
+        self.real(code + 20, 0.0);
+    }
+
+    pub fn vector(&mut self, code: i32, vector: Vector2) {
+        self.real(code, vector.x());
+        self.real(code + 10, vector.y());
+        self.real(code + 20, 0.0);
+    }
+
+    pub fn begin_section(&mut self, name: &str) {
+        self.pair(0, "SECTION");
+        self.pair(2, name);
+    }
+
+    pub fn end_section(&mut self) {
+        self.pair(0, "ENDSEC");
+    }
+
+    pub fn finish(mut self) -> String {
+        self.pair(0, "EOF");
+        self.out
+    }
+}
+
+/// 句柄分配器：从文档中已用句柄的最大值之后递增分配十六进制句柄。
+#[derive(Debug)]
+pub struct HandleAllocator {
+    next: u64,
+}
+
+impl HandleAllocator {
+    pub fn for_document(document: &Document) -> Self {
+        let mut handles: Vec<&str> = Vec::new();
+        for (_, definition) in document.raster_image_definitions() {
+            handles.push(&definition.handle);
+        }
+        handles.extend(
+            document
+                .image_def_reactors()
+                .flat_map(|(_, reactor)| [Some(&reactor.handle), reactor.image_handle.as_ref()])
+                .flatten()
+                .map(String::as_str),
+        );
+        if let Some(dictionary) = document.image_dictionary() {
+            handles.extend(dictionary.handle.as_deref());
+        }
+        if let Some(variables) = document.raster_image_variables() {
+            handles.extend(variables.handle.as_deref());
+        }
+        if let Some(variables) = document.wipeout_variables() {
+            handles.extend(variables.handle.as_deref());
+        }
+        if let Some(geodata) = document.geodata() {
+            handles.extend(geodata.handle.as_deref());
+        }
+        handles.extend(document.block_handles().map(|(handle, _)| handle));
+        handles.extend(document.shape_files().map(|file| file.handle.as_str()));
+        handles.extend(
+            document
+                .underlay_definitions()
+                .map(|definition| definition.handle.as_str()),
+        );
+        handles.extend(
+            document
+                .linetypes()
+                .flat_map(|linetype| &linetype.elements)
+                .filter_map(|element| element.embedded.as_ref()?.style_handle.as_deref()),
+        );
+        // 实体中引用的句柄即使目标缺失也不能被新对象占用
+        let entities = document
+            .entities()
+            .map(|(_, entity)| entity)
+            .chain(document.blocks().flat_map(|block| &block.entities));
+        for entity in entities {
+            match entity {
+                Entity::RasterImage(image) => {
+                    handles.push(&image.image_def_handle);
+                    handles.extend(image.image_def_reactor_handle.as_deref());
+                }
+                Entity::Underlay(underlay) => handles.push(&underlay.definition_handle),
+                Entity::Shape(shape) => handles.extend(shape.style_handle.as_deref()),
+                Entity::Hatch(hatch) => handles.extend(
+                    hatch
+                        .loops
+                        .iter()
+                        .flat_map(|hatch_loop| &hatch_loop.boundary_handles)
+                        .map(String::as_str),
+                ),
+                Entity::MLeader(mleader) => {
+                    if let MLeaderContent::Block { block } = &mleader.content {
+                        handles.extend(block.block_handle.as_deref());
+                    }
+                }
+                _ => {}
+            }
+        }
+        let max = handles
+            .into_iter()
+            .filter_map(|handle| u64::from_str_radix(handle, 16).ok())
+            .max()
+            .unwrap_or(0);
+        Self { next: max + 1 }
+    }
+
+    /// 下一个可用句柄，即 `$HANDSEED`。
+    pub fn seed(&self) -> String {
+        format!("{:X}", self.next)
+    }
+
+    pub fn allocate(&mut self) -> String {
+        let handle = format!("{:X}", self.next);
+        self.next += 1;
+        handle
+    }
+}
+
+/// 写出 IMAGE 实体；`handle` 为实体句柄，IMAGEDEF_REACTOR 通过它回指图像，
+/// `reactor` 为该图像对应的 IMAGEDEF_REACTOR 句柄。
+pub fn write_raster_image(
+    writer: &mut DxfWriter,
+    image: &RasterImage,
+    handle: &str,
+    reactor: Option<&str>,
+) {
+    writer.pair(0, "IMAGE");
+    writer.handle(handle);
+    writer.subclass("AcDbEntity");
+    writer.entity_layer(&image.layer);
+    writer.subclass("AcDbRasterImage");
+    write_image_frame(
+        writer,
+        image.insert,
+        image.u_vector,
+        image.v_vector,
+        image.image_size,
+    );
+    writer.pointer(340, &image.image_def_handle);
+    write_display_options(writer, &image.display_options, &image.clip);
+    if let Some(reactor) = reactor {
+        writer.pointer(360, reactor);
+    }
+}
+
+/// 写出 WIPEOUT 实体，其结构与 IMAGE 相同但不引用 IMAGEDEF。
+pub fn write_wipeout(writer: &mut DxfWriter, wipeout: &Wipeout, handle: &str) {
+    writer.pair(0, "WIPEOUT");
+    writer.handle(handle);
+    writer.subclass("AcDbEntity");
+    writer.entity_layer(&wipeout.layer);
+    writer.subclass("AcDbWipeout");
+    write_image_frame(
+        writer,
+        wipeout.insert,
+        wipeout.u_vector,
+        wipeout.v_vector,
+        wipeout.image_size,
+    );
+    write_display_options(writer, &wipeout.display_options, &wipeout.clip);
+}
+
+fn write_image_frame(
+    writer: &mut DxfWriter,
+    insert: Point2,
+    u_vector: Vector2,
+    v_vector: Vector2,
+    size: Vector2,
+) {
+    writer.point(10, insert);
+    writer.vector(11, u_vector);
+    writer.vector(12, v_vector);
+    writer.real(13, size.x());
+    writer.real(23, size.y());
+}
+
+fn write_display_options(
+    writer: &mut DxfWriter,
+    options: &RasterImageDisplayOptions,
+    clip: &Option<RasterImageClip>,
+) {
+    let flags = i16::from(options.show_image)
+        | (i16::from(options.show_border) << 1)
+        | (i16::from(options.use_clipping) << 2);
+    writer.pair(70, flags);
+    for (code, value) in [
+        (280, options.brightness),
+        (281, options.contrast),
+        (282, options.fade),
+    ] {
+        if let Some(value) = value {
+            writer.pair(code, value);
+        }
+    }
+    writer.pair(71, i16::from(clip.is_some()));
+    let Some(clip) = clip else {
+        return;
+    };
+    match clip {
+        RasterImageClip::Rectangle { min, max, .. } => {
+            writer.pair(72, 1);
+            writer.pair(91, 2);
+            for vertex in [min, max] {
+                writer.real(14, vertex.x());
+                writer.real(24, vertex.y());
+            }
+        }
+        RasterImageClip::Polygon { vertices, .. } => {
+            writer.pair(72, 2);
+            writer.pair(91, vertices.len());
+            for vertex in vertices {
+                writer.real(14, vertex.x());
+                writer.real(24, vertex.y());
+            }
+        }
+    }
+    writer.pair(290, i16::from(clip.mode() == ClipMode::Inside));
+}
+
+/// 写出 IMAGEDEF 对象，`owner` 为所属的 ACAD_IMAGE_DICT 字典句柄。
+pub fn write_image_definition(
+    writer: &mut DxfWriter,
+    definition: &RasterImageDefinition,
+    owner: &str,
+    reactors: &[&str],
+) {
+    writer.pair(0, "IMAGEDEF");
+    writer.handle(&definition.handle);
+    if !reactors.is_empty() && writer.options().write_handles {
+        writer.pair(102, "{ACAD_REACTORS");
+        for reactor in reactors {
+            writer.pointer(330, reactor);
+        }
+        writer.pair(102, "}");
+    }
+    writer.pointer(330, owner);
+    writer.subclass("AcDbRasterImageDef");
+    writer.pair(90, 0);
+    writer.pair(1, &definition.file_path);
+    if let Some(name) = &definition.name {
+        writer.pair(2, name);
+    }
+    if let Some(size) = definition.image_size_pixels {
+        writer.real(10, size.x());
+        writer.real(20, size.y());
+    }
+    if let Some(pixel) = definition.pixel_size {
+        writer.real(11, pixel.x());
+        writer.real(21, pixel.y());
+    }
+    writer.pair(280, 1);
+    writer.pair(281, 0);
+}
+
+/// 写出 IMAGEDEF_REACTOR：第一个 330 为所属 IMAGEDEF，子类中的 330 指向 IMAGE 实体。
+pub fn write_image_def_reactor(writer: &mut DxfWriter, reactor: &ImageDefReactor) {
+    writer.pair(0, "IMAGEDEF_REACTOR");
+    writer.handle(&reactor.handle);
+    if let Some(owner) = &reactor.owner_handle {
+        writer.pointer(330, owner);
+    }
+    writer.subclass("AcDbRasterImageDefReactor");
+    writer.pair(90, reactor.class_version);
+    if let Some(image) = &reactor.image_handle {
+        writer.pointer(330, image);
+    }
+}
+
+/// 写出 RASTERVARIABLES，只输出文档中记录的字段。
+pub fn write_raster_variables(
+    writer: &mut DxfWriter,
+    variables: &RasterImageVariables,
+    handle: &str,
+    owner: &str,
+) {
+    writer.pair(0, "RASTERVARIABLES");
+    writer.handle(handle);
+    writer.pointer(330, owner);
+    writer.subclass("AcDbRasterVariables");
+    if let Some(version) = variables.class_version {
+        writer.pair(90, version);
+    }
+    for (code, value) in [
+        (70, variables.frame),
+        (71, variables.quality),
+        (72, variables.units),
+    ] {
+        if let Some(value) = value {
+            writer.pair(code, value);
+        }
+    }
+}
+
+/// 写出 WIPEOUTVARIABLES，只输出文档中记录的字段。
+pub fn write_wipeout_variables(
+    writer: &mut DxfWriter,
+    variables: &WipeoutVariables,
+    handle: &str,
+    owner: &str,
+) {
+    writer.pair(0, "WIPEOUTVARIABLES");
+    writer.handle(handle);
+    writer.pointer(330, owner);
+    writer.subclass("AcDbWipeoutVariables");
+    if let Some(version) = variables.class_version {
+        writer.pair(90, version);
+    }
+    if let Some(frame) = variables.frame {
+        writer.pair(70, frame);
+    }
+}
+
+/// 文档没有 RASTERVARIABLES 时写出的缺省值，与 AutoCAD 新建图纸一致
+/// （显示边框、高质量、无单位）。
+fn default_raster_variables() -> RasterImageVariables {
+    RasterImageVariables {
+        handle: None,
+        class_version: Some(RASTER_VARIABLES_CLASS_VERSION),
+        frame: Some(1),
+        quality: Some(1),
+        units: Some(0),
+    }
+}
+
+/// 写出 DICTIONARY 对象，`entries` 为（条目名，对象句柄）。
+pub fn write_dictionary(
+    writer: &mut DxfWriter,
+    handle: &str,
+    owner: &str,
+    entries: &[(String, String)],
+) {
+    writer.pair(0, "DICTIONARY");
+    writer.handle(handle);
+    writer.pointer(330, owner);
+    writer.subclass("AcDbDictionary");
+    writer.pair(281, 1);
+    for (name, object) in entries {
+        writer.pair(3, name);
+        writer.pointer(350, object);
+    }
+}
+
+/// 光栅相关对象的句柄规划：实体句柄、图像字典条目与各 IMAGE 对应的反应器。
+#[derive(Debug)]
+struct RasterPlan {
+    /// 按文档顺序排列的 IMAGE/WIPEOUT 句柄，以及 IMAGE 的反应器句柄。
+    entities: Vec<(String, Option<String>)>,
+    dictionary_handle: String,
+    dictionary_entries: Vec<(String, String)>,
+    variables_handle: String,
+    /// 文档记录了 WIPEOUTVARIABLES 时才写出，缺省时由读取方按默认值显示边框。
+    wipeout_variables_handle: Option<String>,
+    reactors: Vec<ImageDefReactor>,
+}
+
+impl RasterPlan {
+    fn new(document: &Document, handles: &mut HandleAllocator) -> Self {
+        let mut reactors: Vec<ImageDefReactor> = document
+            .image_def_reactors()
+            .map(|(_, reactor)| reactor.clone())
+            .collect();
+
+        // 已有反应器时沿用其记录的图像句柄；缺少反应器的 IMAGE 补建一个
+        let mut entities = Vec::new();
+        for (_, entity) in document.entities() {
+            match entity {
+                Entity::RasterImage(image) => {
+                    let existing = image.image_def_reactor_handle.as_ref().and_then(|handle| {
+                        reactors.iter().find(|reactor| &reactor.handle == handle)
+                    });
+                    match existing {
+                        Some(reactor) => {
+                            let image_handle = reactor
+                                .image_handle
+                                .clone()
+                                .unwrap_or_else(|| handles.allocate());
+                            entities.push((image_handle, Some(reactor.handle.clone())));
+                        }
+                        None => {
+                            let image_handle = handles.allocate();
+                            let reactor = ImageDefReactor {
+                                handle: handles.allocate(),
+                                class_version: IMAGE_DEF_REACTOR_CLASS_VERSION,
+                                owner_handle: Some(image.image_def_handle.clone()),
+                                image_handle: Some(image_handle.clone()),
+                            };
+                            entities.push((image_handle, Some(reactor.handle.clone())));
+                            reactors.push(reactor);
+                        }
+                    }
+                }
+                Entity::Wipeout(_) => entities.push((handles.allocate(), None)),
+                _ => {}
+            }
+        }
+        reactors.sort_by(|a, b| a.handle.cmp(&b.handle));
+
+        let dictionary = document.image_dictionary();
+        let dictionary_handle = dictionary
+            .and_then(|dictionary| dictionary.handle.clone())
+            .unwrap_or_else(|| handles.allocate());
+        let mut dictionary_entries: Vec<(String, String)> = dictionary
+            .map(|dictionary| {
+                dictionary
+                    .entries
+                    .iter()
+                    .map(|entry| (entry.name.clone(), entry.image_def_handle.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // 未登记在字典中的 IMAGEDEF 以名称或文件名补登，保证每个定义都有所属字典
+        let mut names: HashSet<String> = dictionary_entries
+            .iter()
+            .map(|(name, _)| name.to_ascii_uppercase())
+            .collect();
+        for definition in sorted_definitions(document) {
+            if dictionary_entries
+                .iter()
+                .any(|(_, handle)| handle == &definition.handle)
+            {
+                continue;
+            }
+            let base = definition
+                .name
+                .clone()
+                .or_else(|| {
+                    Path::new(&definition.file_path.replace('\\', "/"))
+                        .file_stem()
+                        .map(|stem| stem.to_string_lossy().into_owned())
+                })
+                .unwrap_or_else(|| definition.handle.clone());
+            let mut name = base.clone();
+            let mut suffix = 1;
+            while !names.insert(name.to_ascii_uppercase()) {
+                suffix += 1;
+                name = format!("{base}_{suffix}");
+            }
+            dictionary_entries.push((name, definition.handle.clone()));
+        }
+        dictionary_entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let variables_handle = document
+            .raster_image_variables()
+            .and_then(|variables| variables.handle.clone())
+            .unwrap_or_else(|| handles.allocate());
+        let wipeout_variables_handle = document.wipeout_variables().map(|variables| {
+            variables
+                .handle
+                .clone()
+                .unwrap_or_else(|| handles.allocate())
+        });
+
+        Self {
+            entities,
+            dictionary_handle,
+            dictionary_entries,
+            variables_handle,
+            wipeout_variables_handle,
+            reactors,
+        }
+    }
+
+    /// 根字典中的 ACAD_IMAGE_DICT 与 ACAD_IMAGE_VARS 条目。
+    fn image_root_entries(&self) -> Vec<(String, String)> {
+        vec![
+            (
+                IMAGE_DICTIONARY_KEY.to_string(),
+                self.dictionary_handle.clone(),
+            ),
+            (
+                IMAGE_VARIABLES_KEY.to_string(),
+                self.variables_handle.clone(),
+            ),
+        ]
+    }
+
+    /// 根字典中的 ACAD_WIPEOUT_VARS 条目。
+    fn wipeout_root_entry(&self) -> Option<(String, String)> {
+        self.wipeout_variables_handle
+            .as_ref()
+            .map(|handle| (WIPEOUT_VARIABLES_KEY.to_string(), handle.clone()))
+    }
+}
+
+fn sorted_definitions(document: &Document) -> Vec<&RasterImageDefinition> {
+    let mut definitions: Vec<&RasterImageDefinition> = document
+        .raster_image_definitions()
+        .map(|(_, definition)| definition)
+        .collect();
+    definitions.sort_by(|a, b| a.handle.cmp(&b.handle));
+    definitions
+}
+
+/// 写出 ENTITIES 段中的 IMAGE 与 WIPEOUT。
+fn write_raster_entities(writer: &mut DxfWriter, document: &Document, plan: &RasterPlan) {
+    let mut planned = plan.entities.iter();
+    for (_, entity) in document.entities() {
+        let (handle, reactor) = match entity {
+            Entity::RasterImage(_) | Entity::Wipeout(_) => {
+                planned.next().expect("每个光栅实体都已分配句柄")
+            }
+            _ => continue,
+        };
+        match entity {
+            Entity::RasterImage(image) => {
+                write_raster_image(writer, image, handle, reactor.as_deref())
+            }
+            Entity::Wipeout(wipeout) => write_wipeout(writer, wipeout, handle),
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// 写出 OBJECTS 段中的光栅对象：ACAD_IMAGE_DICT、IMAGEDEF、IMAGEDEF_REACTOR 与
+/// RASTERVARIABLES。`root_handle` 为根字典句柄。
+fn write_raster_objects(
+    writer: &mut DxfWriter,
+    document: &Document,
+    plan: &RasterPlan,
+    root_handle: &str,
+) {
+    write_dictionary(
+        writer,
+        &plan.dictionary_handle,
+        root_handle,
+        &plan.dictionary_entries,
+    );
+
+    for definition in sorted_definitions(document) {
+        let reactors: Vec<&str> = plan
+            .reactors
+            .iter()
+            .filter(|reactor| reactor.owner_handle.as_deref() == Some(definition.handle.as_str()))
+            .map(|reactor| reactor.handle.as_str())
+            .collect();
+        write_image_definition(writer, definition, &plan.dictionary_handle, &reactors);
+    }
+    for reactor in &plan.reactors {
+        write_image_def_reactor(writer, reactor);
+    }
+
+    let variables = document
+        .raster_image_variables()
+        .cloned()
+        .unwrap_or_else(default_raster_variables);
+    write_raster_variables(writer, &variables, &plan.variables_handle, root_handle);
+}
+
+/// 文档记录了 WIPEOUTVARIABLES 时写出，所属字典为根字典。
+fn write_planned_wipeout_variables(
+    writer: &mut DxfWriter,
+    document: &Document,
+    plan: &RasterPlan,
+    root_handle: &str,
+) {
+    if let (Some(variables), Some(handle)) = (
+        document.wipeout_variables(),
+        plan.wipeout_variables_handle.as_deref(),
+    ) {
+        write_wipeout_variables(writer, variables, handle, root_handle);
+    }
+}
+
+/// 写出 HEADER 段：`$ACADVER`、`$DWGCODEPAGE`，写出句柄时附带 `$HANDSEED`。
+pub fn write_header(writer: &mut DxfWriter, handle_seed: &str) {
+    begin_header(writer, handle_seed);
+    writer.end_section();
+}
+
+fn begin_header(writer: &mut DxfWriter, handle_seed: &str) {
+    let version = writer.options().version.acadver();
+    let code_page = writer.options().code_page.clone();
+    writer.begin_section("HEADER");
+    writer.pair(9, "$ACADVER");
+    writer.pair(1, version);
+    writer.pair(9, "$DWGCODEPAGE");
+    writer.pair(3, code_page);
+    if writer.options().write_handles {
+        writer.pair(9, "$HANDSEED");
+        writer.pair(5, handle_seed);
+    }
+}
+
+/// 按默认选项写出光栅图像与遮罩，见 [`write_raster_sections_with_options`]。
+pub fn write_raster_sections(document: &Document) -> String {
+    write_raster_sections_with_options(document, &DxfWriteOptions::default())
+}
+
+/// 将文档中的光栅图像与遮罩写成含 HEADER、ENTITIES 与 OBJECTS 段的 DXF 文本。
+/// 图像字典、反应器与光栅变量按文档记录写出，缺失的字典条目与句柄会自动补齐；
+/// 不写句柄时省略 OBJECTS 段。
+pub fn write_raster_sections_with_options(
+    document: &Document,
+    options: &DxfWriteOptions,
+) -> String {
+    let mut handles = HandleAllocator::for_document(document);
+    let root_handle = handles.allocate();
+    let plan = RasterPlan::new(document, &mut handles);
+    let mut writer = DxfWriter::with_options(options.clone());
+
+    write_header(&mut writer, &handles.seed());
+
+    writer.begin_section("ENTITIES");
+    write_raster_entities(&mut writer, document, &plan);
+    writer.end_section();
+
+    if options.write_handles {
+        writer.begin_section("OBJECTS");
+        let mut root_entries = plan.image_root_entries();
+        root_entries.extend(plan.wipeout_root_entry());
+        write_dictionary(&mut writer, &root_handle, "0", &root_entries);
+        write_raster_objects(&mut writer, document, &plan, &root_handle);
+        write_planned_wipeout_variables(&mut writer, document, &plan, &root_handle);
+        writer.end_section();
+    }
+
+    writer.finish()
+}
+
+/// 按默认选项写出完整文档，见 [`write_document_with_options`]。
+pub fn write_document(document: &Document) -> String {
+    write_document_with_options(document, &DxfWriteOptions::default())
+}
+
+/// 将文档写成含 HEADER、TABLES、BLOCKS、ENTITIES 与 OBJECTS 段的 DXF 文本。
+/// 已有句柄（块、型文件、底图与图像定义等）原样保留，实体与表记录的句柄重新分配；
+/// 不写句柄时省略 OBJECTS 段与依赖句柄的型文件记录。
+pub fn write_document_with_options(document: &Document, options: &DxfWriteOptions) -> String {
+    let mut handles = HandleAllocator::for_document(document);
+    let root_handle = handles.allocate();
+    let plan = RasterPlan::new(document, &mut handles);
+
+    // HEADER 中的 $HANDSEED 取决于全部句柄分配完成后的结果，因此先写其余各段
+    let mut body = DxfWriter::with_options(options.clone());
+    write_tables(&mut body, document, &mut handles);
+    write_blocks(&mut body, document, &mut handles);
+
+    body.begin_section("ENTITIES");
+    let boundaries = allocate_hatch_boundaries(document, &mut handles);
+    let mut planned = plan.entities.iter();
+    let mut entity_handles = HashMap::new();
+    for (id, entity) in document.entities() {
+        let user_data = document.user_data(*id).filter(|_| options.write_user_data);
+        body.set_entity_properties(document.entity_properties(*id));
+        let handle = match entity {
+            Entity::RasterImage(image) => {
+                let (handle, reactor) = planned.next().expect("每个光栅实体都已分配句柄");
+                write_raster_image(&mut body, image, handle, reactor.as_deref());
+                if let Some(data) = user_data {
+                    write_user_data(&mut body, data);
+                }
+                handle.clone()
+            }
+            Entity::Wipeout(wipeout) => {
+                let (handle, _) = planned.next().expect("每个光栅实体都已分配句柄");
+                write_wipeout(&mut body, wipeout, handle);
+                if let Some(data) = user_data {
+                    write_user_data(&mut body, data);
+                }
+                handle.clone()
+            }
+            _ => {
+                let handle = boundaries
+                    .get(id)
+                    .cloned()
+                    .unwrap_or_else(|| handles.allocate());
+                match entity {
+                    Entity::Hatch(hatch) if !boundaries.is_empty() => {
+                        let hatch = Entity::Hatch(with_boundary_handles(hatch, &boundaries));
+                        write_entity_record(&mut body, &hatch, &handle, &mut handles, user_data);
+                    }
+                    _ => write_entity_record(&mut body, entity, &handle, &mut handles, user_data),
+                }
+                handle
+            }
+        };
+        entity_handles.insert(*id, handle);
+    }
+    body.end_section();
+
+    if options.write_handles {
+        body.begin_section("OBJECTS");
+        write_document_objects(
+            &mut body,
+            document,
+            &plan,
+            &root_handle,
+            &entity_handles,
+            &mut handles,
+        );
+        body.end_section();
+    }
+
+    let mut writer = DxfWriter::with_options(options.clone());
+    begin_header(&mut writer, &handles.seed());
+    writer.pair(9, "$MIRRTEXT");
+    writer.pair(70, i16::from(document.mirror_text()));
+    let units = document.units();
+    for (variable, code) in [
+        ("$INSUNITS", units.insertion.code()),
+        ("$LUNITS", units.length_format.code()),
+        ("$LUPREC", i16::from(units.length_precision)),
+        ("$AUNITS", units.angle_format.code()),
+        ("$AUPREC", i16::from(units.angle_precision)),
+    ] {
+        writer.pair(9, variable);
+        writer.pair(70, code);
+    }
+    writer.end_section();
+    writer.out.push_str(&body.out);
+    writer.finish()
+}
+
+/// 写出符号表记录外层的 TABLE 头，记录数写入组码 70。
+fn begin_table(writer: &mut DxfWriter, name: &str, count: usize, handles: &mut HandleAllocator) {
+    writer.pair(0, "TABLE");
+    writer.pair(2, name);
+    writer.handle(&handles.allocate());
+    writer.subclass("AcDbSymbolTable");
+    writer.pair(70, count);
+}
+
+fn begin_table_record(
+    writer: &mut DxfWriter,
+    kind: &str,
+    handle: &str,
+    subclass: &str,
+    name: &str,
+    flags: i16,
+) {
+    writer.pair(0, kind);
+    writer.handle(handle);
+    writer.subclass("AcDbSymbolTableRecord");
+    writer.subclass(subclass);
+    writer.pair(2, name);
+    writer.pair(70, flags);
+}
+
+/// 写出 TABLES 段：LTYPE、LAYER、写出用户数据时的 APPID 以及登记型文件的 STYLE 记录。
+fn write_tables(writer: &mut DxfWriter, document: &Document, handles: &mut HandleAllocator) {
+    writer.begin_section("TABLES");
+
+    let mut linetypes: Vec<&Linetype> = document.linetypes().collect();
+    linetypes.sort_by(|a, b| a.name.cmp(&b.name));
+    if !linetypes.is_empty() {
+        begin_table(writer, "LTYPE", linetypes.len(), handles);
+        for linetype in linetypes {
+            write_linetype(writer, linetype, &handles.allocate());
+        }
+        writer.pair(0, "ENDTAB");
+    }
+
+    let mut layers: Vec<&Layer> = document.layers().collect();
+    layers.sort_by(|a, b| a.name.cmp(&b.name));
+    begin_table(writer, "LAYER", layers.len(), handles);
+    for layer in layers {
+        write_layer(writer, layer, &handles.allocate());
+    }
+    writer.pair(0, "ENDTAB");
+
+    let has_user_data = document
+        .entities()
+        .any(|(id, _)| document.user_data(*id).is_some());
+    if has_user_data && writer.options().write_user_data {
+        begin_table(writer, "APPID", 1, handles);
+        begin_table_record(
+            writer,
+            "APPID",
+            &handles.allocate(),
+            "AcDbRegAppTableRecord",
+            USER_DATA_XDATA_APP,
+            0,
+        );
+        writer.pair(0, "ENDTAB");
+    }
+
+    let mut shape_files: Vec<&ShapeFile> = document.shape_files().collect();
+    shape_files.sort_by(|a, b| a.handle.cmp(&b.handle));
+    if !shape_files.is_empty() && writer.options().write_handles {
+        begin_table(writer, "STYLE", shape_files.len(), handles);
+        for shape_file in shape_files {
+            write_shape_file(writer, shape_file);
+        }
+        writer.pair(0, "ENDTAB");
+    }
+
+    writer.end_section();
+}
+
+/// 写出 LAYER 记录；关闭的图层以负颜色号表示，真彩色同时写出近似的索引色。
+pub fn write_layer(writer: &mut DxfWriter, layer: &Layer, handle: &str) {
+    // 标志位 1 为冻结、4 为锁定
+    let flags = i16::from(layer.is_frozen) | i16::from(layer.is_locked) << 2;
+    begin_table_record(
+        writer,
+        "LAYER",
+        handle,
+        "AcDbLayerTableRecord",
+        &layer.name,
+        flags,
+    );
+    let index = match layer.color {
+        ObjectColor::Index(index) => i16::from(index),
+        _ => 7,
+    };
+    writer.pair(62, if layer.is_visible { index } else { -index });
+    if let ObjectColor::TrueColor(rgb) = layer.color {
+        writer.pair(420, rgb);
+    }
+    writer.pair(6, &layer.linetype);
+    writer.pair(370, layer.lineweight.code());
+}
+
+/// 按绘制顺序重新分配实体句柄作为排序句柄：绘制顺序第 k 个实体取全部实体句柄中第 k 小的一个，
+/// 按排序句柄比较即得到绘制顺序。
+fn sort_handles(
+    document: &Document,
+    entity_handles: &HashMap<EntityId, String>,
+) -> Vec<(String, String)> {
+    let ordered: Vec<&String> = document
+        .entities_in_draw_order()
+        .into_iter()
+        .filter_map(|(id, _)| entity_handles.get(id))
+        .collect();
+    let mut sorted: Vec<&String> = ordered.clone();
+    sorted.sort_by_key(|handle| u64::from_str_radix(handle, 16).unwrap_or(u64::MAX));
+    ordered
+        .into_iter()
+        .zip(sorted)
+        .map(|(entity, sort)| (entity.clone(), sort.clone()))
+        .collect()
+}
+
+/// 写出 SORTENTSTABLE：每个条目依次为实体句柄（组码 331）与排序句柄（组码 5）。
+pub fn write_sortents_table(
+    writer: &mut DxfWriter,
+    handle: &str,
+    owner: &str,
+    entries: &[(String, String)],
+) {
+    writer.pair(0, "SORTENTSTABLE");
+    writer.handle(handle);
+    writer.pointer(330, owner);
+    writer.subclass("AcDbSortentsTable");
+    for (entity, sort) in entries {
+        writer.pointer(331, entity);
+        writer.handle(sort);
+    }
+}
+
+/// 写出图层状态 XRECORD：组码 301 为说明，其后每个图层依次写出 8 图层名、
+/// 90 标志（1 冻结、4 锁定）、62 颜色（图层关闭时取负）与真彩色 420。
+pub fn write_layer_state(writer: &mut DxfWriter, state: &LayerState, handle: &str, owner: &str) {
+    writer.pair(0, "XRECORD");
+    writer.handle(handle);
+    writer.pointer(330, owner);
+    writer.subclass("AcDbXrecord");
+    writer.pair(280, 1);
+    writer.pair(301, &state.description);
+    for entry in &state.layers {
+        writer.pair(8, &entry.layer);
+        writer.pair(
+            90,
+            i32::from(entry.is_frozen) | i32::from(entry.is_locked) << 2,
+        );
+        let index = match entry.color {
+            ObjectColor::Index(index) => i16::from(index),
+            _ => 7,
+        };
+        writer.pair(62, if entry.is_visible { index } else { -index });
+        if let ObjectColor::TrueColor(rgb) = entry.color {
+            writer.pair(420, rgb);
+        }
+    }
+}
+
+/// 写出 LTYPE 记录；嵌入文字或型的元素依次写出组码 74/75/340/46/50/44/45/9。
+pub fn write_linetype(writer: &mut DxfWriter, linetype: &Linetype, handle: &str) {
+    begin_table_record(
+        writer,
+        "LTYPE",
+        handle,
+        "AcDbLinetypeTableRecord",
+        &linetype.name,
+        0,
+    );
+    writer.pair(3, &linetype.description);
+    writer.pair(72, 65);
+    writer.pair(73, linetype.elements.len());
+    writer.real(40, linetype.pattern_length);
+    for element in &linetype.elements {
+        writer.real(49, element.length);
+        let Some(embedded) = &element.embedded else {
+            writer.pair(74, 0);
+            continue;
+        };
+        let (kind, shape_number) = match &embedded.content {
+            LinetypeEmbeddedContent::Text(_) => (2, 0),
+            LinetypeEmbeddedContent::Shape(number) => (4, *number),
+        };
+        writer.pair(74, kind | i16::from(embedded.is_absolute_rotation));
+        writer.pair(75, shape_number);
+        if let Some(style) = &embedded.style_handle {
+            writer.pointer(340, style);
+        }
+        writer.real(46, embedded.scale);
+        writer.real(50, embedded.rotation);
+        writer.real(44, embedded.offset.x());
+        writer.real(45, embedded.offset.y());
+        if let LinetypeEmbeddedContent::Text(text) = &embedded.content {
+            writer.pair(9, text);
+        }
+    }
+}
+
+/// 写出型文件 STYLE 记录（组码 70 位 1），句柄即 SHAPE 与复杂线型引用的样式句柄。
+pub fn write_shape_file(writer: &mut DxfWriter, shape_file: &ShapeFile) {
+    writer.pair(0, "STYLE");
+    writer.pair(5, &shape_file.handle);
+    writer.subclass("AcDbSymbolTableRecord");
+    writer.subclass("AcDbTextStyleTableRecord");
+    writer.pair(2, "");
+    writer.pair(70, 1);
+    writer.real(40, 0.0);
+    writer.real(41, 1.0);
+    writer.pair(3, &shape_file.file);
+}
+
+/// 写出 BLOCKS 段：块定义按名称排序，外部参照以带路径的 XREF 块写出。
+fn write_blocks(writer: &mut DxfWriter, document: &Document, handles: &mut HandleAllocator) {
+    let mut block_handles: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (handle, name) in document.block_handles() {
+        block_handles.entry(name).or_default().push(handle);
+    }
+    for known in block_handles.values_mut() {
+        known.sort();
+    }
+
+    writer.begin_section("BLOCKS");
+    let mut blocks: Vec<&BlockDefinition> = document.blocks().collect();
+    blocks.sort_by(|a, b| a.name.cmp(&b.name));
+    for block in blocks {
+        let known = block_handles.get(block.name.as_str());
+        write_block(writer, block, known.map_or(&[][..], Vec::as_slice), handles);
+    }
+    let mut xrefs: Vec<&XRef> = document.xrefs().collect();
+    xrefs.sort_by(|a, b| a.name.cmp(&b.name));
+    for xref in xrefs {
+        write_xref_block(writer, xref, handles);
+    }
+    writer.end_section();
+}
+
+/// 写出块定义。`known` 为文档记录的块与块记录句柄，首个作为 BLOCK 句柄，第二个作为所属块记录，
+/// 使 MULTILEADER 等按句柄引用的块在重新读取后仍能解析。
+fn write_block(
+    writer: &mut DxfWriter,
+    block: &BlockDefinition,
+    known: &[&str],
+    handles: &mut HandleAllocator,
+) {
+    let flags = if block.attributes.is_empty() { 0 } else { 2 };
+    begin_block(writer, &block.name, flags, block.base_point, known, handles);
+    for entity in &block.entities {
+        match entity {
+            Entity::RasterImage(image) => {
+                write_raster_image(writer, image, &handles.allocate(), None)
+            }
+            _ => write_entity(writer, entity, handles),
+        }
+    }
+    for attribute in &block.attributes {
+        write_attribute_definition(writer, attribute, &handles.allocate());
+    }
+    end_block(writer, handles);
+}
+
+fn write_xref_block(writer: &mut DxfWriter, xref: &XRef, handles: &mut HandleAllocator) {
+    begin_block(writer, &xref.name, 4, Point2::new(0.0, 0.0), &[], handles);
+    writer.pair(1, &xref.path);
+    end_block(writer, handles);
+}
+
+fn begin_block(
+    writer: &mut DxfWriter,
+    name: &str,
+    flags: i16,
+    base_point: Point2,
+    known: &[&str],
+    handles: &mut HandleAllocator,
+) {
+    writer.pair(0, "BLOCK");
+    match known.first() {
+        Some(handle) => writer.handle(handle),
+        None => writer.handle(&handles.allocate()),
+    }
+    if let Some(record) = known.get(1) {
+        writer.pointer(330, record);
+    }
+    writer.subclass("AcDbEntity");
+    writer.pair(8, "0");
+    writer.subclass("AcDbBlockBegin");
+    writer.pair(2, name);
+    writer.pair(70, flags);
+    writer.point(10, base_point);
+    writer.pair(3, name);
+}
+
+fn end_block(writer: &mut DxfWriter, handles: &mut HandleAllocator) {
+    writer.pair(0, "ENDBLK");
+    writer.handle(&handles.allocate());
+    writer.subclass("AcDbEntity");
+    writer.pair(8, "0");
+    writer.subclass("AcDbBlockEnd");
+}
+
+/// 写出 OBJECTS 段：根字典、图层状态、绘制顺序表、光栅对象、WIPEOUTVARIABLES、
+/// 底图定义及其字典与 GEODATA。文档不含光栅图像时不写出图像字典与 RASTERVARIABLES，
+/// 未设定绘制顺序时不写出 SORTENTSTABLE。
+fn write_document_objects(
+    writer: &mut DxfWriter,
+    document: &Document,
+    plan: &RasterPlan,
+    root_handle: &str,
+    entity_handles: &HashMap<EntityId, String>,
+    handles: &mut HandleAllocator,
+) {
+    let has_raster = document.raster_image_definitions().next().is_some()
+        || document.image_dictionary().is_some()
+        || document.raster_image_variables().is_some()
+        || document
+            .entities()
+            .any(|(_, entity)| matches!(entity, Entity::RasterImage(_)));
+
+    let mut root_entries = Vec::new();
+    if has_raster {
+        root_entries.extend(plan.image_root_entries());
+    }
+    root_entries.extend(plan.wipeout_root_entry());
+
+    // 底图定义字典的条目名即底图名称，未命名的定义直接归属根字典
+    let mut underlay_dictionaries = Vec::new();
+    for kind in UnderlayKind::ALL {
+        let mut definitions: Vec<&UnderlayDefinition> = document
+            .underlay_definitions()
+            .filter(|definition| definition.kind == kind)
+            .collect();
+        if definitions.is_empty() {
+            continue;
+        }
+        definitions.sort_by(|a, b| a.handle.cmp(&b.handle));
+        let mut entries: Vec<(String, String)> = definitions
+            .iter()
+            .filter_map(|definition| {
+                let name = definition.name.clone()?;
+                Some((name, definition.handle.clone()))
+            })
+            .collect();
+        entries.sort();
+        let dictionary_handle = (!entries.is_empty()).then(|| handles.allocate());
+        if let Some(handle) = &dictionary_handle {
+            root_entries.push((kind.dictionary_key().to_string(), handle.clone()));
+        }
+        underlay_dictionaries.push((definitions, dictionary_handle, entries));
+    }
+
+    let mut layer_states: Vec<&LayerState> = document.layer_states().collect();
+    layer_states.sort_by(|a, b| a.name.cmp(&b.name));
+    let layer_states_handle = (!layer_states.is_empty()).then(|| handles.allocate());
+    let layer_state_entries: Vec<(String, String)> = layer_states
+        .iter()
+        .map(|state| (state.name.clone(), handles.allocate()))
+        .collect();
+    if let Some(handle) = &layer_states_handle {
+        root_entries.push((LAYER_STATES_KEY.to_string(), handle.clone()));
+    }
+    let sortents_handle = document.has_draw_order().then(|| handles.allocate());
+    if let Some(handle) = &sortents_handle {
+        root_entries.push((SORTENTS_KEY.to_string(), handle.clone()));
+    }
+
+    write_dictionary(writer, root_handle, "0", &root_entries);
+    if let Some(handle) = &sortents_handle {
+        let entries = sort_handles(document, entity_handles);
+        write_sortents_table(writer, handle, root_handle, &entries);
+    }
+    if let Some(dictionary) = &layer_states_handle {
+        write_dictionary(writer, dictionary, root_handle, &layer_state_entries);
+        for (state, (_, handle)) in layer_states.iter().zip(&layer_state_entries) {
+            write_layer_state(writer, state, handle, dictionary);
+        }
+    }
+    if has_raster {
+        write_raster_objects(writer, document, plan, root_handle);
+    }
+    write_planned_wipeout_variables(writer, document, plan, root_handle);
+    for (definitions, dictionary_handle, entries) in underlay_dictionaries {
+        if let Some(handle) = &dictionary_handle {
+            write_dictionary(writer, handle, root_handle, &entries);
+        }
+        for definition in definitions {
+            let owner = match (&definition.name, &dictionary_handle) {
+                (Some(_), Some(handle)) => handle.as_str(),
+                _ => root_handle,
+            };
+            write_underlay_definition(writer, definition, owner);
+        }
+    }
+    if let Some(geodata) = document.geodata() {
+        let handle = geodata.handle.clone().unwrap_or_else(|| handles.allocate());
+        write_geodata(writer, geodata, &handle, root_handle);
+    }
+}
+
+/// 写出 PDFDEFINITION/DGNDEFINITION/DWFDEFINITION。
+pub fn write_underlay_definition(
+    writer: &mut DxfWriter,
+    definition: &UnderlayDefinition,
+    owner: &str,
+) {
+    writer.pair(0, definition.kind.definition_name());
+    writer.handle(&definition.handle);
+    writer.pointer(330, owner);
+    writer.subclass("AcDbUnderlayDefinition");
+    writer.pair(1, &definition.file_path);
+    writer.pair(2, &definition.page);
+}
+
+/// 写出 GEODATA；坐标系定义超过 255 字符时以组码 303 续接。
+pub fn write_geodata(writer: &mut DxfWriter, geodata: &GeoData, handle: &str, owner: &str) {
+    writer.pair(0, "GEODATA");
+    writer.handle(handle);
+    writer.pointer(330, owner);
+    writer.subclass("AcDbGeoData");
+    writer.pair(90, geodata.version);
+    writer.pair(70, geodata.coordinate_type.code());
+    write_point3(writer, 10, geodata.design_point);
+    write_point3(writer, 11, geodata.reference_point);
+    writer.real(40, geodata.horizontal_unit_scale);
+    writer.pair(91, geodata.horizontal_units);
+    writer.real(41, geodata.vertical_unit_scale);
+    writer.pair(92, geodata.vertical_units);
+    let up = geodata.up_direction.as_vec3();
+    writer.real(210, up.x);
+    writer.real(220, up.y);
+    writer.real(230, up.z);
+    writer.real(12, geodata.north_direction.x());
+    writer.real(22, geodata.north_direction.y());
+    writer.pair(95, geodata.scale_estimation_method);
+    writer.real(141, geodata.user_scale_factor);
+    writer.pair(294, i16::from(geodata.sea_level_correction));
+    writer.real(142, geodata.sea_level_elevation);
+    for (index, chunk) in split_chunks(&geodata.coordinate_system, 255)
+        .into_iter()
+        .enumerate()
+    {
+        writer.pair(if index == 0 { 301 } else { 303 }, chunk);
+    }
+}
+
+fn write_point3(writer: &mut DxfWriter, code: i32, point: Point3) {
+    writer.real(code, point.x());
+    writer.real(code + 10, point.y());
+    writer.real(code + 20, point.z());
+}
+
+/// 按字符数切分长字符串，空字符串返回单个空片段。
+fn split_chunks(text: &str, size: usize) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.is_empty() {
+        return vec![String::new()];
+    }
+    chars
+        .chunks(size)
+        .map(|chunk| chunk.iter().collect())
+        .collect()
+}
+
+/// 实体公共部分：类型、句柄、图层与子类标记。
+fn begin_entity(writer: &mut DxfWriter, kind: &str, handle: &str, layer: &str, subclass: &str) {
+    writer.pair(0, kind);
+    writer.handle(handle);
+    writer.subclass("AcDbEntity");
+    writer.entity_layer(layer);
+    writer.subclass(subclass);
+}
+
+/// 写出单个实体并为其分配句柄。IMAGE 不带反应器写出；模型空间中的图像与遮罩
+/// 由 [`write_document`] 按光栅规划的句柄写出。
+pub fn write_entity(writer: &mut DxfWriter, entity: &Entity, handles: &mut HandleAllocator) {
+    let handle = handles.allocate();
+    write_entity_with_handle(writer, entity, &handle, handles);
+}
+
+/// 以预先分配的句柄写出实体，块参照的属性等附属对象仍从 `handles` 分配。
+pub fn write_entity_with_handle(
+    writer: &mut DxfWriter,
+    entity: &Entity,
+    handle: &str,
+    handles: &mut HandleAllocator,
+) {
+    write_entity_record(writer, entity, handle, handles, None);
+}
+
+/// 写出实体及其用户数据扩展数据；扩展数据位于实体自身组码之后、属性序列之前。
+fn write_entity_record(
+    writer: &mut DxfWriter,
+    entity: &Entity,
+    handle: &str,
+    handles: &mut HandleAllocator,
+    user_data: Option<&UserData>,
+) {
+    if let Entity::BlockReference(reference) = entity {
+        write_insert(writer, reference, handle, handles, user_data);
+        return;
+    }
+    write_entity_body(writer, entity, handle, handles);
+    if let Some(data) = user_data {
+        write_user_data(writer, data);
+    }
+}
+
+fn write_entity_body(
+    writer: &mut DxfWriter,
+    entity: &Entity,
+    handle: &str,
+    handles: &mut HandleAllocator,
+) {
+    match entity {
+        Entity::Line(line) => write_line(writer, line, handle),
+        Entity::Circle(circle) => write_circle(writer, circle, handle),
+        Entity::Arc(arc) => write_arc(writer, arc, handle),
+        Entity::Ellipse(ellipse) => write_ellipse(writer, ellipse, handle),
+        Entity::Polyline(polyline) => write_lwpolyline(writer, polyline, handle),
+        Entity::Spline(spline) => write_spline(writer, spline, handle),
+        Entity::Text(text) => write_text(writer, text, handle),
+        Entity::MText(mtext) => write_mtext(writer, mtext, handle),
+        Entity::BlockReference(reference) => {
+            write_insert(writer, reference, handle, handles, None);
+        }
+        Entity::Hatch(hatch) => write_hatch(writer, hatch, handle),
+        Entity::Dimension(dimension) => write_dimension(writer, dimension, handle),
+        Entity::Leader(leader) => write_leader(writer, leader, handle),
+        Entity::MLeader(mleader) => write_mleader(writer, mleader, handle),
+        Entity::RasterImage(image) => write_raster_image(writer, image, handle, None),
+        Entity::Wipeout(wipeout) => write_wipeout(writer, wipeout, handle),
+        Entity::Face3D(face) => write_3dface(writer, face, handle),
+        Entity::Shape(shape) => write_shape(writer, shape, handle),
+        Entity::Underlay(underlay) => write_underlay(writer, underlay, handle),
+        Entity::RevisionCloud(cloud) => write_revision_cloud(writer, cloud, handle),
+    }
+}
+
+/// 为关联填充引用的模型空间边界对象确定句柄，使填充的组码 330 指向本次写出的对象。
+/// 环路记录的原句柄已被保留，优先沿用，其余重新分配。
+fn allocate_hatch_boundaries(
+    document: &Document,
+    handles: &mut HandleAllocator,
+) -> HashMap<EntityId, String> {
+    let mut boundaries = HashMap::new();
+    for (_, entity) in document.entities() {
+        let Entity::Hatch(hatch) = entity else {
+            continue;
+        };
+        for hatch_loop in &hatch.loops {
+            let recorded = hatch_loop.boundary_handles.len() == hatch_loop.boundary_entities.len();
+            for (index, &id) in hatch_loop.boundary_entities.iter().enumerate() {
+                if document.entity(id).is_none() {
+                    continue;
+                }
+                boundaries.entry(id).or_insert_with(|| {
+                    if recorded {
+                        hatch_loop.boundary_handles[index].clone()
+                    } else {
+                        handles.allocate()
+                    }
+                });
+            }
+        }
+    }
+    boundaries
+}
+
+/// 边界对象都已分配句柄的环路改用新句柄引用边界。
+fn with_boundary_handles(hatch: &Hatch, boundaries: &HashMap<EntityId, String>) -> Hatch {
+    let mut hatch = hatch.clone();
+    for hatch_loop in &mut hatch.loops {
+        let mapped: Option<Vec<String>> = hatch_loop
+            .boundary_entities
+            .iter()
+            .map(|id| boundaries.get(id).cloned())
+            .collect();
+        if let Some(mapped) = mapped.filter(|mapped| !mapped.is_empty()) {
+            hatch_loop.boundary_handles = mapped;
+        }
+    }
+    hatch
+}
+
+fn write_line(writer: &mut DxfWriter, line: &Line, handle: &str) {
+    begin_entity(writer, "LINE", handle, &line.layer, "AcDbLine");
+    writer.point(10, line.start);
+    writer.point(11, line.end);
+}
+
+fn write_circle(writer: &mut DxfWriter, circle: &Circle, handle: &str) {
+    begin_entity(writer, "CIRCLE", handle, &circle.layer, "AcDbCircle");
+    writer.point(10, circle.center);
+    writer.real(40, circle.radius);
+}
+
+fn write_arc(writer: &mut DxfWriter, arc: &Arc, handle: &str) {
+    begin_entity(writer, "ARC", handle, &arc.layer, "AcDbCircle");
+    writer.point(10, arc.center);
+    writer.real(40, arc.radius);
+    writer.subclass("AcDbArc");
+    writer.real(50, arc.start_angle.to_degrees());
+    writer.real(51, arc.end_angle.to_degrees());
+}
+
+fn write_ellipse(writer: &mut DxfWriter, ellipse: &Ellipse, handle: &str) {
+    begin_entity(writer, "ELLIPSE", handle, &ellipse.layer, "AcDbEllipse");
+    writer.point(10, ellipse.center);
+    writer.vector(11, ellipse.major_axis);
+    writer.real(40, ellipse.ratio);
+    writer.real(41, ellipse.start_parameter);
+    writer.real(42, ellipse.end_parameter);
+}
+
+fn write_lwpolyline(writer: &mut DxfWriter, polyline: &Polyline, handle: &str) {
+    begin_entity(
+        writer,
+        "LWPOLYLINE",
+        handle,
+        &polyline.layer,
+        "AcDbPolyline",
+    );
+    writer.pair(90, polyline.vertices.len());
+    writer.pair(70, i16::from(polyline.is_closed));
+    for vertex in &polyline.vertices {
+        writer.real(10, vertex.position.x());
+        writer.real(20, vertex.position.y());
+        if vertex.bulge != 0.0 {
+            writer.real(42, vertex.bulge);
+        }
+    }
+}
+
+/// 修订云线以带 `RevcloudProps` 扩展数据的 LWPOLYLINE 写出。
+fn write_revision_cloud(writer: &mut DxfWriter, cloud: &RevisionCloud, handle: &str) {
+    write_lwpolyline(writer, &cloud.to_polyline(), handle);
+    writer.pair(1001, REVCLOUD_XDATA_APP);
+    writer.pair(1070, cloud.style.code());
+    writer.real(1040, cloud.arc_length);
+}
+
+/// 以 `ZCAD_USERDATA` 扩展数据写出用户数据：每项以 `{`/`}` 括起，先写键，
+/// 再写值的 JSON 文本，超过 255 个字符时拆为多个组码 1000。
+pub fn write_user_data(writer: &mut DxfWriter, data: &UserData) {
+    writer.pair(1001, USER_DATA_XDATA_APP);
+    for (key, value) in data {
+        writer.pair(1002, "{");
+        writer.pair(1000, key);
+        let json = serde_json::to_string(value).expect("用户数据总能序列化为 JSON");
+        for chunk in split_chunks(&json, XDATA_STRING_LIMIT) {
+            writer.pair(1000, chunk);
+        }
+        writer.pair(1002, "}");
+    }
+}
+
+fn write_spline(writer: &mut DxfWriter, spline: &Spline, handle: &str) {
+    begin_entity(writer, "SPLINE", handle, &spline.layer, "AcDbSpline");
+    let flags = i16::from(spline.is_closed)
+        | (i16::from(spline.is_periodic) << 1)
+        | (i16::from(spline.is_rational) << 2)
+        | 8;
+    writer.pair(70, flags);
+    writer.pair(71, spline.degree);
+    writer.pair(72, spline.knot_values.len());
+    writer.pair(73, spline.control_points.len());
+    writer.pair(74, spline.fit_points.len());
+    if let Some(tangent) = spline.start_tangent {
+        writer.vector(12, tangent);
+    }
+    if let Some(tangent) = spline.end_tangent {
+        writer.vector(13, tangent);
+    }
+    for knot in &spline.knot_values {
+        writer.real(40, *knot);
+    }
+    for weight in &spline.weights {
+        writer.real(41, *weight);
+    }
+    for point in &spline.control_points {
+        writer.point(10, *point);
+    }
+    for point in &spline.fit_points {
+        writer.point(11, *point);
+    }
+}
+
+/// TEXT 的多行内容按行拆成多个组码 1，读取时以换行拼接。
+fn write_text(writer: &mut DxfWriter, text: &Text, handle: &str) {
+    begin_entity(writer, "TEXT", handle, &text.layer, "AcDbText");
+    writer.point(10, text.insert);
+    writer.real(40, text.height);
+    for line in text.content.split('\n') {
+        writer.pair(1, line);
+    }
+    writer.real(50, text.rotation.to_degrees());
+    writer.pair(71, text.generation_flags());
+    writer.pair(72, text.horizontal_align);
+    if let Some(alignment) = text.alignment {
+        writer.point(11, alignment);
+    }
+    writer.subclass("AcDbText");
+    writer.pair(73, text.vertical_align);
+}
+
+fn write_mtext(writer: &mut DxfWriter, mtext: &MText, handle: &str) {
+    begin_entity(writer, "MTEXT", handle, &mtext.layer, "AcDbMText");
+    writer.point(10, mtext.insert);
+    writer.real(40, mtext.height);
+    if let Some(width) = mtext.reference_width {
+        writer.real(41, width);
+    }
+    writer.pair(71, mtext.attachment_point);
+    writer.pair(72, mtext.drawing_direction);
+    let chunks = chunk_mtext_content(mtext);
+    let last = chunks.len() - 1;
+    for (index, chunk) in chunks.into_iter().enumerate() {
+        writer.pair(if index == last { 1 } else { 3 }, chunk);
+    }
+    if let Some(style) = &mtext.style {
+        writer.pair(7, style);
+    }
+    writer.vector(11, mtext.direction);
+}
+
+/// MTEXT 内容按 250 字符分段，格式码与转义序列不跨段。
+fn chunk_mtext_content(mtext: &MText) -> Vec<String> {
+    const CHUNK: usize = 250;
+    let mut chunks = vec![String::new()];
+    let mut length = 0;
+    for token in crate::mtext::encode_mtext(&mtext.content, &mtext.runs) {
+        let token_length = token.chars().count();
+        if length + token_length > CHUNK && length > 0 {
+            chunks.push(String::new());
+            length = 0;
+        }
+        chunks.last_mut().expect("至少有一段").push_str(&token);
+        length += token_length;
+    }
+    chunks
+}
+
+/// 属性与多重引线文字读取时会解析行内转义，写出前将反斜杠加倍。
+fn escape_inline_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+}
+
+fn write_insert(
+    writer: &mut DxfWriter,
+    reference: &BlockReference,
+    handle: &str,
+    handles: &mut HandleAllocator,
+    user_data: Option<&UserData>,
+) {
+    begin_entity(
+        writer,
+        "INSERT",
+        handle,
+        &reference.layer,
+        "AcDbBlockReference",
+    );
+    if !reference.attributes.is_empty() {
+        writer.pair(66, 1);
+    }
+    writer.pair(2, &reference.name);
+    writer.point(10, reference.insert);
+    writer.real(41, reference.scale.x());
+    writer.real(42, reference.scale.y());
+    writer.real(43, 1.0);
+    writer.real(50, reference.rotation.to_degrees());
+    if let Some(data) = user_data {
+        write_user_data(writer, data);
+    }
+    if reference.attributes.is_empty() {
+        return;
+    }
+    for attribute in &reference.attributes {
+        write_attribute(writer, attribute, &handles.allocate());
+    }
+    writer.pair(0, "SEQEND");
+    writer.handle(&handles.allocate());
+    writer.subclass("AcDbEntity");
+    writer.pair(8, &reference.layer);
+}
+
+/// ATTRIB 与 ATTDEF 共有的文字与对齐字段。
+struct AttributeFields<'a> {
+    text: &'a str,
+    insert: Point2,
+    height: f64,
+    rotation: f64,
+    width_factor: f64,
+    oblique: f64,
+    style: Option<&'a str>,
+    alignment: Option<Point2>,
+    horizontal_align: i16,
+    vertical_align: i16,
+    line_spacing_factor: f64,
+    line_spacing_style: i16,
+    tag: &'a str,
+    prompt: Option<&'a str>,
+    flags: i16,
+    lock_position: bool,
+}
+
+fn attribute_flags(invisible: bool, constant: bool, verify: bool, preset: bool) -> i16 {
+    i16::from(invisible)
+        | (i16::from(constant) << 1)
+        | (i16::from(verify) << 2)
+        | (i16::from(preset) << 3)
+}
+
+fn write_attribute_fields(writer: &mut DxfWriter, subclass: &str, fields: &AttributeFields) {
+    writer.point(10, fields.insert);
+    writer.real(40, fields.height);
+    for line in fields.text.split('\n') {
+        writer.pair(1, escape_inline_text(line));
+    }
+    writer.real(50, fields.rotation.to_degrees());
+    writer.real(41, fields.width_factor);
+    writer.real(51, fields.oblique.to_degrees());
+    if let Some(style) = fields.style {
+        writer.pair(7, style);
+    }
+    writer.pair(72, fields.horizontal_align);
+    if let Some(alignment) = fields.alignment {
+        writer.point(11, alignment);
+    }
+    writer.subclass(subclass);
+    if let Some(prompt) = fields.prompt {
+        writer.pair(3, prompt);
+    }
+    writer.pair(2, fields.tag);
+    writer.pair(70, fields.flags);
+    writer.pair(73, fields.vertical_align);
+    writer.pair(74, fields.line_spacing_style);
+    writer.real(44, fields.line_spacing_factor);
+    writer.pair(280, i16::from(fields.lock_position));
+}
+
+fn write_attribute(writer: &mut DxfWriter, attribute: &Attribute, handle: &str) {
+    begin_entity(writer, "ATTRIB", handle, &attribute.layer, "AcDbText");
+    write_attribute_fields(
+        writer,
+        "AcDbAttribute",
+        &AttributeFields {
+            text: &attribute.text,
+            insert: attribute.insert,
+            height: attribute.height,
+            rotation: attribute.rotation,
+            width_factor: attribute.width_factor,
+            oblique: attribute.oblique,
+            style: attribute.style.as_deref(),
+            alignment: attribute.alignment,
+            horizontal_align: attribute.horizontal_align,
+            vertical_align: attribute.vertical_align,
+            line_spacing_factor: attribute.line_spacing_factor,
+            line_spacing_style: attribute.line_spacing_style,
+            tag: &attribute.tag,
+            prompt: attribute.prompt.as_deref(),
+            flags: attribute_flags(
+                attribute.is_invisible,
+                attribute.is_constant,
+                attribute.is_verify,
+                attribute.is_preset,
+            ),
+            lock_position: attribute.lock_position,
+        },
+    );
+}
+
+fn write_attribute_definition(
+    writer: &mut DxfWriter,
+    definition: &AttributeDefinition,
+    handle: &str,
+) {
+    begin_entity(writer, "ATTDEF", handle, &definition.layer, "AcDbText");
+    write_attribute_fields(
+        writer,
+        "AcDbAttributeDefinition",
+        &AttributeFields {
+            text: &definition.default_text,
+            insert: definition.insert,
+            height: definition.height,
+            rotation: definition.rotation,
+            width_factor: definition.width_factor,
+            oblique: definition.oblique,
+            style: definition.style.as_deref(),
+            alignment: definition.alignment,
+            horizontal_align: definition.horizontal_align,
+            vertical_align: definition.vertical_align,
+            line_spacing_factor: definition.line_spacing_factor,
+            line_spacing_style: definition.line_spacing_style,
+            tag: &definition.tag,
+            prompt: definition.prompt.as_deref(),
+            flags: attribute_flags(
+                definition.is_invisible,
+                definition.is_constant,
+                definition.is_verify,
+                definition.is_preset,
+            ),
+            lock_position: definition.lock_position,
+        },
+    );
+}
+
+fn write_hatch(writer: &mut DxfWriter, hatch: &Hatch, handle: &str) {
+    begin_entity(writer, "HATCH", handle, &hatch.layer, "AcDbHatch");
+    writer.point(10, Point2::new(0.0, 0.0));
+    writer.real(210, 0.0);
+    writer.real(220, 0.0);
+    writer.real(230, 1.0);
+    writer.pair(2, &hatch.pattern_name);
+    writer.pair(70, i16::from(hatch.is_solid));
+    writer.pair(71, i16::from(hatch.is_associative));
+    writer.pair(91, hatch.loops.len());
+    for hatch_loop in &hatch.loops {
+        write_hatch_loop(writer, hatch_loop);
+    }
+    writer.pair(75, hatch.style.code());
+    writer.pair(76, 1);
+    if !hatch.pattern_lines.is_empty() {
+        writer.pair(78, hatch.pattern_lines.len());
+        for line in &hatch.pattern_lines {
+            writer.real(53, line.angle.to_degrees());
+            writer.real(43, line.base_point.x());
+            writer.real(44, line.base_point.y());
+            writer.real(45, line.offset.x());
+            writer.real(46, line.offset.y());
+            writer.pair(79, line.dash_lengths.len());
+            for dash in &line.dash_lengths {
+                writer.real(49, *dash);
+            }
+        }
+    }
+    writer.pair(98, hatch.seed_points.len());
+    for seed in &hatch.seed_points {
+        writer.real(10, seed.x());
+        writer.real(20, seed.y());
+    }
+    if let Some(gradient) = &hatch.gradient {
+        writer.pair(450, 1);
+        writer.pair(451, 0);
+        writer.real(452, gradient.angle);
+        writer.pair(453, i16::from(gradient.is_single_color));
+        if let Some(shift) = gradient.shift {
+            writer.real(460, shift);
+        }
+        if let Some(tint) = gradient.tint {
+            writer.real(461, tint);
+        }
+        for color in [gradient.color1, gradient.color2].into_iter().flatten() {
+            writer.pair(421, color);
+        }
+        writer.pair(470, &gradient.name);
+    }
+}
+
+/// 多段线环路按顶点写出：闭合环路的最后一条边由读取方补回，开放环路追加末点。
+fn write_hatch_loop(writer: &mut DxfWriter, hatch_loop: &HatchLoop) {
+    if hatch_loop.is_polyline {
+        let segments: Vec<(Point2, Point2, f64)> = hatch_loop
+            .edges
+            .iter()
+            .filter_map(|edge| match edge {
+                HatchEdge::PolylineSegment { start, end, bulge } => Some((*start, *end, *bulge)),
+                _ => None,
+            })
+            .collect();
+        let mut vertices: Vec<(Point2, f64)> = segments
+            .iter()
+            .map(|(start, _, bulge)| (*start, *bulge))
+            .collect();
+        if !hatch_loop.is_closed
+            && let Some((_, end, _)) = segments.last()
+        {
+            vertices.push((*end, 0.0));
+        }
+        let has_bulge = vertices.iter().any(|(_, bulge)| *bulge != 0.0);
+        writer.pair(92, 2);
+        writer.pair(72, i16::from(has_bulge));
+        writer.pair(73, i16::from(hatch_loop.is_closed));
+        writer.pair(93, vertices.len());
+        for (point, bulge) in vertices {
+            writer.real(10, point.x());
+            writer.real(20, point.y());
+            if has_bulge {
+                writer.real(42, bulge);
+            }
+        }
+    } else {
+        let edges: Vec<&HatchEdge> = hatch_loop
+            .edges
+            .iter()
+            .filter(|edge| !matches!(edge, HatchEdge::BoundaryReference { .. }))
+            .collect();
+        writer.pair(92, 0);
+        writer.pair(93, edges.len());
+        for edge in edges {
+            write_hatch_edge(writer, edge);
+        }
+    }
+    let boundary_count = if writer.options().write_handles {
+        hatch_loop.boundary_handles.len()
+    } else {
+        0
+    };
+    writer.pair(97, boundary_count);
+    for handle in &hatch_loop.boundary_handles {
+        writer.pointer(330, handle);
+    }
+}
+
+fn write_hatch_edge(writer: &mut DxfWriter, edge: &HatchEdge) {
+    match edge {
+        HatchEdge::Line { start, end } | HatchEdge::PolylineSegment { start, end, .. } => {
+            writer.pair(72, 1);
+            writer.real(10, start.x());
+            writer.real(20, start.y());
+            writer.real(11, end.x());
+            writer.real(21, end.y());
+        }
+        HatchEdge::Arc {
+            center,
+            radius,
+            start_angle,
+            end_angle,
+            is_counter_clockwise,
+        } => {
+            writer.pair(72, 2);
+            writer.real(10, center.x());
+            writer.real(20, center.y());
+            writer.real(40, *radius);
+            writer.real(50, *start_angle);
+            writer.real(51, *end_angle);
+            writer.pair(73, i16::from(*is_counter_clockwise));
+        }
+        HatchEdge::Ellipse {
+            center,
+            major_axis,
+            minor_ratio,
+            start_angle,
+            end_angle,
+            is_counter_clockwise,
+        } => {
+            writer.pair(72, 3);
+            writer.real(10, center.x());
+            writer.real(20, center.y());
+            writer.real(11, major_axis.x());
+            writer.real(21, major_axis.y());
+            writer.real(40, *minor_ratio);
+            writer.real(50, *start_angle);
+            writer.real(51, *end_angle);
+            writer.pair(73, i16::from(*is_counter_clockwise));
+        }
+        HatchEdge::Spline {
+            control_points,
+            fit_points,
+            knot_values,
+            degree,
+            is_rational,
+            is_periodic,
+        } => {
+            writer.pair(72, 4);
+            writer.pair(94, *degree);
+            writer.pair(73, i16::from(*is_rational));
+            writer.pair(74, i16::from(*is_periodic));
+            writer.pair(95, knot_values.len());
+            writer.pair(96, control_points.len());
+            for knot in knot_values {
+                writer.real(40, *knot);
+            }
+            for point in control_points {
+                writer.real(10, point.x());
+                writer.real(20, point.y());
+            }
+            writer.pair(97, fit_points.len());
+            for point in fit_points {
+                writer.real(11, point.x());
+                writer.real(21, point.y());
+            }
+        }
+        HatchEdge::BoundaryReference { .. } => {}
+    }
+}
+
+/// 标注类型（组码 70 低 4 位）。
+fn dimension_type_code(kind: DimensionKind) -> i16 {
+    match kind {
+        DimensionKind::Linear => 0,
+        DimensionKind::Aligned => 1,
+        DimensionKind::Angular => 2,
+        DimensionKind::Diameter => 3,
+        DimensionKind::Radius => 4,
+        DimensionKind::Angular3Point => 5,
+        DimensionKind::Ordinate => 6,
+        DimensionKind::ArcLength => 8,
+        DimensionKind::JoggedRadius => 9,
+        DimensionKind::Unknown(code) => code,
+    }
+}
+
+/// 写出标注。弧长与折弯半径标注的专有点位写在各自子类标记之后，
+/// 与读取器按子类解释组码的方式一致。
+fn write_dimension(writer: &mut DxfWriter, dimension: &Dimension, handle: &str) {
+    let kind = match dimension.kind {
+        DimensionKind::ArcLength => "ARC_DIMENSION",
+        DimensionKind::JoggedRadius => "LARGE_RADIAL_DIMENSION",
+        _ => "DIMENSION",
+    };
+    begin_entity(writer, kind, handle, &dimension.layer, "AcDbDimension");
+    if let Some(block) = &dimension.block_name {
+        writer.pair(2, block);
+    }
+    if let Some(style) = &dimension.style_name {
+        writer.pair(3, style);
+    }
+    writer.point(10, dimension.definition_point);
+    writer.point(11, dimension.text_midpoint);
+    let block_flag = if dimension.block_name.is_some() {
+        32
+    } else {
+        0
+    };
+    writer.pair(70, dimension_type_code(dimension.kind) | block_flag);
+    if let Some(text) = &dimension.text {
+        writer.pair(1, text);
+    }
+    if let Some(measurement) = dimension.measurement {
+        writer.real(42, measurement);
+    }
+    if let Some(oblique) = dimension.oblique_angle {
+        writer.real(51, oblique.to_degrees());
+    }
+    if let Some(rotation) = dimension.text_rotation {
+        writer.real(52, rotation.to_degrees());
+    }
+    writer.real(50, dimension.rotation.to_degrees());
+
+    let optional_point = |writer: &mut DxfWriter, code: i32, point: Option<Point2>| {
+        if let Some(point) = point {
+            writer.point(code, point);
+        }
+    };
+    optional_point(writer, 12, dimension.secondary_point);
+    optional_point(writer, 13, dimension.dimension_line_point);
+    optional_point(writer, 16, dimension.arc_definition_point);
+    match dimension.kind {
+        DimensionKind::ArcLength => {
+            writer.subclass("AcDbArcDimension");
+            optional_point(writer, 13, dimension.extension_line_origin);
+            optional_point(writer, 14, dimension.extension_line_end);
+            optional_point(writer, 15, dimension.center_point);
+            if let Some(arc) = &dimension.arc {
+                writer.real(40, arc.start_angle);
+                writer.real(41, arc.end_angle);
+                writer.pair(70, i16::from(arc.is_partial));
+                writer.pair(71, i16::from(arc.has_leader));
+                optional_point(writer, 16, arc.leader_start);
+                optional_point(writer, 17, arc.leader_end);
+            }
+        }
+        DimensionKind::JoggedRadius => {
+            optional_point(writer, 14, dimension.extension_line_origin);
+            writer.subclass("AcDbRadialDimensionLarge");
+            optional_point(writer, 13, dimension.extension_line_end);
+            optional_point(writer, 14, dimension.center_point);
+            optional_point(writer, 15, dimension.jog_point);
+        }
+        _ => {
+            optional_point(writer, 14, dimension.extension_line_origin);
+            optional_point(writer, 15, dimension.extension_line_end);
+            optional_point(writer, 17, dimension.center_point);
+        }
+    }
+}
+
+fn write_leader(writer: &mut DxfWriter, leader: &Leader, handle: &str) {
+    begin_entity(writer, "LEADER", handle, &leader.layer, "AcDbLeader");
+    if let Some(style) = &leader.style_name {
+        writer.pair(3, style);
+    }
+    writer.pair(71, i16::from(leader.has_arrowhead));
+    writer.pair(72, 0);
+    writer.pair(76, leader.vertices.len());
+    for vertex in &leader.vertices {
+        writer.point(10, *vertex);
+    }
+}
+
+/// 写出 MULTILEADER：内容写在 CONTEXT_DATA 中，引线以 LEADER{/LEADER_LINE{ 分组。
+fn write_mleader(writer: &mut DxfWriter, mleader: &MLeader, handle: &str) {
+    begin_entity(writer, "MULTILEADER", handle, &mleader.layer, "AcDbMLeader");
+    if let Some(style) = &mleader.style_name {
+        writer.pair(3, style);
+    }
+    writer.pair(300, "CONTEXT_DATA{");
+    if let Some(scale) = mleader.scale {
+        writer.real(40, scale);
+    }
+    if let Some(height) = mleader.text_height {
+        writer.real(140, height);
+    }
+    if let Some(gap) = mleader.landing_gap {
+        writer.real(145, gap);
+    }
+    match &mleader.content {
+        MLeaderContent::MText { text, location } => {
+            writer.pair(172, 2);
+            writer.pair(290, 1);
+            for line in text.split('\n') {
+                writer.pair(304, escape_inline_text(line));
+            }
+            writer.real(12, location.x());
+            writer.real(22, location.y());
+        }
+        MLeaderContent::Block { block } => {
+            writer.pair(172, 1);
+            writer.pair(290, 0);
+            writer.pair(296, 1);
+            // 块缩放紧跟在块句柄之后，不写句柄时一并省略
+            if let Some(block_handle) = &block.block_handle
+                && writer.options().write_handles
+            {
+                writer.pointer(344, block_handle);
+                writer.real(10, block.scale.x());
+                writer.real(20, block.scale.y());
+                writer.real(30, 1.0);
+            }
+            writer.real(43, block.rotation);
+            writer.real(15, block.location.x());
+            writer.real(25, block.location.y());
+            if let Some(connection) = block.connection_type {
+                writer.pair(176, connection);
+            }
+        }
+        MLeaderContent::None => {}
+    }
+    writer.pair(302, "LEADER{");
+    if let Some(length) = mleader.dogleg_length {
+        writer.real(40, length);
+    }
+    for (index, line) in mleader.leader_lines.iter().enumerate() {
+        writer.pair(304, "LEADER_LINE{");
+        for vertex in &line.vertices {
+            writer.real(10, vertex.x());
+            writer.real(20, vertex.y());
+        }
+        writer.pair(91, index);
+        writer.pair(305, "}");
+    }
+    writer.pair(303, "}");
+    writer.pair(301, "}");
+}
+
+fn write_3dface(writer: &mut DxfWriter, face: &ThreeDFace, handle: &str) {
+    begin_entity(writer, "3DFACE", handle, &face.layer, "AcDbFace");
+    for (index, vertex) in face.vertices.iter().enumerate() {
+        write_point3(writer, 10 + index as i32, *vertex);
+    }
+    let flags = face
+        .invisible_edges
+        .iter()
+        .enumerate()
+        .fold(0_i16, |flags, (index, hidden)| {
+            flags | (i16::from(*hidden) << index)
+        });
+    writer.pair(70, flags);
+}
+
+fn write_shape(writer: &mut DxfWriter, shape: &Shape, handle: &str) {
+    begin_entity(writer, "SHAPE", handle, &shape.layer, "AcDbShape");
+    writer.point(10, shape.insert);
+    writer.real(40, shape.size);
+    writer.pair(2, &shape.name);
+    writer.real(50, shape.rotation.to_degrees());
+    writer.real(41, shape.width_factor);
+    writer.real(51, shape.oblique.to_degrees());
+}
+
+fn write_underlay(writer: &mut DxfWriter, underlay: &Underlay, handle: &str) {
+    begin_entity(
+        writer,
+        underlay.kind.entity_name(),
+        handle,
+        &underlay.layer,
+        "AcDbUnderlayReference",
+    );
+    writer.pointer(340, &underlay.definition_handle);
+    writer.point(10, underlay.insert);
+    writer.real(41, underlay.scale.x());
+    writer.real(42, underlay.scale.y());
+    writer.real(43, 1.0);
+    writer.real(50, underlay.rotation.to_degrees());
+    writer.pair(280, underlay.display_options.flags());
+    writer.pair(281, underlay.display_options.contrast);
+    writer.pair(282, underlay.display_options.fade);
+    for point in &underlay.clip_boundary {
+        writer.real(11, point.x());
+        writer.real(21, point.y());
+    }
+}
@@ -0,0 +1,61 @@
+//! 外部参照载入：按宿主图纸目录解析 XRef 路径，读取参照文件并附着到宿主文档。
+
+use std::path::{Path, PathBuf};
+
+use zcad_core::document::Document;
+
+use crate::{DocumentLoader, IoError};
+
+/// 单个外部参照载入失败的记录，调用方可据此提示用户而不中断整体加载。
+#[derive(Debug)]
+pub struct XRefLoadFailure {
+    pub name: String,
+    pub error: IoError,
+}
+
+/// 载入文档中所有已登记但尚未载入的外部参照，返回失败列表。
+/// 嵌套参照不会递归载入，仍保留在参照文档自身的登记表中。
+pub fn load_xrefs(
+    document: &mut Document,
+    base_dir: Option<&Path>,
+    loader: &dyn DocumentLoader,
+) -> Vec<XRefLoadFailure> {
+    let pending: Vec<(String, String)> = document
+        .xrefs()
+        .filter(|xref| !xref.is_loaded)
+        .map(|xref| (xref.name.clone(), xref.path.clone()))
+        .collect();
+
+    let mut failures = Vec::new();
+    for (name, path) in pending {
+        let resolved = resolve_xref_path(&path, base_dir);
+        let result = loader.load(&resolved).and_then(|source| {
+            document
+                .attach_xref(name.clone(), path, source)
+                .map_err(|err| IoError::InvalidDocument(err.to_string()))
+        });
+        if let Err(error) = result {
+            failures.push(XRefLoadFailure { name, error });
+        }
+    }
+    failures
+}
+
+/// 绝对路径原样返回；相对路径依次尝试宿主目录下的相对路径与同名文件。
+pub fn resolve_xref_path(raw: &str, base_dir: Option<&Path>) -> PathBuf {
+    let path = PathBuf::from(raw);
+    if path.is_absolute() {
+        return path;
+    }
+    let Some(base_dir) = base_dir else {
+        return path;
+    };
+    let relative = base_dir.join(&path);
+    if relative.exists() {
+        return relative;
+    }
+    match path.file_name() {
+        Some(file_name) => base_dir.join(file_name),
+        None => relative,
+    }
+}
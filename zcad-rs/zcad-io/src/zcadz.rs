@@ -0,0 +1,87 @@
+//! 压缩原生格式 `.zcadz`：文件头魔数后跟以 zstd 压缩的原生 JSON 信封，
+//! 适合保存大型图纸。
+//!
+//! 布局：`ZCADZ` 五字节魔数、一字节容器版本，其后为单个 zstd 帧。
+
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use ruzstd::decoding::StreamingDecoder;
+use ruzstd::encoding::{CompressionLevel, compress_to_vec};
+use zcad_core::document::Document;
+
+use crate::image::ImagePathResolver;
+use crate::json::JsonFacade;
+use crate::{DocumentLoader, DocumentSaver, IoError};
+
+/// `.zcadz` 文件头魔数。
+pub const ZCADZ_MAGIC: &[u8; 5] = b"ZCADZ";
+/// 当前容器版本；读取时拒绝更高的版本。
+pub const ZCADZ_CONTAINER_VERSION: u8 = 1;
+
+/// 压缩原生格式的读写门面。
+#[derive(Debug, Clone, Default)]
+pub struct ZcadzFacade {
+    json: JsonFacade,
+}
+
+impl ZcadzFacade {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 将文档编码为 `.zcadz` 字节序列。
+    pub fn to_bytes(&self, document: &Document) -> Result<Vec<u8>, IoError> {
+        let json = self.json.to_json(document)?;
+        let mut bytes = Vec::with_capacity(json.len() / 4);
+        bytes.extend_from_slice(ZCADZ_MAGIC);
+        bytes.push(ZCADZ_CONTAINER_VERSION);
+        bytes.extend(compress_to_vec(json.as_bytes(), CompressionLevel::Fastest));
+        Ok(bytes)
+    }
+
+    /// 从 `.zcadz` 字节序列解码文档。
+    pub fn from_bytes(&self, bytes: &[u8]) -> Result<Document, IoError> {
+        let payload = bytes
+            .strip_prefix(ZCADZ_MAGIC.as_slice())
+            .ok_or_else(|| IoError::InvalidDocument("缺少 .zcadz 文件头".to_string()))?;
+        let (&version, mut frame) = payload
+            .split_first()
+            .ok_or_else(|| IoError::InvalidDocument(".zcadz 缺少容器版本".to_string()))?;
+        if version > ZCADZ_CONTAINER_VERSION {
+            return Err(IoError::UnsupportedFeature(format!(
+                ".zcadz 容器版本 {version}（当前支持至 {ZCADZ_CONTAINER_VERSION}）"
+            )));
+        }
+        let mut decoder = StreamingDecoder::new(&mut frame)
+            .map_err(|err| IoError::InvalidDocument(format!(".zcadz 压缩数据无效: {err}")))?;
+        let mut json = String::new();
+        decoder
+            .read_to_string(&mut json)
+            .map_err(|err| IoError::InvalidDocument(format!(".zcadz 解压失败: {err}")))?;
+        self.json.from_json(&json)
+    }
+}
+
+impl DocumentLoader for ZcadzFacade {
+    fn load(&self, path: &Path) -> Result<Document, IoError> {
+        let bytes = fs::read(path).map_err(|source| IoError::ReadError {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let mut document = self.from_bytes(&bytes)?;
+        ImagePathResolver::new(path.parent()).apply(&mut document);
+        Ok(document)
+    }
+}
+
+impl DocumentSaver for ZcadzFacade {
+    fn save(&self, document: &Document, path: &Path) -> Result<(), IoError> {
+        let bytes = self.to_bytes(document)?;
+        fs::write(path, bytes).map_err(|source| IoError::WriteError {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
@@ -6,13 +6,21 @@ use std::path::PathBuf;
 use glam::DVec2;
 
 use golden::assert_golden;
+use zcad_config::{GeometryConfig, ResourceConfig};
 use zcad_core::{
     document::{
-        ClipMode, DimensionKind, Entity, HatchEdge, HatchLoop, MLeaderContent, RasterImageClip,
+        AuditSeverity, ClipMode, DimensionKind, Entity, FlattenOptions, GeoCoordinateType,
+        HatchEdge, HatchLoop, HatchStyle, LineWeight, LinetypeEmbeddedContent, MLeaderContent,
+        ObjectColor, RasterImageClip, RevisionCloudStyle, StackKind, TextRunAlignment,
+        UnderlayKind,
     },
-    geometry::{Point2, Vector2},
+    geometry::{Point2, Tolerance, Vector2},
+};
+use zcad_io::{
+    DocumentLoader, DxfFacade,
+    image::{ImagePathResolver, WorldFile},
+    xref::load_xrefs,
 };
-use zcad_io::{DocumentLoader, DxfFacade};
 
 #[test]
 fn load_basic_entities_matches_expected_document() {
@@ -84,6 +92,156 @@ fn load_mtext_entity() {
     assert_eq!(mtext.layer, "ANNOT");
 }
 
+#[test]
+fn load_mtext_formatting_runs() {
+    let mut fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    fixtures.push("tests/data/mtext_formatting.dxf");
+
+    let doc = DxfFacade::new()
+        .load(&fixtures)
+        .expect("读取 MTEXT 格式 DXF 失败");
+    let mtext = doc
+        .entities()
+        .find_map(|(_, entity)| match entity {
+            Entity::MText(mtext) => Some(mtext),
+            _ => None,
+        })
+        .expect("未找到 MText 实体");
+
+    assert_eq!(mtext.content, "Plain Bold Big red\nSize 1/2Blue {x}");
+    let texts: Vec<&str> = mtext.runs.iter().map(|run| run.text.as_str()).collect();
+    assert_eq!(
+        texts,
+        [
+            "Plain ", "Bold", " ", "Big red", "\n", "Size ", "1/2", "Blue", " {x}"
+        ]
+    );
+
+    let bold = &mtext.runs[1].format;
+    assert_eq!(bold.font.as_deref(), Some("Arial"));
+    assert!(bold.bold && !bold.italic);
+
+    let big = &mtext.runs[3].format;
+    assert!((big.height.expect("相对字高应换算为绝对值") - 3.0).abs() < 1e-9);
+    assert_eq!(big.color, Some(ObjectColor::Index(1)));
+    assert!(
+        mtext.runs[4].format.is_default(),
+        "分组结束后应恢复实体样式"
+    );
+
+    let stacked = &mtext.runs[6];
+    let stack = stacked.stack.as_ref().expect("应解析出堆叠分数");
+    assert_eq!((stack.upper.as_str(), stack.lower.as_str()), ("1", "2"));
+    assert_eq!(stack.kind, StackKind::Horizontal);
+    assert_eq!(stacked.format.width_factor, Some(0.8));
+    assert_eq!(stacked.format.alignment, Some(TextRunAlignment::Center));
+
+    assert_eq!(
+        mtext.runs[7].format.color,
+        Some(ObjectColor::TrueColor(0x0000FF))
+    );
+}
+
+#[test]
+fn load_text_justification() {
+    let mut fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    fixtures.push("tests/data/text_alignment.dxf");
+
+    let doc = DxfFacade::new()
+        .load(&fixtures)
+        .expect("读取 TEXT 对齐 DXF 失败");
+    let texts: Vec<_> = doc
+        .entities()
+        .filter_map(|(_, entity)| match entity {
+            Entity::Text(text) => Some(text),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(texts.len(), 3);
+
+    let center = texts[0];
+    assert_eq!((center.horizontal_align, center.vertical_align), (1, 2));
+    assert_eq!(center.anchor(), Point2::new(10.0, 5.0));
+    let start = center.baseline_start(6.0);
+    assert!((start.x() - 7.0).abs() < 1e-9 && (start.y() - 4.0).abs() < 1e-9);
+
+    let aligned = texts[1];
+    assert!(aligned.is_fitted());
+    assert_eq!(aligned.anchor(), Point2::new(0.0, 10.0));
+    assert!((aligned.effective_rotation() - PI / 4.0).abs() < 1e-9);
+
+    let left = texts[2];
+    assert_eq!(left.alignment, None, "左对齐基线文字应忽略第二对齐点");
+    assert_eq!(left.anchor(), Point2::new(3.0, 4.0));
+}
+
+#[test]
+fn header_extents_scale_document_tolerance() {
+    let dxf = |extent: &str| {
+        format!(
+            "0\nSECTION\n2\nHEADER\n9\n$EXTMIN\n10\n-{extent}\n20\n0\n30\n0\n\
+             9\n$EXTMAX\n10\n{extent}\n20\n0\n30\n0\n0\nENDSEC\n0\nEOF\n"
+        )
+    };
+    let geometry = GeometryConfig {
+        linear_tolerance: 1e-6,
+        angular_tolerance: 1e-8,
+    };
+    let facade = DxfFacade::new().with_geometry(&geometry);
+
+    let small = facade.from_dxf(&dxf("100.0")).expect("解析头变量失败");
+    assert_eq!(small.tolerance(), Tolerance::new(1e-6, 1e-8));
+
+    let far = facade.from_dxf(&dxf("5e12")).expect("解析头变量失败");
+    assert_eq!(
+        far.tolerance(),
+        Tolerance::new(1e-6, 1e-8).for_magnitude(5e12)
+    );
+    assert!(far.tolerance().linear > 1e-4);
+
+    // 空图纸的 ±1e20 占位范围不放大容差
+    let empty = DxfFacade::new()
+        .from_dxf(&dxf("1e20"))
+        .expect("解析头变量失败");
+    assert_eq!(empty.tolerance(), Tolerance::DEFAULT);
+}
+
+#[test]
+fn load_text_generation_flags_and_mirrtext() {
+    let mut fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    fixtures.push("tests/data/text_mirror.dxf");
+
+    let doc = DxfFacade::new()
+        .load(&fixtures)
+        .expect("读取 TEXT 镜像 DXF 失败");
+    assert!(doc.mirror_text(), "应读取 $MIRRTEXT");
+    let texts: Vec<_> = doc
+        .entities()
+        .filter_map(|(_, entity)| match entity {
+            Entity::Text(text) => Some(text),
+            _ => None,
+        })
+        .collect();
+    assert!(texts[0].is_backward && !texts[0].is_upside_down);
+    assert_eq!(texts[0].generation_flags(), 2);
+    assert!(!texts[1].is_backward && texts[1].is_upside_down);
+    assert_eq!(texts[1].generation_flags(), 4);
+
+    let flat = doc.flatten(&FlattenOptions::default());
+    let flags: Vec<(bool, bool)> = flat
+        .entities()
+        .filter_map(|(_, entity)| match entity {
+            Entity::Text(text) => Some((text.is_backward, text.is_upside_down)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        flags,
+        [(true, false), (false, true)],
+        "展开后应保留生成标志"
+    );
+}
+
 #[test]
 fn load_ellipse_entity() {
     let mut fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -595,6 +753,47 @@ fn load_radius_dimension_entity() {
     assert_eq!(dimension.measurement, Some(10.0));
 }
 
+#[test]
+fn load_arc_length_and_jogged_radius_dimensions() {
+    let mut fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    fixtures.push("tests/data/dimension_arc_jogged.dxf");
+
+    let loader = DxfFacade::new();
+    let doc = loader.load(&fixtures).expect("读取弧长/折弯标注 DXF 失败");
+    assert_golden("dimension_arc_jogged", &doc);
+
+    let dimensions: Vec<_> = doc
+        .entities()
+        .filter_map(|(_, entity)| match entity {
+            Entity::Dimension(dimension) => Some(dimension),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(dimensions.len(), 2);
+
+    let arc_length = dimensions[0];
+    assert!(matches!(arc_length.kind, DimensionKind::ArcLength));
+    assert_eq!(
+        arc_length.extension_line_origin,
+        Some(Point2::new(10.0, 0.0))
+    );
+    assert_eq!(arc_length.extension_line_end, Some(Point2::new(-10.0, 0.0)));
+    assert_eq!(arc_length.center_point, Some(Point2::new(0.0, 0.0)));
+    let arc = arc_length.arc.as_ref().expect("弧长标注缺少圆弧信息");
+    assert!((arc.end_angle - PI).abs() < 1e-6);
+    assert!(!arc.is_partial);
+    assert!(arc.has_leader);
+    assert_eq!(arc.leader_end, Some(Point2::new(0.0, 12.0)));
+
+    let jogged = dimensions[1];
+    assert!(matches!(jogged.kind, DimensionKind::JoggedRadius));
+    assert_eq!(jogged.extension_line_end, Some(Point2::new(30.0, 10.0)));
+    assert_eq!(jogged.center_point, Some(Point2::new(20.0, -30.0)));
+    assert_eq!(jogged.jog_point, Some(Point2::new(35.0, -5.0)));
+    assert!(jogged.arc.is_none());
+    assert_eq!(jogged.text.as_deref(), Some("R100"));
+}
+
 #[test]
 fn load_angular_three_point_dimension_entity() {
     let mut fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -719,6 +918,259 @@ fn load_gradient_hatch_entity() {
     assert_eq!(gradient.color2, Some(3));
 }
 
+#[test]
+fn load_pattern_hatch_definition_lines() {
+    let mut fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    fixtures.push("tests/data/hatch_pattern.dxf");
+
+    let loader = DxfFacade::new();
+    let doc = loader
+        .load(&fixtures)
+        .expect("读取含图案 HATCH 的 DXF 失败");
+    assert_golden("hatch_pattern", &doc);
+
+    let hatch = doc
+        .entities()
+        .find_map(|(_, entity)| match entity {
+            Entity::Hatch(hatch) => Some(hatch),
+            _ => None,
+        })
+        .expect("未找到 HATCH 实体");
+    assert_eq!(hatch.pattern_name, "ANSI33");
+    assert!(!hatch.is_solid);
+    assert_eq!(hatch.loops.len(), 1);
+    assert_eq!(hatch.pattern_lines.len(), 2);
+
+    let continuous = &hatch.pattern_lines[0];
+    assert!((continuous.angle - PI / 4.0).abs() < 1e-9);
+    assert!(continuous.dash_lengths.is_empty());
+
+    let dashed = &hatch.pattern_lines[1];
+    assert!((dashed.base_point.x() - 0.1768).abs() < 1e-9);
+    assert!((dashed.offset.x() + 0.2245).abs() < 1e-9);
+    assert!((dashed.offset.y() - 0.2245).abs() < 1e-9);
+    assert_eq!(dashed.dash_lengths, vec![0.125, -0.0625]);
+}
+
+#[test]
+fn load_dimension_binds_anonymous_block_when_retained() {
+    let mut fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    fixtures.push("tests/data/dimension_block.dxf");
+
+    let doc = DxfFacade::new()
+        .load(&fixtures)
+        .expect("读取含标注匿名块的 DXF 失败");
+    let dimension = doc
+        .entities()
+        .find_map(|(_, entity)| match entity {
+            Entity::Dimension(dimension) => Some(dimension),
+            _ => None,
+        })
+        .expect("未找到 DIMENSION 实体");
+    assert_eq!(dimension.block_name.as_deref(), Some("*D1"));
+    assert!(doc.dimension_block(dimension).is_none());
+
+    let doc = DxfFacade::new()
+        .with_dimension_blocks(true)
+        .load(&fixtures)
+        .expect("读取含标注匿名块的 DXF 失败");
+    assert_golden("dimension_block", &doc);
+    let dimension = doc
+        .entities()
+        .find_map(|(_, entity)| match entity {
+            Entity::Dimension(dimension) => Some(dimension),
+            _ => None,
+        })
+        .expect("未找到 DIMENSION 实体");
+    let block = doc.dimension_block(dimension).expect("标注匿名块未保留");
+    assert_eq!(block.name, "*D1");
+    assert_eq!(block.entities.len(), 2);
+    assert!(matches!(block.entities[0], Entity::Line(_)));
+    assert!(matches!(block.entities[1], Entity::Text(_)));
+}
+
+#[test]
+fn load_hatch_seed_points_style_and_associativity() {
+    let mut fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    fixtures.push("tests/data/hatch_seed.dxf");
+
+    let loader = DxfFacade::new();
+    let doc = loader
+        .load(&fixtures)
+        .expect("读取含种子点 HATCH 的 DXF 失败");
+    assert_golden("hatch_seed", &doc);
+
+    let hatch = doc
+        .entities()
+        .find_map(|(_, entity)| match entity {
+            Entity::Hatch(hatch) => Some(hatch),
+            _ => None,
+        })
+        .expect("未找到 HATCH 实体");
+    assert!(hatch.is_associative);
+    assert_eq!(hatch.style, HatchStyle::Ignore);
+    assert_eq!(hatch.loops.len(), 1);
+    assert_eq!(hatch.loops[0].edges.len(), 4);
+    assert_eq!(hatch.loops[0].boundary_handles, vec!["2A".to_string()]);
+    assert_eq!(
+        hatch.seed_points,
+        vec![Point2::new(5.0, 5.0), Point2::new(15.0, 5.0)]
+    );
+
+    let outline = doc
+        .entities()
+        .find_map(|(id, entity)| matches!(entity, Entity::Polyline(_)).then_some(*id))
+        .expect("未找到边界多段线");
+    assert_eq!(hatch.loops[0].boundary_entities, vec![outline]);
+}
+
+#[test]
+fn load_layer_table_default_properties() {
+    let mut fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    fixtures.push("tests/data/layer_properties.dxf");
+
+    let loader = DxfFacade::new();
+    let doc = loader.load(&fixtures).expect("读取含图层表的 DXF 失败");
+    assert_golden("layer_properties", &doc);
+
+    let wall = doc.layer("WALL").expect("未找到 WALL 图层");
+    assert_eq!(wall.color, ObjectColor::Index(1));
+    assert_eq!(wall.linetype, "DASHED");
+    assert_eq!(wall.lineweight, LineWeight::Hundredths(50));
+    assert!(
+        !doc.layer("HIDDEN_OFF")
+            .expect("未找到 HIDDEN_OFF 图层")
+            .is_visible
+    );
+    let accent = doc.layer("ACCENT").expect("未找到 ACCENT 图层");
+    assert!(
+        accent.is_frozen && !accent.is_displayed(),
+        "冻结图层应视为不可见"
+    );
+    assert_eq!(accent.color, ObjectColor::TrueColor(0x336699));
+
+    let (line_id, _) = doc
+        .entities()
+        .find(|(_, entity)| matches!(entity, Entity::Line(_)))
+        .expect("未找到 LINE 实体");
+    let resolved = doc.resolve_properties(*line_id).expect("解析实体特性失败");
+    assert_eq!(resolved.color, ObjectColor::Index(1));
+    assert_eq!(resolved.linetype, "DASHED");
+    assert_eq!(resolved.lineweight.millimeters(), Some(0.5));
+    assert!(doc.entity_properties(*line_id).is_none());
+
+    // 实体公共部分的颜色、线型与线宽覆盖图层特性，真彩色优先于索引色
+    let (circle_id, _) = doc
+        .entities()
+        .find(|(_, entity)| matches!(entity, Entity::Circle(_)))
+        .expect("未找到 CIRCLE 实体");
+    let resolved = doc
+        .resolve_properties(*circle_id)
+        .expect("解析实体特性失败");
+    assert_eq!(resolved.color, ObjectColor::TrueColor(0x112233));
+    assert_eq!(resolved.linetype, "Center");
+    assert_eq!(resolved.lineweight, LineWeight::Hundredths(25));
+}
+
+#[test]
+fn load_shape_entities_and_bind_shape_file() {
+    let mut fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    fixtures.push("tests/data/shape_basic.dxf");
+
+    let loader = DxfFacade::new();
+    let doc = loader.load(&fixtures).expect("读取含 SHAPE 的 DXF 失败");
+    assert_golden("shape_basic", &doc);
+
+    assert_eq!(doc.shape_files().count(), 1, "普通文字样式不应登记为型文件");
+    let shapes: Vec<_> = doc
+        .entities()
+        .filter_map(|(_, entity)| match entity {
+            Entity::Shape(shape) => Some(shape),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(shapes.len(), 2);
+    let track = shapes[0];
+    assert_eq!(track.name, "TRACK1");
+    assert_eq!(track.insert, Point2::new(10.0, 5.0));
+    assert!((track.rotation - PI / 2.0).abs() < 1e-9);
+    assert!((track.width_factor - 0.8).abs() < 1e-9);
+    assert_eq!(track.style_handle.as_deref(), Some("1A"));
+    let shape_file = doc.shape_file_for(track).expect("SHAPE 未关联型文件");
+    assert_eq!(shape_file.file, "ltypeshp.shx");
+    assert!((shapes[1].width_factor - 1.0).abs() < 1e-9);
+
+    let bounds = doc.bounds().expect("SHAPE 应参与范围计算");
+    assert!(bounds.max().x() >= 10.0 && bounds.max().y() >= 7.0);
+}
+
+#[test]
+fn load_complex_linetypes_with_text_and_shapes() {
+    let mut fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    fixtures.push("tests/data/linetype_complex.dxf");
+
+    let loader = DxfFacade::new();
+    let doc = loader.load(&fixtures).expect("读取复杂线型 DXF 失败");
+    assert_golden("linetype_complex", &doc);
+
+    let gas = doc.linetype("gas_line").expect("未找到 GAS_LINE 线型");
+    assert!(gas.is_complex());
+    assert_eq!(gas.elements.len(), 3);
+    let embedded = gas.elements[1].embedded.as_ref().expect("缺少嵌入文字");
+    assert_eq!(
+        embedded.content,
+        LinetypeEmbeddedContent::Text("GAS".to_string())
+    );
+    assert_eq!(embedded.style_handle.as_deref(), Some("11"));
+    assert!((embedded.scale - 0.1).abs() < 1e-9);
+    assert_eq!(embedded.offset, Vector2::new(-0.1, -0.05));
+
+    let tracks = doc.linetype("TRACKS").expect("未找到 TRACKS 线型");
+    let embedded = tracks.elements[0].embedded.as_ref().expect("缺少嵌入型");
+    assert_eq!(embedded.content, LinetypeEmbeddedContent::Shape(1));
+    let shape_file = doc
+        .shape_file(embedded.style_handle.as_deref().unwrap())
+        .expect("型元素应引用型文件");
+    assert_eq!(shape_file.file, "ltypeshp.shx");
+    assert!(doc.linetype("Continuous").unwrap().is_continuous());
+
+    let (line_id, _) = doc.entities().next().expect("缺少 LINE 实体");
+    let resolved = doc.resolved_linetype(*line_id).expect("应解析随层线型");
+    assert_eq!(resolved.name, "GAS_LINE");
+}
+
+#[test]
+fn load_xref_namespaces_reference_layers_and_blocks() {
+    let data_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+
+    let loader = DxfFacade::new();
+    let mut doc = loader
+        .load(&data_dir.join("xref_host.dxf"))
+        .expect("读取含外部参照的 DXF 失败");
+    let xref = doc.xref("SITE").expect("未登记外部参照 SITE");
+    assert_eq!(xref.path, "xref_site.dxf");
+    assert!(!xref.is_loaded);
+    assert!(doc.block("SITE").is_none());
+
+    let failures = load_xrefs(&mut doc, Some(&data_dir), &loader);
+    assert!(failures.is_empty(), "外部参照载入失败: {failures:?}");
+
+    let xref = doc.xref("SITE").expect("外部参照 SITE 丢失");
+    assert!(xref.is_loaded);
+    assert_eq!(xref.layers, vec!["SITE|PLANTS", "SITE|WALLS"]);
+    assert_eq!(xref.blocks, vec!["SITE|TREE"]);
+    let site = doc.block("SITE").expect("未生成参照块 SITE");
+    assert_eq!(site.entities.len(), 2);
+    assert_eq!(site.entities[0].layer_name(), "SITE|WALLS");
+    assert!(doc.block("SITE|TREE").is_some());
+    assert!(doc.layers().any(|layer| layer.name == "WALLS"));
+
+    doc.detach_xref("SITE").expect("拆离外部参照失败");
+    assert!(doc.block("SITE|TREE").is_none());
+    assert!(doc.layers().all(|layer| !layer.name.starts_with("SITE|")));
+    assert_eq!(doc.entities().count(), 1);
+}
+
 fn compute_polygon_area(vertices: &[zcad_core::geometry::Point2]) -> f64 {
     if vertices.len() < 3 {
         return 0.0;
@@ -1343,6 +1795,86 @@ fn load_raster_image_with_missing_file() {
     );
 }
 
+#[test]
+fn load_resolves_image_paths_from_drawing_dir_and_search_roots() {
+    let root = tempfile::tempdir().expect("创建临时目录失败");
+    let drawings = root.path().join("drawings");
+    let shared = root.path().join("shared");
+    let library = root.path().join("library");
+    for dir in [&drawings, &shared, &library] {
+        std::fs::create_dir_all(dir).unwrap();
+    }
+    std::fs::write(shared.join("site.png"), b"png").unwrap();
+    std::fs::write(shared.join("site.pgw"), "0.5\n0\n0\n-0.5\n100\n200\n").unwrap();
+    std::fs::write(library.join("logo.png"), b"png").unwrap();
+
+    let mut pairs = vec![("0", "SECTION"), ("2", "OBJECTS")];
+    for (handle, path) in [
+        ("20", r"..\shared\site.png"),
+        ("21", r"C:\Projects\library\logo.png"),
+        ("22", "missing.png"),
+    ] {
+        pairs.extend([
+            ("0", "IMAGEDEF"),
+            ("5", handle),
+            ("1", path),
+            ("10", "2.0"),
+            ("20", "1.0"),
+        ]);
+    }
+    pairs.extend([("0", "ENDSEC"), ("0", "EOF")]);
+    let content: String = pairs
+        .iter()
+        .map(|(code, value)| format!("{code}\n{value}\n"))
+        .collect();
+    let drawing = drawings.join("plan.dxf");
+    std::fs::write(&drawing, content).unwrap();
+
+    // 未配置搜索目录时只能找到相对图纸目录的图像
+    let doc = DxfFacade::new().load(&drawing).expect("读取 DXF 失败");
+    let site = doc.raster_image_definition("20").unwrap();
+    let site_path = PathBuf::from(site.resolved_path.as_deref().expect("应解析相对路径"));
+    assert!(site_path.ends_with("shared/site.png"));
+    assert!(
+        doc.raster_image_definition("21")
+            .unwrap()
+            .resolved_path
+            .is_none()
+    );
+
+    let resources = ResourceConfig {
+        image_roots: vec![library.clone(), root.path().join("not-a-dir")],
+        ..ResourceConfig::default()
+    };
+    let resolver = ImagePathResolver::from_resources(Some(&drawings), &resources);
+    assert_eq!(resolver.search_roots(), std::slice::from_ref(&library));
+
+    let doc = DxfFacade::new()
+        .with_image_resources(&resources)
+        .load(&drawing)
+        .expect("读取 DXF 失败");
+    let logo = doc.raster_image_definition("21").unwrap();
+    assert!(
+        PathBuf::from(
+            logo.resolved_path
+                .as_deref()
+                .expect("应按文件名在搜索目录中找到")
+        )
+        .ends_with("library/logo.png")
+    );
+    assert!(
+        doc.raster_image_definition("22")
+            .unwrap()
+            .resolved_path
+            .is_none()
+    );
+
+    let world_path = WorldFile::find_for_image(&site_path).expect("应找到世界文件");
+    let world = WorldFile::load(&world_path).expect("解析世界文件失败");
+    assert_eq!(world.pixel_to_world(4.0, 2.0), (102.0, 199.0));
+    assert!(WorldFile::parse("1 2 3").is_err());
+}
+
 #[test]
 fn load_raster_image_with_polygon_clip() {
     let mut fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -1590,3 +2122,210 @@ fn normalize_angle(angle: f64) -> f64 {
 fn point_on_circle(center: Point2, radius: f64, angle: f64) -> Point2 {
     center.translate(Vector2::new(radius * angle.cos(), radius * angle.sin()))
 }
+
+#[test]
+fn load_pdf_underlays_with_definitions_and_clip() {
+    let mut fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    fixtures.push("tests/data/pdf_underlay.dxf");
+
+    let doc = DxfFacade::new().load(&fixtures).expect("读取 DXF 失败");
+    assert_golden("pdf_underlay", &doc);
+
+    let underlays: Vec<_> = doc
+        .entities()
+        .filter_map(|(_, entity)| match entity {
+            Entity::Underlay(underlay) => Some(underlay),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(underlays.len(), 2);
+    let site = underlays[0];
+    assert_eq!(site.kind, UnderlayKind::Pdf);
+    assert_eq!(site.definition_handle, "2A");
+    assert!((site.rotation - PI / 2.0).abs() < 1e-9);
+    assert!(site.display_options.is_clipping && site.display_options.is_on);
+    assert_eq!(site.display_options.contrast, 60);
+    assert_eq!(site.display_options.fade, 25);
+    assert_eq!(site.clip_outline().len(), 4);
+    let details = underlays[1];
+    assert!(details.display_options.is_clip_inverted);
+    assert_eq!(details.clip_boundary.len(), 3);
+
+    let definition = doc.underlay_definition("2A").expect("缺少 PDFDEFINITION");
+    assert_eq!(definition.file_path, r"plans\site-plan.pdf");
+    assert_eq!(definition.page, "1");
+    assert_eq!(definition.name.as_deref(), Some("site-plan - 1"));
+
+    let dependencies = doc.underlay_dependencies();
+    assert_eq!(dependencies.len(), 2);
+    assert!(
+        dependencies
+            .iter()
+            .all(|dependency| dependency.reference_count == 1)
+    );
+}
+
+#[test]
+fn load_dgn_and_dwf_underlays_as_generic_underlays() {
+    let mut fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    fixtures.push("tests/data/dgn_dwf_underlay.dxf");
+
+    let doc = DxfFacade::new().load(&fixtures).expect("读取 DXF 失败");
+    assert_golden("dgn_dwf_underlay", &doc);
+
+    let kinds: Vec<UnderlayKind> = doc
+        .entities()
+        .filter_map(|(_, entity)| match entity {
+            Entity::Underlay(underlay) => Some(underlay.kind),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(kinds, vec![UnderlayKind::Dgn, UnderlayKind::Dwf]);
+
+    let dgn = doc.underlay_definition("4A").expect("缺少 DGNDEFINITION");
+    assert_eq!(dgn.kind, UnderlayKind::Dgn);
+    assert_eq!(dgn.page, "Default");
+    assert_eq!(dgn.name.as_deref(), Some("survey-Default"));
+    let dwf = doc.underlay_definition("4B").expect("缺少 DWFDEFINITION");
+    assert_eq!(dwf.kind, UnderlayKind::Dwf);
+    assert_eq!(dwf.file_path, "review/markup.dwf");
+
+    let dependencies = doc.underlay_dependencies();
+    let files: Vec<_> = dependencies
+        .iter()
+        .map(|dependency| (dependency.kind, dependency.file_path.as_str()))
+        .collect();
+    assert_eq!(
+        files,
+        vec![
+            (UnderlayKind::Dwf, "review/markup.dwf"),
+            (UnderlayKind::Dgn, "survey/base.dgn"),
+        ]
+    );
+}
+
+#[test]
+fn load_revision_clouds_from_revcloud_xdata() {
+    let mut fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    fixtures.push("tests/data/revcloud.dxf");
+
+    let doc = DxfFacade::new().load(&fixtures).expect("读取 DXF 失败");
+    assert_golden("revcloud", &doc);
+
+    let clouds: Vec<_> = doc.revision_clouds().map(|(_, cloud)| cloud).collect();
+    assert_eq!(clouds.len(), 2);
+    assert_eq!(clouds[0].style, RevisionCloudStyle::Calligraphy);
+    assert_eq!(clouds[0].arc_length, 5.0);
+    assert!(clouds[0].is_closed);
+    assert_eq!(clouds[0].vertices.len(), 4);
+    // 缺少弧长时按平均弦长估算
+    assert_eq!(clouds[1].style, RevisionCloudStyle::Normal);
+    assert!((clouds[1].arc_length - 2.0).abs() < 1e-9);
+
+    // 其他应用的扩展数据不会把多段线识别为云线
+    let plain = doc
+        .entities()
+        .filter(|(_, entity)| matches!(entity, Entity::Polyline(_)))
+        .count();
+    assert_eq!(plain, 1);
+}
+
+#[test]
+fn load_wipeout_variables_hides_wipeout_frames() {
+    let mut fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    fixtures.push("tests/data/wipeout_frame_hidden.dxf");
+
+    let doc = DxfFacade::new().load(&fixtures).expect("读取 DXF 失败");
+    assert_golden("wipeout_frame_hidden", &doc);
+
+    let vars = doc.wipeout_variables().expect("应解析出 WIPEOUTVARIABLES");
+    assert_eq!(vars.handle.as_deref(), Some("WV1"));
+    assert_eq!(vars.frame, Some(0));
+    assert!(!vars.frame_plotted());
+    assert!(!doc.wipeout_frame_visible());
+
+    // 边框关闭时压平结果只保留普通直线
+    let flat = doc.flatten(&FlattenOptions::default());
+    let kinds: Vec<_> = flat
+        .entities()
+        .map(|(_, entity)| entity.kind_name())
+        .collect();
+    assert_eq!(kinds, vec!["LINE"]);
+}
+
+#[test]
+fn load_geodata_and_map_design_points_to_geographic_coordinates() {
+    let mut fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    fixtures.push("tests/data/geodata_geographic.dxf");
+
+    let doc = DxfFacade::new().load(&fixtures).expect("读取 DXF 失败");
+    assert_golden("geodata_geographic", &doc);
+
+    let geodata = doc.geodata().expect("缺少 GEODATA");
+    assert_eq!(geodata.handle.as_deref(), Some("61"));
+    assert_eq!(geodata.coordinate_type, GeoCoordinateType::Geographic);
+    assert_eq!(geodata.horizontal_units, 4);
+    assert!(geodata.sea_level_correction);
+    assert!(geodata.coordinate_system.starts_with("<?xml"));
+    assert!(geodata.coordinate_system.ends_with("</Dictionary>"));
+
+    // 图纸单位为毫米，1000 单位对应向东 1 米
+    let origin = geodata
+        .to_geojson_position(Point2::new(0.0, 0.0))
+        .expect("地理坐标应可输出 GeoJSON");
+    assert!((origin[0] - 121.5).abs() < 1e-12 && (origin[1] - 31.2).abs() < 1e-12);
+    let east = geodata.to_map(Point2::new(1000.0, 0.0));
+    assert!(east.x() > 121.5 && (east.x() - 121.5) < 1e-4);
+    assert!((east.y() - 31.2).abs() < 1e-12);
+}
+
+#[test]
+fn diagnostics_mode_skips_bad_entities_and_keeps_handles() {
+    let path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/invalid/diagnostics_mixed.dxf");
+    let err = DxfFacade::new()
+        .load(&path)
+        .expect_err("严格模式应拒绝 POINT");
+    assert!(err.to_string().contains("POINT"), "{err}");
+
+    let (doc, diagnostics) = DxfFacade::new()
+        .load_with_diagnostics(&path)
+        .expect("诊断模式读取失败");
+    let ids: Vec<_> = doc.entities().map(|(id, _)| *id).collect();
+    assert_eq!(ids.len(), 3);
+    let handles: Vec<_> = ids
+        .iter()
+        .map(|id| diagnostics.entity_handle(*id).unwrap())
+        .collect();
+    assert_eq!(handles, vec!["1A", "1D", "1E"]);
+    assert_eq!(doc.block("MARK").unwrap().entities.len(), 1);
+
+    let findings: Vec<_> = diagnostics
+        .findings
+        .iter()
+        .map(|finding| {
+            (
+                finding.severity,
+                finding.entity.as_str(),
+                finding.handle.as_deref(),
+                finding.block.as_deref(),
+                finding.line,
+            )
+        })
+        .collect();
+    assert_eq!(
+        findings,
+        vec![
+            (
+                AuditSeverity::Warning,
+                "POINT",
+                Some("B1"),
+                Some("MARK"),
+                17
+            ),
+            (AuditSeverity::Warning, "POINT", Some("1B"), None, 63),
+            (AuditSeverity::Error, "LINE", Some("1C"), None, 73),
+        ]
+    );
+    assert!(diagnostics.findings[2].message.contains("abc"));
+}
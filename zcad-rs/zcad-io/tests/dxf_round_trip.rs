@@ -0,0 +1,84 @@
+use std::path::PathBuf;
+
+use zcad_core::document::{Document, Entity, Line};
+use zcad_core::geometry::Point2;
+use zcad_io::{DocumentLoader, DocumentSaver, DxfFacade, compare::compare_documents};
+
+fn fixtures() -> Vec<PathBuf> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .expect("读取测试数据目录失败")
+        .map(|entry| entry.expect("读取目录项失败").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "dxf"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn assert_close(a: f64, b: f64, context: &str) {
+    assert!(
+        (a - b).abs() <= 1e-9 * 1f64.max(a.abs()).max(b.abs()),
+        "{context}: {a} != {b}"
+    );
+}
+
+#[test]
+fn every_fixture_survives_save_and_reload() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let facade = DxfFacade::new();
+    let fixtures = fixtures();
+    assert!(!fixtures.is_empty(), "未找到 DXF 测试数据");
+    for path in fixtures {
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        let original = facade
+            .load(&path)
+            .unwrap_or_else(|err| panic!("读取 {name} 失败: {err}"));
+        let saved = dir.path().join(&name);
+        facade
+            .save(&original, &saved)
+            .unwrap_or_else(|err| panic!("保存 {name} 失败: {err}"));
+        let reloaded = facade
+            .load(&saved)
+            .unwrap_or_else(|err| panic!("重新读取 {name} 失败: {err}"));
+
+        assert_eq!(
+            original.entities().count(),
+            reloaded.entities().count(),
+            "{name}: 实体数量不一致"
+        );
+        match (original.bounds(), reloaded.bounds()) {
+            (Some(a), Some(b)) => {
+                assert_close(a.min().x(), b.min().x(), &name);
+                assert_close(a.min().y(), b.min().y(), &name);
+                assert_close(a.max().x(), b.max().x(), &name);
+                assert_close(a.max().y(), b.max().y(), &name);
+            }
+            (None, None) => {}
+            (a, b) => panic!("{name}: 包围盒不一致 {a:?} / {b:?}"),
+        }
+        if let Some(divergence) = compare_documents(&original, &reloaded) {
+            panic!("{name}: 往返后出现差异 {divergence}");
+        }
+    }
+}
+
+fn line_document(end_x: f64) -> Document {
+    let mut document = Document::new();
+    document.add_entity(Entity::Line(Line {
+        start: Point2::new(0.0, 0.0),
+        end: Point2::new(end_x, 0.0),
+        layer: "0".into(),
+    }));
+    document
+}
+
+#[test]
+fn compare_documents_reports_first_divergent_path() {
+    assert_eq!(
+        compare_documents(&line_document(10.0), &line_document(10.0 + 1e-12)),
+        None
+    );
+    let divergence =
+        compare_documents(&line_document(10.0), &line_document(11.0)).expect("应检测到差异");
+    assert!(divergence.path == "entities[0].Line.end[0]", "{divergence}");
+}
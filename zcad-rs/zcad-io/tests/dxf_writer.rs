@@ -0,0 +1,377 @@
+mod golden;
+
+use std::path::{Path, PathBuf};
+
+use golden::assert_golden;
+use zcad_core::{
+    document::{
+        AngleFormat, Document, Entity, EntityProperties, HatchLoop, HatchStyle, InsertUnits,
+        LINETYPE_BYBLOCK, LengthFormat, LineWeight, ObjectColor, Units, UserValue,
+    },
+    geometry::Point2,
+};
+use zcad_io::{
+    DocumentLoader, DxfFacade,
+    compare::compare_documents,
+    writer::{
+        DxfVersion, DxfWriteOptions, LineEnding, write_document, write_document_with_options,
+        write_raster_sections, write_raster_sections_with_options,
+    },
+};
+
+fn load_fixture(name: &str) -> Document {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("tests/data/{name}.dxf"));
+    DxfFacade::new()
+        .load(&path)
+        .unwrap_or_else(|err| panic!("读取 {name}.dxf 失败: {err}"))
+}
+
+fn reload(content: &str, dir: &Path) -> Document {
+    let path = dir.join("round_trip.dxf");
+    std::fs::write(&path, content).expect("写入临时 DXF 失败");
+    DxfFacade::new()
+        .load(&path)
+        .expect("重新读取写出的 DXF 失败")
+}
+
+#[test]
+fn raster_objects_round_trip_through_writer() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    for name in [
+        "image_basic",
+        "image_clip_dict_basic",
+        "image_clip_dict_runtime",
+        "image_clip_polygon",
+        "image_clip_polygon_inverted",
+        "wipeout_clip",
+    ] {
+        let written = write_raster_sections(&load_fixture(name));
+        let reloaded = reload(&written, dir.path());
+        assert_golden(name, &reloaded);
+        assert_eq!(
+            write_raster_sections(&reloaded),
+            written,
+            "{name} 二次写出结果应保持一致"
+        );
+    }
+}
+
+#[test]
+fn writer_creates_image_dictionary_and_reactor_when_missing() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let document = load_fixture("image_missing_file");
+    assert!(document.image_dictionary().is_none());
+
+    let reloaded = reload(&write_raster_sections(&document), dir.path());
+    let dictionary = reloaded
+        .image_dictionary()
+        .expect("写出时应补建 ACAD_IMAGE_DICT");
+    assert_eq!(dictionary.entries.len(), 1);
+    let entry = &dictionary.entries[0];
+    assert_eq!(entry.name, "image");
+    assert_eq!(entry.image_def_handle, "20");
+
+    let image = reloaded
+        .entities()
+        .find_map(|(_, entity)| match entity {
+            Entity::RasterImage(image) => Some(image),
+            _ => None,
+        })
+        .expect("未找到 IMAGE 实体");
+    let reactor_handle = image
+        .image_def_reactor_handle
+        .as_deref()
+        .expect("IMAGE 应指向新建的反应器");
+    assert_eq!(entry.reactor_handle.as_deref(), Some(reactor_handle));
+    let reactor = reloaded
+        .image_def_reactor(reactor_handle)
+        .expect("应写出 IMAGEDEF_REACTOR");
+    assert_eq!(reactor.owner_handle.as_deref(), Some("20"));
+    assert!(reactor.image_handle.is_some());
+
+    let variables = reloaded
+        .raster_image_variables()
+        .expect("应写出缺省 RASTERVARIABLES");
+    assert_eq!(variables.frame, Some(1));
+    assert_eq!(variables.quality, Some(1));
+    assert_eq!(variables.units, Some(0));
+}
+
+#[test]
+fn writer_keeps_wipeout_frame_setting() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let written = write_raster_sections(&load_fixture("wipeout_frame_hidden"));
+    let reloaded = reload(&written, dir.path());
+
+    let variables = reloaded
+        .wipeout_variables()
+        .expect("应写出 WIPEOUTVARIABLES");
+    assert_eq!(variables.handle.as_deref(), Some("WV1"));
+    assert_eq!(variables.frame, Some(0));
+    assert!(!reloaded.wipeout_frame_visible());
+    // 首次写出会补建图像字典与光栅变量，之后的写出保持稳定
+    let rewritten = write_raster_sections(&reloaded);
+    assert_eq!(
+        write_raster_sections(&reload(&rewritten, dir.path())),
+        rewritten
+    );
+
+    // 没有记录时不补写，保持缺省的边框显示
+    let plain = reload(
+        &write_raster_sections(&load_fixture("wipeout_clip")),
+        dir.path(),
+    );
+    assert!(plain.wipeout_variables().is_none());
+    assert!(plain.wipeout_frame_visible());
+}
+
+#[test]
+fn writer_round_trips_units_header_variables() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let mut document = Document::new();
+    document.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+    let units = Units {
+        insertion: InsertUnits::Feet,
+        length_format: LengthFormat::Architectural,
+        length_precision: 4,
+        angle_format: AngleFormat::DegreesMinutesSeconds,
+        angle_precision: 2,
+    };
+    document.set_units(units);
+
+    let reloaded = reload(&write_document(&document), dir.path());
+    assert_eq!(reloaded.units(), units);
+    assert_eq!(load_fixture("wipeout_clip").units(), Units::default());
+}
+
+#[test]
+fn writer_round_trips_layer_flags_and_layer_states() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let mut document = Document::new();
+    document.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "WALL");
+    document.add_line(Point2::new(0.0, 1.0), Point2::new(1.0, 1.0), "NOTES");
+    document
+        .save_layer_state("All On", "working view")
+        .expect("保存图层状态失败");
+    let notes = document.layer_mut("NOTES").unwrap();
+    notes.is_frozen = true;
+    notes.color = ObjectColor::TrueColor(0x336699);
+    let wall = document.layer_mut("WALL").unwrap();
+    wall.is_locked = true;
+    wall.is_visible = false;
+    document
+        .save_layer_state("Plot", "")
+        .expect("保存图层状态失败");
+
+    let mut reloaded = reload(&write_document(&document), dir.path());
+    let notes = reloaded.layer("NOTES").unwrap();
+    assert!(notes.is_frozen && notes.is_visible && !notes.is_locked);
+    let wall = reloaded.layer("WALL").unwrap();
+    assert!(wall.is_locked && !wall.is_visible && !wall.is_frozen);
+    assert_eq!(
+        reloaded.layer_state("all on"),
+        document.layer_state("All On")
+    );
+    assert_eq!(reloaded.layer_state("PLOT"), document.layer_state("Plot"));
+
+    assert_eq!(reloaded.restore_layer_state("All On"), Ok(3));
+    assert_eq!(reloaded.visible_entities().count(), 2);
+    assert!(!reloaded.layer("WALL").unwrap().is_locked);
+}
+
+#[test]
+fn writer_round_trips_draw_order_through_sortents_table() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let mut document = Document::new();
+    let lines: Vec<_> = (0..3)
+        .map(|i| {
+            let y = f64::from(i);
+            document.add_line(Point2::new(0.0, y), Point2::new(1.0, y), "0")
+        })
+        .collect();
+    let unordered = write_document(&document);
+    assert!(!unordered.contains("SORTENTSTABLE"));
+
+    document.bring_to_front(&[lines[0]]);
+    document.send_to_back(&[lines[2]]);
+    let content = write_document(&document);
+    assert!(content.contains("SORTENTSTABLE"));
+
+    let reloaded = reload(&content, dir.path());
+    let starts: Vec<f64> = reloaded
+        .display_list()
+        .iter()
+        .map(|(_, entity)| match entity {
+            Entity::Line(line) => line.start.y(),
+            other => panic!("意外的实体: {other:?}"),
+        })
+        .collect();
+    assert_eq!(starts, [2.0, 1.0, 0.0]);
+    assert!(!reload(&unordered, dir.path()).has_draw_order());
+}
+
+#[test]
+fn writer_round_trips_user_data_as_xdata() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let mut document = Document::new();
+    let line = document.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+    let circle = document.add_circle(Point2::new(0.0, 0.0), 1.0, "0");
+    let note = "备注".repeat(200);
+    document.set_user_value(line, "asset", "P-101").unwrap();
+    document
+        .set_user_value(line, "note", note.as_str())
+        .unwrap();
+    document
+        .set_user_value(
+            line,
+            "ports",
+            vec![UserValue::Integer(1), UserValue::Real(2.5), UserValue::Null],
+        )
+        .unwrap();
+
+    assert!(!write_document(&document).contains("ZCAD_USERDATA"));
+
+    let options = DxfWriteOptions {
+        write_user_data: true,
+        ..DxfWriteOptions::default()
+    };
+    let content = write_document_with_options(&document, &options);
+    assert!(content.contains("APPID"));
+    let reloaded = reload(&content, dir.path());
+    let ids: Vec<_> = reloaded.entities().map(|(id, _)| *id).collect();
+    assert_eq!(reloaded.user_data(ids[0]), document.user_data(line));
+    assert_eq!(
+        reloaded
+            .user_value(ids[0], "note")
+            .and_then(UserValue::as_str),
+        Some(note.as_str())
+    );
+    assert!(reloaded.user_data(ids[1]).is_none());
+    assert!(document.user_data(circle).is_none());
+}
+
+#[test]
+fn writer_round_trips_entity_property_overrides() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let mut document = Document::new();
+    let line = document.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+    let circle = document.add_circle(Point2::new(0.0, 0.0), 1.0, "0");
+    let text = document.add_text(Point2::new(0.0, 0.0), "A", 1.0, 0.0, "0");
+    document.add_line(Point2::new(0.0, 1.0), Point2::new(1.0, 1.0), "0");
+    document.set_entity_properties(
+        line,
+        EntityProperties {
+            color: ObjectColor::Index(1),
+            linetype: "DASHED".into(),
+            lineweight: LineWeight::Hundredths(35),
+        },
+    );
+    document.set_entity_properties(
+        circle,
+        EntityProperties {
+            color: ObjectColor::TrueColor(0x336699),
+            ..EntityProperties::default()
+        },
+    );
+    document.set_entity_properties(
+        text,
+        EntityProperties {
+            color: ObjectColor::ByBlock,
+            linetype: LINETYPE_BYBLOCK.into(),
+            lineweight: LineWeight::ByBlock,
+        },
+    );
+
+    let reloaded = reload(&write_document(&document), dir.path());
+    assert_eq!(compare_documents(&document, &reloaded), None);
+    let ids: Vec<_> = reloaded.entities().map(|(id, _)| *id).collect();
+    assert_eq!(
+        reloaded.entity_properties(ids[0]),
+        document.entity_properties(line)
+    );
+    assert!(reloaded.entity_properties(ids[3]).is_none());
+
+    // 比较也覆盖特性覆盖
+    document.set_entity_properties(circle, EntityProperties::default());
+    let divergence = compare_documents(&document, &reloaded).expect("应检测到特性差异");
+    assert!(
+        divergence.path.starts_with("entity_properties[1]"),
+        "{divergence}"
+    );
+}
+
+#[test]
+fn writer_keeps_hatch_boundary_references_resolvable() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let mut document = Document::new();
+    let circle = document.add_circle(Point2::new(0.0, 0.0), 2.0, "0");
+    document.add_hatch(
+        "SOLID",
+        true,
+        vec![HatchLoop {
+            is_polyline: false,
+            is_closed: true,
+            edges: Vec::new(),
+            boundary_handles: Vec::new(),
+            boundary_entities: vec![circle],
+        }],
+        None,
+        Vec::new(),
+        HatchStyle::default(),
+        true,
+        Vec::new(),
+        "0",
+    );
+
+    let mut reloaded = reload(&write_document(&document), dir.path());
+    let (circle, hatch) = {
+        let mut ids = reloaded.entities().map(|(id, _)| *id);
+        (ids.next().unwrap(), ids.next().unwrap())
+    };
+    let Some(Entity::Hatch(loaded)) = reloaded.entity(hatch) else {
+        panic!("期望第二个实体为 HATCH");
+    };
+    assert_eq!(loaded.loops[0].boundary_entities, vec![circle]);
+    assert_eq!(loaded.loops[0].boundary_handles.len(), 1);
+
+    reloaded.regenerate_hatch(hatch).expect("重建关联填充失败");
+    let Some(Entity::Hatch(regenerated)) = reloaded.entity(hatch) else {
+        panic!("期望第二个实体为 HATCH");
+    };
+    assert_eq!(regenerated.loops[0].edges.len(), 1);
+}
+
+#[test]
+fn write_options_control_formatting_version_and_handles() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let document = load_fixture("wipeout_clip");
+
+    let options = DxfWriteOptions {
+        precision: Some(3),
+        line_ending: LineEnding::CrLf,
+        code_page: "ANSI_936".to_string(),
+        ..DxfWriteOptions::default()
+    };
+    let written = write_raster_sections_with_options(&document, &options);
+    assert!(written.split("\r\n").count() > 1);
+    assert!(!written.replace("\r\n", "").contains('\n'));
+    assert!(written.contains("$ACADVER\r\n  1\r\nAC1032\r\n"));
+    assert!(written.contains("$DWGCODEPAGE\r\n  3\r\nANSI_936\r\n"));
+    assert!(written.contains(" 14\r\n1.800\r\n"));
+    let reloaded = reload(&written, dir.path());
+    assert_eq!(reloaded.entities().count(), document.entities().count());
+
+    let minimal = write_raster_sections_with_options(
+        &document,
+        &DxfWriteOptions {
+            version: DxfVersion::R12,
+            write_handles: false,
+            ..DxfWriteOptions::default()
+        },
+    );
+    assert!(minimal.contains("AC1009"));
+    assert!(!minimal.contains("$HANDSEED"));
+    assert!(!minimal.contains("OBJECTS"));
+    assert!(!minimal.contains("AcDbWipeout"));
+    assert!(!minimal.lines().any(|line| line == "  5"));
+}
@@ -0,0 +1,110 @@
+use serde_json::Value;
+use zcad_core::document::{AttributeDefinition, BlockDefinition, Document, ExtractionOptions};
+use zcad_core::geometry::{Point2, Vector2};
+use zcad_io::DocumentSaver;
+use zcad_io::extract::{
+    ExtractionExportOptions, ExtractionExporter, ExtractionFormat, table_to_csv,
+};
+
+fn definition(tag: &str, default_text: &str) -> AttributeDefinition {
+    AttributeDefinition {
+        tag: tag.to_string(),
+        prompt: None,
+        default_text: default_text.to_string(),
+        insert: Point2::new(0.0, 0.0),
+        height: 1.0,
+        rotation: 0.0,
+        width_factor: 1.0,
+        oblique: 0.0,
+        style: None,
+        alignment: None,
+        horizontal_align: 0,
+        vertical_align: 0,
+        line_spacing_factor: 1.0,
+        line_spacing_style: 1,
+        is_invisible: false,
+        is_constant: false,
+        is_verify: false,
+        is_preset: false,
+        lock_position: false,
+        layer: "0".to_string(),
+    }
+}
+
+fn schedule() -> Document {
+    let mut document = Document::new();
+    document.add_block_definition(BlockDefinition {
+        name: "WINDOW".to_string(),
+        base_point: Point2::new(0.0, 0.0),
+        entities: Vec::new(),
+        attributes: vec![definition("MARK", "W?"), definition("NOTE", "")],
+    });
+    for (index, note) in ["sill 900", "frosted, \"privacy\"", "sill 900"]
+        .into_iter()
+        .enumerate()
+    {
+        let id = document.add_block_reference(
+            "WINDOW",
+            Point2::new(index as f64 * 2.5, 1.0),
+            Vector2::new(1.0, 1.0),
+            0.0,
+            Vec::new(),
+            "A-GLAZ",
+        );
+        document.set_attribute_value(id, "MARK", "W1").unwrap();
+        document.set_attribute_value(id, "NOTE", note).unwrap();
+    }
+    document
+}
+
+#[test]
+fn csv_lists_each_reference_and_escapes_fields() {
+    let table = schedule().extract_attributes(&ExtractionOptions::default());
+    let csv = table_to_csv(&table);
+    let lines: Vec<&str> = csv.split_terminator("\r\n").collect();
+    assert_eq!(
+        lines,
+        [
+            "Block,Layer,X,Y,MARK,NOTE",
+            "WINDOW,A-GLAZ,0,1,W1,sill 900",
+            "WINDOW,A-GLAZ,2.5,1,W1,\"frosted, \"\"privacy\"\"\"",
+            "WINDOW,A-GLAZ,5,1,W1,sill 900",
+        ]
+    );
+}
+
+#[test]
+fn grouped_export_counts_identical_rows() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let exporter = ExtractionExporter::new().with_options(ExtractionExportOptions {
+        extraction: ExtractionOptions {
+            group_by_block: true,
+            ..ExtractionOptions::default()
+        },
+        ..ExtractionExportOptions::default()
+    });
+    let csv = exporter.export(&schedule()).unwrap();
+    assert!(csv.starts_with("Block,Count,MARK,NOTE\r\n"));
+    assert!(csv.contains("WINDOW,2,W1,sill 900\r\n"));
+
+    let path = dir.path().join("windows.json");
+    assert_eq!(
+        ExtractionFormat::from_path(&path),
+        Some(ExtractionFormat::Json)
+    );
+    let exporter = ExtractionExporter::new().with_options(ExtractionExportOptions {
+        format: ExtractionFormat::Json,
+        ..exporter.options().clone()
+    });
+    exporter.save(&schedule(), &path).unwrap();
+    let value: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(value["grouped"], true);
+    assert_eq!(value["tags"], serde_json::json!(["MARK", "NOTE"]));
+    let counts: Vec<u64> = value["rows"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row["count"].as_u64().unwrap())
+        .collect();
+    assert_eq!(counts, [1, 2]);
+}
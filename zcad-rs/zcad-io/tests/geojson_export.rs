@@ -0,0 +1,219 @@
+use std::path::PathBuf;
+
+use serde_json::{Value, json};
+use zcad_core::document::{Document, HatchEdge, HatchLoop, HatchStyle};
+use zcad_core::geometry::Point2;
+use zcad_core::tessellate::TessellateOptions;
+use zcad_io::geojson::{GeoJsonExportOptions, GeoJsonExporter};
+use zcad_io::{DocumentLoader, DocumentSaver, DxfFacade};
+
+fn load_fixture(name: &str) -> Document {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("tests/data/{name}.dxf"));
+    DxfFacade::new()
+        .load(&path)
+        .unwrap_or_else(|err| panic!("读取 {name}.dxf 失败: {err}"))
+}
+
+fn feature_list(value: &Value) -> &Vec<Value> {
+    assert_eq!(value["type"], "FeatureCollection");
+    value["features"].as_array().expect("缺少 features")
+}
+
+fn square_loop(min: f64, max: f64) -> HatchLoop {
+    let corners = [
+        Point2::new(min, min),
+        Point2::new(max, min),
+        Point2::new(max, max),
+        Point2::new(min, max),
+    ];
+    HatchLoop {
+        is_polyline: false,
+        is_closed: true,
+        edges: (0..4)
+            .map(|index| HatchEdge::Line {
+                start: corners[index],
+                end: corners[(index + 1) % 4],
+            })
+            .collect(),
+        boundary_handles: Vec::new(),
+        boundary_entities: Vec::new(),
+    }
+}
+
+#[test]
+fn geojson_applies_geographic_geodata() {
+    let document = load_fixture("geodata_geographic");
+    let value = GeoJsonExporter::new().to_value(&document);
+    let features = feature_list(&value);
+    assert_eq!(features.len(), 1);
+    let feature = &features[0];
+    assert_eq!(feature["geometry"]["type"], "LineString");
+    let coordinates = feature["geometry"]["coordinates"].as_array().unwrap();
+    // 1000 个毫米单位 = 向东 1 米
+    assert!((coordinates[0][0].as_f64().unwrap() - 121.5).abs() < 1e-12);
+    assert!((coordinates[0][1].as_f64().unwrap() - 31.2).abs() < 1e-12);
+    let east = coordinates[1][0].as_f64().unwrap() - 121.5;
+    assert!(east > 0.0 && east < 2e-5, "{east}");
+    assert_eq!(feature["properties"]["layer"], "SITE");
+    assert_eq!(feature["properties"]["source_type"], "LINE");
+
+    let raw = GeoJsonExporter::new()
+        .with_options(GeoJsonExportOptions {
+            apply_geodata: false,
+            ..GeoJsonExportOptions::default()
+        })
+        .to_value(&document);
+    assert_eq!(
+        feature_list(&raw)[0]["geometry"]["coordinates"],
+        json!([[0.0, 0.0], [1000.0, 0.0]])
+    );
+}
+
+#[test]
+fn geojson_attaches_block_attributes_to_exploded_geometry() {
+    let document = load_fixture("block_insert");
+    let value = GeoJsonExporter::new().to_value(&document);
+    let line = feature_list(&value)
+        .iter()
+        .find(|feature| feature["geometry"]["type"] == "LineString")
+        .expect("块中的直线应导出");
+    let properties = &line["properties"];
+    assert_eq!(properties["source_type"], "INSERT");
+    assert_eq!(properties["block"], "MYBLOCK");
+    assert_eq!(properties["attributes"], json!({ "TAG": "VALUE" }));
+    assert!(properties["color"].is_string());
+}
+
+#[test]
+fn geojson_writes_polygons_with_holes_and_closed_rings() {
+    let mut document = Document::new();
+    document.add_hatch(
+        "SOLID",
+        true,
+        // 外环顺时针给出，导出后应调整为逆时针
+        vec![square_loop(2.0, 8.0), {
+            let mut outer = square_loop(0.0, 10.0);
+            outer.edges.reverse();
+            for edge in &mut outer.edges {
+                if let HatchEdge::Line { start, end } = edge {
+                    std::mem::swap(start, end);
+                }
+            }
+            outer
+        }],
+        None,
+        Vec::new(),
+        HatchStyle::Normal,
+        false,
+        Vec::new(),
+        "PARCELS",
+    );
+    document.add_polyline(
+        vec![
+            Point2::new(20.0, 0.0),
+            Point2::new(30.0, 0.0),
+            Point2::new(30.0, 10.0),
+        ],
+        true,
+        "PARCELS",
+    );
+    document.add_text(Point2::new(5.0, 5.0), "LOT 1", 1.0, 0.0, "LABELS");
+    document.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 1.0), "HIDDEN");
+    document.layer_mut("HIDDEN").unwrap().is_visible = false;
+
+    let value = GeoJsonExporter::new()
+        .with_options(GeoJsonExportOptions {
+            closed_as_polygons: true,
+            ..GeoJsonExportOptions::default()
+        })
+        .to_value(&document);
+    let features = feature_list(&value);
+    assert_eq!(features.len(), 3, "隐藏图层不应导出");
+
+    let hatch = &features[0];
+    assert_eq!(hatch["geometry"]["type"], "Polygon");
+    assert_eq!(hatch["properties"]["pattern"], "SOLID");
+    let rings = hatch["geometry"]["coordinates"].as_array().unwrap();
+    assert_eq!(rings.len(), 2);
+    assert_eq!(
+        rings[0],
+        json!([
+            [10.0, 0.0],
+            [10.0, 10.0],
+            [0.0, 10.0],
+            [0.0, 0.0],
+            [10.0, 0.0]
+        ])
+    );
+    assert_eq!(
+        rings[1],
+        json!([[2.0, 8.0], [8.0, 8.0], [8.0, 2.0], [2.0, 2.0], [2.0, 8.0]])
+    );
+
+    assert_eq!(
+        features[1]["geometry"],
+        json!({
+            "type": "Polygon",
+            "coordinates": [[[20.0, 0.0], [30.0, 0.0], [30.0, 10.0], [20.0, 0.0]]],
+        })
+    );
+    assert_eq!(features[2]["geometry"]["type"], "Point");
+    assert_eq!(features[2]["properties"]["text"], "LOT 1");
+}
+
+#[test]
+fn geojson_samples_circles_by_chord_tolerance() {
+    let mut document = Document::new();
+    document.add_circle(Point2::new(0.0, 0.0), 100.0, "0");
+    let ring_length = |chord_tolerance: f64| {
+        let value = GeoJsonExporter::new()
+            .with_options(GeoJsonExportOptions {
+                tessellation: TessellateOptions {
+                    chord_tolerance,
+                    max_angle: 0.0,
+                },
+                ..GeoJsonExportOptions::default()
+            })
+            .to_value(&document);
+        let ring = feature_list(&value)[0]["geometry"]["coordinates"]
+            .as_array()
+            .unwrap()
+            .clone();
+        for position in &ring {
+            let (x, y) = (position[0].as_f64().unwrap(), position[1].as_f64().unwrap());
+            assert!((x.hypot(y) - 100.0).abs() < 1e-9);
+        }
+        ring.len()
+    };
+    // 弦高 h 对应每段圆心角 2·acos(1 - h/r)，r = 100、h = 1 时整圆 23 段，首尾重复
+    let coarse = ring_length(1.0);
+    let fine = ring_length(0.01);
+    assert_eq!(coarse, 23 + 1);
+    assert!(fine > 4 * coarse, "{fine} / {coarse}");
+}
+
+#[test]
+fn geojson_exporter_saves_every_fixture() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+    for entry in std::fs::read_dir(&fixtures).expect("读取样例目录失败") {
+        let path = entry.expect("读取目录项失败").path();
+        if path.extension().is_none_or(|ext| ext != "dxf") {
+            continue;
+        }
+        let Ok(document) = DxfFacade::new().load(&path) else {
+            continue;
+        };
+        let name = path.file_stem().unwrap().to_string_lossy().to_string();
+        let target = dir.path().join(format!("{name}.geojson"));
+        GeoJsonExporter::new()
+            .save(&document, &target)
+            .unwrap_or_else(|err| panic!("导出 {name}.geojson 失败: {err}"));
+        let value: Value = serde_json::from_str(&std::fs::read_to_string(&target).unwrap())
+            .unwrap_or_else(|err| panic!("{name}: GeoJSON 无效 {err}"));
+        for feature in feature_list(&value) {
+            assert_eq!(feature["type"], "Feature", "{name}");
+            assert!(feature["geometry"]["coordinates"].is_array(), "{name}");
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use std::path::PathBuf;
+
+use serde_json::{Value, json};
+use zcad_core::document::{
+    BlockDefinition, Document, Entity, EntityProperties, ObjectColor, ThreeDFace,
+};
+use zcad_core::geometry::{Point2, Point3, Vector2};
+use zcad_io::gltf::{GltfExportOptions, GltfExporter};
+use zcad_io::{DocumentLoader, DocumentSaver, DxfFacade};
+
+fn square(z: f64) -> [Point3; 4] {
+    [
+        Point3::new(0.0, 0.0, z),
+        Point3::new(1.0, 0.0, z),
+        Point3::new(1.0, 1.0, z),
+        Point3::new(0.0, 1.0, z),
+    ]
+}
+
+/// 按 accessor 读取内嵌缓冲区中的 VEC3 浮点数据。
+fn read_vec3(gltf: &Value, accessor: usize) -> Vec<[f32; 3]> {
+    use base64::Engine as _;
+    let uri = gltf["buffers"][0]["uri"].as_str().unwrap();
+    let data = base64::engine::general_purpose::STANDARD
+        .decode(uri.split_once(',').unwrap().1)
+        .unwrap();
+    let accessor = &gltf["accessors"][accessor];
+    let view = &gltf["bufferViews"][accessor["bufferView"].as_u64().unwrap() as usize];
+    let offset = view["byteOffset"].as_u64().unwrap() as usize;
+    (0..accessor["count"].as_u64().unwrap() as usize)
+        .map(|index| {
+            std::array::from_fn(|axis| {
+                let start = offset + (index * 3 + axis) * 4;
+                f32::from_le_bytes(data[start..start + 4].try_into().unwrap())
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn gltf_splits_faces_into_layer_meshes_and_color_materials() {
+    let mut document = Document::new();
+    document.add_face3d(square(2.0), [false; 4], "ROOF");
+    let triangle = [
+        Point3::new(0.0, 0.0, 0.0),
+        Point3::new(0.0, 1.0, 0.0),
+        Point3::new(0.0, 1.0, 1.0),
+        Point3::new(0.0, 1.0, 1.0),
+    ];
+    let red = document.add_face3d(triangle, [false; 4], "ROOF");
+    document.set_entity_properties(
+        red,
+        EntityProperties {
+            color: ObjectColor::Index(1),
+            ..EntityProperties::default()
+        },
+    );
+    document.add_face3d(square(0.0), [false; 4], "HIDDEN");
+    document.layer_mut("HIDDEN").unwrap().is_visible = false;
+    // 共线顶点构成的退化面不输出
+    document.add_face3d(
+        [
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(2.0, 0.0, 0.0),
+            Point3::new(2.0, 0.0, 0.0),
+        ],
+        [false; 4],
+        "ROOF",
+    );
+    document.add_line(Point2::new(0.0, 0.0), Point2::new(5.0, 5.0), "ROOF");
+
+    let gltf = GltfExporter::new().to_gltf(&document);
+    assert_eq!(gltf["asset"]["version"], "2.0");
+    assert_eq!(gltf["scenes"][0]["nodes"], json!([0]));
+    assert_eq!(gltf["nodes"], json!([{ "name": "ROOF", "mesh": 0 }]));
+    let primitives = gltf["meshes"][0]["primitives"].as_array().unwrap();
+    assert_eq!(primitives.len(), 2);
+
+    // 四边形拆为两个三角形，Z 向上转换为 Y 向上
+    let position = primitives[0]["attributes"]["POSITION"].as_u64().unwrap() as usize;
+    let positions = read_vec3(&gltf, position);
+    assert_eq!(positions.len(), 6);
+    assert_eq!(positions[1], [1.0, 2.0, 0.0]);
+    assert_eq!(positions[2], [1.0, 2.0, -1.0]);
+    assert_eq!(gltf["accessors"][position]["min"], json!([0.0, 2.0, -1.0]));
+    assert_eq!(gltf["accessors"][position]["max"], json!([1.0, 2.0, 0.0]));
+    let normal = primitives[0]["attributes"]["NORMAL"].as_u64().unwrap() as usize;
+    assert!(
+        read_vec3(&gltf, normal)
+            .iter()
+            .all(|normal| *normal == [0.0, 1.0, 0.0])
+    );
+
+    // 三角形面只输出一个三角形，颜色覆盖使用独立材质
+    let red_positions = read_vec3(
+        &gltf,
+        primitives[1]["attributes"]["POSITION"].as_u64().unwrap() as usize,
+    );
+    assert_eq!(red_positions.len(), 3);
+    let materials = gltf["materials"].as_array().unwrap();
+    assert_eq!(materials.len(), 2);
+    assert_eq!(primitives[1]["material"], 1);
+    assert_eq!(materials[0]["name"], "ROOF");
+    assert_eq!(materials[1]["name"], "ROOF #ff0000");
+    assert_eq!(
+        materials[1]["pbrMetallicRoughness"]["baseColorFactor"],
+        json!([1.0, 0.0, 0.0, 1.0])
+    );
+    assert_eq!(materials[1]["doubleSided"], true);
+}
+
+#[test]
+fn gltf_expands_block_references_with_layer_inheritance() {
+    let mut document = Document::new();
+    document.add_block_definition(BlockDefinition {
+        name: "PANEL".to_string(),
+        base_point: Point2::new(1.0, 0.0),
+        entities: vec![Entity::Face3D(ThreeDFace {
+            layer: "0".to_string(),
+            vertices: square(3.0),
+            invisible_edges: [false; 4],
+        })],
+        attributes: Vec::new(),
+    });
+    document.add_block_reference(
+        "PANEL",
+        Point2::new(10.0, 0.0),
+        Vector2::new(2.0, 2.0),
+        std::f64::consts::FRAC_PI_2,
+        Vec::new(),
+        "FACADE",
+    );
+
+    let gltf = GltfExporter::new()
+        .with_options(GltfExportOptions {
+            y_up: false,
+            ..GltfExportOptions::default()
+        })
+        .to_gltf(&document);
+    assert_eq!(gltf["nodes"][0]["name"], "FACADE");
+    let position = gltf["meshes"][0]["primitives"][0]["attributes"]["POSITION"]
+        .as_u64()
+        .unwrap() as usize;
+    let positions = read_vec3(&gltf, position);
+    // 基点 (1,0) 移至原点，放大两倍并旋转 90° 后插入到 (10,0)，Z 不变
+    assert_eq!(positions[0], [10.0, -2.0, 3.0]);
+    assert_eq!(positions[1], [10.0, 0.0, 3.0]);
+    assert_eq!(positions[2], [8.0, 0.0, 3.0]);
+}
+
+#[test]
+fn glb_packs_json_and_binary_chunks() {
+    let mut document = Document::new();
+    document.add_face3d(square(0.0), [false; 4], "0");
+    let exporter = GltfExporter::new().with_options(GltfExportOptions {
+        binary: true,
+        ..GltfExportOptions::default()
+    });
+    let glb = exporter.to_glb(&document);
+    let word = |offset: usize| u32::from_le_bytes(glb[offset..offset + 4].try_into().unwrap());
+    assert_eq!(&glb[..4], b"glTF");
+    assert_eq!(word(4), 2);
+    assert_eq!(word(8) as usize, glb.len());
+    let json_length = word(12) as usize;
+    assert_eq!(json_length % 4, 0);
+    assert_eq!(&glb[16..20], b"JSON");
+    let gltf: Value = serde_json::from_slice(&glb[20..20 + json_length]).unwrap();
+    assert!(gltf["buffers"][0].get("uri").is_none());
+    // 两个三角形：6 个顶点的位置与法线
+    assert_eq!(gltf["buffers"][0]["byteLength"], 6 * 12 * 2);
+    let bin = 20 + json_length;
+    assert_eq!(word(bin) as usize, 6 * 12 * 2);
+    assert_eq!(&glb[bin + 4..bin + 8], b"BIN\0");
+    assert_eq!(bin + 8 + 6 * 12 * 2, glb.len());
+
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let target = dir.path().join("face.glb");
+    exporter.save(&document, &target).expect("保存 GLB 失败");
+    assert_eq!(std::fs::read(&target).unwrap(), glb);
+
+    let empty = GltfExporter::new().to_gltf(&Document::new());
+    assert_eq!(empty["scenes"][0]["nodes"], json!([]));
+    assert!(empty.get("buffers").is_none());
+}
+
+#[test]
+fn gltf_exporter_saves_mesh_fixtures() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+    for name in [
+        "face3d_basic",
+        "polyface_basic",
+        "mesh_grid_basic",
+        "mesh_wrap_basic",
+    ] {
+        let document = DxfFacade::new()
+            .load(&fixtures.join(format!("{name}.dxf")))
+            .unwrap_or_else(|err| panic!("读取 {name}.dxf 失败: {err}"));
+        let target = dir.path().join(format!("{name}.gltf"));
+        GltfExporter::new()
+            .save(&document, &target)
+            .unwrap_or_else(|err| panic!("导出 {name}.gltf 失败: {err}"));
+        let gltf: Value = serde_json::from_str(&std::fs::read_to_string(&target).unwrap())
+            .unwrap_or_else(|err| panic!("{name}: glTF 无效 {err}"));
+        let meshes = gltf["meshes"].as_array().expect("应包含网格");
+        assert!(!meshes.is_empty(), "{name}");
+        for accessor in gltf["accessors"].as_array().unwrap() {
+            assert_eq!(accessor["count"].as_u64().unwrap() % 3, 0, "{name}");
+        }
+    }
+}
@@ -4,8 +4,8 @@ use std::fs;
 use std::path::PathBuf;
 use zcad_core::document::{
     Attribute, AttributeDefinition, DimensionKind, Document, Entity, HatchEdge, HatchGradient,
-    HatchLoop, MLeaderContent, Polyline, PolylineVertex, RasterImageClip,
-    RasterImageDisplayOptions,
+    HatchLoop, HatchPatternLine, HatchStyle, MLeaderContent, ObjectColor, Polyline, PolylineVertex,
+    RasterImageClip, RasterImageDisplayOptions,
 };
 use zcad_core::geometry::{Point2, Point3, Vector2};
 
@@ -20,14 +20,27 @@ pub struct GoldenDocument {
     image_dictionary: Option<GoldenImageDictionary>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     raster_image_variables: Option<GoldenRasterVariables>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    wipeout_variables: Option<Value>,
     #[serde(default)]
     image_def_reactors: Vec<GoldenImageDefReactor>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    shape_files: Vec<Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    linetypes: Vec<Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    underlay_definitions: Vec<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    geodata: Option<Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 struct GoldenLayer {
     name: String,
     is_visible: bool,
+    color: String,
+    linetype: String,
+    lineweight: i16,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -144,7 +157,10 @@ impl GoldenDocument {
             .layers()
             .map(|layer| GoldenLayer {
                 name: layer.name.clone(),
-                is_visible: layer.is_visible,
+                is_visible: layer.is_displayed(),
+                color: object_color_to_string(layer.color),
+                linetype: layer.linetype.clone(),
+                lineweight: layer.lineweight.code(),
             })
             .collect();
         layers.sort_by(|a, b| a.name.cmp(&b.name));
@@ -237,6 +253,35 @@ impl GoldenDocument {
             .collect();
         image_def_reactors.sort_by(|a, b| a.handle.cmp(&b.handle));
 
+        let mut shape_files: Vec<_> = document.shape_files().collect();
+        shape_files.sort_by(|a, b| a.handle.cmp(&b.handle));
+        let shape_files = shape_files
+            .into_iter()
+            .map(|shape_file| json!({ "handle": shape_file.handle, "file": shape_file.file }))
+            .collect();
+
+        let mut linetypes: Vec<_> = document.linetypes().collect();
+        linetypes.sort_by(|a, b| a.name.cmp(&b.name));
+        let linetypes = linetypes
+            .into_iter()
+            .map(|linetype| serde_json::to_value(linetype).expect("序列化线型失败"))
+            .collect();
+
+        let mut underlay_definitions: Vec<_> = document.underlay_definitions().collect();
+        underlay_definitions.sort_by(|a, b| a.handle.cmp(&b.handle));
+        let underlay_definitions = underlay_definitions
+            .into_iter()
+            .map(|definition| serde_json::to_value(definition).expect("序列化底图定义失败"))
+            .collect();
+
+        let wipeout_variables = document
+            .wipeout_variables()
+            .map(|vars| serde_json::to_value(vars).expect("序列化区域覆盖变量失败"));
+
+        let geodata = document
+            .geodata()
+            .map(|geodata| serde_json::to_value(geodata).expect("序列化地理位置失败"));
+
         Self {
             layers,
             entities,
@@ -244,7 +289,12 @@ impl GoldenDocument {
             image_definitions,
             image_dictionary,
             raster_image_variables,
+            wipeout_variables,
             image_def_reactors,
+            shape_files,
+            linetypes,
+            underlay_definitions,
+            geodata,
         }
     }
 }
@@ -359,7 +409,19 @@ fn entity_payload(entity: &Entity) -> (String, String, Value) {
                 "pattern": hatch.pattern_name,
                 "is_solid": hatch.is_solid,
                 "gradient": hatch.gradient.as_ref().map(hatch_gradient_to_value),
-                "loops": hatch.loops.iter().map(hatch_loop_to_value).collect::<Vec<_>>()
+                "loops": hatch.loops.iter().map(hatch_loop_to_value).collect::<Vec<_>>(),
+                "pattern_lines": hatch
+                    .pattern_lines
+                    .iter()
+                    .map(hatch_pattern_line_to_value)
+                    .collect::<Vec<_>>(),
+                "style": hatch_style_to_string(hatch.style),
+                "is_associative": hatch.is_associative,
+                "seed_points": hatch
+                    .seed_points
+                    .iter()
+                    .map(|point| point_to_array(*point))
+                    .collect::<Vec<_>>()
             }),
         ),
         Entity::Dimension(dimension) => (
@@ -379,7 +441,17 @@ fn entity_payload(entity: &Entity) -> (String, String, Value) {
                 "measurement": dimension.measurement,
                 "rotation": dimension.rotation,
                 "text_rotation": dimension.text_rotation,
-                "oblique_angle": dimension.oblique_angle
+                "oblique_angle": dimension.oblique_angle,
+                "block_name": dimension.block_name,
+                "jog_point": dimension.jog_point.map(point_to_array),
+                "arc": dimension.arc.as_ref().map(|arc| json!({
+                    "start_angle": arc.start_angle,
+                    "end_angle": arc.end_angle,
+                    "is_partial": arc.is_partial,
+                    "has_leader": arc.has_leader,
+                    "leader_start": arc.leader_start.map(point_to_array),
+                    "leader_end": arc.leader_end.map(point_to_array)
+                }))
             }),
         ),
         Entity::Leader(leader) => (
@@ -477,6 +549,45 @@ fn entity_payload(entity: &Entity) -> (String, String, Value) {
                 "invisible_edges": face.invisible_edges,
             }),
         ),
+        Entity::Shape(shape) => (
+            "SHAPE".to_string(),
+            shape.layer.clone(),
+            json!({
+                "name": shape.name,
+                "insert": point_to_array(shape.insert),
+                "size": shape.size,
+                "rotation": shape.rotation,
+                "width_factor": shape.width_factor,
+                "oblique": shape.oblique,
+                "style_handle": shape.style_handle,
+            }),
+        ),
+        Entity::Underlay(underlay) => (
+            underlay.kind.entity_name().to_string(),
+            underlay.layer.clone(),
+            json!({
+                "definition_handle": underlay.definition_handle,
+                "insert": point_to_array(underlay.insert),
+                "scale": vector_to_array(underlay.scale),
+                "rotation": underlay.rotation,
+                "display_options": serde_json::to_value(underlay.display_options)
+                    .expect("序列化底图显示选项失败"),
+                "clip_boundary": underlay
+                    .clip_boundary
+                    .iter()
+                    .map(|point| point_to_array(*point))
+                    .collect::<Vec<_>>(),
+            }),
+        ),
+        Entity::RevisionCloud(cloud) => (
+            "REVCLOUD".to_string(),
+            cloud.layer.clone(),
+            json!({
+                "style": serde_json::to_value(cloud.style).expect("序列化云线样式失败"),
+                "arc_length": cloud.arc_length,
+                "polyline": polyline_to_value(&cloud.to_polyline()),
+            }),
+        ),
     }
 }
 
@@ -604,6 +715,32 @@ fn hatch_gradient_to_value(gradient: &HatchGradient) -> Value {
     })
 }
 
+fn hatch_pattern_line_to_value(line: &HatchPatternLine) -> Value {
+    json!({
+        "angle": line.angle,
+        "base_point": point_to_array(line.base_point),
+        "offset": vector_to_array(line.offset),
+        "dash_lengths": line.dash_lengths.clone()
+    })
+}
+
+fn object_color_to_string(color: ObjectColor) -> String {
+    match color {
+        ObjectColor::ByLayer => "by_layer".to_string(),
+        ObjectColor::ByBlock => "by_block".to_string(),
+        ObjectColor::Index(index) => format!("aci:{index}"),
+        ObjectColor::TrueColor(rgb) => format!("rgb:#{rgb:06X}"),
+    }
+}
+
+fn hatch_style_to_string(style: HatchStyle) -> &'static str {
+    match style {
+        HatchStyle::Normal => "normal",
+        HatchStyle::Outer => "outer",
+        HatchStyle::Ignore => "ignore",
+    }
+}
+
 fn dimension_kind_to_string(kind: DimensionKind) -> String {
     match kind {
         DimensionKind::Linear => "Linear".to_string(),
@@ -613,6 +750,8 @@ fn dimension_kind_to_string(kind: DimensionKind) -> String {
         DimensionKind::Radius => "Radius".to_string(),
         DimensionKind::Angular3Point => "Angular3Point".to_string(),
         DimensionKind::Ordinate => "Ordinate".to_string(),
+        DimensionKind::ArcLength => "ArcLength".to_string(),
+        DimensionKind::JoggedRadius => "JoggedRadius".to_string(),
         DimensionKind::Unknown(code) => format!("Unknown({code})"),
     }
 }
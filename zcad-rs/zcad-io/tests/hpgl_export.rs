@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use zcad_core::document::{Document, PolylineVertex};
+use zcad_core::geometry::Point2;
+use zcad_io::hpgl::{HpglExportOptions, HpglExporter};
+use zcad_io::{DocumentLoader, DocumentSaver, DxfFacade};
+
+#[test]
+fn hpgl_groups_layers_by_pen_in_plotter_units() {
+    let mut document = Document::new();
+    document.add_line(Point2::new(10.0, 10.0), Point2::new(20.0, 10.0), "CUT");
+    document.add_circle(Point2::new(15.0, 15.0), 5.0, "SCORE");
+    document.add_line(Point2::new(10.0, 20.0), Point2::new(20.0, 20.0), "CUT");
+    document.add_circle(Point2::new(15.0, 15.0), 1.0, "HIDDEN");
+    document.layer_mut("HIDDEN").unwrap().is_visible = false;
+
+    let hpgl = HpglExporter::new().to_hpgl(&document);
+    assert_eq!(
+        hpgl,
+        "IN;\nSP1;\nPU0,0;PD400,0;\nPU0,400;PD400,400;\nSP2;\nPU200,200;CI200;\nPU;SP0;\n"
+    );
+
+    let hpgl = HpglExporter::new()
+        .with_options(HpglExportOptions {
+            millimeters_per_unit: 0.5,
+            origin_at_extents: false,
+            layer_pens: HashMap::from([("CUT".to_string(), 3)]),
+            ..HpglExportOptions::default()
+        })
+        .to_hpgl(&document);
+    assert!(
+        hpgl.starts_with("IN;\nSP1;\nPU300,300;CI100;\nSP3;\nPU200,200;PD400,200;\n"),
+        "{hpgl}"
+    );
+}
+
+#[test]
+fn hpgl_uses_arc_commands_for_arcs_and_bulges() {
+    let mut document = Document::new();
+    document.add_arc(
+        Point2::new(0.0, 0.0),
+        10.0,
+        0.0,
+        std::f64::consts::FRAC_PI_2,
+        "0",
+    );
+    document.add_polyline_with_vertices(
+        vec![
+            PolylineVertex::new(Point2::new(0.0, 0.0)),
+            PolylineVertex::new(Point2::new(10.0, 0.0)),
+            PolylineVertex::with_bulge(Point2::new(20.0, 0.0), 1.0),
+            PolylineVertex::new(Point2::new(30.0, 0.0)),
+        ],
+        true,
+        "0",
+    );
+    let hpgl = HpglExporter::new()
+        .with_options(HpglExportOptions {
+            origin_at_extents: false,
+            ..HpglExportOptions::default()
+        })
+        .to_hpgl(&document);
+    assert!(hpgl.contains("PU400,0;PD;AA0,0,90;\n"), "{hpgl}");
+    // 直线段合并为一条折线，凸度 1 为逆时针半圆，闭合段回到起点
+    assert!(
+        hpgl.contains("PU0,0;PD400,0,800,0;PD;AA1000,0,180;PD0,0;\n"),
+        "{hpgl}"
+    );
+}
+
+#[test]
+fn hpgl_writes_labels_with_direction_and_size() {
+    let mut document = Document::new();
+    document.add_text(
+        Point2::new(0.0, 0.0),
+        "Ø A",
+        5.0,
+        std::f64::consts::FRAC_PI_2,
+        "0",
+    );
+    let hpgl = HpglExporter::new()
+        .with_options(HpglExportOptions {
+            origin_at_extents: false,
+            ..HpglExportOptions::default()
+        })
+        .to_hpgl(&document);
+    assert!(
+        hpgl.contains("PU0,0;DI0,1;SI0.3,0.5;LB? A\u{3}\n"),
+        "{hpgl:?}"
+    );
+}
+
+#[test]
+fn hpgl_exporter_saves_every_fixture() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+    for entry in std::fs::read_dir(&fixtures).expect("读取样例目录失败") {
+        let path = entry.expect("读取目录项失败").path();
+        if path.extension().is_none_or(|ext| ext != "dxf") {
+            continue;
+        }
+        let Ok(document) = DxfFacade::new().load(&path) else {
+            continue;
+        };
+        let name = path.file_stem().unwrap().to_string_lossy().to_string();
+        let target = dir.path().join(format!("{name}.plt"));
+        HpglExporter::new()
+            .save(&document, &target)
+            .unwrap_or_else(|err| panic!("导出 {name}.plt 失败: {err}"));
+        let hpgl = std::fs::read_to_string(&target).unwrap();
+        assert!(
+            hpgl.starts_with("IN;\n") && hpgl.ends_with("PU;SP0;\n"),
+            "{name}"
+        );
+        assert!(!hpgl.contains("NaN") && !hpgl.contains("inf"), "{name}");
+    }
+}
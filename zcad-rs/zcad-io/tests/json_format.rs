@@ -0,0 +1,161 @@
+use std::path::PathBuf;
+
+use zcad_io::{
+    DocumentLoader, DocumentSaver, DxfFacade, IoError, JsonFacade,
+    compare::compare_documents,
+    json::{JSON_FORMAT_NAME, JSON_FORMAT_VERSION},
+};
+
+fn load_fixture(name: &str) -> zcad_core::document::Document {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("tests/data/{name}.dxf"));
+    DxfFacade::new()
+        .load(&path)
+        .unwrap_or_else(|err| panic!("读取 {name}.dxf 失败: {err}"))
+}
+
+#[test]
+fn json_round_trip_preserves_document_and_entity_ids() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    for name in [
+        "basic_entities",
+        "block_hatch_gradient",
+        "mtext_formatting",
+        "text_alignment",
+        "image_basic",
+    ] {
+        let original = load_fixture(name);
+        let path = dir.path().join(format!("{name}.json"));
+        JsonFacade::new()
+            .with_pretty(true)
+            .save(&original, &path)
+            .unwrap_or_else(|err| panic!("保存 {name}.json 失败: {err}"));
+        let reloaded = JsonFacade::new()
+            .load(&path)
+            .unwrap_or_else(|err| panic!("读取 {name}.json 失败: {err}"));
+
+        if let Some(divergence) = compare_documents(&original, &reloaded) {
+            panic!("{name}: JSON 往返后出现差异 {divergence}");
+        }
+        let ids = |document: &zcad_core::document::Document| {
+            document.entities().map(|(id, _)| *id).collect::<Vec<_>>()
+        };
+        assert_eq!(ids(&original), ids(&reloaded), "{name}: 实体标识应保留");
+        // 反序列化后按编号查找依赖重建的索引
+        for (id, entity) in original.entities() {
+            let found = reloaded.entity(*id).expect("重新加载后应能按编号查找");
+            assert_eq!(found.kind_name(), entity.kind_name());
+        }
+    }
+}
+
+#[test]
+fn json_envelope_records_format_and_version() {
+    let json = JsonFacade::new()
+        .to_json(&load_fixture("basic_entities"))
+        .expect("序列化失败");
+    let value: serde_json::Value = serde_json::from_str(&json).expect("输出应为合法 JSON");
+    assert_eq!(value["format"], JSON_FORMAT_NAME);
+    assert_eq!(value["version"], JSON_FORMAT_VERSION);
+    assert!(value["document"].is_object());
+}
+
+#[test]
+fn json_loader_rejects_unknown_format_and_newer_version() {
+    let facade = JsonFacade::new();
+    let newer = format!(
+        r#"{{"format":"{JSON_FORMAT_NAME}","version":{},"document":{{}}}}"#,
+        JSON_FORMAT_VERSION + 1
+    );
+    assert!(matches!(
+        facade.from_json(&newer),
+        Err(IoError::UnsupportedFeature(_))
+    ));
+    assert!(matches!(
+        facade.from_json(r#"{"format":"other","version":1,"document":{}}"#),
+        Err(IoError::InvalidDocument(_))
+    ));
+}
+
+#[test]
+fn json_round_trip_keeps_typed_user_data() {
+    use zcad_core::document::{Document, UserValue};
+    use zcad_core::geometry::Point2;
+
+    let mut document = Document::new();
+    let line = document.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+    document.set_user_value(line, "count", 3).unwrap();
+    document.set_user_value(line, "ratio", 2.0).unwrap();
+    document
+        .set_user_value(
+            line,
+            "tags",
+            vec![UserValue::from("a"), UserValue::Bool(true)],
+        )
+        .unwrap();
+
+    let facade = JsonFacade::new();
+    let json = facade.to_json(&document).expect("序列化失败");
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        value["document"]["user_data"][line.get().to_string()]["count"],
+        3
+    );
+
+    let reloaded = facade.from_json(&json).expect("解析失败");
+    assert_eq!(reloaded.user_data(line), document.user_data(line));
+    assert_eq!(
+        reloaded.user_value(line, "ratio"),
+        Some(&UserValue::Real(2.0))
+    );
+}
+
+#[test]
+fn json_round_trip_keeps_provenance() {
+    use zcad_core::document::{Document, Editor};
+    use zcad_core::geometry::Point2;
+
+    let mut document = Document::new();
+    document.set_provenance_clock(|| 1_700_000_000_000);
+    document.enable_provenance(Editor::new("alice").with_tool("zcad-io tests"));
+    let line = document.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+
+    let facade = JsonFacade::new();
+    let reloaded = facade
+        .from_json(&facade.to_json(&document).expect("序列化失败"))
+        .expect("解析失败");
+    assert_eq!(reloaded.editor(), document.editor());
+    assert_eq!(
+        reloaded.document_provenance(),
+        document.document_provenance()
+    );
+    let provenance = reloaded
+        .entity_provenance(line)
+        .expect("实体来源信息应保留");
+    assert_eq!(provenance.created, Some(1_700_000_000_000));
+    assert_eq!(
+        provenance.modified_by.tool.as_deref(),
+        Some("zcad-io tests")
+    );
+}
+
+#[test]
+fn json_round_trip_keeps_journal() {
+    use zcad_core::document::Document;
+    use zcad_core::geometry::{Point2, Vector2};
+
+    let mut document = Document::new();
+    document.enable_journal();
+    let line = document.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+    document.commit_revision("draw line");
+    // 未定稿的登记随文档保存，重新打开后仍能提交
+    document.move_entities([line], Vector2::new(0.0, 2.0));
+
+    let facade = JsonFacade::new();
+    let mut reloaded = facade
+        .from_json(&facade.to_json(&document).expect("序列化失败"))
+        .expect("解析失败");
+    assert_eq!(reloaded.commit_revision("move line"), Some(2));
+    let journal = reloaded.journal().expect("日志应保留");
+    assert_eq!(journal.revisions()[0].description, "draw line");
+    assert_eq!(journal.summary_since(1).modified, vec![line]);
+}
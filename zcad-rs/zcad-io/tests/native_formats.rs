@@ -0,0 +1,229 @@
+use std::path::PathBuf;
+
+use zcad_io::{
+    DocumentFormat, DocumentLoader, DocumentSaver, DxfFacade, IoError, JsonFacade, SaveFormat,
+    ZcadzFacade,
+    compare::compare_documents,
+    detect_acadver, open_document, open_document_bytes, save_document,
+    writer::{DxfVersion, DxfWriteOptions},
+    zcadz::{ZCADZ_CONTAINER_VERSION, ZCADZ_MAGIC},
+};
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("tests/data/{name}.dxf"))
+}
+
+fn load_fixture(name: &str) -> zcad_core::document::Document {
+    DxfFacade::new()
+        .load(&fixture_path(name))
+        .unwrap_or_else(|err| panic!("读取 {name}.dxf 失败: {err}"))
+}
+
+#[test]
+fn zcadz_round_trip_preserves_document() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    for name in ["basic_entities", "block_hatch_gradient", "mtext_formatting"] {
+        let original = load_fixture(name);
+        let path = dir.path().join(format!("{name}.zcadz"));
+        ZcadzFacade::new()
+            .save(&original, &path)
+            .unwrap_or_else(|err| panic!("保存 {name}.zcadz 失败: {err}"));
+        let reloaded = ZcadzFacade::new()
+            .load(&path)
+            .unwrap_or_else(|err| panic!("读取 {name}.zcadz 失败: {err}"));
+        if let Some(divergence) = compare_documents(&original, &reloaded) {
+            panic!("{name}: .zcadz 往返后出现差异 {divergence}");
+        }
+    }
+}
+
+#[test]
+fn zcadz_is_smaller_than_json() {
+    let document = load_fixture("block_hatch_gradient");
+    let json = JsonFacade::new().to_json(&document).expect("序列化失败");
+    let bytes = ZcadzFacade::new().to_bytes(&document).expect("压缩失败");
+    assert!(bytes.starts_with(ZCADZ_MAGIC));
+    assert_eq!(bytes[ZCADZ_MAGIC.len()], ZCADZ_CONTAINER_VERSION);
+    assert!(
+        bytes.len() < json.len(),
+        "压缩后 {} 字节，JSON {} 字节",
+        bytes.len(),
+        json.len()
+    );
+}
+
+#[test]
+fn zcadz_rejects_bad_magic_and_newer_container() {
+    let facade = ZcadzFacade::new();
+    assert!(matches!(
+        facade.from_bytes(b"{\"format\":\"zcad-document\"}"),
+        Err(IoError::InvalidDocument(_))
+    ));
+
+    let mut bytes = facade
+        .to_bytes(&load_fixture("basic_entities"))
+        .expect("压缩失败");
+    bytes[ZCADZ_MAGIC.len()] = ZCADZ_CONTAINER_VERSION + 1;
+    assert!(matches!(
+        facade.from_bytes(&bytes),
+        Err(IoError::UnsupportedFeature(_))
+    ));
+
+    bytes[ZCADZ_MAGIC.len()] = ZCADZ_CONTAINER_VERSION;
+    bytes.truncate(ZCADZ_MAGIC.len() + 4);
+    assert!(matches!(
+        facade.from_bytes(&bytes),
+        Err(IoError::InvalidDocument(_))
+    ));
+}
+
+#[test]
+fn detect_format_from_content() {
+    assert_eq!(
+        DocumentFormat::detect(b"ZCADZ\x01rest"),
+        DocumentFormat::Zcadz
+    );
+    assert_eq!(
+        DocumentFormat::detect(b"  \n{\"format\":1}"),
+        DocumentFormat::Json
+    );
+    assert_eq!(
+        DocumentFormat::detect(b"\xEF\xBB\xBF{}"),
+        DocumentFormat::Json
+    );
+    assert_eq!(
+        DocumentFormat::detect(b"  0\nSECTION\n"),
+        DocumentFormat::Dxf
+    );
+    assert_eq!(DocumentFormat::detect(b""), DocumentFormat::Dxf);
+    assert_eq!(DocumentFormat::detect(b"AC1032\0\0\0"), DocumentFormat::Dwg);
+    assert_eq!(DocumentFormat::detect(b"AC1"), DocumentFormat::Dxf);
+}
+
+#[test]
+fn detect_acadver_reads_header_or_dwg_magic() {
+    let header = b"  0\nSECTION\n  2\nHEADER\n  9\n$ACADVER\n  1\nAC1027\n  0\nENDSEC\n";
+    assert_eq!(detect_acadver(header).as_deref(), Some("AC1027"));
+    assert_eq!(DxfVersion::from_acadver("AC1027"), Some(DxfVersion::R2013));
+    assert_eq!(DxfVersion::from_acadver("AC1014"), None);
+    assert_eq!(detect_acadver(b"AC1018\0\0").as_deref(), Some("AC1018"));
+    assert_eq!(detect_acadver(b"{\"format\":1}"), None);
+    assert_eq!(
+        detect_acadver(b"  0\nSECTION\n  2\nHEADER\n  0\nENDSEC\n  9\n$ACADVER\n  1\nAC1009\n"),
+        None
+    );
+
+    // 写出器输出的版本号能被识别
+    let document = load_fixture("basic_entities");
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let path = dir.path().join("r2010.dxf");
+    DxfFacade::new()
+        .with_write_options(DxfWriteOptions {
+            version: DxfVersion::R2010,
+            ..DxfWriteOptions::default()
+        })
+        .save(&document, &path)
+        .expect("保存 DXF 失败");
+    let bytes = std::fs::read(&path).unwrap();
+    assert_eq!(detect_acadver(&bytes).as_deref(), Some("AC1024"));
+}
+
+#[test]
+fn open_document_dispatches_by_content_not_extension() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let original = load_fixture("basic_entities");
+
+    // 扩展名刻意与内容不符，确保按魔数而非扩展名分派
+    let json_path = dir.path().join("drawing.dxf");
+    JsonFacade::new()
+        .save(&original, &json_path)
+        .expect("保存 JSON 失败");
+    let zcadz_path = dir.path().join("drawing.json");
+    ZcadzFacade::new()
+        .save(&original, &zcadz_path)
+        .expect("保存 .zcadz 失败");
+
+    for path in [fixture_path("basic_entities"), json_path, zcadz_path] {
+        let opened = open_document(&path)
+            .unwrap_or_else(|err| panic!("打开 {} 失败: {err}", path.display()));
+        if let Some(divergence) = compare_documents(&original, &opened) {
+            panic!("{}: 打开结果出现差异 {divergence}", path.display());
+        }
+    }
+
+    assert!(matches!(
+        open_document(&dir.path().join("missing.zcadz")),
+        Err(IoError::ReadError { .. })
+    ));
+
+    let dwg_path = dir.path().join("drawing.dwg");
+    std::fs::write(&dwg_path, b"AC1027\0\0\0\0").unwrap();
+    assert!(matches!(
+        open_document(&dwg_path),
+        Err(IoError::UnsupportedFeature(feature)) if feature.contains("AC1027")
+    ));
+}
+
+#[test]
+fn open_document_bytes_matches_file_loading() {
+    let original = load_fixture("basic_entities");
+    let dxf = std::fs::read(fixture_path("basic_entities")).unwrap();
+    let json = JsonFacade::new().to_json(&original).unwrap();
+    let zcadz = ZcadzFacade::new().to_bytes(&original).unwrap();
+
+    let mut with_bom = b"\xEF\xBB\xBF".to_vec();
+    with_bom.extend_from_slice(&dxf);
+    for (label, bytes) in [
+        ("dxf", dxf.clone()),
+        ("dxf+bom", with_bom),
+        ("json", json.into_bytes()),
+        ("zcadz", zcadz),
+    ] {
+        let opened = open_document_bytes(&bytes)
+            .unwrap_or_else(|err| panic!("{label}: 内存加载失败: {err}"));
+        if let Some(divergence) = compare_documents(&original, &opened) {
+            panic!("{label}: 内存加载结果出现差异 {divergence}");
+        }
+    }
+
+    assert!(matches!(
+        open_document_bytes(b"AC1027\0\0\0\0"),
+        Err(IoError::UnsupportedFeature(_))
+    ));
+    assert!(matches!(
+        open_document_bytes(b"  0\nSECTION\n\xFF\xFE"),
+        Err(IoError::InvalidDocument(_))
+    ));
+}
+
+#[test]
+fn save_document_picks_format_from_extension() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let original = load_fixture("basic_entities");
+
+    for format in SaveFormat::ALL {
+        let path = dir.path().join(format!("drawing.{}", format.extension()));
+        save_document(&original, &path)
+            .unwrap_or_else(|err| panic!("保存 {} 失败: {err}", path.display()));
+        assert!(std::fs::metadata(&path).unwrap().len() > 0);
+        assert_eq!(SaveFormat::from_path(&path), Some(format));
+    }
+    assert_eq!(SaveFormat::from_extension("HPGL"), Some(SaveFormat::Hpgl));
+    assert_eq!(SaveFormat::from_extension("DXF"), Some(SaveFormat::Dxf));
+
+    // 可读回的格式与原文档一致
+    for extension in ["dxf", "json", "zcadz"] {
+        let path = dir.path().join(format!("drawing.{extension}"));
+        let reopened = open_document(&path).unwrap();
+        if let Some(divergence) = compare_documents(&original, &reopened) {
+            panic!("{extension}: 保存后读回出现差异 {divergence}");
+        }
+    }
+    let glb = std::fs::read(dir.path().join("drawing.glb")).unwrap();
+    assert!(glb.starts_with(b"glTF"));
+
+    assert!(matches!(
+        save_document(&original, &dir.path().join("drawing.xyz")),
+        Err(IoError::UnsupportedFeature(_))
+    ));
+}
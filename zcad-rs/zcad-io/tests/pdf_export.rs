@@ -0,0 +1,176 @@
+use std::path::PathBuf;
+
+use zcad_core::document::{Document, EntityProperties, LineWeight, ObjectColor};
+use zcad_core::geometry::Point2;
+use zcad_io::pdf::{PaperSize, PdfExporter, PdfPlotOptions, PlotArea, PlotScale};
+use zcad_io::{DocumentLoader, DocumentSaver, DxfFacade};
+
+fn load_fixture(name: &str) -> Document {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("tests/data/{name}.dxf"));
+    DxfFacade::new()
+        .load(&path)
+        .unwrap_or_else(|err| panic!("读取 {name}.dxf 失败: {err}"))
+}
+
+fn uncompressed(options: PdfPlotOptions) -> PdfExporter {
+    PdfExporter::new().with_options(PdfPlotOptions {
+        compress: false,
+        ..options
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> usize {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .unwrap_or_else(|| panic!("缺少 {:?}", String::from_utf8_lossy(needle)))
+}
+
+/// 取出唯一页面的内容流（原始字节）。
+fn raw_stream(pdf: &[u8]) -> &[u8] {
+    let start = find(pdf, b"stream\n") + b"stream\n".len();
+    let end = find(pdf, b"\nendstream");
+    &pdf[start..end]
+}
+
+fn content_stream(pdf: &[u8]) -> String {
+    String::from_utf8(raw_stream(pdf).to_vec()).expect("未压缩的内容流应为文本")
+}
+
+/// 校验交叉引用表中每个偏移都指向对应的对象头。
+fn assert_valid_xref(pdf: &[u8]) {
+    assert!(pdf.starts_with(b"%PDF-1.4\n"));
+    assert!(pdf.ends_with(b"%%EOF\n"));
+    let tail = String::from_utf8_lossy(&pdf[pdf.len() - 32..]).to_string();
+    let xref: usize = tail
+        .split("startxref\n")
+        .nth(1)
+        .and_then(|rest| rest.lines().next())
+        .and_then(|line| line.parse().ok())
+        .expect("缺少 startxref");
+    let table = String::from_utf8(pdf[xref..].to_vec()).expect("交叉引用表应为文本");
+    assert!(table.starts_with("xref\n0 6\n"), "{table}");
+    for (index, line) in table.lines().skip(3).take(5).enumerate() {
+        let offset: usize = line[..10].parse().unwrap();
+        let header = format!("{} 0 obj", index + 1);
+        assert!(
+            pdf[offset..].starts_with(header.as_bytes()),
+            "对象 {} 的偏移 {offset} 错误",
+            index + 1
+        );
+    }
+}
+
+#[test]
+fn pdf_plots_at_ratio_scale_centered_on_paper() {
+    let mut document = Document::new();
+    document.add_line(Point2::new(0.0, 0.0), Point2::new(1000.0, 0.0), "0");
+    let pdf = uncompressed(PdfPlotOptions {
+        paper: PaperSize::A3.landscape(),
+        scale: PlotScale::Ratio {
+            paper_mm: 1.0,
+            drawing_units: 10.0,
+        },
+        ..PdfPlotOptions::default()
+    })
+    .to_pdf(&document);
+    assert_valid_xref(&pdf);
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.contains("/MediaBox [0 0 1190.551 841.89]"), "{text}");
+
+    // 1:10 下 1000 单位为 100 mm ≈ 283.465 pt，居中于 420 mm 宽的图纸
+    let content = content_stream(&pdf);
+    assert!(
+        content.contains("453.543 420.945 m\n737.008 420.945 l\nS"),
+        "{content}"
+    );
+}
+
+#[test]
+fn pdf_honours_lineweights_colors_and_layer_visibility() {
+    let mut document = Document::new();
+    let heavy = document.add_line(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), "WALLS");
+    document.set_entity_properties(
+        heavy,
+        EntityProperties {
+            color: ObjectColor::Index(1),
+            lineweight: LineWeight::Hundredths(50),
+            ..EntityProperties::default()
+        },
+    );
+    document.add_circle(Point2::new(5.0, 5.0), 5.0, "HIDDEN");
+    document.layer_mut("HIDDEN").unwrap().is_visible = false;
+    document.add_text(Point2::new(0.0, 10.0), "Ø (A)", 2.5, 0.0, "NOTES");
+
+    let content = content_stream(&uncompressed(PdfPlotOptions::default()).to_pdf(&document));
+    assert!(content.contains("1 0 0 RG\n1.417 w\n"), "{content}");
+    assert!(
+        !content.contains(" c\n"),
+        "隐藏图层中的圆不应输出: {content}"
+    );
+    assert!(content.contains(r"(\330 \(A\)) Tj"), "{content}");
+
+    let content = content_stream(
+        &uncompressed(PdfPlotOptions {
+            plot_lineweights: false,
+            monochrome: true,
+            ..PdfPlotOptions::default()
+        })
+        .to_pdf(&document),
+    );
+    assert!(content.contains("0 0 0 RG\n0.709 w\n"), "{content}");
+}
+
+#[test]
+fn pdf_window_plot_clips_and_fills_hatches() {
+    let document = load_fixture("hatch_gradient");
+    let pdf = uncompressed(PdfPlotOptions {
+        area: PlotArea::Window {
+            min: Point2::new(0.0, 0.0),
+            max: Point2::new(5.0, 5.0),
+        },
+        ..PdfPlotOptions::default()
+    })
+    .to_pdf(&document);
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(
+        text.contains("/Shading << /Sh1 << /ShadingType 2"),
+        "{text}"
+    );
+    assert!(content_stream(&pdf).contains("W* n /Sh1 sh"));
+
+    let content = content_stream(
+        &uncompressed(PdfPlotOptions::default()).to_pdf(&load_fixture("hatch_pattern")),
+    );
+    assert!(content.contains("W* n\n"), "{content}");
+    assert!(content.contains("] 0"), "虚线图案应设置线型: {content}");
+}
+
+#[test]
+fn pdf_exporter_saves_every_fixture() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+    for entry in std::fs::read_dir(&fixtures).expect("读取样例目录失败") {
+        let path = entry.expect("读取目录项失败").path();
+        if path.extension().is_none_or(|ext| ext != "dxf") {
+            continue;
+        }
+        let Ok(document) = DxfFacade::new().load(&path) else {
+            continue;
+        };
+        let name = path.file_stem().unwrap().to_string_lossy().to_string();
+        let target = dir.path().join(format!("{name}.pdf"));
+        PdfExporter::new()
+            .save(&document, &target)
+            .unwrap_or_else(|err| panic!("导出 {name}.pdf 失败: {err}"));
+        let pdf = std::fs::read(&target).unwrap();
+        assert_valid_xref(&pdf);
+        let content = miniz_oxide::inflate::decompress_to_vec_zlib(raw_stream(&pdf))
+            .unwrap_or_else(|err| panic!("{name}: 内容流解压失败 {err:?}"));
+        let content = String::from_utf8(content).unwrap();
+        assert!(
+            !content.contains("NaN") && !content.contains("inf"),
+            "{name}"
+        );
+    }
+}
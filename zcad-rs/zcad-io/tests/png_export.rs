@@ -0,0 +1,134 @@
+use std::path::PathBuf;
+
+use zcad_core::document::{Document, EntityProperties, ObjectColor};
+use zcad_core::geometry::Point2;
+use zcad_io::pdf::PlotArea;
+use zcad_io::raster::{PngExporter, RasterExportOptions};
+use zcad_io::{DocumentLoader, DocumentSaver, DxfFacade};
+
+fn load_fixture(name: &str) -> Document {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("tests/data/{name}.dxf"));
+    DxfFacade::new()
+        .load(&path)
+        .unwrap_or_else(|err| panic!("读取 {name}.dxf 失败: {err}"))
+}
+
+#[test]
+fn png_renders_lines_over_background_with_antialiasing() {
+    let mut document = Document::new();
+    document.add_line(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), "0");
+    let marker = document.add_line(Point2::new(0.0, 10.0), Point2::new(0.0, 5.0), "0");
+    document.set_entity_properties(
+        marker,
+        EntityProperties {
+            color: ObjectColor::Index(1),
+            ..EntityProperties::default()
+        },
+    );
+    let options = RasterExportOptions {
+        width: 100,
+        height: 100,
+        margin: 10,
+        background: [10, 20, 30, 255],
+        ..RasterExportOptions::default()
+    };
+
+    let image = PngExporter::new()
+        .with_options(options.clone())
+        .render(&document);
+    assert_eq!((image.width, image.height), (100, 100));
+    assert_eq!(image.pixel(0, 0), Some([10, 20, 30, 255]));
+    assert_eq!(image.pixel(100, 0), None);
+    // 深色背景上 7 号色取白色；1 像素线落在两行像素之间，抗锯齿后为半色调
+    let [red, green, blue, _] = image.pixel(50, 90).unwrap();
+    assert!(red > 10 && red < 255, "{red}");
+    assert!(green > 20 && blue > 30);
+    let [red, green, _, _] = image.pixel(10, 20).unwrap();
+    assert!(red > green + 50, "红色线条: {red} {green}");
+
+    let aliased = PngExporter::new()
+        .with_options(RasterExportOptions {
+            anti_alias: false,
+            ..options
+        })
+        .render(&document);
+    assert!(
+        (89..=90).any(|y| aliased.pixel(50, y) == Some([255, 255, 255, 255])),
+        "关闭抗锯齿后线条应为纯色"
+    );
+}
+
+#[test]
+fn png_fills_solid_hatch_on_transparent_background() {
+    let document = load_fixture("hatch_simple");
+    let exporter = PngExporter::new().with_options(RasterExportOptions {
+        width: 64,
+        height: 64,
+        margin: 0,
+        background: [0, 0, 0, 0],
+        ..RasterExportOptions::default()
+    });
+    let image = exporter.render(&document);
+    assert_eq!(image.pixel(0, 0).unwrap()[3], 0, "背景应透明");
+    assert_eq!(image.pixel(32, 32), Some([0, 0, 0, 255]));
+
+    let png = exporter.to_png(&document).unwrap();
+    let decoder = png::Decoder::new(std::io::Cursor::new(png));
+    let mut reader = decoder.read_info().expect("PNG 文件头无效");
+    let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+    let info = reader.next_frame(&mut pixels).expect("PNG 数据无效");
+    assert_eq!((info.width, info.height), (64, 64));
+    assert_eq!(info.color_type, png::ColorType::Rgba);
+    assert_eq!(pixels, image.pixels);
+}
+
+#[test]
+fn png_window_export_clips_outside_objects() {
+    let document = load_fixture("hatch_simple");
+    let image = PngExporter::new()
+        .with_options(RasterExportOptions {
+            width: 120,
+            height: 80,
+            margin: 0,
+            area: PlotArea::Window {
+                min: Point2::new(0.0, 0.0),
+                max: Point2::new(3.0, 4.0),
+            },
+            ..RasterExportOptions::default()
+        })
+        .render(&document);
+    // 窗口 3×4 缩放为 60×80 像素并水平居中
+    assert_eq!(image.pixel(60, 40), Some([0, 0, 0, 255]));
+    assert_eq!(image.pixel(100, 40), Some([255, 255, 255, 255]));
+    assert_eq!(image.pixel(10, 40), Some([255, 255, 255, 255]));
+}
+
+#[test]
+fn png_exporter_saves_thumbnails_for_every_fixture() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+    let exporter = PngExporter::new().with_options(RasterExportOptions::thumbnail(64));
+    for entry in std::fs::read_dir(&fixtures).expect("读取样例目录失败") {
+        let path = entry.expect("读取目录项失败").path();
+        if path.extension().is_none_or(|ext| ext != "dxf") {
+            continue;
+        }
+        let Ok(document) = DxfFacade::new().load(&path) else {
+            continue;
+        };
+        let name = path.file_stem().unwrap().to_string_lossy().to_string();
+        let target = dir.path().join(format!("{name}.png"));
+        exporter
+            .save(&document, &target)
+            .unwrap_or_else(|err| panic!("导出 {name}.png 失败: {err}"));
+        let file = std::fs::File::open(&target).unwrap();
+        let reader = png::Decoder::new(std::io::BufReader::new(file))
+            .read_info()
+            .unwrap_or_else(|err| panic!("{name}: PNG 无效 {err}"));
+        assert_eq!(
+            (reader.info().width, reader.info().height),
+            (64, 64),
+            "{name}"
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use std::path::PathBuf;
+
+use zcad_core::document::{Document, EntityProperties, ObjectColor, PolylineVertex};
+use zcad_core::geometry::Point2;
+use zcad_io::pdf::PlotArea;
+use zcad_io::svg::{SvgExportOptions, SvgExporter};
+use zcad_io::{DocumentLoader, DocumentSaver, DxfFacade};
+
+fn load_fixture(name: &str) -> Document {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("tests/data/{name}.dxf"));
+    DxfFacade::new()
+        .load(&path)
+        .unwrap_or_else(|err| panic!("读取 {name}.dxf 失败: {err}"))
+}
+
+#[test]
+fn svg_groups_layers_and_styles_overridden_colors() {
+    let mut document = Document::new();
+    document.add_line(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), "WALLS");
+    let red = document.add_line(Point2::new(0.0, 5.0), Point2::new(10.0, 5.0), "WALLS");
+    document.set_entity_properties(
+        red,
+        EntityProperties {
+            color: ObjectColor::Index(1),
+            ..EntityProperties::default()
+        },
+    );
+    document.add_circle(Point2::new(5.0, 5.0), 2.0, "HIDDEN");
+    document.layer_mut("HIDDEN").unwrap().is_visible = false;
+    document.layer_mut("WALLS").unwrap().color = ObjectColor::Index(5);
+    document.add_text(Point2::new(0.0, 10.0), "A<B", 2.5, 0.0, "NOTES");
+
+    let svg = SvgExporter::new().to_svg(&document);
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(
+        svg.contains(r##"<g id="layer-WALLS" data-layer="WALLS" fill="none" stroke="#0000ff""##)
+    );
+    assert!(svg.contains(r##"stroke="#ff0000"/>"##), "{svg}");
+    assert!(!svg.contains("HIDDEN"), "隐藏图层不应输出");
+    assert!(svg.contains(">A&lt;B</text>"));
+    // 7 号色在浅色背景下按黑色输出
+    assert!(svg.contains(r##"data-layer="NOTES" fill="none" stroke="#000000""##));
+}
+
+#[test]
+fn svg_keeps_arcs_and_bulges_with_flipped_y() {
+    let mut document = Document::new();
+    document.add_polyline_with_vertices(
+        vec![
+            PolylineVertex::with_bulge(Point2::new(0.0, 0.0), 1.0),
+            PolylineVertex::new(Point2::new(10.0, 0.0)),
+        ],
+        false,
+        "0",
+    );
+    let svg = SvgExporter::new()
+        .with_options(SvgExportOptions {
+            margin: 0.0,
+            ..SvgExportOptions::default()
+        })
+        .to_svg(&document);
+    // 半圆向下凸出 5，范围 y ∈ [-5, 0]，无留白时原点位于 (0, 0) 的左上角
+    assert!(svg.contains(r#"viewBox="0 0 10 5""#), "{svg}");
+    assert!(svg.contains(r#"d="M0 0 A5 5 0 0 0 10 0""#), "{svg}");
+}
+
+#[test]
+fn svg_window_area_crops_view_and_skips_outside_entities() {
+    let mut document = Document::new();
+    document.add_line(Point2::new(0.0, 0.0), Point2::new(10.0, 10.0), "0");
+    document.add_line(Point2::new(100.0, 100.0), Point2::new(110.0, 100.0), "FAR");
+    let svg = SvgExporter::new()
+        .with_options(SvgExportOptions {
+            area: PlotArea::Window {
+                min: Point2::new(8.0, 5.0),
+                max: Point2::new(2.0, 1.0),
+            },
+            ..SvgExportOptions::default()
+        })
+        .to_svg(&document);
+    // 窗口角点顺序无关，不加留白
+    assert!(svg.contains(r#"viewBox="0 0 6 4""#), "{svg}");
+    assert!(!svg.contains("FAR"), "{svg}");
+}
+
+#[test]
+fn svg_exports_hatches_and_wipeouts_as_paths() {
+    let svg = SvgExporter::new().to_svg(&load_fixture("hatch_simple"));
+    assert!(
+        svg.contains(r#"fill-rule="evenodd" stroke="none""#),
+        "{svg}"
+    );
+
+    let svg = SvgExporter::new().to_svg(&load_fixture("hatch_gradient"));
+    assert!(svg.contains("<linearGradient id=\"gradient-1\""), "{svg}");
+    assert!(svg.contains(r#"fill="url(#gradient-1)""#));
+
+    let svg = SvgExporter::new().to_svg(&load_fixture("hatch_pattern"));
+    assert!(svg.contains("<clipPath id=\"hatch-clip-1\">"), "{svg}");
+    assert!(svg.contains(r#"clip-path="url(#hatch-clip-1)""#));
+
+    let svg = SvgExporter::new()
+        .with_options(SvgExportOptions {
+            background: Some(0x202020),
+            ..SvgExportOptions::default()
+        })
+        .to_svg(&load_fixture("wipeout_clip"));
+    assert!(svg.contains(r##"<rect width="100%" height="100%" fill="#202020"/>"##));
+    assert!(
+        svg.contains(r##"fill="#202020""##),
+        "区域覆盖应以背景色遮挡"
+    );
+}
+
+#[test]
+fn svg_exporter_saves_every_fixture() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+    for entry in std::fs::read_dir(&fixtures).expect("读取样例目录失败") {
+        let path = entry.expect("读取目录项失败").path();
+        if path.extension().is_none_or(|ext| ext != "dxf") {
+            continue;
+        }
+        let Ok(document) = DxfFacade::new().load(&path) else {
+            continue;
+        };
+        let name = path.file_stem().unwrap().to_string_lossy().to_string();
+        let target = dir.path().join(format!("{name}.svg"));
+        SvgExporter::new()
+            .save(&document, &target)
+            .unwrap_or_else(|err| panic!("导出 {name}.svg 失败: {err}"));
+        let svg = std::fs::read_to_string(&target).unwrap();
+        assert!(svg.trim_end().ends_with("</svg>"), "{name}");
+        assert!(
+            !svg.contains("NaN") && !svg.contains("inf"),
+            "{name}: {svg}"
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use std::path::PathBuf;
+
+use zcad_core::document::{Document, Entity, ObjectColor};
+use zcad_io::svg_import::{SvgFacade, SvgImportOptions};
+use zcad_io::{DocumentLoader, DocumentSaver, DxfFacade, IoError};
+
+fn entities(document: &Document) -> Vec<&Entity> {
+    document.entities().map(|(_, entity)| entity).collect()
+}
+
+fn color(document: &Document, index: usize) -> ObjectColor {
+    let (id, _) = document.entities().nth(index).unwrap();
+    document
+        .entity_properties(*id)
+        .map_or(ObjectColor::ByLayer, |properties| properties.color)
+}
+
+fn assert_point(actual: (f64, f64), expected: (f64, f64)) {
+    assert!(
+        (actual.0 - expected.0).abs() < 1e-9 && (actual.1 - expected.1).abs() < 1e-9,
+        "{actual:?} != {expected:?}"
+    );
+}
+
+#[test]
+fn svg_import_maps_shapes_layers_and_millimeter_units() {
+    let svg = r##"<svg xmlns="http://www.w3.org/2000/svg"
+        xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape"
+        width="100mm" height="50mm" viewBox="0 0 200 100">
+      <g inkscape:groupmode="layer" inkscape:label="Outline" style="stroke:#ff0000;fill:none">
+        <line x1="0" y1="0" x2="200" y2="0"/>
+        <rect x="10" y="10" width="20" height="10"/>
+      </g>
+      <g inkscape:groupmode="layer" inkscape:label="Hidden" style="display:none">
+        <circle cx="100" cy="50" r="10"/>
+      </g>
+      <defs><circle id="unused" r="99"/></defs>
+      <circle cx="100" cy="50" r="20" stroke="blue"/>
+      <ellipse cx="50" cy="50" rx="20" ry="10" transform="rotate(90 50 50)"/>
+    </svg>"##;
+    let document = SvgFacade::new().from_svg(svg).expect("解析 SVG 失败");
+    let list = entities(&document);
+    assert_eq!(list.len(), 5);
+
+    // viewBox 200 个单位对应 100 mm，Y 轴向上且左下角为原点
+    let Entity::Line(line) = list[0] else {
+        panic!("应为直线: {:?}", list[0]);
+    };
+    assert_eq!(line.layer, "Outline");
+    assert_point((line.start.x(), line.start.y()), (0.0, 50.0));
+    assert_point((line.end.x(), line.end.y()), (100.0, 50.0));
+    assert_eq!(color(&document, 0), ObjectColor::TrueColor(0xFF0000));
+
+    let Entity::Polyline(rect) = list[1] else {
+        panic!("应为多段线: {:?}", list[1]);
+    };
+    assert!(rect.is_closed);
+    let corners: Vec<_> = rect
+        .vertices
+        .iter()
+        .map(|vertex| (vertex.position.x(), vertex.position.y()))
+        .collect();
+    assert_eq!(
+        corners,
+        [(5.0, 45.0), (15.0, 45.0), (15.0, 40.0), (5.0, 40.0)]
+    );
+
+    let hidden = document.layer("Hidden").expect("隐藏图层应保留");
+    assert!(!hidden.is_visible);
+    assert!(matches!(list[2], Entity::Circle(circle) if circle.layer == "Hidden"));
+
+    let Entity::Circle(circle) = list[3] else {
+        panic!("应为圆: {:?}", list[3]);
+    };
+    assert_eq!(circle.layer, "0");
+    assert_point((circle.center.x(), circle.center.y()), (50.0, 25.0));
+    assert!((circle.radius - 10.0).abs() < 1e-9);
+    assert_eq!(color(&document, 3), ObjectColor::TrueColor(0x0000FF));
+
+    let Entity::Ellipse(ellipse) = list[4] else {
+        panic!("应为椭圆: {:?}", list[4]);
+    };
+    assert_point(
+        (ellipse.major_axis.x(), ellipse.major_axis.y()),
+        (0.0, -10.0),
+    );
+    assert!((ellipse.ratio - 0.5).abs() < 1e-12);
+    // 无描边时取填充色，纯黑映射为 7 号色
+    assert_eq!(color(&document, 4), ObjectColor::Index(7));
+}
+
+#[test]
+fn svg_import_parses_path_commands() {
+    let svg = r#"<svg xmlns="http://www.w3.org/2000/svg">
+      <path d="M10 10 h 20 v 20 H 10 Z m40 0l10-5"/>
+      <path d="M0 0 A10 10 0 0 1 20 0"/>
+      <path d="M0,0C0,10 10,10 10,0S20-10 20,0"/>
+      <path d="M0 0 A20 10 30 1 0 30 5"/>
+    </svg>"#;
+    let document = SvgFacade::new().from_svg(svg).expect("解析 SVG 失败");
+    let list = entities(&document);
+    assert_eq!(list.len(), 5);
+
+    // 没有 viewBox 与高度时只翻转 Y 轴
+    let Entity::Polyline(square) = list[0] else {
+        panic!("应为多段线: {:?}", list[0]);
+    };
+    assert!(square.is_closed);
+    assert_eq!(square.vertices.len(), 4);
+    assert_point(
+        (
+            square.vertices[2].position.x(),
+            square.vertices[2].position.y(),
+        ),
+        (30.0, -30.0),
+    );
+
+    // Z 之后的相对 m 以子路径起点为基准
+    let Entity::Line(line) = list[1] else {
+        panic!("应为直线: {:?}", list[1]);
+    };
+    assert_point((line.start.x(), line.start.y()), (50.0, -10.0));
+    assert_point((line.end.x(), line.end.y()), (60.0, -5.0));
+
+    // SVG 中顺时针向上凸起的半圆，翻转后为顺时针凸度 -1
+    let Entity::Polyline(arc) = list[2] else {
+        panic!("应为多段线: {:?}", list[2]);
+    };
+    assert_eq!(arc.vertices.len(), 2);
+    assert!(
+        (arc.vertices[0].bulge + 1.0).abs() < 1e-9,
+        "{:?}",
+        arc.vertices
+    );
+
+    // 每段三次贝塞尔取 16 段，S 的反射控制点保持曲线连续
+    let Entity::Polyline(curve) = list[3] else {
+        panic!("应为多段线: {:?}", list[3]);
+    };
+    assert_eq!(curve.vertices.len(), 33);
+    assert_point(
+        (
+            curve.vertices[8].position.x(),
+            curve.vertices[8].position.y(),
+        ),
+        (5.0, -7.5),
+    );
+    assert_point(
+        (
+            curve.vertices[24].position.x(),
+            curve.vertices[24].position.y(),
+        ),
+        (15.0, 7.5),
+    );
+
+    // 椭圆弧离散后终点准确
+    let Entity::Polyline(elliptical) = list[4] else {
+        panic!("应为多段线: {:?}", list[4]);
+    };
+    assert!(elliptical.vertices.iter().all(|vertex| vertex.bulge == 0.0));
+    let last = elliptical.vertices.last().unwrap().position;
+    assert_point((last.x(), last.y()), (30.0, -5.0));
+}
+
+#[test]
+fn svg_import_handles_text_use_transforms_and_fills() {
+    let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" viewBox="0 0 100 100">
+      <defs>
+        <path id="tri" d="M0 0 L10 0 L0 10 Z" fill="rgb(0, 128, 255)" stroke="none"/>
+      </defs>
+      <g id="Logo" transform="translate(10 20) scale(2)">
+        <use xlink:href="#tri" x="5" y="5"/>
+        <text x="20" y="10" font-size="4" text-anchor="middle">Z<tspan>CAD</tspan></text>
+      </g>
+      <g id="Skewed" transform="skewX(30)">
+        <circle cx="0" cy="0" r="5"/>
+      </g>
+    </svg>"##;
+    let document = SvgFacade::new()
+        .with_options(SvgImportOptions {
+            import_fills: true,
+            ..SvgImportOptions::default()
+        })
+        .from_svg(svg)
+        .expect("解析 SVG 失败");
+    let list = entities(&document);
+    assert_eq!(list.len(), 5);
+
+    let Entity::Polyline(triangle) = list[0] else {
+        panic!("应为多段线: {:?}", list[0]);
+    };
+    assert_eq!(triangle.layer, "Logo");
+    assert_eq!(triangle.vertices.len(), 3);
+    assert_point(
+        (
+            triangle.vertices[0].position.x(),
+            triangle.vertices[0].position.y(),
+        ),
+        (20.0, 70.0),
+    );
+    assert_eq!(color(&document, 0), ObjectColor::TrueColor(0x0080FF));
+
+    let Entity::Hatch(hatch) = list[1] else {
+        panic!("应为填充: {:?}", list[1]);
+    };
+    assert!(hatch.is_solid);
+    assert_eq!(hatch.pattern_name, "SOLID");
+    assert_eq!(hatch.loops.len(), 1);
+    assert_eq!(hatch.loops[0].edges.len(), 3);
+    assert_eq!(color(&document, 1), ObjectColor::TrueColor(0x0080FF));
+
+    let Entity::Text(text) = list[2] else {
+        panic!("应为文字: {:?}", list[2]);
+    };
+    assert_eq!(text.content, "ZCAD");
+    assert!((text.height - 8.0).abs() < 1e-9);
+    assert_eq!(text.horizontal_align, 1);
+    let alignment = text.alignment.expect("居中文字应有对齐点");
+    assert_point((alignment.x(), alignment.y()), (50.0, 60.0));
+
+    // 非相似变换下的圆离散为闭合多段线
+    let Entity::Polyline(skewed) = list[3] else {
+        panic!("应为多段线: {:?}", list[3]);
+    };
+    assert_eq!(skewed.layer, "Skewed");
+    assert!(skewed.is_closed);
+    assert_eq!(skewed.vertices.len(), 64);
+    assert!(matches!(list[4], Entity::Hatch(_)));
+}
+
+#[test]
+fn svg_import_rejects_invalid_documents() {
+    let facade = SvgFacade::new();
+    assert!(matches!(
+        facade.from_svg("<svg><line></svg>"),
+        Err(IoError::InvalidDocument(_))
+    ));
+    assert!(matches!(
+        facade.from_svg("<html/>"),
+        Err(IoError::InvalidDocument(_))
+    ));
+}
+
+#[test]
+fn svg_facade_round_trips_exported_fixtures() {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/block_insert.dxf");
+    let document = DxfFacade::new().load(&path).expect("读取样例失败");
+    let target = dir.path().join("block_insert.svg");
+    let facade = SvgFacade::new();
+    facade.save(&document, &target).expect("导出 SVG 失败");
+
+    let imported = facade.load(&target).expect("读取 SVG 失败");
+    assert!(imported.entities().next().is_some());
+    let line = imported
+        .entities()
+        .find_map(|(_, entity)| match entity {
+            Entity::Line(line) => Some(line),
+            _ => None,
+        })
+        .expect("导出的直线应能读回");
+    assert!(document.layer(&line.layer).is_some(), "{}", line.layer);
+}
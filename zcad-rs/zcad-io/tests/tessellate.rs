@@ -0,0 +1,138 @@
+use std::path::PathBuf;
+
+use zcad_core::document::{Document, Entity, EntityProperties, Layer, ObjectColor};
+use zcad_core::geometry::Point2;
+use zcad_core::tessellate::TessellateOptions;
+use zcad_io::open_document_bytes;
+use zcad_io::tessellate::{Tessellator, TessellatorOptions};
+
+fn load_fixture(name: &str) -> Document {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("tests/data/{name}.dxf"));
+    let bytes = std::fs::read(&path).expect("读取样例失败");
+    open_document_bytes(&bytes).unwrap_or_else(|err| panic!("解析 {name}.dxf 失败: {err}"))
+}
+
+#[test]
+fn tessellates_curves_with_requested_segments() {
+    let mut document = Document::new();
+    let line = document.add_line(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), "0");
+    let circle = document.add_circle(Point2::new(0.0, 0.0), 2.0, "0");
+    document.add_arc(
+        Point2::new(0.0, 0.0),
+        1.0,
+        0.0,
+        std::f64::consts::FRAC_PI_2,
+        "0",
+    );
+
+    let paths = Tessellator::new()
+        .with_options(TessellatorOptions {
+            tessellation: TessellateOptions::per_turn(16),
+            ..TessellatorOptions::default()
+        })
+        .tessellate(&document);
+    assert_eq!(paths.len(), 3);
+
+    assert_eq!(paths[0].source, line);
+    assert_eq!(paths[0].points.len(), 2);
+    assert!(!paths[0].closed);
+    // 图层 0 默认 7 号色，白底显示为黑
+    assert_eq!(paths[0].color, 0x000000);
+
+    assert_eq!(paths[1].source, circle);
+    assert!(paths[1].closed);
+    assert_eq!(paths[1].points.len(), 16);
+    for point in &paths[1].points {
+        let radius = (point.x().powi(2) + point.y().powi(2)).sqrt();
+        assert!((radius - 2.0).abs() < 1e-9);
+    }
+
+    // 四分之一圆取 4 段，含起点共 5 个点
+    assert_eq!(paths[2].points.len(), 5);
+    let end = paths[2].points.last().unwrap();
+    assert!(end.x().abs() < 1e-9 && (end.y() - 1.0).abs() < 1e-9);
+
+    let dark = Tessellator::new()
+        .with_options(TessellatorOptions {
+            background: Some(0x202020),
+            ..TessellatorOptions::default()
+        })
+        .tessellate(&document);
+    assert_eq!(dark[0].color, 0xFFFFFF);
+
+    // 只按弦高控制时，半径 2 的圆以 0.5 的弦高取 5 段
+    let coarse = Tessellator::new()
+        .with_options(TessellatorOptions {
+            tessellation: TessellateOptions {
+                chord_tolerance: 0.5,
+                max_angle: 0.0,
+            },
+            ..TessellatorOptions::default()
+        })
+        .tessellate(&document);
+    assert_eq!(coarse[1].points.len(), 5);
+}
+
+#[test]
+fn block_pieces_keep_reference_as_source() {
+    let document = load_fixture("block_insert");
+    let (reference, _) = document
+        .entities()
+        .find(|(_, entity)| matches!(entity, Entity::BlockReference(_)))
+        .expect("样例应包含块参照");
+
+    let paths = Tessellator::new().tessellate(&document);
+    assert!(!paths.is_empty());
+    assert!(paths.iter().all(|path| path.source == *reference));
+}
+
+#[test]
+fn hatch_loops_become_filled_paths_and_hidden_layers_are_skipped() {
+    let document = load_fixture("hatch_simple");
+    let paths = Tessellator::new().tessellate(&document);
+    assert!(paths.iter().any(|path| path.filled && path.closed));
+
+    let outlines_only = Tessellator::new()
+        .with_options(TessellatorOptions {
+            include_fills: false,
+            ..TessellatorOptions::default()
+        })
+        .tessellate(&document);
+    assert!(outlines_only.iter().all(|path| !path.filled));
+
+    let mut document = Document::new();
+    document.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "HIDDEN");
+    document.add_text(Point2::new(0.0, 0.0), "AB", 1.0, 0.0, "0");
+    document.add_layer(Layer {
+        is_visible: false,
+        ..Layer::new("HIDDEN")
+    });
+    let paths = Tessellator::new().tessellate(&document);
+    // 只剩文字轮廓
+    assert_eq!(paths.len(), 1);
+    assert!(paths[0].closed);
+    assert_eq!(paths[0].layer, "0");
+
+    let without_text = Tessellator::new()
+        .with_options(TessellatorOptions {
+            outline_text: false,
+            ..TessellatorOptions::default()
+        })
+        .tessellate(&document);
+    assert!(without_text.is_empty());
+}
+
+#[test]
+fn resolved_colors_follow_entity_overrides() {
+    let mut document = Document::new();
+    let id = document.add_line(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), "0");
+    document.set_entity_properties(
+        id,
+        EntityProperties {
+            color: ObjectColor::Index(1),
+            ..EntityProperties::default()
+        },
+    );
+    let paths = Tessellator::new().tessellate(&document);
+    assert_eq!(paths[0].color, 0xFF0000);
+}
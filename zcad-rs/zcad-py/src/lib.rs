@@ -0,0 +1,499 @@
+//! Python 绑定：通过 PyO3 暴露 `Document`、实体、DXF 等格式的读写与几何辅助函数，
+//! 便于用 Python 脚本批量修改图纸或提取数据。
+//!
+//! ```python
+//! import zcad
+//!
+//! doc = zcad.Document.open("plan.dxf")
+//! for entity in doc.entities(type="LINE", layer="WALL"):
+//!     entity.layer = "WALL-OLD"
+//!     doc.replace(entity.id, entity)
+//! doc.add(zcad.Entity.line((0, 0), (10, 0), layer="NOTE"))
+//! doc.save("plan-edited.dxf")
+//! ```
+//!
+//! 实体数据与原生 JSON 格式一致，`Entity.to_dict()` / `Entity.from_dict()` 可读写任意实体类型；
+//! 坐标统一以 `(x, y)` 元组表示。
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyOSError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use zcad_core::document::{
+    Arc, Circle, Document, Entity, EntityId, EntityProperties, FlattenOptions, Line, Polyline,
+    PolylineVertex,
+};
+use zcad_core::geometry::{Bounds2D, Point2};
+use zcad_core::tessellate::TessellateOptions;
+use zcad_io::tessellate::{Tessellator, TessellatorOptions};
+use zcad_io::{DxfFacade, IoError, SaveFormat, open_document, open_document_bytes};
+
+create_exception!(
+    zcad,
+    ZcadError,
+    PyException,
+    "图纸无法解析、格式不受支持或数据无效。"
+);
+
+type XY = (f64, f64);
+
+fn io_error(err: IoError) -> PyErr {
+    match err {
+        IoError::ReadError { .. } | IoError::WriteError { .. } => {
+            PyOSError::new_err(err.to_string())
+        }
+        err => ZcadError::new_err(err.to_string()),
+    }
+}
+
+/// 离散精度，省略的一项取默认值。
+fn tessellate_options(chord_tolerance: Option<f64>, max_angle: Option<f64>) -> TessellateOptions {
+    let defaults = TessellateOptions::default();
+    TessellateOptions {
+        chord_tolerance: chord_tolerance.unwrap_or(defaults.chord_tolerance),
+        max_angle: max_angle.unwrap_or(defaults.max_angle),
+    }
+}
+
+/// 经 JSON 把 serde 值转换为 Python 对象（dict/list/数值）。
+fn to_python(py: Python<'_>, value: &impl Serialize) -> PyResult<PyObject> {
+    let text = serde_json::to_string(value)
+        .map_err(|err| ZcadError::new_err(format!("序列化失败: {err}")))?;
+    Ok(py.import("json")?.call_method1("loads", (text,))?.unbind())
+}
+
+/// 经 JSON 把 Python 对象转换为 serde 值。
+fn from_python<T: DeserializeOwned>(value: &Bound<'_, PyAny>) -> PyResult<T> {
+    let text: String = value
+        .py()
+        .import("json")?
+        .call_method1("dumps", (value,))?
+        .extract()?;
+    serde_json::from_str(&text).map_err(|err| PyValueError::new_err(format!("数据无效: {err}")))
+}
+
+fn point((x, y): XY) -> Point2 {
+    Point2::new(x, y)
+}
+
+fn xy(point: Point2) -> XY {
+    (point.x(), point.y())
+}
+
+/// 轴对齐包围盒。
+#[pyclass(name = "Bounds", module = "zcad", frozen)]
+#[derive(Clone, Copy)]
+struct PyBounds {
+    inner: Bounds2D,
+}
+
+#[pymethods]
+impl PyBounds {
+    /// 由两个角点构造，角点顺序任意。
+    #[new]
+    fn new(a: XY, b: XY) -> Self {
+        let inner = Bounds2D::new(
+            Point2::new(a.0.min(b.0), a.1.min(b.1)),
+            Point2::new(a.0.max(b.0), a.1.max(b.1)),
+        );
+        Self { inner }
+    }
+
+    #[getter]
+    fn min(&self) -> XY {
+        xy(self.inner.min())
+    }
+
+    #[getter]
+    fn max(&self) -> XY {
+        xy(self.inner.max())
+    }
+
+    #[getter]
+    fn center(&self) -> XY {
+        xy(self.inner.center())
+    }
+
+    #[getter]
+    fn width(&self) -> f64 {
+        self.inner.max().x() - self.inner.min().x()
+    }
+
+    #[getter]
+    fn height(&self) -> f64 {
+        self.inner.max().y() - self.inner.min().y()
+    }
+
+    /// 点是否在包围盒内（含边界）。
+    fn contains(&self, point: XY) -> bool {
+        let (min, max) = (self.inner.min(), self.inner.max());
+        (min.x()..=max.x()).contains(&point.0) && (min.y()..=max.y()).contains(&point.1)
+    }
+
+    fn intersects(&self, other: &PyBounds) -> bool {
+        let (a, b) = (&self.inner, &other.inner);
+        a.min().x() <= b.max().x()
+            && a.max().x() >= b.min().x()
+            && a.min().y() <= b.max().y()
+            && a.max().y() >= b.min().y()
+    }
+
+    fn union(&self, other: &PyBounds) -> PyBounds {
+        let mut inner = self.inner;
+        inner.include_bounds(&other.inner);
+        PyBounds { inner }
+    }
+
+    fn __repr__(&self) -> String {
+        let (min, max) = (self.inner.min(), self.inner.max());
+        format!(
+            "Bounds(({}, {}), ({}, {}))",
+            min.x(),
+            min.y(),
+            max.x(),
+            max.y()
+        )
+    }
+}
+
+fn bounds(bounds: Option<Bounds2D>) -> Option<PyBounds> {
+    bounds
+        .filter(|bounds| !bounds.is_empty())
+        .map(|inner| PyBounds { inner })
+}
+
+/// 图纸实体。从文档取出的实体带有 `id`，修改后需通过 `Document.replace` 写回。
+#[pyclass(name = "Entity", module = "zcad")]
+#[derive(Clone)]
+struct PyEntity {
+    id: Option<u64>,
+    entity: Entity,
+}
+
+impl PyEntity {
+    fn detached(entity: Entity) -> Self {
+        Self { id: None, entity }
+    }
+}
+
+#[pymethods]
+impl PyEntity {
+    /// 由 `{"Line": {...}}` 形式的字典构造任意类型的实体。
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(Self::detached(from_python(data)?))
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (start, end, layer = "0"))]
+    fn line(start: XY, end: XY, layer: &str) -> Self {
+        Self::detached(Entity::Line(Line {
+            start: point(start),
+            end: point(end),
+            layer: layer.to_string(),
+        }))
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (center, radius, layer = "0"))]
+    fn circle(center: XY, radius: f64, layer: &str) -> Self {
+        Self::detached(Entity::Circle(Circle {
+            center: point(center),
+            radius,
+            layer: layer.to_string(),
+        }))
+    }
+
+    /// 角度为弧度，逆时针从起始角到终止角。
+    #[staticmethod]
+    #[pyo3(signature = (center, radius, start_angle, end_angle, layer = "0"))]
+    fn arc(center: XY, radius: f64, start_angle: f64, end_angle: f64, layer: &str) -> Self {
+        Self::detached(Entity::Arc(Arc {
+            center: point(center),
+            radius,
+            start_angle,
+            end_angle,
+            layer: layer.to_string(),
+        }))
+    }
+
+    /// 顶点为 `(x, y)` 或带凸度的 `(x, y, bulge)`。
+    #[staticmethod]
+    #[pyo3(signature = (vertices, closed = false, layer = "0"))]
+    fn polyline(vertices: Vec<Bound<'_, PyAny>>, closed: bool, layer: &str) -> PyResult<Self> {
+        let vertices = vertices
+            .iter()
+            .map(|vertex| {
+                if let Ok((x, y, bulge)) = vertex.extract::<(f64, f64, f64)>() {
+                    Ok(PolylineVertex::with_bulge(Point2::new(x, y), bulge))
+                } else {
+                    Ok(PolylineVertex::new(point(vertex.extract::<XY>()?)))
+                }
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(Self::detached(Entity::Polyline(Polyline {
+            vertices,
+            is_closed: closed,
+            layer: layer.to_string(),
+        })))
+    }
+
+    #[getter]
+    fn id(&self) -> Option<u64> {
+        self.id
+    }
+
+    /// DXF 实体类型名，例如 `LINE`。
+    #[getter]
+    fn kind(&self) -> &'static str {
+        self.entity.kind_name()
+    }
+
+    #[getter]
+    fn layer(&self) -> &str {
+        self.entity.layer_name()
+    }
+
+    #[setter]
+    fn set_layer(&mut self, layer: &str) {
+        self.entity.set_layer_name(layer);
+    }
+
+    fn bounds(&self) -> Option<PyBounds> {
+        bounds(self.entity.bounds())
+    }
+
+    /// 与原生 JSON 格式一致的字典，例如 `{"Line": {"start": [0, 0], ...}}`。
+    fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python(py, &self.entity)
+    }
+
+    fn __repr__(&self) -> String {
+        match self.id {
+            Some(id) => format!(
+                "<Entity #{id} {} layer={:?}>",
+                self.entity.kind_name(),
+                self.entity.layer_name()
+            ),
+            None => format!(
+                "<Entity {} layer={:?}>",
+                self.entity.kind_name(),
+                self.entity.layer_name()
+            ),
+        }
+    }
+}
+
+/// 图纸文档。
+#[pyclass(name = "Document", module = "zcad")]
+struct PyDocument {
+    document: Document,
+}
+
+#[pymethods]
+impl PyDocument {
+    /// 新建只含 0 层的空白文档。
+    #[new]
+    fn new() -> Self {
+        Self {
+            document: Document::new(),
+        }
+    }
+
+    /// 按内容识别格式（DXF、原生 JSON、`.zcadz`）打开图纸。
+    #[staticmethod]
+    fn open(path: std::path::PathBuf) -> PyResult<Self> {
+        let document = open_document(&path).map_err(io_error)?;
+        Ok(Self { document })
+    }
+
+    /// 从内存中的文件内容解析图纸。
+    #[staticmethod]
+    fn from_bytes(data: &Bound<'_, PyBytes>) -> PyResult<Self> {
+        let document = open_document_bytes(data.as_bytes()).map_err(io_error)?;
+        Ok(Self { document })
+    }
+
+    /// 从 DXF 文本解析图纸。
+    #[staticmethod]
+    fn from_dxf(text: &str) -> PyResult<Self> {
+        let document = DxfFacade::new().from_dxf(text).map_err(io_error)?;
+        Ok(Self { document })
+    }
+
+    /// 保存图纸；`format` 为扩展名（如 `dxf`、`json`、`svg`、`glb`），省略时按路径扩展名选择。
+    #[pyo3(signature = (path, format = None))]
+    fn save(&self, path: std::path::PathBuf, format: Option<&str>) -> PyResult<()> {
+        let format = match format {
+            Some(format) => SaveFormat::from_extension(format),
+            None => SaveFormat::from_path(&path),
+        }
+        .ok_or_else(|| PyValueError::new_err(format!("无法确定保存格式: {}", path.display())))?;
+        format.save(&self.document, &path).map_err(io_error)
+    }
+
+    fn __len__(&self) -> usize {
+        self.document.entities().count()
+    }
+
+    /// 按实体顺序返回实体，可按类型名（不区分大小写）与图层过滤。
+    #[pyo3(signature = (r#type = None, layer = None))]
+    fn entities(&self, r#type: Option<&str>, layer: Option<&str>) -> Vec<PyEntity> {
+        self.document
+            .entities()
+            .filter(|(_, entity)| {
+                r#type.is_none_or(|kind| entity.kind_name().eq_ignore_ascii_case(kind))
+            })
+            .filter(|(_, entity)| layer.is_none_or(|layer| entity.layer_name() == layer))
+            .map(|(id, entity)| PyEntity {
+                id: Some(id.get()),
+                entity: entity.clone(),
+            })
+            .collect()
+    }
+
+    fn entity(&self, id: u64) -> Option<PyEntity> {
+        self.document
+            .entity(EntityId::new(id))
+            .map(|entity| PyEntity {
+                id: Some(id),
+                entity: entity.clone(),
+            })
+    }
+
+    /// 添加实体并返回新编号；实体原有的 `id` 被忽略。
+    fn add(&mut self, entity: &PyEntity) -> u64 {
+        self.document.add_entity(entity.entity.clone()).get()
+    }
+
+    /// 以新数据替换实体，保留编号与特性覆盖。
+    fn replace(&mut self, id: u64, entity: &PyEntity) -> PyResult<()> {
+        self.document
+            .replace_entity(EntityId::new(id), entity.entity.clone())
+            .map(|_| ())
+            .ok_or_else(|| PyValueError::new_err(format!("实体 {id} 不存在")))
+    }
+
+    /// 删除实体并返回其数据，不存在时返回 `None`。
+    fn remove(&mut self, id: u64) -> Option<PyEntity> {
+        self.document
+            .remove_entity(EntityId::new(id))
+            .map(PyEntity::detached)
+    }
+
+    /// 图层字典列表，字段与原生 JSON 格式一致。
+    fn layers(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let mut layers: Vec<_> = self.document.layers().collect();
+        layers.sort_by(|a, b| a.name.cmp(&b.name));
+        to_python(py, &layers)
+    }
+
+    fn bounds(&self) -> Option<PyBounds> {
+        bounds(self.document.bounds())
+    }
+
+    /// 实体的特性覆盖（颜色、线型、线宽），全部随层时返回 `None`。
+    fn properties(&self, py: Python<'_>, id: u64) -> PyResult<Option<PyObject>> {
+        self.document
+            .entity_properties(EntityId::new(id))
+            .map(|properties| to_python(py, properties))
+            .transpose()
+    }
+
+    fn set_properties(&mut self, id: u64, properties: &Bound<'_, PyAny>) -> PyResult<()> {
+        let id = EntityId::new(id);
+        if self.document.entity(id).is_none() {
+            return Err(PyValueError::new_err(format!("实体 {} 不存在", id.get())));
+        }
+        let properties: EntityProperties = from_python(properties)?;
+        self.document.set_entity_properties(id, properties);
+        Ok(())
+    }
+
+    /// 炸开块参照、离散样条与椭圆，返回只含简单图元的新文档；
+    /// `chord_tolerance`、`max_angle` 省略时使用默认离散精度。
+    #[pyo3(signature = (chord_tolerance = None, max_angle = None))]
+    fn flatten(&self, chord_tolerance: Option<f64>, max_angle: Option<f64>) -> Self {
+        Self {
+            document: self.document.flatten(&FlattenOptions {
+                tessellation: tessellate_options(chord_tolerance, max_angle),
+                ..FlattenOptions::default()
+            }),
+        }
+    }
+
+    /// 离散为折线字典列表：`source`、`layer`、`color`、`closed`、`filled`、`points`。
+    #[pyo3(signature = (chord_tolerance = None, max_angle = None, outline_text = true, include_fills = true))]
+    fn tessellate(
+        &self,
+        py: Python<'_>,
+        chord_tolerance: Option<f64>,
+        max_angle: Option<f64>,
+        outline_text: bool,
+        include_fills: bool,
+    ) -> PyResult<PyObject> {
+        let paths = Tessellator::new()
+            .with_options(TessellatorOptions {
+                tessellation: tessellate_options(chord_tolerance, max_angle),
+                outline_text,
+                include_fills,
+                ..TessellatorOptions::default()
+            })
+            .tessellate(&self.document);
+        to_python(py, &paths)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "<Document entities={} layers={}>",
+            self.document.entities().count(),
+            self.document.layers().count()
+        )
+    }
+}
+
+/// 两点间距离。
+#[pyfunction]
+fn distance(a: XY, b: XY) -> f64 {
+    point(a).as_vec2().distance(point(b).as_vec2())
+}
+
+/// 从 `a` 指向 `b` 的方向角（弧度）。
+#[pyfunction]
+fn angle(a: XY, b: XY) -> f64 {
+    (b.1 - a.1).atan2(b.0 - a.0)
+}
+
+/// 从 `origin` 沿 `angle`（弧度）方向前进 `length` 得到的点。
+#[pyfunction]
+fn polar(origin: XY, angle: f64, length: f64) -> XY {
+    (
+        origin.0 + angle.cos() * length,
+        origin.1 + angle.sin() * length,
+    )
+}
+
+/// 点集的包围盒，空集返回 `None`。
+#[pyfunction]
+fn bounds_of(points: Vec<XY>) -> Option<PyBounds> {
+    let mut inner = Bounds2D::empty();
+    for xy in points {
+        inner.include_point(point(xy));
+    }
+    bounds(Some(inner))
+}
+
+#[pymodule]
+fn zcad(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyDocument>()?;
+    module.add_class::<PyEntity>()?;
+    module.add_class::<PyBounds>()?;
+    module.add("ZcadError", module.py().get_type::<ZcadError>())?;
+    module.add_function(wrap_pyfunction!(distance, module)?)?;
+    module.add_function(wrap_pyfunction!(angle, module)?)?;
+    module.add_function(wrap_pyfunction!(polar, module)?)?;
+    module.add_function(wrap_pyfunction!(bounds_of, module)?)?;
+    Ok(())
+}
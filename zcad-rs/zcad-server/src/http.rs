@@ -0,0 +1,251 @@
+//! 最小的 HTTP/1.1 请求解析与响应写出，只覆盖渲染服务需要的部分：
+//! 带 `Content-Length` 的请求体、查询参数百分号解码，每个连接处理一个请求后关闭。
+
+use std::io::{self, BufRead, Write};
+
+use serde::Serialize;
+use thiserror::Error;
+
+/// 请求头总长度上限。
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Error)]
+pub enum HttpError {
+    #[error("连接读写失败: {0}")]
+    Io(#[from] io::Error),
+    #[error("请求格式错误: {0}")]
+    BadRequest(String),
+    #[error("请求体缺少 Content-Length")]
+    LengthRequired,
+    #[error("请求体超过 {limit} 字节上限")]
+    PayloadTooLarge { limit: usize },
+}
+
+impl HttpError {
+    /// 对应的错误响应；连接读写失败时返回 `None`。
+    pub fn to_response(&self) -> Option<Response> {
+        let status = match self {
+            HttpError::Io(_) => return None,
+            HttpError::BadRequest(_) => 400,
+            HttpError::LengthRequired => 411,
+            HttpError::PayloadTooLarge { .. } => 413,
+        };
+        Some(Response::error(status, self.to_string()))
+    }
+}
+
+/// 解析后的请求。
+#[derive(Debug, Clone, Default)]
+pub struct Request {
+    pub method: String,
+    /// 不含查询串的路径，已解码。
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    /// 头名称统一为小写。
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// 构造不带请求体的请求，`target` 可包含查询串。
+    pub fn new(method: &str, target: &str) -> Self {
+        let (path, query) = split_target(target);
+        Self {
+            method: method.to_ascii_uppercase(),
+            path,
+            query,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// 查询参数，同名时取第一个。
+    pub fn query(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// 从连接读取一个请求；请求体超过 `max_body` 字节时返回 [`HttpError::PayloadTooLarge`]。
+    pub fn read_from(reader: &mut impl BufRead, max_body: usize) -> Result<Self, HttpError> {
+        let mut head_bytes = 0;
+        let mut line = String::new();
+        let mut read_line = |line: &mut String| -> Result<(), HttpError> {
+            line.clear();
+            let read = reader.read_line(line)?;
+            head_bytes += read;
+            if read == 0 {
+                return Err(HttpError::BadRequest("连接提前关闭".to_string()));
+            }
+            if head_bytes > MAX_HEAD_BYTES {
+                return Err(HttpError::BadRequest("请求头过长".to_string()));
+            }
+            let trimmed = line.trim_end_matches(['\r', '\n']).len();
+            line.truncate(trimmed);
+            Ok(())
+        };
+
+        read_line(&mut line)?;
+        let mut parts = line.split_whitespace();
+        let (Some(method), Some(target), Some(version)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(HttpError::BadRequest(format!("无效的请求行 {line:?}")));
+        };
+        if !version.starts_with("HTTP/1.") {
+            return Err(HttpError::BadRequest(format!("不支持的协议版本 {version}")));
+        }
+        let mut request = Request::new(method, target);
+
+        loop {
+            read_line(&mut line)?;
+            if line.is_empty() {
+                break;
+            }
+            let (name, value) = line
+                .split_once(':')
+                .ok_or_else(|| HttpError::BadRequest(format!("无效的请求头 {line:?}")))?;
+            request
+                .headers
+                .push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+
+        if request
+            .header("transfer-encoding")
+            .is_some_and(|value| !value.eq_ignore_ascii_case("identity"))
+        {
+            return Err(HttpError::LengthRequired);
+        }
+        let length = match request.header("content-length") {
+            Some(value) => value
+                .parse::<usize>()
+                .map_err(|_| HttpError::BadRequest(format!("无效的 Content-Length {value:?}")))?,
+            None if matches!(request.method.as_str(), "POST" | "PUT") => {
+                return Err(HttpError::LengthRequired);
+            }
+            None => 0,
+        };
+        if length > max_body {
+            return Err(HttpError::PayloadTooLarge { limit: max_body });
+        }
+        request.body = vec![0; length];
+        reader.read_exact(&mut request.body)?;
+        Ok(request)
+    }
+}
+
+/// 待写出的响应。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn new(status: u16, content_type: &'static str, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            content_type,
+            body: body.into(),
+        }
+    }
+
+    pub fn json(status: u16, value: &impl Serialize) -> Self {
+        match serde_json::to_vec(value) {
+            Ok(body) => Self::new(status, "application/json", body),
+            Err(err) => Self::error(500, format!("响应序列化失败: {err}")),
+        }
+    }
+
+    /// `{"error": message}` 形式的错误响应。
+    pub fn error(status: u16, message: impl Into<String>) -> Self {
+        let body = serde_json::json!({ "error": message.into() });
+        Self::new(status, "application/json", body.to_string())
+    }
+
+    /// 响应体按 UTF-8 解析为 JSON，便于测试与客户端检查。
+    pub fn json_body(&self) -> Option<serde_json::Value> {
+        serde_json::from_slice(&self.body).ok()
+    }
+
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        write!(
+            writer,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.status,
+            reason_phrase(self.status),
+            self.content_type,
+            self.body.len()
+        )?;
+        writer.write_all(&self.body)?;
+        writer.flush()
+    }
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        415 => "Unsupported Media Type",
+        422 => "Unprocessable Entity",
+        _ => "Internal Server Error",
+    }
+}
+
+fn split_target(target: &str) -> (String, Vec<(String, String)>) {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key, true), percent_decode(value, true))
+        })
+        .collect();
+    (percent_decode(path, false), query)
+}
+
+/// 百分号解码，查询串中的 `+` 视为空格；非法转义按原样保留。
+fn percent_decode(text: &str, plus_as_space: bool) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'%' if index + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[index + 1..index + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        index += 3;
+                        continue;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            b'+' if plus_as_space => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
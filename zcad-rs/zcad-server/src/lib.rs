@@ -0,0 +1,73 @@
+//! 无界面图纸渲染服务：通过 HTTP 上传图纸、按区域渲染 PNG/SVG 预览并以 JSON 查询实体，
+//! 复用 `zcad-io` 的读取器与导出管线。
+//!
+//! | 方法 | 路径 | 说明 |
+//! | --- | --- | --- |
+//! | `GET` | `/health` | 存活检查 |
+//! | `POST` | `/drawings?name=` | 上传图纸，返回编号与概况 |
+//! | `GET` | `/drawings` | 已上传的图纸 |
+//! | `GET`/`DELETE` | `/drawings/{id}` | 图纸概况 / 删除 |
+//! | `GET` | `/drawings/{id}/render.png?width=&height=&window=&background=` | PNG 渲染 |
+//! | `GET` | `/drawings/{id}/render.svg?width=&window=&background=` | SVG 渲染 |
+//! | `GET` | `/drawings/{id}/entities?type=&layer=&window=&offset=&limit=` | 实体列表 |
+//! | `GET` | `/drawings/{id}/entities/{entity}` | 单个实体 |
+//! | `POST` | `/rpc` | JSON-RPC 2.0 文档服务，见 [`rpc`] |
+
+pub mod http;
+pub mod rpc;
+mod service;
+
+use std::io::{BufReader, BufWriter};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::{debug, warn};
+
+pub use crate::http::{HttpError, Request, Response};
+pub use crate::rpc::{DocumentService, DocumentServiceOptions, FileAccess, RpcError};
+pub use crate::service::{RenderService, ServerOptions};
+
+/// 单个连接的读写超时。
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 在监听端口上循环接受连接，每个连接在独立线程中处理一个请求。
+pub fn serve(listener: TcpListener, service: Arc<RenderService>) -> std::io::Result<()> {
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                warn!(error = %err, "接受连接失败");
+                continue;
+            }
+        };
+        let service = Arc::clone(&service);
+        std::thread::spawn(move || {
+            if let Err(err) = handle_connection(stream, &service) {
+                debug!(error = %err, "连接处理中断");
+            }
+        });
+    }
+    Ok(())
+}
+
+fn handle_connection(stream: TcpStream, service: &RenderService) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(CONNECTION_TIMEOUT))?;
+    stream.set_write_timeout(Some(CONNECTION_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let response = match Request::read_from(&mut reader, service.options().max_upload_bytes) {
+        Ok(request) => {
+            let response = service.handle(&request);
+            debug!(
+                method = %request.method,
+                path = %request.path,
+                status = response.status,
+                "处理请求"
+            );
+            response
+        }
+        Err(HttpError::Io(err)) => return Err(err),
+        Err(err) => err.to_response().expect("非 IO 错误总有对应响应"),
+    };
+    response.write_to(&mut BufWriter::new(stream))
+}
@@ -0,0 +1,89 @@
+use std::io;
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use clap::Parser;
+use tracing::{error, info};
+use tracing_subscriber::{EnvFilter, fmt};
+use zcad_server::{
+    DocumentService, DocumentServiceOptions, FileAccess, RenderService, ServerOptions, serve,
+};
+
+#[derive(Parser, Debug)]
+#[command(name = "zcad-server", version)]
+#[command(about = "ZCAD 无界面渲染服务：上传图纸并通过 HTTP 获取 PNG/SVG 预览与实体数据", long_about = None)]
+struct Cli {
+    /// 监听地址
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    bind: String,
+    /// 上传图纸的大小上限（MiB）
+    #[arg(long, default_value_t = 64)]
+    max_upload_mb: usize,
+    /// 内存中保留的图纸数量，超出时淘汰最早上传的图纸
+    #[arg(long, default_value_t = 32)]
+    max_drawings: usize,
+    /// 渲染 PNG 的最大边长（像素）
+    #[arg(long, default_value_t = 8192)]
+    max_image_size: u32,
+    /// 允许 JSON-RPC 按路径打开与保存图纸的根目录；未指定时只能上传内容
+    #[arg(long)]
+    file_root: Option<PathBuf>,
+    /// 不监听端口，改为在标准输入输出上逐行收发 JSON-RPC 消息，按路径读写不受限制
+    #[arg(long, conflicts_with_all = ["bind", "file_root"])]
+    stdio: bool,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    // 标准输出在 --stdio 模式下用于回复，日志一律写到标准错误
+    if fmt()
+        .with_env_filter(filter)
+        .with_writer(io::stderr)
+        .try_init()
+        .is_err()
+    {
+        // 已初始化，忽略
+    }
+
+    if cli.stdio {
+        let documents = DocumentService::new().with_options(DocumentServiceOptions {
+            file_access: FileAccess::Unrestricted,
+            ..DocumentServiceOptions::default()
+        });
+        if let Err(err) = documents.serve_lines(io::stdin().lock(), io::stdout().lock()) {
+            error!(error = %err, "标准输入输出中断");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let listener = match TcpListener::bind(&cli.bind) {
+        Ok(listener) => listener,
+        Err(err) => {
+            error!(bind = %cli.bind, error = %err, "无法监听地址");
+            std::process::exit(1);
+        }
+    };
+    let service = RenderService::new()
+        .with_options(ServerOptions {
+            max_upload_bytes: cli.max_upload_mb.saturating_mul(1024 * 1024),
+            max_drawings: cli.max_drawings,
+            max_image_size: cli.max_image_size,
+            ..ServerOptions::default()
+        })
+        .with_documents(
+            DocumentService::new().with_options(DocumentServiceOptions {
+                file_access: cli
+                    .file_root
+                    .map_or(FileAccess::Disabled, FileAccess::Within),
+                ..DocumentServiceOptions::default()
+            }),
+        );
+    info!(bind = %cli.bind, "渲染服务已启动");
+    if let Err(err) = serve(listener, Arc::new(service)) {
+        error!(error = %err, "渲染服务异常退出");
+        std::process::exit(1);
+    }
+}
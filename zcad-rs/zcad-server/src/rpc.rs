@@ -0,0 +1,617 @@
+//! JSON-RPC 2.0 文档服务：以会话为单位持有 [`Scene`]，提供加载、查询、修改、选择与保存操作，
+//! 外部编辑器与自动化脚本无需链接本仓库的 crate 即可驱动文档。
+//!
+//! 同一服务可经 HTTP `POST /rpc` 调用，也可通过 [`DocumentService::serve_lines`]
+//! 以每行一条消息的方式挂在标准输入输出上。支持批量请求与通知（不带 `id` 的请求不回复）。
+//!
+//! | 方法 | 参数 | 结果 |
+//! | --- | --- | --- |
+//! | `document.new` | — | `{session}` |
+//! | `document.open` | `path` 或 `content`（DXF/JSON 文本），可选 `name` | `{session, format, summary}` |
+//! | `document.summary` | `session` | 图纸概况 |
+//! | `document.save` | `session`、`path`，可选 `format`（扩展名，如 `dxf`、`glb`） | `{path, format}` |
+//! | `document.close` | `session` | `{closed}` |
+//! | `session.list` | — | `{sessions}` |
+//! | `entities.query` | `session`，可选 `type`、`layer`、`window`、`offset`、`limit` | `{total, offset, entities}` |
+//! | `entities.get` | `session`、`id` | 实体 |
+//! | `entities.add` | `session`、`entity`，可选 `properties` | `{id}` |
+//! | `entities.update` | `session`、`id`，`entity` 与 `properties` 至少一项 | 实体 |
+//! | `entities.delete` | `session`、`ids` | `{deleted}` |
+//! | `selection.get` | `session` | `{ids, bounds}` |
+//! | `selection.set` | `session`、`ids`，可选 `mode`（`replace`/`add`/`remove`） | `{ids, bounds}` |
+//!
+//! `entity` 与 `entities.get` 返回的 `data` 字段结构一致，例如
+//! `{"Line": {"start": [0, 0], "end": [10, 0], "layer": "0"}}`。
+
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use zcad_core::document::{Document, Entity, EntityId, EntityProperties};
+use zcad_core::geometry::{Bounds2D, Point2};
+use zcad_engine::scene::Scene;
+use zcad_io::{IoError, SaveFormat, open_document};
+
+use crate::service::{EntityFilter, bounds_json, document_summary, entity_json, load_bytes};
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+/// 会话不存在。
+pub const SESSION_NOT_FOUND: i64 = -32001;
+/// 实体不存在。
+pub const ENTITY_NOT_FOUND: i64 = -32002;
+/// 图纸读写失败。
+pub const DOCUMENT_ERROR: i64 = -32003;
+/// 文件访问未开启或路径越界。
+pub const FILE_ACCESS_DENIED: i64 = -32004;
+
+/// JSON-RPC 错误对象。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({ "code": self.code, "message": self.message })
+    }
+}
+
+impl From<IoError> for RpcError {
+    fn from(err: IoError) -> Self {
+        RpcError::new(DOCUMENT_ERROR, err.to_string())
+    }
+}
+
+/// 按路径打开与保存图纸的权限。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum FileAccess {
+    /// 不允许按路径读写，只能通过 `content` 打开图纸。
+    #[default]
+    Disabled,
+    /// 只允许访问根目录下的相对路径，不得包含 `..`。
+    Within(PathBuf),
+    /// 不限制路径，用于本机标准输入输出模式。
+    Unrestricted,
+}
+
+/// 文档服务选项。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentServiceOptions {
+    pub file_access: FileAccess,
+    /// 同时打开的会话数量上限，超出时拒绝新建。
+    pub max_sessions: usize,
+    /// 实体查询单页的最大条数。
+    pub max_page_size: usize,
+}
+
+impl Default for DocumentServiceOptions {
+    fn default() -> Self {
+        Self {
+            file_access: FileAccess::Disabled,
+            max_sessions: 32,
+            max_page_size: 1000,
+        }
+    }
+}
+
+struct Session {
+    name: Option<String>,
+    scene: Scene,
+}
+
+/// JSON-RPC 文档服务，可在多个线程间共享。
+#[derive(Default)]
+pub struct DocumentService {
+    options: DocumentServiceOptions,
+    sessions: Mutex<BTreeMap<u64, Session>>,
+    next_id: AtomicU64,
+}
+
+impl DocumentService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(mut self, options: DocumentServiceOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn options(&self) -> &DocumentServiceOptions {
+        &self.options
+    }
+
+    /// 处理一条 JSON-RPC 消息（单个请求或批量数组），返回待发送的回复；
+    /// 全部为通知时返回 `None`。
+    pub fn handle_message(&self, message: &str) -> Option<String> {
+        let reply = match serde_json::from_str::<Value>(message) {
+            Err(err) => Some(error_reply(
+                Value::Null,
+                RpcError::new(PARSE_ERROR, format!("无法解析 JSON: {err}")),
+            )),
+            Ok(Value::Array(batch)) if batch.is_empty() => Some(error_reply(
+                Value::Null,
+                RpcError::new(INVALID_REQUEST, "批量请求为空"),
+            )),
+            Ok(Value::Array(batch)) => {
+                let replies: Vec<Value> = batch
+                    .into_iter()
+                    .filter_map(|request| self.handle_request(request))
+                    .collect();
+                (!replies.is_empty()).then_some(Value::Array(replies))
+            }
+            Ok(request) => self.handle_request(request),
+        };
+        reply.map(|reply| reply.to_string())
+    }
+
+    /// 逐行读取消息并逐行写出回复，直到输入结束；空行被忽略。
+    pub fn serve_lines(&self, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(reply) = self.handle_message(&line) {
+                writeln!(output, "{reply}")?;
+                output.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&self, request: Value) -> Option<Value> {
+        let Value::Object(mut request) = request else {
+            return Some(error_reply(
+                Value::Null,
+                RpcError::new(INVALID_REQUEST, "请求应为对象"),
+            ));
+        };
+        let id = request.remove("id");
+        let valid_id = matches!(
+            id,
+            None | Some(Value::Null | Value::Number(_) | Value::String(_))
+        );
+        let method = match request.remove("method") {
+            Some(Value::String(method))
+                if valid_id && request.get("jsonrpc") == Some(&json!("2.0")) =>
+            {
+                method
+            }
+            _ => {
+                return Some(error_reply(
+                    id.filter(|_| valid_id).unwrap_or(Value::Null),
+                    RpcError::new(INVALID_REQUEST, "缺少 jsonrpc 2.0 或 method"),
+                ));
+            }
+        };
+        let params = request.remove("params").unwrap_or(Value::Null);
+        let result = self.call(&method, params);
+        // 不带 id 的通知不回复
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(err) => error_reply(id, err),
+        })
+    }
+
+    /// 直接调用方法，`params` 为对象或 `null`。
+    pub fn call(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "document.new" => {
+                let () = parse_params(params)?;
+                self.insert_session(None, Document::new())
+                    .map(|session| json!({ "session": session }))
+            }
+            "document.open" => self.open(parse_params(params)?),
+            "document.summary" => {
+                let SessionParams { session } = parse_params(params)?;
+                self.with_scene(session, |scene| Ok(document_summary(scene.document())))
+            }
+            "document.save" => self.save(parse_params(params)?),
+            "document.close" => {
+                let SessionParams { session } = parse_params(params)?;
+                self.lock()
+                    .remove(&session)
+                    .map(|_| json!({ "closed": session }))
+                    .ok_or_else(|| session_not_found(session))
+            }
+            "session.list" => {
+                let () = parse_params(params)?;
+                let sessions: Vec<Value> = self
+                    .lock()
+                    .iter()
+                    .map(|(id, session)| {
+                        json!({
+                            "session": id,
+                            "name": session.name,
+                            "entities": session.scene.document().entities().count(),
+                        })
+                    })
+                    .collect();
+                Ok(json!({ "sessions": sessions }))
+            }
+            "entities.query" => self.query(parse_params(params)?),
+            "entities.get" => {
+                let EntityParams { session, id } = parse_params(params)?;
+                self.with_scene(session, |scene| entity_value(scene.document(), id))
+            }
+            "entities.add" => {
+                let AddParams {
+                    session,
+                    entity,
+                    properties,
+                } = parse_params(params)?;
+                self.with_scene(session, |scene| {
+                    let id = scene.edit("添加实体", |document| {
+                        let id = document.add_entity(entity);
+                        if let Some(properties) = properties {
+                            document.set_entity_properties(id, properties);
+                        }
+                        id
+                    });
+                    Ok(json!({ "id": id.get() }))
+                })
+            }
+            "entities.update" => {
+                let UpdateParams {
+                    session,
+                    id,
+                    entity,
+                    properties,
+                } = parse_params(params)?;
+                if entity.is_none() && properties.is_none() {
+                    return Err(RpcError::new(
+                        INVALID_PARAMS,
+                        "entity 与 properties 至少提供一项",
+                    ));
+                }
+                self.with_scene(session, |scene| {
+                    let entity_id = EntityId::new(id);
+                    if scene.entity(entity_id).is_none() {
+                        return Err(entity_not_found(id));
+                    }
+                    scene.edit("修改实体", |document| {
+                        if let Some(entity) = entity {
+                            document.replace_entity(entity_id, entity);
+                        }
+                        if let Some(properties) = properties {
+                            document.set_entity_properties(entity_id, properties);
+                        }
+                    });
+                    entity_value(scene.document(), id)
+                })
+            }
+            "entities.delete" => {
+                let IdsParams { session, ids } = parse_params(params)?;
+                self.with_scene(session, |scene| {
+                    let mut deleted = Vec::new();
+                    for id in ids {
+                        let entity_id = EntityId::new(id);
+                        if scene.remove_entity(entity_id).is_ok() {
+                            deleted.push(id);
+                        }
+                    }
+                    Ok(json!({ "deleted": deleted }))
+                })
+            }
+            "selection.get" => {
+                let SessionParams { session } = parse_params(params)?;
+                self.with_scene(session, |scene| Ok(selection_json(scene)))
+            }
+            "selection.set" => {
+                let SelectionParams { session, ids, mode } = parse_params(params)?;
+                self.with_scene(session, |scene| {
+                    if let Some(missing) = ids
+                        .iter()
+                        .find(|id| scene.entity(EntityId::new(**id)).is_none())
+                    {
+                        return Err(entity_not_found(*missing));
+                    }
+                    if mode == SelectionMode::Replace {
+                        scene.clear_selection();
+                    }
+                    for id in ids.into_iter().map(EntityId::new) {
+                        match mode {
+                            SelectionMode::Replace | SelectionMode::Add => {
+                                // 前面已确认实体存在
+                                let _ = scene.select(id);
+                            }
+                            SelectionMode::Remove => {
+                                scene.deselect(id);
+                            }
+                        }
+                    }
+                    Ok(selection_json(scene))
+                })
+            }
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("未知方法 {method}"),
+            )),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<u64, Session>> {
+        self.sessions.lock().expect("会话表锁已中毒")
+    }
+
+    fn insert_session(&self, name: Option<String>, document: Document) -> Result<u64, RpcError> {
+        let mut sessions = self.lock();
+        if sessions.len() >= self.options.max_sessions {
+            return Err(RpcError::new(
+                INTERNAL_ERROR,
+                format!(
+                    "会话数量已达上限 {}，请先关闭不用的会话",
+                    self.options.max_sessions
+                ),
+            ));
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        sessions.insert(
+            id,
+            Session {
+                name,
+                scene: Scene::with_document(document),
+            },
+        );
+        Ok(id)
+    }
+
+    fn with_scene(
+        &self,
+        session: u64,
+        action: impl FnOnce(&mut Scene) -> Result<Value, RpcError>,
+    ) -> Result<Value, RpcError> {
+        let mut sessions = self.lock();
+        let session_state = sessions
+            .get_mut(&session)
+            .ok_or_else(|| session_not_found(session))?;
+        action(&mut session_state.scene)
+    }
+
+    fn open(&self, params: OpenParams) -> Result<Value, RpcError> {
+        let (document, format, name) = match (params.path, params.content) {
+            (Some(path), None) => {
+                let resolved = self.resolve_path(&path)?;
+                let format = resolved
+                    .extension()
+                    .and_then(|extension| extension.to_str())
+                    .map(str::to_ascii_lowercase);
+                let name = params
+                    .name
+                    .or_else(|| path.file_name().map(|name| name.to_string_lossy().into()));
+                (open_document(&resolved)?, format, name)
+            }
+            (None, Some(content)) => {
+                let (document, format) = load_bytes(content.as_bytes())?;
+                (document, Some(format.to_string()), params.name)
+            }
+            _ => {
+                return Err(RpcError::new(
+                    INVALID_PARAMS,
+                    "path 与 content 须且只能提供一项",
+                ));
+            }
+        };
+        let summary = document_summary(&document);
+        let session = self.insert_session(name, document)?;
+        Ok(json!({ "session": session, "format": format, "summary": summary }))
+    }
+
+    fn save(&self, params: SaveParams) -> Result<Value, RpcError> {
+        let path = self.resolve_path(&params.path)?;
+        let format = match params.format {
+            Some(format) => SaveFormat::from_extension(&format).ok_or_else(|| {
+                RpcError::new(INVALID_PARAMS, format!("不支持的保存格式 {format}"))
+            })?,
+            None => SaveFormat::from_path(&path).ok_or_else(|| {
+                RpcError::new(INVALID_PARAMS, "无法从路径推断格式，请指定 format")
+            })?,
+        };
+        self.with_scene(params.session, |scene| {
+            format.save(scene.document(), &path)?;
+            Ok(json!({ "path": path, "format": format.extension() }))
+        })
+    }
+
+    fn query(&self, params: QueryParams) -> Result<Value, RpcError> {
+        let window = params.window.map(|[x1, y1, x2, y2]| {
+            Bounds2D::new(
+                Point2::new(x1.min(x2), y1.min(y2)),
+                Point2::new(x1.max(x2), y1.max(y2)),
+            )
+        });
+        let filter = EntityFilter {
+            kind: params.kind.as_deref(),
+            layer: params.layer.as_deref(),
+            window,
+        };
+        let limit = params
+            .limit
+            .unwrap_or(self.options.max_page_size)
+            .min(self.options.max_page_size);
+        self.with_scene(params.session, |scene| {
+            let document = scene.document();
+            let matched: Vec<&(EntityId, Entity)> = document
+                .entities()
+                .filter(|(_, entity)| filter.matches(document, entity))
+                .collect();
+            let entities: Vec<Value> = matched
+                .iter()
+                .skip(params.offset)
+                .take(limit)
+                .map(|(id, entity)| entity_json(document, *id, entity))
+                .collect();
+            Ok(json!({
+                "total": matched.len(),
+                "offset": params.offset,
+                "entities": entities,
+            }))
+        })
+    }
+
+    fn resolve_path(&self, path: &Path) -> Result<PathBuf, RpcError> {
+        match &self.options.file_access {
+            FileAccess::Disabled => Err(RpcError::new(
+                FILE_ACCESS_DENIED,
+                "服务未开启按路径读写，请改用 content 打开图纸",
+            )),
+            FileAccess::Unrestricted => Ok(path.to_path_buf()),
+            FileAccess::Within(root) => {
+                let escapes = path.components().any(|component| {
+                    !matches!(component, Component::Normal(_) | Component::CurDir)
+                });
+                if escapes {
+                    return Err(RpcError::new(
+                        FILE_ACCESS_DENIED,
+                        format!("路径 {} 须为不含 .. 的相对路径", path.display()),
+                    ));
+                }
+                Ok(root.join(path))
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SessionParams {
+    session: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OpenParams {
+    path: Option<PathBuf>,
+    content: Option<String>,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SaveParams {
+    session: u64,
+    path: PathBuf,
+    format: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct QueryParams {
+    session: u64,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    layer: Option<String>,
+    window: Option<[f64; 4]>,
+    #[serde(default)]
+    offset: usize,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EntityParams {
+    session: u64,
+    id: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AddParams {
+    session: u64,
+    entity: Entity,
+    properties: Option<EntityProperties>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UpdateParams {
+    session: u64,
+    id: u64,
+    entity: Option<Entity>,
+    properties: Option<EntityProperties>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IdsParams {
+    session: u64,
+    ids: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum SelectionMode {
+    #[default]
+    Replace,
+    Add,
+    Remove,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SelectionParams {
+    session: u64,
+    ids: Vec<u64>,
+    #[serde(default)]
+    mode: SelectionMode,
+}
+
+/// 解析参数；无参数方法以 `()` 接收，此时 `params` 须为 `null` 或空对象。
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = match params {
+        Value::Object(map) if map.is_empty() => Value::Null,
+        params => params,
+    };
+    let params = match params {
+        // 带参数的方法在缺少参数时按空对象解析，以便报告缺失的字段
+        Value::Null => serde_json::from_value::<T>(Value::Null)
+            .or_else(|_| serde_json::from_value::<T>(json!({}))),
+        params => serde_json::from_value::<T>(params),
+    };
+    params.map_err(|err| RpcError::new(INVALID_PARAMS, format!("参数错误: {err}")))
+}
+
+fn error_reply(id: Value, error: RpcError) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": error.to_json() })
+}
+
+fn session_not_found(session: u64) -> RpcError {
+    RpcError::new(SESSION_NOT_FOUND, format!("会话 {session} 不存在"))
+}
+
+fn entity_not_found(id: u64) -> RpcError {
+    RpcError::new(ENTITY_NOT_FOUND, format!("实体 {id} 不存在"))
+}
+
+fn entity_value(document: &Document, id: u64) -> Result<Value, RpcError> {
+    let entity_id = EntityId::new(id);
+    document
+        .entity(entity_id)
+        .map(|entity| entity_json(document, entity_id, entity))
+        .ok_or_else(|| entity_not_found(id))
+}
+
+fn selection_json(scene: &Scene) -> Value {
+    let mut ids: Vec<u64> = scene.selection().map(|id| id.get()).collect();
+    ids.sort_unstable();
+    json!({ "ids": ids, "bounds": bounds_json(scene.selection_bounds()) })
+}
@@ -0,0 +1,452 @@
+//! 渲染服务的路由与处理：上传图纸、按区域渲染 PNG/SVG、以 JSON 查询实体。
+//!
+//! 图纸保存在内存中，按上传顺序编号；超过容量时淘汰最早上传的图纸。
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use serde_json::{Value, json};
+use zcad_core::document::{Document, Entity, EntityId};
+use zcad_core::geometry::{Bounds2D, Point2};
+use zcad_io::pdf::PlotArea;
+use zcad_io::raster::{PngExporter, RasterExportOptions};
+use zcad_io::svg::{SvgExportOptions, SvgExporter};
+use zcad_io::{DocumentFormat, IoError, open_document_bytes};
+
+use crate::http::{Request, Response};
+use crate::rpc::DocumentService;
+
+/// 服务选项。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerOptions {
+    /// 上传请求体上限（字节）。
+    pub max_upload_bytes: usize,
+    /// 内存中保留的图纸数量上限。
+    pub max_drawings: usize,
+    /// 渲染 PNG 的最大边长（像素）。
+    pub max_image_size: u32,
+    /// 实体查询单页的最大条数。
+    pub max_page_size: usize,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        Self {
+            max_upload_bytes: 64 * 1024 * 1024,
+            max_drawings: 32,
+            max_image_size: 8192,
+            max_page_size: 1000,
+        }
+    }
+}
+
+struct StoredDrawing {
+    name: Option<String>,
+    format: &'static str,
+    document: Document,
+}
+
+/// 无界面渲染服务，[`RenderService::handle`] 处理单个请求，可在多个线程间共享。
+///
+/// `POST /rpc` 转交给内置的 [`DocumentService`]。
+#[derive(Default)]
+pub struct RenderService {
+    options: ServerOptions,
+    drawings: Mutex<BTreeMap<u64, Arc<StoredDrawing>>>,
+    next_id: AtomicU64,
+    documents: DocumentService,
+}
+
+impl RenderService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(mut self, options: ServerOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn options(&self) -> &ServerOptions {
+        &self.options
+    }
+
+    /// 替换 `POST /rpc` 使用的文档服务。
+    pub fn with_documents(mut self, documents: DocumentService) -> Self {
+        self.documents = documents;
+        self
+    }
+
+    pub fn documents(&self) -> &DocumentService {
+        &self.documents
+    }
+
+    /// 按方法与路径分派请求。
+    pub fn handle(&self, request: &Request) -> Response {
+        let segments: Vec<&str> = request
+            .path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        let method = request.method.as_str();
+        match (method, segments.as_slice()) {
+            ("GET", ["health"]) => Response::json(200, &json!({ "status": "ok" })),
+            ("GET", ["drawings"]) => self.list(),
+            ("POST", ["drawings"]) => self.upload(request),
+            ("POST", ["rpc"]) => self.rpc(request),
+            (_, ["drawings", id, rest @ ..]) => {
+                let Some(drawing) = self.drawing(id) else {
+                    return Response::error(404, format!("图纸 {id} 不存在"));
+                };
+                match (method, rest) {
+                    ("GET", []) => Response::json(200, &summary(id, &drawing)),
+                    ("DELETE", []) => self.delete(id),
+                    ("GET", ["render.png"]) => self.render_png(&drawing.document, request),
+                    ("GET", ["render.svg"]) => render_svg(&drawing.document, request),
+                    ("GET", ["entities"]) => self.entities(&drawing.document, request),
+                    ("GET", ["entities", entity]) => entity_detail(&drawing.document, entity),
+                    (_, [] | ["render.png" | "render.svg" | "entities"] | ["entities", _]) => {
+                        Response::error(405, format!("不支持的方法 {method}"))
+                    }
+                    _ => Response::error(404, format!("未知路径 {}", request.path)),
+                }
+            }
+            (_, ["health" | "drawings" | "rpc"]) => {
+                Response::error(405, format!("不支持的方法 {method}"))
+            }
+            _ => Response::error(404, format!("未知路径 {}", request.path)),
+        }
+    }
+
+    fn drawing(&self, id: &str) -> Option<Arc<StoredDrawing>> {
+        let id = id.parse::<u64>().ok()?;
+        self.drawings.lock().ok()?.get(&id).cloned()
+    }
+
+    fn list(&self) -> Response {
+        let drawings = self.drawings.lock().expect("图纸表锁已中毒");
+        let items: Vec<Value> = drawings
+            .iter()
+            .map(|(id, drawing)| {
+                json!({
+                    "id": id.to_string(),
+                    "name": drawing.name,
+                    "entities": drawing.document.entities().count(),
+                })
+            })
+            .collect();
+        Response::json(200, &json!({ "drawings": items }))
+    }
+
+    /// 请求体为图纸内容（DXF、原生 JSON 或 `.zcadz`），格式按内容识别；`?name=` 记录原文件名。
+    fn upload(&self, request: &Request) -> Response {
+        if request.body.is_empty() {
+            return Response::error(400, "请求体为空");
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let (document, extension) = match load_bytes(&request.body) {
+            Ok(loaded) => loaded,
+            Err(IoError::UnsupportedFeature(feature)) => {
+                return Response::error(415, format!("暂不支持: {feature}"));
+            }
+            Err(err) => return Response::error(422, format!("无法解析图纸: {err}")),
+        };
+
+        let drawing = Arc::new(StoredDrawing {
+            name: request.query("name").map(str::to_string),
+            format: extension,
+            document,
+        });
+        let body = summary(&id.to_string(), &drawing);
+        let mut drawings = self.drawings.lock().expect("图纸表锁已中毒");
+        drawings.insert(id, drawing);
+        while drawings.len() > self.options.max_drawings.max(1) {
+            drawings.pop_first();
+        }
+        Response::json(201, &body)
+    }
+
+    /// JSON-RPC 请求体；全部为通知时返回 204。
+    fn rpc(&self, request: &Request) -> Response {
+        let Ok(message) = std::str::from_utf8(&request.body) else {
+            return Response::error(400, "请求体不是 UTF-8 文本");
+        };
+        match self.documents.handle_message(message) {
+            Some(reply) => Response::new(200, "application/json", reply),
+            None => Response::new(204, "application/json", Vec::new()),
+        }
+    }
+
+    fn delete(&self, id: &str) -> Response {
+        let removed = id
+            .parse::<u64>()
+            .ok()
+            .and_then(|id| self.drawings.lock().ok()?.remove(&id));
+        match removed {
+            Some(_) => Response::new(204, "application/json", Vec::new()),
+            None => Response::error(404, format!("图纸 {id} 不存在")),
+        }
+    }
+
+    /// `width`/`height` 为像素尺寸，`window=x1,y1,x2,y2` 指定模型空间区域，
+    /// `background` 为 `rrggbb` 或 `transparent`。
+    fn render_png(&self, document: &Document, request: &Request) -> Response {
+        let defaults = RasterExportOptions::default();
+        let size = |name: &str, default: u32| -> Result<u32, Response> {
+            let Some(value) = request.query(name) else {
+                return Ok(default);
+            };
+            match value.parse::<u32>() {
+                Ok(size) if (1..=self.options.max_image_size).contains(&size) => Ok(size),
+                _ => Err(Response::error(
+                    400,
+                    format!(
+                        "{name} 应为 1 到 {} 之间的整数",
+                        self.options.max_image_size
+                    ),
+                )),
+            }
+        };
+        let options = (|| {
+            Ok(RasterExportOptions {
+                width: size("width", defaults.width)?,
+                height: size("height", defaults.height)?,
+                area: plot_area(request)?,
+                background: match request.query("background") {
+                    Some("transparent") => [0, 0, 0, 0],
+                    Some(value) => {
+                        let rgb = parse_rgb(value)?;
+                        [(rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8, 255]
+                    }
+                    None => defaults.background,
+                },
+                ..defaults
+            })
+        })();
+        let options = match options {
+            Ok(options) => options,
+            Err(response) => return response,
+        };
+        match PngExporter::new().with_options(options).to_png(document) {
+            Ok(png) => Response::new(200, "image/png", png),
+            Err(err) => Response::error(500, format!("渲染失败: {err}")),
+        }
+    }
+
+    /// `type`、`layer` 过滤实体类型与图层，`window=x1,y1,x2,y2` 只返回范围相交的实体，
+    /// `offset`/`limit` 分页。
+    fn entities(&self, document: &Document, request: &Request) -> Response {
+        let window = match plot_area(request) {
+            Ok(PlotArea::Window { min, max }) => Some(Bounds2D::new(min, max)),
+            Ok(PlotArea::Extents) => None,
+            Err(response) => return response,
+        };
+        let number = |name: &str, default: usize| -> Result<usize, Response> {
+            request.query(name).map_or(Ok(default), |value| {
+                value
+                    .parse::<usize>()
+                    .map_err(|_| Response::error(400, format!("{name} 应为非负整数")))
+            })
+        };
+        let (offset, limit) = match (
+            number("offset", 0),
+            number("limit", self.options.max_page_size),
+        ) {
+            (Ok(offset), Ok(limit)) => (offset, limit.min(self.options.max_page_size)),
+            (Err(response), _) | (_, Err(response)) => return response,
+        };
+        let filter = EntityFilter {
+            kind: request.query("type"),
+            layer: request.query("layer"),
+            window,
+        };
+
+        let matched: Vec<&(EntityId, Entity)> = document
+            .entities()
+            .filter(|(_, entity)| filter.matches(document, entity))
+            .collect();
+        let entities: Vec<Value> = matched
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .map(|(id, entity)| entity_json(document, *id, entity))
+            .collect();
+        Response::json(
+            200,
+            &json!({
+                "total": matched.len(),
+                "offset": offset,
+                "entities": entities,
+            }),
+        )
+    }
+}
+
+fn render_svg(document: &Document, request: &Request) -> Response {
+    let mut options = SvgExportOptions::default();
+    match plot_area(request) {
+        Ok(area) => options.area = area,
+        Err(response) => return response,
+    }
+    if let Some(width) = request.query("width") {
+        match width.parse::<f64>() {
+            Ok(width) if width > 0.0 && width.is_finite() => options.width = Some(width),
+            _ => return Response::error(400, "width 应为正数"),
+        }
+    }
+    match request.query("background") {
+        None | Some("transparent") => {}
+        Some(value) => match parse_rgb(value) {
+            Ok(rgb) => options.background = Some(rgb),
+            Err(response) => return response,
+        },
+    }
+    let svg = SvgExporter::new().with_options(options).to_svg(document);
+    Response::new(200, "image/svg+xml", svg)
+}
+
+/// 按内容识别格式并在内存中加载图纸，返回文档与格式扩展名。
+pub(crate) fn load_bytes(bytes: &[u8]) -> Result<(Document, &'static str), IoError> {
+    let extension = match DocumentFormat::detect(bytes) {
+        DocumentFormat::Dxf => "dxf",
+        DocumentFormat::Json => "json",
+        DocumentFormat::Zcadz => "zcadz",
+        DocumentFormat::Dwg => "dwg",
+    };
+    open_document_bytes(bytes).map(|document| (document, extension))
+}
+
+/// 实体过滤条件：类型名（不区分大小写）、图层与相交窗口，未指定的条件不参与过滤。
+#[derive(Debug, Default)]
+pub(crate) struct EntityFilter<'a> {
+    pub kind: Option<&'a str>,
+    pub layer: Option<&'a str>,
+    pub window: Option<Bounds2D>,
+}
+
+impl EntityFilter<'_> {
+    pub fn matches(&self, document: &Document, entity: &Entity) -> bool {
+        self.kind
+            .is_none_or(|kind| entity.kind_name().eq_ignore_ascii_case(kind))
+            && self.layer.is_none_or(|layer| entity.layer_name() == layer)
+            && self.window.is_none_or(|window| {
+                document
+                    .entity_extent(entity)
+                    .is_some_and(|bounds| intersects(&bounds, &window))
+            })
+    }
+}
+
+fn entity_detail(document: &Document, entity: &str) -> Response {
+    let found = entity
+        .parse::<u64>()
+        .ok()
+        .map(EntityId::new)
+        .and_then(|id| document.entity(id).map(|entity| (id, entity)));
+    match found {
+        Some((id, entity)) => Response::json(200, &entity_json(document, id, entity)),
+        None => Response::error(404, format!("实体 {entity} 不存在")),
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct BoundsJson {
+    min: [f64; 2],
+    max: [f64; 2],
+}
+
+pub(crate) fn bounds_json(bounds: Option<Bounds2D>) -> Option<BoundsJson> {
+    bounds
+        .filter(|bounds| !bounds.is_empty())
+        .map(|bounds| BoundsJson {
+            min: [bounds.min().x(), bounds.min().y()],
+            max: [bounds.max().x(), bounds.max().y()],
+        })
+}
+
+fn summary(id: &str, drawing: &StoredDrawing) -> Value {
+    let mut summary = json!({
+        "id": id,
+        "name": drawing.name,
+        "format": drawing.format,
+    });
+    if let (Value::Object(summary), Value::Object(details)) =
+        (&mut summary, document_summary(&drawing.document))
+    {
+        summary.extend(details);
+    }
+    summary
+}
+
+/// 图纸概况：实体总数与分类计数、图层、块数量与范围。
+pub(crate) fn document_summary(document: &Document) -> Value {
+    let mut layers: Vec<Value> = document
+        .layers()
+        .map(|layer| json!({ "name": layer.name, "visible": layer.is_visible }))
+        .collect();
+    layers.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+    let mut by_type: BTreeMap<&str, usize> = BTreeMap::new();
+    for (_, entity) in document.entities() {
+        *by_type.entry(entity.kind_name()).or_default() += 1;
+    }
+    json!({
+        "entities": document.entities().count(),
+        "by_type": by_type,
+        "layers": layers,
+        "blocks": document.blocks().count(),
+        "bounds": bounds_json(document.bounds()),
+    })
+}
+
+pub(crate) fn entity_json(document: &Document, id: EntityId, entity: &Entity) -> Value {
+    json!({
+        "id": id.get(),
+        "type": entity.kind_name(),
+        "layer": entity.layer_name(),
+        "bounds": bounds_json(document.entity_extent(entity)),
+        "properties": document.entity_properties(id),
+        "data": entity,
+    })
+}
+
+fn intersects(a: &Bounds2D, b: &Bounds2D) -> bool {
+    a.min().x() <= b.max().x()
+        && a.max().x() >= b.min().x()
+        && a.min().y() <= b.max().y()
+        && a.max().y() >= b.min().y()
+}
+
+/// 解析 `window=x1,y1,x2,y2`，角点顺序任意；未指定时为图纸范围。
+fn plot_area(request: &Request) -> Result<PlotArea, Response> {
+    let Some(window) = request.query("window") else {
+        return Ok(PlotArea::Extents);
+    };
+    let values: Vec<f64> = window
+        .split(',')
+        .map(|value| value.trim().parse::<f64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| Response::error(400, "window 应为 x1,y1,x2,y2"))?;
+    let [x1, y1, x2, y2] = values[..] else {
+        return Err(Response::error(400, "window 应为 x1,y1,x2,y2"));
+    };
+    if values.iter().any(|value| !value.is_finite()) || x1 == x2 || y1 == y2 {
+        return Err(Response::error(400, "window 的宽高必须为非零有限值"));
+    }
+    Ok(PlotArea::Window {
+        min: Point2::new(x1.min(x2), y1.min(y2)),
+        max: Point2::new(x1.max(x2), y1.max(y2)),
+    })
+}
+
+fn parse_rgb(value: &str) -> Result<u32, Response> {
+    let hex = value.trim_start_matches('#');
+    match u32::from_str_radix(hex, 16) {
+        Ok(rgb) if hex.len() == 6 => Ok(rgb),
+        _ => Err(Response::error(
+            400,
+            format!("颜色 {value} 应为 rrggbb 或 transparent"),
+        )),
+    }
+}
@@ -0,0 +1,203 @@
+use std::io::{BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde_json::Value;
+use zcad_server::{RenderService, Request, Response, ServerOptions, serve};
+
+fn fixture(name: &str) -> Vec<u8> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../zcad-io/tests/data")
+        .join(format!("{name}.dxf"));
+    std::fs::read(path).expect("读取样例失败")
+}
+
+fn upload(service: &RenderService, name: &str) -> String {
+    let response = service.handle(
+        &Request::new("POST", &format!("/drawings?name={name}.dxf")).with_body(fixture(name)),
+    );
+    assert_eq!(response.status, 201, "{:?}", response.json_body());
+    response.json_body().unwrap()["id"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+fn get(service: &RenderService, target: &str) -> Response {
+    service.handle(&Request::new("GET", target))
+}
+
+#[test]
+fn upload_then_query_summary_and_entities() {
+    let service = RenderService::new();
+    let id = upload(&service, "basic_entities");
+
+    let summary = get(&service, &format!("/drawings/{id}"))
+        .json_body()
+        .unwrap();
+    assert_eq!(summary["name"], "basic_entities.dxf");
+    assert_eq!(summary["format"], "dxf");
+    let total = summary["entities"].as_u64().unwrap();
+    assert!(total > 0);
+    assert!(summary["bounds"]["min"].is_array());
+
+    let list = get(&service, "/drawings").json_body().unwrap();
+    assert_eq!(list["drawings"][0]["id"], id.as_str());
+
+    let lines = get(
+        &service,
+        &format!("/drawings/{id}/entities?type=line&limit=1"),
+    )
+    .json_body()
+    .unwrap();
+    assert!(lines["total"].as_u64().unwrap() >= 1);
+    let entities = lines["entities"].as_array().unwrap();
+    assert_eq!(entities.len(), 1);
+    assert_eq!(entities[0]["type"], "LINE");
+    assert!(entities[0]["data"]["Line"]["start"].is_array());
+
+    let entity_id = entities[0]["id"].as_u64().unwrap();
+    let detail = get(&service, &format!("/drawings/{id}/entities/{entity_id}"));
+    assert_eq!(detail.status, 200);
+    assert_eq!(detail.json_body().unwrap()["id"], entity_id);
+
+    // 窗口远离图纸时没有实体相交
+    let empty = get(
+        &service,
+        &format!("/drawings/{id}/entities?window=1000000,1000000,1000001,1000001"),
+    )
+    .json_body()
+    .unwrap();
+    assert_eq!(empty["total"], 0);
+
+    let deleted = service.handle(&Request::new("DELETE", &format!("/drawings/{id}")));
+    assert_eq!(deleted.status, 204);
+    assert_eq!(get(&service, &format!("/drawings/{id}")).status, 404);
+}
+
+#[test]
+fn renders_png_and_svg_regions() {
+    let service = RenderService::new();
+    let id = upload(&service, "block_insert");
+
+    let png = get(
+        &service,
+        &format!("/drawings/{id}/render.png?width=64&height=32&background=transparent"),
+    );
+    assert_eq!(png.status, 200);
+    assert_eq!(png.content_type, "image/png");
+    assert!(png.body.starts_with(b"\x89PNG"));
+    // IHDR 中的宽高
+    assert_eq!(&png.body[16..24], &[0, 0, 0, 64, 0, 0, 0, 32]);
+
+    let svg = get(
+        &service,
+        &format!("/drawings/{id}/render.svg?window=10,10,12,11&width=200"),
+    );
+    assert_eq!(svg.status, 200);
+    assert_eq!(svg.content_type, "image/svg+xml");
+    let text = String::from_utf8(svg.body).unwrap();
+    assert!(text.contains(r#"viewBox="0 0 2 1""#), "{text}");
+    assert!(text.contains(r#"width="200""#), "{text}");
+
+    for query in ["width=0", "width=99999", "window=1,2,3", "background=red"] {
+        let response = get(&service, &format!("/drawings/{id}/render.png?{query}"));
+        assert_eq!(response.status, 400, "{query}");
+    }
+}
+
+#[test]
+fn rejects_bad_uploads_and_unknown_routes() {
+    let service = RenderService::new().with_options(ServerOptions {
+        max_drawings: 1,
+        ..ServerOptions::default()
+    });
+    let invalid = service.handle(&Request::new("POST", "/drawings").with_body("0\nSECTION\n"));
+    assert_eq!(invalid.status, 422);
+    let dwg = service.handle(&Request::new("POST", "/drawings").with_body(b"AC1015\0\0".to_vec()));
+    assert_eq!(dwg.status, 415);
+    assert_eq!(
+        service.handle(&Request::new("POST", "/drawings")).status,
+        400
+    );
+
+    // 容量为 1 时淘汰最早的图纸
+    let first = upload(&service, "basic_entities");
+    let second = upload(&service, "block_insert");
+    assert_eq!(get(&service, &format!("/drawings/{first}")).status, 404);
+    assert_eq!(get(&service, &format!("/drawings/{second}")).status, 200);
+
+    assert_eq!(get(&service, "/nope").status, 404);
+    assert_eq!(get(&service, "/drawings/abc").status, 404);
+    assert_eq!(
+        service
+            .handle(&Request::new("PUT", &format!("/drawings/{second}")))
+            .status,
+        405
+    );
+}
+
+/// 发送原始 HTTP 请求并返回状态码与响应体。
+fn roundtrip(address: std::net::SocketAddr, raw: &[u8]) -> (u16, Vec<u8>) {
+    let mut stream = TcpStream::connect(address).unwrap();
+    stream.write_all(raw).unwrap();
+    let mut response = Vec::new();
+    BufReader::new(stream).read_to_end(&mut response).unwrap();
+    let split = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .expect("响应缺少头部结束标记");
+    let head = String::from_utf8_lossy(&response[..split]);
+    let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+    (status, response[split + 4..].to_vec())
+}
+
+#[test]
+fn serves_requests_over_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let service = Arc::new(RenderService::new().with_options(ServerOptions {
+        max_upload_bytes: 4096,
+        ..ServerOptions::default()
+    }));
+    std::thread::spawn(move || serve(listener, service));
+
+    let (status, body) = roundtrip(address, b"GET /health HTTP/1.1\r\nHost: x\r\n\r\n");
+    assert_eq!(status, 200);
+    assert_eq!(
+        serde_json::from_slice::<Value>(&body).unwrap()["status"],
+        "ok"
+    );
+
+    let drawing = fixture("block_insert");
+    let mut request = format!(
+        "POST /drawings HTTP/1.1\r\nHost: x\r\nContent-Length: {}\r\n\r\n",
+        drawing.len()
+    )
+    .into_bytes();
+    request.extend_from_slice(&drawing);
+    let (status, body) = roundtrip(address, &request);
+    assert_eq!(status, 201);
+    let id = serde_json::from_slice::<Value>(&body).unwrap()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let (status, body) = roundtrip(
+        address,
+        format!("GET /drawings/{id}/render.png?width=16&height=16 HTTP/1.1\r\n\r\n").as_bytes(),
+    );
+    assert_eq!(status, 200);
+    assert!(body.starts_with(b"\x89PNG"));
+
+    let (status, _) = roundtrip(
+        address,
+        b"POST /drawings HTTP/1.1\r\nContent-Length: 100000\r\n\r\n",
+    );
+    assert_eq!(status, 413);
+    let (status, _) = roundtrip(address, b"POST /drawings HTTP/1.1\r\n\r\n");
+    assert_eq!(status, 411);
+    let (status, _) = roundtrip(address, b"garbage\r\n\r\n");
+    assert_eq!(status, 400);
+}
@@ -0,0 +1,257 @@
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use serde_json::{Value, json};
+use zcad_server::rpc::{
+    ENTITY_NOT_FOUND, FILE_ACCESS_DENIED, INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND,
+    PARSE_ERROR, SESSION_NOT_FOUND,
+};
+use zcad_server::{DocumentService, DocumentServiceOptions, FileAccess, RenderService, Request};
+
+fn fixture_text(name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../zcad-io/tests/data")
+        .join(format!("{name}.dxf"));
+    std::fs::read_to_string(path).expect("读取样例失败")
+}
+
+fn call(service: &DocumentService, method: &str, params: Value) -> Value {
+    service
+        .call(method, params)
+        .unwrap_or_else(|err| panic!("{method} 调用失败: {err:?}"))
+}
+
+fn error_code(service: &DocumentService, method: &str, params: Value) -> i64 {
+    service.call(method, params).expect_err("调用应失败").code
+}
+
+#[test]
+fn open_modify_select_and_save_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let service = DocumentService::new().with_options(DocumentServiceOptions {
+        file_access: FileAccess::Within(dir.path().to_path_buf()),
+        ..DocumentServiceOptions::default()
+    });
+
+    let opened = call(
+        &service,
+        "document.open",
+        json!({ "content": fixture_text("basic_entities"), "name": "basic.dxf" }),
+    );
+    assert_eq!(opened["format"], "dxf");
+    let session = opened["session"].as_u64().unwrap();
+    let initial = opened["summary"]["entities"].as_u64().unwrap();
+    assert!(initial > 0);
+
+    let added = call(
+        &service,
+        "entities.add",
+        json!({
+            "session": session,
+            "entity": { "Line": { "start": [0.0, 0.0], "end": [10.0, 0.0], "layer": "RPC" } },
+            "properties": { "color": { "index": 1 } },
+        }),
+    );
+    let id = added["id"].as_u64().unwrap();
+    let entity = call(
+        &service,
+        "entities.get",
+        json!({ "session": session, "id": id }),
+    );
+    assert_eq!(entity["type"], "LINE");
+    assert_eq!(entity["layer"], "RPC");
+    assert_eq!(entity["properties"]["color"]["index"], 1);
+
+    let updated = call(
+        &service,
+        "entities.update",
+        json!({
+            "session": session,
+            "id": id,
+            "entity": { "Circle": { "center": [5.0, 5.0], "radius": 2.0, "layer": "RPC" } },
+        }),
+    );
+    assert_eq!(updated["type"], "CIRCLE");
+    assert_eq!(updated["id"], id);
+
+    let query = call(
+        &service,
+        "entities.query",
+        json!({ "session": session, "layer": "RPC", "window": [0.0, 0.0, 4.0, 4.0] }),
+    );
+    assert_eq!(query["total"], 1);
+    assert_eq!(query["entities"][0]["id"], id);
+
+    let selection = call(
+        &service,
+        "selection.set",
+        json!({ "session": session, "ids": [id] }),
+    );
+    assert_eq!(selection["ids"], json!([id]));
+    assert_eq!(selection["bounds"]["min"], json!([3.0, 3.0]));
+    assert_eq!(
+        error_code(
+            &service,
+            "selection.set",
+            json!({ "session": session, "ids": [id, 99_999], "mode": "add" }),
+        ),
+        ENTITY_NOT_FOUND
+    );
+
+    let deleted = call(
+        &service,
+        "entities.delete",
+        json!({ "session": session, "ids": [id, 99_999] }),
+    );
+    assert_eq!(deleted["deleted"], json!([id]));
+    let selection = call(&service, "selection.get", json!({ "session": session }));
+    assert_eq!(selection["ids"], json!([]));
+    assert!(selection["bounds"].is_null());
+
+    call(
+        &service,
+        "entities.add",
+        json!({
+            "session": session,
+            "entity": { "Line": { "start": [0.0, 0.0], "end": [1.0, 1.0], "layer": "0" } },
+        }),
+    );
+    assert_eq!(
+        error_code(
+            &service,
+            "document.save",
+            json!({ "session": session, "path": "../escape.json" }),
+        ),
+        FILE_ACCESS_DENIED
+    );
+    let saved = call(
+        &service,
+        "document.save",
+        json!({ "session": session, "path": "saved.json" }),
+    );
+    assert_eq!(saved["format"], "json");
+    assert!(dir.path().join("saved.json").exists());
+
+    let reopened = call(&service, "document.open", json!({ "path": "saved.json" }));
+    assert_eq!(reopened["format"], "json");
+    assert_eq!(reopened["summary"]["entities"], initial + 1);
+    let sessions = call(&service, "session.list", Value::Null);
+    assert_eq!(sessions["sessions"].as_array().unwrap().len(), 2);
+    assert_eq!(sessions["sessions"][0]["name"], "basic.dxf");
+    assert_eq!(sessions["sessions"][1]["name"], "saved.json");
+
+    call(&service, "document.close", json!({ "session": session }));
+    assert_eq!(
+        error_code(&service, "document.summary", json!({ "session": session })),
+        SESSION_NOT_FOUND
+    );
+}
+
+#[test]
+fn reports_protocol_and_parameter_errors() {
+    let service = DocumentService::new();
+    let session = call(&service, "document.new", json!({}))["session"]
+        .as_u64()
+        .unwrap();
+
+    assert_eq!(
+        error_code(&service, "document.open", json!({ "path": "plan.dxf" })),
+        FILE_ACCESS_DENIED
+    );
+    assert_eq!(
+        error_code(&service, "document.open", json!({})),
+        INVALID_PARAMS
+    );
+    assert_eq!(
+        error_code(&service, "entities.get", json!({ "session": session })),
+        INVALID_PARAMS
+    );
+    assert_eq!(
+        error_code(
+            &service,
+            "entities.update",
+            json!({ "session": session, "id": 0 }),
+        ),
+        INVALID_PARAMS
+    );
+    assert_eq!(
+        error_code(
+            &service,
+            "entities.get",
+            json!({ "session": session, "id": 0 })
+        ),
+        ENTITY_NOT_FOUND
+    );
+    assert_eq!(
+        error_code(&service, "entities.query", json!({ "session": 42 })),
+        SESSION_NOT_FOUND
+    );
+    assert_eq!(
+        error_code(&service, "document.new", json!({ "unexpected": 1 })),
+        INVALID_PARAMS
+    );
+
+    let reply = |message: &str| -> Value {
+        serde_json::from_str(&service.handle_message(message).expect("应有回复")).unwrap()
+    };
+    assert_eq!(reply("{not json")["error"]["code"], PARSE_ERROR);
+    assert_eq!(reply("[]")["error"]["code"], INVALID_REQUEST);
+    assert_eq!(
+        reply(r#"{"jsonrpc":"1.0","id":1,"method":"session.list"}"#)["error"]["code"],
+        INVALID_REQUEST
+    );
+    let unknown = reply(r#"{"jsonrpc":"2.0","id":"a","method":"nope"}"#);
+    assert_eq!(unknown["id"], "a");
+    assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+
+    // 通知不回复，批量中只返回带 id 的请求结果
+    assert!(
+        service
+            .handle_message(r#"{"jsonrpc":"2.0","method":"document.new"}"#)
+            .is_none()
+    );
+    let batch = reply(
+        r#"[{"jsonrpc":"2.0","method":"document.new"},
+            {"jsonrpc":"2.0","id":7,"method":"session.list"}]"#,
+    );
+    let batch = batch.as_array().unwrap();
+    assert_eq!(batch.len(), 1);
+    assert_eq!(batch[0]["id"], 7);
+    assert_eq!(batch[0]["result"]["sessions"].as_array().unwrap().len(), 3);
+}
+
+#[test]
+fn serves_rpc_over_http_and_stdio() {
+    let service = RenderService::new();
+    let response = service.handle(
+        &Request::new("POST", "/rpc")
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"method":"document.new"}"#),
+    );
+    assert_eq!(response.status, 200);
+    assert_eq!(response.json_body().unwrap()["result"]["session"], 1);
+
+    let notification = service.handle(
+        &Request::new("POST", "/rpc").with_body(r#"{"jsonrpc":"2.0","method":"document.new"}"#),
+    );
+    assert_eq!(notification.status, 204);
+    assert_eq!(service.handle(&Request::new("GET", "/rpc")).status, 405);
+
+    let input = concat!(
+        r#"{"jsonrpc":"2.0","id":1,"method":"document.new"}"#,
+        "\n\n",
+        r#"{"jsonrpc":"2.0","id":2,"method":"entities.add","params":{"session":1,"entity":{"Circle":{"center":[0,0],"radius":1,"layer":"0"}}}}"#,
+        "\n",
+    );
+    let mut output = Vec::new();
+    DocumentService::new()
+        .serve_lines(Cursor::new(input), &mut output)
+        .unwrap();
+    let replies: Vec<Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(replies.len(), 2);
+    assert_eq!(replies[0]["result"]["session"], 1);
+    assert_eq!(replies[1]["result"]["id"], 0);
+}
@@ -0,0 +1,233 @@
+//! 浏览器端绑定：通过 `wasm-bindgen` 向 JavaScript 暴露图纸解析、实体遍历、范围与离散化，
+//! 查看器可直接在页面中加载 DXF/JSON/`.zcadz`，无需服务端。
+//!
+//! ```js
+//! import init, { ZcadDocument } from "./pkg/zcad_wasm.js";
+//! await init();
+//! const doc = ZcadDocument.fromBytes(new Uint8Array(await file.arrayBuffer()));
+//! const [minX, minY, maxX, maxY] = doc.bounds() ?? [0, 0, 0, 0];
+//! const mesh = doc.tessellateBuffers({ chordTolerance: 0.05, background: 0x1e1e1e });
+//! ```
+//!
+//! 加载在内存中完成（[`zcad_io::open_document_bytes`]），图像等外部引用不做解析。
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use zcad_core::document::{Document, Entity, EntityId};
+use zcad_core::geometry::Bounds2D;
+use zcad_core::tessellate::TessellateOptions;
+use zcad_io::open_document_bytes;
+use zcad_io::tessellate::{Tessellator, TessellatorOptions};
+
+/// 浏览器中持有的图纸。
+#[wasm_bindgen]
+pub struct ZcadDocument {
+    document: Document,
+}
+
+#[wasm_bindgen]
+impl ZcadDocument {
+    /// 按内容识别格式并解析图纸；不支持的格式或无效内容抛出错误。
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<ZcadDocument, JsError> {
+        let document = open_document_bytes(bytes).map_err(|err| JsError::new(&err.to_string()))?;
+        Ok(Self { document })
+    }
+
+    #[wasm_bindgen(getter, js_name = entityCount)]
+    pub fn entity_count(&self) -> usize {
+        self.document.entities().count()
+    }
+
+    /// 图纸范围 `[minX, minY, maxX, maxY]`，空图纸返回 `undefined`。
+    pub fn bounds(&self) -> Option<Vec<f64>> {
+        bounds_array(self.document.bounds())
+    }
+
+    /// 图层列表：`{ name, visible, color }`，颜色为随层颜色的 `0xRRGGBB`。
+    pub fn layers(&self) -> Result<JsValue, JsError> {
+        let mut layers: Vec<LayerInfo> = self
+            .document
+            .layers()
+            .map(|layer| LayerInfo {
+                name: &layer.name,
+                visible: layer.is_visible,
+                color: layer.color.rgb(),
+            })
+            .collect();
+        layers.sort_by(|a, b| a.name.cmp(b.name));
+        to_js(&layers)
+    }
+
+    /// 按实体顺序返回 `{ id, type, layer, bounds, data }`，`data` 与原生 JSON 格式中的实体一致。
+    pub fn entities(&self) -> Result<JsValue, JsError> {
+        let entities: Vec<EntityInfo> = self
+            .document
+            .entities()
+            .map(|(id, entity)| EntityInfo::new(*id, entity))
+            .collect();
+        to_js(&entities)
+    }
+
+    /// 单个实体，不存在时返回 `undefined`。
+    pub fn entity(&self, id: f64) -> Result<JsValue, JsError> {
+        let id = EntityId::new(id as u64);
+        match self.document.entity(id) {
+            Some(entity) => to_js(&EntityInfo::new(id, entity)),
+            None => Ok(JsValue::UNDEFINED),
+        }
+    }
+
+    /// 离散化为路径对象数组：`{ source, layer, color, closed, filled, points }`。
+    pub fn tessellate(&self, options: JsValue) -> Result<JsValue, JsError> {
+        let tessellator = tessellator(options)?;
+        to_js(&tessellator.tessellate(&self.document))
+    }
+
+    /// 离散化为适合 WebGL 上传的扁平缓冲区。
+    #[wasm_bindgen(js_name = tessellateBuffers)]
+    pub fn tessellate_buffers(&self, options: JsValue) -> Result<TessellatedBuffers, JsError> {
+        let paths = tessellator(options)?.tessellate(&self.document);
+        let mut buffers = TessellatedBuffers::default();
+        for path in paths {
+            buffers.offsets.push((buffers.positions.len() / 2) as u32);
+            buffers
+                .positions
+                .extend(path.points.iter().flat_map(|point| [point.x(), point.y()]));
+            buffers.colors.push(path.color);
+            buffers.sources.push(path.source.get() as f64);
+            buffers
+                .flags
+                .push(u8::from(path.closed) | (u8::from(path.filled) << 1));
+        }
+        buffers.offsets.push((buffers.positions.len() / 2) as u32);
+        Ok(buffers)
+    }
+}
+
+/// 扁平的离散结果：第 `i` 条路径的点为 `positions[2 * offsets[i] .. 2 * offsets[i + 1]]`。
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct TessellatedBuffers {
+    positions: Vec<f64>,
+    offsets: Vec<u32>,
+    colors: Vec<u32>,
+    sources: Vec<f64>,
+    flags: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl TessellatedBuffers {
+    #[wasm_bindgen(getter, js_name = pathCount)]
+    pub fn path_count(&self) -> usize {
+        self.colors.len()
+    }
+
+    /// 交错的 `x, y` 坐标。
+    #[wasm_bindgen(getter)]
+    pub fn positions(&self) -> js_sys::Float64Array {
+        js_sys::Float64Array::from(self.positions.as_slice())
+    }
+
+    /// 每条路径首点的序号，末尾追加总点数。
+    #[wasm_bindgen(getter)]
+    pub fn offsets(&self) -> js_sys::Uint32Array {
+        js_sys::Uint32Array::from(self.offsets.as_slice())
+    }
+
+    /// 每条路径的 `0xRRGGBB` 颜色。
+    #[wasm_bindgen(getter)]
+    pub fn colors(&self) -> js_sys::Uint32Array {
+        js_sys::Uint32Array::from(self.colors.as_slice())
+    }
+
+    /// 每条路径的源实体编号，用于拾取。
+    #[wasm_bindgen(getter)]
+    pub fn sources(&self) -> js_sys::Float64Array {
+        js_sys::Float64Array::from(self.sources.as_slice())
+    }
+
+    /// 位标记：`1` 为闭合，`2` 为填充边界。
+    #[wasm_bindgen(getter)]
+    pub fn flags(&self) -> js_sys::Uint8Array {
+        js_sys::Uint8Array::from(self.flags.as_slice())
+    }
+}
+
+/// JavaScript 传入的离散化选项，字段均可省略。
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct JsTessellatorOptions {
+    chord_tolerance: Option<f64>,
+    max_angle: Option<f64>,
+    outline_text: Option<bool>,
+    include_fills: Option<bool>,
+    background: Option<u32>,
+}
+
+fn tessellator(options: JsValue) -> Result<Tessellator, JsError> {
+    let options: JsTessellatorOptions = if options.is_undefined() || options.is_null() {
+        JsTessellatorOptions::default()
+    } else {
+        serde_wasm_bindgen::from_value(options)
+            .map_err(|err| JsError::new(&format!("离散化选项无效: {err}")))?
+    };
+    let defaults = TessellatorOptions::default();
+    Ok(Tessellator::new().with_options(TessellatorOptions {
+        tessellation: TessellateOptions {
+            chord_tolerance: options
+                .chord_tolerance
+                .unwrap_or(defaults.tessellation.chord_tolerance),
+            max_angle: options.max_angle.unwrap_or(defaults.tessellation.max_angle),
+        },
+        outline_text: options.outline_text.unwrap_or(defaults.outline_text),
+        include_fills: options.include_fills.unwrap_or(defaults.include_fills),
+        background: options.background.or(defaults.background),
+    }))
+}
+
+#[derive(Serialize)]
+struct LayerInfo<'a> {
+    name: &'a str,
+    visible: bool,
+    color: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct EntityInfo<'a> {
+    id: u64,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    layer: &'a str,
+    bounds: Option<Vec<f64>>,
+    data: &'a Entity,
+}
+
+impl<'a> EntityInfo<'a> {
+    fn new(id: EntityId, entity: &'a Entity) -> Self {
+        Self {
+            id: id.get(),
+            kind: entity.kind_name(),
+            layer: entity.layer_name(),
+            bounds: bounds_array(entity.bounds()),
+            data: entity,
+        }
+    }
+}
+
+fn bounds_array(bounds: Option<Bounds2D>) -> Option<Vec<f64>> {
+    bounds.filter(|bounds| !bounds.is_empty()).map(|bounds| {
+        vec![
+            bounds.min().x(),
+            bounds.min().y(),
+            bounds.max().x(),
+            bounds.max().y(),
+        ]
+    })
+}
+
+fn to_js(value: &impl Serialize) -> Result<JsValue, JsError> {
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|err| JsError::new(&format!("结果转换失败: {err}")))
+}